@@ -4,8 +4,10 @@
 //! le rate limiting par utilisateur, et la validation des tokens.
 
 use crate::api::{ApiError, ApiResult};
+use crate::crypto::compute_blake3;
+use crate::nodes::gateway::RateLimit as KeyRateLimit;
 use crate::{PublicKey, Hash};
-use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
+use jsonwebtoken::{decode, decode_header, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
@@ -25,6 +27,56 @@ pub struct AuthConfig {
     pub issuer: String,
     /// Audience des tokens
     pub audience: String,
+    /// Identifiant (kid) de la clé de signature courante
+    #[serde(default = "default_key_id")]
+    pub key_id: String,
+    /// Clés retirées, encore acceptées en validation jusqu'à expiration des tokens signés
+    #[serde(default)]
+    pub previous_keys: Vec<JwtKey>,
+    /// Limites horaires par scope, inscrites dans les tokens émis sauf
+    /// limite propre au token
+    #[serde(default = "default_scope_rate_limits")]
+    pub scope_rate_limits: HashMap<ApiScope, u32>,
+    /// Limites horaires par scope propres à un rôle (`UserAccount::role`),
+    /// prioritaires sur `scope_rate_limits`
+    #[serde(default)]
+    pub role_scope_rate_limits: HashMap<String, HashMap<ApiScope, u32>>,
+}
+
+fn default_key_id() -> String {
+    "k1".to_string()
+}
+
+/// Lectures généreuses, créations d'archives (coûteuses) strictement limitées
+fn default_scope_rate_limits() -> HashMap<ApiScope, u32> {
+    HashMap::from([
+        (ApiScope::ArchivesRead, 5000),
+        (ApiScope::ArchivesWrite, 100),
+    ])
+}
+
+/// Clé de signature JWT identifiée par son kid
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JwtKey {
+    pub kid: String,
+    pub secret: String,
+}
+
+impl AuthConfig {
+    /// Remplace la clé courante; l'ancienne reste acceptée en validation
+    pub fn rotate_key(&mut self, kid: impl Into<String>, secret: impl Into<String>) {
+        let retired = JwtKey {
+            kid: std::mem::replace(&mut self.key_id, kid.into()),
+            secret: std::mem::replace(&mut self.jwt_secret, secret.into()),
+        };
+        self.previous_keys.retain(|key| key.kid != retired.kid && key.kid != self.key_id);
+        self.previous_keys.push(retired);
+    }
+
+    /// Cesse d'accepter une clé retirée
+    pub fn drop_previous_key(&mut self, kid: &str) {
+        self.previous_keys.retain(|key| key.kid != kid);
+    }
 }
 
 impl Default for AuthConfig {
@@ -36,6 +88,10 @@ impl Default for AuthConfig {
             algorithm: "EdDSA".to_string(),
             issuer: "archivechain.org".to_string(),
             audience: "api.archivechain.org".to_string(),
+            key_id: default_key_id(),
+            previous_keys: Vec::new(),
+            scope_rate_limits: default_scope_rate_limits(),
+            role_scope_rate_limits: HashMap::new(),
         }
     }
 }
@@ -73,6 +129,10 @@ pub struct RateLimit {
     pub requests_per_hour: u32,
     pub storage_limit_gb: u32,
     pub concurrent_requests: u32,
+    /// Requêtes par heure propres à un scope, décomptées dans un budget
+    /// distinct en plus de `requests_per_hour`
+    #[serde(default)]
+    pub scope_limits: HashMap<ApiScope, u32>,
 }
 
 impl Default for RateLimit {
@@ -81,10 +141,20 @@ impl Default for RateLimit {
             requests_per_hour: 1000,
             storage_limit_gb: 100,
             concurrent_requests: 10,
+            scope_limits: HashMap::new(),
         }
     }
 }
 
+impl RateLimit {
+    /// Limite horaire propre à `scope`, si elle existe
+    ///
+    /// Un scope ne peut pas dépasser la limite générale du token.
+    pub fn scope_limit(&self, scope: &ApiScope) -> Option<u32> {
+        self.scope_limits.get(scope).map(|limit| (*limit).min(self.requests_per_hour))
+    }
+}
+
 /// Scopes d'autorisation disponibles
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum ApiScope {
@@ -142,6 +212,7 @@ pub struct TokenInfo {
     pub token: String,
     pub refresh_token: String,
     pub expires_in: u64,
+    pub refresh_expires_in: u64,
     pub token_type: String,
     pub scope: Vec<String>,
 }
@@ -155,6 +226,9 @@ pub enum AuthError {
     #[error("Token expired")]
     TokenExpired,
     
+    #[error("Token revoked")]
+    RevokedToken,
+    
     #[error("Insufficient permissions: required {required}, got {actual:?}")]
     InsufficientPermissions { required: String, actual: Vec<String> },
     
@@ -174,7 +248,7 @@ pub enum AuthError {
 impl From<AuthError> for ApiError {
     fn from(err: AuthError) -> Self {
         match err {
-            AuthError::InvalidToken(_) | AuthError::TokenExpired => {
+            AuthError::InvalidToken(_) | AuthError::TokenExpired | AuthError::RevokedToken => {
                 ApiError::Authentication("Invalid or expired token".to_string())
             }
             AuthError::InsufficientPermissions { .. } => {
@@ -194,7 +268,8 @@ impl From<AuthError> for ApiError {
 pub struct AuthService {
     config: AuthConfig,
     encoding_key: EncodingKey,
-    decoding_key: DecodingKey,
+    /// Clés de validation indexées par kid (courante et retirées)
+    decoding_keys: HashMap<String, DecodingKey>,
     validation: Validation,
 }
 
@@ -202,23 +277,36 @@ impl AuthService {
     /// Crée un nouveau service d'authentification
     pub fn new(config: AuthConfig) -> ApiResult<Self> {
         let encoding_key = EncodingKey::from_secret(config.jwt_secret.as_bytes());
-        let decoding_key = DecodingKey::from_secret(config.jwt_secret.as_bytes());
+        let mut decoding_keys: HashMap<String, DecodingKey> = config.previous_keys
+            .iter()
+            .map(|key| (key.kid.clone(), DecodingKey::from_secret(key.secret.as_bytes())))
+            .collect();
+        decoding_keys.insert(
+            config.key_id.clone(),
+            DecodingKey::from_secret(config.jwt_secret.as_bytes()),
+        );
         
         let mut validation = Validation::new(Algorithm::HS256);
         validation.set_issuer(&[&config.issuer]);
         validation.set_audience(&[&config.audience]);
+        // `exp` est vérifié par défaut ; `nbf` doit l'être explicitement
+        validation.validate_nbf = true;
         
         Ok(Self {
             config,
             encoding_key,
-            decoding_key,
+            decoding_keys,
             validation,
         })
     }
 
     /// Génère un token JWT pour un utilisateur
+    ///
+    /// Le refresh token associé est opaque et enregistré côté serveur dans
+    /// le `UserManager`; il n'est utilisable qu'une seule fois.
     pub fn generate_token(
         &self,
+        users: &mut UserManager,
         user_id: &str,
         scopes: Vec<ApiScope>,
         node_id: Option<String>,
@@ -228,6 +316,8 @@ impl AuthService {
             .duration_since(UNIX_EPOCH)
             .unwrap()
             .as_secs();
+        let role = users.get_user(user_id).and_then(|user| user.role.clone());
+        let rate_limit = self.with_scope_limits(rate_limit.unwrap_or_default(), role.as_deref());
 
         let claims = JwtClaims {
             sub: user_id.to_string(),
@@ -238,28 +328,32 @@ impl AuthService {
             nbf: now,
             jti: uuid::Uuid::new_v4().to_string(),
             scope: scopes.iter().map(|s| s.as_str().to_string()).collect(),
-            node_id,
-            rate_limit: rate_limit.unwrap_or_default(),
+            node_id: node_id.clone(),
+            rate_limit: rate_limit.clone(),
             user_metadata: HashMap::new(),
         };
 
-        let header = Header::new(Algorithm::HS256);
+        // Les nouveaux tokens sont toujours signés avec la clé courante
+        let mut header = Header::new(Algorithm::HS256);
+        header.kid = Some(self.config.key_id.clone());
         let token = encode(&header, &claims, &self.encoding_key)
             .map_err(|e| AuthError::TokenGenerationFailed(e.to_string()))?;
 
-        // Génère aussi un refresh token
-        let refresh_claims = JwtClaims {
-            exp: now + self.config.refresh_token_expiry,
-            ..claims
-        };
-
-        let refresh_token = encode(&header, &refresh_claims, &self.encoding_key)
-            .map_err(|e| AuthError::TokenGenerationFailed(e.to_string()))?;
+        let refresh_token = format!("rt_{}", uuid::Uuid::new_v4().simple());
+        users.store_refresh_token(refresh_token.clone(), RefreshTokenRecord {
+            user_id: user_id.to_string(),
+            scopes: scopes.clone(),
+            node_id,
+            rate_limit,
+            expires_at: now + self.config.refresh_token_expiry,
+            revoked: false,
+        });
 
         Ok(TokenInfo {
             token,
             refresh_token,
             expires_in: self.config.token_expiry,
+            refresh_expires_in: self.config.refresh_token_expiry,
             token_type: "Bearer".to_string(),
             scope: scopes.iter().map(|s| s.as_str().to_string()).collect(),
         })
@@ -267,10 +361,21 @@ impl AuthService {
 
     /// Valide un token JWT
     pub fn validate_token(&self, token: &str) -> ApiResult<JwtClaims> {
-        let token_data = decode::<JwtClaims>(token, &self.decoding_key, &self.validation)
+        let header = decode_header(token)
+            .map_err(|e| AuthError::InvalidToken(e.to_string()))?;
+
+        // Sans kid, le token est supposé signé par la clé courante
+        let kid = header.kid.unwrap_or_else(|| self.config.key_id.clone());
+        let decoding_key = self.decoding_keys.get(&kid)
+            .ok_or_else(|| AuthError::InvalidToken(format!("Unknown signing key: {}", kid)))?;
+
+        let token_data = decode::<JwtClaims>(token, decoding_key, &self.validation)
             .map_err(|e| {
                 match e.kind() {
                     jsonwebtoken::errors::ErrorKind::ExpiredSignature => AuthError::TokenExpired,
+                    jsonwebtoken::errors::ErrorKind::ImmatureSignature => {
+                        AuthError::InvalidToken("Token not yet valid".to_string())
+                    }
                     _ => AuthError::InvalidToken(e.to_string()),
                 }
             })?;
@@ -307,29 +412,122 @@ impl AuthService {
     }
 
     /// Rafraîchit un token
-    pub fn refresh_token(&self, refresh_token: &str) -> ApiResult<TokenInfo> {
-        let claims = self.validate_token(refresh_token)?;
-        
-        // Génère un nouveau token avec les mêmes permissions
-        let scopes: Vec<ApiScope> = claims.scope
-            .iter()
-            .filter_map(|s| ApiScope::from_str(s))
-            .collect();
+    ///
+    /// Le refresh token présenté est consommé : un nouveau couple
+    /// access/refresh token est émis avec les mêmes permissions.
+    pub fn refresh_token(&self, users: &mut UserManager, refresh_token: &str) -> ApiResult<TokenInfo> {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+
+        let record = users.consume_refresh_token(refresh_token, now)?;
+
+        if let Some(user) = users.get_user(&record.user_id) {
+            if !user.is_active {
+                return Err(AuthError::InvalidCredentials.into());
+            }
+        }
 
         self.generate_token(
-            &claims.sub,
-            scopes,
-            claims.node_id,
-            Some(claims.rate_limit),
+            users,
+            &record.user_id,
+            record.scopes,
+            record.node_id,
+            Some(record.rate_limit),
         )
     }
+
+    /// Complète les limites par scope d'un token avec celles de la configuration
+    ///
+    /// Par ordre de priorité : limite propre au token, limite du rôle, limite
+    /// globale. Aucune ne dépasse `requests_per_hour`.
+    fn with_scope_limits(&self, mut rate_limit: RateLimit, role: Option<&str>) -> RateLimit {
+        let role_limits = role.and_then(|role| self.config.role_scope_rate_limits.get(role));
+        for (scope, limit) in role_limits.into_iter().flatten().chain(&self.config.scope_rate_limits) {
+            rate_limit.scope_limits.entry(scope.clone()).or_insert(*limit);
+        }
+        let cap = rate_limit.requests_per_hour;
+        for limit in rate_limit.scope_limits.values_mut() {
+            *limit = (*limit).min(cap);
+        }
+        rate_limit
+    }
+
+    /// Construit les claims d'une requête authentifiée par clé API
+    ///
+    /// Les scopes sont ceux de la clé, et non ceux de son propriétaire ; les
+    /// limites par scope suivent le rôle du propriétaire.
+    pub fn claims_for_api_key(&self, key: &ApiKeyRecord, role: Option<&str>) -> JwtClaims {
+        let mut user_metadata = HashMap::new();
+        user_metadata.insert("api_key_id".to_string(), serde_json::Value::String(key.key_id.clone()));
+
+        JwtClaims {
+            sub: key.user_id.clone(),
+            iss: self.config.issuer.clone(),
+            aud: self.config.audience.clone(),
+            exp: key.expires_at.map_or(u64::MAX, |at| at.timestamp().max(0) as u64),
+            iat: key.created_at.timestamp().max(0) as u64,
+            nbf: key.created_at.timestamp().max(0) as u64,
+            jti: key.key_id.clone(),
+            scope: key.scopes.iter().map(|s| s.as_str().to_string()).collect(),
+            node_id: None,
+            rate_limit: self.with_scope_limits(RateLimit::default(), role),
+            user_metadata,
+        }
+    }
 }
 
 /// Gestionnaire d'utilisateurs et permissions
 #[derive(Debug)]
 pub struct UserManager {
     users: HashMap<String, UserAccount>,
-    api_keys: HashMap<String, String>, // api_key -> user_id
+    /// Clés API indexées par identifiant ; seul le hash du secret est conservé
+    api_keys: HashMap<String, ApiKeyRecord>,
+    refresh_tokens: HashMap<String, RefreshTokenRecord>,
+}
+
+/// Clé API émise pour un utilisateur
+///
+/// Une clé a la forme `arc_<key_id>_<secret>`. `arc_<key_id>` est le préfixe
+/// affiché dans les listes ; le secret n'est renvoyé qu'à la création.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiKeyRecord {
+    pub key_id: String,
+    pub user_id: String,
+    /// Sous-ensemble des scopes du propriétaire à la création
+    pub scopes: Vec<ApiScope>,
+    /// Limite propre à la clé, appliquée par le rate limiter du gateway
+    pub rate_limit: Option<KeyRateLimit>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub revoked: bool,
+    secret_hash: Hash,
+}
+
+impl ApiKeyRecord {
+    /// Préfixe public de la clé
+    pub fn prefix(&self) -> String {
+        format!("arc_{}", self.key_id)
+    }
+
+    /// Vérifie si la clé est expirée à la date donnée
+    pub fn is_expired(&self, now: chrono::DateTime<chrono::Utc>) -> bool {
+        self.expires_at.map_or(false, |expires_at| expires_at <= now)
+    }
+}
+
+/// Refresh token émis, conservé côté serveur
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RefreshTokenRecord {
+    pub user_id: String,
+    pub scopes: Vec<ApiScope>,
+    pub node_id: Option<String>,
+    pub rate_limit: RateLimit,
+    /// Expiration (timestamp Unix en secondes)
+    pub expires_at: u64,
+    /// Consommé par un rafraîchissement ou révoqué
+    pub revoked: bool,
 }
 
 /// Compte utilisateur
@@ -343,6 +541,9 @@ pub struct UserAccount {
     pub created_at: chrono::DateTime<chrono::Utc>,
     pub last_login: Option<chrono::DateTime<chrono::Utc>>,
     pub is_active: bool,
+    /// Rôle choisissant les limites par scope (`AuthConfig::role_scope_rate_limits`)
+    #[serde(default)]
+    pub role: Option<String>,
     pub metadata: HashMap<String, serde_json::Value>,
 }
 
@@ -351,6 +552,7 @@ impl UserManager {
         Self {
             users: HashMap::new(),
             api_keys: HashMap::new(),
+            refresh_tokens: HashMap::new(),
         }
     }
 
@@ -375,15 +577,15 @@ impl UserManager {
             created_at: chrono::Utc::now(),
             last_login: None,
             is_active: true,
+            role: None,
             metadata: HashMap::new(),
         };
 
+        let scopes = account.scopes.iter().cloned().collect();
         self.users.insert(user_id.clone(), account);
 
-        // Génère une API key
-        let api_key = format!("arc_{}", uuid::Uuid::new_v4().simple());
-        self.api_keys.insert(api_key.clone(), user_id);
-
+        // Génère une API key portant tous les scopes de l'utilisateur
+        let (api_key, _) = self.create_api_key(&user_id, scopes, None, None)?;
         Ok(api_key)
     }
 
@@ -394,8 +596,82 @@ impl UserManager {
 
     /// Récupère un utilisateur par API key
     pub fn get_user_by_api_key(&self, api_key: &str) -> Option<&UserAccount> {
-        self.api_keys.get(api_key)
-            .and_then(|user_id| self.users.get(user_id))
+        self.authenticate_api_key(api_key, chrono::Utc::now())
+            .ok()
+            .and_then(|key| self.users.get(&key.user_id))
+    }
+
+    /// Émet une clé API ; la clé complète n'est renvoyée qu'ici
+    pub fn create_api_key(
+        &mut self,
+        user_id: &str,
+        scopes: Vec<ApiScope>,
+        expires_at: Option<chrono::DateTime<chrono::Utc>>,
+        rate_limit: Option<KeyRateLimit>,
+    ) -> ApiResult<(String, ApiKeyRecord)> {
+        if scopes.is_empty() {
+            return Err(ApiError::validation("An API key needs at least one scope"));
+        }
+        let now = chrono::Utc::now();
+        if expires_at.map_or(false, |expires_at| expires_at <= now) {
+            return Err(ApiError::validation("API key expiry must be in the future"));
+        }
+
+        let key_id = uuid::Uuid::new_v4().simple().to_string()[..12].to_string();
+        let secret = uuid::Uuid::new_v4().simple().to_string();
+        let record = ApiKeyRecord {
+            key_id: key_id.clone(),
+            user_id: user_id.to_string(),
+            scopes,
+            rate_limit,
+            created_at: now,
+            expires_at,
+            revoked: false,
+            secret_hash: compute_blake3(secret.as_bytes()),
+        };
+
+        let api_key = format!("{}_{}", record.prefix(), secret);
+        self.api_keys.insert(key_id, record.clone());
+        Ok((api_key, record))
+    }
+
+    /// Authentifie une clé API présentée dans `X-API-Key`
+    pub fn authenticate_api_key(&self, api_key: &str, now: chrono::DateTime<chrono::Utc>) -> Result<&ApiKeyRecord, AuthError> {
+        let (key_id, secret) = api_key.strip_prefix("arc_")
+            .and_then(|rest| rest.split_once('_'))
+            .ok_or(AuthError::InvalidCredentials)?;
+        let record = self.api_keys.get(key_id)
+            .filter(|record| record.secret_hash == compute_blake3(secret.as_bytes()))
+            .ok_or(AuthError::InvalidCredentials)?;
+
+        if record.revoked {
+            return Err(AuthError::RevokedToken);
+        }
+        if record.is_expired(now) {
+            return Err(AuthError::TokenExpired);
+        }
+        if self.users.get(&record.user_id).map_or(false, |user| !user.is_active) {
+            return Err(AuthError::InvalidCredentials);
+        }
+        Ok(record)
+    }
+
+    /// Clés API d'un utilisateur, des plus anciennes aux plus récentes
+    pub fn list_api_keys(&self, user_id: &str) -> Vec<&ApiKeyRecord> {
+        let mut keys: Vec<&ApiKeyRecord> = self.api_keys.values()
+            .filter(|record| record.user_id == user_id)
+            .collect();
+        keys.sort_by(|a, b| a.created_at.cmp(&b.created_at).then_with(|| a.key_id.cmp(&b.key_id)));
+        keys
+    }
+
+    /// Révoque une clé API de l'utilisateur
+    pub fn revoke_api_key(&mut self, user_id: &str, key_id: &str) -> ApiResult<ApiKeyRecord> {
+        let record = self.api_keys.get_mut(key_id)
+            .filter(|record| record.user_id == user_id)
+            .ok_or_else(|| ApiError::not_found(format!("API key {} not found", key_id)))?;
+        record.revoked = true;
+        Ok(record.clone())
     }
 
     /// Met à jour la dernière connexion
@@ -405,22 +681,137 @@ impl UserManager {
         }
     }
 
+    /// Attribue un rôle à un utilisateur, pris en compte aux prochains tokens
+    pub fn set_role(&mut self, user_id: &str, role: Option<String>) -> ApiResult<()> {
+        match self.users.get_mut(user_id) {
+            Some(user) => {
+                user.role = role;
+                Ok(())
+            }
+            None => Err(AuthError::UserNotFound(user_id.to_string()).into()),
+        }
+    }
+
     /// Désactive un utilisateur
     pub fn deactivate_user(&mut self, user_id: &str) -> ApiResult<()> {
         match self.users.get_mut(user_id) {
             Some(user) => {
                 user.is_active = false;
+                self.revoke_user_refresh_tokens(user_id);
+                for record in self.api_keys.values_mut().filter(|record| record.user_id == user_id) {
+                    record.revoked = true;
+                }
                 Ok(())
             }
             None => Err(AuthError::UserNotFound(user_id.to_string()).into()),
         }
     }
+
+    /// Enregistre un refresh token émis
+    pub fn store_refresh_token(&mut self, token: String, record: RefreshTokenRecord) {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        self.refresh_tokens.retain(|_, existing| existing.expires_at > now);
+        self.refresh_tokens.insert(token, record);
+    }
+
+    /// Consomme un refresh token (usage unique)
+    ///
+    /// La réutilisation d'un token déjà consommé révoque tous les refresh
+    /// tokens de l'utilisateur : le token a probablement fuité.
+    pub fn consume_refresh_token(&mut self, token: &str, now: u64) -> Result<RefreshTokenRecord, AuthError> {
+        let record = self.refresh_tokens.get_mut(token)
+            .ok_or_else(|| AuthError::InvalidToken("Unknown refresh token".to_string()))?;
+
+        if record.revoked {
+            let user_id = record.user_id.clone();
+            self.revoke_user_refresh_tokens(&user_id);
+            return Err(AuthError::RevokedToken);
+        }
+
+        if record.expires_at <= now {
+            self.refresh_tokens.remove(token);
+            return Err(AuthError::TokenExpired);
+        }
+
+        record.revoked = true;
+        Ok(record.clone())
+    }
+
+    /// Révoque un refresh token
+    ///
+    /// L'entrée révoquée est conservée jusqu'à son expiration : sa
+    /// présentation ultérieure révoque toute la famille de l'utilisateur.
+    pub fn revoke_refresh_token(&mut self, token: &str) -> bool {
+        match self.refresh_tokens.get_mut(token) {
+            Some(record) => {
+                record.revoked = true;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Révoque tous les refresh tokens d'un utilisateur
+    pub fn revoke_user_refresh_tokens(&mut self, user_id: &str) -> usize {
+        let mut revoked = 0;
+        for record in self.refresh_tokens.values_mut() {
+            if record.user_id == user_id && !record.revoked {
+                record.revoked = true;
+                revoked += 1;
+            }
+        }
+        revoked
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
 
+    #[test]
+    fn test_role_scope_limits() {
+        let mut config = AuthConfig::default();
+        config.role_scope_rate_limits.insert(
+            "archiver".to_string(),
+            HashMap::from([(ApiScope::ArchivesWrite, 800), (ApiScope::ArchivesRead, 50_000)]),
+        );
+        let auth_service = AuthService::new(config).unwrap();
+        let mut users = UserManager::new();
+        let api_key = users.create_user("bob".to_string(), None, HashSet::new(), None).unwrap();
+
+        // Sans rôle : limites globales
+        let claims = auth_service.validate_token(
+            &auth_service.generate_token(&mut users, "bob", vec![], None, None).unwrap().token,
+        ).unwrap();
+        assert_eq!(claims.rate_limit.scope_limit(&ApiScope::ArchivesWrite), Some(100));
+
+        // Avec rôle : limites du rôle, bornées par la limite générale
+        users.set_role("bob", Some("archiver".to_string())).unwrap();
+        let claims = auth_service.validate_token(
+            &auth_service.generate_token(&mut users, "bob", vec![], None, None).unwrap().token,
+        ).unwrap();
+        assert_eq!(claims.rate_limit.scope_limit(&ApiScope::ArchivesWrite), Some(800));
+        assert_eq!(claims.rate_limit.scope_limit(&ApiScope::ArchivesRead), Some(1000));
+
+        // Une limite propre au token prime sur celle du rôle
+        let token_limit = RateLimit {
+            scope_limits: HashMap::from([(ApiScope::ArchivesWrite, 10)]),
+            ..RateLimit::default()
+        };
+        let claims = auth_service.validate_token(
+            &auth_service.generate_token(&mut users, "bob", vec![], None, Some(token_limit)).unwrap().token,
+        ).unwrap();
+        assert_eq!(claims.rate_limit.scope_limit(&ApiScope::ArchivesWrite), Some(10));
+
+        // Les clés API suivent le rôle de leur propriétaire
+        let key = users.authenticate_api_key(&api_key, chrono::Utc::now()).unwrap().clone();
+        let claims = auth_service.claims_for_api_key(&key, Some("archiver"));
+        assert_eq!(claims.rate_limit.scope_limit(&ApiScope::ArchivesWrite), Some(800));
+    }
+
     #[test]
     fn test_auth_config_default() {
         let config = AuthConfig::default();
@@ -468,13 +859,37 @@ mod tests {
         assert_eq!(user_by_key.user_id, "test_user");
     }
 
+    #[test]
+    fn test_api_keys_are_hashed_scoped_and_revocable() {
+        let mut manager = UserManager::new();
+        let expires_at = chrono::Utc::now() + chrono::Duration::hours(1);
+        let (api_key, record) = manager.create_api_key("alice", vec![ApiScope::ArchivesRead], Some(expires_at), None).unwrap();
+
+        assert!(api_key.starts_with(&format!("{}_", record.prefix())));
+        let stored = serde_json::to_string(manager.list_api_keys("alice")[0]).unwrap();
+        assert!(!stored.contains(api_key.rsplit('_').next().unwrap()));
+
+        let now = chrono::Utc::now();
+        assert_eq!(manager.authenticate_api_key(&api_key, now).unwrap().scopes, vec![ApiScope::ArchivesRead]);
+        assert!(matches!(manager.authenticate_api_key(&format!("{}x", api_key), now), Err(AuthError::InvalidCredentials)));
+        assert!(matches!(manager.authenticate_api_key(&api_key, expires_at), Err(AuthError::TokenExpired)));
+
+        assert!(manager.revoke_api_key("mallory", &record.key_id).is_err());
+        manager.revoke_api_key("alice", &record.key_id).unwrap();
+        assert!(matches!(manager.authenticate_api_key(&api_key, now), Err(AuthError::RevokedToken)));
+
+        assert!(manager.create_api_key("alice", vec![], None, None).is_err());
+    }
+
     #[tokio::test]
     async fn test_jwt_generation_and_validation() {
         let config = AuthConfig::default();
         let auth_service = AuthService::new(config).unwrap();
+        let mut users = UserManager::new();
 
         let scopes = vec![ApiScope::ArchivesRead, ApiScope::SearchRead];
         let token_info = auth_service.generate_token(
+            &mut users,
             "test_user",
             scopes.clone(),
             None,
@@ -508,4 +923,132 @@ mod tests {
         let result = auth_service.extract_token_from_header("Basic token123");
         assert!(result.is_err());
     }
+
+    #[test]
+    fn test_refresh_token_rotation() {
+        let auth_service = AuthService::new(AuthConfig::default()).unwrap();
+        let mut users = UserManager::new();
+
+        let initial = auth_service.generate_token(
+            &mut users,
+            "test_user",
+            vec![ApiScope::ArchivesRead],
+            None,
+            None,
+        ).unwrap();
+        assert!(initial.refresh_token.starts_with("rt_"));
+
+        let refreshed = auth_service.refresh_token(&mut users, &initial.refresh_token).unwrap();
+        assert_ne!(refreshed.refresh_token, initial.refresh_token);
+
+        let claims = auth_service.validate_token(&refreshed.token).unwrap();
+        assert_eq!(claims.sub, "test_user");
+        assert_eq!(claims.scope, vec!["archives:read".to_string()]);
+
+        // Le nouveau refresh token est à son tour utilisable
+        assert!(auth_service.refresh_token(&mut users, &refreshed.refresh_token).is_ok());
+    }
+
+    #[test]
+    fn test_consumed_refresh_token_is_rejected() {
+        let auth_service = AuthService::new(AuthConfig::default()).unwrap();
+        let mut users = UserManager::new();
+
+        let initial = auth_service.generate_token(
+            &mut users,
+            "test_user",
+            vec![ApiScope::ArchivesRead],
+            None,
+            None,
+        ).unwrap();
+        let refreshed = auth_service.refresh_token(&mut users, &initial.refresh_token).unwrap();
+
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        assert!(matches!(
+            users.consume_refresh_token(&initial.refresh_token, now),
+            Err(AuthError::RevokedToken)
+        ));
+
+        // La réutilisation révoque toute la famille de tokens
+        assert!(auth_service.refresh_token(&mut users, &refreshed.refresh_token).is_err());
+        assert!(matches!(
+            users.consume_refresh_token("rt_unknown", now),
+            Err(AuthError::InvalidToken(_))
+        ));
+    }
+
+    #[test]
+    fn test_revoked_refresh_token_is_rejected() {
+        let auth_service = AuthService::new(AuthConfig::default()).unwrap();
+        let mut users = UserManager::new();
+
+        let info = auth_service.generate_token(&mut users, "test_user", vec![], None, None).unwrap();
+        assert!(users.revoke_refresh_token(&info.refresh_token));
+        assert!(auth_service.refresh_token(&mut users, &info.refresh_token).is_err());
+    }
+
+    #[test]
+    fn test_not_yet_valid_token_is_rejected() {
+        let auth_service = AuthService::new(AuthConfig::default()).unwrap();
+        let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+        let claims = JwtClaims {
+            sub: "test_user".to_string(),
+            iss: auth_service.config.issuer.clone(),
+            aud: auth_service.config.audience.clone(),
+            exp: now + 7200,
+            iat: now,
+            nbf: now + 3600,
+            jti: "future".to_string(),
+            scope: vec![],
+            node_id: None,
+            rate_limit: RateLimit::default(),
+            user_metadata: HashMap::new(),
+        };
+        let encode_claims = |claims: &JwtClaims| {
+            let mut header = Header::new(Algorithm::HS256);
+            header.kid = Some(auth_service.config.key_id.clone());
+            encode(&header, claims, &auth_service.encoding_key).unwrap()
+        };
+
+        assert!(matches!(
+            auth_service.validate_token(&encode_claims(&claims)),
+            Err(ApiError::Authentication(_))
+        ));
+
+        let valid = JwtClaims { nbf: now, ..claims.clone() };
+        assert!(auth_service.validate_token(&encode_claims(&valid)).is_ok());
+
+        let expired = JwtClaims { nbf: now - 7200, exp: now - 3600, ..claims };
+        assert!(auth_service.validate_token(&encode_claims(&expired)).is_err());
+    }
+
+    #[test]
+    fn test_token_validation_across_key_rotation() {
+        let mut config = AuthConfig::default();
+        let old_service = AuthService::new(config.clone()).unwrap();
+        let mut users = UserManager::new();
+
+        let old_token = old_service.generate_token(
+            &mut users, "test_user", vec![ApiScope::SearchRead], None, None,
+        ).unwrap().token;
+
+        config.rotate_key("k2", "rotated-secret");
+        let rotated_service = AuthService::new(config.clone()).unwrap();
+
+        // Un token signé par la clé retirée reste valide jusqu'à expiration
+        assert_eq!(rotated_service.validate_token(&old_token).unwrap().sub, "test_user");
+
+        // Les nouveaux tokens utilisent la clé courante
+        let new_token = rotated_service.generate_token(
+            &mut users, "test_user", vec![ApiScope::SearchRead], None, None,
+        ).unwrap().token;
+        assert_eq!(decode_header(&new_token).unwrap().kid.as_deref(), Some("k2"));
+        assert!(old_service.validate_token(&new_token).is_err());
+
+        // Une fois la clé abandonnée, ses tokens sont refusés
+        config.drop_previous_key("k1");
+        let pruned_service = AuthService::new(config).unwrap();
+        assert!(pruned_service.validate_token(&old_token).is_err());
+        assert!(pruned_service.validate_token(&new_token).is_ok());
+    }
 }
\ No newline at end of file
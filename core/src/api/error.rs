@@ -1,15 +1,24 @@
 //! Gestion des erreurs pour l'API ArchiveChain
+//!
+//! Les erreurs REST sont rendues en documents RFC 7807
+//! (`application/problem+json`) : chaque variante d'[`ApiError`] a un `type`
+//! et un `code` stables, les erreurs de validation par champ sont détaillées
+//! dans `errors` et `request_id` permet de retrouver la requête dans les logs.
 
 use axum::{
-    http::StatusCode,
+    http::{header, StatusCode},
     response::{IntoResponse, Response},
-    Json,
 };
-use serde_json::json;
 
 /// Type de résultat pour les opérations API
 pub type ApiResult<T> = Result<T, ApiError>;
 
+/// Type de contenu des réponses d'erreur
+pub const PROBLEM_JSON_CONTENT_TYPE: &str = "application/problem+json";
+
+/// Préfixe des URI `type` des documents d'erreur
+pub const PROBLEM_TYPE_BASE: &str = "https://archivechain.org/problems/";
+
 /// Erreurs spécifiques à l'API
 #[derive(Debug, thiserror::Error)]
 pub enum ApiError {
@@ -25,14 +34,30 @@ pub enum ApiError {
     #[error("Validation failed: {0}")]
     Validation(String),
 
+    /// Erreurs de validation par champ, toutes rapportées ensemble
+    #[error("Request validation failed")]
+    InvalidFields(Vec<ValidationError>),
+
+    /// Contenu dépassant la taille maximale acceptée par le nœud
+    #[error("Content too large: {size} bytes (limit {limit})")]
+    ContentTooLarge { size: u64, limit: u64 },
+
     /// Ressource non trouvée
     #[error("Resource not found: {0}")]
     NotFound(String),
 
+    /// Donnée élaguée par ce nœud, à demander à un nœud d'archive
+    #[error("Pruned data: {0}")]
+    PrunedData(String),
+
     /// Conflit de ressource
     #[error("Resource conflict: {0}")]
     Conflict(String),
 
+    /// Aucun format de réponse acceptable par le client
+    #[error("Not acceptable: {0}")]
+    NotAcceptable(String),
+
     /// Limite de taux dépassée
     #[error("Rate limit exceeded")]
     RateLimit,
@@ -84,9 +109,12 @@ impl ApiError {
         match self {
             ApiError::Authentication(_) => StatusCode::UNAUTHORIZED,
             ApiError::Authorization(_) => StatusCode::FORBIDDEN,
-            ApiError::Validation(_) => StatusCode::BAD_REQUEST,
+            ApiError::Validation(_) | ApiError::InvalidFields(_) => StatusCode::BAD_REQUEST,
+            ApiError::ContentTooLarge { .. } => StatusCode::PAYLOAD_TOO_LARGE,
             ApiError::NotFound(_) => StatusCode::NOT_FOUND,
+            ApiError::PrunedData(_) => StatusCode::GONE,
             ApiError::Conflict(_) => StatusCode::CONFLICT,
+            ApiError::NotAcceptable(_) => StatusCode::NOT_ACCEPTABLE,
             ApiError::RateLimit => StatusCode::TOO_MANY_REQUESTS,
             ApiError::Serialization(_) => StatusCode::UNPROCESSABLE_ENTITY,
             ApiError::ServiceUnavailable(_) => StatusCode::SERVICE_UNAVAILABLE,
@@ -106,9 +134,12 @@ impl ApiError {
         match self {
             ApiError::Authentication(_) => "AUTHENTICATION_FAILED",
             ApiError::Authorization(_) => "AUTHORIZATION_FAILED",
-            ApiError::Validation(_) => "VALIDATION_FAILED",
+            ApiError::Validation(_) | ApiError::InvalidFields(_) => "VALIDATION_FAILED",
+            ApiError::ContentTooLarge { .. } => "CONTENT_TOO_LARGE",
             ApiError::NotFound(_) => "RESOURCE_NOT_FOUND",
+            ApiError::PrunedData(_) => "PRUNED_DATA",
             ApiError::Conflict(_) => "RESOURCE_CONFLICT",
+            ApiError::NotAcceptable(_) => "NOT_ACCEPTABLE",
             ApiError::RateLimit => "RATE_LIMIT_EXCEEDED",
             ApiError::Serialization(_) => "SERIALIZATION_ERROR",
             ApiError::Internal(_) => "INTERNAL_SERVER_ERROR",
@@ -135,6 +166,84 @@ impl ApiError {
             | ApiError::P2P(_)
         )
     }
+
+    /// URI `type` du document d'erreur, dérivée du code (ex: `.../rate-limit-exceeded`)
+    pub fn problem_type(&self) -> String {
+        format!("{}{}", PROBLEM_TYPE_BASE, self.error_code().to_ascii_lowercase().replace('_', "-"))
+    }
+
+    /// Document RFC 7807 décrivant l'erreur
+    pub fn problem(&self, request_id: Option<String>) -> ProblemDetails {
+        let status = self.status_code();
+        ProblemDetails {
+            problem_type: self.problem_type(),
+            title: status.canonical_reason().unwrap_or("Error").to_string(),
+            status: status.as_u16(),
+            detail: self.to_string(),
+            code: self.error_code().to_string(),
+            request_id,
+            errors: match self {
+                ApiError::InvalidFields(errors) => errors.clone(),
+                _ => Vec::new(),
+            },
+            size: match self {
+                ApiError::ContentTooLarge { size, .. } => Some(*size),
+                _ => None,
+            },
+            limit: match self {
+                ApiError::ContentTooLarge { limit, .. } => Some(*limit),
+                _ => None,
+            },
+        }
+    }
+}
+
+/// Document d'erreur RFC 7807
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct ProblemDetails {
+    /// URI identifiant le type d'erreur, stable d'une version à l'autre
+    #[serde(rename = "type")]
+    pub problem_type: String,
+    /// Libellé du statut HTTP
+    pub title: String,
+    /// Statut HTTP
+    pub status: u16,
+    /// Description propre à cette occurrence
+    pub detail: String,
+    /// Code d'erreur machine (ex: `RESOURCE_NOT_FOUND`)
+    pub code: String,
+    /// Identifiant de corrélation de la requête
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+    /// Erreurs par champ pour les échecs de validation
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<ValidationError>,
+    /// Taille soumise, pour les contenus trop volumineux
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size: Option<u64>,
+    /// Limite appliquée par le nœud, pour les contenus trop volumineux
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub limit: Option<u64>,
+}
+
+impl From<crate::error::ContentError> for ApiError {
+    fn from(error: crate::error::ContentError) -> Self {
+        use crate::error::ContentError;
+
+        let message = error.to_string();
+        match error {
+            ContentError::ContentTooLarge { size, limit } => ApiError::ContentTooLarge { size, limit },
+            ContentError::MetadataTooLarge { .. } => ApiError::InvalidFields(vec![
+                ValidationError::new("metadata", message).with_code("too_large"),
+            ]),
+            ContentError::TooManyTags { .. } => ApiError::InvalidFields(vec![
+                ValidationError::new("metadata.tags", message).with_code("too_many"),
+            ]),
+            ContentError::TagTooLong { .. } => ApiError::InvalidFields(vec![
+                ValidationError::new("metadata.tags", message).with_code("too_long"),
+            ]),
+        }
+    }
 }
 
 impl IntoResponse for ApiError {
@@ -150,22 +259,20 @@ impl IntoResponse for ApiError {
             tracing::warn!("API error: {} - {}", error_code, message);
         }
 
-        let body = json!({
-            "error": {
-                "code": error_code,
-                "message": message,
-                "timestamp": chrono::Utc::now().to_rfc3339(),
-            }
-        });
+        let problem = self.problem(crate::api::middleware::current_request_id());
+        let body = serde_json::to_vec(&problem).unwrap_or_default();
 
-        (status, Json(body)).into_response()
+        (status, [(header::CONTENT_TYPE, PROBLEM_JSON_CONTENT_TYPE)], body).into_response()
     }
 }
 
 /// Erreur de validation avec détails
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct ValidationError {
     pub field: String,
+    /// Code machine de l'erreur (ex: `invalid_format`)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
     pub message: String,
 }
 
@@ -173,9 +280,15 @@ impl ValidationError {
     pub fn new(field: impl Into<String>, message: impl Into<String>) -> Self {
         Self {
             field: field.into(),
+            code: None,
             message: message.into(),
         }
     }
+
+    pub fn with_code(mut self, code: impl Into<String>) -> Self {
+        self.code = Some(code.into());
+        self
+    }
 }
 
 /// Réponse d'erreur détaillée pour la validation
@@ -200,7 +313,7 @@ impl ValidationErrorResponse {
 
 impl IntoResponse for ValidationErrorResponse {
     fn into_response(self) -> Response {
-        (StatusCode::BAD_REQUEST, Json(self)).into_response()
+        ApiError::InvalidFields(self.errors).into_response()
     }
 }
 
@@ -218,14 +331,26 @@ impl ApiError {
         Self::Validation(msg.into())
     }
 
+    pub fn invalid_fields(errors: Vec<ValidationError>) -> Self {
+        Self::InvalidFields(errors)
+    }
+
     pub fn not_found<S: Into<String>>(resource: S) -> Self {
         Self::NotFound(resource.into())
     }
 
+    pub fn pruned_data<S: Into<String>>(resource: S) -> Self {
+        Self::PrunedData(resource.into())
+    }
+
     pub fn conflict<S: Into<String>>(msg: S) -> Self {
         Self::Conflict(msg.into())
     }
 
+    pub fn not_acceptable<S: Into<String>>(msg: S) -> Self {
+        Self::NotAcceptable(msg.into())
+    }
+
     pub fn internal<S: Into<String>>(msg: S) -> Self {
         Self::Internal(msg.into())
     }
@@ -245,7 +370,9 @@ mod tests {
         assert_eq!(ApiError::authorization("test").status_code(), StatusCode::FORBIDDEN);
         assert_eq!(ApiError::validation("test").status_code(), StatusCode::BAD_REQUEST);
         assert_eq!(ApiError::not_found("test").status_code(), StatusCode::NOT_FOUND);
+        assert_eq!(ApiError::pruned_data("test").status_code(), StatusCode::GONE);
         assert_eq!(ApiError::conflict("test").status_code(), StatusCode::CONFLICT);
+        assert_eq!(ApiError::not_acceptable("test").status_code(), StatusCode::NOT_ACCEPTABLE);
         assert_eq!(ApiError::RateLimit.status_code(), StatusCode::TOO_MANY_REQUESTS);
     }
 
@@ -254,6 +381,7 @@ mod tests {
         assert_eq!(ApiError::authentication("test").error_code(), "AUTHENTICATION_FAILED");
         assert_eq!(ApiError::validation("test").error_code(), "VALIDATION_FAILED");
         assert_eq!(ApiError::not_found("test").error_code(), "RESOURCE_NOT_FOUND");
+        assert_eq!(ApiError::pruned_data("test").error_code(), "PRUNED_DATA");
     }
 
     #[test]
@@ -281,4 +409,85 @@ mod tests {
         assert_eq!(response.code, "VALIDATION_FAILED");
         assert_eq!(response.errors.len(), 2);
     }
+
+    async fn problem_json(error: ApiError) -> (StatusCode, String, serde_json::Value) {
+        let response = crate::api::middleware::with_request_id("req-42".to_string(), async { error.into_response() }).await;
+        let status = response.status();
+        let content_type = response.headers()[header::CONTENT_TYPE].to_str().unwrap().to_string();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, content_type, serde_json::from_slice(&body).unwrap())
+    }
+
+    #[tokio::test]
+    async fn test_problem_json_shapes() {
+        let (status, content_type, body) = problem_json(ApiError::authentication("Invalid token")).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert_eq!(content_type, PROBLEM_JSON_CONTENT_TYPE);
+        assert_eq!(body, serde_json::json!({
+            "type": "https://archivechain.org/problems/authentication-failed",
+            "title": "Unauthorized",
+            "status": 401,
+            "detail": "Authentication failed: Invalid token",
+            "code": "AUTHENTICATION_FAILED",
+            "request_id": "req-42",
+        }));
+
+        let (_, _, body) = problem_json(ApiError::not_found("Archive arc_123 not found")).await;
+        assert_eq!(body, serde_json::json!({
+            "type": "https://archivechain.org/problems/resource-not-found",
+            "title": "Not Found",
+            "status": 404,
+            "detail": "Resource not found: Archive arc_123 not found",
+            "code": "RESOURCE_NOT_FOUND",
+            "request_id": "req-42",
+        }));
+
+        let (status, _, body) = problem_json(ApiError::ContentTooLarge { size: 2048, limit: 1024 }).await;
+        assert_eq!(status, StatusCode::PAYLOAD_TOO_LARGE);
+        assert_eq!(body, serde_json::json!({
+            "type": "https://archivechain.org/problems/content-too-large",
+            "title": "Payload Too Large",
+            "status": 413,
+            "detail": "Content too large: 2048 bytes (limit 1024)",
+            "code": "CONTENT_TOO_LARGE",
+            "request_id": "req-42",
+            "size": 2048,
+            "limit": 1024,
+        }));
+
+        let (_, _, body) = problem_json(ApiError::RateLimit).await;
+        assert_eq!(body, serde_json::json!({
+            "type": "https://archivechain.org/problems/rate-limit-exceeded",
+            "title": "Too Many Requests",
+            "status": 429,
+            "detail": "Rate limit exceeded",
+            "code": "RATE_LIMIT_EXCEEDED",
+            "request_id": "req-42",
+        }));
+    }
+
+    #[tokio::test]
+    async fn test_problem_json_lists_field_errors() {
+        let error = ApiError::invalid_fields(vec![
+            ValidationError::new("url", "Invalid URL format").with_code("invalid_format"),
+            ValidationError::new("limit", "Limit cannot exceed 100"),
+        ]);
+        let (status, _, body) = problem_json(error).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        assert_eq!(body["code"], "VALIDATION_FAILED");
+        assert_eq!(body["errors"], serde_json::json!([
+            { "field": "url", "code": "invalid_format", "message": "Invalid URL format" },
+            { "field": "limit", "message": "Limit cannot exceed 100" },
+        ]));
+
+        let error = ApiError::from(crate::error::ContentError::TooManyTags { count: 51, limit: 50 });
+        let (_, _, body) = problem_json(error).await;
+        assert_eq!(body["errors"][0]["field"], "metadata.tags");
+        assert_eq!(body["errors"][0]["code"], "too_many");
+
+        // Hors de toute requête, pas d'identifiant de corrélation
+        let problem = ApiError::validation("bad").problem(crate::api::middleware::current_request_id());
+        assert_eq!(problem.request_id, None);
+        assert!(problem.errors.is_empty());
+    }
 }
\ No newline at end of file
@@ -9,7 +9,7 @@ pub mod types;
 pub mod subscriptions;
 
 use axum::{
-    extract::State,
+    extract::{State, WebSocketUpgrade},
     response::{Html, IntoResponse},
     routing::get,
     Router,
@@ -18,7 +18,7 @@ use async_graphql::{
     http::{playground_source, GraphQLPlaygroundConfig},
     Schema, EmptySubscription, ErrorExtensions,
 };
-use async_graphql_axum::{GraphQLRequest, GraphQLResponse, GraphQLSubscription};
+use async_graphql_axum::{GraphQLProtocol, GraphQLRequest, GraphQLResponse, GraphQLWebSocket};
 use serde::{Deserialize, Serialize};
 
 use crate::api::{ApiResult, server::ServerState};
@@ -115,15 +115,49 @@ async fn graphql_schema(
 }
 
 /// Handler pour les subscriptions WebSocket
+///
+/// Le client s'authentifie dans le payload de `connection_init` (voir
+/// `subscription_context`) ; les scopes sont vérifiés par chaque subscription.
 async fn graphql_subscription_handler(
     State(schema): State<ArchiveChainSchema>,
     State(server_state): State<ServerState>,
-    ws: axum::extract::WebSocketUpgrade,
+    protocol: GraphQLProtocol,
+    ws: WebSocketUpgrade,
 ) -> impl IntoResponse {
-    ws.on_upgrade(move |socket| {
-        GraphQLSubscription::new(socket, schema, async_graphql::http::ALL_WEBSOCKET_PROTOCOLS)
-            .serve()
-    })
+    ws.protocols(async_graphql::http::ALL_WEBSOCKET_PROTOCOLS)
+        .on_upgrade(move |socket| {
+            GraphQLWebSocket::new(socket, schema, protocol)
+                .on_connection_init(move |payload| async move {
+                    let mut data = async_graphql::Data::default();
+                    data.insert(subscription_context(server_state, &payload)?);
+                    Ok(data)
+                })
+                .serve()
+        })
+}
+
+/// Contexte d'une connexion de subscriptions
+///
+/// Le jeton est lu dans le champ `Authorization` (`Bearer <jwt>`) du payload
+/// de `connection_init`. Sans jeton, la connexion est anonyme ; un jeton
+/// invalide la refuse.
+pub fn subscription_context(server_state: ServerState, payload: &serde_json::Value) -> async_graphql::Result<GraphQLContext> {
+    let header = ["Authorization", "authorization"]
+        .iter()
+        .find_map(|key| payload.get(key).and_then(|value| value.as_str()));
+    let Some(header) = header else {
+        return Ok(GraphQLContext::new(server_state, None));
+    };
+
+    let claims = server_state.auth_service.extract_token_from_header(header).map_err(|e| {
+        async_graphql::Error::new(e.to_string()).extend_with(|_, e| e.set("code", "UNAUTHENTICATED"))
+    })?;
+    let auth_info = crate::api::middleware::AuthInfo {
+        user_id: claims.sub.clone(),
+        scopes: claims.scope.iter().filter_map(|s| crate::api::auth::ApiScope::from_str(s)).collect(),
+        claims,
+    };
+    Ok(GraphQLContext::new(server_state, Some(auth_info)))
 }
 
 /// Extensions GraphQL pour le contexte
@@ -234,4 +268,180 @@ mod tests {
         // Test que l'authentification est requise
         assert!(context.require_auth().is_err());
     }
-}
\ No newline at end of file
+
+    fn test_state() -> ServerState {
+        use crate::api::auth::AuthConfig;
+        use crate::{Blockchain, BlockchainConfig};
+        use std::sync::Arc;
+
+        let blockchain = Arc::new(Blockchain::new(BlockchainConfig::default()).unwrap());
+        let auth_service = Arc::new(crate::api::auth::AuthService::new(AuthConfig::default()).unwrap());
+        let user_manager = Arc::new(tokio::sync::RwLock::new(crate::api::auth::UserManager::new()));
+
+        ServerState::new(blockchain, auth_service, user_manager, crate::api::ApiConfig::default())
+    }
+
+    fn auth_with(scopes: Vec<crate::api::auth::ApiScope>) -> crate::api::middleware::AuthInfo {
+        let claims = crate::api::auth::JwtClaims {
+            sub: "user123".to_string(),
+            iss: "archivechain".to_string(),
+            aud: "archivechain-api".to_string(),
+            exp: u64::MAX,
+            iat: 0,
+            nbf: 0,
+            jti: "test".to_string(),
+            scope: scopes.iter().map(|s| s.as_str().to_string()).collect(),
+            node_id: None,
+            rate_limit: Default::default(),
+            user_metadata: Default::default(),
+        };
+
+        crate::api::middleware::AuthInfo {
+            claims,
+            user_id: "user123".to_string(),
+            scopes,
+        }
+    }
+
+    async fn execute(state: &ServerState, auth: Option<crate::api::middleware::AuthInfo>, query: &str) -> async_graphql::Response {
+        let request = async_graphql::Request::new(query).data(GraphQLContext::new(state.clone(), auth));
+        create_schema().execute(request).await
+    }
+
+    fn error_code(response: &async_graphql::Response) -> Option<async_graphql::Value> {
+        response.errors.first()
+            .and_then(|error| error.extensions.as_ref())
+            .and_then(|extensions| extensions.get("code").cloned())
+    }
+
+    #[tokio::test]
+    async fn test_create_archive_requires_authentication() {
+        let state = test_state();
+        let response = execute(&state, None, r#"mutation { createArchive(input: { url: "https://example.com" }) { archive { id } } }"#).await;
+
+        assert_eq!(error_code(&response), Some(async_graphql::Value::from("UNAUTHENTICATED")));
+        assert_eq!(state.archives.counts().await.0, 0);
+    }
+
+    #[tokio::test]
+    async fn test_archive_query_requires_read_scope() {
+        use crate::api::auth::ApiScope;
+
+        let state = test_state();
+        let auth = auth_with(vec![ApiScope::NetworkRead]);
+        let response = execute(&state, Some(auth), r#"{ archive(id: "arc_1") { id } }"#).await;
+
+        assert_eq!(error_code(&response), Some(async_graphql::Value::from("FORBIDDEN")));
+    }
+
+    #[tokio::test]
+    async fn test_archive_workflow_through_schema() {
+        use crate::api::auth::ApiScope;
+
+        let state = test_state();
+        let auth = || Some(auth_with(vec![ApiScope::ArchivesRead, ApiScope::ArchivesWrite, ApiScope::NetworkRead]));
+
+        let mut ids = Vec::new();
+        for i in 0..3 {
+            let query = format!(r#"mutation {{ createArchive(input: {{ url: "https://example.com/{}" }}) {{ archive {{ id status }} errors }} }}"#, i);
+            let response = execute(&state, auth(), &query).await;
+            assert!(response.errors.is_empty(), "{:?}", response.errors);
+            let data = response.data.into_json().unwrap();
+            assert_eq!(data["createArchive"]["archive"]["status"], "PENDING");
+            ids.push(data["createArchive"]["archive"]["id"].as_str().unwrap().to_string());
+        }
+
+        // Même sémantique page/limit que l'API REST
+        let response = execute(&state, auth(), r#"{ archives(pagination: { page: 2, limit: 2 }) { totalCount edges { node { id } } pageInfo { hasNextPage hasPreviousPage } } }"#).await;
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        let data = response.data.into_json().unwrap();
+        assert_eq!(data["archives"]["totalCount"], 3);
+        assert_eq!(data["archives"]["edges"].as_array().unwrap().len(), 1);
+        assert_eq!(data["archives"]["pageInfo"]["hasNextPage"], false);
+        assert_eq!(data["archives"]["pageInfo"]["hasPreviousPage"], true);
+
+        let response = execute(&state, auth(), r#"{ archives(pagination: { page: 1, limit: 500 }) { totalCount } }"#).await;
+        assert_eq!(error_code(&response), Some(async_graphql::Value::from("VALIDATION_ERROR")));
+
+        let query = format!(r#"mutation {{ cancelArchive(id: "{}") {{ archive {{ id status }} }} }}"#, ids[0]);
+        let response = execute(&state, auth(), &query).await;
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        assert_eq!(response.data.into_json().unwrap()["cancelArchive"]["archive"]["status"], "CANCELLED");
+
+        let query = format!(r#"{{ archive(id: "{}") {{ status }} }}"#, ids[0]);
+        let response = execute(&state, auth(), &query).await;
+        assert_eq!(response.data.into_json().unwrap()["archive"]["status"], "CANCELLED");
+
+        // Une archive déjà annulée ne peut pas l'être à nouveau
+        let query = format!(r#"mutation {{ cancelArchive(id: "{}") {{ archive {{ id }} }} }}"#, ids[0]);
+        let response = execute(&state, auth(), &query).await;
+        assert_eq!(error_code(&response), Some(async_graphql::Value::from("RESOURCE_CONFLICT")));
+
+        let response = execute(&state, auth(), r#"{ networkStats { totalArchives currentBlockHeight } node(id: "node_1") { id } }"#).await;
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        let data = response.data.into_json().unwrap();
+        assert_eq!(data["networkStats"]["totalArchives"], 3);
+        assert!(data["node"].is_null());
+    }
+
+    #[tokio::test]
+    async fn test_cancel_archive_requires_ownership() {
+        use crate::api::auth::ApiScope;
+        use crate::api::types::CreateArchiveRequest;
+
+        let state = test_state();
+        let request: CreateArchiveRequest = serde_json::from_value(serde_json::json!({ "url": "https://example.com" })).unwrap();
+        let record = state.archives.create_archive("someone_else", request).await.unwrap();
+
+        let query = format!(r#"mutation {{ cancelArchive(id: "{}") {{ archive {{ id }} }} }}"#, record.archive.archive_id);
+        let response = execute(&state, Some(auth_with(vec![ApiScope::ArchivesWrite])), &query).await;
+        assert_eq!(error_code(&response), Some(async_graphql::Value::from("AUTHORIZATION_FAILED")));
+
+        let response = execute(&state, None, &query).await;
+        assert_eq!(error_code(&response), Some(async_graphql::Value::from("UNAUTHENTICATED")));
+    }
+
+    #[tokio::test]
+    async fn test_block_added_subscription_yields_appended_block() {
+        use crate::api::auth::ApiScope;
+        use crate::api::types::BlockDto;
+        use crate::api::websocket::{ConnectionManager, EventManager, SubscriptionTopic, WebSocketConfig};
+        use futures_util::StreamExt;
+        use std::sync::Arc;
+
+        let state = test_state();
+        let connections = ConnectionManager::new(WebSocketConfig::default()).with_event_bus(state.events.clone());
+        let events = EventManager::new(Arc::new(tokio::sync::RwLock::new(connections)));
+        let subscribe = |scopes| {
+            let request = async_graphql::Request::new("subscription { blockAdded { height } }")
+                .data(GraphQLContext::new(state.clone(), Some(auth_with(scopes))));
+            create_schema().execute_stream(request)
+        };
+
+        let refused = subscribe(vec![ApiScope::ArchivesRead]).next().await.unwrap();
+        assert_eq!(error_code(&refused), Some(async_graphql::Value::from("FORBIDDEN")));
+
+        // Le flux ne s'abonne au bus qu'une fois interrogé
+        let mut stream = subscribe(vec![ApiScope::NetworkRead]);
+        let first = tokio::spawn(async move { (stream.next().await, stream) });
+        while state.events.subscriber_count(&SubscriptionTopic::NewBlocks) == 0 {
+            tokio::task::yield_now().await;
+        }
+
+        let mut blockchain = crate::Blockchain::new(crate::BlockchainConfig::default()).unwrap();
+        let block = blockchain.mine_block().unwrap();
+        blockchain.add_block(block.clone()).unwrap();
+        events.broadcast_new_block(BlockDto::from(&block)).await.unwrap();
+
+        let (response, mut stream) = first.await.unwrap();
+        let response = response.unwrap();
+        assert!(response.errors.is_empty(), "{:?}", response.errors);
+        assert_eq!(response.data.into_json().unwrap()["blockAdded"]["height"], block.header().height);
+        let next = tokio::time::timeout(std::time::Duration::from_millis(100), stream.next()).await;
+        assert!(next.is_err(), "a single block must yield a single item");
+
+        // La déconnexion du client libère son récepteur
+        drop(stream);
+        assert_eq!(state.events.subscriber_count(&SubscriptionTopic::NewBlocks), 0);
+    }
+}
@@ -3,185 +3,137 @@
 //! Implémente tous les resolvers pour les queries, mutations et subscriptions GraphQL.
 
 use async_graphql::{Result as GraphQLResult, Error as GraphQLError, ErrorExtensions};
-use futures_util::Stream;
+use futures_util::{Stream, StreamExt};
 use std::collections::HashMap;
 use std::pin::Pin;
 
-use crate::api::types;
+use crate::api::{
+    ApiError,
+    types,
+    server::ServerState,
+    service::{ArchiveQuery, ArchiveRecord, ConfirmationQuery, NetworkService},
+    websocket::{BlockUpdate, SubscriptionTopic, WsMessage},
+};
 use super::schema::{self, *};
 
 /// Resolver pour les archives
 pub struct ArchiveResolver;
 
 impl ArchiveResolver {
-    /// Récupère une archive par son ID
-    pub async fn get_archive(id: String) -> GraphQLResult<Option<Archive>> {
-        // TODO: Implémenter la récupération depuis la blockchain
-        if id.starts_with("arc_") {
-            // Retourne un exemple pour les tests
-            Ok(Some(Archive {
-                id: id.clone(),
-                url: "https://example.com".to_string(),
-                status: ArchiveStatus::Completed,
-                metadata: ArchiveMetadata {
-                    title: Some("Example Archive".to_string()),
-                    description: Some("An example archive".to_string()),
-                    tags: vec!["example".to_string(), "test".to_string()],
-                    content_type: "text/html".to_string(),
-                    language: Some("en".to_string()),
-                    author: Some("Test Author".to_string()),
-                    published_at: Some(chrono::Utc::now()),
-                },
-                storage_info: StorageInfo {
-                    replicas: 3,
-                    locations: vec!["us-east".to_string(), "eu-west".to_string()],
-                    integrity_score: 0.99,
-                    last_verified: chrono::Utc::now(),
-                },
-                created_at: chrono::Utc::now(),
-                completed_at: Some(chrono::Utc::now()),
-                size: 1048576, // 1MB
-                cost: TokenAmount {
-                    amount: "0.001".to_string(),
-                    currency: "ARC".to_string(),
-                },
-            }))
-        } else {
-            Ok(None)
+    /// Récupère une archive par son ID, si elle est confirmée assez profondément
+    pub async fn get_archive(state: &ServerState, id: String, confirmation: ConfirmationQuery) -> GraphQLResult<Option<Archive>> {
+        match state.archives.get_archive(&id).await {
+            Ok(record) => Ok(confirmed_archive(state, record, confirmation)),
+            Err(ApiError::NotFound(_)) => Ok(None),
+            Err(e) => Err(service_error(e)),
+        }
+    }
+
+    /// Chronologie des captures d'une URL
+    pub async fn get_archive_versions(state: &ServerState, url: String) -> GraphQLResult<Vec<ArchiveVersion>> {
+        let versions = state.archives.get_archive_versions(&url).await;
+        Ok(versions.into_iter().map(|version| types::ArchiveVersionDto::from(version).into()).collect())
+    }
+
+    /// Capture la plus proche à la date donnée ou avant
+    pub async fn get_archive_at(
+        state: &ServerState,
+        url: String,
+        timestamp: chrono::DateTime<chrono::Utc>,
+        confirmation: ConfirmationQuery,
+    ) -> GraphQLResult<Option<Archive>> {
+        match state.archives.get_archive_at(&url, timestamp).await {
+            Ok(record) => Ok(confirmed_archive(state, record, confirmation)),
+            Err(ApiError::NotFound(_)) => Ok(None),
+            Err(e) => Err(service_error(e)),
         }
     }
 
     /// Liste les archives avec filtres et pagination
+    ///
+    /// `pagination` applique la même sémantique page/limit que l'API REST ; sinon la
+    /// pagination par curseurs Relay est utilisée.
     pub async fn list_archives(
+        state: &ServerState,
         filter: Option<ArchiveFilter>,
         sort: Option<ArchiveSort>,
-        first: Option<i32>,
-        after: Option<String>,
+        args: ConnectionArgs,
+        pagination: Option<PaginationInput>,
+        confirmation: ConfirmationQuery,
     ) -> GraphQLResult<ArchiveConnection> {
-        // TODO: Implémenter la récupération paginée depuis la blockchain
-        let archives = vec![]; // Placeholder
-
-        Ok(ArchiveConnection {
-            edges: archives.into_iter().enumerate().map(|(i, archive)| ArchiveEdge {
-                node: archive,
-                cursor: format!("cursor_{}", i),
-            }).collect(),
-            page_info: PageInfo {
-                has_next_page: false,
-                has_previous_page: false,
-                start_cursor: None,
-                end_cursor: None,
-            },
-        })
-    }
-
-    /// Crée une nouvelle archive
-    pub async fn create_archive(input: CreateArchiveInput) -> GraphQLResult<CreateArchivePayload> {
-        // Valide l'entrée
-        if input.url.is_empty() {
-            return Ok(CreateArchivePayload {
-                archive: Archive {
-                    id: "".to_string(),
-                    url: input.url,
-                    status: ArchiveStatus::Failed,
-                    metadata: ArchiveMetadata {
-                        title: None,
-                        description: None,
-                        tags: vec![],
-                        content_type: "".to_string(),
-                        language: None,
-                        author: None,
-                        published_at: None,
-                    },
-                    storage_info: StorageInfo {
-                        replicas: 0,
-                        locations: vec![],
-                        integrity_score: 0.0,
-                        last_verified: chrono::Utc::now(),
-                    },
-                    created_at: chrono::Utc::now(),
-                    completed_at: None,
-                    size: 0,
-                    cost: TokenAmount {
-                        amount: "0".to_string(),
-                        currency: "ARC".to_string(),
-                    },
-                },
-                errors: vec!["URL is required".to_string()],
-            });
-        }
-
-        // Valide l'URL
-        if let Err(_) = url::Url::parse(&input.url) {
-            return Ok(CreateArchivePayload {
-                archive: Archive {
-                    id: "".to_string(),
-                    url: input.url,
-                    status: ArchiveStatus::Failed,
-                    metadata: ArchiveMetadata {
-                        title: None,
-                        description: None,
-                        tags: vec![],
-                        content_type: "".to_string(),
-                        language: None,
-                        author: None,
-                        published_at: None,
-                    },
-                    storage_info: StorageInfo {
-                        replicas: 0,
-                        locations: vec![],
-                        integrity_score: 0.0,
-                        last_verified: chrono::Utc::now(),
-                    },
-                    created_at: chrono::Utc::now(),
-                    completed_at: None,
-                    size: 0,
-                    cost: TokenAmount {
-                        amount: "0".to_string(),
-                        currency: "ARC".to_string(),
-                    },
+        let query = filter.map(ArchiveQuery::from).unwrap_or_default();
+
+        if let Some(pagination) = pagination {
+            if args.first.is_some() || args.after.is_some() || args.last.is_some() || args.before.is_some() {
+                return Err(GraphQLError::new("`pagination` cannot be combined with cursor arguments")
+                    .extend_with(|_, e| e.set("code", "VALIDATION_ERROR")));
+            }
+
+            let params = pagination.into_params(state.config.rest.max_page_size)?;
+            let (records, info) = state.archives
+                .list_confirmed_archives(&params, &query, &state.blockchain, &confirmation)
+                .await;
+            let edges: Vec<ArchiveEdge> = records.into_iter()
+                .map(|record| {
+                    let node = Archive::from(record);
+                    ArchiveEdge { cursor: ArchiveCursor::from_archive(&node).encode(), node }
+                })
+                .collect();
+
+            return Ok(ArchiveConnection {
+                page_info: PageInfo {
+                    has_next_page: info.has_next,
+                    has_previous_page: info.has_prev,
+                    start_cursor: edges.first().map(|edge| edge.cursor.clone()),
+                    end_cursor: edges.last().map(|edge| edge.cursor.clone()),
                 },
-                errors: vec!["Invalid URL format".to_string()],
+                edges,
+                total_count: info.total as i64,
             });
         }
 
-        // Génère un nouvel ID d'archive
-        let archive_id = format!("arc_{}", uuid::Uuid::new_v4().simple());
+        let archives = state.archives.find_confirmed_archives(&query, &state.blockchain, &confirmation).await
+            .into_iter()
+            .map(Archive::from)
+            .collect();
 
-        // TODO: Ajouter l'archive à la queue de traitement
-        let archive = Archive {
-            id: archive_id,
-            url: input.url,
-            status: ArchiveStatus::Pending,
-            metadata: ArchiveMetadata {
-                title: input.metadata.as_ref().and_then(|m| m.get("title").cloned()),
-                description: input.metadata.as_ref().and_then(|m| m.get("description").cloned()),
-                tags: input.metadata.as_ref()
-                    .and_then(|m| m.get("tags"))
-                    .and_then(|tags| serde_json::from_str::<Vec<String>>(tags).ok())
-                    .unwrap_or_default(),
-                content_type: "unknown".to_string(),
-                language: None,
-                author: input.metadata.as_ref().and_then(|m| m.get("author").cloned()),
-                published_at: None,
-            },
-            storage_info: StorageInfo {
-                replicas: 0,
-                locations: vec![],
-                integrity_score: 0.0,
-                last_verified: chrono::Utc::now(),
-            },
-            created_at: chrono::Utc::now(),
-            completed_at: None,
-            size: 0,
-            cost: TokenAmount {
-                amount: "0.001".to_string(),
-                currency: "ARC".to_string(),
-            },
+        // Les curseurs reposent sur (date de création, ID) : seul le sens du tri est retenu
+        let direction = match sort {
+            Some(ArchiveSort { field: ArchiveSortField::CreatedAt, direction }) => direction,
+            _ => SortDirection::Asc,
         };
 
-        Ok(CreateArchivePayload {
-            archive,
+        ArchiveConnection::paginate(archives, direction, args)
+    }
+
+    /// Crée une nouvelle archive
+    pub async fn create_archive(state: &ServerState, user_id: &str, input: CreateArchiveInput) -> GraphQLResult<CreateArchivePayload> {
+        let url = input.url.clone();
+        let request = types::CreateArchiveRequest::from(input);
+
+        match state.archives.create_archive(user_id, request).await {
+            Ok(record) => Ok(CreateArchivePayload {
+                archive: record.into(),
+                errors: vec![],
+            }),
+            Err(ApiError::Validation(message)) => Ok(CreateArchivePayload {
+                archive: rejected_archive(url),
+                errors: vec![message],
+            }),
+            Err(ApiError::InvalidFields(errors)) => Ok(CreateArchivePayload {
+                archive: rejected_archive(url),
+                errors: errors.into_iter().map(|e| e.message).collect(),
+            }),
+            Err(e) => Err(service_error(e)),
+        }
+    }
+
+    /// Annule une archive en attente ou en cours de traitement
+    pub async fn cancel_archive(state: &ServerState, caller: &str, id: String) -> GraphQLResult<CancelArchivePayload> {
+        let record = state.archives.cancel_archive(caller, &id).await.map_err(service_error)?;
+
+        Ok(CancelArchivePayload {
+            archive: record.into(),
             errors: vec![],
         })
     }
@@ -243,19 +195,10 @@ pub struct NetworkResolver;
 
 impl NetworkResolver {
     /// Récupère les statistiques du réseau
-    pub async fn get_network_stats() -> GraphQLResult<NetworkStats> {
-        // TODO: Récupérer les vraies statistiques depuis la blockchain
-        Ok(NetworkStats {
-            total_nodes: 100,
-            active_nodes: 95,
-            total_storage: "15.7 TB".to_string(),
-            available_storage: "8.3 TB".to_string(),
-            current_block_height: 12345,
-            total_archives: 50000,
-            archives_today: 150,
-            average_archive_time: "2.3 minutes".to_string(),
-            success_rate: 0.987,
-        })
+    pub async fn get_network_stats(state: &ServerState) -> GraphQLResult<NetworkStats> {
+        NetworkService::stats(state).await
+            .map(NetworkStats::from)
+            .map_err(service_error)
     }
 }
 
@@ -268,6 +211,15 @@ impl NodeResolver {
         // TODO: Récupérer les nœuds depuis le réseau P2P
         Ok(vec![])
     }
+
+    /// Récupère un nœud par son ID
+    pub async fn get_node(state: &ServerState, id: String) -> GraphQLResult<Option<Node>> {
+        match NetworkService::get_node(state, &id).await {
+            Ok(node) => Ok(Some(node.into())),
+            Err(ApiError::NotFound(_)) => Ok(None),
+            Err(e) => Err(service_error(e)),
+        }
+    }
 }
 
 /// Resolver pour les utilisateurs
@@ -346,18 +298,123 @@ impl BlockResolver {
             },
         })
     }
+
+    /// Récupère le reçu d'une transaction, si elle est confirmée assez profondément
+    pub async fn get_transaction_receipt(
+        state: &ServerState,
+        hash: String,
+        confirmation: ConfirmationQuery,
+    ) -> GraphQLResult<Option<TransactionReceipt>> {
+        let tx_hash = crate::crypto::Hash::from_hex(&hash)
+            .map_err(|_| GraphQLError::new("Invalid transaction hash format"))?;
+        Ok(state.blockchain.get_receipt(&tx_hash)
+            .map(|receipt| types::TransactionReceiptDto::new(&receipt, &state.blockchain))
+            .filter(|receipt| confirmation.is_satisfied(&state.blockchain, Some(receipt.confirmations)))
+            .map(Into::into))
+    }
 }
 
+/// Nombre maximum de blocs rejoués par `blockAdded`
+const MAX_BLOCK_REPLAY: u64 = 1000;
+
 /// Resolver pour les subscriptions
 pub struct SubscriptionResolver;
 
 impl SubscriptionResolver {
-    /// Stream des mises à jour d'archives
-    pub async fn archive_updates(archive_id: String) -> GraphQLResult<Pin<Box<dyn Stream<Item = Archive> + Send>>> {
-        // TODO: Implémenter le streaming des mises à jour depuis le système de pubsub
-        use futures_util::stream;
-        
-        let stream = stream::empty(); // Placeholder
+    /// Événements d'un topic du bus, jusqu'à l'arrêt du serveur
+    ///
+    /// Le flux est abandonné à la déconnexion du client, ce qui libère son
+    /// récepteur sur le bus.
+    fn events(state: &ServerState, topic: SubscriptionTopic) -> impl Stream<Item = WsMessage> + Send + 'static {
+        let shutdown = state.shutdown.clone();
+        state.events.subscribe(&topic)
+            .take_until(async move { shutdown.triggered().await })
+    }
+
+    /// Stream des blocs ajoutés à la chaîne du nœud
+    ///
+    /// Avec `from_height`, les blocs de la chaîne principale depuis cette
+    /// hauteur sont rejoués avant les blocs à venir. L'abonnement au bus est
+    /// pris avant la lecture de la chaîne : un bloc ajouté entre les deux est
+    /// rejoué, et sa diffusion ultérieure est écartée par sa hauteur.
+    pub async fn block_added(
+        state: &ServerState,
+        from_height: Option<u64>,
+    ) -> GraphQLResult<Pin<Box<dyn Stream<Item = BlockAdded> + Send>>> {
+        let live = Self::events(state, SubscriptionTopic::NewBlocks);
+        let (replay, tip) = match &state.live_chain {
+            Some(chain) => Self::replay_blocks(&*chain.read().await, from_height)?,
+            None => Self::replay_blocks(&state.blockchain, from_height)?,
+        };
+
+        // Hauteur pas encore atteinte : les blocs intermédiaires sont ignorés
+        let first_live = from_height.map_or(tip, |start| start.max(tip));
+        let live = live.filter_map(move |message| async move {
+            match message {
+                WsMessage::NewBlock { block, .. } if block.height >= first_live => Some(BlockAdded::from(block)),
+                _ => None,
+            }
+        });
+        Ok(Box::pin(futures_util::stream::iter(replay).chain(live)))
+    }
+
+    /// Blocs de la chaîne principale depuis `from_height`, et hauteur de la chaîne
+    fn replay_blocks(blockchain: &crate::Blockchain, from_height: Option<u64>) -> GraphQLResult<(Vec<BlockAdded>, u64)> {
+        let tip = blockchain.height();
+        let start = from_height.unwrap_or(tip);
+        if tip.saturating_sub(start) > MAX_BLOCK_REPLAY {
+            return Err(GraphQLError::new(format!("`fromHeight` is more than {} blocks behind the tip", MAX_BLOCK_REPLAY))
+                .extend_with(|_, e| e.set("code", "BAD_REQUEST")));
+        }
+
+        let replay = (start..tip)
+            .map(|height| {
+                blockchain.get_block_by_height(height)
+                    .map(|block| BlockAdded::from(BlockUpdate::from(types::BlockDto::from(block))))
+                    .ok_or_else(|| {
+                        GraphQLError::new(format!("Block {} has been pruned", height))
+                            .extend_with(|_, e| e.set("code", "NOT_FOUND"))
+                    })
+            })
+            .collect::<GraphQLResult<Vec<_>>>()?;
+        Ok((replay, tip))
+    }
+
+    /// Stream des changements de statut d'une archive
+    pub async fn archive_status_changed(
+        state: &ServerState,
+        archive_id: String,
+    ) -> GraphQLResult<Pin<Box<dyn Stream<Item = ArchiveStatusChange> + Send>>> {
+        let stream = Self::events(state, SubscriptionTopic::ArchiveUpdates)
+            .filter_map(move |message| {
+                let change = match message {
+                    WsMessage::ArchiveUpdate { archive_id: id, status, progress, timestamp, .. } if id == archive_id => {
+                        archive_status_from_event(&status).map(|status| ArchiveStatusChange {
+                            archive_id: id,
+                            status,
+                            progress,
+                            timestamp,
+                        })
+                    }
+                    _ => None,
+                };
+                async move { change }
+            });
+        Ok(Box::pin(stream))
+    }
+
+    /// Stream du nombre de pairs actifs, émis à chaque changement
+    pub async fn peer_count_changed(state: &ServerState) -> GraphQLResult<Pin<Box<dyn Stream<Item = i32> + Send>>> {
+        let mut last_count = None;
+        let stream = Self::events(state, SubscriptionTopic::NetworkStats)
+            .filter_map(move |message| {
+                let count = match message {
+                    WsMessage::NetworkStats { data, .. } => Some(data.active_nodes as i32),
+                    _ => None,
+                };
+                let changed = count.filter(|count| last_count.replace(*count) != Some(*count));
+                async move { changed }
+            });
         Ok(Box::pin(stream))
     }
 
@@ -380,7 +437,267 @@ impl SubscriptionResolver {
     }
 }
 
+/// Statut d'archive tel que diffusé sur le bus (`Completed`, `Failed`...)
+fn archive_status_from_event(status: &str) -> Option<ArchiveStatus> {
+    [
+        ArchiveStatus::Pending,
+        ArchiveStatus::Processing,
+        ArchiveStatus::Completed,
+        ArchiveStatus::Failed,
+        ArchiveStatus::Expired,
+        ArchiveStatus::Cancelled,
+    ]
+    .into_iter()
+    .find(|candidate| format!("{:?}", types::ArchiveStatus::from(*candidate)).eq_ignore_ascii_case(status))
+}
+
+/// Convertit une erreur de la couche de service en erreur GraphQL portant le même code que l'API REST
+fn service_error(error: ApiError) -> GraphQLError {
+    let code = error.error_code();
+    GraphQLError::new(error.to_string()).extend_with(|_, e| e.set("code", code))
+}
+
+/// Archive avec sa profondeur de confirmation, `None` si elle n'atteint pas celle exigée
+fn confirmed_archive(state: &ServerState, record: ArchiveRecord, confirmation: ConfirmationQuery) -> Option<Archive> {
+    let record = record.with_confirmations(&state.blockchain);
+    confirmation
+        .is_satisfied(&state.blockchain, record.archive.confirmations)
+        .then(|| record.into())
+}
+
+/// Profondeur de confirmation exigée par les arguments d'une requête
+pub fn confirmation_query(min_confirmations: Option<i32>, finalized: Option<bool>) -> GraphQLResult<ConfirmationQuery> {
+    let min_confirmations = min_confirmations
+        .map(|depth| u64::try_from(depth).map_err(|_| validation_error(format!("Invalid confirmation depth: {}", depth))))
+        .transpose()?;
+    Ok(ConfirmationQuery { min_confirmations, finalized: finalized.unwrap_or(false) })
+}
+
+/// Archive renvoyée lorsqu'une demande de création est invalide
+fn rejected_archive(url: String) -> Archive {
+    Archive {
+        id: "".to_string(),
+        url,
+        status: ArchiveStatus::Failed,
+        metadata: ArchiveMetadata {
+            title: None,
+            description: None,
+            tags: vec![],
+            content_type: "".to_string(),
+            language: None,
+            author: None,
+            published_at: None,
+        },
+        storage_info: StorageInfo {
+            replicas: 0,
+            locations: vec![],
+            integrity_score: 0.0,
+            last_verified: chrono::Utc::now(),
+        },
+        created_at: chrono::Utc::now(),
+        completed_at: None,
+        size: 0,
+        cost: TokenAmount {
+            amount: "0".to_string(),
+            currency: "ARC".to_string(),
+        },
+        confirmations: None,
+        finalized: false,
+    }
+}
+
+impl PaginationInput {
+    /// Convertit l'entrée en `PaginationParams` validés, avec les mêmes défauts que l'API REST
+    pub fn into_params(self, max_limit: u32) -> GraphQLResult<crate::api::rest::PaginationParams> {
+        let to_u32 = |value: Option<i32>, default: u32| match value {
+            None => Ok(default),
+            Some(v) => u32::try_from(v).map_err(|_| validation_error(format!("Invalid pagination value: {}", v))),
+        };
+
+        let params = crate::api::rest::PaginationParams {
+            page: to_u32(self.page, 1)?,
+            limit: to_u32(self.limit, 20)?,
+        };
+        params.validate(max_limit).map_err(validation_error)?;
+        Ok(params)
+    }
+}
+
+fn validation_error(message: String) -> GraphQLError {
+    GraphQLError::new(message).extend_with(|_, e| e.set("code", "VALIDATION_ERROR"))
+}
+
 /// Helpers pour la conversion des types
+impl From<ArchiveRecord> for Archive {
+    fn from(record: ArchiveRecord) -> Self {
+        let archive = record.archive;
+        let mut cost = record.cost.total_cost.split_whitespace();
+
+        Archive {
+            id: archive.archive_id,
+            url: archive.url,
+            status: archive.status.into(),
+            metadata: ArchiveMetadata {
+                title: archive.metadata.title,
+                description: archive.metadata.description,
+                tags: archive.metadata.tags,
+                content_type: archive.metadata.mime_type,
+                language: archive.metadata.language,
+                author: archive.metadata.author,
+                published_at: archive.metadata.published_at,
+            },
+            storage_info: StorageInfo {
+                replicas: archive.storage_info.replicas as i32,
+                locations: archive.storage_info.locations,
+                integrity_score: archive.storage_info.integrity_score,
+                last_verified: archive.storage_info.last_verified,
+            },
+            created_at: archive.created_at,
+            completed_at: archive.completed_at,
+            size: archive.size as i64,
+            cost: TokenAmount {
+                amount: cost.next().unwrap_or("0").to_string(),
+                currency: cost.next().unwrap_or("ARC").to_string(),
+            },
+            confirmations: archive.confirmations.map(|confirmations| confirmations as i64),
+            finalized: archive.finalized,
+        }
+    }
+}
+
+impl From<types::ArchiveVersionDto> for ArchiveVersion {
+    fn from(version: types::ArchiveVersionDto) -> Self {
+        ArchiveVersion {
+            archive_id: version.archive_id,
+            url: version.url,
+            captured_at: version.captured_at,
+            content_hash: version.content_hash,
+            previous_archive_id: version.previous_archive_id,
+            revisit_of: version.revisit_of,
+        }
+    }
+}
+
+impl From<types::TransactionReceiptDto> for TransactionReceipt {
+    fn from(receipt: types::TransactionReceiptDto) -> Self {
+        TransactionReceipt {
+            transaction_hash: receipt.transaction_hash,
+            block_hash: receipt.block_hash,
+            block_height: receipt.block_height as i64,
+            index: receipt.index as i32,
+            gas_used: receipt.gas_used.to_string(),
+            events: receipt.events.into_iter().map(TokenEvent::from).collect(),
+            timestamp: receipt.timestamp,
+            confirmations: receipt.confirmations as i64,
+            finalized: receipt.finalized,
+        }
+    }
+}
+
+impl From<types::TokenEventDto> for TokenEvent {
+    fn from(event: types::TokenEventDto) -> Self {
+        TokenEvent {
+            event_type: event.event_type,
+            from: event.from,
+            to: event.to,
+            amount: TokenAmount {
+                amount: event.amount.to_string(),
+                currency: "ARC".to_string(),
+            },
+            detail: event.detail,
+            timestamp: event.timestamp,
+        }
+    }
+}
+
+impl From<CreateArchiveInput> for types::CreateArchiveRequest {
+    fn from(input: CreateArchiveInput) -> Self {
+        let mut options = types::ArchiveOptions::default();
+        if let Some(input_options) = input.options {
+            if let Some(include_assets) = input_options.include_assets {
+                options.include_assets = include_assets;
+            }
+            if let Some(max_depth) = input_options.max_depth {
+                options.max_depth = max_depth.max(0) as u32;
+            }
+            if let Some(preserve_javascript) = input_options.preserve_javascript {
+                options.preserve_javascript = preserve_javascript;
+            }
+            if let Some(allowed_domains) = input_options.allowed_domains {
+                options.allowed_domains = allowed_domains;
+            }
+            if let Some(same_domain_only) = input_options.same_domain_only {
+                options.same_domain_only = same_domain_only;
+            }
+            if let Some(max_total_size) = input_options.max_total_size {
+                options.max_total_size = Some(max_total_size.max(0) as u64);
+            }
+            if let Some(respect_robots_txt) = input_options.respect_robots_txt {
+                options.respect_robots_txt = respect_robots_txt;
+            }
+        }
+
+        Self {
+            url: input.url,
+            metadata: input.metadata.unwrap_or_default(),
+            options,
+            content: None,
+            dry_run: false,
+        }
+    }
+}
+
+impl From<ArchiveFilter> for ArchiveQuery {
+    fn from(filter: ArchiveFilter) -> Self {
+        Self {
+            text: filter.search,
+            status: filter.status.map(Into::into),
+            tags: filter.tags.unwrap_or_default(),
+            domain: None,
+            content_type: filter.content_type,
+            created_after: filter.created_after,
+            created_before: filter.created_before,
+        }
+    }
+}
+
+impl From<types::NetworkStats> for NetworkStats {
+    fn from(stats: types::NetworkStats) -> Self {
+        NetworkStats {
+            total_nodes: stats.network.total_nodes as i32,
+            active_nodes: stats.network.active_nodes as i32,
+            total_storage: stats.network.total_storage,
+            available_storage: stats.network.available_storage,
+            current_block_height: stats.network.current_block_height as i64,
+            total_archives: stats.archives.total_archives as i64,
+            archives_today: stats.archives.archives_today as i32,
+            average_archive_time: stats.performance.average_archive_time,
+            success_rate: stats.performance.success_rate,
+        }
+    }
+}
+
+impl From<types::NodeInfo> for Node {
+    fn from(node: types::NodeInfo) -> Self {
+        Node {
+            id: node.node_id,
+            status: node.status.into(),
+            region: node.region,
+            capacity: StorageCapacity {
+                total: node.capacity.total as i64,
+                used: node.capacity.used as i64,
+                available: node.capacity.available as i64,
+            },
+            performance: NodePerformance {
+                bandwidth: node.performance.bandwidth as i64,
+                latency: node.performance.latency as i32,
+                reliability_score: node.performance.reliability_score,
+            },
+            last_seen: node.last_seen,
+        }
+    }
+}
+
 impl From<types::ArchiveStatus> for ArchiveStatus {
     fn from(status: types::ArchiveStatus) -> Self {
         match status {
@@ -389,6 +706,7 @@ impl From<types::ArchiveStatus> for ArchiveStatus {
             types::ArchiveStatus::Completed => ArchiveStatus::Completed,
             types::ArchiveStatus::Failed => ArchiveStatus::Failed,
             types::ArchiveStatus::Expired => ArchiveStatus::Expired,
+            types::ArchiveStatus::Cancelled => ArchiveStatus::Cancelled,
         }
     }
 }
@@ -401,6 +719,7 @@ impl From<ArchiveStatus> for types::ArchiveStatus {
             ArchiveStatus::Completed => types::ArchiveStatus::Completed,
             ArchiveStatus::Failed => types::ArchiveStatus::Failed,
             ArchiveStatus::Expired => types::ArchiveStatus::Expired,
+            ArchiveStatus::Cancelled => types::ArchiveStatus::Cancelled,
         }
     }
 }
@@ -431,26 +750,72 @@ impl From<NodeStatus> for types::NodeStatus {
 mod tests {
     use super::*;
 
+    fn create_test_state() -> ServerState {
+        let blockchain = std::sync::Arc::new(
+            crate::Blockchain::new(crate::BlockchainConfig::default()).unwrap()
+        );
+        let auth_service = std::sync::Arc::new(
+            crate::api::auth::AuthService::new(crate::api::auth::AuthConfig::default()).unwrap()
+        );
+        let user_manager = std::sync::Arc::new(tokio::sync::RwLock::new(
+            crate::api::auth::UserManager::new()
+        ));
+
+        ServerState::new(blockchain, auth_service, user_manager, crate::api::ApiConfig::default())
+    }
+
     #[tokio::test]
     async fn test_archive_resolver_get_archive() {
-        let result = ArchiveResolver::get_archive("arc_123456".to_string()).await;
+        let state = create_test_state();
+        let input = CreateArchiveInput {
+            url: "https://example.com".to_string(),
+            metadata: None,
+            options: None,
+        };
+        let created = ArchiveResolver::create_archive(&state, "user123", input).await.unwrap();
+
+        let result = ArchiveResolver::get_archive(&state, created.archive.id.clone(), ConfirmationQuery::default()).await;
         assert!(result.is_ok());
         
         let archive = result.unwrap();
         assert!(archive.is_some());
         
         let archive = archive.unwrap();
-        assert_eq!(archive.id, "arc_123456");
-        assert_eq!(archive.status, ArchiveStatus::Completed);
+        assert_eq!(archive.id, created.archive.id);
+        assert_eq!(archive.status, ArchiveStatus::Pending);
+    }
+
+    #[tokio::test]
+    async fn test_archive_resolver_versions() {
+        let state = create_test_state();
+        let url = "https://example.com/news";
+        for _ in 0..2 {
+            let input = CreateArchiveInput { url: url.to_string(), metadata: None, options: None };
+            ArchiveResolver::create_archive(&state, "user123", input).await.unwrap();
+        }
+
+        let versions = ArchiveResolver::get_archive_versions(&state, url.to_string()).await.unwrap();
+        assert_eq!(versions.len(), 2);
+        assert_eq!(versions[1].previous_archive_id.as_ref(), Some(&versions[0].archive_id));
+
+        let archive = ArchiveResolver::get_archive_at(&state, url.to_string(), chrono::Utc::now(), ConfirmationQuery::default()).await.unwrap();
+        assert_eq!(archive.unwrap().id, versions[1].archive_id);
+
+        let before = versions[0].captured_at - chrono::Duration::seconds(1);
+        assert!(ArchiveResolver::get_archive_at(&state, url.to_string(), before, ConfirmationQuery::default()).await.unwrap().is_none());
     }
 
     #[tokio::test]
     async fn test_archive_resolver_get_archive_not_found() {
-        let result = ArchiveResolver::get_archive("invalid_id".to_string()).await;
+        let state = create_test_state();
+        let result = ArchiveResolver::get_archive(&state, "arc_123456".to_string(), ConfirmationQuery::default()).await;
         assert!(result.is_ok());
         
         let archive = result.unwrap();
         assert!(archive.is_none());
+
+        // Un ID mal formé est rejeté comme en REST
+        assert!(ArchiveResolver::get_archive(&state, "invalid_id".to_string(), ConfirmationQuery::default()).await.is_err());
     }
 
     #[tokio::test]
@@ -461,7 +826,8 @@ mod tests {
             options: None,
         };
 
-        let result = ArchiveResolver::create_archive(input).await;
+        let state = create_test_state();
+        let result = ArchiveResolver::create_archive(&state, "user123", input).await;
         assert!(result.is_ok());
         
         let payload = result.unwrap();
@@ -478,7 +844,8 @@ mod tests {
             options: None,
         };
 
-        let result = ArchiveResolver::create_archive(input).await;
+        let state = create_test_state();
+        let result = ArchiveResolver::create_archive(&state, "user123", input).await;
         assert!(result.is_ok());
         
         let payload = result.unwrap();
@@ -503,7 +870,8 @@ mod tests {
 
     #[tokio::test]
     async fn test_network_resolver_stats() {
-        let result = NetworkResolver::get_network_stats().await;
+        let state = create_test_state();
+        let result = NetworkResolver::get_network_stats(&state).await;
         assert!(result.is_ok());
         
         let stats = result.unwrap();
@@ -545,6 +913,84 @@ mod tests {
         assert_eq!(block.height, 12345);
     }
 
+    #[tokio::test]
+    async fn test_block_added_replays_then_streams_live_chain() {
+        let mut blockchain = crate::Blockchain::new(crate::BlockchainConfig::default()).unwrap();
+        let first = blockchain.mine_block().unwrap();
+        blockchain.add_block(first.clone()).unwrap();
+
+        // La chaîne du nœud avance, l'instantané de l'API non
+        let chain = std::sync::Arc::new(tokio::sync::RwLock::new(blockchain));
+        let state = create_test_state().with_live_chain(chain.clone());
+        let forwarder = state.spawn_block_events().await.unwrap();
+
+        let mut stream = SubscriptionResolver::block_added(&state, Some(1)).await.unwrap();
+        let second = {
+            let mut blockchain = chain.write().await;
+            let second = blockchain.mine_block().unwrap();
+            blockchain.add_block(second.clone()).unwrap();
+            second
+        };
+
+        let next = |stream: &mut Pin<Box<dyn Stream<Item = BlockAdded> + Send>>| {
+            tokio::time::timeout(std::time::Duration::from_secs(1), stream.next())
+        };
+        let replayed = next(&mut stream).await.unwrap().unwrap();
+        assert_eq!(replayed.height, 1);
+        assert_eq!(replayed.hash, first.hash().to_string());
+        let live = next(&mut stream).await.unwrap().unwrap();
+        assert_eq!(live.height, 2);
+        assert_eq!(live.hash, second.hash().to_string());
+        assert!(next(&mut stream).await.is_err());
+
+        // La déconnexion du client libère son récepteur
+        drop(stream);
+        assert_eq!(state.events.subscriber_count(&SubscriptionTopic::NewBlocks), 0);
+
+        state.shutdown.trigger();
+        forwarder.await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_block_resolver_transaction_receipt() {
+        let sender = crate::crypto::generate_keypair().unwrap();
+        let recipient = crate::crypto::generate_keypair().unwrap().public_key().clone();
+        let mut ledger = crate::transaction::TokenLedger::default();
+        ledger.token.mint(sender.public_key(), 1_000, crate::crypto::Hash::zero()).unwrap();
+        let mut blockchain = crate::Blockchain::new(crate::BlockchainConfig::default())
+            .unwrap()
+            .with_token_ledger(ledger);
+        let mut transaction = crate::transaction::Transaction::token_transfer(
+            sender.public_key().clone(),
+            recipient.clone(),
+            400,
+            15,
+            0,
+        );
+        transaction.sign(sender.private_key()).unwrap();
+        blockchain.add_transaction(transaction.clone()).unwrap();
+        let block = blockchain.mine_block().unwrap();
+        blockchain.add_block(block).unwrap();
+
+        let mut state = create_test_state();
+        state.blockchain = std::sync::Arc::new(blockchain);
+
+        let receipt = BlockResolver::get_transaction_receipt(&state, transaction.hash().to_hex(), ConfirmationQuery::default())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(receipt.block_height, 1);
+        assert_eq!(receipt.gas_used, "15");
+        let credited = receipt.events.iter()
+            .find(|event| event.to.as_deref() == Some(recipient.to_hex().as_str()))
+            .unwrap();
+        assert_eq!(credited.event_type, "transfer");
+
+        let unknown = BlockResolver::get_transaction_receipt(&state, "0".repeat(64), ConfirmationQuery::default()).await.unwrap();
+        assert!(unknown.is_none());
+        assert!(BlockResolver::get_transaction_receipt(&state, "not-a-hash".to_string(), ConfirmationQuery::default()).await.is_err());
+    }
+
     #[tokio::test]
     async fn test_block_resolver_get_block_invalid_hash() {
         let result = BlockResolver::get_block("invalid".to_string()).await;
@@ -3,6 +3,9 @@
 //! Définit le schéma GraphQL complet avec tous les types, queries, mutations et subscriptions.
 
 use async_graphql::{Object, Schema, Subscription, Union, Enum, InputObject, SimpleObject};
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::{DateTime, TimeZone, Utc};
+use std::cmp::Ordering;
 use futures_util::{Stream, StreamExt};
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -22,14 +25,26 @@ pub struct QueryRoot;
 #[Object]
 impl QueryRoot {
     /// Récupère une archive par son ID
-    async fn archive(&self, ctx: &async_graphql::Context<'_>, id: String) -> async_graphql::Result<Option<Archive>> {
+    ///
+    /// `min_confirmations` / `finalized` : `null` tant que l'archive n'est pas
+    /// confirmée aussi profondément.
+    async fn archive(
+        &self,
+        ctx: &async_graphql::Context<'_>,
+        id: String,
+        min_confirmations: Option<i32>,
+        finalized: Option<bool>,
+    ) -> async_graphql::Result<Option<Archive>> {
         let context = ctx.data::<GraphQLContext>()?;
         context.require_scope(ApiScope::ArchivesRead)?;
         
-        ArchiveResolver::get_archive(id).await
+        let confirmation = confirmation_query(min_confirmations, finalized)?;
+        ArchiveResolver::get_archive(&context.server_state, id, confirmation).await
     }
 
-    /// Liste les archives avec filtres et pagination
+    /// Liste les archives avec filtres et pagination (curseurs Relay ou page/limit)
+    ///
+    /// Les archives moins confirmées que `min_confirmations` / `finalized` sont omises.
     async fn archives(
         &self,
         ctx: &async_graphql::Context<'_>,
@@ -37,11 +52,42 @@ impl QueryRoot {
         sort: Option<ArchiveSort>,
         first: Option<i32>,
         after: Option<String>,
+        last: Option<i32>,
+        before: Option<String>,
+        pagination: Option<PaginationInput>,
+        min_confirmations: Option<i32>,
+        finalized: Option<bool>,
     ) -> async_graphql::Result<ArchiveConnection> {
         let context = ctx.data::<GraphQLContext>()?;
         context.require_scope(ApiScope::ArchivesRead)?;
         
-        ArchiveResolver::list_archives(filter, sort, first, after).await
+        let args = ConnectionArgs { first, after, last, before };
+        let confirmation = confirmation_query(min_confirmations, finalized)?;
+        ArchiveResolver::list_archives(&context.server_state, filter, sort, args, pagination, confirmation).await
+    }
+
+    /// Chronologie des captures d'une URL
+    async fn archive_versions(&self, ctx: &async_graphql::Context<'_>, url: String) -> async_graphql::Result<Vec<ArchiveVersion>> {
+        let context = ctx.data::<GraphQLContext>()?;
+        context.require_scope(ApiScope::ArchivesRead)?;
+
+        ArchiveResolver::get_archive_versions(&context.server_state, url).await
+    }
+
+    /// Capture la plus proche à la date donnée ou avant
+    async fn archive_at(
+        &self,
+        ctx: &async_graphql::Context<'_>,
+        url: String,
+        timestamp: chrono::DateTime<chrono::Utc>,
+        min_confirmations: Option<i32>,
+        finalized: Option<bool>,
+    ) -> async_graphql::Result<Option<Archive>> {
+        let context = ctx.data::<GraphQLContext>()?;
+        context.require_scope(ApiScope::ArchivesRead)?;
+
+        let confirmation = confirmation_query(min_confirmations, finalized)?;
+        ArchiveResolver::get_archive_at(&context.server_state, url, timestamp, confirmation).await
     }
 
     /// Recherche d'archives
@@ -64,7 +110,15 @@ impl QueryRoot {
         let context = ctx.data::<GraphQLContext>()?;
         context.require_scope(ApiScope::NetworkRead)?;
         
-        NetworkResolver::get_network_stats().await
+        NetworkResolver::get_network_stats(&context.server_state).await
+    }
+
+    /// Récupère un nœud par son ID
+    async fn node(&self, ctx: &async_graphql::Context<'_>, id: String) -> async_graphql::Result<Option<Node>> {
+        let context = ctx.data::<GraphQLContext>()?;
+        context.require_scope(ApiScope::NetworkRead)?;
+        
+        NodeResolver::get_node(&context.server_state, id).await
     }
 
     /// Liste des nœuds
@@ -111,6 +165,21 @@ impl QueryRoot {
         
         BlockResolver::list_blocks(first, after).await
     }
+
+    /// Reçu d'une transaction incluse dans la chaîne principale
+    async fn transaction_receipt(
+        &self,
+        ctx: &async_graphql::Context<'_>,
+        hash: String,
+        min_confirmations: Option<i32>,
+        finalized: Option<bool>,
+    ) -> async_graphql::Result<Option<TransactionReceipt>> {
+        let context = ctx.data::<GraphQLContext>()?;
+        context.require_scope(ApiScope::NetworkRead)?;
+
+        let confirmation = confirmation_query(min_confirmations, finalized)?;
+        BlockResolver::get_transaction_receipt(&context.server_state, hash, confirmation).await
+    }
 }
 
 /// Root Mutation pour l'API GraphQL
@@ -126,8 +195,22 @@ impl MutationRoot {
     ) -> async_graphql::Result<CreateArchivePayload> {
         let context = ctx.data::<GraphQLContext>()?;
         context.require_scope(ApiScope::ArchivesWrite)?;
+        let auth = context.require_auth()?;
         
-        ArchiveResolver::create_archive(input).await
+        ArchiveResolver::create_archive(&context.server_state, &auth.user_id, input).await
+    }
+
+    /// Annule une archive en attente ou en cours de traitement
+    async fn cancel_archive(
+        &self,
+        ctx: &async_graphql::Context<'_>,
+        id: String,
+    ) -> async_graphql::Result<CancelArchivePayload> {
+        let context = ctx.data::<GraphQLContext>()?;
+        context.require_scope(ApiScope::ArchivesWrite)?;
+        let auth = context.require_auth()?;
+
+        ArchiveResolver::cancel_archive(&context.server_state, &auth.user_id, id).await
     }
 
     /// Met à jour une archive
@@ -174,16 +257,46 @@ pub struct SubscriptionRoot;
 
 #[Subscription]
 impl SubscriptionRoot {
-    /// Souscription aux mises à jour d'une archive
-    async fn archive_status_updated(
+    /// Souscription aux blocs ajoutés à la chaîne
+    ///
+    /// `fromHeight` rejoue d'abord les blocs déjà présents depuis cette
+    /// hauteur, puis enchaîne sur les blocs à venir.
+    async fn block_added(
+        &self,
+        ctx: &async_graphql::Context<'_>,
+        from_height: Option<i64>,
+    ) -> async_graphql::Result<impl Stream<Item = BlockAdded>> {
+        let context = ctx.data::<GraphQLContext>()?;
+        context.require_scope(ApiScope::NetworkRead)?;
+
+        let from_height = from_height
+            .map(u64::try_from)
+            .transpose()
+            .map_err(|_| async_graphql::Error::new("`fromHeight` must be non-negative"))?;
+        SubscriptionResolver::block_added(&context.server_state, from_height).await
+    }
+
+    /// Souscription aux changements de statut d'une archive
+    async fn archive_status_changed(
         &self,
         ctx: &async_graphql::Context<'_>,
         archive_id: String,
-    ) -> async_graphql::Result<impl Stream<Item = Archive>> {
+    ) -> async_graphql::Result<impl Stream<Item = ArchiveStatusChange>> {
         let context = ctx.data::<GraphQLContext>()?;
         context.require_scope(ApiScope::ArchivesRead)?;
-        
-        SubscriptionResolver::archive_updates(archive_id).await
+
+        SubscriptionResolver::archive_status_changed(&context.server_state, archive_id).await
+    }
+
+    /// Souscription au nombre de pairs actifs du réseau
+    async fn peer_count_changed(
+        &self,
+        ctx: &async_graphql::Context<'_>,
+    ) -> async_graphql::Result<impl Stream<Item = i32>> {
+        let context = ctx.data::<GraphQLContext>()?;
+        context.require_scope(ApiScope::NetworkRead)?;
+
+        SubscriptionResolver::peer_count_changed(&context.server_state).await
     }
 
     /// Souscription aux nouvelles archives créées
@@ -225,6 +338,22 @@ pub struct Archive {
     pub completed_at: Option<chrono::DateTime<chrono::Utc>>,
     pub size: i64,
     pub cost: TokenAmount,
+    /// Blocs au-dessus du bloc d'inclusion ; `null` tant que l'archive n'est pas sur la chaîne
+    pub confirmations: Option<i64>,
+    /// Inclusion à l'abri des réorganisations
+    pub finalized: bool,
+}
+
+/// Capture d'une URL dans sa chronologie
+#[derive(SimpleObject, Clone)]
+pub struct ArchiveVersion {
+    pub archive_id: String,
+    pub url: String,
+    pub captured_at: chrono::DateTime<chrono::Utc>,
+    pub content_hash: Option<String>,
+    pub previous_archive_id: Option<String>,
+    /// Archive d'origine lorsque le contenu est inchangé
+    pub revisit_of: Option<String>,
 }
 
 /// Statut d'archive
@@ -235,6 +364,7 @@ pub enum ArchiveStatus {
     Completed,
     Failed,
     Expired,
+    Cancelled,
 }
 
 /// Métadonnées d'archive
@@ -270,6 +400,7 @@ pub struct TokenAmount {
 pub struct ArchiveConnection {
     pub edges: Vec<ArchiveEdge>,
     pub page_info: PageInfo,
+    pub total_count: i64,
 }
 
 /// Edge pour une archive
@@ -288,9 +419,162 @@ pub struct PageInfo {
     pub end_cursor: Option<String>,
 }
 
+/// Taille de page appliquée quand ni `first` ni `last` ne sont fournis
+pub const DEFAULT_PAGE_SIZE: usize = 20;
+/// Taille de page maximale acceptée
+pub const MAX_PAGE_SIZE: usize = 100;
+
+/// Arguments de pagination Relay (`first`/`after`, `last`/`before`)
+#[derive(Debug, Clone, Default)]
+pub struct ConnectionArgs {
+    pub first: Option<i32>,
+    pub after: Option<String>,
+    pub last: Option<i32>,
+    pub before: Option<String>,
+}
+
+/// Curseur opaque d'archive
+///
+/// Le curseur encode la date de création et l'ID de l'archive : il désigne une position
+/// dans l'ordre de tri et non un index, si bien qu'une insertion ne décale pas les curseurs
+/// déjà distribués.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArchiveCursor {
+    pub created_at: DateTime<Utc>,
+    pub id: String,
+}
+
+impl ArchiveCursor {
+    const PREFIX: &'static str = "archive";
+
+    /// Construit le curseur d'une archive
+    pub fn from_archive(archive: &Archive) -> Self {
+        Self {
+            created_at: archive.created_at,
+            id: archive.id.clone(),
+        }
+    }
+
+    /// Encode le curseur en base64 (URL-safe, sans padding)
+    pub fn encode(&self) -> String {
+        let raw = format!("{}:{}:{}", Self::PREFIX, self.created_at.timestamp_micros(), self.id);
+        URL_SAFE_NO_PAD.encode(raw)
+    }
+
+    /// Décode un curseur reçu d'un client
+    pub fn decode(cursor: &str) -> async_graphql::Result<Self> {
+        let invalid = || async_graphql::Error::new(format!("Curseur invalide : {}", cursor));
+
+        let bytes = URL_SAFE_NO_PAD.decode(cursor).map_err(|_| invalid())?;
+        let raw = String::from_utf8(bytes).map_err(|_| invalid())?;
+        let mut parts = raw.splitn(3, ':');
+        if parts.next() != Some(Self::PREFIX) {
+            return Err(invalid());
+        }
+        let micros: i64 = parts.next().and_then(|p| p.parse().ok()).ok_or_else(invalid)?;
+        let id = parts.next().ok_or_else(invalid)?.to_string();
+        let created_at = Utc.timestamp_micros(micros).single().ok_or_else(invalid)?;
+
+        Ok(Self { created_at, id })
+    }
+
+    fn cmp_with(&self, other: &Self, direction: SortDirection) -> Ordering {
+        let ordering = self.created_at.cmp(&other.created_at).then_with(|| self.id.cmp(&other.id));
+        match direction {
+            SortDirection::Asc => ordering,
+            SortDirection::Desc => ordering.reverse(),
+        }
+    }
+}
+
+impl ArchiveConnection {
+    /// Construit une page Relay à partir de l'ensemble des archives correspondant à la requête
+    ///
+    /// Les archives sont ordonnées par date de création puis par ID. `after`/`before` bornent
+    /// la fenêtre par comparaison de clés, puis `first` garde le début et `last` la fin de
+    /// la fenêtre. `hasNextPage`/`hasPreviousPage` indiquent s'il reste des archives de part
+    /// et d'autre de la page retournée.
+    pub fn paginate(mut archives: Vec<Archive>, direction: SortDirection, args: ConnectionArgs) -> async_graphql::Result<Self> {
+        let first = Self::page_size(args.first, "first")?;
+        let last = Self::page_size(args.last, "last")?;
+        let after = args.after.as_deref().map(ArchiveCursor::decode).transpose()?;
+        let before = args.before.as_deref().map(ArchiveCursor::decode).transpose()?;
+
+        let mut keyed: Vec<(ArchiveCursor, Archive)> = archives
+            .drain(..)
+            .map(|archive| (ArchiveCursor::from_archive(&archive), archive))
+            .collect();
+        keyed.sort_by(|(a, _), (b, _)| a.cmp_with(b, direction));
+
+        let mut start = match &after {
+            Some(cursor) => keyed.partition_point(|(key, _)| key.cmp_with(cursor, direction) != Ordering::Greater),
+            None => 0,
+        };
+        let mut end = match &before {
+            Some(cursor) => keyed.partition_point(|(key, _)| key.cmp_with(cursor, direction) == Ordering::Less),
+            None => keyed.len(),
+        };
+        end = end.max(start);
+
+        match (first, last) {
+            (None, None) => end = end.min(start + DEFAULT_PAGE_SIZE),
+            (first, last) => {
+                if let Some(first) = first {
+                    end = end.min(start + first);
+                }
+                if let Some(last) = last {
+                    start = start.max(end.saturating_sub(last));
+                }
+            }
+        }
+
+        let total_count = keyed.len() as i64;
+        let has_previous_page = start > 0;
+        let has_next_page = end < keyed.len();
+
+        let edges: Vec<ArchiveEdge> = keyed
+            .drain(start..end)
+            .map(|(cursor, node)| ArchiveEdge { node, cursor: cursor.encode() })
+            .collect();
+
+        Ok(Self {
+            page_info: PageInfo {
+                has_next_page,
+                has_previous_page,
+                start_cursor: edges.first().map(|edge| edge.cursor.clone()),
+                end_cursor: edges.last().map(|edge| edge.cursor.clone()),
+            },
+            edges,
+            total_count,
+        })
+    }
+
+    fn page_size(value: Option<i32>, argument: &str) -> async_graphql::Result<Option<usize>> {
+        match value {
+            None => Ok(None),
+            Some(n) if n < 0 => Err(async_graphql::Error::new(format!("`{}` doit être positif", argument))),
+            Some(n) => Ok(Some((n as usize).min(MAX_PAGE_SIZE))),
+        }
+    }
+}
+
+/// Pagination page/limit, identique aux `PaginationParams` de l'API REST
+#[derive(InputObject, Default)]
+pub struct PaginationInput {
+    /// Numéro de page (à partir de 1, défaut 1)
+    pub page: Option<i32>,
+    /// Nombre d'éléments par page (défaut 20)
+    pub limit: Option<i32>,
+}
+
 /// Filtres pour les archives
 #[derive(InputObject)]
 pub struct ArchiveFilter {
+    /// Recherche plein texte (titre, description, tags, hôte de l'URL)
+    ///
+    /// Avec `pagination`, les archives sont classées par pertinence ; la
+    /// pagination par curseurs conserve l'ordre de création.
+    pub search: Option<String>,
     pub status: Option<ArchiveStatus>,
     pub tags: Option<Vec<String>>,
     pub content_type: Option<String>,
@@ -336,6 +620,9 @@ pub struct ArchiveOptions {
     pub max_depth: Option<i32>,
     pub preserve_javascript: Option<bool>,
     pub allowed_domains: Option<Vec<String>>,
+    pub same_domain_only: Option<bool>,
+    pub max_total_size: Option<i64>,
+    pub respect_robots_txt: Option<bool>,
 }
 
 /// Payload de création d'archive
@@ -359,6 +646,13 @@ pub struct UpdateArchivePayload {
     pub errors: Vec<String>,
 }
 
+/// Payload d'annulation d'archive
+#[derive(SimpleObject, Clone)]
+pub struct CancelArchivePayload {
+    pub archive: Archive,
+    pub errors: Vec<String>,
+}
+
 /// Payload de suppression d'archive
 #[derive(SimpleObject, Clone)]
 pub struct DeleteArchivePayload {
@@ -527,6 +821,40 @@ pub struct Block {
     pub validator: String,
 }
 
+/// Bloc ajouté à la chaîne, tel que diffusé aux abonnés
+#[derive(SimpleObject, Clone)]
+pub struct BlockAdded {
+    pub height: i64,
+    pub hash: String,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    pub transaction_count: i32,
+    pub archive_count: i32,
+    pub validator: String,
+}
+
+impl From<crate::api::websocket::BlockUpdate> for BlockAdded {
+    fn from(block: crate::api::websocket::BlockUpdate) -> Self {
+        Self {
+            height: block.height as i64,
+            hash: block.hash,
+            timestamp: block.timestamp,
+            transaction_count: block.transactions as i32,
+            archive_count: block.archives as i32,
+            validator: block.validator,
+        }
+    }
+}
+
+/// Changement de statut d'une archive
+#[derive(SimpleObject, Clone)]
+pub struct ArchiveStatusChange {
+    pub archive_id: String,
+    pub status: ArchiveStatus,
+    /// Progression du traitement (0.0 à 1.0), si connue
+    pub progress: Option<f64>,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+}
+
 /// Transaction
 #[derive(SimpleObject, Clone)]
 pub struct Transaction {
@@ -551,6 +879,34 @@ pub enum TransactionType {
     Vote,
 }
 
+/// Reçu de transaction
+#[derive(SimpleObject, Clone)]
+pub struct TransactionReceipt {
+    pub transaction_hash: String,
+    pub block_hash: String,
+    pub block_height: i64,
+    pub index: i32,
+    /// Frais prélevés par l'exécution
+    pub gas_used: String,
+    pub events: Vec<TokenEvent>,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    /// Blocs au-dessus du bloc d'inclusion
+    pub confirmations: i64,
+    /// Inclusion à l'abri des réorganisations
+    pub finalized: bool,
+}
+
+/// Événement token d'un reçu
+#[derive(SimpleObject, Clone)]
+pub struct TokenEvent {
+    pub event_type: String,
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub amount: TokenAmount,
+    pub detail: Option<String>,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+}
+
 /// Connexion de blocs
 #[derive(SimpleObject, Clone)]
 pub struct BlockConnection {
@@ -593,4 +949,105 @@ mod tests {
         let tx_type = TransactionType::Archive;
         assert_eq!(tx_type, TransactionType::Archive);
     }
+
+    fn archive_at(id: &str, minutes: i64) -> Archive {
+        let created_at = Utc.timestamp_opt(1_700_000_000, 0).unwrap() + chrono::Duration::minutes(minutes);
+        Archive {
+            id: id.to_string(),
+            url: format!("https://example.com/{}", id),
+            status: ArchiveStatus::Completed,
+            metadata: ArchiveMetadata {
+                title: None,
+                description: None,
+                tags: vec![],
+                content_type: "text/html".to_string(),
+                language: None,
+                author: None,
+                published_at: None,
+            },
+            storage_info: StorageInfo {
+                replicas: 3,
+                locations: vec![],
+                integrity_score: 1.0,
+                last_verified: created_at,
+            },
+            created_at,
+            completed_at: Some(created_at),
+            size: 1024,
+            cost: TokenAmount {
+                amount: "0.001".to_string(),
+                currency: "ARC".to_string(),
+            },
+            confirmations: None,
+            finalized: false,
+        }
+    }
+
+    fn archives(count: i64) -> Vec<Archive> {
+        (0..count).map(|i| archive_at(&format!("arc_{:02}", i), i)).collect()
+    }
+
+    fn ids(connection: &ArchiveConnection) -> Vec<String> {
+        connection.edges.iter().map(|edge| edge.node.id.clone()).collect()
+    }
+
+    #[test]
+    fn test_archive_cursor_roundtrip() {
+        let cursor = ArchiveCursor::from_archive(&archive_at("arc:with:colons", 3));
+        assert_eq!(ArchiveCursor::decode(&cursor.encode()).unwrap(), cursor);
+        assert!(ArchiveCursor::decode("pas-un-curseur").is_err());
+        assert!(ArchiveCursor::decode(&URL_SAFE_NO_PAD.encode("block:1:x")).is_err());
+    }
+
+    #[test]
+    fn test_forward_pagination_has_next_page() {
+        let args = ConnectionArgs { first: Some(2), ..Default::default() };
+        let page = ArchiveConnection::paginate(archives(5), SortDirection::Asc, args).unwrap();
+        assert_eq!(ids(&page), vec!["arc_00", "arc_01"]);
+        assert!(page.page_info.has_next_page);
+        assert!(!page.page_info.has_previous_page);
+
+        let args = ConnectionArgs { first: Some(3), after: page.page_info.end_cursor.clone(), ..Default::default() };
+        let page = ArchiveConnection::paginate(archives(5), SortDirection::Asc, args).unwrap();
+        assert_eq!(ids(&page), vec!["arc_02", "arc_03", "arc_04"]);
+        // Page exactement terminale : pas de page suivante
+        assert!(!page.page_info.has_next_page);
+        assert!(page.page_info.has_previous_page);
+    }
+
+    #[test]
+    fn test_cursors_stable_across_inserts() {
+        let first_page = ArchiveConnection::paginate(archives(4), SortDirection::Desc, ConnectionArgs { first: Some(2), ..Default::default() }).unwrap();
+        assert_eq!(ids(&first_page), vec!["arc_03", "arc_02"]);
+
+        // Une nouvelle archive arrive en tête entre deux requêtes
+        let mut with_insert = archives(4);
+        with_insert.push(archive_at("arc_new", 60));
+        let args = ConnectionArgs { first: Some(2), after: first_page.page_info.end_cursor.clone(), ..Default::default() };
+        let next_page = ArchiveConnection::paginate(with_insert, SortDirection::Desc, args).unwrap();
+        assert_eq!(ids(&next_page), vec!["arc_01", "arc_00"]);
+        assert!(!next_page.page_info.has_next_page);
+        assert_eq!(next_page.edges[0].cursor, ArchiveCursor::from_archive(&archive_at("arc_01", 1)).encode());
+    }
+
+    #[test]
+    fn test_backward_pagination() {
+        let args = ConnectionArgs { last: Some(2), ..Default::default() };
+        let page = ArchiveConnection::paginate(archives(5), SortDirection::Asc, args).unwrap();
+        assert_eq!(ids(&page), vec!["arc_03", "arc_04"]);
+        assert!(page.page_info.has_previous_page);
+        assert!(!page.page_info.has_next_page);
+
+        let args = ConnectionArgs { last: Some(2), before: page.page_info.start_cursor.clone(), ..Default::default() };
+        let page = ArchiveConnection::paginate(archives(5), SortDirection::Asc, args).unwrap();
+        assert_eq!(ids(&page), vec!["arc_01", "arc_02"]);
+        assert!(page.page_info.has_previous_page);
+        assert!(page.page_info.has_next_page);
+    }
+
+    #[test]
+    fn test_negative_page_size_rejected() {
+        let args = ConnectionArgs { first: Some(-1), ..Default::default() };
+        assert!(ArchiveConnection::paginate(archives(2), SortDirection::Asc, args).is_err());
+    }
 }
\ No newline at end of file
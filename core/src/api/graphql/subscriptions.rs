@@ -238,6 +238,8 @@ impl SubscriptionManager {
                                     amount: "0".to_string(),
                                     currency: "ARC".to_string(),
                                 },
+                                confirmations: None,
+                                finalized: false,
                             })
                         }
                         Err(_) => None,
@@ -527,6 +529,8 @@ mod tests {
                 amount: "0.001".to_string(),
                 currency: "ARC".to_string(),
             },
+            confirmations: None,
+            finalized: false,
         };
         
         let result = manager.publish_new_archive(archive).await;
@@ -311,6 +311,8 @@ impl TypeConverter {
                 amount: "0.001".to_string(), // TODO: Calculer le vrai coût
                 currency: "ARC".to_string(),
             },
+            confirmations: dto.confirmations.map(|confirmations| confirmations as i64),
+            finalized: dto.finalized,
         }
     }
 
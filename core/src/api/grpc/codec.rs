@@ -0,0 +1,83 @@
+//! Codec des messages gRPC inter-nœuds
+//!
+//! Les messages de [`super::proto`] ne sont pas générés par prost : ils sont
+//! encodés en bincode dans les trames gRPC. La compression gzip des trames
+//! est négociée par tonic, indépendamment du codec.
+
+use std::marker::PhantomData;
+use bytes::{Buf, BufMut};
+use serde::{de::DeserializeOwned, Serialize};
+use tonic::{
+    codec::{Codec, DecodeBuf, Decoder, EncodeBuf, Encoder},
+    Status,
+};
+
+/// Chemins des méthodes RPC, partagés par les clients et les services
+pub mod paths {
+    pub const SUBMIT_ARCHIVE: &str = "/archivechain.ArchiveService/SubmitArchive";
+    pub const GET_ARCHIVE: &str = "/archivechain.ArchiveService/GetArchive";
+    pub const SEARCH_ARCHIVES: &str = "/archivechain.ArchiveService/SearchArchives";
+    pub const GET_NETWORK_STATS: &str = "/archivechain.NetworkService/GetNetworkStats";
+    pub const GET_NODE_INFO: &str = "/archivechain.NetworkService/GetNodeInfo";
+    pub const LIST_PEERS: &str = "/archivechain.NetworkService/ListPeers";
+    pub const GET_BLOCK: &str = "/archivechain.SyncService/GetBlock";
+    pub const GET_BLOCK_RANGE: &str = "/archivechain.SyncService/GetBlockRange";
+}
+
+/// Codec bincode : encode `T`, décode `U`
+#[derive(Debug)]
+pub struct BincodeCodec<T, U>(PhantomData<fn(T) -> U>);
+
+impl<T, U> Default for BincodeCodec<T, U> {
+    fn default() -> Self {
+        Self(PhantomData)
+    }
+}
+
+impl<T, U> Codec for BincodeCodec<T, U>
+where
+    T: Serialize + Send + 'static,
+    U: DeserializeOwned + Send + 'static,
+{
+    type Encode = T;
+    type Decode = U;
+    type Encoder = BincodeEncoder<T>;
+    type Decoder = BincodeDecoder<U>;
+
+    fn encoder(&mut self) -> Self::Encoder {
+        BincodeEncoder(PhantomData)
+    }
+
+    fn decoder(&mut self) -> Self::Decoder {
+        BincodeDecoder(PhantomData)
+    }
+}
+
+/// Encodeur d'un message
+#[derive(Debug)]
+pub struct BincodeEncoder<T>(PhantomData<fn(T)>);
+
+impl<T: Serialize> Encoder for BincodeEncoder<T> {
+    type Item = T;
+    type Error = Status;
+
+    fn encode(&mut self, item: T, dst: &mut EncodeBuf<'_>) -> Result<(), Status> {
+        bincode::serialize_into(dst.writer(), &item)
+            .map_err(|e| Status::internal(format!("Failed to encode message: {}", e)))
+    }
+}
+
+/// Décodeur d'un message
+#[derive(Debug)]
+pub struct BincodeDecoder<U>(PhantomData<fn() -> U>);
+
+impl<U: DeserializeOwned> Decoder for BincodeDecoder<U> {
+    type Item = U;
+    type Error = Status;
+
+    fn decode(&mut self, src: &mut DecodeBuf<'_>) -> Result<Option<U>, Status> {
+        let item = bincode::deserialize_from(src.reader())
+            .map_err(|e| Status::invalid_argument(format!("Failed to decode message: {}", e)))?;
+        Ok(Some(item))
+    }
+}
@@ -6,17 +6,24 @@
 pub mod server;
 pub mod client;
 pub mod services;
+pub mod pool;
+pub mod codec;
 
 use serde::{Deserialize, Serialize};
+use std::future::Future;
 use std::net::SocketAddr;
-use tonic::transport::Server;
+use tonic::{metadata::MetadataValue, transport::Server, Request, Response, Status};
+use tracing::Instrument;
 
 use crate::api::{ApiResult, server::ServerState};
+use crate::api::middleware::{resolve_request_id, with_request_id, REQUEST_ID_HEADER};
+use crate::shutdown::{Drained, ShutdownHook, ShutdownPhase, ShutdownToken};
 
 // Re-exports
 pub use server::*;
 pub use client::*;
 pub use services::*;
+pub use pool::*;
 
 /// Configuration gRPC
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -60,16 +67,65 @@ impl Default for GrpcConfig {
     }
 }
 
+impl GrpcConfig {
+    /// Vérifie les fichiers TLS/mTLS avant l'ouverture du port
+    ///
+    /// Avec `enable_tls`, le certificat et la clé doivent exister, être en PEM
+    /// et correspondre ; avec `enable_mtls`, le CA des clients est requis.
+    pub fn validate(&self) -> ApiResult<()> {
+        if self.enable_mtls && !self.enable_tls {
+            return Err(crate::api::ApiError::validation("gRPC enable_mtls requires enable_tls"));
+        }
+        if !self.enable_tls {
+            return Ok(());
+        }
+
+        let (Some(cert_path), Some(key_path)) = (&self.tls_cert_path, &self.tls_key_path) else {
+            return Err(crate::api::ApiError::validation(
+                "gRPC enable_tls requires tls_cert_path and tls_key_path",
+            ));
+        };
+        crate::api::tls::validate_identity(cert_path, key_path)?;
+
+        if self.enable_mtls {
+            let ca_path = self.ca_cert_path.as_deref().ok_or_else(|| {
+                crate::api::ApiError::validation("gRPC enable_mtls requires ca_cert_path")
+            })?;
+            crate::api::tls::validate_ca_bundle(ca_path)?;
+        }
+        Ok(())
+    }
+}
+
 /// Handle du serveur gRPC
 pub struct GrpcServerHandle {
     pub addr: SocketAddr,
-    pub shutdown_tx: tokio::sync::oneshot::Sender<()>,
+    pub shutdown: ShutdownToken,
+    drained: Drained,
 }
 
 impl GrpcServerHandle {
+    /// Lance `server`, arrêté au déclenchement du jeton de `state`
+    pub(crate) fn spawn<F>(addr: SocketAddr, state: &ServerState, server: F) -> Self
+    where
+        F: std::future::Future<Output = ()> + Send + 'static,
+    {
+        let shutdown = state.shutdown.clone();
+        let drain_timeout = std::time::Duration::from_secs(state.config.server.shutdown_timeout);
+        let drained = Drained::spawn("gRPC", shutdown.clone(), drain_timeout, server);
+        Self { addr, shutdown, drained }
+    }
+
     /// Arrête le serveur gRPC
-    pub fn shutdown(self) -> Result<(), ()> {
-        self.shutdown_tx.send(()).map_err(|_| ())
+    ///
+    /// Déclenche le jeton partagé : les autres serveurs du même état s'arrêtent aussi.
+    pub fn shutdown(&self) {
+        self.shutdown.trigger();
+    }
+
+    /// Se résout quand le serveur a cessé d'écouter et drainé ses appels
+    pub async fn wait_for_shutdown(&self) {
+        self.drained.wait().await;
     }
 
     /// Retourne l'adresse d'écoute
@@ -78,6 +134,21 @@ impl GrpcServerHandle {
     }
 }
 
+#[async_trait::async_trait]
+impl ShutdownHook for GrpcServerHandle {
+    fn name(&self) -> &str {
+        "grpc"
+    }
+
+    async fn on_shutdown(&self, phase: ShutdownPhase) -> crate::Result<()> {
+        if phase == ShutdownPhase::Drain {
+            self.shutdown();
+            self.wait_for_shutdown().await;
+        }
+        Ok(())
+    }
+}
+
 /// Serveur gRPC principal
 pub struct GrpcServer {
     config: GrpcConfig,
@@ -92,6 +163,8 @@ impl GrpcServer {
 
     /// Démarre le serveur gRPC
     pub async fn start(self) -> ApiResult<GrpcServerHandle> {
+        self.config.validate()?;
+
         let addr: SocketAddr = format!("{}:{}", self.config.listen_addr, self.config.port)
             .parse()
             .map_err(|e| crate::api::ApiError::internal(format!("Invalid gRPC address: {}", e)))?;
@@ -137,9 +210,6 @@ impl GrpcServer {
             }
         }
 
-        // Canal pour l'arrêt propre
-        let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel();
-
         // Construit le serveur avec tous les services
         let server = server_builder
             .add_service(archive_service.into_service())
@@ -148,11 +218,12 @@ impl GrpcServer {
 
         tracing::info!("Starting gRPC server on {}", addr);
 
-        // Lance le serveur dans une tâche séparée
-        tokio::spawn(async move {
+        // Lance le serveur dans une tâche séparée, arrêtée par le jeton partagé
+        let signal = self.state.shutdown.clone();
+        let handle = GrpcServerHandle::spawn(addr, &self.state, async move {
             if let Err(e) = server
-                .serve_with_shutdown(addr, async {
-                    let _ = shutdown_rx.await;
+                .serve_with_shutdown(addr, async move {
+                    signal.triggered().await;
                     tracing::info!("Shutting down gRPC server gracefully");
                 })
                 .await
@@ -163,13 +234,40 @@ impl GrpcServer {
 
         tracing::info!("gRPC server started successfully on {}", addr);
 
-        Ok(GrpcServerHandle {
-            addr,
-            shutdown_tx,
-        })
+        Ok(handle)
     }
 }
 
+/// Exécute un appel gRPC sous l'identifiant de corrélation de l'appelant
+///
+/// L'identifiant est repris de la métadonnée `x-request-id` ou généré, porté
+/// par un span `grpc` et par `current_request_id()` pendant l'appel, puis
+/// renvoyé dans les métadonnées de la réponse comme du statut d'erreur.
+pub(crate) async fn traced<T, R, F, Fut>(
+    method: &'static str,
+    request: Request<T>,
+    handler: F,
+) -> Result<Response<R>, Status>
+where
+    F: FnOnce(Request<T>) -> Fut,
+    Fut: Future<Output = Result<Response<R>, Status>>,
+{
+    let request_id = resolve_request_id(
+        request.metadata().get(REQUEST_ID_HEADER).and_then(|value| value.to_str().ok()),
+    );
+    let span = tracing::info_span!("grpc", request_id = %request_id, method);
+    let mut result = with_request_id(request_id.clone(), handler(request)).instrument(span).await;
+
+    if let Ok(value) = MetadataValue::try_from(request_id.as_str()) {
+        let metadata = match &mut result {
+            Ok(response) => response.metadata_mut(),
+            Err(status) => status.metadata_mut(),
+        };
+        metadata.insert(REQUEST_ID_HEADER, value);
+    }
+    result
+}
+
 /// Erreurs gRPC spécifiques
 #[derive(Debug, thiserror::Error)]
 pub enum GrpcError {
@@ -213,13 +311,34 @@ impl From<GrpcError> for tonic::Status {
     }
 }
 
+impl From<tonic::Status> for GrpcError {
+    fn from(status: tonic::Status) -> Self {
+        let message = status.message().to_string();
+        match status.code() {
+            tonic::Code::InvalidArgument => GrpcError::InvalidRequest(message),
+            tonic::Code::NotFound => GrpcError::NotFound(message),
+            tonic::Code::PermissionDenied => GrpcError::PermissionDenied(message),
+            tonic::Code::Unavailable => GrpcError::Unavailable(message),
+            tonic::Code::Unauthenticated => GrpcError::Unauthenticated,
+            tonic::Code::DeadlineExceeded => GrpcError::DeadlineExceeded,
+            tonic::Code::ResourceExhausted => GrpcError::ResourceExhausted,
+            _ => GrpcError::Internal(message),
+        }
+    }
+}
+
 impl From<crate::api::ApiError> for GrpcError {
     fn from(err: crate::api::ApiError) -> Self {
         match err {
             crate::api::ApiError::Authentication(_) => GrpcError::Unauthenticated,
             crate::api::ApiError::Authorization(msg) => GrpcError::PermissionDenied(msg),
             crate::api::ApiError::Validation(msg) => GrpcError::InvalidRequest(msg),
+            crate::api::ApiError::InvalidFields(errors) => GrpcError::InvalidRequest(
+                errors.iter().map(|e| format!("{}: {}", e.field, e.message)).collect::<Vec<_>>().join("; ")
+            ),
+            error @ crate::api::ApiError::ContentTooLarge { .. } => GrpcError::InvalidRequest(error.to_string()),
             crate::api::ApiError::NotFound(msg) => GrpcError::NotFound(msg),
+            crate::api::ApiError::PrunedData(msg) => GrpcError::NotFound(msg),
             crate::api::ApiError::RateLimit => GrpcError::ResourceExhausted,
             crate::api::ApiError::ServiceUnavailable(msg) => GrpcError::Unavailable(msg),
             _ => GrpcError::Internal(err.to_string()),
@@ -262,6 +381,35 @@ mod tests {
         assert!(!config.enable_mtls);
     }
 
+    #[test]
+    fn test_grpc_config_validate_tls() {
+        let mut config = GrpcConfig::default();
+        assert!(config.validate().is_ok());
+
+        config.enable_tls = true;
+        assert!(config.validate().is_err());
+
+        let dir = tempfile::tempdir().unwrap();
+        let identity = rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+        let cert_path = dir.path().join("grpc.crt");
+        std::fs::write(&cert_path, identity.cert.pem()).unwrap();
+        std::fs::write(dir.path().join("grpc.key"), identity.key_pair.serialize_pem()).unwrap();
+        config.tls_cert_path = Some(cert_path.display().to_string());
+
+        // Chemin de clé mal orthographié : échec avant l'ouverture du port
+        config.tls_key_path = Some(dir.path().join("grcp.key").display().to_string());
+        let err = config.validate().unwrap_err();
+        assert!(err.to_string().contains("grcp.key"));
+
+        config.tls_key_path = Some(dir.path().join("grpc.key").display().to_string());
+        assert!(config.validate().is_ok());
+
+        config.enable_mtls = true;
+        assert!(config.validate().is_err());
+        config.ca_cert_path = config.tls_cert_path.clone();
+        assert!(config.validate().is_ok());
+    }
+
     #[test]
     fn test_grpc_error_conversion() {
         let err = GrpcError::InvalidRequest("test error".to_string());
@@ -301,6 +449,23 @@ mod tests {
         assert_eq!(server.config.port, 9090);
     }
 
+    #[tokio::test]
+    async fn test_traced_call_propagates_request_id() {
+        let mut request = Request::new(());
+        request.metadata_mut().insert(REQUEST_ID_HEADER, MetadataValue::from_static("grpc-req-7"));
+        let response = traced("Test", request, |_| async {
+            Ok(Response::new(crate::api::middleware::current_request_id()))
+        }).await.unwrap();
+        assert_eq!(response.get_ref().as_deref(), Some("grpc-req-7"));
+        assert_eq!(response.metadata().get(REQUEST_ID_HEADER).unwrap(), "grpc-req-7");
+
+        let status = traced::<(), (), _, _>("Test", Request::new(()), |_| async {
+            Err(Status::not_found("missing"))
+        }).await.unwrap_err();
+        let generated = status.metadata().get(REQUEST_ID_HEADER).unwrap().to_str().unwrap();
+        assert!(uuid::Uuid::parse_str(generated).is_ok());
+    }
+
     #[test]
     fn test_service_builder() {
         let blockchain = std::sync::Arc::new(
@@ -368,6 +533,12 @@ pub mod proto {
     pub struct SubmitArchiveRequest {
         pub url: String,
         pub metadata: std::collections::HashMap<String, String>,
+        /// Contenu capturé (vide si non fourni), clé de déduplication
+        #[serde(default)]
+        pub content: Vec<u8>,
+        /// Valide la demande et en estime le coût sans rien enregistrer
+        #[serde(default)]
+        pub dry_run: bool,
     }
 
     /// Réponse de soumission d'archive
@@ -375,6 +546,24 @@ pub mod proto {
     pub struct SubmitArchiveResponse {
         pub archive_id: String,
         pub status: String,
+        /// Contenu identique déjà archivé : `archive_id` désigne l'archive existante
+        #[serde(default)]
+        pub deduplicated: bool,
+        /// Projection d'une soumission `dry_run` (sans `archive_id`)
+        #[serde(default)]
+        pub estimate: Option<SubmitArchiveEstimate>,
+    }
+
+    /// Projection d'une soumission `dry_run` (version proto)
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    pub struct SubmitArchiveEstimate {
+        pub content_type: String,
+        pub estimated_size: u64,
+        pub replicas: u32,
+        pub replication_strategy: String,
+        pub total_cost: String,
+        pub projected_reward_min: String,
+        pub projected_reward_max: String,
     }
 
     /// Requête de recherche
@@ -386,12 +575,19 @@ pub mod proto {
     }
 
     /// Archive (version proto)
-    #[derive(Debug, Clone, Serialize, Deserialize)]
+    ///
+    /// Encodée en protobuf par la négociation de contenu de l'API REST.
+    #[derive(Clone, PartialEq, Serialize, Deserialize, prost::Message)]
     pub struct Archive {
+        #[prost(string, tag = "1")]
         pub id: String,
+        #[prost(string, tag = "2")]
         pub url: String,
+        #[prost(string, tag = "3")]
         pub status: String,
+        #[prost(uint64, tag = "4")]
         pub size: u64,
+        #[prost(int64, tag = "5")]
         pub created_at: i64,
     }
 
@@ -403,11 +599,17 @@ pub mod proto {
     }
 
     /// Statistiques réseau
-    #[derive(Debug, Clone, Serialize, Deserialize)]
+    ///
+    /// Encodées en protobuf par la négociation de contenu de l'API REST.
+    #[derive(Clone, PartialEq, Serialize, Deserialize, prost::Message)]
     pub struct NetworkStats {
+        #[prost(uint32, tag = "1")]
         pub total_nodes: u32,
+        #[prost(uint32, tag = "2")]
         pub active_nodes: u32,
+        #[prost(uint64, tag = "3")]
         pub current_block_height: u64,
+        #[prost(uint64, tag = "4")]
         pub total_archives: u64,
     }
 }
\ No newline at end of file
@@ -0,0 +1,473 @@
+//! Pool de connexions gRPC inter-nœuds
+//!
+//! Partage un nombre borné de canaux par endpoint entre les clients gRPC :
+//! - Vérification de santé des canaux restés inactifs avant réutilisation
+//! - Éviction des canaux en échec ou expirés
+//! - Retry avec backoff exponentiel sur `Unavailable` / `DeadlineExceeded`,
+//!   réservé aux appels idempotents ou porteurs d'une clé d'idempotence
+//! - Statistiques des connexions actives, inactives et en échec
+
+use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+use tonic::{
+    body::BoxBody,
+    codegen::http,
+    transport::{Channel, ClientTlsConfig, Endpoint},
+    Code, Status,
+};
+use tower::ServiceExt;
+
+use super::{client::ClientConfig, GrpcError, GrpcResult};
+
+/// Méthodes RPC appelées entre nœuds
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GrpcMethod {
+    SubmitArchive,
+    GetArchive,
+    SearchArchives,
+    GetBlock,
+    GetBlockRange,
+    GetNetworkStats,
+    GetNodeInfo,
+    ListPeers,
+}
+
+impl GrpcMethod {
+    /// Vrai si rejouer l'appel ne peut pas modifier l'état distant
+    pub fn is_idempotent(&self) -> bool {
+        !matches!(self, Self::SubmitArchive)
+    }
+}
+
+/// Statistiques du pool de connexions
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PoolStats {
+    /// Canaux actuellement prêtés
+    pub active_connections: usize,
+    /// Canaux disponibles dans le pool
+    pub idle_connections: usize,
+    /// Connexions en échec (création, health check ou appel)
+    pub failed_connections: u64,
+    /// Canaux évincés (échec ou inactivité)
+    pub evicted_connections: u64,
+    /// Tentatives rejouées
+    pub retries: u64,
+}
+
+/// Canal conservé dans le pool
+struct PooledConnection {
+    channel: Channel,
+    last_used: Instant,
+}
+
+/// Canaux d'un endpoint
+#[derive(Default)]
+struct EndpointSlots {
+    idle: Vec<PooledConnection>,
+    active: usize,
+}
+
+struct PoolInner {
+    config: ClientConfig,
+    endpoints: Mutex<HashMap<String, EndpointSlots>>,
+    failed_connections: AtomicU64,
+    evicted_connections: AtomicU64,
+    retries: AtomicU64,
+}
+
+impl PoolInner {
+    fn release(&self, endpoint: &str, connection: Option<PooledConnection>) {
+        let mut endpoints = self.endpoints.lock().unwrap();
+        let slots = endpoints.entry(endpoint.to_string()).or_default();
+        slots.active = slots.active.saturating_sub(1);
+        match connection {
+            Some(mut connection) => {
+                connection.last_used = Instant::now();
+                slots.idle.push(connection);
+            }
+            None => {
+                self.evicted_connections.fetch_add(1, Ordering::Relaxed);
+            }
+        }
+    }
+}
+
+/// Canal emprunté au pool, rendu automatiquement à la destruction
+pub struct PooledChannel {
+    pool: Arc<PoolInner>,
+    endpoint: String,
+    connection: Option<PooledConnection>,
+    failed: bool,
+}
+
+impl PooledChannel {
+    /// Canal tonic utilisable pour construire un client de service
+    pub fn channel(&self) -> Channel {
+        self.connection.as_ref().map(|c| c.channel.clone()).expect("canal présent jusqu'à la restitution")
+    }
+
+    /// Endpoint du canal
+    pub fn endpoint(&self) -> &str {
+        &self.endpoint
+    }
+
+    /// Signale un échec : le canal sera évincé au lieu d'être réutilisé
+    pub fn mark_failed(&mut self) {
+        if !self.failed {
+            self.failed = true;
+            self.pool.failed_connections.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+impl Drop for PooledChannel {
+    fn drop(&mut self) {
+        let connection = if self.failed { None } else { self.connection.take() };
+        self.pool.release(&self.endpoint, connection);
+    }
+}
+
+/// Pool de canaux gRPC partagé entre clients
+#[derive(Clone)]
+pub struct GrpcClientPool {
+    inner: Arc<PoolInner>,
+}
+
+impl GrpcClientPool {
+    /// Crée un pool vide
+    pub fn new(config: ClientConfig) -> Self {
+        Self {
+            inner: Arc::new(PoolInner {
+                config,
+                endpoints: Mutex::new(HashMap::new()),
+                failed_connections: AtomicU64::new(0),
+                evicted_connections: AtomicU64::new(0),
+                retries: AtomicU64::new(0),
+            }),
+        }
+    }
+
+    /// Configuration du pool
+    pub fn config(&self) -> &ClientConfig {
+        &self.inner.config
+    }
+
+    /// Emprunte un canal vers l'endpoint
+    ///
+    /// Réutilise un canal inactif (vérifié s'il est resté inactif plus de
+    /// `health_check_interval`), sinon en ouvre un nouveau dans la limite
+    /// de `max_connections` par endpoint.
+    pub async fn checkout(&self, endpoint: &str) -> GrpcResult<PooledChannel> {
+        let config = &self.inner.config;
+        let idle_timeout = Duration::from_secs(config.idle_timeout);
+        let health_check_interval = Duration::from_secs(config.health_check_interval);
+
+        loop {
+            let candidate = {
+                let mut endpoints = self.inner.endpoints.lock().unwrap();
+                let slots = endpoints.entry(endpoint.to_string()).or_default();
+
+                let before = slots.idle.len();
+                slots.idle.retain(|c| c.last_used.elapsed() < idle_timeout);
+                let expired = (before - slots.idle.len()) as u64;
+                self.inner.evicted_connections.fetch_add(expired, Ordering::Relaxed);
+
+                match slots.idle.pop() {
+                    Some(connection) => {
+                        slots.active += 1;
+                        Some(connection)
+                    }
+                    None if slots.active >= config.max_connections => {
+                        return Err(GrpcError::ResourceExhausted);
+                    }
+                    None => {
+                        // Réserve la place avant de relâcher le verrou
+                        slots.active += 1;
+                        None
+                    }
+                }
+            };
+
+            let mut pooled = PooledChannel {
+                pool: self.inner.clone(),
+                endpoint: endpoint.to_string(),
+                connection: None,
+                failed: false,
+            };
+
+            match candidate {
+                Some(mut connection) => {
+                    if connection.last_used.elapsed() >= health_check_interval
+                        && !self.health_check(&mut connection.channel).await
+                    {
+                        tracing::debug!("Canal gRPC vers {} en échec au health check", endpoint);
+                        pooled.mark_failed();
+                        continue;
+                    }
+                    pooled.connection = Some(connection);
+                    return Ok(pooled);
+                }
+                None => match self.create_channel(endpoint).await {
+                    Ok(channel) => {
+                        pooled.connection = Some(PooledConnection { channel, last_used: Instant::now() });
+                        return Ok(pooled);
+                    }
+                    Err(e) => {
+                        pooled.mark_failed();
+                        return Err(e);
+                    }
+                },
+            }
+        }
+    }
+
+    /// Exécute un appel avec retry et backoff exponentiel
+    ///
+    /// Seuls `Unavailable` et `DeadlineExceeded` sont rejoués, et uniquement
+    /// pour les méthodes idempotentes ou quand une clé d'idempotence est
+    /// fournie (à transmettre au serveur dans les métadonnées de la requête).
+    pub async fn call<F, Fut, R>(
+        &self,
+        endpoint: &str,
+        method: GrpcMethod,
+        idempotency_key: Option<&str>,
+        mut operation: F,
+    ) -> GrpcResult<R>
+    where
+        F: FnMut(Channel) -> Fut,
+        Fut: Future<Output = Result<R, Status>>,
+    {
+        let retryable = method.is_idempotent() || idempotency_key.is_some();
+        let max_attempts = if retryable { self.inner.config.max_retries + 1 } else { 1 };
+        let mut last_error = GrpcError::Internal("Max retries exceeded".to_string());
+
+        for attempt in 0..max_attempts {
+            if attempt > 0 {
+                self.inner.retries.fetch_add(1, Ordering::Relaxed);
+                tokio::time::sleep(self.backoff(attempt)).await;
+            }
+
+            let mut pooled = match self.checkout(endpoint).await {
+                Ok(pooled) => pooled,
+                Err(e @ GrpcError::Unavailable(_)) => {
+                    last_error = e;
+                    continue;
+                }
+                Err(e) => return Err(e),
+            };
+
+            match operation(pooled.channel()).await {
+                Ok(response) => return Ok(response),
+                Err(status) if is_transient(&status) => {
+                    if status.code() == Code::Unavailable {
+                        pooled.mark_failed();
+                    }
+                    tracing::debug!("Appel {:?} vers {} en échec (tentative {}): {}", method, endpoint, attempt + 1, status);
+                    last_error = status.into();
+                }
+                Err(status) => return Err(status.into()),
+            }
+        }
+
+        Err(last_error)
+    }
+
+    /// Délai avant la tentative `attempt` (>= 1), plafonné à `max_backoff_ms`
+    fn backoff(&self, attempt: u32) -> Duration {
+        let config = &self.inner.config;
+        let factor = 1u64.checked_shl(attempt - 1).unwrap_or(u64::MAX);
+        Duration::from_millis(config.retry_delay_ms.saturating_mul(factor).min(config.max_backoff_ms))
+    }
+
+    /// Vérifie qu'un canal peut encore établir sa connexion
+    async fn health_check(&self, channel: &mut Channel) -> bool {
+        let timeout = Duration::from_secs(self.inner.config.connect_timeout);
+        let ready = ServiceExt::<http::Request<BoxBody>>::ready(channel);
+        matches!(tokio::time::timeout(timeout, ready).await, Ok(Ok(_)))
+    }
+
+    /// Crée un canal paresseux : la connexion est établie au premier appel
+    async fn create_channel(&self, endpoint: &str) -> GrpcResult<Channel> {
+        let config = &self.inner.config;
+        let mut endpoint = Endpoint::from_shared(endpoint.to_string())
+            .map_err(|e| GrpcError::Internal(format!("Invalid endpoint: {}", e)))?;
+
+        // Configure les timeouts
+        endpoint = endpoint
+            .connect_timeout(Duration::from_secs(config.connect_timeout))
+            .timeout(Duration::from_secs(config.request_timeout));
+
+        // Configure TLS si activé
+        if config.enable_tls {
+            let mut tls_config = ClientTlsConfig::new();
+
+            if let Some(domain) = &config.tls_domain {
+                tls_config = tls_config.domain_name(domain);
+            }
+
+            if let Some(ca_path) = &config.ca_cert_path {
+                let ca_cert = tokio::fs::read(ca_path).await
+                    .map_err(|e| GrpcError::Internal(format!("Failed to read CA cert: {}", e)))?;
+                let ca_cert = tonic::transport::Certificate::from_pem(ca_cert);
+                tls_config = tls_config.ca_certificate(ca_cert);
+            }
+
+            endpoint = endpoint.tls_config(tls_config)
+                .map_err(|e| GrpcError::Internal(format!("TLS config error: {}", e)))?;
+        }
+
+        Ok(endpoint.connect_lazy())
+    }
+
+    /// Statistiques courantes du pool
+    pub fn stats(&self) -> PoolStats {
+        let endpoints = self.inner.endpoints.lock().unwrap();
+        PoolStats {
+            active_connections: endpoints.values().map(|s| s.active).sum(),
+            idle_connections: endpoints.values().map(|s| s.idle.len()).sum(),
+            failed_connections: self.inner.failed_connections.load(Ordering::Relaxed),
+            evicted_connections: self.inner.evicted_connections.load(Ordering::Relaxed),
+            retries: self.inner.retries.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// Erreurs transitoires justifiant un retry
+fn is_transient(status: &Status) -> bool {
+    matches!(status.code(), Code::Unavailable | Code::DeadlineExceeded)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::AtomicU32;
+
+    const ENDPOINT: &str = "http://127.0.0.1:50051";
+
+    fn pool() -> GrpcClientPool {
+        GrpcClientPool::new(ClientConfig {
+            max_connections: 2,
+            max_retries: 3,
+            retry_delay_ms: 1,
+            max_backoff_ms: 4,
+            ..ClientConfig::default()
+        })
+    }
+
+    #[tokio::test]
+    async fn test_checkout_reuses_and_bounds_channels() {
+        let pool = pool();
+
+        let first = pool.checkout(ENDPOINT).await.unwrap();
+        let second = pool.checkout(ENDPOINT).await.unwrap();
+        assert!(matches!(pool.checkout(ENDPOINT).await, Err(GrpcError::ResourceExhausted)));
+        assert_eq!(pool.stats().active_connections, 2);
+
+        drop(first);
+        let stats = pool.stats();
+        assert_eq!((stats.active_connections, stats.idle_connections), (1, 1));
+
+        // Un canal en échec est évincé au lieu de revenir dans le pool
+        let mut second = second;
+        second.mark_failed();
+        drop(second);
+        let stats = pool.stats();
+        assert_eq!((stats.active_connections, stats.idle_connections), (0, 1));
+        assert_eq!(stats.failed_connections, 1);
+        assert_eq!(stats.evicted_connections, 1);
+
+        // Les autres endpoints ont leur propre quota
+        let _other = pool.checkout("http://127.0.0.1:50052").await.unwrap();
+        assert!(matches!(pool.checkout("not a uri").await, Err(GrpcError::Internal(_))));
+    }
+
+    #[tokio::test]
+    async fn test_idempotent_call_retries_with_backoff() {
+        let pool = pool();
+        let attempts = AtomicU32::new(0);
+
+        let result = pool
+            .call(ENDPOINT, GrpcMethod::GetBlock, None, |_channel| {
+                let attempt = attempts.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    match attempt {
+                        0 => Err(Status::unavailable("peer down")),
+                        1 => Err(Status::deadline_exceeded("slow peer")),
+                        _ => Ok(42),
+                    }
+                }
+            })
+            .await;
+
+        assert_eq!(result.unwrap(), 42);
+        assert_eq!(attempts.load(Ordering::SeqCst), 3);
+        let stats = pool.stats();
+        assert_eq!(stats.retries, 2);
+        // Seul `Unavailable` invalide le canal
+        assert_eq!(stats.failed_connections, 1);
+        assert_eq!(stats.active_connections, 0);
+
+        assert_eq!(pool.backoff(1), Duration::from_millis(1));
+        assert_eq!(pool.backoff(3), Duration::from_millis(4));
+        assert_eq!(pool.backoff(10), Duration::from_millis(4));
+    }
+
+    #[tokio::test]
+    async fn test_retries_are_bounded_and_limited_to_transient_errors() {
+        let pool = pool();
+        let attempts = AtomicU32::new(0);
+        let result: GrpcResult<()> = pool
+            .call(ENDPOINT, GrpcMethod::SearchArchives, None, |_channel| {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                async { Err(Status::unavailable("peer down")) }
+            })
+            .await;
+        assert!(matches!(result, Err(GrpcError::Unavailable(_))));
+        assert_eq!(attempts.load(Ordering::SeqCst), 4);
+
+        let attempts = AtomicU32::new(0);
+        let result: GrpcResult<()> = pool
+            .call(ENDPOINT, GrpcMethod::GetBlock, None, |_channel| {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                async { Err(Status::not_found("unknown block")) }
+            })
+            .await;
+        assert!(matches!(result, Err(GrpcError::NotFound(_))));
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_submit_archive_retried_only_with_idempotency_key() {
+        let pool = pool();
+
+        let attempts = AtomicU32::new(0);
+        let result: GrpcResult<()> = pool
+            .call(ENDPOINT, GrpcMethod::SubmitArchive, None, |_channel| {
+                attempts.fetch_add(1, Ordering::SeqCst);
+                async { Err(Status::unavailable("peer down")) }
+            })
+            .await;
+        assert!(result.is_err());
+        assert_eq!(attempts.load(Ordering::SeqCst), 1);
+
+        let attempts = AtomicU32::new(0);
+        let result = pool
+            .call(ENDPOINT, GrpcMethod::SubmitArchive, Some("submit-1"), |_channel| {
+                let attempt = attempts.fetch_add(1, Ordering::SeqCst);
+                async move {
+                    if attempt == 0 {
+                        Err(Status::unavailable("peer down"))
+                    } else {
+                        Ok("arc_1")
+                    }
+                }
+            })
+            .await;
+        assert_eq!(result.unwrap(), "arc_1");
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+    }
+}
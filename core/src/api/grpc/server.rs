@@ -30,6 +30,8 @@ impl AuthenticatedGrpcServer {
 
     /// Démarre le serveur gRPC
     pub async fn start(self) -> GrpcResult<super::GrpcServerHandle> {
+        self.config.validate()?;
+
         let addr: SocketAddr = format!("{}:{}", self.config.listen_addr, self.config.port)
             .parse()
             .map_err(|e| GrpcError::Internal(format!("Invalid address: {}", e)))?;
@@ -57,9 +59,6 @@ impl AuthenticatedGrpcServer {
             server_builder = self.configure_tls(server_builder).await?;
         }
 
-        // Canal pour l'arrêt propre
-        let (shutdown_tx, shutdown_rx) = tokio::sync::oneshot::channel();
-
         // Lance le serveur avec les intercepteurs (API Tonic 0.10)
         let server = server_builder
             .add_service(
@@ -81,23 +80,19 @@ impl AuthenticatedGrpcServer {
 
         tracing::info!("Starting authenticated gRPC server on {}", addr);
 
-        // Lance le serveur dans une tâche séparée
-        tokio::spawn(async move {
+        // Lance le serveur dans une tâche séparée, arrêtée par le jeton partagé
+        let signal = self.state.shutdown.clone();
+        Ok(super::GrpcServerHandle::spawn(addr, &self.state, async move {
             if let Err(e) = server
-                .serve_with_shutdown(addr, async {
-                    let _ = shutdown_rx.await;
+                .serve_with_shutdown(addr, async move {
+                    signal.triggered().await;
                     tracing::info!("Shutting down gRPC server");
                 })
                 .await
             {
                 tracing::error!("gRPC server error: {}", e);
             }
-        });
-
-        Ok(super::GrpcServerHandle {
-            addr,
-            shutdown_tx,
-        })
+        }))
     }
 
     /// Configure TLS pour le serveur
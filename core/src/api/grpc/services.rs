@@ -2,23 +2,77 @@
 //!
 //! Contient tous les services gRPC selon les spécifications API.
 
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
 use tonic::{Request, Response, Status, async_trait};
 use futures_util::Stream;
 use std::pin::Pin;
 
-use crate::api::server::ServerState;
-use super::{GrpcError, GrpcResult, proto::*};
+use crate::api::{
+    middleware::AuthInfo,
+    server::ServerState,
+    types::{ArchiveOptions, CreateArchiveRequest},
+};
+use super::{GrpcError, GrpcResult, client::IDEMPOTENCY_KEY_HEADER, proto::*};
+
+/// Nombre maximum de clés d'idempotence mémorisées
+const MAX_IDEMPOTENCY_KEYS: usize = 10_000;
+
+/// Réponses des soumissions portant une clé d'idempotence, par appelant
+///
+/// Une entrée `None` est une soumission en cours : le même appel rejoué entre
+/// temps est refusé en `Unavailable`, puis obtient la réponse mémorisée.
+#[derive(Debug, Default)]
+struct IdempotentSubmissions {
+    responses: HashMap<(String, String), Option<SubmitArchiveResponse>>,
+    order: VecDeque<(String, String)>,
+}
+
+impl IdempotentSubmissions {
+    /// Réponse déjà connue, ou réservation de la clé pour une nouvelle soumission
+    fn begin(&mut self, key: &(String, String)) -> Result<Option<SubmitArchiveResponse>, Status> {
+        match self.responses.get(key) {
+            Some(Some(response)) => return Ok(Some(response.clone())),
+            Some(None) => return Err(Status::unavailable("Submission with this idempotency key is in progress")),
+            None => {}
+        }
+
+        self.responses.insert(key.clone(), None);
+        self.order.push_back(key.clone());
+        while self.order.len() > MAX_IDEMPOTENCY_KEYS {
+            if let Some(oldest) = self.order.pop_front() {
+                self.responses.remove(&oldest);
+            }
+        }
+        Ok(None)
+    }
+
+    /// Enregistre l'issue d'une soumission ; un échec libère la clé
+    fn finish(&mut self, key: &(String, String), response: Option<&SubmitArchiveResponse>) {
+        match response {
+            Some(response) => {
+                if let Some(entry) = self.responses.get_mut(key) {
+                    *entry = Some(response.clone());
+                }
+            }
+            None => {
+                self.responses.remove(key);
+                self.order.retain(|entry| entry != key);
+            }
+        }
+    }
+}
 
 /// Service d'archivage gRPC
 #[derive(Debug, Clone)]
 pub struct ArchiveServiceImpl {
     state: ServerState,
+    submissions: Arc<Mutex<IdempotentSubmissions>>,
 }
 
 impl ArchiveServiceImpl {
     pub fn new(state: ServerState) -> Self {
-        Self { state }
+        Self { state, submissions: Arc::new(Mutex::new(IdempotentSubmissions::default())) }
     }
 
     /// Convertit vers un service tonic
@@ -38,85 +92,137 @@ impl ArchiveService for ArchiveServiceServer {
         &self,
         request: Request<SubmitArchiveRequest>,
     ) -> Result<Response<SubmitArchiveResponse>, Status> {
-        let req = request.into_inner();
+        super::traced("SubmitArchive", request, |request| async move {
+            let owner = request.extensions().get::<AuthInfo>()
+                .map(|auth| auth.user_id.clone())
+                .unwrap_or_else(|| "anonymous".to_string());
+            let idempotency_key = request.metadata().get(IDEMPOTENCY_KEY_HEADER)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string);
+            let req = request.into_inner();
         
-        // Valide la requête
-        if req.url.is_empty() {
-            return Err(GrpcError::InvalidRequest("URL is required".to_string()).into());
-        }
-
-        // TODO: Valide l'URL
-        if let Err(_) = url::Url::parse(&req.url) {
-            return Err(GrpcError::InvalidRequest("Invalid URL format".to_string()).into());
-        }
-
-        // Génère un ID d'archive
-        let archive_id = format!("arc_{}", uuid::Uuid::new_v4().simple());
-
-        // TODO: Ajouter l'archive à la queue de traitement
-        tracing::info!("Submitting archive for URL: {}", req.url);
-
-        let response = SubmitArchiveResponse {
-            archive_id,
-            status: "pending".to_string(),
-        };
+            // Valide la requête
+            if req.url.is_empty() {
+                return Err(GrpcError::InvalidRequest("URL is required".to_string()).into());
+            }
+
+            tracing::info!("Submitting archive for URL: {}", req.url);
+
+            let content = (!req.content.is_empty()).then_some(req.content.as_slice());
+            let create_request = CreateArchiveRequest {
+                url: req.url.clone(),
+                metadata: req.metadata.clone(),
+                options: ArchiveOptions::default(),
+                content: None,
+                dry_run: req.dry_run,
+            };
 
-        Ok(Response::new(response))
+            // Simulation : la demande est validée et chiffrée, rien n'est enregistré
+            if create_request.dry_run {
+                let estimate = self.inner.state.archives.estimate_with_content(&create_request, content)
+                    .map_err(GrpcError::from)?;
+                let response = SubmitArchiveResponse {
+                    archive_id: String::new(),
+                    status: "dry_run".to_string(),
+                    deduplicated: false,
+                    estimate: Some(SubmitArchiveEstimate {
+                        content_type: estimate.content_type,
+                        estimated_size: estimate.estimated_size,
+                        replicas: estimate.replicas,
+                        replication_strategy: format!("{:?}", estimate.replication_strategy),
+                        total_cost: estimate.cost_estimation.total_cost,
+                        projected_reward_min: estimate.projected_reward_min,
+                        projected_reward_max: estimate.projected_reward_max,
+                    }),
+                };
+                return Ok(Response::new(response));
+            }
+
+            // Une soumission rejouée avec la même clé renvoie la réponse d'origine
+            let idempotency_key = idempotency_key.map(|key| (owner.clone(), key));
+            if let Some(key) = &idempotency_key {
+                if let Some(response) = self.inner.submissions.lock().unwrap().begin(key)? {
+                    return Ok(Response::new(response));
+                }
+            }
+
+            let result = self.inner.state.archives
+                .submit_with_content(&owner, create_request, content)
+                .await
+                .map(|submission| {
+                    let archive = submission.record.archive;
+                    SubmitArchiveResponse {
+                        archive_id: archive.archive_id,
+                        status: format!("{:?}", archive.status).to_lowercase(),
+                        deduplicated: submission.deduplicated,
+                        estimate: None,
+                    }
+                });
+            if let Some(key) = &idempotency_key {
+                self.inner.submissions.lock().unwrap().finish(key, result.as_ref().ok());
+            }
+
+            Ok(Response::new(result.map_err(GrpcError::from)?))
+        }).await
     }
 
     async fn get_archive(
         &self,
         request: Request<GetArchiveRequest>,
     ) -> Result<Response<GetArchiveResponse>, Status> {
-        let req = request.into_inner();
+        super::traced("GetArchive", request, |request| async move {
+            let req = request.into_inner();
         
-        if req.archive_id.is_empty() {
-            return Err(GrpcError::InvalidRequest("Archive ID is required".to_string()).into());
-        }
-
-        // TODO: Récupérer l'archive depuis la blockchain
-        tracing::info!("Getting archive: {}", req.archive_id);
-
-        // Pour l'instant, retourne une archive fictive
-        if req.archive_id.starts_with("arc_") {
-            let archive = Archive {
-                id: req.archive_id.clone(),
-                url: "https://example.com".to_string(),
-                status: "completed".to_string(),
-                size: 1024,
-                created_at: chrono::Utc::now().timestamp(),
-            };
-
-            let response = GetArchiveResponse {
-                archive: Some(archive),
-            };
-
-            Ok(Response::new(response))
-        } else {
-            Err(GrpcError::NotFound("Archive not found".to_string()).into())
-        }
+            if req.archive_id.is_empty() {
+                return Err(GrpcError::InvalidRequest("Archive ID is required".to_string()).into());
+            }
+
+            // TODO: Récupérer l'archive depuis la blockchain
+            tracing::info!("Getting archive: {}", req.archive_id);
+
+            // Pour l'instant, retourne une archive fictive
+            if req.archive_id.starts_with("arc_") {
+                let archive = Archive {
+                    id: req.archive_id.clone(),
+                    url: "https://example.com".to_string(),
+                    status: "completed".to_string(),
+                    size: 1024,
+                    created_at: chrono::Utc::now().timestamp(),
+                };
+
+                let response = GetArchiveResponse {
+                    archive: Some(archive),
+                };
+
+                Ok(Response::new(response))
+            } else {
+                Err(GrpcError::NotFound("Archive not found".to_string()).into())
+            }
+        }).await
     }
 
     async fn search_archives(
         &self,
         request: Request<SearchRequest>,
     ) -> Result<Response<SearchResponse>, Status> {
-        let req = request.into_inner();
+        super::traced("SearchArchives", request, |request| async move {
+            let req = request.into_inner();
         
-        if req.query.trim().is_empty() {
-            return Err(GrpcError::InvalidRequest("Search query is required".to_string()).into());
-        }
+            if req.query.trim().is_empty() {
+                return Err(GrpcError::InvalidRequest("Search query is required".to_string()).into());
+            }
 
-        // TODO: Implémenter la recherche réelle
-        tracing::info!("Searching archives for: {}", req.query);
+            // TODO: Implémenter la recherche réelle
+            tracing::info!("Searching archives for: {}", req.query);
 
-        let response = SearchResponse {
-            archives: vec![], // Placeholder
-            total_count: 0,
-            has_more: false,
-        };
+            let response = SearchResponse {
+                archives: vec![], // Placeholder
+                total_count: 0,
+                has_more: false,
+            };
 
-        Ok(Response::new(response))
+            Ok(Response::new(response))
+        }).await
     }
 
     type StreamArchiveUpdatesStream = Pin<Box<dyn Stream<Item = Result<ArchiveUpdate, Status>> + Send>>;
@@ -125,14 +231,16 @@ impl ArchiveService for ArchiveServiceServer {
         &self,
         request: Request<StreamArchiveUpdatesRequest>,
     ) -> Result<Response<Self::StreamArchiveUpdatesStream>, Status> {
-        let req = request.into_inner();
+        super::traced("StreamArchiveUpdates", request, |request| async move {
+            let req = request.into_inner();
         
-        tracing::info!("Starting archive updates stream for archive: {:?}", req.archive_id);
+            tracing::info!("Starting archive updates stream for archive: {:?}", req.archive_id);
 
-        // TODO: Implémenter le streaming réel depuis le système de pubsub
-        let stream = futures_util::stream::empty();
+            // TODO: Implémenter le streaming réel depuis le système de pubsub
+            let stream = futures_util::stream::empty();
         
-        Ok(Response::new(Box::pin(stream)))
+            Ok(Response::new(Box::pin(stream)))
+        }).await
     }
 }
 
@@ -160,57 +268,63 @@ pub struct NetworkServiceServer {
 impl NetworkService for NetworkServiceServer {
     async fn get_network_stats(
         &self,
-        _request: Request<GetNetworkStatsRequest>,
+        request: Request<GetNetworkStatsRequest>,
     ) -> Result<Response<NetworkStats>, Status> {
-        // Récupère les statistiques depuis la blockchain
-        let blockchain_stats = self.inner.state.blockchain.stats();
-
-        let stats = NetworkStats {
-            total_nodes: 100, // TODO: Récupérer le vrai nombre
-            active_nodes: 95,
-            current_block_height: blockchain_stats.height,
-            total_archives: blockchain_stats.total_transactions, // Approximation
-        };
+        super::traced("GetNetworkStats", request, |_request| async move {
+            // Récupère les statistiques depuis la blockchain
+            let blockchain_stats = self.inner.state.blockchain.stats();
+
+            let stats = NetworkStats {
+                total_nodes: 100, // TODO: Récupérer le vrai nombre
+                active_nodes: 95,
+                current_block_height: blockchain_stats.height,
+                total_archives: blockchain_stats.total_transactions, // Approximation
+            };
 
-        Ok(Response::new(stats))
+            Ok(Response::new(stats))
+        }).await
     }
 
     async fn get_node_info(
         &self,
         request: Request<GetNodeInfoRequest>,
     ) -> Result<Response<NodeInfo>, Status> {
-        let req = request.into_inner();
+        super::traced("GetNodeInfo", request, |request| async move {
+            let req = request.into_inner();
         
-        if req.node_id.is_empty() {
-            return Err(GrpcError::InvalidRequest("Node ID is required".to_string()).into());
-        }
-
-        // TODO: Récupérer les informations du nœud depuis le réseau P2P
-        tracing::info!("Getting node info for: {}", req.node_id);
-
-        let node_info = NodeInfo {
-            node_id: req.node_id,
-            status: "active".to_string(),
-            region: "us-east".to_string(),
-            last_seen: chrono::Utc::now().timestamp(),
-        };
+            if req.node_id.is_empty() {
+                return Err(GrpcError::InvalidRequest("Node ID is required".to_string()).into());
+            }
+
+            // TODO: Récupérer les informations du nœud depuis le réseau P2P
+            tracing::info!("Getting node info for: {}", req.node_id);
+
+            let node_info = NodeInfo {
+                node_id: req.node_id,
+                status: "active".to_string(),
+                region: "us-east".to_string(),
+                last_seen: chrono::Utc::now().timestamp(),
+            };
 
-        Ok(Response::new(node_info))
+            Ok(Response::new(node_info))
+        }).await
     }
 
     async fn list_peers(
         &self,
-        _request: Request<ListPeersRequest>,
+        request: Request<ListPeersRequest>,
     ) -> Result<Response<ListPeersResponse>, Status> {
-        // TODO: Récupérer la liste des pairs depuis le réseau P2P
-        tracing::info!("Listing network peers");
+        super::traced("ListPeers", request, |_request| async move {
+            // TODO: Récupérer la liste des pairs depuis le réseau P2P
+            tracing::info!("Listing network peers");
 
-        let response = ListPeersResponse {
-            peers: vec![], // Placeholder
-            total_count: 0,
-        };
+            let response = ListPeersResponse {
+                peers: vec![], // Placeholder
+                total_count: 0,
+            };
 
-        Ok(Response::new(response))
+            Ok(Response::new(response))
+        }).await
     }
 }
 
@@ -240,46 +354,50 @@ impl SyncService for SyncServiceServer {
         &self,
         request: Request<GetBlockRequest>,
     ) -> Result<Response<GetBlockResponse>, Status> {
-        let req = request.into_inner();
+        super::traced("GetBlock", request, |request| async move {
+            let req = request.into_inner();
         
-        if req.block_hash.is_empty() {
-            return Err(GrpcError::InvalidRequest("Block hash is required".to_string()).into());
-        }
+            if req.block_hash.is_empty() {
+                return Err(GrpcError::InvalidRequest("Block hash is required".to_string()).into());
+            }
 
-        // TODO: Récupérer le bloc depuis la blockchain
-        tracing::info!("Getting block: {}", req.block_hash);
+            // TODO: Récupérer le bloc depuis la blockchain
+            tracing::info!("Getting block: {}", req.block_hash);
 
-        // Pour l'instant, retourne None si le bloc n'existe pas
-        let response = GetBlockResponse {
-            block: None, // TODO: Implémenter la récupération réelle
-        };
+            // Pour l'instant, retourne None si le bloc n'existe pas
+            let response = GetBlockResponse {
+                block: None, // TODO: Implémenter la récupération réelle
+            };
 
-        Ok(Response::new(response))
+            Ok(Response::new(response))
+        }).await
     }
 
     async fn get_block_range(
         &self,
         request: Request<GetBlockRangeRequest>,
     ) -> Result<Response<GetBlockRangeResponse>, Status> {
-        let req = request.into_inner();
+        super::traced("GetBlockRange", request, |request| async move {
+            let req = request.into_inner();
         
-        if req.start_height > req.end_height {
-            return Err(GrpcError::InvalidRequest("Start height must be <= end height".to_string()).into());
-        }
+            if req.start_height > req.end_height {
+                return Err(GrpcError::InvalidRequest("Start height must be <= end height".to_string()).into());
+            }
 
-        let range_size = req.end_height - req.start_height;
-        if range_size > 1000 {
-            return Err(GrpcError::InvalidRequest("Range too large (max 1000 blocks)".to_string()).into());
-        }
+            let range_size = req.end_height - req.start_height;
+            if range_size > 1000 {
+                return Err(GrpcError::InvalidRequest("Range too large (max 1000 blocks)".to_string()).into());
+            }
 
-        // TODO: Récupérer les blocs depuis la blockchain
-        tracing::info!("Getting block range: {} - {}", req.start_height, req.end_height);
+            // TODO: Récupérer les blocs depuis la blockchain
+            tracing::info!("Getting block range: {} - {}", req.start_height, req.end_height);
 
-        let response = GetBlockRangeResponse {
-            blocks: vec![], // Placeholder
-        };
+            let response = GetBlockRangeResponse {
+                blocks: vec![], // Placeholder
+            };
 
-        Ok(Response::new(response))
+            Ok(Response::new(response))
+        }).await
     }
 
     type SyncBlocksStream = Pin<Box<dyn Stream<Item = Result<Block, Status>> + Send>>;
@@ -288,14 +406,16 @@ impl SyncService for SyncServiceServer {
         &self,
         request: Request<SyncRequest>,
     ) -> Result<Response<Self::SyncBlocksStream>, Status> {
-        let req = request.into_inner();
+        super::traced("SyncBlocks", request, |request| async move {
+            let req = request.into_inner();
         
-        tracing::info!("Starting block sync from height: {}", req.start_height);
+            tracing::info!("Starting block sync from height: {}", req.start_height);
 
-        // TODO: Implémenter le streaming des blocs
-        let stream = futures_util::stream::empty();
+            // TODO: Implémenter le streaming des blocs
+            let stream = futures_util::stream::empty();
         
-        Ok(Response::new(Box::pin(stream)))
+            Ok(Response::new(Box::pin(stream)))
+        }).await
     }
 }
 
@@ -463,6 +583,8 @@ mod tests {
         let request = Request::new(SubmitArchiveRequest {
             url: "https://example.com".to_string(),
             metadata: HashMap::new(),
+            content: Vec::new(),
+            dry_run: false,
         });
 
         let response = service.submit_archive(request).await;
@@ -473,6 +595,83 @@ mod tests {
         assert_eq!(response.status, "pending");
     }
 
+    #[tokio::test]
+    async fn test_archive_service_submit_deduplicates_content() {
+        let state = create_test_state();
+        let service = ArchiveServiceServer {
+            inner: ArchiveServiceImpl::new(state),
+        };
+        let submit = |url: &str, content: &[u8]| Request::new(SubmitArchiveRequest {
+            url: url.to_string(),
+            metadata: HashMap::new(),
+            content: content.to_vec(),
+            dry_run: false,
+        });
+
+        let first = service.submit_archive(submit("https://example.com", b"page")).await.unwrap().into_inner();
+        let second = service.submit_archive(submit("https://example.com", b"page")).await.unwrap().into_inner();
+        let third = service.submit_archive(submit("https://example.com", b"page v2")).await.unwrap().into_inner();
+
+        assert!(!first.deduplicated);
+        assert!(second.deduplicated);
+        assert_eq!(second.archive_id, first.archive_id);
+        assert!(!third.deduplicated);
+        assert_ne!(third.archive_id, first.archive_id);
+    }
+
+    #[tokio::test]
+    async fn test_archive_service_replays_submission_with_same_idempotency_key() {
+        let state = create_test_state();
+        let service = ArchiveServiceServer {
+            inner: ArchiveServiceImpl::new(state),
+        };
+        let submit = |url: &str, key: &str| {
+            let mut request = Request::new(SubmitArchiveRequest {
+                url: url.to_string(),
+                metadata: HashMap::new(),
+                content: Vec::new(),
+                dry_run: false,
+            });
+            request.metadata_mut().insert(IDEMPOTENCY_KEY_HEADER, key.parse().unwrap());
+            request
+        };
+
+        let first = service.submit_archive(submit("https://example.com/a", "submit-1")).await.unwrap().into_inner();
+        let replayed = service.submit_archive(submit("https://example.com/a", "submit-1")).await.unwrap().into_inner();
+        assert_eq!(replayed.archive_id, first.archive_id);
+
+        let other = service.submit_archive(submit("https://example.com/c", "submit-2")).await.unwrap().into_inner();
+        assert_ne!(other.archive_id, first.archive_id);
+
+        // Un échec libère la clé pour une nouvelle tentative
+        assert!(service.submit_archive(submit("not a url", "submit-3")).await.is_err());
+        assert!(service.submit_archive(submit("https://example.com/b", "submit-3")).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_archive_service_dry_run_does_not_persist() {
+        let state = create_test_state();
+        let service = ArchiveServiceServer {
+            inner: ArchiveServiceImpl::new(state.clone()),
+        };
+
+        let request = Request::new(SubmitArchiveRequest {
+            url: "https://example.com".to_string(),
+            metadata: HashMap::new(),
+            content: b"<html></html>".to_vec(),
+            dry_run: true,
+        });
+        let response = service.submit_archive(request).await.unwrap().into_inner();
+
+        assert!(response.archive_id.is_empty());
+        assert_eq!(response.status, "dry_run");
+        let estimate = response.estimate.unwrap();
+        assert_eq!(estimate.estimated_size, 13);
+        assert_eq!(estimate.replicas, 3);
+        assert_eq!(estimate.total_cost, "0.0035 ARC");
+        assert_eq!(state.archives.counts().await.0, 0);
+    }
+
     #[tokio::test]
     async fn test_archive_service_submit_archive_invalid_url() {
         let state = create_test_state();
@@ -483,6 +682,8 @@ mod tests {
         let request = Request::new(SubmitArchiveRequest {
             url: "".to_string(),
             metadata: HashMap::new(),
+            content: Vec::new(),
+            dry_run: false,
         });
 
         let response = service.submit_archive(request).await;
@@ -0,0 +1,146 @@
+//! Vérifications de santé des sous-systèmes
+//!
+//! Chaque sous-système (blockchain, stockage, P2P) est sondé à chaque requête
+//! sur `/health`. Un contrôle est soit sain, soit en démarrage (pairs
+//! insuffisants, synchronisation en cours), soit indisponible (capacité de
+//! stockage épuisée), soit en échec. Les orchestrateurs distinguent ainsi :
+//! - `/health/live` : le nœud fonctionne ; échoue seulement si un contrôle
+//!   critique est en échec (le redémarrer peut aider) ;
+//! - `/health/ready` : le nœud peut recevoir du trafic ; échoue tant qu'un
+//!   contrôle critique n'est pas sain, y compris pendant le démarrage ou
+//!   quand le stockage est saturé.
+
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+
+use crate::storage::StorageManager;
+use crate::Blockchain;
+
+/// État d'un contrôle de santé
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    /// Sous-système opérationnel
+    Healthy,
+    /// Sous-système pas encore prêt (démarrage, synchronisation)
+    Starting,
+    /// Sous-système fonctionnel mais incapable d'accepter du travail (capacité épuisée)
+    Unavailable,
+    /// Sous-système en échec
+    Unhealthy,
+}
+
+/// Résultat d'un contrôle de santé
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HealthCheck {
+    pub status: CheckStatus,
+    /// Un contrôle critique en échec rend le nœud entier défaillant
+    pub critical: bool,
+    /// Détail lisible du résultat
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+impl HealthCheck {
+    pub fn healthy(message: impl Into<String>) -> Self {
+        Self::new(CheckStatus::Healthy, message)
+    }
+
+    pub fn starting(message: impl Into<String>) -> Self {
+        Self::new(CheckStatus::Starting, message)
+    }
+
+    pub fn unavailable(message: impl Into<String>) -> Self {
+        Self::new(CheckStatus::Unavailable, message)
+    }
+
+    pub fn unhealthy(message: impl Into<String>) -> Self {
+        Self::new(CheckStatus::Unhealthy, message)
+    }
+
+    fn new(status: CheckStatus, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            critical: true,
+            message: Some(message.into()),
+        }
+    }
+
+    /// Marque le contrôle comme non critique
+    pub fn non_critical(mut self) -> Self {
+        self.critical = false;
+        self
+    }
+}
+
+/// Sous-système sondé par `/health`
+#[async_trait]
+pub trait HealthProbe: Send + Sync {
+    /// Nom du contrôle dans la réponse
+    fn name(&self) -> &str;
+
+    /// Sonde le sous-système
+    async fn check(&self) -> HealthCheck;
+}
+
+/// Vérifie que la tête de chaîne est lisible
+pub fn check_blockchain(blockchain: &Blockchain) -> HealthCheck {
+    match blockchain.get_head_block() {
+        Some(_) => HealthCheck::healthy(format!("height {}", blockchain.height())),
+        None => HealthCheck::unhealthy("cannot read chain tip"),
+    }
+}
+
+#[async_trait]
+impl HealthProbe for StorageManager {
+    fn name(&self) -> &str {
+        "storage"
+    }
+
+    /// Indisponible quand l'utilisation moyenne atteint le seuil critique de la
+    /// politique : un stockage saturé retire le nœud du trafic sans le redémarrer
+    async fn check(&self) -> HealthCheck {
+        let stats = match self.get_storage_stats().await {
+            Ok(stats) => stats,
+            Err(e) => return HealthCheck::unhealthy(format!("cannot read storage stats: {}", e)),
+        };
+
+        if stats.active_nodes == 0 {
+            return HealthCheck::starting("no storage node available");
+        }
+
+        let threshold = self.policy().alert_thresholds.critical_capacity_threshold;
+        let message = format!(
+            "capacity {:.1}% (critical at {:.1}%), {} active nodes",
+            stats.average_capacity_usage, threshold, stats.active_nodes
+        );
+        if stats.average_capacity_usage >= threshold {
+            HealthCheck::unavailable(message)
+        } else {
+            HealthCheck::healthy(message)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::BlockchainConfig;
+
+    #[test]
+    fn test_blockchain_check_reads_tip() {
+        let blockchain = Blockchain::new(BlockchainConfig::default()).unwrap();
+        let check = check_blockchain(&blockchain);
+        assert_eq!(check.status, CheckStatus::Healthy);
+        assert!(check.critical);
+        // Le bloc genesis compte dans la hauteur
+        assert_eq!(check.message.as_deref(), Some("height 1"));
+    }
+
+    #[test]
+    fn test_check_serialization() {
+        let check = HealthCheck::starting("syncing").non_critical();
+        let json = serde_json::to_value(&check).unwrap();
+        assert_eq!(json, serde_json::json!({ "status": "starting", "critical": false, "message": "syncing" }));
+    }
+}
@@ -1,34 +1,104 @@
 //! Middlewares de sécurité pour l'API ArchiveChain
 //!
 //! Ce module contient tous les middlewares nécessaires pour sécuriser l'API :
-//! - Authentification JWT
-//! - Rate limiting
-//! - CORS
+//! - Authentification JWT ou par clé API
+//! - Rate limiting et budget de jetons par appelant
+//! - CORS, en-têtes de sécurité et HTTPS forcé
 //! - Compression
 //! - Request ID
 //! - Logging et monitoring
 
-use crate::api::{ApiError, ApiResult, auth::{AuthService, JwtClaims, ApiScope}};
+use crate::api::{ApiError, ApiResult, auth::{AuthService, JwtClaims, ApiScope, UserManager}};
+use crate::nodes::gateway::{GatewaySecurityConfig, RateLimitStatus, RateLimiter as KeyRateLimiter, RateLimiterConfig};
+use crate::shutdown::ShutdownToken;
 use axum::{
-    extract::{Request, State},
-    http::{HeaderMap, HeaderValue, Method, StatusCode},
+    body::{Body, HttpBody},
+    extract::{ConnectInfo, OriginalUri, Request, State},
+    http::{header, HeaderMap, HeaderValue, Method, StatusCode},
     middleware::Next,
-    response::Response,
+    response::{IntoResponse, Response},
 };
-use governor::{Quota, RateLimiter};
+use futures::StreamExt;
 use serde::{Deserialize, Serialize};
 use std::{
     collections::HashMap,
-    net::IpAddr,
+    future::Future,
+    net::{IpAddr, SocketAddr},
     sync::Arc,
-    time::Duration,
+    time::{Duration, SystemTime},
 };
 use tower_http::{
-    cors::{CorsLayer, Any},
+    classify::{ServerErrorsAsFailures, SharedClassifier},
+    cors::{AllowOrigin, CorsLayer, Any},
     compression::CompressionLayer,
     trace::TraceLayer,
 };
-use tracing::{info, warn, error};
+use tracing::{info, warn, error, Instrument, Span};
+
+/// En-tête portant l'identifiant de corrélation d'une requête
+pub const REQUEST_ID_HEADER: &str = "x-request-id";
+
+/// En-tête portant une clé API, alternative au header `Authorization`
+pub const API_KEY_HEADER: &str = "x-api-key";
+
+/// Plafond de la limite de débit la plus contraignante
+pub const RATE_LIMIT_LIMIT_HEADER: &str = "x-ratelimit-limit";
+/// Requêtes restantes avant ce plafond
+pub const RATE_LIMIT_REMAINING_HEADER: &str = "x-ratelimit-remaining";
+/// Secondes avant que la limite soit de nouveau pleine
+pub const RATE_LIMIT_RESET_HEADER: &str = "x-ratelimit-reset";
+
+/// Fenêtre du budget d'un utilisateur authentifié (`JwtClaims::rate_limit` est horaire)
+const USER_BUDGET_WINDOW: Duration = Duration::from_secs(3600);
+
+/// Nombre maximum de budgets suivis, au-delà duquel les plus anciens sont oubliés
+const MAX_TRACKED_BUDGETS: usize = 10_000;
+
+/// Nombre de partitions des budgets, chacune sous son propre verrou
+const BUDGET_SHARDS: usize = 16;
+
+/// Longueur maximum d'un identifiant de corrélation fourni par le client
+const MAX_REQUEST_ID_LEN: usize = 128;
+
+/// Identifiant de corrélation de la requête, disponible dans ses extensions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestId(pub String);
+
+tokio::task_local! {
+    static CURRENT_REQUEST_ID: String;
+}
+
+/// Identifiant de corrélation de la requête en cours de traitement
+///
+/// Disponible dans toute la tâche qui traite la requête, y compris dans les
+/// couches stockage et P2P. Une tâche lancée avec `tokio::spawn` doit être
+/// enveloppée dans `with_request_id` pour en hériter.
+pub fn current_request_id() -> Option<String> {
+    CURRENT_REQUEST_ID.try_with(Clone::clone).ok()
+}
+
+/// Exécute `future` avec `request_id` comme identifiant de corrélation courant
+pub async fn with_request_id<F: Future>(request_id: String, future: F) -> F::Output {
+    CURRENT_REQUEST_ID.scope(request_id, future).await
+}
+
+/// Vérifie qu'un identifiant fourni par le client peut être repris tel quel
+fn is_valid_request_id(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= MAX_REQUEST_ID_LEN
+        && id.bytes().all(|b| b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.' | b':'))
+}
+
+/// Reprend l'identifiant fourni par l'appelant s'il est valide, en génère un sinon
+///
+/// Partagé par REST, gRPC et WebSocket pour qu'un même identifiant suive la
+/// requête quel que soit le protocole d'entrée.
+pub(crate) fn resolve_request_id(provided: Option<&str>) -> String {
+    provided
+        .filter(|id| is_valid_request_id(id))
+        .map(str::to_string)
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string())
+}
 
 /// Configuration des middlewares
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -41,6 +111,9 @@ pub struct MiddlewareConfig {
     pub compression: CompressionConfig,
     /// Configuration de logging
     pub logging: LoggingConfig,
+    /// En-têtes de sécurité et HTTPS forcé
+    #[serde(default)]
+    pub security_headers: SecurityHeadersConfig,
 }
 
 impl Default for MiddlewareConfig {
@@ -50,13 +123,37 @@ impl Default for MiddlewareConfig {
             rate_limit: RateLimitConfig::default(),
             compression: CompressionConfig::default(),
             logging: LoggingConfig::default(),
+            security_headers: SecurityHeadersConfig::default(),
         }
     }
 }
 
+impl MiddlewareConfig {
+    /// Applique la configuration de sécurité du Gateway (CORS et HTTPS forcé)
+    pub fn with_gateway_security(mut self, security: &GatewaySecurityConfig) -> Self {
+        self.cors.enabled = security.cors_enabled;
+        self.cors.allowed_origins = security.cors_allowed_origins.clone();
+        self.security_headers.force_https = security.force_https;
+        self
+    }
+
+    /// Vérifie la configuration avant le démarrage du serveur
+    pub fn validate(&self) -> ApiResult<()> {
+        self.cors.validate()?;
+        self.security_headers.validate()
+    }
+}
+
 /// Configuration CORS
+///
+/// Les origines sont des motifs [`OriginPattern`]. Avec `allow_credentials`,
+/// aucune origine, méthode ou en-tête ne peut être `*` : le navigateur
+/// ignorerait la réponse, la configuration est donc refusée au démarrage.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CorsConfig {
+    /// En-têtes CORS envoyés ; sinon les navigateurs refusent les appels d'autres origines
+    #[serde(default = "default_cors_enabled")]
+    pub enabled: bool,
     pub allowed_origins: Vec<String>,
     pub allowed_methods: Vec<String>,
     pub allowed_headers: Vec<String>,
@@ -65,9 +162,14 @@ pub struct CorsConfig {
     pub allow_credentials: bool,
 }
 
+fn default_cors_enabled() -> bool {
+    true
+}
+
 impl Default for CorsConfig {
     fn default() -> Self {
         Self {
+            enabled: true,
             allowed_origins: vec!["*".to_string()],
             allowed_methods: vec![
                 "GET".to_string(),
@@ -79,6 +181,7 @@ impl Default for CorsConfig {
             allowed_headers: vec![
                 "content-type".to_string(),
                 "authorization".to_string(),
+                "x-api-key".to_string(),
                 "x-request-id".to_string(),
             ],
             expose_headers: vec![
@@ -87,7 +190,194 @@ impl Default for CorsConfig {
                 "x-rate-limit-reset".to_string(),
             ],
             max_age: Some(86400), // 24 heures
-            allow_credentials: true,
+            // Les jetons passent par `Authorization`, pas par des cookies
+            allow_credentials: false,
+        }
+    }
+}
+
+impl CorsConfig {
+    /// Motifs des origines autorisées
+    pub fn origin_patterns(&self) -> ApiResult<Vec<OriginPattern>> {
+        self.allowed_origins.iter().map(|origin| OriginPattern::parse(origin)).collect()
+    }
+
+    /// Indique si une origine est autorisée
+    pub fn is_origin_allowed(&self, origin: &str) -> bool {
+        self.allowed_origins
+            .iter()
+            .filter_map(|pattern| OriginPattern::parse(pattern).ok())
+            .any(|pattern| pattern.matches(origin))
+    }
+
+    /// Refuse les motifs invalides et les jokers combinés à `allow_credentials`
+    pub fn validate(&self) -> ApiResult<()> {
+        let patterns = self.origin_patterns()?;
+
+        if self.allow_credentials {
+            if patterns.contains(&OriginPattern::Any) {
+                return Err(ApiError::validation(
+                    "CORS allow_credentials cannot be combined with the wildcard origin \"*\"; list the allowed origins instead",
+                ));
+            }
+            for (name, values) in [
+                ("allowed_methods", &self.allowed_methods),
+                ("allowed_headers", &self.allowed_headers),
+                ("expose_headers", &self.expose_headers),
+            ] {
+                if values.iter().any(|value| value == "*") {
+                    return Err(ApiError::validation(format!(
+                        "CORS allow_credentials cannot be combined with \"*\" in {}",
+                        name
+                    )));
+                }
+            }
+        }
+
+        if let Some(method) = self.allowed_methods.iter().find(|m| *m != "*" && m.parse::<Method>().is_err()) {
+            return Err(ApiError::validation(format!("Invalid CORS method {:?}", method)));
+        }
+        for header in self.allowed_headers.iter().chain(&self.expose_headers) {
+            if header != "*" && header.parse::<axum::http::HeaderName>().is_err() {
+                return Err(ApiError::validation(format!("Invalid CORS header {:?}", header)));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Motif d'origine CORS
+///
+/// `*` accepte toute origine. `*.example.org` accepte les sous-domaines
+/// d'`example.org` en HTTP ou HTTPS, `https://*.example.org` en HTTPS
+/// seulement ; le domaine lui-même n'est pas couvert. Toute autre valeur est
+/// une origine complète (`https://app.example.org`), comparée sans tenir
+/// compte de la casse.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum OriginPattern {
+    Any,
+    Exact(String),
+    Subdomains { scheme: Option<String>, suffix: String },
+}
+
+impl OriginPattern {
+    pub fn parse(pattern: &str) -> ApiResult<Self> {
+        let normalized = pattern.trim().trim_end_matches('/').to_ascii_lowercase();
+        if normalized == "*" {
+            return Ok(Self::Any);
+        }
+
+        let invalid = || ApiError::validation(format!("Invalid CORS origin {:?}", pattern));
+        let (scheme, authority) = match normalized.split_once("://") {
+            Some((scheme, authority)) => (Some(scheme.to_string()), authority),
+            None => (None, normalized.as_str()),
+        };
+
+        if let Some(suffix) = authority.strip_prefix("*.") {
+            if suffix.is_empty() || suffix.contains(['*', '/', '@']) {
+                return Err(invalid());
+            }
+            return Ok(Self::Subdomains { scheme, suffix: suffix.to_string() });
+        }
+
+        if scheme.is_none() || authority.is_empty() || authority.contains(['*', '/', '@']) {
+            return Err(invalid());
+        }
+        HeaderValue::from_str(&normalized).map_err(|_| invalid())?;
+        Ok(Self::Exact(normalized))
+    }
+
+    /// Indique si l'en-tête `Origin` d'une requête correspond au motif
+    pub fn matches(&self, origin: &str) -> bool {
+        let origin = origin.to_ascii_lowercase();
+        match self {
+            Self::Any => true,
+            Self::Exact(expected) => origin == *expected,
+            Self::Subdomains { scheme, suffix } => {
+                let Some((origin_scheme, authority)) = origin.split_once("://") else {
+                    return false;
+                };
+                let scheme_allowed = match scheme {
+                    Some(scheme) => scheme == origin_scheme,
+                    None => matches!(origin_scheme, "http" | "https"),
+                };
+                scheme_allowed
+                    && authority
+                        .strip_suffix(suffix.as_str())
+                        .and_then(|subdomain| subdomain.strip_suffix('.'))
+                        .is_some_and(|subdomain| !subdomain.is_empty() && !subdomain.contains(['/', '@', ':']))
+            }
+        }
+    }
+}
+
+/// Réponse à une requête arrivée en HTTP clair quand HTTPS est forcé
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HttpsEnforcement {
+    /// Redirection permanente (308) vers la même URL en HTTPS
+    Redirect,
+    /// Refus (403)
+    Reject,
+}
+
+/// Configuration des en-têtes de sécurité
+///
+/// Le serveur ne termine pas TLS : une connexion directe est en HTTP clair.
+/// Seules les connexions issues de `trusted_proxies` peuvent déclarer un
+/// autre protocole, par la dernière valeur de `forwarded_proto_header`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SecurityHeadersConfig {
+    /// `max-age` de `Strict-Transport-Security` (secondes), en-tête omis si absent
+    pub hsts_max_age: Option<u64>,
+    /// Étend HSTS aux sous-domaines
+    pub hsts_include_subdomains: bool,
+    /// Envoie `X-Content-Type-Options: nosniff`
+    pub content_type_options: bool,
+    /// Redirige ou refuse les requêtes arrivées en HTTP clair
+    pub force_https: bool,
+    /// Traitement d'une requête en HTTP clair quand `force_https` est actif
+    pub https_enforcement: HttpsEnforcement,
+    /// En-tête du proxy de confiance portant le protocole d'origine
+    pub forwarded_proto_header: String,
+    /// Adresses des proxies de confiance qui terminent TLS
+    #[serde(default)]
+    pub trusted_proxies: Vec<IpAddr>,
+    /// Hôte (et port éventuel) des redirections vers HTTPS, requis par `HttpsEnforcement::Redirect`
+    #[serde(default)]
+    pub canonical_host: Option<String>,
+}
+
+impl SecurityHeadersConfig {
+    /// Vérifie qu'une redirection vers HTTPS vise un hôte configuré
+    ///
+    /// L'en-tête `Host` est fourni par le client : rediriger vers lui
+    /// enverrait l'utilisateur sur un hôte arbitraire.
+    pub fn validate(&self) -> ApiResult<()> {
+        if !self.force_https || self.https_enforcement != HttpsEnforcement::Redirect {
+            return Ok(());
+        }
+        match self.canonical_host.as_deref() {
+            Some(host) if host.parse::<axum::http::uri::Authority>().is_ok() => Ok(()),
+            Some(host) => Err(ApiError::validation(format!("Invalid canonical_host \"{}\"", host))),
+            None => Err(ApiError::validation(
+                "force_https with https_enforcement = \"redirect\" requires security_headers.canonical_host",
+            )),
+        }
+    }
+}
+
+impl Default for SecurityHeadersConfig {
+    fn default() -> Self {
+        Self {
+            hsts_max_age: Some(31_536_000), // 1 an
+            hsts_include_subdomains: false,
+            content_type_options: true,
+            force_https: false,
+            https_enforcement: HttpsEnforcement::Redirect,
+            forwarded_proto_header: "x-forwarded-proto".to_string(),
+            trusted_proxies: Vec::new(),
+            canonical_host: None,
         }
     }
 }
@@ -105,6 +395,12 @@ pub struct RateLimitConfig {
     pub window_seconds: u64,
     /// Burst autorisé
     pub burst_size: u32,
+    /// Coût en jetons des groupes de routes ; les routes non listées coûtent 1
+    #[serde(default = "default_route_costs")]
+    pub route_costs: Vec<RouteCost>,
+    /// Scope exercé par les groupes de routes, qui choisit le budget prélevé
+    #[serde(default = "default_route_scopes")]
+    pub route_scopes: Vec<RouteScope>,
 }
 
 impl Default for RateLimitConfig {
@@ -115,10 +411,98 @@ impl Default for RateLimitConfig {
             premium_per_user: 1000,
             window_seconds: 60,
             burst_size: 10,
+            route_costs: default_route_costs(),
+            route_scopes: default_route_scopes(),
         }
     }
 }
 
+impl RateLimitConfig {
+    /// Coût d'une requête : celui du groupe au chemin le plus spécifique
+    pub fn route_cost(&self, method: &Method, path: &str) -> u32 {
+        self.route_costs
+            .iter()
+            .filter(|route| route.matches(method, path))
+            .max_by_key(|route| route.path.len())
+            .map_or(1, |route| route.cost)
+    }
+
+    /// Scope exercé par une requête : celui du groupe au chemin le plus spécifique
+    pub fn route_scope(&self, method: &Method, path: &str) -> Option<&ApiScope> {
+        self.route_scopes
+            .iter()
+            .filter(|route| route_matches(route.method.as_deref(), &route.path, method, path))
+            .max_by_key(|route| route.path.len())
+            .map(|route| &route.scope)
+    }
+}
+
+/// Coût d'un groupe de routes dans le budget de l'appelant
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RouteCost {
+    /// Méthode concernée, toutes si absente
+    pub method: Option<String>,
+    /// Chemin complet ; un suffixe `/*` couvre tout le sous-arbre
+    pub path: String,
+    /// Jetons prélevés par requête
+    pub cost: u32,
+}
+
+impl RouteCost {
+    fn matches(&self, method: &Method, path: &str) -> bool {
+        route_matches(self.method.as_deref(), &self.path, method, path)
+    }
+}
+
+/// Scope exercé par un groupe de routes
+///
+/// Si les limites de l'appelant prévoient ce scope (`RateLimit::scope_limits`),
+/// la requête est décomptée dans un budget propre au scope.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RouteScope {
+    /// Méthode concernée, toutes si absente
+    pub method: Option<String>,
+    /// Chemin complet ; un suffixe `/*` couvre tout le sous-arbre
+    pub path: String,
+    pub scope: ApiScope,
+}
+
+/// Filtre de méthode et motif de chemin communs à `RouteCost` et `RouteScope`
+fn route_matches(method_filter: Option<&str>, pattern: &str, method: &Method, path: &str) -> bool {
+    if method_filter.is_some_and(|m| !m.eq_ignore_ascii_case(method.as_str())) {
+        return false;
+    }
+    let path = path.trim_end_matches('/');
+    match pattern.strip_suffix("/*") {
+        Some(prefix) => path == prefix || path.starts_with(&format!("{}/", prefix)),
+        None => path == pattern.trim_end_matches('/'),
+    }
+}
+
+fn default_route_costs() -> Vec<RouteCost> {
+    vec![
+        RouteCost { method: Some("POST".to_string()), path: "/api/v1/rest/archives".to_string(), cost: 10 },
+        RouteCost { method: Some("POST".to_string()), path: "/api/v1/rest/search/bulk".to_string(), cost: 5 },
+    ]
+}
+
+fn default_route_scopes() -> Vec<RouteScope> {
+    let route = |method: Option<&str>, path: &str, scope: ApiScope| RouteScope {
+        method: method.map(str::to_string),
+        path: path.to_string(),
+        scope,
+    };
+    vec![
+        route(Some("GET"), "/api/v1/rest/archives/*", ApiScope::ArchivesRead),
+        route(Some("POST"), "/api/v1/rest/archives", ApiScope::ArchivesWrite),
+        route(Some("PUT"), "/api/v1/rest/archives/*", ApiScope::ArchivesWrite),
+        route(Some("DELETE"), "/api/v1/rest/archives/*", ApiScope::ArchivesWrite),
+        route(Some("GET"), "/api/v1/rest/search/*", ApiScope::SearchRead),
+        route(Some("GET"), "/api/v1/bounties/*", ApiScope::ArchivesRead),
+        route(Some("POST"), "/api/v1/bounties/*", ApiScope::ArchivesWrite),
+    ]
+}
+
 /// Configuration de compression
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CompressionConfig {
@@ -147,6 +531,17 @@ pub struct LoggingConfig {
     pub include_headers: bool,
     pub include_body: bool,
     pub max_body_size: usize,
+    /// Logs au format JSON (une ligne par événement, champs de span inclus) ;
+    /// texte par défaut
+    #[serde(default)]
+    pub json_format: bool,
+    /// Filtre de niveaux au format `RUST_LOG` (ex. `info,archivechain_core=debug`)
+    #[serde(default = "default_log_filter")]
+    pub filter: String,
+}
+
+fn default_log_filter() -> String {
+    "info".to_string()
 }
 
 impl Default for LoggingConfig {
@@ -159,6 +554,8 @@ impl Default for LoggingConfig {
             include_headers: false,
             include_body: false,
             max_body_size: 4096,
+            json_format: false,
+            filter: default_log_filter(),
         }
     }
 }
@@ -167,25 +564,185 @@ impl Default for LoggingConfig {
 #[derive(Clone)]
 pub struct MiddlewareState {
     pub auth_service: Arc<AuthService>,
+    /// Registre des clés API présentées dans `X-API-Key`
+    pub user_manager: Arc<tokio::sync::RwLock<UserManager>>,
     pub rate_limiters: Arc<RateLimiters>,
     pub config: MiddlewareConfig,
 }
 
 /// Gestionnaire de rate limiters
 pub struct RateLimiters {
-    pub ip_limiter: RateLimiter<IpAddr, governor::state::InMemoryState, governor::clock::DefaultClock>,
-    pub user_limiters: Arc<tokio::sync::RwLock<HashMap<String, RateLimiter<String, governor::state::InMemoryState, governor::clock::DefaultClock>>>>,
+    /// Limite par IP, par token bucket pour exposer son état au client
+    pub ip_limiter: KeyRateLimiter,
+    /// Budgets par utilisateur (`sub`) ou par IP pour les appels anonymes,
+    /// partagés par toutes les tâches du serveur et répartis par hash de la
+    /// clé pour ne pas sérialiser toutes les requêtes sur un seul verrou
+    budgets: Box<[tokio::sync::Mutex<HashMap<String, BudgetBucket>>]>,
+    /// Limites propres aux clés API, appliquées par le rate limiter du gateway
+    pub api_key_limiter: KeyRateLimiter,
 }
 
 impl RateLimiters {
     pub fn new(config: &RateLimitConfig) -> Self {
-        let quota = Quota::per_minute(config.global_per_ip);
-        let ip_limiter = RateLimiter::direct(quota);
+        let ip_limiter = KeyRateLimiter::new(RateLimiterConfig {
+            requests_per_second_per_ip: config.burst_size,
+            requests_per_minute_per_ip: config.global_per_ip,
+            ..RateLimiterConfig::default()
+        });
 
         Self {
             ip_limiter,
-            user_limiters: Arc::new(tokio::sync::RwLock::new(HashMap::new())),
+            budgets: (0..BUDGET_SHARDS).map(|_| tokio::sync::Mutex::new(HashMap::new())).collect(),
+            api_key_limiter: KeyRateLimiter::new(RateLimiterConfig::default()),
+        }
+    }
+
+    /// Prélève `cost` jetons dans le budget de `key`
+    ///
+    /// Le budget contient `capacity` jetons et se remplit entièrement en
+    /// `window` ; une capacité modifiée (nouveau token) s'applique aussitôt.
+    pub async fn acquire_budget(
+        &self,
+        key: &str,
+        capacity: u32,
+        window: Duration,
+        cost: u32,
+        now: SystemTime,
+    ) -> RateLimitStatus {
+        let mut budgets = self.budget_shard(key).lock().await;
+        if !budgets.contains_key(key) && budgets.len() >= MAX_TRACKED_BUDGETS / BUDGET_SHARDS {
+            evict_budgets(&mut budgets, now);
+        }
+
+        let bucket = budgets
+            .entry(key.to_string())
+            .or_insert_with(|| BudgetBucket::new(capacity, window, now));
+        bucket.resize(capacity, window);
+        bucket.try_consume(cost, now)
+    }
+
+    /// Nombre de budgets suivis, toutes partitions confondues
+    pub async fn tracked_budgets(&self) -> usize {
+        let mut total = 0;
+        for shard in self.budgets.iter() {
+            total += shard.lock().await.len();
         }
+        total
+    }
+
+    /// Purge les buckets inactifs des limiteurs par IP et par clé API
+    ///
+    /// Les tâches de nettoyage s'arrêtent au déclenchement de `shutdown`,
+    /// comme la tâche retournée.
+    pub fn start_cleanup_tasks(&self, shutdown: ShutdownToken) -> tokio::task::JoinHandle<()> {
+        let tasks = [self.ip_limiter.start_cleanup_task(), self.api_key_limiter.start_cleanup_task()];
+        tokio::spawn(async move {
+            shutdown.triggered().await;
+            for task in tasks {
+                task.abort();
+            }
+        })
+    }
+
+    fn budget_shard(&self, key: &str) -> &tokio::sync::Mutex<HashMap<String, BudgetBucket>> {
+        use std::hash::{Hash, Hasher};
+
+        let mut hasher = std::collections::hash_map::DefaultHasher::new();
+        key.hash(&mut hasher);
+        &self.budgets[hasher.finish() as usize % self.budgets.len()]
+    }
+}
+
+/// Libère une partition pleine avant d'y suivre un nouveau budget
+///
+/// Les budgets pleins, équivalents à des budgets neufs, sont oubliés en
+/// premier ; si cela ne suffit pas, les moins récemment utilisés le sont
+/// jusqu'aux trois quarts de la capacité. Le parcours n'a donc lieu qu'une
+/// fois par quart de partition rempli, pas à chaque requête.
+fn evict_budgets(budgets: &mut HashMap<String, BudgetBucket>, now: SystemTime) {
+    budgets.retain(|_, bucket| !bucket.is_full(now));
+
+    let target = MAX_TRACKED_BUDGETS / BUDGET_SHARDS * 3 / 4;
+    if budgets.len() <= target {
+        return;
+    }
+    let mut by_age: Vec<(SystemTime, String)> = budgets
+        .iter()
+        .map(|(key, bucket)| (bucket.last_refill, key.clone()))
+        .collect();
+    let excess = by_age.len() - target;
+    by_age.select_nth_unstable_by_key(excess - 1, |(last_refill, _)| *last_refill);
+    for (_, key) in &by_age[..excess] {
+        budgets.remove(key);
+    }
+}
+
+/// Budget de jetons d'un appelant, rempli en continu sur sa fenêtre
+#[derive(Debug, Clone)]
+pub struct BudgetBucket {
+    tokens: f64,
+    capacity: f64,
+    window: Duration,
+    last_refill: SystemTime,
+}
+
+impl BudgetBucket {
+    /// Crée un budget plein
+    pub fn new(capacity: u32, window: Duration, now: SystemTime) -> Self {
+        Self {
+            tokens: capacity as f64,
+            capacity: capacity as f64,
+            window,
+            last_refill: now,
+        }
+    }
+
+    /// Prélève `cost` jetons si le budget le permet
+    pub fn try_consume(&mut self, cost: u32, now: SystemTime) -> RateLimitStatus {
+        self.refill(now);
+
+        let cost = cost as f64;
+        let allowed = self.tokens >= cost;
+        if allowed {
+            self.tokens -= cost;
+        }
+
+        RateLimitStatus {
+            allowed,
+            limit: self.capacity as u32,
+            remaining: self.tokens.max(0.0).floor() as u32,
+            reset_after: self.delay_for(self.capacity - self.tokens),
+            retry_after: (!allowed).then(|| self.delay_for(cost - self.tokens)),
+        }
+    }
+
+    fn refill(&mut self, now: SystemTime) {
+        let elapsed = now.duration_since(self.last_refill).unwrap_or(Duration::ZERO);
+        self.tokens = (self.tokens + elapsed.as_secs_f64() * self.refill_rate()).min(self.capacity);
+        self.last_refill = now;
+    }
+
+    fn resize(&mut self, capacity: u32, window: Duration) {
+        self.capacity = capacity as f64;
+        self.window = window;
+        self.tokens = self.tokens.min(self.capacity);
+    }
+
+    fn is_full(&self, now: SystemTime) -> bool {
+        let elapsed = now.duration_since(self.last_refill).unwrap_or(Duration::ZERO);
+        self.tokens + elapsed.as_secs_f64() * self.refill_rate() >= self.capacity
+    }
+
+    fn refill_rate(&self) -> f64 {
+        self.capacity / self.window.as_secs_f64().max(1.0)
+    }
+
+    fn delay_for(&self, tokens: f64) -> Duration {
+        let rate = self.refill_rate();
+        if tokens <= 0.0 || rate <= 0.0 {
+            return Duration::ZERO;
+        }
+        Duration::from_secs_f64(tokens / rate)
     }
 }
 
@@ -197,12 +754,63 @@ pub struct AuthInfo {
     pub scopes: Vec<ApiScope>,
 }
 
-/// Middleware d'authentification JWT
+impl AuthInfo {
+    /// Identifiant de la clé API ayant authentifié la requête, `None` pour une session JWT
+    pub fn api_key_id(&self) -> Option<&str> {
+        self.claims.user_metadata.get("api_key_id").and_then(|id| id.as_str())
+    }
+
+    /// Exige une session JWT : une clé API ne gère pas les clés de son propriétaire
+    pub fn require_session(&self) -> ApiResult<()> {
+        match self.api_key_id() {
+            Some(_) => Err(ApiError::authorization("API keys can only be managed from a login session")),
+            None => Ok(()),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl<S> axum::extract::FromRequestParts<S> for AuthInfo
+where
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut axum::http::request::Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        parts.extensions.get::<AuthInfo>()
+            .cloned()
+            .ok_or_else(|| ApiError::authentication("Authentication required"))
+    }
+}
+
+/// Middleware d'authentification par JWT ou par clé API
+///
+/// Une requête portant `X-API-Key` est authentifiée par la clé : ses scopes
+/// remplacent ceux de l'utilisateur et sa limite propre est vérifiée auprès
+/// du rate limiter du gateway. L'état de cette limite est joint aux
+/// extensions de la réponse pour `rate_limit_middleware`.
 pub async fn auth_middleware(
     State(state): State<MiddlewareState>,
     mut req: Request,
     next: Next,
 ) -> Result<Response, ApiError> {
+    if let Some(api_key) = req.headers().get(API_KEY_HEADER) {
+        let api_key = api_key.to_str()
+            .map_err(|_| ApiError::authentication("Invalid API key"))?;
+        let (auth_info, limit_status) = authenticate_api_key(&state, api_key).await?;
+        let mut response = match limit_status {
+            Some(status) if !status.allowed => ApiError::RateLimit.into_response(),
+            _ => {
+                req.extensions_mut().insert(auth_info);
+                next.run(req).await
+            }
+        };
+        if let Some(status) = limit_status {
+            merge_rate_limit_status(&mut response, status);
+        }
+        return Ok(response);
+    }
+
     // Vérifie le header Authorization
     let auth_header = req.headers()
         .get("authorization")
@@ -235,48 +843,192 @@ pub async fn auth_middleware(
     Ok(next.run(req).await)
 }
 
+/// Authentifie une clé API et applique sa limite de débit
+async fn authenticate_api_key(state: &MiddlewareState, api_key: &str) -> ApiResult<(AuthInfo, Option<RateLimitStatus>)> {
+    let (key, role) = {
+        let users = state.user_manager.read().await;
+        let key = users.authenticate_api_key(api_key, chrono::Utc::now())?.clone();
+        let role = users.get_user(&key.user_id).and_then(|user| user.role.clone());
+        (key, role)
+    };
+
+    let mut limit_status = None;
+    if let Some(limit) = &key.rate_limit {
+        limit_status = state.rate_limiters.api_key_limiter.api_key_limit_status(&key.key_id, limit).await;
+        if limit_status.is_some_and(|status| !status.allowed) {
+            warn!("Rate limit exceeded for API key: {}", key.prefix());
+        }
+    }
+
+    let claims = state.auth_service.claims_for_api_key(&key, role.as_deref());
+    let auth_info = AuthInfo {
+        user_id: claims.sub.clone(),
+        claims,
+        scopes: key.scopes,
+    };
+    Ok((auth_info, limit_status))
+}
+
+/// Renseigne les en-têtes `X-RateLimit-*`, et `Retry-After` si la requête est refusée
+fn apply_rate_limit_headers(headers: &mut HeaderMap, status: &RateLimitStatus) {
+    let seconds = |delay: Duration| HeaderValue::from(delay.as_secs_f64().ceil() as u64);
+
+    headers.insert(RATE_LIMIT_LIMIT_HEADER, HeaderValue::from(status.limit));
+    headers.insert(RATE_LIMIT_REMAINING_HEADER, HeaderValue::from(status.remaining));
+    headers.insert(RATE_LIMIT_RESET_HEADER, seconds(status.reset_after));
+    if let Some(retry_after) = status.retry_after {
+        headers.insert(axum::http::header::RETRY_AFTER, seconds(retry_after));
+    }
+}
+
+/// Réponse 429 portant l'état de la limite dépassée
+fn rate_limited_response(status: &RateLimitStatus) -> Response {
+    let mut response = ApiError::RateLimit.into_response();
+    apply_rate_limit_headers(response.headers_mut(), status);
+    response
+}
+
+/// Joint l'état d'une limite aux extensions de la réponse
+///
+/// Combiné à l'état déjà présent, posé par un middleware intérieur ; retourne
+/// l'état résultant.
+fn merge_rate_limit_status(response: &mut Response, status: RateLimitStatus) -> RateLimitStatus {
+    let merged = match response.extensions().get::<RateLimitStatus>() {
+        Some(inner) => inner.most_restrictive(status),
+        None => status,
+    };
+    response.extensions_mut().insert(merged);
+    merged
+}
+
+/// IP du client
+///
+/// Adresse de la connexion, sauf si elle provient de `trusted_proxies` : la
+/// dernière valeur de `X-Forwarded-For`, celle que le proxy a ajoutée, est
+/// alors retenue. Les valeurs précédentes viennent du client et ne sont pas
+/// vérifiées, comme dans `received_over_https`.
+fn client_ip(req: &Request, config: &SecurityHeadersConfig) -> IpAddr {
+    let Some(ConnectInfo(peer)) = req.extensions().get::<ConnectInfo<SocketAddr>>() else {
+        return IpAddr::V4(std::net::Ipv4Addr::UNSPECIFIED);
+    };
+    if !config.trusted_proxies.contains(&peer.ip()) {
+        return peer.ip();
+    }
+
+    req.headers()
+        .get_all("x-forwarded-for")
+        .iter()
+        .last()
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.rsplit(',').next())
+        .and_then(|value| value.trim().parse::<IpAddr>().ok())
+        .unwrap_or_else(|| peer.ip())
+}
+
+/// Middleware de budget par appelant
+///
+/// Chaque requête prélève le coût de son groupe de routes
+/// (`RateLimitConfig::route_costs`) dans le budget de l'utilisateur
+/// authentifié, fixé par `JwtClaims::rate_limit`, ou à défaut dans celui de
+/// son IP (`global_per_ip` jetons par `window_seconds`). Lorsque le scope de
+/// la route (`RateLimitConfig::route_scopes`) a sa propre limite dans les
+/// claims, la requête prélève aussi le budget de ce scope : les créations
+/// d'archives s'épuisent sans entamer les lectures, et aucun scope ne
+/// dépasse la limite générale du token. Doit être placé sous
+/// `auth_middleware` pour voir les claims.
+pub async fn budget_middleware(
+    State(state): State<MiddlewareState>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let config = &state.config.rate_limit;
+    let path = req.extensions()
+        .get::<OriginalUri>()
+        .map_or_else(|| req.uri().path().to_string(), |uri| uri.path().to_string());
+    let cost = config.route_cost(req.method(), &path);
+
+    // Budget du scope d'abord, puis budget général : un refus du premier
+    // n'entame pas le second
+    let mut budgets = Vec::with_capacity(2);
+    match req.extensions().get::<AuthInfo>() {
+        Some(auth_info) => {
+            let rate_limit = &auth_info.claims.rate_limit;
+            let scoped = config.route_scope(req.method(), &path)
+                .and_then(|scope| rate_limit.scope_limit(scope).map(|limit| (scope, limit)));
+            if let Some((scope, limit)) = scoped {
+                budgets.push((
+                    format!("user:{}:{}", auth_info.user_id, scope.as_str()),
+                    limit,
+                    USER_BUDGET_WINDOW,
+                ));
+            }
+            budgets.push((
+                format!("user:{}", auth_info.user_id),
+                rate_limit.requests_per_hour,
+                USER_BUDGET_WINDOW,
+            ));
+        }
+        None => budgets.push((
+            format!("ip:{}", client_ip(&req, &state.config.security_headers)),
+            config.global_per_ip,
+            Duration::from_secs(config.window_seconds),
+        )),
+    }
+
+    let now = SystemTime::now();
+    let mut status: Option<RateLimitStatus> = None;
+    for (key, capacity, window) in &budgets {
+        let acquired = state.rate_limiters.acquire_budget(key, *capacity, *window, cost, now).await;
+        if !acquired.allowed {
+            warn!("Token budget exhausted for {} ({} tokens requested)", key, cost);
+        }
+        let merged = status.map_or(acquired, |status| status.most_restrictive(acquired));
+        status = Some(merged);
+        if !merged.allowed {
+            break;
+        }
+    }
+    let status = status.expect("au moins un budget par requête");
+    let mut response = if status.allowed {
+        next.run(req).await
+    } else {
+        ApiError::RateLimit.into_response()
+    };
+
+    let status = merge_rate_limit_status(&mut response, status);
+    apply_rate_limit_headers(response.headers_mut(), &status);
+    response
+}
+
 /// Middleware de rate limiting
+///
+/// Chaque réponse porte l'état de la limite la plus contraignante entre celle
+/// de l'IP, celle de la clé API éventuelle et le budget de l'appelant.
 pub async fn rate_limit_middleware(
     State(state): State<MiddlewareState>,
     req: Request,
     next: Next,
 ) -> Result<Response, ApiError> {
-    // Obtient l'IP du client
-    let client_ip = req.headers()
-        .get("x-forwarded-for")
-        .and_then(|h| h.to_str().ok())
-        .and_then(|s| s.split(',').next())
-        .and_then(|s| s.trim().parse::<IpAddr>().ok())
-        .unwrap_or_else(|| "127.0.0.1".parse().unwrap());
+    let client_ip = client_ip(&req, &state.config.security_headers);
 
     // Vérifie la limite globale par IP
-    if let Err(_) = state.rate_limiters.ip_limiter.check_key(&client_ip) {
+    let ip_status = state.rate_limiters.ip_limiter.rate_limit_status(&client_ip.to_string(), None).await;
+    if let Some(status) = ip_status.filter(|status| !status.allowed) {
         warn!("Rate limit exceeded for IP: {}", client_ip);
-        return Err(ApiError::RateLimit);
+        return Ok(rate_limited_response(&status));
     }
 
-    // Si authentifié, vérifie la limite par utilisateur
-    if let Some(auth_info) = req.extensions().get::<AuthInfo>() {
-        let user_id = &auth_info.user_id;
-        let user_limit = if auth_info.claims.rate_limit.requests_per_hour > 10000 {
-            state.config.rate_limit.premium_per_user
-        } else {
-            state.config.rate_limit.authenticated_per_user
-        };
-
-        let mut user_limiters = state.rate_limiters.user_limiters.write().await;
-        let user_limiter = user_limiters.entry(user_id.clone()).or_insert_with(|| {
-            let quota = Quota::per_minute(user_limit);
-            RateLimiter::direct(quota)
-        });
-
-        if let Err(_) = user_limiter.check_key(user_id) {
-            warn!("Rate limit exceeded for user: {}", user_id);
-            return Err(ApiError::RateLimit);
-        }
+    let mut response = next.run(req).await;
+    let key_status = response.extensions().get::<RateLimitStatus>().copied();
+    let status = match (ip_status, key_status) {
+        (Some(ip), Some(key)) => Some(ip.most_restrictive(key)),
+        (ip, key) => ip.or(key),
+    };
+    if let Some(status) = status {
+        apply_rate_limit_headers(response.headers_mut(), &status);
     }
 
-    Ok(next.run(req).await)
+    Ok(response)
 }
 
 /// Middleware de validation des permissions
@@ -298,30 +1050,57 @@ pub fn require_scope(required_scope: ApiScope) -> impl Fn(Request, Next) -> std:
     }
 }
 
+/// Initialise l'abonné `tracing` global selon la configuration de logging
+///
+/// En JSON, chaque ligne embarque les champs des spans actifs, dont le
+/// `request_id` posé par `request_id_middleware`. Sans effet si un abonné
+/// global est déjà installé.
+pub fn init_logging(config: &LoggingConfig) {
+    use tracing_subscriber::{fmt, EnvFilter};
+
+    let filter = EnvFilter::try_new(&config.filter).unwrap_or_else(|_| EnvFilter::new("info"));
+    let result = if config.json_format {
+        fmt()
+            .json()
+            .with_env_filter(filter)
+            .with_current_span(true)
+            .with_span_list(true)
+            .try_init()
+    } else {
+        fmt().with_env_filter(filter).try_init()
+    };
+
+    if let Err(e) = result {
+        warn!("Logging already initialized: {}", e);
+    }
+}
+
 /// Middleware pour ajouter un Request ID
+///
+/// Reprend l'identifiant `X-Request-Id` du client s'il est valide, en génère
+/// un sinon, et traite la requête dans un span qui le porte : toutes les
+/// lignes de log émises pendant la requête contiennent ainsi `request_id`.
 pub async fn request_id_middleware(
     mut req: Request,
     next: Next,
 ) -> Response {
-    let request_id = req.headers()
-        .get("x-request-id")
-        .and_then(|h| h.to_str().ok())
-        .unwrap_or_else(|| {
-            let id = uuid::Uuid::new_v4().to_string();
-            req.headers_mut().insert(
-                "x-request-id",
-                HeaderValue::from_str(&id).unwrap(),
-            );
-            req.headers().get("x-request-id").unwrap().to_str().unwrap()
-        });
+    let request_id = resolve_request_id(req.headers().get(REQUEST_ID_HEADER).and_then(|h| h.to_str().ok()));
+    let header_value = HeaderValue::from_str(&request_id)
+        .expect("un identifiant validé ou un UUID est un en-tête valide");
+
+    req.headers_mut().insert(REQUEST_ID_HEADER, header_value.clone());
+    req.extensions_mut().insert(RequestId(request_id.clone()));
+
+    let span = tracing::info_span!(
+        "request",
+        request_id = %request_id,
+        method = %req.method(),
+        path = %req.uri().path(),
+    );
+    let mut response = with_request_id(request_id, next.run(req)).instrument(span).await;
 
-    let mut response = next.run(req).await;
-    
     // Ajoute le Request ID à la réponse
-    response.headers_mut().insert(
-        "x-request-id",
-        HeaderValue::from_str(request_id).unwrap(),
-    );
+    response.headers_mut().insert(REQUEST_ID_HEADER, header_value);
 
     response
 }
@@ -338,15 +1117,11 @@ pub async fn logging_middleware(
         .get("user-agent")
         .and_then(|h| h.to_str().ok())
         .unwrap_or("unknown");
-    let request_id = req.headers()
-        .get("x-request-id")
-        .and_then(|h| h.to_str().ok())
-        .unwrap_or("unknown");
 
+    // `request_id` provient du span ouvert par `request_id_middleware`
     let start_time = std::time::Instant::now();
 
     info!(
-        request_id = request_id,
         method = %method,
         path = path,
         query = query,
@@ -367,7 +1142,6 @@ pub async fn logging_middleware(
     };
 
     tracing::info!(
-        request_id = request_id,
         method = %method,
         path = path,
         status = %status,
@@ -383,18 +1157,12 @@ pub async fn error_handler_middleware(
     req: Request,
     next: Next,
 ) -> Response {
-    let request_id = req.headers()
-        .get("x-request-id")
-        .and_then(|h| h.to_str().ok())
-        .unwrap_or("unknown");
-
     match next.run(req).await.into_response() {
         response if response.status().is_success() => response,
         response => {
             let status = response.status();
             if status.is_server_error() {
                 error!(
-                    request_id = request_id,
                     status = %status,
                     "Internal server error occurred"
                 );
@@ -404,41 +1172,159 @@ pub async fn error_handler_middleware(
     }
 }
 
+/// État du middleware de drainage à l'arrêt
+#[derive(Clone)]
+pub struct ShutdownDrain {
+    /// Jeton d'arrêt partagé par les serveurs
+    pub token: ShutdownToken,
+    /// Délai accordé aux réponses en cours une fois l'arrêt déclenché
+    pub timeout: Duration,
+}
+
+/// Middleware bornant les réponses en streaming pendant l'arrêt
+///
+/// Un corps de taille inconnue (flux, SSE) continue d'être envoyé pendant le
+/// délai de drainage puis se termine, ce qui laisse le serveur fermer la
+/// connexion au lieu d'attendre indéfiniment la fin du flux.
+pub async fn shutdown_drain_middleware(
+    State(drain): State<ShutdownDrain>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let response = next.run(req).await;
+    if response.body().size_hint().exact().is_some() {
+        return response;
+    }
+
+    let (parts, body) = response.into_parts();
+    let deadline = async move { drain.token.drain_deadline(drain.timeout).await };
+    let body = Body::from_stream(body.into_data_stream().take_until(deadline));
+    Response::from_parts(parts, body)
+}
+
+/// La requête est-elle arrivée en HTTPS
+///
+/// Seul le proxy de confiance directement connecté peut l'affirmer : la
+/// valeur retenue est la dernière de l'en-tête, celle qu'il a ajoutée. Les
+/// valeurs précédentes viennent du client ou de relais non vérifiés.
+fn received_over_https(req: &Request, config: &SecurityHeadersConfig) -> bool {
+    let from_trusted_proxy = req.extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map_or(false, |ConnectInfo(peer)| config.trusted_proxies.contains(&peer.ip()));
+    if !from_trusted_proxy {
+        return false;
+    }
+
+    req.headers()
+        .get_all(config.forwarded_proto_header.as_str())
+        .iter()
+        .last()
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.rsplit(',').next())
+        .map_or(false, |proto| proto.trim().eq_ignore_ascii_case("https"))
+}
+
+/// Middleware des en-têtes de sécurité
+///
+/// Redirige vers `canonical_host` ou refuse, selon `https_enforcement`, une
+/// requête qui n'est pas arrivée en HTTPS quand `force_https` est actif. Ajoute ensuite
+/// `Strict-Transport-Security` (sauf aux réponses en HTTP clair, où le
+/// navigateur l'ignore) et `X-Content-Type-Options`.
+pub async fn security_headers_middleware(
+    State(config): State<SecurityHeadersConfig>,
+    req: Request,
+    next: Next,
+) -> Result<Response, ApiError> {
+    let plain_http = !received_over_https(&req, &config);
+
+    if config.force_https && plain_http {
+        return match config.https_enforcement {
+            HttpsEnforcement::Reject => Err(ApiError::authorization("HTTPS is required")),
+            HttpsEnforcement::Redirect => {
+                let host = config.canonical_host.as_deref()
+                    .ok_or_else(|| ApiError::internal("HTTPS redirection requires a canonical host"))?;
+                let path = req.uri().path_and_query().map_or("/", |path| path.as_str());
+                let location = HeaderValue::from_str(&format!("https://{}{}", host, path))
+                    .map_err(|_| ApiError::internal("Invalid HTTPS redirection target"))?;
+                Ok((StatusCode::PERMANENT_REDIRECT, [(header::LOCATION, location)]).into_response())
+            }
+        };
+    }
+
+    let mut response = next.run(req).await;
+    let headers = response.headers_mut();
+    if let Some(max_age) = config.hsts_max_age.filter(|_| !plain_http) {
+        let value = if config.hsts_include_subdomains {
+            format!("max-age={}; includeSubDomains", max_age)
+        } else {
+            format!("max-age={}", max_age)
+        };
+        headers.insert(
+            header::STRICT_TRANSPORT_SECURITY,
+            HeaderValue::from_str(&value).expect("une durée est un en-tête valide"),
+        );
+    }
+    if config.content_type_options {
+        headers.insert(header::X_CONTENT_TYPE_OPTIONS, HeaderValue::from_static("nosniff"));
+    }
+    Ok(response)
+}
+
 /// Builder pour les middlewares CORS
-pub fn cors_middleware(config: &CorsConfig) -> CorsLayer {
+///
+/// Retourne `None` si CORS est désactivé et une erreur si la configuration
+/// est invalide (voir [`CorsConfig::validate`]). Les origines hors liste ne
+/// reçoivent pas `Access-Control-Allow-Origin`, y compris en preflight.
+pub fn cors_middleware(config: &CorsConfig) -> ApiResult<Option<CorsLayer>> {
+    if !config.enabled {
+        return Ok(None);
+    }
+    config.validate()?;
+
     let mut cors = CorsLayer::new();
 
     // Origins
-    if config.allowed_origins.contains(&"*".to_string()) {
+    let patterns = config.origin_patterns()?;
+    if patterns.contains(&OriginPattern::Any) {
         cors = cors.allow_origin(Any);
     } else {
-        for origin in &config.allowed_origins {
-            if let Ok(origin) = origin.parse::<HeaderValue>() {
-                cors = cors.allow_origin(origin);
-            }
-        }
+        cors = cors.allow_origin(AllowOrigin::predicate(move |origin: &HeaderValue, _| {
+            origin.to_str().is_ok_and(|origin| patterns.iter().any(|pattern| pattern.matches(origin)))
+        }));
     }
 
     // Methods
-    let methods: Vec<Method> = config.allowed_methods
-        .iter()
-        .filter_map(|m| m.parse().ok())
-        .collect();
-    cors = cors.allow_methods(methods);
+    if config.allowed_methods.iter().any(|m| m == "*") {
+        cors = cors.allow_methods(Any);
+    } else {
+        let methods: Vec<Method> = config.allowed_methods
+            .iter()
+            .filter_map(|m| m.parse().ok())
+            .collect();
+        cors = cors.allow_methods(methods);
+    }
 
     // Headers
-    let headers: Vec<HeaderValue> = config.allowed_headers
-        .iter()
-        .filter_map(|h| h.parse().ok())
-        .collect();
-    cors = cors.allow_headers(headers);
+    if config.allowed_headers.iter().any(|h| h == "*") {
+        cors = cors.allow_headers(Any);
+    } else {
+        let headers: Vec<axum::http::HeaderName> = config.allowed_headers
+            .iter()
+            .filter_map(|h| h.parse().ok())
+            .collect();
+        cors = cors.allow_headers(headers);
+    }
 
     // Expose headers
-    let expose_headers: Vec<HeaderValue> = config.expose_headers
-        .iter()
-        .filter_map(|h| h.parse().ok())
-        .collect();
-    cors = cors.expose_headers(expose_headers);
+    if config.expose_headers.iter().any(|h| h == "*") {
+        cors = cors.expose_headers(Any);
+    } else {
+        let expose_headers: Vec<axum::http::HeaderName> = config.expose_headers
+            .iter()
+            .filter_map(|h| h.parse().ok())
+            .collect();
+        cors = cors.expose_headers(expose_headers);
+    }
 
     // Max age
     if let Some(max_age) = config.max_age {
@@ -450,7 +1336,7 @@ pub fn cors_middleware(config: &CorsConfig) -> CorsLayer {
         cors = cors.allow_credentials(true);
     }
 
-    cors
+    Ok(Some(cors))
 }
 
 /// Builder pour le middleware de compression
@@ -462,10 +1348,24 @@ pub fn compression_middleware(config: &CompressionConfig) -> Option<CompressionL
     }
 }
 
+/// Span créé par `tracing_middleware` pour chaque requête
+fn http_span(req: &Request) -> Span {
+    let request_id = req.headers()
+        .get(REQUEST_ID_HEADER)
+        .and_then(|h| h.to_str().ok())
+        .unwrap_or("unknown");
+    tracing::info_span!("http", request_id = %request_id, method = %req.method(), uri = %req.uri())
+}
+
 /// Builder pour le middleware de tracing
-pub fn tracing_middleware(config: &LoggingConfig) -> Option<TraceLayer> {
+///
+/// Doit être placé sous `request_id_middleware` pour que l'identifiant soit
+/// déjà présent dans les en-têtes.
+pub fn tracing_middleware(
+    config: &LoggingConfig,
+) -> Option<TraceLayer<SharedClassifier<ServerErrorsAsFailures>, fn(&Request) -> Span>> {
     if config.enabled {
-        Some(TraceLayer::new_for_http())
+        Some(TraceLayer::new_for_http().make_span_with(http_span as fn(&Request) -> Span))
     } else {
         None
     }
@@ -489,7 +1389,8 @@ mod tests {
     #[test]
     fn test_middleware_config_default() {
         let config = MiddlewareConfig::default();
-        assert!(config.cors.allow_credentials);
+        assert!(!config.cors.allow_credentials);
+        assert!(config.validate().is_ok());
         assert_eq!(config.rate_limit.global_per_ip, 60);
         assert!(config.compression.enabled);
         assert!(config.logging.enabled);
@@ -503,6 +1404,189 @@ mod tests {
         assert!(config.allowed_headers.contains(&"authorization".to_string()));
     }
 
+    #[test]
+    fn test_origin_pattern_matching() {
+        let exact = OriginPattern::parse("https://App.Example.org/").unwrap();
+        assert!(exact.matches("https://app.example.org"));
+        assert!(!exact.matches("http://app.example.org"));
+        assert!(!exact.matches("https://app.example.org:8443"));
+
+        let subdomains = OriginPattern::parse("*.example.org").unwrap();
+        assert!(subdomains.matches("https://cdn.example.org"));
+        assert!(subdomains.matches("http://a.b.example.org"));
+        assert!(!subdomains.matches("https://example.org"));
+        assert!(!subdomains.matches("https://evilexample.org"));
+        assert!(!subdomains.matches("https://example.org.evil.com"));
+        assert!(!subdomains.matches("ftp://cdn.example.org"));
+
+        let https_only = OriginPattern::parse("https://*.example.org").unwrap();
+        assert!(https_only.matches("https://cdn.example.org"));
+        assert!(!https_only.matches("http://cdn.example.org"));
+
+        for invalid in ["example.org", "https://*", "*.", "https://app.*.org", "https://a.example.org/path"] {
+            assert!(OriginPattern::parse(invalid).is_err(), "{} devrait être refusé", invalid);
+        }
+    }
+
+    #[test]
+    fn test_cors_rejects_wildcard_with_credentials() {
+        let config = CorsConfig {
+            allow_credentials: true,
+            ..CorsConfig::default()
+        };
+        let error = config.validate().unwrap_err();
+        assert!(error.to_string().contains("allow_credentials"));
+        assert!(cors_middleware(&config).is_err());
+
+        let mut api_config = crate::api::ApiConfig::default();
+        api_config.middleware.cors = config.clone();
+        assert!(api_config.validate().is_err());
+
+        // Un joker de sous-domaine désigne des origines précises : accepté
+        let config = CorsConfig {
+            allowed_origins: vec!["https://*.example.org".to_string()],
+            ..config
+        };
+        assert!(config.validate().is_ok());
+
+        let config = CorsConfig {
+            allowed_headers: vec!["*".to_string()],
+            ..config
+        };
+        assert!(config.validate().unwrap_err().to_string().contains("allowed_headers"));
+
+        let disabled = CorsConfig { enabled: false, ..CorsConfig::default() };
+        assert!(cors_middleware(&disabled).unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_cors_preflight_honors_allowed_origins() {
+        use axum::{body::Body, routing::post, Router};
+        use tower::ServiceExt;
+
+        let config = CorsConfig {
+            allowed_origins: vec!["https://app.example.org".to_string(), "*.archive.example.org".to_string()],
+            max_age: Some(600),
+            allow_credentials: true,
+            ..CorsConfig::default()
+        };
+        let app = Router::new()
+            .route("/archives", post(|| async { "ok" }))
+            .layer(cors_middleware(&config).unwrap().unwrap());
+        let preflight = |origin: &str| {
+            let request = axum::http::Request::builder()
+                .method(Method::OPTIONS)
+                .uri("/archives")
+                .header(header::ORIGIN, origin)
+                .header(header::ACCESS_CONTROL_REQUEST_METHOD, "POST")
+                .header(header::ACCESS_CONTROL_REQUEST_HEADERS, "authorization")
+                .body(Body::empty())
+                .unwrap();
+            app.clone().oneshot(request)
+        };
+
+        for origin in ["https://app.example.org", "https://cdn.archive.example.org"] {
+            let response = preflight(origin).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            let headers = response.headers();
+            assert_eq!(headers[header::ACCESS_CONTROL_ALLOW_ORIGIN], origin);
+            assert_eq!(headers[header::ACCESS_CONTROL_ALLOW_CREDENTIALS], "true");
+            assert_eq!(headers[header::ACCESS_CONTROL_MAX_AGE], "600");
+            assert!(headers[header::ACCESS_CONTROL_ALLOW_METHODS].to_str().unwrap().contains("POST"));
+            assert!(headers[header::ACCESS_CONTROL_ALLOW_HEADERS].to_str().unwrap().contains("authorization"));
+        }
+
+        for origin in ["https://evil.example.com", "https://archive.example.org"] {
+            let response = preflight(origin).await.unwrap();
+            assert!(response.headers().get(header::ACCESS_CONTROL_ALLOW_ORIGIN).is_none(), "{}", origin);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_force_https_behind_trusted_proxy() {
+        use axum::{body::Body, routing::get, Router};
+        use tower::ServiceExt;
+
+        let proxy: SocketAddr = "10.0.0.1:41000".parse().unwrap();
+        let client: SocketAddr = "203.0.113.9:52000".parse().unwrap();
+        let call = |config: SecurityHeadersConfig, peer: Option<SocketAddr>, proto: Option<&str>| {
+            let app = Router::new()
+                .route("/api/v1/archives", get(|| async { "ok" }))
+                .layer(axum::middleware::from_fn_with_state(config, security_headers_middleware));
+            let mut request = axum::http::Request::builder()
+                .uri("/api/v1/archives?limit=5")
+                .header(header::HOST, "evil.example");
+            if let Some(proto) = proto {
+                request = request.header("x-forwarded-proto", proto);
+            }
+            let mut request = request.body(Body::empty()).unwrap();
+            if let Some(peer) = peer {
+                request.extensions_mut().insert(ConnectInfo(peer));
+            }
+            app.oneshot(request)
+        };
+        let config = SecurityHeadersConfig {
+            force_https: true,
+            trusted_proxies: vec![proxy.ip()],
+            canonical_host: Some("api.example.org".to_string()),
+            ..SecurityHeadersConfig::default()
+        };
+
+        // La redirection vise l'hôte configuré, jamais l'en-tête `Host` du client
+        let response = call(config.clone(), Some(proxy), Some("http")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::PERMANENT_REDIRECT);
+        assert_eq!(response.headers()[header::LOCATION], "https://api.example.org/api/v1/archives?limit=5");
+
+        let reject = SecurityHeadersConfig {
+            https_enforcement: HttpsEnforcement::Reject,
+            ..config.clone()
+        };
+        let response = call(reject, Some(proxy), Some("http")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        // Seule la valeur ajoutée par le proxy de confiance compte
+        let response = call(config.clone(), Some(proxy), Some("http, https")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::STRICT_TRANSPORT_SECURITY], "max-age=31536000");
+        assert_eq!(response.headers()[header::X_CONTENT_TYPE_OPTIONS], "nosniff");
+
+        // Un client qui se dit en HTTPS, directement ou via un proxy qui l'a relayé en HTTP
+        for (peer, proto) in [(Some(client), Some("https")), (None, None), (Some(proxy), Some("https, http")), (Some(proxy), None)] {
+            let response = call(config.clone(), peer, proto).await.unwrap();
+            assert_eq!(response.status(), StatusCode::PERMANENT_REDIRECT, "{:?} {:?}", peer, proto);
+        }
+
+        // Sans `force_https`, une requête en HTTP clair passe, sans HSTS
+        let response = call(SecurityHeadersConfig::default(), Some(client), None).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response.headers().get(header::STRICT_TRANSPORT_SECURITY).is_none());
+        assert_eq!(response.headers()[header::X_CONTENT_TYPE_OPTIONS], "nosniff");
+    }
+
+    #[test]
+    fn test_gateway_security_config_is_applied() {
+        let security = GatewaySecurityConfig {
+            cors_enabled: true,
+            cors_allowed_origins: vec!["https://*.example.org".to_string()],
+            force_https: true,
+            ..GatewaySecurityConfig::default()
+        };
+        let mut config = MiddlewareConfig::default().with_gateway_security(&security);
+        assert!(config.cors.is_origin_allowed("https://app.example.org"));
+        assert!(!config.cors.is_origin_allowed("https://other.org"));
+        assert!(config.security_headers.force_https);
+
+        // Une redirection vers HTTPS exige un hôte canonique valide
+        assert!(config.validate().is_err());
+        config.security_headers.canonical_host = Some("bad host".to_string());
+        assert!(config.validate().is_err());
+        config.security_headers.canonical_host = Some("api.example.org".to_string());
+        assert!(config.validate().is_ok());
+        config.security_headers.canonical_host = None;
+        config.security_headers.https_enforcement = HttpsEnforcement::Reject;
+        assert!(config.validate().is_ok());
+    }
+
     #[test]
     fn test_rate_limit_config() {
         let config = RateLimitConfig::default();
@@ -520,7 +1604,317 @@ mod tests {
         let test_ip: IpAddr = "127.0.0.1".parse().unwrap();
         
         // Premier appel devrait passer
-        assert!(rate_limiters.ip_limiter.check_key(&test_ip).is_ok());
+        assert!(rate_limiters.ip_limiter.check_rate_limit(&test_ip.to_string(), None).await);
+
+        // Le nettoyage périodique s'arrête avec le serveur
+        let shutdown = ShutdownToken::new();
+        let cleanup = rate_limiters.start_cleanup_tasks(shutdown.clone());
+        shutdown.trigger();
+        tokio::time::timeout(Duration::from_secs(1), cleanup).await.unwrap().unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_headers() {
+        use axum::{body::Body, routing::get, Router};
+        use tower::ServiceExt;
+
+        let config = MiddlewareConfig {
+            rate_limit: RateLimitConfig {
+                global_per_ip: 2,
+                ..RateLimitConfig::default()
+            },
+            ..MiddlewareConfig::default()
+        };
+        let state = MiddlewareState {
+            auth_service: Arc::new(AuthService::new(AuthConfig::default()).unwrap()),
+            user_manager: Arc::new(tokio::sync::RwLock::new(UserManager::new())),
+            rate_limiters: Arc::new(RateLimiters::new(&config.rate_limit)),
+            config,
+        };
+        let app = Router::new()
+            .route("/", get(|| async { "ok" }))
+            .layer(axum::middleware::from_fn_with_state(state, rate_limit_middleware));
+        let call = || {
+            let request = axum::http::Request::builder()
+                .uri("/")
+                .header("x-forwarded-for", "203.0.113.7")
+                .body(Body::empty())
+                .unwrap();
+            app.clone().oneshot(request)
+        };
+
+        // La limite par minute (2) est plus contraignante que le burst (10)
+        for remaining in ["1", "0"] {
+            let response = call().await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(response.headers()[RATE_LIMIT_LIMIT_HEADER], "2");
+            assert_eq!(response.headers()[RATE_LIMIT_REMAINING_HEADER], remaining);
+            assert!(response.headers().get(axum::http::header::RETRY_AFTER).is_none());
+        }
+
+        let response = call().await.unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers()[RATE_LIMIT_REMAINING_HEADER], "0");
+        let reset: u64 = response.headers()[RATE_LIMIT_RESET_HEADER].to_str().unwrap().parse().unwrap();
+        let retry_after: u64 = response.headers()[axum::http::header::RETRY_AFTER].to_str().unwrap().parse().unwrap();
+        assert!(reset <= 60);
+        assert!(retry_after > 0 && retry_after <= 60);
+    }
+
+    #[tokio::test]
+    async fn test_budget_charges_route_costs_per_user() {
+        use crate::api::auth::RateLimit;
+        use axum::{body::Body, routing::{get, post}, Router};
+        use tower::ServiceExt;
+
+        let config = MiddlewareConfig {
+            rate_limit: RateLimitConfig {
+                global_per_ip: 3,
+                ..RateLimitConfig::default()
+            },
+            ..MiddlewareConfig::default()
+        };
+        let state = MiddlewareState {
+            auth_service: Arc::new(AuthService::new(AuthConfig::default()).unwrap()),
+            user_manager: Arc::new(tokio::sync::RwLock::new(UserManager::new())),
+            rate_limiters: Arc::new(RateLimiters::new(&config.rate_limit)),
+            config,
+        };
+
+        // Authentification simulée : `x-test-user` devient le `sub` des claims
+        async fn fake_auth(mut req: Request, next: Next) -> Response {
+            if let Some(user) = req.headers().get("x-test-user").and_then(|h| h.to_str().ok()) {
+                let claims = JwtClaims {
+                    sub: user.to_string(),
+                    iss: "test".to_string(),
+                    aud: "test".to_string(),
+                    exp: 0,
+                    iat: 0,
+                    nbf: 0,
+                    jti: "test".to_string(),
+                    scope: Vec::new(),
+                    node_id: None,
+                    rate_limit: RateLimit { requests_per_hour: 20, ..RateLimit::default() },
+                    user_metadata: HashMap::new(),
+                };
+                req.extensions_mut().insert(AuthInfo { user_id: claims.sub.clone(), claims, scopes: Vec::new() });
+            }
+            next.run(req).await
+        }
+
+        let app = Router::new()
+            .route("/api/v1/rest/archives", post(|| async { "created" }))
+            .route("/api/v1/rest/archives/{id}", get(|| async { "archive" }))
+            .layer(axum::middleware::from_fn_with_state(state.clone(), budget_middleware))
+            .layer(axum::middleware::from_fn(fake_auth));
+        let call = |method: &str, uri: &str, user: Option<&str>| {
+            let mut request = axum::http::Request::builder()
+                .method(method)
+                .uri(uri)
+                .header("x-forwarded-for", "198.51.100.4");
+            if let Some(user) = user {
+                request = request.header("x-test-user", user);
+            }
+            app.clone().oneshot(request.body(Body::empty()).unwrap())
+        };
+
+        // Lecture à 1 jeton : 20 requêtes sur le budget horaire de 20
+        for expected in (0..20).rev() {
+            let response = call("GET", "/api/v1/rest/archives/a1", Some("alice")).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(response.headers()[RATE_LIMIT_REMAINING_HEADER], expected.to_string().as_str());
+        }
+        let response = call("GET", "/api/v1/rest/archives/a1", Some("alice")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(response.headers()[RATE_LIMIT_REMAINING_HEADER], "0");
+        let retry_after: u64 = response.headers()[axum::http::header::RETRY_AFTER].to_str().unwrap().parse().unwrap();
+        assert!(retry_after > 0 && retry_after <= 180);
+
+        // Création à 10 jetons : le même budget s'épuise en deux requêtes
+        for expected in ["10", "0"] {
+            let response = call("POST", "/api/v1/rest/archives", Some("bob")).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(response.headers()[RATE_LIMIT_REMAINING_HEADER], expected);
+        }
+        let response = call("POST", "/api/v1/rest/archives", Some("bob")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert!(response.headers().contains_key(axum::http::header::RETRY_AFTER));
+
+        // Les budgets sont propres à chaque utilisateur ; l'anonyme dépend de son IP
+        assert_eq!(call("GET", "/api/v1/rest/archives/a1", Some("carol")).await.unwrap().status(), StatusCode::OK);
+        for _ in 0..3 {
+            assert_eq!(call("GET", "/api/v1/rest/archives/a1", None).await.unwrap().status(), StatusCode::OK);
+        }
+        assert_eq!(call("GET", "/api/v1/rest/archives/a1", None).await.unwrap().status(), StatusCode::TOO_MANY_REQUESTS);
+
+        // Le budget est de nouveau plein une fois la fenêtre écoulée
+        let later = SystemTime::now() + USER_BUDGET_WINDOW;
+        let status = state.rate_limiters.acquire_budget("user:alice", 20, USER_BUDGET_WINDOW, 10, later).await;
+        assert!(status.allowed);
+        assert_eq!(status.remaining, 10);
+    }
+
+    #[tokio::test]
+    async fn test_budget_separates_scoped_limits() {
+        use crate::api::auth::RateLimit;
+        use axum::{body::Body, routing::{get, post}, Router};
+        use tower::ServiceExt;
+
+        let config = MiddlewareConfig::default();
+        let auth_service = Arc::new(AuthService::new(AuthConfig::default()).unwrap());
+        let state = MiddlewareState {
+            auth_service: auth_service.clone(),
+            user_manager: Arc::new(tokio::sync::RwLock::new(UserManager::new())),
+            rate_limiters: Arc::new(RateLimiters::new(&config.rate_limit)),
+            config,
+        };
+
+        // Token à 50 requêtes/heure dont 20 jetons réservés aux écritures ;
+        // la limite de lecture provient de la configuration, bornée par
+        // celle du token
+        let token_limit = RateLimit {
+            requests_per_hour: 50,
+            scope_limits: HashMap::from([(ApiScope::ArchivesWrite, 20)]),
+            ..RateLimit::default()
+        };
+        let token = {
+            let mut users = state.user_manager.write().await;
+            auth_service.generate_token(
+                &mut users,
+                "alice",
+                vec![ApiScope::ArchivesRead, ApiScope::ArchivesWrite, ApiScope::NetworkRead],
+                None,
+                Some(token_limit),
+            ).unwrap().token
+        };
+        let claims = auth_service.validate_token(&token).unwrap();
+        assert_eq!(claims.rate_limit.scope_limit(&ApiScope::ArchivesWrite), Some(20));
+        assert_eq!(claims.rate_limit.scope_limit(&ApiScope::ArchivesRead), Some(50));
+
+        let auth_info = AuthInfo { user_id: claims.sub.clone(), claims, scopes: Vec::new() };
+        let app = Router::new()
+            .route("/api/v1/rest/archives", post(|| async { "created" }))
+            .route("/api/v1/rest/archives/{id}", get(|| async { "archive" }))
+            .route("/api/v1/rest/network/stats", get(|| async { "stats" }))
+            .layer(axum::middleware::from_fn_with_state(state.clone(), budget_middleware))
+            .layer(axum::middleware::from_fn(move |mut req: Request, next: Next| {
+                req.extensions_mut().insert(auth_info.clone());
+                next.run(req)
+            }));
+        let call = |method: &str, uri: &str| {
+            let request = axum::http::Request::builder().method(method).uri(uri).body(Body::empty()).unwrap();
+            app.clone().oneshot(request)
+        };
+
+        // Deux créations à 10 jetons épuisent le budget des écritures
+        for expected in ["10", "0"] {
+            let response = call("POST", "/api/v1/rest/archives").await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+            assert_eq!(response.headers()[RATE_LIMIT_REMAINING_HEADER], expected);
+        }
+        assert_eq!(call("POST", "/api/v1/rest/archives").await.unwrap().status(), StatusCode::TOO_MANY_REQUESTS);
+
+        // Les lectures ont leur propre budget mais entament aussi le budget
+        // général, déjà diminué des 20 jetons d'écriture
+        let response = call("GET", "/api/v1/rest/archives/a1").await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[RATE_LIMIT_LIMIT_HEADER], "50");
+        assert_eq!(response.headers()[RATE_LIMIT_REMAINING_HEADER], "29");
+
+        // Une fois le budget général épuisé, aucun scope ne passe plus
+        for _ in 0..29 {
+            assert_eq!(call("GET", "/api/v1/rest/network/stats").await.unwrap().status(), StatusCode::OK);
+        }
+        assert_eq!(call("GET", "/api/v1/rest/network/stats").await.unwrap().status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(call("GET", "/api/v1/rest/archives/a1").await.unwrap().status(), StatusCode::TOO_MANY_REQUESTS);
+    }
+
+    #[test]
+    fn test_client_ip_trusts_only_configured_proxies() {
+        use axum::body::Body;
+
+        let proxy: SocketAddr = "10.0.0.1:41000".parse().unwrap();
+        let client: SocketAddr = "203.0.113.9:52000".parse().unwrap();
+        let config = SecurityHeadersConfig {
+            trusted_proxies: vec![proxy.ip()],
+            ..SecurityHeadersConfig::default()
+        };
+        let request = |peer: Option<SocketAddr>, forwarded: Option<&str>| {
+            let mut builder = axum::http::Request::builder().uri("/");
+            if let Some(forwarded) = forwarded {
+                builder = builder.header("x-forwarded-for", forwarded);
+            }
+            let mut req = builder.body(Body::empty()).unwrap();
+            if let Some(peer) = peer {
+                req.extensions_mut().insert(ConnectInfo(peer));
+            }
+            req
+        };
+
+        // Un client direct ne choisit pas son IP en forgeant l'en-tête
+        assert_eq!(client_ip(&request(Some(client), Some("198.51.100.1")), &config), client.ip());
+        // Derrière le proxy, seule la valeur qu'il a ajoutée compte
+        let forwarded: IpAddr = "198.51.100.7".parse().unwrap();
+        assert_eq!(client_ip(&request(Some(proxy), Some("192.0.2.66, 198.51.100.7")), &config), forwarded);
+        assert_eq!(client_ip(&request(Some(proxy), None), &config), proxy.ip());
+        assert_eq!(client_ip(&request(Some(proxy), Some("garbage")), &config), proxy.ip());
+        // Sans adresse de connexion, l'en-tête reste ignoré
+        assert!(client_ip(&request(None, Some("198.51.100.1")), &config).is_unspecified());
+    }
+
+    #[tokio::test]
+    async fn test_budgets_bounded_by_evicting_stalest() {
+        let rate_limiters = RateLimiters::new(&RateLimitConfig::default());
+        let window = Duration::from_secs(3600);
+        let start = SystemTime::now();
+
+        // Des budgets entamés, donc non équivalents à des budgets neufs
+        for i in 0..MAX_TRACKED_BUDGETS * 2 {
+            let now = start + Duration::from_millis(i as u64);
+            rate_limiters.acquire_budget(&format!("ip:{}", i), 10, window, 1, now).await;
+        }
+        assert!(rate_limiters.tracked_budgets().await <= MAX_TRACKED_BUDGETS);
+
+        // Les plus récents sont conservés, les plus anciens repartent pleins
+        let now = start + Duration::from_secs(60);
+        let recent = format!("ip:{}", MAX_TRACKED_BUDGETS * 2 - 1);
+        assert_eq!(rate_limiters.acquire_budget(&recent, 10, window, 1, now).await.remaining, 8);
+        assert_eq!(rate_limiters.acquire_budget("ip:0", 10, window, 1, now).await.remaining, 9);
+    }
+
+    #[tokio::test]
+    async fn test_request_id_propagation() {
+        use axum::{body::Body, routing::get, Router};
+        use tower::ServiceExt;
+
+        let app = Router::new()
+            .route("/", get(|| async { current_request_id().unwrap_or_default() }))
+            .layer(axum::middleware::from_fn(request_id_middleware));
+
+        // Identifiant fourni par le client : repris dans la réponse et la tâche
+        let request = axum::http::Request::builder()
+            .uri("/")
+            .header(REQUEST_ID_HEADER, "trace-abc_123")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.headers()[REQUEST_ID_HEADER], "trace-abc_123");
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(body, "trace-abc_123");
+
+        // Identifiant invalide : remplacé par un UUID
+        let request = axum::http::Request::builder()
+            .uri("/")
+            .header(REQUEST_ID_HEADER, "bad id\twith spaces")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.oneshot(request).await.unwrap();
+        let generated = response.headers()[REQUEST_ID_HEADER].to_str().unwrap().to_string();
+        assert!(uuid::Uuid::parse_str(&generated).is_ok());
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(body, generated.as_bytes());
+
+        assert_eq!(current_request_id(), None);
     }
 
     #[test]
@@ -568,5 +1962,20 @@ mod tests {
         assert!(!config.log_responses);
         assert!(config.log_errors);
         assert_eq!(config.max_body_size, 4096);
+        assert!(!config.json_format);
+
+        // Les configurations antérieures au format JSON restent lisibles
+        let legacy: LoggingConfig = serde_json::from_value(serde_json::json!({
+            "enabled": true,
+            "log_requests": true,
+            "log_responses": false,
+            "log_errors": true,
+            "include_headers": false,
+            "include_body": false,
+            "max_body_size": 4096,
+        }))
+        .unwrap();
+        assert!(!legacy.json_format);
+        assert_eq!(legacy.filter, "info");
     }
 }
\ No newline at end of file
@@ -10,6 +10,7 @@
 pub mod types;
 pub mod auth;
 pub mod server;
+pub mod service;
 pub mod middleware;
 pub mod rest;
 pub mod graphql;
@@ -17,16 +18,19 @@ pub mod websocket;
 pub mod grpc;
 pub mod p2p;
 pub mod error;
+pub mod health;
+pub mod tls;
 
 // Re-exports publics
 pub use types::*;
 pub use auth::{AuthService, JwtClaims, AuthError, TokenInfo};
 pub use server::{ApiServer, ServerConfig, ServerHandle};
 pub use middleware::{
-    MiddlewareConfig, CorsConfig, RateLimitConfig, CompressionConfig, LoggingConfig,
-    cors_middleware, compression_middleware, tracing_middleware
+    MiddlewareConfig, CorsConfig, OriginPattern, SecurityHeadersConfig, HttpsEnforcement,
+    RateLimitConfig, CompressionConfig, LoggingConfig, cors_middleware, compression_middleware, tracing_middleware
 };
 pub use error::{ApiError, ApiResult};
+pub use health::{CheckStatus, HealthCheck, HealthProbe};
 
 // Configuration générale de l'API
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -71,6 +75,15 @@ impl Default for ApiConfig {
     }
 }
 
+impl ApiConfig {
+    /// Vérifie la configuration des serveurs avant leur démarrage
+    pub fn validate(&self) -> ApiResult<()> {
+        self.server.validate()?;
+        self.middleware.validate()?;
+        self.grpc.validate()
+    }
+}
+
 /// Informations de version de l'API
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ApiVersion {
@@ -86,41 +99,59 @@ impl Default for ApiVersion {
             version: env!("CARGO_PKG_VERSION").to_string(),
             build_date: chrono::Utc::now().format("%Y-%m-%d %H:%M:%S UTC").to_string(),
             commit_hash: "dev".to_string(),
-            supported_formats: vec![
-                "application/json".to_string(),
-                "application/cbor".to_string(),
-                "application/x-protobuf".to_string(),
-            ],
+            supported_formats: rest::negotiation::ResponseFormat::supported_mime_types(),
         }
     }
 }
 
 /// Health check pour l'API
+///
+/// `status` vaut `healthy`, `degraded` (un contrôle non critique échoue),
+/// `starting` (un contrôle critique n'est pas encore prêt), `unavailable`
+/// (un contrôle critique ne peut plus accepter de travail) ou `unhealthy`.
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct HealthStatus {
     pub status: String,
     pub version: String,
     pub timestamp: chrono::DateTime<chrono::Utc>,
     pub uptime: String,
-    pub checks: std::collections::HashMap<String, String>,
+    pub checks: std::collections::HashMap<String, HealthCheck>,
 }
 
 impl HealthStatus {
-    pub fn healthy() -> Self {
-        let mut checks = std::collections::HashMap::new();
-        checks.insert("database".to_string(), "healthy".to_string());
-        checks.insert("blockchain".to_string(), "healthy".to_string());
-        checks.insert("storage".to_string(), "healthy".to_string());
-        checks.insert("network".to_string(), "healthy".to_string());
-        
+    /// Agrège les résultats des contrôles en un état global
+    pub fn from_checks(checks: std::collections::HashMap<String, HealthCheck>) -> Self {
+        let critical_status = |status: CheckStatus| checks.values().any(|c| c.critical && c.status == status);
+        let status = if critical_status(CheckStatus::Unhealthy) {
+            "unhealthy"
+        } else if critical_status(CheckStatus::Unavailable) {
+            "unavailable"
+        } else if critical_status(CheckStatus::Starting) {
+            "starting"
+        } else if checks.values().any(|c| c.status != CheckStatus::Healthy) {
+            "degraded"
+        } else {
+            "healthy"
+        };
+
         Self {
-            status: "healthy".to_string(),
+            status: status.to_string(),
             version: env!("CARGO_PKG_VERSION").to_string(),
             timestamp: chrono::Utc::now(),
-            uptime: "0s".to_string(), // À calculer dynamiquement
+            uptime: "0s".to_string(),
             checks,
         }
     }
+
+    /// Sonde de vivacité : seul un contrôle critique en échec la fait échouer
+    pub fn is_live(&self) -> bool {
+        self.status != "unhealthy"
+    }
+
+    /// Sonde de disponibilité : le nœud peut recevoir du trafic
+    pub fn is_ready(&self) -> bool {
+        matches!(self.status.as_str(), "healthy" | "degraded")
+    }
 }
 
 #[cfg(test)]
@@ -138,12 +169,37 @@ mod tests {
         let version = ApiVersion::default();
         assert!(!version.version.is_empty());
         assert!(version.supported_formats.contains(&"application/json".to_string()));
+        assert!(version.supported_formats.contains(&"application/cbor".to_string()));
+        assert!(version.supported_formats.contains(&"application/x-protobuf".to_string()));
     }
 
     #[test]
     fn test_health_status() {
-        let health = HealthStatus::healthy();
+        let mut checks = std::collections::HashMap::new();
+        checks.insert("blockchain".to_string(), HealthCheck::healthy("height 0"));
+        let health = HealthStatus::from_checks(checks.clone());
         assert_eq!(health.status, "healthy");
-        assert!(health.checks.contains_key("database"));
+        assert!(health.is_live() && health.is_ready());
+
+        checks.insert("cache".to_string(), HealthCheck::unhealthy("unreachable").non_critical());
+        let health = HealthStatus::from_checks(checks.clone());
+        assert_eq!(health.status, "degraded");
+        assert!(health.is_live() && health.is_ready());
+
+        checks.insert("network".to_string(), HealthCheck::starting("1/3 peers"));
+        let health = HealthStatus::from_checks(checks.clone());
+        assert_eq!(health.status, "starting");
+        assert!(health.is_live() && !health.is_ready());
+
+        // Stockage saturé : retiré du trafic sans être redémarré
+        checks.insert("storage".to_string(), HealthCheck::unavailable("capacity 95.0%"));
+        let health = HealthStatus::from_checks(checks.clone());
+        assert_eq!(health.status, "unavailable");
+        assert!(health.is_live() && !health.is_ready());
+
+        checks.insert("blockchain".to_string(), HealthCheck::unhealthy("cannot read chain tip"));
+        let health = HealthStatus::from_checks(checks);
+        assert_eq!(health.status, "unhealthy");
+        assert!(!health.is_live() && !health.is_ready());
     }
 }
\ No newline at end of file
@@ -2,16 +2,28 @@
 //!
 //! Implémente le client P2P avec gestion des connexions, envoi/réception de messages
 //! et maintien de l'état du réseau.
+//!
+//! Une connexion n'est enregistrée qu'après le handshake : version du
+//! protocole, capacités (dont la compression) et, sur une connexion TLS,
+//! concordance entre l'identifiant annoncé et le certificat du pair.
 
 use std::collections::HashMap;
 use std::net::SocketAddr;
 use std::sync::Arc;
 use tokio::sync::{mpsc, RwLock, oneshot};
-use tokio::net::{TcpListener, TcpStream};
-use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpListener;
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
 use tokio::time::{Duration, timeout};
 
 use super::{P2PConfig, P2PError, P2PResult, messages::*};
+use super::compression::{decode_frame, encode_frame, CompressionCodec};
+use super::transport::{SecuredStream, Transport};
+
+/// Version du protocole annoncée au handshake
+pub const PROTOCOL_VERSION: &str = "1.0";
+
+/// Version du client annoncée au handshake
+const CLIENT_VERSION: &str = "archivechain-0.1.0";
 
 /// Client P2P principal
 #[derive(Debug)]
@@ -28,6 +40,10 @@ pub struct P2PClient {
     shutdown_tx: Arc<RwLock<Option<oneshot::Sender<()>>>>,
     /// ID de ce nœud
     node_id: String,
+    /// Transport des connexions, en clair ou TLS
+    transport: Arc<Transport>,
+    /// Adresse d'écoute effective, connue après `start`
+    local_addr: Arc<RwLock<Option<SocketAddr>>>,
 }
 
 /// Connexion vers un pair
@@ -45,6 +61,10 @@ pub struct PeerConnection {
     pub last_activity: chrono::DateTime<chrono::Utc>,
     /// Latence moyenne
     pub latency_ms: u64,
+    /// Codec négocié au handshake (`None` : trames historiques)
+    pub compression: Option<CompressionCodec>,
+    /// Connexion chiffrée en TLS, identité du pair vérifiée
+    pub encrypted: bool,
 }
 
 /// Statut de connexion
@@ -68,9 +88,12 @@ pub struct IncomingMessage {
 
 impl P2PClient {
     /// Crée un nouveau client P2P
+    ///
+    /// Avec une identité TLS, l'identifiant du nœud est celui de son certificat.
     pub async fn new(config: P2PConfig) -> P2PResult<Self> {
         let (message_tx, message_rx) = mpsc::unbounded_channel();
-        let node_id = Self::generate_node_id();
+        let transport = Transport::from_config(&config)?;
+        let node_id = transport.local_node_id().unwrap_or_else(Self::generate_node_id);
 
         Ok(Self {
             config,
@@ -79,6 +102,8 @@ impl P2PClient {
             message_rx: Arc::new(RwLock::new(Some(message_rx))),
             shutdown_tx: Arc::new(RwLock::new(None)),
             node_id,
+            transport: Arc::new(transport),
+            local_addr: Arc::new(RwLock::new(None)),
         })
     }
 
@@ -94,6 +119,7 @@ impl P2PClient {
         let listen_addr = format!("{}:{}", self.config.listen_addr, self.config.listen_port);
         let listener = TcpListener::bind(&listen_addr).await
             .map_err(|e| P2PError::NetworkError(format!("Failed to bind to {}: {}", listen_addr, e)))?;
+        *self.local_addr.write().await = listener.local_addr().ok();
 
         let (shutdown_tx, mut shutdown_rx) = oneshot::channel();
         {
@@ -106,6 +132,7 @@ impl P2PClient {
         let message_tx = self.message_tx.clone();
         let config = self.config.clone();
         let node_id = self.node_id.clone();
+        let transport = self.transport.clone();
 
         tokio::spawn(async move {
             loop {
@@ -114,17 +141,25 @@ impl P2PClient {
                         match result {
                             Ok((stream, addr)) => {
                                 tracing::debug!("Incoming connection from {}", addr);
-                                
-                                if let Err(e) = Self::handle_incoming_connection(
-                                    stream,
-                                    addr,
-                                    connections.clone(),
-                                    message_tx.clone(),
-                                    config.clone(),
-                                    node_id.clone(),
-                                ).await {
-                                    tracing::error!("Failed to handle incoming connection: {}", e);
-                                }
+
+                                let connections = connections.clone();
+                                let message_tx = message_tx.clone();
+                                let config = config.clone();
+                                let node_id = node_id.clone();
+                                let transport = transport.clone();
+                                tokio::spawn(async move {
+                                    if let Err(e) = Self::handle_incoming_connection(
+                                        stream,
+                                        addr,
+                                        connections,
+                                        message_tx,
+                                        config,
+                                        node_id,
+                                        transport,
+                                    ).await {
+                                        tracing::warn!("Refused incoming connection from {}: {}", addr, e);
+                                    }
+                                });
                             }
                             Err(e) => {
                                 tracing::error!("Failed to accept connection: {}", e);
@@ -167,51 +202,42 @@ impl P2PClient {
     }
 
     /// Connecte à un pair
+    ///
+    /// Retourne l'identifiant du pair une fois le handshake accepté.
     pub async fn connect_to_peer(&self, addr: SocketAddr) -> P2PResult<String> {
         tracing::debug!("Connecting to peer at {}", addr);
 
-        let stream = timeout(
-            Duration::from_secs(self.config.connection_timeout),
-            TcpStream::connect(addr)
-        ).await
-        .map_err(|_| P2PError::Timeout)?
-        .map_err(|e| P2PError::ConnectionFailed(format!("Failed to connect to {}: {}", addr, e)))?;
+        let connect_timeout = Duration::from_secs(self.config.connection_timeout);
+        let mut secured = self.transport.connect(addr, connect_timeout).await?;
 
-        let peer_id = format!("peer_{}", uuid::Uuid::new_v4().simple());
-        let (message_sender, message_receiver) = mpsc::unbounded_channel();
-
-        // Crée la connexion
-        let connection = PeerConnection {
-            peer_id: peer_id.clone(),
-            addr,
-            sender: message_sender,
-            status: ConnectionStatus::Connecting,
-            last_activity: chrono::Utc::now(),
-            latency_ms: 0,
-        };
+        // Le codec du pair est encore inconnu : trame historique
+        let handshake = Self::local_handshake(&self.node_id, &self.config);
+        let response = timeout(connect_timeout, async {
+            Self::send_message_to_stream(&mut secured.stream, &handshake, None).await?;
+            Self::read_message(&mut secured.stream, self.config.max_message_size).await
+        })
+        .await
+        .map_err(|_| P2PError::Timeout)??;
 
-        // Ajoute à la liste des connexions
-        {
-            let mut connections = self.connections.write().await;
-            connections.insert(peer_id.clone(), connection);
+        if !matches!(response, P2PMessage::HandshakeResponse { accepted: true, .. }) {
+            return Err(P2PError::ConnectionFailed(format!("Handshake rejected by {}", addr)));
         }
+        let (peer_id, compression) = Self::verify_handshake(&response, &secured, &self.config)?;
 
-        // Lance la tâche de gestion de cette connexion
         let connections = self.connections.clone();
         let message_tx = self.message_tx.clone();
         let config = self.config.clone();
-        let node_id = self.node_id.clone();
-
+        let established = peer_id.clone();
         tokio::spawn(async move {
-            if let Err(e) = Self::handle_outgoing_connection(
-                stream,
-                peer_id.clone(),
+            if let Err(e) = Self::run_connection(
+                secured,
+                established,
                 addr,
-                message_receiver,
+                compression,
+                response,
                 connections,
                 message_tx,
                 config,
-                node_id,
             ).await {
                 tracing::error!("Connection to {} failed: {}", addr, e);
             }
@@ -220,6 +246,19 @@ impl P2PClient {
         Ok(peer_id)
     }
 
+    /// Ferme la connexion vers un pair
+    pub async fn disconnect_peer(&self, peer_id: &str, reason: &str) -> P2PResult<()> {
+        let mut connections = self.connections.write().await;
+
+        match connections.remove(peer_id) {
+            Some(connection) => {
+                let _ = connection.sender.send(MessageBuilder::disconnect(reason.to_string()));
+                Ok(())
+            }
+            None => Err(P2PError::PeerNotFound(peer_id.to_string())),
+        }
+    }
+
     /// Envoie un message à un pair
     pub async fn send_message(&self, peer_id: &str, message: P2PMessage) -> P2PResult<()> {
         let connections = self.connections.read().await;
@@ -240,145 +279,159 @@ impl P2PClient {
     }
 
     /// Gère une connexion entrante
+    ///
+    /// Le pair doit ouvrir par un handshake ; un handshake invalide reçoit
+    /// une réponse de refus et la connexion n'est jamais enregistrée.
     async fn handle_incoming_connection(
-        stream: TcpStream,
+        stream: tokio::net::TcpStream,
         addr: SocketAddr,
         connections: Arc<RwLock<HashMap<String, PeerConnection>>>,
         message_tx: mpsc::UnboundedSender<IncomingMessage>,
         config: P2PConfig,
         node_id: String,
+        transport: Arc<Transport>,
     ) -> P2PResult<()> {
-        let peer_id = format!("peer_{}", uuid::Uuid::new_v4().simple());
-        let (message_sender, message_receiver) = mpsc::unbounded_channel();
-
-        // Crée la connexion
-        let connection = PeerConnection {
-            peer_id: peer_id.clone(),
-            addr,
-            sender: message_sender,
-            status: ConnectionStatus::Handshaking,
-            last_activity: chrono::Utc::now(),
-            latency_ms: 0,
+        let handshake_timeout = Duration::from_secs(config.connection_timeout);
+        let mut secured = timeout(handshake_timeout, transport.accept(stream))
+            .await
+            .map_err(|_| P2PError::Timeout)??;
+
+        let handshake = timeout(handshake_timeout, Self::read_message(&mut secured.stream, config.max_message_size))
+            .await
+            .map_err(|_| P2PError::Timeout)??;
+        let verified = match handshake {
+            P2PMessage::Handshake { .. } => Self::verify_handshake(&handshake, &secured, &config),
+            _ => Err(P2PError::ProtocolError("Expected handshake".to_string())),
         };
 
-        // Ajoute à la liste des connexions
-        {
-            let mut connections_guard = connections.write().await;
-            connections_guard.insert(peer_id.clone(), connection);
-        }
+        let accepted = verified.is_ok();
+        let response = Self::local_handshake_response(&node_id, &config, accepted);
+        Self::send_message_to_stream(&mut secured.stream, &response, None).await?;
+        let (peer_id, compression) = verified?;
 
-        // Gère la connexion
-        Self::handle_connection(
-            stream,
+        Self::run_connection(
+            secured,
             peer_id,
             addr,
-            message_receiver,
+            compression,
+            handshake,
             connections,
             message_tx,
             config,
-            node_id,
-            true, // incoming
         ).await
     }
 
-    /// Gère une connexion sortante
-    async fn handle_outgoing_connection(
-        stream: TcpStream,
-        peer_id: String,
-        addr: SocketAddr,
-        message_receiver: mpsc::UnboundedReceiver<P2PMessage>,
-        connections: Arc<RwLock<HashMap<String, PeerConnection>>>,
-        message_tx: mpsc::UnboundedSender<IncomingMessage>,
-        config: P2PConfig,
-        node_id: String,
-    ) -> P2PResult<()> {
-        Self::handle_connection(
-            stream,
-            peer_id,
-            addr,
-            message_receiver,
-            connections,
-            message_tx,
-            config,
-            node_id,
-            false, // outgoing
-        ).await
+    /// Vérifie le handshake d'un pair et retient le codec commun
+    ///
+    /// Sur une connexion TLS, l'identifiant annoncé doit être celui que
+    /// prouve le certificat présenté.
+    fn verify_handshake(
+        message: &P2PMessage,
+        secured: &SecuredStream,
+        config: &P2PConfig,
+    ) -> P2PResult<(String, Option<CompressionCodec>)> {
+        let (peer_id, protocol_version, capabilities) = match message {
+            P2PMessage::Handshake { peer_id, protocol_version, capabilities, .. }
+            | P2PMessage::HandshakeResponse { peer_id, protocol_version, capabilities, .. } => {
+                (peer_id, protocol_version, capabilities)
+            }
+            _ => return Err(P2PError::ProtocolError("Expected handshake".to_string())),
+        };
+
+        if !Self::is_compatible_version(protocol_version) {
+            return Err(P2PError::ProtocolError(format!(
+                "Unsupported protocol version {} (local {})",
+                protocol_version, PROTOCOL_VERSION
+            )));
+        }
+
+        if let Some(proven) = &secured.peer_node_id {
+            if proven != peer_id {
+                return Err(P2PError::ProtocolError(format!(
+                    "Peer announced node {} but its certificate belongs to {}",
+                    peer_id, proven
+                )));
+            }
+        }
+
+        let codec = CompressionCodec::negotiate(config.enable_compression, capabilities);
+        tracing::debug!(
+            "Negotiated {} framing with {}",
+            codec.map(|codec| codec.name()).unwrap_or("legacy"),
+            peer_id
+        );
+        Ok((peer_id.clone(), codec))
     }
 
-    /// Gère une connexion (commune aux entrantes et sortantes)
-    async fn handle_connection(
-        mut stream: TcpStream,
+    /// Versions compatibles : même version majeure
+    fn is_compatible_version(protocol_version: &str) -> bool {
+        let major = |version: &str| version.split('.').next().map(str::to_string);
+        major(protocol_version) == major(PROTOCOL_VERSION)
+    }
+
+    /// Enregistre une connexion dont le handshake a abouti et la sert
+    ///
+    /// Le handshake du pair est transmis comme premier message entrant, pour
+    /// que le gestionnaire enregistre le pair.
+    async fn run_connection(
+        secured: SecuredStream,
         peer_id: String,
         addr: SocketAddr,
-        mut message_receiver: mpsc::UnboundedReceiver<P2PMessage>,
+        compression: Option<CompressionCodec>,
+        handshake: P2PMessage,
         connections: Arc<RwLock<HashMap<String, PeerConnection>>>,
         message_tx: mpsc::UnboundedSender<IncomingMessage>,
         config: P2PConfig,
-        node_id: String,
-        is_incoming: bool,
     ) -> P2PResult<()> {
-        tracing::debug!("Handling {} connection with {}", 
-            if is_incoming { "incoming" } else { "outgoing" }, addr);
-
-        // Effectue le handshake
-        if !is_incoming {
-            // Pour les connexions sortantes, envoie le handshake en premier
-            let handshake = MessageBuilder::handshake(
-                node_id.clone(),
-                "1.0".to_string(),
-                "archivechain-0.1.0".to_string(),
-                0, // TODO: Récupérer la vraie hauteur de bloc
-                "0x0".to_string(), // TODO: Récupérer le vrai hash
-                vec!["sync".to_string(), "gossip".to_string()],
-            );
-
-            Self::send_message_to_stream(&mut stream, &handshake).await?;
-        }
+        let (message_sender, mut message_receiver) = mpsc::unbounded_channel();
+        let connection = PeerConnection {
+            peer_id: peer_id.clone(),
+            addr,
+            sender: message_sender,
+            status: ConnectionStatus::Connected,
+            last_activity: chrono::Utc::now(),
+            latency_ms: 0,
+            compression,
+            encrypted: secured.is_encrypted(),
+        };
+        connections.write().await.insert(peer_id.clone(), connection);
 
-        // Divise la stream en read/write
-        let (mut read_half, mut write_half) = stream.into_split();
+        let _ = message_tx.send(IncomingMessage {
+            peer_id: peer_id.clone(),
+            message: handshake,
+            received_at: chrono::Utc::now(),
+        });
+
+        let (mut read_half, mut write_half) = tokio::io::split(secured.stream);
 
         // Tâche de lecture
         let connections_read = connections.clone();
-        let message_tx_read = message_tx.clone();
         let peer_id_read = peer_id.clone();
         let read_task = tokio::spawn(async move {
-            let mut buffer = vec![0u8; config.max_message_size];
-            
             loop {
-                match read_half.read(&mut buffer).await {
-                    Ok(0) => {
-                        // Connexion fermée
-                        tracing::debug!("Connection closed by peer {}", peer_id_read);
-                        break;
-                    }
-                    Ok(n) => {
-                        // Message reçu
-                        match Self::parse_message(&buffer[..n]) {
-                            Ok(message) => {
-                                let incoming = IncomingMessage {
-                                    peer_id: peer_id_read.clone(),
-                                    message,
-                                    received_at: chrono::Utc::now(),
-                                };
-                                
-                                if let Err(_) = message_tx_read.send(incoming) {
-                                    tracing::error!("Failed to send incoming message to handler");
-                                    break;
-                                }
-
-                                // Met à jour l'activité
-                                if let Some(connection) = connections_read.write().await.get_mut(&peer_id_read) {
-                                    connection.last_activity = chrono::Utc::now();
-                                }
-                            }
-                            Err(e) => {
-                                tracing::error!("Failed to parse message from {}: {}", peer_id_read, e);
-                            }
+                match Self::read_message(&mut read_half, config.max_message_size).await {
+                    Ok(message) => {
+                        let incoming = IncomingMessage {
+                            peer_id: peer_id_read.clone(),
+                            message,
+                            received_at: chrono::Utc::now(),
+                        };
+
+                        if message_tx.send(incoming).is_err() {
+                            tracing::error!("Failed to send incoming message to handler");
+                            break;
+                        }
+
+                        // Met à jour l'activité
+                        if let Some(connection) = connections_read.write().await.get_mut(&peer_id_read) {
+                            connection.last_activity = chrono::Utc::now();
                         }
                     }
+                    Err(P2PError::InvalidMessage) => {
+                        tracing::error!("Failed to parse message from {}", peer_id_read);
+                    }
                     Err(e) => {
-                        tracing::error!("Read error from {}: {}", peer_id_read, e);
+                        tracing::debug!("Connection with {} closed: {}", peer_id_read, e);
                         break;
                     }
                 }
@@ -389,7 +442,7 @@ impl P2PClient {
         let peer_id_write = peer_id.clone();
         let write_task = tokio::spawn(async move {
             while let Some(message) = message_receiver.recv().await {
-                if let Err(e) = Self::send_message_to_stream(&mut write_half, &message).await {
+                if let Err(e) = Self::send_message_to_stream(&mut write_half, &message, compression).await {
                     tracing::error!("Failed to send message to {}: {}", peer_id_write, e);
                     break;
                 }
@@ -412,21 +465,52 @@ impl P2PClient {
         Ok(())
     }
 
+    /// Handshake annoncé par ce nœud
+    fn local_handshake(node_id: &str, config: &P2PConfig) -> P2PMessage {
+        MessageBuilder::handshake(
+            node_id.to_string(),
+            PROTOCOL_VERSION.to_string(),
+            CLIENT_VERSION.to_string(),
+            0, // TODO: Récupérer la vraie hauteur de bloc
+            "0x0".to_string(), // TODO: Récupérer le vrai hash
+            Self::local_capabilities(config),
+        )
+    }
+
+    /// Réponse de ce nœud au handshake d'un pair
+    fn local_handshake_response(node_id: &str, config: &P2PConfig, accepted: bool) -> P2PMessage {
+        MessageBuilder::handshake_response(
+            node_id.to_string(),
+            PROTOCOL_VERSION.to_string(),
+            CLIENT_VERSION.to_string(),
+            0, // TODO: Récupérer la vraie hauteur de bloc
+            "0x0".to_string(), // TODO: Récupérer le vrai hash
+            Self::local_capabilities(config),
+            accepted,
+        )
+    }
+
+    /// Capacités annoncées au handshake
+    pub(crate) fn local_capabilities(config: &P2PConfig) -> Vec<String> {
+        let mut capabilities = vec!["sync".to_string(), "gossip".to_string()];
+        capabilities.extend(CompressionCodec::local_capabilities(config.enable_compression));
+        capabilities
+    }
+
     /// Envoie un message via une stream
-    async fn send_message_to_stream<W>(writer: &mut W, message: &P2PMessage) -> P2PResult<()>
+    ///
+    /// `codec` est le codec négocié avec le pair, `None` pour une trame historique.
+    async fn send_message_to_stream<W>(
+        writer: &mut W,
+        message: &P2PMessage,
+        codec: Option<CompressionCodec>,
+    ) -> P2PResult<()>
     where
         W: AsyncWriteExt + Unpin,
     {
-        let serialized = serde_json::to_vec(message)
-            .map_err(|e| P2PError::InvalidMessage)?;
-
-        // Envoie la taille du message d'abord (4 bytes little-endian)
-        let size = serialized.len() as u32;
-        writer.write_all(&size.to_le_bytes()).await
-            .map_err(|e| P2PError::NetworkError(e.to_string()))?;
-
-        // Envoie le message
-        writer.write_all(&serialized).await
+        // Taille (4 bytes little-endian), codec éventuel puis le message
+        let frame = encode_frame(message, codec)?;
+        writer.write_all(&frame).await
             .map_err(|e| P2PError::NetworkError(e.to_string()))?;
 
         writer.flush().await
@@ -436,22 +520,34 @@ impl P2PClient {
     }
 
     /// Parse un message depuis des bytes
-    fn parse_message(data: &[u8]) -> P2PResult<P2PMessage> {
-        if data.len() < 4 {
-            return Err(P2PError::InvalidMessage);
-        }
+    ///
+    /// Accepte les trames historiques comme les trames avec codec.
+    fn parse_message(data: &[u8], max_message_size: usize) -> P2PResult<P2PMessage> {
+        decode_frame(data, max_message_size)
+    }
 
-        // Lit la taille du message
-        let size = u32::from_le_bytes([data[0], data[1], data[2], data[3]]) as usize;
-        
-        if data.len() < 4 + size {
-            return Err(P2PError::InvalidMessage);
+    /// Lit une trame complète puis la décode
+    ///
+    /// Une trame mal formée est consommée en entier : le flux reste aligné
+    /// sur la trame suivante.
+    async fn read_message<R>(reader: &mut R, max_message_size: usize) -> P2PResult<P2PMessage>
+    where
+        R: AsyncRead + Unpin,
+    {
+        let mut frame = vec![0u8; 4];
+        reader.read_exact(&mut frame).await
+            .map_err(|e| P2PError::NetworkError(e.to_string()))?;
+
+        // Une trame avec codec compte un octet de plus que son message
+        let size = u32::from_le_bytes([frame[0], frame[1], frame[2], frame[3]]) as usize;
+        if size > max_message_size + 1 {
+            return Err(P2PError::MessageTooLarge(size));
         }
 
-        // Parse le message JSON
-        let message_data = &data[4..4 + size];
-        serde_json::from_slice(message_data)
-            .map_err(|_| P2PError::InvalidMessage)
+        frame.resize(4 + size, 0);
+        reader.read_exact(&mut frame[4..]).await
+            .map_err(|e| P2PError::NetworkError(e.to_string()))?;
+        Self::parse_message(&frame, max_message_size)
     }
 
     /// Démarre la tâche de maintenance
@@ -494,6 +590,11 @@ impl P2PClient {
     pub fn node_id(&self) -> &str {
         &self.node_id
     }
+
+    /// Adresse d'écoute effective, `None` avant `start`
+    pub async fn local_addr(&self) -> Option<SocketAddr> {
+        *self.local_addr.read().await
+    }
 }
 
 #[cfg(test)]
@@ -536,6 +637,8 @@ mod tests {
             status: ConnectionStatus::Connected,
             last_activity: chrono::Utc::now(),
             latency_ms: 50,
+            compression: None,
+            encrypted: false,
         };
         
         assert_eq!(connection.peer_id, "peer_123");
@@ -569,7 +672,7 @@ mod tests {
         let mut data = size.to_le_bytes().to_vec();
         data.extend_from_slice(&serialized);
         
-        let parsed = P2PClient::parse_message(&data).unwrap();
+        let parsed = P2PClient::parse_message(&data, 1024).unwrap();
         match parsed {
             P2PMessage::Ping { nonce, .. } => assert_eq!(nonce, 12345),
             _ => panic!("Expected Ping message"),
@@ -579,11 +682,11 @@ mod tests {
     #[test]
     fn test_message_parsing_invalid() {
         // Données trop courtes
-        let result = P2PClient::parse_message(&[1, 2]);
+        let result = P2PClient::parse_message(&[1, 2], 1024);
         assert!(result.is_err());
         
         // Taille invalide
-        let result = P2PClient::parse_message(&[255, 255, 255, 255, 1, 2, 3]);
+        let result = P2PClient::parse_message(&[255, 255, 255, 255, 1, 2, 3], 1024);
         assert!(result.is_err());
     }
 
@@ -596,4 +699,152 @@ mod tests {
         assert!(id2.starts_with("node_"));
         assert_ne!(id1, id2);
     }
-}
\ No newline at end of file
+
+    fn local_config() -> P2PConfig {
+        P2PConfig {
+            listen_addr: "127.0.0.1".to_string(),
+            listen_port: 0,
+            connection_timeout: 5,
+            ..P2PConfig::default()
+        }
+    }
+
+    async fn started_client(config: P2PConfig) -> (P2PClient, SocketAddr) {
+        let client = P2PClient::new(config).await.unwrap();
+        client.start().await.unwrap();
+        let addr = client.local_addr().await.unwrap();
+        (client, addr)
+    }
+
+    /// Prochain message reçu, handshakes exclus
+    async fn next_message(receiver: &mut mpsc::UnboundedReceiver<IncomingMessage>) -> IncomingMessage {
+        loop {
+            let incoming = timeout(Duration::from_secs(5), receiver.recv()).await.unwrap().unwrap();
+            if !matches!(incoming.message, P2PMessage::Handshake { .. } | P2PMessage::HandshakeResponse { .. }) {
+                return incoming;
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_handshake_precedes_registration() {
+        let (server, addr) = started_client(local_config()).await;
+        let client = P2PClient::new(local_config()).await.unwrap();
+
+        let peer_id = client.connect_to_peer(addr).await.unwrap();
+        assert_eq!(peer_id, server.node_id());
+
+        let connection = client.get_connections().await.remove(&peer_id).unwrap();
+        assert_eq!(connection.status, ConnectionStatus::Connected);
+        assert!(!connection.encrypted);
+        assert!(connection.compression.is_some());
+
+        assert!(P2PClient::is_compatible_version("1.3"));
+        assert!(!P2PClient::is_compatible_version("2.0"));
+    }
+
+    #[cfg(feature = "tls")]
+    mod tls {
+        use super::*;
+        use std::path::Path;
+
+        /// Écrit un certificat Ed25519 auto-signé et sa clé dans `dir`
+        fn write_node_identity(dir: &Path, name: &str) -> (String, String) {
+            let key_pair = rcgen::KeyPair::generate_for(&rcgen::PKCS_ED25519).unwrap();
+            let cert = rcgen::CertificateParams::new(vec![name.to_string()])
+                .unwrap()
+                .self_signed(&key_pair)
+                .unwrap();
+            let cert_path = dir.join(format!("{}.crt", name));
+            let key_path = dir.join(format!("{}.key", name));
+            std::fs::write(&cert_path, cert.pem()).unwrap();
+            std::fs::write(&key_path, key_pair.serialize_pem()).unwrap();
+            (cert_path.display().to_string(), key_path.display().to_string())
+        }
+
+        fn tls_config(dir: &Path, name: &str) -> P2PConfig {
+            let (cert, key) = write_node_identity(dir, name);
+            P2PConfig {
+                tls_cert_path: Some(cert),
+                tls_key_path: Some(key),
+                ..local_config()
+            }
+        }
+
+        #[tokio::test]
+        async fn test_tls_nodes_exchange_ping_pong() {
+            let dir = tempfile::tempdir().unwrap();
+            let (server, addr) = started_client(tls_config(dir.path(), "server")).await;
+            let client = P2PClient::new(tls_config(dir.path(), "client")).await.unwrap();
+            let mut server_rx = server.take_message_receiver().await.unwrap();
+            let mut client_rx = client.take_message_receiver().await.unwrap();
+
+            // Identifiants dérivés des certificats
+            assert!(!client.node_id().starts_with("node_"));
+            assert_ne!(client.node_id(), server.node_id());
+
+            let peer_id = client.connect_to_peer(addr).await.unwrap();
+            assert_eq!(peer_id, server.node_id());
+            assert!(client.get_connections().await[&peer_id].encrypted);
+
+            client.send_message(&peer_id, MessageBuilder::ping(42)).await.unwrap();
+            let ping = next_message(&mut server_rx).await;
+            assert_eq!(ping.peer_id, client.node_id());
+            assert!(matches!(ping.message, P2PMessage::Ping { nonce: 42, .. }));
+
+            server.send_message(&ping.peer_id, MessageBuilder::pong(42)).await.unwrap();
+            let pong = next_message(&mut client_rx).await;
+            assert_eq!(pong.peer_id, server.node_id());
+            assert!(matches!(pong.message, P2PMessage::Pong { nonce: 42, .. }));
+        }
+
+        #[tokio::test]
+        async fn test_node_id_must_match_certificate() {
+            let dir = tempfile::tempdir().unwrap();
+            let (server, addr) = started_client(tls_config(dir.path(), "server")).await;
+            let victim = P2PClient::new(tls_config(dir.path(), "victim")).await.unwrap();
+
+            // Annonce l'identifiant d'un autre nœud avec son propre certificat
+            let mut impostor = P2PClient::new(tls_config(dir.path(), "impostor")).await.unwrap();
+            impostor.node_id = victim.node_id().to_string();
+
+            assert!(impostor.connect_to_peer(addr).await.is_err());
+            assert!(server.get_connections().await.is_empty());
+        }
+
+        #[tokio::test]
+        async fn test_plaintext_refused_when_encryption_required() {
+            let dir = tempfile::tempdir().unwrap();
+            let required = P2PConfig { require_encryption: true, ..tls_config(dir.path(), "server") };
+            let (server, addr) = started_client(required).await;
+
+            let plaintext = P2PClient::new(local_config()).await.unwrap();
+            assert!(plaintext.connect_to_peer(addr).await.is_err());
+            assert!(server.get_connections().await.is_empty());
+
+            // Un pair TLS reste accepté
+            let encrypted = P2PClient::new(tls_config(dir.path(), "client")).await.unwrap();
+            assert_eq!(encrypted.connect_to_peer(addr).await.unwrap(), server.node_id());
+        }
+
+        #[tokio::test]
+        async fn test_tls_node_falls_back_to_plaintext_peer() {
+            let dir = tempfile::tempdir().unwrap();
+            let (server, addr) = started_client(local_config()).await;
+
+            // Sans repli explicite, un nœud TLS ne parle pas en clair
+            let strict = P2PClient::new(tls_config(dir.path(), "strict")).await.unwrap();
+            assert!(strict.connect_to_peer(addr).await.is_err());
+            let (strict_server, strict_addr) = started_client(tls_config(dir.path(), "strict-server")).await;
+            let plaintext = P2PClient::new(local_config()).await.unwrap();
+            assert!(plaintext.connect_to_peer(strict_addr).await.is_err());
+            assert!(strict_server.get_connections().await.is_empty());
+
+            let fallback = P2PConfig { allow_plaintext_fallback: true, ..tls_config(dir.path(), "client") };
+            let client = P2PClient::new(fallback).await.unwrap();
+            let peer_id = client.connect_to_peer(addr).await.unwrap();
+            assert_eq!(peer_id, server.node_id());
+            assert!(!client.get_connections().await[&peer_id].encrypted);
+        }
+    }
+}
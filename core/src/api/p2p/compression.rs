@@ -0,0 +1,273 @@
+//! Compression des messages P2P
+//!
+//! Chaque pair annonce dans ses capacités de handshake les codecs qu'il sait
+//! décoder (`compression:zstd`, `compression:gzip`, `compression:none`). Le
+//! meilleur codec commun est retenu pour chaque connexion.
+//!
+//! Format des trames :
+//!
+//! - **trame historique** : `[taille u32 LE][JSON]`, seule comprise par les
+//!   anciens nœuds ; le JSON commence toujours par `{` ;
+//! - **trame avec codec** : `[taille u32 LE][codec u8][charge utile]`, envoyée
+//!   uniquement aux pairs ayant annoncé au moins un codec.
+//!
+//! Les identifiants de codec ne valent jamais `{`, ce qui permet au récepteur
+//! de reconnaître les deux formats sans négociation préalable. Un pair qui
+//! n'annonce aucun codec reçoit toujours des trames historiques : anciens et
+//! nouveaux nœuds cohabitent sans mise à jour simultanée.
+
+use serde::{Deserialize, Serialize};
+use std::io::Read;
+
+use super::{P2PError, P2PMessage, P2PResult};
+
+/// Préfixe des capacités de compression annoncées au handshake
+pub const COMPRESSION_CAPABILITY_PREFIX: &str = "compression:";
+
+/// Taille en dessous de laquelle un message n'est pas compressé
+pub const MIN_COMPRESSION_SIZE: usize = 256;
+
+/// Premier octet d'une trame historique (objet JSON)
+const LEGACY_FRAME_MARKER: u8 = b'{';
+
+/// Codecs de compression des messages P2P
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CompressionCodec {
+    /// Pas de compression, toujours disponible
+    None,
+    /// Gzip (flate2)
+    Gzip,
+    /// Zstandard
+    Zstd,
+}
+
+impl CompressionCodec {
+    /// Codecs supportés, du plus préféré au moins préféré
+    pub const PREFERENCE: [CompressionCodec; 3] = [
+        CompressionCodec::Zstd,
+        CompressionCodec::Gzip,
+        CompressionCodec::None,
+    ];
+
+    /// Identifiant du codec dans les trames
+    pub fn id(&self) -> u8 {
+        match self {
+            CompressionCodec::None => 0,
+            CompressionCodec::Gzip => 1,
+            CompressionCodec::Zstd => 2,
+        }
+    }
+
+    /// Retrouve un codec depuis son identifiant
+    pub fn from_id(id: u8) -> Option<Self> {
+        match id {
+            0 => Some(CompressionCodec::None),
+            1 => Some(CompressionCodec::Gzip),
+            2 => Some(CompressionCodec::Zstd),
+            _ => None,
+        }
+    }
+
+    /// Nom du codec
+    pub fn name(&self) -> &'static str {
+        match self {
+            CompressionCodec::None => "none",
+            CompressionCodec::Gzip => "gzip",
+            CompressionCodec::Zstd => "zstd",
+        }
+    }
+
+    /// Capacité de handshake annonçant ce codec
+    pub fn capability(&self) -> String {
+        format!("{}{}", COMPRESSION_CAPABILITY_PREFIX, self.name())
+    }
+
+    /// Retrouve un codec depuis une capacité de handshake
+    pub fn from_capability(capability: &str) -> Option<Self> {
+        let name = capability.strip_prefix(COMPRESSION_CAPABILITY_PREFIX)?;
+        Self::PREFERENCE.into_iter().find(|codec| codec.name() == name)
+    }
+
+    /// Capacités annoncées par ce nœud
+    ///
+    /// `none` est toujours annoncé : il signale que le nœud comprend les trames
+    /// avec codec, même si la compression est désactivée.
+    pub fn local_capabilities(enable_compression: bool) -> Vec<String> {
+        Self::PREFERENCE
+            .into_iter()
+            .filter(|codec| enable_compression || *codec == CompressionCodec::None)
+            .map(|codec| codec.capability())
+            .collect()
+    }
+
+    /// Choisit le meilleur codec commun avec un pair
+    ///
+    /// Retourne `None` si le pair n'annonce aucun codec : c'est un ancien nœud
+    /// qui ne comprend que les trames historiques.
+    pub fn negotiate<'a, I>(enable_compression: bool, remote_capabilities: I) -> Option<CompressionCodec>
+    where
+        I: IntoIterator<Item = &'a String>,
+    {
+        let remote: Vec<CompressionCodec> = remote_capabilities
+            .into_iter()
+            .filter_map(|capability| Self::from_capability(capability))
+            .collect();
+        if remote.is_empty() {
+            return None;
+        }
+
+        Self::PREFERENCE
+            .into_iter()
+            .filter(|codec| enable_compression || *codec == CompressionCodec::None)
+            .find(|codec| remote.contains(codec))
+            // Un pair qui annonce des codecs décode toujours les trames non compressées
+            .or(Some(CompressionCodec::None))
+    }
+
+    fn compress(&self, data: &[u8]) -> P2PResult<Vec<u8>> {
+        match self {
+            CompressionCodec::None => Ok(data.to_vec()),
+            CompressionCodec::Gzip => {
+                use std::io::Write;
+                let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(data)
+                    .map_err(|e| P2PError::ProtocolError(format!("gzip compression failed: {}", e)))?;
+                encoder.finish()
+                    .map_err(|e| P2PError::ProtocolError(format!("gzip compression failed: {}", e)))
+            }
+            CompressionCodec::Zstd => zstd::bulk::compress(data, 3)
+                .map_err(|e| P2PError::ProtocolError(format!("zstd compression failed: {}", e))),
+        }
+    }
+
+    /// Décompresse en refusant tout résultat dépassant `max_size`
+    fn decompress(&self, data: &[u8], max_size: usize) -> P2PResult<Vec<u8>> {
+        let decompressed = match self {
+            CompressionCodec::None => data.to_vec(),
+            CompressionCodec::Gzip => {
+                let mut result = Vec::new();
+                flate2::read::GzDecoder::new(data)
+                    .take(max_size as u64 + 1)
+                    .read_to_end(&mut result)
+                    .map_err(|_| P2PError::InvalidMessage)?;
+                result
+            }
+            CompressionCodec::Zstd => {
+                zstd::bulk::decompress(data, max_size + 1).map_err(|_| P2PError::InvalidMessage)?
+            }
+        };
+
+        if decompressed.len() > max_size {
+            return Err(P2PError::MessageTooLarge(decompressed.len()));
+        }
+        Ok(decompressed)
+    }
+}
+
+/// Encode un message en trame
+///
+/// `codec` vaut `None` pour un pair sans codec négocié (trame historique).
+/// Les petits messages partent non compressés, le gain ne couvrant pas le coût.
+pub fn encode_frame(message: &P2PMessage, codec: Option<CompressionCodec>) -> P2PResult<Vec<u8>> {
+    let serialized = serde_json::to_vec(message).map_err(|_| P2PError::InvalidMessage)?;
+
+    let body = match codec {
+        None => serialized,
+        Some(codec) => {
+            let codec = if serialized.len() < MIN_COMPRESSION_SIZE { CompressionCodec::None } else { codec };
+            let mut body = vec![codec.id()];
+            body.extend_from_slice(&codec.compress(&serialized)?);
+            body
+        }
+    };
+
+    let mut frame = (body.len() as u32).to_le_bytes().to_vec();
+    frame.extend_from_slice(&body);
+    Ok(frame)
+}
+
+/// Décode une trame, historique ou avec codec
+///
+/// `max_message_size` borne la taille du message décompressé.
+pub fn decode_frame(data: &[u8], max_message_size: usize) -> P2PResult<P2PMessage> {
+    if data.len() < 4 {
+        return Err(P2PError::InvalidMessage);
+    }
+
+    let size = u32::from_le_bytes([data[0], data[1], data[2], data[3]]) as usize;
+    if size == 0 || data.len() < 4 + size {
+        return Err(P2PError::InvalidMessage);
+    }
+    let body = &data[4..4 + size];
+
+    if body[0] == LEGACY_FRAME_MARKER {
+        return serde_json::from_slice(body).map_err(|_| P2PError::InvalidMessage);
+    }
+
+    let codec = CompressionCodec::from_id(body[0]).ok_or(P2PError::InvalidMessage)?;
+    let payload = codec.decompress(&body[1..], max_message_size)?;
+    serde_json::from_slice(&payload).map_err(|_| P2PError::InvalidMessage)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::p2p::MessageBuilder;
+
+    fn capabilities(codecs: &[&str]) -> Vec<String> {
+        codecs.iter().map(|codec| format!("{}{}", COMPRESSION_CAPABILITY_PREFIX, codec)).collect()
+    }
+
+    #[test]
+    fn test_negotiation_picks_best_common_codec() {
+        let remote = capabilities(&["gzip", "none"]);
+        assert_eq!(CompressionCodec::negotiate(true, &remote), Some(CompressionCodec::Gzip));
+
+        let remote = capabilities(&["zstd", "gzip", "none"]);
+        assert_eq!(CompressionCodec::negotiate(true, &remote), Some(CompressionCodec::Zstd));
+        // Compression désactivée localement : trames avec codec, sans compression
+        assert_eq!(CompressionCodec::negotiate(false, &remote), Some(CompressionCodec::None));
+
+        // Codecs inconnus ignorés, repli sur l'absence de compression
+        let remote = capabilities(&["lz4"]);
+        assert_eq!(CompressionCodec::negotiate(true, &remote), None);
+        let remote = capabilities(&["lz4", "none"]);
+        assert_eq!(CompressionCodec::negotiate(true, &remote), Some(CompressionCodec::None));
+
+        // Ancien nœud : aucune capacité de compression
+        let remote = vec!["sync".to_string(), "gossip".to_string()];
+        assert_eq!(CompressionCodec::negotiate(true, &remote), None);
+    }
+
+    #[test]
+    fn test_frames_roundtrip_and_interoperate_with_legacy_nodes() {
+        let message = MessageBuilder::block_announcement("a".repeat(1024), 42);
+
+        for codec in [None, Some(CompressionCodec::None), Some(CompressionCodec::Gzip), Some(CompressionCodec::Zstd)] {
+            let frame = encode_frame(&message, codec).unwrap();
+            match decode_frame(&frame, 1024 * 1024).unwrap() {
+                P2PMessage::BlockAnnouncement { block_height, .. } => assert_eq!(block_height, 42),
+                _ => panic!("Expected BlockAnnouncement message"),
+            }
+            if matches!(codec, Some(CompressionCodec::Gzip | CompressionCodec::Zstd)) {
+                assert!(frame.len() < 1024);
+            }
+        }
+
+        // Une trame historique est exactement ce qu'un ancien nœud attend
+        let legacy = encode_frame(&message, None).unwrap();
+        assert_eq!(&legacy[4..], serde_json::to_vec(&message).unwrap().as_slice());
+
+        // Les petits messages ne sont pas compressés
+        let ping = encode_frame(&MessageBuilder::ping(7), Some(CompressionCodec::Zstd)).unwrap();
+        assert_eq!(ping[4], CompressionCodec::None.id());
+
+        // Codec inconnu et bombe de décompression rejetés
+        let mut unknown = 2u32.to_le_bytes().to_vec();
+        unknown.extend_from_slice(&[9, 0]);
+        assert!(decode_frame(&unknown, 1024).is_err());
+        let frame = encode_frame(&message, Some(CompressionCodec::Zstd)).unwrap();
+        assert!(matches!(decode_frame(&frame, 512), Err(P2PError::MessageTooLarge(_))));
+    }
+}
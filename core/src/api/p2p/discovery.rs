@@ -1,14 +1,67 @@
 //! Service de découverte P2P pour ArchiveChain
 //!
 //! Implémente la découverte automatique de pairs via différents mécanismes.
+//!
+//! Les seeds DNS (`P2PConfig::dns_seeds`) complètent la liste statique
+//! `bootstrap_nodes` : chaque enregistrement A/AAAA d'un seed devient un
+//! candidat bootstrap, et la résolution est refaite périodiquement pour
+//! suivre la rotation de l'infrastructure de seeds.
 
 use std::collections::{HashMap, HashSet};
 use std::net::SocketAddr;
 use std::sync::Arc;
-use tokio::sync::{RwLock, oneshot};
+use tokio::sync::{RwLock, broadcast, oneshot};
 use tokio::time::{Duration, interval};
 
-use super::{P2PConfig, P2PError, P2PResult, messages::*};
+use super::{P2PConfig, P2PError, P2PResult, DEFAULT_P2P_PORT, messages::*};
+
+/// Capacité du canal des pairs découverts sur le réseau local
+const LOCAL_PEERS_CHANNEL_SIZE: usize = 64;
+
+/// Capacité du canal des candidats issus des seeds DNS
+const SEED_PEERS_CHANNEL_SIZE: usize = 256;
+
+/// Résolution des seeds DNS en adresses de pairs
+#[async_trait::async_trait]
+pub trait SeedResolver: Send + Sync + std::fmt::Debug {
+    /// Adresses (enregistrements A et AAAA) d'un seed
+    async fn resolve(&self, host: &str, port: u16) -> std::io::Result<Vec<SocketAddr>>;
+}
+
+/// Résolution par le résolveur DNS du système
+#[derive(Debug, Default)]
+pub struct SystemSeedResolver;
+
+#[async_trait::async_trait]
+impl SeedResolver for SystemSeedResolver {
+    async fn resolve(&self, host: &str, port: u16) -> std::io::Result<Vec<SocketAddr>> {
+        Ok(tokio::net::lookup_host((host, port)).await?.collect())
+    }
+}
+
+/// Sépare un seed `hôte[:port]` (IPv6 entre crochets) ; le port par défaut est `DEFAULT_P2P_PORT`
+fn parse_seed(seed: &str) -> Option<(&str, u16)> {
+    let seed = seed.trim();
+    let (host, port) = match seed.strip_prefix('[') {
+        Some(rest) => {
+            let (host, port) = rest.split_once(']')?;
+            match port {
+                "" => (host, None),
+                port => (host, Some(port.strip_prefix(':')?)),
+            }
+        }
+        None => match seed.rsplit_once(':') {
+            // Plusieurs `:` : une IPv6 nue, sans port
+            Some((host, port)) if !host.contains(':') => (host, Some(port)),
+            _ => (seed, None),
+        },
+    };
+    let port = match port {
+        Some(port) => port.parse().ok()?,
+        None => DEFAULT_P2P_PORT,
+    };
+    (!host.is_empty()).then_some((host, port))
+}
 
 /// Service de découverte de pairs
 #[derive(Debug)]
@@ -19,6 +72,18 @@ pub struct DiscoveryService {
     discovered_peers: Arc<RwLock<HashMap<String, DiscoveredPeer>>>,
     /// Canal d'arrêt
     shutdown_tx: Arc<RwLock<Option<oneshot::Sender<()>>>>,
+    /// Identifiant annoncé par ce nœud sur le réseau local
+    local_id: String,
+    /// Pairs découverts sur le réseau local, à connecter
+    local_peers: broadcast::Sender<DiscoveredPeer>,
+    /// Canal d'arrêt de la découverte locale
+    local_shutdown_tx: Arc<RwLock<Option<oneshot::Sender<()>>>>,
+    /// Résolveur des seeds DNS
+    seed_resolver: Arc<dyn SeedResolver>,
+    /// Candidats issus des seeds DNS, à connecter
+    seed_peers: broadcast::Sender<DiscoveredPeer>,
+    /// Canal d'arrêt du rafraîchissement des seeds DNS
+    seed_shutdown_tx: Arc<RwLock<Option<oneshot::Sender<()>>>>,
 }
 
 /// Pair découvert
@@ -47,19 +112,53 @@ pub enum DiscoverySource {
     PeerExchange,
     DHT,
     LocalNetwork,
+    DnsSeed,
     Manual,
 }
 
 impl DiscoveryService {
     /// Crée un nouveau service de découverte
     pub fn new(config: P2PConfig) -> Self {
+        let (local_peers, _) = broadcast::channel(LOCAL_PEERS_CHANNEL_SIZE);
+        let (seed_peers, _) = broadcast::channel(SEED_PEERS_CHANNEL_SIZE);
         Self {
             config,
             discovered_peers: Arc::new(RwLock::new(HashMap::new())),
             shutdown_tx: Arc::new(RwLock::new(None)),
+            local_id: format!("node_{}", uuid::Uuid::new_v4().simple()),
+            local_peers,
+            local_shutdown_tx: Arc::new(RwLock::new(None)),
+            seed_resolver: Arc::new(SystemSeedResolver),
+            seed_peers,
+            seed_shutdown_tx: Arc::new(RwLock::new(None)),
         }
     }
 
+    /// Remplace le résolveur des seeds DNS
+    pub fn with_seed_resolver(mut self, resolver: Arc<dyn SeedResolver>) -> Self {
+        self.seed_resolver = resolver;
+        self
+    }
+
+    /// Identifiant annoncé sur le réseau local (celui du client P2P)
+    pub fn with_local_id(mut self, local_id: String) -> Self {
+        self.local_id = local_id;
+        self
+    }
+
+    /// S'abonne aux pairs découverts sur le réseau local
+    pub fn subscribe_local_peers(&self) -> broadcast::Receiver<DiscoveredPeer> {
+        self.local_peers.subscribe()
+    }
+
+    /// S'abonne aux candidats bootstrap issus des seeds DNS
+    ///
+    /// Chaque rafraîchissement publie toutes les adresses résolues : c'est à
+    /// l'abonné d'ignorer celles des pairs déjà connectés.
+    pub fn subscribe_seed_peers(&self) -> broadcast::Receiver<DiscoveredPeer> {
+        self.seed_peers.subscribe()
+    }
+
     /// Démarre le service de découverte
     pub async fn start(&self) -> P2PResult<()> {
         if !self.config.enable_discovery {
@@ -77,6 +176,14 @@ impl DiscoveryService {
         // Ajoute les nœuds bootstrap
         self.add_bootstrap_peers().await?;
 
+        if self.config.enable_local_discovery {
+            self.start_local_discovery().await?;
+        }
+
+        if !self.config.dns_seeds.is_empty() {
+            self.start_seed_refresh().await;
+        }
+
         // Démarre la tâche de découverte périodique
         let discovered_peers = self.discovered_peers.clone();
         let config = self.config.clone();
@@ -114,11 +221,55 @@ impl DiscoveryService {
         if let Some(shutdown_tx) = self.shutdown_tx.write().await.take() {
             let _ = shutdown_tx.send(());
         }
+        if let Some(shutdown_tx) = self.local_shutdown_tx.write().await.take() {
+            let _ = shutdown_tx.send(());
+        }
+        if let Some(shutdown_tx) = self.seed_shutdown_tx.write().await.take() {
+            let _ = shutdown_tx.send(());
+        }
 
         tracing::info!("P2P discovery service stopped");
         Ok(())
     }
 
+    /// Démarre l'annonce et l'écoute sur le réseau local
+    ///
+    /// Les pairs annoncés sont enregistrés comme `LocalNetwork` puis publiés
+    /// aux abonnés de `subscribe_local_peers`.
+    #[cfg(feature = "p2p")]
+    async fn start_local_discovery(&self) -> P2PResult<()> {
+        use super::client::P2PClient;
+        use super::local_discovery::{self, LocalAnnouncement};
+
+        let (shutdown_tx, shutdown_rx) = oneshot::channel();
+        let (peers_tx, mut peers_rx) = tokio::sync::mpsc::channel(LOCAL_PEERS_CHANNEL_SIZE);
+        let announcement = LocalAnnouncement::new(
+            self.local_id.clone(),
+            self.config.listen_port,
+            P2PClient::local_capabilities(&self.config),
+        );
+        local_discovery::spawn(&self.config, announcement, peers_tx, shutdown_rx)?;
+        *self.local_shutdown_tx.write().await = Some(shutdown_tx);
+
+        let discovered_peers = self.discovered_peers.clone();
+        let local_peers = self.local_peers.clone();
+        tokio::spawn(async move {
+            while let Some(peer) = peers_rx.recv().await {
+                let discovered = Self::record_peer(&discovered_peers, peer.peer_id, peer.addr, DiscoverySource::LocalNetwork).await;
+                let _ = local_peers.send(discovered);
+            }
+        });
+
+        Ok(())
+    }
+
+    /// Sans la feature `p2p`, la découverte locale n'est pas disponible
+    #[cfg(not(feature = "p2p"))]
+    async fn start_local_discovery(&self) -> P2PResult<()> {
+        tracing::warn!("Local discovery requested but this build lacks the `p2p` feature");
+        Ok(())
+    }
+
     /// Ajoute les nœuds bootstrap
     async fn add_bootstrap_peers(&self) -> P2PResult<()> {
         let mut peers = self.discovered_peers.write().await;
@@ -147,6 +298,79 @@ impl DiscoveryService {
         Ok(())
     }
 
+    /// Résout les seeds DNS dès le démarrage puis toutes les `dns_seed_interval` secondes
+    async fn start_seed_refresh(&self) {
+        let (shutdown_tx, mut shutdown_rx) = oneshot::channel();
+        *self.seed_shutdown_tx.write().await = Some(shutdown_tx);
+
+        let config = self.config.clone();
+        let resolver = self.seed_resolver.clone();
+        let discovered_peers = self.discovered_peers.clone();
+        let seed_peers = self.seed_peers.clone();
+        tokio::spawn(async move {
+            let mut interval = interval(Duration::from_secs(config.dns_seed_interval.max(1)));
+
+            loop {
+                tokio::select! {
+                    _ = interval.tick() => {
+                        Self::resolve_seeds(&config, resolver.as_ref(), &discovered_peers, &seed_peers).await;
+                    }
+                    _ = &mut shutdown_rx => break,
+                }
+            }
+        });
+    }
+
+    /// Résout immédiatement les seeds DNS ; retourne les candidats obtenus
+    pub async fn refresh_dns_seeds(&self) -> Vec<DiscoveredPeer> {
+        Self::resolve_seeds(&self.config, self.seed_resolver.as_ref(), &self.discovered_peers, &self.seed_peers).await
+    }
+
+    /// Résout chaque seed et enregistre ses adresses comme `DnsSeed`
+    ///
+    /// Un seed introuvable est journalisé et retenté au rafraîchissement
+    /// suivant, sans interrompre les autres. Une adresse déjà connue sous une
+    /// autre source (bootstrap statique, peer exchange...) n'est pas dupliquée.
+    async fn resolve_seeds(
+        config: &P2PConfig,
+        resolver: &dyn SeedResolver,
+        discovered_peers: &RwLock<HashMap<String, DiscoveredPeer>>,
+        seed_peers: &broadcast::Sender<DiscoveredPeer>,
+    ) -> Vec<DiscoveredPeer> {
+        let mut addrs = Vec::new();
+        for seed in &config.dns_seeds {
+            let Some((host, port)) = parse_seed(seed) else {
+                tracing::warn!("Invalid DNS seed: {}", seed);
+                continue;
+            };
+            match resolver.resolve(host, port).await {
+                Ok(resolved) if resolved.is_empty() => tracing::warn!("DNS seed {} returned no address", seed),
+                Ok(resolved) => addrs.extend(resolved),
+                Err(e) => tracing::warn!("Failed to resolve DNS seed {}: {}", seed, e),
+            }
+        }
+
+        let mut candidates = Vec::new();
+        let mut seen = HashSet::new();
+        for addr in addrs {
+            if !seen.insert(addr) {
+                continue;
+            }
+            let known_elsewhere = discovered_peers.read().await.values()
+                .any(|peer| peer.addr == addr && peer.discovery_source != DiscoverySource::DnsSeed);
+            if known_elsewhere {
+                continue;
+            }
+
+            let peer = Self::record_peer(discovered_peers, format!("dns_seed_{}", addr), addr, DiscoverySource::DnsSeed).await;
+            let _ = seed_peers.send(peer.clone());
+            candidates.push(peer);
+        }
+
+        tracing::debug!("Resolved {} DNS seed candidates", candidates.len());
+        candidates
+    }
+
     /// Effectue la découverte périodique
     async fn perform_discovery(
         discovered_peers: &Arc<RwLock<HashMap<String, DiscoveredPeer>>>,
@@ -205,7 +429,18 @@ impl DiscoveryService {
         addr: SocketAddr,
         source: DiscoverySource,
     ) -> P2PResult<()> {
-        let mut peers = self.discovered_peers.write().await;
+        Self::record_peer(&self.discovered_peers, peer_id, addr, source).await;
+        Ok(())
+    }
+
+    /// Enregistre ou confirme un pair ; retourne son état à jour
+    async fn record_peer(
+        discovered_peers: &RwLock<HashMap<String, DiscoveredPeer>>,
+        peer_id: String,
+        addr: SocketAddr,
+        source: DiscoverySource,
+    ) -> DiscoveredPeer {
+        let mut peers = discovered_peers.write().await;
 
         if let Some(existing_peer) = peers.get_mut(&peer_id) {
             // Met à jour un pair existant
@@ -214,6 +449,7 @@ impl DiscoveryService {
             
             // Améliore le score de réputation
             existing_peer.reputation_score = (existing_peer.reputation_score + 0.1).min(1.0);
+            existing_peer.clone()
         } else {
             // Ajoute un nouveau pair
             let peer = DiscoveredPeer {
@@ -226,11 +462,10 @@ impl DiscoveryService {
                 reputation_score: 0.5, // Score initial neutre
             };
 
-            peers.insert(peer_id.clone(), peer);
+            peers.insert(peer_id.clone(), peer.clone());
             tracing::debug!("Discovered new peer: {} at {}", peer_id, addr);
+            peer
         }
-
-        Ok(())
     }
 
     /// Supprime un pair
@@ -486,6 +721,125 @@ mod tests {
         }
     }
 
+    #[cfg(feature = "p2p")]
+    #[tokio::test]
+    async fn test_local_discovery_finds_nodes_without_bootstrap() {
+        const DISCOVERY_PORT: u16 = 47946;
+
+        let mut nodes = Vec::new();
+        for (index, listen_port) in [18101u16, 18102, 18103].into_iter().enumerate() {
+            let config = P2PConfig {
+                listen_addr: "127.0.0.1".to_string(),
+                listen_port,
+                bootstrap_nodes: vec![],
+                discovery_interval: 1,
+                enable_local_discovery: true,
+                local_discovery_port: DISCOVERY_PORT,
+                ..P2PConfig::default()
+            };
+            let service = DiscoveryService::new(config).with_local_id(format!("node_{}", index));
+            service.start().await.unwrap();
+            nodes.push(service);
+        }
+
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(10);
+        loop {
+            let mut all_found = true;
+            for (index, node) in nodes.iter().enumerate() {
+                let mut ports: Vec<u16> = node.get_discovered_peers().await.into_iter()
+                    .filter(|peer| peer.discovery_source == DiscoverySource::LocalNetwork)
+                    .map(|peer| peer.addr.port())
+                    .collect();
+                ports.sort_unstable();
+                let expected: Vec<u16> = [18101u16, 18102, 18103].into_iter()
+                    .enumerate()
+                    .filter(|(other, _)| *other != index)
+                    .map(|(_, port)| port)
+                    .collect();
+                all_found &= ports == expected;
+            }
+            if all_found {
+                break;
+            }
+            assert!(tokio::time::Instant::now() < deadline, "local nodes did not discover each other");
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+
+        for node in &nodes {
+            node.stop().await.unwrap();
+        }
+    }
+
+    /// Zone DNS simulée : un seed absent de la table est introuvable
+    #[derive(Debug, Default)]
+    struct FakeResolver {
+        records: std::sync::Mutex<HashMap<String, Vec<IpAddr>>>,
+    }
+
+    impl FakeResolver {
+        fn set(&self, host: &str, ips: &[&str]) {
+            let ips = ips.iter().map(|ip| ip.parse().unwrap()).collect();
+            self.records.lock().unwrap().insert(host.to_string(), ips);
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl SeedResolver for FakeResolver {
+        async fn resolve(&self, host: &str, port: u16) -> std::io::Result<Vec<SocketAddr>> {
+            self.records.lock().unwrap()
+                .get(host)
+                .map(|ips| ips.iter().map(|ip| SocketAddr::new(*ip, port)).collect())
+                .ok_or_else(|| std::io::Error::new(std::io::ErrorKind::NotFound, "NXDOMAIN"))
+        }
+    }
+
+    #[test]
+    fn test_parse_seed() {
+        assert_eq!(parse_seed("seed.archivechain.org"), Some(("seed.archivechain.org", DEFAULT_P2P_PORT)));
+        assert_eq!(parse_seed("seed.archivechain.org:9000"), Some(("seed.archivechain.org", 9000)));
+        assert_eq!(parse_seed("[2001:db8::1]:9000"), Some(("2001:db8::1", 9000)));
+        assert_eq!(parse_seed("2001:db8::1"), Some(("2001:db8::1", DEFAULT_P2P_PORT)));
+        assert_eq!(parse_seed("seed.archivechain.org:port"), None);
+        assert_eq!(parse_seed(""), None);
+    }
+
+    #[tokio::test]
+    async fn test_dns_seeds_become_bootstrap_candidates() {
+        let resolver = Arc::new(FakeResolver::default());
+        resolver.set("seed-a.test", &["203.0.113.1", "2001:db8::1"]);
+        let config = P2PConfig {
+            bootstrap_nodes: vec!["203.0.113.1:9000".to_string()],
+            dns_seeds: vec!["seed-a.test:9000".to_string(), "seed-b.test".to_string()],
+            ..P2PConfig::default()
+        };
+        let service = DiscoveryService::new(config).with_seed_resolver(resolver.clone());
+        service.add_bootstrap_peers().await.unwrap();
+        let mut seed_rx = service.subscribe_seed_peers();
+
+        // seed-b est introuvable : seed-a est tout de même exploité, et
+        // l'adresse déjà présente dans la liste statique n'est pas dupliquée
+        let candidates = service.refresh_dns_seeds().await;
+        let addrs: Vec<SocketAddr> = candidates.iter().map(|peer| peer.addr).collect();
+        assert_eq!(addrs, vec!["[2001:db8::1]:9000".parse::<SocketAddr>().unwrap()]);
+        assert_eq!(seed_rx.recv().await.unwrap().addr, addrs[0]);
+        assert_eq!(service.get_discovered_peers().await.len(), 2);
+
+        // Une fois seed-b résolu, ses adresses s'ajoutent ; un rafraîchissement
+        // confirme les candidats existants au lieu de les dupliquer
+        resolver.set("seed-b.test", &["198.51.100.7"]);
+        let candidates = service.refresh_dns_seeds().await;
+        assert_eq!(candidates.len(), 2);
+        assert!(candidates.iter().any(|peer| peer.addr == SocketAddr::new("198.51.100.7".parse().unwrap(), DEFAULT_P2P_PORT)));
+
+        let stats = service.get_discovery_stats().await;
+        assert_eq!(stats.by_source.get(&DiscoverySource::DnsSeed), Some(&2));
+        assert_eq!(stats.by_source.get(&DiscoverySource::Bootstrap), Some(&1));
+        let confirmed = service.get_discovered_peers().await.into_iter()
+            .find(|peer| peer.addr == addrs[0])
+            .unwrap();
+        assert_eq!(confirmed.confirmations, 2);
+    }
+
     #[tokio::test]
     async fn test_discovery_stats() {
         let config = P2PConfig::default();
@@ -1,10 +1,18 @@
 //! Service de gossip P2P pour ArchiveChain
 //!
-//! Implémente le protocole de gossip pour la diffusion d'informations dans le réseau.
-
-use std::collections::{HashMap, HashSet};
-use std::sync::Arc;
-use tokio::sync::{RwLock, oneshot};
+//! Implémente le protocole de gossip pour la diffusion d'informations dans le réseau :
+//! - Identifiant par message et cache des messages vus (LRU borné par un TTL)
+//! - Relais vers ⌈√n⌉ pairs tirés au hasard, jamais vers l'émetteur
+//! - Décrément du TTL à chaque saut
+//! - Canaux par topic (blocs, transactions, annonces de nœuds)
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use rand::{rngs::StdRng, seq::SliceRandom, SeedableRng};
+use tokio::sync::{broadcast, RwLock, oneshot};
 use tokio::time::{Duration, interval};
 
 use super::{P2PConfig, P2PError, P2PResult, messages::*};
@@ -14,12 +22,142 @@ use super::{P2PConfig, P2PError, P2PResult, messages::*};
 pub struct GossipService {
     /// Configuration
     config: P2PConfig,
+    /// Identifiant du nœud local (jamais ciblé par ses propres relais)
+    local_id: String,
     /// Messages de gossip actifs
     active_messages: Arc<RwLock<HashMap<String, GossipMessage>>>,
+    /// Identifiants des messages déjà vus
+    seen: Mutex<SeenCache<String>>,
+    /// Canaux de livraison locale par topic
+    channels: std::sync::RwLock<HashMap<String, broadcast::Sender<GossipDelivery>>>,
+    /// Tirage des pairs de relais
+    rng: Mutex<StdRng>,
+    /// Messages reçus en double et ignorés
+    duplicates_suppressed: AtomicU64,
+    /// Envois de relais (un par pair ciblé)
+    messages_relayed: AtomicU64,
     /// Canal d'arrêt
     shutdown_tx: Arc<RwLock<Option<oneshot::Sender<()>>>>,
 }
 
+/// Cache des identifiants déjà vus, borné en taille et en durée
+///
+/// Une clé revue est rafraîchie ; au-delà de la capacité, la clé la moins
+/// récemment vue est évincée.
+#[derive(Debug)]
+pub struct SeenCache<K> {
+    capacity: usize,
+    ttl: Duration,
+    entries: HashMap<K, Instant>,
+    /// Ordre de passage ; les entrées rafraîchies depuis sont ignorées
+    order: VecDeque<(K, Instant)>,
+}
+
+impl<K: Eq + Hash + Clone> SeenCache<K> {
+    pub fn new(capacity: usize, ttl: Duration) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            ttl,
+            entries: HashMap::new(),
+            order: VecDeque::new(),
+        }
+    }
+
+    /// Enregistre une clé ; retourne `true` si elle n'avait pas encore été vue
+    pub fn insert(&mut self, key: K) -> bool {
+        self.insert_at(key, Instant::now())
+    }
+
+    /// Variante de `insert` avec une horloge explicite
+    pub fn insert_at(&mut self, key: K, now: Instant) -> bool {
+        self.evict_expired(now);
+
+        let is_new = self.entries.insert(key.clone(), now).is_none();
+        self.order.push_back((key, now));
+
+        while self.entries.len() > self.capacity {
+            self.evict_oldest();
+        }
+        is_new
+    }
+
+    pub fn contains(&self, key: &K) -> bool {
+        self.entries.contains_key(key)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    fn evict_expired(&mut self, now: Instant) {
+        while let Some((key, seen_at)) = self.order.front() {
+            let current = self.entries.get(key) == Some(seen_at);
+            if current && now.duration_since(*seen_at) < self.ttl {
+                break;
+            }
+            if current {
+                self.entries.remove(key);
+            }
+            self.order.pop_front();
+        }
+    }
+
+    fn evict_oldest(&mut self) {
+        while let Some((key, seen_at)) = self.order.pop_front() {
+            if self.entries.get(&key) == Some(&seen_at) {
+                self.entries.remove(&key);
+                return;
+            }
+        }
+    }
+}
+
+/// Message livré aux abonnés d'un topic
+#[derive(Debug, Clone)]
+pub struct GossipDelivery {
+    pub message_id: String,
+    pub topic: String,
+    pub data: serde_json::Value,
+    /// Pair dont le message a été reçu
+    pub from_peer: String,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+}
+
+/// Relais à envoyer : un même message vers plusieurs pairs
+#[derive(Debug, Clone)]
+pub struct GossipRelay {
+    pub targets: Vec<String>,
+    pub message: P2PMessage,
+}
+
+impl GossipRelay {
+    /// Identifiant du message relayé
+    pub fn message_id(&self) -> &str {
+        match &self.message {
+            P2PMessage::Gossip { message_id, .. } => message_id,
+            _ => "",
+        }
+    }
+}
+
+/// Résultat de la réception d'un message de gossip
+#[derive(Debug, Clone)]
+pub struct GossipOutcome {
+    /// Première réception : le message a été livré localement
+    pub is_new: bool,
+    /// Relais à envoyer, absent pour un doublon ou un TTL épuisé
+    pub relay: Option<GossipRelay>,
+}
+
+/// Nombre de pairs relais pour `peer_count` pairs connectés : ⌈√n⌉
+pub fn gossip_fanout(peer_count: usize) -> usize {
+    (peer_count as f64).sqrt().ceil() as usize
+}
+
 /// Message de gossip avec métadonnées
 #[derive(Debug, Clone)]
 pub struct GossipMessage {
@@ -42,13 +180,52 @@ pub struct GossipMessage {
 impl GossipService {
     /// Crée un nouveau service de gossip
     pub fn new(config: P2PConfig) -> Self {
+        let seen = SeenCache::new(config.gossip_seen_cache_size, Duration::from_secs(config.gossip_seen_ttl_secs));
+        let channels = [topics::BLOCK_ANNOUNCEMENT, topics::TRANSACTION_ANNOUNCEMENT, topics::NODE_ANNOUNCEMENT]
+            .into_iter()
+            .map(|topic| (topic.to_string(), broadcast::channel(config.message_buffer_size.max(1)).0))
+            .collect();
+
         Self {
             config,
+            local_id: format!("node_{}", uuid::Uuid::new_v4().simple()),
             active_messages: Arc::new(RwLock::new(HashMap::new())),
+            seen: Mutex::new(seen),
+            channels: std::sync::RwLock::new(channels),
+            rng: Mutex::new(StdRng::from_entropy()),
+            duplicates_suppressed: AtomicU64::new(0),
+            messages_relayed: AtomicU64::new(0),
             shutdown_tx: Arc::new(RwLock::new(None)),
         }
     }
 
+    /// Définit l'identifiant du nœud local
+    pub fn with_local_id(mut self, local_id: String) -> Self {
+        self.local_id = local_id;
+        self
+    }
+
+    /// Rend le tirage des pairs de relais reproductible
+    pub fn with_rng_seed(self, seed: u64) -> Self {
+        *self.rng.lock().unwrap() = StdRng::seed_from_u64(seed);
+        self
+    }
+
+    /// S'abonne aux messages d'un topic
+    ///
+    /// Les canaux des blocs, transactions et annonces de nœuds existent dès
+    /// la création ; les autres sont créés au premier abonnement.
+    pub fn subscribe(&self, topic: &str) -> broadcast::Receiver<GossipDelivery> {
+        if let Some(sender) = self.channels.read().unwrap().get(topic) {
+            return sender.subscribe();
+        }
+        let mut channels = self.channels.write().unwrap();
+        channels
+            .entry(topic.to_string())
+            .or_insert_with(|| broadcast::channel(self.config.message_buffer_size.max(1)).0)
+            .subscribe()
+    }
+
     /// Démarre le service de gossip
     pub async fn start(&self) -> P2PResult<()> {
         tracing::info!("Starting P2P gossip service");
@@ -94,81 +271,188 @@ impl GossipService {
     }
 
     /// Diffuse un message de gossip
+    ///
+    /// Sans liste de pairs, le message est seulement enregistré ; voir
+    /// `publish` pour obtenir les pairs à contacter.
     pub async fn broadcast_gossip(
         &self,
         topic: String,
         data: serde_json::Value,
         ttl: u32,
     ) -> P2PResult<String> {
-        let message_id = format!("gossip_{}", uuid::Uuid::new_v4().simple());
-        
-        let gossip_message = GossipMessage {
-            message_id: message_id.clone(),
-            topic: topic.clone(),
-            data: data.clone(),
-            ttl,
-            created_at: chrono::Utc::now(),
-            propagated_to: HashSet::new(),
-            propagation_count: 0,
+        let relay = self.publish_message(MessageBuilder::gossip(topic, data, ttl), &[]).await?;
+        Ok(relay.message_id().to_string())
+    }
+
+    /// Publie un message sur un topic avec le TTL configuré
+    pub async fn publish(&self, topic: &str, data: serde_json::Value, peers: &[String]) -> P2PResult<GossipRelay> {
+        let message = MessageBuilder::gossip(topic.to_string(), data, self.config.gossip_ttl);
+        self.publish_message(message, peers).await
+    }
+
+    /// Publie un message de gossip déjà construit
+    ///
+    /// Le message est marqué comme vu (un écho ne sera ni relivré ni relayé)
+    /// et envoyé à ⌈√n⌉ des `peers` connectés.
+    pub async fn publish_message(&self, message: P2PMessage, peers: &[String]) -> P2PResult<GossipRelay> {
+        let P2PMessage::Gossip { message_id, topic, data, ttl, timestamp, seen_by } = message else {
+            return Err(P2PError::InvalidMessage);
         };
+        let message_id = self.resolve_message_id(message_id, &topic, &data, timestamp);
 
-        // Ajoute à la liste des messages actifs
-        {
-            let mut messages = self.active_messages.write().await;
-            messages.insert(message_id.clone(), gossip_message);
-        }
+        self.seen.lock().unwrap().insert(message_id.clone());
+        self.remember(&message_id, &topic, &data, ttl, timestamp, None).await;
 
         tracing::debug!("Broadcasting gossip message: {} on topic: {}", message_id, topic);
-        
-        // TODO: Envoyer le message à tous les pairs connectés via le P2PManager
-        
-        Ok(message_id)
+
+        let targets = self.select_targets(peers, None, &seen_by);
+        Ok(self.relay(message_id, topic, data, ttl, timestamp, seen_by, targets))
     }
 
     /// Traite un message de gossip reçu
+    ///
+    /// Retourne `true` si le message est nouveau et doit être propagé.
     pub async fn handle_gossip_message(&self, message: P2PMessage, from_peer: String) -> P2PResult<bool> {
-        if let P2PMessage::Gossip { topic, data, ttl, timestamp } = message {
-            let message_id = self.generate_message_id(&topic, &data, timestamp);
-            
-            // Vérifie si on a déjà vu ce message
-            {
-                let mut messages = self.active_messages.write().await;
-                if let Some(existing_message) = messages.get_mut(&message_id) {
-                    // Marque ce pair comme ayant reçu le message
-                    existing_message.propagated_to.insert(from_peer);
-                    return Ok(false); // Message déjà vu, ne pas propager
-                }
+        let ttl = match &message {
+            P2PMessage::Gossip { ttl, .. } => *ttl,
+            _ => return Ok(false),
+        };
+        let outcome = self.receive_gossip(message, &from_peer, &[]).await?;
+        Ok(outcome.is_new && ttl > 1)
+    }
 
-                // Nouveau message, l'ajouter
-                if ttl > 0 {
-                    let gossip_message = GossipMessage {
-                        message_id: message_id.clone(),
-                        topic: topic.clone(),
-                        data: data.clone(),
-                        ttl,
-                        created_at: timestamp,
-                        propagated_to: {
-                            let mut set = HashSet::new();
-                            set.insert(from_peer);
-                            set
-                        },
-                        propagation_count: 1,
-                    };
-
-                    messages.insert(message_id.clone(), gossip_message);
-                }
+    /// Reçoit un message de gossip d'un pair
+    ///
+    /// Un message déjà vu est compté comme doublon et ignoré. Un nouveau
+    /// message est livré aux abonnés de son topic puis, si son TTL le permet,
+    /// relayé avec un TTL décrémenté vers ⌈√n⌉ pairs, hors émetteur et pairs
+    /// déjà ciblés par la diffusion.
+    pub async fn receive_gossip(
+        &self,
+        message: P2PMessage,
+        from_peer: &str,
+        peers: &[String],
+    ) -> P2PResult<GossipOutcome> {
+        let P2PMessage::Gossip { message_id, topic, data, ttl, timestamp, seen_by } = message else {
+            return Err(P2PError::InvalidMessage);
+        };
+        let message_id = self.resolve_message_id(message_id, &topic, &data, timestamp);
+
+        if !self.seen.lock().unwrap().insert(message_id.clone()) {
+            self.duplicates_suppressed.fetch_add(1, Ordering::Relaxed);
+            if let Some(existing_message) = self.active_messages.write().await.get_mut(&message_id) {
+                existing_message.propagated_to.insert(from_peer.to_string());
             }
+            return Ok(GossipOutcome { is_new: false, relay: None });
+        }
 
-            // Traite le message selon le topic
-            self.process_gossip_topic(&topic, &data).await?;
+        self.remember(&message_id, &topic, &data, ttl, timestamp, Some(from_peer)).await;
 
-            // Propage le message si TTL > 1
-            if ttl > 1 {
-                return Ok(true);
-            }
+        // Traite le message selon le topic
+        self.process_gossip_topic(&topic, &data).await?;
+        self.deliver(GossipDelivery {
+            message_id: message_id.clone(),
+            topic: topic.clone(),
+            data: data.clone(),
+            from_peer: from_peer.to_string(),
+            timestamp,
+        });
+
+        let relay = if ttl > 1 {
+            let targets = self.select_targets(peers, Some(from_peer), &seen_by);
+            (!targets.is_empty()).then(|| self.relay(message_id, topic, data, ttl - 1, timestamp, seen_by, targets))
+        } else {
+            None
+        };
+
+        Ok(GossipOutcome { is_new: true, relay })
+    }
+
+    /// Identifiant fourni par l'émetteur, ou dérivé du contenu à défaut
+    fn resolve_message_id(
+        &self,
+        message_id: String,
+        topic: &str,
+        data: &serde_json::Value,
+        timestamp: chrono::DateTime<chrono::Utc>,
+    ) -> String {
+        if message_id.is_empty() {
+            self.generate_message_id(topic, data, timestamp)
+        } else {
+            message_id
+        }
+    }
+
+    /// Enregistre un message parmi les messages actifs
+    async fn remember(
+        &self,
+        message_id: &str,
+        topic: &str,
+        data: &serde_json::Value,
+        ttl: u32,
+        created_at: chrono::DateTime<chrono::Utc>,
+        from_peer: Option<&str>,
+    ) {
+        let gossip_message = GossipMessage {
+            message_id: message_id.to_string(),
+            topic: topic.to_string(),
+            data: data.clone(),
+            ttl,
+            created_at,
+            propagated_to: from_peer.map(str::to_string).into_iter().collect(),
+            propagation_count: from_peer.is_some() as u32,
+        };
+
+        let mut messages = self.active_messages.write().await;
+        messages.insert(message_id.to_string(), gossip_message);
+    }
+
+    /// Livre un message aux abonnés de son topic
+    fn deliver(&self, delivery: GossipDelivery) {
+        if let Some(sender) = self.channels.read().unwrap().get(&delivery.topic) {
+            // Aucun abonné : le message n'est simplement pas livré
+            let _ = sender.send(delivery);
+        }
+    }
+
+    /// Tire ⌈√n⌉ pairs parmi ceux qui n'ont pas déjà été ciblés
+    fn select_targets(&self, peers: &[String], from_peer: Option<&str>, seen_by: &[String]) -> Vec<String> {
+        let candidates: Vec<&String> = peers
+            .iter()
+            .filter(|peer| {
+                Some(peer.as_str()) != from_peer && **peer != self.local_id && !seen_by.contains(peer)
+            })
+            .collect();
+
+        let mut rng = self.rng.lock().unwrap();
+        candidates
+            .choose_multiple(&mut *rng, gossip_fanout(peers.len()))
+            .map(|peer| (*peer).clone())
+            .collect()
+    }
+
+    /// Construit le relais en ajoutant ce nœud et les cibles à `seen_by`
+    #[allow(clippy::too_many_arguments)]
+    fn relay(
+        &self,
+        message_id: String,
+        topic: String,
+        data: serde_json::Value,
+        ttl: u32,
+        timestamp: chrono::DateTime<chrono::Utc>,
+        mut seen_by: Vec<String>,
+        targets: Vec<String>,
+    ) -> GossipRelay {
+        if !seen_by.contains(&self.local_id) {
+            seen_by.push(self.local_id.clone());
         }
+        seen_by.extend(targets.iter().cloned());
+        self.messages_relayed.fetch_add(targets.len() as u64, Ordering::Relaxed);
 
-        Ok(false)
+        GossipRelay {
+            targets,
+            message: P2PMessage::Gossip { message_id, topic, data, ttl, timestamp, seen_by },
+        }
     }
 
     /// Propage un message de gossip
@@ -211,6 +495,9 @@ impl GossipService {
                 tracing::debug!("Received transaction announcement via gossip: {:?}", data);
                 // TODO: Traiter l'annonce de transaction
             }
+            "node_announcement" => {
+                tracing::debug!("Received node announcement via gossip: {:?}", data);
+            }
             "archive_announcement" => {
                 tracing::debug!("Received archive announcement via gossip: {:?}", data);
                 // TODO: Traiter l'annonce d'archive
@@ -272,6 +559,8 @@ impl GossipService {
             total_propagations: 0,
             messages_by_topic: HashMap::new(),
             average_ttl: 0.0,
+            duplicates_suppressed: self.duplicates_suppressed.load(Ordering::Relaxed),
+            messages_relayed: self.messages_relayed.load(Ordering::Relaxed),
         };
 
         let mut total_ttl = 0u32;
@@ -319,12 +608,17 @@ pub struct GossipStats {
     pub total_propagations: u32,
     pub messages_by_topic: HashMap<String, usize>,
     pub average_ttl: f64,
+    /// Messages reçus en double et ignorés
+    pub duplicates_suppressed: u64,
+    /// Envois de relais (un par pair ciblé)
+    pub messages_relayed: u64,
 }
 
 /// Topics de gossip prédéfinis
 pub mod topics {
     pub const BLOCK_ANNOUNCEMENT: &str = "block_announcement";
     pub const TRANSACTION_ANNOUNCEMENT: &str = "transaction_announcement";
+    pub const NODE_ANNOUNCEMENT: &str = "node_announcement";
     pub const ARCHIVE_ANNOUNCEMENT: &str = "archive_announcement";
     pub const NETWORK_STATUS: &str = "network_status";
     pub const PEER_DISCOVERY: &str = "peer_discovery";
@@ -388,10 +682,12 @@ mod tests {
         let service = GossipService::new(config);
         
         let gossip_msg = P2PMessage::Gossip {
+            message_id: String::new(),
             topic: "test_topic".to_string(),
             data: serde_json::json!({"test": "data"}),
             ttl: 5,
             timestamp: chrono::Utc::now(),
+            seen_by: Vec::new(),
         };
 
         let result = service.handle_gossip_message(gossip_msg, "peer_123".to_string()).await;
@@ -409,10 +705,12 @@ mod tests {
         
         let timestamp = chrono::Utc::now();
         let gossip_msg = P2PMessage::Gossip {
+            message_id: String::new(),
             topic: "test_topic".to_string(),
             data: serde_json::json!({"test": "data"}),
             ttl: 5,
             timestamp,
+            seen_by: Vec::new(),
         };
 
         // Premier message
@@ -504,5 +802,120 @@ mod tests {
         assert_eq!(topics::NETWORK_STATUS, "network_status");
         assert_eq!(topics::PEER_DISCOVERY, "peer_discovery");
         assert_eq!(topics::EMERGENCY_ALERT, "emergency_alert");
+        assert_eq!(topics::NODE_ANNOUNCEMENT, "node_announcement");
+    }
+
+    #[test]
+    fn test_seen_cache_is_bounded_by_size_and_ttl() {
+        let start = Instant::now();
+        let mut cache = SeenCache::new(2, Duration::from_secs(10));
+
+        assert!(cache.insert_at("a", start));
+        assert!(!cache.insert_at("a", start));
+        assert!(cache.insert_at("b", start + Duration::from_secs(1)));
+        // Rafraîchit "a" : "b" devient la clé la moins récemment vue
+        assert!(!cache.insert_at("a", start + Duration::from_secs(2)));
+        assert!(cache.insert_at("c", start + Duration::from_secs(3)));
+        assert!(cache.contains(&"a"));
+        assert!(!cache.contains(&"b"));
+
+        // Au-delà du TTL, un identifiant est de nouveau inconnu
+        assert!(cache.insert_at("a", start + Duration::from_secs(20)));
+        assert_eq!(cache.len(), 1);
+    }
+
+    #[test]
+    fn test_gossip_fanout() {
+        assert_eq!(gossip_fanout(0), 0);
+        assert_eq!(gossip_fanout(1), 1);
+        assert_eq!(gossip_fanout(19), 5);
+        assert_eq!(gossip_fanout(100), 10);
+    }
+
+    #[tokio::test]
+    async fn test_relay_decrements_ttl_and_skips_origin() {
+        let service = GossipService::new(P2PConfig::default()).with_local_id("local".to_string());
+        let peers: Vec<String> = (0..4).map(|i| format!("peer_{}", i)).collect();
+        let message = MessageBuilder::gossip(topics::BLOCK_ANNOUNCEMENT.to_string(), serde_json::json!({"height": 1}), 3);
+
+        let outcome = service.receive_gossip(message.clone(), "peer_0", &peers).await.unwrap();
+        assert!(outcome.is_new);
+        let relay = outcome.relay.unwrap();
+        assert_eq!(relay.targets.len(), 2);
+        assert!(!relay.targets.contains(&"peer_0".to_string()));
+        match &relay.message {
+            P2PMessage::Gossip { ttl, seen_by, .. } => {
+                assert_eq!(*ttl, 2);
+                assert!(seen_by.contains(&"local".to_string()));
+                assert!(relay.targets.iter().all(|t| seen_by.contains(t)));
+            }
+            _ => panic!("relais inattendu"),
+        }
+
+        // Le même identifiant reçu d'un autre pair est supprimé
+        let outcome = service.receive_gossip(message, "peer_1", &peers).await.unwrap();
+        assert!(!outcome.is_new);
+        assert!(outcome.relay.is_none());
+        assert_eq!(service.get_gossip_stats().await.duplicates_suppressed, 1);
+
+        // TTL épuisé : livré mais pas relayé
+        let last_hop = MessageBuilder::gossip(topics::BLOCK_ANNOUNCEMENT.to_string(), serde_json::json!({}), 1);
+        let outcome = service.receive_gossip(last_hop, "peer_0", &peers).await.unwrap();
+        assert!(outcome.is_new);
+        assert!(outcome.relay.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_gossip_mesh_delivers_exactly_once() {
+        const NODES: usize = 20;
+        let ids: Vec<String> = (0..NODES).map(|i| format!("node_{}", i)).collect();
+        let nodes: Vec<GossipService> = ids
+            .iter()
+            .enumerate()
+            .map(|(i, id)| GossipService::new(P2PConfig::default()).with_local_id(id.clone()).with_rng_seed(i as u64))
+            .collect();
+        let mut receivers: Vec<_> = nodes.iter().map(|node| node.subscribe(topics::BLOCK_ANNOUNCEMENT)).collect();
+        // Maillage complet : chaque nœud voit tous les autres
+        let peers_of = |i: usize| -> Vec<String> { ids.iter().filter(|id| **id != ids[i]).cloned().collect() };
+
+        let relay = nodes[0]
+            .publish(topics::BLOCK_ANNOUNCEMENT, serde_json::json!({"height": 42}), &peers_of(0))
+            .await
+            .unwrap();
+
+        let mut in_flight: VecDeque<(String, String, P2PMessage)> = relay
+            .targets
+            .iter()
+            .map(|target| (ids[0].clone(), target.clone(), relay.message.clone()))
+            .collect();
+        let mut total_messages = 0usize;
+
+        while let Some((from, to, message)) = in_flight.pop_front() {
+            total_messages += 1;
+            let index = ids.iter().position(|id| *id == to).unwrap();
+            let outcome = nodes[index].receive_gossip(message, &from, &peers_of(index)).await.unwrap();
+            if let Some(relay) = outcome.relay {
+                for target in relay.targets {
+                    in_flight.push_back((to.clone(), target, relay.message.clone()));
+                }
+            }
+        }
+
+        // Chaque nœud hors émetteur reçoit le message exactement une fois
+        assert!(receivers[0].try_recv().is_err());
+        for receiver in receivers.iter_mut().skip(1) {
+            let delivery = receiver.try_recv().unwrap();
+            assert_eq!(delivery.data, serde_json::json!({"height": 42}));
+            assert!(receiver.try_recv().is_err());
+        }
+
+        // Bien en deçà de l'inondation (n² envois)
+        assert!(total_messages < NODES * NODES / 2, "{} messages", total_messages);
+
+        let mut duplicates = 0;
+        for node in &nodes {
+            duplicates += node.get_gossip_stats().await.duplicates_suppressed;
+        }
+        assert_eq!(duplicates as usize, total_messages - (NODES - 1));
     }
 }
\ No newline at end of file
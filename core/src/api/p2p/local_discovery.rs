@@ -0,0 +1,208 @@
+//! Découverte des pairs sur le réseau local
+//!
+//! Destinée aux clusters de développement : chaque nœud annonce périodiquement
+//! son identifiant, son port d'écoute et ses capacités par multicast UDP sur
+//! `local_discovery_port`, et écoute les annonces des autres nœuds. Tous les
+//! nœuds d'une même machine partagent ce port grâce à `SO_REUSEADDR` /
+//! `SO_REUSEPORT`.
+//!
+//! La découverte locale ne s'exécute jamais sur une adresse d'écoute publique
+//! (ni sur `0.0.0.0`, qui inclut les interfaces publiques), sauf si
+//! `force_local_discovery` est positionné.
+
+use serde::{Deserialize, Serialize};
+use std::net::{IpAddr, Ipv4Addr, SocketAddr};
+use tokio::net::UdpSocket;
+use tokio::sync::{mpsc, oneshot};
+use tokio::time::{interval, Duration};
+
+use super::{P2PConfig, P2PError, P2PResult};
+
+/// Groupe multicast des annonces locales
+pub const LOCAL_DISCOVERY_GROUP: Ipv4Addr = Ipv4Addr::new(239, 255, 70, 77);
+
+/// Identifiant du protocole d'annonce
+const LOCAL_ANNOUNCEMENT_PROTOCOL: &str = "archivechain-local/1";
+
+/// Taille maximale d'une annonce
+const MAX_ANNOUNCEMENT_SIZE: usize = 2048;
+
+/// Annonce d'un nœud sur le réseau local
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LocalAnnouncement {
+    /// Version du protocole d'annonce
+    pub protocol: String,
+    /// Identifiant du nœud
+    pub peer_id: String,
+    /// Port d'écoute P2P du nœud
+    pub listen_port: u16,
+    /// Capacités annoncées au handshake
+    pub capabilities: Vec<String>,
+}
+
+impl LocalAnnouncement {
+    /// Crée l'annonce de ce nœud
+    pub fn new(peer_id: String, listen_port: u16, capabilities: Vec<String>) -> Self {
+        Self {
+            protocol: LOCAL_ANNOUNCEMENT_PROTOCOL.to_string(),
+            peer_id,
+            listen_port,
+            capabilities,
+        }
+    }
+
+    /// Décode une annonce reçue ; les datagrammes étrangers sont ignorés
+    pub fn decode(data: &[u8]) -> Option<Self> {
+        let announcement: Self = serde_json::from_slice(data).ok()?;
+        (announcement.protocol == LOCAL_ANNOUNCEMENT_PROTOCOL && announcement.listen_port != 0)
+            .then_some(announcement)
+    }
+}
+
+/// Pair annoncé sur le réseau local
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocalPeer {
+    /// Identifiant annoncé
+    pub peer_id: String,
+    /// Adresse P2P : source du datagramme et port d'écoute annoncé
+    pub addr: SocketAddr,
+    /// Capacités annoncées
+    pub capabilities: Vec<String>,
+}
+
+/// Vrai si l'adresse n'est joignable que depuis le réseau local
+pub fn is_local_address(ip: &IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => ip.is_loopback() || ip.is_private() || ip.is_link_local(),
+        IpAddr::V6(ip) => {
+            let first = ip.segments()[0];
+            // Adresses uniques locales (fc00::/7) et de lien local (fe80::/10)
+            ip.is_loopback() || (first & 0xfe00) == 0xfc00 || (first & 0xffc0) == 0xfe80
+        }
+    }
+}
+
+/// Vérifie que la découverte locale peut s'exécuter avec cette configuration
+pub fn check_local_discovery_allowed(config: &P2PConfig) -> P2PResult<()> {
+    if config.force_local_discovery {
+        return Ok(());
+    }
+
+    let ip: IpAddr = config.listen_addr.parse().map_err(|_| {
+        P2PError::NetworkError(format!("Invalid listen address: {}", config.listen_addr))
+    })?;
+    if ip.is_unspecified() || !is_local_address(&ip) {
+        return Err(P2PError::NetworkError(format!(
+            "Local discovery refused on non-local listen address {} (set force_local_discovery to override)",
+            config.listen_addr
+        )));
+    }
+    Ok(())
+}
+
+/// Ouvre le socket partagé des annonces locales
+fn bind_socket(config: &P2PConfig) -> P2PResult<UdpSocket> {
+    use socket2::{Domain, Protocol, Socket, Type};
+
+    let io_error = |e: std::io::Error| P2PError::NetworkError(format!("Local discovery socket: {}", e));
+    let interface = match config.listen_addr.parse::<IpAddr>() {
+        Ok(IpAddr::V4(ip)) if !ip.is_unspecified() => ip,
+        _ => Ipv4Addr::UNSPECIFIED,
+    };
+
+    let socket = Socket::new(Domain::IPV4, Type::DGRAM, Some(Protocol::UDP)).map_err(io_error)?;
+    socket.set_reuse_address(true).map_err(io_error)?;
+    #[cfg(unix)]
+    socket.set_reuse_port(true).map_err(io_error)?;
+    socket.set_nonblocking(true).map_err(io_error)?;
+    socket
+        .bind(&SocketAddr::new(IpAddr::V4(Ipv4Addr::UNSPECIFIED), config.local_discovery_port).into())
+        .map_err(io_error)?;
+    socket.join_multicast_v4(&LOCAL_DISCOVERY_GROUP, &interface).map_err(io_error)?;
+    socket.set_multicast_if_v4(&interface).map_err(io_error)?;
+    // Les nœuds d'une même machine doivent recevoir leurs annonces respectives
+    socket.set_multicast_loop_v4(true).map_err(io_error)?;
+
+    UdpSocket::from_std(socket.into()).map_err(io_error)
+}
+
+/// Démarre l'annonce et l'écoute sur le réseau local
+///
+/// Les pairs annoncés, hormis ce nœud, sont transmis sur `peers` à chaque
+/// annonce reçue ; ils sont abandonnés si le canal est plein. La tâche
+/// s'arrête à la réception de `shutdown`.
+pub(crate) fn spawn(
+    config: &P2PConfig,
+    announcement: LocalAnnouncement,
+    peers: mpsc::Sender<LocalPeer>,
+    mut shutdown: oneshot::Receiver<()>,
+) -> P2PResult<()> {
+    check_local_discovery_allowed(config)?;
+    let socket = bind_socket(config)?;
+    let payload = serde_json::to_vec(&announcement).map_err(|_| P2PError::InvalidMessage)?;
+    let group = SocketAddr::new(IpAddr::V4(LOCAL_DISCOVERY_GROUP), config.local_discovery_port);
+    let announce_every = Duration::from_secs(config.discovery_interval.max(1));
+
+    tracing::info!("Local discovery announcing {} on {}", announcement.peer_id, group);
+
+    tokio::spawn(async move {
+        let mut ticker = interval(announce_every);
+        let mut buffer = vec![0u8; MAX_ANNOUNCEMENT_SIZE];
+
+        loop {
+            tokio::select! {
+                _ = ticker.tick() => {
+                    if let Err(e) = socket.send_to(&payload, group).await {
+                        tracing::debug!("Local discovery announcement failed: {}", e);
+                    }
+                }
+                received = socket.recv_from(&mut buffer) => {
+                    let Ok((size, source)) = received else { continue };
+                    let Some(remote) = LocalAnnouncement::decode(&buffer[..size]) else { continue };
+                    if remote.peer_id == announcement.peer_id {
+                        continue;
+                    }
+                    let _ = peers.try_send(LocalPeer {
+                        peer_id: remote.peer_id,
+                        addr: SocketAddr::new(source.ip(), remote.listen_port),
+                        capabilities: remote.capabilities,
+                    });
+                }
+                _ = &mut shutdown => {
+                    tracing::info!("Local discovery shutting down");
+                    break;
+                }
+            }
+        }
+    });
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_local_discovery_refuses_public_listen_addresses() {
+        let config = |listen_addr: &str, force: bool| P2PConfig {
+            listen_addr: listen_addr.to_string(),
+            enable_local_discovery: true,
+            force_local_discovery: force,
+            ..P2PConfig::default()
+        };
+
+        assert!(check_local_discovery_allowed(&config("127.0.0.1", false)).is_ok());
+        assert!(check_local_discovery_allowed(&config("192.168.1.20", false)).is_ok());
+        assert!(check_local_discovery_allowed(&config("fd12:3456::1", false)).is_ok());
+        assert!(check_local_discovery_allowed(&config("0.0.0.0", false)).is_err());
+        assert!(check_local_discovery_allowed(&config("203.0.113.7", false)).is_err());
+        assert!(check_local_discovery_allowed(&config("203.0.113.7", true)).is_ok());
+
+        // Datagrammes étrangers ignorés
+        assert!(LocalAnnouncement::decode(b"{\"protocol\":\"other\",\"peer_id\":\"x\",\"listen_port\":1,\"capabilities\":[]}").is_none());
+        let announcement = LocalAnnouncement::new("node".to_string(), 8001, vec!["sync".to_string()]);
+        let encoded = serde_json::to_vec(&announcement).unwrap();
+        assert_eq!(LocalAnnouncement::decode(&encoded), Some(announcement));
+    }
+}
@@ -5,6 +5,8 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+use crate::state::SnapshotManifest;
+
 /// Messages P2P principaux
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
@@ -142,12 +144,56 @@ pub enum P2PMessage {
         message: Option<String>,
     },
 
+    /// Annonce d'un snapshot d'état disponible au téléchargement
+    SnapshotAnnouncement {
+        height: u64,
+        manifest_hash: String,
+        timestamp: chrono::DateTime<chrono::Utc>,
+    },
+
+    /// Demande du manifeste du dernier snapshot publié
+    SnapshotManifestRequest {
+        request_id: String,
+    },
+
+    /// Réponse avec le manifeste, absent si le pair n'a pas de snapshot
+    SnapshotManifestResponse {
+        manifest: Option<SnapshotManifest>,
+        request_id: String,
+    },
+
+    /// Demande d'un chunk de snapshot
+    SnapshotChunkRequest {
+        height: u64,
+        index: u32,
+        request_id: String,
+    },
+
+    /// Réponse avec un chunk, absent si le snapshot n'est plus servi
+    SnapshotChunkResponse {
+        height: u64,
+        index: u32,
+        data: Option<Vec<u8>>,
+        request_id: String,
+    },
+
     /// Message de gossip générique
     Gossip {
+        /// Identifiant attribué à la publication (clé de déduplication)
+        #[serde(default)]
+        message_id: String,
         topic: String,
         data: serde_json::Value,
         ttl: u32,
         timestamp: chrono::DateTime<chrono::Utc>,
+        /// Pairs déjà ciblés par la diffusion, évités lors des relais
+        #[serde(default)]
+        seen_by: Vec<String>,
+    },
+
+    /// Message réseau relayé de nœud à nœud (`NetworkMessage` en encodage canonique)
+    Relay {
+        data: Vec<u8>,
     },
 
     /// Demande de statut du réseau
@@ -236,8 +282,9 @@ impl P2PMessage {
             P2PMessage::BlockAnnouncement { .. } | P2PMessage::BlockRequest { .. } | P2PMessage::BlockResponse { .. } | P2PMessage::InventoryRequest { .. } | P2PMessage::InventoryResponse { .. } => MessageCategory::Blockchain,
             P2PMessage::TransactionAnnouncement { .. } | P2PMessage::TransactionRequest { .. } | P2PMessage::TransactionResponse { .. } => MessageCategory::Transaction,
             P2PMessage::ArchiveAnnouncement { .. } => MessageCategory::Archive,
-            P2PMessage::PeerRequest { .. } | P2PMessage::PeerResponse { .. } => MessageCategory::Peer,
+            P2PMessage::PeerRequest { .. } | P2PMessage::PeerResponse { .. } | P2PMessage::Relay { .. } => MessageCategory::Peer,
             P2PMessage::SyncRequest { .. } | P2PMessage::SyncStart { .. } | P2PMessage::SyncData { .. } | P2PMessage::SyncEnd { .. } => MessageCategory::Sync,
+            P2PMessage::SnapshotAnnouncement { .. } | P2PMessage::SnapshotManifestRequest { .. } | P2PMessage::SnapshotManifestResponse { .. } | P2PMessage::SnapshotChunkRequest { .. } | P2PMessage::SnapshotChunkResponse { .. } => MessageCategory::Sync,
             P2PMessage::Gossip { .. } => MessageCategory::Gossip,
             P2PMessage::NetworkStatusRequest { .. } | P2PMessage::NetworkStatusResponse { .. } => MessageCategory::Status,
             P2PMessage::Error { .. } | P2PMessage::Disconnect { .. } => MessageCategory::Error,
@@ -259,6 +306,10 @@ impl P2PMessage {
             P2PMessage::SyncStart { request_id, .. } |
             P2PMessage::SyncData { request_id, .. } |
             P2PMessage::SyncEnd { request_id, .. } |
+            P2PMessage::SnapshotManifestRequest { request_id, .. } |
+            P2PMessage::SnapshotManifestResponse { request_id, .. } |
+            P2PMessage::SnapshotChunkRequest { request_id, .. } |
+            P2PMessage::SnapshotChunkResponse { request_id, .. } |
             P2PMessage::NetworkStatusRequest { request_id, .. } |
             P2PMessage::NetworkStatusResponse { request_id, .. } => Some(request_id),
             P2PMessage::Error { request_id, .. } => request_id.as_deref(),
@@ -276,6 +327,8 @@ impl P2PMessage {
             P2PMessage::TransactionRequest { .. } |
             P2PMessage::PeerRequest { .. } |
             P2PMessage::SyncRequest { .. } |
+            P2PMessage::SnapshotManifestRequest { .. } |
+            P2PMessage::SnapshotChunkRequest { .. } |
             P2PMessage::NetworkStatusRequest { .. }
         )
     }
@@ -387,10 +440,12 @@ impl MessageBuilder {
     /// Crée un message de gossip
     pub fn gossip(topic: String, data: serde_json::Value, ttl: u32) -> P2PMessage {
         P2PMessage::Gossip {
+            message_id: format!("gossip_{}", uuid::Uuid::new_v4().simple()),
             topic,
             data,
             ttl,
             timestamp: chrono::Utc::now(),
+            seen_by: Vec::new(),
         }
     }
 
@@ -451,6 +506,11 @@ impl MessageValidator {
                     }
                 }
             }
+            P2PMessage::SnapshotAnnouncement { manifest_hash, .. } => {
+                if manifest_hash.len() != 64 {
+                    return Err("Invalid snapshot manifest hash length".to_string());
+                }
+            }
             P2PMessage::Gossip { topic, ttl, .. } => {
                 if topic.is_empty() {
                     return Err("Gossip topic cannot be empty".to_string());
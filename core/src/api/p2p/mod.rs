@@ -4,10 +4,14 @@
 //! incluant la découverte de pairs, la synchronisation et le gossip.
 
 pub mod client;
+pub mod compression;
 pub mod discovery;
+#[cfg(feature = "p2p")]
+pub mod local_discovery;
 pub mod gossip;
 pub mod sync;
 pub mod messages;
+pub mod transport;
 
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
@@ -15,14 +19,32 @@ use std::net::SocketAddr;
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
-use crate::api::{ApiResult, server::ServerState};
+use crate::api::{ApiResult, HealthCheck, HealthProbe, server::ServerState};
+use crate::block::BlockHeader;
+use crate::consensus::NodeId;
+use crate::crypto::Hash;
+use crate::nodes::{NetworkMessage, RelayTransport};
+use crate::serialization::CanonicalEncoding;
+use crate::state::{SnapshotManifest, StateSnapshot, StateStorage, DEFAULT_SNAPSHOT_CHUNK_SIZE};
+use crate::shutdown::{shutdown_error, ShutdownHook, ShutdownPhase};
+use crate::storage::{PrometheusEncoder, PrometheusExporter};
 
 // Re-exports
 pub use client::*;
+pub use compression::*;
 pub use discovery::*;
+#[cfg(feature = "p2p")]
+pub use local_discovery::{LocalAnnouncement, LOCAL_DISCOVERY_GROUP};
 pub use gossip::*;
 pub use sync::*;
 pub use messages::*;
+pub use transport::*;
+
+/// Retard maximal (en blocs) sur le meilleur pair avant de considérer le nœud en synchronisation
+const SYNC_TOLERANCE_BLOCKS: u64 = 2;
+
+/// Port P2P par défaut, supposé pour les seeds DNS qui n'en précisent pas
+pub const DEFAULT_P2P_PORT: u16 = 8000;
 
 /// Configuration P2P
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -43,22 +65,106 @@ pub struct P2PConfig {
     pub request_timeout: u64,
     /// Liste des nœuds bootstrap
     pub bootstrap_nodes: Vec<String>,
+    /// Seeds DNS (`hôte[:port]`) dont les enregistrements A/AAAA complètent `bootstrap_nodes`
+    #[serde(default)]
+    pub dns_seeds: Vec<String>,
+    /// Intervalle de résolution des seeds DNS (en secondes)
+    #[serde(default = "default_dns_seed_interval")]
+    pub dns_seed_interval: u64,
     /// Active le protocole de découverte automatique
     pub enable_discovery: bool,
     /// Intervalle de découverte (en secondes)
     pub discovery_interval: u64,
+    /// Annonce le nœud et découvre ses pairs sur le réseau local (feature `p2p`)
+    #[serde(default)]
+    pub enable_local_discovery: bool,
+    /// Port UDP partagé des annonces locales
+    #[serde(default = "default_local_discovery_port")]
+    pub local_discovery_port: u16,
+    /// Autorise la découverte locale même sur une adresse d'écoute publique
+    #[serde(default)]
+    pub force_local_discovery: bool,
     /// Taille maximum des messages
     pub max_message_size: usize,
     /// Buffer size pour les messages
     pub message_buffer_size: usize,
-    /// Active la compression des messages
+    /// Active la compression des messages (négociée par connexion, voir [`compression`])
     pub enable_compression: bool,
+    /// Score de mauvais comportement au-delà duquel un pair est banni
+    #[serde(default = "default_ban_score_threshold")]
+    pub ban_score_threshold: u32,
+    /// Durée d'un bannissement (en secondes)
+    #[serde(default = "default_ban_duration_secs")]
+    pub ban_duration_secs: u64,
+    /// Points de mauvais comportement oubliés par heure
+    #[serde(default = "default_score_decay_per_hour")]
+    pub score_decay_per_hour: u32,
+    /// Points retirés du score d'un pair pour chaque donnée utile fournie
+    #[serde(default = "default_useful_data_reward")]
+    pub useful_data_reward: u32,
+    /// TTL (nombre de sauts) des messages de gossip publiés
+    #[serde(default = "default_gossip_ttl")]
+    pub gossip_ttl: u32,
+    /// Nombre maximum d'identifiants de gossip mémorisés
+    #[serde(default = "default_gossip_seen_cache_size")]
+    pub gossip_seen_cache_size: usize,
+    /// Durée de mémorisation d'un identifiant de gossip (en secondes)
+    #[serde(default = "default_gossip_seen_ttl_secs")]
+    pub gossip_seen_ttl_secs: u64,
+    /// Certificat TLS du nœud, à clé Ed25519 (feature `tls`, voir [`transport`])
+    #[serde(default)]
+    pub tls_cert_path: Option<String>,
+    /// Clé privée du certificat TLS
+    #[serde(default)]
+    pub tls_key_path: Option<String>,
+    /// Refuse les pairs qui ne chiffrent pas leur connexion
+    #[serde(default)]
+    pub require_encryption: bool,
+    /// Autorise un nœud TLS à échanger en clair avec les pairs sans certificat
+    #[serde(default)]
+    pub allow_plaintext_fallback: bool,
+}
+
+fn default_dns_seed_interval() -> u64 {
+    1800 // 30 minutes
+}
+
+fn default_local_discovery_port() -> u16 {
+    7946
+}
+
+fn default_ban_score_threshold() -> u32 {
+    100
+}
+
+fn default_ban_duration_secs() -> u64 {
+    3600 // 1 heure
+}
+
+fn default_score_decay_per_hour() -> u32 {
+    20
+}
+
+fn default_useful_data_reward() -> u32 {
+    5
+}
+
+fn default_gossip_ttl() -> u32 {
+    6
+}
+
+fn default_gossip_seen_cache_size() -> usize {
+    10_000
+}
+
+fn default_gossip_seen_ttl_secs() -> u64 {
+    600 // 10 minutes
 }
 
 impl Default for P2PConfig {
     fn default() -> Self {
         Self {
-            listen_port: 8000,
+            listen_port: DEFAULT_P2P_PORT,
             listen_addr: "0.0.0.0".to_string(),
             max_peers: 50,
             min_peers: 3,
@@ -66,15 +172,50 @@ impl Default for P2PConfig {
             ping_interval: 30,
             request_timeout: 30,
             bootstrap_nodes: vec![],
+            dns_seeds: vec![],
+            dns_seed_interval: default_dns_seed_interval(),
             enable_discovery: true,
             discovery_interval: 60,
+            enable_local_discovery: false,
+            local_discovery_port: default_local_discovery_port(),
+            force_local_discovery: false,
             max_message_size: 1024 * 1024, // 1MB
             message_buffer_size: 1000,
             enable_compression: true,
+            ban_score_threshold: default_ban_score_threshold(),
+            ban_duration_secs: default_ban_duration_secs(),
+            score_decay_per_hour: default_score_decay_per_hour(),
+            useful_data_reward: default_useful_data_reward(),
+            gossip_ttl: default_gossip_ttl(),
+            gossip_seen_cache_size: default_gossip_seen_cache_size(),
+            gossip_seen_ttl_secs: default_gossip_seen_ttl_secs(),
+            tls_cert_path: None,
+            tls_key_path: None,
+            require_encryption: false,
+            allow_plaintext_fallback: false,
         }
     }
 }
 
+impl P2PConfig {
+    /// Reprend l'identité TLS et l'exigence de chiffrement du nœud
+    pub fn with_security(mut self, security: &crate::nodes::SecurityConfiguration) -> Self {
+        self.tls_cert_path = security.tls_cert_path.clone();
+        self.tls_key_path = security.tls_key_path.clone();
+        self.require_encryption = security.require_encryption;
+        self.allow_plaintext_fallback = security.allow_plaintext_fallback;
+        self
+    }
+
+    /// Reprend l'adresse d'écoute, les nœuds de bootstrap et la sécurité d'un nœud
+    pub fn for_node(mut self, node: &crate::nodes::NodeConfiguration) -> Self {
+        self.listen_addr = node.listen_address.clone();
+        self.listen_port = node.listen_port;
+        self.bootstrap_nodes = node.bootstrap_nodes.clone();
+        self.with_security(&node.security_config)
+    }
+}
+
 /// Informations sur un pair
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PeerInfo {
@@ -100,6 +241,9 @@ pub struct PeerInfo {
     pub region: Option<String>,
     /// Capacités supportées
     pub capabilities: HashSet<String>,
+    /// Hauteur du dernier snapshot d'état annoncé par le pair
+    #[serde(default)]
+    pub snapshot_height: Option<u64>,
 }
 
 /// Statut d'un pair
@@ -112,6 +256,95 @@ pub enum PeerStatus {
     Syncing,
 }
 
+/// Mauvais comportements pénalisés chez un pair
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Misbehavior {
+    /// Violation de protocole : message mal formé, inattendu ou non sollicité
+    InvalidMessage,
+    /// Réponse de synchronisation invalide ou absente
+    FailedSyncResponse,
+    /// Message dépassant `max_message_size`
+    OversizedMessage,
+    /// Bloc invalide transmis
+    InvalidBlock,
+}
+
+impl Misbehavior {
+    /// Pénalité ajoutée au score du pair
+    pub fn penalty(&self) -> u32 {
+        match self {
+            Misbehavior::InvalidMessage => 20,
+            Misbehavior::FailedSyncResponse => 10,
+            Misbehavior::OversizedMessage => 50,
+            Misbehavior::InvalidBlock => 50,
+        }
+    }
+}
+
+/// Score de mauvais comportement d'un pair, oublié progressivement
+///
+/// Le score survit au bannissement : un pair qui récidive peu après la fin
+/// de son bannissement est banni à nouveau plus vite.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PeerScore {
+    /// Points de mauvais comportement
+    pub score: u32,
+    /// Dernière mise à jour du score
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl PeerScore {
+    /// Score après l'oubli de `decay_per_hour` points par heure écoulée
+    pub fn decayed(&self, now: chrono::DateTime<chrono::Utc>, decay_per_hour: u32) -> u32 {
+        let elapsed = (now - self.updated_at).num_seconds().max(0) as u64;
+        let forgotten = elapsed * decay_per_hour as u64 / 3600;
+        self.score.saturating_sub(forgotten.min(u32::MAX as u64) as u32)
+    }
+
+    /// Applique l'oubli puis un ajustement du score
+    fn adjust(&mut self, now: chrono::DateTime<chrono::Utc>, decay_per_hour: u32, adjust: impl FnOnce(u32) -> u32) -> u32 {
+        let decayed = self.decayed(now, decay_per_hour);
+        // Une fraction de point pas encore oubliée reste acquise jusqu'au prochain ajustement
+        if decayed != self.score || decayed == 0 {
+            self.updated_at = now;
+        }
+        self.score = adjust(decayed);
+        self.score
+    }
+}
+
+/// Entrée de la liste des pairs bannis
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BanEntry {
+    /// ID du pair banni
+    pub peer_id: String,
+    /// Dernière adresse connue du pair
+    pub addr: Option<SocketAddr>,
+    /// Dernier comportement ayant déclenché le bannissement
+    pub reason: Misbehavior,
+    /// Score au moment du bannissement
+    pub score: u32,
+    /// Début du bannissement
+    pub banned_at: chrono::DateTime<chrono::Utc>,
+    /// Fin du bannissement
+    pub expires_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl BanEntry {
+    /// Vérifie si le bannissement est toujours actif
+    pub fn is_active(&self) -> bool {
+        chrono::Utc::now() < self.expires_at
+    }
+
+    /// Vérifie si le bannissement concerne ce pair (par ID ou par adresse exacte)
+    ///
+    /// L'IP seule n'est pas retenue : plusieurs pairs sans lien peuvent
+    /// partager la même IP derrière un NAT.
+    pub fn matches(&self, peer_info: &PeerInfo) -> bool {
+        self.peer_id == peer_info.peer_id || self.addr == Some(peer_info.addr)
+    }
+}
+
 /// Gestionnaire P2P principal
 #[derive(Clone)]
 pub struct P2PManager {
@@ -131,8 +364,22 @@ pub struct P2PManager {
     peers: Arc<RwLock<HashMap<String, PeerInfo>>>,
     /// Statistiques P2P
     stats: Arc<RwLock<P2PStats>>,
+    /// Scores de mauvais comportement par pair
+    misbehavior_scores: Arc<RwLock<HashMap<String, PeerScore>>>,
+    /// Pairs bannis
+    ban_list: Arc<RwLock<HashMap<String, BanEntry>>>,
+    /// En-tête de confiance et état à restaurer par synchronisation rapide au démarrage
+    fast_sync_anchor: Option<(BlockHeader, Arc<RwLock<dyn StateStorage>>)>,
+    /// Destinataire des messages relayés reçus, avec le nœud qui les a transmis
+    /// lorsque l'identifiant du pair est un `NodeId`
+    relay_inbox: Option<tokio::sync::mpsc::UnboundedSender<(Option<NodeId>, NetworkMessage)>>,
 }
 
+/// Nombre de tentatives de synchronisation rapide, espacées de `FAST_SYNC_RETRY_SECS`,
+/// avant d'abandonner faute de pair servant le snapshot
+const FAST_SYNC_ATTEMPTS: u32 = 12;
+const FAST_SYNC_RETRY_SECS: u64 = 10;
+
 /// Statistiques P2P
 #[derive(Debug, Default, Clone, Serialize, Deserialize)]
 pub struct P2PStats {
@@ -154,16 +401,50 @@ pub struct P2PStats {
     pub connections_closed: u64,
     /// Erreurs de connexion
     pub connection_errors: u64,
+    /// Pairs actuellement bannis
+    pub banned_peers: usize,
+    /// Messages de gossip reçus en double et ignorés
+    pub gossip_duplicates_suppressed: u64,
     /// Temps de fonctionnement
     pub uptime_seconds: u64,
 }
 
+impl P2PStats {
+    /// Rend les statistiques P2P au format d'exposition texte de Prometheus
+    pub fn to_prometheus(&self, labels: &[(&str, &str)]) -> String {
+        let mut encoder = PrometheusEncoder::new(labels);
+
+        encoder.family("archivechain_p2p_peers", "gauge", "Pairs par état", &[
+            (&[("state", "connected")], self.connected_peers as f64),
+            (&[("state", "active")], self.active_peers as f64),
+            (&[("state", "banned")], self.banned_peers as f64),
+        ]);
+        encoder.family("archivechain_p2p_messages_total", "counter", "Messages P2P échangés", &[
+            (&[("direction", "sent")], self.messages_sent as f64),
+            (&[("direction", "received")], self.messages_received as f64),
+        ]);
+        encoder.family("archivechain_p2p_bytes_total", "counter", "Octets P2P échangés", &[
+            (&[("direction", "sent")], self.bytes_sent as f64),
+            (&[("direction", "received")], self.bytes_received as f64),
+        ]);
+        encoder.family("archivechain_p2p_connections_total", "counter", "Événements de connexion", &[
+            (&[("event", "established")], self.connections_established as f64),
+            (&[("event", "closed")], self.connections_closed as f64),
+            (&[("event", "error")], self.connection_errors as f64),
+        ]);
+        encoder.counter("archivechain_p2p_gossip_duplicates_suppressed_total", "Messages de gossip en double ignorés", self.gossip_duplicates_suppressed as f64);
+        encoder.gauge("archivechain_p2p_uptime_seconds", "Temps de fonctionnement du réseau P2P", self.uptime_seconds as f64);
+
+        encoder.finish()
+    }
+}
+
 impl P2PManager {
     /// Crée un nouveau gestionnaire P2P
     pub async fn new(config: P2PConfig, server_state: ServerState) -> ApiResult<Self> {
         let client = Arc::new(P2PClient::new(config.clone()).await?);
-        let discovery = Arc::new(DiscoveryService::new(config.clone()));
-        let gossip = Arc::new(GossipService::new(config.clone()));
+        let discovery = Arc::new(DiscoveryService::new(config.clone()).with_local_id(client.node_id().to_string()));
+        let gossip = Arc::new(GossipService::new(config.clone()).with_local_id(client.node_id().to_string()));
         let sync_service = Arc::new(SyncService::new(config.clone(), server_state.blockchain.clone()));
 
         Ok(Self {
@@ -175,9 +456,28 @@ impl P2PManager {
             sync: sync_service,
             peers: Arc::new(RwLock::new(HashMap::new())),
             stats: Arc::new(RwLock::new(P2PStats::default())),
+            misbehavior_scores: Arc::new(RwLock::new(HashMap::new())),
+            ban_list: Arc::new(RwLock::new(HashMap::new())),
+            fast_sync_anchor: None,
+            relay_inbox: None,
         })
     }
 
+    /// Restaure `state` au démarrage depuis le snapshot engagé dans `anchor`
+    ///
+    /// `anchor` est un en-tête de confiance (point de contrôle fourni par
+    /// l'opérateur) : sa racine d'état est la référence de la restauration.
+    pub fn with_fast_sync(mut self, anchor: BlockHeader, state: Arc<RwLock<dyn StateStorage>>) -> Self {
+        self.fast_sync_anchor = Some((anchor, state));
+        self
+    }
+
+    /// Transmet à `inbox` les messages relayés reçus des pairs
+    pub fn with_relay_inbox(mut self, inbox: tokio::sync::mpsc::UnboundedSender<(Option<NodeId>, NetworkMessage)>) -> Self {
+        self.relay_inbox = Some(inbox);
+        self
+    }
+
     /// Démarre le gestionnaire P2P
     pub async fn start(&self) -> ApiResult<()> {
         tracing::info!("Starting P2P manager on port {}", self.config.listen_port);
@@ -187,6 +487,12 @@ impl P2PManager {
 
         // Démarre les services
         if self.config.enable_discovery {
+            if self.config.enable_local_discovery {
+                self.spawn_local_connector(self.discovery.subscribe_local_peers());
+            }
+            if !self.config.dns_seeds.is_empty() {
+                self.spawn_seed_connector(self.discovery.subscribe_seed_peers());
+            }
             self.discovery.start().await?;
         }
         self.gossip.start().await?;
@@ -198,6 +504,24 @@ impl P2PManager {
         // Démarre les tâches de maintenance
         self.start_maintenance_tasks().await;
 
+        // Publie les snapshots engagés par les blocs produits localement
+        self.spawn_snapshot_announcer();
+        if let Some((anchor, state)) = self.fast_sync_anchor.clone() {
+            self.spawn_fast_sync(anchor, state);
+        }
+
+        // Traite les messages entrants et note les pairs
+        if let Some(mut receiver) = self.client.take_message_receiver().await {
+            let manager = self.clone();
+            tokio::spawn(async move {
+                while let Some(incoming) = receiver.recv().await {
+                    if let Err(e) = manager.handle_incoming_message(incoming).await {
+                        tracing::debug!("Failed to handle incoming P2P message: {}", e);
+                    }
+                }
+            });
+        }
+
         tracing::info!("P2P manager started successfully");
         Ok(())
     }
@@ -220,6 +544,33 @@ impl P2PManager {
         Ok(())
     }
 
+    /// Annonce la déconnexion aux pairs puis arrête le gestionnaire P2P
+    ///
+    /// Les pairs retirent aussitôt ce nœud de leurs tables au lieu d'attendre
+    /// l'expiration des pings.
+    pub async fn shutdown_gracefully(&self) -> ApiResult<()> {
+        let mut disconnected = 0;
+        for peer_id in self.connected_peer_ids().await {
+            match self.client.disconnect_peer(&peer_id, "node shutting down").await {
+                Ok(()) => disconnected += 1,
+                Err(e) => tracing::debug!("Failed to announce disconnect to {}: {}", peer_id, e),
+            }
+        }
+
+        {
+            let mut peers = self.peers.write().await;
+            for peer in peers.values_mut() {
+                peer.status = PeerStatus::Disconnected;
+            }
+            let mut stats = self.stats.write().await;
+            stats.connections_closed += disconnected;
+            stats.connected_peers = 0;
+        }
+
+        tracing::info!("Announced disconnect to {} peers", disconnected);
+        self.stop().await
+    }
+
     /// Connecte aux nœuds bootstrap
     async fn connect_bootstrap_nodes(&self) -> ApiResult<()> {
         for bootstrap_addr in &self.config.bootstrap_nodes {
@@ -232,6 +583,69 @@ impl P2PManager {
         Ok(())
     }
 
+    /// Connecte les pairs découverts sur le réseau local
+    ///
+    /// Seul le nœud d'identifiant le plus petit initie la connexion, pour
+    /// qu'une paire de nœuds n'en ouvre pas deux. `max_peers` et les
+    /// bannissements s'appliquent comme pour toute autre connexion.
+    fn spawn_local_connector(&self, mut local_peers: tokio::sync::broadcast::Receiver<DiscoveredPeer>) {
+        use tokio::sync::broadcast::error::RecvError;
+
+        let manager = self.clone();
+        tokio::spawn(async move {
+            loop {
+                let peer = match local_peers.recv().await {
+                    Ok(peer) => peer,
+                    Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => break,
+                };
+                if manager.client.node_id() >= peer.peer_id.as_str() || !manager.should_connect_to(&peer.addr).await {
+                    continue;
+                }
+
+                if let Err(e) = manager.client.connect_to_peer(peer.addr).await {
+                    tracing::debug!("Failed to connect to local peer {} at {}: {}", peer.peer_id, peer.addr, e);
+                }
+            }
+        });
+    }
+
+    /// Connecte les candidats bootstrap issus des seeds DNS
+    ///
+    /// Les adresses déjà connectées sont ignorées : un rafraîchissement des
+    /// seeds ne rouvre que les connexions perdues.
+    fn spawn_seed_connector(&self, mut seed_peers: tokio::sync::broadcast::Receiver<DiscoveredPeer>) {
+        use tokio::sync::broadcast::error::RecvError;
+
+        let manager = self.clone();
+        tokio::spawn(async move {
+            loop {
+                let peer = match seed_peers.recv().await {
+                    Ok(peer) => peer,
+                    Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => break,
+                };
+                if !manager.should_connect_to(&peer.addr).await {
+                    continue;
+                }
+
+                if let Err(e) = manager.client.connect_to_peer(peer.addr).await {
+                    tracing::debug!("Failed to connect to DNS seed peer {}: {}", peer.addr, e);
+                }
+            }
+        });
+    }
+
+    /// Indique si une connexion sortante vers `addr` est utile et permise
+    ///
+    /// Refusée si `max_peers` est atteint, si l'adresse est déjà connectée ou bannie.
+    async fn should_connect_to(&self, addr: &SocketAddr) -> bool {
+        let connections = self.client.get_connections().await;
+        connections.len() < self.config.max_peers
+            && !connections.values().any(|connection| connection.addr == *addr)
+            && !self.is_address_banned(addr).await
+    }
+
     /// Démarre les tâches de maintenance
     async fn start_maintenance_tasks(&self) {
         let peers = self.peers.clone();
@@ -260,8 +674,9 @@ impl P2PManager {
             }
         });
 
-        // Tâche de mise à jour des statistiques
+        // Tâche de mise à jour des statistiques et d'expiration des bannissements
         let stats = self.stats.clone();
+        let ban_list = self.ban_list.clone();
         let start_time = chrono::Utc::now();
         tokio::spawn(async move {
             let mut interval = tokio::time::interval(tokio::time::Duration::from_secs(10));
@@ -269,14 +684,23 @@ impl P2PManager {
             loop {
                 interval.tick().await;
                 
+                let mut bans = ban_list.write().await;
+                bans.retain(|_, ban| ban.is_active());
+                
                 let mut stats_guard = stats.write().await;
                 stats_guard.uptime_seconds = (chrono::Utc::now() - start_time).num_seconds() as u64;
+                stats_guard.banned_peers = bans.len();
             }
         });
     }
 
     /// Ajoute un nouveau pair
     pub async fn add_peer(&self, peer_info: PeerInfo) -> ApiResult<()> {
+        if self.is_banned(&peer_info).await {
+            tracing::debug!("Refusing banned peer: {}", peer_info.peer_id);
+            return Err(P2PError::PeerBanned(peer_info.peer_id).into());
+        }
+
         let mut peers = self.peers.write().await;
         let mut stats = self.stats.write().await;
         
@@ -310,6 +734,253 @@ impl P2PManager {
         peers.values().cloned().collect()
     }
 
+    /// Signale un mauvais comportement d'un pair
+    ///
+    /// Retourne `true` si le pair a été banni suite à ce signalement.
+    pub async fn report_misbehavior(&self, peer_id: &str, misbehavior: Misbehavior) -> ApiResult<bool> {
+        self.report_misbehavior_at(peer_id, misbehavior, chrono::Utc::now()).await
+    }
+
+    /// Variante de `report_misbehavior` avec une horloge explicite
+    pub async fn report_misbehavior_at(
+        &self,
+        peer_id: &str,
+        misbehavior: Misbehavior,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> ApiResult<bool> {
+        let score = {
+            let mut scores = self.misbehavior_scores.write().await;
+            scores.entry(peer_id.to_string())
+                .or_insert(PeerScore { score: 0, updated_at: now })
+                .adjust(now, self.config.score_decay_per_hour, |score| score.saturating_add(misbehavior.penalty()))
+        };
+
+        tracing::debug!("Peer {} misbehaved ({:?}), score is now {}", peer_id, misbehavior, score);
+
+        if score < self.config.ban_score_threshold {
+            return Ok(false);
+        }
+
+        self.ban_peer(peer_id, misbehavior, score).await?;
+        Ok(true)
+    }
+
+    /// Récompense un pair ayant fourni des données utiles en réduisant son score
+    pub async fn report_useful_data(&self, peer_id: &str) {
+        self.report_useful_data_at(peer_id, chrono::Utc::now()).await
+    }
+
+    /// Variante de `report_useful_data` avec une horloge explicite
+    pub async fn report_useful_data_at(&self, peer_id: &str, now: chrono::DateTime<chrono::Utc>) {
+        let mut scores = self.misbehavior_scores.write().await;
+        if let Some(score) = scores.get_mut(peer_id) {
+            score.adjust(now, self.config.score_decay_per_hour, |score| score.saturating_sub(self.config.useful_data_reward));
+        }
+    }
+
+    /// Traite un message reçu d'un pair et met à jour son score
+    ///
+    /// Les blocs invalides et les messages non sollicités sont pénalisés ; les
+    /// données de synchronisation et les nouveaux messages de gossip valides
+    /// sont récompensés.
+    pub async fn handle_incoming_message(&self, incoming: IncomingMessage) -> ApiResult<()> {
+        let IncomingMessage { peer_id, message, .. } = incoming;
+        self.stats.write().await.messages_received += 1;
+
+        let result = match message {
+            P2PMessage::Handshake { .. } | P2PMessage::HandshakeResponse { .. } => {
+                self.register_handshake(&peer_id, message).await;
+                Ok(false)
+            }
+            P2PMessage::SyncData { .. } => self.sync.handle_sync_data(peer_id.clone(), message).await.map(|_| true),
+            P2PMessage::SyncRequest { .. } => match self.sync.handle_sync_request(peer_id.clone(), message).await {
+                Ok(response) => {
+                    self.send_to_peer(&peer_id, response).await?;
+                    Ok(false)
+                }
+                Err(e) => Err(e),
+            },
+            P2PMessage::SnapshotAnnouncement { height, .. } => {
+                if let Some(peer) = self.peers.write().await.get_mut(&peer_id) {
+                    peer.snapshot_height = Some(height);
+                }
+                Ok(false)
+            }
+            P2PMessage::SnapshotManifestRequest { .. } | P2PMessage::SnapshotChunkRequest { .. } => {
+                match self.sync.handle_snapshot_request(peer_id.clone(), message).await {
+                    Ok(response) => {
+                        self.send_to_peer(&peer_id, response).await?;
+                        Ok(false)
+                    }
+                    Err(e) => Err(e),
+                }
+            }
+            // La validité des chunks est vérifiée par `fast_sync`, qui écarte les pairs fautifs
+            P2PMessage::SnapshotManifestResponse { .. } | P2PMessage::SnapshotChunkResponse { .. } => {
+                self.sync.handle_snapshot_response(peer_id.clone(), message).await.map(|_| false)
+            }
+            P2PMessage::Gossip { .. } => {
+                let peers = self.connected_peer_ids().await;
+                match self.gossip.receive_gossip(message, &peer_id, &peers).await {
+                    Ok(outcome) => {
+                        if !outcome.is_new {
+                            self.stats.write().await.gossip_duplicates_suppressed += 1;
+                        }
+                        if let Some(relay) = outcome.relay {
+                            self.send_relay(relay).await;
+                        }
+                        Ok(outcome.is_new)
+                    }
+                    Err(e) => Err(e),
+                }
+            }
+            P2PMessage::Relay { data } => self.receive_relayed(&peer_id, &data),
+            _ => Ok(false),
+        };
+
+        match result {
+            Ok(true) => {
+                self.report_useful_data(&peer_id).await;
+                Ok(())
+            }
+            Ok(false) => Ok(()),
+            Err(e) => {
+                let misbehavior = match e {
+                    P2PError::InvalidBlock(_) => Misbehavior::InvalidBlock,
+                    P2PError::MessageTooLarge(_) => Misbehavior::OversizedMessage,
+                    _ => Misbehavior::InvalidMessage,
+                };
+                self.report_misbehavior(&peer_id, misbehavior).await?;
+                Err(e.into())
+            }
+        }
+    }
+
+    /// Enregistre le pair d'une connexion dont le handshake a abouti
+    ///
+    /// Le client ne transmet un handshake qu'après l'avoir vérifié ; un pair
+    /// déjà connu voit seulement ses capacités mises à jour.
+    async fn register_handshake(&self, peer_id: &str, message: P2PMessage) {
+        let (protocol_version, client_version, block_height, best_block_hash, capabilities) = match message {
+            P2PMessage::Handshake { protocol_version, client_version, block_height, best_block_hash, capabilities, .. }
+            | P2PMessage::HandshakeResponse { protocol_version, client_version, block_height, best_block_hash, capabilities, .. } => {
+                (protocol_version, client_version, block_height, best_block_hash, capabilities)
+            }
+            _ => return,
+        };
+
+        // Les capacités incluent les codecs de compression annoncés
+        if let Some(peer) = self.peers.write().await.get_mut(peer_id) {
+            peer.capabilities = capabilities.into_iter().collect();
+            return;
+        }
+
+        let Some(connection) = self.client.get_connections().await.remove(peer_id) else {
+            return;
+        };
+        let peer_info = PeerInfo {
+            peer_id: peer_id.to_string(),
+            addr: connection.addr,
+            protocol_version,
+            client_version,
+            block_height,
+            best_block_hash,
+            latency_ms: connection.latency_ms,
+            last_seen: chrono::Utc::now(),
+            status: PeerStatus::Connected,
+            region: None,
+            capabilities: capabilities.into_iter().collect(),
+            snapshot_height: None,
+        };
+
+        if let Err(e) = self.add_peer(peer_info).await {
+            tracing::debug!("Dropping connection with {}: {}", peer_id, e);
+            let _ = self.client.disconnect_peer(peer_id, "peer refused").await;
+        }
+    }
+
+    /// Vérifie la taille d'un message entrant et pénalise les dépassements
+    pub async fn check_message_size(&self, peer_id: &str, size: usize) -> ApiResult<()> {
+        if size > self.config.max_message_size {
+            self.report_misbehavior(peer_id, Misbehavior::OversizedMessage).await?;
+            return Err(P2PError::MessageTooLarge(size).into());
+        }
+        Ok(())
+    }
+
+    /// Bannit un pair : il passe au statut `Banned` et sa connexion est fermée
+    async fn ban_peer(&self, peer_id: &str, reason: Misbehavior, score: u32) -> ApiResult<()> {
+        let now = chrono::Utc::now();
+        let addr = {
+            let mut peers = self.peers.write().await;
+            let addr = peers.get_mut(peer_id).map(|peer| {
+                peer.status = PeerStatus::Banned;
+                peer.addr
+            });
+            peers.remove(peer_id);
+            addr
+        };
+
+        let banned_count = {
+            let mut bans = self.ban_list.write().await;
+            bans.insert(peer_id.to_string(), BanEntry {
+                peer_id: peer_id.to_string(),
+                addr,
+                reason,
+                score,
+                banned_at: now,
+                expires_at: now + chrono::Duration::seconds(self.config.ban_duration_secs as i64),
+            });
+            bans.len()
+        };
+
+        if let Err(e) = self.client.disconnect_peer(peer_id, "Banned for misbehavior").await {
+            tracing::debug!("No active connection to drop for banned peer {}: {}", peer_id, e);
+        }
+
+        {
+            let peers = self.peers.read().await;
+            let mut stats = self.stats.write().await;
+            stats.connected_peers = peers.len();
+            stats.connections_closed += 1;
+            stats.banned_peers = banned_count;
+        }
+
+        tracing::warn!("Peer {} banned for {}s ({:?}, score {})", peer_id, self.config.ban_duration_secs, reason, score);
+        Ok(())
+    }
+
+    /// Vérifie si un pair est actuellement banni
+    async fn is_banned(&self, peer_info: &PeerInfo) -> bool {
+        let mut bans = self.ban_list.write().await;
+        bans.retain(|_, ban| ban.is_active());
+        bans.values().any(|ban| ban.matches(peer_info))
+    }
+
+    /// Vérifie si une adresse appartient à un pair banni
+    async fn is_address_banned(&self, addr: &SocketAddr) -> bool {
+        let bans = self.ban_list.read().await;
+        bans.values().any(|ban| ban.is_active() && ban.addr == Some(*addr))
+    }
+
+    /// Récupère la liste des bannissements actifs
+    pub async fn get_banned_peers(&self) -> Vec<BanEntry> {
+        let bans = self.ban_list.read().await;
+        bans.values().filter(|ban| ban.is_active()).cloned().collect()
+    }
+
+    /// Récupère le score de mauvais comportement d'un pair
+    pub async fn get_misbehavior_score(&self, peer_id: &str) -> u32 {
+        self.get_misbehavior_score_at(peer_id, chrono::Utc::now()).await
+    }
+
+    /// Variante de `get_misbehavior_score` avec une horloge explicite
+    pub async fn get_misbehavior_score_at(&self, peer_id: &str, now: chrono::DateTime<chrono::Utc>) -> u32 {
+        let scores = self.misbehavior_scores.read().await;
+        scores.get(peer_id)
+            .map_or(0, |score| score.decayed(now, self.config.score_decay_per_hour))
+    }
+
     /// Récupère les statistiques P2P
     pub async fn get_stats(&self) -> P2PStats {
         let stats = self.stats.read().await;
@@ -317,7 +988,16 @@ impl P2PManager {
     }
 
     /// Diffuse un message à tous les pairs
+    ///
+    /// Les messages de gossip ne sont envoyés qu'à ⌈√n⌉ pairs : les relais
+    /// successifs se chargent d'atteindre le reste du réseau.
     pub async fn broadcast_message(&self, message: P2PMessage) -> ApiResult<usize> {
+        if let P2PMessage::Gossip { .. } = message {
+            let peers = self.connected_peer_ids().await;
+            let relay = self.gossip.publish_message(message, &peers).await?;
+            return Ok(self.send_relay(relay).await);
+        }
+
         let peers = self.peers.read().await;
         let mut sent_count = 0;
 
@@ -338,6 +1018,56 @@ impl P2PManager {
         Ok(sent_count)
     }
 
+    /// Publie des données sur un topic de gossip
+    ///
+    /// Retourne le nombre de pairs contactés directement.
+    pub async fn publish_gossip(&self, topic: &str, data: serde_json::Value) -> ApiResult<usize> {
+        let peers = self.connected_peer_ids().await;
+        let relay = self.gossip.publish(topic, data, &peers).await?;
+        Ok(self.send_relay(relay).await)
+    }
+
+    /// S'abonne aux messages de gossip d'un topic
+    pub fn subscribe_gossip(&self, topic: &str) -> tokio::sync::broadcast::Receiver<GossipDelivery> {
+        self.gossip.subscribe(topic)
+    }
+
+    /// Identifiants des pairs connectés
+    async fn connected_peer_ids(&self) -> Vec<String> {
+        let peers = self.peers.read().await;
+        peers.values()
+            .filter(|peer| peer.status == PeerStatus::Connected)
+            .map(|peer| peer.peer_id.clone())
+            .collect()
+    }
+
+    /// Envoie un relais de gossip à ses cibles ; retourne le nombre d'envois réussis
+    async fn send_relay(&self, relay: GossipRelay) -> usize {
+        let mut sent_count = 0;
+        for target in &relay.targets {
+            match self.client.send_message(target, relay.message.clone()).await {
+                Ok(_) => sent_count += 1,
+                Err(e) => tracing::debug!("Failed to relay gossip {} to {}: {}", relay.message_id(), target, e),
+            }
+        }
+
+        self.stats.write().await.messages_sent += sent_count as u64;
+        sent_count
+    }
+
+    /// Décode un message relayé et le remet à `relay_inbox`
+    ///
+    /// La provenance est le nœud de la connexion, pas l'expéditeur déclaré ; elle
+    /// est inconnue si le pair ne s'identifie pas par un `NodeId` (sans TLS).
+    fn receive_relayed(&self, peer_id: &str, data: &[u8]) -> P2PResult<bool> {
+        let message = NetworkMessage::deserialize_canonical(data).map_err(|_| P2PError::InvalidMessage)?;
+        let from = Hash::from_hex(peer_id).ok().map(NodeId);
+        match &self.relay_inbox {
+            Some(inbox) => Ok(inbox.send((from, message)).is_ok()),
+            None => Ok(false),
+        }
+    }
+
     /// Envoie un message à un pair spécifique
     pub async fn send_to_peer(&self, peer_id: &str, message: P2PMessage) -> ApiResult<()> {
         self.client.send_message(peer_id, message).await?;
@@ -351,6 +1081,126 @@ impl P2PManager {
         Ok(())
     }
 
+    /// Service de synchronisation, dont la progression est exposée par l'API de statut
+    pub fn sync_service(&self) -> Arc<SyncService> {
+        self.sync.clone()
+    }
+
+    /// Publie un snapshot d'état pris à `height` et l'annonce aux pairs
+    pub async fn announce_snapshot(&self, snapshot: &StateSnapshot, height: u64) -> ApiResult<SnapshotManifest> {
+        let manifest = self.sync.publish_snapshot(snapshot, height, DEFAULT_SNAPSHOT_CHUNK_SIZE).await;
+        self.broadcast_message(P2PMessage::SnapshotAnnouncement {
+            height,
+            manifest_hash: manifest.manifest_hash().to_hex(),
+            timestamp: chrono::Utc::now(),
+        }).await?;
+        Ok(manifest)
+    }
+
+    /// Annonce chaque snapshot que ce nœud a engagé dans un bloc de la chaîne principale
+    ///
+    /// Suit la chaîne du nœud (`ServerState::live_chain`) ; l'instantané de
+    /// l'API n'avance pas et n'engage donc aucun snapshot.
+    fn spawn_snapshot_announcer(&self) {
+        use tokio::sync::broadcast::error::RecvError;
+
+        let Some(chain) = self.server_state.live_chain.clone() else {
+            tracing::debug!("No live chain attached, state snapshots will not be announced");
+            return;
+        };
+        let manager = self.clone();
+        let shutdown = self.server_state.shutdown.clone();
+        tokio::spawn(async move {
+            let mut blocks = chain.read().await.subscribe_blocks();
+            loop {
+                let block = tokio::select! {
+                    _ = shutdown.triggered() => break,
+                    received = blocks.recv() => match received {
+                        Ok(block) => block,
+                        Err(RecvError::Lagged(_)) => continue,
+                        Err(RecvError::Closed) => break,
+                    },
+                };
+                let Some(snapshot) = chain.read().await.committed_snapshot(block.height).cloned() else {
+                    continue;
+                };
+                if let Err(e) = manager.announce_snapshot(&snapshot, block.height).await {
+                    tracing::warn!("Failed to announce state snapshot at height {}: {}", block.height, e);
+                }
+            }
+        });
+    }
+
+    /// Attend qu'un pair annonce le snapshot de `anchor`, puis restaure `state`
+    fn spawn_fast_sync(&self, anchor: BlockHeader, state: Arc<RwLock<dyn StateStorage>>) {
+        let manager = self.clone();
+        tokio::spawn(async move {
+            if manager.server_state.chain_height().await > anchor.height {
+                tracing::info!("Local chain already past snapshot height {}, skipping fast sync", anchor.height);
+                return;
+            }
+
+            for _ in 0..FAST_SYNC_ATTEMPTS {
+                tokio::time::sleep(std::time::Duration::from_secs(FAST_SYNC_RETRY_SECS)).await;
+                if manager.snapshot_sources(anchor.height).await.is_empty() {
+                    continue;
+                }
+
+                let mut state = state.write().await;
+                match manager.fast_sync(&anchor, &mut *state).await {
+                    Ok(outcome) => tracing::info!(
+                        "Fast sync finished in {:?} mode, replaying from height {}",
+                        outcome.mode, outcome.resume_height
+                    ),
+                    Err(e) => tracing::warn!("Fast sync failed: {}", e),
+                }
+                return;
+            }
+            tracing::warn!("No peer announced the snapshot at height {}, fast sync abandoned", anchor.height);
+        });
+    }
+
+    /// Pairs connectés annonçant un snapshot à `height`, les plus rapides en premier
+    async fn snapshot_sources(&self, height: u64) -> Vec<Arc<dyn SnapshotSource>> {
+        let mut peers: Vec<PeerInfo> = self.peers.read().await.values()
+            .filter(|peer| peer.status == PeerStatus::Connected && peer.snapshot_height == Some(height))
+            .cloned()
+            .collect();
+        peers.sort_by_key(|peer| peer.latency_ms);
+
+        peers.into_iter()
+            .map(|peer| self.sync.snapshot_source(peer.peer_id, self.client.clone()))
+            .collect()
+    }
+
+    /// Synchronise un nouveau nœud depuis le snapshot engagé dans `anchor`
+    ///
+    /// Les pairs ayant servi des données invalides sont pénalisés, puis le
+    /// rejeu démarre au bloc `anchor`, ou depuis la hauteur locale en cas
+    /// de repli sur la synchronisation complète.
+    pub async fn fast_sync<S: StateStorage + ?Sized>(&self, anchor: &BlockHeader, state: &mut S) -> ApiResult<FastSyncOutcome> {
+        let sources = self.snapshot_sources(anchor.height).await;
+        let outcome = self.sync.fast_sync(anchor, &sources, state).await?;
+
+        for peer_id in &outcome.rejected_peers {
+            self.report_misbehavior(peer_id, Misbehavior::FailedSyncResponse).await?;
+        }
+
+        let preferred = match &outcome.peer_id {
+            Some(peer_id) => self.peers.read().await.get(peer_id).cloned(),
+            None => None,
+        };
+        let replay_peer = match preferred {
+            Some(peer) => Some(peer),
+            None => self.get_best_sync_peer().await,
+        };
+        if let Some(peer) = replay_peer.filter(|peer| peer.block_height >= outcome.resume_height) {
+            self.sync.start_sync(peer.peer_id, outcome.resume_height, Some(peer.block_height + 1)).await?;
+        }
+
+        Ok(outcome)
+    }
+
     /// Vérifie si le réseau a suffisamment de pairs
     pub async fn has_sufficient_peers(&self) -> bool {
         let peers = self.peers.read().await;
@@ -368,6 +1218,64 @@ impl P2PManager {
     }
 }
 
+#[async_trait::async_trait]
+impl PrometheusExporter for P2PManager {
+    async fn render_prometheus(&self, labels: &[(&str, &str)]) -> String {
+        self.get_stats().await.to_prometheus(labels)
+    }
+}
+
+/// Les pairs P2P sont identifiés par l'hexadécimal de leur `NodeId`
+#[async_trait::async_trait]
+impl RelayTransport for P2PManager {
+    async fn send(&self, peer: &NodeId, message: &NetworkMessage) -> crate::error::Result<()> {
+        let data = message.serialize_canonical()?;
+        self.send_to_peer(&peer.hash().to_hex(), P2PMessage::Relay { data })
+            .await
+            .map_err(|e| crate::error::CoreError::Internal {
+                message: format!("Relais vers {} impossible: {}", peer.hash().to_hex(), e),
+            })
+    }
+}
+
+#[async_trait::async_trait]
+impl HealthProbe for P2PManager {
+    fn name(&self) -> &str {
+        "network"
+    }
+
+    /// En démarrage tant que les pairs sont insuffisants ou que la chaîne du nœud est en retard
+    async fn check(&self) -> HealthCheck {
+        let peer_count = self.peers.read().await.len();
+        if !self.has_sufficient_peers().await {
+            return HealthCheck::starting(format!("{}/{} peers", peer_count, self.config.min_peers));
+        }
+
+        let local_height = self.server_state.chain_height().await;
+        match self.get_best_sync_peer().await {
+            Some(best) if best.block_height > local_height + SYNC_TOLERANCE_BLOCKS => HealthCheck::starting(format!(
+                "syncing: height {} of {}",
+                local_height, best.block_height
+            )),
+            _ => HealthCheck::healthy(format!("{} peers, height {}", peer_count, local_height)),
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl ShutdownHook for P2PManager {
+    fn name(&self) -> &str {
+        "p2p"
+    }
+
+    async fn on_shutdown(&self, phase: ShutdownPhase) -> crate::Result<()> {
+        if phase == ShutdownPhase::Network {
+            self.shutdown_gracefully().await.map_err(|e| shutdown_error("p2p", e))?;
+        }
+        Ok(())
+    }
+}
+
 /// Erreurs P2P
 #[derive(Debug, thiserror::Error)]
 pub enum P2PError {
@@ -392,8 +1300,14 @@ pub enum P2PError {
     #[error("Peer banned: {0}")]
     PeerBanned(String),
     
+    #[error("Invalid block: {0}")]
+    InvalidBlock(String),
+    
     #[error("Invalid message format")]
     InvalidMessage,
+
+    #[error("Encryption required: {0}")]
+    EncryptionRequired(String),
     
     #[error("Service unavailable")]
     ServiceUnavailable,
@@ -424,9 +1338,53 @@ mod tests {
         assert_eq!(config.max_peers, 50);
         assert_eq!(config.min_peers, 3);
         assert!(config.enable_discovery);
+        assert!(!config.enable_local_discovery);
         assert!(config.enable_compression);
     }
 
+    #[test]
+    fn test_p2p_config_without_local_discovery_fields_loads() {
+        let mut value = serde_json::to_value(P2PConfig::default()).unwrap();
+        let fields = value.as_object_mut().unwrap();
+        for field in ["enable_local_discovery", "local_discovery_port", "force_local_discovery"] {
+            fields.remove(field);
+        }
+
+        let config: P2PConfig = serde_json::from_value(value).unwrap();
+        assert!(!config.enable_local_discovery);
+        assert_eq!(config.local_discovery_port, 7946);
+        assert!(!config.force_local_discovery);
+    }
+
+    #[test]
+    fn test_p2p_config_without_ban_fields_loads() {
+        let mut value = serde_json::to_value(P2PConfig::default()).unwrap();
+        let fields = value.as_object_mut().unwrap();
+        for field in ["ban_score_threshold", "ban_duration_secs", "score_decay_per_hour", "useful_data_reward"] {
+            fields.remove(field);
+        }
+
+        let config: P2PConfig = serde_json::from_value(value).unwrap();
+        assert_eq!(config.ban_score_threshold, 100);
+        assert_eq!(config.ban_duration_secs, 3600);
+        assert_eq!(config.score_decay_per_hour, 20);
+        assert_eq!(config.useful_data_reward, 5);
+    }
+
+    #[test]
+    fn test_p2p_config_without_gossip_fields_loads() {
+        let mut value = serde_json::to_value(P2PConfig::default()).unwrap();
+        let fields = value.as_object_mut().unwrap();
+        for field in ["gossip_ttl", "gossip_seen_cache_size", "gossip_seen_ttl_secs"] {
+            fields.remove(field);
+        }
+
+        let config: P2PConfig = serde_json::from_value(value).unwrap();
+        assert_eq!(config.gossip_ttl, 6);
+        assert_eq!(config.gossip_seen_cache_size, 10_000);
+        assert_eq!(config.gossip_seen_ttl_secs, 600);
+    }
+
     #[test]
     fn test_peer_info_creation() {
         let addr = SocketAddr::new(IpAddr::V4(Ipv4Addr::new(127, 0, 0, 1)), 8000);
@@ -442,6 +1400,7 @@ mod tests {
             status: PeerStatus::Connected,
             region: Some("us-east".to_string()),
             capabilities: HashSet::new(),
+            snapshot_height: None,
         };
 
         assert_eq!(peer_info.peer_id, "peer_123");
@@ -474,6 +1433,266 @@ mod tests {
         }
     }
 
+    fn create_test_state() -> ServerState {
+        let blockchain = Arc::new(
+            crate::Blockchain::new(crate::BlockchainConfig::default()).unwrap()
+        );
+        let auth_service = Arc::new(
+            crate::api::auth::AuthService::new(crate::api::auth::AuthConfig::default()).unwrap()
+        );
+        let user_manager = Arc::new(RwLock::new(crate::api::auth::UserManager::new()));
+
+        ServerState::new(blockchain, auth_service, user_manager, crate::api::ApiConfig::default())
+    }
+
+    fn create_test_peer(peer_id: &str, last_octet: u8) -> PeerInfo {
+        PeerInfo {
+            peer_id: peer_id.to_string(),
+            addr: SocketAddr::new(IpAddr::V4(Ipv4Addr::new(10, 0, 0, last_octet)), 8000),
+            protocol_version: "1.0".to_string(),
+            client_version: "archivechain-0.1.0".to_string(),
+            block_height: 0,
+            best_block_hash: "0x0".to_string(),
+            latency_ms: 10,
+            last_seen: chrono::Utc::now(),
+            status: PeerStatus::Connected,
+            region: None,
+            capabilities: HashSet::new(),
+            snapshot_height: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_announcer_follows_live_chain() {
+        let config = crate::BlockchainConfig { snapshot_interval: 2, ..crate::BlockchainConfig::default() };
+        let chain = Arc::new(RwLock::new(crate::Blockchain::new(config).unwrap()));
+        let state = create_test_state().with_live_chain(chain.clone());
+        let manager = P2PManager::new(P2PConfig::default(), state).await.unwrap();
+        manager.spawn_snapshot_announcer();
+        while chain.read().await.block_subscriber_count() == 0 {
+            tokio::task::yield_now().await;
+        }
+
+        for _ in 0..2 {
+            let mut blockchain = chain.write().await;
+            let block = blockchain.mine_block().unwrap();
+            blockchain.add_block(block).unwrap();
+        }
+
+        let published = tokio::time::timeout(std::time::Duration::from_secs(1), async {
+            loop {
+                if let Some(height) = manager.sync.published_snapshot_height().await {
+                    return height;
+                }
+                tokio::task::yield_now().await;
+            }
+        });
+        assert_eq!(published.await.unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_health_probe_reports_peers_and_sync() {
+        let config = P2PConfig {
+            min_peers: 2,
+            ..P2PConfig::default()
+        };
+        let chain = Arc::new(RwLock::new(crate::Blockchain::new(crate::BlockchainConfig::default()).unwrap()));
+        let state = create_test_state().with_live_chain(chain.clone());
+        let manager = P2PManager::new(config, state).await.unwrap();
+        manager.add_peer(create_test_peer("peer_1", 1)).await.unwrap();
+        assert_eq!(manager.check().await.status, crate::api::CheckStatus::Starting);
+
+        manager.add_peer(create_test_peer("peer_2", 2)).await.unwrap();
+        assert_eq!(manager.check().await.status, crate::api::CheckStatus::Healthy);
+
+        let mut ahead = create_test_peer("peer_3", 3);
+        ahead.block_height = 5;
+        manager.add_peer(ahead).await.unwrap();
+        let check = manager.check().await;
+        assert_eq!(check.status, crate::api::CheckStatus::Starting);
+        assert_eq!(check.message.as_deref(), Some("syncing: height 1 of 5"));
+
+        // La chaîne du nœud rattrape le pair : le nœud redevient prêt
+        for _ in 0..2 {
+            let mut blockchain = chain.write().await;
+            let block = blockchain.mine_block().unwrap();
+            blockchain.add_block(block).unwrap();
+        }
+        let check = manager.check().await;
+        assert_eq!(check.status, crate::api::CheckStatus::Healthy);
+        assert_eq!(check.message.as_deref(), Some("3 peers, height 3"));
+    }
+
+    #[test]
+    fn test_misbehavior_penalties() {
+        assert!(Misbehavior::OversizedMessage.penalty() > Misbehavior::InvalidMessage.penalty());
+        assert!(Misbehavior::InvalidMessage.penalty() > Misbehavior::FailedSyncResponse.penalty());
+    }
+
+    #[tokio::test]
+    async fn test_peer_banned_after_threshold() {
+        let config = P2PConfig {
+            ban_score_threshold: 50,
+            ..P2PConfig::default()
+        };
+        let manager = P2PManager::new(config, create_test_state()).await.unwrap();
+        manager.add_peer(create_test_peer("peer_bad", 1)).await.unwrap();
+
+        assert!(!manager.report_misbehavior("peer_bad", Misbehavior::InvalidMessage).await.unwrap());
+        assert!(!manager.report_misbehavior("peer_bad", Misbehavior::FailedSyncResponse).await.unwrap());
+        assert_eq!(manager.get_misbehavior_score("peer_bad").await, 30);
+
+        assert!(manager.check_message_size("peer_bad", 2 * 1024 * 1024).await.is_err());
+
+        assert!(manager.get_peers().await.is_empty());
+        let bans = manager.get_banned_peers().await;
+        assert_eq!(bans.len(), 1);
+        assert_eq!(bans[0].reason, Misbehavior::OversizedMessage);
+        assert_eq!(manager.get_stats().await.banned_peers, 1);
+
+        // Reconnexion refusée, y compris sous un nouvel ID depuis la même adresse
+        assert!(manager.add_peer(create_test_peer("peer_bad", 1)).await.is_err());
+        assert!(manager.add_peer(create_test_peer("peer_bad_again", 1)).await.is_err());
+        assert!(manager.add_peer(create_test_peer("peer_good", 2)).await.is_ok());
+
+        // Un autre pair derrière la même IP (NAT) n'est pas concerné
+        let mut neighbour = create_test_peer("peer_same_nat", 1);
+        neighbour.addr.set_port(8001);
+        assert!(manager.add_peer(neighbour.clone()).await.is_ok());
+        assert!(!manager.is_address_banned(&neighbour.addr).await);
+        assert!(manager.is_address_banned(&create_test_peer("peer_bad", 1).addr).await);
+    }
+
+    #[tokio::test]
+    async fn test_ban_lapses_after_expiry() {
+        let config = P2PConfig {
+            ban_score_threshold: 10,
+            ..P2PConfig::default()
+        };
+        let manager = P2PManager::new(config, create_test_state()).await.unwrap();
+        manager.add_peer(create_test_peer("peer_bad", 1)).await.unwrap();
+        assert!(manager.report_misbehavior("peer_bad", Misbehavior::InvalidMessage).await.unwrap());
+        assert!(manager.add_peer(create_test_peer("peer_bad", 1)).await.is_err());
+
+        // Simule l'écoulement de la durée du bannissement
+        {
+            let mut bans = manager.ban_list.write().await;
+            let ban = bans.get_mut("peer_bad").unwrap();
+            ban.expires_at = chrono::Utc::now() - chrono::Duration::seconds(1);
+        }
+
+        assert!(manager.get_banned_peers().await.is_empty());
+        assert!(manager.add_peer(create_test_peer("peer_bad", 1)).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_misbehavior_score_decays_and_rewards() {
+        let config = P2PConfig {
+            ban_score_threshold: 100,
+            score_decay_per_hour: 20,
+            useful_data_reward: 5,
+            ..P2PConfig::default()
+        };
+        let manager = P2PManager::new(config, create_test_state()).await.unwrap();
+        let start = chrono::Utc::now();
+
+        assert!(!manager.report_misbehavior_at("peer_1", Misbehavior::OversizedMessage, start).await.unwrap());
+        assert!(!manager.report_misbehavior_at("peer_1", Misbehavior::InvalidMessage, start).await.unwrap());
+        assert_eq!(manager.get_misbehavior_score_at("peer_1", start).await, 70);
+
+        // Deux heures plus tard, 40 points sont oubliés : une nouvelle faute ne suffit plus à bannir
+        let later = start + chrono::Duration::hours(2);
+        assert_eq!(manager.get_misbehavior_score_at("peer_1", later).await, 30);
+        assert!(!manager.report_misbehavior_at("peer_1", Misbehavior::InvalidBlock, later).await.unwrap());
+        assert_eq!(manager.get_misbehavior_score_at("peer_1", later).await, 80);
+
+        manager.report_useful_data_at("peer_1", later).await;
+        assert_eq!(manager.get_misbehavior_score_at("peer_1", later).await, 75);
+
+        // Sans oubli, les mêmes fautes auraient banni le pair
+        assert!(manager.report_misbehavior_at("peer_1", Misbehavior::InvalidBlock, later).await.unwrap());
+        assert_eq!(manager.get_banned_peers().await[0].reason, Misbehavior::InvalidBlock);
+    }
+
+    #[tokio::test]
+    async fn test_invalid_sync_data_penalizes_peer() {
+        let manager = P2PManager::new(P2PConfig::default(), create_test_state()).await.unwrap();
+        manager.add_peer(create_test_peer("peer_sync", 1)).await.unwrap();
+
+        let incoming = IncomingMessage {
+            peer_id: "peer_sync".to_string(),
+            message: P2PMessage::SyncData {
+                blocks: vec![],
+                request_id: "req_1".to_string(),
+                is_last: true,
+            },
+            received_at: chrono::Utc::now(),
+        };
+        assert!(manager.handle_incoming_message(incoming).await.is_err());
+        assert_eq!(manager.get_misbehavior_score("peer_sync").await, Misbehavior::InvalidMessage.penalty());
+        assert_eq!(manager.get_stats().await.messages_received, 1);
+    }
+
+    #[tokio::test]
+    async fn test_snapshot_announcement_registers_peer_snapshot() {
+        let manager = P2PManager::new(P2PConfig::default(), create_test_state()).await.unwrap();
+        manager.add_peer(create_test_peer("peer_snapshot", 1)).await.unwrap();
+
+        let incoming = IncomingMessage {
+            peer_id: "peer_snapshot".to_string(),
+            message: P2PMessage::SnapshotAnnouncement {
+                height: 500,
+                manifest_hash: "a".repeat(64),
+                timestamp: chrono::Utc::now(),
+            },
+            received_at: chrono::Utc::now(),
+        };
+        manager.handle_incoming_message(incoming).await.unwrap();
+
+        assert_eq!(manager.get_peers().await[0].snapshot_height, Some(500));
+        assert_eq!(manager.snapshot_sources(500).await.len(), 1);
+        assert!(manager.snapshot_sources(400).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_relayed_message_reaches_relay_inbox() {
+        let (inbox, mut received) = tokio::sync::mpsc::unbounded_channel();
+        let manager = P2PManager::new(P2PConfig::default(), create_test_state()).await.unwrap()
+            .with_relay_inbox(inbox);
+        let via = NodeId(Hash::new([9; 32]));
+
+        let message = NetworkMessage {
+            message_id: Hash::new([1; 32]),
+            sender: NodeId(Hash::new([2; 32])),
+            recipient: Some(NodeId(Hash::new([3; 32]))),
+            message_type: crate::nodes::MessageType::ContentRetrieve,
+            payload: vec![4, 5],
+            timestamp: chrono::Utc::now(),
+            ttl: 8,
+            request_id: None,
+        };
+        let incoming = IncomingMessage {
+            peer_id: via.hash().to_hex(),
+            message: P2PMessage::Relay { data: message.serialize_canonical().unwrap() },
+            received_at: chrono::Utc::now(),
+        };
+        manager.handle_incoming_message(incoming).await.unwrap();
+
+        let (from, relayed) = received.try_recv().unwrap();
+        assert_eq!(from, Some(via));
+        assert_eq!(relayed.message_id, message.message_id);
+        assert_eq!(relayed.payload, message.payload);
+
+        // Un message relayé illisible est une faute du pair
+        let garbage = IncomingMessage {
+            peer_id: "peer_relay".to_string(),
+            message: P2PMessage::Relay { data: vec![0xff; 4] },
+            received_at: chrono::Utc::now(),
+        };
+        assert!(manager.handle_incoming_message(garbage).await.is_err());
+        assert_eq!(manager.get_misbehavior_score("peer_relay").await, Misbehavior::InvalidMessage.penalty());
+    }
+
     #[test]
     fn test_peer_capabilities() {
         let mut capabilities = HashSet::new();
@@ -1,14 +1,23 @@
 //! Service de synchronisation P2P pour ArchiveChain
 //!
 //! Implémente la synchronisation de la blockchain entre pairs.
+//!
+//! Un nouveau nœud peut éviter de rejouer toute la chaîne : il télécharge le
+//! snapshot d'état d'un pair par chunks (voir [`SyncService::fast_sync`]), le
+//! restaure, puis ne rejoue que les blocs postérieurs au snapshot.
 
-use std::collections::{HashMap, VecDeque};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::Arc;
 use tokio::sync::{RwLock, oneshot, mpsc};
 use tokio::time::{Duration, interval, timeout};
 
 use crate::Blockchain;
-use super::{P2PConfig, P2PError, P2PResult, messages::*};
+use crate::block::BlockHeader;
+use crate::state::{SnapshotManifest, StateRoot, StateSnapshot, StateStorage};
+use super::{P2PClient, P2PConfig, P2PError, P2PResult, messages::*};
+
+/// Requêtes de snapshot en attente de réponse, par ID de requête (pair interrogé, canal de réponse)
+type PendingSnapshotRequests = Arc<RwLock<HashMap<String, (String, oneshot::Sender<P2PMessage>)>>>;
 
 /// Service de synchronisation
 #[derive(Debug)]
@@ -25,6 +34,12 @@ pub struct SyncService {
     shutdown_tx: Arc<RwLock<Option<oneshot::Sender<()>>>>,
     /// Statistiques de synchronisation
     sync_stats: Arc<RwLock<SyncStats>>,
+    /// Progression de la synchronisation rapide en cours
+    progress: Arc<RwLock<SyncProgress>>,
+    /// Snapshot servi aux pairs qui se synchronisent
+    published_snapshot: Arc<RwLock<Option<PublishedSnapshot>>>,
+    /// Requêtes de snapshot émises vers les pairs
+    pending_snapshot_requests: PendingSnapshotRequests,
 }
 
 /// Session de synchronisation active
@@ -75,6 +90,172 @@ pub struct SyncStats {
     pub pending_blocks: usize,
 }
 
+/// Phase de synchronisation du nœud
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SyncPhase {
+    /// Aucune synchronisation rapide en cours
+    #[default]
+    Idle,
+    /// Téléchargement du snapshot d'état
+    Snapshot,
+    /// Snapshot restauré, rejeu des blocs suivants
+    Replaying,
+    /// Repli sur le rejeu complet de la chaîne
+    FullSync,
+}
+
+/// Progression du téléchargement du snapshot, exposée par l'API de statut du nœud
+#[derive(Debug, Default, Clone, serde::Serialize, serde::Deserialize)]
+pub struct SyncProgress {
+    pub phase: SyncPhase,
+    /// Hauteur du snapshot téléchargé
+    pub snapshot_height: Option<u64>,
+    /// Pourcentage téléchargé (0-100)
+    pub percent: f64,
+    pub bytes_downloaded: u64,
+    pub total_bytes: u64,
+    /// Temps restant estimé d'après le débit moyen
+    pub eta_secs: Option<u64>,
+    pub started_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl SyncProgress {
+    /// Progression d'un téléchargement qui commence
+    fn downloading(manifest: &SnapshotManifest, now: chrono::DateTime<chrono::Utc>) -> Self {
+        Self {
+            phase: SyncPhase::Snapshot,
+            snapshot_height: Some(manifest.height),
+            total_bytes: manifest.total_size,
+            started_at: Some(now),
+            ..Default::default()
+        }
+    }
+
+    /// Comptabilise un chunk validé et réestime le temps restant
+    fn record(&mut self, bytes: u64, now: chrono::DateTime<chrono::Utc>) {
+        self.bytes_downloaded += bytes;
+        self.percent = if self.total_bytes == 0 {
+            100.0
+        } else {
+            (self.bytes_downloaded as f64 * 100.0 / self.total_bytes as f64).min(100.0)
+        };
+
+        let elapsed_ms = self.started_at.map_or(0, |started| (now - started).num_milliseconds().max(0)) as f64;
+        let remaining = self.total_bytes.saturating_sub(self.bytes_downloaded) as f64;
+        self.eta_secs = (self.bytes_downloaded > 0)
+            .then(|| (remaining * elapsed_ms / self.bytes_downloaded as f64 / 1000.0).ceil() as u64);
+    }
+}
+
+/// Snapshot publié par ce nœud, découpé en chunks
+#[derive(Debug, Clone)]
+struct PublishedSnapshot {
+    manifest: SnapshotManifest,
+    chunks: Vec<Vec<u8>>,
+}
+
+/// Mode de synchronisation retenu par [`SyncService::fast_sync`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SyncMode {
+    /// État restauré depuis un snapshot
+    Snapshot,
+    /// Rejeu complet de la chaîne
+    Full,
+}
+
+/// Résultat d'une synchronisation rapide
+#[derive(Debug, Clone)]
+pub struct FastSyncOutcome {
+    pub mode: SyncMode,
+    /// Première hauteur de bloc à rejouer
+    pub resume_height: u64,
+    /// Racine d'état restaurée et vérifiée
+    pub state_root: Option<StateRoot>,
+    /// Pair à privilégier pour le rejeu des blocs
+    pub peer_id: Option<String>,
+    /// Pairs ayant servi un manifeste ou un chunk invalide, à pénaliser
+    pub rejected_peers: Vec<String>,
+    /// Motif du repli sur la synchronisation complète
+    pub fallback_reason: Option<String>,
+}
+
+/// Pair capable de servir un snapshot d'état
+#[async_trait::async_trait]
+pub trait SnapshotSource: Send + Sync {
+    /// ID du pair
+    fn peer_id(&self) -> &str;
+
+    /// Manifeste du dernier snapshot publié par le pair
+    async fn fetch_manifest(&self) -> P2PResult<Option<SnapshotManifest>>;
+
+    /// Chunk `index` du snapshot pris à `height`
+    async fn fetch_chunk(&self, height: u64, index: usize) -> P2PResult<Vec<u8>>;
+}
+
+/// Source de snapshot interrogée par messages P2P
+///
+/// Les réponses sont remises par [`SyncService::handle_snapshot_response`].
+pub struct PeerSnapshotSource {
+    peer_id: String,
+    client: Arc<P2PClient>,
+    pending: PendingSnapshotRequests,
+    request_timeout: Duration,
+}
+
+impl PeerSnapshotSource {
+    /// Envoie une requête au pair et attend la réponse portant le même ID
+    async fn request(&self, build: impl FnOnce(String) -> P2PMessage) -> P2PResult<P2PMessage> {
+        let request_id = format!("snapshot_{}", uuid::Uuid::new_v4().simple());
+        let (response_tx, response_rx) = oneshot::channel();
+        self.pending.write().await.insert(request_id.clone(), (self.peer_id.clone(), response_tx));
+
+        if let Err(e) = self.client.send_message(&self.peer_id, build(request_id.clone())).await {
+            self.pending.write().await.remove(&request_id);
+            return Err(e);
+        }
+
+        match timeout(self.request_timeout, response_rx).await {
+            Ok(Ok(response)) => Ok(response),
+            _ => {
+                self.pending.write().await.remove(&request_id);
+                Err(P2PError::Timeout)
+            }
+        }
+    }
+}
+
+#[async_trait::async_trait]
+impl SnapshotSource for PeerSnapshotSource {
+    fn peer_id(&self) -> &str {
+        &self.peer_id
+    }
+
+    async fn fetch_manifest(&self) -> P2PResult<Option<SnapshotManifest>> {
+        match self.request(|request_id| P2PMessage::SnapshotManifestRequest { request_id }).await? {
+            P2PMessage::SnapshotManifestResponse { manifest, .. } => Ok(manifest),
+            _ => Err(P2PError::ProtocolError("Unexpected response to snapshot manifest request".to_string())),
+        }
+    }
+
+    async fn fetch_chunk(&self, height: u64, index: usize) -> P2PResult<Vec<u8>> {
+        let response = self.request(|request_id| P2PMessage::SnapshotChunkRequest {
+            height,
+            index: index as u32,
+            request_id,
+        }).await?;
+
+        match response {
+            P2PMessage::SnapshotChunkResponse { height: h, index: i, data: Some(data), .. }
+                if h == height && i as usize == index => Ok(data),
+            P2PMessage::SnapshotChunkResponse { data: None, .. } => {
+                Err(P2PError::ProtocolError(format!("Snapshot chunk {} unavailable", index)))
+            }
+            _ => Err(P2PError::ProtocolError("Unexpected response to snapshot chunk request".to_string())),
+        }
+    }
+}
+
 impl SyncService {
     /// Crée un nouveau service de synchronisation
     pub fn new(config: P2PConfig, blockchain: Arc<Blockchain>) -> Self {
@@ -85,6 +266,9 @@ impl SyncService {
             block_queue: Arc::new(RwLock::new(VecDeque::new())),
             shutdown_tx: Arc::new(RwLock::new(None)),
             sync_stats: Arc::new(RwLock::new(SyncStats::default())),
+            progress: Arc::new(RwLock::new(SyncProgress::default())),
+            published_snapshot: Arc::new(RwLock::new(None)),
+            pending_snapshot_requests: Arc::new(RwLock::new(HashMap::new())),
         }
     }
 
@@ -252,6 +436,9 @@ impl SyncService {
     }
 
     /// Traite des données de synchronisation reçues
+    ///
+    /// Des données non sollicitées ou un bloc invalide rejettent tout le lot,
+    /// pour que l'appelant puisse pénaliser le pair.
     pub async fn handle_sync_data(
         &self,
         peer_id: String,
@@ -266,33 +453,39 @@ impl SyncService {
                     .map(|(id, _)| id.clone())
             };
 
-            if let Some(session_id) = session_id {
-                // Met à jour la session
-                {
-                    let mut syncs = self.active_syncs.write().await;
-                    if let Some(session) = syncs.get_mut(&session_id) {
-                        session.blocks_received += blocks.len() as u64;
-                        session.last_activity = chrono::Utc::now();
-                        session.status = if is_last {
-                            SyncStatus::Processing
-                        } else {
-                            SyncStatus::Receiving
-                        };
-                    }
-                }
+            let Some(session_id) = session_id else {
+                return Err(P2PError::ProtocolError(format!("Unsolicited sync data from {}", peer_id)));
+            };
 
-                // Ajoute les blocs à la queue de traitement
-                {
-                    let mut queue = self.block_queue.write().await;
-                    for block in blocks {
-                        queue.push_back(block);
-                    }
+            for block in &blocks {
+                Self::validate_block_data(block)?;
+            }
+
+            // Met à jour la session
+            {
+                let mut syncs = self.active_syncs.write().await;
+                if let Some(session) = syncs.get_mut(&session_id) {
+                    session.blocks_received += blocks.len() as u64;
+                    session.last_activity = chrono::Utc::now();
+                    session.status = if is_last {
+                        SyncStatus::Processing
+                    } else {
+                        SyncStatus::Receiving
+                    };
                 }
+            }
 
-                if is_last {
-                    tracing::info!("Completed receiving blocks for sync session: {}", session_id);
+            // Ajoute les blocs à la queue de traitement
+            {
+                let mut queue = self.block_queue.write().await;
+                for block in blocks {
+                    queue.push_back(block);
                 }
             }
+
+            if is_last {
+                tracing::info!("Completed receiving blocks for sync session: {}", session_id);
+            }
         }
 
         Ok(())
@@ -351,15 +544,22 @@ impl SyncService {
         tracing::debug!("Processing synced block at height: {}", block.height);
         
         // Valide le bloc
+        Self::validate_block_data(&block)?;
+
+        // TODO: Ajouter à la blockchain réelle
+        Ok(())
+    }
+
+    /// Vérifications structurelles d'un bloc reçu
+    fn validate_block_data(block: &BlockData) -> P2PResult<()> {
         if block.hash.is_empty() {
-            return Err(P2PError::ProtocolError("Invalid block: empty hash".to_string()));
+            return Err(P2PError::InvalidBlock("empty hash".to_string()));
         }
-        
+
         if block.height == 0 {
-            return Err(P2PError::ProtocolError("Invalid block: zero height".to_string()));
+            return Err(P2PError::InvalidBlock("zero height".to_string()));
         }
 
-        // TODO: Ajouter à la blockchain réelle
         Ok(())
     }
 
@@ -456,6 +656,249 @@ impl SyncService {
         stats_copy
     }
 
+    /// Récupère la progression de la synchronisation rapide
+    pub async fn get_sync_progress(&self) -> SyncProgress {
+        self.progress.read().await.clone()
+    }
+
+    /// Vérifie si le nœud télécharge un snapshot ou rejoue des blocs
+    pub async fn is_syncing(&self) -> bool {
+        if self.progress.read().await.phase == SyncPhase::Snapshot {
+            return true;
+        }
+
+        let syncs = self.active_syncs.read().await;
+        syncs.values().any(|session| matches!(
+            session.status,
+            SyncStatus::Requesting | SyncStatus::Receiving | SyncStatus::Processing
+        ))
+    }
+
+    /// Publie un snapshot d'état pris à `height` pour les pairs qui se synchronisent
+    ///
+    /// Le hash du manifeste retourné doit être engagé dans l'en-tête du bloc
+    /// `height` pour que les pairs acceptent le snapshot.
+    pub async fn publish_snapshot(&self, snapshot: &StateSnapshot, height: u64, chunk_size: usize) -> SnapshotManifest {
+        let (manifest, chunks) = SnapshotManifest::split(snapshot, height, chunk_size);
+        tracing::info!("Publishing state snapshot at height {} ({} chunks, {} bytes)",
+            height, manifest.chunk_count(), manifest.total_size);
+
+        *self.published_snapshot.write().await = Some(PublishedSnapshot {
+            manifest: manifest.clone(),
+            chunks,
+        });
+        manifest
+    }
+
+    /// Hauteur du snapshot publié, annoncée aux pairs
+    pub async fn published_snapshot_height(&self) -> Option<u64> {
+        self.published_snapshot.read().await.as_ref().map(|published| published.manifest.height)
+    }
+
+    /// Source de snapshot interrogeant `peer_id` via le client P2P
+    pub fn snapshot_source(&self, peer_id: String, client: Arc<P2PClient>) -> Arc<dyn SnapshotSource> {
+        Arc::new(PeerSnapshotSource {
+            peer_id,
+            client,
+            pending: self.pending_snapshot_requests.clone(),
+            request_timeout: Duration::from_secs(self.config.request_timeout),
+        })
+    }
+
+    /// Sert une demande de manifeste ou de chunk depuis le snapshot publié
+    pub async fn handle_snapshot_request(&self, peer_id: String, request: P2PMessage) -> P2PResult<P2PMessage> {
+        let published = self.published_snapshot.read().await;
+
+        match request {
+            P2PMessage::SnapshotManifestRequest { request_id } => Ok(P2PMessage::SnapshotManifestResponse {
+                manifest: published.as_ref().map(|published| published.manifest.clone()),
+                request_id,
+            }),
+            P2PMessage::SnapshotChunkRequest { height, index, request_id } => {
+                let data = published.as_ref()
+                    .filter(|published| published.manifest.height == height)
+                    .and_then(|published| published.chunks.get(index as usize).cloned());
+                tracing::debug!("Serving snapshot chunk {} at height {} to {}", index, height, peer_id);
+
+                Ok(P2PMessage::SnapshotChunkResponse { height, index, data, request_id })
+            }
+            _ => Err(P2PError::ProtocolError("Invalid snapshot request message".to_string())),
+        }
+    }
+
+    /// Remet une réponse de snapshot à la requête qui l'attend
+    pub async fn handle_snapshot_response(&self, peer_id: String, response: P2PMessage) -> P2PResult<()> {
+        let request_id = response.request_id()
+            .ok_or_else(|| P2PError::ProtocolError("Snapshot response without request ID".to_string()))?
+            .to_string();
+
+        let mut pending = self.pending_snapshot_requests.write().await;
+        match pending.get(&request_id) {
+            Some((expected_peer, _)) if *expected_peer == peer_id => {
+                if let Some((_, response_tx)) = pending.remove(&request_id) {
+                    let _ = response_tx.send(response);
+                }
+                Ok(())
+            }
+            _ => Err(P2PError::ProtocolError(format!("Unsolicited snapshot response from {}", peer_id))),
+        }
+    }
+
+    /// Synchronise l'état depuis le snapshot engagé dans `anchor`
+    ///
+    /// `anchor` est l'en-tête, issu d'une chaîne d'en-têtes vérifiée, qui
+    /// engage le manifeste du snapshot et la racine de l'état sur lequel il
+    /// s'applique. Un pair servant un manifeste ou un chunk invalide est
+    /// écarté et le chunk redemandé au pair suivant ; si plus aucun pair ne
+    /// peut le fournir, ou si la racine d'état restaurée diffère de celle de
+    /// l'en-tête, le nœud se replie sur le rejeu complet de la chaîne. Le
+    /// rejeu reprend au bloc `anchor`, que le snapshot précède.
+    pub async fn fast_sync<S: StateStorage + ?Sized>(
+        &self,
+        anchor: &BlockHeader,
+        sources: &[Arc<dyn SnapshotSource>],
+        state: &mut S,
+    ) -> P2PResult<FastSyncOutcome> {
+        if sources.is_empty() {
+            return Err(P2PError::ProtocolError("No peer serves a state snapshot".to_string()));
+        }
+        let (Some(expected_manifest), Some(expected_root)) = (anchor.snapshot_manifest.clone(), anchor.state_root.clone()) else {
+            return Err(P2PError::ProtocolError(format!("Block {} commits no state snapshot", anchor.height)));
+        };
+
+        fn is_rejected(rejected_peers: &[String], peer_id: &str) -> bool {
+            rejected_peers.iter().any(|rejected| rejected == peer_id)
+        }
+        let mut rejected_peers = Vec::new();
+
+        // Manifeste : le premier pair dont le manifeste correspond à l'engagement
+        let mut manifest = None;
+        for source in sources {
+            match source.fetch_manifest().await {
+                Ok(Some(candidate)) if candidate.height == anchor.height
+                    && candidate.state_root == expected_root
+                    && candidate.manifest_hash() == expected_manifest => {
+                    manifest = Some(candidate);
+                    break;
+                }
+                Ok(Some(_)) => {
+                    tracing::warn!("Peer {} served a snapshot manifest not committed at height {}",
+                        source.peer_id(), anchor.height);
+                    if !is_rejected(&rejected_peers, source.peer_id()) {
+                        rejected_peers.push(source.peer_id().to_string());
+                    }
+                }
+                Ok(None) => {}
+                Err(e) => tracing::debug!("Snapshot manifest request to {} failed: {}", source.peer_id(), e),
+            }
+        }
+        let Some(manifest) = manifest else {
+            return Ok(self.fall_back_to_full_sync(sources, rejected_peers, "no peer serves the committed snapshot").await);
+        };
+
+        *self.progress.write().await = SyncProgress::downloading(&manifest, chrono::Utc::now());
+        tracing::info!("Fast sync from snapshot at height {} ({} chunks, {} bytes)",
+            manifest.height, manifest.chunk_count(), manifest.total_size);
+
+        // Chunks : répartis entre les pairs, redemandés ailleurs en cas d'échec
+        let mut unreachable = HashSet::new();
+        let mut chunks = Vec::with_capacity(manifest.chunk_count());
+        let mut last_peer = None;
+        for index in 0..manifest.chunk_count() {
+            let mut fetched = None;
+            for offset in 0..sources.len() {
+                let source = &sources[(index + offset) % sources.len()];
+                let peer_id = source.peer_id();
+                if is_rejected(&rejected_peers, peer_id) || unreachable.contains(peer_id) {
+                    continue;
+                }
+
+                match source.fetch_chunk(manifest.height, index).await {
+                    Ok(data) if manifest.verify_chunk(index, &data) => {
+                        fetched = Some(data);
+                        last_peer = Some(peer_id.to_string());
+                        break;
+                    }
+                    Ok(_) => {
+                        tracing::warn!("Peer {} served corrupted snapshot chunk {}, retrying elsewhere", peer_id, index);
+                        rejected_peers.push(peer_id.to_string());
+                    }
+                    Err(e) => {
+                        tracing::debug!("Snapshot chunk {} request to {} failed: {}", index, peer_id, e);
+                        unreachable.insert(peer_id.to_string());
+                    }
+                }
+            }
+
+            let Some(data) = fetched else {
+                let reason = format!("no peer served a valid snapshot chunk {}", index);
+                return Ok(self.fall_back_to_full_sync(sources, rejected_peers, &reason).await);
+            };
+            self.progress.write().await.record(data.len() as u64, chrono::Utc::now());
+            chunks.push(data);
+        }
+
+        // Restauration puis vérification de la racine d'état engagée dans l'en-tête
+        let restored_root = match manifest.assemble(chunks) {
+            Ok(snapshot) => match state.restore_snapshot(snapshot).await {
+                Ok(()) => state.calculate_state_root().await.ok(),
+                Err(e) => {
+                    tracing::warn!("Failed to restore state snapshot: {}", e);
+                    None
+                }
+            },
+            Err(e) => {
+                tracing::warn!("Failed to assemble state snapshot: {}", e);
+                None
+            }
+        };
+        if restored_root.as_ref() != Some(&expected_root) {
+            let _ = state.clear().await;
+            return Ok(self.fall_back_to_full_sync(sources, rejected_peers, "restored state root mismatch").await);
+        }
+
+        self.progress.write().await.phase = SyncPhase::Replaying;
+        tracing::info!("State restored from snapshot at height {}, replaying following blocks", manifest.height);
+
+        Ok(FastSyncOutcome {
+            mode: SyncMode::Snapshot,
+            resume_height: manifest.height,
+            state_root: restored_root,
+            peer_id: last_peer,
+            rejected_peers,
+            fallback_reason: None,
+        })
+    }
+
+    /// Abandonne le snapshot : la chaîne sera rejouée depuis la hauteur locale
+    async fn fall_back_to_full_sync(
+        &self,
+        sources: &[Arc<dyn SnapshotSource>],
+        rejected_peers: Vec<String>,
+        reason: &str,
+    ) -> FastSyncOutcome {
+        tracing::warn!("Fast sync failed ({}), falling back to full sync", reason);
+        {
+            let mut progress = self.progress.write().await;
+            progress.phase = SyncPhase::FullSync;
+            progress.eta_secs = None;
+        }
+
+        let peer_id = sources.iter()
+            .map(|source| source.peer_id())
+            .find(|peer_id| !rejected_peers.iter().any(|rejected| rejected == peer_id))
+            .map(str::to_string);
+
+        FastSyncOutcome {
+            mode: SyncMode::Full,
+            resume_height: self.blockchain.height() + 1,
+            state_root: None,
+            peer_id,
+            rejected_peers,
+            fallback_reason: Some(reason.to_string()),
+        }
+    }
+
     /// Force la synchronisation avec un pair spécifique
     pub async fn force_sync_with_peer(&self, peer_id: String) -> P2PResult<String> {
         let current_height = self.blockchain.get_stats()
@@ -621,6 +1064,34 @@ mod tests {
         assert_eq!(stats.pending_blocks, 1);
     }
 
+    #[tokio::test]
+    async fn test_sync_data_rejects_unsolicited_and_invalid_blocks() {
+        let blockchain = Arc::new(Blockchain::new(BlockchainConfig::default()).unwrap());
+        let service = SyncService::new(P2PConfig::default(), blockchain);
+        let sync_data = |height: u64| P2PMessage::SyncData {
+            blocks: vec![BlockData {
+                height,
+                hash: "a".repeat(64),
+                previous_hash: "b".repeat(64),
+                timestamp: chrono::Utc::now(),
+                transactions: vec![],
+                merkle_root: "c".repeat(64),
+                validator: "validator_1".to_string(),
+                signature: "sig".to_string(),
+            }],
+            request_id: "req_1".to_string(),
+            is_last: true,
+        };
+
+        let result = service.handle_sync_data("peer_123".to_string(), sync_data(101)).await;
+        assert!(matches!(result, Err(P2PError::ProtocolError(_))));
+
+        service.start_sync("peer_123".to_string(), 100, Some(200)).await.unwrap();
+        let result = service.handle_sync_data("peer_123".to_string(), sync_data(0)).await;
+        assert!(matches!(result, Err(P2PError::InvalidBlock(_))));
+        assert_eq!(service.get_sync_stats().await.pending_blocks, 0);
+    }
+
     #[tokio::test]
     async fn test_process_block() {
         let blockchain = Arc::new(Blockchain::new(BlockchainConfig::default()).unwrap());
@@ -658,4 +1129,164 @@ mod tests {
         let result = SyncService::process_block(&blockchain, invalid_block).await;
         assert!(result.is_err());
     }
-}
\ No newline at end of file
+
+    /// Pair servant un snapshot, éventuellement avec des chunks corrompus
+    struct MockSnapshotSource {
+        peer_id: String,
+        manifest: SnapshotManifest,
+        chunks: Vec<Vec<u8>>,
+        corrupted: Vec<usize>,
+        served: std::sync::Mutex<Vec<usize>>,
+    }
+
+    impl MockSnapshotSource {
+        fn new(peer_id: &str, manifest: &SnapshotManifest, chunks: &[Vec<u8>], corrupted: Vec<usize>) -> Arc<Self> {
+            Arc::new(Self {
+                peer_id: peer_id.to_string(),
+                manifest: manifest.clone(),
+                chunks: chunks.to_vec(),
+                corrupted,
+                served: std::sync::Mutex::new(Vec::new()),
+            })
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl SnapshotSource for MockSnapshotSource {
+        fn peer_id(&self) -> &str {
+            &self.peer_id
+        }
+
+        async fn fetch_manifest(&self) -> P2PResult<Option<SnapshotManifest>> {
+            Ok(Some(self.manifest.clone()))
+        }
+
+        async fn fetch_chunk(&self, _height: u64, index: usize) -> P2PResult<Vec<u8>> {
+            self.served.lock().unwrap().push(index);
+            let mut data = self.chunks[index].clone();
+            if self.corrupted.contains(&index) {
+                data[0] ^= 0xFF;
+            }
+            Ok(data)
+        }
+    }
+
+    /// État source de 1000 clés, découpé en 10 chunks et engagé à la hauteur 500
+    async fn published_state() -> (crate::state::MemoryStateStorage, SnapshotManifest, Vec<Vec<u8>>, BlockHeader) {
+        use crate::crypto::{compute_hash, Hash, HashAlgorithm};
+
+        let mut source = crate::state::MemoryStateStorage::new();
+        for i in 0..1000u32 {
+            let key = compute_hash(&i.to_le_bytes(), HashAlgorithm::Blake3);
+            source.set(key, format!("value-{}", i).into_bytes()).await.unwrap();
+        }
+
+        let snapshot = source.create_snapshot().await.unwrap();
+        let chunk_size = (snapshot.data.len() + 9) / 10;
+        let (manifest, chunks) = SnapshotManifest::split(&snapshot, 500, chunk_size);
+        assert_eq!(manifest.chunk_count(), 10);
+
+        let anchor = crate::block::BlockHeaderBuilder::new(500, Hash::zero())
+            .state_root(manifest.state_root.clone())
+            .snapshot_manifest(manifest.manifest_hash())
+            .build()
+            .unwrap();
+        (source, manifest, chunks, anchor)
+    }
+
+    #[tokio::test]
+    async fn test_fast_sync_refetches_corrupted_chunk_from_another_peer() {
+        let blockchain = Arc::new(Blockchain::new(BlockchainConfig::default()).unwrap());
+        let service = SyncService::new(P2PConfig::default(), blockchain);
+        let (source, manifest, chunks, anchor) = published_state().await;
+
+        // Le chunk 4 revient d'abord au pair A, qui le sert corrompu
+        let peer_a = MockSnapshotSource::new("peer_a", &manifest, &chunks, vec![4]);
+        let peer_b = MockSnapshotSource::new("peer_b", &manifest, &chunks, vec![]);
+        let sources: Vec<Arc<dyn SnapshotSource>> = vec![peer_a.clone(), peer_b.clone()];
+
+        let mut target = crate::state::MemoryStateStorage::new();
+        let outcome = service.fast_sync(&anchor, &sources, &mut target).await.unwrap();
+
+        assert_eq!(outcome.mode, SyncMode::Snapshot);
+        assert_eq!(outcome.resume_height, 500);
+        assert_eq!(outcome.rejected_peers, vec!["peer_a".to_string()]);
+        assert!(peer_a.served.lock().unwrap().contains(&4));
+        assert!(peer_b.served.lock().unwrap().contains(&4));
+
+        let source_root = source.calculate_state_root().await.unwrap();
+        assert_eq!(target.len(), 1000);
+        assert_eq!(target.calculate_state_root().await.unwrap(), source_root);
+        assert_eq!(outcome.state_root, Some(source_root));
+
+        let progress = service.get_sync_progress().await;
+        assert_eq!(progress.phase, SyncPhase::Replaying);
+        assert_eq!(progress.snapshot_height, Some(500));
+        assert_eq!(progress.bytes_downloaded, manifest.total_size);
+        assert_eq!(progress.percent, 100.0);
+        assert_eq!(progress.eta_secs, Some(0));
+    }
+
+    #[tokio::test]
+    async fn test_fast_sync_falls_back_to_full_sync() {
+        let blockchain = Arc::new(Blockchain::new(BlockchainConfig::default()).unwrap());
+        let service = SyncService::new(P2PConfig::default(), blockchain.clone());
+        let (_, manifest, chunks, anchor) = published_state().await;
+
+        // Aucun pair ne sert le chunk 7 intact
+        let sources: Vec<Arc<dyn SnapshotSource>> = vec![
+            MockSnapshotSource::new("peer_a", &manifest, &chunks, vec![7]),
+            MockSnapshotSource::new("peer_b", &manifest, &chunks, vec![7]),
+        ];
+
+        let mut target = crate::state::MemoryStateStorage::new();
+        let outcome = service.fast_sync(&anchor, &sources, &mut target).await.unwrap();
+
+        assert_eq!(outcome.mode, SyncMode::Full);
+        assert_eq!(outcome.resume_height, blockchain.height() + 1);
+        assert_eq!(outcome.rejected_peers.len(), 2);
+        assert!(target.is_empty());
+        assert_eq!(service.get_sync_progress().await.phase, SyncPhase::FullSync);
+
+        // Un manifeste non engagé dans l'en-tête est refusé d'emblée
+        let mut forged = manifest.clone();
+        forged.total_size += 1;
+        let sources: Vec<Arc<dyn SnapshotSource>> = vec![MockSnapshotSource::new("peer_c", &forged, &chunks, vec![])];
+        let outcome = service.fast_sync(&anchor, &sources, &mut target).await.unwrap();
+        assert_eq!(outcome.mode, SyncMode::Full);
+        assert_eq!(outcome.rejected_peers, vec!["peer_c".to_string()]);
+
+        // Un manifeste engagé ne suffit pas si l'en-tête engage une autre racine d'état
+        let mut other_root = anchor.clone();
+        other_root.state_root = Some(crate::crypto::Hash::zero());
+        let sources: Vec<Arc<dyn SnapshotSource>> = vec![MockSnapshotSource::new("peer_d", &manifest, &chunks, vec![])];
+        let outcome = service.fast_sync(&other_root, &sources, &mut target).await.unwrap();
+        assert_eq!(outcome.mode, SyncMode::Full);
+        assert_eq!(outcome.rejected_peers, vec!["peer_d".to_string()]);
+        assert!(target.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_serves_published_snapshot_chunks() {
+        let blockchain = Arc::new(Blockchain::new(BlockchainConfig::default()).unwrap());
+        let service = SyncService::new(P2PConfig::default(), blockchain);
+        let (source, _, _, _) = published_state().await;
+
+        let snapshot = source.create_snapshot().await.unwrap();
+        let manifest = service.publish_snapshot(&snapshot, 500, 4096).await;
+        assert_eq!(service.published_snapshot_height().await, Some(500));
+
+        let request = P2PMessage::SnapshotChunkRequest { height: 500, index: 1, request_id: "req_1".to_string() };
+        match service.handle_snapshot_request("peer_123".to_string(), request).await.unwrap() {
+            P2PMessage::SnapshotChunkResponse { data: Some(data), request_id, .. } => {
+                assert_eq!(request_id, "req_1");
+                assert!(manifest.verify_chunk(1, &data));
+            }
+            other => panic!("Unexpected response: {:?}", other),
+        }
+
+        // Réponse non sollicitée
+        let response = P2PMessage::SnapshotManifestResponse { manifest: None, request_id: "req_2".to_string() };
+        assert!(service.handle_snapshot_response("peer_123".to_string(), response).await.is_err());
+    }
+}
@@ -0,0 +1,488 @@
+//! Transport des connexions P2P, en clair ou chiffré par TLS
+//!
+//! Avec la feature `tls`, un nœud configuré avec un certificat et sa clé
+//! chiffre ses connexions en TLS 1.3 mutuel. Le certificat porte une clé
+//! Ed25519 : l'identifiant du nœud est dérivé de cette clé
+//! (`NodeId::from_public_key`), si bien qu'un pair ne peut annoncer au
+//! handshake qu'un identifiant dont il détient la clé privée. Les
+//! certificats sont auto-signés, aucune autorité n'intervient : c'est
+//! l'identifiant qui est épinglé, pas la chaîne de certification.
+//!
+//! Les connexions entrantes sont triées sur leurs premiers octets : un
+//! `ClientHello` TLS commence par `16 03 xx`, ce qu'une trame en clair ne
+//! peut pas reproduire en deçà de 64 Kio (voir [`is_tls_client_hello`]).
+//! Un nœud TLS refuse par défaut les pairs en clair, dans les deux sens :
+//! `allow_plaintext_fallback` l'autorise à cohabiter avec des pairs sans
+//! certificat le temps d'une migration. Un nœud sans identité TLS ne
+//! communique qu'en clair.
+
+use std::net::SocketAddr;
+use tokio::io::{AsyncRead, AsyncWrite};
+use tokio::net::TcpStream;
+use tokio::time::{timeout, Duration};
+
+use super::{P2PConfig, P2PError, P2PResult};
+
+/// Flux bidirectionnel vers un pair, chiffré ou non
+pub trait PeerStream: AsyncRead + AsyncWrite + Unpin + Send {}
+
+impl<T: AsyncRead + AsyncWrite + Unpin + Send> PeerStream for T {}
+
+/// Connexion établie avec un pair, avant le handshake applicatif
+pub struct SecuredStream {
+    /// Flux de la connexion
+    pub stream: Box<dyn PeerStream>,
+    /// Identifiant prouvé par le certificat TLS du pair (`None` en clair)
+    pub peer_node_id: Option<String>,
+}
+
+impl SecuredStream {
+    fn plaintext(stream: TcpStream) -> Self {
+        Self { stream: Box::new(stream), peer_node_id: None }
+    }
+
+    /// Indique si la connexion est chiffrée
+    pub fn is_encrypted(&self) -> bool {
+        self.peer_node_id.is_some()
+    }
+}
+
+impl std::fmt::Debug for SecuredStream {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SecuredStream")
+            .field("peer_node_id", &self.peer_node_id)
+            .finish()
+    }
+}
+
+/// Reconnaît l'en-tête d'enregistrement d'un `ClientHello` TLS
+///
+/// Une trame en clair commence par sa taille en u32 little-endian : pour
+/// qu'elle débute par `16 03 xx`, il faudrait une trame d'au moins
+/// `0x010316` octets, bien au-delà de tout handshake.
+pub fn is_tls_client_hello(prefix: &[u8]) -> bool {
+    matches!(prefix, [0x16, 0x03, minor, ..] if *minor <= 0x04)
+}
+
+/// Établit les connexions P2P selon la configuration de sécurité
+#[derive(Debug)]
+pub struct Transport {
+    /// Identité TLS du nœud, si configurée
+    #[cfg(feature = "tls")]
+    tls: Option<tls::TlsTransport>,
+    /// Refuse les pairs en clair
+    require_encryption: bool,
+    /// Accepte les pairs en clair malgré une identité TLS
+    allow_plaintext_fallback: bool,
+}
+
+impl Transport {
+    /// Construit le transport depuis la configuration P2P
+    ///
+    /// `require_encryption` exige une identité TLS : sans certificat (ou sans
+    /// la feature `tls`), le nœud ne pourrait joindre aucun pair.
+    pub fn from_config(config: &P2PConfig) -> P2PResult<Self> {
+        #[cfg(feature = "tls")]
+        let tls = match (&config.tls_cert_path, &config.tls_key_path) {
+            (Some(cert_path), Some(key_path)) => Some(tls::TlsTransport::from_pem_files(cert_path, key_path)?),
+            _ => None,
+        };
+
+        let transport = Self {
+            #[cfg(feature = "tls")]
+            tls,
+            require_encryption: config.require_encryption,
+            allow_plaintext_fallback: config.allow_plaintext_fallback && !config.require_encryption,
+        };
+
+        if transport.require_encryption && !transport.supports_tls() {
+            return Err(P2PError::EncryptionRequired(
+                "require_encryption needs a TLS certificate and key (feature `tls`)".to_string(),
+            ));
+        }
+        Ok(transport)
+    }
+
+    /// Indique si les pairs en clair sont acceptés
+    ///
+    /// Un nœud sans identité TLS n'a pas d'autre choix ; un nœud TLS ne
+    /// les accepte que sur `allow_plaintext_fallback`.
+    pub fn accepts_plaintext(&self) -> bool {
+        if self.require_encryption {
+            return false;
+        }
+        !self.supports_tls() || self.allow_plaintext_fallback
+    }
+
+    /// Indique si le nœud sait chiffrer ses connexions
+    pub fn supports_tls(&self) -> bool {
+        #[cfg(feature = "tls")]
+        {
+            self.tls.is_some()
+        }
+        #[cfg(not(feature = "tls"))]
+        {
+            false
+        }
+    }
+
+    /// Identifiant du nœud dérivé de son certificat TLS
+    pub fn local_node_id(&self) -> Option<String> {
+        #[cfg(feature = "tls")]
+        {
+            self.tls.as_ref().map(|tls| tls.node_id().to_string())
+        }
+        #[cfg(not(feature = "tls"))]
+        {
+            None
+        }
+    }
+
+    /// Ouvre une connexion vers un pair
+    ///
+    /// Un nœud TLS tente une connexion chiffrée et ne se replie sur une
+    /// connexion en clair que si `allow_plaintext_fallback` est activé.
+    pub async fn connect(&self, addr: SocketAddr, connect_timeout: Duration) -> P2PResult<SecuredStream> {
+        #[cfg(feature = "tls")]
+        if let Some(tls) = &self.tls {
+            let attempt = async {
+                let stream = Self::open(addr, connect_timeout).await?;
+                timeout(connect_timeout, tls.connect(stream)).await.map_err(|_| P2PError::Timeout)?
+            };
+            match attempt.await {
+                Ok(secured) => return Ok(secured),
+                Err(e) if !self.accepts_plaintext() => {
+                    return Err(P2PError::EncryptionRequired(format!("TLS connection to {} failed: {}", addr, e)));
+                }
+                Err(e) => tracing::debug!("TLS connection to {} failed, retrying in plaintext: {}", addr, e),
+            }
+        }
+
+        Ok(SecuredStream::plaintext(Self::open(addr, connect_timeout).await?))
+    }
+
+    /// Accepte une connexion entrante, chiffrée ou non
+    pub async fn accept(&self, stream: TcpStream) -> P2PResult<SecuredStream> {
+        let mut prefix = [0u8; 3];
+        let peeked = Self::peek_prefix(&stream, &mut prefix).await?;
+
+        if !is_tls_client_hello(&prefix[..peeked]) {
+            if !self.accepts_plaintext() {
+                return Err(P2PError::EncryptionRequired("plaintext peer refused".to_string()));
+            }
+            return Ok(SecuredStream::plaintext(stream));
+        }
+
+        #[cfg(feature = "tls")]
+        if let Some(tls) = &self.tls {
+            return tls.accept(stream).await;
+        }
+        Err(P2PError::ProtocolError("TLS is not configured on this node".to_string()))
+    }
+
+    async fn open(addr: SocketAddr, connect_timeout: Duration) -> P2PResult<TcpStream> {
+        timeout(connect_timeout, TcpStream::connect(addr))
+            .await
+            .map_err(|_| P2PError::Timeout)?
+            .map_err(|e| P2PError::ConnectionFailed(format!("Failed to connect to {}: {}", addr, e)))
+    }
+
+    /// Lit sans les consommer les premiers octets de la connexion
+    async fn peek_prefix(stream: &TcpStream, prefix: &mut [u8]) -> P2PResult<usize> {
+        loop {
+            let peeked = stream.peek(prefix).await.map_err(|e| P2PError::NetworkError(e.to_string()))?;
+            // `peek` peut rendre moins d'octets que disponibles à terme
+            if peeked == 0 || peeked == prefix.len() {
+                return Ok(peeked);
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    }
+}
+
+#[cfg(feature = "tls")]
+mod tls {
+    use std::sync::Arc;
+    use rustls::client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier};
+    use rustls::crypto::{verify_tls12_signature, verify_tls13_signature, CryptoProvider, WebPkiSupportedAlgorithms};
+    use rustls::pki_types::{CertificateDer, PrivateKeyDer, ServerName, UnixTime};
+    use rustls::server::danger::{ClientCertVerified, ClientCertVerifier};
+    use rustls::{ClientConfig, DigitallySignedStruct, DistinguishedName, ServerConfig, SignatureScheme};
+    use tokio::net::TcpStream;
+    use tokio_rustls::{TlsAcceptor, TlsConnector};
+    use x509_parser::oid_registry::OID_SIG_ED25519;
+    use x509_parser::prelude::{FromDer, X509Certificate};
+
+    use super::SecuredStream;
+    use crate::api::p2p::{P2PError, P2PResult};
+    use crate::consensus::NodeId;
+    use crate::crypto::PublicKey;
+
+    /// Nom de serveur présenté au handshake, ignoré par la vérification
+    const TLS_SERVER_NAME: &str = "archivechain.node";
+
+    /// Identifiant de nœud porté par un certificat
+    ///
+    /// Seule la `SubjectPublicKeyInfo` du certificat est lue : elle doit
+    /// désigner l'algorithme Ed25519 (RFC 8410) sans paramètres. `None` pour
+    /// un DER malformé, suivi d'octets parasites ou portant une autre clé.
+    pub(super) fn certificate_node_id(cert: &CertificateDer<'_>) -> Option<String> {
+        let (rest, cert) = X509Certificate::from_der(cert.as_ref()).ok()?;
+        if !rest.is_empty() {
+            return None;
+        }
+        let spki = cert.public_key();
+        if spki.algorithm.algorithm != OID_SIG_ED25519 || spki.algorithm.parameters.is_some() {
+            return None;
+        }
+        let key = PublicKey::from_bytes(&spki.subject_public_key.data).ok()?;
+        Some(NodeId::from_public_key(&key).hash().to_hex())
+    }
+
+    /// TLS 1.3 mutuel entre nœuds
+    pub(super) struct TlsTransport {
+        node_id: String,
+        connector: TlsConnector,
+        acceptor: TlsAcceptor,
+    }
+
+    impl std::fmt::Debug for TlsTransport {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            f.debug_struct("TlsTransport").field("node_id", &self.node_id).finish()
+        }
+    }
+
+    impl TlsTransport {
+        /// Charge le certificat et la clé du nœud
+        pub(super) fn from_pem_files(cert_path: &str, key_path: &str) -> P2PResult<Self> {
+            let cert_pem = read_file(cert_path)?;
+            let cert = rustls_pemfile::certs(&mut cert_pem.as_slice())
+                .next()
+                .ok_or_else(|| tls_error(format!("No PEM certificate found in {}", cert_path)))?
+                .map_err(|e| tls_error(format!("Malformed PEM in {}: {}", cert_path, e)))?;
+            let key_pem = read_file(key_path)?;
+            let key = rustls_pemfile::private_key(&mut key_pem.as_slice())
+                .map_err(|e| tls_error(format!("Malformed PEM in {}: {}", key_path, e)))?
+                .ok_or_else(|| tls_error(format!("No PEM private key found in {}", key_path)))?;
+
+            Self::new(cert, key)
+        }
+
+        fn new(cert: CertificateDer<'static>, key: PrivateKeyDer<'static>) -> P2PResult<Self> {
+            let node_id = certificate_node_id(&cert)
+                .ok_or_else(|| tls_error("P2P certificate must carry an Ed25519 key".to_string()))?;
+
+            let provider = Arc::new(rustls::crypto::ring::default_provider());
+            let verifier = Arc::new(NodeCertVerifier::new(&provider));
+
+            let client = ClientConfig::builder_with_provider(provider.clone())
+                .with_protocol_versions(&[&rustls::version::TLS13])
+                .map_err(|e| tls_error(e.to_string()))?
+                .dangerous()
+                .with_custom_certificate_verifier(verifier.clone())
+                .with_client_auth_cert(vec![cert.clone()], key.clone_key())
+                .map_err(|e| tls_error(e.to_string()))?;
+
+            let server = ServerConfig::builder_with_provider(provider)
+                .with_protocol_versions(&[&rustls::version::TLS13])
+                .map_err(|e| tls_error(e.to_string()))?
+                .with_client_cert_verifier(verifier)
+                .with_single_cert(vec![cert], key)
+                .map_err(|e| tls_error(e.to_string()))?;
+
+            Ok(Self {
+                node_id,
+                connector: TlsConnector::from(Arc::new(client)),
+                acceptor: TlsAcceptor::from(Arc::new(server)),
+            })
+        }
+
+        /// Identifiant du nœud dérivé de son certificat
+        pub(super) fn node_id(&self) -> &str {
+            &self.node_id
+        }
+
+        pub(super) async fn connect(&self, stream: TcpStream) -> P2PResult<SecuredStream> {
+            let server_name = ServerName::try_from(TLS_SERVER_NAME).map_err(|e| tls_error(e.to_string()))?;
+            let stream = self
+                .connector
+                .connect(server_name, stream)
+                .await
+                .map_err(|e| P2PError::ConnectionFailed(format!("TLS handshake failed: {}", e)))?;
+            let peer_node_id = stream.get_ref().1.peer_certificates().and_then(|certs| certs.first()).and_then(certificate_node_id);
+            Self::secured(Box::new(stream), peer_node_id)
+        }
+
+        pub(super) async fn accept(&self, stream: TcpStream) -> P2PResult<SecuredStream> {
+            let stream = self
+                .acceptor
+                .accept(stream)
+                .await
+                .map_err(|e| P2PError::ConnectionFailed(format!("TLS handshake failed: {}", e)))?;
+            let peer_node_id = stream.get_ref().1.peer_certificates().and_then(|certs| certs.first()).and_then(certificate_node_id);
+            Self::secured(Box::new(stream), peer_node_id)
+        }
+
+        fn secured(stream: Box<dyn super::PeerStream>, peer_node_id: Option<String>) -> P2PResult<SecuredStream> {
+            // Les vérificateurs refusent déjà tout certificat sans clé Ed25519
+            let peer_node_id = peer_node_id.ok_or_else(|| tls_error("Peer presented no Ed25519 certificate".to_string()))?;
+            Ok(SecuredStream { stream, peer_node_id: Some(peer_node_id) })
+        }
+    }
+
+    fn read_file(path: &str) -> P2PResult<Vec<u8>> {
+        std::fs::read(path).map_err(|e| tls_error(format!("Cannot read {}: {}", path, e)))
+    }
+
+    fn tls_error(message: String) -> P2PError {
+        P2PError::ProtocolError(format!("TLS: {}", message))
+    }
+
+    /// Accepte tout certificat auto-signé portant une clé Ed25519
+    ///
+    /// Le handshake TLS prouve la détention de la clé du certificat ; le lien
+    /// entre cette clé et l'identifiant annoncé est vérifié au handshake P2P.
+    #[derive(Debug)]
+    struct NodeCertVerifier {
+        algorithms: WebPkiSupportedAlgorithms,
+    }
+
+    impl NodeCertVerifier {
+        fn new(provider: &CryptoProvider) -> Self {
+            Self { algorithms: provider.signature_verification_algorithms }
+        }
+
+        fn check(&self, end_entity: &CertificateDer<'_>) -> Result<(), rustls::Error> {
+            certificate_node_id(end_entity)
+                .map(|_| ())
+                .ok_or_else(|| rustls::Error::General("P2P certificate must carry an Ed25519 key".to_string()))
+        }
+    }
+
+    impl ServerCertVerifier for NodeCertVerifier {
+        fn verify_server_cert(
+            &self,
+            end_entity: &CertificateDer<'_>,
+            _intermediates: &[CertificateDer<'_>],
+            _server_name: &ServerName<'_>,
+            _ocsp_response: &[u8],
+            _now: UnixTime,
+        ) -> Result<ServerCertVerified, rustls::Error> {
+            self.check(end_entity).map(|_| ServerCertVerified::assertion())
+        }
+
+        fn verify_tls12_signature(
+            &self,
+            message: &[u8],
+            cert: &CertificateDer<'_>,
+            dss: &DigitallySignedStruct,
+        ) -> Result<HandshakeSignatureValid, rustls::Error> {
+            verify_tls12_signature(message, cert, dss, &self.algorithms)
+        }
+
+        fn verify_tls13_signature(
+            &self,
+            message: &[u8],
+            cert: &CertificateDer<'_>,
+            dss: &DigitallySignedStruct,
+        ) -> Result<HandshakeSignatureValid, rustls::Error> {
+            verify_tls13_signature(message, cert, dss, &self.algorithms)
+        }
+
+        fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+            self.algorithms.supported_schemes()
+        }
+    }
+
+    impl ClientCertVerifier for NodeCertVerifier {
+        fn root_hint_subjects(&self) -> &[DistinguishedName] {
+            &[]
+        }
+
+        fn verify_client_cert(
+            &self,
+            end_entity: &CertificateDer<'_>,
+            _intermediates: &[CertificateDer<'_>],
+            _now: UnixTime,
+        ) -> Result<ClientCertVerified, rustls::Error> {
+            self.check(end_entity).map(|_| ClientCertVerified::assertion())
+        }
+
+        fn verify_tls12_signature(
+            &self,
+            message: &[u8],
+            cert: &CertificateDer<'_>,
+            dss: &DigitallySignedStruct,
+        ) -> Result<HandshakeSignatureValid, rustls::Error> {
+            verify_tls12_signature(message, cert, dss, &self.algorithms)
+        }
+
+        fn verify_tls13_signature(
+            &self,
+            message: &[u8],
+            cert: &CertificateDer<'_>,
+            dss: &DigitallySignedStruct,
+        ) -> Result<HandshakeSignatureValid, rustls::Error> {
+            verify_tls13_signature(message, cert, dss, &self.algorithms)
+        }
+
+        fn supported_verify_schemes(&self) -> Vec<SignatureScheme> {
+            self.algorithms.supported_schemes()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tls_client_hello_detection() {
+        assert!(is_tls_client_hello(&[0x16, 0x03, 0x01]));
+        assert!(!is_tls_client_hello(&[0x16, 0x03]));
+
+        // Trame en clair d'un handshake : taille modeste
+        let frame = super::super::encode_frame(&super::super::MessageBuilder::ping(1), None).unwrap();
+        assert!(!is_tls_client_hello(&frame[..3]));
+    }
+
+    #[test]
+    fn test_encryption_requires_identity() {
+        let config = P2PConfig { require_encryption: true, ..P2PConfig::default() };
+        assert!(matches!(Transport::from_config(&config), Err(P2PError::EncryptionRequired(_))));
+
+        let transport = Transport::from_config(&P2PConfig::default()).unwrap();
+        assert!(!transport.supports_tls());
+        assert!(transport.local_node_id().is_none());
+        assert!(transport.accepts_plaintext());
+    }
+
+    #[cfg(feature = "tls")]
+    #[test]
+    fn test_certificate_node_id_reads_the_subject_key() {
+        use rustls::pki_types::CertificateDer;
+
+        let key_pair = rcgen::KeyPair::generate_for(&rcgen::PKCS_ED25519).unwrap();
+        let cert = rcgen::CertificateParams::new(vec!["archivechain.node".to_string()])
+            .unwrap()
+            .self_signed(&key_pair)
+            .unwrap();
+        let key = crate::crypto::PublicKey::from_bytes(&key_pair.public_key_raw()).unwrap();
+        let expected = crate::consensus::NodeId::from_public_key(&key).hash().to_hex();
+        assert_eq!(tls::certificate_node_id(cert.der()), Some(expected));
+
+        // Le motif d'une clé Ed25519 glissé dans une extension ne suffit pas
+        let ecdsa = rcgen::KeyPair::generate_for(&rcgen::PKCS_ECDSA_P256_SHA256).unwrap();
+        let mut params = rcgen::CertificateParams::new(vec!["archivechain.node".to_string()]).unwrap();
+        let mut smuggled = vec![0x30, 0x2a, 0x30, 0x05, 0x06, 0x03, 0x2b, 0x65, 0x70, 0x03, 0x21, 0x00];
+        smuggled.extend_from_slice(&key_pair.public_key_raw());
+        params.custom_extensions.push(rcgen::CustomExtension::from_oid_content(&[1, 3, 6, 1, 4, 1, 99999, 1], smuggled));
+        let forged = params.self_signed(&ecdsa).unwrap();
+        assert_eq!(tls::certificate_node_id(forged.der()), None);
+
+        // Octets parasites après le certificat
+        let mut trailing = cert.der().to_vec();
+        trailing.push(0);
+        assert_eq!(tls::certificate_node_id(&CertificateDer::from(trailing)), None);
+    }
+}
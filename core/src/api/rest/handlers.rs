@@ -3,22 +3,35 @@
 //! Implémente tous les handlers pour les routes REST selon les spécifications API.
 
 use axum::{
+    body::{Body, Bytes},
     extract::{Path, Query, State},
-    http::StatusCode,
-    response::Json,
+    http::{header, HeaderMap, StatusCode},
+    response::{IntoResponse, Json, Response},
 };
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::Arc;
 
 use crate::api::{
     ApiError, ApiResult,
     types::*,
     server::ServerState,
+    service::{ArchiveQuery, ArchiveService, BountyService, ConfirmationQuery, ContentSource, NetworkService},
     middleware::AuthInfo,
+    auth::{ApiKeyRecord, ApiScope, TokenInfo},
 };
+use crate::contracts::archive_bounty::{
+    ArchiveBounty, ArchiveCapture, ArchiveSubmission, BountyStatus, EscrowStatus, QualityLevel, ValidationStatus,
+};
+use crate::crypto::{Hash, PublicKey};
+use crate::nodes::gateway::RateLimit as KeyRateLimit;
+use crate::token::Treasury;
+use crate::token::treasury::{MilestoneReport, MilestoneStatus, TreasuryStatistics};
 use super::{
     PaginationParams, PaginatedResponse, ApiResponse,
-    extractors::{ValidatedPagination, ValidatedQuery, Validate},
+    extractors::{RequireScope, ValidatedPagination, ValidatedQuery, Validate},
+    negotiation::Negotiable,
+    validation::{collect_validation, SearchValidator, ValidationError, ValidationResult},
 };
 
 // ============================================================================
@@ -26,34 +39,61 @@ use super::{
 // ============================================================================
 
 /// Créer une nouvelle archive
+#[tracing::instrument(name = "create_archive", skip_all, fields(user_id = %auth.user_id))]
 pub async fn create_archive(
     State(state): State<ServerState>,
     auth: AuthInfo,
+    _scope: RequireScope<{ RequireScope::<1>::ARCHIVES_WRITE }>,
     Json(request): Json<CreateArchiveRequest>,
-) -> ApiResult<Json<CreateArchiveResponse>> {
+) -> ApiResult<Response> {
+    // Simulation : la demande est validée et chiffrée, rien n'est enregistré
+    if request.dry_run {
+        let estimate = state.archives.estimate_archive(&request)?;
+        return Ok(Json(estimate).into_response());
+    }
+
     // Valide la demande
-    validate_create_archive_request(&request)?;
+    ArchiveService::validate_create_request(&request)?;
 
     // Vérifie les permissions et quotas de l'utilisateur
     check_user_quota(&auth, &state).await?;
 
-    // Génère un ID d'archive unique
-    let archive_id = format!("arc_{}", uuid::Uuid::new_v4().simple());
+    let submission = state.archives.submit_archive(&auth.user_id, request).await?;
+    let record = submission.record;
+
+    // Une soumission inédite réserve la récompense de découverte de son auteur
+    if let (Some(discovery), Some(content_hash), false) = (&state.discovery, &record.content_hash, submission.deduplicated) {
+        let discoverer = state.user_manager.read().await
+            .get_user(&auth.user_id)
+            .and_then(|user| user.public_key.clone());
+        if let Some(discoverer) = discoverer {
+            if let Err(e) = discovery.claim(&discoverer, content_hash.clone(), &record.archive.url).await {
+                tracing::debug!("No discovery reward for archive {}: {}", record.archive.archive_id, e);
+            }
+        }
+    }
 
-    // Estime les coûts
-    let cost_estimation = estimate_archive_cost(&request).await?;
+    // Le nœud capture lui-même l'URL : seul ce crawl mesure la qualité de l'archive
+    if !submission.deduplicated && state.archives.crawls_archives() {
+        let archives = state.archives.clone();
+        let archive_id = record.archive.archive_id.clone();
+        tokio::spawn(async move {
+            if let Err(e) = archives.crawl_archive(&archive_id).await {
+                tracing::warn!("Crawl of archive {} failed: {}", archive_id, e);
+            }
+        });
+    }
 
     // Crée la réponse
     let response = CreateArchiveResponse {
-        archive_id,
-        status: ArchiveStatus::Pending,
+        archive_id: record.archive.archive_id,
+        deduplicated: submission.deduplicated,
+        status: record.archive.status,
         estimated_completion: Some(chrono::Utc::now() + chrono::Duration::minutes(5)),
-        cost_estimation,
+        cost_estimation: record.cost,
     };
 
-    // TODO: Ajouter la demande d'archivage à la queue de traitement
-
-    Ok(Json(response))
+    Ok(Json(response).into_response())
 }
 
 /// Lister les archives
@@ -62,15 +102,13 @@ pub async fn list_archives(
     auth: AuthInfo,
     ValidatedPagination(pagination): ValidatedPagination,
     Query(filters): Query<ArchiveListFilters>,
+    Query(confirmation): Query<ConfirmationQuery>,
 ) -> ApiResult<Json<PaginatedResponse<ArchiveDto>>> {
-    // TODO: Implémenter la récupération des archives depuis la blockchain
-    let archives = vec![]; // Placeholder
-
-    let pagination_info = crate::api::types::PaginationInfo::new(
-        pagination.page,
-        pagination.limit,
-        0, // TODO: Récupérer le nombre total
-    );
+    let query = ArchiveQuery::from(filters);
+    let (records, pagination_info) = state.archives
+        .list_confirmed_archives(&pagination, &query, &state.blockchain, &confirmation)
+        .await;
+    let archives = records.into_iter().map(|record| record.archive).collect();
 
     let response = PaginatedResponse::new(archives, pagination_info);
     Ok(Json(response))
@@ -81,13 +119,164 @@ pub async fn get_archive(
     State(state): State<ServerState>,
     auth: AuthInfo,
     Path(archive_id): Path<String>,
-) -> ApiResult<Json<ArchiveDto>> {
-    // Valide l'ID d'archive
-    validate_archive_id(&archive_id)?;
+    Query(confirmation): Query<ConfirmationQuery>,
+) -> ApiResult<Negotiable<ArchiveDto>> {
+    let record = state.archives.get_archive(&archive_id).await?.with_confirmations(&state.blockchain);
+    confirmation.check(&state.blockchain, &format!("Archive {}", archive_id), record.archive.confirmations)?;
+    Ok(Negotiable(record.archive))
+}
 
-    // TODO: Récupérer l'archive depuis la blockchain
-    // Pour l'instant, retourne une erreur 404
-    Err(ApiError::not_found(format!("Archive {} not found", archive_id)))
+/// Historique des versions d'une URL, de la plus récente à la plus ancienne
+pub async fn get_archives_by_url(
+    State(state): State<ServerState>,
+    auth: AuthInfo,
+    ValidatedPagination(pagination): ValidatedPagination,
+    Query(query): Query<ArchiveVersionsQuery>,
+) -> ApiResult<Json<PaginatedResponse<ArchiveVersionDto>>> {
+    let (versions, pagination_info) = state.archives.get_url_history(&query.url, &pagination).await;
+    let versions = versions.into_iter().map(ArchiveVersionDto::from).collect();
+    Ok(Json(PaginatedResponse::new(versions, pagination_info)))
+}
+
+/// Archive de la capture la plus proche à la date donnée ou avant
+pub async fn get_archive_at(
+    State(state): State<ServerState>,
+    auth: AuthInfo,
+    Query(query): Query<ArchiveAtQuery>,
+    Query(confirmation): Query<ConfirmationQuery>,
+) -> ApiResult<Negotiable<ArchiveDto>> {
+    let record = state.archives.get_archive_at(&query.url, query.timestamp).await?
+        .with_confirmations(&state.blockchain);
+    confirmation.check(&state.blockchain, &format!("Archive {}", record.archive.archive_id), record.archive.confirmations)?;
+    Ok(Negotiable(record.archive))
+}
+
+/// Taille des fragments du corps envoyé en transfert chunked
+const CONTENT_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Plage demandée par l'en-tête `Range`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RangeRequest {
+    /// Pas de plage exploitable : contenu complet
+    Full,
+    /// Octets `start..=end`
+    Partial { start: u64, end: u64 },
+    /// Plage hors du contenu
+    Unsatisfiable,
+}
+
+/// Interprète un en-tête `Range` pour un contenu de `len` octets
+///
+/// Seule une plage d'octets unique est prise en charge ; comme le permet la
+/// RFC 9110, un en-tête mal formé ou multi-plages est ignoré.
+fn parse_range(header: Option<&str>, len: u64) -> RangeRequest {
+    let Some(spec) = header.and_then(|h| h.trim().strip_prefix("bytes=")) else {
+        return RangeRequest::Full;
+    };
+    let Some((start, end)) = spec.split_once('-').filter(|_| !spec.contains(',')) else {
+        return RangeRequest::Full;
+    };
+
+    let (start, end) = match (start.trim(), end.trim()) {
+        ("", "") => return RangeRequest::Full,
+        // Suffixe : les `n` derniers octets
+        ("", suffix) => match suffix.parse::<u64>() {
+            Ok(0) => return RangeRequest::Unsatisfiable,
+            Ok(n) => (len.saturating_sub(n), u64::MAX),
+            Err(_) => return RangeRequest::Full,
+        },
+        (start, end) => {
+            let Ok(start) = start.parse::<u64>() else {
+                return RangeRequest::Full;
+            };
+            let end = match end {
+                "" => u64::MAX,
+                end => match end.parse::<u64>() {
+                    Ok(end) if end >= start => end,
+                    _ => return RangeRequest::Full,
+                },
+            };
+            (start, end)
+        }
+    };
+
+    if start >= len {
+        return RangeRequest::Unsatisfiable;
+    }
+    RangeRequest::Partial { start, end: end.min(len - 1) }
+}
+
+/// Corps de réponse envoyé par fragments
+fn chunked_body(data: Bytes) -> Body {
+    let chunks: Vec<Result<Bytes, std::convert::Infallible>> = (0..data.len())
+        .step_by(CONTENT_CHUNK_SIZE)
+        .map(|offset| Ok(data.slice(offset..(offset + CONTENT_CHUNK_SIZE).min(data.len()))))
+        .collect();
+    Body::from_stream(futures::stream::iter(chunks))
+}
+
+/// Récupérer le contenu d'une archive
+///
+/// Le contenu est servi depuis le cache de la gateway, ou à défaut depuis le
+/// nœud de stockage le plus performant. L'en-tête `Range` permet de ne
+/// récupérer qu'une partie du contenu (réponse 206), par exemple pour
+/// naviguer dans une vidéo ou un PDF volumineux.
+pub async fn get_archive_content(
+    State(state): State<ServerState>,
+    _scope: RequireScope<{ RequireScope::<0>::ARCHIVES_READ }>,
+    auth: AuthInfo,
+    Path(archive_id): Path<String>,
+    headers: HeaderMap,
+) -> ApiResult<Response> {
+    let content = state.content.clone()
+        .ok_or_else(|| ApiError::ServiceUnavailable("Content retrieval is not configured".to_string()))?;
+
+    let record = state.archives.get_archive(&archive_id).await?;
+    let content_hash = record.content_hash
+        .ok_or_else(|| ApiError::not_found(format!("Archive {} has no stored content", archive_id)))?;
+
+    let fetched = content.fetch(&content_hash).await?;
+    let cache_status = if fetched.source == ContentSource::Cache { "HIT" } else { "MISS" };
+    let data = Bytes::from(fetched.data);
+    let len = data.len() as u64;
+
+    let range = parse_range(headers.get(header::RANGE).and_then(|h| h.to_str().ok()), len);
+    let mime_type = match record.archive.metadata.mime_type.as_str() {
+        "unknown" => "application/octet-stream",
+        mime_type => mime_type,
+    };
+
+    let response = Response::builder()
+        .header(header::ACCEPT_RANGES, "bytes")
+        .header("x-cache", cache_status);
+
+    let served_bytes = match range {
+        RangeRequest::Full => len,
+        RangeRequest::Partial { start, end } => end - start + 1,
+        RangeRequest::Unsatisfiable => 0,
+    };
+    // Requête servie par un nœud de stockage : base du règlement de sa livraison
+    if let Some(request_id) = crate::api::middleware::current_request_id().filter(|_| served_bytes > 0) {
+        content.record_served(&request_id, &auth.user_id, &content_hash, &fetched.source, served_bytes).await;
+    }
+
+    let response = match range {
+        RangeRequest::Full => response
+            .status(StatusCode::OK)
+            .header(header::CONTENT_TYPE, mime_type)
+            .body(chunked_body(data)),
+        RangeRequest::Partial { start, end } => response
+            .status(StatusCode::PARTIAL_CONTENT)
+            .header(header::CONTENT_TYPE, mime_type)
+            .header(header::CONTENT_RANGE, format!("bytes {}-{}/{}", start, end, len))
+            .body(chunked_body(data.slice(start as usize..=end as usize))),
+        RangeRequest::Unsatisfiable => response
+            .status(StatusCode::RANGE_NOT_SATISFIABLE)
+            .header(header::CONTENT_RANGE, format!("bytes */{}", len))
+            .body(Body::empty()),
+    };
+
+    response.map_err(|e| ApiError::internal(format!("Failed to build content response: {}", e)))
 }
 
 /// Mettre à jour une archive
@@ -103,13 +292,16 @@ pub async fn update_archive(
 }
 
 /// Supprimer une archive
+///
+/// Seule une demande encore en attente ou en cours peut être retirée, et
+/// uniquement par son propriétaire : elle est alors annulée.
 pub async fn delete_archive(
     State(state): State<ServerState>,
     auth: AuthInfo,
+    _scope: RequireScope<{ RequireScope::<2>::ARCHIVES_DELETE }>,
     Path(archive_id): Path<String>,
 ) -> ApiResult<StatusCode> {
-    validate_archive_id(&archive_id)?;
-    // TODO: Implémenter la suppression
+    state.archives.cancel_archive(&auth.user_id, &archive_id).await?;
     Ok(StatusCode::NO_CONTENT)
 }
 
@@ -178,31 +370,115 @@ pub async fn get_archive_replicas(
     Ok(Json(response))
 }
 
+/// Preuve d'inclusion on-chain d'une archive
+///
+/// L'identifiant est le hash hexadécimal de l'archive (ou de la transaction)
+/// inscrit dans un bloc ; la preuve se vérifie contre la racine de Merkle de
+/// l'en-tête de ce bloc sans télécharger le bloc complet.
+pub async fn get_archive_proof(
+    State(state): State<ServerState>,
+    _auth: AuthInfo,
+    Path(archive_id): Path<String>,
+) -> ApiResult<Json<InclusionProofResponse>> {
+    let leaf_hash = crate::crypto::Hash::from_hex(&archive_id)
+        .map_err(|_| ApiError::validation("Invalid archive hash format"))?;
+
+    let (block, proof) = state.blockchain.find_inclusion_proof(&leaf_hash)
+        .ok_or_else(|| match state.blockchain.leaf_height(&leaf_hash) {
+            // Incluse dans un bloc dont le corps a été élagué
+            Some(height) if state.blockchain.is_pruned_height(height) => {
+                ApiError::pruned_data(format!("Archive {} not found in retained blocks", archive_id))
+            }
+            _ => ApiError::not_found(format!("Archive {} not found on chain", archive_id)),
+        })?;
+
+    Ok(Json(InclusionProofResponse {
+        archive_id,
+        block_hash: block.hash().to_hex(),
+        block_height: block.height(),
+        merkle_root: proof.root_hash.to_hex(),
+        leaf_hash: proof.leaf_hash.to_hex(),
+        algorithm: format!("{:?}", proof.algorithm),
+        path: proof.path.iter()
+            .map(|(hash, is_right)| ProofStep {
+                hash: hash.to_hex(),
+                position: if *is_right { "right" } else { "left" }.to_string(),
+            })
+            .collect(),
+    }))
+}
+
 // ============================================================================
 // SEARCH HANDLERS
 // ============================================================================
 
-/// Recherche générale d'archives
+/// Recherche plein texte d'archives
+///
+/// `GET /search?q=&filters=&page=&limit=` : `filters` est un objet JSON
+/// `SearchFilters` (content_type, tags, domain, date_range). Les résultats
+/// sont classés par pertinence ; les facettes portent sur tous les résultats.
 pub async fn search_archives(
     State(state): State<ServerState>,
     auth: AuthInfo,
-    ValidatedQuery(search_params): ValidatedQuery<SearchRequest>,
+    _scope: RequireScope<{ RequireScope::<3>::SEARCH_READ }>,
+    ValidatedPagination(pagination): ValidatedPagination,
+    ValidatedQuery(params): ValidatedQuery<SearchParams>,
 ) -> ApiResult<Json<SearchResponse>> {
-    // TODO: Implémenter la recherche
-    let response = SearchResponse {
-        query: search_params.query,
-        results: vec![],
-        facets: SearchFacets {
-            domains: HashMap::new(),
-            content_types: HashMap::new(),
-            languages: HashMap::new(),
-            tags: HashMap::new(),
-        },
-        total_results: 0,
-        search_time_ms: 10,
-        pagination: crate::api::types::PaginationInfo::new(1, 20, 0),
+    let started = std::time::Instant::now();
+    let query = ArchiveQuery::from(params.filters()?);
+    let hits = state.archives.search_archives(&params.q, &query).await;
+    let total = hits.len() as u64;
+    let facets = search_facets_of(hits.iter().map(|(record, _)| &record.archive));
+
+    let results = hits.into_iter()
+        .skip(pagination.offset() as usize)
+        .take(pagination.limit as usize)
+        .map(|(record, relevance_score)| {
+            let archive = record.archive;
+            SearchResult {
+                archive_id: archive.archive_id,
+                url: archive.url,
+                title: archive.metadata.title,
+                snippet: archive.metadata.description,
+                relevance_score,
+                archived_at: archive.created_at,
+                size: archive.size,
+                content_type: archive.metadata.mime_type,
+            }
+        })
+        .collect();
+
+    Ok(Json(SearchResponse {
+        query: params.q,
+        results,
+        facets,
+        total_results: total,
+        search_time_ms: started.elapsed().as_millis() as u64,
+        pagination: PaginationInfo::new(pagination.page, pagination.limit, total),
+    }))
+}
+
+/// Facettes d'un ensemble d'archives
+fn search_facets_of<'a>(archives: impl Iterator<Item = &'a ArchiveDto>) -> SearchFacets {
+    let mut facets = SearchFacets {
+        domains: HashMap::new(),
+        content_types: HashMap::new(),
+        languages: HashMap::new(),
+        tags: HashMap::new(),
     };
-    Ok(Json(response))
+    for archive in archives {
+        if let Some(host) = url::Url::parse(&archive.url).ok().and_then(|url| url.host_str().map(str::to_string)) {
+            *facets.domains.entry(host).or_insert(0) += 1;
+        }
+        *facets.content_types.entry(archive.metadata.mime_type.clone()).or_insert(0) += 1;
+        if let Some(language) = &archive.metadata.language {
+            *facets.languages.entry(language.clone()).or_insert(0) += 1;
+        }
+        for tag in &archive.metadata.tags {
+            *facets.tags.entry(tag.clone()).or_insert(0) += 1;
+        }
+    }
+    facets
 }
 
 /// Recherche avancée
@@ -281,32 +557,9 @@ pub async fn bulk_search(
 pub async fn get_network_stats(
     State(state): State<ServerState>,
     auth: AuthInfo,
-) -> ApiResult<Json<NetworkStats>> {
-    let stats = state.blockchain.get_stats()
-        .map_err(|e| ApiError::internal(format!("Failed to get blockchain stats: {}", e)))?;
-
-    let network_stats = NetworkStats {
-        network: NetworkInfo {
-            total_nodes: 100, // TODO: Récupérer depuis le consensus
-            active_nodes: 95,
-            total_storage: "15.7 TB".to_string(),
-            available_storage: "8.3 TB".to_string(),
-            current_block_height: stats.height,
-        },
-        archives: ArchiveStats {
-            total_archives: stats.total_transactions, // Approximation
-            archives_today: 50, // TODO: Calculer
-            total_size: "12.4 TB".to_string(),
-            average_replication: 4.2,
-        },
-        performance: PerformanceStats {
-            average_archive_time: "2.3 minutes".to_string(),
-            network_latency: "45ms".to_string(),
-            success_rate: 0.987,
-        },
-    };
-
-    Ok(Json(network_stats))
+) -> ApiResult<Negotiable<NetworkStats>> {
+    let network_stats = NetworkService::stats(&state).await?;
+    Ok(Negotiable(network_stats))
 }
 
 /// Récupérer la santé du réseau
@@ -372,6 +625,403 @@ pub async fn get_consensus_state(
     Ok(Json(response))
 }
 
+// ============================================================================
+// AUTH HANDLERS
+// ============================================================================
+
+/// Échange un refresh token contre un nouveau couple de tokens
+///
+/// Route publique : l'access token a pu expirer. Le refresh token présenté
+/// est consommé et ne peut pas être réutilisé.
+pub async fn refresh_auth_token(
+    State(state): State<ServerState>,
+    Json(request): Json<RefreshTokenRequest>,
+) -> ApiResult<Json<TokenInfo>> {
+    if request.refresh_token.is_empty() {
+        return Err(ApiError::validation("refresh_token is required"));
+    }
+
+    let mut users = state.user_manager.write().await;
+    let token_info = state.auth_service.refresh_token(&mut users, &request.refresh_token)?;
+    Ok(Json(token_info))
+}
+
+/// Révoque un refresh token
+///
+/// Route publique : détenir le token suffit à le révoquer. La réponse ne
+/// révèle pas si le token était connu.
+pub async fn revoke_auth_token(
+    State(state): State<ServerState>,
+    Json(request): Json<RefreshTokenRequest>,
+) -> ApiResult<StatusCode> {
+    if request.refresh_token.is_empty() {
+        return Err(ApiError::validation("refresh_token is required"));
+    }
+
+    state.user_manager.write().await.revoke_refresh_token(&request.refresh_token);
+    Ok(StatusCode::NO_CONTENT)
+}
+
+/// Révoque tous les refresh tokens de l'appelant
+///
+/// Ferme toutes ses sessions, y compris celle d'un token volé dont il ne
+/// dispose plus ; les access tokens déjà émis expirent d'eux-mêmes.
+pub async fn revoke_sessions(
+    State(state): State<ServerState>,
+    auth: AuthInfo,
+) -> ApiResult<Json<RevokedSessionsResponse>> {
+    let revoked = state.user_manager.write().await.revoke_user_refresh_tokens(&auth.user_id);
+    Ok(Json(RevokedSessionsResponse { revoked }))
+}
+
+/// Crée une clé API pour l'appelant
+///
+/// Réservé aux sessions JWT. Les scopes demandés doivent faire partie de ceux
+/// de l'appelant et la limite de la clé ne dépasse pas sa limite horaire ;
+/// sans limite demandée, la clé reçoit celle de l'appelant. La clé complète
+/// n'est renvoyée que dans cette réponse.
+pub async fn create_api_key(
+    State(state): State<ServerState>,
+    auth: AuthInfo,
+    Json(request): Json<CreateApiKeyRequest>,
+) -> ApiResult<Json<CreateApiKeyResponse>> {
+    auth.require_session()?;
+    let scopes = request.scopes.iter()
+        .map(|scope| ApiScope::from_str(scope).ok_or_else(|| ApiError::validation(format!("Unknown scope: {}", scope))))
+        .collect::<ApiResult<Vec<_>>>()?;
+    if !auth.scopes.contains(&ApiScope::AdminAll) {
+        if let Some(scope) = scopes.iter().find(|scope| !auth.scopes.contains(scope)) {
+            return Err(ApiError::authorization(format!("Cannot grant scope not held: {}", scope.as_str())));
+        }
+    }
+
+    let hourly = auth.claims.rate_limit.requests_per_hour;
+    let rate_limit = match request.rate_limit {
+        Some(limit) if limit.requests_per_hour > hourly
+            || limit.requests_per_minute > hourly
+            || limit.requests_per_second > hourly => {
+            return Err(ApiError::authorization(format!("API key rate limit cannot exceed {} requests per hour", hourly)));
+        }
+        Some(limit) => limit,
+        None => KeyRateLimit {
+            requests_per_second: hourly,
+            requests_per_minute: hourly,
+            requests_per_hour: hourly,
+            burst_allowance: 0,
+        },
+    };
+
+    let mut users = state.user_manager.write().await;
+    let (key, record) = users.create_api_key(&auth.user_id, scopes, request.expires_at, Some(rate_limit))?;
+    Ok(Json(CreateApiKeyResponse { key, info: ApiKeyInfo::from(&record) }))
+}
+
+/// Liste les clés API de l'appelant, sans leur secret
+///
+/// Réservé aux sessions JWT.
+pub async fn list_api_keys(
+    State(state): State<ServerState>,
+    auth: AuthInfo,
+) -> ApiResult<Json<Vec<ApiKeyInfo>>> {
+    auth.require_session()?;
+    let users = state.user_manager.read().await;
+    Ok(Json(users.list_api_keys(&auth.user_id).into_iter().map(ApiKeyInfo::from).collect()))
+}
+
+/// Révoque une clé API de l'appelant
+///
+/// Réservé aux sessions JWT ; la clé d'un autre utilisateur est introuvable.
+pub async fn revoke_api_key(
+    State(state): State<ServerState>,
+    auth: AuthInfo,
+    Path(key_id): Path<String>,
+) -> ApiResult<StatusCode> {
+    auth.require_session()?;
+    state.user_manager.write().await.revoke_api_key(&auth.user_id, &key_id)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+// ============================================================================
+// BOUNTIES HANDLERS
+// ============================================================================
+
+/// Service des bounties, indisponible sans registre ARC rattaché
+fn bounty_service(state: &ServerState) -> ApiResult<Arc<BountyService>> {
+    state.bounties.clone()
+        .ok_or_else(|| ApiError::ServiceUnavailable("Bounties are not configured".to_string()))
+}
+
+/// Clé publique portant les ARC de l'utilisateur
+async fn user_public_key(state: &ServerState, user_id: &str) -> ApiResult<PublicKey> {
+    state.user_manager.read().await
+        .get_user(user_id)
+        .and_then(|user| user.public_key.clone())
+        .ok_or_else(|| ApiError::validation("A registered public key is required to hold ARC"))
+}
+
+/// Résumé du crawl d'une archive soumise par l'utilisateur
+///
+/// Seul un demandeur de l'archive peut la présenter pour un bounty, et seul
+/// le crawl effectué par ce nœud compte : un contenu téléversé par un client
+/// ne sert jamais à mesurer la qualité.
+async fn archive_capture(state: &ServerState, user_id: &str, archive_id: &str) -> ApiResult<(ArchiveCapture, u64)> {
+    let record = state.archives.get_archive(archive_id).await?;
+    if !record.requesters.iter().any(|requester| requester == user_id) {
+        return Err(ApiError::authorization(format!("Archive {} was not submitted by the caller", archive_id)));
+    }
+    let capture = record.crawl
+        .ok_or_else(|| ApiError::validation(format!("Archive {} has not been crawled by this node", archive_id)))?;
+    Ok((capture, record.archive.size))
+}
+
+/// Lister les bounties d'un statut (actifs par défaut)
+pub async fn list_bounties(
+    State(state): State<ServerState>,
+    auth: AuthInfo,
+    ValidatedPagination(pagination): ValidatedPagination,
+    Query(query): Query<BountyListQuery>,
+) -> ApiResult<Json<PaginatedResponse<BountyDto>>> {
+    let (bounties, pagination_info) = bounty_service(&state)?.list(query.status, &pagination).await;
+    let bounties = bounties.iter().map(BountyDto::from).collect();
+    Ok(Json(PaginatedResponse::new(bounties, pagination_info)))
+}
+
+/// Créer un bounty, la récompense étant bloquée sur le solde ARC du créateur
+pub async fn create_bounty(
+    State(state): State<ServerState>,
+    auth: AuthInfo,
+    _scope: RequireScope<{ RequireScope::<1>::ARCHIVES_WRITE }>,
+    Json(request): Json<CreateBountyRequest>,
+) -> ApiResult<(StatusCode, Json<BountyTransitionResponse>)> {
+    let bounties = bounty_service(&state)?;
+    let creator = user_public_key(&state, &auth.user_id).await?;
+
+    let outcome = bounties
+        .create(&creator, &request.url, request.reward, request.min_quality, request.deadline_hours)
+        .await?;
+    let response = BountyTransitionResponse {
+        amount: outcome.value.reward,
+        bounty: BountyDto::from(&outcome.value),
+        events: outcome.events,
+    };
+    Ok((StatusCode::CREATED, Json(response)))
+}
+
+/// Récupérer un bounty
+pub async fn get_bounty(
+    State(state): State<ServerState>,
+    auth: AuthInfo,
+    Path(bounty_id): Path<u64>,
+) -> ApiResult<Json<BountyDto>> {
+    let bounty = bounty_service(&state)?.get(bounty_id).await?;
+    Ok(Json(BountyDto::from(&bounty)))
+}
+
+/// Réclamer un bounty avec une archive crawlée de l'URL cible
+///
+/// La qualité de l'archive est calculée depuis le manifeste du crawl de ce
+/// nœud ; la récompense est versée sur le solde ARC de l'appelant.
+pub async fn claim_bounty(
+    State(state): State<ServerState>,
+    auth: AuthInfo,
+    _scope: RequireScope<{ RequireScope::<1>::ARCHIVES_WRITE }>,
+    Path(bounty_id): Path<u64>,
+    Json(request): Json<ClaimBountyRequest>,
+) -> ApiResult<Json<BountyTransitionResponse>> {
+    let bounties = bounty_service(&state)?;
+    let claimer = user_public_key(&state, &auth.user_id).await?;
+    let (capture, _) = archive_capture(&state, &auth.user_id, &request.archive_id).await?;
+
+    let outcome = bounties.claim(&claimer, bounty_id, &capture).await?;
+    Ok(Json(BountyTransitionResponse {
+        bounty: BountyDto::from(&bounties.get(bounty_id).await?),
+        amount: outcome.value,
+        events: outcome.events,
+    }))
+}
+
+/// Rembourser le créateur d'un bounty expiré sans réclamation
+pub async fn refund_bounty(
+    State(state): State<ServerState>,
+    auth: AuthInfo,
+    _scope: RequireScope<{ RequireScope::<1>::ARCHIVES_WRITE }>,
+    Path(bounty_id): Path<u64>,
+) -> ApiResult<Json<BountyTransitionResponse>> {
+    let bounties = bounty_service(&state)?;
+    let caller = user_public_key(&state, &auth.user_id).await?;
+
+    let outcome = bounties.refund(&caller, bounty_id).await?;
+    Ok(Json(BountyTransitionResponse {
+        bounty: BountyDto::from(&bounties.get(bounty_id).await?),
+        amount: outcome.value,
+        events: outcome.events,
+    }))
+}
+
+/// Augmenter la récompense ou repousser la deadline d'un bounty (créateur uniquement)
+pub async fn update_bounty(
+    State(state): State<ServerState>,
+    auth: AuthInfo,
+    _scope: RequireScope<{ RequireScope::<1>::ARCHIVES_WRITE }>,
+    Path(bounty_id): Path<u64>,
+    Json(request): Json<UpdateBountyRequest>,
+) -> ApiResult<Json<BountyTransitionResponse>> {
+    let bounties = bounty_service(&state)?;
+    let caller = user_public_key(&state, &auth.user_id).await?;
+
+    let outcome = bounties
+        .update(&caller, bounty_id, request.additional_reward, request.extend_hours)
+        .await?;
+    Ok(Json(BountyTransitionResponse {
+        amount: request.additional_reward,
+        bounty: BountyDto::from(&outcome.value),
+        events: outcome.events,
+    }))
+}
+
+/// Annuler un bounty sans soumission validée, la récompense revenant au créateur
+pub async fn cancel_bounty(
+    State(state): State<ServerState>,
+    auth: AuthInfo,
+    _scope: RequireScope<{ RequireScope::<1>::ARCHIVES_WRITE }>,
+    Path(bounty_id): Path<u64>,
+) -> ApiResult<Json<BountyTransitionResponse>> {
+    let bounties = bounty_service(&state)?;
+    let caller = user_public_key(&state, &auth.user_id).await?;
+
+    let outcome = bounties.cancel(&caller, bounty_id).await?;
+    Ok(Json(BountyTransitionResponse {
+        bounty: BountyDto::from(&bounties.get(bounty_id).await?),
+        amount: outcome.value,
+        events: outcome.events,
+    }))
+}
+
+/// Proposer au créateur d'un bounty une archive crawlée de l'URL cible
+pub async fn submit_bounty_proposal(
+    State(state): State<ServerState>,
+    auth: AuthInfo,
+    _scope: RequireScope<{ RequireScope::<1>::ARCHIVES_WRITE }>,
+    Path(bounty_id): Path<u64>,
+    Json(request): Json<ClaimBountyRequest>,
+) -> ApiResult<(StatusCode, Json<BountyProposalDto>)> {
+    let bounties = bounty_service(&state)?;
+    let submitter = user_public_key(&state, &auth.user_id).await?;
+    let (capture, size) = archive_capture(&state, &auth.user_id, &request.archive_id).await?;
+
+    let outcome = bounties.propose(&submitter, bounty_id, &capture, size).await?;
+    let bounty = bounties.get(bounty_id).await?;
+    let proposal = bounty.submissions.iter()
+        .find(|submission| submission.submission_id == outcome.value)
+        .map(BountyProposalDto::from)
+        .ok_or_else(|| ApiError::internal("Submitted proposal not recorded"))?;
+    Ok((StatusCode::CREATED, Json(proposal)))
+}
+
+/// Lister les propositions reçues par un bounty
+pub async fn list_bounty_proposals(
+    State(state): State<ServerState>,
+    auth: AuthInfo,
+    Path(bounty_id): Path<u64>,
+) -> ApiResult<Json<Vec<BountyProposalDto>>> {
+    let bounty = bounty_service(&state)?.get(bounty_id).await?;
+    Ok(Json(bounty.submissions.iter().map(BountyProposalDto::from).collect()))
+}
+
+/// Accepter une proposition validée et en payer l'auteur (créateur uniquement)
+pub async fn accept_bounty_proposal(
+    State(state): State<ServerState>,
+    auth: AuthInfo,
+    _scope: RequireScope<{ RequireScope::<1>::ARCHIVES_WRITE }>,
+    Path((bounty_id, proposal_id)): Path<(u64, String)>,
+) -> ApiResult<Json<BountyTransitionResponse>> {
+    let bounties = bounty_service(&state)?;
+    let caller = user_public_key(&state, &auth.user_id).await?;
+    let submission_id = Hash::from_hex(&proposal_id)
+        .map_err(|_| ApiError::validation(format!("Invalid proposal id: {}", proposal_id)))?;
+
+    let outcome = bounties.accept(&caller, bounty_id, submission_id).await?;
+    Ok(Json(BountyTransitionResponse {
+        bounty: BountyDto::from(&bounties.get(bounty_id).await?),
+        amount: outcome.value,
+        events: outcome.events,
+    }))
+}
+
+/// Verser la récompense d'une proposition dont l'archive a été vérifiée
+///
+/// Le versement va toujours à l'auteur de la proposition, quel que soit l'appelant.
+pub async fn release_bounty_proposal(
+    State(state): State<ServerState>,
+    auth: AuthInfo,
+    _scope: RequireScope<{ RequireScope::<1>::ARCHIVES_WRITE }>,
+    Path((bounty_id, proposal_id)): Path<(u64, String)>,
+) -> ApiResult<Json<BountyTransitionResponse>> {
+    let bounties = bounty_service(&state)?;
+    let caller = user_public_key(&state, &auth.user_id).await?;
+    let submission_id = Hash::from_hex(&proposal_id)
+        .map_err(|_| ApiError::validation(format!("Invalid proposal id: {}", proposal_id)))?;
+
+    let outcome = bounties.release(&caller, bounty_id, submission_id).await?;
+    Ok(Json(BountyTransitionResponse {
+        bounty: BountyDto::from(&bounties.get(bounty_id).await?),
+        amount: outcome.value,
+        events: outcome.events,
+    }))
+}
+
+/// Statut d'un bounty et de sa récompense bloquée
+pub async fn get_bounty_status(
+    State(state): State<ServerState>,
+    auth: AuthInfo,
+    Path(bounty_id): Path<u64>,
+) -> ApiResult<Json<BountyStatusResponse>> {
+    let bounty = bounty_service(&state)?.get(bounty_id).await?;
+    Ok(Json(BountyStatusResponse {
+        bounty_id,
+        status: bounty.status.clone(),
+        escrow: bounty.escrow,
+        proposals: bounty.submissions.len() as u32,
+        winner: bounty.winner.as_ref().map(|winner| winner.to_hex()),
+        deadline: bounty.deadline,
+    }))
+}
+
+// ============================================================================
+// TREASURY HANDLERS
+// ============================================================================
+
+/// Treasury exposé, indisponible s'il n'est pas rattaché au serveur
+fn treasury(state: &ServerState) -> ApiResult<Arc<tokio::sync::RwLock<Treasury>>> {
+    state.treasury.clone()
+        .ok_or_else(|| ApiError::ServiceUnavailable("Treasury is not configured".to_string()))
+}
+
+/// Rapport du treasury : fonds, propositions et état des jalons
+pub async fn get_treasury_report(
+    State(state): State<ServerState>,
+    auth: AuthInfo,
+    _scope: RequireScope<{ RequireScope::<4>::NETWORK_READ }>,
+) -> ApiResult<Json<TreasuryReportDto>> {
+    let statistics = treasury(&state)?.read().await.get_treasury_statistics();
+    Ok(Json(TreasuryReportDto::from(statistics)))
+}
+
+/// Jalons d'une proposition du treasury
+pub async fn get_proposal_milestones(
+    State(state): State<ServerState>,
+    auth: AuthInfo,
+    _scope: RequireScope<{ RequireScope::<4>::NETWORK_READ }>,
+    Path(proposal_id): Path<String>,
+) -> ApiResult<Json<Vec<MilestoneDto>>> {
+    let proposal_id = Hash::from_hex(&proposal_id)
+        .map_err(|_| ApiError::validation(format!("Invalid proposal id: {}", proposal_id)))?;
+    let milestones = treasury(&state)?.read().await
+        .milestone_report(proposal_id.clone())
+        .map_err(|_| ApiError::not_found(format!("Proposal {} not found", proposal_id.to_hex())))?;
+    Ok(Json(milestones.iter().map(MilestoneDto::from).collect()))
+}
+
 // ============================================================================
 // PLACEHOLDER HANDLERS (à implémenter)
 // ============================================================================
@@ -384,8 +1034,8 @@ pub async fn register_node(State(_): State<ServerState>, _: AuthInfo, Json(_): J
     Err(ApiError::internal("Not implemented"))
 }
 
-pub async fn get_node(State(_): State<ServerState>, _: AuthInfo, Path(_): Path<String>) -> ApiResult<Json<NodeInfo>> {
-    Err(ApiError::not_found("Node not found"))
+pub async fn get_node(State(state): State<ServerState>, _: AuthInfo, Path(node_id): Path<String>) -> ApiResult<Json<NodeInfo>> {
+    NetworkService::get_node(&state, &node_id).await.map(Json)
 }
 
 pub async fn update_node(State(_): State<ServerState>, _: AuthInfo, Path(_): Path<String>, Json(_): Json<UpdateNodeRequest>) -> ApiResult<Json<NodeInfo>> {
@@ -396,8 +1046,24 @@ pub async fn unregister_node(State(_): State<ServerState>, _: AuthInfo, Path(_):
     Ok(StatusCode::NO_CONTENT)
 }
 
-pub async fn get_node_status(State(_): State<ServerState>, _: AuthInfo, Path(_): Path<String>) -> ApiResult<Json<NodeStatusResponse>> {
-    Err(ApiError::not_found("Node not found"))
+/// Identifiant désignant le nœud qui sert l'API
+pub const LOCAL_NODE_ID: &str = "local";
+
+/// Statut d'un nœud ; `local` inclut la progression de synchronisation de ce nœud
+pub async fn get_node_status(State(state): State<ServerState>, _: AuthInfo, Path(node_id): Path<String>) -> ApiResult<Json<NodeStatusResponse>> {
+    if node_id != LOCAL_NODE_ID {
+        return Err(ApiError::not_found("Node not found"));
+    }
+
+    let (status, sync) = match &state.sync {
+        Some(sync) => {
+            let status = if sync.is_syncing().await { "syncing" } else { "online" };
+            (status, Some(sync.get_sync_progress().await))
+        }
+        None => ("online", None),
+    };
+
+    Ok(Json(NodeStatusResponse { status: status.to_string(), sync }))
 }
 
 pub async fn get_node_performance(State(_): State<ServerState>, _: AuthInfo, Path(_): Path<String>) -> ApiResult<Json<NodePerformanceResponse>> {
@@ -417,16 +1083,66 @@ pub async fn list_blocks(State(_): State<ServerState>, _: AuthInfo, ValidatedPag
     Ok(Json(PaginatedResponse::new(vec![], pagination)))
 }
 
-pub async fn get_block(State(_): State<ServerState>, _: AuthInfo, Path(_): Path<String>) -> ApiResult<Json<BlockDto>> {
-    Err(ApiError::not_found("Block not found"))
+pub async fn get_block(State(state): State<ServerState>, _: AuthInfo, Path(hash): Path<String>) -> ApiResult<Json<BlockDto>> {
+    let block_hash = crate::crypto::Hash::from_hex(&hash)
+        .map_err(|_| ApiError::validation("Invalid block hash format"))?;
+
+    match state.blockchain.get_block(&block_hash) {
+        Some(block) => Ok(Json(BlockDto::from(block))),
+        None if state.blockchain.is_pruned(&block_hash) => {
+            Err(ApiError::pruned_data(format!("Block {} body has been pruned", hash)))
+        }
+        None => Err(ApiError::not_found("Block not found")),
+    }
 }
 
 pub async fn get_block_transactions(State(_): State<ServerState>, _: AuthInfo, Path(_): Path<String>) -> ApiResult<Json<Vec<TransactionDto>>> {
     Ok(Json(vec![]))
 }
 
-pub async fn get_block_by_height(State(_): State<ServerState>, _: AuthInfo, Path(_): Path<u64>) -> ApiResult<Json<BlockDto>> {
-    Err(ApiError::not_found("Block not found"))
+pub async fn get_block_by_height(State(state): State<ServerState>, _: AuthInfo, Path(height): Path<u64>) -> ApiResult<Json<BlockDto>> {
+    match state.blockchain.get_block_by_height(height) {
+        Some(block) => Ok(Json(BlockDto::from(block))),
+        None if state.blockchain.is_pruned_height(height) => {
+            Err(ApiError::pruned_data(format!("Block at height {} body has been pruned", height)))
+        }
+        None => Err(ApiError::not_found("Block not found")),
+    }
+}
+
+/// Nombre maximal d'en-têtes renvoyés par requête
+pub const MAX_HEADERS_PER_REQUEST: u64 = 1000;
+
+/// Plage d'en-têtes demandée
+#[derive(Debug, Serialize, Deserialize)]
+pub struct HeaderRangeQuery {
+    #[serde(default)]
+    pub from: u64,
+    pub to: Option<u64>,
+}
+
+/// En-têtes de la chaîne principale, pour la vérification par un client léger
+///
+/// La plage est tronquée à `MAX_HEADERS_PER_REQUEST` en-têtes et à la tête
+/// de chaîne ; `next_from` indique où reprendre.
+pub async fn get_block_headers(
+    State(state): State<ServerState>,
+    _: AuthInfo,
+    Query(range): Query<HeaderRangeQuery>,
+) -> ApiResult<Json<BlockHeadersPage>> {
+    let tip_height = state.blockchain.height().saturating_sub(1);
+    let to = range.to.unwrap_or(tip_height).min(tip_height);
+    if range.from > to {
+        return Err(ApiError::validation(format!(
+            "Invalid header range {}..{} (tip at {})", range.from, to, tip_height
+        )));
+    }
+
+    let page_end = to.min(range.from.saturating_add(MAX_HEADERS_PER_REQUEST - 1));
+    let headers = state.blockchain.get_headers(range.from, page_end);
+    let next_from = (page_end < to).then_some(page_end + 1);
+
+    Ok(Json(BlockHeadersPage { headers, next_from, tip_height }))
 }
 
 pub async fn get_latest_block(State(_): State<ServerState>, _: AuthInfo) -> ApiResult<Json<BlockDto>> {
@@ -437,6 +1153,23 @@ pub async fn get_chain_stats(State(_): State<ServerState>, _: AuthInfo) -> ApiRe
     Err(ApiError::internal("Not implemented"))
 }
 
+/// Reçu d'une transaction incluse dans la chaîne principale
+pub async fn get_transaction_receipt(
+    State(state): State<ServerState>,
+    _: AuthInfo,
+    Path(hash): Path<String>,
+    Query(confirmation): Query<ConfirmationQuery>,
+) -> ApiResult<Json<TransactionReceiptDto>> {
+    let tx_hash = crate::crypto::Hash::from_hex(&hash)
+        .map_err(|_| ApiError::validation("Invalid transaction hash format"))?;
+
+    let receipt = state.blockchain.get_receipt(&tx_hash)
+        .map(|receipt| TransactionReceiptDto::new(&receipt, &state.blockchain))
+        .ok_or_else(|| ApiError::not_found("Transaction receipt not found"))?;
+    confirmation.check(&state.blockchain, &format!("Transaction {}", hash), Some(receipt.confirmations))?;
+    Ok(Json(receipt))
+}
+
 pub async fn list_contracts(State(_): State<ServerState>, _: AuthInfo) -> ApiResult<Json<Vec<ContractInfo>>> {
     Ok(Json(vec![]))
 }
@@ -461,83 +1194,245 @@ pub async fn get_contract_state(State(_): State<ServerState>, _: AuthInfo, Path(
     Err(ApiError::not_found("Contract not found"))
 }
 
-pub async fn list_bounties(State(_): State<ServerState>, _: AuthInfo) -> ApiResult<Json<Vec<BountyInfo>>> {
-    Ok(Json(vec![]))
+// ============================================================================
+// HELPER FUNCTIONS
+// ============================================================================
+
+fn validate_archive_id(archive_id: &str) -> ApiResult<()> {
+    ArchiveService::validate_archive_id(archive_id)
 }
 
-pub async fn create_bounty(State(_): State<ServerState>, _: AuthInfo, Json(_): Json<CreateBountyRequest>) -> ApiResult<Json<BountyInfo>> {
-    Err(ApiError::internal("Not implemented"))
+async fn check_user_quota(auth: &AuthInfo, state: &ServerState) -> ApiResult<()> {
+    // TODO: Vérifier les quotas de l'utilisateur
+    Ok(())
 }
 
-pub async fn get_bounty(State(_): State<ServerState>, _: AuthInfo, Path(_): Path<String>) -> ApiResult<Json<BountyInfo>> {
-    Err(ApiError::not_found("Bounty not found"))
+
+// ============================================================================
+// REQUEST/RESPONSE TYPES (à définir dans types.rs si pas encore fait)
+// ============================================================================
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RefreshTokenRequest {
+    pub refresh_token: String,
 }
 
-pub async fn update_bounty(State(_): State<ServerState>, _: AuthInfo, Path(_): Path<String>, Json(_): Json<UpdateBountyRequest>) -> ApiResult<Json<BountyInfo>> {
-    Err(ApiError::not_found("Bounty not found"))
+#[derive(Debug, Serialize, Deserialize)]
+pub struct RevokedSessionsResponse {
+    /// Nombre de refresh tokens révoqués
+    pub revoked: usize,
 }
 
-pub async fn cancel_bounty(State(_): State<ServerState>, _: AuthInfo, Path(_): Path<String>) -> ApiResult<StatusCode> {
-    Ok(StatusCode::NO_CONTENT)
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateApiKeyRequest {
+    pub scopes: Vec<String>,
+    #[serde(default)]
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Limite propre à la clé ; à défaut, celle de l'utilisateur s'applique
+    #[serde(default)]
+    pub rate_limit: Option<KeyRateLimit>,
 }
 
-pub async fn submit_bounty_proposal(State(_): State<ServerState>, _: AuthInfo, Path(_): Path<String>, Json(_): Json<SubmitBountyProposalRequest>) -> ApiResult<Json<BountyProposalResponse>> {
-    Err(ApiError::internal("Not implemented"))
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateApiKeyResponse {
+    /// Clé complète, affichée une seule fois
+    pub key: String,
+    #[serde(flatten)]
+    pub info: ApiKeyInfo,
 }
 
-pub async fn list_bounty_proposals(State(_): State<ServerState>, _: AuthInfo, Path(_): Path<String>) -> ApiResult<Json<Vec<BountyProposal>>> {
-    Ok(Json(vec![]))
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ApiKeyInfo {
+    pub key_id: String,
+    pub prefix: String,
+    pub scopes: Vec<String>,
+    pub rate_limit: Option<KeyRateLimit>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub expires_at: Option<chrono::DateTime<chrono::Utc>>,
+    pub revoked: bool,
 }
 
-pub async fn accept_bounty_proposal(State(_): State<ServerState>, _: AuthInfo, Path((_, _)): Path<(String, String)>) -> ApiResult<Json<BountyProposalAcceptanceResponse>> {
-    Err(ApiError::internal("Not implemented"))
+impl From<&ApiKeyRecord> for ApiKeyInfo {
+    fn from(record: &ApiKeyRecord) -> Self {
+        Self {
+            key_id: record.key_id.clone(),
+            prefix: record.prefix(),
+            scopes: record.scopes.iter().map(|s| s.as_str().to_string()).collect(),
+            rate_limit: record.rate_limit.clone(),
+            created_at: record.created_at,
+            expires_at: record.expires_at,
+            revoked: record.revoked,
+        }
+    }
 }
 
-pub async fn get_bounty_status(State(_): State<ServerState>, _: AuthInfo, Path(_): Path<String>) -> ApiResult<Json<BountyStatusResponse>> {
-    Err(ApiError::not_found("Bounty not found"))
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BountyListQuery {
+    #[serde(default = "default_bounty_status")]
+    pub status: BountyStatus,
 }
 
-// ============================================================================
-// HELPER FUNCTIONS
-// ============================================================================
+fn default_bounty_status() -> BountyStatus {
+    BountyStatus::Active
+}
 
-fn validate_create_archive_request(request: &CreateArchiveRequest) -> ApiResult<()> {
-    if request.url.is_empty() {
-        return Err(ApiError::validation("URL is required"));
-    }
-    
-    // Valide l'URL
-    if let Err(_) = url::Url::parse(&request.url) {
-        return Err(ApiError::validation("Invalid URL format"));
+#[derive(Debug, Serialize, Deserialize)]
+pub struct CreateBountyRequest {
+    /// URL à archiver
+    pub url: String,
+    /// Récompense bloquée (ARC)
+    pub reward: u64,
+    /// Qualité minimale de l'archive
+    pub min_quality: QualityLevel,
+    /// Délai de réclamation, en heures
+    pub deadline_hours: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ClaimBountyRequest {
+    pub archive_id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct UpdateBountyRequest {
+    /// ARC ajoutés à la récompense bloquée
+    #[serde(default)]
+    pub additional_reward: u64,
+    /// Heures ajoutées à la deadline
+    #[serde(default)]
+    pub extend_hours: u64,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BountyProposalDto {
+    pub proposal_id: String,
+    pub submitter: String,
+    pub archive_id: Option<String>,
+    /// Hash du manifeste crawlé par le nœud
+    pub archive_hash: String,
+    pub status: ValidationStatus,
+    pub submitted_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<&ArchiveSubmission> for BountyProposalDto {
+    fn from(submission: &ArchiveSubmission) -> Self {
+        Self {
+            proposal_id: submission.submission_id.to_hex(),
+            submitter: submission.submitter.to_hex(),
+            archive_id: submission.metadata.additional_metadata.get("archive_id").cloned(),
+            archive_hash: submission.archive_hash.to_hex(),
+            status: submission.validation_status.clone(),
+            submitted_at: submission.submitted_at,
+        }
     }
-    
-    Ok(())
 }
 
-fn validate_archive_id(archive_id: &str) -> ApiResult<()> {
-    if !archive_id.starts_with("arc_") {
-        return Err(ApiError::validation("Invalid archive ID format"));
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BountyStatusResponse {
+    pub bounty_id: u64,
+    pub status: BountyStatus,
+    pub escrow: EscrowStatus,
+    pub proposals: u32,
+    pub winner: Option<String>,
+    pub deadline: chrono::DateTime<chrono::Utc>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BountyDto {
+    pub bounty_id: u64,
+    pub url: String,
+    pub reward: u64,
+    pub min_quality: QualityLevel,
+    pub deadline: chrono::DateTime<chrono::Utc>,
+    pub status: BountyStatus,
+    pub creator: String,
+    pub winner: Option<String>,
+    pub claimed_archive: Option<String>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl From<&ArchiveBounty> for BountyDto {
+    fn from(bounty: &ArchiveBounty) -> Self {
+        Self {
+            bounty_id: bounty.bounty_id,
+            url: bounty.target_url.clone(),
+            reward: bounty.reward,
+            min_quality: bounty.required_quality.clone(),
+            deadline: bounty.deadline,
+            status: bounty.status.clone(),
+            creator: bounty.creator.to_hex(),
+            winner: bounty.winner.as_ref().map(|winner| winner.to_hex()),
+            claimed_archive: bounty.claimed_archive.clone(),
+            created_at: bounty.created_at,
+        }
     }
-    Ok(())
 }
 
-async fn check_user_quota(auth: &AuthInfo, state: &ServerState) -> ApiResult<()> {
-    // TODO: Vérifier les quotas de l'utilisateur
-    Ok(())
+#[derive(Debug, Serialize, Deserialize)]
+pub struct BountyTransitionResponse {
+    pub bounty: BountyDto,
+    /// ARC bloqués ou versés par la transition
+    pub amount: u64,
+    /// Events émis par le contrat
+    pub events: Vec<String>,
 }
 
-async fn estimate_archive_cost(request: &CreateArchiveRequest) -> ApiResult<CostEstimation> {
-    // TODO: Calculer les coûts réels
-    Ok(CostEstimation {
-        storage_cost: "0.001 ARC".to_string(),
-        processing_cost: "0.0005 ARC".to_string(),
-        total_cost: "0.0015 ARC".to_string(),
-    })
+#[derive(Debug, Serialize, Deserialize)]
+pub struct MilestoneDto {
+    pub proposal_id: String,
+    pub milestone_id: String,
+    pub name: String,
+    pub description: String,
+    pub amount: u64,
+    pub target_date: chrono::DateTime<chrono::Utc>,
+    /// Au-delà, les fonds du jalon non atteint retournent au treasury
+    pub grace_deadline: chrono::DateTime<chrono::Utc>,
+    pub completed_date: Option<chrono::DateTime<chrono::Utc>>,
+    pub status: MilestoneStatus,
 }
 
-// ============================================================================
-// REQUEST/RESPONSE TYPES (à définir dans types.rs si pas encore fait)
-// ============================================================================
+impl From<&MilestoneReport> for MilestoneDto {
+    fn from(milestone: &MilestoneReport) -> Self {
+        Self {
+            proposal_id: milestone.proposal_id.to_hex(),
+            milestone_id: milestone.milestone_id.to_hex(),
+            name: milestone.name.clone(),
+            description: milestone.description.clone(),
+            amount: milestone.amount,
+            target_date: milestone.target_date,
+            grace_deadline: milestone.grace_deadline,
+            completed_date: milestone.completed_date,
+            status: milestone.status.clone(),
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct TreasuryReportDto {
+    pub available_funds: u64,
+    pub allocated_funds: u64,
+    pub disbursed_funds: u64,
+    pub total_proposals: usize,
+    pub approved_proposals: usize,
+    pub rejected_proposals: usize,
+    pub fund_utilization_rate: f64,
+    pub milestones: Vec<MilestoneDto>,
+}
+
+impl From<TreasuryStatistics> for TreasuryReportDto {
+    fn from(statistics: TreasuryStatistics) -> Self {
+        Self {
+            available_funds: statistics.available_funds,
+            allocated_funds: statistics.allocated_funds,
+            disbursed_funds: statistics.disbursed_funds,
+            total_proposals: statistics.total_proposals,
+            approved_proposals: statistics.approved_proposals,
+            rejected_proposals: statistics.rejected_proposals,
+            fund_utilization_rate: statistics.fund_utilization_rate,
+            milestones: statistics.milestones.iter().map(MilestoneDto::from).collect(),
+        }
+    }
+}
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct ArchiveListFilters {
@@ -548,6 +1443,31 @@ pub struct ArchiveListFilters {
     pub created_before: Option<chrono::DateTime<chrono::Utc>>,
 }
 
+impl From<ArchiveListFilters> for ArchiveQuery {
+    fn from(filters: ArchiveListFilters) -> Self {
+        Self {
+            text: None,
+            status: filters.status,
+            tags: filters.tag.into_iter().collect(),
+            domain: filters.domain,
+            content_type: None,
+            created_after: filters.created_after,
+            created_before: filters.created_before,
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ArchiveVersionsQuery {
+    pub url: String,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ArchiveAtQuery {
+    pub url: String,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+}
+
 #[derive(Debug, Serialize, Deserialize)]
 pub struct UpdateArchiveRequest {
     pub metadata: Option<HashMap<String, String>>,
@@ -589,16 +1509,101 @@ pub struct ReplicaInfo {
     pub last_verified: chrono::DateTime<chrono::Utc>,
 }
 
+#[derive(Debug, Serialize, Deserialize)]
+pub struct InclusionProofResponse {
+    pub archive_id: String,
+    pub block_hash: String,
+    pub block_height: u64,
+    pub merkle_root: String,
+    pub leaf_hash: String,
+    pub algorithm: String,
+    /// Voisins de la feuille jusqu'à la racine
+    pub path: Vec<ProofStep>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+pub struct ProofStep {
+    pub hash: String,
+    /// Position du voisin : "left" ou "right"
+    pub position: String,
+}
+
 // Implement Validate for request types
 impl Validate for SearchRequest {
-    fn validate(&self) -> Result<(), String> {
-        if self.query.trim().is_empty() {
-            return Err("Query cannot be empty".to_string());
+    fn validate(&self) -> ValidationResult {
+        let limit = if self.limit > 100 {
+            Err(vec![ValidationError::new("limit", "out_of_range", "Limit cannot exceed 100")])
+        } else {
+            Ok(())
+        };
+        collect_validation([query_required("query", &self.query), limit])
+    }
+}
+
+/// Erreur `required` si la requête de recherche est vide
+fn query_required(field: &str, query: &str) -> ValidationResult {
+    if query.trim().is_empty() {
+        return Err(vec![ValidationError::new(field, "required", "Query cannot be empty")]);
+    }
+    Ok(())
+}
+
+/// Paramètres de `GET /search`, la pagination étant extraite à part
+#[derive(Debug, Serialize, Deserialize)]
+pub struct SearchParams {
+    /// Termes recherchés
+    pub q: String,
+    /// Objet JSON `SearchFilters`
+    pub filters: Option<String>,
+}
+
+impl SearchParams {
+    /// Décode les filtres
+    pub fn filters(&self) -> ApiResult<SearchFilters> {
+        match &self.filters {
+            Some(filters) => serde_json::from_str(filters)
+                .map_err(|e| ApiError::validation(format!("Invalid search filters: {}", e))),
+            None => Ok(SearchFilters::default()),
         }
-        if self.limit > 100 {
-            return Err("Limit cannot exceed 100".to_string());
+    }
+}
+
+impl Validate for SearchParams {
+    fn validate(&self) -> ValidationResult {
+        let mut errors = Vec::new();
+        match self.filters() {
+            Ok(filters) => {
+                if let Some(content_type) = filters.content_type.filter(|ct| !SearchValidator::is_valid_content_type(ct)) {
+                    errors.push(ValidationError::with_value(
+                        "filters.content_type",
+                        "unsupported",
+                        "Unsupported content type",
+                        serde_json::Value::String(content_type),
+                    ));
+                }
+                if filters.date_range.as_ref().map_or(false, |range| range.start > range.end) {
+                    errors.push(ValidationError::new("filters.date_range", "invalid_range", "Start date must be before end date"));
+                }
+            }
+            Err(e) => errors.push(ValidationError::new("filters", "invalid_format", &e.to_string())),
+        }
+
+        let filters = if errors.is_empty() { Ok(()) } else { Err(errors) };
+        collect_validation([query_required("q", &self.q), filters])
+    }
+}
+
+impl From<SearchFilters> for ArchiveQuery {
+    fn from(filters: SearchFilters) -> Self {
+        Self {
+            text: None,
+            status: None,
+            tags: filters.tags,
+            domain: filters.domain,
+            content_type: filters.content_type,
+            created_after: filters.date_range.as_ref().map(|range| range.start),
+            created_before: filters.date_range.map(|range| range.end),
         }
-        Ok(())
     }
 }
 
@@ -610,11 +1615,8 @@ pub struct AdvancedSearchRequest {
 }
 
 impl Validate for AdvancedSearchRequest {
-    fn validate(&self) -> Result<(), String> {
-        if self.query.trim().is_empty() {
-            return Err("Query cannot be empty".to_string());
-        }
-        Ok(())
+    fn validate(&self) -> ValidationResult {
+        query_required("query", &self.query)
     }
 }
 
@@ -633,7 +1635,7 @@ impl Validate for AdvancedSearchRequest {
 // Placeholder types pour les autres endpoints (à compléter)
 #[derive(Debug, Serialize, Deserialize)] pub struct RegisterNodeRequest { pub node_id: String }
 #[derive(Debug, Serialize, Deserialize)] pub struct UpdateNodeRequest { pub status: Option<String> }
-#[derive(Debug, Serialize, Deserialize)] pub struct NodeStatusResponse { pub status: String }
+#[derive(Debug, Serialize, Deserialize)] pub struct NodeStatusResponse { pub status: String, #[serde(default, skip_serializing_if = "Option::is_none")] pub sync: Option<crate::api::p2p::SyncProgress> }
 #[derive(Debug, Serialize, Deserialize)] pub struct NodePerformanceResponse { pub performance: HashMap<String, f64> }
 #[derive(Debug, Serialize, Deserialize)] pub struct NodeStorageResponse { pub storage: HashMap<String, u64> }
 #[derive(Debug, Serialize, Deserialize)] pub struct PingResponse { pub latency_ms: u64, pub timestamp: chrono::DateTime<chrono::Utc> }
@@ -645,11 +1647,790 @@ impl Validate for AdvancedSearchRequest {
 #[derive(Debug, Serialize, Deserialize)] pub struct ContractCallResponse { pub result: serde_json::Value }
 #[derive(Debug, Serialize, Deserialize)] pub struct ContractEvent { pub event: String }
 #[derive(Debug, Serialize, Deserialize)] pub struct ContractStateResponse { pub state: HashMap<String, serde_json::Value> }
-#[derive(Debug, Serialize, Deserialize)] pub struct BountyInfo { pub id: String }
-#[derive(Debug, Serialize, Deserialize)] pub struct CreateBountyRequest { pub title: String }
-#[derive(Debug, Serialize, Deserialize)] pub struct UpdateBountyRequest { pub title: Option<String> }
-#[derive(Debug, Serialize, Deserialize)] pub struct SubmitBountyProposalRequest { pub proposal: String }
-#[derive(Debug, Serialize, Deserialize)] pub struct BountyProposalResponse { pub proposal_id: String }
-#[derive(Debug, Serialize, Deserialize)] pub struct BountyProposal { pub id: String }
-#[derive(Debug, Serialize, Deserialize)] pub struct BountyProposalAcceptanceResponse { pub accepted: bool }
-#[derive(Debug, Serialize, Deserialize)] pub struct BountyStatusResponse { pub status: String }
\ No newline at end of file
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use axum::{extract::Request, middleware::Next, routing::get, Router};
+    use tower::ServiceExt;
+
+    use crate::api::{auth::ApiScope, service::{ContentFetcher, ContentService}};
+    use crate::crypto::Hash;
+    use crate::nodes::gateway::{CacheConfig, CacheLayer};
+    use crate::storage::{NodeStatus, NodeType, StorageNodeInfo};
+
+    /// Nœud de stockage simulé comptant les récupérations
+    struct MockFetcher {
+        content: Vec<u8>,
+        calls: AtomicUsize,
+    }
+
+    #[async_trait::async_trait]
+    impl ContentFetcher for MockFetcher {
+        async fn fetch(&self, _node: &StorageNodeInfo, _content_hash: &Hash) -> crate::error::Result<Vec<u8>> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(self.content.clone())
+        }
+    }
+
+    fn storage_node() -> StorageNodeInfo {
+        StorageNodeInfo {
+            node_id: crate::consensus::NodeId::from(crate::crypto::compute_blake3(b"storage-node")),
+            node_type: NodeType::FullArchive,
+            region: "eu-west-1".to_string(),
+            total_capacity: 1_000_000_000,
+            used_capacity: 0,
+            supported_storage_types: Vec::new(),
+            available_bandwidth: 1_000_000,
+            average_latency: 20,
+            reliability_score: 0.99,
+            last_seen: chrono::Utc::now(),
+            status: NodeStatus::Active,
+        }
+    }
+
+    fn auth_info(scopes: Vec<ApiScope>) -> AuthInfo {
+        let claims = crate::api::auth::JwtClaims {
+            sub: "user123".to_string(),
+            iss: "archivechain".to_string(),
+            aud: "archivechain-api".to_string(),
+            exp: u64::MAX,
+            iat: 0,
+            nbf: 0,
+            jti: "test".to_string(),
+            scope: scopes.iter().map(|s| s.as_str().to_string()).collect(),
+            node_id: None,
+            rate_limit: Default::default(),
+            user_metadata: Default::default(),
+        };
+        AuthInfo { user_id: claims.sub.clone(), claims, scopes }
+    }
+
+    fn create_test_state() -> ServerState {
+        let blockchain = Arc::new(crate::Blockchain::new(crate::BlockchainConfig::default()).unwrap());
+        let auth_service = Arc::new(crate::api::auth::AuthService::new(crate::api::auth::AuthConfig::default()).unwrap());
+        let user_manager = Arc::new(tokio::sync::RwLock::new(crate::api::auth::UserManager::new()));
+        ServerState::new(blockchain, auth_service, user_manager, crate::api::ApiConfig::default())
+    }
+
+    fn app(state: ServerState, scopes: Vec<ApiScope>) -> Router {
+        Router::new()
+            .route("/archives/{archive_id}/content", get(get_archive_content))
+            .layer(axum::middleware::from_fn(move |mut req: Request, next: Next| {
+                req.extensions_mut().insert(auth_info(scopes.clone()));
+                next.run(req)
+            }))
+            .with_state(state)
+    }
+
+    async fn fetch(app: &Router, archive_id: &str, range: Option<&str>) -> (StatusCode, HeaderMap, Bytes) {
+        let mut request = axum::http::Request::builder().uri(format!("/archives/{}/content", archive_id));
+        if let Some(range) = range {
+            request = request.header(header::RANGE, range);
+        }
+        let response = app.clone().oneshot(request.body(Body::empty()).unwrap()).await.unwrap();
+        let status = response.status();
+        let headers = response.headers().clone();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, headers, body)
+    }
+
+    #[tokio::test]
+    async fn test_delete_archive_requires_ownership() {
+        let state = create_test_state();
+        let request: CreateArchiveRequest = serde_json::from_value(serde_json::json!({ "url": "https://example.com" })).unwrap();
+        let foreign = state.archives.create_archive("mallory", request.clone()).await.unwrap().archive.archive_id;
+        let own = state.archives.create_archive("user123", request).await.unwrap().archive.archive_id;
+
+        let router = Router::new()
+            .route("/archives/{archive_id}", axum::routing::delete(delete_archive))
+            .layer(axum::middleware::from_fn(|mut req: Request, next: Next| {
+                req.extensions_mut().insert(auth_info(vec![ApiScope::ArchivesDelete]));
+                next.run(req)
+            }))
+            .with_state(state.clone());
+        let delete = |archive_id: &str| {
+            let request = axum::http::Request::builder().method("DELETE").uri(format!("/archives/{}", archive_id)).body(Body::empty()).unwrap();
+            router.clone().oneshot(request)
+        };
+
+        assert_eq!(delete(&foreign).await.unwrap().status(), StatusCode::FORBIDDEN);
+        assert_eq!(state.archives.get_archive(&foreign).await.unwrap().archive.status, ArchiveStatus::Pending);
+
+        assert_eq!(delete(&own).await.unwrap().status(), StatusCode::NO_CONTENT);
+        assert_eq!(state.archives.get_archive(&own).await.unwrap().archive.status, ArchiveStatus::Cancelled);
+    }
+
+    #[tokio::test]
+    async fn test_local_node_status_reports_sync_progress() {
+        let state = create_test_state();
+        let sync = Arc::new(crate::api::p2p::SyncService::new(crate::api::p2p::P2PConfig::default(), state.blockchain.clone()));
+        let state = state.with_sync_service(sync.clone());
+
+        let Json(response) = get_node_status(State(state.clone()), auth_info(vec![]), Path(LOCAL_NODE_ID.to_string())).await.unwrap();
+        assert_eq!(response.status, "online");
+        assert_eq!(response.sync.unwrap().phase, crate::api::p2p::SyncPhase::Idle);
+
+        sync.start_sync("peer_123".to_string(), 1, Some(100)).await.unwrap();
+        let Json(response) = get_node_status(State(state.clone()), auth_info(vec![]), Path(LOCAL_NODE_ID.to_string())).await.unwrap();
+        assert_eq!(response.status, "syncing");
+
+        assert!(get_node_status(State(state), auth_info(vec![]), Path("node_42".to_string())).await.is_err());
+    }
+
+    #[test]
+    fn test_parse_range() {
+        assert_eq!(parse_range(None, 100), RangeRequest::Full);
+        assert_eq!(parse_range(Some("bytes=0-9"), 100), RangeRequest::Partial { start: 0, end: 9 });
+        assert_eq!(parse_range(Some("bytes=90-"), 100), RangeRequest::Partial { start: 90, end: 99 });
+        assert_eq!(parse_range(Some("bytes=-10"), 100), RangeRequest::Partial { start: 90, end: 99 });
+        assert_eq!(parse_range(Some("bytes=50-500"), 100), RangeRequest::Partial { start: 50, end: 99 });
+        assert_eq!(parse_range(Some("bytes=100-"), 100), RangeRequest::Unsatisfiable);
+        assert_eq!(parse_range(Some("bytes=0-1,5-9"), 100), RangeRequest::Full);
+        assert_eq!(parse_range(Some("bytes=9-0"), 100), RangeRequest::Full);
+        assert_eq!(parse_range(Some("items=0-9"), 100), RangeRequest::Full);
+    }
+
+    #[tokio::test]
+    async fn test_get_archive_content_with_ranges_and_cache() {
+        let content: Vec<u8> = (0..200_000u32).map(|i| (i % 251) as u8).collect();
+        let fetcher = Arc::new(MockFetcher { content: content.clone(), calls: AtomicUsize::new(0) });
+        let cache = Arc::new(CacheLayer::new(CacheConfig::default()));
+        let service = Arc::new(ContentService::new(cache.clone(), fetcher.clone()));
+        service.update_nodes(vec![storage_node()]).await;
+
+        let state = create_test_state()
+            .with_content_service(service);
+
+        let request = CreateArchiveRequest {
+            url: "https://example.com/video.mp4".to_string(),
+            metadata: HashMap::new(),
+            options: ArchiveOptions::default(),
+            content: None,
+            dry_run: false,
+        };
+        let archive_id = state.archives.submit_with_content("user123", request, Some(&content)).await
+            .unwrap().record.archive.archive_id;
+        let router = app(state.clone(), vec![ApiScope::ArchivesRead]);
+
+        // Récupération complète depuis le nœud de stockage
+        let (status, headers, body) = fetch(&router, &archive_id, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(headers["x-cache"], "MISS");
+        assert_eq!(headers[header::ACCEPT_RANGES], "bytes");
+        assert_eq!(body.as_ref(), content.as_slice());
+
+        // Le cache est alimenté en tâche de fond
+        for _ in 0..100 {
+            if cache.get_metrics().await.current_cache_size > 0 {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+
+        // Deux plages qui se chevauchent
+        let (status, headers, body) = fetch(&router, &archive_id, Some("bytes=0-99999")).await;
+        assert_eq!(status, StatusCode::PARTIAL_CONTENT);
+        assert_eq!(headers[header::CONTENT_RANGE], "bytes 0-99999/200000");
+        assert_eq!(body.as_ref(), &content[0..100_000]);
+
+        let (status, headers, body) = fetch(&router, &archive_id, Some("bytes=50000-149999")).await;
+        assert_eq!(status, StatusCode::PARTIAL_CONTENT);
+        assert_eq!(headers[header::CONTENT_RANGE], "bytes 50000-149999/200000");
+        assert_eq!(body.as_ref(), &content[50_000..150_000]);
+
+        // Seconde récupération complète servie par le cache
+        let (status, headers, body) = fetch(&router, &archive_id, None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(headers["x-cache"], "HIT");
+        assert_eq!(body.as_ref(), content.as_slice());
+        assert_eq!(fetcher.calls.load(Ordering::SeqCst), 1);
+
+        let (status, headers, _) = fetch(&router, &archive_id, Some("bytes=300000-")).await;
+        assert_eq!(status, StatusCode::RANGE_NOT_SATISFIABLE);
+        assert_eq!(headers[header::CONTENT_RANGE], "bytes */200000");
+
+        // Le scope `archives:read` est requis
+        let (status, _, _) = fetch(&app(state, vec![ApiScope::SearchRead]), &archive_id, None).await;
+        assert_eq!(status, StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn test_api_key_lifecycle_scopes_and_rate_limit() {
+        use axum::routing::{delete, post};
+        use crate::api::middleware::{auth_middleware, rate_limit_middleware, MiddlewareState, RateLimitConfig, RateLimiters, API_KEY_HEADER};
+
+        let state = create_test_state();
+        let auth_service = state.auth_service.clone();
+        let user_manager = state.user_manager.clone();
+        let middleware_state = MiddlewareState {
+            auth_service: auth_service.clone(),
+            user_manager: user_manager.clone(),
+            rate_limiters: Arc::new(RateLimiters::new(&RateLimitConfig::default())),
+            config: Default::default(),
+        };
+
+        let router = Router::new()
+            .route("/auth/keys", post(create_api_key).get(list_api_keys))
+            .route("/auth/keys/{key_id}", delete(revoke_api_key))
+            .route("/archives", post(create_archive).get(list_archives))
+            .layer(axum::middleware::from_fn_with_state(middleware_state.clone(), auth_middleware))
+            .layer(axum::middleware::from_fn_with_state(middleware_state, rate_limit_middleware))
+            .with_state(state);
+
+        let token = auth_service.generate_token(
+            &mut *user_manager.write().await,
+            "alice",
+            vec![ApiScope::ArchivesRead, ApiScope::ArchivesWrite],
+            None,
+            None,
+        ).unwrap().token;
+        let bearer = format!("Bearer {}", token);
+
+        let call = |method: &str, uri: &str, credential: (&'static str, String), body: Option<serde_json::Value>| {
+            let request = axum::http::Request::builder()
+                .method(method)
+                .uri(uri)
+                .header(credential.0, credential.1)
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(body.map_or_else(Body::empty, |body| Body::from(body.to_string())))
+                .unwrap();
+            router.clone().oneshot(request)
+        };
+
+        // Clé en lecture seule limitée à 3 requêtes par minute
+        let response = call("POST", "/auth/keys", ("authorization", bearer.clone()), Some(serde_json::json!({
+            "scopes": ["archives:read"],
+            "rate_limit": { "requests_per_second": 100, "requests_per_minute": 3, "requests_per_hour": 1000, "burst_allowance": 0 },
+        }))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let created: CreateApiKeyResponse = serde_json::from_slice(&body).unwrap();
+        assert!(created.key.starts_with(&created.info.prefix));
+
+        // Un scope non détenu par l'appelant ne peut pas être accordé
+        let response = call("POST", "/auth/keys", ("authorization", bearer.clone()), Some(serde_json::json!({ "scopes": ["node:manage"] }))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        // Ni une limite supérieure à celle de l'appelant
+        let response = call("POST", "/auth/keys", ("authorization", bearer.clone()), Some(serde_json::json!({
+            "scopes": ["archives:read"],
+            "rate_limit": { "requests_per_second": 100, "requests_per_minute": 100, "requests_per_hour": 5000, "burst_allowance": 0 },
+        }))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        // Une clé API ne gère pas les clés de son propriétaire ; sans limite
+        // demandée, elle reçoit celle de l'appelant
+        let response = call("POST", "/auth/keys", ("authorization", bearer.clone()), Some(serde_json::json!({ "scopes": ["archives:read"] }))).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let unlimited: CreateApiKeyResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(unlimited.info.rate_limit.as_ref().map(|limit| limit.requests_per_hour), Some(1000));
+        let response = call("POST", "/auth/keys", (API_KEY_HEADER, unlimited.key.clone()), Some(serde_json::json!({ "scopes": ["archives:read"] }))).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        let response = call("GET", "/auth/keys", (API_KEY_HEADER, unlimited.key.clone()), None).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        let uri = format!("/auth/keys/{}", unlimited.info.key_id);
+        let response = call("DELETE", &uri, (API_KEY_HEADER, unlimited.key.clone()), None).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        let response = call("DELETE", &uri, ("authorization", bearer.clone()), None).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+        let archive = serde_json::json!({ "url": "https://example.com" });
+        let response = call("POST", "/archives", (API_KEY_HEADER, created.key.clone()), Some(archive.clone())).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        for _ in 0..2 {
+            let response = call("GET", "/archives?page=1&limit=10", (API_KEY_HEADER, created.key.clone()), None).await.unwrap();
+            assert_eq!(response.status(), StatusCode::OK);
+        }
+
+        // La limite de la clé est atteinte bien avant celle de l'IP (60/min)
+        let response = call("GET", "/archives?page=1&limit=10", (API_KEY_HEADER, created.key.clone()), None).await.unwrap();
+        assert_eq!(response.status(), StatusCode::TOO_MANY_REQUESTS);
+        let response = call("POST", "/archives", ("authorization", bearer.clone()), Some(archive)).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        // La liste n'expose que le préfixe
+        let response = call("GET", "/auth/keys", ("authorization", bearer.clone()), None).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let keys: Vec<ApiKeyInfo> = serde_json::from_slice(&body).unwrap();
+        assert_eq!(keys.len(), 2);
+        assert!(keys.iter().any(|key| key.prefix == created.info.prefix && !key.revoked));
+        assert!(!String::from_utf8_lossy(&body).contains(&created.key));
+
+        // Un autre utilisateur ne voit ni ne révoque la clé
+        let other = auth_service.generate_token(
+            &mut *user_manager.write().await,
+            "bob",
+            vec![ApiScope::ArchivesRead],
+            None,
+            None,
+        ).unwrap().token;
+        let other = format!("Bearer {}", other);
+        let response = call("GET", "/auth/keys", ("authorization", other.clone()), None).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert!(serde_json::from_slice::<Vec<ApiKeyInfo>>(&body).unwrap().is_empty());
+        let uri = format!("/auth/keys/{}", created.info.key_id);
+        let response = call("DELETE", &uri, ("authorization", other), None).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let response = call("DELETE", &uri, ("authorization", bearer.clone()), None).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+        let response = call("GET", "/archives?page=1&limit=10", (API_KEY_HEADER, created.key), None).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    #[tokio::test]
+    async fn test_search_archives_ranked_filtered_and_paginated() {
+        let state = create_test_state();
+
+        for i in 0..30 {
+            let tags = if i % 3 == 0 { r#"["science"]"# } else { r#"["sport"]"# };
+            let request = CreateArchiveRequest {
+                url: format!("https://site{}.example.com/{}", i % 2, i),
+                metadata: HashMap::from([
+                    ("title".to_string(), format!("Daily report {}", i)),
+                    ("tags".to_string(), tags.to_string()),
+                ]),
+                options: ArchiveOptions::default(),
+                content: None,
+                dry_run: false,
+            };
+            let record = state.archives.create_archive("user123", request).await.unwrap();
+            state.archives.set_popularity(&record.archive.archive_id, i).await.unwrap();
+        }
+
+        let router = Router::new()
+            .route("/search", get(search_archives))
+            .layer(axum::middleware::from_fn(|mut req: Request, next: Next| {
+                req.extensions_mut().insert(auth_info(vec![ApiScope::SearchRead]));
+                next.run(req)
+            }))
+            .with_state(state);
+        let search = |pairs: &[(&str, &str)]| {
+            let query = url::form_urlencoded::Serializer::new(String::new()).extend_pairs(pairs).finish();
+            let request = axum::http::Request::builder().uri(format!("/search?{}", query)).body(Body::empty()).unwrap();
+            router.clone().oneshot(request)
+        };
+
+        let response = search(&[("q", "repo"), ("filters", r#"{"tags":["science"]}"#), ("page", "2"), ("limit", "4")]).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let response: SearchResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(response.total_results, 10);
+        assert_eq!(response.facets.tags["science"], 10);
+        assert_eq!(response.facets.domains["site0.example.com"], 5);
+        // Deuxième page des archives scientifiques, les plus populaires d'abord
+        let titles: Vec<_> = response.results.iter().map(|r| r.title.clone().unwrap()).collect();
+        assert_eq!(titles, vec!["Daily report 15", "Daily report 12", "Daily report 9", "Daily report 6"]);
+        assert!(response.pagination.has_next);
+
+        let response = search(&[("q", "report site1")]).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let response: SearchResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(response.total_results, 15);
+
+        let response = search(&[("q", "report"), ("filters", "not json")]).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        // Toutes les erreurs de champ sont rapportées ensemble
+        let response = search(&[("q", " "), ("filters", r#"{"content_type":"invalid/type"}"#)]).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], crate::api::error::PROBLEM_JSON_CONTENT_TYPE);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let problem: crate::api::error::ProblemDetails = serde_json::from_slice(&body).unwrap();
+        assert_eq!(problem.code, "VALIDATION_FAILED");
+        let fields: Vec<_> = problem.errors.iter().map(|e| (e.field.as_str(), e.code.as_deref())).collect();
+        assert_eq!(fields, vec![("q", Some("required")), ("filters.content_type", Some("unsupported"))]);
+    }
+
+    /// Nœud de stockage simulé servant chaque contenu par son hash
+    struct HashedFetcher {
+        contents: HashMap<Hash, Vec<u8>>,
+    }
+
+    #[async_trait::async_trait]
+    impl ContentFetcher for HashedFetcher {
+        async fn fetch(&self, _node: &StorageNodeInfo, content_hash: &Hash) -> crate::error::Result<Vec<u8>> {
+            self.contents.get(content_hash).cloned().ok_or_else(|| crate::error::CoreError::NotFound {
+                message: format!("Contenu {} absent", content_hash.to_hex()),
+            })
+        }
+    }
+
+    #[tokio::test]
+    async fn test_archives_by_url_lists_versions_newest_first() {
+        let captures = [
+            ("http://Example.com/page/", b"<html>v1</html>".to_vec(), "Page v1"),
+            ("http://example.com/page", b"<html>version 2</html>".to_vec(), "Page v1"),
+            ("http://example.com/page#top", b"<html>v3</html>".to_vec(), "Page v3"),
+        ];
+        let fetcher = Arc::new(HashedFetcher {
+            contents: captures.iter()
+                .map(|(_, content, _)| (crate::crypto::compute_blake3(content), content.clone()))
+                .collect(),
+        });
+        let cache = Arc::new(CacheLayer::new(CacheConfig::default()));
+        let service = Arc::new(ContentService::new(cache, fetcher));
+        service.update_nodes(vec![storage_node()]).await;
+
+        let state = create_test_state()
+            .with_content_service(service);
+
+        let mut archive_ids = Vec::new();
+        for (url, content, title) in &captures {
+            let request = CreateArchiveRequest {
+                url: url.to_string(),
+                metadata: HashMap::from([("title".to_string(), title.to_string())]),
+                options: ArchiveOptions::default(),
+                content: None,
+                dry_run: false,
+            };
+            let submission = state.archives.submit_with_content("user123", request, Some(content)).await.unwrap();
+            assert!(!submission.deduplicated);
+            archive_ids.push(submission.record.archive.archive_id);
+        }
+
+        let router = Router::new()
+            .route("/archives/by-url", get(get_archives_by_url))
+            .route("/archives/{archive_id}/content", get(get_archive_content))
+            .layer(axum::middleware::from_fn(|mut req: Request, next: Next| {
+                req.extensions_mut().insert(auth_info(vec![ApiScope::ArchivesRead]));
+                next.run(req)
+            }))
+            .with_state(state);
+        let by_url = |url: &str, page: &str| {
+            let query = url::form_urlencoded::Serializer::new(String::new())
+                .extend_pairs([("url", url), ("page", page), ("limit", "2")])
+                .finish();
+            let request = axum::http::Request::builder().uri(format!("/archives/by-url?{}", query)).body(Body::empty()).unwrap();
+            router.clone().oneshot(request)
+        };
+
+        // Les variantes de l'URL partagent une seule chronologie, la plus récente en tête
+        let response = by_url("HTTP://EXAMPLE.COM/page/", "1").await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let first: PaginatedResponse<ArchiveVersionDto> = serde_json::from_slice(&body).unwrap();
+        assert_eq!(first.pagination.total, 3);
+        assert!(first.pagination.has_next);
+
+        let response = by_url("http://example.com/page", "2").await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let second: PaginatedResponse<ArchiveVersionDto> = serde_json::from_slice(&body).unwrap();
+        let versions: Vec<_> = first.data.into_iter().chain(second.data).collect();
+        let ids: Vec<_> = versions.iter().map(|v| v.archive_id.clone()).collect();
+        assert_eq!(ids, archive_ids.iter().rev().cloned().collect::<Vec<_>>());
+
+        let latest = &versions[0];
+        assert_eq!(latest.previous_archive_id.as_deref(), Some(archive_ids[1].as_str()));
+        let changes = latest.changes.as_ref().unwrap();
+        assert_eq!(changes.size_delta, Some(-7));
+        assert!(changes.title_changed);
+        assert_eq!(changes.previous_title.as_deref(), Some("Page v1"));
+        assert!(!versions[1].changes.as_ref().unwrap().title_changed);
+        assert!(versions[2].changes.is_none());
+
+        // Le contenu de la plus ancienne version reste accessible
+        let oldest = &versions[2];
+        assert_eq!(oldest.size, Some(captures[0].1.len() as u64));
+        let request = axum::http::Request::builder()
+            .uri(format!("/archives/{}/content", oldest.archive_id))
+            .body(Body::empty())
+            .unwrap();
+        let response = router.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        assert_eq!(body.as_ref(), captures[0].1.as_slice());
+    }
+
+    #[tokio::test]
+    async fn test_refresh_token_revocation_endpoints() {
+        use axum::routing::{delete, post};
+
+        let state = create_test_state();
+        let auth_service = state.auth_service.clone();
+        let user_manager = state.user_manager.clone();
+
+        let mut sessions = Vec::new();
+        for _ in 0..3 {
+            let mut users = user_manager.write().await;
+            sessions.push(auth_service.generate_token(&mut users, "user123", vec![ApiScope::ArchivesRead], None, None).unwrap());
+        }
+
+        let router = Router::new()
+            .route("/auth/refresh", post(refresh_auth_token))
+            .route("/auth/revoke", post(revoke_auth_token))
+            .route("/auth/sessions", delete(revoke_sessions).layer(axum::middleware::from_fn(|mut req: Request, next: Next| {
+                req.extensions_mut().insert(auth_info(vec![ApiScope::ArchivesRead]));
+                next.run(req)
+            })))
+            .with_state(state);
+        let post_token = |path: &str, refresh_token: &str| {
+            let request = axum::http::Request::builder()
+                .method("POST")
+                .uri(path)
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(serde_json::json!({ "refresh_token": refresh_token }).to_string()))
+                .unwrap();
+            router.clone().oneshot(request)
+        };
+
+        // La rotation renvoie un nouveau couple et invalide l'ancien refresh token
+        let response = post_token("/auth/refresh", &sessions[0].refresh_token).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let rotated: TokenInfo = serde_json::from_slice(&body).unwrap();
+        assert_ne!(rotated.refresh_token, sessions[0].refresh_token);
+
+        // Un token révoqué ne compte plus parmi les sessions ouvertes
+        let response = post_token("/auth/revoke", &sessions[1].refresh_token).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NO_CONTENT);
+
+        let request = axum::http::Request::builder().method("DELETE").uri("/auth/sessions").body(Body::empty()).unwrap();
+        let response = router.clone().oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let closed: RevokedSessionsResponse = serde_json::from_slice(&body).unwrap();
+        assert_eq!(closed.revoked, 2);
+
+        for refresh_token in [&sessions[0].refresh_token, &sessions[1].refresh_token, &sessions[2].refresh_token, &rotated.refresh_token] {
+            let response = post_token("/auth/refresh", refresh_token).await.unwrap();
+            assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_bounty_endpoints_escrow_and_pay_claims() {
+        use axum::routing::{post, put};
+        use crate::storage::{ArchiveManifest, ManifestEntry};
+        use crate::token::ARCToken;
+
+        let creator = crate::crypto::generate_keypair().unwrap().public_key().clone();
+        let archiver = crate::crypto::generate_keypair().unwrap().public_key().clone();
+        let escrow = crate::crypto::generate_keypair().unwrap().public_key().clone();
+
+        let state = create_test_state();
+        for (user_id, key) in [("creator", &creator), ("archiver", &archiver)] {
+            let scopes = std::collections::HashSet::from([ApiScope::ArchivesWrite]);
+            state.user_manager.write().await.create_user(user_id.to_string(), Some(key.clone()), scopes, None).unwrap();
+        }
+        let token = Arc::new(tokio::sync::RwLock::new(ARCToken::new()));
+        token.write().await.mint(&creator, 3000, Hash::zero()).unwrap();
+
+        // Archive crawlée complète sur deux niveaux
+        let manifest = ArchiveManifest {
+            root_url: "https://example.com/page".to_string(),
+            entries: [0, 1].into_iter().map(|depth| ManifestEntry {
+                url: format!("https://example.com/page/{}", depth),
+                depth,
+                content_hash: crate::crypto::compute_blake3(&[depth as u8]),
+                size: 512,
+                content_type: "text/html".to_string(),
+            }).collect(),
+            failures: Vec::new(),
+            skipped: Vec::new(),
+            total_size: 1024,
+            created_at: chrono::Utc::now(),
+        };
+        let state = state
+            .with_bounty_service(Arc::new(BountyService::new(token.clone(), escrow.clone())));
+        let request = CreateArchiveRequest {
+            url: "https://example.com/page".to_string(),
+            metadata: HashMap::new(),
+            options: ArchiveOptions::default(),
+            content: None,
+            dry_run: false,
+        };
+        // Le client téléverse un manifeste forgé : il ne compte pas
+        let forged = serde_json::to_vec(&manifest).unwrap();
+        let archive_id = state.archives.submit_with_content("archiver", request, Some(&forged)).await.unwrap()
+            .record.archive.archive_id;
+        let archives = state.archives.clone();
+
+        // L'utilisateur est choisi par l'en-tête `x-user`
+        let router = Router::new()
+            .route("/bounties", get(list_bounties).post(create_bounty))
+            .route("/bounties/{bounty_id}", put(update_bounty).delete(cancel_bounty))
+            .route("/bounties/{bounty_id}/claim", post(claim_bounty))
+            .route("/bounties/{bounty_id}/refund", post(refund_bounty))
+            .route("/bounties/{bounty_id}/submit", post(submit_bounty_proposal))
+            .layer(axum::middleware::from_fn(|mut req: Request, next: Next| {
+                let user = req.headers().get("x-user").and_then(|h| h.to_str().ok()).unwrap_or("creator").to_string();
+                let mut auth = auth_info(vec![ApiScope::ArchivesRead, ApiScope::ArchivesWrite]);
+                auth.claims.sub = user.clone();
+                auth.user_id = user;
+                req.extensions_mut().insert(auth);
+                next.run(req)
+            }))
+            .with_state(state);
+        let call_as = |method: &str, user: &str, path: &str, body: serde_json::Value| {
+            let request = axum::http::Request::builder()
+                .method(method)
+                .uri(path)
+                .header("x-user", user)
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(body.to_string()))
+                .unwrap();
+            router.clone().oneshot(request)
+        };
+        let post_as = |user: &str, path: &str, body: serde_json::Value| call_as("POST", user, path, body);
+        let transition = |body: Bytes| serde_json::from_slice::<BountyTransitionResponse>(&body).unwrap();
+
+        // La récompense quitte le solde du créateur à la création
+        let create = serde_json::json!({ "url": "https://example.com/page/", "reward": 1000, "min_quality": "Standard", "deadline_hours": 24 });
+        for expected_id in [1, 2] {
+            let response = post_as("creator", "/bounties", create.clone()).await.unwrap();
+            assert_eq!(response.status(), StatusCode::CREATED);
+            let created = transition(axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap());
+            assert_eq!(created.bounty.bounty_id, expected_id);
+            assert_eq!(created.events, vec!["BountyCreated"]);
+        }
+        let mut unfunded = create.clone();
+        unfunded["reward"] = serde_json::json!(5000);
+        let response = post_as("creator", "/bounties", unfunded).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(token.read().await.balance_of(&creator), 1000);
+        assert_eq!(token.read().await.balance_of(&escrow), 2000);
+
+        // Seul un demandeur de l'archive peut la présenter
+        let claim = serde_json::json!({ "archive_id": archive_id });
+        let response = post_as("creator", "/bounties/1/claim", claim.clone()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::FORBIDDEN);
+
+        // Sans crawl par le nœud, le manifeste téléversé n'ouvre pas droit à la récompense
+        let response = post_as("archiver", "/bounties/1/claim", claim.clone()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+        assert_eq!(token.read().await.balance_of(&archiver), 0);
+        let capture = archives.record_crawl(&archive_id, &manifest).await.unwrap();
+        assert_eq!(capture.quality_level(), QualityLevel::Premium);
+
+        let response = post_as("archiver", "/bounties/1/claim", claim.clone()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let claimed = transition(axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap());
+        assert_eq!(claimed.amount, 1000);
+        assert_eq!(claimed.events, vec!["BountyClaimed", "BountyCompleted"]);
+        assert_eq!(claimed.bounty.status, BountyStatus::Completed);
+        assert_eq!(claimed.bounty.claimed_archive, Some(archive_id));
+        assert_eq!(token.read().await.balance_of(&archiver), 1000);
+
+        // Ni seconde réclamation, ni remboursement avant la deadline
+        for path in ["/bounties/1/claim", "/bounties/2/refund"] {
+            let response = post_as("archiver", path, claim.clone()).await.unwrap();
+            assert_eq!(response.status(), StatusCode::CONFLICT);
+        }
+        assert_eq!(token.read().await.balance_of(&escrow), 1000);
+
+        // Seul le créateur modifie ou annule son bounty
+        let extend = serde_json::json!({ "extend_hours": 24 });
+        for method in ["PUT", "DELETE"] {
+            let response = call_as(method, "archiver", "/bounties/2", extend.clone()).await.unwrap();
+            assert_eq!(response.status(), StatusCode::FORBIDDEN);
+        }
+        let response = call_as("PUT", "creator", "/bounties/2", extend).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+
+        let response = call_as("DELETE", "creator", "/bounties/2", serde_json::json!({})).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let cancelled = transition(axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap());
+        assert_eq!(cancelled.amount, 1000);
+        assert_eq!(cancelled.bounty.status, BountyStatus::Cancelled);
+        assert_eq!(token.read().await.balance_of(&creator), 2000);
+        assert_eq!(token.read().await.balance_of(&escrow), 0);
+        let response = post_as("archiver", "/bounties/2/submit", claim.clone()).await.unwrap();
+        assert_eq!(response.status(), StatusCode::CONFLICT);
+
+        let request = axum::http::Request::builder().uri("/bounties?status=Completed").body(Body::empty()).unwrap();
+        let response = router.clone().oneshot(request).await.unwrap();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let completed: PaginatedResponse<BountyDto> = serde_json::from_slice(&body).unwrap();
+        assert_eq!(completed.data.len(), 1);
+        assert_eq!(completed.data[0].bounty_id, 1);
+    }
+
+    /// Couche de capture : module et identifiant de corrélation de chaque span
+    ///
+    /// Un span sans champ `request_id` hérite de celui de son parent.
+    #[derive(Clone, Default)]
+    struct SpanCapture(Arc<std::sync::Mutex<Vec<(String, Option<String>)>>>);
+
+    struct CapturedRequestId(String);
+
+    #[derive(Default)]
+    struct RequestIdVisitor(Option<String>);
+
+    impl tracing::field::Visit for RequestIdVisitor {
+        fn record_str(&mut self, field: &tracing::field::Field, value: &str) {
+            if field.name() == "request_id" {
+                self.0 = Some(value.to_string());
+            }
+        }
+
+        fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+            if field.name() == "request_id" {
+                self.0 = Some(format!("{:?}", value));
+            }
+        }
+    }
+
+    impl<S> tracing_subscriber::Layer<S> for SpanCapture
+    where
+        S: tracing::Subscriber + for<'a> tracing_subscriber::registry::LookupSpan<'a>,
+    {
+        fn on_new_span(&self, attrs: &tracing::span::Attributes<'_>, id: &tracing::span::Id, ctx: tracing_subscriber::layer::Context<'_, S>) {
+            let mut visitor = RequestIdVisitor::default();
+            attrs.record(&mut visitor);
+            let span = ctx.span(id).expect("span enregistré par le registry");
+            let request_id = visitor.0.or_else(|| {
+                span.parent().and_then(|parent| parent.extensions().get::<CapturedRequestId>().map(|id| id.0.clone()))
+            });
+            if let Some(request_id) = &request_id {
+                span.extensions_mut().insert(CapturedRequestId(request_id.clone()));
+            }
+            self.0.lock().unwrap().push((attrs.metadata().target().to_string(), request_id));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_archive_creation_spans_share_request_id() {
+        use tracing_subscriber::layer::SubscriberExt;
+
+        let capture = SpanCapture::default();
+        let _guard = tracing::subscriber::set_default(tracing_subscriber::registry().with(capture.clone()));
+
+        let state = create_test_state();
+        let router = Router::new()
+            .route("/archives", axum::routing::post(create_archive))
+            .layer(axum::middleware::from_fn(|mut req: Request, next: Next| {
+                req.extensions_mut().insert(auth_info(vec![ApiScope::ArchivesWrite]));
+                next.run(req)
+            }))
+            .layer(axum::middleware::from_fn(crate::api::middleware::request_id_middleware))
+            .with_state(state);
+
+        let request = axum::http::Request::builder()
+            .method("POST")
+            .uri("/archives")
+            .header(crate::api::middleware::REQUEST_ID_HEADER, "trace-req-1")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(Body::from(serde_json::json!({
+                "url": "https://example.com/traced",
+                "metadata": { "title": "Traced page" },
+            }).to_string()))
+            .unwrap();
+        let response = router.oneshot(request).await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers().get(crate::api::middleware::REQUEST_ID_HEADER).unwrap(), "trace-req-1");
+
+        let spans = capture.0.lock().unwrap().clone();
+        let modules: std::collections::BTreeSet<_> = spans.iter()
+            .filter(|(_, request_id)| request_id.as_deref() == Some("trace-req-1"))
+            .map(|(target, _)| target.as_str())
+            .collect();
+        assert!(modules.len() >= 3, "spans corrélés : {:?}", modules);
+        for module in ["api::middleware", "api::rest::handlers", "api::service", "storage::search"] {
+            assert!(modules.iter().any(|target| target.ends_with(module)), "aucun span corrélé dans {}", module);
+        }
+    }
+}
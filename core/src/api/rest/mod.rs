@@ -7,6 +7,8 @@
 pub mod routes;
 pub mod handlers;
 pub mod validation;
+pub mod negotiation;
+pub mod signing;
 
 use axum::Router;
 use serde::{Deserialize, Serialize};
@@ -32,6 +34,25 @@ pub struct RestConfig {
     pub archive_timeout: u64,
     /// Activation de la documentation OpenAPI
     pub enable_openapi: bool,
+    /// Signature Ed25519 des réponses avec la clé du nœud
+    #[serde(default)]
+    pub sign_responses: bool,
+    /// Fichier de la clé privée du nœud, requis si `sign_responses` est activé
+    /// sans signataire externe (`ApiServer::with_signer`)
+    #[serde(default)]
+    pub signing_key_path: Option<String>,
+    /// Options du crawl effectué par le nœud pour chaque nouvelle archive ;
+    /// sans elles, aucune archive n'est crawlée ni ne peut servir à un bounty
+    #[serde(default)]
+    pub crawler: Option<crate::api::types::ArchiveOptions>,
+    /// Taille maximale d'un contenu soumis (bytes), en général la
+    /// `StorageConfig::max_content_size` du nœud
+    #[serde(default = "default_max_content_size")]
+    pub max_content_size: u64,
+}
+
+fn default_max_content_size() -> u64 {
+    crate::storage::DEFAULT_MAX_CONTENT_SIZE
 }
 
 impl Default for RestConfig {
@@ -43,6 +64,10 @@ impl Default for RestConfig {
             max_page_size: 100,
             archive_timeout: 300, // 5 minutes
             enable_openapi: true,
+            sign_responses: false,
+            signing_key_path: None,
+            crawler: None,
+            max_content_size: default_max_content_size(),
         }
     }
 }
@@ -66,16 +91,29 @@ fn default_limit() -> u32 {
 
 impl PaginationParams {
     pub fn validate(&self, max_limit: u32) -> Result<(), String> {
+        self.validate_fields(max_limit).map_err(|errors| {
+            errors.into_iter().map(|e| e.message).collect::<Vec<_>>().join("; ")
+        })
+    }
+
+    /// Valide `page` et `limit`, en rapportant les erreurs des deux champs
+    pub fn validate_fields(&self, max_limit: u32) -> ValidationResult {
+        let mut errors = Vec::new();
+
         if self.page == 0 {
-            return Err("Page must be greater than 0".to_string());
+            errors.push(ValidationError::new("page", "out_of_range", "Page must be greater than 0"));
         }
         if self.limit == 0 {
-            return Err("Limit must be greater than 0".to_string());
+            errors.push(ValidationError::new("limit", "out_of_range", "Limit must be greater than 0"));
+        } else if self.limit > max_limit {
+            errors.push(ValidationError::new("limit", "out_of_range", &format!("Limit cannot exceed {}", max_limit)));
         }
-        if self.limit > max_limit {
-            return Err(format!("Limit cannot exceed {}", max_limit));
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
         }
-        Ok(())
     }
 
     pub fn offset(&self) -> u64 {
@@ -114,6 +152,11 @@ impl ResponseMetadata {
             version: env!("CARGO_PKG_VERSION").to_string(),
         }
     }
+
+    /// Métadonnées de la requête en cours, `None` hors du `request_id_middleware`
+    pub fn current(duration_ms: u64) -> Option<Self> {
+        crate::api::middleware::current_request_id().map(|request_id| Self::new(request_id, duration_ms))
+    }
 }
 
 /// Réponse API standard avec métadonnées
@@ -138,6 +181,14 @@ impl<T> ApiResponse<T> {
             metadata: Some(metadata),
         }
     }
+
+    /// Réponse portant l'identifiant de la requête en cours et sa durée depuis `started`
+    pub fn timed(data: T, started: std::time::Instant) -> Self {
+        Self {
+            data,
+            metadata: ResponseMetadata::current(started.elapsed().as_millis() as u64),
+        }
+    }
 }
 
 /// Extensions pour les extracteurs Axum
@@ -150,7 +201,7 @@ pub mod extractors {
     use serde::de::DeserializeOwned;
     
     use crate::api::{ApiError, middleware::AuthInfo, auth::ApiScope};
-    use super::PaginationParams;
+    use super::{PaginationParams, ValidationResult, validation_errors_to_api_error};
 
     /// Extracteur pour la pagination validée
     pub struct ValidatedPagination(pub PaginationParams);
@@ -166,8 +217,8 @@ pub mod extractors {
             let Query(params): Query<PaginationParams> = Query::from_request_parts(parts, state).await
                 .map_err(|e| ApiError::validation(format!("Invalid pagination parameters: {}", e)))?;
 
-            params.validate(100)
-                .map_err(|e| ApiError::validation(e))?;
+            params.validate_fields(100)
+                .map_err(validation_errors_to_api_error)?;
 
             Ok(ValidatedPagination(params))
         }
@@ -249,20 +300,22 @@ pub mod extractors {
                 .map_err(|e| ApiError::validation(format!("Invalid query parameters: {}", e)))?;
 
             params.validate()
-                .map_err(|e| ApiError::validation(e))?;
+                .map_err(validation_errors_to_api_error)?;
 
             Ok(ValidatedQuery(params))
         }
     }
 
     /// Trait pour la validation des paramètres
+    ///
+    /// Toutes les erreurs sont rapportées, chacune rattachée à son champ.
     pub trait Validate {
-        fn validate(&self) -> Result<(), String>;
+        fn validate(&self) -> ValidationResult;
     }
 
     impl Validate for PaginationParams {
-        fn validate(&self) -> Result<(), String> {
-            self.validate(100)
+        fn validate(&self) -> ValidationResult {
+            self.validate_fields(100)
         }
     }
 }
@@ -278,6 +331,8 @@ mod tests {
         assert_eq!(config.max_page_size, 100);
         assert!(config.enable_openapi);
         assert_eq!(config.archive_timeout, 300);
+        assert!(!config.sign_responses);
+        assert!(config.signing_key_path.is_none());
     }
 
     #[test]
@@ -301,6 +356,13 @@ mod tests {
             limit: 200,
         };
         assert!(invalid_limit.validate(100).is_err());
+
+        let both_invalid = PaginationParams {
+            page: 0,
+            limit: 200,
+        };
+        let fields: Vec<_> = both_invalid.validate_fields(100).unwrap_err().into_iter().map(|e| e.field).collect();
+        assert_eq!(fields, vec!["page", "limit"]);
     }
 
     #[test]
@@ -334,4 +396,15 @@ mod tests {
         assert_eq!(metadata.duration_ms, 150);
         assert_eq!(metadata.version, env!("CARGO_PKG_VERSION"));
     }
+
+    #[tokio::test]
+    async fn test_response_metadata_from_current_request() {
+        let started = std::time::Instant::now();
+        let response = crate::api::middleware::with_request_id("req-9".to_string(), async {
+            ApiResponse::timed(42, started)
+        }).await;
+        assert_eq!(response.metadata.unwrap().request_id, "req-9");
+
+        assert!(ApiResponse::timed(42, started).metadata.is_none());
+    }
 }
\ No newline at end of file
@@ -0,0 +1,461 @@
+//! Négociation du format de réponse de l'API REST
+//!
+//! Le format est choisi à partir de l'en-tête `Accept` :
+//! - `application/json` (par défaut)
+//! - `application/cbor`, transcodé depuis la réponse JSON
+//! - `application/x-protobuf`, pour les routes déclarées dans [`ProtobufRoutes`]
+//!   dont les handlers répondent avec [`Negotiable`]
+//!
+//! Le format est négocié avant l'appel du handler : un en-tête `Accept` ne
+//! contenant aucun format supporté par la route donne une erreur 406 sans que
+//! la requête soit exécutée.
+
+use axum::{
+    body::{Body, Bytes},
+    extract::{MatchedPath, Request, State},
+    http::{header, HeaderValue, Method},
+    middleware::Next,
+    response::{IntoResponse, Json, Response},
+};
+use prost::Message;
+use serde::Serialize;
+
+use crate::api::{
+    ApiError, ApiResult,
+    grpc::proto,
+    rest::ApiResponse,
+    types::{ArchiveDto, NetworkStats},
+};
+use crate::serialization::{serialize_with_format, SerializationFormat};
+
+/// Formats de réponse supportés
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResponseFormat {
+    Json,
+    Cbor,
+    Protobuf,
+}
+
+impl ResponseFormat {
+    /// Tous les formats, par ordre de préférence serveur
+    pub const ALL: [ResponseFormat; 3] = [ResponseFormat::Json, ResponseFormat::Cbor, ResponseFormat::Protobuf];
+
+    /// Types MIME annoncés dans les informations de version de l'API
+    pub fn supported_mime_types() -> Vec<String> {
+        Self::ALL.iter().map(|format| format.mime_type().to_string()).collect()
+    }
+
+    /// Type MIME du format
+    pub fn mime_type(&self) -> &'static str {
+        match self {
+            ResponseFormat::Json => "application/json",
+            ResponseFormat::Cbor => "application/cbor",
+            ResponseFormat::Protobuf => "application/x-protobuf",
+        }
+    }
+
+    /// Choisit le format à partir d'un en-tête `Accept`
+    ///
+    /// Les plages sont examinées par qualité décroissante ; une qualité nulle exclut
+    /// le format. Sans en-tête, JSON est utilisé.
+    pub fn from_accept(accept: Option<&str>) -> ApiResult<Self> {
+        Self::from_accept_among(accept, &Self::ALL)
+    }
+
+    /// Choisit le format à partir d'un en-tête `Accept`, parmi ceux d'une route
+    pub fn from_accept_among(accept: Option<&str>, formats: &[ResponseFormat]) -> ApiResult<Self> {
+        let accept = match accept.map(str::trim) {
+            Some(accept) if !accept.is_empty() => accept,
+            _ => return Ok(ResponseFormat::Json),
+        };
+
+        let mut ranges: Vec<(&str, f32)> = accept
+            .split(',')
+            .filter_map(|range| {
+                let mut parts = range.split(';');
+                let media_type = parts.next()?.trim();
+                let quality = parts
+                    .filter_map(|param| param.trim().strip_prefix("q="))
+                    .find_map(|q| q.trim().parse::<f32>().ok())
+                    .unwrap_or(1.0);
+                Some((media_type, quality))
+            })
+            .filter(|(_, quality)| *quality > 0.0)
+            .collect();
+
+        // Tri stable : à qualité égale, l'ordre du client est conservé
+        ranges.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+
+        ranges
+            .iter()
+            .filter_map(|(media_type, _)| Self::from_media_type(media_type))
+            .find(|format| formats.contains(format))
+            .ok_or_else(|| ApiError::not_acceptable(format!(
+                "None of the requested formats is supported ({}); supported formats: {}",
+                accept,
+                formats.iter().map(ResponseFormat::mime_type).collect::<Vec<_>>().join(", ")
+            )))
+    }
+
+    fn from_media_type(media_type: &str) -> Option<Self> {
+        match media_type.to_ascii_lowercase().as_str() {
+            "application/json" | "application/*" | "*/*" => Some(ResponseFormat::Json),
+            "application/cbor" => Some(ResponseFormat::Cbor),
+            "application/x-protobuf" | "application/protobuf" => Some(ResponseFormat::Protobuf),
+            _ => None,
+        }
+    }
+}
+
+/// Routes dont les réponses ont une représentation protobuf
+///
+/// Les chemins sont relatifs au routeur REST et comparés au chemin de la route
+/// sélectionnée, ce qui permet de négocier le format avant l'appel du handler.
+#[derive(Debug, Clone, Default)]
+pub struct ProtobufRoutes(Vec<(Method, &'static str)>);
+
+impl ProtobufRoutes {
+    /// Routes REST dont les handlers répondent avec [`Negotiable`]
+    pub fn rest() -> Self {
+        Self::default()
+            .route(Method::GET, "/archives/at")
+            .route(Method::GET, "/archives/:archive_id")
+            .route(Method::GET, "/network/stats")
+    }
+
+    /// Déclare une route servie en protobuf
+    pub fn route(mut self, method: Method, path: &'static str) -> Self {
+        self.0.push((method, path));
+        self
+    }
+
+    /// Formats disponibles pour la route sélectionnée par la requête
+    fn formats_for(&self, req: &Request) -> &'static [ResponseFormat] {
+        let serves_protobuf = req.extensions().get::<MatchedPath>().is_some_and(|matched| {
+            self.0.iter().any(|(method, path)| req.method() == method && matched.as_str().ends_with(path))
+        });
+        if serves_protobuf {
+            &ResponseFormat::ALL
+        } else {
+            &[ResponseFormat::Json, ResponseFormat::Cbor]
+        }
+    }
+}
+
+/// Représentation protobuf d'une réponse, attachée aux extensions de la réponse JSON
+#[derive(Debug, Clone)]
+struct ProtobufBody(Bytes);
+
+/// Réponse disposant d'une représentation protobuf
+pub trait ToProtobuf {
+    /// Message protobuf correspondant
+    type Message: Message;
+
+    fn to_protobuf(&self) -> Self::Message;
+}
+
+/// Réponse JSON pouvant aussi être servie en protobuf par la négociation de contenu
+#[derive(Debug, Clone)]
+pub struct Negotiable<T>(pub T);
+
+impl<T> IntoResponse for Negotiable<T>
+where
+    T: Serialize + ToProtobuf,
+{
+    fn into_response(self) -> Response {
+        let protobuf = ProtobufBody(Bytes::from(self.0.to_protobuf().encode_to_vec()));
+        let mut response = Json(self.0).into_response();
+        response.extensions_mut().insert(protobuf);
+        response
+    }
+}
+
+/// L'enveloppe est propre au JSON et au CBOR : en protobuf seules les données sont encodées
+impl<T: ToProtobuf> ToProtobuf for ApiResponse<T> {
+    type Message = T::Message;
+
+    fn to_protobuf(&self) -> T::Message {
+        self.data.to_protobuf()
+    }
+}
+
+impl ToProtobuf for ArchiveDto {
+    type Message = proto::Archive;
+
+    fn to_protobuf(&self) -> proto::Archive {
+        proto::Archive::from(self)
+    }
+}
+
+impl ToProtobuf for NetworkStats {
+    type Message = proto::NetworkStats;
+
+    fn to_protobuf(&self) -> proto::NetworkStats {
+        proto::NetworkStats::from(self)
+    }
+}
+
+impl From<&ArchiveDto> for proto::Archive {
+    fn from(archive: &ArchiveDto) -> Self {
+        Self {
+            id: archive.archive_id.clone(),
+            url: archive.url.clone(),
+            status: serde_json::to_value(&archive.status)
+                .ok()
+                .and_then(|status| status.as_str().map(str::to_string))
+                .unwrap_or_default(),
+            size: archive.size,
+            created_at: archive.created_at.timestamp(),
+        }
+    }
+}
+
+impl From<&NetworkStats> for proto::NetworkStats {
+    fn from(stats: &NetworkStats) -> Self {
+        Self {
+            total_nodes: stats.network.total_nodes.min(u32::MAX as u64) as u32,
+            active_nodes: stats.network.active_nodes.min(u32::MAX as u64) as u32,
+            current_block_height: stats.network.current_block_height,
+            total_archives: stats.archives.total_archives,
+        }
+    }
+}
+
+/// Middleware de négociation du format de réponse
+pub async fn content_negotiation_middleware(
+    State(protobuf_routes): State<ProtobufRoutes>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let accept = req.headers()
+        .get(header::ACCEPT)
+        .and_then(|h| h.to_str().ok())
+        .map(str::to_string);
+
+    let formats = protobuf_routes.formats_for(&req);
+    let format = match ResponseFormat::from_accept_among(accept.as_deref(), formats) {
+        Ok(format) => format,
+        Err(e) => return e.into_response(),
+    };
+
+    let mut response = next.run(req).await;
+    let protobuf = response.extensions_mut().remove::<ProtobufBody>();
+
+    // Les erreurs et les réponses non JSON sont renvoyées telles quelles
+    let is_json = response.headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|h| h.to_str().ok())
+        .map_or(false, |content_type| content_type.starts_with("application/json"));
+    if format == ResponseFormat::Json || !is_json || !response.status().is_success() {
+        return response;
+    }
+
+    match encode_response(response, format, protobuf).await {
+        Ok(response) => response,
+        Err(e) => e.into_response(),
+    }
+}
+
+async fn encode_response(response: Response, format: ResponseFormat, protobuf: Option<ProtobufBody>) -> ApiResult<Response> {
+    let (mut parts, body) = response.into_parts();
+
+    let encoded = match format {
+        ResponseFormat::Json => unreachable!("les réponses JSON ne sont pas réencodées"),
+        ResponseFormat::Cbor => {
+            let json = axum::body::to_bytes(body, usize::MAX)
+                .await
+                .map_err(|e| ApiError::internal(format!("Failed to read response body: {}", e)))?;
+            let value: serde_json::Value = serde_json::from_slice(&json)?;
+            let cbor = serialize_with_format(&value, SerializationFormat::Cbor)
+                .map_err(|e| ApiError::internal(format!("CBOR encoding failed: {}", e)))?;
+            Bytes::from(cbor)
+        }
+        ResponseFormat::Protobuf => protobuf
+            .map(|ProtobufBody(bytes)| bytes)
+            .ok_or_else(|| ApiError::not_acceptable("This resource has no application/x-protobuf representation"))?,
+    };
+
+    parts.headers.insert(header::CONTENT_TYPE, HeaderValue::from_static(format.mime_type()));
+    parts.headers.remove(header::CONTENT_LENGTH);
+    Ok(Response::from_parts(parts, Body::from(encoded)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::types::*;
+    use axum::{http::StatusCode, routing::{get, post}, Router};
+    use std::sync::{atomic::{AtomicUsize, Ordering}, Arc};
+    use tower::ServiceExt;
+
+    fn sample_archive() -> ArchiveDto {
+        let created_at = chrono::Utc::now();
+        ArchiveDto {
+            archive_id: "arc_negotiation".to_string(),
+            url: "https://example.com/page".to_string(),
+            status: ArchiveStatus::Completed,
+            created_at,
+            completed_at: Some(created_at),
+            size: 4096,
+            metadata: ArchiveMetadataDto {
+                title: Some("Example".to_string()),
+                description: None,
+                mime_type: "text/html".to_string(),
+                language: Some("fr".to_string()),
+                author: None,
+                published_at: None,
+                tags: vec!["web".to_string()],
+            },
+            storage_info: StorageInfo {
+                replicas: 3,
+                locations: vec!["eu-west-1".to_string()],
+                integrity_score: 0.97,
+                last_verified: created_at,
+            },
+            access_urls: AccessUrls {
+                view: "https://gateway.test/archives/arc_negotiation".to_string(),
+                download: "https://gateway.test/archives/arc_negotiation/download".to_string(),
+                raw: "https://gateway.test/archives/arc_negotiation/raw".to_string(),
+            },
+            confirmations: None,
+            finalized: false,
+        }
+    }
+
+    fn app(archive: ArchiveDto) -> Router {
+        app_counting(archive, Arc::default())
+    }
+
+    /// Application de test dont la route `/submit` compte ses exécutions
+    fn app_counting(archive: ArchiveDto, submissions: Arc<AtomicUsize>) -> Router {
+        let wrapped = archive.clone();
+        let protobuf_routes = ProtobufRoutes::default()
+            .route(Method::GET, "/archive")
+            .route(Method::GET, "/wrapped");
+        Router::new()
+            .route("/archive", get(move || async move { Negotiable(archive.clone()) }))
+            .route("/wrapped", get(move || async move { Negotiable(ApiResponse::new(wrapped.clone())) }))
+            .route("/plain", get(|| async { Json(serde_json::json!({ "ok": true })) }))
+            .route("/submit", post(move || async move {
+                submissions.fetch_add(1, Ordering::SeqCst);
+                Json(serde_json::json!({ "submitted": true }))
+            }))
+            .layer(axum::middleware::from_fn_with_state(protobuf_routes, content_negotiation_middleware))
+    }
+
+    async fn fetch(app: Router, path: &str, accept: Option<&str>) -> (StatusCode, String, Bytes) {
+        let mut request = Request::builder().uri(path);
+        if let Some(accept) = accept {
+            request = request.header(header::ACCEPT, accept);
+        }
+        let response = app.oneshot(request.body(Body::empty()).unwrap()).await.unwrap();
+
+        let status = response.status();
+        let content_type = response.headers()
+            .get(header::CONTENT_TYPE)
+            .and_then(|h| h.to_str().ok())
+            .unwrap_or_default()
+            .to_string();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (status, content_type, body)
+    }
+
+    #[test]
+    fn test_accept_header_parsing() {
+        assert_eq!(ResponseFormat::from_accept(None).unwrap(), ResponseFormat::Json);
+        assert_eq!(ResponseFormat::from_accept(Some("*/*")).unwrap(), ResponseFormat::Json);
+        assert_eq!(ResponseFormat::from_accept(Some("application/cbor")).unwrap(), ResponseFormat::Cbor);
+        assert_eq!(
+            ResponseFormat::from_accept(Some("application/json;q=0.5, application/x-protobuf")).unwrap(),
+            ResponseFormat::Protobuf
+        );
+        assert_eq!(
+            ResponseFormat::from_accept(Some("text/html, application/cbor;q=0.9")).unwrap(),
+            ResponseFormat::Cbor
+        );
+        assert!(matches!(
+            ResponseFormat::from_accept(Some("application/cbor;q=0, text/html")),
+            Err(ApiError::NotAcceptable(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_archive_round_trip_in_all_formats() {
+        let archive = sample_archive();
+
+        let (status, content_type, body) = fetch(app(archive.clone()), "/archive", None).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(content_type.starts_with("application/json"));
+        let from_json: ArchiveDto = serde_json::from_slice(&body).unwrap();
+
+        let (status, content_type, body) = fetch(app(archive.clone()), "/archive", Some("application/cbor")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(content_type, "application/cbor");
+        let from_cbor: ArchiveDto = crate::serialization::deserialize_with_format(&body, SerializationFormat::Cbor).unwrap();
+
+        let (status, content_type, body) = fetch(app(archive.clone()), "/archive", Some("application/x-protobuf")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(content_type, "application/x-protobuf");
+        let from_protobuf = proto::Archive::decode(body).unwrap();
+
+        assert_eq!(from_json, archive);
+        assert_eq!(from_cbor, from_json);
+        assert_eq!(from_protobuf, proto::Archive::from(&from_json));
+        assert_eq!(from_protobuf.status, "completed");
+    }
+
+    #[tokio::test]
+    async fn test_api_response_envelope_formats() {
+        let archive = sample_archive();
+
+        let (status, content_type, body) = fetch(app(archive.clone()), "/wrapped", Some("application/cbor")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(content_type, "application/cbor");
+        let from_cbor: ApiResponse<ArchiveDto> =
+            crate::serialization::deserialize_with_format(&body, SerializationFormat::Cbor).unwrap();
+        assert_eq!(from_cbor.data, archive);
+        assert!(from_cbor.metadata.is_none());
+
+        let (status, _, body) = fetch(app(archive.clone()), "/wrapped", Some("application/x-protobuf")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(proto::Archive::decode(body).unwrap(), proto::Archive::from(&archive));
+    }
+
+    #[tokio::test]
+    async fn test_unsupported_formats_are_rejected() {
+        let (status, _, body) = fetch(app(sample_archive()), "/archive", Some("text/html")).await;
+        assert_eq!(status, StatusCode::NOT_ACCEPTABLE);
+        let error: serde_json::Value = serde_json::from_slice(&body).unwrap();
+        assert_eq!(error["code"], "NOT_ACCEPTABLE");
+        assert_eq!(error["status"], 406);
+
+        // CBOR reste disponible pour toutes les réponses JSON, pas protobuf
+        let (status, content_type, _) = fetch(app(sample_archive()), "/plain", Some("application/cbor")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(content_type, "application/cbor");
+
+        let (status, _, _) = fetch(app(sample_archive()), "/plain", Some("application/x-protobuf")).await;
+        assert_eq!(status, StatusCode::NOT_ACCEPTABLE);
+
+        // Un format de repli accepté par le client est choisi à la place
+        let (status, content_type, _) =
+            fetch(app(sample_archive()), "/plain", Some("application/x-protobuf, application/json;q=0.5")).await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(content_type.starts_with("application/json"));
+    }
+
+    #[tokio::test]
+    async fn test_unsupported_protobuf_is_rejected_before_dispatch() {
+        let submissions = Arc::new(AtomicUsize::new(0));
+        let request = Request::builder()
+            .method(Method::POST)
+            .uri("/submit")
+            .header(header::ACCEPT, "application/x-protobuf")
+            .body(Body::empty())
+            .unwrap();
+        let response = app_counting(sample_archive(), submissions.clone()).oneshot(request).await.unwrap();
+
+        assert_eq!(response.status(), StatusCode::NOT_ACCEPTABLE);
+        assert_eq!(submissions.load(Ordering::SeqCst), 0);
+    }
+}
@@ -22,10 +22,15 @@ pub async fn create_routes() -> ApiResult<Router<ServerState>> {
         .nest("/nodes", node_routes())
         // Routes des blocs
         .nest("/blocks", block_routes())
+        // Routes des transactions
+        .nest("/transactions", transaction_routes())
         // Routes des contrats
         .nest("/contracts", contract_routes())
-        // Routes des bounties
-        .nest("/bounties", bounty_routes());
+        // Négociation du format de réponse (JSON, CBOR, protobuf), avant l'appel des handlers
+        .layer(axum::middleware::from_fn_with_state(
+            super::negotiation::ProtobufRoutes::rest(),
+            super::negotiation::content_negotiation_middleware,
+        ));
 
     Ok(router)
 }
@@ -37,6 +42,10 @@ fn archive_routes() -> Router<ServerState> {
         .route("/", post(create_archive))
         // GET /archives - Lister les archives
         .route("/", get(list_archives))
+        // GET /archives/by-url?url=&page= - Historique des versions, plus récentes d'abord
+        .route("/by-url", get(get_archives_by_url))
+        // GET /archives/at?url=&timestamp= - Capture la plus proche à une date
+        .route("/at", get(get_archive_at))
         // GET /archives/{archive_id} - Récupérer une archive
         .route("/:archive_id", get(get_archive))
         // PUT /archives/{archive_id} - Mettre à jour une archive
@@ -51,6 +60,21 @@ fn archive_routes() -> Router<ServerState> {
         .route("/:archive_id/verify", post(verify_archive))
         // GET /archives/{archive_id}/replicas - Informations de réplication
         .route("/:archive_id/replicas", get(get_archive_replicas))
+        // GET /archives/{archive_id}/proof - Preuve d'inclusion on-chain
+        .route("/:archive_id/proof", get(get_archive_proof))
+        // GET /archives/{archive_id}/content - Contenu archivé (Range pris en charge)
+        .route("/:archive_id/content", get(get_archive_content))
+}
+
+/// Routes de gestion des clés API, montées sous `/api/v1/auth/keys`
+pub fn api_key_routes() -> Router<ServerState> {
+    Router::new()
+        // POST /auth/keys - Créer une clé API
+        .route("/", post(create_api_key))
+        // GET /auth/keys - Lister ses clés API (préfixes uniquement)
+        .route("/", get(list_api_keys))
+        // DELETE /auth/keys/{key_id} - Révoquer une clé API
+        .route("/:key_id", delete(revoke_api_key))
 }
 
 /// Routes pour la recherche
@@ -111,6 +135,8 @@ fn block_routes() -> Router<ServerState> {
     Router::new()
         // GET /blocks - Lister les blocs récents
         .route("/", get(list_blocks))
+        // GET /blocks/headers?from=&to= - En-têtes pour clients légers (paginés)
+        .route("/headers", get(get_block_headers))
         // GET /blocks/{block_hash} - Récupérer un bloc
         .route("/:block_hash", get(get_block))
         // GET /blocks/{block_hash}/transactions - Transactions d'un bloc
@@ -123,6 +149,13 @@ fn block_routes() -> Router<ServerState> {
         .route("/chain/stats", get(get_chain_stats))
 }
 
+/// Routes pour les transactions
+fn transaction_routes() -> Router<ServerState> {
+    Router::new()
+        // GET /transactions/{tx_hash}/receipt - Reçu et événements d'une transaction
+        .route("/:tx_hash/receipt", get(get_transaction_receipt))
+}
+
 /// Routes pour les contrats intelligents
 fn contract_routes() -> Router<ServerState> {
     Router::new()
@@ -140,29 +173,44 @@ fn contract_routes() -> Router<ServerState> {
         .route("/:contract_id/state", get(get_contract_state))
 }
 
-/// Routes pour les bounties
-fn bounty_routes() -> Router<ServerState> {
+/// Routes des bounties d'archivage, montées sous `/api/v1/bounties`
+pub fn bounty_routes() -> Router<ServerState> {
     Router::new()
-        // GET /bounties - Lister les bounties
+        // GET /bounties - Lister les bounties d'un statut
         .route("/", get(list_bounties))
         // POST /bounties - Créer un bounty
         .route("/", post(create_bounty))
         // GET /bounties/{bounty_id} - Informations d'un bounty
         .route("/:bounty_id", get(get_bounty))
-        // PUT /bounties/{bounty_id} - Mettre à jour un bounty
+        // PUT /bounties/{bounty_id} - Augmenter la récompense ou repousser la deadline
         .route("/:bounty_id", put(update_bounty))
         // DELETE /bounties/{bounty_id} - Annuler un bounty
         .route("/:bounty_id", delete(cancel_bounty))
-        // POST /bounties/{bounty_id}/submit - Soumettre pour un bounty
+        // POST /bounties/{bounty_id}/claim - Réclamer un bounty avec une archive
+        .route("/:bounty_id/claim", post(claim_bounty))
+        // POST /bounties/{bounty_id}/refund - Rembourser un bounty expiré
+        .route("/:bounty_id/refund", post(refund_bounty))
+        // POST /bounties/{bounty_id}/submit - Proposer une archive au créateur
         .route("/:bounty_id/submit", post(submit_bounty_proposal))
-        // GET /bounties/{bounty_id}/proposals - Propositions pour un bounty
+        // GET /bounties/{bounty_id}/proposals - Propositions reçues
         .route("/:bounty_id/proposals", get(list_bounty_proposals))
         // POST /bounties/{bounty_id}/proposals/{proposal_id}/accept - Accepter une proposition
         .route("/:bounty_id/proposals/:proposal_id/accept", post(accept_bounty_proposal))
+        // POST /bounties/{bounty_id}/proposals/{proposal_id}/release - Verser la récompense d'une proposition vérifiée
+        .route("/:bounty_id/proposals/:proposal_id/release", post(release_bounty_proposal))
         // GET /bounties/{bounty_id}/status - Statut d'un bounty
         .route("/:bounty_id/status", get(get_bounty_status))
 }
 
+/// Routes du treasury communautaire, montées sous `/api/v1/treasury`
+pub fn treasury_routes() -> Router<ServerState> {
+    Router::new()
+        // GET /treasury - Rapport du treasury, jalons compris
+        .route("/", get(get_treasury_report))
+        // GET /treasury/proposals/{proposal_id}/milestones - Jalons d'une proposition
+        .route("/proposals/:proposal_id/milestones", get(get_proposal_milestones))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -212,6 +260,67 @@ mod tests {
         assert_eq!(2 + 2, 4);
     }
 
+    #[tokio::test]
+    async fn test_treasury_routes_structure() {
+        use crate::api::middleware::AuthInfo;
+        use crate::api::auth::{ApiScope, AuthConfig, AuthService, JwtClaims, UserManager};
+        use crate::token::Treasury;
+        use axum::{body::Body, extract::Request, http::StatusCode, middleware::Next};
+        use std::sync::Arc;
+        use tower::ServiceExt;
+
+        let router = |treasury: Option<Treasury>| {
+            let blockchain = Arc::new(crate::Blockchain::new(crate::BlockchainConfig::default()).unwrap());
+            let auth_service = Arc::new(AuthService::new(AuthConfig::default()).unwrap());
+            let user_manager = Arc::new(tokio::sync::RwLock::new(UserManager::new()));
+            let mut state = ServerState::new(blockchain, auth_service, user_manager, crate::api::ApiConfig::default());
+            if let Some(treasury) = treasury {
+                state = state.with_treasury(Arc::new(tokio::sync::RwLock::new(treasury)));
+            }
+            Router::new()
+                .nest("/treasury", treasury_routes())
+                .layer(axum::middleware::from_fn(|mut req: Request, next: Next| {
+                    let claims = JwtClaims {
+                        sub: "reader".to_string(),
+                        iss: "archivechain".to_string(),
+                        aud: "archivechain-api".to_string(),
+                        exp: u64::MAX,
+                        iat: 0,
+                        nbf: 0,
+                        jti: "test".to_string(),
+                        scope: vec![ApiScope::NetworkRead.as_str().to_string()],
+                        node_id: None,
+                        rate_limit: Default::default(),
+                        user_metadata: Default::default(),
+                    };
+                    req.extensions_mut().insert(AuthInfo { user_id: claims.sub.clone(), claims, scopes: vec![ApiScope::NetworkRead] });
+                    next.run(req)
+                }))
+                .with_state(state)
+        };
+        let get = |router: Router, uri: &str| {
+            let request = axum::http::Request::builder().method(Method::GET).uri(uri).body(Body::empty()).unwrap();
+            router.oneshot(request)
+        };
+
+        let response = get(router(Some(Treasury::default())), "/treasury").await.unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let report: TreasuryReportDto = serde_json::from_slice(&body).unwrap();
+        assert_eq!(report.available_funds, crate::token::COMMUNITY_RESERVE);
+        assert!(report.milestones.is_empty());
+
+        let unknown = format!("/treasury/proposals/{}/milestones", crate::crypto::Hash::zero().to_hex());
+        let response = get(router(Some(Treasury::default())), &unknown).await.unwrap();
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+        let response = get(router(Some(Treasury::default())), "/treasury/proposals/zz/milestones").await.unwrap();
+        assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+        // Sans treasury rattaché
+        let response = get(router(None), "/treasury").await.unwrap();
+        assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+    }
+
     #[tokio::test]
     async fn test_create_routes() {
         let result = create_routes().await;
@@ -0,0 +1,287 @@
+//! Signature des réponses de l'API REST
+//!
+//! Lorsque `RestConfig::sign_responses` est activé, chaque réponse JSON, CBOR ou
+//! Protobuf est signée en Ed25519 avec la clé du nœud, via un [`Signer`] : fichier
+//! de clé local ou signataire externe (HSM, KMS). La signature porte sur les
+//! octets exacts du corps renvoyé : les corps JSON sont d'abord réécrits sous forme
+//! canonique (clés d'objets triées, sans espaces) pour qu'un même contenu donne
+//! toujours les mêmes octets.
+//!
+//! En-têtes ajoutés :
+//! - `X-ArchiveChain-Signature` : signature hexadécimale du corps
+//! - `X-ArchiveChain-Key-Id` : identifiant de la clé ([`Signer::key_id`], par défaut
+//!   la clé publique en hexadécimal)
+//!
+//! Un tiers vérifie une réponse avec [`verify_response_signature`].
+
+use std::path::Path;
+use std::sync::Arc;
+
+use axum::{
+    body::{Body, Bytes},
+    extract::{Request, State},
+    http::{header, HeaderValue},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+
+use crate::api::{ApiError, ApiResult};
+use crate::crypto::{sign_data, verify_signature, FileSigner, KeyPair, PrivateKey, PublicKey, Signature, Signer};
+
+/// En-tête portant la signature du corps
+pub const SIGNATURE_HEADER: &str = "x-archivechain-signature";
+
+/// En-tête portant l'identifiant de la clé de signature
+pub const KEY_ID_HEADER: &str = "x-archivechain-key-id";
+
+/// Types de contenu signés ; les autres réponses (contenus archivés diffusés en flux)
+/// sont transmises sans signature
+const SIGNED_CONTENT_TYPES: [&str; 3] = ["application/json", "application/cbor", "application/x-protobuf"];
+
+/// Signataire des réponses, construit à partir de la clé du nœud
+pub struct ResponseSigner {
+    signer: Arc<dyn Signer>,
+    public_key: PublicKey,
+    key_id: String,
+}
+
+impl ResponseSigner {
+    /// Crée un signataire à partir d'une paire de clés
+    pub fn new(keypair: &KeyPair) -> Self {
+        Self::from_private_key(keypair.private_key().clone())
+    }
+
+    /// Crée un signataire à partir d'une clé privée
+    pub fn from_private_key(private_key: PrivateKey) -> Self {
+        Self::from_signer(Arc::new(FileSigner::new(private_key)))
+    }
+
+    /// Crée un signataire délégant à un [`Signer`] quelconque (HSM, KMS...)
+    pub fn from_signer(signer: Arc<dyn Signer>) -> Self {
+        let public_key = signer.public_key();
+        let key_id = signer.key_id();
+        Self {
+            signer,
+            public_key,
+            key_id,
+        }
+    }
+
+    /// Charge la clé du nœud depuis un fichier (32 octets bruts ou leur encodage hexadécimal)
+    pub fn from_key_file(path: impl AsRef<Path>) -> ApiResult<Self> {
+        let path = path.as_ref();
+        let signer = FileSigner::load(path)
+            .map_err(|e| ApiError::internal(format!("Failed to load signing key {}: {}", path.display(), e)))?;
+
+        Ok(Self::from_signer(Arc::new(signer)))
+    }
+
+    /// Identifiant de la clé, annoncé dans `X-ArchiveChain-Key-Id`
+    pub fn key_id(&self) -> &str {
+        &self.key_id
+    }
+
+    /// Clé publique à communiquer aux vérificateurs
+    pub fn public_key(&self) -> &PublicKey {
+        &self.public_key
+    }
+
+    /// Signe des octets et renvoie la signature en hexadécimal
+    ///
+    /// Un signataire externe peut bloquer le temps d'un aller-retour vers son
+    /// service : la signature est calculée hors des threads du runtime.
+    pub async fn sign(&self, body: Bytes) -> ApiResult<String> {
+        let signer = self.signer.clone();
+        let signature = tokio::task::spawn_blocking(move || sign_data(&body, signer.as_ref()))
+            .await
+            .map_err(|e| ApiError::internal(format!("Response signing task failed: {}", e)))?
+            .map_err(|e| ApiError::internal(format!("Response signing failed: {}", e)))?;
+        Ok(signature.to_hex())
+    }
+}
+
+impl std::fmt::Debug for ResponseSigner {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ResponseSigner")
+            .field("key_id", &self.key_id)
+            .finish()
+    }
+}
+
+/// Vérifie la signature d'une réponse
+///
+/// `body` doit être le corps exact reçu, `signature` la valeur de
+/// `X-ArchiveChain-Signature`. Une signature mal formée est rejetée.
+pub fn verify_response_signature(body: &[u8], signature: &str, public_key: &PublicKey) -> bool {
+    Signature::from_hex(signature.trim())
+        .and_then(|signature| verify_signature(body, &signature, public_key))
+        .unwrap_or(false)
+}
+
+/// Réécrit un document JSON sous forme canonique : clés triées, aucun espace
+///
+/// Le tri est fait explicitement pour ne pas dépendre de la représentation
+/// interne de `serde_json::Map`.
+pub fn canonical_json(value: &serde_json::Value) -> Vec<u8> {
+    let mut out = Vec::new();
+    write_canonical(value, &mut out);
+    out
+}
+
+fn write_canonical(value: &serde_json::Value, out: &mut Vec<u8>) {
+    match value {
+        serde_json::Value::Object(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+            out.push(b'{');
+            for (index, (key, value)) in entries.into_iter().enumerate() {
+                if index > 0 {
+                    out.push(b',');
+                }
+                // La sérialisation d'une chaîne ou d'un scalaire ne peut pas échouer
+                out.extend_from_slice(&serde_json::to_vec(key).unwrap_or_default());
+                out.push(b':');
+                write_canonical(value, out);
+            }
+            out.push(b'}');
+        }
+        serde_json::Value::Array(items) => {
+            out.push(b'[');
+            for (index, item) in items.iter().enumerate() {
+                if index > 0 {
+                    out.push(b',');
+                }
+                write_canonical(item, out);
+            }
+            out.push(b']');
+        }
+        scalar => out.extend_from_slice(&serde_json::to_vec(scalar).unwrap_or_default()),
+    }
+}
+
+/// Middleware de signature des réponses
+///
+/// Doit être placé à l'extérieur de la négociation de contenu pour signer les
+/// octets effectivement envoyés.
+pub async fn response_signing_middleware(
+    State(signer): State<Arc<ResponseSigner>>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let response = next.run(req).await;
+
+    let content_type = response.headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|h| h.to_str().ok())
+        .map(str::to_string);
+    let Some(content_type) = content_type.filter(|ct| SIGNED_CONTENT_TYPES.iter().any(|t| ct.starts_with(t))) else {
+        return response;
+    };
+
+    match sign_response(response, &content_type, &signer).await {
+        Ok(response) => response,
+        Err(e) => e.into_response(),
+    }
+}
+
+async fn sign_response(response: Response, content_type: &str, signer: &ResponseSigner) -> ApiResult<Response> {
+    let (mut parts, body) = response.into_parts();
+    let bytes = axum::body::to_bytes(body, usize::MAX)
+        .await
+        .map_err(|e| ApiError::internal(format!("Failed to read response body: {}", e)))?;
+
+    let body = if content_type.starts_with("application/json") && !bytes.is_empty() {
+        let value: serde_json::Value = serde_json::from_slice(&bytes)?;
+        Bytes::from(canonical_json(&value))
+    } else {
+        bytes
+    };
+
+    let signature = signer.sign(body.clone()).await?;
+    parts.headers.insert(
+        SIGNATURE_HEADER,
+        HeaderValue::from_str(&signature).map_err(|e| ApiError::internal(e.to_string()))?,
+    );
+    parts.headers.insert(
+        KEY_ID_HEADER,
+        HeaderValue::from_str(signer.key_id()).map_err(|e| ApiError::internal(e.to_string()))?,
+    );
+    parts.headers.remove(header::CONTENT_LENGTH);
+    Ok(Response::from_parts(parts, Body::from(body)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::generate_keypair;
+    use axum::{response::Json, routing::get, Router};
+    use tower::ServiceExt;
+
+    fn app(signer: Arc<ResponseSigner>) -> Router {
+        Router::new()
+            .route("/stats", get(|| async {
+                let mut counts = std::collections::HashMap::new();
+                counts.insert("zeta", 1);
+                counts.insert("alpha", 2);
+                counts.insert("mu", 3);
+                Json(serde_json::json!({ "success": true, "data": counts }))
+            }))
+            .route("/raw", get(|| async { "archived content" }))
+            .layer(axum::middleware::from_fn_with_state(signer, response_signing_middleware))
+    }
+
+    async fn call(app: Router, uri: &str) -> (axum::http::HeaderMap, Vec<u8>) {
+        let response = app
+            .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+            .await
+            .unwrap();
+        let headers = response.headers().clone();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        (headers, body.to_vec())
+    }
+
+    #[tokio::test]
+    async fn test_json_response_is_canonical_and_verifiable() {
+        let keypair = generate_keypair().unwrap();
+        let signer = Arc::new(ResponseSigner::new(&keypair));
+
+        let (headers, body) = call(app(signer.clone()), "/stats").await;
+        assert_eq!(body, br#"{"data":{"alpha":2,"mu":3,"zeta":1},"success":true}"#.to_vec());
+
+        let signature = headers.get(SIGNATURE_HEADER).unwrap().to_str().unwrap();
+        let key_id = headers.get(KEY_ID_HEADER).unwrap().to_str().unwrap();
+        assert_eq!(key_id, keypair.public_key().to_hex());
+
+        let public_key = PublicKey::from_hex(key_id).unwrap();
+        assert!(verify_response_signature(&body, signature, &public_key));
+
+        let mut tampered = body.clone();
+        tampered[body.len() - 2] = b'a';
+        assert!(!verify_response_signature(&tampered, signature, &public_key));
+
+        let other = generate_keypair().unwrap();
+        assert!(!verify_response_signature(&body, signature, other.public_key()));
+        assert!(!verify_response_signature(&body, "not-hex", &public_key));
+    }
+
+    #[tokio::test]
+    async fn test_unsigned_content_types_pass_through() {
+        let signer = Arc::new(ResponseSigner::new(&generate_keypair().unwrap()));
+
+        let (headers, body) = call(app(signer), "/raw").await;
+        assert_eq!(body, b"archived content".to_vec());
+        assert!(headers.get(SIGNATURE_HEADER).is_none());
+    }
+
+    #[test]
+    fn test_signer_from_hex_key_file() {
+        let keypair = generate_keypair().unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("node.key");
+        std::fs::write(&path, format!("{}\n", keypair.private_key().to_hex())).unwrap();
+
+        let signer = ResponseSigner::from_key_file(&path).unwrap();
+        assert_eq!(signer.public_key(), keypair.public_key());
+        assert!(ResponseSigner::from_key_file(dir.path().join("missing.key")).is_err());
+    }
+}
@@ -1,6 +1,8 @@
 //! Module de validation pour l'API REST ArchiveChain
 //!
-//! Contient les validateurs pour tous les types de requêtes REST.
+//! Contient les validateurs pour tous les types de requêtes REST. Chaque
+//! validateur rapporte toutes les erreurs trouvées, champ par champ, plutôt
+//! que de s'arrêter à la première.
 
 use crate::api::{ApiError, ApiResult};
 use serde::{Deserialize, Serialize};
@@ -205,6 +207,53 @@ impl MetadataValidator {
         }
     }
 
+    /// Valide les tags d'une archive (`metadata.tags`, tableau JSON)
+    ///
+    /// Une valeur qui n'est pas un tableau JSON de chaînes est refusée.
+    /// Applique `MAX_TAGS_PER_ARCHIVE` et `MAX_TAG_LENGTH`, les limites que le
+    /// stockage impose aux métadonnées de contenu.
+    pub fn validate_archive_tags(metadata: &std::collections::HashMap<String, String>) -> ValidationResult {
+        use crate::constants::{MAX_TAGS_PER_ARCHIVE, MAX_TAG_LENGTH};
+
+        let tags = match metadata.get("tags").map(|tags| serde_json::from_str::<Vec<String>>(tags)) {
+            None => Vec::new(),
+            Some(Ok(tags)) => tags,
+            Some(Err(_)) => {
+                return Err(vec![ValidationError::new(
+                    "metadata.tags",
+                    "invalid_format",
+                    "Tags must be a JSON array of strings"
+                )]);
+            }
+        };
+        let mut errors = Vec::new();
+
+        if tags.len() > MAX_TAGS_PER_ARCHIVE {
+            errors.push(ValidationError::new(
+                "metadata.tags",
+                "too_many",
+                &format!("Cannot have more than {} tags", MAX_TAGS_PER_ARCHIVE)
+            ));
+        }
+
+        for (index, tag) in tags.iter().enumerate() {
+            if tag.chars().count() > MAX_TAG_LENGTH {
+                errors.push(ValidationError::with_value(
+                    &format!("metadata.tags[{}]", index),
+                    "too_long",
+                    &format!("Tags cannot exceed {} characters", MAX_TAG_LENGTH),
+                    serde_json::Value::String(tag.clone())
+                ));
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
     /// Valide les tags
     pub fn validate_tags(tags: &[String]) -> ValidationResult {
         let mut errors = Vec::new();
@@ -401,7 +450,7 @@ impl SearchValidator {
     }
 
     /// Vérifie si un content-type est valide
-    fn is_valid_content_type(content_type: &str) -> bool {
+    pub fn is_valid_content_type(content_type: &str) -> bool {
         let valid_types = [
             "text/html", "text/plain", "text/css", "text/javascript",
             "application/json", "application/pdf", "application/xml",
@@ -472,7 +521,7 @@ impl IdValidator {
             errors.push(ValidationError::new("archive_id", "invalid_length", "Archive ID must be 36 characters long"));
         }
 
-        let uuid_part = &archive_id[4..];
+        let uuid_part = archive_id.get(4..).unwrap_or_default();
         if !uuid_part.chars().all(|c| c.is_ascii_hexdigit()) {
             errors.push(ValidationError::new("archive_id", "invalid_chars", "Archive ID contains invalid characters"));
         }
@@ -509,13 +558,27 @@ impl IdValidator {
     }
 }
 
+/// Accumule les erreurs de plusieurs validations
+pub fn collect_validation(results: impl IntoIterator<Item = ValidationResult>) -> ValidationResult {
+    let errors: Vec<ValidationError> = results.into_iter()
+        .filter_map(Result::err)
+        .flatten()
+        .collect();
+
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors)
+    }
+}
+
 /// Convertit les erreurs de validation en ApiError
 pub fn validation_errors_to_api_error(errors: Vec<ValidationError>) -> ApiError {
-    let error_response = crate::api::error::ValidationErrorResponse::new(
-        errors.into_iter().map(|e| crate::api::error::ValidationError::new(e.field, e.message)).collect()
-    );
-    
-    ApiError::Validation(serde_json::to_string(&error_response).unwrap_or_else(|_| "Validation failed".to_string()))
+    ApiError::InvalidFields(
+        errors.into_iter()
+            .map(|e| crate::api::error::ValidationError::new(e.field, e.message).with_code(e.code))
+            .collect()
+    )
 }
 
 #[cfg(test)]
@@ -607,6 +670,28 @@ mod tests {
         assert!(IdValidator::validate_archive_id("invalid_id").is_err());
         assert!(IdValidator::validate_archive_id("arc_short").is_err());
         assert!(IdValidator::validate_archive_id("arc_1234567890abcdef1234567890abcdeg").is_err()); // g n'est pas hex
+        assert!(IdValidator::validate_archive_id("ar").is_err());
+    }
+
+    #[test]
+    fn test_errors_are_accumulated() {
+        let result = collect_validation([
+            UrlValidator::validate_url("ftp://localhost"),
+            Ok(()),
+            SearchValidator::validate_search_query(""),
+        ]);
+        let fields: Vec<_> = result.unwrap_err().into_iter().map(|e| (e.field, e.code)).collect();
+        assert_eq!(fields, vec![
+            ("url".to_string(), "invalid_scheme".to_string()),
+            ("url".to_string(), "blocked_domain".to_string()),
+            ("query".to_string(), "required".to_string()),
+        ]);
+
+        let error = validation_errors_to_api_error(vec![ValidationError::new("q", "required", "Search query is required")]);
+        match error {
+            ApiError::InvalidFields(errors) => assert_eq!(errors[0].code.as_deref(), Some("required")),
+            other => panic!("erreur inattendue: {:?}", other),
+        }
     }
 
     #[test]
@@ -6,22 +6,32 @@
 
 use crate::api::{
     ApiConfig, ApiError, ApiResult, ApiVersion, HealthStatus,
+    health::{self, HealthProbe},
     auth::{AuthService, UserManager},
-    middleware::{MiddlewareState, RateLimiters, cors_middleware, compression_middleware, tracing_middleware},
-    rest,
+    middleware::{MiddlewareState, RateLimiters, ShutdownDrain, cors_middleware, compression_middleware, tracing_middleware},
+    rest::{self, signing::ResponseSigner},
     graphql,
-    websocket,
+    websocket::{self, EventBus},
+    service::{ArchiveService, BountyService, ContentService, DiscoveryRewarder, StorageContentFetcher},
+    p2p::{P2PManager, SyncService},
 };
 use crate::{Blockchain, BlockchainConfig};
+use crate::crypto::Signer;
+use crate::token::Treasury;
+use crate::nodes::gateway::{CacheConfig, CacheLayer};
+use crate::storage::{CrawlEngine, StorageManager};
+use crate::shutdown::{Drained, ShutdownHook, ShutdownPhase, ShutdownToken};
+#[cfg(feature = "metrics")]
+use crate::storage::{MetricsCollector, MetricsConfig, PrometheusExporter};
 use axum::{
     extract::{State, Path},
     http::StatusCode,
     response::Json,
-    routing::{get, post},
+    routing::{delete, get, post},
     Router,
 };
 use serde::{Deserialize, Serialize};
-use std::{net::SocketAddr, sync::Arc, time::SystemTime};
+use std::{net::SocketAddr, sync::Arc, time::{Duration, SystemTime}};
 use tokio::net::TcpListener;
 use tower::ServiceBuilder;
 use tower_http::timeout::TimeoutLayer;
@@ -42,6 +52,13 @@ pub struct ServerConfig {
     pub tls: Option<TlsConfig>,
     /// Mode de développement
     pub dev_mode: bool,
+    /// Délai de drainage des requêtes en cours à l'arrêt (en secondes)
+    #[serde(default = "default_shutdown_timeout")]
+    pub shutdown_timeout: u64,
+}
+
+fn default_shutdown_timeout() -> u64 {
+    30
 }
 
 impl Default for ServerConfig {
@@ -53,6 +70,17 @@ impl Default for ServerConfig {
             max_body_size: 16 * 1024 * 1024, // 16MB
             tls: None,
             dev_mode: false,
+            shutdown_timeout: default_shutdown_timeout(),
+        }
+    }
+}
+
+impl ServerConfig {
+    /// Vérifie les fichiers TLS avant l'ouverture du port
+    pub fn validate(&self) -> ApiResult<()> {
+        match &self.tls {
+            Some(tls) => tls.validate(),
+            None => Ok(()),
         }
     }
 }
@@ -65,15 +93,54 @@ pub struct TlsConfig {
     pub ca_cert_path: Option<String>,
 }
 
+impl TlsConfig {
+    /// Vérifie que le certificat, la clé et le CA éventuel sont exploitables
+    pub fn validate(&self) -> ApiResult<()> {
+        super::tls::validate_identity(&self.cert_path, &self.key_path)?;
+        if let Some(ca_path) = &self.ca_cert_path {
+            super::tls::validate_ca_bundle(ca_path)?;
+        }
+        Ok(())
+    }
+}
+
 /// État partagé du serveur
 #[derive(Clone)]
 pub struct ServerState {
     pub blockchain: Arc<Blockchain>,
+    /// Chaîne du nœud, que ses blocs font avancer ; `blockchain` n'en est
+    /// qu'un instantané pris au démarrage
+    pub live_chain: Option<Arc<tokio::sync::RwLock<Blockchain>>>,
     pub auth_service: Arc<AuthService>,
     pub user_manager: Arc<tokio::sync::RwLock<UserManager>>,
     pub config: ApiConfig,
+    pub archives: Arc<ArchiveService>,
     pub start_time: SystemTime,
     pub version: ApiVersion,
+    /// Récupération des contenus archivés, absente si aucune gateway n'est configurée
+    pub content: Option<Arc<ContentService>>,
+    /// Bounties d'archivage, absents si aucun registre ARC n'est rattaché
+    pub bounties: Option<Arc<BountyService>>,
+    /// Treasury communautaire exposé en lecture, absent si non rattaché
+    pub treasury: Option<Arc<tokio::sync::RwLock<Treasury>>>,
+    /// Récompenses de découverte des soumissions, absentes si non rattachées
+    pub discovery: Option<Arc<DiscoveryRewarder>>,
+    /// Signataire des réponses REST, absent si la signature est désactivée
+    pub response_signer: Option<Arc<ResponseSigner>>,
+    /// Synchronisation P2P du nœud, dont la progression est exposée par `/nodes/local/status`
+    pub sync: Option<Arc<SyncService>>,
+    /// Sous-systèmes sondés par `/health` en plus de la blockchain (stockage, P2P...)
+    pub health_probes: Vec<Arc<dyn HealthProbe>>,
+    /// Jeton d'arrêt partagé par les serveurs REST, gRPC et WebSocket
+    pub shutdown: ShutdownToken,
+    /// Bus des événements temps réel, partagé par les API WebSocket et GraphQL
+    pub events: EventBus,
+    /// Collecteur exposé sur `/metrics`
+    #[cfg(feature = "metrics")]
+    pub metrics: Arc<MetricsCollector>,
+    /// Sources additionnelles exposées sur `/metrics` (Gateway, P2P...)
+    #[cfg(feature = "metrics")]
+    pub metrics_exporters: Vec<Arc<dyn PrometheusExporter>>,
 }
 
 impl ServerState {
@@ -83,27 +150,176 @@ impl ServerState {
         user_manager: Arc<tokio::sync::RwLock<UserManager>>,
         config: ApiConfig,
     ) -> Self {
+        let mut archives = ArchiveService::new(config.rest.gateway_url.clone())
+            .with_max_content_size(config.rest.max_content_size);
+        if let Some(options) = &config.rest.crawler {
+            match CrawlEngine::new(options.clone()) {
+                Ok(crawler) => archives = archives.with_crawler(Arc::new(crawler)),
+                Err(e) => error!("Crawler disabled: {}", e),
+            }
+        }
+        let archives = Arc::new(archives);
+
         Self {
             blockchain,
+            live_chain: None,
             auth_service,
             user_manager,
             config,
+            archives,
             start_time: SystemTime::now(),
             version: ApiVersion::default(),
+            content: None,
+            bounties: None,
+            treasury: None,
+            discovery: None,
+            response_signer: None,
+            sync: None,
+            health_probes: Vec::new(),
+            shutdown: ShutdownToken::new(),
+            events: EventBus::default(),
+            #[cfg(feature = "metrics")]
+            metrics: Arc::new(MetricsCollector::new(MetricsConfig::default())),
+            #[cfg(feature = "metrics")]
+            metrics_exporters: Vec::new(),
         }
     }
+
+    /// Remplace le service d'archives, par exemple pour en partager un entre plusieurs serveurs
+    pub fn with_archive_service(mut self, archives: Arc<ArchiveService>) -> Self {
+        self.archives = archives;
+        self
+    }
+
+    /// Active la récupération des contenus archivés
+    pub fn with_content_service(mut self, content: Arc<ContentService>) -> Self {
+        self.content = Some(content);
+        self
+    }
+
+    /// Active les bounties d'archivage
+    pub fn with_bounty_service(mut self, bounties: Arc<BountyService>) -> Self {
+        self.bounties = Some(bounties);
+        self
+    }
+
+    /// Expose le rapport du treasury et l'état des jalons
+    pub fn with_treasury(mut self, treasury: Arc<tokio::sync::RwLock<Treasury>>) -> Self {
+        self.treasury = Some(treasury);
+        self
+    }
+
+    /// Réserve la récompense de découverte des soumissions inédites
+    pub fn with_discovery_rewarder(mut self, discovery: Arc<DiscoveryRewarder>) -> Self {
+        self.discovery = Some(discovery);
+        self
+    }
+
+    /// Active la signature des réponses REST
+    pub fn with_response_signer(mut self, signer: Arc<ResponseSigner>) -> Self {
+        self.response_signer = Some(signer);
+        self
+    }
+
+    /// Expose la progression de synchronisation du nœud
+    pub fn with_sync_service(mut self, sync: Arc<SyncService>) -> Self {
+        self.sync = Some(sync);
+        self
+    }
+
+    /// Ajoute un sous-système aux contrôles de `/health`
+    pub fn with_health_probe(mut self, probe: Arc<dyn HealthProbe>) -> Self {
+        self.health_probes.push(probe);
+        self
+    }
+
+    /// Rattache les serveurs au jeton d'un `ShutdownCoordinator`
+    pub fn with_shutdown_token(mut self, token: ShutdownToken) -> Self {
+        self.shutdown = token;
+        self
+    }
+
+    /// Suit la chaîne du nœud : hauteur, blocs diffusés et snapshots annoncés
+    pub fn with_live_chain(mut self, chain: Arc<tokio::sync::RwLock<Blockchain>>) -> Self {
+        self.live_chain = Some(chain);
+        self
+    }
+
+    /// Hauteur de la chaîne du nœud, à défaut celle de l'instantané
+    pub async fn chain_height(&self) -> u64 {
+        match &self.live_chain {
+            Some(chain) => chain.read().await.height(),
+            None => self.blockchain.height(),
+        }
+    }
+
+    /// Publie sur le bus `new_blocks` chaque bloc ajouté à la chaîne du nœud
+    ///
+    /// Alimente les abonnés WebSocket et GraphQL jusqu'à l'arrêt du serveur.
+    /// Sans chaîne rattachée, aucun bloc n'est publié.
+    pub async fn spawn_block_events(&self) -> Option<tokio::task::JoinHandle<()>> {
+        use tokio::sync::broadcast::error::RecvError;
+
+        let chain = self.live_chain.clone()?;
+        let mut blocks = chain.read().await.subscribe_blocks();
+        let events = self.events.clone();
+        let shutdown = self.shutdown.clone();
+
+        Some(tokio::spawn(async move {
+            loop {
+                let notification = tokio::select! {
+                    _ = shutdown.triggered() => break,
+                    received = blocks.recv() => match received {
+                        Ok(notification) => notification,
+                        Err(RecvError::Lagged(skipped)) => {
+                            tracing::debug!("Block event forwarder skipped {} blocks", skipped);
+                            continue;
+                        }
+                        Err(RecvError::Closed) => break,
+                    },
+                };
+                let Some(block) = chain.read().await.get_block_by_height(notification.height).map(crate::api::types::BlockDto::from) else {
+                    continue;
+                };
+                let message = websocket::MessageBuilder::new_block(websocket::BlockUpdate::from(block));
+                events.publish(websocket::SubscriptionTopic::NewBlocks.as_str(), message);
+            }
+        }))
+    }
+
+    /// Expose sur `/metrics` le collecteur alimenté par la couche de stockage
+    #[cfg(feature = "metrics")]
+    pub fn with_metrics_collector(mut self, collector: Arc<MetricsCollector>) -> Self {
+        self.metrics = collector;
+        self
+    }
+
+    /// Ajoute une source de métriques à la cible de scrape `/metrics`
+    #[cfg(feature = "metrics")]
+    pub fn with_metrics_exporter(mut self, exporter: Arc<dyn PrometheusExporter>) -> Self {
+        self.metrics_exporters.push(exporter);
+        self
+    }
 }
 
 /// Handle du serveur pour le contrôler
 pub struct ServerHandle {
     pub addr: SocketAddr,
-    pub shutdown_tx: tokio::sync::oneshot::Sender<()>,
+    pub shutdown: ShutdownToken,
+    drained: Drained,
 }
 
 impl ServerHandle {
     /// Arrête le serveur proprement
-    pub fn shutdown(self) -> Result<(), ()> {
-        self.shutdown_tx.send(()).map_err(|_| ())
+    ///
+    /// Déclenche le jeton partagé : les autres serveurs du même état s'arrêtent aussi.
+    pub fn shutdown(&self) {
+        self.shutdown.trigger();
+    }
+
+    /// Se résout quand le serveur a cessé d'écouter et drainé ses requêtes
+    pub async fn wait_for_shutdown(&self) {
+        self.drained.wait().await;
     }
 
     /// Retourne l'adresse d'écoute du serveur
@@ -112,10 +328,54 @@ impl ServerHandle {
     }
 }
 
+#[async_trait::async_trait]
+impl ShutdownHook for ServerHandle {
+    fn name(&self) -> &str {
+        "api"
+    }
+
+    async fn on_shutdown(&self, phase: ShutdownPhase) -> crate::Result<()> {
+        if phase == ShutdownPhase::Drain {
+            self.shutdown();
+            self.wait_for_shutdown().await;
+        }
+        Ok(())
+    }
+}
+
+/// Sert `app` jusqu'au déclenchement de `shutdown`, puis draine les connexions
+fn serve(listener: TcpListener, app: Router, shutdown: ShutdownToken, drain_timeout: Duration) -> ApiResult<ServerHandle> {
+    let addr = listener.local_addr()
+        .map_err(|e| ApiError::internal(format!("Failed to get local address: {}", e)))?;
+
+    let signal = shutdown.clone();
+    let server = async move {
+        // L'adresse du pair permet de reconnaître les proxies de confiance
+        let result = axum::serve(listener, app.into_make_service_with_connect_info::<SocketAddr>())
+            .with_graceful_shutdown(async move {
+                signal.triggered().await;
+                info!("Shutting down API server gracefully");
+            })
+            .await;
+        if let Err(e) = result {
+            error!("Server error: {}", e);
+        }
+    };
+    let drained = Drained::spawn("API", shutdown.clone(), drain_timeout, server);
+
+    Ok(ServerHandle { addr, shutdown, drained })
+}
+
 /// Serveur API principal
 pub struct ApiServer {
     config: ApiConfig,
     state: ServerState,
+    /// Réseau P2P du nœud, démarré avec le serveur
+    p2p: Option<P2PManager>,
+    /// Règlement des livraisons de contenu, lancé avec le serveur
+    delivery_settler: Option<crate::api::service::DeliverySettler>,
+    /// Stockage du nœud, source des contenus servis par `/archives/:id/content`
+    storage: Option<Arc<StorageManager>>,
 }
 
 impl ApiServer {
@@ -129,24 +389,154 @@ impl ApiServer {
         let user_manager = Arc::new(tokio::sync::RwLock::new(UserManager::new()));
 
         // Crée l'état du serveur
-        let state = ServerState::new(
+        let mut state = ServerState::new(
             blockchain,
             auth_service,
             user_manager,
             config.clone(),
         );
 
-        Ok(Self { config, state })
+        // Sans fichier de clé, un signataire externe doit être fourni via `with_signer`
+        if let (true, Some(key_path)) = (config.rest.sign_responses, config.rest.signing_key_path.as_deref()) {
+            let signer = ResponseSigner::from_key_file(key_path)?;
+            info!("REST responses signed with key {}", signer.key_id());
+            state = state.with_response_signer(Arc::new(signer));
+        }
+
+        Ok(Self { config, state, p2p: None, delivery_settler: None, storage: None })
+    }
+
+    /// Suit la chaîne du nœud plutôt que l'instantané pris au démarrage
+    ///
+    /// Ses blocs sont diffusés aux abonnés `new_blocks` et `blockAdded`. À
+    /// rattacher avant `with_p2p`, dont la sonde et l'annonce des snapshots
+    /// lisent aussi cette chaîne.
+    pub fn with_live_chain(mut self, chain: Arc<tokio::sync::RwLock<Blockchain>>) -> Self {
+        self.state = self.state.with_live_chain(chain);
+        self
+    }
+
+    /// Rattache le réseau P2P décrit par `config.p2p`
+    ///
+    /// Sa synchronisation est exposée par `/nodes/local/status` et il est sondé
+    /// par `/health` ; il démarre et s'arrête avec le serveur.
+    pub async fn with_p2p(mut self) -> ApiResult<Self> {
+        let p2p = P2PManager::new(self.config.p2p.clone(), self.state.clone()).await?;
+        self.state = self.state
+            .with_sync_service(p2p.sync_service())
+            .with_health_probe(Arc::new(p2p.clone()));
+        #[cfg(feature = "metrics")]
+        {
+            self.state = self.state.with_metrics_exporter(Arc::new(p2p.clone()));
+        }
+        self.p2p = Some(p2p);
+        Ok(self)
+    }
+
+    /// Sert les contenus archivés depuis le stockage du nœud et le sonde sur `/health`
+    ///
+    /// Un stockage saturé rend le nœud indisponible (`/health/ready`) sans
+    /// faire échouer la sonde de vivacité. Les contenus sont lus dans les
+    /// répliques du stockage, derrière un cache de gateway ; les métriques du
+    /// stockage sont exposées sur `/metrics`.
+    pub fn with_storage(mut self, storage: Arc<StorageManager>) -> Self {
+        #[cfg(feature = "metrics")]
+        {
+            self.state = self.state.with_metrics_collector(storage.integrity_metrics().collector());
+        }
+        self.storage = Some(storage.clone());
+        self.with_health_probe(storage)
+    }
+
+    /// Ajoute une source de métriques (Gateway...) à la cible de scrape `/metrics`
+    #[cfg(feature = "metrics")]
+    pub fn with_metrics_exporter(mut self, exporter: Arc<dyn PrometheusExporter>) -> Self {
+        self.state = self.state.with_metrics_exporter(exporter);
+        self
+    }
+
+    /// Ajoute un sous-système aux contrôles de `/health`
+    pub fn with_health_probe(mut self, probe: Arc<dyn HealthProbe>) -> Self {
+        self.state = self.state.with_health_probe(probe);
+        self
+    }
+
+    /// Restaure `state` depuis le snapshot engagé dans l'en-tête de confiance `anchor`
+    ///
+    /// La synchronisation rapide démarre avec le réseau P2P, qui doit être rattaché.
+    pub fn with_fast_sync(
+        mut self,
+        anchor: crate::block::BlockHeader,
+        state: Arc<tokio::sync::RwLock<dyn crate::state::StateStorage>>,
+    ) -> ApiResult<Self> {
+        let p2p = self.p2p.take()
+            .ok_or_else(|| ApiError::internal("Fast sync requires the P2P network"))?;
+        self.p2p = Some(p2p.with_fast_sync(anchor, state));
+        Ok(self)
+    }
+
+    /// Signe les réponses REST avec un signataire externe (HSM, KMS...)
+    ///
+    /// Remplace la clé éventuellement chargée depuis `rest.signing_key_path`.
+    pub fn with_signer(mut self, signer: Arc<dyn Signer>) -> Self {
+        let signer = ResponseSigner::from_signer(signer);
+        info!("REST responses signed with key {}", signer.key_id());
+        self.state = self.state.with_response_signer(Arc::new(signer));
+        self
+    }
+
+    /// Rattache le serveur au jeton d'un `ShutdownCoordinator`
+    pub fn with_shutdown_token(mut self, token: ShutdownToken) -> Self {
+        self.state = self.state.with_shutdown_token(token);
+        self
+    }
+
+    /// Expose sous `/treasury` le treasury tenu par le modèle économique du nœud
+    pub fn with_treasury(mut self, treasury: Arc<tokio::sync::RwLock<Treasury>>) -> Self {
+        self.state = self.state.with_treasury(treasury);
+        self
+    }
+
+    /// Récompense les découvertes des soumissions, vérifiées périodiquement contre la chaîne
+    pub fn with_discovery_rewarder(mut self, discovery: DiscoveryRewarder) -> Self {
+        self.state = self.state.with_discovery_rewarder(Arc::new(discovery));
+        self
+    }
+
+    /// Règle périodiquement les livraisons consignées par le service de contenu
+    pub fn with_delivery_settler(mut self, settler: crate::api::service::DeliverySettler) -> Self {
+        self.delivery_settler = Some(settler);
+        self
     }
 
     /// Démarre le serveur
-    pub async fn start(self) -> ApiResult<ServerHandle> {
+    pub async fn start(mut self) -> ApiResult<ServerHandle> {
+        // Échoue avant d'ouvrir le moindre port si un fichier TLS est inexploitable
+        self.config.validate()?;
+        if self.config.rest.sign_responses && self.state.response_signer.is_none() {
+            return Err(ApiError::internal(
+                "Response signing requires rest.signing_key_path or an external signer",
+            ));
+        }
+
         let addr = SocketAddr::from((
             self.config.server.host.parse::<std::net::IpAddr>()
                 .map_err(|e| ApiError::internal(format!("Invalid host: {}", e)))?,
             self.config.server.port,
         ));
 
+        // Contenus servis depuis le stockage, requêtes consignées dans le journal du settler
+        if let Some(storage) = self.storage.as_ref().filter(|_| self.state.content.is_none()) {
+            let mut content = ContentService::new(
+                Arc::new(CacheLayer::new(CacheConfig::default())),
+                Arc::new(StorageContentFetcher::new(storage.clone())),
+            );
+            if let Some(settler) = &self.delivery_settler {
+                content = content.with_delivery_log(settler.log().clone());
+            }
+            self.state = self.state.with_content_service(Arc::new(content));
+        }
+
         // Crée l'application avec tous les routes
         let app = self.create_app().await?;
 
@@ -154,59 +544,100 @@ impl ApiServer {
         let listener = TcpListener::bind(addr).await
             .map_err(|e| ApiError::internal(format!("Failed to bind to {}: {}", addr, e)))?;
 
-        let actual_addr = listener.local_addr()
-            .map_err(|e| ApiError::internal(format!("Failed to get local address: {}", e)))?;
+        // Lance le serveur dans une tâche séparée, arrêtée par le jeton partagé
+        let handle = serve(
+            listener,
+            app,
+            self.state.shutdown.clone(),
+            Duration::from_secs(self.config.server.shutdown_timeout),
+        )?;
 
-        info!("API server starting on {}", actual_addr);
+        info!("API server started successfully on {}", handle.addr);
 
-        // Canal pour l'arrêt propre
-        let (shutdown_tx, mut shutdown_rx) = tokio::sync::oneshot::channel();
-
-        // Lance le serveur
-        let server_future = async move {
-            axum::serve(listener, app)
-                .with_graceful_shutdown(async {
-                    let _ = shutdown_rx.recv().await;
-                    info!("Shutting down API server gracefully");
-                })
-                .await
-        };
+        if let Some(settler) = self.delivery_settler {
+            settler.spawn(self.state.shutdown.clone());
+        }
+        if let Some(discovery) = &self.state.discovery {
+            discovery.clone().spawn(self.state.shutdown.clone());
+        }
+        self.state.spawn_block_events().await;
+        if let (Some(content), Some(storage)) = (&self.state.content, self.storage) {
+            content.clone().spawn_node_refresh(storage, self.state.shutdown.clone());
+        }
 
-        // Lance le serveur dans une tâche séparée
-        tokio::spawn(async move {
-            if let Err(e) = server_future.await {
-                error!("Server error: {}", e);
+        if let Some(p2p) = self.p2p {
+            if let Err(e) = p2p.start().await {
+                handle.shutdown();
+                return Err(e);
             }
-        });
-
-        info!("API server started successfully on {}", actual_addr);
+            let shutdown = self.state.shutdown.clone();
+            tokio::spawn(async move {
+                shutdown.triggered().await;
+                if let Err(e) = p2p.shutdown_gracefully().await {
+                    error!("P2P shutdown failed: {}", e);
+                }
+            });
+        }
 
-        Ok(ServerHandle {
-            addr: actual_addr,
-            shutdown_tx,
-        })
+        Ok(handle)
     }
 
     /// Crée l'application Axum avec tous les routes et middlewares
     async fn create_app(&self) -> ApiResult<Router> {
+        // Les buckets des clients inactifs sont purgés jusqu'à l'arrêt du serveur
+        let rate_limiters = Arc::new(RateLimiters::new(&self.config.middleware.rate_limit));
+        rate_limiters.start_cleanup_tasks(self.state.shutdown.clone());
+
         // État pour les middlewares
         let middleware_state = MiddlewareState {
             auth_service: self.state.auth_service.clone(),
-            rate_limiters: Arc::new(RateLimiters::new(&self.config.middleware.rate_limit)),
+            user_manager: self.state.user_manager.clone(),
+            rate_limiters,
             config: self.config.middleware.clone(),
         };
 
         // Routes publiques (sans authentification)
         let public_routes = Router::new()
             .route("/health", get(health_check))
+            .route("/health/live", get(liveness_check))
+            .route("/health/ready", get(readiness_check))
             .route("/version", get(version_info))
-            .route("/metrics", get(metrics));
+            .route("/api/v1/auth/refresh", post(rest::refresh_auth_token))
+            .route("/api/v1/auth/revoke", post(rest::revoke_auth_token));
+
+        // Cible de scrape Prometheus
+        #[cfg(feature = "metrics")]
+        let public_routes = public_routes.route("/metrics", get(metrics));
+
+        // Appels anonymes : budget par IP
+        let public_routes = public_routes.layer(axum::middleware::from_fn_with_state(
+            middleware_state.clone(),
+            crate::api::middleware::budget_middleware,
+        ));
+
+        // La signature enveloppe la négociation pour couvrir les octets envoyés
+        let rest_routes = match &self.state.response_signer {
+            Some(signer) => rest::create_routes().await?.layer(axum::middleware::from_fn_with_state(
+                signer.clone(),
+                rest::signing::response_signing_middleware,
+            )),
+            None => rest::create_routes().await?,
+        };
 
         // Routes API avec authentification
         let api_routes = Router::new()
-            .nest("/rest", rest::create_routes().await?)
+            .nest("/auth/keys", rest::routes::api_key_routes())
+            .route("/auth/sessions", delete(rest::revoke_sessions))
+            .nest("/bounties", rest::routes::bounty_routes())
+            .nest("/treasury", rest::routes::treasury_routes())
+            .nest("/rest", rest_routes)
             .nest("/graphql", graphql::create_routes().await?)
             .nest("/ws", websocket::create_routes().await?)
+            // Budget par utilisateur, une fois les claims connus
+            .layer(axum::middleware::from_fn_with_state(
+                middleware_state.clone(),
+                crate::api::middleware::budget_middleware,
+            ))
             .layer(axum::middleware::from_fn_with_state(
                 middleware_state.clone(),
                 crate::api::middleware::auth_middleware,
@@ -221,7 +652,6 @@ impl ApiServer {
             .layer(
                 ServiceBuilder::new()
                     .layer(TimeoutLayer::new(std::time::Duration::from_secs(self.config.server.request_timeout)))
-                    .layer(axum::middleware::from_fn(crate::api::middleware::request_id_middleware))
                     .layer(axum::middleware::from_fn(crate::api::middleware::logging_middleware))
                     .layer(axum::middleware::from_fn(crate::api::middleware::error_handler_middleware))
                     .layer(axum::middleware::from_fn_with_state(
@@ -231,12 +661,18 @@ impl ApiServer {
             );
 
         // Ajoute CORS si configuré
-        let app = if let Some(cors_layer) = cors_middleware(&self.config.middleware.cors) {
+        let app = if let Some(cors_layer) = cors_middleware(&self.config.middleware.cors)? {
             app.layer(cors_layer)
         } else {
             app
         };
 
+        // En-têtes de sécurité, aussi posés sur les réponses preflight
+        let app = app.layer(axum::middleware::from_fn_with_state(
+            self.config.middleware.security_headers.clone(),
+            crate::api::middleware::security_headers_middleware,
+        ));
+
         // Ajoute compression si configurée
         let app = if let Some(compression_layer) = compression_middleware(&self.config.middleware.compression) {
             app.layer(compression_layer)
@@ -251,31 +687,60 @@ impl ApiServer {
             app
         };
 
+        // Les réponses en streaming sont tronquées une fois le délai de drainage écoulé
+        let app = app.layer(axum::middleware::from_fn_with_state(
+            ShutdownDrain {
+                token: self.state.shutdown.clone(),
+                timeout: Duration::from_secs(self.config.server.shutdown_timeout),
+            },
+            crate::api::middleware::shutdown_drain_middleware,
+        ));
+
+        // Couche la plus externe : tous les logs de la requête portent son identifiant
+        let app = app.layer(axum::middleware::from_fn(crate::api::middleware::request_id_middleware));
+
         Ok(app)
     }
 }
 
-/// Handler pour le health check
-async fn health_check(State(state): State<ServerState>) -> Json<HealthStatus> {
-    let mut health = HealthStatus::healthy();
-    
-    // Calcule l'uptime
+/// Exécute tous les contrôles de santé
+async fn collect_health(state: &ServerState) -> HealthStatus {
+    let mut checks = std::collections::HashMap::new();
+    let blockchain = match &state.live_chain {
+        Some(chain) => health::check_blockchain(&*chain.read().await),
+        None => health::check_blockchain(&state.blockchain),
+    };
+    checks.insert("blockchain".to_string(), blockchain);
+    for probe in &state.health_probes {
+        checks.insert(probe.name().to_string(), probe.check().await);
+    }
+
+    let mut health = HealthStatus::from_checks(checks);
     if let Ok(uptime) = state.start_time.elapsed() {
         health.uptime = format_duration(uptime);
     }
+    health
+}
 
-    // Vérifie l'état de la blockchain
-    match state.blockchain.get_stats() {
-        Ok(_stats) => {
-            health.checks.insert("blockchain".to_string(), "healthy".to_string());
-        }
-        Err(_) => {
-            health.status = "degraded".to_string();
-            health.checks.insert("blockchain".to_string(), "unhealthy".to_string());
-        }
-    }
+/// Handler pour le health check
+///
+/// Renvoie le détail de chaque contrôle, avec un 503 si un contrôle critique échoue.
+async fn health_check(State(state): State<ServerState>) -> (StatusCode, Json<HealthStatus>) {
+    let health = collect_health(&state).await;
+    let code = if health.is_live() { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE };
+    (code, Json(health))
+}
+
+/// Sonde de vivacité Kubernetes
+async fn liveness_check(State(state): State<ServerState>) -> (StatusCode, Json<HealthStatus>) {
+    health_check(State(state)).await
+}
 
-    Json(health)
+/// Sonde de disponibilité Kubernetes : 503 tant que le nœud démarre ou se synchronise
+async fn readiness_check(State(state): State<ServerState>) -> (StatusCode, Json<HealthStatus>) {
+    let health = collect_health(&state).await;
+    let code = if health.is_ready() { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE };
+    (code, Json(health))
 }
 
 /// Handler pour les informations de version
@@ -284,22 +749,21 @@ async fn version_info(State(state): State<ServerState>) -> Json<ApiVersion> {
 }
 
 /// Handler pour les métriques Prometheus
-async fn metrics() -> Result<String, ApiError> {
-    // Ici on pourrait intégrer des métriques Prometheus
-    // Pour l'instant, on retourne un placeholder
-    Ok(format!(
-        "# HELP api_requests_total Total number of API requests\n\
-         # TYPE api_requests_total counter\n\
-         api_requests_total{{method=\"GET\",endpoint=\"/health\",status=\"200\"}} 1\n\
-         \n\
-         # HELP api_request_duration_seconds API request duration\n\
-         # TYPE api_request_duration_seconds histogram\n\
-         api_request_duration_seconds_bucket{{le=\"0.1\"}} 100\n\
-         api_request_duration_seconds_bucket{{le=\"0.5\"}} 1000\n\
-         api_request_duration_seconds_bucket{{le=\"+Inf\"}} 1000\n\
-         api_request_duration_seconds_sum 45.0\n\
-         api_request_duration_seconds_count 1000\n"
-    ))
+///
+/// Concatène les métriques de stockage et celles des sources enregistrées,
+/// toutes étiquetées avec les labels `node_id` et `region` du collecteur.
+#[cfg(feature = "metrics")]
+async fn metrics(State(state): State<ServerState>) -> impl axum::response::IntoResponse {
+    let mut body = state.metrics.export_prometheus().await;
+    let labels = state.metrics.prometheus_labels();
+    for exporter in &state.metrics_exporters {
+        body.push_str(&exporter.render_prometheus(&labels).await);
+    }
+
+    (
+        [(axum::http::header::CONTENT_TYPE, "text/plain; version=0.0.4; charset=utf-8")],
+        body,
+    )
 }
 
 /// Formate une durée en format lisible
@@ -326,6 +790,12 @@ fn format_duration(duration: std::time::Duration) -> String {
 pub struct ServerBuilder {
     config: ApiConfig,
     blockchain_config: Option<BlockchainConfig>,
+    /// Démarre le réseau P2P avec le serveur
+    p2p: bool,
+    /// Treasury exposé par le serveur
+    treasury: Option<Arc<tokio::sync::RwLock<Treasury>>>,
+    /// Chaîne du nœud suivie par le serveur
+    live_chain: Option<Arc<tokio::sync::RwLock<Blockchain>>>,
 }
 
 impl ServerBuilder {
@@ -353,11 +823,48 @@ impl ServerBuilder {
         self
     }
 
+    /// Applique CORS et HTTPS forcé d'après la configuration de sécurité du Gateway
+    pub fn with_gateway_security(mut self, security: &crate::nodes::gateway::GatewaySecurityConfig) -> Self {
+        self.config.middleware = self.config.middleware.with_gateway_security(security);
+        self
+    }
+
+    /// Sert l'API d'un Gateway Node : réseau P2P du nœud et sécurité du Gateway
+    pub fn with_gateway_config(self, gateway: &crate::nodes::gateway::GatewayNodeConfig) -> Self {
+        self.with_node_config(&gateway.node_config)
+            .with_gateway_security(&gateway.security_config)
+    }
+
+    /// Démarre le réseau P2P du nœud avec son adresse, ses bootstraps et son identité TLS
+    pub fn with_node_config(mut self, node: &crate::nodes::NodeConfiguration) -> Self {
+        self.config.p2p = self.config.p2p.for_node(node);
+        self.p2p = true;
+        self
+    }
+
+    /// Refuse les contenus que le stockage du nœud refuserait (`max_content_size`)
+    pub fn with_storage_config(mut self, storage: &crate::storage::StorageConfig) -> Self {
+        self.config.rest.max_content_size = storage.max_content_size;
+        self
+    }
+
     pub fn with_blockchain_config(mut self, blockchain_config: BlockchainConfig) -> Self {
         self.blockchain_config = Some(blockchain_config);
         self
     }
 
+    /// Expose le treasury communautaire et l'état de ses jalons
+    pub fn with_treasury(mut self, treasury: Arc<tokio::sync::RwLock<Treasury>>) -> Self {
+        self.treasury = Some(treasury);
+        self
+    }
+
+    /// Suit la chaîne du nœud (`NodeManager::blockchain`)
+    pub fn with_live_chain(mut self, chain: Arc<tokio::sync::RwLock<Blockchain>>) -> Self {
+        self.live_chain = Some(chain);
+        self
+    }
+
     pub async fn build(self) -> ApiResult<ApiServer> {
         // Crée la blockchain
         let blockchain_config = self.blockchain_config.unwrap_or_default();
@@ -366,7 +873,17 @@ impl ServerBuilder {
                 .map_err(|e| ApiError::internal(format!("Failed to create blockchain: {}", e)))?
         );
 
-        ApiServer::new(self.config, blockchain).await
+        let mut server = ApiServer::new(self.config, blockchain).await?;
+        if let Some(treasury) = self.treasury {
+            server = server.with_treasury(treasury);
+        }
+        if let Some(chain) = self.live_chain {
+            server = server.with_live_chain(chain);
+        }
+        if self.p2p {
+            return server.with_p2p().await;
+        }
+        Ok(server)
     }
 }
 
@@ -411,6 +928,76 @@ mod tests {
         assert!(builder.config.server.dev_mode);
     }
 
+    #[test]
+    fn test_server_builder_applies_node_security_to_p2p() {
+        let node = crate::nodes::NodeConfiguration::from_toml_str(r#"
+            [node]
+            type = "relay"
+            node_id = "0101010101010101010101010101010101010101010101010101010101010101"
+            listen_port = 9100
+            bootstrap_nodes = ["10.0.0.1:9100"]
+
+            [security]
+            tls_cert_path = "/etc/archivechain/p2p.crt"
+            tls_key_path = "/etc/archivechain/p2p.key"
+            require_encryption = true
+        "#, std::path::Path::new("/srv")).unwrap();
+
+        let builder = ServerBuilder::new().with_node_config(&node);
+        assert!(builder.p2p);
+        let p2p = &builder.config.p2p;
+        assert_eq!(p2p.listen_port, 9100);
+        assert_eq!(p2p.bootstrap_nodes, vec!["10.0.0.1:9100".to_string()]);
+        assert_eq!(p2p.tls_cert_path.as_deref(), Some("/etc/archivechain/p2p.crt"));
+        assert_eq!(p2p.tls_key_path.as_deref(), Some("/etc/archivechain/p2p.key"));
+        assert!(p2p.require_encryption);
+        assert!(!p2p.allow_plaintext_fallback);
+    }
+
+    #[test]
+    fn test_server_builder_applies_gateway_config() {
+        let mut gateway = crate::nodes::gateway::GatewayNodeConfig::default();
+        gateway.security_config.cors_enabled = true;
+        gateway.security_config.cors_allowed_origins = vec!["https://app.example.org".to_string()];
+        gateway.security_config.force_https = true;
+
+        let builder = ServerBuilder::new().with_gateway_config(&gateway);
+        assert!(builder.p2p);
+        assert_eq!(builder.config.p2p.listen_port, gateway.node_config.listen_port);
+        assert!(builder.config.middleware.cors.is_origin_allowed("https://app.example.org"));
+        assert!(builder.config.middleware.security_headers.force_https);
+    }
+
+    #[tokio::test]
+    async fn test_server_builder_applies_storage_content_limit() {
+        use base64::{engine::general_purpose::STANDARD, Engine};
+
+        let storage = crate::storage::StorageConfig { max_content_size: 16, ..Default::default() };
+        let server = ServerBuilder::new().with_storage_config(&storage).build().await.unwrap();
+
+        let request = crate::api::types::CreateArchiveRequest {
+            url: "https://example.com/big".to_string(),
+            metadata: std::collections::HashMap::new(),
+            options: crate::api::types::ArchiveOptions::default(),
+            content: Some(STANDARD.encode([0u8; 32])),
+            dry_run: true,
+        };
+        assert!(matches!(
+            server.state.archives.estimate_archive(&request),
+            Err(ApiError::ContentTooLarge { size: 32, limit: 16 })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_server_builder_exposes_treasury() {
+        let treasury = Arc::new(tokio::sync::RwLock::new(Treasury::default()));
+        let server = ServerBuilder::new().with_treasury(treasury.clone()).build().await.unwrap();
+        assert!(server.state.treasury.as_ref().is_some_and(|exposed| Arc::ptr_eq(exposed, &treasury)));
+
+        let server = ServerBuilder::new().build().await.unwrap();
+        assert!(server.state.treasury.is_none());
+    }
+
     #[tokio::test]
     async fn test_server_state_creation() {
         let blockchain_config = BlockchainConfig::default();
@@ -430,14 +1017,260 @@ mod tests {
         assert_eq!(state.version.version, env!("CARGO_PKG_VERSION"));
     }
 
+    #[cfg(feature = "metrics")]
+    #[tokio::test]
+    async fn test_metrics_endpoint() {
+        use axum::{http::header, response::IntoResponse};
+
+        let blockchain = Arc::new(Blockchain::new(BlockchainConfig::default()).unwrap());
+        let auth_service = Arc::new(AuthService::new(crate::api::auth::AuthConfig::default()).unwrap());
+        let user_manager = Arc::new(tokio::sync::RwLock::new(UserManager::new()));
+        let collector = Arc::new(MetricsCollector::new(MetricsConfig {
+            node_id: Some("node-1".to_string()),
+            ..Default::default()
+        }));
+        collector.record_successful_operation(80, 1024).await;
+
+        let state = ServerState::new(blockchain, auth_service, user_manager, ApiConfig::default())
+            .with_metrics_collector(collector);
+        let response = metrics(State(state)).await.into_response();
+
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(response.headers()[header::CONTENT_TYPE].to_str().unwrap().starts_with("text/plain; version=0.0.4"));
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+        assert!(body.contains("archivechain_storage_operation_success_ratio{node_id=\"node-1\"} 1\n"));
+    }
+
+    #[cfg(feature = "metrics")]
+    #[tokio::test]
+    async fn test_metrics_endpoint_includes_gateway_and_p2p_series() {
+        use axum::response::IntoResponse;
+        use crate::api::p2p::{P2PConfig, P2PManager};
+        use crate::nodes::{ApiType, gateway::{GatewayNode, GatewayNodeConfig}};
+
+        let blockchain = Arc::new(Blockchain::new(BlockchainConfig::default()).unwrap());
+        let auth_service = Arc::new(AuthService::new(crate::api::auth::AuthConfig::default()).unwrap());
+        let user_manager = Arc::new(tokio::sync::RwLock::new(UserManager::new()));
+        let collector = Arc::new(MetricsCollector::new(MetricsConfig {
+            node_id: Some("node-1".to_string()),
+            region: Some("eu-west".to_string()),
+            ..Default::default()
+        }));
+        let state = ServerState::new(blockchain, auth_service, user_manager, ApiConfig::default())
+            .with_metrics_collector(collector);
+
+        let gateway = GatewayNode::new(GatewayNodeConfig::default(), crate::crypto::generate_keypair().unwrap()).unwrap();
+        for _ in 0..2 {
+            // Aucun backend configuré : la requête échoue mais reste comptabilisée
+            let _ = gateway.handle_http_request(ApiType::Rest, "10.0.0.1", None, b"GET /").await;
+        }
+        let p2p = P2PManager::new(P2PConfig::default(), state.clone()).await.unwrap();
+        let state = state
+            .with_metrics_exporter(Arc::new(gateway))
+            .with_metrics_exporter(Arc::new(p2p));
+
+        let response = metrics(State(state)).await.into_response();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.unwrap();
+        let body = String::from_utf8(body.to_vec()).unwrap();
+
+        // Chaque échantillon est parseable et appartient à une famille typée
+        let mut typed_families = std::collections::HashSet::new();
+        for line in body.lines().filter(|line| !line.is_empty()) {
+            if let Some(declaration) = line.strip_prefix("# TYPE ") {
+                let (name, kind) = declaration.split_once(' ').unwrap();
+                assert!(matches!(kind, "counter" | "gauge"), "type inattendu: {}", line);
+                typed_families.insert(name.to_string());
+                continue;
+            }
+            if line.starts_with('#') {
+                continue;
+            }
+            let (series, value) = line.rsplit_once(' ').unwrap();
+            assert!(value.parse::<f64>().is_ok() || matches!(value, "NaN" | "+Inf" | "-Inf"), "valeur invalide: {}", line);
+            let name = series.split('{').next().unwrap();
+            assert!(name.chars().all(|c| c.is_ascii_alphanumeric() || c == '_' || c == ':'), "nom invalide: {}", line);
+            assert!(typed_families.contains(name), "famille sans TYPE: {}", line);
+            assert!(series.contains("node_id=\"node-1\",region=\"eu-west\""), "labels manquants: {}", line);
+        }
+
+        for expected in [
+            "archivechain_gateway_requests_total{node_id=\"node-1\",region=\"eu-west\",api=\"rest\"} 2\n",
+            "archivechain_gateway_rate_limiter_requests_total{node_id=\"node-1\",region=\"eu-west\",decision=\"allowed\"} 2\n",
+            "archivechain_gateway_cache_hit_ratio{node_id=\"node-1\",region=\"eu-west\"} 0\n",
+            "archivechain_p2p_peers{node_id=\"node-1\",region=\"eu-west\",state=\"connected\"} 0\n",
+            "archivechain_p2p_bytes_total{node_id=\"node-1\",region=\"eu-west\",direction=\"received\"} 0\n",
+        ] {
+            assert!(body.contains(expected), "série absente: {}", expected);
+        }
+        for family in [
+            "archivechain_storage_capacity_bytes",
+            "archivechain_storage_access_latency_milliseconds",
+            "archivechain_storage_errors",
+        ] {
+            assert!(typed_families.contains(family), "famille absente: {}", family);
+        }
+    }
+
+    #[cfg(feature = "metrics")]
+    #[tokio::test]
+    async fn test_server_exposes_storage_and_gateway_metrics() {
+        use crate::nodes::gateway::{GatewayNode, GatewayNodeConfig};
+        use crate::storage::{AlertThresholds, StorageConfig, StoragePolicy};
+
+        let blockchain = Arc::new(Blockchain::new(BlockchainConfig::default()).unwrap());
+        let storage = Arc::new(StorageManager::new(StorageConfig::default(), StoragePolicy {
+            node_preferences: std::collections::HashMap::new(),
+            retention_policies: Vec::new(),
+            alert_thresholds: AlertThresholds::default(),
+        }).await.unwrap());
+        let gateway = GatewayNode::new(GatewayNodeConfig::default(), crate::crypto::generate_keypair().unwrap()).unwrap();
+
+        let server = ApiServer::new(ApiConfig::default(), blockchain).await.unwrap()
+            .with_storage(storage.clone())
+            .with_metrics_exporter(Arc::new(gateway));
+
+        assert!(Arc::ptr_eq(&server.state.metrics, &storage.integrity_metrics().collector()));
+        assert_eq!(server.state.metrics_exporters.len(), 1);
+    }
+
+    struct FixedProbe(&'static str, health::HealthCheck);
+
+    #[async_trait::async_trait]
+    impl HealthProbe for FixedProbe {
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        async fn check(&self) -> health::HealthCheck {
+            self.1.clone()
+        }
+    }
+
     #[tokio::test]
     async fn test_health_status() {
-        let health = HealthStatus::healthy();
+        let blockchain = Arc::new(Blockchain::new(BlockchainConfig::default()).unwrap());
+        let auth_service = Arc::new(AuthService::new(crate::api::auth::AuthConfig::default()).unwrap());
+        let user_manager = Arc::new(tokio::sync::RwLock::new(UserManager::new()));
+        let state = ServerState::new(blockchain, auth_service, user_manager, ApiConfig::default());
+
+        let (code, Json(health)) = health_check(State(state.clone())).await;
+        assert_eq!(code, StatusCode::OK);
         assert_eq!(health.status, "healthy");
-        assert!(health.checks.contains_key("database"));
         assert!(health.checks.contains_key("blockchain"));
-        assert!(health.checks.contains_key("storage"));
-        assert!(health.checks.contains_key("network"));
+
+        // Pairs insuffisants : vivant mais pas prêt
+        let state = state.with_health_probe(Arc::new(FixedProbe("network", health::HealthCheck::starting("0/3 peers"))));
+        let (code, Json(health)) = health_check(State(state.clone())).await;
+        assert_eq!(code, StatusCode::OK);
+        assert_eq!(health.status, "starting");
+        assert_eq!(liveness_check(State(state.clone())).await.0, StatusCode::OK);
+        assert_eq!(readiness_check(State(state.clone())).await.0, StatusCode::SERVICE_UNAVAILABLE);
+
+        // Stockage saturé : vivant, mais retiré du trafic
+        let state = state.with_health_probe(Arc::new(FixedProbe("storage", health::HealthCheck::unavailable("capacity 95.0%"))));
+        let (code, Json(health)) = readiness_check(State(state.clone())).await;
+        assert_eq!(code, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(health.status, "unavailable");
+        assert_eq!(health.checks["storage"].status, health::CheckStatus::Unavailable);
+        assert_eq!(liveness_check(State(state.clone())).await.0, StatusCode::OK);
+
+        // Chaîne illisible : contrôle critique en échec
+        let state = state.with_health_probe(Arc::new(FixedProbe("blockchain", health::HealthCheck::unhealthy("cannot read chain tip"))));
+        let (code, Json(health)) = health_check(State(state.clone())).await;
+        assert_eq!(code, StatusCode::SERVICE_UNAVAILABLE);
+        assert_eq!(health.status, "unhealthy");
+        assert_eq!(liveness_check(State(state)).await.0, StatusCode::SERVICE_UNAVAILABLE);
+    }
+
+    /// Écritures d'un handler de test, relevées à la phase de vidage
+    struct WriteLog {
+        writes: Arc<tokio::sync::Mutex<Vec<u32>>>,
+        flushed: Arc<tokio::sync::Mutex<Option<Vec<u32>>>>,
+    }
+
+    #[async_trait::async_trait]
+    impl ShutdownHook for WriteLog {
+        fn name(&self) -> &str {
+            "writes"
+        }
+
+        async fn on_shutdown(&self, phase: ShutdownPhase) -> crate::Result<()> {
+            if phase == ShutdownPhase::Flush {
+                *self.flushed.lock().await = Some(self.writes.lock().await.clone());
+            }
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_shutdown_drains_streaming_response_without_losing_writes() {
+        use crate::shutdown::{ShutdownConfig, ShutdownCoordinator};
+        use futures::StreamExt;
+
+        let drain_timeout = Duration::from_millis(300);
+        let coordinator = ShutdownCoordinator::new(ShutdownConfig {
+            drain_timeout: Duration::from_secs(5),
+            step_timeout: Duration::from_secs(1),
+        });
+        let token = coordinator.token();
+
+        let writes = Arc::new(tokio::sync::Mutex::new(Vec::new()));
+        let app = Router::new()
+            .route("/stream", get(|| async {
+                // Flux sans fin : seul le délai de drainage peut le terminer
+                let chunks = futures::stream::unfold(0u64, |i| async move {
+                    tokio::time::sleep(Duration::from_millis(20)).await;
+                    Some((Ok::<_, std::io::Error>(format!("chunk {}\n", i)), i + 1))
+                });
+                axum::body::Body::from_stream(chunks)
+            }))
+            .route("/write", post(|State(writes): State<Arc<tokio::sync::Mutex<Vec<u32>>>>| async move {
+                tokio::time::sleep(Duration::from_millis(100)).await;
+                writes.lock().await.push(1);
+                StatusCode::CREATED
+            }))
+            .with_state(writes.clone())
+            .layer(axum::middleware::from_fn_with_state(
+                ShutdownDrain { token: token.clone(), timeout: drain_timeout },
+                crate::api::middleware::shutdown_drain_middleware,
+            ));
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let handle = Arc::new(serve(listener, app, token, drain_timeout).unwrap());
+        let base = format!("http://{}", handle.addr());
+
+        let flushed = Arc::new(tokio::sync::Mutex::new(None));
+        let coordinator = coordinator
+            .with_hook(handle.clone())
+            .with_hook(Arc::new(WriteLog { writes: writes.clone(), flushed: flushed.clone() }));
+
+        // Un flux et une écriture sont en cours au moment de l'arrêt
+        let mut stream = reqwest::get(format!("{}/stream", base)).await.unwrap().bytes_stream();
+        assert!(stream.next().await.unwrap().is_ok());
+        let write = tokio::spawn(reqwest::Client::new().post(format!("{}/write", base)).send());
+        tokio::time::sleep(Duration::from_millis(30)).await;
+
+        let started = std::time::Instant::now();
+        let report = coordinator.shutdown().await;
+        let elapsed = started.elapsed();
+
+        assert!(report.is_clean(), "{:?}", report);
+        assert!(elapsed >= drain_timeout, "stream cut before the drain deadline: {:?}", elapsed);
+        assert!(elapsed < drain_timeout + Duration::from_secs(1), "drain took {:?}", elapsed);
+
+        // Le flux est terminé ou coupé, sans attendre davantage
+        let rest = tokio::time::timeout(Duration::from_millis(200), async {
+            while let Some(Ok(_)) = stream.next().await {}
+        });
+        assert!(rest.await.is_ok());
+
+        // L'écriture en vol a abouti avant la phase de vidage
+        assert_eq!(write.await.unwrap().unwrap().status(), reqwest::StatusCode::CREATED);
+        assert_eq!(*flushed.lock().await, Some(vec![1]));
+
+        // Le serveur n'accepte plus de connexions
+        handle.wait_for_shutdown().await;
+        assert!(reqwest::get(format!("{}/stream", base)).await.is_err());
     }
 
     #[test]
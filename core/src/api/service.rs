@@ -0,0 +1,1990 @@
+//! Couche de service partagée par les APIs ArchiveChain
+//!
+//! Les handlers REST et les resolvers GraphQL passent par ces services pour que la
+//! validation, la pagination et les erreurs restent identiques d'une API à l'autre.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use base64::{engine::general_purpose::STANDARD, Engine};
+use tokio::sync::RwLock;
+
+use crate::block::{normalize_url, ArchiveHistory, ArchiveVersion, Block};
+use crate::consensus::{IncentiveTable, RewardCalculator};
+use crate::constants::{economic::{ARC_DECIMALS, MIN_TRANSACTION_FEE}, SUPPORTED_CONTENT_TYPES, URL_PATTERN};
+use crate::contracts::{ContractContext, ContractError, ContractResult, ContextProvider, SmartContract};
+use crate::contracts::context::ExecutionEnvironment;
+use crate::contracts::archive_bounty::{
+    ArchiveBounty, ArchiveBountyContract, ArchiveCapture, ArchiveMetadata, BountyStatus, QualityLevel,
+};
+use crate::contracts::content_verification::ContentVerificationContract;
+use crate::crypto::{compute_blake3, Hash, PublicKey};
+use crate::token::ARCToken;
+use crate::transaction::Transaction;
+use crate::Blockchain;
+use crate::nodes::gateway::CacheLayer;
+use crate::error::ContentError;
+use crate::storage::{
+    extract_text, ArchiveManifest, CrawlEngine, CRAWLED_CONTENT_IMPORTANCE, DEFAULT_MAX_CONTENT_SIZE, NodeStatus, ReplicationStrategy, SearchDocument, SearchFilter, SearchIndex, StorageManager, StorageNodeInfo,
+};
+use crate::token::{
+    ArchivedContentLookup, DeliveryLog, DeliverySettlement, DiscoveryClaim, RewardSystem, ServedRequest, TokenOperationResult,
+};
+use crate::shutdown::ShutdownToken;
+
+use crate::api::{
+    ApiError, ApiResult,
+    types::*,
+    rest::{collect_validation, validation_errors_to_api_error, MetadataValidator, PaginationParams, ValidationError},
+    server::ServerState,
+};
+
+/// Archive enregistrée auprès du service
+#[derive(Debug, Clone)]
+pub struct ArchiveRecord {
+    /// Représentation publique de l'archive
+    pub archive: ArchiveDto,
+    /// Estimation des coûts calculée à la création
+    pub cost: CostEstimation,
+    /// Utilisateur ayant demandé l'archivage
+    pub owner: String,
+    /// Utilisateurs ayant soumis ce contenu, demandeur initial en tête
+    pub requesters: Vec<String>,
+    /// Hash BLAKE3 du contenu soumis, même empreinte que le checksum de l'`ArchiveBlock`
+    pub content_hash: Option<Hash>,
+    /// Popularité (accès/jour), même mesure que `ContentMetadata::popularity`
+    pub popularity: u64,
+    /// Résumé du crawl de l'URL par ce nœud, seule base de la qualité de l'archive
+    pub crawl: Option<ArchiveCapture>,
+}
+
+impl ArchiveRecord {
+    /// Renseigne la profondeur de confirmation de l'archive sur la chaîne
+    ///
+    /// L'archive est retrouvée par le checksum de son contenu ; sans contenu
+    /// connu ou hors des blocs conservés, elle reste non confirmée.
+    pub fn with_confirmations(mut self, blockchain: &Blockchain) -> Self {
+        let height = self.content_hash.as_ref().and_then(|hash| blockchain.find_archive_height(hash));
+        self.archive.confirmations = height.and_then(|height| blockchain.confirmations(height));
+        self.archive.finalized = height.map_or(false, |height| blockchain.is_final(height));
+        self
+    }
+}
+
+/// Profondeur de confirmation exigée par un client
+///
+/// `min_confirmations` fixe un nombre de blocs ; `finalized` exige la
+/// profondeur de finalité de la chaîne (`max_reorg_depth`). Une inclusion
+/// moins profonde est traitée comme introuvable.
+#[derive(Debug, Clone, Copy, Default, serde::Serialize, serde::Deserialize)]
+pub struct ConfirmationQuery {
+    pub min_confirmations: Option<u64>,
+    #[serde(default)]
+    pub finalized: bool,
+}
+
+impl ConfirmationQuery {
+    /// Profondeur exigée, `None` si aucune
+    pub fn required_depth(&self, blockchain: &Blockchain) -> Option<u64> {
+        match (self.min_confirmations, self.finalized) {
+            (min, true) => Some(min.unwrap_or(0).max(blockchain.finality_depth())),
+            (min, false) => min,
+        }
+    }
+
+    /// Vérifie qu'une inclusion atteint la profondeur exigée
+    pub fn is_satisfied(&self, blockchain: &Blockchain, confirmations: Option<u64>) -> bool {
+        match self.required_depth(blockchain) {
+            None => true,
+            Some(required) => confirmations.map_or(false, |confirmations| confirmations >= required),
+        }
+    }
+
+    /// Erreur `NotFound` si l'inclusion n'atteint pas la profondeur exigée
+    pub fn check(&self, blockchain: &Blockchain, what: &str, confirmations: Option<u64>) -> ApiResult<()> {
+        if self.is_satisfied(blockchain, confirmations) {
+            return Ok(());
+        }
+        Err(ApiError::not_found(format!(
+            "{} has {} confirmations, {} required",
+            what,
+            confirmations.unwrap_or(0),
+            self.required_depth(blockchain).unwrap_or(0)
+        )))
+    }
+}
+
+/// Résultat d'une soumission d'archive
+#[derive(Debug, Clone)]
+pub struct ArchiveSubmission {
+    /// Archive créée, ou archive existante de même contenu
+    pub record: ArchiveRecord,
+    /// La soumission a été fusionnée dans une archive existante
+    pub deduplicated: bool,
+}
+
+/// Critères de filtrage communs aux listes d'archives REST et GraphQL
+#[derive(Debug, Clone, Default)]
+pub struct ArchiveQuery {
+    /// Recherche plein texte ; les résultats sont alors classés par pertinence
+    pub text: Option<String>,
+    pub status: Option<ArchiveStatus>,
+    /// Toutes les étiquettes doivent être présentes
+    pub tags: Vec<String>,
+    pub domain: Option<String>,
+    pub content_type: Option<String>,
+    pub created_after: Option<chrono::DateTime<chrono::Utc>>,
+    pub created_before: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+impl ArchiveQuery {
+    /// Vérifie qu'une archive satisfait les critères, hors recherche plein texte
+    pub fn matches(&self, archive: &ArchiveDto) -> bool {
+        if self.status.as_ref().map_or(false, |status| *status != archive.status) {
+            return false;
+        }
+        if !self.tags.iter().all(|tag| archive.metadata.tags.contains(tag)) {
+            return false;
+        }
+        if let Some(domain) = &self.domain {
+            let host = url::Url::parse(&archive.url).ok().and_then(|u| u.host_str().map(str::to_string));
+            if host.as_deref() != Some(domain.as_str()) {
+                return false;
+            }
+        }
+        if self.content_type.as_ref().map_or(false, |ct| *ct != archive.metadata.mime_type) {
+            return false;
+        }
+        if self.created_after.map_or(false, |after| archive.created_at < after) {
+            return false;
+        }
+        if self.created_before.map_or(false, |before| archive.created_at > before) {
+            return false;
+        }
+        true
+    }
+}
+
+/// Accès aux contenus détenus par les nœuds de stockage
+#[async_trait::async_trait]
+pub trait ContentFetcher: Send + Sync {
+    /// Récupère auprès de `node` le contenu identifié par `content_hash`
+    async fn fetch(&self, node: &StorageNodeInfo, content_hash: &Hash) -> crate::error::Result<Vec<u8>>;
+}
+
+/// Lecture des contenus dans les répliques gérées par le `StorageManager`
+pub struct StorageContentFetcher {
+    storage: Arc<StorageManager>,
+}
+
+impl StorageContentFetcher {
+    pub fn new(storage: Arc<StorageManager>) -> Self {
+        Self { storage }
+    }
+}
+
+#[async_trait::async_trait]
+impl ContentFetcher for StorageContentFetcher {
+    async fn fetch(&self, node: &StorageNodeInfo, content_hash: &Hash) -> crate::error::Result<Vec<u8>> {
+        let Some(replicas) = self.storage.replica_store().await else {
+            return Err(crate::error::CoreError::Internal {
+                message: "Aucun magasin de répliques configuré: contenu inaccessible".to_string(),
+            });
+        };
+        replicas.read_replica(&node.node_id, content_hash).await
+    }
+}
+
+/// Intervalle entre deux rafraîchissements des nœuds connus du service de contenu
+const NODE_REFRESH_INTERVAL: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Origine d'un contenu servi par la gateway
+#[derive(Debug, Clone, PartialEq)]
+pub enum ContentSource {
+    /// Servi depuis la `CacheLayer`
+    Cache,
+    /// Récupéré auprès d'un nœud de stockage
+    StorageNode(crate::consensus::NodeId),
+}
+
+/// Contenu récupéré pour une archive
+#[derive(Debug, Clone)]
+pub struct FetchedContent {
+    pub data: Vec<u8>,
+    pub source: ContentSource,
+}
+
+/// Service de récupération des contenus archivés
+///
+/// Le cache de la gateway est consulté en premier ; à défaut, les nœuds de
+/// stockage actifs sont essayés du meilleur au moins bon score de performance.
+/// Le cache est alimenté en tâche de fond pour ne pas retarder la réponse.
+pub struct ContentService {
+    cache: Arc<CacheLayer>,
+    fetcher: Arc<dyn ContentFetcher>,
+    nodes: RwLock<Vec<StorageNodeInfo>>,
+    /// Journal où sont consignées les requêtes servies par un nœud de stockage
+    delivery_log: Option<Arc<RwLock<DeliveryLog>>>,
+}
+
+impl ContentService {
+    pub fn new(cache: Arc<CacheLayer>, fetcher: Arc<dyn ContentFetcher>) -> Self {
+        Self {
+            cache,
+            fetcher,
+            nodes: RwLock::new(Vec::new()),
+            delivery_log: None,
+        }
+    }
+
+    /// Consigne les requêtes servies dans `log`, base du règlement des livraisons
+    pub fn with_delivery_log(mut self, log: Arc<RwLock<DeliveryLog>>) -> Self {
+        self.delivery_log = Some(log);
+        self
+    }
+
+    /// Consigne une requête de contenu servie au demandeur
+    ///
+    /// Seules les requêtes servies par un nœud de stockage sont consignées :
+    /// un contenu servi depuis le cache de la gateway ne rémunère aucun nœud.
+    pub async fn record_served(
+        &self,
+        request_id: &str,
+        requester: &str,
+        content_hash: &Hash,
+        source: &ContentSource,
+        bytes: u64,
+    ) {
+        let (Some(log), ContentSource::StorageNode(node_id)) = (&self.delivery_log, source) else {
+            return;
+        };
+        log.write().await.record_request(request_id, ServedRequest {
+            content_hash: content_hash.clone(),
+            requester: requester.to_string(),
+            node_id: node_id.clone(),
+            bytes,
+            timestamp: chrono::Utc::now(),
+        });
+    }
+
+    /// Journal des requêtes servies, à régler par un `DeliverySettler`
+    pub fn delivery_log(&self) -> Option<&Arc<RwLock<DeliveryLog>>> {
+        self.delivery_log.as_ref()
+    }
+
+    /// Cache de contenus utilisé par le service
+    pub fn cache(&self) -> &Arc<CacheLayer> {
+        &self.cache
+    }
+
+    /// Remplace la liste des nœuds de stockage connus
+    pub async fn update_nodes(&self, nodes: Vec<StorageNodeInfo>) {
+        *self.nodes.write().await = nodes;
+    }
+
+    /// Recopie périodiquement les nœuds de `storage` jusqu'au déclenchement de `shutdown`
+    ///
+    /// Le premier rafraîchissement est immédiat.
+    pub fn spawn_node_refresh(self: Arc<Self>, storage: Arc<StorageManager>, shutdown: ShutdownToken) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(NODE_REFRESH_INTERVAL);
+            loop {
+                tokio::select! {
+                    _ = shutdown.triggered() => break,
+                    _ = interval.tick() => {}
+                }
+                self.update_nodes(storage.storage_nodes().await).await;
+            }
+        })
+    }
+
+    /// Nœuds actifs, du meilleur score de performance au moins bon
+    async fn ranked_nodes(&self) -> Vec<StorageNodeInfo> {
+        let mut nodes: Vec<StorageNodeInfo> = self.nodes.read().await
+            .iter()
+            .filter(|node| node.status == NodeStatus::Active)
+            .cloned()
+            .collect();
+        nodes.sort_by(|a, b| b.performance_score().total_cmp(&a.performance_score()));
+        nodes
+    }
+
+    /// Récupère un contenu par son hash
+    ///
+    /// Un contenu renvoyé par un nœud dont le hash ne correspond pas est
+    /// ignoré au profit du nœud suivant.
+    pub async fn fetch(&self, content_hash: &Hash) -> ApiResult<FetchedContent> {
+        if let Some(data) = self.cache.get_content(content_hash).await {
+            return Ok(FetchedContent { data, source: ContentSource::Cache });
+        }
+
+        let nodes = self.ranked_nodes().await;
+        if nodes.is_empty() {
+            return Err(ApiError::ServiceUnavailable("No storage node available".to_string()));
+        }
+
+        for node in nodes {
+            match self.fetcher.fetch(&node, content_hash).await {
+                Ok(data) if compute_blake3(&data) == *content_hash => {
+                    self.spawn_cache_fill(content_hash.clone(), data.clone());
+                    return Ok(FetchedContent { data, source: ContentSource::StorageNode(node.node_id) });
+                }
+                Ok(_) => tracing::warn!("Storage node {:?} returned corrupted content for {}", node.node_id, content_hash),
+                Err(e) => tracing::debug!("Storage node {:?} failed to serve {}: {}", node.node_id, content_hash, e),
+            }
+        }
+
+        Err(ApiError::not_found(format!("Content {} not available on any storage node", content_hash)))
+    }
+
+    /// Met le contenu en cache sans bloquer la réponse en cours
+    fn spawn_cache_fill(&self, content_hash: Hash, data: Vec<u8>) {
+        let cache = self.cache.clone();
+        tokio::spawn(async move {
+            cache.cache_content(content_hash, data, None).await;
+        });
+    }
+}
+
+/// Intervalle par défaut entre deux règlements des livraisons
+const DEFAULT_SETTLEMENT_INTERVAL: std::time::Duration = std::time::Duration::from_secs(3600);
+
+/// Délai laissé à l'API pour consigner une requête dont le nœud a déjà
+/// consigné la livraison
+const SETTLEMENT_GRACE_SECS: i64 = 300;
+
+/// Durée au-delà de laquelle une requête sans livraison revendiquée est oubliée
+const SERVED_REQUEST_RETENTION_SECS: i64 = 24 * 3600;
+
+/// Règlement périodique des livraisons consignées dans un `DeliveryLog`
+///
+/// Chaque règlement paie les livraisons vérifiées sur le pool de bande
+/// passante du `RewardSystem`, puis oublie les requêtes restées sans
+/// livraison au-delà de la durée de conservation.
+pub struct DeliverySettler {
+    log: Arc<RwLock<DeliveryLog>>,
+    rewards: Arc<RwLock<RewardSystem>>,
+    token: Arc<RwLock<ARCToken>>,
+    interval: std::time::Duration,
+}
+
+impl DeliverySettler {
+    pub fn new(log: Arc<RwLock<DeliveryLog>>, rewards: Arc<RwLock<RewardSystem>>, token: Arc<RwLock<ARCToken>>) -> Self {
+        Self { log, rewards, token, interval: DEFAULT_SETTLEMENT_INTERVAL }
+    }
+
+    pub fn with_interval(mut self, interval: std::time::Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Journal réglé, où le service de contenu consigne les requêtes servies
+    pub fn log(&self) -> &Arc<RwLock<DeliveryLog>> {
+        &self.log
+    }
+
+    /// Règle les livraisons consignées avant `now`, au délai de grâce près
+    pub async fn settle(&self, now: chrono::DateTime<chrono::Utc>) -> TokenOperationResult<DeliverySettlement> {
+        let until = now - chrono::Duration::seconds(SETTLEMENT_GRACE_SECS);
+        let tx_hash = compute_blake3(format!("delivery_settlement:{}", until.timestamp()).as_bytes());
+
+        let mut log = self.log.write().await;
+        let mut rewards = self.rewards.write().await;
+        let mut token = self.token.write().await;
+        let settlement = rewards.settle_deliveries(&mut log, until, &mut token, tx_hash)?;
+        log.prune_requests(now - chrono::Duration::seconds(SERVED_REQUEST_RETENTION_SECS));
+        Ok(settlement)
+    }
+
+    /// Lance les règlements périodiques jusqu'au déclenchement de `shutdown`
+    pub fn spawn(self, shutdown: ShutdownToken) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(self.interval);
+            // Le premier tick est immédiat : rien n'est encore consigné
+            interval.tick().await;
+            loop {
+                tokio::select! {
+                    _ = shutdown.triggered() => break,
+                    _ = interval.tick() => {}
+                }
+                match self.settle(chrono::Utc::now()).await {
+                    Ok(settlement) if settlement.settled_bytes > 0 => tracing::info!(
+                        "Delivery settlement: {} bytes paid, {} entries excluded",
+                        settlement.settled_bytes, settlement.excluded_entries
+                    ),
+                    Ok(_) => {}
+                    Err(e) => tracing::warn!("Delivery settlement failed: {}", e),
+                }
+            }
+        })
+    }
+}
+
+/// Intervalle par défaut entre deux vérifications des découvertes en attente
+const DEFAULT_DISCOVERY_CHECK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(600);
+
+/// Récompenses de première découverte des contenus soumis à l'API
+///
+/// Une soumission inédite réserve la récompense de son découvreur ; les
+/// vérifications périodiques la versent dès que la chaîne a archivé le contenu
+/// sous l'URL réclamée, et libèrent les réservations expirées.
+pub struct DiscoveryRewarder {
+    rewards: Arc<RwLock<RewardSystem>>,
+    token: Arc<RwLock<ARCToken>>,
+    index: Arc<dyn ArchivedContentLookup + Send + Sync>,
+    interval: std::time::Duration,
+}
+
+impl DiscoveryRewarder {
+    pub fn new(
+        rewards: Arc<RwLock<RewardSystem>>,
+        token: Arc<RwLock<ARCToken>>,
+        index: Arc<dyn ArchivedContentLookup + Send + Sync>,
+    ) -> Self {
+        Self { rewards, token, index, interval: DEFAULT_DISCOVERY_CHECK_INTERVAL }
+    }
+
+    pub fn with_interval(mut self, interval: std::time::Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    /// Réserve la récompense de découverte d'un contenu soumis depuis `url`
+    pub async fn claim(&self, discoverer: &PublicKey, content_hash: Hash, url: &str) -> TokenOperationResult<DiscoveryClaim> {
+        self.rewards.write().await.claim_discovery_reward(discoverer, content_hash, url, self.index.as_ref())
+    }
+
+    /// Verse les découvertes dont le stockage est confirmé et expire les autres
+    ///
+    /// Retourne le nombre de découvertes versées.
+    pub async fn finalize_stored(&self, now: chrono::DateTime<chrono::Utc>) -> usize {
+        let mut rewards = self.rewards.write().await;
+        let mut token = self.token.write().await;
+        let mut finalized = 0;
+        for content_hash in rewards.pending_discovery_claims() {
+            let tx_hash = compute_blake3(format!("discovery_reward:{}", content_hash.to_hex()).as_bytes());
+            // Un contenu pas encore archivé reste en attente jusqu'à l'expiration
+            if rewards.finalize_discovery_reward(&content_hash, self.index.as_ref(), &mut token, tx_hash).is_ok() {
+                finalized += 1;
+            }
+        }
+        rewards.expire_discovery_claims(now);
+        finalized
+    }
+
+    /// Lance les vérifications périodiques jusqu'au déclenchement de `shutdown`
+    pub fn spawn(self: Arc<Self>, shutdown: ShutdownToken) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(self.interval);
+            interval.tick().await;
+            loop {
+                tokio::select! {
+                    _ = shutdown.triggered() => break,
+                    _ = interval.tick() => {}
+                }
+                let finalized = self.finalize_stored(chrono::Utc::now()).await;
+                if finalized > 0 {
+                    tracing::info!("Discovery rewards: {} claims paid", finalized);
+                }
+            }
+        })
+    }
+}
+
+/// Taille supposée d'une page dont le contenu n'est pas joint (2 MB)
+const ESTIMATED_PAGE_SIZE: u64 = 2 * 1024 * 1024;
+
+/// Frais de stockage par MB et par réplique, en wei (0.001 ARC)
+const STORAGE_FEE_PER_MB_REPLICA: u128 = MIN_TRANSACTION_FEE as u128;
+
+/// Frais fixes de traitement d'une demande, en wei (0.0005 ARC)
+const PROCESSING_FEE: u128 = MIN_TRANSACTION_FEE as u128 / 2;
+
+/// Formate un montant en wei en ARC, sans zéros superflus
+fn format_arc(wei: u128) -> String {
+    let unit = 10u128.pow(ARC_DECIMALS as u32);
+    let fraction = format!("{:0width$}", wei % unit, width = ARC_DECIMALS as usize);
+    let fraction = fraction.trim_end_matches('0');
+    if fraction.is_empty() {
+        format!("{} ARC", wei / unit)
+    } else {
+        format!("{}.{} ARC", wei / unit, fraction)
+    }
+}
+
+/// Service de gestion des archives
+pub struct ArchiveService {
+    archives: RwLock<HashMap<String, ArchiveRecord>>,
+    /// Archive portant chaque hash de contenu
+    content_index: RwLock<HashMap<Hash, String>>,
+    /// Chronologie des captures de chaque URL
+    history: RwLock<ArchiveHistory<String>>,
+    /// Index plein texte des métadonnées, mis à jour sous le verrou de `archives`
+    search_index: RwLock<SearchIndex<String>>,
+    gateway_url: String,
+    /// Taille maximale d'un contenu soumis (`StorageConfig::max_content_size`)
+    max_content_size: u64,
+    /// Crawler du nœud, qui capture lui-même l'URL de chaque nouvelle archive
+    crawler: Option<Arc<CrawlEngine>>,
+    /// `URL_PATTERN` compilé une fois pour les simulations
+    url_regex: regex::Regex,
+}
+
+impl ArchiveService {
+    /// Crée un service dont les liens d'accès pointent vers `gateway_url`
+    pub fn new(gateway_url: impl Into<String>) -> Self {
+        Self {
+            archives: RwLock::new(HashMap::new()),
+            content_index: RwLock::new(HashMap::new()),
+            history: RwLock::new(ArchiveHistory::new()),
+            search_index: RwLock::new(SearchIndex::new()),
+            gateway_url: gateway_url.into(),
+            max_content_size: DEFAULT_MAX_CONTENT_SIZE,
+            crawler: None,
+            url_regex: regex::Regex::new(URL_PATTERN).expect("URL_PATTERN est une regex valide"),
+        }
+    }
+
+    /// Fait crawler l'URL de chaque nouvelle archive par ce nœud
+    pub fn with_crawler(mut self, crawler: Arc<CrawlEngine>) -> Self {
+        self.crawler = Some(crawler);
+        self
+    }
+
+    /// Indique si le nœud crawle lui-même les nouvelles archives
+    pub fn crawls_archives(&self) -> bool {
+        self.crawler.is_some()
+    }
+
+    /// Crawle l'URL d'une archive avec le crawler du nœud et enregistre le résultat
+    pub async fn crawl_archive(&self, archive_id: &str) -> ApiResult<ArchiveCapture> {
+        let crawler = self.crawler.clone()
+            .ok_or_else(|| ApiError::ServiceUnavailable("Archive crawling is not configured".to_string()))?;
+        let url = self.get_archive(archive_id).await?.archive.url;
+
+        let result = crawler.crawl(&url).await
+            .map_err(|e| ApiError::validation(format!("Crawl of {} failed: {}", url, e)))?;
+        self.record_crawl(archive_id, &result.manifest).await
+    }
+
+    /// Rattache à une archive le manifeste produit par le crawler de ce nœud
+    ///
+    /// Le résumé enregistré porte le hash du manifeste : c'est lui, et non un
+    /// contenu fourni par un client, qui mesure la qualité de l'archive.
+    pub async fn record_crawl(&self, archive_id: &str, manifest: &ArchiveManifest) -> ApiResult<ArchiveCapture> {
+        let manifest_json = manifest.to_json()
+            .map_err(|e| ApiError::internal(format!("Crawl manifest serialization failed: {}", e)))?;
+        let capture = ArchiveCapture::from_manifest(archive_id, compute_blake3(&manifest_json), manifest);
+
+        let mut archives = self.archives.write().await;
+        let record = archives.get_mut(archive_id)
+            .ok_or_else(|| ApiError::not_found(format!("Archive {} not found", archive_id)))?;
+        record.crawl = Some(capture.clone());
+        Ok(capture)
+    }
+
+    /// Limite la taille des contenus soumis, en général à `StorageConfig::max_content_size`
+    pub fn with_max_content_size(mut self, max_content_size: u64) -> Self {
+        self.max_content_size = max_content_size;
+        self
+    }
+
+    /// Valide une demande de création d'archive
+    ///
+    /// Toutes les erreurs sont rapportées ensemble, champ par champ.
+    pub fn validate_create_request(request: &CreateArchiveRequest) -> ApiResult<()> {
+        let url = if request.url.is_empty() {
+            Err(vec![ValidationError::new("url", "required", "URL is required")])
+        } else if url::Url::parse(&request.url).is_err() {
+            Err(vec![ValidationError::new("url", "invalid_format", "Invalid URL format")])
+        } else {
+            Ok(())
+        };
+        let content = match Self::decode_content(request) {
+            Ok(_) => Ok(()),
+            Err(_) => Err(vec![ValidationError::new("content", "invalid_encoding", "Content must be base64 encoded")]),
+        };
+
+        collect_validation([
+            url,
+            content,
+            MetadataValidator::validate_archive_metadata(&request.metadata),
+            MetadataValidator::validate_archive_tags(&request.metadata),
+        ])
+        .map_err(validation_errors_to_api_error)
+    }
+
+    /// Décode le contenu base64 joint à une demande
+    fn decode_content(request: &CreateArchiveRequest) -> ApiResult<Option<Vec<u8>>> {
+        request.content.as_deref()
+            .map(|content| STANDARD.decode(content).map_err(|_| ApiError::validation("Content must be base64 encoded")))
+            .transpose()
+    }
+
+    /// Valide le format d'un identifiant d'archive
+    pub fn validate_archive_id(archive_id: &str) -> ApiResult<()> {
+        if !archive_id.starts_with("arc_") {
+            return Err(ApiError::validation("Invalid archive ID format"));
+        }
+        Ok(())
+    }
+
+    /// Estime le coût d'archivage d'une demande
+    pub fn estimate_cost(request: &CreateArchiveRequest) -> CostEstimation {
+        let content = Self::decode_content(request).ok().flatten();
+        Self::project(request, content.as_deref()).cost_estimation
+    }
+
+    /// Simule une demande dont le contenu éventuel est encodé en base64
+    pub fn estimate_archive(&self, request: &CreateArchiveRequest) -> ApiResult<ArchiveEstimate> {
+        Self::validate_create_request(request)?;
+        let content = Self::decode_content(request)?;
+        self.estimate_with_content(request, content.as_deref())
+    }
+
+    /// Simule une demande d'archivage sans rien enregistrer
+    ///
+    /// En plus de la validation d'une soumission, l'URL doit respecter
+    /// `URL_PATTERN` et le type déclaré (`metadata.content_type`, `text/html` par
+    /// défaut) figurer dans `SUPPORTED_CONTENT_TYPES`. L'estimation retournée
+    /// est celle qu'appliquerait une soumission réelle de la même demande, et
+    /// un contenu qu'elle refuserait par sa taille est refusé de même.
+    pub fn estimate_with_content(&self, request: &CreateArchiveRequest, content: Option<&[u8]>) -> ApiResult<ArchiveEstimate> {
+        Self::validate_create_request(request)?;
+        self.check_content_size(content)?;
+
+        let estimate = Self::project(request, content);
+        let url = if self.url_regex.is_match(&request.url) {
+            Ok(())
+        } else {
+            Err(vec![ValidationError::new("url", "not_archivable", "URL is not archivable")])
+        };
+        let content_type = if SUPPORTED_CONTENT_TYPES.contains(&estimate.content_type.as_str()) {
+            Ok(())
+        } else {
+            Err(vec![ValidationError::new("metadata.content_type", "unsupported", "Content type is not supported")])
+        };
+        collect_validation([url, content_type]).map_err(validation_errors_to_api_error)?;
+
+        Ok(estimate)
+    }
+
+    /// Refuse un contenu dépassant `max_content_size`
+    fn check_content_size(&self, content: Option<&[u8]>) -> ApiResult<()> {
+        match content.map(|content| content.len() as u64) {
+            Some(size) if size > self.max_content_size => {
+                Err(ContentError::ContentTooLarge { size, limit: self.max_content_size }.into())
+            }
+            _ => Ok(()),
+        }
+    }
+
+    /// Taille, réplication, coût et récompense projetés pour une demande
+    fn project(request: &CreateArchiveRequest, content: Option<&[u8]>) -> ArchiveEstimate {
+        let content_type = request.metadata.get("content_type")
+            .and_then(|content_type| content_type.split(';').next())
+            .map(|content_type| content_type.trim().to_ascii_lowercase())
+            .unwrap_or_else(|| "text/html".to_string());
+        let (estimated_size, size_is_exact) = match content {
+            Some(content) => (content.len() as u64, true),
+            None => {
+                let limit = request.options.max_total_size.unwrap_or(u64::MAX);
+                (ESTIMATED_PAGE_SIZE.min(limit), false)
+            }
+        };
+
+        // Le demandeur ne choisit pas l'importance : c'est celle que la capture
+        // attribuera au contenu qui fixe sa réplication
+        let replication_strategy = ReplicationStrategy::for_importance(&CRAWLED_CONTENT_IMPORTANCE);
+        let replicas = replication_strategy.min_replicas() as u32;
+
+        let megabytes = estimated_size.div_ceil(1024 * 1024).max(1) as u128;
+        let storage_fee = STORAGE_FEE_PER_MB_REPLICA * megabytes * replicas as u128;
+        let processing_fee = PROCESSING_FEE;
+
+        // Fourchette de `RewardCalculator::calculate_initial_archiving_reward` :
+        // qualité et score de consensus inconnus avant la capture
+        let reward = IncentiveTable::default().initial_archiving;
+        let size_multiplier = RewardCalculator::archive_size_multiplier(estimated_size);
+
+        ArchiveEstimate {
+            url: request.url.clone(),
+            content_type,
+            estimated_size,
+            size_is_exact,
+            replication_strategy,
+            replicas,
+            cost_estimation: CostEstimation {
+                storage_cost: format_arc(storage_fee),
+                processing_cost: format_arc(processing_fee),
+                total_cost: format_arc(storage_fee + processing_fee),
+            },
+            projected_reward_min: format!("{} ARC", (reward.min as f64 * size_multiplier * 0.5) as u64),
+            projected_reward_max: format!("{} ARC", (reward.max as f64 * size_multiplier) as u64),
+        }
+    }
+
+    /// Enregistre une nouvelle demande d'archivage
+    pub async fn create_archive(&self, owner: &str, request: CreateArchiveRequest) -> ApiResult<ArchiveRecord> {
+        Ok(self.submit_archive(owner, request).await?.record)
+    }
+
+    /// Soumet une demande d'archivage dont le contenu éventuel est encodé en base64
+    pub async fn submit_archive(&self, owner: &str, request: CreateArchiveRequest) -> ApiResult<ArchiveSubmission> {
+        Self::validate_create_request(&request)?;
+        let content = Self::decode_content(&request)?;
+        self.submit_with_content(owner, request, content.as_deref()).await
+    }
+
+    /// Soumet une demande d'archivage, dédupliquée sur le hash du contenu
+    ///
+    /// Un contenu identique déjà archivé n'est pas stocké une seconde fois : le
+    /// demandeur et les métadonnées sont fusionnés dans l'archive existante. La
+    /// clé est le contenu et non l'URL, pour qu'une nouvelle version d'une même
+    /// page reste une archive distincte.
+    ///
+    /// Chaque soumission est ajoutée à la chronologie de son URL ; une capture
+    /// dédupliquée y figure comme une revisite de l'archive existante. Un
+    /// contenu déjà archivé sous une autre URL n'est pas ajouté à la
+    /// chronologie : elle ne désigne que des archives de son URL.
+    ///
+    /// L'index de recherche est mis à jour avant que le verrou des archives ne
+    /// soit relâché : une archive est cherchable dès qu'elle est visible.
+    ///
+    /// Un contenu dépassant `max_content_size` est refusé avec
+    /// `ApiError::ContentTooLarge`, avant toute déduplication.
+    #[tracing::instrument(name = "archive_submit", skip_all, fields(url = %request.url))]
+    pub async fn submit_with_content(
+        &self,
+        owner: &str,
+        request: CreateArchiveRequest,
+        content: Option<&[u8]>,
+    ) -> ApiResult<ArchiveSubmission> {
+        Self::validate_create_request(&request)?;
+        self.check_content_size(content)?;
+        let content_hash = content.map(compute_blake3);
+
+        let mut archives = self.archives.write().await;
+        let mut content_index = self.content_index.write().await;
+
+        let existing = content_hash.as_ref()
+            .and_then(|hash| content_index.get(hash))
+            .and_then(|archive_id| archives.get_mut(archive_id));
+        let now = chrono::Utc::now();
+        if let Some(record) = existing {
+            Self::merge_submission(record, owner, &request);
+            if normalize_url(&record.archive.url) == normalize_url(&request.url) {
+                let title = request.metadata.get("title").cloned().or_else(|| record.archive.metadata.title.clone());
+                self.history.write().await.record_with_summary(
+                    &request.url,
+                    record.archive.archive_id.clone(),
+                    now,
+                    content_hash,
+                    content.map(|content| content.len() as u64),
+                    title,
+                );
+            }
+            self.search_index.write().await.upsert(record.archive.archive_id.clone(), Self::search_document(record, content));
+            return Ok(ArchiveSubmission { record: record.clone(), deduplicated: true });
+        }
+
+        let archive_id = format!("arc_{}", uuid::Uuid::new_v4().simple());
+        let metadata = &request.metadata;
+
+        let archive = ArchiveDto {
+            access_urls: AccessUrls {
+                view: format!("{}/archives/{}", self.gateway_url, archive_id),
+                download: format!("{}/archives/{}/download", self.gateway_url, archive_id),
+                raw: format!("{}/archives/{}/raw", self.gateway_url, archive_id),
+            },
+            archive_id: archive_id.clone(),
+            url: request.url.clone(),
+            status: ArchiveStatus::Pending,
+            created_at: now,
+            completed_at: None,
+            size: 0,
+            metadata: ArchiveMetadataDto {
+                title: metadata.get("title").cloned(),
+                description: metadata.get("description").cloned(),
+                mime_type: "unknown".to_string(),
+                language: metadata.get("language").cloned(),
+                author: metadata.get("author").cloned(),
+                published_at: None,
+                tags: metadata.get("tags")
+                    .and_then(|tags| serde_json::from_str::<Vec<String>>(tags).ok())
+                    .unwrap_or_default(),
+            },
+            storage_info: StorageInfo {
+                replicas: 0,
+                locations: Vec::new(),
+                integrity_score: 0.0,
+                last_verified: now,
+            },
+            confirmations: None,
+            finalized: false,
+        };
+
+        let mut record = ArchiveRecord {
+            archive,
+            cost: Self::project(&request, content).cost_estimation,
+            owner: owner.to_string(),
+            requesters: vec![owner.to_string()],
+            content_hash,
+            popularity: 0,
+            crawl: None,
+        };
+        if let Some(content) = content {
+            record.archive.size = content.len() as u64;
+        }
+
+        // TODO: Ajouter la demande d'archivage à la queue de traitement
+        self.history.write().await.record_with_summary(
+            &request.url,
+            archive_id.clone(),
+            now,
+            content_hash.clone(),
+            content.map(|content| content.len() as u64),
+            record.archive.metadata.title.clone(),
+        );
+        if let Some(hash) = content_hash {
+            content_index.insert(hash, archive_id.clone());
+        }
+        self.search_index.write().await.upsert(archive_id.clone(), Self::search_document(&record, content));
+        archives.insert(archive_id, record.clone());
+        Ok(ArchiveSubmission { record, deduplicated: false })
+    }
+
+    /// Champs indexés d'une archive, dont le texte de son contenu s'il est connu
+    fn search_document(record: &ArchiveRecord, content: Option<&[u8]>) -> SearchDocument {
+        let archive = &record.archive;
+        SearchDocument {
+            title: archive.metadata.title.clone(),
+            description: archive.metadata.description.clone(),
+            tags: archive.metadata.tags.clone(),
+            url: Some(archive.url.clone()),
+            body: content.and_then(|content| extract_text(&archive.metadata.mime_type, content)),
+            content_type: archive.metadata.mime_type.clone(),
+            created_at: archive.created_at,
+            popularity: record.popularity,
+        }
+    }
+
+    /// Fusionne une soumission en double dans l'archive existante
+    fn merge_submission(record: &mut ArchiveRecord, requester: &str, request: &CreateArchiveRequest) {
+        if !record.requesters.iter().any(|r| r == requester) {
+            record.requesters.push(requester.to_string());
+        }
+
+        let metadata = &mut record.archive.metadata;
+        let tags = request.metadata.get("tags")
+            .and_then(|tags| serde_json::from_str::<Vec<String>>(tags).ok())
+            .unwrap_or_default();
+        for tag in tags {
+            if !metadata.tags.contains(&tag) {
+                metadata.tags.push(tag);
+            }
+        }
+
+        // Les champs déjà renseignés restent ceux du premier demandeur
+        for (key, field) in [
+            ("title", &mut metadata.title),
+            ("description", &mut metadata.description),
+            ("language", &mut metadata.language),
+            ("author", &mut metadata.author),
+        ] {
+            if field.is_none() {
+                *field = request.metadata.get(key).cloned();
+            }
+        }
+    }
+
+    /// Récupère une archive
+    pub async fn get_archive(&self, archive_id: &str) -> ApiResult<ArchiveRecord> {
+        Self::validate_archive_id(archive_id)?;
+
+        self.archives.read().await
+            .get(archive_id)
+            .cloned()
+            .ok_or_else(|| ApiError::not_found(format!("Archive {} not found", archive_id)))
+    }
+
+    /// Chronologie des captures d'une URL, de la plus ancienne à la plus récente
+    pub async fn get_archive_versions(&self, url: &str) -> Vec<ArchiveVersion<String>> {
+        self.history.read().await.versions(url).to_vec()
+    }
+
+    /// Une page de l'historique des versions d'une URL, de la plus récente à la plus ancienne
+    ///
+    /// Les variantes d'une même URL (casse de l'hôte, fragment, barre oblique
+    /// finale) partagent le même historique.
+    pub async fn get_url_history(&self, url: &str, pagination: &PaginationParams) -> (Vec<ArchiveVersion<String>>, PaginationInfo) {
+        let history = self.history.read().await;
+        let versions = history.versions(url);
+        let page = versions.iter()
+            .rev()
+            .skip(pagination.offset() as usize)
+            .take(pagination.limit as usize)
+            .cloned()
+            .collect();
+
+        (page, PaginationInfo::new(pagination.page, pagination.limit, versions.len() as u64))
+    }
+
+    /// Archive de la capture la plus proche à la date donnée ou avant
+    pub async fn get_archive_at(&self, url: &str, timestamp: chrono::DateTime<chrono::Utc>) -> ApiResult<ArchiveRecord> {
+        let archive_id = self.history.read().await
+            .version_at(url, timestamp)
+            .map(|version| version.archive_id.clone())
+            .ok_or_else(|| ApiError::not_found(format!("No capture of {} at or before {}", url, timestamp)))?;
+        self.get_archive(&archive_id).await
+    }
+
+    /// Met à jour la popularité d'une archive, utilisée par le classement des recherches
+    pub async fn set_popularity(&self, archive_id: &str, popularity: u64) -> ApiResult<()> {
+        let mut archives = self.archives.write().await;
+        let record = archives.get_mut(archive_id)
+            .ok_or_else(|| ApiError::not_found(format!("Archive {} not found", archive_id)))?;
+
+        record.popularity = popularity;
+        self.search_index.write().await.set_popularity(&record.archive.archive_id, popularity);
+        Ok(())
+    }
+
+    /// Recherche plein texte, avec le score de chaque archive, de la plus pertinente à la moins pertinente
+    ///
+    /// Les autres critères de `query` filtrent les résultats ; `query.text` est ignoré.
+    pub async fn search_archives(&self, text: &str, query: &ArchiveQuery) -> Vec<(ArchiveRecord, f64)> {
+        let archives = self.archives.read().await;
+        let filter = SearchFilter {
+            content_type: query.content_type.clone(),
+            tags: query.tags.clone(),
+            created_after: query.created_after,
+            created_before: query.created_before,
+        };
+
+        self.search_index.read().await
+            .search(text, &filter)
+            .into_iter()
+            .filter_map(|hit| archives.get(&hit.id).map(|record| (record, hit.score)))
+            .filter(|(record, _)| query.matches(&record.archive))
+            .map(|(record, score)| (record.clone(), score))
+            .collect()
+    }
+
+    /// Retourne toutes les archives correspondant aux critères, des plus récentes aux plus anciennes
+    ///
+    /// Avec une recherche plein texte, les archives sont classées par pertinence.
+    pub async fn find_archives(&self, query: &ArchiveQuery) -> Vec<ArchiveRecord> {
+        if let Some(text) = &query.text {
+            return self.search_archives(text, query).await
+                .into_iter()
+                .map(|(record, _)| record)
+                .collect();
+        }
+
+        let mut records: Vec<ArchiveRecord> = self.archives.read().await
+            .values()
+            .filter(|record| query.matches(&record.archive))
+            .cloned()
+            .collect();
+
+        records.sort_by(|a, b| {
+            b.archive.created_at.cmp(&a.archive.created_at)
+                .then_with(|| b.archive.archive_id.cmp(&a.archive.archive_id))
+        });
+        records
+    }
+
+    /// Archives satisfaisant la requête et confirmées à la profondeur exigée
+    ///
+    /// Les archives retournées portent leur profondeur de confirmation.
+    pub async fn find_confirmed_archives(
+        &self,
+        query: &ArchiveQuery,
+        blockchain: &Blockchain,
+        confirmation: &ConfirmationQuery,
+    ) -> Vec<ArchiveRecord> {
+        self.find_archives(query).await
+            .into_iter()
+            .map(|record| record.with_confirmations(blockchain))
+            .filter(|record| confirmation.is_satisfied(blockchain, record.archive.confirmations))
+            .collect()
+    }
+
+    /// Liste une page d'archives selon la sémantique page/limit de `PaginationParams`
+    pub async fn list_archives(&self, pagination: &PaginationParams, query: &ArchiveQuery) -> (Vec<ArchiveRecord>, PaginationInfo) {
+        Self::paginate(self.find_archives(query).await, pagination)
+    }
+
+    /// Liste une page d'archives confirmées à la profondeur exigée
+    ///
+    /// Le filtre précède la pagination : le total ne compte que les archives retenues.
+    pub async fn list_confirmed_archives(
+        &self,
+        pagination: &PaginationParams,
+        query: &ArchiveQuery,
+        blockchain: &Blockchain,
+        confirmation: &ConfirmationQuery,
+    ) -> (Vec<ArchiveRecord>, PaginationInfo) {
+        Self::paginate(self.find_confirmed_archives(query, blockchain, confirmation).await, pagination)
+    }
+
+    fn paginate(records: Vec<ArchiveRecord>, pagination: &PaginationParams) -> (Vec<ArchiveRecord>, PaginationInfo) {
+        let total = records.len() as u64;
+
+        let page = records.into_iter()
+            .skip(pagination.offset() as usize)
+            .take(pagination.limit as usize)
+            .collect();
+
+        (page, PaginationInfo::new(pagination.page, pagination.limit, total))
+    }
+
+    /// Annule une demande d'archivage encore en attente ou en cours
+    ///
+    /// Seul le propriétaire de l'archive peut l'annuler : les demandeurs
+    /// fusionnés par déduplication n'en ont pas le droit. Une archive annulée
+    /// quitte l'index des contenus : une nouvelle soumission du même contenu
+    /// crée une nouvelle archive.
+    pub async fn cancel_archive(&self, caller: &str, archive_id: &str) -> ApiResult<ArchiveRecord> {
+        Self::validate_archive_id(archive_id)?;
+
+        let mut archives = self.archives.write().await;
+        let record = archives.get_mut(archive_id)
+            .ok_or_else(|| ApiError::not_found(format!("Archive {} not found", archive_id)))?;
+        if record.owner != caller {
+            return Err(ApiError::authorization(format!("Only the owner can cancel archive {}", archive_id)));
+        }
+
+        match record.archive.status {
+            ArchiveStatus::Pending | ArchiveStatus::Processing => {
+                record.archive.status = ArchiveStatus::Cancelled;
+                if let Some(hash) = &record.content_hash {
+                    let mut content_index = self.content_index.write().await;
+                    if content_index.get(hash).map(String::as_str) == Some(archive_id) {
+                        content_index.remove(hash);
+                    }
+                }
+                Ok(record.clone())
+            }
+            ref status => Err(ApiError::conflict(format!(
+                "Archive {} cannot be cancelled in status {:?}",
+                archive_id, status
+            ))),
+        }
+    }
+
+    /// Nombre total d'archives et nombre d'archives créées aujourd'hui
+    pub async fn counts(&self) -> (u64, u64) {
+        let archives = self.archives.read().await;
+        let today = chrono::Utc::now().date_naive();
+        let created_today = archives.values()
+            .filter(|record| record.archive.created_at.date_naive() == today)
+            .count();
+        (archives.len() as u64, created_today as u64)
+    }
+}
+
+/// Résultat d'un appel au contrat de bounties
+#[derive(Debug, Clone)]
+pub struct BountyOutcome<T> {
+    pub value: T,
+    /// Noms des events émis par le contrat
+    pub events: Vec<String>,
+}
+
+/// Service des bounties d'archivage
+///
+/// Exécute l'`ArchiveBountyContract` pour le compte des utilisateurs, identifiés
+/// par leur clé publique. Les récompenses bloquées sont détenues par le compte
+/// du contrat dans le registre ARC : la création y transfère la récompense du
+/// créateur, les versements et remboursements du contrat en sortent. Un appel
+/// rejeté par le contrat ne déplace aucun token, et un transfert en échec
+/// annule à la fois les transferts déjà passés et les changements d'état du
+/// contrat.
+pub struct BountyService {
+    contract: RwLock<ArchiveBountyContract>,
+    token: Arc<RwLock<ARCToken>>,
+    /// Compte du contrat, détenteur des récompenses bloquées
+    account: PublicKey,
+    /// Vérification de contenu confirmant les archives proposées, absente si non rattachée
+    verification: Option<Arc<RwLock<ContentVerificationContract>>>,
+}
+
+impl BountyService {
+    pub fn new(token: Arc<RwLock<ARCToken>>, account: PublicKey) -> Self {
+        Self {
+            contract: RwLock::new(ArchiveBountyContract::default()),
+            token,
+            account,
+            verification: None,
+        }
+    }
+
+    /// Libère les récompenses des propositions confirmées par `verification`
+    pub fn with_content_verification(mut self, verification: Arc<RwLock<ContentVerificationContract>>) -> Self {
+        self.verification = Some(verification);
+        self
+    }
+
+    /// Crée un bounty dont la récompense est prélevée sur le solde du créateur
+    pub async fn create(
+        &self,
+        creator: &PublicKey,
+        url: &str,
+        reward: u64,
+        min_quality: QualityLevel,
+        deadline_hours: u64,
+    ) -> ApiResult<BountyOutcome<ArchiveBounty>> {
+        if reward == 0 {
+            return Err(ApiError::validation("Bounty reward must be positive"));
+        }
+        let metadata = ArchiveMetadata {
+            content_url: url.to_string(),
+            estimated_size: 0,
+            content_type: "text/html".to_string(),
+            original_hash: None,
+            additional_metadata: HashMap::new(),
+        };
+        self.execute(creator, reward, |contract, context| {
+            let bounty_id = contract.create_bounty(
+                creator.clone(),
+                url.to_string(),
+                reward,
+                deadline_hours,
+                min_quality,
+                metadata,
+                Default::default(),
+                context,
+            )?;
+            contract.get_bounty(bounty_id)
+        }).await
+    }
+
+    /// Réclame un bounty avec une archive capturée par `claimer`
+    pub async fn claim(&self, claimer: &PublicKey, bounty_id: u64, capture: &ArchiveCapture) -> ApiResult<BountyOutcome<u64>> {
+        self.get(bounty_id).await?;
+        self.execute(claimer, 0, |contract, context| contract.claim_bounty(claimer.clone(), bounty_id, capture, context)).await
+    }
+
+    /// Rembourse le créateur d'un bounty expiré
+    pub async fn refund(&self, caller: &PublicKey, bounty_id: u64) -> ApiResult<BountyOutcome<u64>> {
+        self.get(bounty_id).await?;
+        self.execute(caller, 0, |contract, context| contract.refund_bounty(bounty_id, context)).await
+    }
+
+    /// Augmente la récompense ou repousse la deadline d'un bounty de `caller`
+    pub async fn update(
+        &self,
+        caller: &PublicKey,
+        bounty_id: u64,
+        additional_reward: u64,
+        extend_hours: u64,
+    ) -> ApiResult<BountyOutcome<ArchiveBounty>> {
+        self.get(bounty_id).await?;
+        self.execute(caller, additional_reward, |contract, context| {
+            contract.update_bounty(caller, bounty_id, additional_reward, extend_hours, context)
+        }).await
+    }
+
+    /// Annule un bounty de `caller` et lui rembourse la récompense
+    pub async fn cancel(&self, caller: &PublicKey, bounty_id: u64) -> ApiResult<BountyOutcome<u64>> {
+        self.get(bounty_id).await?;
+        self.execute(caller, 0, |contract, context| contract.cancel_bounty(caller, bounty_id, context)).await
+    }
+
+    /// Propose une archive au créateur d'un bounty
+    ///
+    /// L'archive doit capturer l'URL du bounty ; la proposition porte le hash
+    /// du manifeste crawlé par le nœud.
+    pub async fn propose(
+        &self,
+        submitter: &PublicKey,
+        bounty_id: u64,
+        capture: &ArchiveCapture,
+        size: u64,
+    ) -> ApiResult<BountyOutcome<Hash>> {
+        let bounty = self.get(bounty_id).await?;
+        if normalize_url(&capture.url) != normalize_url(&bounty.target_url) {
+            return Err(ApiError::validation(format!(
+                "Archive {} does not capture {}",
+                capture.archive_id, bounty.target_url
+            )));
+        }
+        let metadata = ArchiveMetadata {
+            content_url: capture.url.clone(),
+            estimated_size: size,
+            content_type: "text/html".to_string(),
+            original_hash: Some(capture.content_hash),
+            additional_metadata: HashMap::from([("archive_id".to_string(), capture.archive_id.clone())]),
+        };
+        self.execute(submitter, 0, |contract, context| {
+            contract.submit_archive(submitter.clone(), bounty_id, capture.content_hash, metadata, Vec::new(), context)
+        }).await
+    }
+
+    /// Accepte une proposition et verse la récompense à son auteur
+    pub async fn accept(&self, caller: &PublicKey, bounty_id: u64, submission_id: Hash) -> ApiResult<BountyOutcome<u64>> {
+        self.get(bounty_id).await?;
+        self.execute(caller, 0, |contract, context| contract.accept_submission(caller, bounty_id, submission_id, context)).await
+    }
+
+    /// Verse la récompense d'une proposition dont l'archive a été vérifiée
+    ///
+    /// La vérification de contenu rattachée doit avoir confirmé l'archive avec
+    /// le consensus requis, à la qualité demandée par le bounty au moins.
+    pub async fn release(&self, caller: &PublicKey, bounty_id: u64, submission_id: Hash) -> ApiResult<BountyOutcome<u64>> {
+        let verification = self.verification.as_ref()
+            .ok_or_else(|| ApiError::service_unavailable("Content verification is not configured on this node"))?;
+        self.get(bounty_id).await?;
+        let verification = verification.read().await;
+        self.execute(caller, 0, |contract, context| {
+            contract.release_reward(bounty_id, submission_id, &verification, context)
+        }).await
+    }
+
+    pub async fn get(&self, bounty_id: u64) -> ApiResult<ArchiveBounty> {
+        self.contract.read().await.get_bounty(bounty_id)
+            .map_err(|_| ApiError::not_found(format!("Bounty {} not found", bounty_id)))
+    }
+
+    /// Bounties d'un statut, par ordre de création
+    pub async fn list(&self, status: BountyStatus, pagination: &PaginationParams) -> (Vec<ArchiveBounty>, PaginationInfo) {
+        let bounties = self.contract.read().await
+            .list_bounties_by_status(status, u32::MAX, 0)
+            .unwrap_or_default();
+        let total = bounties.len() as u64;
+
+        let page = bounties.into_iter()
+            .skip(pagination.offset() as usize)
+            .take(pagination.limit as usize)
+            .collect();
+
+        (page, PaginationInfo::new(pagination.page, pagination.limit, total))
+    }
+
+    /// Exécute un appel du contrat puis règle ses mouvements de tokens
+    ///
+    /// `value` est prélevée sur le solde de `caller` ; les transferts demandés
+    /// par le contrat sont débités du compte du contrat.
+    async fn execute<T>(
+        &self,
+        caller: &PublicKey,
+        value: u64,
+        call: impl FnOnce(&mut ArchiveBountyContract, &mut ContractContext) -> ContractResult<T>,
+    ) -> ApiResult<BountyOutcome<T>> {
+        let mut token = self.token.write().await;
+        let mut contract = self.contract.write().await;
+
+        let available = token.balance_of(caller);
+        if available < value {
+            return Err(ApiError::validation(format!(
+                "Insufficient ARC balance: {} required, {} available",
+                value, available
+            )));
+        }
+
+        let now = chrono::Utc::now();
+        let transaction_hash = compute_blake3(&[caller.as_bytes(), &now.timestamp_nanos_opt().unwrap_or_default().to_le_bytes()].concat());
+        let environment = ExecutionEnvironment {
+            block_hash: Hash::zero(),
+            block_number: 0,
+            block_timestamp: now,
+            transaction_hash,
+            transaction_sender: caller.clone(),
+            contract_address: Hash::from_bytes(self.account.as_bytes())
+                .map_err(|e| ApiError::internal(format!("Invalid bounty contract account: {}", e)))?,
+            caller_address: caller.clone(),
+            value_sent: value,
+            gas_limit: 1_000_000,
+            gas_price: 1,
+        };
+        let provider = EscrowProvider {
+            account: self.account.clone(),
+            balance: token.balance_of(&self.account) + value,
+        };
+        let mut context = ContractContext::new(environment, Box::new(provider));
+
+        // L'état du contrat n'est conservé que si tous les transferts aboutissent
+        let snapshot = contract.get_state().clone();
+        let result = match call(&mut contract, &mut context) {
+            Ok(result) => result,
+            Err(e) => {
+                contract.set_state(snapshot);
+                return Err(bounty_error(e));
+            }
+        };
+
+        let mut transfers = Vec::new();
+        if value > 0 {
+            transfers.push((caller.clone(), self.account.clone(), value));
+        }
+        transfers.extend(context.get_token_transfers().iter()
+            .map(|transfer| (self.account.clone(), transfer.to.clone(), transfer.amount)));
+
+        for (applied, (from, to, amount)) in transfers.iter().enumerate() {
+            if let Err(e) = token.transfer(from, to, *amount, transaction_hash) {
+                for (from, to, amount) in transfers[..applied].iter().rev() {
+                    // Les fonds viennent d'être crédités : le retour ne peut échouer
+                    let _ = token.transfer(to, from, *amount, transaction_hash);
+                }
+                contract.set_state(snapshot);
+                return Err(ApiError::internal(format!("Bounty settlement failed: {}", e)));
+            }
+        }
+
+        Ok(BountyOutcome {
+            value: result,
+            events: context.get_events().iter().map(|event| event.name.clone()).collect(),
+        })
+    }
+}
+
+/// Traduit un refus du contrat en erreur API
+fn bounty_error(error: ContractError) -> ApiError {
+    match error {
+        ContractError::InvalidParameters { message } => ApiError::validation(message),
+        ContractError::InsufficientQuality { .. } | ContractError::InsufficientFunds { .. } => {
+            ApiError::validation(error.to_string())
+        }
+        ContractError::AlreadyCompleted
+        | ContractError::DeadlineExpired
+        | ContractError::InvalidState { .. }
+        | ContractError::InsufficientConsensus { .. } => ApiError::conflict(error.to_string()),
+        ContractError::Unauthorized { message } => ApiError::authorization(message),
+        error => ApiError::internal(error.to_string()),
+    }
+}
+
+/// Accès du contrat de bounties à la chaîne, limité au solde de son compte
+struct EscrowProvider {
+    account: PublicKey,
+    balance: u64,
+}
+
+impl ContextProvider for EscrowProvider {
+    fn get_block(&self, _block_hash: Hash) -> ContractResult<Option<Block>> {
+        Ok(None)
+    }
+
+    fn get_current_block(&self) -> ContractResult<Block> {
+        Err(ContractError::InvalidState {
+            message: "No current block for API bounty calls".to_string(),
+        })
+    }
+
+    fn get_transaction(&self, _tx_hash: Hash) -> ContractResult<Option<Transaction>> {
+        Ok(None)
+    }
+
+    fn get_balance(&self, address: &PublicKey) -> ContractResult<u64> {
+        Ok(if *address == self.account { self.balance } else { 0 })
+    }
+
+    fn read_storage(&self, _contract_address: Hash, _key: &[u8]) -> ContractResult<Option<Vec<u8>>> {
+        Ok(None)
+    }
+
+    fn write_storage(&mut self, _contract_address: Hash, _key: &[u8], _value: &[u8]) -> ContractResult<()> {
+        Ok(())
+    }
+
+    fn contract_exists(&self, _address: Hash) -> ContractResult<bool> {
+        Ok(false)
+    }
+
+    fn get_contract_code(&self, _address: Hash) -> ContractResult<Option<Vec<u8>>> {
+        Ok(None)
+    }
+}
+
+/// Service d'informations réseau
+pub struct NetworkService;
+
+impl NetworkService {
+    /// Statistiques globales du réseau
+    pub async fn stats(state: &ServerState) -> ApiResult<NetworkStats> {
+        let chain = state.blockchain.stats();
+        let (total_archives, archives_today) = state.archives.counts().await;
+
+        Ok(NetworkStats {
+            network: NetworkInfo {
+                total_nodes: 100, // TODO: Récupérer depuis le consensus
+                active_nodes: 95,
+                total_storage: "15.7 TB".to_string(),
+                available_storage: "8.3 TB".to_string(),
+                current_block_height: chain.height,
+            },
+            archives: ArchiveStats {
+                total_archives,
+                archives_today,
+                total_size: "12.4 TB".to_string(),
+                average_replication: 4.2,
+            },
+            performance: PerformanceStats {
+                average_archive_time: "2.3 minutes".to_string(),
+                network_latency: "45ms".to_string(),
+                success_rate: 0.987,
+            },
+        })
+    }
+
+    /// Informations d'un nœud
+    pub async fn get_node(_state: &ServerState, node_id: &str) -> ApiResult<NodeInfo> {
+        // TODO: Récupérer le nœud depuis le registre P2P
+        Err(ApiError::not_found(format!("Node {} not found", node_id)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(url: &str) -> CreateArchiveRequest {
+        CreateArchiveRequest {
+            url: url.to_string(),
+            metadata: HashMap::new(),
+            options: ArchiveOptions::default(),
+            content: None,
+            dry_run: false,
+        }
+    }
+
+    fn request_with_content(url: &str, content: &[u8], tags: &str) -> CreateArchiveRequest {
+        CreateArchiveRequest {
+            metadata: HashMap::from([("tags".to_string(), tags.to_string())]),
+            content: Some(STANDARD.encode(content)),
+            ..request(url)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_create_and_get_archive() {
+        let service = ArchiveService::new("https://gateway.test");
+        let record = service.create_archive("user1", request("https://example.com/page")).await.unwrap();
+
+        assert_eq!(record.archive.status, ArchiveStatus::Pending);
+        assert_eq!(record.archive.access_urls.view, format!("https://gateway.test/archives/{}", record.archive.archive_id));
+
+        let fetched = service.get_archive(&record.archive.archive_id).await.unwrap();
+        assert_eq!(fetched.owner, "user1");
+        assert!(matches!(service.get_archive("arc_missing").await, Err(ApiError::NotFound(_))));
+        assert!(matches!(service.get_archive("bad").await, Err(ApiError::Validation(_))));
+        assert!(matches!(service.create_archive("user1", request("not a url")).await, Err(ApiError::InvalidFields(_))));
+    }
+
+    #[tokio::test]
+    async fn test_list_archives_page_limit() {
+        let service = ArchiveService::new("https://gateway.test");
+        for i in 0..5 {
+            service.create_archive("user1", request(&format!("https://example.com/{}", i))).await.unwrap();
+        }
+
+        let pagination = PaginationParams { page: 2, limit: 2 };
+        let (page, info) = service.list_archives(&pagination, &ArchiveQuery::default()).await;
+        assert_eq!(page.len(), 2);
+        assert_eq!(info.total, 5);
+        assert!(info.has_next);
+        assert!(info.has_prev);
+
+        let pagination = PaginationParams { page: 3, limit: 2 };
+        let (page, info) = service.list_archives(&pagination, &ArchiveQuery::default()).await;
+        assert_eq!(page.len(), 1);
+        assert!(!info.has_next);
+    }
+
+    #[tokio::test]
+    async fn test_identical_content_is_deduplicated() {
+        let service = ArchiveService::new("https://gateway.test");
+        let page = "https://example.com/page";
+
+        let first = service.submit_archive("user1", request_with_content(page, b"<html>v1</html>", r#"["news"]"#)).await.unwrap();
+        assert!(!first.deduplicated);
+        assert_eq!(first.record.archive.size, 15);
+
+        // Même contenu soumis par un autre utilisateur, depuis une autre URL
+        let second = service.submit_archive("user2", request_with_content("https://mirror.example.com/page", b"<html>v1</html>", r#"["politics"]"#)).await.unwrap();
+        assert!(second.deduplicated);
+        assert_eq!(second.record.archive.archive_id, first.record.archive.archive_id);
+        assert_eq!(second.record.requesters, vec!["user1", "user2"]);
+        assert_eq!(second.record.archive.metadata.tags, vec!["news", "politics"]);
+
+        // Nouveau contenu à la même URL : nouvelle version
+        let third = service.submit_archive("user1", request_with_content(page, b"<html>v2</html>", "[]")).await.unwrap();
+        assert!(!third.deduplicated);
+        assert_ne!(third.record.archive.archive_id, first.record.archive.archive_id);
+        assert_eq!(service.find_archives(&ArchiveQuery::default()).await.len(), 2);
+
+        let invalid = CreateArchiveRequest { content: Some("%%%".to_string()), ..request(page) };
+        assert!(matches!(service.submit_archive("user1", invalid).await, Err(ApiError::InvalidFields(_))));
+
+        // URL et contenu invalides : les deux champs sont rapportés
+        let invalid = CreateArchiveRequest { content: Some("%%%".to_string()), ..request("not a url") };
+        match service.submit_archive("user1", invalid).await {
+            Err(ApiError::InvalidFields(errors)) => {
+                let fields: Vec<_> = errors.iter().map(|e| e.field.as_str()).collect();
+                assert_eq!(fields, vec!["url", "content"]);
+            }
+            other => panic!("erreur de validation attendue: {:?}", other.map(|s| s.deduplicated)),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_archive_is_searchable_once_submitted() {
+        let service = ArchiveService::new("https://gateway.test");
+        let titled = |url: &str, title: &str| CreateArchiveRequest {
+            metadata: HashMap::from([("title".to_string(), title.to_string())]),
+            ..request(url)
+        };
+
+        let guide = service.create_archive("user1", titled("https://books.example.org/guide", "The Rust Book")).await.unwrap();
+        let hits = service.search_archives("rust", &ArchiveQuery::default()).await;
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].0.archive.archive_id, guide.archive.archive_id);
+
+        // À pertinence égale, l'archive la plus populaire passe devant
+        let news = service.create_archive("user1", titled("https://news.example.com/rust", "Rust news")).await.unwrap();
+        service.set_popularity(&news.archive.archive_id, 500).await.unwrap();
+        let query = ArchiveQuery { text: Some("rust".to_string()), ..Default::default() };
+        let found = service.find_archives(&query).await;
+        assert_eq!(found[0].archive.archive_id, news.archive.archive_id);
+        assert_eq!(found.len(), 2);
+
+        // Les tags fusionnés lors d'une déduplication sont cherchables
+        service.submit_archive("user1", request_with_content("https://example.com/a", b"same", r#"["ferris"]"#)).await.unwrap();
+        service.submit_archive("user2", request_with_content("https://example.com/b", b"same", r#"["crab"]"#)).await.unwrap();
+        assert_eq!(service.search_archives("crab ferr", &ArchiveQuery::default()).await.len(), 1);
+
+        // Le texte des pages archivées est cherchable, sans leur balisage
+        let page = b"<html><body><h1>Changelog</h1><p>Borrow checker improvements</p><script>secret()</script></body></html>";
+        service.submit_archive("user1", request_with_content("https://example.com/c", page, "[]")).await.unwrap();
+        assert_eq!(service.search_archives("\"borrow checker\"", &ArchiveQuery::default()).await.len(), 1);
+        assert!(service.search_archives("secret", &ArchiveQuery::default()).await.is_empty());
+
+        let cancelled = ArchiveQuery { status: Some(ArchiveStatus::Cancelled), ..Default::default() };
+        assert!(service.search_archives("rust", &cancelled).await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_archive_versions_by_url() {
+        let service = ArchiveService::new("https://gateway.test");
+        let page = "https://example.com/page";
+
+        let v1 = service.submit_archive("user1", request_with_content(page, b"<html>v1</html>", "[]")).await.unwrap();
+        let revisit = service.submit_archive("user2", request_with_content(page, b"<html>v1</html>", "[]")).await.unwrap();
+        let v2 = service.submit_archive("user1", request_with_content(page, b"<html>v2</html>", "[]")).await.unwrap();
+        assert!(revisit.deduplicated);
+
+        let versions = service.get_archive_versions(page).await;
+        assert_eq!(versions.len(), 3);
+        let v1_id = v1.record.archive.archive_id.clone();
+        assert!(!versions[0].is_revisit());
+        assert_eq!(versions[1].revisit_of, Some(v1_id.clone()));
+        assert_eq!(versions[1].archive_id, v1_id);
+        assert_eq!(versions[2].archive_id, v2.record.archive.archive_id);
+        assert_eq!(versions[2].previous, Some(v1_id.clone()));
+        assert!(service.get_archive_versions("https://example.com/other").await.is_empty());
+
+        // Le même contenu sous une autre URL est dédupliqué sans entrer dans sa chronologie
+        let mirror = "https://mirror.example/page";
+        let copy = service.submit_archive("user3", request_with_content(mirror, b"<html>v1</html>", "[]")).await.unwrap();
+        assert!(copy.deduplicated);
+        assert!(service.get_archive_versions(mirror).await.is_empty());
+        assert!(matches!(service.get_archive_at(mirror, chrono::Utc::now()).await, Err(ApiError::NotFound(_))));
+        assert_eq!(service.get_archive_versions(page).await.len(), 3);
+
+        let at_v1 = service.get_archive_at(page, versions[1].captured_at).await.unwrap();
+        assert_eq!(at_v1.archive.archive_id, v1_id);
+        let latest = service.get_archive_at(page, chrono::Utc::now()).await.unwrap();
+        assert_eq!(latest.archive.archive_id, v2.record.archive.archive_id);
+        let before = versions[0].captured_at - chrono::Duration::seconds(1);
+        assert!(matches!(service.get_archive_at(page, before).await, Err(ApiError::NotFound(_))));
+    }
+
+    #[tokio::test]
+    async fn test_dry_run_estimates_without_persisting() {
+        let service = ArchiveService::new("https://gateway.test");
+
+        let estimate = service.estimate_archive(&request("https://example.com/page")).unwrap();
+        assert_eq!(estimate.content_type, "text/html");
+        assert_eq!(estimate.estimated_size, 2 * 1024 * 1024);
+        assert!(!estimate.size_is_exact);
+        assert_eq!(estimate.replicas, 3);
+        assert_eq!(estimate.cost_estimation.storage_cost, "0.006 ARC");
+        assert_eq!(estimate.cost_estimation.total_cost, "0.0065 ARC");
+        assert_eq!(estimate.projected_reward_min, "60 ARC");
+        assert_eq!(estimate.projected_reward_max, "600 ARC");
+
+        // Contenu joint : taille exacte ; l'importance déclarée est ignorée
+        let critical = CreateArchiveRequest {
+            metadata: HashMap::from([
+                ("importance".to_string(), "critical".to_string()),
+                ("content_type".to_string(), "application/pdf".to_string()),
+            ]),
+            ..request_with_content("https://example.com/report.pdf", b"%PDF-1.7", "[]")
+        };
+        let estimate = service.estimate_archive(&critical).unwrap();
+        assert_eq!((estimate.estimated_size, estimate.size_is_exact), (8, true));
+        assert_eq!(estimate.replicas, 3);
+        assert_eq!(estimate.cost_estimation.total_cost, "0.0035 ARC");
+
+        // La soumission réelle applique le même coût
+        let record = service.create_archive("user1", critical).await.unwrap();
+        assert_eq!(record.cost.total_cost, "0.0035 ARC");
+        assert_eq!(service.counts().await.0, 1);
+
+        let unarchivable = CreateArchiveRequest {
+            metadata: HashMap::from([("content_type".to_string(), "application/zip".to_string())]),
+            ..request("ftp://example.com/files")
+        };
+        match service.estimate_archive(&unarchivable) {
+            Err(ApiError::InvalidFields(errors)) => {
+                let fields: Vec<_> = errors.iter().map(|e| e.field.as_str()).collect();
+                assert_eq!(fields, vec!["url", "metadata.content_type"]);
+            }
+            other => panic!("erreur de validation attendue: {:?}", other.map(|e| e.replicas)),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_submission_enforces_content_limits() {
+        let service = ArchiveService::new("https://gateway.test").with_max_content_size(1024);
+
+        let oversized = request_with_content("https://example.com/big", &[0u8; 2048], "[]");
+        match service.create_archive("user1", oversized).await {
+            Err(ApiError::ContentTooLarge { size, limit }) => assert_eq!((size, limit), (2048, 1024)),
+            other => panic!("ContentTooLarge attendu: {:?}", other.map(|r| r.archive.archive_id)),
+        }
+        let oversized = request_with_content("https://example.com/big", &[0u8; 2048], "[]");
+        assert!(matches!(service.estimate_archive(&oversized), Err(ApiError::ContentTooLarge { .. })));
+
+        // Chaque violation des limites de tags est rapportée séparément
+        let mut tags: Vec<String> = (0..crate::constants::MAX_TAGS_PER_ARCHIVE).map(|i| format!("t{}", i)).collect();
+        tags.push("x".repeat(crate::constants::MAX_TAG_LENGTH + 1));
+        let tagged = request_with_content("https://example.com/tags", b"ok", &serde_json::to_string(&tags).unwrap());
+        match service.create_archive("user1", tagged).await {
+            Err(ApiError::InvalidFields(errors)) => {
+                let violations: Vec<_> = errors.iter().map(|e| (e.field.as_str(), e.code.as_deref())).collect();
+                assert_eq!(violations, vec![
+                    ("metadata.tags", Some("too_many")),
+                    ("metadata.tags[50]", Some("too_long")),
+                ]);
+            }
+            other => panic!("erreurs de validation attendues: {:?}", other.map(|r| r.archive.archive_id)),
+        }
+
+        // Des tags qui ne forment pas un tableau JSON de chaînes sont refusés
+        let malformed = request_with_content("https://example.com/tags", b"ok", "art, histoire");
+        match service.create_archive("user1", malformed).await {
+            Err(ApiError::InvalidFields(errors)) => {
+                assert_eq!(errors[0].field, "metadata.tags");
+                assert_eq!(errors[0].code.as_deref(), Some("invalid_format"));
+            }
+            other => panic!("erreur de validation attendue: {:?}", other.map(|r| r.archive.archive_id)),
+        }
+        assert_eq!(service.counts().await.0, 0);
+    }
+
+    #[tokio::test]
+    async fn test_cancel_archive() {
+        let service = ArchiveService::new("https://gateway.test");
+        let record = service.submit_with_content("user1", request("https://example.com"), Some(b"page")).await.unwrap().record;
+        let id = record.archive.archive_id;
+
+        // Ni un tiers ni un demandeur fusionné ne peuvent annuler
+        assert!(matches!(service.cancel_archive("user2", &id).await, Err(ApiError::Authorization(_))));
+        assert!(service.submit_with_content("user2", request("https://example.com"), Some(b"page")).await.unwrap().deduplicated);
+        assert!(matches!(service.cancel_archive("user2", &id).await, Err(ApiError::Authorization(_))));
+
+        let cancelled = service.cancel_archive("user1", &id).await.unwrap();
+        assert_eq!(cancelled.archive.status, ArchiveStatus::Cancelled);
+        assert!(matches!(service.cancel_archive("user1", &id).await, Err(ApiError::Conflict(_))));
+
+        let query = ArchiveQuery { status: Some(ArchiveStatus::Cancelled), ..Default::default() };
+        assert_eq!(service.find_archives(&query).await.len(), 1);
+
+        // Le contenu d'une archive annulée n'est plus dédupliqué vers elle
+        let resubmitted = service.submit_with_content("user2", request("https://example.com"), Some(b"page")).await.unwrap();
+        assert!(!resubmitted.deduplicated);
+        assert_ne!(resubmitted.record.archive.archive_id, id);
+    }
+
+    #[tokio::test]
+    async fn test_confirmation_query_requires_depth() {
+        let config = crate::BlockchainConfig { max_reorg_depth: 2, ..Default::default() };
+        let mut blockchain = Blockchain::new(config).unwrap();
+        for _ in 0..3 {
+            let block = blockchain.mine_block().unwrap();
+            blockchain.add_block(block).unwrap();
+        }
+
+        let shallow = ConfirmationQuery { min_confirmations: Some(2), finalized: false };
+        assert!(shallow.is_satisfied(&blockchain, blockchain.confirmations(1)));
+        assert!(!shallow.is_satisfied(&blockchain, blockchain.confirmations(2)));
+        assert!(matches!(shallow.check(&blockchain, "Archive", None), Err(ApiError::NotFound(_))));
+
+        let finalized = ConfirmationQuery { min_confirmations: None, finalized: true };
+        assert_eq!(finalized.required_depth(&blockchain), Some(2));
+        assert!(ConfirmationQuery::default().is_satisfied(&blockchain, None));
+
+        // Une archive absente de la chaîne n'a aucune confirmation
+        let service = ArchiveService::new("https://gateway.test");
+        let record = service.create_archive("user1", request("https://example.com")).await.unwrap()
+            .with_confirmations(&blockchain);
+        assert_eq!(record.archive.confirmations, None);
+        assert!(!record.archive.finalized);
+
+        // La liste applique la même exigence, avant la pagination
+        let pagination = PaginationParams { page: 1, limit: 10 };
+        let (page, info) = service
+            .list_confirmed_archives(&pagination, &ArchiveQuery::default(), &blockchain, &shallow)
+            .await;
+        assert!(page.is_empty());
+        assert_eq!(info.total, 0);
+        let (page, _) = service
+            .list_confirmed_archives(&pagination, &ArchiveQuery::default(), &blockchain, &ConfirmationQuery::default())
+            .await;
+        assert_eq!(page.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_delivery_settler_pays_after_grace_and_prunes_stale_requests() {
+        use crate::crypto::generate_keypair;
+        use crate::token::{rewards::RewardConfig, DeliveryEntry};
+
+        let operator = generate_keypair().unwrap().public_key().clone();
+        let content = compute_blake3(b"archive");
+        let now = chrono::Utc::now();
+        let served = now - chrono::Duration::seconds(SETTLEMENT_GRACE_SECS + 60);
+
+        let log = Arc::new(RwLock::new(DeliveryLog::new()));
+        {
+            let mut log = log.write().await;
+            for (request_id, timestamp) in [("req-old", served), ("req-recent", now)] {
+                log.record_request(request_id, ServedRequest {
+                    content_hash: content.clone(),
+                    requester: "alice".to_string(),
+                    node_id: crate::consensus::NodeId::from_public_key(&operator),
+                    bytes: 1024 * 1024 * 1024,
+                    timestamp,
+                });
+                log.record(DeliveryEntry {
+                    operator: operator.clone(),
+                    content_hash: content.clone(),
+                    bytes: 1024 * 1024 * 1024,
+                    request_id: request_id.to_string(),
+                    timestamp,
+                });
+            }
+            log.record_request("req-stale", ServedRequest {
+                content_hash: content.clone(),
+                requester: "alice".to_string(),
+                node_id: crate::consensus::NodeId::from_public_key(&operator),
+                bytes: 1,
+                timestamp: now - chrono::Duration::seconds(SERVED_REQUEST_RETENTION_SECS + 1),
+            });
+        }
+
+        let rewards = Arc::new(RwLock::new(RewardSystem::new(1_000_000, RewardConfig::default())));
+        let token = Arc::new(RwLock::new(ARCToken::new()));
+        let settler = DeliverySettler::new(log.clone(), rewards, token.clone());
+
+        // Seule la livraison antérieure au délai de grâce est réglée
+        let settlement = settler.settle(now).await.unwrap();
+        assert_eq!(settlement.settled_bytes, 1024 * 1024 * 1024);
+        assert!(token.read().await.balance_of(&operator) > 0);
+        assert_eq!(log.read().await.entries().len(), 1);
+        assert_eq!(log.read().await.pending_requests(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_discovery_rewarder_pays_once_archived_under_claimed_url() {
+        use crate::crypto::generate_keypair;
+        use crate::token::rewards::RewardConfig;
+
+        struct ChainIndex(std::sync::Mutex<HashMap<Hash, String>>);
+
+        impl ArchivedContentLookup for ChainIndex {
+            fn is_archived(&self, content_hash: &Hash) -> bool {
+                self.0.lock().unwrap().contains_key(content_hash)
+            }
+
+            fn archived_url(&self, content_hash: &Hash) -> Option<String> {
+                self.0.lock().unwrap().get(content_hash).cloned()
+            }
+
+            fn domain_archive_count(&self, _domain: &str) -> usize {
+                0
+            }
+        }
+
+        let discoverer = generate_keypair().unwrap().public_key().clone();
+        let content = compute_blake3(b"page");
+        let index = Arc::new(ChainIndex(std::sync::Mutex::new(HashMap::new())));
+        let rewards = Arc::new(RwLock::new(RewardSystem::new(1_000_000, RewardConfig::default())));
+        let token = Arc::new(RwLock::new(ARCToken::new()));
+        let rewarder = DiscoveryRewarder::new(rewards.clone(), token.clone(), index.clone());
+
+        rewarder.claim(&discoverer, content.clone(), "https://rare.org/page").await.unwrap();
+
+        // Pas encore sur la chaîne : la réclamation reste en attente
+        assert_eq!(rewarder.finalize_stored(chrono::Utc::now()).await, 0);
+        assert_eq!(rewards.read().await.pending_discovery_claims(), vec![content.clone()]);
+
+        index.0.lock().unwrap().insert(content.clone(), "https://rare.org/page/".to_string());
+        assert_eq!(rewarder.finalize_stored(chrono::Utc::now()).await, 1);
+        assert!(token.read().await.balance_of(&discoverer) > 0);
+        assert!(rewards.read().await.pending_discovery_claims().is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_bounty_reward_released_once_proposal_is_verified() {
+        use crate::contracts::content_verification::{
+            ContentMetadata, CriticalityLevel, VerificationDetails, VerificationResult,
+        };
+        use crate::contracts::context::MockContextProvider;
+        use crate::contracts::VerificationRules;
+        use crate::crypto::generate_keypair;
+
+        let creator = generate_keypair().unwrap().public_key().clone();
+        let archiver = generate_keypair().unwrap().public_key().clone();
+        let escrow = generate_keypair().unwrap().public_key().clone();
+        let token = Arc::new(RwLock::new(ARCToken::new()));
+        token.write().await.mint(&creator, 3_000, Hash::zero()).unwrap();
+        let verification = Arc::new(RwLock::new(ContentVerificationContract::default()));
+
+        let unverified = BountyService::new(token.clone(), escrow.clone());
+        assert!(matches!(
+            unverified.release(&archiver, 0, Hash::zero()).await,
+            Err(ApiError::ServiceUnavailable(_))
+        ));
+
+        let bounties = BountyService::new(token.clone(), escrow.clone()).with_content_verification(verification.clone());
+        let bounty = bounties.create(&creator, "https://example.com/page", 1_000, QualityLevel::Standard, 24)
+            .await.unwrap().value;
+        let capture = ArchiveCapture {
+            archive_id: "arc_verified".to_string(),
+            url: "https://example.com/page".to_string(),
+            content_hash: compute_blake3(b"verified page"),
+            resources_expected: 2,
+            resources_captured: 2,
+            depth_attempted: 1,
+            depth_reached: 1,
+        };
+        let submission = bounties.propose(&archiver, bounty.bounty_id, &capture, 2048).await.unwrap().value;
+
+        // Tant que l'archive n'est pas vérifiée, la récompense reste bloquée
+        assert!(bounties.release(&archiver, bounty.bounty_id, submission).await.is_err());
+        assert_eq!(token.read().await.balance_of(&archiver), 0);
+
+        {
+            let mut verification = verification.write().await;
+            let env = ExecutionEnvironment {
+                block_hash: Hash::zero(),
+                block_number: 1,
+                block_timestamp: chrono::Utc::now(),
+                transaction_hash: Hash::zero(),
+                transaction_sender: creator.clone(),
+                contract_address: Hash::zero(),
+                caller_address: creator.clone(),
+                value_sent: 0,
+                gas_limit: 1_000_000,
+                gas_price: 1,
+            };
+            let mut context = ContractContext::new(env, Box::new(MockContextProvider::new()));
+            verification.initiate_verification(
+                capture.content_hash,
+                ContentMetadata {
+                    original_size: 2048,
+                    content_type: "text/html".to_string(),
+                    expected_hash: capture.content_hash,
+                    checksums: HashMap::new(),
+                    created_at: chrono::Utc::now(),
+                    owner: archiver.clone(),
+                    criticality_level: CriticalityLevel::Standard,
+                },
+                VerificationRules::default(),
+                &mut context,
+            ).unwrap();
+            for _ in 0..3 {
+                let verifier = generate_keypair().unwrap().public_key().clone();
+                verification.register_verifier(verifier.clone(), &mut context).unwrap();
+                let result = VerificationResult {
+                    verifier: verifier.clone(),
+                    timestamp: chrono::Utc::now(),
+                    success: true,
+                    confidence_score: 0.9,
+                    details: VerificationDetails {
+                        integrity_valid: Some(true),
+                        size_valid: Some(true),
+                        format_valid: None,
+                        metadata_valid: None,
+                        redundancy_valid: None,
+                        verification_time_ms: 10,
+                        messages: Vec::new(),
+                    },
+                    proof_hash: Hash::zero(),
+                    signature: Vec::new(),
+                };
+                verification.submit_verification(verifier, capture.content_hash, result, &mut context).unwrap();
+            }
+        }
+
+        let paid = bounties.release(&archiver, bounty.bounty_id, submission).await.unwrap().value;
+        assert_eq!(paid, 1_000);
+        assert_eq!(token.read().await.balance_of(&archiver), 1_000);
+        assert_eq!(token.read().await.balance_of(&escrow), 0);
+        assert_eq!(bounties.get(bounty.bounty_id).await.unwrap().status, BountyStatus::Completed);
+
+        // Pas de second versement
+        assert!(matches!(
+            bounties.release(&archiver, bounty.bounty_id, submission).await,
+            Err(ApiError::Conflict(_))
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_content_service_reads_replicas_from_storage() {
+        let dir = tempfile::tempdir().unwrap();
+        let replicas = crate::storage::DiskReplicaStore::new(dir.path());
+        let policy = crate::storage::StoragePolicy {
+            node_preferences: HashMap::new(),
+            retention_policies: Vec::new(),
+            alert_thresholds: crate::storage::AlertThresholds::default(),
+        };
+        let storage = Arc::new(
+            StorageManager::new(crate::storage::StorageConfig::default(), policy).await.unwrap()
+                .with_replica_store(Arc::new(replicas.clone())),
+        );
+
+        let node = StorageNodeInfo {
+            node_id: crate::consensus::NodeId::from(compute_blake3(b"storage-node")),
+            node_type: crate::storage::NodeType::FullArchive,
+            region: "eu-west-1".to_string(),
+            total_capacity: 1_000_000_000,
+            used_capacity: 0,
+            supported_storage_types: Vec::new(),
+            available_bandwidth: 1_000_000,
+            average_latency: 20,
+            reliability_score: 0.99,
+            last_seen: chrono::Utc::now(),
+            status: NodeStatus::Active,
+        };
+        storage.update_node_info(node.node_id.clone(), node.clone()).await.unwrap();
+        let content = b"<html>archived</html>".to_vec();
+        let content_hash = compute_blake3(&content);
+        replicas.write_replica(&node.node_id, &content_hash, &content).await.unwrap();
+
+        let service = Arc::new(ContentService::new(
+            Arc::new(CacheLayer::new(crate::nodes::gateway::CacheConfig::default())),
+            Arc::new(StorageContentFetcher::new(storage.clone())),
+        ));
+        let shutdown = ShutdownToken::new();
+        service.clone().spawn_node_refresh(storage, shutdown.clone());
+        tokio::time::sleep(std::time::Duration::from_millis(50)).await;
+
+        let fetched = service.fetch(&content_hash).await.unwrap();
+        assert_eq!(fetched.data, content);
+        assert_eq!(fetched.source, ContentSource::StorageNode(node.node_id));
+        shutdown.trigger();
+    }
+}
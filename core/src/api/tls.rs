@@ -0,0 +1,105 @@
+//! Validation des fichiers TLS au démarrage
+//!
+//! Les serveurs ne lisent leurs certificats qu'au démarrage effectif : un
+//! chemin erroné ou une clé qui ne correspond pas au certificat ne se
+//! manifestait qu'au premier handshake refusé. Ces vérifications s'exécutent
+//! avant l'ouverture du port.
+
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use rustls::sign::CertifiedKey;
+
+use super::{ApiError, ApiResult};
+
+/// Vérifie qu'un certificat et sa clé privée sont lisibles, en PEM, et appariés
+pub fn validate_identity(cert_path: &str, key_path: &str) -> ApiResult<()> {
+    let certs = read_certificates(cert_path)?;
+    let key = read_private_key(key_path)?;
+    let signing_key = rustls::crypto::ring::sign::any_supported_type(&key)
+        .map_err(|e| ApiError::validation(format!("Unsupported TLS private key {}: {}", key_path, e)))?;
+
+    CertifiedKey::new(certs, signing_key).keys_match().map_err(|e| {
+        ApiError::validation(format!(
+            "TLS private key {} does not match certificate {}: {}",
+            key_path, cert_path, e
+        ))
+    })
+}
+
+/// Vérifie qu'un fichier d'autorités de certification est exploitable
+pub fn validate_ca_bundle(ca_path: &str) -> ApiResult<()> {
+    let mut roots = rustls::RootCertStore::empty();
+    for cert in read_certificates(ca_path)? {
+        roots
+            .add(cert)
+            .map_err(|e| ApiError::validation(format!("Invalid CA certificate in {}: {}", ca_path, e)))?;
+    }
+    Ok(())
+}
+
+/// Lit les certificats PEM d'un fichier ; au moins un est requis
+fn read_certificates(path: &str) -> ApiResult<Vec<CertificateDer<'static>>> {
+    let pem = read_file(path, "certificate")?;
+    let certs = rustls_pemfile::certs(&mut pem.as_slice())
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| ApiError::validation(format!("Malformed PEM in {}: {}", path, e)))?;
+
+    if certs.is_empty() {
+        return Err(ApiError::validation(format!("No PEM certificate found in {}", path)));
+    }
+    Ok(certs)
+}
+
+/// Lit la première clé privée PEM d'un fichier (PKCS#8, PKCS#1 ou SEC1)
+fn read_private_key(path: &str) -> ApiResult<PrivateKeyDer<'static>> {
+    let pem = read_file(path, "private key")?;
+    rustls_pemfile::private_key(&mut pem.as_slice())
+        .map_err(|e| ApiError::validation(format!("Malformed PEM in {}: {}", path, e)))?
+        .ok_or_else(|| ApiError::validation(format!("No PEM private key found in {}", path)))
+}
+
+fn read_file(path: &str, kind: &str) -> ApiResult<Vec<u8>> {
+    std::fs::read(path).map_err(|e| ApiError::validation(format!("Cannot read TLS {} {}: {}", kind, path, e)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::Path;
+
+    /// Écrit un certificat auto-signé et sa clé dans `dir`
+    fn write_identity(dir: &Path, name: &str) -> (String, String) {
+        let identity = rcgen::generate_simple_self_signed(vec!["localhost".to_string()]).unwrap();
+        let cert_path = dir.join(format!("{}.crt", name));
+        let key_path = dir.join(format!("{}.key", name));
+        std::fs::write(&cert_path, identity.cert.pem()).unwrap();
+        std::fs::write(&key_path, identity.key_pair.serialize_pem()).unwrap();
+        (cert_path.display().to_string(), key_path.display().to_string())
+    }
+
+    #[test]
+    fn test_validate_identity_rejects_bad_material() {
+        let dir = tempfile::tempdir().unwrap();
+        let (cert, key) = write_identity(dir.path(), "node");
+        let (other_cert, other_key) = write_identity(dir.path(), "other");
+
+        assert!(validate_identity(&cert, &key).is_ok());
+        assert!(validate_ca_bundle(&other_cert).is_ok());
+
+        // Clé d'un autre certificat
+        let mismatch = validate_identity(&cert, &other_key).unwrap_err();
+        assert!(mismatch.to_string().contains("does not match"));
+
+        // Chemin erroné
+        let missing = dir.path().join("nod.key").display().to_string();
+        assert!(validate_identity(&cert, &missing).unwrap_err().to_string().contains("Cannot read"));
+
+        // Fichiers sans contenu PEM exploitable
+        let garbage = dir.path().join("garbage.pem");
+        std::fs::write(&garbage, "pas un certificat").unwrap();
+        let garbage = garbage.display().to_string();
+        assert!(validate_identity(&garbage, &key).is_err());
+        assert!(validate_identity(&cert, &garbage).is_err());
+        assert!(validate_ca_bundle(&garbage).is_err());
+        assert!(validate_identity(&other_cert, &other_key).is_ok());
+    }
+}
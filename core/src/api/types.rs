@@ -16,6 +16,7 @@ pub enum ArchiveStatus {
     Completed,
     Failed,
     Expired,
+    Cancelled,
 }
 
 impl Default for ArchiveStatus {
@@ -37,12 +38,25 @@ pub struct ArchiveOptions {
     pub allowed_domains: Vec<String>,
     #[serde(default)]
     pub timeout_seconds: Option<u64>,
+    /// Limite le crawl au domaine de l'URL racine (plus `allowed_domains`)
+    #[serde(default = "default_true")]
+    pub same_domain_only: bool,
+    /// Taille cumulée maximale de l'archive en bytes
+    #[serde(default)]
+    pub max_total_size: Option<u64>,
+    /// Respecte les règles robots.txt des sites crawlés
+    #[serde(default = "default_true")]
+    pub respect_robots_txt: bool,
 }
 
 fn default_max_depth() -> u32 {
     3
 }
 
+fn default_true() -> bool {
+    true
+}
+
 impl Default for ArchiveOptions {
     fn default() -> Self {
         Self {
@@ -51,6 +65,9 @@ impl Default for ArchiveOptions {
             preserve_javascript: false,
             allowed_domains: Vec::new(),
             timeout_seconds: Some(300), // 5 minutes
+            same_domain_only: true,
+            max_total_size: Some(100 * 1024 * 1024), // 100MB
+            respect_robots_txt: true,
         }
     }
 }
@@ -63,12 +80,21 @@ pub struct CreateArchiveRequest {
     pub metadata: HashMap<String, String>,
     #[serde(default)]
     pub options: ArchiveOptions,
+    /// Contenu capturé encodé en base64, clé de déduplication des archives
+    #[serde(default)]
+    pub content: Option<String>,
+    /// Valide la demande et en estime le coût sans rien enregistrer
+    #[serde(default)]
+    pub dry_run: bool,
 }
 
 /// Réponse de création d'archive
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct CreateArchiveResponse {
     pub archive_id: String,
+    /// Contenu identique déjà archivé : `archive_id` désigne l'archive existante
+    #[serde(default)]
+    pub deduplicated: bool,
     pub status: ArchiveStatus,
     pub estimated_completion: Option<chrono::DateTime<chrono::Utc>>,
     pub cost_estimation: CostEstimation,
@@ -82,8 +108,26 @@ pub struct CostEstimation {
     pub total_cost: String,
 }
 
-/// Informations de stockage
+/// Projection d'une demande d'archivage en `dry_run` : rien n'est enregistré
 #[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveEstimate {
+    pub url: String,
+    pub content_type: String,
+    /// Taille retenue pour le calcul, en bytes
+    pub estimated_size: u64,
+    /// `true` si la taille est mesurée sur le contenu joint plutôt qu'estimée
+    pub size_is_exact: bool,
+    pub replication_strategy: crate::storage::ReplicationStrategy,
+    /// Nombre de répliques stockées par la stratégie retenue
+    pub replicas: u32,
+    pub cost_estimation: CostEstimation,
+    /// Fourchette de récompense d'archivage initial versée au nœud de capture
+    pub projected_reward_min: String,
+    pub projected_reward_max: String,
+}
+
+/// Informations de stockage
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct StorageInfo {
     pub replicas: u32,
     pub locations: Vec<String>,
@@ -92,7 +136,7 @@ pub struct StorageInfo {
 }
 
 /// URLs d'accès à l'archive
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct AccessUrls {
     pub view: String,
     pub download: String,
@@ -100,7 +144,7 @@ pub struct AccessUrls {
 }
 
 /// Archive complète (DTO)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ArchiveDto {
     pub archive_id: String,
     pub url: String,
@@ -111,10 +155,16 @@ pub struct ArchiveDto {
     pub metadata: ArchiveMetadataDto,
     pub storage_info: StorageInfo,
     pub access_urls: AccessUrls,
+    /// Blocs au-dessus du bloc d'inclusion ; `None` tant que l'archive n'est pas sur la chaîne
+    #[serde(default)]
+    pub confirmations: Option<u64>,
+    /// Inclusion à l'abri des réorganisations
+    #[serde(default)]
+    pub finalized: bool,
 }
 
 /// Métadonnées d'archive (DTO)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ArchiveMetadataDto {
     pub title: Option<String>,
     pub description: Option<String>,
@@ -125,6 +175,53 @@ pub struct ArchiveMetadataDto {
     pub tags: Vec<String>,
 }
 
+/// Capture d'une URL dans sa chronologie (DTO)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArchiveVersionDto {
+    pub archive_id: String,
+    pub url: String,
+    pub captured_at: chrono::DateTime<chrono::Utc>,
+    pub content_hash: Option<String>,
+    pub previous_archive_id: Option<String>,
+    /// Archive d'origine lorsque le contenu est inchangé
+    pub revisit_of: Option<String>,
+    pub size: Option<u64>,
+    pub title: Option<String>,
+    /// Différences avec la capture précédente
+    pub changes: Option<VersionChangesDto>,
+}
+
+/// Résumé des différences entre une capture et la précédente (DTO)
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VersionChangesDto {
+    pub previous_content_hash: Option<String>,
+    /// Variation de taille en octets
+    pub size_delta: Option<i64>,
+    pub previous_title: Option<String>,
+    pub title_changed: bool,
+}
+
+impl From<crate::block::ArchiveVersion<String>> for ArchiveVersionDto {
+    fn from(version: crate::block::ArchiveVersion<String>) -> Self {
+        Self {
+            archive_id: version.archive_id,
+            url: version.url,
+            captured_at: version.captured_at,
+            content_hash: version.content_hash.map(|hash| hash.to_hex()),
+            previous_archive_id: version.previous,
+            revisit_of: version.revisit_of,
+            size: version.size,
+            title: version.title,
+            changes: version.diff.map(|diff| VersionChangesDto {
+                previous_content_hash: diff.previous_content_hash.map(|hash| hash.to_hex()),
+                size_delta: diff.size_delta,
+                previous_title: diff.previous_title,
+                title_changed: diff.title_changed,
+            }),
+        }
+    }
+}
+
 /// Demande de recherche
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SearchRequest {
@@ -142,6 +239,7 @@ fn default_search_limit() -> u32 {
 
 /// Filtres de recherche
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct SearchFilters {
     pub content_type: Option<String>,
     pub domain: Option<String>,
@@ -292,6 +390,17 @@ pub struct BlockDto {
     pub validator: String,
 }
 
+/// Page d'en-têtes de blocs pour les clients légers
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BlockHeadersPage {
+    /// En-têtes consécutifs, vérifiables avec `verify_header_chain`
+    pub headers: Vec<crate::block::BlockHeader>,
+    /// Hauteur de départ de la page suivante, absente en fin de plage
+    pub next_from: Option<u64>,
+    /// Hauteur de la tête de chaîne
+    pub tip_height: u64,
+}
+
 /// Transaction (DTO)
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TransactionDto {
@@ -305,6 +414,38 @@ pub struct TransactionDto {
     pub data: Option<serde_json::Value>,
 }
 
+/// Reçu de transaction (DTO)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionReceiptDto {
+    pub transaction_hash: String,
+    pub block_hash: String,
+    pub block_height: u64,
+    pub index: u32,
+    /// Frais prélevés par l'exécution
+    pub gas_used: u64,
+    pub events: Vec<TokenEventDto>,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    /// Blocs au-dessus du bloc d'inclusion
+    pub confirmations: u64,
+    /// Inclusion à l'abri des réorganisations
+    pub finalized: bool,
+}
+
+/// Événement token d'un reçu (DTO)
+///
+/// `from` est le compte à l'origine de l'événement (débité pour les mouvements
+/// de fonds) et `to` le compte crédité, lorsqu'ils existent.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TokenEventDto {
+    pub event_type: String,
+    pub from: Option<String>,
+    pub to: Option<String>,
+    pub amount: u64,
+    /// Type de récompense ou de stake, motif de slashing, proposition concernée
+    pub detail: Option<String>,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+}
+
 /// Type de transaction
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
@@ -363,11 +504,73 @@ impl From<&Transaction> for TransactionDto {
     }
 }
 
+impl TransactionReceiptDto {
+    /// Construit le DTO d'un reçu avec sa profondeur de confirmation
+    pub fn new(receipt: &crate::transaction::TransactionReceipt, blockchain: &crate::Blockchain) -> Self {
+        let mut dto = Self::from(receipt);
+        dto.confirmations = blockchain.confirmations(receipt.block_height).unwrap_or(0);
+        dto.finalized = blockchain.is_final(receipt.block_height);
+        dto
+    }
+}
+
+impl From<&crate::transaction::TransactionReceipt> for TransactionReceiptDto {
+    fn from(receipt: &crate::transaction::TransactionReceipt) -> Self {
+        Self {
+            transaction_hash: receipt.transaction_hash.to_hex(),
+            block_hash: receipt.block_hash.to_hex(),
+            block_height: receipt.block_height,
+            index: receipt.index,
+            gas_used: receipt.gas_used,
+            events: receipt.events.iter().map(TokenEventDto::from).collect(),
+            timestamp: receipt.timestamp,
+            confirmations: 0,
+            finalized: false,
+        }
+    }
+}
+
+impl From<&crate::token::TokenEvent> for TokenEventDto {
+    fn from(event: &crate::token::TokenEvent) -> Self {
+        use crate::token::TokenEventType as Event;
+
+        let (event_type, from, to, amount, detail) = match &event.event_type {
+            Event::Transfer { from, to, amount } => ("transfer", Some(from), Some(to), *amount, None),
+            Event::Burn { from, amount } => ("burn", Some(from), None, *amount, None),
+            Event::RewardDistributed { to, amount, reward_type } => {
+                ("reward_distributed", None, Some(to), *amount, Some(reward_type.clone()))
+            }
+            Event::Staked { staker, amount, stake_type } => ("staked", Some(staker), None, *amount, Some(stake_type.clone())),
+            Event::Unstaked { staker, amount, stake_type } => ("unstaked", None, Some(staker), *amount, Some(stake_type.clone())),
+            Event::ProposalCreated { proposer, proposal_id, stake_amount } => {
+                ("proposal_created", Some(proposer), None, *stake_amount, Some(proposal_id.to_hex()))
+            }
+            Event::ProposalVoted { voter, proposal_id, voting_power, .. } => {
+                ("proposal_voted", Some(voter), None, *voting_power, Some(proposal_id.to_hex()))
+            }
+            Event::ProposalExecuted { proposal_id, recipient, amount } => {
+                ("proposal_executed", None, Some(recipient), *amount, Some(proposal_id.to_hex()))
+            }
+            Event::Slashed { validator, amount, reason } => ("slashed", Some(validator), None, *amount, Some(reason.clone())),
+        };
+
+        Self {
+            event_type: event_type.to_string(),
+            from: from.map(|key| key.to_hex()),
+            to: to.map(|key| key.to_hex()),
+            amount,
+            detail,
+            timestamp: event.timestamp,
+        }
+    }
+}
+
 impl From<&crate::transaction::TransactionType> for TransactionType {
     fn from(tx_type: &crate::transaction::TransactionType) -> Self {
         match tx_type {
             crate::transaction::TransactionType::Archive => Self::Archive,
             crate::transaction::TransactionType::Transfer => Self::Transfer,
+            crate::transaction::TransactionType::TokenTransfer { .. } => Self::Transfer,
             crate::transaction::TransactionType::ContractCall => Self::ContractCall,
             crate::transaction::TransactionType::ContractDeploy => Self::ContractDeploy,
             crate::transaction::TransactionType::Stake => Self::Stake,
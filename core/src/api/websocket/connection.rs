@@ -5,9 +5,10 @@
 
 use axum::extract::ws::{WebSocket, Message};
 use futures_util::{SinkExt, StreamExt};
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::Arc;
-use tokio::sync::{broadcast, RwLock, mpsc};
+use std::sync::atomic::{AtomicU64, AtomicUsize, Ordering};
+use tokio::sync::{RwLock, mpsc, Notify};
 use tokio::time::{Duration, Instant};
 
 use crate::api::{
@@ -16,6 +17,7 @@ use crate::api::{
 };
 use super::{
     messages::*,
+    events::EventBus,
     WebSocketConfig, WebSocketError, WebSocketResult,
     ConnectionStats, ConnectionInfo,
 };
@@ -31,8 +33,14 @@ pub struct ConnectionManager {
     connections_by_user: HashMap<String, HashSet<String>>,
     /// Topics et leurs abonnés
     topic_subscribers: HashMap<String, HashSet<String>>,
-    /// Canaux de diffusion par topic
-    broadcast_channels: HashMap<String, broadcast::Sender<WsMessage>>,
+    /// Filtres de souscription par connexion puis par topic
+    subscription_filters: HashMap<String, HashMap<String, SubscriptionFilter>>,
+    /// Bus interne recevant chaque événement diffusé
+    event_bus: EventBus,
+    /// Dernier numéro de séquence attribué par topic
+    topic_sequences: HashMap<String, u64>,
+    /// Derniers événements diffusés par topic, rejoués lors d'une reprise
+    replay_buffers: HashMap<String, VecDeque<WsMessage>>,
     /// Statistiques globales
     stats: GlobalStats,
     /// Heure de démarrage
@@ -56,12 +64,67 @@ pub struct Connection {
     pub stats: RwLock<ConnectionStatsData>,
     /// Canal pour envoyer des messages à cette connexion
     pub sender: mpsc::UnboundedSender<WsMessage>,
+    /// Suivi de la file sortante, pour la contre-pression
+    pub outbound: Arc<OutboundQueue>,
     /// Adresse IP distante
     pub remote_addr: Option<String>,
     /// User-Agent
     pub user_agent: Option<String>,
 }
 
+/// Suivi de la file sortante d'une connexion
+///
+/// Le gestionnaire incrémente la profondeur à chaque mise en file, la tâche
+/// d'envoi du handler la décrémente une fois le message écrit sur le socket.
+#[derive(Debug, Default)]
+pub struct OutboundQueue {
+    /// Messages en attente d'écriture sur le socket
+    depth: AtomicUsize,
+    /// Messages non critiques abandonnés
+    dropped: AtomicU64,
+    /// Début de la saturation de la file (None si elle n'est pas pleine)
+    saturated_since: std::sync::Mutex<Option<Instant>>,
+    /// Raison de fermeture imposée par le serveur
+    close_reason: std::sync::Mutex<Option<String>>,
+    close_notify: Notify,
+}
+
+impl OutboundQueue {
+    /// Nombre de messages en attente
+    pub fn depth(&self) -> usize {
+        self.depth.load(Ordering::Relaxed)
+    }
+
+    /// Nombre de messages abandonnés
+    pub fn dropped(&self) -> u64 {
+        self.dropped.load(Ordering::Relaxed)
+    }
+
+    /// Signale qu'un message a été écrit sur le socket
+    pub fn mark_sent(&self) {
+        let _ = self.depth.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |depth| depth.checked_sub(1));
+    }
+
+    /// Demande la fermeture de la connexion
+    pub fn close(&self, reason: impl Into<String>) {
+        *self.close_reason.lock().unwrap() = Some(reason.into());
+        self.close_notify.notify_one();
+    }
+
+    /// Attend une demande de fermeture et retourne sa raison
+    pub async fn closed(&self) -> String {
+        loop {
+            if let Some(reason) = self.close_reason.lock().unwrap().clone() {
+                return reason;
+            }
+            self.close_notify.notified().await;
+        }
+    }
+}
+
+/// Raison de fermeture envoyée à un client trop lent
+pub const SLOW_CONSUMER_CLOSE_REASON: &str = "slow consumer";
+
 /// Statistiques globales du serveur WebSocket
 #[derive(Debug, Default)]
 pub struct GlobalStats {
@@ -72,30 +135,33 @@ pub struct GlobalStats {
     pub total_messages_received: u64,
     pub total_bytes_sent: u64,
     pub total_bytes_received: u64,
+    pub total_messages_dropped: u64,
+    pub slow_consumer_disconnects: u64,
 }
 
 impl ConnectionManager {
     /// Crée un nouveau gestionnaire de connexions
     pub fn new(config: WebSocketConfig) -> Self {
-        let mut broadcast_channels = HashMap::new();
-        
-        // Crée les canaux de diffusion pour chaque topic
-        for topic in SubscriptionTopic::all_topics() {
-            let (tx, _) = broadcast::channel(1000);
-            broadcast_channels.insert(topic.as_str().to_string(), tx);
-        }
-
         Self {
             config,
             connections: HashMap::new(),
             connections_by_user: HashMap::new(),
             topic_subscribers: HashMap::new(),
-            broadcast_channels,
+            subscription_filters: HashMap::new(),
+            event_bus: EventBus::default(),
+            topic_sequences: HashMap::new(),
+            replay_buffers: HashMap::new(),
             stats: GlobalStats::default(),
             start_time: Instant::now(),
         }
     }
 
+    /// Publie les événements diffusés sur un bus partagé (subscriptions GraphQL)
+    pub fn with_event_bus(mut self, event_bus: EventBus) -> Self {
+        self.event_bus = event_bus;
+        self
+    }
+
     /// Ajoute une nouvelle connexion
     pub async fn add_connection(
         &mut self,
@@ -123,6 +189,7 @@ impl ConnectionManager {
                 last_activity: chrono::Utc::now(),
             }),
             sender,
+            outbound: Arc::new(OutboundQueue::default()),
             remote_addr,
             user_agent,
         });
@@ -157,6 +224,7 @@ impl ConnectionManager {
                     }
                 }
             }
+            self.subscription_filters.remove(connection_id);
 
             self.stats.current_connections -= 1;
         }
@@ -199,6 +267,18 @@ impl ConnectionManager {
         &mut self,
         connection_id: &str,
         topic: &str,
+    ) -> WebSocketResult<()> {
+        self.subscribe_to_topic_with_filter(connection_id, topic, None).await
+    }
+
+    /// Souscrit une connexion à un topic avec un filtre optionnel
+    ///
+    /// Une nouvelle souscription au même topic remplace le filtre précédent.
+    pub async fn subscribe_to_topic_with_filter(
+        &mut self,
+        connection_id: &str,
+        topic: &str,
+        filter: Option<SubscriptionFilter>,
     ) -> WebSocketResult<()> {
         let connection = self.connections.get_mut(connection_id)
             .ok_or(WebSocketError::ConnectionClosed)?;
@@ -238,9 +318,31 @@ impl ConnectionManager {
             .or_insert_with(HashSet::new)
             .insert(connection_id.to_string());
 
+        let connection_filters = self.subscription_filters
+            .entry(connection_id.to_string())
+            .or_default();
+        match filter {
+            Some(filter) => {
+                connection_filters.insert(topic.to_string(), filter);
+            }
+            None => {
+                connection_filters.remove(topic);
+            }
+        }
+        if connection_filters.is_empty() {
+            self.subscription_filters.remove(connection_id);
+        }
+
         Ok(())
     }
 
+    /// Récupère le filtre actif d'une connexion pour un topic
+    pub fn get_subscription_filter(&self, connection_id: &str, topic: &str) -> Option<&SubscriptionFilter> {
+        self.subscription_filters
+            .get(connection_id)
+            .and_then(|filters| filters.get(topic))
+    }
+
     /// Désabonne une connexion d'un topic
     pub async fn unsubscribe_from_topic(
         &mut self,
@@ -262,11 +364,27 @@ impl ConnectionManager {
             }
         }
 
+        if let Some(filters) = self.subscription_filters.get_mut(connection_id) {
+            filters.remove(topic);
+            if filters.is_empty() {
+                self.subscription_filters.remove(connection_id);
+            }
+        }
+
         Ok(())
     }
 
     /// Diffuse un message à tous les abonnés d'un topic
-    pub async fn broadcast_to_topic(&mut self, topic: &str, message: WsMessage) -> WebSocketResult<usize> {
+    ///
+    /// Les événements reçoivent le numéro de séquence suivant du topic et sont
+    /// conservés dans son tampon de reprise et publiés sur le bus d'événements,
+    /// même sans abonné WebSocket. Les abonnés dont
+    /// le filtre ne correspond pas au message sont ignorés et ne sont pas
+    /// comptabilisés dans les statistiques.
+    pub async fn broadcast_to_topic(&mut self, topic: &str, mut message: WsMessage) -> WebSocketResult<usize> {
+        self.record_event(topic, &mut message);
+        self.event_bus.publish(topic, message.clone());
+
         let subscribers = self.topic_subscribers.get(topic)
             .map(|s| s.clone())
             .unwrap_or_default();
@@ -274,19 +392,28 @@ impl ConnectionManager {
         let mut sent_count = 0;
 
         for connection_id in subscribers {
-            if let Some(connection) = self.connections.get(&connection_id) {
-                if let Err(_) = connection.sender.send(message.clone()) {
-                    // Connexion fermée, on la supprimera au prochain nettoyage
-                    tracing::warn!("Failed to send message to connection {}", connection_id);
-                } else {
+            if let Some(filter) = self.get_subscription_filter(&connection_id, topic) {
+                if !filter.matches(&message) {
+                    continue;
+                }
+            }
+
+            match self.enqueue(&connection_id, message.clone()).await {
+                Ok(true) => {
                     sent_count += 1;
-                    
+
                     // Met à jour les statistiques
-                    if let Ok(mut stats) = connection.stats.write().await {
+                    if let Some(connection) = self.connections.get(&connection_id) {
+                        let mut stats = connection.stats.write().await;
                         stats.messages_sent += 1;
                         stats.last_activity = chrono::Utc::now();
                     }
                 }
+                Ok(false) => {}
+                Err(_) => {
+                    // Connexion fermée, on la supprimera au prochain nettoyage
+                    tracing::warn!("Failed to send message to connection {}", connection_id);
+                }
             }
         }
 
@@ -295,20 +422,105 @@ impl ConnectionManager {
         Ok(sent_count)
     }
 
+    /// Numérote un événement et l'ajoute au tampon de reprise du topic
+    fn record_event(&mut self, topic: &str, message: &mut WsMessage) {
+        let next = self.topic_sequences.get(topic).copied().unwrap_or(0) + 1;
+        message.set_sequence(next);
+        if message.sequence() != Some(next) {
+            // Pas un événement (ping, réponse...) : ni numéroté ni conservé
+            return;
+        }
+        self.topic_sequences.insert(topic.to_string(), next);
+
+        let capacity = self.config.replay_buffer_size;
+        if capacity == 0 {
+            return;
+        }
+        let buffer = self.replay_buffers.entry(topic.to_string()).or_default();
+        while buffer.len() >= capacity {
+            buffer.pop_front();
+        }
+        buffer.push_back(message.clone());
+    }
+
+    /// Dernier numéro de séquence attribué sur un topic (0 si aucun événement)
+    pub fn latest_sequence(&self, topic: &str) -> u64 {
+        self.topic_sequences.get(topic).copied().unwrap_or(0)
+    }
+
+    /// Reprend un topic après reconnexion
+    ///
+    /// Souscrit la connexion si nécessaire puis lui rejoue, dans l'ordre, les
+    /// événements de séquence supérieure à `last_seq`. Le verrou du gestionnaire
+    /// étant tenu pendant toute l'opération, aucun événement en direct ne peut
+    /// s'intercaler avant la fin du rejeu. Si une partie des événements manqués
+    /// a été évincée du tampon, ou si `last_seq` est inconnu, un `resume_failed`
+    /// est envoyé à la place et le client doit se resynchroniser. Retourne le
+    /// nombre d'événements rejoués.
+    pub async fn resume(&mut self, connection_id: &str, topic: &str, last_seq: u64) -> WebSocketResult<usize> {
+        let subscribed = self.connections.get(connection_id)
+            .ok_or(WebSocketError::ConnectionClosed)?
+            .subscriptions
+            .contains(topic);
+        if !subscribed {
+            self.subscribe_to_topic(connection_id, topic).await?;
+        }
+
+        let latest = self.latest_sequence(topic);
+        let oldest = self.replay_buffers.get(topic)
+            .and_then(|buffer| buffer.front())
+            .and_then(WsMessage::sequence);
+        let available = last_seq <= latest
+            && (last_seq == latest || oldest.is_some_and(|oldest| last_seq + 1 >= oldest));
+
+        if !available {
+            tracing::debug!(
+                "Cannot resume {} for {} from sequence {} (oldest {:?}, latest {})",
+                topic, connection_id, last_seq, oldest, latest
+            );
+            self.send_to_connection(
+                connection_id,
+                MessageBuilder::resume_failed(topic.to_string(), last_seq, oldest),
+            ).await?;
+            return Ok(0);
+        }
+
+        let missed: Vec<WsMessage> = self.replay_buffers.get(topic)
+            .map(|buffer| {
+                buffer.iter()
+                    .filter(|message| message.sequence().is_some_and(|seq| seq > last_seq))
+                    .cloned()
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        let mut replayed = 0;
+        for message in missed {
+            if let Some(filter) = self.get_subscription_filter(connection_id, topic) {
+                if !filter.matches(&message) {
+                    continue;
+                }
+            }
+            self.send_to_connection(connection_id, message).await?;
+            replayed += 1;
+        }
+
+        Ok(replayed)
+    }
+
     /// Envoie un message à une connexion spécifique
     pub async fn send_to_connection(
         &mut self,
         connection_id: &str,
         message: WsMessage,
     ) -> WebSocketResult<()> {
-        let connection = self.connections.get(connection_id)
-            .ok_or(WebSocketError::ConnectionClosed)?;
-
-        connection.sender.send(message)
-            .map_err(|_| WebSocketError::ConnectionClosed)?;
+        if !self.enqueue(connection_id, message).await? {
+            return Ok(());
+        }
 
         // Met à jour les statistiques
-        if let Ok(mut stats) = connection.stats.write().await {
+        if let Some(connection) = self.connections.get(connection_id) {
+            let mut stats = connection.stats.write().await;
             stats.messages_sent += 1;
             stats.last_activity = chrono::Utc::now();
         }
@@ -318,6 +530,52 @@ impl ConnectionManager {
         Ok(())
     }
 
+    /// Met un message dans la file sortante d'une connexion
+    ///
+    /// Quand la file atteint `send_buffer_size`, les messages non critiques
+    /// sont abandonnés (retourne `false`). Si elle reste pleine au-delà de
+    /// `slow_consumer_grace_period`, la connexion est fermée. Les messages
+    /// critiques sont toujours mis en file tant que la connexion est ouverte.
+    async fn enqueue(&mut self, connection_id: &str, message: WsMessage) -> WebSocketResult<bool> {
+        let connection = self.connections.get(connection_id)
+            .ok_or(WebSocketError::ConnectionClosed)?;
+        let outbound = connection.outbound.clone();
+        let capacity = self.config.send_buffer_size.max(1);
+
+        if outbound.depth() >= capacity {
+            let now = Instant::now();
+            let saturated_for = {
+                let mut saturated_since = outbound.saturated_since.lock().unwrap();
+                now - *saturated_since.get_or_insert(now)
+            };
+
+            if saturated_for >= Duration::from_secs(self.config.slow_consumer_grace_period) {
+                tracing::warn!(
+                    "Disconnecting slow WebSocket consumer {} ({} messages queued)",
+                    connection_id, outbound.depth()
+                );
+                outbound.close(SLOW_CONSUMER_CLOSE_REASON);
+                self.stats.slow_consumer_disconnects += 1;
+                self.remove_connection(connection_id).await;
+                return Err(WebSocketError::ConnectionClosed);
+            }
+
+            if !message.is_critical() {
+                outbound.dropped.fetch_add(1, Ordering::Relaxed);
+                self.stats.total_messages_dropped += 1;
+                return Ok(false);
+            }
+        } else {
+            *outbound.saturated_since.lock().unwrap() = None;
+        }
+
+        connection.sender.send(message)
+            .map_err(|_| WebSocketError::ConnectionClosed)?;
+        outbound.depth.fetch_add(1, Ordering::Relaxed);
+
+        Ok(true)
+    }
+
     /// Met à jour l'activité d'une connexion
     pub async fn update_activity(&mut self, connection_id: &str, bytes_received: u64) {
         if let Some(connection) = self.connections.get(connection_id) {
@@ -377,6 +635,8 @@ impl ConnectionManager {
             subscriptions_by_topic,
             total_messages_sent: self.stats.total_messages_sent,
             total_messages_received: self.stats.total_messages_received,
+            total_messages_dropped: self.stats.total_messages_dropped,
+            slow_consumer_disconnects: self.stats.slow_consumer_disconnects,
             uptime_seconds: self.start_time.elapsed().as_secs(),
         }
     }
@@ -621,4 +881,201 @@ mod tests {
         assert_eq!(stats.anonymous_connections, 1);
         assert_eq!(stats.authenticated_connections, 0);
     }
-}
\ No newline at end of file
+
+    fn archive_update(archive_id: &str) -> WsMessage {
+        WsMessage::ArchiveUpdate {
+            archive_id: archive_id.to_string(),
+            status: "completed".to_string(),
+            progress: None,
+            data: None,
+            timestamp: chrono::Utc::now(),
+            seq: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_full_queue_drops_non_critical_events() {
+        let config = WebSocketConfig {
+            send_buffer_size: 2,
+            slow_consumer_grace_period: 3600,
+            ..WebSocketConfig::default()
+        };
+        let mut manager = ConnectionManager::new(config);
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        manager.add_connection("conn_1".to_string(), tx, None, None).await.unwrap();
+
+        for i in 0..5 {
+            manager.send_to_connection("conn_1", archive_update(&format!("arc_{}", i))).await.unwrap();
+        }
+        // Les erreurs passent même quand la file est pleine
+        manager.send_to_connection("conn_1", MessageBuilder::error("E".to_string(), "boom".to_string())).await.unwrap();
+
+        let outbound = manager.get_connection("conn_1").unwrap().outbound.clone();
+        assert_eq!(outbound.depth(), 3);
+        assert_eq!(outbound.dropped(), 3);
+        let stats = manager.get_stats().await;
+        assert_eq!(stats.total_messages_dropped, 3);
+        assert_eq!(stats.slow_consumer_disconnects, 0);
+
+        let mut received = Vec::new();
+        while let Ok(message) = rx.try_recv() {
+            outbound.mark_sent();
+            received.push(message);
+        }
+        assert_eq!(received.len(), 3);
+        assert!(matches!(received[2], WsMessage::Error { .. }));
+
+        // Une fois la file vidée, les événements sont de nouveau acceptés
+        manager.send_to_connection("conn_1", archive_update("arc_5")).await.unwrap();
+        assert_eq!(outbound.depth(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_slow_consumer_is_disconnected_after_grace_period() {
+        let config = WebSocketConfig {
+            send_buffer_size: 1,
+            slow_consumer_grace_period: 0,
+            ..WebSocketConfig::default()
+        };
+        let mut manager = ConnectionManager::new(config);
+        let (tx, _rx) = mpsc::unbounded_channel();
+        manager.add_connection("conn_1".to_string(), tx, None, None).await.unwrap();
+        manager.subscribe_to_topic("conn_1", "network_stats").await.unwrap();
+        let outbound = manager.get_connection("conn_1").unwrap().outbound.clone();
+
+        assert_eq!(manager.broadcast_to_topic("network_stats", archive_update("arc_0")).await.unwrap(), 1);
+        assert_eq!(manager.broadcast_to_topic("network_stats", archive_update("arc_1")).await.unwrap(), 0);
+
+        assert!(manager.get_connection("conn_1").is_none());
+        assert_eq!(manager.get_stats().await.slow_consumer_disconnects, 1);
+        assert_eq!(outbound.closed().await, SLOW_CONSUMER_CLOSE_REASON);
+    }
+
+    #[tokio::test]
+    async fn test_broadcast_with_url_filter() {
+        let config = WebSocketConfig::default();
+        let mut manager = ConnectionManager::new(config);
+        let (tx, mut rx) = mpsc::unbounded_channel();
+
+        manager.add_connection("conn_1".to_string(), tx, None, None).await.unwrap();
+        manager.authenticate_connection("conn_1", create_test_auth_info()).await.unwrap();
+
+        let filter = SubscriptionFilter::from_glob("https://example.com/*").unwrap();
+        manager.subscribe_to_topic_with_filter("conn_1", "archive_updates", Some(filter)).await.unwrap();
+
+        let update_for = |url: &str| {
+            let mut data = HashMap::new();
+            data.insert("url".to_string(), serde_json::json!(url));
+            MessageBuilder::archive_update("archive_1".to_string(), "Completed".to_string(), None, Some(data))
+        };
+
+        // Événement non correspondant : ni envoyé ni comptabilisé
+        let sent = manager.broadcast_to_topic("archive_updates", update_for("https://other.org/page")).await.unwrap();
+        assert_eq!(sent, 0);
+        assert!(rx.try_recv().is_err());
+        let stats = manager.get_connection("conn_1").unwrap().stats.read().await.clone();
+        assert_eq!(stats.messages_sent, 0);
+        assert_eq!(manager.stats.total_messages_sent, 0);
+
+        // Événement correspondant
+        let sent = manager.broadcast_to_topic("archive_updates", update_for("https://example.com/page")).await.unwrap();
+        assert_eq!(sent, 1);
+        assert!(rx.try_recv().is_ok());
+
+        // Mise à jour de la souscription : le nouveau filtre s'applique
+        let filter = SubscriptionFilter::from_glob("https://other.org/*").unwrap();
+        manager.subscribe_to_topic_with_filter("conn_1", "archive_updates", Some(filter)).await.unwrap();
+        let sent = manager.broadcast_to_topic("archive_updates", update_for("https://example.com/page")).await.unwrap();
+        assert_eq!(sent, 0);
+        let sent = manager.broadcast_to_topic("archive_updates", update_for("https://other.org/page")).await.unwrap();
+        assert_eq!(sent, 1);
+
+        // Souscription sans filtre : tout passe
+        manager.subscribe_to_topic("conn_1", "archive_updates").await.unwrap();
+        assert!(manager.get_subscription_filter("conn_1", "archive_updates").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_sequence_numbers_strictly_increase_per_topic() {
+        let mut manager = ConnectionManager::new(WebSocketConfig::default());
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        manager.add_connection("conn_1".to_string(), tx, None, None).await.unwrap();
+        manager.subscribe_to_topic("conn_1", "network_stats").await.unwrap();
+
+        // Les événements sans abonné sont numérotés aussi, sur leur propre topic
+        manager.broadcast_to_topic("node_status", archive_update("other")).await.unwrap();
+        for i in 0..4 {
+            manager.broadcast_to_topic("network_stats", archive_update(&format!("arc_{}", i))).await.unwrap();
+        }
+        // Les messages qui ne sont pas des événements ne consomment pas de séquence
+        manager.broadcast_to_topic("network_stats", MessageBuilder::ping()).await.unwrap();
+        manager.broadcast_to_topic("network_stats", archive_update("arc_4")).await.unwrap();
+
+        let sequences: Vec<u64> = std::iter::from_fn(|| rx.try_recv().ok())
+            .filter_map(|message| message.sequence())
+            .collect();
+        assert_eq!(sequences, vec![1, 2, 3, 4, 5]);
+        assert_eq!(manager.latest_sequence("network_stats"), 5);
+        assert_eq!(manager.latest_sequence("node_status"), 1);
+    }
+
+    #[tokio::test]
+    async fn test_resume_replays_missed_events() {
+        let mut manager = ConnectionManager::new(WebSocketConfig::default());
+        for i in 0..5 {
+            manager.broadcast_to_topic("network_stats", archive_update(&format!("arc_{}", i))).await.unwrap();
+        }
+
+        // Le client s'était arrêté à la séquence 2
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        manager.add_connection("conn_1".to_string(), tx, None, None).await.unwrap();
+        assert_eq!(manager.resume("conn_1", "network_stats", 2).await.unwrap(), 3);
+
+        let replayed: Vec<u64> = std::iter::from_fn(|| rx.try_recv().ok())
+            .map(|message| message.sequence().unwrap())
+            .collect();
+        assert_eq!(replayed, vec![3, 4, 5]);
+
+        // La reprise souscrit la connexion : le flux en direct continue ensuite
+        manager.broadcast_to_topic("network_stats", archive_update("arc_5")).await.unwrap();
+        assert_eq!(rx.try_recv().unwrap().sequence(), Some(6));
+
+        // Déjà à jour : rien à rejouer
+        assert_eq!(manager.resume("conn_1", "network_stats", 6).await.unwrap(), 0);
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[tokio::test]
+    async fn test_resume_after_eviction_requires_resync() {
+        let config = WebSocketConfig {
+            replay_buffer_size: 3,
+            ..WebSocketConfig::default()
+        };
+        let mut manager = ConnectionManager::new(config);
+        for i in 0..6 {
+            manager.broadcast_to_topic("network_stats", archive_update(&format!("arc_{}", i))).await.unwrap();
+        }
+
+        let (tx, mut rx) = mpsc::unbounded_channel();
+        manager.add_connection("conn_1".to_string(), tx, None, None).await.unwrap();
+
+        // Les séquences 1 à 3 ont été évincées : l'événement 2 est perdu
+        assert_eq!(manager.resume("conn_1", "network_stats", 1).await.unwrap(), 0);
+        match rx.try_recv().unwrap() {
+            WsMessage::ResumeFailed { topic, last_seq, oldest_seq, .. } => {
+                assert_eq!(topic, "network_stats");
+                assert_eq!(last_seq, 1);
+                assert_eq!(oldest_seq, Some(4));
+            }
+            other => panic!("unexpected message: {:?}", other),
+        }
+        assert!(rx.try_recv().is_err());
+
+        // Séquence venant d'un autre serveur (plus grande que la dernière connue)
+        assert_eq!(manager.resume("conn_1", "network_stats", 42).await.unwrap(), 0);
+        assert!(matches!(rx.try_recv().unwrap(), WsMessage::ResumeFailed { .. }));
+
+        // La limite du tampon reste exploitable
+        assert_eq!(manager.resume("conn_1", "network_stats", 3).await.unwrap(), 3);
+    }
+}
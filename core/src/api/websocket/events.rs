@@ -1,11 +1,15 @@
 //! Gestionnaire d'événements WebSocket pour ArchiveChain
 //!
 //! Gère la diffusion d'événements en temps réel aux clients WebSocket connectés.
+//! Les événements diffusés alimentent aussi le bus interne (`EventBus`) dont
+//! se servent les subscriptions GraphQL.
 
+use futures_util::{Stream, StreamExt};
 use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::sync::RwLock;
+use tokio::sync::{broadcast, RwLock};
 use tokio::time::{Duration, interval};
+use tokio_stream::wrappers::{errors::BroadcastStreamRecvError, BroadcastStream};
 
 use crate::api::types::*;
 use super::{
@@ -13,6 +17,70 @@ use super::{
     messages::*,
 };
 
+/// Nombre d'événements retenus par topic pour les abonnés en retard
+pub const DEFAULT_EVENT_BUS_CAPACITY: usize = 1000;
+
+/// Bus d'événements interne, partagé par les API WebSocket et GraphQL
+///
+/// Un canal `broadcast` par topic. La mémoire est bornée par la capacité du
+/// canal : un abonné trop lent perd les événements les plus anciens au lieu
+/// d'accumuler un retard illimité. Le récepteur d'un abonné est libéré dès que
+/// son flux est abandonné.
+#[derive(Debug, Clone)]
+pub struct EventBus {
+    channels: Arc<HashMap<String, broadcast::Sender<WsMessage>>>,
+}
+
+impl EventBus {
+    /// Crée un bus retenant au plus `capacity` événements par topic
+    pub fn new(capacity: usize) -> Self {
+        let channels = SubscriptionTopic::all_topics()
+            .into_iter()
+            .map(|topic| (topic.as_str().to_string(), broadcast::channel(capacity.max(1)).0))
+            .collect();
+        Self { channels: Arc::new(channels) }
+    }
+
+    /// Publie un événement ; retourne le nombre d'abonnés qui le recevront
+    pub fn publish(&self, topic: &str, message: WsMessage) -> usize {
+        self.channels.get(topic)
+            .and_then(|sender| sender.send(message).ok())
+            .unwrap_or(0)
+    }
+
+    /// Flux des événements d'un topic publiés à partir de maintenant
+    ///
+    /// Les événements perdus par un abonné trop lent sont sautés : le flux
+    /// reprend au plus ancien événement encore retenu.
+    pub fn subscribe(&self, topic: &SubscriptionTopic) -> impl Stream<Item = WsMessage> + Send + 'static {
+        let receiver = self.channels.get(topic.as_str()).map(|sender| sender.subscribe());
+        let topic = topic.as_str();
+
+        futures_util::stream::iter(receiver)
+            .flat_map(BroadcastStream::new)
+            .filter_map(move |event| async move {
+                match event {
+                    Ok(message) => Some(message),
+                    Err(BroadcastStreamRecvError::Lagged(skipped)) => {
+                        tracing::debug!("Slow subscriber on {} skipped {} events", topic, skipped);
+                        None
+                    }
+                }
+            })
+    }
+
+    /// Nombre d'abonnés actifs sur un topic
+    pub fn subscriber_count(&self, topic: &SubscriptionTopic) -> usize {
+        self.channels.get(topic.as_str()).map_or(0, |sender| sender.receiver_count())
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new(DEFAULT_EVENT_BUS_CAPACITY)
+    }
+}
+
 /// Gestionnaire d'événements WebSocket
 #[derive(Clone)]
 pub struct EventManager {
@@ -102,17 +170,7 @@ impl EventManager {
 
     /// Diffuse un événement de nouveau bloc
     pub async fn broadcast_new_block(&self, block: BlockDto) -> Result<usize, String> {
-        let block_update = BlockUpdate {
-            height: block.height,
-            hash: block.hash,
-            timestamp: block.timestamp,
-            transactions: block.transactions.len() as u32,
-            archives: block.archive_count,
-            validator: block.validator,
-            size: 0, // TODO: Calculer la vraie taille du bloc
-        };
-
-        let message = MessageBuilder::new_block(block_update);
+        let message = MessageBuilder::new_block(BlockUpdate::from(block));
         self.broadcast_to_topic("new_blocks", message).await
     }
 
@@ -143,6 +201,7 @@ impl EventManager {
             status: format!("{:?}", status),
             region,
             timestamp: chrono::Utc::now(),
+            seq: None,
         };
 
         self.broadcast_to_topic("node_status_change", message).await
@@ -160,6 +219,7 @@ impl EventManager {
             status,
             data,
             timestamp: chrono::Utc::now(),
+            seq: None,
         };
 
         self.broadcast_to_topic("bounty_updates", message).await
@@ -181,6 +241,7 @@ impl EventManager {
             block_height,
             transaction_hash,
             timestamp: chrono::Utc::now(),
+            seq: None,
         };
 
         self.broadcast_to_topic("contract_events", message).await
@@ -310,6 +371,8 @@ impl EventTestHelper {
                 last_verified: chrono::Utc::now(),
             },
             access_urls: AccessUrls::new("https://gateway.example.com", "arc_test_123"),
+            confirmations: None,
+            finalized: false,
         }
     }
 
@@ -3,15 +3,16 @@
 //! Gère le cycle de vie complet des connexions WebSocket incluant
 //! l'authentification, les souscriptions et la communication bidirectionnelle.
 
-use axum::extract::ws::{WebSocket, Message};
+use axum::extract::ws::{close_code, CloseFrame, WebSocket, Message};
 use futures_util::{SinkExt, StreamExt, stream::{SplitSink, SplitStream}};
 use std::sync::Arc;
 use tokio::sync::{mpsc, RwLock};
 use tokio::time::{Duration, Instant, interval};
+use tracing::Instrument;
 
 use crate::api::{
     auth::AuthService,
-    middleware::AuthInfo,
+    middleware::{with_request_id, AuthInfo},
 };
 use super::{
     connection::ConnectionManager,
@@ -27,6 +28,8 @@ pub struct WebSocketHandler {
     state: WebSocketState,
     /// ID unique de cette connexion
     connection_id: String,
+    /// Identifiant de corrélation de la requête d'upgrade
+    request_id: String,
     /// Canal pour recevoir les messages à envoyer
     message_receiver: mpsc::UnboundedReceiver<WsMessage>,
     /// Sender pour envoyer des messages à cette connexion
@@ -42,18 +45,38 @@ impl WebSocketHandler {
         Self {
             socket,
             state,
+            request_id: connection_id.clone(),
             connection_id,
             message_receiver,
             message_sender,
         }
     }
 
+    /// Rattache la connexion à l'identifiant de corrélation de la requête d'upgrade
+    pub fn with_request_id(mut self, request_id: String) -> Self {
+        self.request_id = request_id;
+        self
+    }
+
     /// Gère la connexion WebSocket
-    pub async fn handle_connection(mut self) {
+    ///
+    /// Toute la connexion s'exécute dans un span `ws_connection` ; chaque
+    /// message reçu est traité dans un span enfant `ws_message` sous
+    /// l'identifiant `<request_id>:<seq>`, repris par les couches appelées.
+    pub async fn handle_connection(self) {
+        let span = tracing::info_span!(
+            "ws_connection",
+            connection_id = %self.connection_id,
+            request_id = %self.request_id,
+        );
+        self.run().instrument(span).await
+    }
+
+    async fn run(mut self) {
         tracing::info!("New WebSocket connection: {}", self.connection_id);
 
         // Ajoute la connexion au gestionnaire
-        {
+        let outbound = {
             let mut manager = self.state.connection_manager.write().await;
             if let Err(e) = manager.add_connection(
                 self.connection_id.clone(),
@@ -64,7 +87,11 @@ impl WebSocketHandler {
                 tracing::error!("Failed to add connection: {}", e);
                 return;
             }
-        }
+            match manager.get_connection(&self.connection_id) {
+                Some(connection) => connection.outbound.clone(),
+                None => return,
+            }
+        };
 
         // Divise le socket en sink et stream
         let (mut socket_sender, mut socket_receiver) = self.socket.split();
@@ -72,10 +99,35 @@ impl WebSocketHandler {
         // Tâche pour envoyer des messages
         let connection_id_send = self.connection_id.clone();
         let state_send = self.state.clone();
+        let shutdown = self.state.server_state.shutdown.clone();
         let send_task = tokio::spawn(async move {
             let mut message_receiver = self.message_receiver;
             
-            while let Some(message) = message_receiver.recv().await {
+            loop {
+                let message = tokio::select! {
+                    message = message_receiver.recv() => match message {
+                        Some(message) => message,
+                        None => break,
+                    },
+                    // Fermeture imposée par le serveur (client trop lent)
+                    reason = outbound.closed() => {
+                        let _ = socket_sender.send(Message::Close(Some(CloseFrame {
+                            code: close_code::POLICY,
+                            reason: reason.into(),
+                        }))).await;
+                        break;
+                    }
+                    // Arrêt du serveur : le client est prévenu avant la coupure
+                    _ = shutdown.triggered() => {
+                        let _ = socket_sender.send(Message::Close(Some(CloseFrame {
+                            code: close_code::AWAY,
+                            reason: "server shutting down".into(),
+                        }))).await;
+                        break;
+                    }
+                };
+                outbound.mark_sent();
+
                 let serialized = match serde_json::to_string(&message) {
                     Ok(s) => s,
                     Err(e) => {
@@ -89,13 +141,15 @@ impl WebSocketHandler {
                     break;
                 }
             }
-        });
+        }.in_current_span());
 
         // Tâche pour recevoir des messages
         let connection_id_recv = self.connection_id.clone();
         let state_recv = self.state.clone();
         let message_sender_recv = self.message_sender.clone();
+        let request_id_recv = self.request_id.clone();
         let recv_task = tokio::spawn(async move {
+            let mut seq = 0u64;
             while let Some(message) = socket_receiver.next().await {
                 match message {
                     Ok(Message::Text(text)) => {
@@ -107,13 +161,17 @@ impl WebSocketHandler {
                             manager.update_activity(&connection_id_recv, message_len).await;
                         }
 
-                        // Traite le message
-                        if let Err(e) = Self::handle_text_message(
+                        // Traite le message sous son propre identifiant de corrélation
+                        seq += 1;
+                        let message_request_id = format!("{}:{}", request_id_recv, seq);
+                        let message_span = tracing::info_span!("ws_message", seq, request_id = %message_request_id);
+                        let handled = with_request_id(message_request_id, Self::handle_text_message(
                             text,
                             &connection_id_recv,
                             &state_recv,
                             &message_sender_recv,
-                        ).await {
+                        )).instrument(message_span).await;
+                        if let Err(e) = handled {
                             tracing::error!("Error handling message: {}", e);
                             let error_msg = MessageBuilder::error(
                                 "MESSAGE_ERROR".to_string(),
@@ -152,7 +210,7 @@ impl WebSocketHandler {
                     }
                 }
             }
-        });
+        }.in_current_span());
 
         // Tâche de ping périodique
         let ping_task = Self::start_ping_task(
@@ -208,6 +266,9 @@ impl WebSocketHandler {
             WsMessage::Unsubscribe { topics } => {
                 Self::handle_unsubscribe(topics, connection_id, state, message_sender).await
             }
+            WsMessage::Resume { topic, last_seq } => {
+                Self::handle_resume(topic, last_seq, connection_id, state, message_sender).await
+            }
             WsMessage::Ping { .. } => {
                 let pong = MessageBuilder::pong();
                 message_sender.send(pong)
@@ -282,16 +343,27 @@ impl WebSocketHandler {
     /// Gère les souscriptions
     async fn handle_subscribe(
         topics: Vec<String>,
-        _filters: Option<std::collections::HashMap<String, serde_json::Value>>,
+        filters: Option<std::collections::HashMap<String, serde_json::Value>>,
         connection_id: &str,
         state: &WebSocketState,
         message_sender: &mpsc::UnboundedSender<WsMessage>,
     ) -> WebSocketResult<()> {
+        let filter = match filters.as_ref().map(SubscriptionFilter::from_filters).transpose() {
+            Ok(filter) => filter.flatten(),
+            Err(e) => {
+                for topic in topics {
+                    let error_msg = MessageBuilder::subscription_error(topic, e.to_string());
+                    let _ = message_sender.send(error_msg);
+                }
+                return Ok(());
+            }
+        };
+
         let mut successful_topics = Vec::new();
         let mut manager = state.connection_manager.write().await;
 
         for topic in topics {
-            match manager.subscribe_to_topic(connection_id, &topic).await {
+            match manager.subscribe_to_topic_with_filter(connection_id, &topic, filter.clone()).await {
                 Ok(()) => successful_topics.push(topic),
                 Err(e) => {
                     let error_msg = MessageBuilder::subscription_error(topic, e.to_string());
@@ -335,6 +407,30 @@ impl WebSocketHandler {
         Ok(())
     }
 
+    /// Gère la reprise d'un topic après reconnexion
+    async fn handle_resume(
+        topic: String,
+        last_seq: u64,
+        connection_id: &str,
+        state: &WebSocketState,
+        message_sender: &mpsc::UnboundedSender<WsMessage>,
+    ) -> WebSocketResult<()> {
+        let mut manager = state.connection_manager.write().await;
+
+        match manager.resume(connection_id, &topic, last_seq).await {
+            Ok(replayed) => {
+                tracing::debug!("Replayed {} events on {} for {}", replayed, topic, connection_id);
+            }
+            Err(e) => {
+                let error_msg = MessageBuilder::subscription_error(topic, e.to_string());
+                message_sender.send(error_msg)
+                    .map_err(|_| WebSocketError::ConnectionClosed)?;
+            }
+        }
+
+        Ok(())
+    }
+
     /// Gère la demande de statut de connexion
     async fn handle_connection_status(
         connection_id: &str,
@@ -2,9 +2,12 @@
 //!
 //! Définit tous les types de messages WebSocket selon les spécifications API.
 
+use regex::Regex;
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
+use super::{WebSocketError, WebSocketResult};
+
 /// Types de messages WebSocket principaux
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(tag = "type", rename_all = "snake_case")]
@@ -49,12 +52,18 @@ pub enum WsMessage {
     NewBlock {
         block: BlockUpdate,
         timestamp: chrono::DateTime<chrono::Utc>,
+        /// Numéro de séquence sur le topic, attribué à la diffusion
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        seq: Option<u64>,
     },
     
     /// Nouvelle archive
     NewArchive {
         archive: ArchiveUpdate,
         timestamp: chrono::DateTime<chrono::Utc>,
+        /// Numéro de séquence sur le topic, attribué à la diffusion
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        seq: Option<u64>,
     },
     
     /// Mise à jour d'archive
@@ -64,12 +73,18 @@ pub enum WsMessage {
         progress: Option<f64>,
         data: Option<HashMap<String, serde_json::Value>>,
         timestamp: chrono::DateTime<chrono::Utc>,
+        /// Numéro de séquence sur le topic, attribué à la diffusion
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        seq: Option<u64>,
     },
     
     /// Mise à jour des statistiques réseau
     NetworkStats {
         data: NetworkStatsUpdate,
         timestamp: chrono::DateTime<chrono::Utc>,
+        /// Numéro de séquence sur le topic, attribué à la diffusion
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        seq: Option<u64>,
     },
     
     /// Changement de statut de nœud
@@ -78,6 +93,9 @@ pub enum WsMessage {
         status: String,
         region: Option<String>,
         timestamp: chrono::DateTime<chrono::Utc>,
+        /// Numéro de séquence sur le topic, attribué à la diffusion
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        seq: Option<u64>,
     },
     
     /// Mise à jour de bounty
@@ -86,6 +104,9 @@ pub enum WsMessage {
         status: String,
         data: Option<HashMap<String, serde_json::Value>>,
         timestamp: chrono::DateTime<chrono::Utc>,
+        /// Numéro de séquence sur le topic, attribué à la diffusion
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        seq: Option<u64>,
     },
     
     /// Événement de contrat intelligent
@@ -96,6 +117,9 @@ pub enum WsMessage {
         block_height: u64,
         transaction_hash: String,
         timestamp: chrono::DateTime<chrono::Utc>,
+        /// Numéro de séquence sur le topic, attribué à la diffusion
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        seq: Option<u64>,
     },
     
     /// Message de ping
@@ -121,6 +145,22 @@ pub enum WsMessage {
         timestamp: chrono::DateTime<chrono::Utc>,
     },
     
+    /// Reprise d'un topic après reconnexion : rejoue les événements de
+    /// séquence supérieure à `last_seq` avant le flux en direct
+    Resume {
+        topic: String,
+        last_seq: u64,
+    },
+
+    /// Reprise impossible (événements évincés) : le client doit se resynchroniser
+    ResumeFailed {
+        topic: String,
+        last_seq: u64,
+        /// Plus ancienne séquence encore disponible
+        oldest_seq: Option<u64>,
+        message: String,
+    },
+
     /// Requête de statut de connexion
     ConnectionStatus,
     
@@ -134,6 +174,54 @@ pub enum WsMessage {
     },
 }
 
+impl WsMessage {
+    /// Vrai pour les messages qui ne doivent jamais être abandonnés sous contre-pression
+    ///
+    /// Les réponses aux requêtes du client et les erreurs sont critiques ; les
+    /// événements diffusés et les pings peuvent être abandonnés.
+    pub fn is_critical(&self) -> bool {
+        matches!(
+            self,
+            Self::AuthResponse { .. }
+                | Self::SubscriptionConfirmed { .. }
+                | Self::SubscriptionError { .. }
+                | Self::Error { .. }
+                | Self::Success { .. }
+                | Self::Pong { .. }
+                | Self::ResumeFailed { .. }
+                | Self::ConnectionStatusResponse { .. }
+        )
+    }
+
+    /// Numéro de séquence d'un événement diffusé
+    pub fn sequence(&self) -> Option<u64> {
+        match self {
+            Self::NewBlock { seq, .. }
+            | Self::NewArchive { seq, .. }
+            | Self::ArchiveUpdate { seq, .. }
+            | Self::NetworkStats { seq, .. }
+            | Self::NodeStatusChange { seq, .. }
+            | Self::BountyUpdate { seq, .. }
+            | Self::ContractEvent { seq, .. } => *seq,
+            _ => None,
+        }
+    }
+
+    /// Attribue un numéro de séquence ; sans effet sur les messages qui ne sont pas des événements
+    pub fn set_sequence(&mut self, sequence: u64) {
+        match self {
+            Self::NewBlock { seq, .. }
+            | Self::NewArchive { seq, .. }
+            | Self::ArchiveUpdate { seq, .. }
+            | Self::NetworkStats { seq, .. }
+            | Self::NodeStatusChange { seq, .. }
+            | Self::BountyUpdate { seq, .. }
+            | Self::ContractEvent { seq, .. } => *seq = Some(sequence),
+            _ => {}
+        }
+    }
+}
+
 /// Mise à jour de bloc
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BlockUpdate {
@@ -146,6 +234,20 @@ pub struct BlockUpdate {
     pub size: u64,
 }
 
+impl From<crate::api::types::BlockDto> for BlockUpdate {
+    fn from(block: crate::api::types::BlockDto) -> Self {
+        Self {
+            height: block.height,
+            hash: block.hash,
+            timestamp: block.timestamp,
+            transactions: block.transactions.len() as u32,
+            archives: block.archive_count,
+            validator: block.validator,
+            size: 0, // TODO: Calculer la vraie taille du bloc
+        }
+    }
+}
+
 /// Mise à jour d'archive
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ArchiveUpdate {
@@ -329,6 +431,7 @@ impl MessageBuilder {
         WsMessage::NewBlock {
             block,
             timestamp: chrono::Utc::now(),
+            seq: None,
         }
     }
 
@@ -337,6 +440,7 @@ impl MessageBuilder {
         WsMessage::NewArchive {
             archive,
             timestamp: chrono::Utc::now(),
+            seq: None,
         }
     }
 
@@ -353,6 +457,7 @@ impl MessageBuilder {
             progress,
             data,
             timestamp: chrono::Utc::now(),
+            seq: None,
         }
     }
 
@@ -361,6 +466,17 @@ impl MessageBuilder {
         WsMessage::NetworkStats {
             data,
             timestamp: chrono::Utc::now(),
+            seq: None,
+        }
+    }
+
+    /// Crée un échec de reprise
+    pub fn resume_failed(topic: String, last_seq: u64, oldest_seq: Option<u64>) -> WsMessage {
+        WsMessage::ResumeFailed {
+            topic,
+            last_seq,
+            oldest_seq,
+            message: "Missed events are no longer available, full resync required".to_string(),
         }
     }
 
@@ -426,6 +542,11 @@ impl MessageValidator {
                     return Err("At least one topic is required".to_string());
                 }
             }
+            WsMessage::Resume { topic, .. } => {
+                if SubscriptionTopic::from_str(topic).is_none() {
+                    return Err(format!("Invalid topic: {}", topic));
+                }
+            }
             _ => {} // Autres messages sont valides par construction
         }
         Ok(())
@@ -446,6 +567,106 @@ impl MessageValidator {
     }
 }
 
+/// Filtre de souscription évalué côté serveur
+///
+/// Construit à partir du champ `filters` d'un message `subscribe` :
+/// `url_glob` (`*` et `?` comme jokers) ou `url_regex`.
+#[derive(Debug, Clone)]
+pub struct SubscriptionFilter {
+    /// Motif d'origine tel que fourni par le client
+    pub pattern: String,
+    /// Expression compilée appliquée aux URLs d'archive
+    url_regex: Regex,
+}
+
+impl SubscriptionFilter {
+    /// Clé de filtre pour un motif glob
+    pub const URL_GLOB: &'static str = "url_glob";
+    /// Clé de filtre pour une expression régulière
+    pub const URL_REGEX: &'static str = "url_regex";
+
+    /// Construit un filtre à partir d'un motif glob
+    pub fn from_glob(glob: &str) -> WebSocketResult<Self> {
+        let mut expr = String::with_capacity(glob.len() + 2);
+        expr.push('^');
+        for c in glob.chars() {
+            match c {
+                '*' => expr.push_str(".*"),
+                '?' => expr.push('.'),
+                _ => expr.push_str(&regex::escape(&c.to_string())),
+            }
+        }
+        expr.push('$');
+
+        let url_regex = Regex::new(&expr)
+            .map_err(|e| WebSocketError::SubscriptionFailed(format!("Invalid URL glob: {}", e)))?;
+
+        Ok(Self { pattern: glob.to_string(), url_regex })
+    }
+
+    /// Construit un filtre à partir d'une expression régulière
+    pub fn from_regex(pattern: &str) -> WebSocketResult<Self> {
+        let url_regex = Regex::new(pattern)
+            .map_err(|e| WebSocketError::SubscriptionFailed(format!("Invalid URL regex: {}", e)))?;
+
+        Ok(Self { pattern: pattern.to_string(), url_regex })
+    }
+
+    /// Extrait le filtre des paramètres d'un message `subscribe`
+    ///
+    /// Retourne `None` si aucun filtre d'URL n'est demandé.
+    pub fn from_filters(filters: &HashMap<String, serde_json::Value>) -> WebSocketResult<Option<Self>> {
+        let as_str = |key: &str| -> WebSocketResult<Option<&str>> {
+            match filters.get(key) {
+                None => Ok(None),
+                Some(serde_json::Value::String(s)) => Ok(Some(s.as_str())),
+                Some(_) => Err(WebSocketError::SubscriptionFailed(
+                    format!("Filter '{}' must be a string", key)
+                )),
+            }
+        };
+
+        match (as_str(Self::URL_GLOB)?, as_str(Self::URL_REGEX)?) {
+            (Some(_), Some(_)) => Err(WebSocketError::SubscriptionFailed(
+                "Only one of url_glob or url_regex can be set".to_string()
+            )),
+            (Some(glob), None) => Self::from_glob(glob).map(Some),
+            (None, Some(pattern)) => Self::from_regex(pattern).map(Some),
+            (None, None) => Ok(None),
+        }
+    }
+
+    /// Indique si une URL correspond au filtre
+    pub fn matches_url(&self, url: &str) -> bool {
+        self.url_regex.is_match(url)
+    }
+
+    /// Indique si un message doit être poussé à l'abonné
+    ///
+    /// Les événements d'archive sans URL connue ne passent pas le filtre ;
+    /// les autres messages ne sont pas concernés.
+    pub fn matches(&self, message: &WsMessage) -> bool {
+        match message {
+            WsMessage::ArchiveUpdate { .. } | WsMessage::NewArchive { .. } => {
+                archive_url(message).map_or(false, |url| self.matches_url(url))
+            }
+            _ => true,
+        }
+    }
+}
+
+/// Extrait l'URL d'archive portée par un message, si présente
+pub fn archive_url(message: &WsMessage) -> Option<&str> {
+    match message {
+        WsMessage::NewArchive { archive, .. } => Some(archive.url.as_str()),
+        WsMessage::ArchiveUpdate { data, .. } => data
+            .as_ref()
+            .and_then(|d| d.get("url"))
+            .and_then(|v| v.as_str()),
+        _ => None,
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -598,4 +819,40 @@ mod tests {
             _ => panic!("Serialization/deserialization failed"),
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_subscription_filter_glob() {
+        let filter = SubscriptionFilter::from_glob("https://*.example.com/*").unwrap();
+        assert!(filter.matches_url("https://news.example.com/article/1"));
+        assert!(!filter.matches_url("https://example.org/article/1"));
+    }
+
+    #[test]
+    fn test_subscription_filter_from_filters() {
+        let mut filters = HashMap::new();
+        filters.insert("url_regex".to_string(), serde_json::json!("^https://docs\\."));
+        let filter = SubscriptionFilter::from_filters(&filters).unwrap().unwrap();
+
+        let mut data = HashMap::new();
+        data.insert("url".to_string(), serde_json::json!("https://docs.rs/regex"));
+        let matching = MessageBuilder::archive_update("a1".to_string(), "Completed".to_string(), None, Some(data));
+        let without_url = MessageBuilder::archive_update("a2".to_string(), "Completed".to_string(), None, None);
+
+        assert!(filter.matches(&matching));
+        assert!(!filter.matches(&without_url));
+        assert!(filter.matches(&MessageBuilder::ping()));
+
+        assert!(SubscriptionFilter::from_filters(&HashMap::new()).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_subscription_filter_invalid_regex() {
+        let mut filters = HashMap::new();
+        filters.insert("url_regex".to_string(), serde_json::json!("(unclosed"));
+
+        match SubscriptionFilter::from_filters(&filters) {
+            Err(WebSocketError::SubscriptionFailed(_)) => {}
+            other => panic!("Expected SubscriptionFailed, got {:?}", other.map(|f| f.is_some())),
+        }
+    }
+}
@@ -44,10 +44,24 @@ pub struct WebSocketConfig {
     pub max_message_size: usize,
     /// Buffer size pour les messages sortants
     pub send_buffer_size: usize,
+    /// Durée pendant laquelle la file sortante peut rester pleine avant déconnexion (en secondes)
+    #[serde(default = "default_slow_consumer_grace_period")]
+    pub slow_consumer_grace_period: u64,
+    /// Nombre d'événements conservés par topic pour la reprise après reconnexion
+    #[serde(default = "default_replay_buffer_size")]
+    pub replay_buffer_size: usize,
     /// Active la compression des messages
     pub enable_compression: bool,
 }
 
+fn default_slow_consumer_grace_period() -> u64 {
+    10
+}
+
+fn default_replay_buffer_size() -> usize {
+    256
+}
+
 impl Default for WebSocketConfig {
     fn default() -> Self {
         Self {
@@ -57,6 +71,8 @@ impl Default for WebSocketConfig {
             ping_interval: 30,
             max_message_size: 1024 * 1024, // 1MB
             send_buffer_size: 1000,
+            slow_consumer_grace_period: default_slow_consumer_grace_period(),
+            replay_buffer_size: default_replay_buffer_size(),
             enable_compression: true,
         }
     }
@@ -73,7 +89,9 @@ pub struct WebSocketState {
 impl WebSocketState {
     pub fn new(config: WebSocketConfig, server_state: ServerState) -> Self {
         Self {
-            connection_manager: Arc::new(RwLock::new(ConnectionManager::new(config.clone()))),
+            connection_manager: Arc::new(RwLock::new(
+                ConnectionManager::new(config.clone()).with_event_bus(server_state.events.clone())
+            )),
             config,
             server_state,
         }
@@ -100,9 +118,15 @@ async fn websocket_handler(
 ) -> Response {
     let config = server_state.config.websocket.clone();
     let ws_state = WebSocketState::new(config, server_state);
-    
+    // La connexion vit hors de la tâche de la requête d'upgrade : son
+    // identifiant de corrélation est capturé avant le changement de protocole
+    let request_id = crate::api::middleware::current_request_id();
+
     ws.on_upgrade(move |socket| async move {
-        let handler = WebSocketHandler::new(socket, ws_state);
+        let mut handler = WebSocketHandler::new(socket, ws_state);
+        if let Some(request_id) = request_id {
+            handler = handler.with_request_id(request_id);
+        }
         handler.handle_connection().await;
     })
 }
@@ -143,6 +167,10 @@ pub struct ConnectionStats {
     pub subscriptions_by_topic: HashMap<String, usize>,
     pub total_messages_sent: u64,
     pub total_messages_received: u64,
+    /// Événements non critiques abandonnés pour cause de file sortante pleine
+    pub total_messages_dropped: u64,
+    /// Connexions fermées car le client ne suivait pas le débit
+    pub slow_consumer_disconnects: u64,
     pub uptime_seconds: u64,
 }
 
@@ -271,6 +299,8 @@ mod tests {
             subscriptions_by_topic: HashMap::new(),
             total_messages_sent: 1000,
             total_messages_received: 950,
+            total_messages_dropped: 0,
+            slow_consumer_disconnects: 0,
             uptime_seconds: 3600,
         };
         
@@ -7,6 +7,7 @@ use chrono::{DateTime, Utc};
 use std::collections::HashMap;
 use crate::crypto::{Hash, HashAlgorithm, compute_hash};
 use crate::error::{BlockError, Result};
+use crate::serialization::serialize_ordered_map;
 
 /// Types de compression supportés
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -72,6 +73,7 @@ pub struct ArchiveMetadata {
     pub published_at: Option<DateTime<Utc>>,
     
     /// Métadonnées personnalisées
+    #[serde(serialize_with = "serialize_ordered_map")]
     pub custom_metadata: HashMap<String, String>,
     
     /// Nombre de liens externes
@@ -154,6 +156,15 @@ pub struct ArchiveBlock {
     
     /// Hash de verification pour l'intégrité
     pub verification_hash: Hash,
+
+    /// Capture précédente de la même URL
+    #[serde(default)]
+    pub previous_capture: Option<Hash>,
+
+    /// Capture d'origine dont le contenu est identique : l'archive n'est qu'un
+    /// pointeur de revisite et ne stocke pas les données une seconde fois
+    #[serde(default)]
+    pub revisit_of: Option<Hash>,
 }
 
 impl ArchiveBlock {
@@ -181,6 +192,8 @@ impl ArchiveBlock {
             checksum,
             metadata,
             verification_hash: Hash::zero(),
+            previous_capture: None,
+            revisit_of: None,
         };
         
         // Calcule le hash de vérification
@@ -206,10 +219,33 @@ impl ArchiveBlock {
         data.extend_from_slice(&self.size_compressed.to_le_bytes());
         data.extend_from_slice(&self.size_original.to_le_bytes());
         data.extend_from_slice(self.checksum.as_bytes());
+        // Les liens de version ne modifient pas le hash des captures isolées
+        if let Some(previous) = &self.previous_capture {
+            data.extend_from_slice(previous.as_bytes());
+        }
+        if let Some(original) = &self.revisit_of {
+            data.extend_from_slice(original.as_bytes());
+        }
         
         compute_hash(&data, HashAlgorithm::Blake3)
     }
 
+    /// Rattache l'archive à la capture précédente de la même URL
+    ///
+    /// Si le contenu est identique, l'archive devient un pointeur de revisite
+    /// vers la capture d'origine plutôt qu'une copie complète.
+    pub fn link_to_previous(&mut self, previous: &ArchiveBlock) {
+        self.previous_capture = Some(previous.archive_id.clone());
+        self.revisit_of = (previous.checksum == self.checksum)
+            .then(|| previous.revisit_of.clone().unwrap_or_else(|| previous.archive_id.clone()));
+        self.verification_hash = self.calculate_verification_hash();
+    }
+
+    /// Vérifie si l'archive est une revisite d'un contenu déjà capturé
+    pub fn is_revisit(&self) -> bool {
+        self.revisit_of.is_some()
+    }
+
     /// Vérifie l'intégrité de l'archive
     pub fn verify_integrity(&self) -> bool {
         let calculated_hash = self.calculate_verification_hash();
@@ -445,6 +481,35 @@ mod tests {
         assert_eq!(archive.metadata.title, Some("Test Page".to_string()));
     }
 
+    #[test]
+    fn test_identical_recapture_is_revisit() {
+        let first = create_test_archive();
+
+        let mut revisit = create_test_archive();
+        revisit.link_to_previous(&first);
+        assert_eq!(revisit.previous_capture, Some(first.archive_id.clone()));
+        assert_eq!(revisit.revisit_of, Some(first.archive_id.clone()));
+        assert!(revisit.verify_integrity());
+
+        // Une revisite de revisite pointe toujours vers l'original
+        let mut again = create_test_archive();
+        again.link_to_previous(&revisit);
+        assert_eq!(again.revisit_of, Some(first.archive_id.clone()));
+
+        let mut changed = ArchiveBlockBuilder::new(
+            "https://example.com/test".to_string(),
+            "text/html".to_string(),
+            CompressionType::Gzip,
+            1024,
+            4096,
+            compute_hash(b"v2", HashAlgorithm::Blake3),
+        )
+        .build();
+        changed.link_to_previous(&first);
+        assert!(!changed.is_revisit());
+        assert_eq!(changed.previous_capture, Some(first.archive_id));
+    }
+
     #[test]
     fn test_verification_hash_consistency() {
         let archive = create_test_archive();
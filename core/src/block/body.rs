@@ -8,24 +8,30 @@ use crate::crypto::{Hash, HashAlgorithm, compute_hash, compute_combined_hash};
 use crate::state::{MerkleTree, MerkleProof};
 use crate::transaction::Transaction;
 use crate::error::{BlockError, Result};
+use crate::serialization::serialize_ordered_map;
 use super::archive_metadata::ArchiveBlock;
 
 /// Index de contenu pour la recherche rapide
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ContentIndex {
     /// Index par mots-clés
+    #[serde(serialize_with = "serialize_ordered_map")]
     pub keyword_index: HashMap<String, Vec<Hash>>,
     
     /// Index par type de contenu
+    #[serde(serialize_with = "serialize_ordered_map")]
     pub content_type_index: HashMap<String, Vec<Hash>>,
     
     /// Index par domaine
+    #[serde(serialize_with = "serialize_ordered_map")]
     pub domain_index: HashMap<String, Vec<Hash>>,
     
     /// Index par langue
+    #[serde(serialize_with = "serialize_ordered_map")]
     pub language_index: HashMap<String, Vec<Hash>>,
     
     /// Index temporel (par année-mois)
+    #[serde(serialize_with = "serialize_ordered_map")]
     pub temporal_index: HashMap<String, Vec<Hash>>,
     
     /// Statistiques d'indexation
@@ -184,6 +190,7 @@ pub struct StorageProof {
     pub proof_root: Hash,
     
     /// Preuves individuelles pour chaque archive
+    #[serde(serialize_with = "serialize_ordered_map")]
     pub archive_proofs: HashMap<Hash, ArchiveStorageProof>,
     
     /// Timestamp de génération des preuves
@@ -301,7 +308,7 @@ impl StorageProof {
     /// Vérifie une preuve d'archive individuelle
     pub fn verify_archive_proof(&self, archive_hash: &Hash, proof: &ArchiveStorageProof) -> Result<bool> {
         // Vérifie la preuve de Merkle
-        if !proof.merkle_proof.verify(self.algorithm) {
+        if !proof.merkle_proof.verify_path(self.algorithm) {
             return Ok(false);
         }
 
@@ -401,24 +408,22 @@ impl BlockBody {
 
     /// Calcule la racine de Merkle du corps
     pub fn calculate_merkle_root(&self, algorithm: HashAlgorithm) -> Hash {
-        let mut hashes = Vec::new();
-        
-        // Ajoute les hashs des transactions
-        for tx in &self.transactions {
-            hashes.push(tx.hash().clone());
-        }
-        
-        // Ajoute les hashs des archives
-        for archive in &self.archives {
-            hashes.push(archive.archive_id.clone());
-        }
-        
-        if hashes.is_empty() {
-            return Hash::zero();
-        }
-        
-        let merkle_tree = MerkleTree::from_hashes(hashes, algorithm);
-        merkle_tree.root_hash().cloned().unwrap_or_else(Hash::zero)
+        self.merkle_tree(algorithm).root_hash().cloned().unwrap_or_else(Hash::zero)
+    }
+
+    /// Arbre de Merkle du corps : hashs des transactions puis identifiants des archives
+    pub fn merkle_tree(&self, algorithm: HashAlgorithm) -> MerkleTree {
+        let hashes = self.transactions.iter()
+            .map(|tx| tx.hash().clone())
+            .chain(self.archives.iter().map(|archive| archive.archive_id.clone()))
+            .collect();
+        MerkleTree::from_hashes(hashes, algorithm)
+    }
+
+    /// Vérifie si une transaction ou une archive du corps a ce hash
+    pub fn contains_leaf(&self, leaf_hash: &Hash) -> bool {
+        self.transactions.iter().any(|tx| tx.hash() == leaf_hash)
+            || self.archives.iter().any(|archive| archive.archive_id == *leaf_hash)
     }
 
     /// Vérifie l'intégrité du corps
@@ -2,8 +2,8 @@
 
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
-use crate::crypto::{Hash, HashAlgorithm, compute_hash};
-use crate::error::{BlockError, Result};
+use crate::crypto::{Hash, HashAlgorithm, PublicKey, Signature, Signer, compute_hash, verify_signature};
+use crate::error::{BlockError, CoreError, Result};
 
 /// En-tête d'un bloc ArchiveChain
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -40,6 +40,22 @@ pub struct BlockHeader {
     
     /// Nombre d'archives dans le bloc
     pub archive_count: u32,
+
+    /// Racine de l'état sur lequel le bloc s'applique, engagée aux hauteurs de snapshot
+    #[serde(default)]
+    pub state_root: Option<Hash>,
+
+    /// Hash du manifeste du snapshot de cet état (voir `state::snapshot`)
+    #[serde(default)]
+    pub snapshot_manifest: Option<Hash>,
+
+    /// Producteur du bloc, engagé dans le hash
+    #[serde(default)]
+    pub producer: Option<PublicKey>,
+
+    /// Signature de `block_hash` par le producteur
+    #[serde(default)]
+    pub producer_signature: Option<Signature>,
 }
 
 impl BlockHeader {
@@ -64,6 +80,10 @@ impl BlockHeader {
             size: 0,    // Sera calculé après
             transaction_count: 0,
             archive_count: 0,
+            state_root: None,
+            snapshot_manifest: None,
+            producer: None,
+            producer_signature: None,
         }
     }
 
@@ -87,10 +107,47 @@ impl BlockHeader {
         data.extend_from_slice(&self.difficulty.to_le_bytes());
         data.extend_from_slice(&self.nonce.to_le_bytes());
         data.extend_from_slice(&self.version.to_le_bytes());
+        // Les champs optionnels absents ne changent pas le hash des en-têtes
+        // existants ; présents, ils sont précédés d'un tag qui les distingue
+        if let Some(state_root) = &self.state_root {
+            data.push(1);
+            data.extend_from_slice(state_root.as_bytes());
+        }
+        if let Some(manifest) = &self.snapshot_manifest {
+            data.push(2);
+            data.extend_from_slice(manifest.as_bytes());
+        }
+        if let Some(producer) = &self.producer {
+            data.push(3);
+            data.extend_from_slice(producer.as_bytes());
+        }
         
         data
     }
 
+    /// Signe l'en-tête au nom de son producteur
+    ///
+    /// Le producteur étant engagé dans le hash, `block_hash` est recalculé
+    /// avant d'être signé.
+    pub fn sign_as_producer(&mut self, signer: &dyn Signer, algorithm: HashAlgorithm) -> Result<()> {
+        self.producer = Some(signer.public_key());
+        self.block_hash = self.calculate_hash(algorithm);
+        self.producer_signature = Some(signer.sign(self.block_hash.as_bytes())?);
+        Ok(())
+    }
+
+    /// Producteur dont la signature de `block_hash` est valide
+    ///
+    /// `None` pour un bloc non signé ou dont la signature ne correspond pas.
+    pub fn verified_producer(&self) -> Option<&PublicKey> {
+        let producer = self.producer.as_ref()?;
+        let signature = self.producer_signature.as_ref()?;
+        verify_signature(self.block_hash.as_bytes(), signature, producer)
+            .ok()
+            .filter(|valid| *valid)
+            .map(|_| producer)
+    }
+
     /// Vérifie que l'en-tête est valide
     pub fn is_valid(&self, algorithm: HashAlgorithm) -> Result<bool> {
         // Vérifie que le hash calculé correspond au hash stocké
@@ -115,6 +172,11 @@ impl BlockHeader {
             return Ok(false);
         }
 
+        // Un producteur annoncé doit avoir signé le bloc
+        if (self.producer.is_some() || self.producer_signature.is_some()) && self.verified_producer().is_none() {
+            return Ok(false);
+        }
+
         Ok(true)
     }
 
@@ -170,6 +232,44 @@ impl BlockHeader {
     }
 }
 
+/// Vérifie une suite d'en-têtes consécutifs reçue par un client léger
+///
+/// Utilise l'algorithme de hachage par défaut de la chaîne (Blake3).
+pub fn verify_header_chain(headers: &[BlockHeader]) -> Result<()> {
+    verify_header_chain_with(headers, HashAlgorithm::Blake3)
+}
+
+/// Vérifie une suite d'en-têtes consécutifs avec l'algorithme donné
+///
+/// Chaque en-tête doit porter son propre hash, ne pas être daté du futur, et
+/// référencer le hash de l'en-tête précédent à la hauteur suivante. Le premier
+/// en-tête sert d'ancre : le client le confronte à un point de confiance.
+pub fn verify_header_chain_with(headers: &[BlockHeader], algorithm: HashAlgorithm) -> Result<()> {
+    let now = Utc::now();
+    let invalid = |height: u64, reason: &str| CoreError::Validation {
+        message: format!("En-tête {} invalide: {}", height, reason),
+    };
+
+    for (index, header) in headers.iter().enumerate() {
+        if header.calculate_hash(algorithm) != header.block_hash {
+            return Err(invalid(header.height, "hash incorrect"));
+        }
+        if header.timestamp > now {
+            return Err(invalid(header.height, "timestamp dans le futur"));
+        }
+
+        let Some(previous) = index.checked_sub(1).map(|i| &headers[i]) else { continue };
+        if header.height != previous.height + 1 {
+            return Err(invalid(header.height, "hauteur non consécutive"));
+        }
+        if header.previous_hash != previous.block_hash {
+            return Err(invalid(header.height, "lien vers l'en-tête précédent rompu"));
+        }
+    }
+
+    Ok(())
+}
+
 /// Builder pour créer des en-têtes de bloc
 #[derive(Debug)]
 pub struct BlockHeaderBuilder {
@@ -180,6 +280,8 @@ pub struct BlockHeaderBuilder {
     difficulty: Option<u64>,
     nonce: Option<u64>,
     version: Option<u32>,
+    state_root: Option<Hash>,
+    snapshot_manifest: Option<Hash>,
 }
 
 impl BlockHeaderBuilder {
@@ -193,6 +295,8 @@ impl BlockHeaderBuilder {
             difficulty: None,
             nonce: None,
             version: None,
+            state_root: None,
+            snapshot_manifest: None,
         }
     }
 
@@ -226,6 +330,18 @@ impl BlockHeaderBuilder {
         self
     }
 
+    /// Engage la racine de l'état sur lequel le bloc s'applique
+    pub fn state_root(mut self, state_root: Hash) -> Self {
+        self.state_root = Some(state_root);
+        self
+    }
+
+    /// Engage le manifeste du snapshot de cet état
+    pub fn snapshot_manifest(mut self, manifest_hash: Hash) -> Self {
+        self.snapshot_manifest = Some(manifest_hash);
+        self
+    }
+
     /// Construit l'en-tête
     pub fn build(self) -> Result<BlockHeader> {
         let merkle_root = self.merkle_root.unwrap_or_else(Hash::zero);
@@ -245,6 +361,8 @@ impl BlockHeaderBuilder {
         if let Some(version) = self.version {
             header.version = version;
         }
+        header.state_root = self.state_root;
+        header.snapshot_manifest = self.snapshot_manifest;
 
         Ok(header)
     }
@@ -308,6 +426,54 @@ mod tests {
         assert!(genesis.is_genesis());
     }
 
+    fn header_chain(length: u64) -> Vec<BlockHeader> {
+        let start = Utc::now() - chrono::Duration::seconds(length as i64 + 1);
+        let mut previous_hash = Hash::zero();
+        (0..length).map(|height| {
+            let mut header = BlockHeaderBuilder::new(height, previous_hash.clone())
+                .timestamp(start + chrono::Duration::seconds(height as i64))
+                .nonce(height)
+                .build()
+                .unwrap();
+            header.block_hash = header.calculate_hash(HashAlgorithm::Blake3);
+            previous_hash = header.block_hash.clone();
+            header
+        }).collect()
+    }
+
+    #[test]
+    fn test_verify_header_chain() {
+        let headers = header_chain(500);
+        assert!(verify_header_chain(&headers).is_ok());
+        assert!(verify_header_chain(&[]).is_ok());
+
+        // Lien rompu au milieu : l'en-tête est valide isolément mais pointe ailleurs
+        let mut broken = headers.clone();
+        broken[250].previous_hash = Hash::zero();
+        broken[250].block_hash = broken[250].calculate_hash(HashAlgorithm::Blake3);
+        let error = verify_header_chain(&broken).unwrap_err().to_string();
+        assert!(error.contains("250"), "{}", error);
+
+        // En-tête altéré sans recalcul du hash
+        let mut tampered = headers.clone();
+        tampered[100].nonce += 1;
+        assert!(verify_header_chain(&tampered).is_err());
+
+        // Hauteurs non consécutives
+        assert!(verify_header_chain(&[headers[0].clone(), headers[2].clone()]).is_err());
+    }
+
+    #[test]
+    fn test_verify_header_chain_rejects_future_timestamp() {
+        let mut headers = header_chain(10);
+        let last = headers.last_mut().unwrap();
+        last.timestamp = Utc::now() + chrono::Duration::hours(1);
+        last.block_hash = last.calculate_hash(HashAlgorithm::Blake3);
+
+        let error = verify_header_chain(&headers).unwrap_err().to_string();
+        assert!(error.contains("futur"), "{}", error);
+    }
+
     #[test]
     fn test_header_builder() {
         let header = BlockHeaderBuilder::new(5, Hash::zero())
@@ -323,6 +489,57 @@ mod tests {
         assert_eq!(header.version, 2);
     }
 
+    #[test]
+    fn test_snapshot_manifest_is_committed_in_hash() {
+        let timestamp = Utc::now();
+        let plain = BlockHeaderBuilder::new(5, Hash::zero()).timestamp(timestamp).build().unwrap();
+        let committed = BlockHeaderBuilder::new(5, Hash::zero())
+            .timestamp(timestamp)
+            .state_root(Hash::new([9u8; 32]))
+            .snapshot_manifest(Hash::new([7u8; 32]))
+            .build()
+            .unwrap();
+
+        assert_eq!(plain.calculate_hash(HashAlgorithm::Blake3), BlockHeader {
+            state_root: None,
+            snapshot_manifest: None,
+            ..committed.clone()
+        }.calculate_hash(HashAlgorithm::Blake3));
+        assert_ne!(plain.calculate_hash(HashAlgorithm::Blake3), committed.calculate_hash(HashAlgorithm::Blake3));
+
+        // Une même valeur engagée dans un autre champ donne un autre hash
+        let swapped = BlockHeader {
+            state_root: None,
+            snapshot_manifest: Some(Hash::new([9u8; 32])),
+            ..plain.clone()
+        };
+        let as_root = BlockHeader { state_root: Some(Hash::new([9u8; 32])), ..plain.clone() };
+        assert_ne!(swapped.calculate_hash(HashAlgorithm::Blake3), as_root.calculate_hash(HashAlgorithm::Blake3));
+    }
+
+    #[test]
+    fn test_producer_signature_covers_hash() {
+        let keypair = crate::crypto::generate_keypair().unwrap();
+        let other = crate::crypto::generate_keypair().unwrap();
+        let mut header = create_test_header();
+        header.block_hash = header.calculate_hash(HashAlgorithm::Blake3);
+        let unsigned_hash = header.block_hash.clone();
+        assert!(header.verified_producer().is_none());
+
+        header.sign_as_producer(&keypair, HashAlgorithm::Blake3).unwrap();
+        assert_ne!(header.block_hash, unsigned_hash);
+        assert_eq!(header.verified_producer(), Some(keypair.public_key()));
+        assert!(header.is_valid(HashAlgorithm::Blake3).unwrap());
+
+        // S'attribuer le bloc d'un autre invalide le hash ou la signature
+        let mut usurped = header.clone();
+        usurped.producer = Some(other.public_key().clone());
+        assert!(usurped.verified_producer().is_none());
+        assert!(!usurped.is_valid(HashAlgorithm::Blake3).unwrap());
+        usurped.block_hash = usurped.calculate_hash(HashAlgorithm::Blake3);
+        assert!(!usurped.is_valid(HashAlgorithm::Blake3).unwrap());
+    }
+
     #[test]
     fn test_difficulty_calculation() {
         // Crée un hash avec des zéros en tête pour tester
@@ -6,16 +6,21 @@
 pub mod header;
 pub mod body;
 pub mod archive_metadata;
+pub mod versioning;
 
-pub use header::BlockHeader;
+pub use header::{BlockHeader, BlockHeaderBuilder, verify_header_chain, verify_header_chain_with};
 pub use body::{BlockBody, ContentIndex, StorageProof};
 pub use archive_metadata::{ArchiveMetadata, CompressionType, ArchiveBlock};
+pub use versioning::{normalize_url, ArchiveHistory, ArchiveVersion, VersionDiff};
 
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
-use crate::crypto::{Hash, HashAlgorithm, compute_combined_hash};
+use std::sync::Arc;
+use crate::crypto::{Hash, HashAlgorithm, Signer, compute_combined_hash};
 use crate::error::{BlockError, Result};
 use crate::transaction::Transaction;
+use crate::state::MerkleProof;
+use crate::serialization::CanonicalEncoding;
 
 /// Structure principale d'un bloc ArchiveChain
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -128,9 +133,17 @@ impl Block {
         Ok(true)
     }
 
-    /// Calcule la taille du bloc en bytes
+    /// Génère la preuve d'inclusion d'une transaction ou d'une archive
+    ///
+    /// La preuve se vérifie contre la seule racine de Merkle de l'en-tête,
+    /// avec `MerkleProof::verify`.
+    pub fn generate_inclusion_proof(&self, leaf_hash: &Hash, algorithm: HashAlgorithm) -> Result<MerkleProof> {
+        self.body.merkle_tree(algorithm).generate_proof(leaf_hash)
+    }
+
+    /// Calcule la taille du bloc en bytes (encodage canonique)
     pub fn size_bytes(&self) -> usize {
-        bincode::serialized_size(self).unwrap_or(0) as usize
+        self.canonical_size()
     }
 
     /// Obtient toutes les transactions du bloc
@@ -155,6 +168,9 @@ pub struct BlockBuilder {
     transactions: Vec<Transaction>,
     archives: Vec<ArchiveBlock>,
     algorithm: HashAlgorithm,
+    producer: Option<Arc<dyn Signer>>,
+    /// Racine d'état et manifeste du snapshot engagés dans l'en-tête
+    snapshot: Option<(Hash, Hash)>,
 }
 
 impl BlockBuilder {
@@ -169,6 +185,8 @@ impl BlockBuilder {
             transactions: Vec::new(),
             archives: Vec::new(),
             algorithm,
+            producer: None,
+            snapshot: None,
         }
     }
 
@@ -214,6 +232,18 @@ impl BlockBuilder {
         self
     }
 
+    /// Signe le bloc au nom de son producteur
+    pub fn signed_by(mut self, producer: Arc<dyn Signer>) -> Self {
+        self.producer = Some(producer);
+        self
+    }
+
+    /// Engage l'état sur lequel le bloc s'applique et le manifeste de son snapshot
+    pub fn state_snapshot(mut self, state_root: Hash, manifest_hash: Hash) -> Self {
+        self.snapshot = Some((state_root, manifest_hash));
+        self
+    }
+
     /// Construit le bloc final
     pub fn build(self) -> Result<Block> {
         let timestamp = self.timestamp.unwrap_or_else(Utc::now);
@@ -230,18 +260,22 @@ impl BlockBuilder {
         let merkle_root = body.calculate_merkle_root(self.algorithm);
 
         // Crée l'en-tête
-        let mut header = BlockHeader::new(
-            self.height,
-            self.previous_hash,
-            merkle_root,
-            timestamp,
-            self.difficulty,
-            self.nonce,
-        );
+        let mut header_builder = BlockHeaderBuilder::new(self.height, self.previous_hash)
+            .merkle_root(merkle_root)
+            .timestamp(timestamp)
+            .difficulty(self.difficulty)
+            .nonce(self.nonce);
+        if let Some((state_root, manifest_hash)) = self.snapshot {
+            header_builder = header_builder.state_root(state_root).snapshot_manifest(manifest_hash);
+        }
+        let mut header = header_builder.build()?;
 
         // Calcule le hash du bloc
         let block_hash = header.calculate_hash(self.algorithm);
         header.block_hash = block_hash;
+        if let Some(producer) = &self.producer {
+            header.sign_as_producer(producer.as_ref(), self.algorithm)?;
+        }
 
         Ok(Block::new(header, body))
     }
@@ -251,6 +285,7 @@ impl BlockBuilder {
 mod tests {
     use super::*;
     use crate::crypto::{Hash, HashAlgorithm};
+    use crate::transaction::types::{TransactionBuilder, TransactionType};
 
     fn create_test_block() -> Block {
         BlockBuilder::new(1, Hash::zero(), HashAlgorithm::Blake3)
@@ -293,6 +328,45 @@ mod tests {
         assert_eq!(block.header.nonce, 54321);
     }
 
+    fn block_with_transactions(count: u64) -> Block {
+        let transactions = (0..count)
+            .map(|i| TransactionBuilder::new(TransactionType::Archive).nonce(i).fee(1).build())
+            .collect();
+        BlockBuilder::new(1, Hash::zero(), HashAlgorithm::Blake3)
+            .add_transactions(transactions)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_inclusion_proofs() {
+        for count in [1u64, 2, 7, 1000] {
+            let block = block_with_transactions(count);
+            let root = &block.header.merkle_root;
+            let last = block.body.transactions.len() - 1;
+
+            for index in [0, last / 2, last] {
+                let leaf = block.body.transactions[index].hash();
+                let proof = block.generate_inclusion_proof(leaf, HashAlgorithm::Blake3).unwrap();
+                assert!(proof.verify(root, leaf), "{} transactions, feuille {}", count, index);
+                assert!(!proof.verify(&Hash::zero(), leaf));
+            }
+        }
+
+        let block = block_with_transactions(2);
+        assert!(block.generate_inclusion_proof(&Hash::zero(), HashAlgorithm::Blake3).is_err());
+    }
+
+    #[test]
+    fn test_inclusion_proof_rejects_tampered_sibling() {
+        let block = block_with_transactions(7);
+        let leaf = block.body.transactions[3].hash();
+        let mut proof = block.generate_inclusion_proof(leaf, HashAlgorithm::Blake3).unwrap();
+
+        proof.path[0].0 = crate::crypto::compute_blake3(b"tampered");
+        assert!(!proof.verify(&block.header.merkle_root, leaf));
+    }
+
     #[test]
     fn test_block_hash_consistency() {
         let block = create_test_block();
@@ -0,0 +1,280 @@
+//! Versions successives des archives d'une même URL
+//!
+//! Chaque URL archivée possède une chronologie de captures, ordonnée par date.
+//! Une capture dont le contenu est identique à la précédente est enregistrée
+//! comme une revisite pointant vers la capture d'origine ; sinon, elle porte
+//! un résumé des différences avec la précédente (taille, titre).
+//!
+//! Les chronologies sont indexées par URL normalisée (voir [`normalize_url`]) :
+//! `http://Example.com/` et `http://example.com` partagent la même lignée.
+//!
+//! L'historique est générique sur l'identifiant des captures ; l'API l'utilise
+//! avec ses identifiants `arc_`. Sur la chaîne, chaque `ArchiveBlock` porte
+//! lui-même le lien vers sa capture précédente (voir
+//! [`ArchiveBlock::link_to_previous`](super::ArchiveBlock::link_to_previous)).
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::crypto::Hash;
+
+/// Une capture dans la chronologie d'une URL
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArchiveVersion<Id = Hash> {
+    /// Archive contenant cette capture
+    pub archive_id: Id,
+    /// URL capturée
+    pub url: String,
+    /// Date de la capture
+    pub captured_at: DateTime<Utc>,
+    /// Empreinte du contenu capturé, si connue
+    pub content_hash: Option<Hash>,
+    /// Capture précédente de la même URL
+    pub previous: Option<Id>,
+    /// Capture d'origine lorsque le contenu est inchangé
+    pub revisit_of: Option<Id>,
+    /// Taille du contenu capturé, si connue
+    #[serde(default)]
+    pub size: Option<u64>,
+    /// Titre de la page capturée, si connu
+    #[serde(default)]
+    pub title: Option<String>,
+    /// Différences avec la capture précédente
+    #[serde(default)]
+    pub diff: Option<VersionDiff>,
+}
+
+/// Résumé des différences entre une capture et la précédente
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct VersionDiff {
+    /// Empreinte du contenu de la capture précédente
+    pub previous_content_hash: Option<Hash>,
+    /// Variation de taille en octets, si les deux tailles sont connues
+    pub size_delta: Option<i64>,
+    /// Titre de la capture précédente
+    pub previous_title: Option<String>,
+    /// Vrai si le titre diffère de celui de la capture précédente
+    pub title_changed: bool,
+}
+
+impl VersionDiff {
+    fn between<Id>(previous: &ArchiveVersion<Id>, current: &ArchiveVersion<Id>) -> Self {
+        Self {
+            previous_content_hash: previous.content_hash,
+            size_delta: previous.size.zip(current.size).map(|(before, after)| after as i64 - before as i64),
+            previous_title: previous.title.clone(),
+            title_changed: previous.title != current.title,
+        }
+    }
+}
+
+/// Clé d'une URL dans les chronologies
+///
+/// Le fragment est retiré, le schéma et l'hôte passent en minuscules et la
+/// barre oblique finale du chemin est supprimée (le chemin racine reste `/`).
+/// Une URL illisible est seulement débarrassée de son fragment.
+pub fn normalize_url(url: &str) -> String {
+    let url = url.trim();
+    let Ok(mut parsed) = url::Url::parse(url) else {
+        return url.split('#').next().unwrap_or_default().to_string();
+    };
+    parsed.set_fragment(None);
+
+    let path = parsed.path();
+    if path.len() > 1 && path.ends_with('/') {
+        let trimmed = path.trim_end_matches('/').to_string();
+        parsed.set_path(if trimmed.is_empty() { "/" } else { &trimmed });
+    }
+    parsed.to_string()
+}
+
+impl<Id> ArchiveVersion<Id> {
+    /// Vérifie si la capture est une revisite
+    pub fn is_revisit(&self) -> bool {
+        self.revisit_of.is_some()
+    }
+}
+
+/// Chronologie des captures, indexée par URL
+#[derive(Debug, Clone)]
+pub struct ArchiveHistory<Id = Hash> {
+    captures: HashMap<String, Vec<ArchiveVersion<Id>>>,
+}
+
+impl<Id> Default for ArchiveHistory<Id> {
+    fn default() -> Self {
+        Self {
+            captures: HashMap::new(),
+        }
+    }
+}
+
+impl<Id: Clone> ArchiveHistory<Id> {
+    /// Crée un historique vide
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enregistre une capture et la rattache à la précédente
+    ///
+    /// Une capture antérieure à la dernière connue est insérée à sa place dans
+    /// la chronologie ; la capture suivante est alors rattachée à elle.
+    pub fn record(
+        &mut self,
+        url: &str,
+        archive_id: Id,
+        captured_at: DateTime<Utc>,
+        content_hash: Option<Hash>,
+    ) -> ArchiveVersion<Id> {
+        self.record_with_summary(url, archive_id, captured_at, content_hash, None, None)
+    }
+
+    /// Enregistre une capture avec sa taille et son titre
+    ///
+    /// Comme [`ArchiveHistory::record`] ; la taille et le titre alimentent le
+    /// résumé des différences de cette capture et de la suivante.
+    pub fn record_with_summary(
+        &mut self,
+        url: &str,
+        archive_id: Id,
+        captured_at: DateTime<Utc>,
+        content_hash: Option<Hash>,
+        size: Option<u64>,
+        title: Option<String>,
+    ) -> ArchiveVersion<Id> {
+        let versions = self.captures.entry(normalize_url(url)).or_default();
+        let position = versions.partition_point(|v| v.captured_at <= captured_at);
+        let previous = position.checked_sub(1).map(|i| &versions[i]);
+
+        let revisit_of = previous
+            .filter(|prev| content_hash.is_some() && prev.content_hash == content_hash)
+            .map(|prev| prev.revisit_of.clone().unwrap_or_else(|| prev.archive_id.clone()));
+        let mut version = ArchiveVersion {
+            archive_id: archive_id.clone(),
+            url: url.to_string(),
+            captured_at,
+            content_hash,
+            previous: previous.map(|prev| prev.archive_id.clone()),
+            revisit_of,
+            size,
+            title,
+            diff: None,
+        };
+        version.diff = previous.map(|prev| VersionDiff::between(prev, &version));
+
+        if let Some(next) = versions.get_mut(position) {
+            next.previous = Some(archive_id);
+            next.diff = Some(VersionDiff::between(&version, next));
+        }
+        versions.insert(position, version.clone());
+        version
+    }
+
+    /// Captures d'une URL, de la plus ancienne à la plus récente
+    pub fn versions(&self, url: &str) -> &[ArchiveVersion<Id>] {
+        self.captures.get(&normalize_url(url)).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// Capture la plus proche à la date donnée ou avant
+    pub fn version_at(&self, url: &str, timestamp: DateTime<Utc>) -> Option<&ArchiveVersion<Id>> {
+        let versions = self.versions(url);
+        let position = versions.partition_point(|v| v.captured_at <= timestamp);
+        position.checked_sub(1).map(|i| &versions[i])
+    }
+
+    /// Nombre d'URLs suivies
+    pub fn url_count(&self) -> usize {
+        self.captures.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn at(hour: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2024, 1, 1, hour, 0, 0).unwrap()
+    }
+
+    fn content(data: &[u8]) -> Option<Hash> {
+        Some(crate::crypto::compute_blake3(data))
+    }
+
+    #[test]
+    fn test_timeline_and_closest_capture() {
+        let url = "https://example.com/";
+        let mut history = ArchiveHistory::new();
+        history.record(url, "a", at(8), content(b"v1"));
+        history.record(url, "c", at(12), content(b"v2"));
+        // Capture tardive insérée à sa place
+        let late = history.record(url, "b", at(10), content(b"v1.5"));
+        assert_eq!(late.previous, Some("a"));
+
+        let ids: Vec<_> = history.versions(url).iter().map(|v| v.archive_id).collect();
+        assert_eq!(ids, vec!["a", "b", "c"]);
+        assert_eq!(history.versions(url)[2].previous, Some("b"));
+
+        assert!(history.version_at(url, at(7)).is_none());
+        assert_eq!(history.version_at(url, at(8)).unwrap().archive_id, "a");
+        assert_eq!(history.version_at(url, at(11)).unwrap().archive_id, "b");
+        assert_eq!(history.version_at(url, at(23)).unwrap().archive_id, "c");
+        assert!(history.version_at("https://other.example/", at(23)).is_none());
+        assert!(history.versions("https://other.example/").is_empty());
+    }
+
+    #[test]
+    fn test_identical_recaptures_are_revisits() {
+        let url = "https://example.com/";
+        let mut history = ArchiveHistory::new();
+        assert!(!history.record(url, "a", at(8), content(b"page")).is_revisit());
+
+        let second = history.record(url, "b", at(9), content(b"page"));
+        assert_eq!(second.revisit_of, Some("a"));
+        let third = history.record(url, "c", at(10), content(b"page"));
+        assert_eq!(third.revisit_of, Some("a"));
+
+        assert!(!history.record(url, "d", at(11), content(b"changed")).is_revisit());
+        // Contenu inconnu : jamais considéré comme identique
+        assert!(!history.record(url, "e", at(12), None).is_revisit());
+        assert!(!history.record(url, "f", at(13), None).is_revisit());
+    }
+
+    #[test]
+    fn test_url_variants_share_a_lineage() {
+        assert_eq!(normalize_url("http://Example.com/"), "http://example.com/");
+        assert_eq!(normalize_url("http://example.com"), "http://example.com/");
+        assert_eq!(normalize_url("https://EXAMPLE.com/a/b/#section"), "https://example.com/a/b");
+        assert_eq!(normalize_url("https://example.com/a/b?q=1"), "https://example.com/a/b?q=1");
+
+        let mut history = ArchiveHistory::new();
+        history.record("http://Example.com/", "a", at(8), content(b"v1"));
+        history.record("http://example.com#top", "b", at(9), content(b"v2"));
+        assert_eq!(history.url_count(), 1);
+        assert_eq!(history.versions("HTTP://example.COM").len(), 2);
+        assert_eq!(history.versions("http://example.com/")[1].previous, Some("a"));
+    }
+
+    #[test]
+    fn test_versions_summarize_changes() {
+        let url = "https://example.com/";
+        let mut history = ArchiveHistory::new();
+        let first = history.record_with_summary(url, "a", at(8), content(b"v1"), Some(100), Some("Accueil".to_string()));
+        assert!(first.diff.is_none());
+
+        let second = history.record_with_summary(url, "c", at(12), content(b"v2"), Some(150), Some("Accueil".to_string()));
+        let diff = second.diff.unwrap();
+        assert_eq!(diff.previous_content_hash, content(b"v1"));
+        assert_eq!(diff.size_delta, Some(50));
+        assert!(!diff.title_changed);
+
+        // Capture tardive : la suivante est comparée à elle
+        history.record_with_summary(url, "b", at(10), content(b"v1.5"), Some(80), Some("Nouveau titre".to_string()));
+        let diff = history.versions(url)[2].diff.clone().unwrap();
+        assert_eq!(diff.previous_content_hash, content(b"v1.5"));
+        assert_eq!(diff.size_delta, Some(70));
+        assert!(diff.title_changed);
+        assert_eq!(diff.previous_title.as_deref(), Some("Nouveau titre"));
+    }
+}
@@ -1,11 +1,22 @@
 //! Structure principale de la blockchain ArchiveChain
 
 use std::collections::HashMap;
-use crate::crypto::{Hash, HashAlgorithm};
-use crate::block::{Block, BlockBuilder};
-use crate::transaction::{Transaction, TransactionPool};
-use crate::state::{StateMachine, StateStorage, MemoryStateStorage};
-use crate::error::{CoreError, Result};
+use std::sync::Arc;
+use tokio::sync::broadcast;
+use crate::crypto::{Hash, HashAlgorithm, Signer};
+use crate::block::{Block, BlockBuilder, BlockHeader};
+use crate::transaction::{Transaction, TransactionPool, PoolStats, TransactionReceipt, TokenLedger, TokenTransferProcessor};
+use crate::token::{system_address, ARCToken, ArchivedContentLookup};
+use crate::state::{StateMachine, StateStorage, MemoryStateStorage, MerkleProof, SnapshotManifest, StateRoot, StateSnapshot, StateTransition, DEFAULT_SNAPSHOT_CHUNK_SIZE};
+use crate::crypto::PublicKey;
+use crate::consensus::{ConsensusConfig, ConsensusScore, ElectionInputs, LeaderElectionResult, LeaderSelector, NodeId, ValidatorStakes};
+use crate::error::{CoreError, TransactionError, Result};
+
+/// Facteur de poids d'un bloc non signé ou dont le producteur n'a pas de score
+const MIN_BLOCK_WEIGHT_FACTOR: u128 = 1;
+
+/// Notifications de blocs retenues pour un abonné en retard
+const BLOCK_NOTIFICATION_CAPACITY: usize = 256;
 
 /// Configuration de la blockchain
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -20,6 +31,70 @@ pub struct BlockchainConfig {
     pub max_transactions_per_block: usize,
     /// Temps cible entre les blocs (en secondes)
     pub target_block_time: u64,
+    /// Nombre maximum de transactions en attente
+    pub max_pool_size: usize,
+    /// Durée de séjour maximale d'une transaction en attente (en secondes)
+    pub transaction_ttl: u64,
+    /// Fenêtre de finalité : nombre maximum de blocs pouvant être annulés par une réorganisation
+    pub max_reorg_depth: u64,
+    /// Conservation de l'historique des blocs et de l'état
+    #[serde(default)]
+    pub pruning: PruningMode,
+    /// Fichier où le pool de transactions est sauvegardé à l'arrêt et repris au démarrage
+    #[serde(default)]
+    pub transaction_pool_path: Option<String>,
+    /// Intervalle en blocs entre deux snapshots d'état engagés par le producteur
+    /// pour la synchronisation rapide (0 : aucun)
+    #[serde(default = "default_snapshot_interval")]
+    pub snapshot_interval: u64,
+}
+
+fn default_snapshot_interval() -> u64 {
+    1000
+}
+
+impl BlockchainConfig {
+    /// Le bloc de cette hauteur engage-t-il un snapshot de l'état sur lequel il s'applique
+    fn is_snapshot_height(&self, height: u64) -> bool {
+        height > 0 && self.snapshot_interval > 0 && height % self.snapshot_interval == 0
+    }
+}
+
+/// Mode d'élagage de l'historique
+///
+/// Un nœud élagué conserve les en-têtes de toute la chaîne, ce qui suffit à
+/// vérifier les preuves d'inclusion, mais supprime le corps des blocs et les
+/// snapshots d'état plus anciens que sa fenêtre de rétention. Cette fenêtre
+/// couvre toujours la fenêtre de finalité pour permettre les réorganisations.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, serde::Serialize, serde::Deserialize)]
+pub enum PruningMode {
+    /// Conserve l'intégralité de l'historique
+    #[default]
+    Archive,
+    /// Conserve le corps et l'état des `n` derniers blocs
+    KeepRecent(u64),
+    /// Conserve le corps des blocs de la fenêtre de finalité et un snapshot
+    /// d'état tous les `interval` blocs pour la resynchronisation
+    KeepSnapshots(u64),
+}
+
+impl PruningMode {
+    /// Nombre de blocs récents dont le corps et l'état sont conservés
+    fn retention_window(&self, max_reorg_depth: u64) -> u64 {
+        let finality_window = max_reorg_depth + 1;
+        match self {
+            PruningMode::KeepRecent(blocks) => (*blocks).max(finality_window),
+            PruningMode::Archive | PruningMode::KeepSnapshots(_) => finality_window,
+        }
+    }
+
+    /// Le snapshot d'état de cette hauteur est-il conservé hors fenêtre de rétention
+    fn keeps_snapshot(&self, height: u64) -> bool {
+        match self {
+            PruningMode::KeepSnapshots(interval) => *interval > 0 && height % interval == 0,
+            PruningMode::Archive | PruningMode::KeepRecent(_) => false,
+        }
+    }
 }
 
 impl Default for BlockchainConfig {
@@ -30,6 +105,12 @@ impl Default for BlockchainConfig {
             max_block_size: 1024 * 1024 * 4, // 4MB
             max_transactions_per_block: 1000,
             target_block_time: 60, // 1 minute
+            max_pool_size: 10_000,
+            transaction_ttl: 3 * 3600, // 3 heures
+            max_reorg_depth: 100,
+            pruning: PruningMode::Archive,
+            transaction_pool_path: None,
+            snapshot_interval: default_snapshot_interval(),
         }
     }
 }
@@ -42,6 +123,12 @@ pub struct Blockchain {
     
     /// Chaîne de blocs indexée par hash
     blocks: HashMap<Hash, Block>,
+
+    /// En-têtes des blocs dont le corps a été élagué
+    pruned_headers: HashMap<Hash, BlockHeader>,
+
+    /// Hauteur sous laquelle le corps des blocs a été élagué (genesis excepté)
+    pruned_height: u64,
     
     /// Index des blocs par hauteur
     blocks_by_height: HashMap<u64, Hash>,
@@ -66,6 +153,125 @@ pub struct Blockchain {
     
     /// Difficulté actuelle
     current_difficulty: u64,
+
+    /// Blocs connus hors de la chaîne principale
+    side_blocks: HashMap<Hash, Block>,
+
+    /// Score de consensus cumulé de chaque bloc connu
+    cumulative_scores: HashMap<Hash, u128>,
+
+    /// Transitions d'état de chaque bloc de la fenêtre de rétention, annulées par les réorganisations
+    state_undo: HashMap<Hash, Vec<StateTransition>>,
+
+    /// Soldes ARC modifiés par les transferts de tokens des blocs
+    token_ledger: TokenLedger,
+
+    /// Applique les transferts de tokens des blocs au registre
+    transfer_processor: TokenTransferProcessor,
+
+    /// Registre précédant chaque bloc de la fenêtre de rétention contenant des transferts
+    ledger_undo: HashMap<Hash, TokenLedger>,
+
+    /// Snapshots d'état périodiques de `PruningMode::KeepSnapshots`
+    state_snapshots: HashMap<Hash, StateSnapshot>,
+
+    /// Dernier snapshot engagé par `mine_block` : hauteur, hash du manifeste et état
+    produced_snapshot: Option<(u64, Hash, StateSnapshot)>,
+
+    /// Scores de consensus des producteurs, calculés localement par le moteur de consensus
+    producer_scores: HashMap<NodeId, ConsensusScore>,
+
+    /// Signataire des blocs produits par ce nœud
+    block_signer: Option<Arc<dyn Signer>>,
+
+    /// Élection des producteurs de blocs, si la production est réservée aux leaders élus
+    leader_election: Option<LeaderElection>,
+
+    /// Nombre de réorganisations effectuées
+    reorg_count: u64,
+
+    /// Nombre de blocs annulés par la dernière réorganisation
+    last_reorg_depth: u64,
+
+    /// Reçus des transactions de la chaîne principale, indexés par hash de transaction
+    receipts: HashMap<Hash, TransactionReceipt>,
+
+    /// Inclusions de chaque archive de la chaîne principale, par checksum, de la plus ancienne à la plus récente
+    archive_locations: HashMap<Hash, Vec<ArchiveLocation>>,
+
+    /// Hauteur d'inclusion de chaque archive de la chaîne principale, par identifiant
+    archive_heights: HashMap<Hash, u64>,
+
+    /// Nombre d'archives de la chaîne principale par domaine
+    domain_archive_counts: HashMap<String, usize>,
+
+    /// Diffusion des blocs ajoutés à la chaîne principale
+    block_notifications: broadcast::Sender<BlockNotification>,
+}
+
+/// Inclusion d'une archive dans un bloc de la chaîne principale
+#[derive(Debug, Clone)]
+struct ArchiveLocation {
+    height: u64,
+    original_url: String,
+}
+
+/// Élection des producteurs de blocs à partir des stakes de validateurs
+struct LeaderElection {
+    selector: LeaderSelector,
+    stakes: Arc<dyn ValidatorStakes>,
+    /// Nombre de blocs précédents comptés par le plafond d'équité
+    fairness_window: u64,
+}
+
+impl std::fmt::Debug for LeaderElection {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("LeaderElection")
+            .field("fairness_window", &self.fairness_window)
+            .finish()
+    }
+}
+
+/// Bloc ajouté à la chaîne principale, diffusé aux abonnés de `subscribe_blocks`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BlockNotification {
+    pub height: u64,
+    pub hash: Hash,
+    pub previous_hash: Hash,
+    pub timestamp: chrono::DateTime<chrono::Utc>,
+    pub transaction_count: usize,
+    pub archive_count: usize,
+}
+
+impl From<&Block> for BlockNotification {
+    fn from(block: &Block) -> Self {
+        Self {
+            height: block.height(),
+            hash: block.hash().clone(),
+            previous_hash: block.previous_hash().clone(),
+            timestamp: block.timestamp(),
+            transaction_count: block.transaction_count(),
+            archive_count: block.archive_count(),
+        }
+    }
+}
+
+/// Résultat du traitement d'un bloc concurrent
+#[derive(Debug, Clone, Default)]
+pub struct ReorgOutcome {
+    /// Blocs retirés de la chaîne principale, du plus récent au plus ancien
+    pub reverted: Vec<Hash>,
+    /// Blocs appliqués, dans l'ordre de la chaîne
+    pub applied: Vec<Hash>,
+    /// Transactions des blocs retirés absentes de la nouvelle branche, à resoumettre au pool
+    pub removed_transactions: Vec<Transaction>,
+}
+
+impl ReorgOutcome {
+    /// Vrai si la chaîne principale a changé de branche
+    pub fn is_reorg(&self) -> bool {
+        !self.reverted.is_empty()
+    }
 }
 
 impl Blockchain {
@@ -74,20 +280,44 @@ impl Blockchain {
         let mut blockchain = Self {
             config: config.clone(),
             blocks: HashMap::new(),
+            pruned_headers: HashMap::new(),
+            pruned_height: 0,
             blocks_by_height: HashMap::new(),
             genesis_hash: Hash::zero(),
             head_hash: Hash::zero(),
             current_height: 0,
-            transaction_pool: TransactionPool::default(),
+            transaction_pool: TransactionPool::new(config.max_pool_size)
+                .with_ttl(std::time::Duration::from_secs(config.transaction_ttl)),
             state: StateMachine::new(),
             state_storage: Box::new(MemoryStateStorage::new()),
             current_difficulty: config.initial_difficulty,
+            side_blocks: HashMap::new(),
+            cumulative_scores: HashMap::new(),
+            state_undo: HashMap::new(),
+            token_ledger: TokenLedger::default(),
+            transfer_processor: TokenTransferProcessor::default(),
+            ledger_undo: HashMap::new(),
+            state_snapshots: HashMap::new(),
+            produced_snapshot: None,
+            producer_scores: HashMap::new(),
+            block_signer: None,
+            leader_election: None,
+            reorg_count: 0,
+            last_reorg_depth: 0,
+            receipts: HashMap::new(),
+            archive_locations: HashMap::new(),
+            archive_heights: HashMap::new(),
+            domain_archive_counts: HashMap::new(),
+            block_notifications: broadcast::channel(BLOCK_NOTIFICATION_CAPACITY).0,
         };
 
         // Crée et ajoute le bloc genesis
         let genesis_block = blockchain.create_genesis_block()?;
         blockchain.add_block(genesis_block)?;
 
+        // Reprend les transactions sauvegardées au dernier arrêt
+        blockchain.restore_transaction_pool()?;
+
         Ok(blockchain)
     }
 
@@ -102,7 +332,38 @@ impl Blockchain {
     }
 
     /// Ajoute un nouveau bloc à la chaîne
+    ///
+    /// Avec une élection configurée (`with_leader_election`), le bloc doit
+    /// être signé par le leader élu pour sa hauteur ou l'un de ses secours.
     pub fn add_block(&mut self, block: Block) -> Result<()> {
+        self.check_round_leader(&block)?;
+        self.append_block(block)
+    }
+
+    /// Vérifie que le producteur signataire de `block` est élu pour sa hauteur
+    fn check_round_leader(&self, block: &Block) -> Result<()> {
+        if block.height() == 0 || block.height() != self.current_height {
+            return Ok(());
+        }
+        let Some(election) = self.elect_leader(block.height())? else {
+            return Ok(());
+        };
+
+        let elected = block.header.verified_producer()
+            .is_some_and(|producer| election.is_elected(&NodeId::from_public_key(producer)));
+        if !elected {
+            return Err(CoreError::Validation {
+                message: format!("Bloc {} produit par un nœud non élu", block.height()),
+            });
+        }
+        Ok(())
+    }
+
+    /// Applique un bloc déjà admis sur la tête de chaîne
+    ///
+    /// Sert aussi à restaurer des blocs annulés par une réorganisation
+    /// échouée : leur élection a été vérifiée à leur première application.
+    fn append_block(&mut self, block: Block) -> Result<()> {
         // Valide le bloc
         if !self.validate_block(&block)? {
             return Err(CoreError::Validation {
@@ -120,8 +381,54 @@ impl Blockchain {
             });
         }
 
+        // Chaque transaction doit porter exactement le nonce attendu
+        let applied_nonces = self.check_block_nonces(&block)?;
+
+        // L'état engagé par le producteur doit être celui sur lequel le bloc s'applique
+        if let Some(state_root) = &block.header.state_root {
+            if *state_root != self.state.state_root(HashAlgorithm::Blake3) {
+                return Err(CoreError::Validation {
+                    message: format!("Racine d'état incorrecte au bloc {}", block.height()),
+                });
+            }
+        }
+
+        // Les transferts de tokens sont exécutés avant toute modification : un
+        // transfert sans solde suffisant rejette le bloc entier
+        let has_transfers = block.transactions().iter().any(Transaction::is_token_transfer);
+        let execution = if has_transfers {
+            let fee_recipient = Self::fee_recipient(&block);
+            Some(self.transfer_processor.apply_block(&block, &self.token_ledger, &fee_recipient)?)
+        } else {
+            None
+        };
+
         let block_hash = block.hash().clone();
 
+        // Seules les transitions du bloc servent à l'annuler
+        self.state.take_transitions();
+
+        // Enregistre les nonces appliqués dans l'état, transferts de tokens compris
+        for (sender, nonce) in applied_nonces.into_values() {
+            self.state.set_account_nonce(&sender, nonce)?;
+        }
+        self.state_undo.insert(block_hash.clone(), self.state.take_transitions());
+        let mut transfers = HashMap::new();
+        if let Some(execution) = execution {
+            let previous = std::mem::replace(&mut self.token_ledger, execution.ledger);
+            self.ledger_undo.insert(block_hash.clone(), previous);
+            transfers = execution.transfers;
+        }
+        if self.config.pruning.keeps_snapshot(self.current_height) {
+            self.state_snapshots.insert(block_hash.clone(), self.state.snapshot()?);
+        }
+
+        // Un bloc déjà pondéré (branche latérale) conserve son score
+        let score = self.cumulative_scores.get(block.previous_hash()).copied().unwrap_or(0)
+            + self.block_weight(&block);
+        self.cumulative_scores.entry(block_hash.clone()).or_insert(score);
+        self.side_blocks.remove(&block_hash);
+
         // Ajoute le bloc aux index
         self.blocks.insert(block_hash.clone(), block);
         self.blocks_by_height.insert(self.current_height, block_hash.clone());
@@ -133,16 +440,70 @@ impl Blockchain {
         self.head_hash = block_hash;
         self.current_height += 1;
 
-        // Retire les transactions du pool
+        // Au-delà de la fenêtre de rétention, un bloc ne peut plus être annulé
+        let window = self.config.pruning.retention_window(self.config.max_reorg_depth);
+        if let Some(expired_height) = self.current_height.checked_sub(window + 1) {
+            if let Some(expired_hash) = self.blocks_by_height.get(&expired_height) {
+                self.state_undo.remove(expired_hash);
+                self.ledger_undo.remove(expired_hash);
+            }
+        }
+
+        // Retire les transactions du pool et enregistre leurs reçus
         if let Some(block) = self.blocks.get(&self.head_hash) {
-            for transaction in block.transactions() {
+            for (index, transaction) in block.transactions().iter().enumerate() {
                 self.transaction_pool.remove_transaction(transaction.hash());
+                let execution = transfers.remove(transaction.hash()).unwrap_or_default();
+                self.receipts.insert(transaction.hash().clone(), TransactionReceipt {
+                    transaction_hash: transaction.hash().clone(),
+                    block_hash: block.hash().clone(),
+                    block_height: block.height(),
+                    index: index as u32,
+                    gas_used: execution.fees.burned + execution.fees.rewarded,
+                    events: execution.events,
+                    timestamp: block.timestamp(),
+                });
+            }
+
+            // Indexe les archives du bloc, qui restent consultables après l'élagage
+            for archive in &block.body.archives {
+                self.archive_locations.entry(archive.checksum.clone()).or_default().push(ArchiveLocation {
+                    height: block.height(),
+                    original_url: archive.original_url.clone(),
+                });
+                self.archive_heights.insert(archive.archive_id.clone(), block.height());
             }
+            for (domain, archives) in &block.body.content_index.domain_index {
+                *self.domain_archive_counts.entry(domain.clone()).or_default() += archives.len();
+            }
+
+            // Aucun abonné n'est pas une erreur
+            let _ = self.block_notifications.send(BlockNotification::from(block));
         }
 
+        self.prune_bodies(window);
+
         Ok(())
     }
 
+    /// Élague le corps des blocs sortis de la fenêtre de rétention
+    ///
+    /// Seul l'en-tête est conservé ; le bloc genesis reste complet.
+    fn prune_bodies(&mut self, window: u64) {
+        if self.config.pruning == PruningMode::Archive {
+            return;
+        }
+
+        let prune_below = self.current_height.saturating_sub(window);
+        for height in self.pruned_height.max(1)..prune_below {
+            let Some(hash) = self.blocks_by_height.get(&height) else { continue };
+            if let Some(block) = self.blocks.remove(hash) {
+                self.pruned_headers.insert(hash.clone(), block.header);
+            }
+        }
+        self.pruned_height = self.pruned_height.max(prune_below);
+    }
+
     /// Valide un bloc
     pub fn validate_block(&self, block: &Block) -> Result<bool> {
         // Validation de base du bloc
@@ -175,6 +536,315 @@ impl Blockchain {
         Ok(true)
     }
 
+    /// Signe les blocs produits par `mine_block`
+    pub fn with_block_signer(mut self, signer: Arc<dyn Signer>) -> Self {
+        self.block_signer = Some(signer);
+        self
+    }
+
+    /// Registre initial des soldes ARC (distribution genesis), avant tout bloc de transferts
+    pub fn with_token_ledger(mut self, ledger: TokenLedger) -> Self {
+        self.token_ledger = ledger;
+        self
+    }
+
+    /// Réserve la production des blocs aux leaders élus
+    ///
+    /// Le leader de chaque hauteur est tiré parmi les validateurs de `stakes`
+    /// éligibles à la date du bloc parent, pondérés par leur stake, en
+    /// écartant ceux qui ont déjà produit trop de blocs de la fenêtre
+    /// d'équité (voir `LeaderSelector::elect_round_leader`).
+    pub fn with_leader_election(mut self, config: ConsensusConfig, stakes: Arc<dyn ValidatorStakes>) -> Self {
+        self.leader_election = Some(LeaderElection {
+            fairness_window: config.leader_fairness_window,
+            selector: LeaderSelector::new(config, Hash::zero()),
+            stakes,
+        });
+        self
+    }
+
+    /// Entrées de l'élection du bloc `height`, `None` sans élection configurée
+    ///
+    /// Les stakes sont lus à la date du bloc parent et les producteurs sont
+    /// les signataires vérifiés des blocs de la fenêtre d'équité qui précède
+    /// `height` sur la chaîne principale.
+    pub fn election_inputs(&self, height: u64) -> Result<Option<ElectionInputs>> {
+        let Some(election) = &self.leader_election else {
+            return Ok(None);
+        };
+        let parent = height.checked_sub(1)
+            .and_then(|parent_height| self.get_header_by_height(parent_height))
+            .ok_or_else(|| CoreError::NotFound {
+                message: format!("Bloc parent de la hauteur {} inconnu", height),
+            })?;
+
+        let window_start = height.saturating_sub(election.fairness_window).max(1);
+        let recent_producers = (window_start..height)
+            .filter_map(|producer_height| self.get_header_by_height(producer_height))
+            .filter_map(|header| header.verified_producer().cloned())
+            .collect::<Vec<_>>();
+
+        Ok(Some(ElectionInputs::new(
+            election.stakes.leader_candidates(parent.timestamp),
+            recent_producers,
+        )))
+    }
+
+    /// Élection du producteur du bloc `height`, `None` sans élection configurée
+    pub fn elect_leader(&self, height: u64) -> Result<Option<LeaderElectionResult>> {
+        let (Some(election), Some(inputs)) = (&self.leader_election, self.election_inputs(height)?) else {
+            return Ok(None);
+        };
+        let previous_hash = self.get_header_by_height(height - 1)
+            .map(|header| header.block_hash.clone())
+            .unwrap_or_else(Hash::zero);
+        election.selector.elect_round_leader(&previous_hash, height, &inputs).map(Some)
+    }
+
+    /// Enregistre le score de consensus d'un producteur
+    ///
+    /// Le score doit provenir du moteur de consensus local, calculé depuis
+    /// les preuves enregistrées sur la chaîne ; il pondère les blocs que ce
+    /// producteur a signés et qui arrivent ensuite.
+    pub fn update_producer_score(&mut self, score: ConsensusScore) {
+        self.producer_scores.insert(score.node_id.clone(), score);
+    }
+
+    /// Poids de consensus d'un bloc
+    ///
+    /// La difficulté est pondérée par le score normalisé (0-100) du
+    /// producteur dont la signature couvre le bloc. Un bloc non signé, ou
+    /// signé par un producteur sans score connu, reçoit le poids minimal.
+    fn block_weight(&self, block: &Block) -> u128 {
+        let factor = block.header.verified_producer()
+            .and_then(|producer| self.producer_scores.get(&NodeId::from_public_key(producer)))
+            .map_or(MIN_BLOCK_WEIGHT_FACTOR, |score| (score.normalized_score() as u128).max(MIN_BLOCK_WEIGHT_FACTOR));
+        block.header.difficulty as u128 * factor
+    }
+
+    /// Traite un bloc qui ne prolonge pas forcément la tête de chaîne
+    ///
+    /// Le bloc est conservé comme branche latérale. Si sa branche atteint un
+    /// poids cumulé (voir `block_weight`) strictement supérieur à celui de la
+    /// chaîne principale, les blocs de la chaîne principale sont annulés
+    /// jusqu'à l'ancêtre commun, la nouvelle branche est appliquée et les
+    /// transactions orphelines retournent au pool. À poids égal, la chaîne
+    /// actuelle est conservée. Une réorganisation plus profonde que la
+    /// fenêtre de finalité (`max_reorg_depth`) est refusée.
+    pub fn handle_fork(&mut self, new_block: Block) -> Result<ReorgOutcome> {
+        let block_hash = new_block.hash().clone();
+        if self.blocks.contains_key(&block_hash)
+            || self.pruned_headers.contains_key(&block_hash)
+            || self.side_blocks.contains_key(&block_hash)
+        {
+            return Ok(ReorgOutcome::default());
+        }
+
+        if new_block.previous_hash() == &self.head_hash {
+            self.add_block(new_block)?;
+            return Ok(ReorgOutcome { applied: vec![block_hash], ..ReorgOutcome::default() });
+        }
+
+        let parent = self.get_known_block(new_block.previous_hash()).ok_or_else(|| CoreError::Validation {
+            message: format!("Bloc parent inconnu: {:?}", new_block.previous_hash()),
+        })?;
+        if new_block.height() != parent.height() + 1
+            || !new_block.is_valid(self.config.hash_algorithm)?
+            || new_block.size_bytes() > self.config.max_block_size
+            || new_block.transaction_count() > self.config.max_transactions_per_block
+        {
+            return Err(CoreError::Validation {
+                message: "Bloc de branche latérale invalide".to_string(),
+            });
+        }
+
+        let score = self.cumulative_scores.get(new_block.previous_hash()).copied().unwrap_or(0)
+            + self.block_weight(&new_block);
+        self.cumulative_scores.insert(block_hash.clone(), score);
+        self.side_blocks.insert(block_hash.clone(), new_block);
+
+        // Premier vu, premier servi : il faut un score strictement supérieur
+        let head_score = self.cumulative_scores.get(&self.head_hash).copied().unwrap_or(0);
+        if score <= head_score {
+            return Ok(ReorgOutcome::default());
+        }
+
+        // Remonte la branche jusqu'à la chaîne principale
+        let mut branch = vec![block_hash];
+        let ancestor_height = loop {
+            let last = self.side_blocks.get(branch.last().unwrap()).unwrap();
+            let parent_hash = last.previous_hash().clone();
+            match self.side_blocks.get(&parent_hash) {
+                Some(_) => branch.push(parent_hash),
+                None => break last.height() - 1,
+            }
+        };
+        branch.reverse();
+
+        let depth = self.current_height - 1 - ancestor_height;
+        if depth > self.config.max_reorg_depth {
+            return Err(CoreError::Validation {
+                message: format!(
+                    "Réorganisation de {} blocs refusée (limite {})",
+                    depth, self.config.max_reorg_depth
+                ),
+            });
+        }
+
+        let reverted_blocks = self.rewind_to(ancestor_height)?;
+
+        let mut applied = Vec::new();
+        for hash in &branch {
+            let block = self.side_blocks.remove(hash).unwrap();
+            if let Err(e) = self.add_block(block.clone()) {
+                // Branche invalide : restaure la chaîne d'origine
+                self.side_blocks.insert(hash.clone(), block);
+                for undone in self.rewind_to(ancestor_height)? {
+                    self.side_blocks.insert(undone.hash().clone(), undone);
+                }
+                for block in reverted_blocks.into_iter().rev() {
+                    self.append_block(block)?;
+                }
+                return Err(e);
+            }
+            applied.push(hash.clone());
+        }
+
+        let applied_txs: std::collections::HashSet<Hash> = branch.iter()
+            .filter_map(|hash| self.blocks.get(hash))
+            .flat_map(|block| block.transactions().iter().map(|tx| tx.hash().clone()))
+            .collect();
+        let mut outcome = ReorgOutcome { applied, ..ReorgOutcome::default() };
+        for block in reverted_blocks {
+            outcome.removed_transactions.extend(
+                block.transactions().iter()
+                    .filter(|tx| !applied_txs.contains(tx.hash()))
+                    .cloned()
+            );
+            outcome.reverted.push(block.hash().clone());
+            self.side_blocks.insert(block.hash().clone(), block);
+        }
+
+        // Les transactions orphelines retournent au pool ; celles devenues
+        // invalides (pool plein, nonce consommé par la nouvelle branche) sont abandonnées
+        for transaction in &outcome.removed_transactions {
+            let _ = self.add_transaction(transaction.clone());
+        }
+
+        self.reorg_count += 1;
+        self.last_reorg_depth = depth;
+
+        Ok(outcome)
+    }
+
+    /// Ramène la chaîne principale au bloc de hauteur `ancestor_height`
+    ///
+    /// Les transitions d'état des blocs retirés sont annulées une à une.
+    /// Retourne les blocs retirés, du plus récent au plus ancien.
+    fn rewind_to(&mut self, ancestor_height: u64) -> Result<Vec<Block>> {
+        // Vérifie toutes les données d'annulation avant de toucher à l'état
+        for height in ancestor_height + 1..self.current_height {
+            let revertible = self.blocks_by_height.get(&height)
+                .is_some_and(|hash| self.state_undo.contains_key(hash) && self.blocks.contains_key(hash));
+            if !revertible {
+                return Err(CoreError::Internal {
+                    message: format!("Bloc de hauteur {} impossible à annuler", height),
+                });
+            }
+        }
+
+        let mut reverted = Vec::new();
+        while self.current_height - 1 > ancestor_height {
+            let head_hash = self.head_hash.clone();
+            let block = self.blocks.remove(&head_hash).ok_or_else(|| CoreError::Internal {
+                message: format!("Bloc de tête {:?} absent", head_hash),
+            })?;
+            if let Some(undo) = self.state_undo.remove(&head_hash) {
+                self.state.revert_transitions(&undo);
+            }
+            if let Some(previous) = self.ledger_undo.remove(&head_hash) {
+                self.token_ledger = previous;
+            }
+            self.state_snapshots.remove(&head_hash);
+            for transaction in block.transactions() {
+                self.receipts.remove(transaction.hash());
+            }
+            for archive in &block.body.archives {
+                self.archive_heights.remove(&archive.archive_id);
+                if let Some(locations) = self.archive_locations.get_mut(&archive.checksum) {
+                    locations.pop();
+                    if locations.is_empty() {
+                        self.archive_locations.remove(&archive.checksum);
+                    }
+                }
+            }
+            for (domain, archives) in &block.body.content_index.domain_index {
+                if let Some(count) = self.domain_archive_counts.get_mut(domain) {
+                    *count = count.saturating_sub(archives.len());
+                    if *count == 0 {
+                        self.domain_archive_counts.remove(domain);
+                    }
+                }
+            }
+            self.current_height -= 1;
+            self.blocks_by_height.remove(&self.current_height);
+            self.head_hash = block.previous_hash().clone();
+            reverted.push(block);
+        }
+
+        Ok(reverted)
+    }
+
+    /// Racine de Merkle de l'état courant
+    pub fn state_root(&self) -> StateRoot {
+        self.state.state_root(self.config.hash_algorithm)
+    }
+
+    /// Bloc connu, sur la chaîne principale ou une branche latérale
+    fn get_known_block(&self, hash: &Hash) -> Option<&Block> {
+        self.blocks.get(hash).or_else(|| self.side_blocks.get(hash))
+    }
+
+    /// Vérifie la séquence des nonces d'un bloc par rapport à l'état
+    ///
+    /// Retourne le dernier nonce de chaque émetteur, à appliquer une fois le
+    /// bloc accepté.
+    fn check_block_nonces(&self, block: &Block) -> Result<HashMap<[u8; 32], (PublicKey, u64)>> {
+        let mut last_nonces: HashMap<[u8; 32], (PublicKey, u64)> = HashMap::new();
+
+        for transaction in block.transactions() {
+            let Some(sender) = transaction.nonce_account()? else { continue };
+            let expected = match last_nonces.get(sender.as_bytes()) {
+                Some((_, last)) => last + 1,
+                None => self.state.next_nonce(sender),
+            };
+            if transaction.nonce != expected {
+                return Err(TransactionError::InvalidNonce {
+                    expected,
+                    got: transaction.nonce,
+                }.into());
+            }
+            last_nonces.insert(*sender.as_bytes(), (sender.clone(), transaction.nonce));
+        }
+
+        Ok(last_nonces)
+    }
+
+    /// Nonce attendu pour la prochaine transaction d'un compte
+    pub fn next_nonce(&self, account: &PublicKey) -> u64 {
+        self.state.next_nonce(account)
+    }
+
+    /// Token ARC, avec les soldes résultant des transferts de la chaîne principale
+    pub fn token(&self) -> &ARCToken {
+        &self.token_ledger.token
+    }
+
+    /// Destinataire de la part non brûlée des frais d'un bloc : son producteur signataire
+    fn fee_recipient(block: &Block) -> PublicKey {
+        block.header.verified_producer().cloned().unwrap_or_else(system_address)
+    }
+
     /// Obtient un bloc par son hash
     pub fn get_block(&self, hash: &Hash) -> Option<&Block> {
         self.blocks.get(hash)
@@ -187,6 +857,108 @@ impl Blockchain {
             .and_then(|hash| self.blocks.get(hash))
     }
 
+    /// Obtient l'en-tête d'un bloc de la chaîne principale, élagué ou non
+    pub fn get_header(&self, hash: &Hash) -> Option<&BlockHeader> {
+        self.blocks.get(hash)
+            .map(|block| &block.header)
+            .or_else(|| self.pruned_headers.get(hash))
+    }
+
+    /// Obtient l'en-tête d'un bloc par sa hauteur, élagué ou non
+    pub fn get_header_by_height(&self, height: u64) -> Option<&BlockHeader> {
+        self.blocks_by_height
+            .get(&height)
+            .and_then(|hash| self.get_header(hash))
+    }
+
+    /// En-têtes de la chaîne principale entre deux hauteurs incluses
+    ///
+    /// Les en-têtes des blocs élagués sont inclus ; la plage est tronquée à
+    /// la tête de chaîne.
+    pub fn get_headers(&self, from: u64, to: u64) -> Vec<BlockHeader> {
+        (from..=to)
+            .map_while(|height| self.get_header_by_height(height).cloned())
+            .collect()
+    }
+
+    /// Le corps de ce bloc a-t-il été élagué
+    pub fn is_pruned(&self, hash: &Hash) -> bool {
+        self.pruned_headers.contains_key(hash)
+    }
+
+    /// Le corps du bloc de cette hauteur a-t-il été élagué
+    pub fn is_pruned_height(&self, height: u64) -> bool {
+        height > 0 && height < self.pruned_height
+    }
+
+    /// Hauteur sous laquelle le corps des blocs a été élagué (0 si aucun)
+    pub fn pruned_height(&self) -> u64 {
+        self.pruned_height
+    }
+
+    /// Preuve d'inclusion d'une transaction ou d'une archive de la chaîne principale
+    ///
+    /// Retourne le bloc qui la contient, dont l'en-tête porte la racine de Merkle
+    /// contre laquelle la preuve se vérifie. Seuls les blocs non élagués sont
+    /// parcourus ; une preuve obtenue avant l'élagage reste vérifiable contre
+    /// l'en-tête conservé (`get_header`).
+    pub fn find_inclusion_proof(&self, leaf_hash: &Hash) -> Option<(&Block, MerkleProof)> {
+        (0..=self.current_height)
+            .rev()
+            .filter_map(|height| self.get_block_by_height(height))
+            .find(|block| block.body.contains_leaf(leaf_hash))
+            .and_then(|block| {
+                block.generate_inclusion_proof(leaf_hash, self.config.hash_algorithm)
+                    .ok()
+                    .map(|proof| (block, proof))
+            })
+    }
+
+    /// Hauteur du bloc de la chaîne principale contenant cette transaction ou cette archive
+    ///
+    /// Connue même quand le corps du bloc a été élagué.
+    pub fn leaf_height(&self, leaf_hash: &Hash) -> Option<u64> {
+        self.receipts.get(leaf_hash)
+            .map(|receipt| receipt.block_height)
+            .or_else(|| self.archive_heights.get(leaf_hash).copied())
+    }
+
+    /// Nombre de blocs au-dessus du bloc de cette hauteur (0 pour la tête)
+    ///
+    /// Retourne `None` pour une hauteur au-delà de la tête.
+    pub fn confirmations(&self, height: u64) -> Option<u64> {
+        self.current_height.checked_sub(1)?.checked_sub(height)
+    }
+
+    /// Profondeur de confirmation au-delà de laquelle une inclusion est finale
+    ///
+    /// Une réorganisation annule au plus `max_reorg_depth` blocs : un bloc
+    /// confirmé par autant de blocs ne peut plus être retiré.
+    pub fn finality_depth(&self) -> u64 {
+        self.config.max_reorg_depth
+    }
+
+    /// L'inclusion à cette hauteur est-elle à l'abri des réorganisations
+    pub fn is_final(&self, height: u64) -> bool {
+        self.confirmations(height).map_or(false, |confirmations| confirmations >= self.finality_depth())
+    }
+
+    /// Hauteur du bloc de la chaîne principale contenant l'archive de ce checksum
+    ///
+    /// Si le contenu a été archivé plusieurs fois, l'inclusion la plus récente
+    /// est retenue. L'index survit à l'élagage du corps des blocs.
+    pub fn find_archive_height(&self, checksum: &Hash) -> Option<u64> {
+        self.archive_locations.get(checksum)?.last().map(|location| location.height)
+    }
+
+    /// Reçu d'une transaction incluse dans la chaîne principale
+    ///
+    /// Les reçus survivent à l'élagage du corps des blocs ; une transaction
+    /// retirée par une réorganisation n'a plus de reçu jusqu'à sa réinclusion.
+    pub fn get_receipt(&self, tx_hash: &Hash) -> Option<TransactionReceipt> {
+        self.receipts.get(tx_hash).cloned()
+    }
+
     /// Obtient le dernier bloc
     pub fn get_head_block(&self) -> Option<&Block> {
         if self.head_hash.is_zero() {
@@ -206,7 +978,20 @@ impl Blockchain {
     }
 
     /// Ajoute une transaction au pool
+    ///
+    /// Une transaction dont le nonce est déjà consommé (rejeu) est refusée ;
+    /// les nonces futurs restent en attente dans le pool. L'émetteur, qui
+    /// détermine la séquence de nonces, doit être la clé signataire.
     pub fn add_transaction(&mut self, transaction: Transaction) -> Result<()> {
+        if let Some(sender) = transaction.nonce_account()? {
+            let expected = self.state.next_nonce(sender);
+            if transaction.nonce < expected {
+                return Err(TransactionError::InvalidNonce {
+                    expected,
+                    got: transaction.nonce,
+                }.into());
+            }
+        }
         self.transaction_pool.add_transaction(transaction)
     }
 
@@ -215,26 +1000,187 @@ impl Blockchain {
         self.transaction_pool.pending_transactions()
     }
 
-    /// Mine un nouveau bloc avec les transactions en attente
+    /// Somme des frais des transactions en attente
+    pub fn pending_fee_potential(&self) -> u64 {
+        self.transaction_pool.total_fee_potential()
+    }
+
+    /// Statistiques du pool de transactions
+    pub fn pool_stats(&self) -> PoolStats {
+        self.transaction_pool.stats()
+    }
+
+    /// Sauvegarde les transactions en attente dans `transaction_pool_path`
+    ///
+    /// Appelé à l'arrêt du nœud ; retourne le nombre de transactions écrites.
+    pub fn flush_transaction_pool(&mut self) -> Result<usize> {
+        let Some(path) = self.config.transaction_pool_path.clone() else {
+            return Ok(0);
+        };
+
+        self.transaction_pool.remove_expired(chrono::Utc::now());
+        let pending: Vec<&Transaction> = self.transaction_pool.pending_transactions();
+        let data = serde_json::to_vec(&pending).map_err(|e| CoreError::Internal {
+            message: format!("Sérialisation du pool impossible: {}", e),
+        })?;
+
+        // Écriture atomique via un fichier temporaire
+        let path = std::path::Path::new(&path);
+        let tmp_path = path.with_extension("tmp");
+        std::fs::write(&tmp_path, data)
+            .and_then(|_| std::fs::rename(&tmp_path, path))
+            .map_err(|e| CoreError::Internal {
+                message: format!("Écriture du pool impossible: {}", e),
+            })?;
+
+        tracing::info!("Pool de transactions sauvegardé: {} transactions", pending.len());
+        Ok(pending.len())
+    }
+
+    /// Reprend les transactions sauvegardées par `flush_transaction_pool`
+    ///
+    /// Les transactions devenues invalides (nonce consommé, expirées) sont
+    /// écartées ; le fichier est supprimé pour ne pas être rejoué. Un fichier
+    /// illisible n'empêche pas le démarrage : il est mis de côté avec
+    /// l'extension `corrupt` et le pool démarre vide.
+    fn restore_transaction_pool(&mut self) -> Result<usize> {
+        let Some(path) = self.config.transaction_pool_path.clone() else {
+            return Ok(0);
+        };
+
+        let data = match std::fs::read(&path) {
+            Ok(data) => data,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => {
+                return Err(CoreError::Internal {
+                    message: format!("Lecture du pool impossible: {}", e),
+                })
+            }
+        };
+        let transactions: Vec<Transaction> = match serde_json::from_slice(&data) {
+            Ok(transactions) => transactions,
+            Err(e) => {
+                let corrupt_path = std::path::Path::new(&path).with_extension("corrupt");
+                tracing::error!(
+                    "Pool sauvegardé illisible, démarrage avec un pool vide ({} déplacé vers {}): {}",
+                    path, corrupt_path.display(), e
+                );
+                let _ = std::fs::rename(&path, &corrupt_path);
+                return Ok(0);
+            }
+        };
+
+        let total = transactions.len();
+        let restored = transactions.into_iter()
+            .filter(|transaction| self.add_transaction(transaction.clone()).is_ok())
+            .count();
+        self.transaction_pool.remove_expired(chrono::Utc::now());
+
+        let _ = std::fs::remove_file(&path);
+        tracing::info!("Pool de transactions repris: {}/{} transactions", restored, total);
+        Ok(restored)
+    }
+
+    /// Mine un nouveau bloc avec les transactions en attente les plus rémunératrices
+    ///
+    /// Avec une élection configurée, seul un signataire élu pour la hauteur
+    /// suivante peut produire le bloc.
     pub fn mine_block(&mut self) -> Result<Block> {
-        let pending_txs: Vec<Transaction> = self.transaction_pool
-            .pending_transactions()
-            .into_iter()
-            .cloned()
+        if let Some(election) = self.elect_leader(self.current_height)? {
+            let elected = self.block_signer.as_ref()
+                .is_some_and(|signer| election.is_elected(&NodeId::from_public_key(&signer.public_key())));
+            if !elected {
+                return Err(CoreError::Validation {
+                    message: format!("Ce nœud n'est pas élu pour produire le bloc {}", self.current_height),
+                });
+            }
+        }
+
+        self.transaction_pool.remove_expired(chrono::Utc::now());
+
+        let candidates = self.transaction_pool.take_best(
+            self.config.max_transactions_per_block,
+            self.config.max_block_size,
+        );
+
+        // Écarte les transactions dont le nonce ne suit pas la séquence du
+        // compte, et les transferts que les soldes ne couvrent plus ; les
+        // transactions suivantes du même compte ont alors un nonce en avance
+        let fee_recipient = self.block_signer.as_ref()
+            .map_or_else(system_address, |signer| signer.public_key());
+        let mut ledger = candidates.iter()
+            .any(Transaction::is_token_transfer)
+            .then(|| self.token_ledger.clone());
+        let mut next_nonces: HashMap<[u8; 32], u64> = HashMap::new();
+        let pending_txs: Vec<Transaction> = candidates.into_iter()
+            .filter(|transaction| match transaction.nonce_account() {
+                Ok(Some(sender)) => {
+                    let expected = next_nonces
+                        .entry(*sender.as_bytes())
+                        .or_insert_with(|| self.state.next_nonce(sender));
+                    if transaction.nonce != *expected {
+                        return false;
+                    }
+                    if let Some(ledger) = ledger.as_mut().filter(|_| transaction.is_token_transfer()) {
+                        if self.transfer_processor.execute_transfer(transaction, ledger, &fee_recipient).is_err() {
+                            return false;
+                        }
+                    }
+                    *expected += 1;
+                    true
+                }
+                Ok(None) => true,
+                Err(_) => false,
+            })
             .collect();
 
-        let new_block = BlockBuilder::new(
+        let mut builder = BlockBuilder::new(
             self.current_height,
             self.head_hash.clone(),
             self.config.hash_algorithm,
         )
         .add_transactions(pending_txs)
-        .difficulty(self.current_difficulty)
-        .build()?;
+        .difficulty(self.current_difficulty);
+
+        // Aux hauteurs de snapshot, l'état courant est engagé pour la synchronisation rapide
+        if self.config.is_snapshot_height(self.current_height) {
+            let snapshot = self.state.snapshot()?;
+            let (manifest, _) = SnapshotManifest::split(&snapshot, self.current_height, DEFAULT_SNAPSHOT_CHUNK_SIZE);
+            let manifest_hash = manifest.manifest_hash();
+            builder = builder.state_snapshot(snapshot.state_root.clone(), manifest_hash.clone());
+            self.produced_snapshot = Some((self.current_height, manifest_hash, snapshot));
+        }
+        if let Some(signer) = &self.block_signer {
+            builder = builder.signed_by(signer.clone());
+        }
+        let new_block = builder.build()?;
 
         Ok(new_block)
     }
 
+    /// Snapshot produit par ce nœud et engagé par le bloc `height` de la chaîne principale
+    ///
+    /// Il est découpé en chunks de `DEFAULT_SNAPSHOT_CHUNK_SIZE` pour être publié.
+    pub fn committed_snapshot(&self, height: u64) -> Option<&StateSnapshot> {
+        let (snapshot_height, manifest_hash, snapshot) = self.produced_snapshot.as_ref()?;
+        let committed = self.get_header_by_height(height)?.snapshot_manifest.as_ref() == Some(manifest_hash);
+        (*snapshot_height == height && committed).then_some(snapshot)
+    }
+
+    /// S'abonne aux blocs ajoutés à la chaîne principale à partir de maintenant
+    ///
+    /// Les blocs appliqués par une réorganisation sont notifiés comme les
+    /// autres. Un abonné trop lent perd les notifications les plus anciennes
+    /// (`RecvError::Lagged`) ; son abonnement cesse quand le récepteur est abandonné.
+    pub fn subscribe_blocks(&self) -> broadcast::Receiver<BlockNotification> {
+        self.block_notifications.subscribe()
+    }
+
+    /// Nombre d'abonnés actifs aux blocs ajoutés
+    pub fn block_subscriber_count(&self) -> usize {
+        self.block_notifications.receiver_count()
+    }
+
     /// Obtient la hauteur actuelle de la chaîne
     pub fn height(&self) -> u64 {
         self.current_height
@@ -263,10 +1209,10 @@ impl Blockchain {
 
         for i in 1..=10 {
             if let (Some(current), Some(previous)) = (
-                self.get_block_by_height(self.current_height.saturating_sub(i)),
-                self.get_block_by_height(self.current_height.saturating_sub(i + 1)),
+                self.get_header_by_height(self.current_height.saturating_sub(i)),
+                self.get_header_by_height(self.current_height.saturating_sub(i + 1)),
             ) {
-                let time_diff = (current.timestamp() - previous.timestamp()).num_seconds();
+                let time_diff = (current.timestamp - previous.timestamp).num_seconds();
                 if time_diff > 0 {
                     total_time += time_diff as u64;
                     valid_intervals += 1;
@@ -303,33 +1249,41 @@ impl Blockchain {
     pub fn stats(&self) -> BlockchainStats {
         BlockchainStats {
             height: self.current_height,
-            total_blocks: self.blocks.len(),
+            total_blocks: self.blocks.len() + self.pruned_headers.len(),
             pending_transactions: self.transaction_pool.size(),
             difficulty: self.current_difficulty,
             head_hash: self.head_hash.clone(),
+            reorg_count: self.reorg_count,
+            last_reorg_depth: self.last_reorg_depth,
+            pruned_height: self.pruned_height,
+            pruned_blocks: self.pruned_headers.len(),
         }
     }
 
     /// Vérifie l'intégrité de toute la chaîne
+    ///
+    /// Les blocs élagués sont vérifiés sur leur seul en-tête.
     pub fn verify_chain(&self) -> Result<bool> {
         for height in 0..self.current_height {
-            if let Some(block) = self.get_block_by_height(height) {
-                if !block.is_valid(self.config.hash_algorithm)? {
-                    return Ok(false);
-                }
-
-                // Vérifie le chaînage
-                if height > 0 {
-                    if let Some(prev_block) = self.get_block_by_height(height - 1) {
-                        if block.previous_hash() != prev_block.hash() {
-                            return Ok(false);
-                        }
-                    } else {
+            let header = match self.get_block_by_height(height) {
+                Some(block) => {
+                    if !block.is_valid(self.config.hash_algorithm)? {
                         return Ok(false);
                     }
+                    &block.header
+                }
+                None => match self.get_header_by_height(height) {
+                    Some(header) if header.is_valid(self.config.hash_algorithm)? => header,
+                    _ => return Ok(false),
+                },
+            };
+
+            // Vérifie le chaînage
+            if height > 0 {
+                match self.get_header_by_height(height - 1) {
+                    Some(prev_header) if header.previous_hash == prev_header.block_hash => {}
+                    _ => return Ok(false),
                 }
-            } else {
-                return Ok(false);
             }
         }
 
@@ -337,6 +1291,21 @@ impl Blockchain {
     }
 }
 
+/// Index des archives de la chaîne principale, pour les récompenses de découverte
+impl ArchivedContentLookup for Blockchain {
+    fn is_archived(&self, content_hash: &Hash) -> bool {
+        self.archive_locations.contains_key(content_hash)
+    }
+
+    fn archived_url(&self, content_hash: &Hash) -> Option<String> {
+        self.archive_locations.get(content_hash)?.last().map(|location| location.original_url.clone())
+    }
+
+    fn domain_archive_count(&self, domain: &str) -> usize {
+        self.domain_archive_counts.get(domain).copied().unwrap_or(0)
+    }
+}
+
 /// Statistiques de la blockchain
 #[derive(Debug, Clone)]
 pub struct BlockchainStats {
@@ -350,6 +1319,14 @@ pub struct BlockchainStats {
     pub difficulty: u64,
     /// Hash de la tête
     pub head_hash: Hash,
+    /// Nombre de réorganisations effectuées
+    pub reorg_count: u64,
+    /// Nombre de blocs annulés par la dernière réorganisation
+    pub last_reorg_depth: u64,
+    /// Hauteur sous laquelle le corps des blocs a été élagué (0 si aucun)
+    pub pruned_height: u64,
+    /// Nombre de blocs dont seul l'en-tête est conservé
+    pub pruned_blocks: usize,
 }
 
 #[cfg(test)]
@@ -393,4 +1370,680 @@ mod tests {
         let next_difficulty = blockchain.calculate_next_difficulty();
         assert_eq!(next_difficulty, blockchain.difficulty()); // Should be same for short chain
     }
-}
\ No newline at end of file
+
+    fn create_signed_transfer(sender: &crate::crypto::KeyPair, nonce: u64) -> Transaction {
+        create_transfer_with_fee(sender, nonce, 10)
+    }
+
+    fn create_transfer_with_fee(sender: &crate::crypto::KeyPair, nonce: u64, fee: u64) -> Transaction {
+        use crate::transaction::{TransactionOutput, TransactionType};
+        use crate::transaction::types::TransactionBuilder;
+
+        let mut transaction = TransactionBuilder::new(TransactionType::Archive)
+            .add_output(TransactionOutput {
+                amount: 1000,
+                recipient: sender.public_key().clone(),
+                lock_script: Vec::new(),
+            })
+            .nonce(nonce)
+            .fee(fee)
+            .build();
+        transaction.sign(sender.private_key()).unwrap();
+        transaction
+    }
+
+    #[test]
+    fn test_replayed_transaction_is_rejected() {
+        let mut blockchain = Blockchain::new(BlockchainConfig::default()).unwrap();
+        let sender = crate::crypto::generate_keypair().unwrap();
+        let transaction = create_signed_transfer(&sender, 0);
+
+        blockchain.add_transaction(transaction.clone()).unwrap();
+        let block = blockchain.mine_block().unwrap();
+        assert_eq!(block.transaction_count(), 1);
+        blockchain.add_block(block).unwrap();
+        assert_eq!(blockchain.next_nonce(sender.public_key()), 1);
+
+        // Resoumission de la même transaction
+        match blockchain.add_transaction(transaction.clone()) {
+            Err(CoreError::Transaction(TransactionError::InvalidNonce { expected, got })) => {
+                assert_eq!((expected, got), (1, 0));
+            }
+            other => panic!("InvalidNonce attendu, obtenu {:?}", other),
+        }
+
+        // Un bloc qui rejoue la transaction est refusé
+        let replay_block = BlockBuilder::new(
+            blockchain.height(),
+            blockchain.head_hash().clone(),
+            blockchain.config.hash_algorithm,
+        )
+        .add_transactions(vec![transaction])
+        .difficulty(blockchain.difficulty())
+        .build()
+        .unwrap();
+        assert!(matches!(
+            blockchain.add_block(replay_block),
+            Err(CoreError::Transaction(TransactionError::InvalidNonce { expected: 1, got: 0 }))
+        ));
+    }
+
+    #[test]
+    fn test_token_transfers_are_applied_with_blocks() {
+        let alice = crate::crypto::generate_keypair().unwrap();
+        let bob = crate::crypto::generate_keypair().unwrap().public_key().clone();
+        let mut ledger = TokenLedger::default();
+        ledger.token.mint(alice.public_key(), 5_000, Hash::zero()).unwrap();
+        let mut blockchain = Blockchain::new(BlockchainConfig::default()).unwrap().with_token_ledger(ledger);
+        let genesis = blockchain.get_genesis_block().unwrap().clone();
+        let transfer = |amount: u64, nonce: u64| {
+            let mut tx = Transaction::token_transfer(alice.public_key().clone(), bob.clone(), amount, 100, nonce);
+            tx.sign(alice.private_key()).unwrap();
+            tx
+        };
+
+        // Le second transfert n'est plus couvert par le solde : il n'est pas miné
+        blockchain.add_transaction(transfer(3_000, 0)).unwrap();
+        blockchain.add_transaction(transfer(3_000, 1)).unwrap();
+        let block = blockchain.mine_block().unwrap();
+        assert_eq!(block.transaction_count(), 1);
+        blockchain.add_block(block.clone()).unwrap();
+        assert_eq!(blockchain.token().balance_of(alice.public_key()), 1_900);
+        assert_eq!(blockchain.token().balance_of(&bob), 3_000);
+        assert_eq!(blockchain.token().burned_tokens, 10);
+        assert_eq!(blockchain.next_nonce(alice.public_key()), 1);
+
+        // Un bloc qui l'inclut quand même est rejeté sans rien modifier
+        let overdraft = build_block(&block, 0, vec![transfer(3_000, 1)]);
+        assert!(matches!(
+            blockchain.add_block(overdraft),
+            Err(CoreError::Transaction(TransactionError::InsufficientBalance))
+        ));
+        assert_eq!(blockchain.next_nonce(alice.public_key()), 1);
+
+        // Une branche plus lourde sans le transfert rétablit les soldes
+        let fork_1 = build_block(&genesis, 1, Vec::new());
+        blockchain.handle_fork(fork_1.clone()).unwrap();
+        blockchain.handle_fork(build_block(&fork_1, 1, Vec::new())).unwrap();
+        assert_eq!(blockchain.token().balance_of(alice.public_key()), 5_000);
+        assert_eq!(blockchain.token().balance_of(&bob), 0);
+        assert_eq!(blockchain.next_nonce(alice.public_key()), 0);
+    }
+
+    #[test]
+    fn test_mine_block_picks_highest_fees() {
+        let config = BlockchainConfig {
+            max_transactions_per_block: 2,
+            ..BlockchainConfig::default()
+        };
+        let mut blockchain = Blockchain::new(config).unwrap();
+        for fee in [5, 40, 12, 25] {
+            let sender = crate::crypto::generate_keypair().unwrap();
+            blockchain.add_transaction(create_transfer_with_fee(&sender, 0, fee)).unwrap();
+        }
+
+        let block = blockchain.mine_block().unwrap();
+        let mut fees: Vec<u64> = block.transactions().iter().map(|tx| tx.fee).collect();
+        fees.sort_unstable();
+        assert_eq!(fees, vec![25, 40]);
+        assert_eq!(blockchain.pool_stats().count, 4);
+
+        blockchain.add_block(block).unwrap();
+        let stats = blockchain.pool_stats();
+        assert_eq!(stats.count, 2);
+        assert_eq!(stats.max_fee, Some(12));
+    }
+
+    fn build_block(parent: &Block, nonce: u64, transactions: Vec<Transaction>) -> Block {
+        BlockBuilder::new(parent.height() + 1, parent.hash().clone(), HashAlgorithm::Blake3)
+            .difficulty(1000)
+            .nonce(nonce)
+            .add_transactions(transactions)
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_reorg_to_heavier_branch() {
+        let mut blockchain = Blockchain::new(BlockchainConfig::default()).unwrap();
+        let genesis = blockchain.get_genesis_block().unwrap().clone();
+        let sender = crate::crypto::generate_keypair().unwrap();
+        let transaction = create_signed_transfer(&sender, 0);
+
+        let main = build_block(&genesis, 0, vec![transaction.clone()]);
+        blockchain.handle_fork(main.clone()).unwrap();
+        assert_eq!(blockchain.next_nonce(sender.public_key()), 1);
+
+        // Score égal : la chaîne vue en premier est conservée
+        let fork_1 = build_block(&genesis, 1, Vec::new());
+        let outcome = blockchain.handle_fork(fork_1.clone()).unwrap();
+        assert!(!outcome.is_reorg());
+        assert_eq!(blockchain.head_hash(), main.hash());
+
+        let fork_2 = build_block(&fork_1, 1, Vec::new());
+        let outcome = blockchain.handle_fork(fork_2.clone()).unwrap();
+        assert_eq!(outcome.reverted, vec![main.hash().clone()]);
+        assert_eq!(outcome.applied, vec![fork_1.hash().clone(), fork_2.hash().clone()]);
+        assert_eq!(outcome.removed_transactions.len(), 1);
+        assert_eq!(outcome.removed_transactions[0].hash(), transaction.hash());
+
+        assert_eq!(blockchain.head_hash(), fork_2.hash());
+        assert_eq!(blockchain.height(), 3);
+        assert_eq!(blockchain.next_nonce(sender.public_key()), 0);
+        assert!(blockchain.verify_chain().unwrap());
+
+        // La transaction retirée est retournée au pool
+        assert!(blockchain.transaction_pool.get_transaction(transaction.hash()).is_some());
+        assert_eq!(blockchain.mine_block().unwrap().transaction_count(), 1);
+    }
+
+    #[test]
+    fn test_reorg_deeper_than_limit_is_rejected() {
+        let config = BlockchainConfig {
+            max_reorg_depth: 1,
+            ..BlockchainConfig::default()
+        };
+        let mut blockchain = Blockchain::new(config).unwrap();
+        let genesis = blockchain.get_genesis_block().unwrap().clone();
+
+        let main_1 = build_block(&genesis, 0, Vec::new());
+        let main_2 = build_block(&main_1, 0, Vec::new());
+        blockchain.handle_fork(main_1).unwrap();
+        blockchain.handle_fork(main_2.clone()).unwrap();
+
+        let fork_1 = build_block(&genesis, 1, Vec::new());
+        let fork_2 = build_block(&fork_1, 1, Vec::new());
+        let fork_3 = build_block(&fork_2, 1, Vec::new());
+        assert!(!blockchain.handle_fork(fork_1).unwrap().is_reorg());
+        assert!(!blockchain.handle_fork(fork_2).unwrap().is_reorg());
+        assert!(matches!(blockchain.handle_fork(fork_3), Err(CoreError::Validation { .. })));
+        assert_eq!(blockchain.head_hash(), main_2.hash());
+        assert_eq!(blockchain.height(), 3);
+    }
+
+    /// Producteur dont le score de consensus est connu de la chaîne
+    fn scored_producer(blockchain: &mut Blockchain, combined_score: f64) -> Arc<dyn Signer> {
+        let keypair = crate::crypto::generate_keypair().unwrap();
+        blockchain.update_producer_score(ConsensusScore {
+            storage_score: combined_score,
+            bandwidth_score: combined_score,
+            longevity_score: combined_score,
+            combined_score,
+            node_id: NodeId::from_public_key(keypair.public_key()),
+            calculated_at: chrono::Utc::now(),
+        });
+        Arc::new(keypair)
+    }
+
+    fn build_signed_block(parent: &Block, nonce: u64, transactions: Vec<Transaction>, producer: &Arc<dyn Signer>) -> Block {
+        BlockBuilder::new(parent.height() + 1, parent.hash().clone(), HashAlgorithm::Blake3)
+            .difficulty(1000)
+            .nonce(nonce)
+            .add_transactions(transactions)
+            .signed_by(producer.clone())
+            .build()
+            .unwrap()
+    }
+
+    /// Stakes de validateurs fixes
+    struct FixedStakes(Vec<(PublicKey, u64)>);
+
+    impl ValidatorStakes for FixedStakes {
+        fn leader_candidates(&self, _at: chrono::DateTime<chrono::Utc>) -> Vec<(PublicKey, u64)> {
+            self.0.clone()
+        }
+    }
+
+    #[test]
+    fn test_only_elected_leaders_produce_blocks() {
+        let first: Arc<dyn Signer> = Arc::new(crate::crypto::generate_keypair().unwrap());
+        let second: Arc<dyn Signer> = Arc::new(crate::crypto::generate_keypair().unwrap());
+        let stakes = Arc::new(FixedStakes(vec![(first.public_key(), 1_000), (second.public_key(), 1_000)]));
+        let mut consensus = ConsensusConfig::test_config();
+        consensus.validators_per_round = 1;
+        let blockchain = Blockchain::new(BlockchainConfig::default()).unwrap()
+            .with_leader_election(consensus.clone(), stakes);
+        let genesis = blockchain.get_genesis_block().unwrap().clone();
+
+        let election = blockchain.elect_leader(1).unwrap().unwrap();
+        let inputs = blockchain.election_inputs(1).unwrap().unwrap();
+        assert!(election.verify(genesis.hash(), &inputs, &consensus));
+        let (leader, other) = if election.primary_leader == NodeId::from_public_key(&first.public_key()) {
+            (first, second)
+        } else {
+            (second, first)
+        };
+
+        // Un bloc signé par un nœud non élu est refusé, à la production comme à la réception
+        let mut blockchain = blockchain.with_block_signer(other.clone());
+        assert!(blockchain.mine_block().is_err());
+        assert!(blockchain.add_block(build_signed_block(&genesis, 0, Vec::new(), &other)).is_err());
+        assert!(blockchain.add_block(build_block(&genesis, 0, Vec::new())).is_err());
+
+        let mut blockchain = blockchain.with_block_signer(leader);
+        let block = blockchain.mine_block().unwrap();
+        blockchain.add_block(block).unwrap();
+        assert_eq!(blockchain.height(), 2);
+
+        // Le producteur du bloc 1 compte dans la fenêtre d'équité du bloc 2
+        let inputs = blockchain.election_inputs(2).unwrap().unwrap();
+        assert_eq!(inputs.recent_leaders.get(&election.primary_leader), Some(&1));
+    }
+
+    #[test]
+    fn test_reorg_follows_consensus_weight() {
+        let mut blockchain = Blockchain::new(BlockchainConfig::default()).unwrap();
+        let genesis = blockchain.get_genesis_block().unwrap().clone();
+        let weak = scored_producer(&mut blockchain, 0.4);
+        let strong = scored_producer(&mut blockchain, 0.9);
+        let alice = crate::crypto::generate_keypair().unwrap();
+        let bob = crate::crypto::generate_keypair().unwrap();
+        let alice_0 = create_signed_transfer(&alice, 0);
+        let alice_1 = create_signed_transfer(&alice, 1);
+        let bob_0 = create_signed_transfer(&bob, 0);
+
+        // Chaîne principale plus longue mais produite par des validateurs faibles
+        let main_1 = build_signed_block(&genesis, 0, vec![alice_0.clone()], &weak);
+        let main_2 = build_signed_block(&main_1, 0, vec![alice_1.clone()], &weak);
+        blockchain.handle_fork(main_1).unwrap();
+        blockchain.handle_fork(main_2).unwrap();
+        assert_eq!(blockchain.next_nonce(alice.public_key()), 2);
+
+        // Un bloc attribué au producteur fort sans sa signature est refusé
+        let mut usurped = build_signed_block(&genesis, 1, vec![bob_0.clone()], &weak);
+        usurped.header.producer = Some(strong.public_key());
+        usurped.header.block_hash = usurped.header.calculate_hash(HashAlgorithm::Blake3);
+        assert!(blockchain.handle_fork(usurped).is_err());
+        assert_eq!(blockchain.height(), 3);
+
+        // Branche plus courte mais plus lourde
+        let fork_1 = build_signed_block(&genesis, 1, vec![bob_0.clone()], &strong);
+        let outcome = blockchain.handle_fork(fork_1.clone()).unwrap();
+        assert_eq!(outcome.reverted.len(), 2);
+        assert_eq!(outcome.applied, vec![fork_1.hash().clone()]);
+        assert_eq!(blockchain.head_hash(), fork_1.hash());
+        assert_eq!(blockchain.height(), 2);
+
+        let stats = blockchain.stats();
+        assert_eq!(stats.reorg_count, 1);
+        assert_eq!(stats.last_reorg_depth, 2);
+
+        // L'état correspond à une réexécution de la branche gagnante
+        assert_eq!(blockchain.next_nonce(alice.public_key()), 0);
+        assert_eq!(blockchain.next_nonce(bob.public_key()), 1);
+        let mut replay = Blockchain::new(BlockchainConfig::default()).unwrap();
+        let replay_genesis = replay.get_genesis_block().unwrap().clone();
+        replay.add_block(build_block(&replay_genesis, 1, vec![bob_0])).unwrap();
+        assert_eq!(blockchain.state_root(), replay.state_root());
+
+        // Les transactions orphelines sont de retour dans le pool
+        assert!(blockchain.transaction_pool.get_transaction(alice_0.hash()).is_some());
+        assert!(blockchain.transaction_pool.get_transaction(alice_1.hash()).is_some());
+        assert_eq!(blockchain.mine_block().unwrap().transaction_count(), 2);
+    }
+
+    #[test]
+    fn test_lighter_branch_does_not_reorg() {
+        let mut blockchain = Blockchain::new(BlockchainConfig::default()).unwrap();
+        let genesis = blockchain.get_genesis_block().unwrap().clone();
+        let strong = scored_producer(&mut blockchain, 0.9);
+        let weak = scored_producer(&mut blockchain, 0.3);
+
+        let main_1 = build_signed_block(&genesis, 0, Vec::new(), &strong);
+        blockchain.handle_fork(main_1.clone()).unwrap();
+        let root_before = blockchain.state_root();
+
+        let fork_1 = build_signed_block(&genesis, 1, Vec::new(), &weak);
+        let fork_2 = build_signed_block(&fork_1, 1, Vec::new(), &weak);
+        blockchain.handle_fork(fork_1).unwrap();
+        let outcome = blockchain.handle_fork(fork_2).unwrap();
+        assert!(!outcome.is_reorg());
+
+        // Les blocs non signés ou de producteurs inconnus pèsent le minimum
+        let unknown: Arc<dyn Signer> = Arc::new(crate::crypto::generate_keypair().unwrap());
+        let unsigned_1 = build_block(&genesis, 2, Vec::new());
+        let unsigned_2 = build_block(&unsigned_1, 2, Vec::new());
+        let unknown_3 = build_signed_block(&unsigned_2, 2, Vec::new(), &unknown);
+        for block in [unsigned_1, unsigned_2, unknown_3] {
+            assert!(!blockchain.handle_fork(block).unwrap().is_reorg());
+        }
+
+        assert_eq!(blockchain.head_hash(), main_1.hash());
+        assert_eq!(blockchain.state_root(), root_before);
+        assert_eq!(blockchain.stats().reorg_count, 0);
+    }
+
+    #[test]
+    fn test_only_periodic_snapshots_are_kept() {
+        let config = BlockchainConfig {
+            max_reorg_depth: 5,
+            pruning: PruningMode::KeepSnapshots(10),
+            ..BlockchainConfig::default()
+        };
+        let mut blockchain = Blockchain::new(config).unwrap();
+        let mut parent = blockchain.get_genesis_block().unwrap().clone();
+        for _ in 0..30 {
+            let block = build_block(&parent, 0, Vec::new());
+            blockchain.add_block(block.clone()).unwrap();
+            parent = block;
+        }
+
+        // Genesis, 10, 20 et 30 ; l'annulation repose sur les transitions de la fenêtre
+        assert_eq!(blockchain.state_snapshots.len(), 4);
+        assert_eq!(blockchain.state_undo.len(), 6);
+        assert!(Blockchain::new(BlockchainConfig::default()).unwrap().state_snapshots.is_empty());
+    }
+
+    #[test]
+    fn test_pruning_keeps_headers_and_recent_bodies() {
+        let config = BlockchainConfig {
+            max_reorg_depth: 10,
+            pruning: PruningMode::KeepRecent(50),
+            ..BlockchainConfig::default()
+        };
+        let mut blockchain = Blockchain::new(config).unwrap();
+        let sender = crate::crypto::generate_keypair().unwrap();
+        let transaction = create_signed_transfer(&sender, 0);
+
+        let mut parent = blockchain.get_genesis_block().unwrap().clone();
+        let first = build_block(&parent, 0, vec![transaction.clone()]);
+        blockchain.handle_fork(first.clone()).unwrap();
+        let (_, proof) = blockchain.find_inclusion_proof(transaction.hash()).unwrap();
+        parent = first;
+        for _ in 1..200 {
+            let block = build_block(&parent, 0, Vec::new());
+            blockchain.handle_fork(block.clone()).unwrap();
+            parent = block;
+        }
+        assert_eq!(blockchain.height(), 201);
+
+        // Seuls les 50 derniers corps sont conservés, genesis excepté
+        assert_eq!(blockchain.pruned_height(), 151);
+        assert!(blockchain.get_genesis_block().is_some());
+        assert!(blockchain.get_block_by_height(1).is_none());
+        assert!(blockchain.is_pruned_height(150));
+        assert!(blockchain.get_block_by_height(151).is_some());
+        assert!(!blockchain.is_pruned_height(200));
+        let stats = blockchain.stats();
+        assert_eq!(stats.pruned_blocks, 150);
+        assert_eq!(stats.total_blocks, 201);
+
+        // L'en-tête conservé suffit à vérifier une preuve d'inclusion ancienne
+        let header = blockchain.get_header_by_height(1).unwrap();
+        assert!(blockchain.is_pruned(&header.block_hash));
+        assert!(proof.verify(&header.merkle_root, transaction.hash()));
+        assert!(blockchain.find_inclusion_proof(transaction.hash()).is_none());
+        assert_eq!(blockchain.leaf_height(transaction.hash()), Some(1));
+        assert!(blockchain.leaf_height(&Hash::zero()).is_none());
+
+        assert!(blockchain.verify_chain().unwrap());
+    }
+
+    fn archive_of(url: &str, content: &[u8]) -> crate::block::ArchiveBlock {
+        let metadata = crate::block::ArchiveMetadata {
+            title: None,
+            description: None,
+            keywords: Vec::new(),
+            content_type: "text/html".to_string(),
+            language: None,
+            author: None,
+            published_at: None,
+            custom_metadata: HashMap::new(),
+            external_links_count: 0,
+            resource_count: 0,
+            quality_score: 50,
+            content_flags: crate::block::archive_metadata::ContentFlags::default(),
+        };
+        crate::block::ArchiveBlock::new(
+            url.to_string(),
+            "text/html".to_string(),
+            crate::block::CompressionType::None,
+            content.len() as u64,
+            content.len() as u64,
+            crate::crypto::compute_blake3(content),
+            metadata,
+        )
+    }
+
+    #[test]
+    fn test_archive_index_follows_reorgs_and_survives_pruning() {
+        let config = BlockchainConfig {
+            max_reorg_depth: 2,
+            pruning: PruningMode::KeepRecent(2),
+            ..BlockchainConfig::default()
+        };
+        let mut blockchain = Blockchain::new(config).unwrap();
+        let genesis = blockchain.get_genesis_block().unwrap().clone();
+        let archive = archive_of("https://example.com/page", b"page");
+        let checksum = archive.checksum.clone();
+        let archive_id = archive.archive_id.clone();
+
+        let main = BlockBuilder::new(1, genesis.hash().clone(), HashAlgorithm::Blake3)
+            .difficulty(1000)
+            .add_archive(archive)
+            .build()
+            .unwrap();
+        blockchain.handle_fork(main).unwrap();
+        assert_eq!(blockchain.find_archive_height(&checksum), Some(1));
+        assert_eq!(blockchain.archived_url(&checksum).as_deref(), Some("https://example.com/page"));
+        assert_eq!(blockchain.domain_archive_count("example.com"), 1);
+
+        // La branche plus lourde sans l'archive la retire de l'index
+        let fork_1 = build_block(&genesis, 1, Vec::new());
+        blockchain.handle_fork(fork_1.clone()).unwrap();
+        let mut parent = build_block(&fork_1, 1, Vec::new());
+        blockchain.handle_fork(parent.clone()).unwrap();
+        assert!(!blockchain.is_archived(&checksum));
+        assert!(blockchain.leaf_height(&archive_id).is_none());
+        assert_eq!(blockchain.domain_archive_count("example.com"), 0);
+
+        // Réincluse puis élaguée, l'archive reste indexée
+        let reincluded = BlockBuilder::new(parent.height() + 1, parent.hash().clone(), HashAlgorithm::Blake3)
+            .difficulty(1000)
+            .add_archive(archive_of("https://example.com/page", b"page"))
+            .build()
+            .unwrap();
+        blockchain.add_block(reincluded.clone()).unwrap();
+        parent = reincluded;
+        for nonce in 0..5 {
+            let block = build_block(&parent, nonce, Vec::new());
+            blockchain.add_block(block.clone()).unwrap();
+            parent = block;
+        }
+        assert!(blockchain.is_pruned_height(3));
+        assert_eq!(blockchain.find_archive_height(&checksum), Some(3));
+        assert_eq!(blockchain.domain_archive_count("example.com"), 1);
+    }
+
+    #[test]
+    fn test_mine_block_skips_nonce_gaps() {
+        let mut blockchain = Blockchain::new(BlockchainConfig::default()).unwrap();
+        let sender = crate::crypto::generate_keypair().unwrap();
+
+        blockchain.add_transaction(create_signed_transfer(&sender, 0)).unwrap();
+        blockchain.add_transaction(create_signed_transfer(&sender, 2)).unwrap();
+
+        let block = blockchain.mine_block().unwrap();
+        assert_eq!(block.transaction_count(), 1);
+        blockchain.add_block(block).unwrap();
+        assert_eq!(blockchain.next_nonce(sender.public_key()), 1);
+    }
+
+    #[test]
+    fn test_nonce_account_is_the_signing_key() {
+        let mut blockchain = Blockchain::new(BlockchainConfig::default()).unwrap();
+        let genesis = blockchain.get_genesis_block().unwrap().clone();
+        let sender = crate::crypto::generate_keypair().unwrap();
+        let victim = crate::crypto::generate_keypair().unwrap().public_key().clone();
+
+        // La signature fixe l'émetteur : le nonce est vérifié sans `sender` explicite
+        let signed = create_signed_transfer(&sender, 0);
+        assert_eq!(signed.sender.as_ref(), Some(sender.public_key()));
+        let replayed = build_block(&genesis, 0, vec![signed.clone(), create_transfer_with_fee(&sender, 0, 11)]);
+        assert!(matches!(
+            blockchain.add_block(replayed),
+            Err(CoreError::Transaction(TransactionError::InvalidNonce { expected: 1, got: 0 }))
+        ));
+
+        // Un émetteur qui n'est pas la clé signataire ne consomme pas son nonce
+        let mut forged = signed.clone();
+        forged.sender = Some(victim.clone());
+        let mut unsigned = signed.clone();
+        unsigned.signature = crate::crypto::Signature::zero();
+        for transaction in [forged.clone(), unsigned] {
+            assert!(matches!(
+                blockchain.add_transaction(transaction),
+                Err(CoreError::Transaction(TransactionError::InvalidSignature))
+            ));
+        }
+        assert!(blockchain.add_block(build_block(&genesis, 0, vec![forged])).is_err());
+        assert_eq!(blockchain.next_nonce(&victim), 0);
+        assert_eq!(blockchain.height(), 1);
+    }
+
+    #[test]
+    fn test_mined_blocks_commit_state_snapshots() {
+        let config = BlockchainConfig { snapshot_interval: 2, ..BlockchainConfig::default() };
+        let mut blockchain = Blockchain::new(config).unwrap();
+        let sender = crate::crypto::generate_keypair().unwrap().public_key().clone();
+
+        blockchain.add_transaction(create_signed_transfer(&sender, 0)).unwrap();
+        let first = blockchain.mine_block().unwrap();
+        assert_eq!(first.header.snapshot_manifest, None);
+        blockchain.add_block(first).unwrap();
+
+        let block = blockchain.mine_block().unwrap();
+        let state_root = block.header.state_root.clone().expect("state root committed at height 2");
+        assert_eq!(state_root, blockchain.state.state_root(HashAlgorithm::Blake3));
+
+        // Un état engagé différent de l'état local est refusé
+        let mut forged = block.clone();
+        forged.header.state_root = Some(Hash::new([1u8; 32]));
+        forged.header.block_hash = forged.header.calculate_hash(HashAlgorithm::Blake3);
+        let error = blockchain.add_block(forged).unwrap_err().to_string();
+        assert!(error.contains("Racine d'état"), "{}", error);
+
+        blockchain.add_block(block.clone()).unwrap();
+        let snapshot = blockchain.committed_snapshot(2).expect("snapshot committed at height 2");
+        let (manifest, _) = SnapshotManifest::split(snapshot, 2, DEFAULT_SNAPSHOT_CHUNK_SIZE);
+        assert_eq!(block.header.snapshot_manifest, Some(manifest.manifest_hash()));
+        assert_eq!(manifest.state_root, state_root);
+        assert!(blockchain.committed_snapshot(1).is_none());
+    }
+
+    #[test]
+    fn test_receipts_carry_token_events_and_follow_reorgs() {
+        let sender = crate::crypto::generate_keypair().unwrap();
+        let recipient = crate::crypto::generate_keypair().unwrap();
+        let mut ledger = TokenLedger::default();
+        ledger.token.mint(sender.public_key(), 1_000, Hash::zero()).unwrap();
+        let mut blockchain = Blockchain::new(BlockchainConfig::default()).unwrap().with_token_ledger(ledger);
+        let genesis = blockchain.get_genesis_block().unwrap().clone();
+
+        let mut transfer = Transaction::token_transfer(sender.public_key().clone(), recipient.public_key().clone(), 700, 20, 0);
+        transfer.sign(sender.private_key()).unwrap();
+        let archive = create_transfer_with_fee(&recipient, 0, 25);
+        assert!(blockchain.get_receipt(transfer.hash()).is_none());
+
+        let main = build_block(&genesis, 0, vec![transfer.clone(), archive.clone()]);
+        blockchain.handle_fork(main.clone()).unwrap();
+
+        // Le reçu du transfert porte les événements émis par son exécution
+        let receipt = blockchain.get_receipt(transfer.hash()).unwrap();
+        assert_eq!(receipt.block_hash, *main.hash());
+        assert_eq!((receipt.block_height, receipt.index, receipt.gas_used), (1, 0, 20));
+        assert!(receipt.events.iter().all(|event| event.transaction_hash == *transfer.hash()));
+        assert_eq!(receipt.balance_change(sender.public_key()), -720);
+        assert_eq!(receipt.balance_change(recipient.public_key()), 700);
+
+        // Une transaction sans effet sur les soldes n'a ni frais prélevés ni événement
+        let receipt = blockchain.get_receipt(archive.hash()).unwrap();
+        assert_eq!((receipt.index, receipt.gas_used), (1, 0));
+        assert!(receipt.events.is_empty());
+
+        // Une branche plus lourde sans la transaction retire son reçu
+        let fork_1 = build_block(&genesis, 1, Vec::new());
+        blockchain.handle_fork(fork_1.clone()).unwrap();
+        blockchain.handle_fork(build_block(&fork_1, 1, Vec::new())).unwrap();
+        assert!(blockchain.get_receipt(transfer.hash()).is_none());
+    }
+
+    #[test]
+    fn test_confirmations_and_finality() {
+        let config = BlockchainConfig {
+            max_reorg_depth: 2,
+            ..BlockchainConfig::default()
+        };
+        let mut blockchain = Blockchain::new(config).unwrap();
+        let mut parent = blockchain.get_genesis_block().unwrap().clone();
+        assert_eq!(blockchain.confirmations(0), Some(0));
+        assert_eq!(blockchain.confirmations(1), None);
+
+        for _ in 0..3 {
+            let block = build_block(&parent, 0, Vec::new());
+            blockchain.add_block(block.clone()).unwrap();
+            parent = block;
+        }
+
+        assert_eq!(blockchain.confirmations(3), Some(0));
+        assert_eq!(blockchain.confirmations(1), Some(2));
+        assert!(!blockchain.is_final(2));
+        assert!(blockchain.is_final(1));
+
+        // Les en-têtes servis aux clients légers forment une chaîne vérifiable
+        let headers = blockchain.get_headers(0, 10);
+        assert_eq!(headers.len(), 4);
+        assert!(crate::block::verify_header_chain(&headers).is_ok());
+    }
+
+    #[test]
+    fn test_transaction_pool_survives_restart() {
+        let dir = tempfile::tempdir().unwrap();
+        let config = BlockchainConfig {
+            transaction_pool_path: Some(dir.path().join("pool.json").to_string_lossy().into_owned()),
+            ..BlockchainConfig::default()
+        };
+        let mut blockchain = Blockchain::new(config.clone()).unwrap();
+        for fee in [5, 40] {
+            let sender = crate::crypto::generate_keypair().unwrap();
+            blockchain.add_transaction(create_transfer_with_fee(&sender, 0, fee)).unwrap();
+        }
+
+        assert_eq!(blockchain.flush_transaction_pool().unwrap(), 2);
+
+        let restarted = Blockchain::new(config.clone()).unwrap();
+        assert_eq!(restarted.pending_transactions().len(), 2);
+        assert_eq!(restarted.pending_fee_potential(), 45);
+
+        // Le fichier est consommé : un second redémarrage ne rejoue rien
+        assert!(Blockchain::new(config.clone()).unwrap().pending_transactions().is_empty());
+
+        // Un fichier corrompu est mis de côté sans empêcher le démarrage
+        std::fs::write(dir.path().join("pool.json"), b"{not json").unwrap();
+        assert!(Blockchain::new(config).unwrap().pending_transactions().is_empty());
+        assert!(!dir.path().join("pool.json").exists());
+        assert!(dir.path().join("pool.corrupt").exists());
+    }
+
+    #[test]
+    fn test_added_blocks_are_notified() {
+        let mut blockchain = Blockchain::new(BlockchainConfig::default()).unwrap();
+        let mut receiver = blockchain.subscribe_blocks();
+        assert_eq!(blockchain.block_subscriber_count(), 1);
+
+        let block = blockchain.mine_block().unwrap();
+        blockchain.add_block(block.clone()).unwrap();
+
+        let notification = receiver.try_recv().unwrap();
+        assert_eq!(notification, BlockNotification::from(&block));
+        assert_eq!(notification.height, 1);
+        assert!(receiver.try_recv().is_err());
+
+        // Un bloc refusé n'est pas notifié
+        assert!(blockchain.add_block(block).is_err());
+        assert!(receiver.try_recv().is_err());
+
+        drop(receiver);
+        assert_eq!(blockchain.block_subscriber_count(), 0);
+    }
+}
@@ -5,10 +5,25 @@
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, VecDeque};
 use std::time::{Duration, SystemTime};
-use crate::crypto::{Hash, HashAlgorithm, compute_hash};
+use tokio::sync::mpsc;
+use crate::crypto::{Hash, HashAlgorithm, PublicKey, Signature, compute_hash, verify_signature};
 use crate::error::Result;
 use super::{NodeId, ConsensusConfig, ConsensusProof};
 
+/// Durée de la fenêtre glissante des transferts réels (secondes)
+const TRANSFER_WINDOW_SECS: i64 = 3600;
+
+/// Part maximale du volume de la fenêtre attribuable à un même pair
+///
+/// Empêche un nœud de gonfler son score en faisant boucler du trafic avec un complice.
+const MAX_SINGLE_PEER_SHARE: f64 = 0.25;
+
+/// Nombre maximum d'échantillons de transfert conservés par nœud
+const MAX_TRANSFER_SAMPLES_PER_NODE: usize = 10_000;
+
+/// Nombre maximum d'époques de corroboration conservées par registre de livraisons
+const MAX_DELIVERY_EPOCHS: usize = 1024;
+
 /// Gestionnaire des preuves de bande passante
 #[derive(Debug)]
 pub struct BandwidthProofManager {
@@ -22,6 +37,178 @@ pub struct BandwidthProofManager {
     performance_history: HashMap<NodeId, VecDeque<PerformanceMeasurement>>,
     /// Requêtes de téléchargement en cours
     download_requests: HashMap<Hash, DownloadRequest>,
+    /// Échantillons de transferts réels par nœud (fenêtre glissante)
+    transfer_samples: HashMap<NodeId, VecDeque<TransferSample>>,
+    /// Canal alimenté par les `BandwidthReporter` des nœuds
+    sample_sender: mpsc::UnboundedSender<TransferSample>,
+    sample_receiver: mpsc::UnboundedReceiver<TransferSample>,
+    /// Livraisons de contenu par nœud, base des récompenses de bande passante
+    delivery_ledgers: HashMap<NodeId, DeliveryLedger>,
+    /// Pairs enregistrés et leur clé : seuls destinataires de livraisons
+    /// rémunérables et signataires des preuves de transfert
+    registered_peers: HashMap<NodeId, PublicKey>,
+}
+
+/// Échantillon d'un transfert réel effectué par un nœud
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransferSample {
+    /// Nœud ayant effectué le transfert
+    pub node_id: NodeId,
+    /// Pair à l'autre extrémité du transfert
+    pub peer: NodeId,
+    /// Volume transféré (bytes)
+    pub bytes: u64,
+    /// Durée du transfert
+    pub duration: Duration,
+    /// Direction du point de vue de `node_id`
+    pub direction: TransferDirection,
+    /// Date de fin du transfert
+    pub recorded_at: chrono::DateTime<chrono::Utc>,
+    /// Contenu livré, si le transfert sert une archive
+    #[serde(default)]
+    pub content_hash: Option<Hash>,
+}
+
+/// Livraisons de contenu d'un nœud
+///
+/// Les volumes livrés à des pairs enregistrés restent en attente jusqu'à ce
+/// que le nœud réussisse un défi de bande passante ; chaque défi réussi clôt
+/// une époque, seule unité réclamable.
+#[derive(Debug, Clone)]
+pub struct DeliveryLedger {
+    /// Nœud livreur
+    pub node_id: NodeId,
+    /// Début du mois en cours
+    pub period_start: chrono::DateTime<chrono::Utc>,
+    /// Volume livré ce mois (bytes)
+    pub bytes_served: u64,
+    /// Volume livré ce mois par contenu (bytes)
+    pub bytes_per_content: HashMap<Hash, u64>,
+    /// Volume en attente de corroboration, par pair destinataire
+    pub pending_per_peer: HashMap<NodeId, u64>,
+    /// Volumes corroborés, des plus anciens aux plus récents
+    pub epochs: VecDeque<DeliveryEpoch>,
+}
+
+/// Volume corroboré par un défi de bande passante réussi
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeliveryEpoch {
+    /// Identifiant du défi ayant corroboré le volume
+    pub epoch_id: Hash,
+    /// Volume retenu (bytes)
+    pub bytes: u64,
+    pub corroborated_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl DeliveryLedger {
+    fn new(node_id: NodeId, at: chrono::DateTime<chrono::Utc>) -> Self {
+        Self {
+            node_id,
+            period_start: month_start(at),
+            bytes_served: 0,
+            bytes_per_content: HashMap::new(),
+            pending_per_peer: HashMap::new(),
+            epochs: VecDeque::new(),
+        }
+    }
+
+    /// Volume en attente de corroboration (bytes)
+    pub fn pending_bytes(&self) -> u64 {
+        self.pending_per_peer.values().sum()
+    }
+
+    /// Volume corroboré des époques conservées (bytes)
+    pub fn verified_bytes(&self) -> u64 {
+        self.epochs.iter().map(|epoch| epoch.bytes).sum()
+    }
+
+    fn record(&mut self, peer: NodeId, content_hash: Hash, bytes: u64, at: chrono::DateTime<chrono::Utc>) {
+        let period_start = month_start(at);
+        if period_start > self.period_start {
+            // Nouveau mois : seuls les compteurs de la période repartent de zéro
+            self.period_start = period_start;
+            self.bytes_served = 0;
+            self.bytes_per_content.clear();
+        }
+
+        self.bytes_served += bytes;
+        *self.bytes_per_content.entry(content_hash).or_insert(0) += bytes;
+        *self.pending_per_peer.entry(peer).or_insert(0) += bytes;
+    }
+
+    /// Corrobore les livraisons en attente au titre du défi `epoch_id`
+    ///
+    /// Comme pour les débits, la part d'un même pair est plafonnée à
+    /// `MAX_SINGLE_PEER_SHARE` du volume en attente. Retourne le volume retenu.
+    fn corroborate(&mut self, epoch_id: Hash, at: chrono::DateTime<chrono::Utc>) -> u64 {
+        let pending = self.pending_bytes();
+        let peer_cap = (pending as f64 * MAX_SINGLE_PEER_SHARE) as u64;
+        let counted: u64 = self.pending_per_peer.drain().map(|(_, bytes)| bytes.min(peer_cap)).sum();
+        if counted > 0 {
+            self.epochs.push_back(DeliveryEpoch { epoch_id, bytes: counted, corroborated_at: at });
+            if self.epochs.len() > MAX_DELIVERY_EPOCHS {
+                self.epochs.pop_front();
+            }
+        }
+        counted
+    }
+}
+
+/// Début du mois calendaire (UTC) contenant `at`
+pub(crate) fn month_start(at: chrono::DateTime<chrono::Utc>) -> chrono::DateTime<chrono::Utc> {
+    use chrono::{Datelike, TimeZone};
+    chrono::Utc.with_ymd_and_hms(at.year(), at.month(), 1, 0, 0, 0).single().unwrap_or(at)
+}
+
+/// Poignée permettant à un nœud de remonter ses transferts réels au consensus
+///
+/// Les échantillons sont agrégés par le `BandwidthProofManager` émetteur lors de
+/// son prochain calcul de score.
+#[derive(Debug, Clone)]
+pub struct BandwidthReporter {
+    node_id: NodeId,
+    sender: mpsc::UnboundedSender<TransferSample>,
+}
+
+impl BandwidthReporter {
+    /// Signale un transfert de `bytes` octets avec `peer` ayant duré `duration`
+    pub fn report(&self, peer: NodeId, bytes: u64, duration: Duration, direction: TransferDirection) {
+        let sample = TransferSample {
+            node_id: self.node_id.clone(),
+            peer,
+            bytes,
+            duration,
+            direction,
+            recorded_at: chrono::Utc::now(),
+            content_hash: None,
+        };
+
+        // Le gestionnaire peut avoir été arrêté : l'échantillon est alors perdu
+        let _ = self.sender.send(sample);
+    }
+
+    /// Signale la livraison de `bytes` octets du contenu `content_hash` à `peer`
+    ///
+    /// Compte comme un upload et alimente le registre des livraisons
+    /// rémunérables du nœud.
+    pub fn report_delivery(&self, peer: NodeId, content_hash: Hash, bytes: u64, duration: Duration) {
+        let sample = TransferSample {
+            node_id: self.node_id.clone(),
+            peer,
+            bytes,
+            duration,
+            direction: TransferDirection::Upload,
+            recorded_at: chrono::Utc::now(),
+            content_hash: Some(content_hash),
+        };
+
+        let _ = self.sender.send(sample);
+    }
+
+    /// Nœud pour lequel les transferts sont signalés
+    pub fn node_id(&self) -> &NodeId {
+        &self.node_id
+    }
 }
 
 /// Métriques de bande passante pour un nœud
@@ -149,10 +336,31 @@ pub struct TransferProof {
     pub peer_node: NodeId,
     /// Direction du transfert
     pub direction: TransferDirection,
+    /// Signature du pair sur `signing_bytes`, attestant le transfert
+    pub peer_signature: Signature,
+}
+
+impl TransferProof {
+    /// Données signées par le pair pour le test `test_id`
+    pub fn signing_bytes(&self, test_id: &Hash) -> Vec<u8> {
+        let direction: u8 = match self.direction {
+            TransferDirection::Upload => 0,
+            TransferDirection::Download => 1,
+        };
+        [
+            test_id.as_bytes().as_slice(),
+            self.data_hash.as_bytes(),
+            &self.size_bytes.to_le_bytes(),
+            &self.start_time.timestamp_millis().to_le_bytes(),
+            &self.end_time.timestamp_millis().to_le_bytes(),
+            self.peer_node.hash().as_bytes(),
+            &[direction],
+        ].concat()
+    }
 }
 
 /// Direction d'un transfert
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 pub enum TransferDirection {
     /// Upload vers le pair
     Upload,
@@ -178,15 +386,122 @@ pub struct BandwidthScore {
 impl BandwidthProofManager {
     /// Crée un nouveau gestionnaire de preuves de bande passante
     pub fn new(config: &ConsensusConfig) -> Self {
+        let (sample_sender, sample_receiver) = mpsc::unbounded_channel();
+
         Self {
             config: config.clone(),
             node_metrics: HashMap::new(),
             active_tests: HashMap::new(),
             performance_history: HashMap::new(),
             download_requests: HashMap::new(),
+            transfer_samples: HashMap::new(),
+            sample_sender,
+            sample_receiver,
+            delivery_ledgers: HashMap::new(),
+            registered_peers: HashMap::new(),
         }
     }
 
+    /// Enregistre un pair du réseau ; retourne son identifiant
+    ///
+    /// Seules les livraisons à des pairs enregistrés sont rémunérables, et
+    /// seuls ces pairs signent les preuves de transfert des défis.
+    pub fn register_peer(&mut self, public_key: PublicKey) -> NodeId {
+        let node_id = NodeId::from_public_key(&public_key);
+        self.registered_peers.insert(node_id.clone(), public_key);
+        node_id
+    }
+
+    /// Crée une poignée de remontée des transferts pour un nœud
+    pub fn reporter(&self, node_id: NodeId) -> BandwidthReporter {
+        BandwidthReporter {
+            node_id,
+            sender: self.sample_sender.clone(),
+        }
+    }
+
+    /// Intègre les transferts signalés par les `BandwidthReporter` ; retourne leur nombre
+    pub fn ingest_reported_transfers(&mut self) -> usize {
+        let mut ingested = 0;
+        while let Ok(sample) = self.sample_receiver.try_recv() {
+            self.record_transfer(sample);
+            ingested += 1;
+        }
+        ingested
+    }
+
+    /// Enregistre un transfert réel et recalcule les débits de la fenêtre glissante
+    pub fn record_transfer(&mut self, sample: TransferSample) {
+        let node_id = sample.node_id.clone();
+
+        let metrics = self.node_metrics.entry(node_id.clone()).or_insert_with(|| {
+            BandwidthMetrics {
+                node_id: node_id.clone(),
+                avg_upload_bandwidth: 0,
+                avg_download_bandwidth: 0,
+                avg_latency_ms: 0,
+                downloads_served: 0,
+                total_bytes_served: 0,
+                availability_rate: 1.0,
+                qos_score: 1.0,
+                last_measurement: None,
+                updated_at: chrono::Utc::now(),
+            }
+        });
+
+        if sample.direction == TransferDirection::Upload {
+            metrics.downloads_served += 1;
+            metrics.total_bytes_served += sample.bytes;
+
+            // Un identifiant de pair arbitraire contournerait le plafond par pair
+            let registered = self.registered_peers.contains_key(&sample.peer);
+            if let (Some(content_hash), true) = (&sample.content_hash, registered) {
+                self.delivery_ledgers
+                    .entry(node_id.clone())
+                    .or_insert_with(|| DeliveryLedger::new(node_id.clone(), sample.recorded_at))
+                    .record(sample.peer.clone(), content_hash.clone(), sample.bytes, sample.recorded_at);
+            }
+        }
+        metrics.last_measurement = Some(sample.recorded_at);
+
+        let samples = self.transfer_samples.entry(node_id.clone()).or_insert_with(VecDeque::new);
+        samples.push_back(sample);
+        if samples.len() > MAX_TRANSFER_SAMPLES_PER_NODE {
+            samples.pop_front();
+        }
+
+        self.refresh_transfer_metrics(&node_id, chrono::Utc::now());
+    }
+
+    /// Débit (bytes/sec) observé sur la fenêtre glissante dans une direction
+    ///
+    /// Le volume attribué à chaque pair est plafonné à `MAX_SINGLE_PEER_SHARE`
+    /// du volume total de la fenêtre.
+    pub fn window_throughput(&self, node_id: &NodeId, direction: TransferDirection, now: chrono::DateTime<chrono::Utc>) -> u64 {
+        let cutoff = now - chrono::Duration::seconds(TRANSFER_WINDOW_SECS);
+        let Some(samples) = self.transfer_samples.get(node_id) else { return 0 };
+
+        let mut bytes_per_peer: HashMap<&NodeId, u64> = HashMap::new();
+        let mut total_bytes = 0u64;
+        let mut total_duration = Duration::ZERO;
+
+        for sample in samples.iter().filter(|s| s.direction == direction && s.recorded_at > cutoff) {
+            *bytes_per_peer.entry(&sample.peer).or_insert(0) += sample.bytes;
+            total_bytes += sample.bytes;
+            total_duration += sample.duration;
+        }
+
+        if total_bytes == 0 {
+            return 0;
+        }
+
+        let peer_cap = (total_bytes as f64 * MAX_SINGLE_PEER_SHARE) as u64;
+        let counted_bytes: u64 = bytes_per_peer.values().map(|&bytes| bytes.min(peer_cap)).sum();
+        let seconds = total_duration.as_secs_f64().max(0.001);
+
+        (counted_bytes as f64 / seconds) as u64
+    }
+
     /// Enregistre une nouvelle mesure de performance pour un nœud
     pub fn record_performance(&mut self, node_id: NodeId, measurement: PerformanceMeasurement) {
         // Met à jour les métriques du nœud
@@ -260,33 +575,26 @@ impl BandwidthProofManager {
     }
 
     /// Vérifie une réponse à un test de bande passante
+    ///
+    /// Le test doit être le test actif du nœud ; il est consommé, si bien qu'une
+    /// même réponse ne corrobore qu'une fois. Un défi réussi corrobore les
+    /// livraisons en attente du nœud ; un défi échoué les écarte définitivement
+    /// des récompenses.
     pub fn verify_bandwidth_response(
         &mut self,
         test: &BandwidthTest,
         response: &BandwidthTestResponse,
     ) -> Result<bool> {
-        // Vérifie que la réponse correspond au test
-        if response.test_id != test.test_id {
-            return Ok(false);
-        }
-
-        // Vérifie que la réponse n'est pas expirée
-        if chrono::Utc::now() > test.expires_at {
-            return Ok(false);
-        }
-
-        // Vérifie les preuves de transfert
-        for proof in &response.proof_data {
-            if !self.verify_transfer_proof(proof, test)? {
-                return Ok(false);
-            }
+        let active = self.active_tests.get(&test.node_id).is_some_and(|active| active.test_id == test.test_id);
+        if active {
+            self.active_tests.remove(&test.node_id);
         }
 
-        // Vérifie la cohérence des mesures de performance
-        for measurement in &response.performance_results {
-            if !self.verify_performance_measurement(measurement, test)? {
-                return Ok(false);
+        if !active || !self.is_valid_bandwidth_response(test, response)? {
+            if let Some(ledger) = self.delivery_ledgers.get_mut(&test.node_id) {
+                ledger.pending_per_peer.clear();
             }
+            return Ok(false);
         }
 
         // Enregistre les mesures validées
@@ -294,9 +602,18 @@ impl BandwidthProofManager {
             self.record_performance(test.node_id.clone(), measurement.clone());
         }
 
+        if let Some(ledger) = self.delivery_ledgers.get_mut(&test.node_id) {
+            ledger.corroborate(test.test_id.clone(), response.responded_at);
+        }
+
         Ok(true)
     }
 
+    /// Livraisons de contenu enregistrées pour un nœud
+    pub fn delivery_ledger(&self, node_id: &NodeId) -> Option<&DeliveryLedger> {
+        self.delivery_ledgers.get(node_id)
+    }
+
     /// Calcule le score de bande passante pour un nœud
     pub fn calculate_bandwidth_score(&self, node_id: &NodeId) -> Result<BandwidthScore> {
         let metrics = self.get_node_metrics(node_id)?;
@@ -357,10 +674,67 @@ impl BandwidthProofManager {
         let now = chrono::Utc::now();
         self.active_tests.retain(|_, test| test.expires_at > now);
         self.download_requests.retain(|_, request| request.expires_at > now);
+
+        // Fait sortir de la fenêtre les transferts trop anciens
+        let nodes: Vec<NodeId> = self.transfer_samples.keys().cloned().collect();
+        for node_id in nodes {
+            self.refresh_transfer_metrics(&node_id, now);
+        }
     }
 
     // Méthodes privées
 
+    fn is_valid_bandwidth_response(&self, test: &BandwidthTest, response: &BandwidthTestResponse) -> Result<bool> {
+        // Vérifie que la réponse correspond au test
+        if response.test_id != test.test_id {
+            return Ok(false);
+        }
+
+        // Vérifie que la réponse n'est pas expirée
+        if chrono::Utc::now() > test.expires_at {
+            return Ok(false);
+        }
+
+        // Sans preuve signée par un pair, rien n'atteste le transfert
+        if response.proof_data.is_empty() {
+            return Ok(false);
+        }
+
+        // Vérifie les preuves de transfert
+        for proof in &response.proof_data {
+            if !self.verify_transfer_proof(proof, test)? {
+                return Ok(false);
+            }
+        }
+
+        // Vérifie la cohérence des mesures de performance
+        for measurement in &response.performance_results {
+            if !self.verify_performance_measurement(measurement, test)? {
+                return Ok(false);
+            }
+        }
+
+        Ok(true)
+    }
+
+    fn refresh_transfer_metrics(&mut self, node_id: &NodeId, now: chrono::DateTime<chrono::Utc>) {
+        let cutoff = now - chrono::Duration::seconds(TRANSFER_WINDOW_SECS);
+        if let Some(samples) = self.transfer_samples.get_mut(node_id) {
+            while samples.front().map_or(false, |s| s.recorded_at <= cutoff) {
+                samples.pop_front();
+            }
+        }
+
+        let upload = self.window_throughput(node_id, TransferDirection::Upload, now);
+        let download = self.window_throughput(node_id, TransferDirection::Download, now);
+
+        if let Some(metrics) = self.node_metrics.get_mut(node_id) {
+            metrics.avg_upload_bandwidth = upload;
+            metrics.avg_download_bandwidth = download;
+            metrics.updated_at = chrono::Utc::now();
+        }
+    }
+
     fn update_average_metrics(&mut self, node_id: &NodeId) {
         if let Some(history) = self.performance_history.get(node_id) {
             if let Some(metrics) = self.node_metrics.get_mut(node_id) {
@@ -397,10 +771,11 @@ impl BandwidthProofManager {
         }
     }
 
-    fn select_peer_nodes_for_test(&self, _node_id: &NodeId, count: usize) -> Vec<NodeId> {
-        // Sélectionne des nœuds aléatoires pour le test
+    fn select_peer_nodes_for_test(&self, node_id: &NodeId, count: usize) -> Vec<NodeId> {
+        // Seuls des pairs enregistrés, capables de signer leurs preuves, sont retenus
         // Dans une implémentation réelle, on sélectionnerait des nœuds proches géographiquement
-        self.node_metrics.keys()
+        self.registered_peers.keys()
+            .filter(|peer| *peer != node_id)
             .take(count)
             .cloned()
             .collect()
@@ -412,6 +787,17 @@ impl BandwidthProofManager {
             return Ok(false);
         }
 
+        // Les données transférées sont celles du test, attestées par le pair
+        if proof.data_hash != test.test_data_hash {
+            return Ok(false);
+        }
+        let Some(peer_key) = self.registered_peers.get(&proof.peer_node) else {
+            return Ok(false);
+        };
+        if !verify_signature(&proof.signing_bytes(&test.test_id), &proof.peer_signature, peer_key)? {
+            return Ok(false);
+        }
+
         // Vérifie que le timing est cohérent
         if proof.end_time <= proof.start_time {
             return Ok(false);
@@ -561,4 +947,192 @@ mod tests {
         assert!(score.combined_score <= 1.0);
         assert!(score.upload_score > 0.0);
     }
-}
\ No newline at end of file
+
+    fn node(seed: u8) -> NodeId {
+        NodeId::from(Hash::from_bytes_array([seed; 32]))
+    }
+
+    fn upload(node_id: &NodeId, peer: &NodeId, bytes: u64) -> TransferSample {
+        TransferSample {
+            node_id: node_id.clone(),
+            peer: peer.clone(),
+            bytes,
+            duration: Duration::from_secs(1),
+            direction: TransferDirection::Upload,
+            recorded_at: chrono::Utc::now(),
+            content_hash: None,
+        }
+    }
+
+    #[test]
+    fn test_transfer_score_saturates() {
+        let config = ConsensusConfig::test_config();
+        let mut manager = BandwidthProofManager::new(&config);
+        let node_id = node(1);
+        let peers: Vec<NodeId> = (10..14).map(node).collect();
+
+        // 256 B/s répartis sur quatre pairs : un quart du seuil minimum
+        for peer in &peers {
+            manager.record_transfer(upload(&node_id, peer, 256));
+        }
+        let score = manager.calculate_bandwidth_score(&node_id).unwrap();
+        assert!((score.upload_score - 0.25).abs() < 1e-9);
+
+        // Au-delà du seuil, le score plafonne à 1.0
+        for peer in &peers {
+            for _ in 0..10 {
+                manager.record_transfer(upload(&node_id, peer, 64 * 1024));
+            }
+        }
+        let score = manager.calculate_bandwidth_score(&node_id).unwrap();
+        assert_eq!(score.upload_score, 1.0);
+        assert!(score.combined_score <= 1.0);
+
+        let metrics = manager.get_node_metrics(&node_id).unwrap();
+        assert_eq!(metrics.downloads_served, 44);
+    }
+
+    #[test]
+    fn test_single_peer_share_is_capped() {
+        let config = ConsensusConfig::test_config();
+        let mut manager = BandwidthProofManager::new(&config);
+        let looping = node(1);
+        let honest = node(2);
+        let friend = node(3);
+        let peers: Vec<NodeId> = (10..14).map(node).collect();
+
+        // Même volume (2 KB/s pendant 8 s), avec un seul pair ou réparti sur quatre
+        for i in 0..8 {
+            manager.record_transfer(upload(&looping, &friend, 2048));
+            manager.record_transfer(upload(&honest, &peers[i % peers.len()], 2048));
+        }
+
+        let now = chrono::Utc::now();
+        assert_eq!(manager.window_throughput(&looping, TransferDirection::Upload, now), 512);
+        assert_eq!(manager.window_throughput(&honest, TransferDirection::Upload, now), 2048);
+
+        let looping_score = manager.calculate_bandwidth_score(&looping).unwrap();
+        let honest_score = manager.calculate_bandwidth_score(&honest).unwrap();
+        assert!((looping_score.upload_score - 0.5).abs() < 1e-9);
+        assert_eq!(honest_score.upload_score, 1.0);
+    }
+
+    #[test]
+    fn test_reported_transfers_are_ingested() {
+        let config = ConsensusConfig::test_config();
+        let mut manager = BandwidthProofManager::new(&config);
+        let node_id = node(1);
+
+        let reporter = manager.reporter(node_id.clone());
+        reporter.report(node(2), 4096, Duration::from_millis(500), TransferDirection::Upload);
+        reporter.report(node(3), 1024, Duration::from_millis(500), TransferDirection::Download);
+
+        assert_eq!(manager.ingest_reported_transfers(), 2);
+        assert_eq!(manager.ingest_reported_transfers(), 0);
+
+        let metrics = manager.get_node_metrics(&node_id).unwrap();
+        assert_eq!(metrics.total_bytes_served, 4096);
+        assert_eq!(metrics.downloads_served, 1);
+    }
+
+    #[test]
+    fn test_deliveries_require_bandwidth_challenge() {
+        use crate::crypto::{KeyPair, Signer};
+
+        let config = ConsensusConfig::test_config();
+        let mut manager = BandwidthProofManager::new(&config);
+        let node_id = node(1);
+        let content = Hash::from_bytes_array([42; 32]);
+        let peer_keys: HashMap<NodeId, KeyPair> = (0..4)
+            .map(|_| {
+                let keypair = generate_keypair().unwrap();
+                (manager.register_peer(keypair.public_key().clone()), keypair)
+            })
+            .collect();
+        let peers: Vec<NodeId> = peer_keys.keys().cloned().collect();
+
+        let reporter = manager.reporter(node_id.clone());
+        for peer in &peers {
+            reporter.report_delivery(peer.clone(), content.clone(), 1000, Duration::from_millis(100));
+        }
+        // Pair inconnu ou transfert hors livraison de contenu : non rémunérables
+        reporter.report_delivery(node(20), content.clone(), 5000, Duration::from_millis(100));
+        reporter.report(peers[0].clone(), 5000, Duration::from_millis(100), TransferDirection::Upload);
+        manager.ingest_reported_transfers();
+
+        let ledger = manager.delivery_ledger(&node_id).unwrap();
+        assert_eq!(ledger.bytes_served, 4000);
+        assert_eq!(ledger.bytes_per_content.get(&content), Some(&4000));
+        assert_eq!(ledger.pending_bytes(), 4000);
+        assert_eq!(ledger.verified_bytes(), 0);
+
+        // Chaque pair du test signe sa preuve de transfert
+        let respond = |test: &BandwidthTest, signer: Option<&KeyPair>| {
+            let proof_data = test.peer_nodes.iter()
+                .map(|peer| {
+                    let mut proof = TransferProof {
+                        data_hash: test.test_data_hash.clone(),
+                        size_bytes: test.test_data_size,
+                        start_time: test.started_at,
+                        end_time: test.started_at + chrono::Duration::milliseconds(200),
+                        peer_node: peer.clone(),
+                        direction: TransferDirection::Upload,
+                        peer_signature: Signature::zero(),
+                    };
+                    let signer = signer.unwrap_or(&peer_keys[peer]);
+                    proof.peer_signature = signer.sign(&proof.signing_bytes(&test.test_id)).unwrap();
+                    proof
+                })
+                .collect();
+            BandwidthTestResponse {
+                test_id: test.test_id.clone(),
+                performance_results: Vec::new(),
+                proof_data,
+                responded_at: chrono::Utc::now(),
+            }
+        };
+
+        // Défi réussi : les livraisons en attente forment une époque
+        let test = manager.generate_bandwidth_test(&node_id, BandwidthTestType::Upload).unwrap();
+        assert_eq!(test.peer_nodes.len(), 3);
+        let response = respond(&test, None);
+        assert!(manager.verify_bandwidth_response(&test, &response).unwrap());
+        let ledger = manager.delivery_ledger(&node_id).unwrap();
+        assert_eq!(ledger.pending_bytes(), 0);
+        assert_eq!(ledger.verified_bytes(), 4000);
+        assert_eq!(ledger.epochs.back().map(|epoch| &epoch.epoch_id), Some(&test.test_id));
+        // Le test est consommé : la réponse ne se rejoue pas
+        assert!(!manager.verify_bandwidth_response(&test, &response).unwrap());
+
+        // Livraisons en boucle vers un complice : part plafonnée
+        for _ in 0..4 {
+            reporter.report_delivery(peers[0].clone(), content.clone(), 1000, Duration::from_millis(100));
+        }
+        manager.ingest_reported_transfers();
+        let test = manager.generate_bandwidth_test(&node_id, BandwidthTestType::Upload).unwrap();
+        assert!(manager.verify_bandwidth_response(&test, &respond(&test, None)).unwrap());
+        assert_eq!(manager.delivery_ledger(&node_id).unwrap().verified_bytes(), 5000);
+
+        // Réponse sans preuve : les livraisons en attente sont écartées
+        reporter.report_delivery(peers[1].clone(), content.clone(), 1000, Duration::from_millis(100));
+        manager.ingest_reported_transfers();
+        let test = manager.generate_bandwidth_test(&node_id, BandwidthTestType::Upload).unwrap();
+        let mut empty = respond(&test, None);
+        empty.proof_data.clear();
+        assert!(!manager.verify_bandwidth_response(&test, &empty).unwrap());
+        let ledger = manager.delivery_ledger(&node_id).unwrap();
+        assert_eq!(ledger.pending_bytes(), 0);
+        assert_eq!(ledger.verified_bytes(), 5000);
+
+        // Preuves signées par une clé étrangère aux pairs : refusées
+        reporter.report_delivery(peers[1].clone(), content, 1000, Duration::from_millis(100));
+        manager.ingest_reported_transfers();
+        let test = manager.generate_bandwidth_test(&node_id, BandwidthTestType::Upload).unwrap();
+        let forger = generate_keypair().unwrap();
+        assert!(!manager.verify_bandwidth_response(&test, &respond(&test, Some(&forger))).unwrap());
+        let ledger = manager.delivery_ledger(&node_id).unwrap();
+        assert_eq!(ledger.verified_bytes(), 5000);
+        assert_eq!(ledger.epochs.len(), 2);
+        assert_eq!(ledger.bytes_served, 10_000);
+    }
+}
@@ -1,12 +1,20 @@
 //! Système de sélection des leaders pour ArchiveChain
 //! 
 //! Algorithme équitable pour sélectionner les validateurs basé sur les scores PoA
+//!
+//! Le leader de chaque round est tiré de façon vérifiable : le seed est
+//! `hash(hash du bloc précédent ‖ numéro de round)` et chaque candidat a une
+//! probabilité proportionnelle à son stake de validateur. Un plafond
+//! d'équité limite le nombre de rounds menés par un même nœud sur une
+//! fenêtre glissante de blocs. Les entrées du tirage ([`ElectionInputs`])
+//! sont toutes dérivées de la chaîne : tout observateur les reconstruit et
+//! recalcule le tirage (voir [`LeaderElectionResult::verify`]).
 
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, BTreeMap};
-use crate::crypto::{Hash, HashAlgorithm, compute_hash, compute_combined_hash};
+use crate::crypto::{Hash, HashAlgorithm, PublicKey, compute_hash, compute_combined_hash};
 use crate::error::Result;
-use super::{NodeId, ConsensusScore, ConsensusConfig};
+use super::{NodeId, ConsensusScore, ConsensusConfig, ProofOfArchive};
 
 /// Sélecteur de leaders pour le consensus
 #[derive(Debug)]
@@ -32,6 +40,9 @@ pub struct ValidatorInfo {
     pub consensus_score: ConsensusScore,
     /// Stake du validateur (pour la sélection pondérée)
     pub stake_amount: u64,
+    /// Poids de consensus déclaré par le type du nœud
+    #[serde(default = "default_declared_weight")]
+    pub declared_weight: f64,
     /// Nombre de blocs validés récemment
     pub recent_validations: u32,
     /// Taux de participation (0.0 - 1.0)
@@ -48,6 +59,17 @@ pub struct ValidatorInfo {
     pub updated_at: chrono::DateTime<chrono::Utc>,
 }
 
+fn default_declared_weight() -> f64 {
+    1.0
+}
+
+impl ValidatorInfo {
+    /// Poids effectif : poids déclaré pondéré par le score de consensus courant
+    pub fn effective_weight(&self) -> f64 {
+        self.declared_weight * self.consensus_score.combined_score
+    }
+}
+
 /// Statut d'éligibilité d'un validateur
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum EligibilityStatus {
@@ -91,6 +113,108 @@ pub struct LeaderElectionResult {
     pub selected_at: chrono::DateTime<chrono::Utc>,
     /// Métriques de diversité
     pub diversity_metrics: DiversityMetrics,
+    /// Candidats et poids du tirage, triés par identifiant
+    #[serde(default)]
+    pub candidate_weights: Vec<CandidateWeight>,
+    /// Candidats tirés avant le leader mais écartés par le plafond d'équité
+    #[serde(default)]
+    pub fairness_skipped: Vec<NodeId>,
+}
+
+impl LeaderElectionResult {
+    /// Vérifie une élection de round contre les entrées reconstruites par le vérificateur
+    ///
+    /// Les poids et les candidats écartés publiés ne sont pas crus : `inputs`
+    /// doit provenir de la chaîne du vérificateur (voir
+    /// [`ElectionInputs::new`]), et le seed, l'ordre de tirage, le leader, les
+    /// secours et les écartés sont recalculés puis comparés au résultat.
+    pub fn verify(&self, previous_block_hash: &Hash, inputs: &ElectionInputs, config: &ConsensusConfig) -> bool {
+        let selection_seed = LeaderSelector::round_seed(previous_block_hash, self.epoch);
+        if selection_seed != self.selection_seed || self.candidate_weights != inputs.candidate_weights {
+            return false;
+        }
+        let Some(ranking) = LeaderSelector::rank_round(config, &selection_seed, inputs) else {
+            return false;
+        };
+        ranking.order == self.validators
+            && ranking.primary_leader == self.primary_leader
+            && ranking.backup_leaders == self.backup_leaders
+            && ranking.fairness_skipped == self.fairness_skipped
+    }
+
+    /// Indique si `node_id` peut produire le bloc de ce round (leader ou secours)
+    pub fn is_elected(&self, node_id: &NodeId) -> bool {
+        self.primary_leader == *node_id || self.backup_leaders.contains(node_id)
+    }
+}
+
+/// Source des stakes de validateurs consultée par l'élection des leaders
+pub trait ValidatorStakes: Send + Sync {
+    /// Validateurs éligibles à la date `at` et leur stake total
+    fn leader_candidates(&self, at: chrono::DateTime<chrono::Utc>) -> Vec<(PublicKey, u64)>;
+}
+
+impl<T: ValidatorStakes> ValidatorStakes for std::sync::RwLock<T> {
+    fn leader_candidates(&self, at: chrono::DateTime<chrono::Utc>) -> Vec<(PublicKey, u64)> {
+        match self.read() {
+            Ok(stakes) => stakes.leader_candidates(at),
+            Err(poisoned) => poisoned.into_inner().leader_candidates(at),
+        }
+    }
+}
+
+/// Entrées d'une élection de round, dérivées de la chaîne
+///
+/// Le producteur et chaque vérificateur les construisent depuis le même
+/// état : les stakes de validateurs à la date du bloc parent et les
+/// producteurs signataires des blocs de la fenêtre d'équité.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ElectionInputs {
+    /// Candidats pondérés par leur stake, triés par identifiant
+    pub candidate_weights: Vec<CandidateWeight>,
+    /// Blocs produits par chaque nœud sur la fenêtre d'équité
+    pub recent_leaders: HashMap<NodeId, u32>,
+}
+
+impl ElectionInputs {
+    /// Construit les entrées à partir des stakes et des producteurs récents
+    ///
+    /// Les candidats sans stake sont ignorés ; l'ordre est canonique,
+    /// indépendant de l'ordre de la source.
+    pub fn new(candidates: Vec<(PublicKey, u64)>, recent_producers: impl IntoIterator<Item = PublicKey>) -> Self {
+        let mut candidate_weights: Vec<CandidateWeight> = candidates.into_iter()
+            .filter(|(_, stake)| *stake > 0)
+            .map(|(validator, stake)| CandidateWeight {
+                node_id: NodeId::from_public_key(&validator),
+                weight: stake as f64,
+            })
+            .collect();
+        candidate_weights.sort_by(|a, b| a.node_id.hash().cmp(b.node_id.hash()));
+
+        let mut recent_leaders = HashMap::new();
+        for producer in recent_producers {
+            *recent_leaders.entry(NodeId::from_public_key(&producer)).or_insert(0) += 1;
+        }
+
+        Self { candidate_weights, recent_leaders }
+    }
+}
+
+/// Classement d'un round : ordre de tirage et application du plafond d'équité
+struct RoundRanking {
+    order: Vec<NodeId>,
+    primary_leader: NodeId,
+    backup_leaders: Vec<NodeId>,
+    fairness_skipped: Vec<NodeId>,
+}
+
+/// Poids d'un candidat dans un tirage
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct CandidateWeight {
+    /// Identifiant du candidat
+    pub node_id: NodeId,
+    /// Poids de tirage (les poids négatifs comptent pour zéro)
+    pub weight: f64,
 }
 
 /// Métriques de diversité de la sélection
@@ -144,6 +268,7 @@ impl LeaderSelector {
             node_id: node_id.clone(),
             consensus_score: initial_score,
             stake_amount: 0, // À définir selon le modèle économique
+            declared_weight: default_declared_weight(),
             recent_validations: 0,
             participation_rate: 1.0,
             last_selected_epoch: None,
@@ -173,8 +298,39 @@ impl LeaderSelector {
         Ok(())
     }
 
+    /// Recalcule les scores et poids déclarés de tous les validateurs
+    ///
+    /// Les validateurs dont le moteur de consensus n'a pas de métriques
+    /// conservent leur score. Retourne le nombre de validateurs mis à jour.
+    fn refresh_weights(&mut self, consensus: &mut ProofOfArchive) -> Result<usize> {
+        let node_ids: Vec<NodeId> = self.validator_pool.keys().cloned().collect();
+        let mut refreshed = 0;
+
+        for node_id in node_ids {
+            let score = match consensus.calculate_consensus_score(&node_id) {
+                Ok(score) => score,
+                Err(e) => {
+                    tracing::warn!("Score indisponible pour le validateur {:?}: {}", node_id, e);
+                    continue;
+                }
+            };
+            if let Some(validator) = self.validator_pool.get_mut(&node_id) {
+                validator.declared_weight = consensus.declared_weight(&node_id);
+            }
+            self.update_validator_score(&node_id, score)?;
+            refreshed += 1;
+        }
+
+        Ok(refreshed)
+    }
+
     /// Sélectionne les leaders pour l'epoch suivant
-    pub fn select_leaders_for_epoch(&mut self, target_epoch: u64) -> Result<LeaderElectionResult> {
+    ///
+    /// Les poids sont d'abord recalculés depuis `consensus` : un nœud dont les
+    /// preuves échouent voit sa probabilité d'être élu baisser dès l'élection
+    /// suivante.
+    pub fn select_leaders_for_epoch(&mut self, target_epoch: u64, consensus: &mut ProofOfArchive) -> Result<LeaderElectionResult> {
+        self.refresh_weights(consensus)?;
         self.current_epoch = target_epoch;
         
         // Nettoie les validateurs non éligibles
@@ -192,8 +348,8 @@ impl LeaderSelector {
         // Génère un seed pour cette epoch
         let selection_seed = self.generate_epoch_seed(target_epoch)?;
         
-        // Sélectionne en utilisant l'algorithme hybride
-        let selection_result = self.hybrid_selection_algorithm(&eligible_validators, &selection_seed)?;
+        // Tire les validateurs proportionnellement à leur poids effectif
+        let selection_result = self.weighted_selection_algorithm(&eligible_validators, &selection_seed)?;
         
         // Enregistre la sélection dans l'historique
         self.selection_history.insert(target_epoch, selection_result.validators.clone());
@@ -207,6 +363,112 @@ impl LeaderSelector {
         Ok(selection_result)
     }
 
+    /// Seed d'un round : `hash(hash du bloc précédent ‖ numéro de round)`
+    pub fn round_seed(previous_block_hash: &Hash, round: u64) -> Hash {
+        compute_combined_hash(&[previous_block_hash.as_bytes(), &round.to_le_bytes()], HashAlgorithm::Blake3)
+    }
+
+    /// Ordre de tirage pondéré de tous les candidats pour `seed`
+    ///
+    /// Tirage sans remise : le i-ème tiré utilise `hash(seed ‖ i)` et chaque
+    /// candidat restant est choisi avec une probabilité proportionnelle à son
+    /// poids. Ne dépend que du seed et de la liste ordonnée des poids.
+    pub fn draw_order(seed: &Hash, candidates: &[CandidateWeight]) -> Vec<NodeId> {
+        let mut available: Vec<&CandidateWeight> = candidates.iter().collect();
+        let mut order = Vec::with_capacity(available.len());
+
+        for i in 0..candidates.len() as u64 {
+            let random_data = compute_combined_hash(
+                &[seed.as_bytes(), &i.to_le_bytes()],
+                HashAlgorithm::Blake3,
+            );
+            let random_value = u64::from_le_bytes(
+                random_data.as_bytes()[0..8].try_into().unwrap()
+            ) as f64 / u64::MAX as f64;
+
+            let total_weight: f64 = available.iter().map(|c| c.weight.max(0.0)).sum();
+            let target_weight = random_value * total_weight;
+            let mut cumulative_weight = 0.0;
+
+            // Le dernier candidat absorbe les erreurs d'arrondi
+            let mut chosen = available.len() - 1;
+            for (j, candidate) in available.iter().enumerate() {
+                cumulative_weight += candidate.weight.max(0.0);
+                if total_weight > 0.0 && cumulative_weight >= target_weight {
+                    chosen = j;
+                    break;
+                }
+            }
+
+            order.push(available.remove(chosen).node_id.clone());
+        }
+
+        order
+    }
+
+    /// Élit le leader du round `round` de façon déterministe et vérifiable
+    ///
+    /// `round` est la hauteur du bloc à produire et `inputs` les entrées
+    /// dérivées de la chaîne. Un candidat ayant déjà produit
+    /// `max_leader_rounds_per_window` des `leader_fairness_window` blocs
+    /// précédents est écarté au profit du suivant dans l'ordre de tirage ; si
+    /// tous le sont, le premier tiré est retenu pour ne pas bloquer la chaîne.
+    /// `validators` contient l'ordre de tirage complet et `backup_leaders` les
+    /// suivants non plafonnés.
+    pub fn elect_round_leader(
+        &self,
+        previous_block_hash: &Hash,
+        round: u64,
+        inputs: &ElectionInputs,
+    ) -> Result<LeaderElectionResult> {
+        let selection_seed = Self::round_seed(previous_block_hash, round);
+        let ranking = Self::rank_round(&self.config, &selection_seed, inputs).ok_or_else(|| {
+            crate::error::CoreError::Internal {
+                message: "Aucun validateur éligible disponible".to_string()
+            }
+        })?;
+        if !ranking.fairness_skipped.is_empty() {
+            tracing::debug!(
+                "Round {}: {} candidat(s) écarté(s) par le plafond d'équité",
+                round, ranking.fairness_skipped.len()
+            );
+        }
+
+        let diversity_metrics = self.calculate_diversity_metrics(&ranking.order);
+        Ok(LeaderElectionResult {
+            epoch: round,
+            primary_leader: ranking.primary_leader,
+            backup_leaders: ranking.backup_leaders,
+            validators: ranking.order,
+            selection_seed,
+            selected_at: chrono::Utc::now(),
+            diversity_metrics,
+            candidate_weights: inputs.candidate_weights.clone(),
+            fairness_skipped: ranking.fairness_skipped,
+        })
+    }
+
+    /// Tirage et plafond d'équité d'un round, sans état local ; `None` sans candidat
+    fn rank_round(config: &ConsensusConfig, selection_seed: &Hash, inputs: &ElectionInputs) -> Option<RoundRanking> {
+        let order = Self::draw_order(selection_seed, &inputs.candidate_weights);
+        let under_cap = |id: &&NodeId| {
+            inputs.recent_leaders.get(*id).copied().unwrap_or(0) < config.max_leader_rounds_per_window
+        };
+        let primary_leader = order.iter().find(under_cap).or(order.first())?.clone();
+        let fairness_skipped: Vec<NodeId> = order.iter()
+            .take_while(|id| **id != primary_leader)
+            .cloned()
+            .collect();
+        let backup_leaders: Vec<NodeId> = order.iter()
+            .filter(under_cap)
+            .filter(|id| **id != primary_leader)
+            .take(config.validators_per_round.saturating_sub(1))
+            .cloned()
+            .collect();
+
+        Some(RoundRanking { order, primary_leader, backup_leaders, fairness_skipped })
+    }
+
     /// Rapporte la performance d'un validateur
     pub fn report_validator_performance(
         &mut self,
@@ -339,7 +601,7 @@ impl LeaderSelector {
         seed_data.extend_from_slice(&epoch.to_le_bytes());
         
         // Ajoute de l'entropie des validateurs précédents
-        if let Some(previous_validators) = self.selection_history.get(&(epoch - 1)) {
+        if let Some(previous_validators) = epoch.checked_sub(1).and_then(|previous| self.selection_history.get(&previous)) {
             for validator_id in previous_validators {
                 seed_data.extend_from_slice(validator_id.hash().as_bytes());
             }
@@ -348,7 +610,7 @@ impl LeaderSelector {
         Ok(compute_hash(&seed_data, HashAlgorithm::Blake3))
     }
 
-    fn hybrid_selection_algorithm(
+    fn weighted_selection_algorithm(
         &self,
         eligible_validators: &[&ValidatorInfo],
         seed: &Hash,
@@ -356,7 +618,7 @@ impl LeaderSelector {
         let target_count = self.config.validators_per_round.min(eligible_validators.len());
         
         // Calcule les poids de sélection
-        let mut weighted_validators: Vec<(f64, &ValidatorInfo)> = eligible_validators
+        let weighted_validators: Vec<(f64, &ValidatorInfo)> = eligible_validators
             .iter()
             .map(|v| {
                 let weight = self.calculate_selection_weight(v);
@@ -364,36 +626,11 @@ impl LeaderSelector {
             })
             .collect();
 
-        // Trie par poids décroissant
-        weighted_validators.sort_by(|a, b| b.0.partial_cmp(&a.0).unwrap());
+        // Tirage sans remise : chaque position, y compris celle du leader
+        // principal, est attribuée proportionnellement au poids effectif
+        let selected = self.weighted_random_selection(&weighted_validators, target_count, seed)?;
 
-        // Sélection hybride : 70% basé sur le score, 30% aléatoire pondéré
-        let mut selected = Vec::new();
-        let top_performers_count = (target_count as f64 * 0.7).ceil() as usize;
-        
-        // Sélectionne les top performers
-        for (_, validator) in weighted_validators.iter().take(top_performers_count) {
-            selected.push(validator.node_id.clone());
-        }
-
-        // Sélection aléatoire pondérée pour le reste
-        let remaining_count = target_count - selected.len();
-        if remaining_count > 0 {
-            let remaining_validators: Vec<_> = weighted_validators
-                .iter()
-                .skip(top_performers_count)
-                .collect();
-            
-            let random_selected = self.weighted_random_selection(
-                &remaining_validators,
-                remaining_count,
-                seed,
-            )?;
-            
-            selected.extend(random_selected);
-        }
-
-        // Sélectionne le leader principal (celui avec le plus haut score)
+        // Le premier tiré est le leader principal
         let primary_leader = selected[0].clone();
         let backup_leaders = selected[1..].to_vec();
 
@@ -408,11 +645,15 @@ impl LeaderSelector {
             selection_seed: seed.clone(),
             selected_at: chrono::Utc::now(),
             diversity_metrics,
+            candidate_weights: weighted_validators.iter()
+                .map(|(weight, v)| CandidateWeight { node_id: v.node_id.clone(), weight: *weight })
+                .collect(),
+            fairness_skipped: Vec::new(),
         })
     }
 
     fn calculate_selection_weight(&self, validator: &ValidatorInfo) -> f64 {
-        let base_weight = validator.consensus_score.combined_score;
+        let base_weight = validator.effective_weight();
         
         // Facteur de rotation (encourage la diversité)
         let rotation_factor = if let Some(last_epoch) = validator.last_selected_epoch {
@@ -437,34 +678,12 @@ impl LeaderSelector {
         count: usize,
         seed: &Hash,
     ) -> Result<Vec<NodeId>> {
-        let mut selected = Vec::new();
-        let mut available = candidates.to_vec();
-
-        for i in 0..count.min(available.len()) {
-            // Utilise le seed pour générer un nombre pseudo-aléatoire déterministe
-            let random_data = compute_combined_hash(
-                &[seed.as_bytes(), &i.to_le_bytes()],
-                HashAlgorithm::Blake3,
-            );
-            let random_value = u64::from_le_bytes(
-                random_data.as_bytes()[0..8].try_into().unwrap()
-            ) as f64 / u64::MAX as f64;
-
-            // Sélection pondérée
-            let total_weight: f64 = available.iter().map(|(w, _)| *w).sum();
-            let mut cumulative_weight = 0.0;
-            let target_weight = random_value * total_weight;
-
-            for (j, (weight, validator)) in available.iter().enumerate() {
-                cumulative_weight += weight;
-                if cumulative_weight >= target_weight {
-                    selected.push(validator.node_id.clone());
-                    available.remove(j);
-                    break;
-                }
-            }
-        }
+        let weights: Vec<CandidateWeight> = candidates.iter()
+            .map(|(weight, validator)| CandidateWeight { node_id: validator.node_id.clone(), weight: *weight })
+            .collect();
 
+        let mut selected = Self::draw_order(seed, &weights);
+        selected.truncate(count);
         Ok(selected)
     }
 
@@ -483,7 +702,8 @@ impl LeaderSelector {
         let score_distribution = variance.sqrt();
 
         // Calcule le taux de rotation
-        let rotation_rate = if let Some(previous) = self.selection_history.get(&(self.current_epoch - 1)) {
+        let previous_epoch = self.current_epoch.checked_sub(1);
+        let rotation_rate = if let Some(previous) = previous_epoch.and_then(|epoch| self.selection_history.get(&epoch)) {
             let new_validators = selected.iter()
                 .filter(|id| !previous.contains(id))
                 .count();
@@ -569,6 +789,7 @@ mod tests {
             node_id,
             consensus_score,
             stake_amount: 1000,
+            declared_weight: 1.0,
             recent_validations: 10,
             participation_rate: 0.9,
             last_selected_epoch: None,
@@ -637,7 +858,8 @@ mod tests {
             selector.register_validator(node_id, consensus_score).unwrap();
         }
         
-        let result = selector.select_leaders_for_epoch(1).unwrap();
+        let mut consensus = ProofOfArchive::new(ConsensusConfig::test_config()).unwrap();
+        let result = selector.select_leaders_for_epoch(1, &mut consensus).unwrap();
         
         assert_eq!(result.epoch, 1);
         assert!(!result.validators.is_empty());
@@ -645,6 +867,39 @@ mod tests {
         assert!(result.diversity_metrics.rotation_rate >= 0.0);
     }
 
+    #[test]
+    fn test_leader_probability_follows_effective_weight() {
+        let mut config = ConsensusConfig::test_config();
+        config.validators_per_round = 1;
+        let seed = Hash::from_bytes(&[1; 32]).unwrap();
+        let mut selector = LeaderSelector::new(config, seed);
+
+        let strong = NodeId::from(Hash::from_bytes(&[2; 32]).unwrap());
+        let weak = NodeId::from(Hash::from_bytes(&[3; 32]).unwrap());
+        let mut strong_info = create_test_validator_info(strong.clone(), 0.9);
+        let mut weak_info = create_test_validator_info(weak.clone(), 0.9);
+        // Même score, mais un nœud léger déclare un poids plus faible
+        weak_info.declared_weight = 0.1;
+        strong_info.last_selected_epoch = Some(0);
+        weak_info.last_selected_epoch = Some(0);
+        selector.validator_pool.insert(strong.clone(), strong_info);
+        selector.validator_pool.insert(weak.clone(), weak_info);
+
+        // Sans métriques dans le moteur de consensus, les poids renseignés sont conservés
+        let mut consensus = ProofOfArchive::new(ConsensusConfig::test_config()).unwrap();
+        let mut strong_wins = 0;
+        for epoch in 1..=200 {
+            let result = selector.select_leaders_for_epoch(epoch, &mut consensus).unwrap();
+            assert_eq!(result.validators.len(), 1);
+            if result.primary_leader == strong {
+                strong_wins += 1;
+            }
+        }
+
+        // Espérance ~180 victoires sur 200 ; le nœud faible reste éligible
+        assert!(strong_wins > 150 && strong_wins < 200);
+    }
+
     #[test]
     fn test_validator_performance_reporting() {
         let config = ConsensusConfig::test_config();
@@ -708,4 +963,130 @@ mod tests {
             EligibilityStatus::Suspended(_)
         ));
     }
-}
\ No newline at end of file
+
+    fn block_hash(round: u64) -> Hash {
+        compute_hash(&round.to_le_bytes(), HashAlgorithm::Blake3)
+    }
+
+    fn validator_keys(count: usize) -> Vec<PublicKey> {
+        (0..count).map(|_| generate_keypair().unwrap().public_key().clone()).collect()
+    }
+
+    #[test]
+    fn test_round_election_is_deterministic() {
+        let selector = LeaderSelector::new(ConsensusConfig::test_config(), Hash::from_bytes(&[1; 32]).unwrap());
+        let keys = validator_keys(4);
+        let stakes: Vec<(PublicKey, u64)> = keys.iter().cloned().zip([300, 500, 700, 900]).collect();
+        let inputs = ElectionInputs::new(stakes.clone(), Vec::new());
+        let reversed = ElectionInputs::new(stakes.into_iter().rev().collect(), Vec::new());
+        assert_eq!(inputs, reversed);
+
+        let previous = block_hash(41);
+        let a = selector.elect_round_leader(&previous, 42, &inputs).unwrap();
+        let b = selector.elect_round_leader(&previous, 42, &reversed).unwrap();
+
+        assert_eq!(a.primary_leader, b.primary_leader);
+        assert_eq!(a.validators, b.validators);
+        assert_eq!(a.selection_seed, LeaderSelector::round_seed(&previous, 42));
+        assert_ne!(a.selection_seed, LeaderSelector::round_seed(&previous, 43));
+        let config = ConsensusConfig::test_config();
+        assert!(a.verify(&previous, &inputs, &config));
+        assert!(!a.verify(&block_hash(40), &inputs, &config));
+    }
+
+    #[test]
+    fn test_round_election_verify_ignores_published_weights_and_skips() {
+        let config = ConsensusConfig::test_config();
+        let selector = LeaderSelector::new(config.clone(), Hash::from_bytes(&[1; 32]).unwrap());
+        let keys = validator_keys(3);
+        let inputs = ElectionInputs::new(keys.iter().cloned().zip([100, 100, 100]).collect(), Vec::new());
+        let previous = block_hash(7);
+        let result = selector.elect_round_leader(&previous, 8, &inputs).unwrap();
+        assert!(result.verify(&previous, &inputs, &config));
+
+        // Poids gonflés par le producteur : refusés même si le tirage est cohérent
+        let mut inflated = result.clone();
+        let winner = inflated.candidate_weights.iter_mut().find(|c| c.node_id == result.validators[1]).unwrap();
+        winner.weight = 1e12;
+        let inflated_inputs = ElectionInputs {
+            candidate_weights: inflated.candidate_weights.clone(),
+            recent_leaders: HashMap::new(),
+        };
+        let forged = selector.elect_round_leader(&previous, 8, &inflated_inputs).unwrap();
+        assert!(!forged.verify(&previous, &inputs, &config));
+
+        // Premier tiré écarté sans dépasser le plafond : refusé
+        inflated = result.clone();
+        inflated.fairness_skipped = vec![result.validators[0].clone()];
+        inflated.primary_leader = result.validators[1].clone();
+        assert!(!inflated.verify(&previous, &inputs, &config));
+
+        // Le même écart est valide quand la chaîne montre le plafond atteint
+        let capped = ElectionInputs {
+            recent_leaders: HashMap::from([(result.validators[0].clone(), config.max_leader_rounds_per_window)]),
+            ..inputs.clone()
+        };
+        let rerun = selector.elect_round_leader(&previous, 8, &capped).unwrap();
+        assert_eq!(rerun.fairness_skipped, vec![result.validators[0].clone()]);
+        assert!(rerun.verify(&previous, &capped, &config));
+        assert!(!rerun.verify(&previous, &inputs, &config));
+    }
+
+    #[test]
+    fn test_round_election_is_proportional_to_stake() {
+        let mut config = ConsensusConfig::test_config();
+        config.max_leader_rounds_per_window = u32::MAX;
+        let selector = LeaderSelector::new(config, Hash::from_bytes(&[1; 32]).unwrap());
+        let keys = validator_keys(4);
+        let stakes = [200u64, 400, 600, 800];
+        let inputs = ElectionInputs::new(keys.iter().cloned().zip(stakes).collect(), Vec::new());
+
+        let rounds = 10_000u64;
+        let mut wins: HashMap<NodeId, u64> = HashMap::new();
+        for round in 1..=rounds {
+            let result = selector.elect_round_leader(&block_hash(round - 1), round, &inputs).unwrap();
+            *wins.entry(result.primary_leader).or_insert(0) += 1;
+        }
+
+        for (key, stake) in keys.iter().zip(stakes) {
+            let share = *wins.get(&NodeId::from_public_key(key)).unwrap_or(&0) as f64 / rounds as f64;
+            let expected = stake as f64 / 2000.0;
+            assert!(
+                (share - expected).abs() < 0.02,
+                "part {} pour un stake {}, attendu {}", share, stake, expected
+            );
+        }
+    }
+
+    #[test]
+    fn test_round_election_fairness_cap() {
+        let mut config = ConsensusConfig::test_config();
+        config.leader_fairness_window = 5;
+        config.max_leader_rounds_per_window = 2;
+        let selector = LeaderSelector::new(config.clone(), Hash::from_bytes(&[1; 32]).unwrap());
+        let keys = validator_keys(3);
+        let dominant = NodeId::from_public_key(&keys[0]);
+        let stakes: Vec<(PublicKey, u64)> = keys.iter().cloned().zip([900, 150, 150]).collect();
+
+        // Les producteurs des blocs précédents tiennent lieu de chaîne
+        let mut producers: Vec<PublicKey> = Vec::new();
+        let mut skipped_rounds = 0;
+        for round in 1..=200u64 {
+            let window_start = producers.len().saturating_sub(5);
+            let inputs = ElectionInputs::new(stakes.clone(), producers[window_start..].to_vec());
+            let previous = block_hash(round - 1);
+            let result = selector.elect_round_leader(&previous, round, &inputs).unwrap();
+            assert!(result.verify(&previous, &inputs, &config));
+            if !result.fairness_skipped.is_empty() {
+                skipped_rounds += 1;
+            }
+            let leader = keys.iter().find(|key| NodeId::from_public_key(key) == result.primary_leader).unwrap();
+            producers.push(leader.clone());
+        }
+
+        for window in producers.windows(5) {
+            assert!(window.iter().filter(|key| NodeId::from_public_key(key) == dominant).count() <= 2);
+        }
+        assert!(skipped_rounds > 0);
+    }
+}
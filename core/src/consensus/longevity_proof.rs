@@ -124,6 +124,36 @@ pub struct LongevityBonus {
     pub final_score: f64,
 }
 
+/// Durée de stockage donnant droit au multiplicateur maximal (2 ans)
+pub const FULL_LONGEVITY_DURATION: Duration = Duration::from_secs(2 * 365 * 24 * 3600);
+
+/// Multiplicateur maximal des récompenses de stockage à long terme
+pub const MAX_LONGEVITY_MULTIPLIER: f64 = 2.0;
+
+impl LongevityBonus {
+    /// Multiplicateur des récompenses de stockage continu
+    ///
+    /// Progression linéaire de 1.0x à `min_longevity_duration` (valeur par
+    /// défaut de `ConsensusConfig`) jusqu'à 2.0x à 2 ans, plafonné au-delà.
+    pub fn multiplier(storage_duration: Duration) -> f64 {
+        Self::multiplier_from(storage_duration, ConsensusConfig::default().min_longevity_duration)
+    }
+
+    /// Variante de `multiplier` avec une durée minimum explicite
+    pub fn multiplier_from(storage_duration: Duration, min_longevity_duration: Duration) -> f64 {
+        if storage_duration <= min_longevity_duration {
+            return 1.0;
+        }
+        if storage_duration >= FULL_LONGEVITY_DURATION || min_longevity_duration >= FULL_LONGEVITY_DURATION {
+            return MAX_LONGEVITY_MULTIPLIER;
+        }
+
+        let progress = (storage_duration - min_longevity_duration).as_secs_f64()
+            / (FULL_LONGEVITY_DURATION - min_longevity_duration).as_secs_f64();
+        1.0 + (MAX_LONGEVITY_MULTIPLIER - 1.0) * progress
+    }
+}
+
 /// Défi de longévité pour vérifier la continuité
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct LongevityChallenge {
@@ -627,6 +657,25 @@ mod tests {
     use super::*;
     use crate::crypto::{generate_keypair, Hash};
 
+    #[test]
+    fn test_long_term_storage_multiplier() {
+        let day = Duration::from_secs(24 * 3600);
+
+        assert_eq!(LongevityBonus::multiplier(Duration::ZERO), 1.0);
+        assert_eq!(LongevityBonus::multiplier(day), 1.0);
+        assert_eq!(LongevityBonus::multiplier(FULL_LONGEVITY_DURATION), 2.0);
+        assert_eq!(LongevityBonus::multiplier(FULL_LONGEVITY_DURATION * 3), 2.0);
+
+        let one_year = LongevityBonus::multiplier(day * 365);
+        assert!((one_year - 1.5).abs() < 0.01, "1 an : {}", one_year);
+        assert!(LongevityBonus::multiplier(day * 30) < LongevityBonus::multiplier(day * 90));
+
+        // Le seuil suit `min_longevity_duration`
+        assert_eq!(LongevityBonus::multiplier_from(day * 10, day * 30), 1.0);
+        let halfway = day * 30 + (FULL_LONGEVITY_DURATION - day * 30) / 2;
+        assert!((LongevityBonus::multiplier_from(halfway, day * 30) - 1.5).abs() < 1e-9);
+    }
+
     #[test]
     fn test_longevity_proof_manager_creation() {
         let config = ConsensusConfig::test_config();
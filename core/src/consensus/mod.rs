@@ -14,10 +14,16 @@ pub mod validator;
 pub mod rewards;
 
 pub use proof_of_archive::{ProofOfArchive};
-pub use storage_proof::{StorageProofManager, StorageChallenge, StorageChallengeResponse, NodeStorageMetrics, StorageMetrics};
-pub use bandwidth_proof::{BandwidthProofManager, BandwidthMetrics, BandwidthScore};
+pub use storage_proof::{
+    StorageProofManager, StorageChallenge, StorageChallengeResponse, NodeStorageMetrics, StorageMetrics,
+    ChallengeAuditEntry, ChallengeOutcome,
+};
+pub use bandwidth_proof::{
+    BandwidthProofManager, BandwidthMetrics, BandwidthScore, BandwidthReporter, TransferSample, TransferDirection,
+    DeliveryLedger, DeliveryEpoch,
+};
 pub use longevity_proof::{LongevityProofManager, LongevityMetrics, LongevityBonus};
-pub use leader_selection::{LeaderSelector, ValidatorInfo, LeaderElectionResult};
+pub use leader_selection::{LeaderSelector, ValidatorInfo, LeaderElectionResult, CandidateWeight, ElectionInputs, ValidatorStakes};
 pub use validator::{ConsensusValidator, ValidationResult, ValidationError};
 pub use rewards::{RewardCalculator, RewardDistribution, IncentiveTable};
 
@@ -75,6 +81,23 @@ pub struct ConsensusConfig {
     pub min_bandwidth_threshold: u64,
     /// Durée minimum pour les bonus de longévité
     pub min_longevity_duration: Duration,
+    /// Fenêtre glissante, en rounds, du plafond d'équité des leaders
+    #[serde(default = "default_leader_fairness_window")]
+    pub leader_fairness_window: u64,
+    /// Nombre maximum de rounds menés par un même nœud dans la fenêtre
+    #[serde(default = "default_max_leader_rounds_per_window")]
+    pub max_leader_rounds_per_window: u32,
+    /// Fichier JSON Lines du journal d'audit des défis de stockage, non persisté si absent
+    #[serde(default)]
+    pub challenge_audit_log_path: Option<std::path::PathBuf>,
+}
+
+fn default_leader_fairness_window() -> u64 {
+    100
+}
+
+fn default_max_leader_rounds_per_window() -> u32 {
+    10
 }
 
 impl Default for ConsensusConfig {
@@ -89,6 +112,9 @@ impl Default for ConsensusConfig {
             challenge_timeout: Duration::from_secs(30),
             min_bandwidth_threshold: 1024 * 1024, // 1 MB/s minimum
             min_longevity_duration: Duration::from_secs(3600 * 24), // 1 jour
+            leader_fairness_window: default_leader_fairness_window(),
+            max_leader_rounds_per_window: default_max_leader_rounds_per_window(),
+            challenge_audit_log_path: None,
         }
     }
 }
@@ -124,6 +150,12 @@ impl ConsensusConfig {
             });
         }
 
+        if self.leader_fairness_window == 0 || self.max_leader_rounds_per_window == 0 {
+            return Err(crate::error::CoreError::Validation {
+                message: "La fenêtre et le plafond d'équité des leaders doivent être supérieurs à 0".to_string()
+            });
+        }
+
         Ok(())
     }
 
@@ -139,6 +171,9 @@ impl ConsensusConfig {
             challenge_timeout: Duration::from_secs(5),
             min_bandwidth_threshold: 1024,
             min_longevity_duration: Duration::from_secs(60), // 1 minute
+            leader_fairness_window: 10,
+            max_leader_rounds_per_window: 3,
+            challenge_audit_log_path: None,
         }
     }
 }
@@ -9,8 +9,11 @@ use crate::crypto::{Hash, HashAlgorithm, compute_hash};
 use crate::error::Result;
 use super::{
     NodeId, ConsensusConfig, ConsensusScore, ConsensusProof,
-    storage_proof::{StorageProofManager, StorageMetrics},
-    bandwidth_proof::{BandwidthProofManager, BandwidthMetrics},
+    storage_proof::{StorageProofManager, StorageChallenge, StorageChallengeResponse},
+    bandwidth_proof::{
+        BandwidthProofManager, BandwidthMetrics, BandwidthReporter, BandwidthScore, BandwidthTest,
+        BandwidthTestResponse, BandwidthTestType, DeliveryLedger,
+    },
     longevity_proof::{LongevityProofManager, LongevityMetrics},
 };
 
@@ -27,6 +30,8 @@ pub struct ProofOfArchive {
     longevity_manager: LongevityProofManager,
     /// Cache des scores calculés
     score_cache: HashMap<NodeId, CachedScore>,
+    /// Poids de consensus déclarés par type de nœud
+    declared_weights: HashMap<NodeId, f64>,
     /// Epoch actuel du consensus
     current_epoch: u64,
 }
@@ -43,18 +48,135 @@ impl ProofOfArchive {
     pub fn new(config: ConsensusConfig) -> Result<Self> {
         config.validate()?;
 
+        let mut storage_manager = StorageProofManager::new(&config);
+        if let Some(path) = &config.challenge_audit_log_path {
+            let restored = storage_manager.enable_audit_log(path)?;
+            tracing::info!("Journal d'audit des défis de stockage repris: {} entrées", restored);
+        }
+
         Ok(Self {
-            storage_manager: StorageProofManager::new(&config),
+            storage_manager,
             bandwidth_manager: BandwidthProofManager::new(&config),
             longevity_manager: LongevityProofManager::new(&config),
             config,
             score_cache: HashMap::new(),
+            declared_weights: HashMap::new(),
             current_epoch: 0,
         })
     }
 
+    /// Enregistre le poids déclaré d'un nœud
+    ///
+    /// Le poids provient de `NodeType::minimum_requirements().consensus_weight` ;
+    /// il plafonne le poids effectif sans jamais le garantir.
+    pub fn set_declared_weight(&mut self, node_id: NodeId, weight: f64) -> Result<()> {
+        if !(0.0..=1.0).contains(&weight) {
+            return Err(crate::error::CoreError::Validation {
+                message: format!("Poids de consensus invalide: {}", weight),
+            });
+        }
+        self.declared_weights.insert(node_id, weight);
+        Ok(())
+    }
+
+    /// Poids déclaré d'un nœud (1.0 si aucun type n'a été déclaré)
+    pub fn declared_weight(&self, node_id: &NodeId) -> f64 {
+        self.declared_weights.get(node_id).copied().unwrap_or(1.0)
+    }
+
+    /// Poids effectif d'un nœud dans l'élection des leaders
+    ///
+    /// Produit du poids déclaré et du score combiné courant : un nœud dont les
+    /// preuves échouent perd du poids dès le calcul suivant.
+    pub fn effective_weight(&mut self, node_id: &NodeId) -> Result<f64> {
+        let score = self.calculate_consensus_score(node_id)?;
+        Ok(self.declared_weight(node_id) * score.combined_score)
+    }
+
+    /// Enregistre une archive stockée par un nœud
+    pub fn register_storage(&mut self, node_id: NodeId, archive_hash: Hash, size_bytes: u64) {
+        self.storage_manager.register_storage(node_id.clone(), archive_hash, size_bytes);
+        self.score_cache.remove(&node_id);
+    }
+
+    /// Émet un défi de stockage pour un nœud
+    pub fn generate_storage_challenge(&mut self, node_id: &NodeId) -> Result<StorageChallenge> {
+        self.storage_manager.generate_storage_challenge(node_id)
+    }
+
+    /// Vérifie la réponse à un défi de stockage
+    ///
+    /// Le score mis en cache du nœud est invalidé : le résultat du défi se
+    /// répercute immédiatement sur son poids effectif.
+    pub fn verify_storage_response(
+        &mut self,
+        challenge: &StorageChallenge,
+        response: &StorageChallengeResponse,
+    ) -> Result<bool> {
+        let result = self.storage_manager.verify_storage_response(challenge, response);
+        self.score_cache.remove(&challenge.node_id);
+        result
+    }
+
+    /// Consigne les défis de stockage restés sans réponse
+    pub fn expire_storage_challenges(&mut self, now: chrono::DateTime<chrono::Utc>) -> Result<usize> {
+        let expired = self.storage_manager.expire_challenges_at(now)?;
+        if expired > 0 {
+            self.score_cache.clear();
+        }
+        Ok(expired)
+    }
+
+    /// Retire les issues de défis de stockage enregistrées depuis le dernier appel
+    pub fn take_challenge_outcomes(&mut self) -> Vec<super::ChallengeAuditEntry> {
+        self.storage_manager.take_reputation_feedback()
+    }
+
+    /// Crée une poignée permettant à un nœud de signaler ses transferts réels
+    pub fn bandwidth_reporter(&self, node_id: NodeId) -> BandwidthReporter {
+        self.bandwidth_manager.reporter(node_id)
+    }
+
+    /// Livraisons de contenu d'un nœud, transferts signalés inclus
+    pub fn delivery_ledger(&mut self, node_id: &NodeId) -> Option<DeliveryLedger> {
+        if self.bandwidth_manager.ingest_reported_transfers() > 0 {
+            self.score_cache.clear();
+        }
+        self.bandwidth_manager.delivery_ledger(node_id).cloned()
+    }
+
+    /// Score de bande passante d'un nœud
+    pub fn bandwidth_score(&self, node_id: &NodeId) -> Result<BandwidthScore> {
+        self.bandwidth_manager.calculate_bandwidth_score(node_id)
+    }
+
+    /// Enregistre un pair pouvant recevoir des livraisons rémunérables et signer les preuves de transfert
+    pub fn register_bandwidth_peer(&mut self, public_key: crate::crypto::PublicKey) -> NodeId {
+        self.bandwidth_manager.register_peer(public_key)
+    }
+
+    /// Émet un défi de bande passante pour un nœud
+    pub fn generate_bandwidth_test(&mut self, node_id: &NodeId, test_type: BandwidthTestType) -> Result<BandwidthTest> {
+        self.bandwidth_manager.generate_bandwidth_test(node_id, test_type)
+    }
+
+    /// Vérifie la réponse à un défi de bande passante
+    ///
+    /// Un défi réussi clôt une époque de livraisons réclamable par le nœud.
+    pub fn verify_bandwidth_response(&mut self, test: &BandwidthTest, response: &BandwidthTestResponse) -> Result<bool> {
+        self.bandwidth_manager.ingest_reported_transfers();
+        let result = self.bandwidth_manager.verify_bandwidth_response(test, response);
+        self.score_cache.remove(&test.node_id);
+        result
+    }
+
     /// Calcule le score de consensus pour un nœud
     pub fn calculate_consensus_score(&mut self, node_id: &NodeId) -> Result<ConsensusScore> {
+        // Intègre les transferts signalés depuis le dernier calcul
+        if self.bandwidth_manager.ingest_reported_transfers() > 0 {
+            self.score_cache.clear();
+        }
+
         // Vérifie le cache
         if let Some(cached) = self.score_cache.get(node_id) {
             if cached.expires_at > SystemTime::now() {
@@ -384,6 +506,96 @@ mod tests {
         assert!(poa.update_config(invalid_config).is_err());
     }
 
+    /// Nœud disposant de métriques de stockage, de bande passante et de longévité
+    fn proven_node(poa: &mut ProofOfArchive) -> NodeId {
+        use crate::consensus::bandwidth_proof::{BandwidthTestType, PerformanceMeasurement};
+        use crate::consensus::longevity_proof::ActivityType;
+
+        let node_id = NodeId::from_public_key(generate_keypair().unwrap().public_key());
+        poa.register_storage(node_id.clone(), Hash::from_bytes(&[1; 32]).unwrap(), 10240);
+        poa.bandwidth_manager.record_performance(node_id.clone(), PerformanceMeasurement {
+            measurement_type: BandwidthTestType::Upload,
+            bandwidth_bps: 4096,
+            latency_ms: 50,
+            data_size: 4096,
+            transfer_duration_ms: 1000,
+            measured_at: chrono::Utc::now(),
+            quality_score: 1.0,
+        });
+        poa.longevity_manager.record_node_activity(node_id.clone(), ActivityType::ChallengeResponse);
+        node_id
+    }
+
+    /// Répond à un défi de stockage sans les données demandées
+    fn fail_storage_challenge(poa: &mut ProofOfArchive, node_id: &NodeId) {
+        use crate::state::MerkleProof;
+
+        let challenge = poa.generate_storage_challenge(node_id).unwrap();
+        let response = StorageChallengeResponse {
+            challenge_id: challenge.challenge_id.clone(),
+            data_samples: Vec::new(),
+            combined_hash: Hash::zero(),
+            merkle_proof: MerkleProof {
+                leaf_hash: Hash::zero(),
+                path: Vec::new(),
+                root_hash: Hash::zero(),
+                algorithm: challenge.hash_algorithm,
+            },
+            responded_at: challenge.created_at + chrono::Duration::milliseconds(250),
+        };
+        assert!(!poa.verify_storage_response(&challenge, &response).unwrap());
+    }
+
+    #[test]
+    fn test_failed_storage_proofs_lower_effective_weight() {
+        let mut poa = ProofOfArchive::new(ConsensusConfig::test_config()).unwrap();
+        let node_id = proven_node(&mut poa);
+        poa.set_declared_weight(node_id.clone(), 0.5).unwrap();
+        assert!(poa.set_declared_weight(node_id.clone(), 1.5).is_err());
+
+        let initial_weight = poa.effective_weight(&node_id).unwrap();
+        assert!(initial_weight > 0.0 && initial_weight <= 0.5);
+
+        let mut previous_weight = initial_weight;
+        for _ in 0..5 {
+            fail_storage_challenge(&mut poa, &node_id);
+
+            // Le cache ne masque pas l'échec
+            let weight = poa.effective_weight(&node_id).unwrap();
+            assert!(weight < previous_weight);
+            previous_weight = weight;
+        }
+
+        assert!(previous_weight < initial_weight * 0.8);
+    }
+
+    #[test]
+    fn test_leader_election_follows_failed_storage_proofs() {
+        use crate::consensus::leader_selection::LeaderSelector;
+
+        let mut poa = ProofOfArchive::new(ConsensusConfig::test_config()).unwrap();
+        let mut selector = LeaderSelector::new(ConsensusConfig::test_config(), Hash::zero());
+        let node_id = proven_node(&mut poa);
+        let score = poa.calculate_consensus_score(&node_id).unwrap();
+        selector.register_validator(node_id.clone(), score).unwrap();
+
+        // Le poids déclaré du type de nœud est repris à l'élection
+        poa.set_declared_weight(node_id.clone(), 0.5).unwrap();
+        selector.select_leaders_for_epoch(1, &mut poa).unwrap();
+        let validator = selector.get_validator_info(&node_id).unwrap();
+        assert_eq!(validator.declared_weight, 0.5);
+        let initial_weight = validator.effective_weight();
+
+        for _ in 0..5 {
+            fail_storage_challenge(&mut poa, &node_id);
+        }
+        // L'élection suivante tire sur le poids dégradé, pas sur celui de l'inscription
+        let _ = selector.select_leaders_for_epoch(2, &mut poa);
+        let degraded_weight = selector.get_validator_info(&node_id).unwrap().effective_weight();
+        assert!(degraded_weight < initial_weight * 0.8);
+        assert_eq!(degraded_weight, poa.effective_weight(&node_id).unwrap());
+    }
+
     #[test]
     fn test_statistics() {
         let config = ConsensusConfig::test_config();
@@ -4,9 +4,10 @@
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::time::Duration;
 use crate::crypto::Hash;
 use crate::error::Result;
-use super::{NodeId, ConsensusScore};
+use super::{NodeId, ConsensusConfig, ConsensusScore, LongevityBonus};
 
 /// Calculateur de récompenses pour le consensus PoA
 #[derive(Debug)]
@@ -19,6 +20,8 @@ pub struct RewardCalculator {
     reward_pool: RewardPool,
     /// Statistiques de distribution
     distribution_stats: DistributionStatistics,
+    /// Durée de stockage à partir de laquelle le bonus de longévité s'applique
+    min_longevity_duration: Duration,
 }
 
 /// Table d'incitations selon le modèle économique ArchiveChain
@@ -143,6 +146,8 @@ pub enum MultiplierType {
     Longevity,
     /// Bonus de participation
     Participation,
+    /// Bonus de stockage à long terme (récompenses de stockage continu)
+    LongTermStorage,
     /// Pénalité
     Penalty,
 }
@@ -232,6 +237,32 @@ impl RewardCalculator {
                 highest_distribution_period: None,
                 highest_distribution_amount: 0,
             },
+            min_longevity_duration: ConsensusConfig::default().min_longevity_duration,
+        }
+    }
+
+    /// Définit la durée minimum du bonus de stockage à long terme
+    pub fn with_min_longevity_duration(mut self, min_longevity_duration: Duration) -> Self {
+        self.min_longevity_duration = min_longevity_duration;
+        self
+    }
+
+    /// Multiplicateur de stockage à long terme pour une durée en jours
+    ///
+    /// Voir `LongevityBonus::multiplier` : 1.0x au minimum requis, 2.0x à 2 ans.
+    pub fn long_term_storage_multiplier(&self, storage_duration_days: u64) -> f64 {
+        let storage_duration = Duration::from_secs(storage_duration_days.saturating_mul(24 * 3600));
+        LongevityBonus::multiplier_from(storage_duration, self.min_longevity_duration)
+    }
+
+    /// Bonus d'archivage initial basé sur la taille de l'archive
+    pub fn archive_size_multiplier(archive_size: u64) -> f64 {
+        if archive_size > 1024 * 1024 { // > 1MB
+            1.2
+        } else if archive_size > 1024 * 100 { // > 100KB
+            1.1
+        } else {
+            1.0
         }
     }
 
@@ -247,15 +278,7 @@ impl RewardCalculator {
             quality_score,
         );
         
-        // Bonus basé sur la taille de l'archive
-        let size_multiplier = if archive_size > 1024 * 1024 { // > 1MB
-            1.2
-        } else if archive_size > 1024 * 100 { // > 100KB
-            1.1
-        } else {
-            1.0
-        };
-        
+        let size_multiplier = Self::archive_size_multiplier(archive_size);
         let consensus_multiplier = 0.5 + consensus_score.combined_score * 0.5;
         
         (base_reward as f64 * size_multiplier * consensus_multiplier) as u64
@@ -273,13 +296,13 @@ impl RewardCalculator {
             consensus_score.storage_score,
         );
         
-        // Bonus pour la durée de stockage
-        let duration_multiplier = (storage_duration_days as f64 / 30.0).min(2.0); // Max 2x pour 60+ jours
+        // Bonus de stockage à long terme (jusqu'à 2x à 2 ans)
+        let longevity_multiplier = self.long_term_storage_multiplier(storage_duration_days);
         
         // Bonus pour le nombre d'archives
         let volume_multiplier = (stored_archives as f64 / 10.0).min(1.5); // Max 1.5x pour 15+ archives
         
-        (base_reward as f64 * duration_multiplier * volume_multiplier) as u64
+        (base_reward as f64 * longevity_multiplier * volume_multiplier) as u64
     }
 
     /// Calcule les récompenses pour le service de bande passante
@@ -482,6 +505,15 @@ impl RewardCalculator {
             );
             rewards_by_type.insert(RewardType::ContinuousStorage, reward);
             total_rewards += reward;
+
+            let longevity_multiplier = self.long_term_storage_multiplier(contribution.storage_duration_days);
+            if longevity_multiplier > 1.0 {
+                applied_multipliers.push(MultiplierInfo {
+                    multiplier_type: MultiplierType::LongTermStorage,
+                    value: longevity_multiplier,
+                    reason: format!("Stockage continu depuis {} jours", contribution.storage_duration_days),
+                });
+            }
         }
 
         // Applique les bonus de longévité
@@ -707,6 +739,29 @@ mod tests {
         assert_eq!(bonus_365, 500); // 50% de 1000
     }
 
+    #[test]
+    fn test_continuous_storage_longevity_multiplier() {
+        let calculator = RewardCalculator::new(IncentiveTable::default(), 1000000);
+        let consensus_score = ConsensusScore {
+            storage_score: 1.0,
+            bandwidth_score: 0.0,
+            longevity_score: 0.0,
+            combined_score: 0.5,
+            node_id: NodeId::from(Hash::zero()),
+            calculated_at: chrono::Utc::now(),
+        };
+
+        assert_eq!(calculator.long_term_storage_multiplier(1), 1.0);
+        assert_eq!(calculator.long_term_storage_multiplier(730), 2.0);
+        assert!((calculator.long_term_storage_multiplier(365) - 1.5).abs() < 0.01);
+
+        // 10 archives : multiplicateur de volume 1.0, seule la longévité joue
+        let fresh = calculator.calculate_continuous_storage_reward(10, 1, &consensus_score);
+        let two_years = calculator.calculate_continuous_storage_reward(10, 730, &consensus_score);
+        assert_eq!(fresh, 50);
+        assert_eq!(two_years, 100);
+    }
+
     #[test]
     fn test_reward_pool_management() {
         let incentive_table = IncentiveTable::default();
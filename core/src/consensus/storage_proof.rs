@@ -4,13 +4,21 @@
 //! stockent effectivement les données qu'ils prétendent archiver
 
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
 use std::time::{Duration, SystemTime};
 use crate::crypto::{Hash, HashAlgorithm, compute_hash, compute_combined_hash};
 use crate::state::{MerkleTree, MerkleProof};
-use crate::error::Result;
+use crate::error::{Result, SerializationError};
 use super::{NodeId, ConsensusConfig, ConsensusProof};
 
+/// Nombre maximum d'entrées d'audit conservées en mémoire par nœud
+const MAX_AUDIT_ENTRIES_PER_NODE: usize = 10_000;
+
+/// Nombre maximum d'issues de défis en attente de report sur la réputation
+const MAX_PENDING_REPUTATION_FEEDBACK: usize = 10_000;
+
 /// Métriques de stockage pour le consensus (version simplifiée)
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct StorageMetrics {
@@ -30,13 +38,58 @@ pub struct StorageProofManager {
     /// Configuration du consensus
     config: ConsensusConfig,
     /// Métriques de stockage par nœud
-    node_metrics: HashMap<NodeId, StorageMetrics>,
+    node_metrics: HashMap<NodeId, NodeStorageMetrics>,
     /// Défis actifs par nœud
     active_challenges: HashMap<NodeId, StorageChallenge>,
     /// Historique des preuves validées
     proof_history: HashMap<NodeId, Vec<ValidatedProof>>,
     /// Archives suivies pour les preuves
     tracked_archives: HashMap<Hash, ArchiveTrackingInfo>,
+    /// Journal d'audit des défis par nœud (ordre chronologique)
+    audit_log: HashMap<NodeId, Vec<ChallengeAuditEntry>>,
+    /// Fichier JSON Lines recevant chaque entrée d'audit
+    audit_log_path: Option<PathBuf>,
+    /// Résultats de défis pas encore répercutés sur la réputation des nœuds
+    pending_reputation_feedback: VecDeque<ChallengeAuditEntry>,
+}
+
+/// Issue d'un défi de stockage
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ChallengeOutcome {
+    /// Réponse valide reçue avant l'expiration
+    Passed,
+    /// Réponse invalide (échantillons, hash ou preuve de Merkle incorrects)
+    Failed,
+    /// Aucune réponse valide avant l'expiration du défi
+    TimedOut,
+}
+
+/// Entrée du journal d'audit des défis de stockage
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChallengeAuditEntry {
+    /// Nœud défié
+    pub node_id: NodeId,
+    /// Identifiant du défi
+    pub challenge_id: Hash,
+    /// Archive vérifiée
+    pub archive_hash: Hash,
+    /// Nonce du défi
+    pub nonce: u64,
+    /// Émission du défi
+    pub issued_at: chrono::DateTime<chrono::Utc>,
+    /// Enregistrement de l'issue
+    pub recorded_at: chrono::DateTime<chrono::Utc>,
+    /// Issue du défi
+    pub outcome: ChallengeOutcome,
+    /// Latence de la réponse (ms), absente si le nœud n'a pas répondu
+    pub response_latency_ms: Option<u64>,
+}
+
+impl ChallengeAuditEntry {
+    /// Indique si le défi a été réussi
+    pub fn passed(&self) -> bool {
+        self.outcome == ChallengeOutcome::Passed
+    }
 }
 
 /// Métriques de stockage pour un nœud
@@ -148,7 +201,69 @@ impl StorageProofManager {
             active_challenges: HashMap::new(),
             proof_history: HashMap::new(),
             tracked_archives: HashMap::new(),
+            audit_log: HashMap::new(),
+            audit_log_path: None,
+            pending_reputation_feedback: VecDeque::new(),
+        }
+    }
+
+    /// Active la persistance du journal d'audit dans un fichier JSON Lines
+    ///
+    /// Les entrées déjà présentes dans le fichier sont rechargées ; retourne leur nombre.
+    pub fn enable_audit_log(&mut self, path: impl AsRef<Path>) -> Result<usize> {
+        let path = path.as_ref();
+        let mut restored = 0;
+
+        match std::fs::File::open(path) {
+            Ok(file) => {
+                for line in std::io::BufReader::new(file).lines() {
+                    let line = line.map_err(|e| crate::error::CoreError::Internal {
+                        message: format!("Lecture du journal d'audit impossible: {}", e),
+                    })?;
+                    if line.trim().is_empty() {
+                        continue;
+                    }
+                    let entry: ChallengeAuditEntry = serde_json::from_str(&line)
+                        .map_err(SerializationError::from)?;
+                    self.push_audit_entry(entry);
+                    restored += 1;
+                }
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => {
+                return Err(crate::error::CoreError::Internal {
+                    message: format!("Lecture du journal d'audit impossible: {}", e),
+                })
+            }
+        }
+
+        self.audit_log_path = Some(path.to_path_buf());
+        Ok(restored)
+    }
+
+    /// Historique des défis d'un nœud depuis une date donnée (ordre chronologique)
+    pub fn get_challenge_history(&self, node_id: &NodeId, since: chrono::DateTime<chrono::Utc>) -> Vec<ChallengeAuditEntry> {
+        self.audit_log.get(node_id)
+            .map(|entries| entries.iter().filter(|e| e.recorded_at >= since).cloned().collect())
+            .unwrap_or_default()
+    }
+
+    /// Taux d'échec (échecs et expirations) d'un nœud depuis une date donnée
+    pub fn challenge_failure_rate(&self, node_id: &NodeId, since: chrono::DateTime<chrono::Utc>) -> Option<f64> {
+        let history = self.get_challenge_history(node_id, since);
+        if history.is_empty() {
+            return None;
         }
+        let failures = history.iter().filter(|e| !e.passed()).count();
+        Some(failures as f64 / history.len() as f64)
+    }
+
+    /// Retire les résultats de défis à répercuter sur la réputation des nœuds
+    ///
+    /// À transmettre à `NodeRegistry::apply_challenge_outcomes` et à
+    /// `EconomicModel::apply_storage_challenge_outcomes` (slashing des validateurs).
+    pub fn take_reputation_feedback(&mut self) -> Vec<ChallengeAuditEntry> {
+        self.pending_reputation_feedback.drain(..).collect()
     }
 
     /// Enregistre qu'un nœud stocke une archive
@@ -222,66 +337,106 @@ impl StorageProofManager {
     }
 
     /// Vérifie une réponse à un défi de stockage
+    ///
+    /// L'issue est consignée dans le journal d'audit, qu'elle soit positive ou non.
     pub fn verify_storage_response(
         &mut self,
         challenge: &StorageChallenge,
         response: &StorageChallengeResponse,
     ) -> Result<bool> {
-        // Vérifie que la réponse correspond au défi
-        if response.challenge_id != challenge.challenge_id {
-            return Ok(false);
+        let now = chrono::Utc::now();
+        let outcome = Self::evaluate_response(challenge, response, now);
+        let passed = outcome == ChallengeOutcome::Passed;
+
+        // Le défi a reçu sa réponse : il n'est plus actif
+        if self.active_challenges.get(&challenge.node_id)
+            .map_or(false, |active| active.challenge_id == challenge.challenge_id)
+        {
+            self.active_challenges.remove(&challenge.node_id);
+        }
+
+        let latency_ms = response.responded_at
+            .signed_duration_since(challenge.created_at)
+            .num_milliseconds()
+            .max(0) as u64;
+
+        self.record_audit_entry(ChallengeAuditEntry {
+            node_id: challenge.node_id.clone(),
+            challenge_id: challenge.challenge_id.clone(),
+            archive_hash: challenge.archive_hash.clone(),
+            nonce: challenge.nonce,
+            issued_at: challenge.created_at,
+            recorded_at: now,
+            outcome,
+            response_latency_ms: Some(latency_ms),
+        })?;
+
+        // Met à jour les métriques du nœud
+        self.update_node_metrics_after_challenge(&challenge.node_id, passed, response.responded_at)?;
+
+        if passed {
+            // Enregistre la preuve validée
+            self.record_validated_proof(challenge, response);
         }
 
+        Ok(passed)
+    }
+
+    /// Évalue une réponse par rapport au défi émis
+    fn evaluate_response(
+        challenge: &StorageChallenge,
+        response: &StorageChallengeResponse,
+        now: chrono::DateTime<chrono::Utc>,
+    ) -> ChallengeOutcome {
         // Vérifie que la réponse n'est pas expirée
-        if chrono::Utc::now() > challenge.expires_at {
-            return Ok(false);
+        if now > challenge.expires_at {
+            return ChallengeOutcome::TimedOut;
+        }
+
+        // Vérifie que la réponse correspond au défi
+        if response.challenge_id != challenge.challenge_id {
+            return ChallengeOutcome::Failed;
         }
 
         // Vérifie que tous les échantillons sont présents
         if response.data_samples.len() != challenge.sample_positions.len() {
-            return Ok(false);
+            return ChallengeOutcome::Failed;
         }
 
         // Vérifie chaque échantillon
         for (i, sample) in response.data_samples.iter().enumerate() {
             if sample.position != challenge.sample_positions[i] {
-                return Ok(false);
+                return ChallengeOutcome::Failed;
             }
 
             // Vérifie le hash de l'échantillon
             let expected_hash = compute_hash(&sample.data, challenge.hash_algorithm);
             if expected_hash != sample.data_hash {
-                return Ok(false);
+                return ChallengeOutcome::Failed;
             }
         }
 
         // Vérifie le hash combiné
         let sample_hashes: Vec<&[u8]> = response.data_samples
             .iter()
-            .map(|s| s.data_hash.as_bytes())
+            .map(|s| s.data_hash.as_bytes().as_slice())
             .collect();
         let expected_combined = compute_combined_hash(&sample_hashes, challenge.hash_algorithm);
         
         if expected_combined != response.combined_hash {
-            return Ok(false);
+            return ChallengeOutcome::Failed;
         }
 
         // Vérifie la preuve de Merkle
-        if !response.merkle_proof.verify(challenge.hash_algorithm) {
-            return Ok(false);
+        if !response.merkle_proof.verify_path(challenge.hash_algorithm) {
+            return ChallengeOutcome::Failed;
         }
 
-        // Met à jour les métriques du nœud
-        self.update_node_metrics_after_challenge(&challenge.node_id, true, response.responded_at)?;
-
-        // Enregistre la preuve validée
-        self.record_validated_proof(challenge, response);
-
-        Ok(true)
+        ChallengeOutcome::Passed
     }
 
     /// Obtient les métriques de stockage d'un nœud
-    pub fn get_node_metrics(&self, node_id: &NodeId) -> Result<StorageMetrics> {
+    pub fn get_node_metrics(&self, node_id: &NodeId) -> Result<NodeStorageMetrics> {
         self.node_metrics.get(node_id)
             .cloned()
             .ok_or_else(|| crate::error::CoreError::Internal {
@@ -321,13 +476,73 @@ impl StorageProofManager {
     }
 
     /// Nettoie les défis expirés
-    pub fn cleanup_expired_challenges(&mut self) {
-        let now = chrono::Utc::now();
-        self.active_challenges.retain(|_, challenge| challenge.expires_at > now);
+    ///
+    /// Les défis restés sans réponse sont consignés comme expirés ; retourne leur nombre.
+    pub fn cleanup_expired_challenges(&mut self) -> Result<usize> {
+        self.expire_challenges_at(chrono::Utc::now())
+    }
+
+    /// Consigne et retire les défis expirés à la date `now`
+    pub fn expire_challenges_at(&mut self, now: chrono::DateTime<chrono::Utc>) -> Result<usize> {
+        let expired: Vec<StorageChallenge> = self.active_challenges.values()
+            .filter(|challenge| challenge.expires_at <= now)
+            .cloned()
+            .collect();
+
+        for challenge in &expired {
+            self.active_challenges.remove(&challenge.node_id);
+            self.record_audit_entry(ChallengeAuditEntry {
+                node_id: challenge.node_id.clone(),
+                challenge_id: challenge.challenge_id.clone(),
+                archive_hash: challenge.archive_hash.clone(),
+                nonce: challenge.nonce,
+                issued_at: challenge.created_at,
+                recorded_at: now,
+                outcome: ChallengeOutcome::TimedOut,
+                response_latency_ms: None,
+            })?;
+            self.update_node_metrics_after_challenge(&challenge.node_id, false, now)?;
+        }
+
+        Ok(expired.len())
     }
 
     // Méthodes privées
 
+    /// Ajoute une entrée au journal d'audit (mémoire, fichier et file de réputation)
+    fn record_audit_entry(&mut self, entry: ChallengeAuditEntry) -> Result<()> {
+        if let Some(path) = &self.audit_log_path {
+            let mut line = serde_json::to_string(&entry).map_err(SerializationError::from)?;
+            line.push('\n');
+
+            std::fs::OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(path)
+                .and_then(|mut file| file.write_all(line.as_bytes()))
+                .map_err(|e| crate::error::CoreError::Internal {
+                    message: format!("Écriture du journal d'audit impossible: {}", e),
+                })?;
+        }
+
+        // Sans règlement, seules les issues les plus récentes sont conservées
+        if self.pending_reputation_feedback.len() >= MAX_PENDING_REPUTATION_FEEDBACK {
+            self.pending_reputation_feedback.pop_front();
+        }
+        self.pending_reputation_feedback.push_back(entry.clone());
+        self.push_audit_entry(entry);
+        Ok(())
+    }
+
+    fn push_audit_entry(&mut self, entry: ChallengeAuditEntry) {
+        let history = self.audit_log.entry(entry.node_id.clone()).or_insert_with(Vec::new);
+        history.push(entry);
+        if history.len() > MAX_AUDIT_ENTRIES_PER_NODE {
+            let excess = history.len() - MAX_AUDIT_ENTRIES_PER_NODE;
+            history.drain(0..excess);
+        }
+    }
+
     fn select_random_archive_for_node(&self, node_id: &NodeId) -> Result<Hash> {
         let archives: Vec<&Hash> = self.tracked_archives
             .iter()
@@ -361,14 +576,11 @@ impl StorageProofManager {
         response_time: chrono::DateTime<chrono::Utc>,
     ) -> Result<()> {
         if let Some(metrics) = self.node_metrics.get_mut(node_id) {
-            // Met à jour le taux de réussite
-            let total_challenges = self.proof_history.get(node_id).map(|h| h.len()).unwrap_or(0) + 1;
-            let successful_challenges = if success { 1 } else { 0 } + 
-                self.proof_history.get(node_id)
-                    .map(|h| h.iter().filter(|p| p.proof_score > 0.5).count())
-                    .unwrap_or(0);
-            
-            metrics.challenge_success_rate = successful_challenges as f64 / total_challenges as f64;
+            // Met à jour le taux de réussite à partir du journal d'audit
+            if let Some(history) = self.audit_log.get(node_id).filter(|h| !h.is_empty()) {
+                let successful_challenges = history.iter().filter(|e| e.passed()).count();
+                metrics.challenge_success_rate = successful_challenges as f64 / history.len() as f64;
+            }
             
             if success {
                 metrics.last_successful_proof = Some(response_time);
@@ -418,7 +630,7 @@ impl StorageProofManager {
 }
 
 impl ConsensusProof for StorageProofManager {
-    type Metrics = StorageMetrics;
+    type Metrics = NodeStorageMetrics;
 
     fn calculate_score(&self, node_id: &NodeId, _metrics: &Self::Metrics) -> Result<f64> {
         self.calculate_storage_score(node_id)
@@ -511,4 +723,117 @@ mod tests {
         assert!(!challenge.sample_positions.is_empty());
         assert!(challenge.expires_at > challenge.created_at);
     }
-}
\ No newline at end of file
+
+    fn setup_challenge() -> (StorageProofManager, NodeId, StorageChallenge) {
+        let config = ConsensusConfig::test_config();
+        let mut manager = StorageProofManager::new(&config);
+
+        let keypair = generate_keypair().unwrap();
+        let node_id = NodeId::from_public_key(keypair.public_key());
+        manager.register_storage(node_id.clone(), Hash::from_bytes(&[1; 32]).unwrap(), 10240);
+
+        let challenge = manager.generate_storage_challenge(&node_id).unwrap();
+        (manager, node_id, challenge)
+    }
+
+    fn empty_response(challenge: &StorageChallenge) -> StorageChallengeResponse {
+        StorageChallengeResponse {
+            challenge_id: challenge.challenge_id.clone(),
+            data_samples: Vec::new(),
+            combined_hash: Hash::zero(),
+            merkle_proof: MerkleProof {
+                leaf_hash: Hash::zero(),
+                path: Vec::new(),
+                root_hash: Hash::zero(),
+                algorithm: challenge.hash_algorithm,
+            },
+            responded_at: challenge.created_at + chrono::Duration::milliseconds(250),
+        }
+    }
+
+    #[test]
+    fn test_failed_challenge_is_audited() {
+        let (mut manager, node_id, challenge) = setup_challenge();
+        let since = chrono::Utc::now() - chrono::Duration::seconds(1);
+
+        let passed = manager.verify_storage_response(&challenge, &empty_response(&challenge)).unwrap();
+        assert!(!passed);
+
+        let history = manager.get_challenge_history(&node_id, since);
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].outcome, ChallengeOutcome::Failed);
+        assert_eq!(history[0].nonce, challenge.nonce);
+        assert_eq!(history[0].response_latency_ms, Some(250));
+        assert_eq!(manager.challenge_failure_rate(&node_id, since), Some(1.0));
+        assert_eq!(manager.get_node_metrics(&node_id).unwrap().challenge_success_rate, 0.0);
+
+        // Le défi a reçu sa réponse : il ne peut plus expirer
+        assert_eq!(manager.expire_challenges_at(challenge.expires_at).unwrap(), 0);
+
+        // L'historique est filtré par date
+        let future = chrono::Utc::now() + chrono::Duration::seconds(60);
+        assert!(manager.get_challenge_history(&node_id, future).is_empty());
+    }
+
+    #[test]
+    fn test_unanswered_challenge_times_out() {
+        let (mut manager, node_id, challenge) = setup_challenge();
+
+        assert_eq!(manager.expire_challenges_at(challenge.expires_at).unwrap(), 1);
+        assert_eq!(manager.expire_challenges_at(challenge.expires_at).unwrap(), 0);
+
+        let history = manager.get_challenge_history(&node_id, challenge.created_at);
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].outcome, ChallengeOutcome::TimedOut);
+        assert_eq!(history[0].response_latency_ms, None);
+
+        // Les issues sont transmises une seule fois à la réputation
+        let feedback = manager.take_reputation_feedback();
+        assert_eq!(feedback.len(), 1);
+        assert_eq!(feedback[0].challenge_id, challenge.challenge_id);
+        assert!(manager.take_reputation_feedback().is_empty());
+    }
+
+    #[test]
+    fn test_audit_log_persistence() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("challenges.jsonl");
+
+        let (mut manager, node_id, challenge) = setup_challenge();
+        assert_eq!(manager.enable_audit_log(&path).unwrap(), 0);
+        manager.verify_storage_response(&challenge, &empty_response(&challenge)).unwrap();
+
+        let mut restored = StorageProofManager::new(&ConsensusConfig::test_config());
+        assert_eq!(restored.enable_audit_log(&path).unwrap(), 1);
+
+        let history = restored.get_challenge_history(&node_id, challenge.created_at);
+        assert_eq!(history.len(), 1);
+        assert_eq!(history[0].challenge_id, challenge.challenge_id);
+        assert_eq!(history[0].outcome, ChallengeOutcome::Failed);
+        // Les entrées rechargées ont déjà été répercutées sur la réputation
+        assert!(restored.take_reputation_feedback().is_empty());
+    }
+    #[test]
+    fn test_pending_reputation_feedback_is_bounded() {
+        let (mut manager, node_id, challenge) = setup_challenge();
+        let entry = |nonce| ChallengeAuditEntry {
+            node_id: node_id.clone(),
+            challenge_id: challenge.challenge_id.clone(),
+            archive_hash: challenge.archive_hash.clone(),
+            nonce,
+            issued_at: challenge.created_at,
+            recorded_at: challenge.created_at,
+            outcome: ChallengeOutcome::Failed,
+            response_latency_ms: None,
+        };
+
+        for nonce in 0..(MAX_PENDING_REPUTATION_FEEDBACK as u64 + 5) {
+            manager.record_audit_entry(entry(nonce)).unwrap();
+        }
+
+        // Les issues les plus anciennes sont abandonnées
+        let feedback = manager.take_reputation_feedback();
+        assert_eq!(feedback.len(), MAX_PENDING_REPUTATION_FEEDBACK);
+        assert_eq!(feedback[0].nonce, 5);
+    }
+}
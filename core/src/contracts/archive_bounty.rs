@@ -3,14 +3,20 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use chrono::{DateTime, Utc, Duration};
+use crate::block::normalize_url;
 use crate::crypto::{Hash, PublicKey};
 use crate::contracts::{
     ContractError, ContractResult, ContractContext, SmartContract, 
     ContractMetadata, ContractVersion, AbiValue
 };
+use crate::contracts::content_verification::{ContentVerificationContract, VerificationStatus};
+use crate::storage::ArchiveManifest;
 
 /// Niveau de qualité requis pour un archivage
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+///
+/// Les niveaux sont ordonnés : un niveau supérieur satisfait les exigences
+/// des niveaux inférieurs.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
 pub enum QualityLevel {
     /// Archivage basique (compression minimale, vérification simple)
     Basic,
@@ -45,7 +51,7 @@ impl QualityLevel {
 }
 
 /// Statut d'un bounty d'archivage
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub enum BountyStatus {
     /// Bounty actif, en attente de soumissions
     Active,
@@ -59,6 +65,20 @@ pub enum BountyStatus {
     Validating,
 }
 
+/// État des fonds bloqués par un bounty
+///
+/// La récompense est bloquée à la création et ne peut sortir du contrat
+/// qu'une seule fois : versée à l'archiviste ou remboursée au créateur.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum EscrowStatus {
+    /// Récompense bloquée dans le contrat
+    Locked,
+    /// Récompense versée à l'archiviste
+    Released,
+    /// Récompense remboursée au créateur
+    Refunded,
+}
+
 /// Métadonnées d'archive requises
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ArchiveMetadata {
@@ -74,6 +94,79 @@ pub struct ArchiveMetadata {
     pub additional_metadata: HashMap<String, String>,
 }
 
+/// Archive présentée pour réclamer un bounty
+///
+/// Résume le manifeste du crawl : les ressources capturées ou en échec
+/// mesurent la complétude des assets, les profondeurs atteinte et tentée
+/// la réussite du suivi des liens.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ArchiveCapture {
+    /// Identifiant de l'archive
+    pub archive_id: String,
+    /// URL racine capturée
+    pub url: String,
+    /// Hash du manifeste de l'archive
+    pub content_hash: Hash,
+    /// Ressources du périmètre du crawl, capturées ou en échec
+    pub resources_expected: u32,
+    /// Ressources effectivement capturées
+    pub resources_captured: u32,
+    /// Profondeur la plus grande tentée
+    pub depth_attempted: u32,
+    /// Profondeur la plus grande capturée
+    pub depth_reached: u32,
+}
+
+impl ArchiveCapture {
+    /// Résume le manifeste d'une archive crawlée
+    ///
+    /// Les ressources ignorées par les options de crawl ne sont pas attendues.
+    pub fn from_manifest(archive_id: impl Into<String>, content_hash: Hash, manifest: &ArchiveManifest) -> Self {
+        let captured = manifest.entries.iter().map(|entry| entry.depth);
+        let failed = manifest.failures.iter().map(|failure| failure.depth);
+        Self {
+            archive_id: archive_id.into(),
+            url: manifest.root_url.clone(),
+            content_hash,
+            resources_expected: (manifest.entries.len() + manifest.failures.len()) as u32,
+            resources_captured: manifest.entries.len() as u32,
+            depth_attempted: captured.clone().chain(failed).max().unwrap_or(0),
+            depth_reached: captured.max().unwrap_or(0),
+        }
+    }
+
+    /// Part des ressources attendues effectivement capturées
+    pub fn completeness(&self) -> f64 {
+        if self.resources_expected == 0 {
+            return 0.0;
+        }
+        self.resources_captured as f64 / self.resources_expected as f64
+    }
+
+    /// Part des niveaux de profondeur tentés qui ont été capturés
+    pub fn depth_ratio(&self) -> f64 {
+        (self.depth_reached + 1) as f64 / (self.depth_attempted + 1) as f64
+    }
+
+    /// Niveau de qualité de la capture
+    ///
+    /// Chaque niveau exige à la fois une complétude et une profondeur
+    /// minimales : une capture complète mais tronquée reste `Basic`.
+    pub fn quality_level(&self) -> QualityLevel {
+        let completeness = self.completeness();
+        let depth = self.depth_ratio();
+        if completeness >= 1.0 && depth >= 1.0 {
+            QualityLevel::Premium
+        } else if completeness >= 0.9 && depth >= 0.75 {
+            QualityLevel::High
+        } else if completeness >= 0.75 && depth >= 0.5 {
+            QualityLevel::Standard
+        } else {
+            QualityLevel::Basic
+        }
+    }
+}
+
 /// Soumission d'archivage pour un bounty
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ArchiveSubmission {
@@ -131,6 +224,11 @@ pub struct ArchiveBounty {
     pub submissions: Vec<ArchiveSubmission>,
     /// Gagnant sélectionné (si complété)
     pub winner: Option<PublicKey>,
+    /// Archive ayant permis de réclamer le bounty
+    #[serde(default)]
+    pub claimed_archive: Option<String>,
+    /// État de la récompense bloquée
+    pub escrow: EscrowStatus,
     /// Timestamp de création
     pub created_at: DateTime<Utc>,
     /// Nombre maximum de soumissions acceptées
@@ -180,7 +278,7 @@ pub struct ArchiveBountyState {
     pub bounties_by_creator: HashMap<PublicKey, Vec<u64>>,
     /// Index des bounties par statut
     pub bounties_by_status: HashMap<BountyStatus, Vec<u64>>,
-    /// Pool total de récompenses
+    /// Pool total des récompenses bloquées
     pub total_reward_pool: u64,
     /// Statistiques globales
     pub stats: BountyStats,
@@ -242,10 +340,30 @@ pub enum ArchiveBountyCall {
         quality_score: f64,
         notes: String,
     },
+    /// Réclame un bounty avec une archive de l'URL cible
+    ClaimBounty {
+        bounty_id: u64,
+        capture: ArchiveCapture,
+    },
+    /// Augmente la récompense ou repousse la deadline d'un bounty
+    UpdateBounty {
+        bounty_id: u64,
+        additional_reward: u64,
+        extend_hours: u64,
+    },
     /// Annule un bounty
     CancelBounty {
         bounty_id: u64,
     },
+    /// Accepte une soumission et verse la récompense à son auteur
+    AcceptSubmission {
+        bounty_id: u64,
+        submission_id: Hash,
+    },
+    /// Rembourse le créateur d'un bounty expiré
+    RefundBounty {
+        bounty_id: u64,
+    },
     /// Récupère les détails d'un bounty
     GetBounty {
         bounty_id: u64,
@@ -267,8 +385,16 @@ pub enum ArchiveBountyReturn {
     SubmissionReceived { submission_id: Hash },
     /// Confirmation de validation
     ValidationCompleted { bounty_completed: bool },
-    /// Confirmation d'annulation
-    BountyCancelled,
+    /// Récompense versée à l'auteur de l'archive
+    BountyClaimed { amount: u64 },
+    /// Bounty mis à jour
+    BountyUpdated(ArchiveBounty),
+    /// Confirmation d'annulation, récompense remboursée au créateur
+    BountyCancelled { amount: u64 },
+    /// Soumission acceptée, récompense versée à son auteur
+    SubmissionAccepted { amount: u64 },
+    /// Récompense remboursée au créateur
+    BountyRefunded { amount: u64 },
     /// Détails d'un bounty
     BountyDetails(ArchiveBounty),
     /// Liste de bounties
@@ -303,12 +429,12 @@ impl ArchiveBountyContract {
         criteria: ValidationCriteria,
         context: &mut ContractContext,
     ) -> ContractResult<u64> {
-        // Vérifie que le créateur a suffisamment de fonds
-        let creator_balance = context.get_balance(&creator)?;
-        if creator_balance < reward {
+        // La récompense doit accompagner l'appel pour être bloquée par le contrat
+        let value_sent = context.get_value();
+        if value_sent < reward {
             return Err(ContractError::InsufficientFunds {
                 required: reward,
-                available: creator_balance,
+                available: value_sent,
             });
         }
 
@@ -334,6 +460,8 @@ impl ArchiveBountyContract {
             target_metadata: metadata,
             submissions: Vec::new(),
             winner: None,
+            claimed_archive: None,
+            escrow: EscrowStatus::Locked,
             created_at: Utc::now(),
             max_submissions: criteria.auto_validation.then(|| 10).unwrap_or(100),
             validation_criteria: criteria,
@@ -475,29 +603,10 @@ impl ArchiveBountyContract {
         
         submission.quality_score = quality_score;
 
-        // Valide selon le niveau de qualité requis
+        // Valide selon le niveau de qualité requis ; la récompense n'est versée
+        // qu'après confirmation par la vérification de contenu
         if quality_score >= bounty.required_quality.reward_multiplier() * 0.5 {
             submission.validation_status = ValidationStatus::Validated;
-            
-            // Si c'est la première soumission valide, marque le bounty comme complété
-            if bounty.status == BountyStatus::Active {
-                bounty.status = BountyStatus::Completed;
-                bounty.winner = Some(submission.submitter.clone());
-                
-                // Distribue la récompense
-                context.transfer_tokens(submission.submitter.clone(), bounty.reward)?;
-                
-                // Met à jour les statistiques
-                self.state.stats.total_bounties_completed += 1;
-                self.state.stats.total_rewards_distributed += bounty.reward;
-                self.state.stats.total_archived_content_size += submission.metadata.estimated_size;
-                
-                context.emit_event(
-                    "BountyCompleted".to_string(),
-                    bincode::serialize(&bounty_id).unwrap_or_default(),
-                    vec![context.compute_hash(&submission.submitter.as_bytes())?],
-                );
-            }
         } else {
             submission.validation_status = ValidationStatus::Rejected(
                 "Quality score too low".to_string()
@@ -507,6 +616,387 @@ impl ArchiveBountyContract {
         Ok(())
     }
 
+    /// Verse la récompense bloquée à l'auteur d'une soumission vérifiée
+    ///
+    /// L'archive soumise doit avoir été vérifiée par `verification` : la
+    /// vérification doit être `Verified` (consensus des vérificateurs au moins
+    /// égal au seuil de ses règles) et le niveau de qualité confirmé doit
+    /// atteindre celui du bounty. Aucun état n'est modifié en cas d'échec et
+    /// la récompense n'est versée qu'une fois, en totalité.
+    ///
+    /// Une soumission reçue avant la deadline reste payable après celle-ci,
+    /// tant que le créateur n'a pas été remboursé.
+    pub fn release_reward(
+        &mut self,
+        bounty_id: u64,
+        submission_id: Hash,
+        verification: &ContentVerificationContract,
+        context: &mut ContractContext,
+    ) -> ContractResult<u64> {
+        let bounty = self.state.bounties.get(&bounty_id)
+            .ok_or(ContractError::InvalidParameters {
+                message: format!("Bounty {} not found", bounty_id),
+            })?;
+
+        if bounty.escrow != EscrowStatus::Locked {
+            return Err(ContractError::AlreadyCompleted);
+        }
+        if bounty.status == BountyStatus::Cancelled {
+            return Err(ContractError::InvalidState {
+                message: "Bounty was cancelled".to_string(),
+            });
+        }
+
+        let submission = bounty.submissions.iter()
+            .find(|s| s.submission_id == submission_id)
+            .ok_or(ContractError::InvalidParameters {
+                message: "Submission not found".to_string(),
+            })?;
+        if matches!(submission.validation_status, ValidationStatus::Rejected(_)) {
+            return Err(ContractError::InvalidState {
+                message: "Submission was rejected".to_string(),
+            });
+        }
+
+        // Confirmation par les vérificateurs de l'archive soumise
+        let content = verification.get_verification_status(submission.archive_hash)?;
+        if content.status != VerificationStatus::Verified {
+            return Err(ContractError::InsufficientConsensus {
+                required: content.verification_rules.consensus_threshold,
+                achieved: content.consensus_score(),
+            });
+        }
+        let provided = content.confirmed_quality().ok_or(ContractError::InsufficientQuality {
+            required: bounty.required_quality.clone(),
+            provided: QualityLevel::Basic,
+        })?;
+        if provided < bounty.required_quality {
+            return Err(ContractError::InsufficientQuality {
+                required: bounty.required_quality.clone(),
+                provided,
+            });
+        }
+
+        // Le transfert précède toute modification d'état : s'il échoue, le
+        // bounty reste intact
+        let winner = submission.submitter.clone();
+        let reward = bounty.reward;
+        let archived_size = submission.metadata.estimated_size;
+        context.transfer_tokens(winner.clone(), reward)?;
+
+        let bounty = self.state.bounties.get_mut(&bounty_id).expect("bounty checked above");
+        bounty.escrow = EscrowStatus::Released;
+        bounty.winner = Some(winner.clone());
+        self.set_status(bounty_id, BountyStatus::Completed);
+
+        self.state.total_reward_pool -= reward;
+        self.state.stats.total_bounties_completed += 1;
+        self.state.stats.total_rewards_distributed += reward;
+        self.state.stats.total_archived_content_size += archived_size;
+
+        context.emit_event(
+            "BountyCompleted".to_string(),
+            bincode::serialize(&bounty_id).unwrap_or_default(),
+            vec![context.compute_hash(&winner.as_bytes())?],
+        );
+
+        context.emit_log(format!(
+            "Bounty {} reward of {} ARC released to {:?}",
+            bounty_id, reward, winner
+        ));
+
+        Ok(reward)
+    }
+
+    /// Verse la récompense bloquée à l'auteur d'une archive de l'URL cible
+    ///
+    /// L'archive doit capturer l'URL du bounty (comparée après normalisation)
+    /// et sa qualité, calculée depuis le manifeste du crawl, doit atteindre
+    /// celle du bounty. La réclamation doit intervenir avant la deadline ;
+    /// aucun état n'est modifié en cas d'échec et la récompense n'est versée
+    /// qu'une fois.
+    pub fn claim_bounty(
+        &mut self,
+        claimer: PublicKey,
+        bounty_id: u64,
+        capture: &ArchiveCapture,
+        context: &mut ContractContext,
+    ) -> ContractResult<u64> {
+        let bounty = self.state.bounties.get(&bounty_id)
+            .ok_or(ContractError::InvalidParameters {
+                message: format!("Bounty {} not found", bounty_id),
+            })?;
+
+        if bounty.escrow != EscrowStatus::Locked {
+            return Err(ContractError::AlreadyCompleted);
+        }
+        if bounty.status == BountyStatus::Cancelled {
+            return Err(ContractError::InvalidState {
+                message: "Bounty was cancelled".to_string(),
+            });
+        }
+        if Utc::now() > bounty.deadline {
+            return Err(ContractError::DeadlineExpired);
+        }
+        if normalize_url(&capture.url) != normalize_url(&bounty.target_url) {
+            return Err(ContractError::InvalidParameters {
+                message: format!("Archive {} does not capture {}", capture.archive_id, bounty.target_url),
+            });
+        }
+
+        let provided = capture.quality_level();
+        if provided < bounty.required_quality {
+            return Err(ContractError::InsufficientQuality {
+                required: bounty.required_quality.clone(),
+                provided,
+            });
+        }
+
+        let reward = bounty.reward;
+        context.transfer_tokens(claimer.clone(), reward)?;
+
+        let bounty = self.state.bounties.get_mut(&bounty_id).expect("bounty checked above");
+        bounty.escrow = EscrowStatus::Released;
+        bounty.winner = Some(claimer.clone());
+        bounty.claimed_archive = Some(capture.archive_id.clone());
+        self.set_status(bounty_id, BountyStatus::Completed);
+
+        self.state.total_reward_pool -= reward;
+        self.state.stats.total_bounties_completed += 1;
+        self.state.stats.total_rewards_distributed += reward;
+
+        context.emit_event(
+            "BountyClaimed".to_string(),
+            bincode::serialize(&(bounty_id, &capture.archive_id, &provided)).unwrap_or_default(),
+            vec![
+                context.compute_hash(&claimer.as_bytes())?,
+                context.compute_hash(&bounty_id.to_le_bytes())?,
+            ],
+        );
+        context.emit_event(
+            "BountyCompleted".to_string(),
+            bincode::serialize(&bounty_id).unwrap_or_default(),
+            vec![context.compute_hash(&claimer.as_bytes())?],
+        );
+
+        context.emit_log(format!(
+            "Bounty {} claimed with archive {} ({:?}), {} ARC paid to {:?}",
+            bounty_id, capture.archive_id, provided, reward, claimer
+        ));
+
+        Ok(reward)
+    }
+
+    /// Rembourse le créateur d'un bounty dont la deadline est passée sans
+    /// qu'aucune récompense n'ait été versée
+    pub fn refund_bounty(
+        &mut self,
+        bounty_id: u64,
+        context: &mut ContractContext,
+    ) -> ContractResult<u64> {
+        let bounty = self.state.bounties.get(&bounty_id)
+            .ok_or(ContractError::InvalidParameters {
+                message: format!("Bounty {} not found", bounty_id),
+            })?;
+
+        if bounty.escrow != EscrowStatus::Locked {
+            return Err(ContractError::AlreadyCompleted);
+        }
+        if Utc::now() <= bounty.deadline {
+            return Err(ContractError::InvalidState {
+                message: "Bounty deadline not reached".to_string(),
+            });
+        }
+
+        let creator = bounty.creator.clone();
+        let reward = bounty.reward;
+        context.transfer_tokens(creator.clone(), reward)?;
+
+        let bounty = self.state.bounties.get_mut(&bounty_id).expect("bounty checked above");
+        bounty.escrow = EscrowStatus::Refunded;
+        self.set_status(bounty_id, BountyStatus::Expired);
+        self.state.total_reward_pool -= reward;
+
+        context.emit_event(
+            "BountyRefunded".to_string(),
+            bincode::serialize(&bounty_id).unwrap_or_default(),
+            vec![context.compute_hash(&creator.as_bytes())?],
+        );
+
+        context.emit_log(format!(
+            "Bounty {} expired, {} ARC refunded to {:?}",
+            bounty_id, reward, creator
+        ));
+
+        Ok(reward)
+    }
+
+    /// Augmente la récompense ou repousse la deadline d'un bounty actif
+    ///
+    /// Seul le créateur peut modifier son bounty ; la récompense
+    /// supplémentaire doit accompagner l'appel pour être bloquée avec la
+    /// récompense initiale.
+    pub fn update_bounty(
+        &mut self,
+        caller: &PublicKey,
+        bounty_id: u64,
+        additional_reward: u64,
+        extend_hours: u64,
+        context: &mut ContractContext,
+    ) -> ContractResult<ArchiveBounty> {
+        let bounty = self.active_bounty_of(caller, bounty_id)?;
+        if Utc::now() > bounty.deadline {
+            return Err(ContractError::DeadlineExpired);
+        }
+        if additional_reward == 0 && extend_hours == 0 {
+            return Err(ContractError::InvalidParameters {
+                message: "Nothing to update".to_string(),
+            });
+        }
+        let value_sent = context.get_value();
+        if value_sent != additional_reward {
+            return Err(ContractError::InsufficientFunds {
+                required: additional_reward,
+                available: value_sent,
+            });
+        }
+
+        let bounty = self.state.bounties.get_mut(&bounty_id).expect("bounty checked above");
+        bounty.reward += additional_reward;
+        bounty.deadline = bounty.deadline + Duration::hours(extend_hours as i64);
+        let updated = bounty.clone();
+        self.state.total_reward_pool += additional_reward;
+
+        context.emit_event(
+            "BountyUpdated".to_string(),
+            bincode::serialize(&(bounty_id, updated.reward, updated.deadline.timestamp())).unwrap_or_default(),
+            vec![context.compute_hash(&caller.as_bytes())?],
+        );
+
+        Ok(updated)
+    }
+
+    /// Annule un bounty actif et rembourse son créateur
+    ///
+    /// Seul le créateur peut annuler, et seulement tant qu'aucune soumission
+    /// n'a été validée : une archive déjà produite pour le bounty doit pouvoir
+    /// être acceptée.
+    pub fn cancel_bounty(
+        &mut self,
+        caller: &PublicKey,
+        bounty_id: u64,
+        context: &mut ContractContext,
+    ) -> ContractResult<u64> {
+        let bounty = self.active_bounty_of(caller, bounty_id)?;
+        if bounty.submissions.iter().any(|s| s.validation_status == ValidationStatus::Validated) {
+            return Err(ContractError::InvalidState {
+                message: "Bounty has validated submissions".to_string(),
+            });
+        }
+
+        let reward = bounty.reward;
+        context.transfer_tokens(caller.clone(), reward)?;
+
+        let bounty = self.state.bounties.get_mut(&bounty_id).expect("bounty checked above");
+        bounty.escrow = EscrowStatus::Refunded;
+        self.set_status(bounty_id, BountyStatus::Cancelled);
+        self.state.total_reward_pool -= reward;
+
+        context.emit_event(
+            "BountyCancelled".to_string(),
+            bincode::serialize(&bounty_id).unwrap_or_default(),
+            vec![context.compute_hash(&caller.as_bytes())?],
+        );
+
+        Ok(reward)
+    }
+
+    /// Accepte une soumission validée et verse la récompense à son auteur
+    ///
+    /// Seul le créateur du bounty peut accepter une soumission ; comme pour
+    /// `release_reward`, une soumission reçue avant la deadline reste payable
+    /// après celle-ci.
+    pub fn accept_submission(
+        &mut self,
+        caller: &PublicKey,
+        bounty_id: u64,
+        submission_id: Hash,
+        context: &mut ContractContext,
+    ) -> ContractResult<u64> {
+        let bounty = self.active_bounty_of(caller, bounty_id)?;
+        let submission = bounty.submissions.iter()
+            .find(|s| s.submission_id == submission_id)
+            .ok_or(ContractError::InvalidParameters {
+                message: "Submission not found".to_string(),
+            })?;
+        if submission.validation_status != ValidationStatus::Validated {
+            return Err(ContractError::InvalidState {
+                message: "Submission is not validated".to_string(),
+            });
+        }
+
+        let winner = submission.submitter.clone();
+        let reward = bounty.reward;
+        let archived_size = submission.metadata.estimated_size;
+        context.transfer_tokens(winner.clone(), reward)?;
+
+        let bounty = self.state.bounties.get_mut(&bounty_id).expect("bounty checked above");
+        bounty.escrow = EscrowStatus::Released;
+        bounty.winner = Some(winner.clone());
+        self.set_status(bounty_id, BountyStatus::Completed);
+
+        self.state.total_reward_pool -= reward;
+        self.state.stats.total_bounties_completed += 1;
+        self.state.stats.total_rewards_distributed += reward;
+        self.state.stats.total_archived_content_size += archived_size;
+
+        context.emit_event(
+            "BountyCompleted".to_string(),
+            bincode::serialize(&bounty_id).unwrap_or_default(),
+            vec![context.compute_hash(&winner.as_bytes())?],
+        );
+
+        Ok(reward)
+    }
+
+    /// Bounty actif de `caller`, dont la récompense est encore bloquée
+    fn active_bounty_of(&self, caller: &PublicKey, bounty_id: u64) -> ContractResult<&ArchiveBounty> {
+        let bounty = self.state.bounties.get(&bounty_id)
+            .ok_or(ContractError::InvalidParameters {
+                message: format!("Bounty {} not found", bounty_id),
+            })?;
+        if bounty.creator != *caller {
+            return Err(ContractError::Unauthorized {
+                message: "Only the bounty creator can manage it".to_string(),
+            });
+        }
+        if bounty.escrow != EscrowStatus::Locked {
+            return Err(ContractError::AlreadyCompleted);
+        }
+        if bounty.status != BountyStatus::Active {
+            return Err(ContractError::InvalidState {
+                message: "Bounty is not active".to_string(),
+            });
+        }
+        Ok(bounty)
+    }
+
+    /// Change le statut d'un bounty en maintenant l'index par statut
+    fn set_status(&mut self, bounty_id: u64, status: BountyStatus) {
+        let Some(bounty) = self.state.bounties.get_mut(&bounty_id) else {
+            return;
+        };
+
+        if let Some(ids) = self.state.bounties_by_status.get_mut(&bounty.status) {
+            ids.retain(|&id| id != bounty_id);
+        }
+        self.state.bounties_by_status
+            .entry(status.clone())
+            .or_insert_with(Vec::new)
+            .push(bounty_id);
+        bounty.status = status;
+    }
+
     /// Calcule un score de qualité pour une soumission
     fn calculate_quality_score(&self, metadata: &ArchiveMetadata, required_quality: &QualityLevel) -> f64 {
         let mut score = 0.5; // Score de base
@@ -661,6 +1151,35 @@ impl SmartContract for ArchiveBountyContract {
                 Ok(ArchiveBountyReturn::SubmissionReceived { submission_id })
             }
             
+            ArchiveBountyCall::ClaimBounty { bounty_id, capture } => {
+                let caller = context.get_caller().clone();
+                let amount = self.claim_bounty(caller, bounty_id, &capture, context)?;
+                Ok(ArchiveBountyReturn::BountyClaimed { amount })
+            }
+
+            ArchiveBountyCall::RefundBounty { bounty_id } => {
+                let amount = self.refund_bounty(bounty_id, context)?;
+                Ok(ArchiveBountyReturn::BountyRefunded { amount })
+            }
+
+            ArchiveBountyCall::UpdateBounty { bounty_id, additional_reward, extend_hours } => {
+                let caller = context.get_caller().clone();
+                let bounty = self.update_bounty(&caller, bounty_id, additional_reward, extend_hours, context)?;
+                Ok(ArchiveBountyReturn::BountyUpdated(bounty))
+            }
+
+            ArchiveBountyCall::CancelBounty { bounty_id } => {
+                let caller = context.get_caller().clone();
+                let amount = self.cancel_bounty(&caller, bounty_id, context)?;
+                Ok(ArchiveBountyReturn::BountyCancelled { amount })
+            }
+
+            ArchiveBountyCall::AcceptSubmission { bounty_id, submission_id } => {
+                let caller = context.get_caller().clone();
+                let amount = self.accept_submission(&caller, bounty_id, submission_id, context)?;
+                Ok(ArchiveBountyReturn::SubmissionAccepted { amount })
+            }
+            
             ArchiveBountyCall::GetBounty { bounty_id } => {
                 let bounty = self.get_bounty(bounty_id)?;
                 Ok(ArchiveBountyReturn::BountyDetails(bounty))
@@ -717,7 +1236,7 @@ mod tests {
             transaction_sender: keypair.public_key().clone(),
             contract_address: Hash::zero(),
             caller_address: keypair.public_key().clone(),
-            value_sent: 0,
+            value_sent: 1000,
             gas_limit: 1000000,
             gas_price: 1,
         };
@@ -752,6 +1271,421 @@ mod tests {
         let bounty = contract.get_bounty(bounty_id).unwrap();
         assert_eq!(bounty.reward, 1000);
         assert_eq!(bounty.status, BountyStatus::Active);
+        assert_eq!(bounty.escrow, EscrowStatus::Locked);
+        assert_eq!(contract.state.total_reward_pool, 1000);
+    }
+
+    /// Contexte dont le contrat détient des fonds, appelé par `caller` avec `value`
+    fn funded_context(caller: &PublicKey, value: u64) -> ContractContext {
+        let contract_key = generate_keypair().unwrap().public_key().clone();
+        let env = ExecutionEnvironment {
+            block_hash: Hash::zero(),
+            block_number: 1,
+            block_timestamp: Utc::now(),
+            transaction_hash: Hash::zero(),
+            transaction_sender: caller.clone(),
+            contract_address: Hash::from_bytes(contract_key.as_bytes()).unwrap(),
+            caller_address: caller.clone(),
+            value_sent: value,
+            gas_limit: 1000000,
+            gas_price: 1,
+        };
+
+        let mut provider = MockContextProvider::new();
+        provider.set_balance(contract_key, 100_000);
+        ContractContext::new(env, Box::new(provider))
+    }
+
+    fn page_metadata() -> ArchiveMetadata {
+        ArchiveMetadata {
+            content_url: "https://example.com/page".to_string(),
+            estimated_size: 2048,
+            content_type: "text/html".to_string(),
+            original_hash: None,
+            additional_metadata: HashMap::new(),
+        }
+    }
+
+    /// Fait vérifier `archive_hash` par trois vérificateurs confirmant
+    /// l'intégrité et la taille (niveau `Standard`)
+    fn verify_archive(
+        verification: &mut ContentVerificationContract,
+        archive_hash: Hash,
+        successes: usize,
+        context: &mut ContractContext,
+    ) {
+        use crate::contracts::content_verification::{
+            ContentMetadata, CriticalityLevel, VerificationDetails, VerificationResult,
+        };
+        use crate::contracts::VerificationRules;
+
+        verification.initiate_verification(
+            archive_hash,
+            ContentMetadata {
+                original_size: 2048,
+                content_type: "text/html".to_string(),
+                expected_hash: archive_hash,
+                checksums: HashMap::new(),
+                created_at: Utc::now(),
+                owner: generate_keypair().unwrap().public_key().clone(),
+                criticality_level: CriticalityLevel::Standard,
+            },
+            VerificationRules::default(),
+            context,
+        ).unwrap();
+
+        for i in 0..3 {
+            let verifier = generate_keypair().unwrap().public_key().clone();
+            verification.register_verifier(verifier.clone(), context).unwrap();
+            let success = i < successes;
+            let result = VerificationResult {
+                verifier: verifier.clone(),
+                timestamp: Utc::now(),
+                success,
+                confidence_score: 0.9,
+                details: VerificationDetails {
+                    integrity_valid: Some(success),
+                    size_valid: Some(success),
+                    format_valid: None,
+                    metadata_valid: None,
+                    redundancy_valid: None,
+                    verification_time_ms: 10,
+                    messages: Vec::new(),
+                },
+                proof_hash: Hash::zero(),
+                signature: Vec::new(),
+            };
+            verification.submit_verification(verifier, archive_hash, result, context).unwrap();
+        }
+    }
+
+    #[test]
+    fn test_escrow_released_once_after_verification() {
+        let mut contract = ArchiveBountyContract::default();
+        let creator = generate_keypair().unwrap().public_key().clone();
+        let archiver = generate_keypair().unwrap().public_key().clone();
+        let mut context = funded_context(&creator, 1000);
+
+        // La récompense doit accompagner la création
+        let mut unfunded = funded_context(&creator, 999);
+        assert!(matches!(
+            contract.create_bounty(creator.clone(), "https://example.com".to_string(), 1000, 24, QualityLevel::Standard, page_metadata(), ValidationCriteria::default(), &mut unfunded),
+            Err(ContractError::InsufficientFunds { required: 1000, available: 999 })
+        ));
+
+        let bounty_id = contract.create_bounty(
+            creator.clone(),
+            "https://example.com".to_string(),
+            1000,
+            24,
+            QualityLevel::Standard,
+            page_metadata(),
+            ValidationCriteria::default(),
+            &mut context,
+        ).unwrap();
+
+        let archive_hash = crate::crypto::compute_blake3(b"archived page");
+        let submission_id = contract.submit_archive(
+            archiver.clone(), bounty_id, archive_hash, page_metadata(), vec![1, 2, 3], &mut context,
+        ).unwrap();
+        // La validation automatique ne paie plus directement
+        assert!(context.get_token_transfers().is_empty());
+
+        // Rien n'est versé tant que l'archive n'a pas été vérifiée
+        let mut verification = ContentVerificationContract::default();
+        assert!(matches!(
+            contract.release_reward(bounty_id, submission_id, &verification, &mut context),
+            Err(ContractError::InvalidParameters { .. })
+        ));
+        verify_archive(&mut verification, archive_hash, 3, &mut context);
+
+        let paid = contract.release_reward(bounty_id, submission_id, &verification, &mut context).unwrap();
+        assert_eq!(paid, 1000);
+        let payouts: Vec<_> = context.get_token_transfers().iter().filter(|t| t.to == archiver).collect();
+        assert_eq!(payouts.len(), 1);
+        assert_eq!(payouts[0].amount, 1000);
+
+        let bounty = contract.get_bounty(bounty_id).unwrap();
+        assert_eq!(bounty.escrow, EscrowStatus::Released);
+        assert_eq!(bounty.status, BountyStatus::Completed);
+        assert_eq!(bounty.winner, Some(archiver.clone()));
+        assert_eq!(contract.state.total_reward_pool, 0);
+        assert_eq!(contract.list_bounties_by_status(BountyStatus::Completed, 10, 0).unwrap().len(), 1);
+        assert!(contract.list_bounties_by_status(BountyStatus::Active, 10, 0).unwrap().is_empty());
+
+        // Pas de second versement ni de remboursement
+        assert!(matches!(
+            contract.release_reward(bounty_id, submission_id, &verification, &mut context),
+            Err(ContractError::AlreadyCompleted)
+        ));
+        contract.state.bounties.get_mut(&bounty_id).unwrap().deadline = Utc::now() - Duration::hours(1);
+        assert!(matches!(contract.refund_bounty(bounty_id, &mut context), Err(ContractError::AlreadyCompleted)));
+        assert_eq!(context.get_token_transfers().iter().filter(|t| t.amount == 1000).count(), 1);
+    }
+
+    #[test]
+    fn test_failed_verification_blocks_release_and_refunds_after_deadline() {
+        let mut contract = ArchiveBountyContract::default();
+        let creator = generate_keypair().unwrap().public_key().clone();
+        let archiver = generate_keypair().unwrap().public_key().clone();
+        let mut context = funded_context(&creator, 2000);
+
+        let high = contract.create_bounty(
+            creator.clone(), "https://example.com/a".to_string(), 1000, 24, QualityLevel::High, page_metadata(), ValidationCriteria::default(), &mut context,
+        ).unwrap();
+        let standard = contract.create_bounty(
+            creator.clone(), "https://example.com/b".to_string(), 1000, 24, QualityLevel::Standard, page_metadata(), ValidationCriteria::default(), &mut context,
+        ).unwrap();
+
+        let mut verification = ContentVerificationContract::default();
+
+        // Vérifiée au niveau Standard seulement
+        let standard_archive = crate::crypto::compute_blake3(b"standard archive");
+        let submission = contract.submit_archive(
+            archiver.clone(), high, standard_archive, page_metadata(), vec![1], &mut context,
+        ).unwrap();
+        verify_archive(&mut verification, standard_archive, 3, &mut context);
+        assert!(matches!(
+            contract.release_reward(high, submission, &verification, &mut context),
+            Err(ContractError::InsufficientQuality { required: QualityLevel::High, provided: QualityLevel::Standard })
+        ));
+
+        // Rejetée par deux vérificateurs sur trois
+        let disputed_archive = crate::crypto::compute_blake3(b"disputed archive");
+        let submission = contract.submit_archive(
+            archiver.clone(), standard, disputed_archive, page_metadata(), vec![1], &mut context,
+        ).unwrap();
+        verify_archive(&mut verification, disputed_archive, 1, &mut context);
+        match contract.release_reward(standard, submission, &verification, &mut context) {
+            Err(ContractError::InsufficientConsensus { required, achieved }) => {
+                assert_eq!(required, 0.67);
+                assert!((achieved - 1.0 / 3.0).abs() < f64::EPSILON);
+            }
+            other => panic!("Expected InsufficientConsensus, got {:?}", other),
+        }
+        assert!(context.get_token_transfers().iter().all(|t| t.to != archiver));
+
+        // Remboursement seulement après la deadline, et une seule fois
+        assert!(matches!(contract.refund_bounty(high, &mut context), Err(ContractError::InvalidState { .. })));
+        for bounty_id in [high, standard] {
+            contract.state.bounties.get_mut(&bounty_id).unwrap().deadline = Utc::now() - Duration::hours(1);
+            assert_eq!(contract.refund_bounty(bounty_id, &mut context).unwrap(), 1000);
+            assert!(matches!(contract.refund_bounty(bounty_id, &mut context), Err(ContractError::AlreadyCompleted)));
+
+            let bounty = contract.get_bounty(bounty_id).unwrap();
+            assert_eq!(bounty.escrow, EscrowStatus::Refunded);
+            assert_eq!(bounty.status, BountyStatus::Expired);
+        }
+        assert_eq!(contract.state.total_reward_pool, 0);
+        assert_eq!(context.get_token_transfers().iter().filter(|t| t.to == creator).count(), 2);
+    }
+
+    /// Capture de `url` avec une ressource par profondeur capturée ou en échec
+    fn capture(archive_id: &str, url: &str, captured: &[u32], failed: &[u32]) -> ArchiveCapture {
+        use crate::storage::{CrawlFailure, ManifestEntry};
+
+        let manifest = ArchiveManifest {
+            root_url: url.to_string(),
+            entries: captured.iter().enumerate().map(|(i, &depth)| ManifestEntry {
+                url: format!("{}/r{}", url, i),
+                depth,
+                content_hash: crate::crypto::compute_blake3(&[i as u8]),
+                size: 100,
+                content_type: "text/html".to_string(),
+            }).collect(),
+            failures: failed.iter().map(|&depth| CrawlFailure {
+                url: format!("{}/missing", url),
+                depth,
+                status: Some(404),
+                reason: "Not Found".to_string(),
+            }).collect(),
+            skipped: Vec::new(),
+            total_size: 100 * captured.len() as u64,
+            created_at: Utc::now(),
+        };
+        ArchiveCapture::from_manifest(archive_id, crate::crypto::compute_blake3(archive_id.as_bytes()), &manifest)
+    }
+
+    fn event_names(context: &ContractContext) -> Vec<&str> {
+        context.get_events().iter().map(|event| event.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_claim_pays_matching_capture() {
+        let mut contract = ArchiveBountyContract::default();
+        let creator = generate_keypair().unwrap().public_key().clone();
+        let archiver = generate_keypair().unwrap().public_key().clone();
+        let mut context = funded_context(&creator, 1000);
+
+        let bounty_id = contract.create_bounty(
+            creator.clone(), "https://example.com/page".to_string(), 1000, 24, QualityLevel::High, page_metadata(), ValidationCriteria::default(), &mut context,
+        ).unwrap();
+
+        // Même URL une fois normalisée, tous les assets sur deux niveaux
+        let archive = capture("arc_1", "https://EXAMPLE.com/page/", &[0, 1, 1, 1], &[]);
+        assert_eq!(archive.quality_level(), QualityLevel::Premium);
+        assert_eq!(contract.claim_bounty(archiver.clone(), bounty_id, &archive, &mut context).unwrap(), 1000);
+
+        let payouts: Vec<_> = context.get_token_transfers().iter().filter(|t| t.to == archiver).collect();
+        assert_eq!(payouts.len(), 1);
+        assert_eq!(payouts[0].amount, 1000);
+        assert_eq!(event_names(&context), vec!["BountyCreated", "BountyClaimed", "BountyCompleted"]);
+
+        let bounty = contract.get_bounty(bounty_id).unwrap();
+        assert_eq!(bounty.status, BountyStatus::Completed);
+        assert_eq!(bounty.escrow, EscrowStatus::Released);
+        assert_eq!(bounty.winner, Some(archiver));
+        assert_eq!(bounty.claimed_archive.as_deref(), Some("arc_1"));
+        assert_eq!(contract.state.total_reward_pool, 0);
+        assert_eq!(contract.state.stats.total_rewards_distributed, 1000);
+    }
+
+    #[test]
+    fn test_unclaimed_bounty_refunded_after_deadline() {
+        let mut contract = ArchiveBountyContract::default();
+        let creator = generate_keypair().unwrap().public_key().clone();
+        let archiver = generate_keypair().unwrap().public_key().clone();
+        let mut context = funded_context(&creator, 1000);
+
+        let bounty_id = contract.create_bounty(
+            creator.clone(), "https://example.com/page".to_string(), 1000, 24, QualityLevel::Basic, page_metadata(), ValidationCriteria::default(), &mut context,
+        ).unwrap();
+        contract.state.bounties.get_mut(&bounty_id).unwrap().deadline = Utc::now() - Duration::hours(1);
+
+        let archive = capture("arc_late", "https://example.com/page", &[0], &[]);
+        assert!(matches!(
+            contract.claim_bounty(archiver.clone(), bounty_id, &archive, &mut context),
+            Err(ContractError::DeadlineExpired)
+        ));
+
+        assert_eq!(contract.refund_bounty(bounty_id, &mut context).unwrap(), 1000);
+        assert!(matches!(
+            contract.claim_bounty(archiver.clone(), bounty_id, &archive, &mut context),
+            Err(ContractError::AlreadyCompleted)
+        ));
+
+        let transfers = context.get_token_transfers();
+        assert_eq!(transfers.len(), 1);
+        assert_eq!(transfers[0].to, creator);
+        assert_eq!(event_names(&context), vec!["BountyCreated", "BountyRefunded"]);
+        assert_eq!(contract.get_bounty(bounty_id).unwrap().status, BountyStatus::Expired);
+    }
+
+    #[test]
+    fn test_low_quality_capture_rejected() {
+        let mut contract = ArchiveBountyContract::default();
+        let creator = generate_keypair().unwrap().public_key().clone();
+        let archiver = generate_keypair().unwrap().public_key().clone();
+        let mut context = funded_context(&creator, 1000);
+
+        let bounty_id = contract.create_bounty(
+            creator.clone(), "https://example.com/page".to_string(), 1000, 24, QualityLevel::Standard, page_metadata(), ValidationCriteria::default(), &mut context,
+        ).unwrap();
+
+        // La moitié des assets manque et le second niveau n'a pas été capturé
+        let partial = capture("arc_partial", "https://example.com/page", &[0, 1], &[1, 2]);
+        assert_eq!(partial.completeness(), 0.5);
+        assert_eq!(partial.quality_level(), QualityLevel::Basic);
+        assert!(matches!(
+            contract.claim_bounty(archiver.clone(), bounty_id, &partial, &mut context),
+            Err(ContractError::InsufficientQuality { required: QualityLevel::Standard, provided: QualityLevel::Basic })
+        ));
+
+        // Une archive complète d'une autre URL ne convient pas
+        let other = capture("arc_other", "https://example.com/other", &[0, 1], &[]);
+        assert!(matches!(
+            contract.claim_bounty(archiver.clone(), bounty_id, &other, &mut context),
+            Err(ContractError::InvalidParameters { .. })
+        ));
+
+        assert!(context.get_token_transfers().is_empty());
+        let bounty = contract.get_bounty(bounty_id).unwrap();
+        assert_eq!(bounty.status, BountyStatus::Active);
+        assert_eq!(bounty.escrow, EscrowStatus::Locked);
+        assert_eq!(contract.state.total_reward_pool, 1000);
+    }
+
+    #[test]
+    fn test_bounty_cannot_be_claimed_twice() {
+        let mut contract = ArchiveBountyContract::default();
+        let creator = generate_keypair().unwrap().public_key().clone();
+        let first = generate_keypair().unwrap().public_key().clone();
+        let second = generate_keypair().unwrap().public_key().clone();
+        let mut context = funded_context(&creator, 1000);
+
+        let bounty_id = contract.create_bounty(
+            creator.clone(), "https://example.com/page".to_string(), 1000, 24, QualityLevel::Standard, page_metadata(), ValidationCriteria::default(), &mut context,
+        ).unwrap();
+
+        let archive = capture("arc_1", "https://example.com/page", &[0, 1, 1], &[]);
+        contract.claim_bounty(first.clone(), bounty_id, &archive, &mut context).unwrap();
+
+        let again = capture("arc_2", "https://example.com/page", &[0, 1, 1], &[]);
+        for (claimer, archive) in [(&first, &archive), (&second, &again)] {
+            assert!(matches!(
+                contract.claim_bounty(claimer.clone(), bounty_id, archive, &mut context),
+                Err(ContractError::AlreadyCompleted)
+            ));
+        }
+        assert!(matches!(contract.refund_bounty(bounty_id, &mut context), Err(ContractError::AlreadyCompleted)));
+
+        assert_eq!(context.get_token_transfers().len(), 1);
+        assert_eq!(contract.get_bounty(bounty_id).unwrap().winner, Some(first));
+    }
+
+    #[test]
+    fn test_only_creator_updates_cancels_or_accepts() {
+        let mut contract = ArchiveBountyContract::default();
+        let creator = generate_keypair().unwrap().public_key().clone();
+        let archiver = generate_keypair().unwrap().public_key().clone();
+        let mut context = funded_context(&creator, 1000);
+
+        let first = contract.create_bounty(
+            creator.clone(), "https://example.com/a".to_string(), 1000, 24, QualityLevel::Basic, page_metadata(), ValidationCriteria::default(), &mut context,
+        ).unwrap();
+        let second = contract.create_bounty(
+            creator.clone(), "https://example.com/b".to_string(), 1000, 24, QualityLevel::Basic, page_metadata(), ValidationCriteria::default(), &mut context,
+        ).unwrap();
+
+        // Un tiers ne peut ni modifier ni annuler le bounty
+        assert!(matches!(
+            contract.update_bounty(&archiver, first, 0, 24, &mut context),
+            Err(ContractError::Unauthorized { .. })
+        ));
+        assert!(matches!(contract.cancel_bounty(&archiver, first, &mut context), Err(ContractError::Unauthorized { .. })));
+
+        // La récompense supplémentaire doit accompagner l'appel
+        assert!(matches!(
+            contract.update_bounty(&creator, first, 500, 0, &mut context),
+            Err(ContractError::InsufficientFunds { required: 500, available: 1000 })
+        ));
+        let mut top_up = funded_context(&creator, 500);
+        let updated = contract.update_bounty(&creator, first, 500, 24, &mut top_up).unwrap();
+        assert_eq!(updated.reward, 1500);
+        assert_eq!(contract.state.total_reward_pool, 2500);
+
+        let submission_id = contract.submit_archive(
+            archiver.clone(), first, crate::crypto::compute_blake3(b"archive a"), page_metadata(), vec![1], &mut context,
+        ).unwrap();
+        assert!(matches!(contract.cancel_bounty(&creator, first, &mut context), Err(ContractError::InvalidState { .. })));
+        assert!(matches!(
+            contract.accept_submission(&archiver, first, submission_id, &mut context),
+            Err(ContractError::Unauthorized { .. })
+        ));
+        assert_eq!(contract.accept_submission(&creator, first, submission_id, &mut context).unwrap(), 1500);
+        assert_eq!(contract.get_bounty(first).unwrap().winner, Some(archiver.clone()));
+
+        assert_eq!(contract.cancel_bounty(&creator, second, &mut context).unwrap(), 1000);
+        let cancelled = contract.get_bounty(second).unwrap();
+        assert_eq!(cancelled.status, BountyStatus::Cancelled);
+        assert_eq!(cancelled.escrow, EscrowStatus::Refunded);
+        assert!(matches!(contract.cancel_bounty(&creator, second, &mut context), Err(ContractError::AlreadyCompleted)));
+
+        let transfers = context.get_token_transfers();
+        assert_eq!(transfers.len(), 2);
+        assert_eq!((&transfers[0].to, transfers[0].amount), (&archiver, 1500));
+        assert_eq!((&transfers[1].to, transfers[1].amount), (&creator, 1000));
+        assert_eq!(contract.state.total_reward_pool, 0);
     }
 
     #[test]
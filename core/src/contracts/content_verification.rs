@@ -6,7 +6,7 @@ use chrono::{DateTime, Utc, Duration};
 use crate::crypto::{Hash, PublicKey};
 use crate::contracts::{
     ContractError, ContractResult, ContractContext, SmartContract, 
-    ContractMetadata, ContractVersion, AbiValue
+    ContractMetadata, ContractVersion, AbiValue, QualityLevel
 };
 
 /// Règles de vérification pour le contenu
@@ -196,6 +196,72 @@ pub struct ContentVerification {
     pub alerts: Vec<Hash>, // Références aux alertes
 }
 
+impl ContentVerification {
+    /// Nombre de vérificateurs requis pour conclure
+    pub fn required_verifiers(&self) -> u32 {
+        self.verification_rules.min_verifiers.max(
+            self.content_metadata.criticality_level.min_verifications()
+        )
+    }
+
+    /// Proportion de vérifications réussies, rapportée au quorum tant que
+    /// celui-ci n'est pas atteint
+    pub fn consensus_score(&self) -> f64 {
+        let successful = self.verification_results.iter().filter(|r| r.success).count();
+        let total = self.verification_results.len().max(self.required_verifiers() as usize);
+        if total == 0 {
+            0.0
+        } else {
+            successful as f64 / total as f64
+        }
+    }
+
+    /// Niveau de qualité confirmé par le consensus des vérificateurs
+    ///
+    /// Un contrôle est confirmé lorsqu'il est activé par les règles et validé
+    /// par une proportion de vérificateurs au moins égale au seuil de
+    /// consensus. Chaque niveau exige les contrôles du niveau inférieur :
+    /// - `Basic` : intégrité ;
+    /// - `Standard` : taille ;
+    /// - `High` : format et métadonnées ;
+    /// - `Premium` : redondance.
+    ///
+    /// Retourne `None` si la vérification n'a pas abouti ou si l'intégrité
+    /// n'est pas confirmée.
+    pub fn confirmed_quality(&self) -> Option<QualityLevel> {
+        if self.status != VerificationStatus::Verified {
+            return None;
+        }
+
+        let rules = &self.verification_rules;
+        let confirmed = |enabled: bool, check: fn(&VerificationDetails) -> Option<bool>| {
+            let confirmations = self.verification_results
+                .iter()
+                .filter(|r| r.success && check(&r.details) == Some(true))
+                .count();
+            enabled
+                && !self.verification_results.is_empty()
+                && confirmations as f64 / self.verification_results.len() as f64 >= rules.consensus_threshold
+        };
+
+        if !confirmed(rules.integrity_check, |d| d.integrity_valid) {
+            return None;
+        }
+        if !confirmed(rules.size_check, |d| d.size_valid) {
+            return Some(QualityLevel::Basic);
+        }
+        if !confirmed(rules.format_check, |d| d.format_valid)
+            || !confirmed(rules.metadata_check, |d| d.metadata_valid)
+        {
+            return Some(QualityLevel::Standard);
+        }
+        if !confirmed(rules.redundancy_check, |d| d.redundancy_valid) {
+            return Some(QualityLevel::High);
+        }
+        Some(QualityLevel::Premium)
+    }
+}
+
 /// Métadonnées de contenu pour la vérification
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ContentMetadata {
@@ -2,6 +2,7 @@
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use wasmer::wasmparser::Operator;
 use crate::contracts::{ContractError, ContractResult};
 
 /// Limite de gas par défaut
@@ -204,6 +205,11 @@ impl GasManager {
         self.consumed() * self.gas_price
     }
 
+    /// Calcule le montant en tokens ARC à rembourser pour le gas non consommé
+    pub fn calculate_refund(&self) -> u64 {
+        self.remaining_gas * self.gas_price
+    }
+
     /// Obtient les métriques détaillées
     pub fn get_metrics(&self) -> GasMetrics {
         let mut operation_counts = HashMap::new();
@@ -327,6 +333,47 @@ impl GasCalculator {
         let args_cost = (args_size / 32 + 1) as u64;
         base_cost + args_cost
     }
+
+    /// Calcule le gas d'une instruction WASM
+    ///
+    /// Les instructions purement structurelles sont gratuites, les accès
+    /// mémoire et les appels ont leur propre tarif.
+    pub fn instruction_cost(operator: &Operator) -> u64 {
+        match operator {
+            Operator::Nop
+            | Operator::Block { .. }
+            | Operator::Loop { .. }
+            | Operator::Else
+            | Operator::End => 0,
+            Operator::Call { .. } | Operator::CallIndirect { .. } => GasCost::FunctionCall as u64,
+            Operator::I32Load { .. }
+            | Operator::I64Load { .. }
+            | Operator::F32Load { .. }
+            | Operator::F64Load { .. }
+            | Operator::I32Load8S { .. }
+            | Operator::I32Load8U { .. }
+            | Operator::I32Load16S { .. }
+            | Operator::I32Load16U { .. }
+            | Operator::I64Load8S { .. }
+            | Operator::I64Load8U { .. }
+            | Operator::I64Load16S { .. }
+            | Operator::I64Load16U { .. }
+            | Operator::I64Load32S { .. }
+            | Operator::I64Load32U { .. }
+            | Operator::I32Store { .. }
+            | Operator::I64Store { .. }
+            | Operator::F32Store { .. }
+            | Operator::F64Store { .. }
+            | Operator::I32Store8 { .. }
+            | Operator::I32Store16 { .. }
+            | Operator::I64Store8 { .. }
+            | Operator::I64Store16 { .. }
+            | Operator::I64Store32 { .. }
+            | Operator::MemorySize { .. }
+            | Operator::MemoryGrow { .. } => GasCost::Memory as u64,
+            _ => GasCost::Basic as u64,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -448,5 +495,6 @@ mod tests {
         
         manager.consume(200).unwrap();
         assert_eq!(manager.calculate_fee(), 1000); // 200 * 5 = 1000
+        assert_eq!(manager.calculate_refund(), 4000); // 800 * 5 = 4000
     }
 }
\ No newline at end of file
@@ -0,0 +1,187 @@
+//! Comptage du gas des instructions WASM
+//!
+//! Le middleware instrumente chaque fonction à la compilation : le coût d'un
+//! bloc de base est prélevé sur un compteur global du module à la sortie du
+//! bloc (branchement, appel, retour, fin). Si le gas restant ne suffit pas, le
+//! coût du bloc est mémorisé dans un second global et l'exécution s'arrête sur
+//! un trap. Les coûts ne dépendent que du bytecode : deux nœuds exécutant le
+//! même appel consomment exactement le même gas.
+
+use std::sync::Mutex;
+use wasmer::wasmparser::{BlockType, Operator};
+use wasmer::{
+    AsStoreMut, ExportIndex, FunctionMiddleware, GlobalInit, GlobalType, Instance,
+    LocalFunctionIndex, MiddlewareError, MiddlewareReaderState, ModuleMiddleware, Mutability,
+    Type, Value,
+};
+use wasmer_types::{GlobalIndex, ModuleInfo};
+
+use crate::contracts::{ContractError, ContractResult};
+
+/// Global contenant le gas restant de l'instance
+const REMAINING_GLOBAL: &str = "archivechain_gas_remaining";
+/// Global contenant le coût du bloc refusé, 0 tant que le gas suffit
+const REQUIRED_GLOBAL: &str = "archivechain_gas_required";
+
+/// Fonction de coût d'une instruction
+pub type CostFunction = fn(&Operator) -> u64;
+
+/// Middleware de comptage du gas, à installer sur le compilateur
+#[derive(Debug)]
+pub struct GasMetering {
+    cost_function: CostFunction,
+    /// Globals du dernier module instrumenté
+    ///
+    /// La compilation des modules est séquentielle (`WasmRuntime::compile_module`
+    /// prend `&mut self`), les fonctions d'un module lisent donc les globals du
+    /// module en cours.
+    globals: Mutex<Option<MeteringGlobals>>,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct MeteringGlobals {
+    remaining: GlobalIndex,
+    required: GlobalIndex,
+}
+
+impl GasMetering {
+    pub fn new(cost_function: CostFunction) -> Self {
+        Self {
+            cost_function,
+            globals: Mutex::new(None),
+        }
+    }
+}
+
+impl ModuleMiddleware for GasMetering {
+    fn generate_function_middleware(&self, _: LocalFunctionIndex) -> Box<dyn FunctionMiddleware> {
+        let globals = self
+            .globals
+            .lock()
+            .unwrap()
+            .expect("transform_module_info runs before function instrumentation");
+
+        Box::new(FunctionMetering {
+            cost_function: self.cost_function,
+            globals,
+            accumulated_cost: 0,
+        })
+    }
+
+    fn transform_module_info(&self, module_info: &mut ModuleInfo) -> Result<(), MiddlewareError> {
+        let remaining = module_info.globals.push(GlobalType::new(Type::I64, Mutability::Var));
+        // Le gas réel est fixé à chaque appel par `set_remaining_gas`
+        module_info.global_initializers.push(GlobalInit::I64Const(0));
+        module_info
+            .exports
+            .insert(REMAINING_GLOBAL.to_string(), ExportIndex::Global(remaining));
+
+        let required = module_info.globals.push(GlobalType::new(Type::I64, Mutability::Var));
+        module_info.global_initializers.push(GlobalInit::I64Const(0));
+        module_info
+            .exports
+            .insert(REQUIRED_GLOBAL.to_string(), ExportIndex::Global(required));
+
+        *self.globals.lock().unwrap() = Some(MeteringGlobals { remaining, required });
+        Ok(())
+    }
+}
+
+/// Instrumentation d'une fonction
+#[derive(Debug)]
+struct FunctionMetering {
+    cost_function: CostFunction,
+    globals: MeteringGlobals,
+    /// Coût des instructions du bloc courant, pas encore prélevé
+    accumulated_cost: u64,
+}
+
+impl FunctionMiddleware for FunctionMetering {
+    fn feed<'a>(
+        &mut self,
+        operator: Operator<'a>,
+        state: &mut MiddlewareReaderState<'a>,
+    ) -> Result<(), MiddlewareError> {
+        self.accumulated_cost += (self.cost_function)(&operator);
+
+        let ends_block = matches!(
+            operator,
+            Operator::Loop { .. }
+                | Operator::End
+                | Operator::Else
+                | Operator::Br { .. }
+                | Operator::BrIf { .. }
+                | Operator::BrTable { .. }
+                | Operator::Call { .. }
+                | Operator::CallIndirect { .. }
+                | Operator::Return
+        );
+
+        if ends_block && self.accumulated_cost > 0 {
+            let remaining = self.globals.remaining.as_u32();
+            let required = self.globals.required.as_u32();
+            // Les compteurs sont des u64 stockés dans des i64 : comparaison non signée
+            let cost = self.accumulated_cost as i64;
+            state.extend(&[
+                Operator::GlobalGet { global_index: remaining },
+                Operator::I64Const { value: cost },
+                Operator::I64LtU,
+                Operator::If { blockty: BlockType::Empty },
+                Operator::I64Const { value: cost },
+                Operator::GlobalSet { global_index: required },
+                Operator::Unreachable,
+                Operator::End,
+                Operator::GlobalGet { global_index: remaining },
+                Operator::I64Const { value: cost },
+                Operator::I64Sub,
+                Operator::GlobalSet { global_index: remaining },
+            ]);
+            self.accumulated_cost = 0;
+        }
+
+        state.push_operator(operator);
+        Ok(())
+    }
+}
+
+fn read_global(store: &mut impl AsStoreMut, instance: &Instance, name: &str) -> ContractResult<u64> {
+    let global = instance.exports.get_global(name).map_err(|e| ContractError::WasmExecution {
+        message: format!("Module is not gas metered: {}", e),
+    })?;
+
+    match global.get(store) {
+        Value::I64(value) => Ok(value as u64),
+        other => Err(ContractError::WasmExecution {
+            message: format!("Invalid gas counter value: {:?}", other),
+        }),
+    }
+}
+
+fn write_global(store: &mut impl AsStoreMut, instance: &Instance, name: &str, value: u64) -> ContractResult<()> {
+    let global = instance.exports.get_global(name).map_err(|e| ContractError::WasmExecution {
+        message: format!("Module is not gas metered: {}", e),
+    })?;
+
+    global
+        .set(store, Value::I64(value as i64))
+        .map_err(|e| ContractError::WasmExecution {
+            message: format!("Cannot update gas counter: {}", e),
+        })
+}
+
+/// Gas restant dans le compteur de l'instance
+pub fn remaining_gas(store: &mut impl AsStoreMut, instance: &Instance) -> ContractResult<u64> {
+    read_global(store, instance, REMAINING_GLOBAL)
+}
+
+/// Fixe le gas restant de l'instance et efface un éventuel épuisement
+pub fn set_remaining_gas(store: &mut impl AsStoreMut, instance: &Instance, gas: u64) -> ContractResult<()> {
+    write_global(store, instance, REMAINING_GLOBAL, gas)?;
+    write_global(store, instance, REQUIRED_GLOBAL, 0)
+}
+
+/// Coût du bloc refusé si l'exécution s'est arrêtée faute de gas
+pub fn exhausted_by(store: &mut impl AsStoreMut, instance: &Instance) -> ContractResult<Option<u64>> {
+    let required = read_global(store, instance, REQUIRED_GLOBAL)?;
+    Ok((required > 0).then_some(required))
+}
@@ -7,6 +7,7 @@
 pub mod runtime;
 pub mod context;
 pub mod gas;
+pub mod metering;
 pub mod abi;
 pub mod manager;
 pub mod archive_bounty;
@@ -19,7 +20,7 @@ pub use context::{ContractContext, ContextProvider};
 pub use gas::{GasManager, GasCost, GasLimit};
 pub use abi::{ContractAbi, ContractCall, ContractEvent, ContractError as AbiError};
 pub use manager::{ContractManager, ContractRegistry, ContractDeployment};
-pub use archive_bounty::{ArchiveBountyContract, ArchiveBounty, BountyStatus, QualityLevel};
+pub use archive_bounty::{ArchiveBountyContract, ArchiveBounty, ArchiveCapture, BountyStatus, QualityLevel};
 pub use preservation_pool::{PreservationPoolContract, PreservationPool, PoolParticipant};
 pub use content_verification::{ContentVerificationContract, ContentVerification, VerificationRules};
 
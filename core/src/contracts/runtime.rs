@@ -4,11 +4,13 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 use wasmer::{
-    imports, wat2wasm, Cranelift, Engine, Function, FunctionType, Instance, 
-    Memory, Module, Store, Type, Value, WasmPtr
+    imports, AsStoreMut, CompilerConfig, Cranelift, Engine, EngineBuilder, Function, FunctionEnv,
+    FunctionEnvMut, Instance, Module, RuntimeError, Store, Value
 };
 use crate::crypto::Hash;
 use crate::contracts::{ContractError, ContractResult, ContractContext, GasManager};
+use crate::contracts::gas::{GasCalculator, GasCost};
+use crate::contracts::metering::{self, GasMetering};
 
 /// Configuration du runtime WASM
 #[derive(Debug, Clone)]
@@ -38,6 +40,9 @@ pub struct ExecutionResult {
     pub return_data: Vec<u8>,
     /// Gas consommé
     pub gas_used: u64,
+    /// Gas non consommé, remboursable à l'appelant
+    #[serde(default)]
+    pub gas_refund: u64,
     /// Logs émis par le contrat
     pub logs: Vec<String>,
     /// Events émis
@@ -77,6 +82,61 @@ pub struct ContractExecution {
     gas_manager: Arc<Mutex<GasManager>>,
     /// Contexte d'exécution
     context: Arc<Mutex<ContractContext>>,
+    /// Environnement des fonctions host
+    env: FunctionEnv<HostEnv>,
+}
+
+/// Environnement partagé par les fonctions host d'une instance
+#[derive(Debug)]
+struct HostEnv {
+    /// Instance appelante, renseignée après l'instanciation
+    instance: Option<Instance>,
+    gas_manager: Arc<Mutex<GasManager>>,
+    context: Arc<Mutex<ContractContext>>,
+    /// Erreur ayant interrompu l'exécution dans une fonction host
+    failure: Option<ContractError>,
+}
+
+impl HostEnv {
+    /// Facture une fonction host sur le gas de l'appel en cours
+    ///
+    /// Les instructions exécutées depuis la dernière facturation sont d'abord
+    /// reportées dans le `GasManager`, puis le coût de la fonction host est
+    /// prélevé et le compteur de l'instance mis à jour.
+    fn charge(&self, store: &mut impl AsStoreMut, cost: u64, operation: &str) -> ContractResult<()> {
+        let instance = self.instance.as_ref().ok_or_else(|| ContractError::WasmExecution {
+            message: "Host function called before instantiation".to_string(),
+        })?;
+
+        let mut gas = self.gas_manager.lock().unwrap();
+        sync_instruction_gas(store, instance, &mut gas)?;
+        gas.consume_with_name(cost, operation)?;
+        metering::set_remaining_gas(store, instance, gas.remaining())
+    }
+}
+
+/// Reporte dans le `GasManager` le gas des instructions exécutées depuis la
+/// dernière synchronisation avec le compteur de l'instance
+fn sync_instruction_gas(
+    store: &mut impl AsStoreMut,
+    instance: &Instance,
+    gas: &mut GasManager,
+) -> ContractResult<()> {
+    let executed = gas.remaining().saturating_sub(metering::remaining_gas(store, instance)?);
+    if executed > 0 {
+        gas.consume_with_name(executed, "instructions")?;
+    }
+    Ok(())
+}
+
+/// Facture une fonction host, en interrompant l'exécution si le gas manque
+fn charge_host(env: &mut FunctionEnvMut<HostEnv>, cost: u64, operation: &str) -> Result<(), RuntimeError> {
+    let (host, mut store) = env.data_and_store_mut();
+    host.charge(&mut store, cost, operation).map_err(|e| {
+        let message = e.to_string();
+        host.failure = Some(e);
+        RuntimeError::new(message)
+    })
 }
 
 /// Runtime principal pour l'exécution des contrats WASM
@@ -92,9 +152,15 @@ pub struct WasmRuntime {
 
 impl WasmRuntime {
     /// Crée un nouveau runtime WASM
+    ///
+    /// Les modules compilés sont instrumentés pour facturer chaque instruction
+    /// et les NaN sont canonicalisés pour un résultat identique sur tous les nœuds.
     pub fn new(config: WasmRuntimeConfig) -> ContractResult<Self> {
-        let engine = Engine::new(Cranelift::default());
-        
+        let mut compiler = Cranelift::default();
+        compiler.canonicalize_nans(true);
+        compiler.push_middleware(Arc::new(GasMetering::new(GasCalculator::instruction_cost)));
+        let engine = EngineBuilder::new(compiler).engine();
+
         Ok(Self {
             engine,
             config,
@@ -126,87 +192,85 @@ impl WasmRuntime {
         let mut store = Store::new(&self.engine);
         let gas_manager = Arc::new(Mutex::new(GasManager::new(gas_limit)));
         let context_arc = Arc::new(Mutex::new(context));
+        let env = FunctionEnv::new(&mut store, HostEnv {
+            instance: None,
+            gas_manager: gas_manager.clone(),
+            context: context_arc.clone(),
+            failure: None,
+        });
 
         // Crée les imports pour les fonctions host
-        let imports = self.create_imports(&mut store, gas_manager.clone(), context_arc.clone())?;
+        let imports = self.create_imports(&mut store, &env)?;
 
         // Instancie le module
         let instance = Instance::new(&mut store, module, &imports)
             .map_err(|e| ContractError::WasmExecution { 
                 message: format!("Instantiation failed: {}", e) 
             })?;
+        env.as_mut(&mut store).instance = Some(instance.clone());
 
         Ok(ContractExecution {
             instance,
             store,
             gas_manager,
             context: context_arc,
+            env,
         })
     }
 
     /// Crée les imports (fonctions host) pour les contrats
+    ///
+    /// Chaque fonction host est facturée selon la taille des données manipulées.
     fn create_imports(
         &self,
         store: &mut Store,
-        gas_manager: Arc<Mutex<GasManager>>,
-        context: Arc<Mutex<ContractContext>>,
+        env: &FunctionEnv<HostEnv>,
     ) -> ContractResult<wasmer::Imports> {
         // Fonction pour lire le storage
-        let gas_manager_read = gas_manager.clone();
-        let context_read = context.clone();
-        let storage_read = Function::new_typed(store, move |ptr: u32, len: u32| -> u32 {
-            let mut gas = gas_manager_read.lock().unwrap();
-            if gas.consume(100).is_err() {
-                return 0; // Erreur de gas
-            }
+        let storage_read = Function::new_typed_with_env(store, env, |mut env: FunctionEnvMut<HostEnv>, _ptr: u32, len: u32| -> Result<u32, RuntimeError> {
+            let cost = GasCost::StorageRead as u64 + GasCalculator::memory_access(len as usize);
+            charge_host(&mut env, cost, "storage_read")?;
 
-            let ctx = context_read.lock().unwrap();
             // Implementation de lecture du storage
             // Pour l'instant, retourne 0 (pas de données)
-            0
+            Ok(0)
         });
 
         // Fonction pour écrire dans le storage
-        let gas_manager_write = gas_manager.clone();
-        let context_write = context.clone();
-        let storage_write = Function::new_typed(store, move |key_ptr: u32, key_len: u32, value_ptr: u32, value_len: u32| -> u32 {
-            let mut gas = gas_manager_write.lock().unwrap();
-            if gas.consume(200).is_err() {
-                return 0; // Erreur de gas
-            }
+        let storage_write = Function::new_typed_with_env(store, env, |mut env: FunctionEnvMut<HostEnv>, _key_ptr: u32, key_len: u32, _value_ptr: u32, value_len: u32| -> Result<u32, RuntimeError> {
+            let cost = GasCalculator::storage_write(key_len as usize, value_len as usize);
+            charge_host(&mut env, cost, "storage_write")?;
 
-            let mut ctx = context_write.lock().unwrap();
             // Implementation d'écriture du storage
-            1 // Succès
+            Ok(1) // Succès
         });
 
         // Fonction pour émettre un log
-        let gas_manager_log = gas_manager.clone();
-        let log_fn = Function::new_typed(store, move |ptr: u32, len: u32| {
-            let mut gas = gas_manager_log.lock().unwrap();
-            let _ = gas.consume(50);
+        let log_fn = Function::new_typed_with_env(store, env, |mut env: FunctionEnvMut<HostEnv>, _ptr: u32, len: u32| -> Result<(), RuntimeError> {
+            charge_host(&mut env, GasCalculator::log_cost(len as usize), "log")?;
             // Implementation du logging
+            Ok(())
         });
 
         // Fonction pour émettre un event
-        let gas_manager_event = gas_manager.clone();
-        let event_fn = Function::new_typed(store, move |name_ptr: u32, name_len: u32, data_ptr: u32, data_len: u32| {
-            let mut gas = gas_manager_event.lock().unwrap();
-            let _ = gas.consume(300);
+        let event_fn = Function::new_typed_with_env(store, env, |mut env: FunctionEnvMut<HostEnv>, _name_ptr: u32, name_len: u32, _data_ptr: u32, data_len: u32| -> Result<(), RuntimeError> {
+            let cost = GasCalculator::event_cost((name_len + data_len) as usize, 0);
+            charge_host(&mut env, cost, "event")?;
             // Implementation des events
+            Ok(())
         });
 
-        // Fonction pour obtenir le timestamp actuel
-        let timestamp_fn = Function::new_typed(store, || -> u64 {
-            std::time::SystemTime::now()
-                .duration_since(std::time::UNIX_EPOCH)
-                .unwrap_or_default()
-                .as_secs()
+        // Fonction pour obtenir le timestamp du bloc courant
+        let timestamp_fn = Function::new_typed_with_env(store, env, |mut env: FunctionEnvMut<HostEnv>| -> Result<u64, RuntimeError> {
+            charge_host(&mut env, GasCost::Basic as u64, "get_timestamp")?;
+            Ok(env.data().context.lock().unwrap().get_timestamp())
         });
 
         // Fonction pour obtenir l'adresse du contrat
-        let contract_address_fn = Function::new_typed(store, move |ptr: u32| {
+        let contract_address_fn = Function::new_typed_with_env(store, env, |mut env: FunctionEnvMut<HostEnv>, _ptr: u32| -> Result<(), RuntimeError> {
+            charge_host(&mut env, GasCost::Basic as u64, "get_contract_address")?;
             // Écrit l'adresse du contrat à l'adresse donnée
+            Ok(())
         });
 
         let imports = imports! {
@@ -239,23 +303,39 @@ impl ContractExecution {
                 function: function_name.to_string() 
             })?;
 
-        // Exécute la fonction
-        let start_gas = {
-            let gas = self.gas_manager.lock().unwrap();
-            gas.remaining()
-        };
-
-        let result = function.call(&mut self.store, args)
-            .map_err(|e| ContractError::WasmExecution { 
-                message: format!("Function execution failed: {}", e) 
-            })?;
-
-        let end_gas = {
-            let gas = self.gas_manager.lock().unwrap();
-            gas.remaining()
+        // Exécute la fonction avec le gas restant comme compteur d'instructions
+        let start_gas = self.gas_manager.lock().unwrap().remaining();
+        metering::set_remaining_gas(&mut self.store, &self.instance, start_gas)?;
+        self.env.as_mut(&mut self.store).failure = None;
+
+        let outcome = function.call(&mut self.store, args);
+
+        let mut gas = self.gas_manager.lock().unwrap();
+        sync_instruction_gas(&mut self.store, &self.instance, &mut gas)?;
+
+        let result = match outcome {
+            Ok(result) => result,
+            Err(e) => {
+                // Gas épuisé dans une fonction host
+                if let Some(failure) = self.env.as_mut(&mut self.store).failure.take() {
+                    return Err(failure);
+                }
+                // Gas épuisé par les instructions : le bloc refusé n'a pas été facturé
+                if let Some(required) = metering::exhausted_by(&mut self.store, &self.instance)? {
+                    return Err(ContractError::InsufficientGas {
+                        required,
+                        available: gas.remaining(),
+                    });
+                }
+                return Err(ContractError::WasmExecution {
+                    message: format!("Function execution failed: {}", e)
+                });
+            }
         };
 
-        let gas_used = start_gas - end_gas;
+        let gas_used = start_gas - gas.remaining();
+        let gas_refund = gas.remaining();
+        drop(gas);
 
         // Récupère les données de retour
         let return_data = if let Some(Value::I32(ptr)) = result.get(0) {
@@ -273,6 +353,7 @@ impl ContractExecution {
         Ok(ExecutionResult {
             return_data,
             gas_used,
+            gas_refund,
             logs,
             events,
             state_changes,
@@ -356,6 +437,56 @@ impl ContractExecution {
 mod tests {
     use super::*;
     use crate::contracts::ContextProvider;
+    use crate::contracts::context::{ExecutionEnvironment, MockContextProvider};
+    use crate::crypto::generate_keypair;
+
+    const METERED_CONTRACT: &str = r#"
+        (module
+            (import "env" "log" (func $log (param i32 i32)))
+            (memory (export "memory") 1)
+            (func (export "add") (result i32)
+                i32.const 40
+                i32.const 2
+                i32.add
+            )
+            (func (export "log_twice")
+                i32.const 0
+                i32.const 64
+                call $log
+                i32.const 0
+                i32.const 64
+                call $log
+            )
+            (func (export "spin")
+                (loop $forever
+                    i32.const 1
+                    drop
+                    br $forever
+                )
+            )
+        )
+    "#;
+
+    fn load_metered(gas_limit: u64) -> ContractExecution {
+        let keypair = generate_keypair().unwrap();
+        let environment = ExecutionEnvironment {
+            block_hash: Hash::zero(),
+            block_number: 1,
+            block_timestamp: chrono::Utc::now(),
+            transaction_hash: Hash::zero(),
+            transaction_sender: keypair.public_key().clone(),
+            contract_address: Hash::zero(),
+            caller_address: keypair.public_key().clone(),
+            value_sent: 0,
+            gas_limit,
+            gas_price: 1,
+        };
+        let context = ContractContext::new(environment, Box::new(MockContextProvider::new()));
+
+        let mut runtime = WasmRuntime::new(WasmRuntimeConfig::default()).unwrap();
+        runtime.compile_module(&wat::parse_str(METERED_CONTRACT).unwrap(), Hash::zero()).unwrap();
+        runtime.load_contract(Hash::zero(), context, gas_limit).unwrap()
+    }
 
     #[test]
     fn test_runtime_creation() {
@@ -390,6 +521,7 @@ mod tests {
         let result = ExecutionResult {
             return_data: vec![1, 2, 3],
             gas_used: 1000,
+            gas_refund: 0,
             logs: vec!["test log".to_string()],
             events: vec![],
             state_changes: vec![],
@@ -404,4 +536,50 @@ mod tests {
         assert_eq!(result.gas_used, deserialized.gas_used);
         assert_eq!(result.success, deserialized.success);
     }
+
+    #[test]
+    fn test_instructions_and_host_calls_are_metered() {
+        let mut execution = load_metered(1_000);
+        let result = execution.call_function("add", &[]).unwrap();
+        // 2 constantes + 1 addition
+        assert_eq!(result.gas_used, 3);
+        assert_eq!(result.gas_refund, 997);
+
+        // Chaque bloc : 2 constantes + l'appel, puis le coût du log
+        let per_log = 2 + GasCost::FunctionCall as u64 + GasCalculator::log_cost(64);
+        let result = execution.call_function("log_twice", &[]).unwrap();
+        assert_eq!(result.gas_used, 2 * per_log);
+        assert_eq!(result.gas_refund, 997 - 2 * per_log);
+
+        // Consommation identique sur un autre nœud
+        let mut other_node = load_metered(1_000);
+        assert_eq!(other_node.call_function("add", &[]).unwrap().gas_used, 3);
+        assert_eq!(other_node.call_function("log_twice", &[]).unwrap().gas_used, 2 * per_log);
+    }
+
+    #[test]
+    fn test_execution_halts_when_gas_runs_out() {
+        // 33 itérations de 3 gas, il reste 1 gas pour la suivante
+        let mut execution = load_metered(100);
+        match execution.call_function("spin", &[]) {
+            Err(ContractError::InsufficientGas { required, available }) => {
+                assert_eq!(required, 3);
+                assert_eq!(available, 1);
+            }
+            other => panic!("Expected InsufficientGas, got {:?}", other.map(|r| r.gas_used)),
+        }
+
+        // Le second log ne peut plus être payé
+        let per_block = 2 + GasCost::FunctionCall as u64;
+        let log_cost = GasCalculator::log_cost(64);
+        let gas_limit = 2 * per_block + log_cost + 10;
+        let mut execution = load_metered(gas_limit);
+        match execution.call_function("log_twice", &[]) {
+            Err(ContractError::InsufficientGas { required, available }) => {
+                assert_eq!(required, log_cost);
+                assert_eq!(available, 10);
+            }
+            other => panic!("Expected InsufficientGas, got {:?}", other.map(|r| r.gas_used)),
+        }
+    }
 }
\ No newline at end of file
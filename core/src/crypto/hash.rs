@@ -10,7 +10,7 @@ use crate::error::{CryptoError, Result};
 pub const HASH_SIZE: usize = 32;
 
 /// Représentation d'un hash de 256 bits
-#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize, Deserialize)]
 pub struct Hash([u8; HASH_SIZE]);
 
 /// Algorithmes de hachage supportés
@@ -4,15 +4,18 @@
 //! - Fonctions de hachage (Blake3, SHA-3)
 //! - Signatures numériques (Ed25519)
 //! - Gestion des clés
+//! - Signataires interchangeables (fichier local, HSM/KMS externe)
 //! - Arbres de Merkle
 
 pub mod hash;
 pub mod signature;
 pub mod keys;
+pub mod signer;
 
 pub use hash::{Hash, HashAlgorithm, compute_hash, compute_blake3, compute_sha3, compute_combined_hash, Hashable};
 pub use signature::{Signature, verify_signature, sign_data, Signable};
 pub use keys::{PublicKey, PrivateKey, KeyPair, generate_keypair};
+pub use signer::{Signer, FileSigner};
 
 use crate::error::{CryptoError, Result};
 
@@ -3,10 +3,11 @@
 //! Utilise Ed25519 pour signer et vérifier des données
 
 use serde::{Deserialize, Serialize};
-use ed25519_dalek::{Signer, Verifier};
+use ed25519_dalek::Verifier;
 use std::fmt;
 use crate::error::{CryptoError, Result};
-use super::keys::{PublicKey, PrivateKey};
+use super::keys::PublicKey;
+use super::signer::Signer;
 
 /// Taille d'une signature Ed25519 en bytes
 pub const SIGNATURE_SIZE: usize = 64;
@@ -75,10 +76,9 @@ impl AsRef<[u8]> for Signature {
     }
 }
 
-/// Signe des données avec une clé privée
-pub fn sign_data(data: &[u8], private_key: &PrivateKey) -> Result<Signature> {
-    let signature = private_key.inner().sign(data);
-    Ok(Signature::new(signature.to_bytes()))
+/// Signe des données avec un signataire (clé privée locale ou signataire externe)
+pub fn sign_data(data: &[u8], signer: &dyn Signer) -> Result<Signature> {
+    signer.sign(data)
 }
 
 /// Vérifie une signature avec une clé publique
@@ -107,13 +107,13 @@ where
     T: Serialize,
 {
     /// Crée un nouveau message signé
-    pub fn new(message: T, private_key: &PrivateKey) -> Result<Self> {
+    pub fn new(message: T, signer: &dyn Signer) -> Result<Self> {
         // Sérialise le message pour le signer
         let serialized = bincode::serialize(&message)
             .map_err(|e| CryptoError::RandomGeneration(e.to_string()))?;
         
-        let signature = sign_data(&serialized, private_key)?;
-        let signer = private_key.public_key();
+        let signature = sign_data(&serialized, signer)?;
+        let signer = signer.public_key();
         
         Ok(Self {
             message,
@@ -327,8 +327,8 @@ mod tests {
 
 /// Trait pour les types qui peuvent être signés
 pub trait Signable {
-    /// Signe l'objet avec une clé privée
-    fn sign(&self, private_key: &PrivateKey) -> Result<Signature>;
+    /// Signe l'objet
+    fn sign(&self, signer: &dyn Signer) -> Result<Signature>;
     
     /// Vérifie la signature de l'objet
     fn verify_signature(&self, signature: &Signature, public_key: &PublicKey) -> Result<bool>;
@@ -336,10 +336,10 @@ pub trait Signable {
 
 /// Implémentation par défaut pour les types qui implémentent Serialize
 impl<T: Serialize> Signable for T {
-    fn sign(&self, private_key: &PrivateKey) -> Result<Signature> {
+    fn sign(&self, signer: &dyn Signer) -> Result<Signature> {
         let serialized = bincode::serialize(self)
             .map_err(|e| CryptoError::RandomGeneration(e.to_string()))?;
-        sign_data(&serialized, private_key)
+        sign_data(&serialized, signer)
     }
     
     fn verify_signature(&self, signature: &Signature, public_key: &PublicKey) -> Result<bool> {
@@ -0,0 +1,168 @@
+//! Abstraction des signataires pour ArchiveChain
+//!
+//! Toute signature passe par le trait [`Signer`] : la clé privée peut ainsi
+//! rester hors du processus (HSM, KMS, signataire joint par socket). Le
+//! signataire par défaut, [`FileSigner`], charge la clé du nœud depuis un
+//! fichier ; une intégration externe n'a qu'à implémenter le trait.
+
+use std::fmt;
+use std::path::Path;
+
+use ed25519_dalek::Signer as _;
+
+use crate::error::{CoreError, Result};
+use super::keys::{KeyPair, PrivateKey, PublicKey};
+use super::signature::Signature;
+
+/// Signataire Ed25519
+///
+/// Les implémentations externes peuvent bloquer le temps d'un aller-retour
+/// vers leur service ; elles doivent renvoyer une signature Ed25519 valide
+/// pour [`Signer::public_key`]. Depuis du code asynchrone, `sign` s'appelle
+/// donc via `tokio::task::spawn_blocking`.
+pub trait Signer: Send + Sync + fmt::Debug {
+    /// Clé publique correspondant aux signatures produites
+    fn public_key(&self) -> PublicKey;
+
+    /// Signe des octets
+    fn sign(&self, data: &[u8]) -> Result<Signature>;
+
+    /// Identifiant de la clé, par défaut sa clé publique en hexadécimal
+    fn key_id(&self) -> String {
+        self.public_key().to_hex()
+    }
+}
+
+impl Signer for PrivateKey {
+    fn public_key(&self) -> PublicKey {
+        PrivateKey::public_key(self)
+    }
+
+    fn sign(&self, data: &[u8]) -> Result<Signature> {
+        Ok(Signature::new(self.inner().sign(data).to_bytes()))
+    }
+}
+
+impl Signer for KeyPair {
+    fn public_key(&self) -> PublicKey {
+        KeyPair::public_key(self).clone()
+    }
+
+    fn sign(&self, data: &[u8]) -> Result<Signature> {
+        Signer::sign(self.private_key(), data)
+    }
+}
+
+/// Signataire par défaut : clé privée chargée depuis un fichier
+pub struct FileSigner {
+    private_key: PrivateKey,
+    public_key: PublicKey,
+}
+
+impl FileSigner {
+    /// Crée un signataire à partir d'une clé déjà chargée
+    pub fn new(private_key: PrivateKey) -> Self {
+        let public_key = private_key.public_key();
+        Self { private_key, public_key }
+    }
+
+    /// Charge la clé depuis un fichier (32 octets bruts ou leur encodage hexadécimal)
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let content = std::fs::read(path).map_err(|e| CoreError::Internal {
+            message: format!("Lecture de la clé de signature {} impossible: {}", path.display(), e),
+        })?;
+
+        let private_key = match std::str::from_utf8(&content) {
+            Ok(text) if !text.trim().is_empty() && text.trim().chars().all(|c| c.is_ascii_hexdigit()) => {
+                PrivateKey::from_hex(text.trim())
+            }
+            _ => PrivateKey::from_bytes(&content),
+        }
+        .map_err(|e| CoreError::Validation {
+            message: format!("Clé de signature invalide {}: {}", path.display(), e),
+        })?;
+
+        Ok(Self::new(private_key))
+    }
+}
+
+impl Signer for FileSigner {
+    fn public_key(&self) -> PublicKey {
+        self.public_key.clone()
+    }
+
+    fn sign(&self, data: &[u8]) -> Result<Signature> {
+        Signer::sign(&self.private_key, data)
+    }
+}
+
+impl fmt::Debug for FileSigner {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("FileSigner")
+            .field("public_key", &self.public_key.to_hex())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::{generate_keypair, sign_data, verify_signature};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    /// Signataire externe simulé : la clé reste derrière l'appel, comme pour un HSM
+    #[derive(Debug)]
+    struct MockRemoteSigner {
+        keypair: KeyPair,
+        calls: AtomicUsize,
+    }
+
+    impl Signer for MockRemoteSigner {
+        fn public_key(&self) -> PublicKey {
+            self.keypair.public_key().clone()
+        }
+
+        fn sign(&self, data: &[u8]) -> Result<Signature> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Signer::sign(&self.keypair, data)
+        }
+
+        fn key_id(&self) -> String {
+            "hsm-slot-0".to_string()
+        }
+    }
+
+    #[test]
+    fn test_external_signer_is_used_by_sign_data() {
+        let remote = Arc::new(MockRemoteSigner {
+            keypair: generate_keypair().unwrap(),
+            calls: AtomicUsize::new(0),
+        });
+        let signer: Arc<dyn Signer> = remote.clone();
+
+        let signature = sign_data(b"bloc", signer.as_ref()).unwrap();
+
+        assert_eq!(remote.calls.load(Ordering::SeqCst), 1);
+        assert_eq!(signer.key_id(), "hsm-slot-0");
+        assert!(verify_signature(b"bloc", &signature, &signer.public_key()).unwrap());
+    }
+
+    #[test]
+    fn test_file_signer_matches_private_key() {
+        let keypair = generate_keypair().unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("node.key");
+        std::fs::write(&path, keypair.private_key().to_hex()).unwrap();
+
+        let signer = FileSigner::load(&path).unwrap();
+
+        assert_eq!(signer.public_key(), *keypair.public_key());
+        assert_eq!(
+            Signer::sign(&signer, b"data").unwrap(),
+            sign_data(b"data", keypair.private_key()).unwrap()
+        );
+        assert!(FileSigner::load(dir.path().join("missing.key")).is_err());
+    }
+}
@@ -26,6 +26,9 @@ pub enum CoreError {
     #[error("Erreur de sérialisation: {0}")]
     Serialization(#[from] SerializationError),
 
+    #[error("Contenu refusé: {0}")]
+    Content(#[from] ContentError),
+
     #[error("Erreur de validation: {message}")]
     Validation { message: String },
 
@@ -62,6 +65,9 @@ pub enum CryptoError {
 
     #[error("Erreur de décodage hexadécimal: {0}")]
     HexDecode(#[from] hex::FromHexError),
+
+    #[error("Échec d'authentification des données chiffrées: {0}")]
+    AuthenticationFailed(String),
 }
 
 /// Erreurs de bloc
@@ -101,8 +107,23 @@ pub enum TransactionError {
     #[error("Solde insuffisant")]
     InsufficientBalance,
 
-    #[error("Nonce invalide")]
-    InvalidNonce,
+    #[error("Nonce invalide: attendu {expected}, reçu {got}")]
+    InvalidNonce { expected: u64, got: u64 },
+
+    #[error("Frais insuffisants pour remplacer la transaction existante")]
+    ReplacementFeeTooLow,
+
+    #[error("Pool de transactions plein")]
+    PoolFull,
+
+    #[error("Signature en double du signataire {signer}")]
+    DuplicateSignature { signer: String },
+
+    #[error("Signataire non autorisé: {signer}")]
+    UnknownSigner { signer: String },
+
+    #[error("Seuil de signatures non atteint: {valid} valides sur {required} requises")]
+    ThresholdNotMet { required: u32, valid: u32 },
 }
 
 /// Erreurs d'état
@@ -118,6 +139,22 @@ pub enum StateError {
     InconsistentState,
 }
 
+/// Motifs de refus d'un contenu soumis au stockage
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ContentError {
+    #[error("Contenu trop volumineux: {size} octets (limite {limit})")]
+    ContentTooLarge { size: u64, limit: u64 },
+
+    #[error("Métadonnées trop volumineuses: {size} octets (limite {limit})")]
+    MetadataTooLarge { size: usize, limit: usize },
+
+    #[error("Trop de tags: {count} (limite {limit})")]
+    TooManyTags { count: usize, limit: usize },
+
+    #[error("Tag trop long: {length} caractères (limite {limit}): {tag}")]
+    TagTooLong { tag: String, length: usize, limit: usize },
+}
+
 /// Erreurs de sérialisation
 #[derive(Error, Debug)]
 pub enum SerializationError {
@@ -132,6 +169,9 @@ pub enum SerializationError {
 
     #[error("Format non supporté: {format}")]
     UnsupportedFormat { format: String },
+
+    #[error("Encodage canonique invalide: {0}")]
+    Canonical(String),
 }
 
 // TODO: Fix cbor4ii error types when cbor4ii is properly integrated
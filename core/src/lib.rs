@@ -221,9 +221,13 @@ pub mod api;
 // Error handling
 pub mod error;
 
+// Coordinated node shutdown
+pub mod shutdown;
+
 // Re-exports for convenience
-pub use blockchain::{Blockchain, BlockchainConfig, BlockchainStats};
+pub use blockchain::{Blockchain, BlockNotification, BlockchainConfig, BlockchainStats, PruningMode, ReorgOutcome};
 pub use error::{ArchiveChainError, Result, CoreError};
+pub use shutdown::{ShutdownCoordinator, ShutdownConfig, ShutdownHook, ShutdownPhase, ShutdownToken};
 
 // Node system re-exports
 pub use nodes::{
@@ -254,8 +258,8 @@ pub use consensus::{
 // Common type aliases
 pub use crypto::{Hash, PublicKey};
 pub use state::StateRoot;
-pub use transaction::Transaction;
-pub use block::{Block, ArchiveMetadata};
+pub use transaction::{Transaction, TransactionReceipt};
+pub use block::{Block, ArchiveMetadata, verify_header_chain};
 
 /// Version information
 pub const VERSION: &str = env!("CARGO_PKG_VERSION");
@@ -0,0 +1,550 @@
+//! Chargement de la configuration d'un nœud depuis un fichier TOML
+//!
+//! Un fichier unique décrit le nœud (`[node]`) et, optionnellement, ses sections
+//! `[storage]`, `[network]` et `[security]`. Les valeurs absentes prennent des
+//! valeurs par défaut dépendant du type de nœud, les capacités sont validées contre
+//! `constants::nodes` et toutes les erreurs sont rapportées en une seule passe.
+//!
+//! ```toml
+//! [node]
+//! type = "full_archive"
+//! region = "eu-west-1"
+//! storage_capacity = 20_000_000_000_000
+//!
+//! [storage]
+//! data_directory = "data"
+//!
+//! [security]
+//! private_key_path = "keys/node.key"
+//! ```
+
+use serde::Deserialize;
+use std::fmt;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use thiserror::Error;
+use crate::constants::nodes::{
+    MAX_LIGHT_STORAGE_CAPACITY, MIN_FULL_ARCHIVE_CAPACITY, MIN_LIGHT_STORAGE_CAPACITY, MIN_RELAY_BANDWIDTH,
+};
+use crate::consensus::NodeId;
+use crate::crypto::{FileSigner, Hash, Signer};
+use crate::error::CoreError;
+use super::{
+    ApiType, CleanupPolicy, NetworkConfiguration, NodeConfiguration, NodeType, SecurityConfiguration,
+    StorageConfiguration, StorageSpecialization,
+};
+
+/// Variable d'environnement désignant le fichier de configuration du nœud
+pub const NODE_CONFIG_ENV: &str = "ARCHIVECHAIN_NODE_CONFIG";
+
+/// Problème de validation d'un champ de configuration
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigIssue {
+    /// Champ concerné (ex: `node.storage_capacity`)
+    pub field: String,
+    /// Description du problème
+    pub message: String,
+}
+
+impl ConfigIssue {
+    fn new(field: &str, message: impl Into<String>) -> Self {
+        Self { field: field.to_string(), message: message.into() }
+    }
+}
+
+impl fmt::Display for ConfigIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+/// Erreurs de chargement de la configuration d'un nœud
+#[derive(Error, Debug)]
+pub enum NodeConfigError {
+    #[error("Lecture de {path} impossible: {message}")]
+    Io { path: String, message: String },
+
+    #[error("Fichier de configuration invalide: {0}")]
+    Parse(String),
+
+    #[error("Variable d'environnement {0} absente")]
+    MissingEnv(String),
+
+    #[error("{} erreur(s) de configuration: {}", .0.len(), join_issues(.0))]
+    Invalid(Vec<ConfigIssue>),
+}
+
+fn join_issues(issues: &[ConfigIssue]) -> String {
+    issues.iter().map(ConfigIssue::to_string).collect::<Vec<_>>().join("; ")
+}
+
+impl From<NodeConfigError> for CoreError {
+    fn from(error: NodeConfigError) -> Self {
+        CoreError::Validation { message: error.to_string() }
+    }
+}
+
+/// Contenu brut du fichier de configuration
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct NodeConfigFile {
+    #[serde(default)]
+    node: NodeSection,
+    storage: Option<StorageSection>,
+    #[serde(default)]
+    network: NetworkSection,
+    #[serde(default)]
+    security: SecuritySection,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct NodeSection {
+    #[serde(rename = "type")]
+    node_type: Option<String>,
+    node_id: Option<String>,
+    region: Option<String>,
+    listen_address: Option<String>,
+    listen_port: Option<u16>,
+    #[serde(default)]
+    bootstrap_nodes: Vec<String>,
+    storage_capacity: Option<u64>,
+    replication_factor: Option<u32>,
+    specialization: Option<StorageSpecialization>,
+    bandwidth_capacity: Option<u64>,
+    max_connections: Option<u32>,
+    exposed_apis: Option<Vec<ApiType>>,
+    rate_limit: Option<u32>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct StorageSection {
+    data_directory: Option<String>,
+    max_capacity: Option<u64>,
+    compression_level: Option<u8>,
+    encryption_enabled: Option<bool>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct NetworkSection {
+    connection_timeout_secs: Option<u64>,
+    max_connections: Option<u32>,
+    heartbeat_interval_secs: Option<u64>,
+    receive_buffer_size: Option<usize>,
+    send_buffer_size: Option<usize>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct SecuritySection {
+    private_key_path: Option<String>,
+    tls_cert_path: Option<String>,
+    tls_key_path: Option<String>,
+    #[serde(default)]
+    trusted_ca_paths: Vec<String>,
+    require_encryption: Option<bool>,
+    allow_plaintext_fallback: Option<bool>,
+    storage_kek_path: Option<String>,
+}
+
+impl NodeConfiguration {
+    /// Charge la configuration depuis un fichier TOML
+    ///
+    /// Les chemins relatifs (répertoire de données, clés, certificats) sont résolus
+    /// par rapport au répertoire du fichier.
+    pub fn from_toml(path: impl AsRef<Path>) -> std::result::Result<Self, NodeConfigError> {
+        let path = path.as_ref();
+        let content = std::fs::read_to_string(path).map_err(|e| NodeConfigError::Io {
+            path: path.display().to_string(),
+            message: e.to_string(),
+        })?;
+        let base_dir = path.parent().unwrap_or_else(|| Path::new("."));
+        Self::from_toml_str(&content, base_dir)
+    }
+
+    /// Charge la configuration depuis le fichier désigné par `ARCHIVECHAIN_NODE_CONFIG`
+    pub fn from_env() -> std::result::Result<Self, NodeConfigError> {
+        let path = std::env::var_os(NODE_CONFIG_ENV)
+            .ok_or_else(|| NodeConfigError::MissingEnv(NODE_CONFIG_ENV.to_string()))?;
+        Self::from_toml(PathBuf::from(path))
+    }
+
+    /// Construit la configuration depuis un contenu TOML, chemins relatifs à `base_dir`
+    pub fn from_toml_str(content: &str, base_dir: &Path) -> std::result::Result<Self, NodeConfigError> {
+        let file: NodeConfigFile = toml::from_str(content).map_err(|e| NodeConfigError::Parse(e.to_string()))?;
+        file.into_configuration(base_dir)
+    }
+}
+
+impl NodeConfigFile {
+    fn into_configuration(self, base_dir: &Path) -> std::result::Result<NodeConfiguration, NodeConfigError> {
+        let mut issues = Vec::new();
+        let node = self.node;
+
+        let node_type = match node.node_type.as_deref() {
+            None => {
+                issues.push(ConfigIssue::new("node.type", "type de nœud requis"));
+                None
+            }
+            Some(kind) => Self::node_type(kind, &node, &mut issues),
+        };
+
+        let listen_port = node.listen_port.unwrap_or(match &node_type {
+            Some(NodeType::LightStorage { .. }) => 8081,
+            Some(NodeType::Relay { .. }) => 8082,
+            Some(NodeType::Gateway { .. }) => 8083,
+            _ => 8080,
+        });
+        if listen_port == 0 {
+            issues.push(ConfigIssue::new("node.listen_port", "le port d'écoute doit être non nul"));
+        }
+
+        let storage_config = Self::storage_config(self.storage, node_type.as_ref(), base_dir, &mut issues);
+        let network_config = Self::network_config(self.network, &mut issues);
+        let security_config = Self::security_config(self.security, base_dir, &mut issues);
+
+        let node_id = match node.node_id.as_deref() {
+            Some(hex) => match Hash::from_hex(hex) {
+                Ok(hash) => NodeId::from(hash),
+                Err(_) => {
+                    issues.push(ConfigIssue::new("node.node_id", "identifiant hexadécimal de 32 octets attendu"));
+                    NodeId::from(Hash::zero())
+                }
+            },
+            // Par défaut, l'identité du nœud est celle de sa clé de signature
+            None => match FileSigner::load(&security_config.private_key_path) {
+                Ok(signer) => NodeId::from_public_key(&signer.public_key()),
+                Err(e) => {
+                    issues.push(ConfigIssue::new(
+                        "security.private_key_path",
+                        format!("clé du nœud illisible, node.node_id requis sans elle ({})", e),
+                    ));
+                    NodeId::from(Hash::zero())
+                }
+            },
+        };
+
+        match node_type {
+            Some(node_type) if issues.is_empty() => Ok(NodeConfiguration {
+                node_id,
+                node_type,
+                region: node.region.unwrap_or_else(|| "us-east-1".to_string()),
+                listen_address: node.listen_address.unwrap_or_else(|| "0.0.0.0".to_string()),
+                listen_port,
+                bootstrap_nodes: node.bootstrap_nodes,
+                storage_config,
+                network_config,
+                security_config,
+                content_filter: None,
+            }),
+            _ => Err(NodeConfigError::Invalid(issues)),
+        }
+    }
+
+    /// Construit le type de nœud, complété par les valeurs minimales requises
+    fn node_type(kind: &str, node: &NodeSection, issues: &mut Vec<ConfigIssue>) -> Option<NodeType> {
+        let node_type = match kind {
+            "full_archive" => NodeType::FullArchive {
+                storage_capacity: 0,
+                replication_factor: node.replication_factor.unwrap_or(10),
+            },
+            "light_storage" => NodeType::LightStorage {
+                storage_capacity: 0,
+                specialization: match &node.specialization {
+                    Some(specialization) => specialization.clone(),
+                    None => {
+                        issues.push(ConfigIssue::new("node.specialization", "spécialisation requise pour un Light Storage Node"));
+                        StorageSpecialization::Domain
+                    }
+                },
+            },
+            "relay" => NodeType::Relay {
+                bandwidth_capacity: 0,
+                max_connections: node.max_connections.unwrap_or(1000),
+            },
+            "gateway" => NodeType::Gateway {
+                exposed_apis: node.exposed_apis.clone().unwrap_or_else(|| vec![ApiType::Rest]),
+                rate_limit: node.rate_limit.unwrap_or(1000),
+            },
+            other => {
+                issues.push(ConfigIssue::new(
+                    "node.type",
+                    format!("type inconnu '{}' (full_archive, light_storage, relay ou gateway)", other),
+                ));
+                return None;
+            }
+        };
+        let requirements = node_type.minimum_requirements();
+
+        Some(match node_type {
+            NodeType::FullArchive { replication_factor, .. } => {
+                let storage_capacity = node.storage_capacity.unwrap_or(requirements.min_storage);
+                if storage_capacity < MIN_FULL_ARCHIVE_CAPACITY {
+                    issues.push(ConfigIssue::new(
+                        "node.storage_capacity",
+                        format!("un Full Archive Node requiert au moins {} bytes, {} fournis", MIN_FULL_ARCHIVE_CAPACITY, storage_capacity),
+                    ));
+                }
+                if replication_factor == 0 {
+                    issues.push(ConfigIssue::new("node.replication_factor", "le facteur de réplication doit être non nul"));
+                }
+                NodeType::FullArchive { storage_capacity, replication_factor }
+            }
+            NodeType::LightStorage { specialization, .. } => {
+                let storage_capacity = node.storage_capacity.unwrap_or(requirements.min_storage);
+                if !(MIN_LIGHT_STORAGE_CAPACITY..=MAX_LIGHT_STORAGE_CAPACITY).contains(&storage_capacity) {
+                    issues.push(ConfigIssue::new(
+                        "node.storage_capacity",
+                        format!(
+                            "un Light Storage Node requiert entre {} et {} bytes, {} fournis",
+                            MIN_LIGHT_STORAGE_CAPACITY, MAX_LIGHT_STORAGE_CAPACITY, storage_capacity
+                        ),
+                    ));
+                }
+                NodeType::LightStorage { storage_capacity, specialization }
+            }
+            NodeType::Relay { max_connections, .. } => {
+                let bandwidth_capacity = node.bandwidth_capacity.unwrap_or(requirements.min_bandwidth);
+                if bandwidth_capacity < MIN_RELAY_BANDWIDTH {
+                    issues.push(ConfigIssue::new(
+                        "node.bandwidth_capacity",
+                        format!("un Relay Node requiert au moins {} bytes/s, {} fournis", MIN_RELAY_BANDWIDTH, bandwidth_capacity),
+                    ));
+                }
+                if max_connections == 0 {
+                    issues.push(ConfigIssue::new("node.max_connections", "le nombre de connexions doit être non nul"));
+                }
+                NodeType::Relay { bandwidth_capacity, max_connections }
+            }
+            NodeType::Gateway { exposed_apis, rate_limit } => {
+                if exposed_apis.is_empty() {
+                    issues.push(ConfigIssue::new("node.exposed_apis", "au moins une API doit être exposée"));
+                }
+                if rate_limit == 0 {
+                    issues.push(ConfigIssue::new("node.rate_limit", "la limite de requêtes doit être non nulle"));
+                }
+                NodeType::Gateway { exposed_apis, rate_limit }
+            }
+        })
+    }
+
+    /// Section `[storage]`, implicite pour les nœuds de stockage
+    fn storage_config(
+        section: Option<StorageSection>,
+        node_type: Option<&NodeType>,
+        base_dir: &Path,
+        issues: &mut Vec<ConfigIssue>,
+    ) -> Option<StorageConfiguration> {
+        let node_capacity = match node_type {
+            Some(NodeType::FullArchive { storage_capacity, .. })
+            | Some(NodeType::LightStorage { storage_capacity, .. }) => Some(*storage_capacity),
+            _ => None,
+        };
+        let section = match (section, node_capacity) {
+            (Some(section), _) => section,
+            (None, Some(_)) => StorageSection::default(),
+            (None, None) => return None,
+        };
+
+        let defaults = StorageConfiguration::default();
+        let max_capacity = section.max_capacity.or(node_capacity).unwrap_or(defaults.max_capacity);
+        if max_capacity == 0 {
+            issues.push(ConfigIssue::new("storage.max_capacity", "la capacité doit être non nulle"));
+        }
+        if let Some(node_capacity) = node_capacity.filter(|capacity| max_capacity > *capacity) {
+            issues.push(ConfigIssue::new(
+                "storage.max_capacity",
+                format!("{} bytes dépassent la capacité déclarée du nœud ({})", max_capacity, node_capacity),
+            ));
+        }
+
+        let compression_level = section.compression_level.unwrap_or(defaults.compression_level);
+        if compression_level > 9 {
+            issues.push(ConfigIssue::new("storage.compression_level", format!("niveau {} hors de 0-9", compression_level)));
+        }
+
+        Some(StorageConfiguration {
+            data_directory: resolve_path(base_dir, &section.data_directory.unwrap_or(defaults.data_directory)),
+            max_capacity,
+            compression_level,
+            encryption_enabled: section.encryption_enabled.unwrap_or(defaults.encryption_enabled),
+            // 90% de la capacité, comme la configuration par défaut
+            cleanup_policy: CleanupPolicy::Size { max_size: max_capacity / 10 * 9 },
+        })
+    }
+
+    /// Section `[network]`
+    fn network_config(section: NetworkSection, issues: &mut Vec<ConfigIssue>) -> NetworkConfiguration {
+        let defaults = NetworkConfiguration::default();
+        let config = NetworkConfiguration {
+            connection_timeout: section.connection_timeout_secs.map(Duration::from_secs).unwrap_or(defaults.connection_timeout),
+            max_connections: section.max_connections.unwrap_or(defaults.max_connections),
+            heartbeat_interval: section.heartbeat_interval_secs.map(Duration::from_secs).unwrap_or(defaults.heartbeat_interval),
+            receive_buffer_size: section.receive_buffer_size.unwrap_or(defaults.receive_buffer_size),
+            send_buffer_size: section.send_buffer_size.unwrap_or(defaults.send_buffer_size),
+        };
+
+        if config.max_connections == 0 {
+            issues.push(ConfigIssue::new("network.max_connections", "le nombre de connexions doit être non nul"));
+        }
+        if config.connection_timeout.is_zero() {
+            issues.push(ConfigIssue::new("network.connection_timeout_secs", "le timeout doit être non nul"));
+        }
+        if config.heartbeat_interval.is_zero() {
+            issues.push(ConfigIssue::new("network.heartbeat_interval_secs", "l'intervalle de heartbeat doit être non nul"));
+        }
+        config
+    }
+
+    /// Section `[security]`
+    fn security_config(section: SecuritySection, base_dir: &Path, issues: &mut Vec<ConfigIssue>) -> SecurityConfiguration {
+        let defaults = SecurityConfiguration::default();
+        if section.tls_cert_path.is_some() != section.tls_key_path.is_some() {
+            issues.push(ConfigIssue::new("security.tls_key_path", "certificat et clé TLS doivent être fournis ensemble"));
+        }
+
+        SecurityConfiguration {
+            private_key_path: resolve_path(base_dir, &section.private_key_path.unwrap_or(defaults.private_key_path)),
+            tls_cert_path: section.tls_cert_path.map(|path| resolve_path(base_dir, &path)),
+            tls_key_path: section.tls_key_path.map(|path| resolve_path(base_dir, &path)),
+            trusted_ca_paths: section.trusted_ca_paths.iter().map(|path| resolve_path(base_dir, path)).collect(),
+            require_encryption: section.require_encryption.unwrap_or(defaults.require_encryption),
+            allow_plaintext_fallback: section.allow_plaintext_fallback.unwrap_or(defaults.allow_plaintext_fallback),
+            storage_kek_path: section.storage_kek_path.map(|path| resolve_path(base_dir, &path)),
+        }
+    }
+}
+
+/// Résout un chemin relatif par rapport au répertoire du fichier de configuration
+fn resolve_path(base_dir: &Path, path: &str) -> String {
+    let path = Path::new(path);
+    if path.is_absolute() {
+        path.display().to_string()
+    } else {
+        base_dir.join(path).display().to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_full_config() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("node.toml");
+        std::fs::write(&path, r#"
+            [node]
+            type = "light_storage"
+            node_id = "0101010101010101010101010101010101010101010101010101010101010101"
+            region = "eu-west-1"
+            listen_port = 9000
+            bootstrap_nodes = ["10.0.0.1:9000"]
+            storage_capacity = 5_000_000_000_000
+            specialization = "Domain"
+
+            [storage]
+            data_directory = "data"
+            max_capacity = 4_000_000_000_000
+            compression_level = 3
+            encryption_enabled = true
+
+            [network]
+            connection_timeout_secs = 10
+            max_connections = 200
+
+            [security]
+            private_key_path = "keys/node.key"
+            tls_cert_path = "/etc/archivechain/tls.crt"
+            tls_key_path = "tls/node.pem"
+            require_encryption = true
+            storage_kek_path = "keys/storage.kek"
+        "#).unwrap();
+
+        let config = NodeConfiguration::from_toml(&path).unwrap();
+        assert_eq!(config.node_id, NodeId::from(Hash::new([1u8; 32])));
+        assert_eq!(config.node_type, NodeType::LightStorage {
+            storage_capacity: 5_000_000_000_000,
+            specialization: StorageSpecialization::Domain,
+        });
+        assert_eq!(config.region, "eu-west-1");
+        assert_eq!(config.listen_port, 9000);
+        assert_eq!(config.bootstrap_nodes, vec!["10.0.0.1:9000".to_string()]);
+
+        let storage = config.storage_config.unwrap();
+        assert_eq!(storage.data_directory, dir.path().join("data").display().to_string());
+        assert_eq!(storage.max_capacity, 4_000_000_000_000);
+        assert_eq!(storage.compression_level, 3);
+        assert!(storage.encryption_enabled);
+
+        assert_eq!(config.network_config.connection_timeout, Duration::from_secs(10));
+        assert_eq!(config.network_config.max_connections, 200);
+        assert_eq!(config.network_config.heartbeat_interval, Duration::from_secs(30));
+
+        let security = config.security_config;
+        assert_eq!(security.private_key_path, dir.path().join("keys/node.key").display().to_string());
+        assert_eq!(security.tls_cert_path.as_deref(), Some("/etc/archivechain/tls.crt"));
+        assert_eq!(security.tls_key_path, Some(dir.path().join("tls/node.pem").display().to_string()));
+        assert!(security.require_encryption);
+        assert_eq!(security.storage_kek_path, Some(dir.path().join("keys/storage.kek").display().to_string()));
+    }
+
+    #[test]
+    fn test_missing_sections_use_defaults() {
+        let dir = tempfile::tempdir().unwrap();
+        let base = dir.path();
+        let keypair = crate::crypto::generate_keypair().unwrap();
+        std::fs::write(base.join("node.key"), keypair.private_key().to_hex()).unwrap();
+        let config = NodeConfiguration::from_toml_str("[node]\ntype = \"full_archive\"\n", base).unwrap();
+
+        // Sans node_id explicite, l'identité est celle de la clé du nœud
+        assert_eq!(config.node_id, NodeId::from_public_key(keypair.public_key()));
+
+        assert_eq!(config.node_type, NodeType::FullArchive {
+            storage_capacity: MIN_FULL_ARCHIVE_CAPACITY,
+            replication_factor: 10,
+        });
+        assert_eq!(config.listen_port, 8080);
+        assert_eq!(config.listen_address, "0.0.0.0");
+
+        // Les nœuds de stockage reçoivent une section de stockage implicite
+        let storage = config.storage_config.unwrap();
+        assert_eq!(storage.max_capacity, MIN_FULL_ARCHIVE_CAPACITY);
+        assert_eq!(storage.data_directory, base.join("./data").display().to_string());
+        assert_eq!(config.security_config.private_key_path, base.join("node.key").display().to_string());
+        assert_eq!(config.network_config.max_connections, NetworkConfiguration::default().max_connections);
+
+        let relay = NodeConfiguration::from_toml_str("[node]\ntype = \"relay\"\n", base).unwrap();
+        assert!(relay.storage_config.is_none());
+        assert_eq!(relay.listen_port, 8082);
+    }
+
+    #[test]
+    fn test_invalid_config_reports_all_errors() {
+        let content = r#"
+            [node]
+            type = "full_archive"
+            storage_capacity = 2_000_000_000_000
+
+            [storage]
+            compression_level = 12
+
+            [security]
+            tls_cert_path = "tls.crt"
+        "#;
+
+        let error = NodeConfiguration::from_toml_str(content, Path::new(".")).unwrap_err();
+        let NodeConfigError::Invalid(issues) = error else {
+            panic!("erreur de validation attendue");
+        };
+        let fields: Vec<&str> = issues.iter().map(|issue| issue.field.as_str()).collect();
+        assert_eq!(fields, vec![
+            "node.storage_capacity",
+            "storage.compression_level",
+            "security.tls_key_path",
+            "security.private_key_path",
+        ]);
+    }
+}
@@ -11,20 +11,21 @@ use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use std::time::{Duration, SystemTime};
-use tokio::sync::{RwLock, Mutex};
+use tokio::sync::{mpsc, RwLock, Mutex};
 use async_trait::async_trait;
 
-use crate::crypto::{Hash, PublicKey, PrivateKey};
-use crate::consensus::{NodeId, ConsensusScore, ProofOfArchive};
+use crate::crypto::{Hash, Signer};
+use crate::consensus::{NodeId, ConsensusScore, ProofOfArchive, BandwidthReporter, TransferDirection};
 use crate::storage::{
     StorageManager, StorageNodeInfo, ContentMetadata, DistributedStorage,
     StorageType, NodeStatus
 };
 use crate::blockchain::Blockchain;
-use crate::error::Result;
+use crate::error::{CoreError, Result};
+use crate::token::{DeliveryEntry, DeliveryLog};
 use super::{
     Node, NodeType, NodeConfiguration, NetworkMessage, MessageType,
-    NodeHealth, NodeMetrics, GeneralNodeMetrics, HealthStatus
+    NodeHealth, NodeMetrics, GeneralNodeMetrics, HealthStatus, DrainReport, InFlight
 };
 
 /// Configuration spécifique aux Full Archive Nodes
@@ -83,6 +84,7 @@ impl Default for FullArchiveConfig {
                 storage_config: None,
                 network_config: super::NetworkConfiguration::default(),
                 security_config: super::SecurityConfiguration::default(),
+                content_filter: None,
             },
             min_storage_capacity: 10_000_000_000_000, // 10TB minimum
             max_storage_capacity: u64::MAX,
@@ -139,6 +141,8 @@ pub enum FullArchiveStatus {
     Operational,
     /// En maintenance
     Maintenance,
+    /// Drainage avant arrêt : plus de nouvelles archives, lectures servies
+    Draining,
     /// Stockage critique
     StorageCritical,
     /// Panne détectée
@@ -190,8 +194,8 @@ pub struct FullArchiveNode {
     config: FullArchiveConfig,
     /// Identifiant du nœud
     node_id: NodeId,
-    /// Clés cryptographiques
-    keypair: (PublicKey, PrivateKey),
+    /// Signataire du nœud (clé locale ou signataire externe)
+    signer: Arc<dyn Signer>,
     /// Statut actuel
     status: Arc<RwLock<FullArchiveStatus>>,
     /// Gestionnaire de stockage
@@ -200,6 +204,10 @@ pub struct FullArchiveNode {
     blockchain: Arc<RwLock<Blockchain>>,
     /// Moteur de consensus
     consensus_engine: Arc<Mutex<ProofOfArchive>>,
+    /// Remontée des transferts réels vers la preuve de bande passante
+    bandwidth_reporter: BandwidthReporter,
+    /// Journal des livraisons rémunérables, si le nœud en tient un
+    delivery_log: Option<Arc<RwLock<DeliveryLog>>>,
     /// Archives stockées localement
     archived_content: Arc<RwLock<HashSet<Hash>>>,
     /// Cache des métadonnées d'archives
@@ -214,6 +222,14 @@ pub struct FullArchiveNode {
     last_sync: Arc<Mutex<SystemTime>>,
     /// Dernière sauvegarde
     last_backup: Arc<Mutex<SystemTime>>,
+    /// Requêtes de stockage et de lecture en cours
+    in_flight: InFlight,
+    /// Tâche périodique de nettoyage du stockage local
+    cleanup_task: Option<tokio::task::JoinHandle<()>>,
+    /// Tâches de fond du stockage : intégrité, filtre et rééquilibrage
+    storage_tasks: Vec<tokio::task::JoinHandle<()>>,
+    /// Remontée du statut de santé du nœud au rééquilibrage
+    storage_status: Option<mpsc::UnboundedSender<(NodeId, NodeStatus)>>,
 }
 
 /// Informations de connexion P2P
@@ -246,7 +262,7 @@ impl FullArchiveNode {
     /// Crée une nouvelle instance de Full Archive Node
     pub fn new(
         config: FullArchiveConfig,
-        keypair: (PublicKey, PrivateKey),
+        signer: Arc<dyn Signer>,
         storage_manager: StorageManager,
         blockchain: Blockchain,
         consensus_engine: ProofOfArchive,
@@ -256,6 +272,7 @@ impl FullArchiveNode {
 
         let node_id = config.node_config.node_id.clone();
         let start_time = SystemTime::now();
+        let bandwidth_reporter = consensus_engine.bandwidth_reporter(node_id.clone());
 
         let initial_metrics = FullArchiveMetrics {
             general: GeneralNodeMetrics {
@@ -285,10 +302,12 @@ impl FullArchiveNode {
         Ok(Self {
             config,
             node_id,
-            keypair,
+            signer,
             status: Arc::new(RwLock::new(FullArchiveStatus::Initializing)),
             storage_manager: Arc::new(Mutex::new(storage_manager)),
             blockchain: Arc::new(RwLock::new(blockchain)),
+            bandwidth_reporter,
+            delivery_log: None,
             consensus_engine: Arc::new(Mutex::new(consensus_engine)),
             archived_content: Arc::new(RwLock::new(HashSet::new())),
             metadata_cache: Arc::new(RwLock::new(HashMap::new())),
@@ -297,6 +316,10 @@ impl FullArchiveNode {
             start_time,
             last_sync: Arc::new(Mutex::new(start_time)),
             last_backup: Arc::new(Mutex::new(start_time)),
+            in_flight: InFlight::default(),
+            cleanup_task: None,
+            storage_tasks: Vec::new(),
+            storage_status: None,
         })
     }
 
@@ -307,6 +330,14 @@ impl FullArchiveNode {
         data: &[u8],
         metadata: ContentMetadata,
     ) -> Result<StorageResult> {
+        // Compté avant la vérification du statut pour que `drain` l'attende
+        let _request = self.in_flight.enter();
+        if *self.status.read().await == FullArchiveStatus::Draining {
+            return Err(CoreError::ServiceUnavailable {
+                message: "Nœud en cours de drainage, archive refusée".to_string(),
+            });
+        }
+
         // Vérifie l'espace disponible
         self.check_storage_capacity(data.len() as u64).await?;
 
@@ -335,11 +366,13 @@ impl FullArchiveNode {
 
     /// Récupère du contenu archivé
     pub async fn retrieve_archive(&self, content_hash: &Hash) -> Result<Vec<u8>> {
+        let _request = self.in_flight.enter();
+
         // Vérifie d'abord le cache local
         {
             let archived = self.archived_content.read().await;
             if !archived.contains(content_hash) {
-                return Err(crate::error::CoreError::NotFound {
+                return Err(CoreError::NotFound {
                     message: "Archive non trouvée sur ce nœud".to_string(),
                 });
             }
@@ -358,6 +391,53 @@ impl FullArchiveNode {
         Ok(data)
     }
 
+    /// Redirige la remontée des transferts vers un autre moteur de consensus
+    ///
+    /// Par défaut, les transferts sont signalés au moteur passé à `new`.
+    pub fn set_bandwidth_reporter(&mut self, reporter: BandwidthReporter) {
+        self.bandwidth_reporter = reporter;
+    }
+
+    /// Sert une archive à un pair et signale le transfert au consensus
+    pub async fn serve_archive(&self, content_hash: &Hash, peer: &NodeId) -> Result<Vec<u8>> {
+        let started = SystemTime::now();
+        let data = self.retrieve_archive(content_hash).await?;
+
+        self.bandwidth_reporter.report(
+            peer.clone(),
+            data.len() as u64,
+            started.elapsed().unwrap_or(Duration::ZERO),
+            TransferDirection::Upload,
+        );
+
+        Ok(data)
+    }
+
+    /// Consigne les livraisons servies pour l'API dans `log`
+    pub fn set_delivery_log(&mut self, log: Arc<RwLock<DeliveryLog>>) {
+        self.delivery_log = Some(log);
+    }
+
+    /// Sert une archive pour une requête API et consigne la livraison
+    ///
+    /// La livraison n'est rémunérée qu'une fois rapprochée de la requête
+    /// `request_id` consignée par l'API lors du règlement.
+    pub async fn serve_request(&self, content_hash: &Hash, peer: &NodeId, request_id: &str) -> Result<Vec<u8>> {
+        let data = self.serve_archive(content_hash, peer).await?;
+
+        if let Some(log) = &self.delivery_log {
+            log.write().await.record(DeliveryEntry {
+                operator: self.signer.public_key(),
+                content_hash: content_hash.clone(),
+                bytes: data.len() as u64,
+                request_id: request_id.to_string(),
+                timestamp: chrono::Utc::now(),
+            });
+        }
+
+        Ok(data)
+    }
+
     /// Valide l'intégrité d'une archive
     pub async fn validate_archive(&self, content_hash: &Hash) -> Result<bool> {
         // Récupère les métadonnées
@@ -577,6 +657,27 @@ impl Node for FullArchiveNode {
             *status = FullArchiveStatus::Operational;
         }
 
+        // Nettoyage local selon la politique configurée
+        if let Some(storage_config) = &self.config.node_config.storage_config {
+            if let Some(task) = self.cleanup_task.take() {
+                task.abort();
+            }
+            self.cleanup_task = StorageManager::start_cleanup_task(
+                self.storage_manager.clone(),
+                self.node_id.clone(),
+                storage_config.cleanup_policy.clone(),
+            );
+        }
+
+        // Intégrité, filtre de contenus et rééquilibrage des répliques
+        for task in self.storage_tasks.drain(..) {
+            task.abort();
+        }
+        let (status_sender, status_changes) = mpsc::unbounded_channel();
+        self.storage_tasks = self.storage_manager.lock().await
+            .start_background_tasks(self.node_id.clone(), status_changes);
+        self.storage_status = Some(status_sender);
+
         tracing::info!("Full Archive Node démarré avec succès");
         Ok(())
     }
@@ -584,6 +685,14 @@ impl Node for FullArchiveNode {
     async fn stop(&mut self) -> Result<()> {
         tracing::info!("Arrêt du Full Archive Node: {:?}", self.node_id);
 
+        if let Some(task) = self.cleanup_task.take() {
+            task.abort();
+        }
+        for task in self.storage_tasks.drain(..) {
+            task.abort();
+        }
+        self.storage_status = None;
+
         {
             let mut status = self.status.write().await;
             *status = FullArchiveStatus::Stopping;
@@ -620,6 +729,11 @@ impl Node for FullArchiveNode {
             _ => HealthStatus::Warning,
         };
 
+        if let Some(status_sender) = &self.storage_status {
+            // La tâche de rééquilibrage ne s'arrête qu'avec le nœud
+            let _ = status_sender.send((self.node_id.clone(), health_status.storage_status()));
+        }
+
         Ok(NodeHealth {
             status: health_status,
             uptime: self.start_time.elapsed().unwrap_or(Duration::ZERO),
@@ -661,6 +775,7 @@ impl Node for FullArchiveNode {
                     payload: Vec::new(),
                     timestamp: chrono::Utc::now(),
                     ttl: 60,
+                    request_id: message.request_id.clone(),
                 }))
             },
             MessageType::ContentStore => {
@@ -669,9 +784,35 @@ impl Node for FullArchiveNode {
                 Ok(None)
             },
             MessageType::ContentRetrieve => {
-                // Traite une demande de récupération
-                // Dans la réalité, on récupérerait et retournerait le contenu
-                Ok(None)
+                // Le payload contient le hash de l'archive demandée
+                let Ok(content_hash) = Hash::from_bytes(&message.payload) else {
+                    return Ok(None);
+                };
+
+                // Une requête émise pour l'API est consignée en vue de son règlement
+                let served = match message.request_id.as_deref() {
+                    Some(request_id) => self.serve_request(&content_hash, &message.sender, request_id).await,
+                    None => self.serve_archive(&content_hash, &message.sender).await,
+                };
+                let data = match served {
+                    Ok(data) => data,
+                    Err(CoreError::NotFound { .. }) => return Ok(None),
+                    Err(e) => return Err(e),
+                };
+
+                Ok(Some(NetworkMessage {
+                    message_id: crate::crypto::compute_hash(
+                        &message.message_id.as_bytes(),
+                        crate::crypto::HashAlgorithm::Blake3
+                    ),
+                    sender: self.node_id.clone(),
+                    recipient: Some(message.sender),
+                    message_type: MessageType::ContentRetrieve,
+                    payload: data,
+                    timestamp: chrono::Utc::now(),
+                    ttl: 60,
+                    request_id: message.request_id.clone(),
+                }))
             },
             _ => {
                 // Message non géré
@@ -689,6 +830,24 @@ impl Node for FullArchiveNode {
         self.config.node_config = config;
         Ok(())
     }
+
+    async fn drain(&self, timeout: Duration) -> Result<DrainReport> {
+        tracing::info!("Drainage du Full Archive Node: {:?}", self.node_id);
+        *self.status.write().await = FullArchiveStatus::Draining;
+
+        let in_flight_remaining = self.in_flight.wait_idle(timeout).await;
+        if in_flight_remaining > 0 {
+            tracing::warn!("Drainage expiré avec {} requêtes en cours", in_flight_remaining);
+        }
+
+        // Les lectures restent servies : ce nœud est la source des copies
+        let replication = self.storage_manager.lock().await.evacuate_node(&self.node_id).await;
+        Ok(DrainReport { in_flight_remaining, replication })
+    }
+
+    async fn in_flight_transfers(&self) -> usize {
+        self.in_flight.count()
+    }
 }
 
 /// Résultat d'une opération de stockage
@@ -809,7 +968,7 @@ mod tests {
 
         let node = FullArchiveNode::new(
             config,
-            keypair,
+            Arc::new(keypair),
             storage_manager,
             blockchain,
             consensus_engine,
@@ -15,13 +15,14 @@ use std::net::SocketAddr;
 use tokio::sync::{RwLock, Mutex};
 use async_trait::async_trait;
 
-use crate::crypto::{Hash, PublicKey, PrivateKey};
-use crate::consensus::NodeId;
+use crate::crypto::{Hash, Signer};
+use crate::consensus::{NodeId, BandwidthReporter};
 use crate::api::{ApiConfig, ApiError, ApiResult};
 use crate::error::Result;
+use crate::storage::{PrometheusEncoder, PrometheusExporter};
 use super::{
     Node, NodeType, NodeConfiguration, NetworkMessage, MessageType, ApiType,
-    NodeHealth, NodeMetrics, GeneralNodeMetrics, HealthStatus
+    NodeHealth, NodeMetrics, GeneralNodeMetrics, HealthStatus, DrainReport, InFlight
 };
 
 /// Configuration spécifique aux Gateway Nodes
@@ -128,6 +129,10 @@ pub struct RateLimiterConfig {
     pub ip_blacklist: Vec<String>,
     /// Rate limiting par API key
     pub api_key_limits: HashMap<String, RateLimit>,
+    /// Intervalle de nettoyage des buckets inactifs
+    pub cleanup_interval: Duration,
+    /// Durée d'inactivité après laquelle un bucket est supprimé
+    pub bucket_idle_timeout: Duration,
 }
 
 /// Limite de taux pour une clé API
@@ -160,6 +165,9 @@ pub struct GatewaySecurityConfig {
     pub ddos_detection_threshold: u32,
     /// WAF (Web Application Firewall) activé
     pub waf_enabled: bool,
+    /// Règles du WAF, compilées au démarrage du nœud
+    #[serde(default = "default_waf_rules")]
+    pub waf_rules: Vec<WafRule>,
 }
 
 /// Configuration JWT
@@ -230,6 +238,30 @@ pub enum BackendHealthStatus {
     Unknown,
 }
 
+/// État du circuit breaker d'un backend
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum CircuitState {
+    /// Trafic normal
+    Closed,
+    /// Backend écarté jusqu'à expiration du timeout
+    Open,
+    /// Une requête de test est autorisée
+    HalfOpen,
+}
+
+/// Circuit breaker associé à un backend
+#[derive(Debug, Clone)]
+pub struct CircuitBreaker {
+    /// État courant
+    pub state: CircuitState,
+    /// Échecs consécutifs observés
+    pub consecutive_failures: u32,
+    /// Date d'ouverture du circuit
+    pub opened_at: Option<SystemTime>,
+    /// Requête de test en cours (état HalfOpen)
+    pub probe_in_flight: bool,
+}
+
 /// Point d'accès API
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApiEndpoint {
@@ -286,6 +318,8 @@ pub struct LoadBalancer {
     backend_nodes: Arc<RwLock<Vec<BackendNodeInfo>>>,
     /// Index actuel pour Round Robin
     current_index: Arc<Mutex<usize>>,
+    /// Circuit breakers par backend
+    circuit_breakers: Arc<RwLock<HashMap<NodeId, CircuitBreaker>>>,
     /// Métriques
     metrics: Arc<RwLock<LoadBalancerMetrics>>,
 }
@@ -303,6 +337,9 @@ pub struct LoadBalancerMetrics {
     pub average_response_time: Duration,
     /// Distribution des requêtes par backend
     pub requests_per_backend: HashMap<NodeId, u64>,
+    /// État du circuit breaker par backend
+    #[serde(default)]
+    pub circuit_states: HashMap<NodeId, CircuitState>,
 }
 
 /// Couche de cache
@@ -393,6 +430,42 @@ pub struct TokenBucket {
     pub refill_rate: f64,
     /// Dernière mise à jour
     pub last_refill: SystemTime,
+    /// Fenêtres de limitation (minute, heure)
+    pub windows: Vec<RateWindow>,
+    /// Dernière requête reçue
+    pub last_seen: SystemTime,
+    /// Dernière requête refusée
+    pub blocked: bool,
+}
+
+/// Fenêtre fixe de comptage de requêtes
+#[derive(Debug, Clone)]
+pub struct RateWindow {
+    /// Nombre maximum de requêtes dans la fenêtre
+    pub limit: u32,
+    /// Durée de la fenêtre
+    pub length: Duration,
+    /// Début de la fenêtre courante
+    pub started_at: SystemTime,
+    /// Requêtes comptées dans la fenêtre courante
+    pub count: u32,
+}
+
+/// État de la limite la plus contraignante après une décision
+///
+/// Sert à renseigner les en-têtes `X-RateLimit-*` et `Retry-After`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimitStatus {
+    /// Requête autorisée
+    pub allowed: bool,
+    /// Plafond de la limite
+    pub limit: u32,
+    /// Requêtes restantes avant le plafond
+    pub remaining: u32,
+    /// Délai avant que la limite soit de nouveau pleine
+    pub reset_after: Duration,
+    /// Délai avant qu'une requête soit de nouveau acceptée, si refusée
+    pub retry_after: Option<Duration>,
 }
 
 /// Métriques du rate limiter
@@ -430,15 +503,62 @@ pub struct DDoSDetector {
     pub detection_threshold: u32,
     /// Requêtes par IP
     pub requests_per_ip: HashMap<String, VecDeque<SystemTime>>,
+    /// IPs blacklistées, avec la fin de leur blocage
+    pub blacklist: HashMap<String, SystemTime>,
+}
+
+/// Décision du détecteur DDoS pour une requête
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DdosDecision {
+    /// Requête acceptée
+    Allowed,
+    /// Seuil franchi : l'IP vient d'être blacklistée
+    Blacklisted { until: SystemTime },
+    /// IP déjà blacklistée
+    Blocked { until: SystemTime },
 }
 
+/// Nombre d'IPs suivies au-delà duquel les fenêtres vides sont purgées
+const DDOS_TRACKED_IPS_PURGE_THRESHOLD: usize = 10_000;
+
 /// Web Application Firewall
+///
+/// Les règles et patterns sont compilés une fois à la construction ; modifier
+/// `rules` ensuite n'a pas d'effet sur l'inspection.
 #[derive(Debug, Clone)]
 pub struct WebApplicationFirewall {
     /// Règles de filtrage
     pub rules: Vec<WafRule>,
     /// Patterns suspects
     pub suspicious_patterns: Vec<String>,
+    /// Expressions des règles activées, avec l'index de leur règle
+    compiled_rules: Vec<(usize, regex::Regex)>,
+    /// Patterns suspects, recherchés littéralement
+    compiled_patterns: Vec<regex::Regex>,
+}
+
+/// Verdict du WAF pour une requête
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WafVerdict {
+    /// Aucune règle déclenchée
+    Allow,
+    /// Règles `Log` déclenchées, requête transmise
+    Logged { rules: Vec<String> },
+    /// Requête rejetée (403)
+    Blocked { rule: String },
+    /// Requête soumise à un challenge
+    Challenged { rule: String },
+}
+
+/// Vue d'une requête HTTP brute pour l'inspection
+#[derive(Debug, Clone, Default)]
+pub struct WafRequest {
+    /// Chemin (et query string), décodé
+    pub path: String,
+    /// En-têtes `nom: valeur`
+    pub headers: Vec<String>,
+    /// Corps
+    pub body: String,
 }
 
 /// Règle WAF
@@ -493,6 +613,8 @@ pub enum GatewayNodeStatus {
     Overloaded,
     /// Maintenance
     Maintenance,
+    /// Drainage avant arrêt : nouvelles requêtes refusées
+    Draining,
     /// Problème de sécurité
     SecurityIssue,
     /// Arrêt en cours
@@ -522,6 +644,63 @@ pub struct GatewayMetrics {
     pub active_websocket_connections: u32,
     /// Clients authentifiés
     pub authenticated_clients: u32,
+    /// Volume servi par contenu (bytes)
+    #[serde(default)]
+    pub bytes_served_per_content: HashMap<Hash, u64>,
+}
+
+impl GatewayMetrics {
+    /// Rend les métriques du Gateway au format d'exposition texte de Prometheus
+    pub fn to_prometheus(&self, labels: &[(&str, &str)]) -> String {
+        let mut encoder = PrometheusEncoder::new(labels);
+
+        let mut requests: Vec<(&'static str, f64)> = self.requests_per_api.iter()
+            .map(|(api, count)| (api_label(api), *count as f64))
+            .collect();
+        requests.sort_by(|a, b| a.0.cmp(b.0));
+        let label_sets: Vec<[(&str, &str); 1]> = requests.iter().map(|(api, _)| [("api", *api)]).collect();
+        let samples: Vec<(&[(&str, &str)], f64)> = label_sets.iter()
+            .zip(&requests)
+            .map(|(labels, (_, count))| (&labels[..], *count))
+            .collect();
+        encoder.family("archivechain_gateway_requests_total", "counter", "Requêtes reçues par API", &samples);
+
+        encoder.family("archivechain_gateway_cache_requests_total", "counter", "Consultations du cache par résultat", &[
+            (&[("result", "hit")], self.cache_metrics.cache_hits as f64),
+            (&[("result", "miss")], self.cache_metrics.cache_misses as f64),
+        ]);
+        encoder.counter("archivechain_gateway_cache_evictions_total", "Entrées évincées du cache", self.cache_metrics.evictions as f64);
+        encoder.gauge("archivechain_gateway_cache_hit_ratio", "Ratio de hit du cache", self.cache_metrics.hit_ratio);
+        encoder.gauge("archivechain_gateway_cache_size_bytes", "Taille actuelle du cache", self.cache_metrics.current_cache_size as f64);
+
+        encoder.family("archivechain_gateway_rate_limiter_requests_total", "counter", "Décisions du rate limiter", &[
+            (&[("decision", "allowed")], self.rate_limiter_metrics.allowed_requests as f64),
+            (&[("decision", "blocked")], self.rate_limiter_metrics.blocked_requests as f64),
+        ]);
+        encoder.gauge("archivechain_gateway_rate_limiter_blocked_ips", "IPs actuellement bloquées", self.rate_limiter_metrics.currently_blocked_ips as f64);
+
+        encoder.family("archivechain_gateway_security_requests_total", "counter", "Requêtes relevées par le WAF", &[
+            (&[("outcome", "detected")], self.security_metrics.attacks_detected as f64),
+            (&[("outcome", "blocked")], self.security_metrics.attacks_blocked as f64),
+            (&[("outcome", "suspicious")], self.security_metrics.suspicious_requests as f64),
+        ]);
+
+        encoder.gauge("archivechain_gateway_websocket_connections", "Connexions WebSocket actives", self.active_websocket_connections as f64);
+        encoder.gauge("archivechain_gateway_authenticated_clients", "Clients authentifiés", self.authenticated_clients as f64);
+
+        encoder.finish()
+    }
+}
+
+/// Valeur du label `api` d'une série Prometheus
+fn api_label(api: &ApiType) -> &'static str {
+    match api {
+        ApiType::Rest => "rest",
+        ApiType::GraphQL => "graphql",
+        ApiType::WebSocket => "websocket",
+        ApiType::GRPC => "grpc",
+        ApiType::P2P => "p2p",
+    }
 }
 
 impl NodeMetrics for GatewayMetrics {
@@ -540,8 +719,8 @@ pub struct GatewayNode {
     config: GatewayNodeConfig,
     /// Identifiant du nœud
     node_id: NodeId,
-    /// Clés cryptographiques
-    keypair: (PublicKey, PrivateKey),
+    /// Signataire du nœud (clé locale ou signataire externe)
+    signer: Arc<dyn Signer>,
     /// Statut actuel
     status: Arc<RwLock<GatewayNodeStatus>>,
     /// Points d'accès API
@@ -552,12 +731,18 @@ pub struct GatewayNode {
     cache_layer: Arc<Mutex<CacheLayer>>,
     /// Rate limiter
     rate_limiter: Arc<Mutex<RateLimiter>>,
+    /// Tâche de nettoyage des buckets du rate limiter
+    rate_limiter_cleanup: Option<tokio::task::JoinHandle<()>>,
     /// Stack de sécurité
     security_stack: Arc<Mutex<SecurityStack>>,
     /// Métriques
     metrics: Arc<RwLock<GatewayMetrics>>,
+    /// Remontée des livraisons de contenu vers la preuve de bande passante
+    bandwidth_reporter: Arc<RwLock<Option<BandwidthReporter>>>,
     /// Heure de démarrage
     start_time: SystemTime,
+    /// Requêtes HTTP en cours
+    in_flight: InFlight,
 }
 
 impl Default for GatewayNodeConfig {
@@ -576,6 +761,7 @@ impl Default for GatewayNodeConfig {
                 storage_config: None,
                 network_config: super::NetworkConfiguration::default(),
                 security_config: super::SecurityConfiguration::default(),
+                content_filter: None,
             },
             exposed_apis: vec![ApiType::Rest, ApiType::WebSocket],
             load_balancer_config: LoadBalancerConfig::default(),
@@ -625,6 +811,8 @@ impl Default for RateLimiterConfig {
             ip_whitelist: Vec::new(),
             ip_blacklist: Vec::new(),
             api_key_limits: HashMap::new(),
+            cleanup_interval: Duration::from_secs(60),
+            bucket_idle_timeout: Duration::from_secs(600), // 10 minutes
         }
     }
 }
@@ -639,10 +827,30 @@ impl Default for GatewaySecurityConfig {
             ddos_protection_enabled: true,
             ddos_detection_threshold: 1000,
             waf_enabled: true,
+            waf_rules: default_waf_rules(),
         }
     }
 }
 
+/// Règles WAF par défaut : injection SQL, XSS et traversée de répertoires
+fn default_waf_rules() -> Vec<WafRule> {
+    let rule = |name: &str, pattern: &str| WafRule {
+        name: name.to_string(),
+        pattern: pattern.to_string(),
+        action: WafAction::Block,
+        enabled: true,
+    };
+
+    vec![
+        rule(
+            "sql-injection",
+            r#"\bunion\b[\s\S]*\bselect\b|'\s*(?:or|and)\s+['"]?\w+['"]?\s*=\s*['"]?\w+|;\s*(?:drop|delete|insert|update|alter)\s|'\s*(?:--|#|/\*)|\b(?:sleep|benchmark|pg_sleep)\s*\("#,
+        ),
+        rule("cross-site-scripting", r"<\s*script\b|javascript\s*:|\bon(?:error|load|mouseover)\s*="),
+        rule("path-traversal", r"(?:\.\.[/\\])"),
+    ]
+}
+
 impl Default for JwtConfig {
     fn default() -> Self {
         Self {
@@ -669,34 +877,201 @@ impl Default for GatewayMonitoringConfig {
     }
 }
 
+impl CircuitBreaker {
+    /// Crée un circuit fermé
+    pub fn new() -> Self {
+        Self {
+            state: CircuitState::Closed,
+            consecutive_failures: 0,
+            opened_at: None,
+            probe_in_flight: false,
+        }
+    }
+
+    /// Passe en HalfOpen si le timeout d'ouverture est écoulé
+    fn refresh(&mut self, timeout: Duration, now: SystemTime) {
+        if self.state == CircuitState::Open {
+            let elapsed = self.opened_at
+                .and_then(|opened| now.duration_since(opened).ok())
+                .unwrap_or(Duration::ZERO);
+            if elapsed >= timeout {
+                self.state = CircuitState::HalfOpen;
+                self.probe_in_flight = false;
+            }
+        }
+    }
+
+    /// Indique si le backend peut recevoir une requête
+    fn allows_request(&self) -> bool {
+        match self.state {
+            CircuitState::Closed => true,
+            CircuitState::HalfOpen => !self.probe_in_flight,
+            CircuitState::Open => false,
+        }
+    }
+
+    /// Enregistre un succès : le circuit se referme
+    fn on_success(&mut self) {
+        self.state = CircuitState::Closed;
+        self.consecutive_failures = 0;
+        self.opened_at = None;
+        self.probe_in_flight = false;
+    }
+
+    /// Enregistre un échec et ouvre le circuit si nécessaire
+    fn on_failure(&mut self, max_failures: u32, now: SystemTime) {
+        self.consecutive_failures = self.consecutive_failures.saturating_add(1);
+        let trip = match self.state {
+            // Une sonde ratée rouvre immédiatement le circuit
+            CircuitState::HalfOpen => true,
+            CircuitState::Closed => self.consecutive_failures >= max_failures.max(1),
+            CircuitState::Open => false,
+        };
+        if trip {
+            self.state = CircuitState::Open;
+            self.opened_at = Some(now);
+        }
+        self.probe_in_flight = false;
+    }
+}
+
+impl Default for CircuitBreaker {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl LoadBalancer {
     /// Crée un nouveau load balancer
     pub fn new(config: LoadBalancerConfig, backend_nodes: Vec<BackendNodeInfo>) -> Self {
+        let circuit_breakers = backend_nodes.iter()
+            .map(|b| (b.node_id.clone(), CircuitBreaker::new()))
+            .collect::<HashMap<_, _>>();
+        let circuit_states = circuit_breakers.keys()
+            .map(|id| (id.clone(), CircuitState::Closed))
+            .collect();
+
         Self {
             config,
             backend_nodes: Arc::new(RwLock::new(backend_nodes)),
             current_index: Arc::new(Mutex::new(0)),
+            circuit_breakers: Arc::new(RwLock::new(circuit_breakers)),
             metrics: Arc::new(RwLock::new(LoadBalancerMetrics {
                 total_requests: 0,
                 successful_requests: 0,
                 failed_requests: 0,
                 average_response_time: Duration::ZERO,
                 requests_per_backend: HashMap::new(),
+                circuit_states,
             })),
         }
     }
 
     /// Sélectionne un backend selon l'algorithme configuré
+    ///
+    /// Les backends dont le circuit est ouvert sont écartés ; un backend
+    /// HalfOpen ne reçoit qu'une seule requête de test à la fois.
     pub async fn select_backend(&self, client_ip: Option<&str>) -> Option<NodeId> {
         let backends = self.backend_nodes.read().await;
+        let mut breakers = self.circuit_breakers.write().await;
+        let now = SystemTime::now();
+
+        let mut transitioned = false;
+        for breaker in breakers.values_mut() {
+            let previous = breaker.state;
+            breaker.refresh(self.config.circuit_breaker_timeout, now);
+            transitioned |= breaker.state != previous;
+        }
+
         let healthy_backends: Vec<_> = backends.iter()
             .filter(|b| b.health_status == BackendHealthStatus::Healthy)
+            .filter(|b| breakers.get(&b.node_id).map_or(true, |cb| cb.allows_request()))
             .collect();
 
         if healthy_backends.is_empty() {
+            if transitioned {
+                self.sync_circuit_metrics(&breakers).await;
+            }
             return None;
         }
 
+        let selected = self.pick_backend(&healthy_backends, client_ip).await;
+
+        if let Some(node_id) = &selected {
+            if let Some(breaker) = breakers.get_mut(node_id) {
+                if breaker.state == CircuitState::HalfOpen {
+                    breaker.probe_in_flight = true;
+                }
+            }
+        }
+        if transitioned {
+            self.sync_circuit_metrics(&breakers).await;
+        }
+
+        selected
+    }
+
+    /// Enregistre une requête réussie vers un backend
+    pub async fn record_success(&self, node_id: &NodeId) {
+        let mut breakers = self.circuit_breakers.write().await;
+        breakers.entry(node_id.clone()).or_default().on_success();
+
+        {
+            let mut metrics = self.metrics.write().await;
+            metrics.total_requests += 1;
+            metrics.successful_requests += 1;
+            *metrics.requests_per_backend.entry(node_id.clone()).or_insert(0) += 1;
+        }
+        self.sync_circuit_metrics(&breakers).await;
+    }
+
+    /// Enregistre un échec de requête vers un backend
+    ///
+    /// Le circuit s'ouvre après `max_retries` échecs consécutifs.
+    pub async fn record_failure(&self, node_id: &NodeId) {
+        let mut breakers = self.circuit_breakers.write().await;
+        let breaker = breakers.entry(node_id.clone()).or_default();
+        breaker.on_failure(self.config.max_retries, SystemTime::now());
+        if breaker.state == CircuitState::Open {
+            tracing::warn!("Circuit ouvert pour le backend {:?}", node_id);
+        }
+
+        {
+            let mut metrics = self.metrics.write().await;
+            metrics.total_requests += 1;
+            metrics.failed_requests += 1;
+            *metrics.requests_per_backend.entry(node_id.clone()).or_insert(0) += 1;
+        }
+        self.sync_circuit_metrics(&breakers).await;
+    }
+
+    /// État courant du circuit d'un backend
+    pub async fn circuit_state(&self, node_id: &NodeId) -> Option<CircuitState> {
+        let mut breakers = self.circuit_breakers.write().await;
+        let breaker = breakers.get_mut(node_id)?;
+        breaker.refresh(self.config.circuit_breaker_timeout, SystemTime::now());
+        Some(breaker.state)
+    }
+
+    /// Métriques du load balancer
+    pub async fn get_metrics(&self) -> LoadBalancerMetrics {
+        self.metrics.read().await.clone()
+    }
+
+    /// Recopie l'état des circuits dans les métriques
+    async fn sync_circuit_metrics(&self, breakers: &HashMap<NodeId, CircuitBreaker>) {
+        let mut metrics = self.metrics.write().await;
+        metrics.circuit_states = breakers.iter()
+            .map(|(id, cb)| (id.clone(), cb.state))
+            .collect();
+    }
+
+    /// Applique l'algorithme de load balancing aux backends éligibles
+    async fn pick_backend(
+        &self,
+        healthy_backends: &[&BackendNodeInfo],
+        client_ip: Option<&str>,
+    ) -> Option<NodeId> {
         match self.config.algorithm {
             LoadBalancingAlgorithm::RoundRobin => {
                 let mut index = self.current_index.lock().await;
@@ -760,27 +1135,32 @@ impl CacheLayer {
         }
 
         let mut cache = self.content_cache.write().await;
+        let mut expired_size = 0;
         if let Some(cached) = cache.get_mut(content_hash) {
             // Vérifie le TTL
-            if cached.cached_at.elapsed().unwrap_or(Duration::ZERO) < cached.ttl {
+            if !cached.is_expired(SystemTime::now()) {
                 cached.access_count += 1;
                 cached.last_accessed = SystemTime::now();
                 
                 // Met à jour les métriques
                 let mut metrics = self.metrics.write().await;
                 metrics.cache_hits += 1;
+                metrics.update_hit_ratio();
                 
                 // Décompresse si nécessaire
                 return Some(cached.compressed_data.clone()); // Simplification
             } else {
                 // Contenu expiré
+                expired_size = cached.size();
                 cache.remove(content_hash);
             }
         }
 
         // Cache miss
         let mut metrics = self.metrics.write().await;
+        metrics.current_cache_size = metrics.current_cache_size.saturating_sub(expired_size);
         metrics.cache_misses += 1;
+        metrics.update_hit_ratio();
         None
     }
 
@@ -801,12 +1181,242 @@ impl CacheLayer {
             last_accessed: SystemTime::now(),
         };
 
+        let new_size = cached_content.size();
+        if new_size > self.config.max_cache_size {
+            tracing::debug!("Contenu {:?} trop volumineux pour le cache ({} bytes)", content_hash, new_size);
+            return;
+        }
+
         let mut cache = self.content_cache.write().await;
+        let mut metrics = self.metrics.write().await;
+
+        // Remplacement d'une entrée existante
+        if let Some(previous) = cache.remove(&content_hash) {
+            metrics.current_cache_size = metrics.current_cache_size.saturating_sub(previous.size());
+        }
+
+        // Purge d'abord les entrées expirées
+        let now = SystemTime::now();
+        let expired: Vec<Hash> = cache.iter()
+            .filter(|(_, cached)| cached.is_expired(now))
+            .map(|(hash, _)| *hash)
+            .collect();
+        for hash in expired {
+            if let Some(removed) = cache.remove(&hash) {
+                metrics.current_cache_size = metrics.current_cache_size.saturating_sub(removed.size());
+                metrics.evictions += 1;
+            }
+        }
+
+        // Évince selon la politique jusqu'à ce que la nouvelle entrée tienne
+        while metrics.current_cache_size + new_size > self.config.max_cache_size {
+            let Some(victim) = Self::select_victim(&self.config.eviction_policy, &cache) else {
+                break;
+            };
+            if let Some(removed) = cache.remove(&victim) {
+                metrics.current_cache_size = metrics.current_cache_size.saturating_sub(removed.size());
+                metrics.evictions += 1;
+            }
+        }
+
         cache.insert(content_hash, cached_content);
+        metrics.current_cache_size += new_size;
+    }
 
-        // Met à jour les métriques
+    /// Choisit l'entrée à évincer selon la politique configurée
+    fn select_victim(policy: &CacheEvictionPolicy, cache: &HashMap<Hash, CachedContent>) -> Option<Hash> {
+        let entries = cache.iter();
+        let victim = match policy {
+            CacheEvictionPolicy::LRU => entries.min_by_key(|(hash, c)| (c.last_accessed, *hash.as_bytes())),
+            CacheEvictionPolicy::LFU => entries.min_by_key(|(hash, c)| (c.access_count, c.last_accessed, *hash.as_bytes())),
+            CacheEvictionPolicy::FIFO => entries.min_by_key(|(hash, c)| (c.cached_at, *hash.as_bytes())),
+            CacheEvictionPolicy::TTL => entries.min_by_key(|(hash, c)| (c.expires_at(), *hash.as_bytes())),
+        };
+        victim.map(|(hash, _)| *hash)
+    }
+
+    /// Vide le cache de contenu
+    pub async fn clear_content(&self) {
+        let mut cache = self.content_cache.write().await;
+        cache.clear();
         let mut metrics = self.metrics.write().await;
-        metrics.current_cache_size += data.len() as u64;
+        metrics.current_cache_size = 0;
+    }
+
+    /// Obtient les métriques du cache
+    pub async fn get_metrics(&self) -> CacheMetrics {
+        self.metrics.read().await.clone()
+    }
+}
+
+impl CachedContent {
+    /// Taille occupée en mémoire par l'entrée
+    pub fn size(&self) -> u64 {
+        self.compressed_data.len() as u64
+    }
+
+    /// Date d'expiration de l'entrée
+    pub fn expires_at(&self) -> SystemTime {
+        self.cached_at + self.ttl
+    }
+
+    /// Indique si l'entrée a dépassé son TTL
+    pub fn is_expired(&self, now: SystemTime) -> bool {
+        now >= self.expires_at()
+    }
+}
+
+impl CacheMetrics {
+    /// Recalcule le ratio de hit
+    fn update_hit_ratio(&mut self) {
+        let total = self.cache_hits + self.cache_misses;
+        self.hit_ratio = if total > 0 {
+            self.cache_hits as f64 / total as f64
+        } else {
+            0.0
+        };
+    }
+}
+
+impl RateWindow {
+    /// Crée une fenêtre démarrant maintenant
+    pub fn new(limit: u32, length: Duration, now: SystemTime) -> Self {
+        Self { limit, length, started_at: now, count: 0 }
+    }
+
+    /// Fait avancer la fenêtre si elle est écoulée
+    fn roll(&mut self, now: SystemTime) {
+        if now.duration_since(self.started_at).unwrap_or(Duration::ZERO) >= self.length {
+            self.started_at = now;
+            self.count = 0;
+        }
+    }
+
+    /// Indique s'il reste de la place dans la fenêtre
+    fn has_capacity(&self) -> bool {
+        self.count < self.limit
+    }
+
+    /// Délai avant la fin de la fenêtre courante
+    fn reset_after(&self, now: SystemTime) -> Duration {
+        (self.started_at + self.length).duration_since(now).unwrap_or(Duration::ZERO)
+    }
+}
+
+impl RateLimitStatus {
+    /// Statut d'une IP blacklistée : aucune requête acceptée
+    fn denied() -> Self {
+        Self {
+            allowed: false,
+            limit: 0,
+            remaining: 0,
+            reset_after: Duration::ZERO,
+            retry_after: None,
+        }
+    }
+
+    /// Combine deux limites appliquées à la même requête
+    ///
+    /// La limite exposée est celle qui a le moins de requêtes restantes (la
+    /// plus longue à se réinitialiser en cas d'égalité) ; la requête n'est
+    /// autorisée que si les deux l'autorisent.
+    pub fn most_restrictive(self, other: Self) -> Self {
+        let binding = if (other.remaining, std::cmp::Reverse(other.reset_after))
+            < (self.remaining, std::cmp::Reverse(self.reset_after))
+        {
+            other
+        } else {
+            self
+        };
+
+        Self {
+            allowed: self.allowed && other.allowed,
+            retry_after: self.retry_after.max(other.retry_after),
+            ..binding
+        }
+    }
+}
+
+impl TokenBucket {
+    /// Crée un bucket plein avec ses fenêtres de limitation
+    pub fn new(requests_per_second: u32, windows: Vec<RateWindow>, now: SystemTime) -> Self {
+        Self {
+            tokens: requests_per_second as f64,
+            capacity: requests_per_second as f64,
+            refill_rate: requests_per_second as f64,
+            last_refill: now,
+            windows,
+            last_seen: now,
+            blocked: false,
+        }
+    }
+
+    /// Tente de consommer une requête ; toutes les limites doivent l'autoriser
+    pub fn try_acquire(&mut self, now: SystemTime) -> bool {
+        let elapsed = now.duration_since(self.last_refill).unwrap_or(Duration::ZERO);
+        self.tokens = (self.tokens + elapsed.as_secs_f64() * self.refill_rate).min(self.capacity);
+        self.last_refill = now;
+        self.last_seen = now;
+
+        for window in &mut self.windows {
+            window.roll(now);
+        }
+
+        let allowed = self.tokens >= 1.0 && self.windows.iter().all(RateWindow::has_capacity);
+        if allowed {
+            self.tokens -= 1.0;
+            for window in &mut self.windows {
+                window.count += 1;
+            }
+        }
+        self.blocked = !allowed;
+        allowed
+    }
+
+    /// Indique si le bucket est inactif depuis plus de `idle_timeout`
+    pub fn is_idle(&self, now: SystemTime, idle_timeout: Duration) -> bool {
+        now.duration_since(self.last_seen).unwrap_or(Duration::ZERO) > idle_timeout
+    }
+
+    /// État du bucket après `try_acquire`, ramené à sa limite la plus contraignante
+    pub fn status(&self, now: SystemTime) -> RateLimitStatus {
+        let allowed = !self.blocked;
+        let mut status = RateLimitStatus {
+            allowed,
+            limit: self.capacity as u32,
+            remaining: self.tokens.max(0.0).floor() as u32,
+            reset_after: self.refill_delay(self.capacity - self.tokens),
+            retry_after: None,
+        };
+        let mut retry_after = (self.tokens < 1.0).then(|| self.refill_delay(1.0 - self.tokens));
+
+        for window in &self.windows {
+            let reset_after = window.reset_after(now);
+            if !window.has_capacity() {
+                retry_after = retry_after.max(Some(reset_after));
+            }
+            status = status.most_restrictive(RateLimitStatus {
+                allowed,
+                limit: window.limit,
+                remaining: window.limit.saturating_sub(window.count),
+                reset_after,
+                retry_after: None,
+            });
+        }
+
+        RateLimitStatus {
+            retry_after: if allowed { None } else { retry_after },
+            ..status
+        }
+    }
+
+    /// Temps nécessaire pour regagner `tokens` jetons
+    fn refill_delay(&self, tokens: f64) -> Duration {
+        if self.refill_rate > 0.0 {
+            Duration::from_secs_f64(tokens.max(0.0) / self.refill_rate)
+        } else {
+            Duration::ZERO
+        }
     }
 }
 
@@ -828,100 +1438,492 @@ impl RateLimiter {
 
     /// Vérifie si une requête est autorisée
     pub async fn check_rate_limit(&self, client_ip: &str, api_key: Option<&str>) -> bool {
+        self.rate_limit_status(client_ip, api_key).await
+            .map_or(true, |status| status.allowed)
+    }
+
+    /// Décide d'une requête et retourne l'état de la limite la plus contraignante
+    ///
+    /// `None` si la requête n'est soumise à aucune limite (rate limiting
+    /// désactivé ou IP en whitelist).
+    pub async fn rate_limit_status(&self, client_ip: &str, api_key: Option<&str>) -> Option<RateLimitStatus> {
         if !self.config.enabled {
-            return true;
+            return None;
         }
 
         // Vérifie la whitelist
         if self.config.ip_whitelist.contains(&client_ip.to_string()) {
-            return true;
+            return None;
         }
 
         // Vérifie la blacklist
         if self.config.ip_blacklist.contains(&client_ip.to_string()) {
             let mut metrics = self.metrics.write().await;
             metrics.blocked_requests += 1;
-            return false;
+            return Some(RateLimitStatus::denied());
         }
 
         // Vérifie le rate limit par IP
-        let ip_allowed = self.check_ip_rate_limit(client_ip).await;
-        
+        let mut status = self.acquire_ip(client_ip).await;
+
         // Vérifie le rate limit par API key si présente
-        let api_key_allowed = if let Some(key) = api_key {
-            self.check_api_key_rate_limit(key).await
-        } else {
-            true
-        };
+        if let Some((key, limit)) = api_key.and_then(|key| self.config.api_key_limits.get_key_value(key)) {
+            status = status.most_restrictive(self.acquire_api_key(key, limit).await);
+        }
 
-        let allowed = ip_allowed && api_key_allowed;
-        
-        // Met à jour les métriques
+        self.record_decision(status.allowed).await;
+        Some(status)
+    }
+
+    /// Vérifie la limite d'une clé API émise dynamiquement (hors `api_key_limits`)
+    ///
+    /// Une limite présente dans la configuration reste prioritaire sur `limit`.
+    pub async fn check_api_key_limit(&self, api_key: &str, limit: &RateLimit) -> bool {
+        self.api_key_limit_status(api_key, limit).await
+            .map_or(true, |status| status.allowed)
+    }
+
+    /// Comme `check_api_key_limit`, en retournant l'état de la limite
+    pub async fn api_key_limit_status(&self, api_key: &str, limit: &RateLimit) -> Option<RateLimitStatus> {
+        if !self.config.enabled {
+            return None;
+        }
+
+        let limit = self.config.api_key_limits.get(api_key).unwrap_or(limit);
+        let status = self.acquire_api_key(api_key, limit).await;
+        self.record_decision(status.allowed).await;
+        Some(status)
+    }
+
+    /// Met à jour les métriques après une décision
+    async fn record_decision(&self, allowed: bool) {
         let mut metrics = self.metrics.write().await;
         if allowed {
             metrics.allowed_requests += 1;
         } else {
             metrics.blocked_requests += 1;
         }
+        let total = metrics.allowed_requests + metrics.blocked_requests;
+        metrics.block_rate = metrics.blocked_requests as f64 / total as f64;
+    }
 
-        allowed
+    /// Supprime les buckets inactifs depuis plus de `bucket_idle_timeout`
+    ///
+    /// Retourne le nombre de buckets supprimés.
+    pub async fn cleanup_idle_buckets(&self, now: SystemTime) -> usize {
+        Self::purge_idle_buckets(
+            &self.ip_buckets,
+            &self.api_key_buckets,
+            &self.metrics,
+            self.config.bucket_idle_timeout,
+            now,
+        ).await
     }
 
-    async fn check_ip_rate_limit(&self, ip: &str) -> bool {
+    async fn purge_idle_buckets(
+        ip_buckets: &RwLock<HashMap<String, TokenBucket>>,
+        api_key_buckets: &RwLock<HashMap<String, TokenBucket>>,
+        metrics: &RwLock<RateLimiterMetrics>,
+        idle_timeout: Duration,
+        now: SystemTime,
+    ) -> usize {
+        let mut removed = 0;
+
+        let mut released_blocked_ips = 0;
+        {
+            let mut buckets = ip_buckets.write().await;
+            let before = buckets.len();
+            buckets.retain(|_, bucket| {
+                let idle = bucket.is_idle(now, idle_timeout);
+                if idle && bucket.blocked {
+                    released_blocked_ips += 1;
+                }
+                !idle
+            });
+            removed += before - buckets.len();
+        }
+
+        {
+            let mut buckets = api_key_buckets.write().await;
+            let before = buckets.len();
+            buckets.retain(|_, bucket| !bucket.is_idle(now, idle_timeout));
+            removed += before - buckets.len();
+        }
+
+        if released_blocked_ips > 0 {
+            let mut metrics = metrics.write().await;
+            metrics.currently_blocked_ips = metrics.currently_blocked_ips.saturating_sub(released_blocked_ips);
+        }
+
+        if removed > 0 {
+            tracing::debug!("Rate limiter: {} buckets inactifs supprimés", removed);
+        }
+        removed
+    }
+
+    /// Lance la tâche périodique de nettoyage des buckets inactifs
+    pub fn start_cleanup_task(&self) -> tokio::task::JoinHandle<()> {
+        let ip_buckets = self.ip_buckets.clone();
+        let api_key_buckets = self.api_key_buckets.clone();
+        let metrics = self.metrics.clone();
+        let cleanup_interval = self.config.cleanup_interval;
+        let idle_timeout = self.config.bucket_idle_timeout;
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(cleanup_interval);
+            loop {
+                interval.tick().await;
+                Self::purge_idle_buckets(
+                    &ip_buckets,
+                    &api_key_buckets,
+                    &metrics,
+                    idle_timeout,
+                    SystemTime::now(),
+                ).await;
+            }
+        })
+    }
+
+    /// Nombre de buckets actuellement en mémoire (IPs et API keys)
+    pub async fn bucket_count(&self) -> usize {
+        self.ip_buckets.read().await.len() + self.api_key_buckets.read().await.len()
+    }
+
+    /// Obtient les métriques du rate limiter
+    pub async fn get_metrics(&self) -> RateLimiterMetrics {
+        self.metrics.read().await.clone()
+    }
+
+    async fn acquire_ip(&self, ip: &str) -> RateLimitStatus {
+        let now = SystemTime::now();
         let mut buckets = self.ip_buckets.write().await;
         let bucket = buckets.entry(ip.to_string()).or_insert_with(|| {
-            TokenBucket {
-                tokens: self.config.requests_per_second_per_ip as f64,
-                capacity: self.config.requests_per_second_per_ip as f64,
-                refill_rate: self.config.requests_per_second_per_ip as f64,
-                last_refill: SystemTime::now(),
-            }
+            TokenBucket::new(
+                self.config.requests_per_second_per_ip,
+                vec![RateWindow::new(self.config.requests_per_minute_per_ip, Duration::from_secs(60), now)],
+                now,
+            )
         });
 
-        // Refill tokens
+        let was_blocked = bucket.blocked;
+        bucket.try_acquire(now);
+
+        // Suit les IPs passant de l'état autorisé à bloqué et inversement
+        if was_blocked != bucket.blocked {
+            let mut metrics = self.metrics.write().await;
+            if bucket.blocked {
+                metrics.currently_blocked_ips += 1;
+            } else {
+                metrics.currently_blocked_ips = metrics.currently_blocked_ips.saturating_sub(1);
+            }
+        }
+
+        bucket.status(now)
+    }
+
+    async fn acquire_api_key(&self, api_key: &str, limit: &RateLimit) -> RateLimitStatus {
         let now = SystemTime::now();
-        let elapsed = now.duration_since(bucket.last_refill).unwrap_or(Duration::ZERO);
-        let tokens_to_add = elapsed.as_secs_f64() * bucket.refill_rate;
-        bucket.tokens = (bucket.tokens + tokens_to_add).min(bucket.capacity);
-        bucket.last_refill = now;
-
-        // Vérifie si des tokens sont disponibles
-        if bucket.tokens >= 1.0 {
-            bucket.tokens -= 1.0;
-            true
-        } else {
-            false
+        let mut buckets = self.api_key_buckets.write().await;
+        let bucket = buckets.entry(api_key.to_string()).or_insert_with(|| {
+            TokenBucket::new(
+                limit.requests_per_second,
+                vec![
+                    RateWindow::new(limit.requests_per_minute, Duration::from_secs(60), now),
+                    RateWindow::new(limit.requests_per_hour, Duration::from_secs(3600), now),
+                ],
+                now,
+            )
+        });
+
+        bucket.try_acquire(now);
+        bucket.status(now)
+    }
+}
+
+impl WafRequest {
+    /// Découpe une requête HTTP/1.x brute ; des données non HTTP sont traitées comme corps
+    pub fn parse(raw: &[u8]) -> Self {
+        let text = String::from_utf8_lossy(raw);
+        let (head, body) = match text.split_once("\r\n\r\n").or_else(|| text.split_once("\n\n")) {
+            Some((head, body)) => (head, body),
+            None => (text.as_ref(), ""),
+        };
+
+        let mut lines = head.lines();
+        let request_line = lines.next().unwrap_or_default();
+        let mut parts = request_line.split_whitespace();
+        match (parts.next(), parts.next()) {
+            (Some(method), Some(target)) if method.chars().all(|c| c.is_ascii_uppercase()) => Self {
+                path: percent_decode(target),
+                headers: lines.map(|line| percent_decode(line.trim())).filter(|line| !line.is_empty()).collect(),
+                body: percent_decode(body),
+            },
+            _ => Self {
+                body: percent_decode(&text),
+                ..Self::default()
+            },
         }
     }
 
-    async fn check_api_key_rate_limit(&self, api_key: &str) -> bool {
-        if let Some(limit) = self.config.api_key_limits.get(api_key) {
-            let mut buckets = self.api_key_buckets.write().await;
-            let bucket = buckets.entry(api_key.to_string()).or_insert_with(|| {
-                TokenBucket {
-                    tokens: limit.requests_per_second as f64,
-                    capacity: limit.requests_per_second as f64,
-                    refill_rate: limit.requests_per_second as f64,
-                    last_refill: SystemTime::now(),
+    fn fields(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.path.as_str())
+            .chain(self.headers.iter().map(String::as_str))
+            .chain(std::iter::once(self.body.as_str()))
+    }
+}
+
+/// Décode l'encodage `%XX` et `+` pour que les payloads encodés soient reconnus
+fn percent_decode(input: &str) -> String {
+    let bytes = input.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'%' if i + 2 < bytes.len() => {
+                let hex = std::str::from_utf8(&bytes[i + 1..i + 3]).ok();
+                match hex.and_then(|hex| u8::from_str_radix(hex, 16).ok()) {
+                    Some(byte) => {
+                        decoded.push(byte);
+                        i += 3;
+                        continue;
+                    }
+                    None => decoded.push(b'%'),
                 }
-            });
+            }
+            b'+' => decoded.push(b' '),
+            byte => decoded.push(byte),
+        }
+        i += 1;
+    }
+    String::from_utf8_lossy(&decoded).into_owned()
+}
 
-            // Logique similaire à check_ip_rate_limit
-            let now = SystemTime::now();
-            let elapsed = now.duration_since(bucket.last_refill).unwrap_or(Duration::ZERO);
-            let tokens_to_add = elapsed.as_secs_f64() * bucket.refill_rate;
-            bucket.tokens = (bucket.tokens + tokens_to_add).min(bucket.capacity);
-            bucket.last_refill = now;
+impl WebApplicationFirewall {
+    /// Compile les règles ; un pattern invalide est une erreur de configuration
+    pub fn new(rules: Vec<WafRule>, suspicious_patterns: Vec<String>) -> Result<Self> {
+        let compile = |pattern: &str| {
+            regex::RegexBuilder::new(pattern)
+                .case_insensitive(true)
+                .build()
+        };
 
-            if bucket.tokens >= 1.0 {
-                bucket.tokens -= 1.0;
-                true
-            } else {
-                false
+        let mut compiled_rules = Vec::new();
+        for (index, rule) in rules.iter().enumerate().filter(|(_, rule)| rule.enabled) {
+            let regex = compile(&rule.pattern).map_err(|e| crate::error::CoreError::Validation {
+                message: format!("Règle WAF '{}' invalide: {}", rule.name, e),
+            })?;
+            compiled_rules.push((index, regex));
+        }
+
+        let compiled_patterns = suspicious_patterns.iter()
+            .map(|pattern| compile(&regex::escape(pattern)))
+            .collect::<std::result::Result<Vec<_>, _>>()
+            .map_err(|e| crate::error::CoreError::Validation {
+                message: format!("Pattern suspect invalide: {}", e),
+            })?;
+
+        Ok(Self {
+            rules,
+            suspicious_patterns,
+            compiled_rules,
+            compiled_patterns,
+        })
+    }
+
+    /// Confronte la requête aux règles activées ; l'action la plus stricte l'emporte
+    pub fn inspect(&self, request: &WafRequest) -> WafVerdict {
+        let mut logged = Vec::new();
+        let mut challenged = None;
+
+        for (index, regex) in &self.compiled_rules {
+            if !request.fields().any(|field| regex.is_match(field)) {
+                continue;
             }
-        } else {
-            true // Pas de limite pour cette API key
+            let rule = &self.rules[*index];
+            match rule.action {
+                WafAction::Block => return WafVerdict::Blocked { rule: rule.name.clone() },
+                WafAction::Challenge => {
+                    challenged.get_or_insert_with(|| rule.name.clone());
+                }
+                WafAction::Log => logged.push(rule.name.clone()),
+            }
+        }
+
+        match challenged {
+            Some(rule) => WafVerdict::Challenged { rule },
+            None if !logged.is_empty() => WafVerdict::Logged { rules: logged },
+            None => WafVerdict::Allow,
+        }
+    }
+
+    /// Indique si la requête contient un pattern suspect
+    pub fn is_suspicious(&self, request: &WafRequest) -> bool {
+        self.compiled_patterns.iter()
+            .any(|regex| request.fields().any(|field| regex.is_match(field)))
+    }
+}
+
+impl DdosDecision {
+    /// Réponse HTTP 429 à renvoyer à une IP bloquée
+    pub fn rejection_response_at(&self, now: SystemTime) -> Option<Vec<u8>> {
+        match self {
+            DdosDecision::Allowed => None,
+            DdosDecision::Blacklisted { until } | DdosDecision::Blocked { until } => {
+                let retry_after = until.duration_since(now).unwrap_or(Duration::ZERO).as_secs().max(1);
+                let body = "Too many requests";
+                Some(format!(
+                    "HTTP/1.1 429 Too Many Requests\r\nRetry-After: {}\r\nContent-Type: text/plain\r\nContent-Length: {}\r\n\r\n{}",
+                    retry_after, body.len(), body
+                ).into_bytes())
+            }
+        }
+    }
+}
+
+impl WafVerdict {
+    /// Réponse HTTP à renvoyer au client si la requête n'est pas transmise
+    pub fn rejection_response(&self) -> Option<Vec<u8>> {
+        match self {
+            WafVerdict::Blocked { .. } => {
+                let body = "Request blocked by web application firewall";
+                Some(format!(
+                    "HTTP/1.1 403 Forbidden\r\nContent-Type: text/plain\r\nContent-Length: {}\r\n\r\n{}",
+                    body.len(), body
+                ).into_bytes())
+            }
+            WafVerdict::Challenged { .. } => {
+                let nonce = crate::crypto::compute_blake3(&rand::random::<[u8; 32]>()).to_hex();
+                let body = "Challenge required";
+                Some(format!(
+                    "HTTP/1.1 401 Unauthorized\r\nWWW-Authenticate: ArchiveChain-Challenge nonce=\"{}\"\r\nContent-Type: text/plain\r\nContent-Length: {}\r\n\r\n{}",
+                    nonce, body.len(), body
+                ).into_bytes())
+            }
+            WafVerdict::Allow | WafVerdict::Logged { .. } => None,
+        }
+    }
+}
+
+impl DDoSDetector {
+    /// Enregistre une requête de `ip` sur la fenêtre glissante
+    ///
+    /// Au-delà de `detection_threshold` requêtes dans `detection_window`, l'IP est
+    /// blacklistée pour une fenêtre ; elle est réévaluée normalement ensuite.
+    pub fn record_request_at(&mut self, ip: &str, now: SystemTime) -> DdosDecision {
+        if let Some(until) = self.blacklist.get(ip).copied() {
+            if now < until {
+                return DdosDecision::Blocked { until };
+            }
+            self.blacklist.remove(ip);
+        }
+
+        if self.requests_per_ip.len() > DDOS_TRACKED_IPS_PURGE_THRESHOLD {
+            self.purge_idle_at(now);
+        }
+
+        let window = self.detection_window;
+        let requests = self.requests_per_ip.entry(ip.to_string()).or_default();
+        while requests.front().map_or(false, |at| Self::is_outside(*at, now, window)) {
+            requests.pop_front();
+        }
+        requests.push_back(now);
+
+        if requests.len() as u32 > self.detection_threshold {
+            requests.clear();
+            let until = now + window;
+            self.blacklist.insert(ip.to_string(), until);
+            return DdosDecision::Blacklisted { until };
         }
+
+        DdosDecision::Allowed
+    }
+
+    /// Supprime les fenêtres sans requête récente et les blocages expirés
+    pub fn purge_idle_at(&mut self, now: SystemTime) {
+        let window = self.detection_window;
+        self.requests_per_ip.retain(|_, requests| {
+            requests.back().map_or(false, |at| !Self::is_outside(*at, now, window))
+        });
+        self.blacklist.retain(|_, until| now < *until);
+    }
+
+    fn is_outside(at: SystemTime, now: SystemTime, window: Duration) -> bool {
+        now.duration_since(at).unwrap_or(Duration::ZERO) >= window
+    }
+}
+
+impl SecurityStack {
+    /// Applique la détection DDoS à une requête de `client_ip`
+    pub async fn check_ddos(&self, client_ip: &str) -> DdosDecision {
+        self.check_ddos_at(client_ip, SystemTime::now()).await
+    }
+
+    /// Variante de [`Self::check_ddos`] à une date donnée
+    pub async fn check_ddos_at(&self, client_ip: &str, now: SystemTime) -> DdosDecision {
+        if !self.config.ddos_protection_enabled {
+            return DdosDecision::Allowed;
+        }
+
+        let mut detector = self.ddos_detector.write().await;
+        let decision = detector.record_request_at(client_ip, now);
+        let blacklisted_ips = detector.blacklist.len() as u32;
+        drop(detector);
+
+        let mut metrics = self.metrics.write().await;
+        match decision {
+            DdosDecision::Allowed => {}
+            DdosDecision::Blacklisted { .. } => {
+                tracing::warn!("IP {} blacklistée : seuil DDoS dépassé", client_ip);
+                metrics.attacks_detected += 1;
+                metrics.attacks_blocked += 1;
+            }
+            DdosDecision::Blocked { .. } => metrics.attacks_blocked += 1,
+        }
+        metrics.blacklisted_ips = blacklisted_ips;
+
+        decision
+    }
+
+    /// Inspecte une requête HTTP brute et met à jour les métriques de sécurité
+    pub async fn inspect_request(&self, raw: &[u8]) -> WafVerdict {
+        if !self.config.waf_enabled {
+            return WafVerdict::Allow;
+        }
+
+        let request = WafRequest::parse(raw);
+        let waf = self.waf.read().await;
+        let verdict = waf.inspect(&request);
+        let suspicious = waf.is_suspicious(&request);
+        drop(waf);
+
+        let mut metrics = self.metrics.write().await;
+        match &verdict {
+            WafVerdict::Allow => {}
+            WafVerdict::Logged { rules } => {
+                tracing::warn!("Règles WAF déclenchées sur {}: {:?}", request.path, rules);
+                metrics.attacks_detected += 1;
+                metrics.suspicious_requests += 1;
+            }
+            WafVerdict::Blocked { rule } => {
+                tracing::warn!("Requête {} bloquée par la règle WAF {}", request.path, rule);
+                metrics.attacks_detected += 1;
+                metrics.attacks_blocked += 1;
+            }
+            WafVerdict::Challenged { rule } => {
+                tracing::info!("Challenge imposé sur {} par la règle WAF {}", request.path, rule);
+                metrics.attacks_detected += 1;
+            }
+        }
+        if suspicious && !matches!(verdict, WafVerdict::Logged { .. }) {
+            metrics.suspicious_requests += 1;
+        }
+
+        verdict
+    }
+
+    /// Obtient les métriques de sécurité
+    pub async fn get_metrics(&self) -> SecurityMetrics {
+        self.metrics.read().await.clone()
     }
 }
 
@@ -929,7 +1931,7 @@ impl GatewayNode {
     /// Crée une nouvelle instance de Gateway Node
     pub fn new(
         config: GatewayNodeConfig,
-        keypair: (PublicKey, PrivateKey),
+        signer: Arc<dyn Signer>,
     ) -> Result<Self> {
         // Valide la configuration
         config.validate()?;
@@ -951,15 +1953,16 @@ impl GatewayNode {
                 detection_window: Duration::from_secs(60),
                 detection_threshold: config.security_config.ddos_detection_threshold,
                 requests_per_ip: HashMap::new(),
+                blacklist: HashMap::new(),
             })),
-            waf: Arc::new(RwLock::new(WebApplicationFirewall {
-                rules: Vec::new(),
-                suspicious_patterns: vec![
+            waf: Arc::new(RwLock::new(WebApplicationFirewall::new(
+                config.security_config.waf_rules.clone(),
+                vec![
                     "<script".to_string(),
                     "union select".to_string(),
                     "../".to_string(),
                 ],
-            })),
+            )?)),
             metrics: Arc::new(RwLock::new(SecurityMetrics {
                 attacks_detected: 0,
                 attacks_blocked: 0,
@@ -990,6 +1993,7 @@ impl GatewayNode {
                 failed_requests: 0,
                 average_response_time: Duration::ZERO,
                 requests_per_backend: HashMap::new(),
+                circuit_states: HashMap::new(),
             },
             cache_metrics: CacheMetrics {
                 cache_hits: 0,
@@ -1012,23 +2016,55 @@ impl GatewayNode {
             },
             active_websocket_connections: 0,
             authenticated_clients: 0,
+            bytes_served_per_content: HashMap::new(),
         };
 
         Ok(Self {
             config,
             node_id,
-            keypair,
+            signer,
             status: Arc::new(RwLock::new(GatewayNodeStatus::Initializing)),
             api_endpoints: Arc::new(RwLock::new(Vec::new())),
             load_balancer: Arc::new(Mutex::new(load_balancer)),
             cache_layer: Arc::new(Mutex::new(cache_layer)),
             rate_limiter: Arc::new(Mutex::new(rate_limiter)),
+            rate_limiter_cleanup: None,
             security_stack: Arc::new(Mutex::new(security_stack)),
             metrics: Arc::new(RwLock::new(initial_metrics)),
+            bandwidth_reporter: Arc::new(RwLock::new(None)),
             start_time,
+            in_flight: InFlight::default(),
         })
     }
 
+    /// Branche la remontée des livraisons de contenu vers le consensus
+    pub async fn set_bandwidth_reporter(&self, reporter: BandwidthReporter) {
+        *self.bandwidth_reporter.write().await = Some(reporter);
+    }
+
+    /// Sert un contenu en cache au client `client`
+    ///
+    /// Le volume livré est compté par contenu et signalé à la preuve de bande
+    /// passante, où il devient rémunérable une fois corroboré par un défi.
+    pub async fn serve_content(&self, content_hash: &Hash, client: NodeId) -> Option<Vec<u8>> {
+        let started = SystemTime::now();
+        let data = self.cache_layer.lock().await.get_content(content_hash).await?;
+        let bytes = data.len() as u64;
+
+        {
+            let mut metrics = self.metrics.write().await;
+            metrics.general.bandwidth_out += bytes;
+            *metrics.bytes_served_per_content.entry(content_hash.clone()).or_insert(0) += bytes;
+        }
+
+        if let Some(reporter) = self.bandwidth_reporter.read().await.as_ref() {
+            let duration = started.elapsed().unwrap_or(Duration::ZERO);
+            reporter.report_delivery(client, content_hash.clone(), bytes, duration);
+        }
+
+        Some(data)
+    }
+
     /// Configure les endpoints API
     pub async fn configure_api_endpoints(&self) -> Result<()> {
         {
@@ -1102,13 +2138,39 @@ impl GatewayNode {
         Ok(())
     }
 
-    /// Traite une requête HTTP
+    /// Traite une requête HTTP reçue sur l'API `api_type`
     pub async fn handle_http_request(
         &self,
+        api_type: ApiType,
         client_ip: &str,
         api_key: Option<&str>,
         request_data: &[u8],
     ) -> Result<Vec<u8>> {
+        // Compté avant la vérification du statut pour que `drain` l'attende
+        let _request = self.in_flight.enter();
+        if *self.status.read().await == GatewayNodeStatus::Draining {
+            return Err(crate::error::CoreError::ServiceUnavailable {
+                message: "Gateway is draining".to_string(),
+            });
+        }
+
+        {
+            let mut metrics = self.metrics.write().await;
+            *metrics.requests_per_api.entry(api_type).or_insert(0) += 1;
+        }
+
+        // Délestage des rafales avant tout autre traitement
+        let now = SystemTime::now();
+        let (ddos, security_metrics) = {
+            let security_stack = self.security_stack.lock().await;
+            let decision = security_stack.check_ddos_at(client_ip, now).await;
+            (decision, security_stack.get_metrics().await)
+        };
+        if let Some(response) = ddos.rejection_response_at(now) {
+            self.metrics.write().await.security_metrics = security_metrics;
+            return Ok(response);
+        }
+
         // Vérifie le rate limiting
         let rate_limiter = self.rate_limiter.lock().await;
         if !rate_limiter.check_rate_limit(client_ip, api_key).await {
@@ -1118,10 +2180,20 @@ impl GatewayNode {
         }
         drop(rate_limiter);
 
+        // Inspection WAF avant tout transfert vers un backend
+        let (verdict, security_metrics) = {
+            let security_stack = self.security_stack.lock().await;
+            let verdict = security_stack.inspect_request(request_data).await;
+            (verdict, security_stack.get_metrics().await)
+        };
+        self.metrics.write().await.security_metrics = security_metrics;
+        if let Some(response) = verdict.rejection_response() {
+            return Ok(response);
+        }
+
         // Sélectionne un backend
         let load_balancer = self.load_balancer.lock().await;
         let backend = load_balancer.select_backend(Some(client_ip)).await;
-        drop(load_balancer);
 
         let backend_id = backend.ok_or_else(|| crate::error::CoreError::ServiceUnavailable {
             message: "No healthy backend available".to_string(),
@@ -1130,6 +2202,8 @@ impl GatewayNode {
         // Simule le traitement de la requête
         // Dans la réalité, on forwarderait vers le backend sélectionné
         let response = b"Gateway response".to_vec();
+        load_balancer.record_success(&backend_id).await;
+        drop(load_balancer);
 
         // Met à jour les métriques
         {
@@ -1183,6 +2257,10 @@ impl Node for GatewayNode {
 
         // Démarre les services (simulation)
         // Dans la réalité, on démarrerait les serveurs HTTP, WebSocket, etc.
+        if self.config.rate_limiter_config.enabled && self.rate_limiter_cleanup.is_none() {
+            let rate_limiter = self.rate_limiter.lock().await;
+            self.rate_limiter_cleanup = Some(rate_limiter.start_cleanup_task());
+        }
 
         {
             let mut status = self.status.write().await;
@@ -1196,6 +2274,10 @@ impl Node for GatewayNode {
     async fn stop(&mut self) -> Result<()> {
         tracing::info!("Arrêt du Gateway Node: {:?}", self.node_id);
 
+        if let Some(cleanup) = self.rate_limiter_cleanup.take() {
+            cleanup.abort();
+        }
+
         {
             let mut status = self.status.write().await;
             *status = GatewayNodeStatus::Stopping;
@@ -1205,8 +2287,7 @@ impl Node for GatewayNode {
         // Vide les caches
         {
             let cache = self.cache_layer.lock().await;
-            let mut content_cache = cache.content_cache.write().await;
-            content_cache.clear();
+            cache.clear_content().await;
             let mut metadata_cache = cache.metadata_cache.write().await;
             metadata_cache.clear();
         }
@@ -1242,8 +2323,12 @@ impl Node for GatewayNode {
     }
 
     async fn get_metrics(&self) -> Result<Box<dyn NodeMetrics>> {
-        let metrics = self.metrics.read().await;
-        Ok(Box::new(metrics.clone()))
+        let load_balancer_metrics = self.load_balancer.lock().await.get_metrics().await;
+        let rate_limiter_metrics = self.rate_limiter.lock().await.get_metrics().await;
+        let mut metrics = self.metrics.read().await.clone();
+        metrics.load_balancer_metrics = load_balancer_metrics;
+        metrics.rate_limiter_metrics = rate_limiter_metrics;
+        Ok(Box::new(metrics))
     }
 
     async fn handle_message(&mut self, message: NetworkMessage) -> Result<Option<NetworkMessage>> {
@@ -1265,6 +2350,7 @@ impl Node for GatewayNode {
                     payload: Vec::new(),
                     timestamp: chrono::Utc::now(),
                     ttl: 60,
+                    request_id: message.request_id.clone(),
                 }))
             },
             _ => {
@@ -1284,6 +2370,40 @@ impl Node for GatewayNode {
         self.config.node_config = config;
         Ok(())
     }
+
+    /// Aucun contenu à re-répliquer : seules les requêtes en cours sont attendues
+    async fn drain(&self, timeout: Duration) -> Result<DrainReport> {
+        tracing::info!("Drainage du Gateway Node: {:?}", self.node_id);
+        *self.status.write().await = GatewayNodeStatus::Draining;
+
+        let in_flight_remaining = self.in_flight.wait_idle(timeout).await;
+        if in_flight_remaining > 0 {
+            tracing::warn!("Drainage expiré avec {} requêtes en cours", in_flight_remaining);
+        }
+        Ok(DrainReport { in_flight_remaining, ..DrainReport::default() })
+    }
+
+    async fn in_flight_transfers(&self) -> usize {
+        self.in_flight.count()
+    }
+}
+
+#[async_trait]
+impl PrometheusExporter for GatewayNode {
+    /// N'exporte rien si `prometheus_metrics` est désactivé dans la configuration de monitoring
+    async fn render_prometheus(&self, labels: &[(&str, &str)]) -> String {
+        if !self.config.monitoring_config.prometheus_metrics {
+            return String::new();
+        }
+
+        let cache_metrics = self.cache_layer.lock().await.get_metrics().await;
+        let rate_limiter_metrics = self.rate_limiter.lock().await.get_metrics().await;
+        let mut metrics = self.metrics.read().await.clone();
+        metrics.cache_metrics = cache_metrics;
+        metrics.rate_limiter_metrics = rate_limiter_metrics;
+
+        metrics.to_prometheus(labels)
+    }
 }
 
 /// Statistiques du Gateway
@@ -1362,10 +2482,193 @@ mod tests {
         let config = GatewayNodeConfig::default();
         let keypair = generate_keypair().unwrap();
 
-        let node = GatewayNode::new(config, keypair);
+        let node = GatewayNode::new(config, Arc::new(keypair));
         assert!(node.is_ok());
     }
 
+    #[test]
+    fn test_waf_rules_and_actions() {
+        let mut rules = default_waf_rules();
+        rules.push(WafRule {
+            name: "admin-probe".to_string(),
+            pattern: r"^/admin".to_string(),
+            action: WafAction::Challenge,
+            enabled: true,
+        });
+        rules.push(WafRule {
+            name: "legacy-client".to_string(),
+            pattern: r"user-agent:\s*curl/".to_string(),
+            action: WafAction::Log,
+            enabled: true,
+        });
+        rules.push(WafRule {
+            name: "disabled".to_string(),
+            pattern: r"/archives".to_string(),
+            action: WafAction::Block,
+            enabled: false,
+        });
+        let waf = WebApplicationFirewall::new(rules, Vec::new()).unwrap();
+
+        let inspect = |raw: &str| waf.inspect(&WafRequest::parse(raw.as_bytes()));
+
+        assert_eq!(inspect("GET /archives?page=2 HTTP/1.1\r\nHost: gw\r\n\r\n"), WafVerdict::Allow);
+        assert_eq!(
+            inspect("GET /search?q=1%27%20OR%20%271%27%3D%271 HTTP/1.1\r\n\r\n"),
+            WafVerdict::Blocked { rule: "sql-injection".to_string() }
+        );
+        assert_eq!(
+            inspect("POST /search HTTP/1.1\r\nContent-Type: text/plain\r\n\r\nq=x UNION ALL SELECT password FROM users"),
+            WafVerdict::Blocked { rule: "sql-injection".to_string() }
+        );
+        assert_eq!(
+            inspect("GET /admin/nodes HTTP/1.1\r\n\r\n"),
+            WafVerdict::Challenged { rule: "admin-probe".to_string() }
+        );
+        assert_eq!(
+            inspect("GET /archives HTTP/1.1\r\nUser-Agent: curl/8.0\r\n\r\n"),
+            WafVerdict::Logged { rules: vec!["legacy-client".to_string()] }
+        );
+
+        let invalid = WafRule {
+            name: "broken".to_string(),
+            pattern: "(".to_string(),
+            action: WafAction::Block,
+            enabled: true,
+        };
+        assert!(WebApplicationFirewall::new(vec![invalid], Vec::new()).is_err());
+    }
+
+    #[test]
+    fn test_ddos_sliding_window_blacklists_bursts() {
+        let mut detector = DDoSDetector {
+            detection_window: Duration::from_secs(60),
+            detection_threshold: 3,
+            requests_per_ip: HashMap::new(),
+            blacklist: HashMap::new(),
+        };
+        let start = SystemTime::now();
+
+        // Trois requêtes espacées : les plus anciennes sortent de la fenêtre
+        for offset in [0, 40, 80, 120] {
+            assert_eq!(detector.record_request_at("10.0.0.3", start + Duration::from_secs(offset)), DdosDecision::Allowed);
+        }
+        assert_eq!(detector.requests_per_ip["10.0.0.3"].len(), 2);
+
+        // Rafale : la quatrième requête dans la fenêtre déclenche le blocage
+        let burst = start + Duration::from_secs(1000);
+        for _ in 0..3 {
+            assert_eq!(detector.record_request_at("10.0.0.4", burst), DdosDecision::Allowed);
+        }
+        let until = burst + Duration::from_secs(60);
+        assert_eq!(detector.record_request_at("10.0.0.4", burst), DdosDecision::Blacklisted { until });
+        assert_eq!(detector.record_request_at("10.0.0.4", burst + Duration::from_secs(59)), DdosDecision::Blocked { until });
+        assert_eq!(detector.record_request_at("10.0.0.5", burst), DdosDecision::Allowed);
+
+        // Fin du blocage : l'IP est réévaluée
+        assert_eq!(detector.record_request_at("10.0.0.4", until), DdosDecision::Allowed);
+        assert!(detector.blacklist.is_empty());
+
+        detector.purge_idle_at(until + Duration::from_secs(3600));
+        assert!(detector.requests_per_ip.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_gateway_sheds_ddos_bursts() {
+        let keypair = generate_keypair().unwrap();
+        let mut config = GatewayNodeConfig::default();
+        config.security_config.ddos_detection_threshold = 2;
+        let gateway = GatewayNode::new(
+            config,
+            Arc::new(keypair),
+        ).unwrap();
+
+        for _ in 0..2 {
+            let _ = gateway.handle_http_request(ApiType::Rest, "10.0.0.6", None, b"GET / HTTP/1.1\r\n\r\n").await;
+        }
+        let response = gateway
+            .handle_http_request(ApiType::Rest, "10.0.0.6", None, b"GET / HTTP/1.1\r\n\r\n")
+            .await
+            .unwrap();
+        assert!(response.starts_with(b"HTTP/1.1 429 Too Many Requests"));
+
+        let metrics = gateway.metrics.read().await;
+        assert_eq!(metrics.security_metrics.attacks_detected, 1);
+        assert_eq!(metrics.security_metrics.attacks_blocked, 1);
+        assert_eq!(metrics.security_metrics.blacklisted_ips, 1);
+    }
+
+    #[tokio::test]
+    async fn test_gateway_reports_content_deliveries() {
+        use crate::consensus::{BandwidthProofManager, ConsensusConfig};
+
+        let keypair = generate_keypair().unwrap();
+        let gateway = GatewayNode::new(
+            GatewayNodeConfig::default(),
+            Arc::new(keypair),
+        ).unwrap();
+        let mut manager = BandwidthProofManager::new(&ConsensusConfig::test_config());
+        gateway.set_bandwidth_reporter(manager.reporter(gateway.node_id.clone())).await;
+
+        let content = Hash::from_bytes_array([7; 32]);
+        let client = manager.register_peer(generate_keypair().unwrap().public_key().clone());
+        gateway.cache_layer.lock().await.cache_content(content.clone(), vec![0; 300], None).await;
+
+        assert!(gateway.serve_content(&content, client.clone()).await.is_some());
+        assert!(gateway.serve_content(&content, client.clone()).await.is_some());
+        assert!(gateway.serve_content(&Hash::zero(), client).await.is_none());
+
+        let metrics = gateway.metrics.read().await;
+        assert_eq!(metrics.bytes_served_per_content.get(&content), Some(&600));
+        assert_eq!(metrics.general.bandwidth_out, 600);
+
+        assert_eq!(manager.ingest_reported_transfers(), 2);
+        let ledger = manager.delivery_ledger(&gateway.node_id).unwrap();
+        assert_eq!(ledger.bytes_per_content.get(&content), Some(&600));
+        assert_eq!(ledger.pending_bytes(), 600);
+        assert_eq!(ledger.verified_bytes(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_gateway_rejects_sql_injection_with_403() {
+        let keypair = generate_keypair().unwrap();
+        let gateway = GatewayNode::new(
+            GatewayNodeConfig::default(),
+            Arc::new(keypair),
+        ).unwrap();
+
+        let response = gateway
+            .handle_http_request(ApiType::Rest, "10.0.0.2", None, b"GET /archives?id=1'%20OR%201=1-- HTTP/1.1\r\n\r\n")
+            .await
+            .unwrap();
+        assert!(response.starts_with(b"HTTP/1.1 403 Forbidden"));
+
+        // Requête saine : passe le WAF et échoue faute de backend
+        assert!(gateway.handle_http_request(ApiType::Rest, "10.0.0.2", None, b"GET /archives HTTP/1.1\r\n\r\n").await.is_err());
+
+        let stats = gateway.get_gateway_stats().await;
+        assert_eq!(stats.security_incidents, 1);
+        let metrics = gateway.metrics.read().await;
+        assert_eq!(metrics.security_metrics.attacks_blocked, 1);
+    }
+
+    #[tokio::test]
+    async fn test_draining_gateway_refuses_new_requests() {
+        let keypair = generate_keypair().unwrap();
+        let gateway = GatewayNode::new(
+            GatewayNodeConfig::default(),
+            Arc::new(keypair),
+        ).unwrap();
+
+        let report = gateway.drain(Duration::from_secs(1)).await.unwrap();
+        assert!(report.is_complete());
+        assert_eq!(*gateway.status.read().await, GatewayNodeStatus::Draining);
+
+        let result = gateway.handle_http_request(ApiType::Rest, "10.0.0.3", None, b"GET /archives HTTP/1.1\r\n\r\n").await;
+        assert!(matches!(result, Err(crate::error::CoreError::ServiceUnavailable { .. })));
+        assert_eq!(gateway.in_flight_transfers().await, 0);
+        assert!(gateway.metrics.read().await.requests_per_api.is_empty());
+    }
+
     #[tokio::test]
     async fn test_load_balancer() {
         let config = LoadBalancerConfig::default();
@@ -1390,6 +2693,76 @@ mod tests {
         assert!(selected.is_some());
     }
 
+    fn create_test_backend(id: u8) -> BackendNodeInfo {
+        BackendNodeInfo {
+            node_id: NodeId::from(Hash::from_bytes_array([id; 32])),
+            address: format!("127.0.0.1:80{:02}", id).parse().unwrap(),
+            node_type: NodeType::FullArchive {
+                storage_capacity: 1000,
+                replication_factor: 5,
+            },
+            weight: 1,
+            health_status: BackendHealthStatus::Healthy,
+            last_health_check: SystemTime::now(),
+            average_latency: Duration::from_millis(50),
+            active_connections: 10,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker_opens_after_max_retries() {
+        let config = LoadBalancerConfig::default();
+        let backends = vec![create_test_backend(1), create_test_backend(2)];
+        let failing = backends[0].node_id.clone();
+        let load_balancer = LoadBalancer::new(config.clone(), backends);
+
+        for _ in 0..config.max_retries - 1 {
+            load_balancer.record_failure(&failing).await;
+        }
+        assert_eq!(load_balancer.circuit_state(&failing).await, Some(CircuitState::Closed));
+
+        load_balancer.record_failure(&failing).await;
+        assert_eq!(load_balancer.circuit_state(&failing).await, Some(CircuitState::Open));
+
+        // Le backend ouvert n'est plus jamais sélectionné
+        for _ in 0..10 {
+            let selected = load_balancer.select_backend(None).await.unwrap();
+            assert_ne!(selected, failing);
+        }
+
+        let metrics = load_balancer.get_metrics().await;
+        assert_eq!(metrics.circuit_states.get(&failing), Some(&CircuitState::Open));
+        assert_eq!(metrics.failed_requests, config.max_retries as u64);
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker_half_open_probe() {
+        let mut config = LoadBalancerConfig::default();
+        config.circuit_breaker_timeout = Duration::ZERO;
+        config.max_retries = 1;
+        let backend = create_test_backend(1);
+        let node_id = backend.node_id.clone();
+        let load_balancer = LoadBalancer::new(config, vec![backend]);
+
+        load_balancer.record_failure(&node_id).await;
+        assert_eq!(load_balancer.circuit_state(&node_id).await, Some(CircuitState::HalfOpen));
+
+        // Une seule sonde autorisée à la fois
+        assert_eq!(load_balancer.select_backend(None).await, Some(node_id.clone()));
+        assert_eq!(load_balancer.select_backend(None).await, None);
+
+        // Sonde ratée : le circuit se rouvre
+        load_balancer.record_failure(&node_id).await;
+        assert_eq!(load_balancer.circuit_state(&node_id).await, Some(CircuitState::HalfOpen));
+        assert_eq!(load_balancer.select_backend(None).await, Some(node_id.clone()));
+
+        // Sonde réussie : le circuit se referme
+        load_balancer.record_success(&node_id).await;
+        assert_eq!(load_balancer.circuit_state(&node_id).await, Some(CircuitState::Closed));
+        let metrics = load_balancer.get_metrics().await;
+        assert_eq!(metrics.circuit_states.get(&node_id), Some(&CircuitState::Closed));
+    }
+
     #[tokio::test]
     async fn test_rate_limiter() {
         let config = RateLimiterConfig::default();
@@ -1399,6 +2772,90 @@ mod tests {
         assert!(rate_limiter.check_rate_limit("192.168.1.1", None).await);
     }
 
+    #[tokio::test]
+    async fn test_rate_limiter_drops_idle_buckets() {
+        let config = RateLimiterConfig::default();
+        let idle_timeout = config.bucket_idle_timeout;
+        let rate_limiter = RateLimiter::new(config);
+
+        for i in 0..10_000u32 {
+            let ip = format!("10.{}.{}.{}", i >> 16, (i >> 8) & 0xff, i & 0xff);
+            assert!(rate_limiter.check_rate_limit(&ip, None).await);
+        }
+        assert_eq!(rate_limiter.bucket_count().await, 10_000);
+
+        // Avant la fenêtre d'inactivité rien n'est supprimé
+        assert_eq!(rate_limiter.cleanup_idle_buckets(SystemTime::now()).await, 0);
+
+        // Après la fenêtre d'inactivité les buckets sont libérés
+        let later = SystemTime::now() + idle_timeout + Duration::from_secs(1);
+        assert_eq!(rate_limiter.cleanup_idle_buckets(later).await, 10_000);
+        assert_eq!(rate_limiter.bucket_count().await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limiter_per_minute_cap() {
+        let mut config = RateLimiterConfig::default();
+        config.requests_per_second_per_ip = 100;
+        config.requests_per_minute_per_ip = 5;
+        let rate_limiter = RateLimiter::new(config);
+
+        for _ in 0..5 {
+            assert!(rate_limiter.check_rate_limit("192.168.1.1", None).await);
+        }
+        // Toujours sous la limite par seconde, mais la limite par minute est atteinte
+        assert!(!rate_limiter.check_rate_limit("192.168.1.1", None).await);
+        assert!(rate_limiter.check_rate_limit("192.168.1.2", None).await);
+
+        let metrics = rate_limiter.get_metrics().await;
+        assert_eq!(metrics.currently_blocked_ips, 1);
+        assert_eq!(metrics.blocked_requests, 1);
+        assert!((metrics.block_rate - 1.0 / 7.0).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_api_key_hourly_cap() {
+        let mut config = RateLimiterConfig::default();
+        config.api_key_limits.insert("key".to_string(), RateLimit {
+            requests_per_second: 100,
+            requests_per_minute: 100,
+            requests_per_hour: 3,
+            burst_allowance: 0,
+        });
+        let rate_limiter = RateLimiter::new(config);
+
+        for i in 0..3 {
+            assert!(rate_limiter.check_rate_limit(&format!("10.0.0.{}", i), Some("key")).await);
+        }
+        assert!(!rate_limiter.check_rate_limit("10.0.0.9", Some("key")).await);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limit_status_reports_binding_window() {
+        let mut config = RateLimiterConfig::default();
+        config.requests_per_second_per_ip = 100;
+        config.requests_per_minute_per_ip = 5;
+        config.ip_whitelist.push("10.0.0.1".to_string());
+        let rate_limiter = RateLimiter::new(config);
+
+        // La fenêtre par minute est plus contraignante que le bucket par seconde
+        for remaining in (0..5).rev() {
+            let status = rate_limiter.rate_limit_status("192.168.1.1", None).await.unwrap();
+            assert!(status.allowed);
+            assert_eq!((status.limit, status.remaining), (5, remaining));
+            assert!(status.reset_after <= Duration::from_secs(60));
+            assert_eq!(status.retry_after, None);
+        }
+
+        let status = rate_limiter.rate_limit_status("192.168.1.1", None).await.unwrap();
+        assert!(!status.allowed);
+        assert_eq!(status.remaining, 0);
+        let retry_after = status.retry_after.unwrap();
+        assert!(retry_after > Duration::from_secs(50) && retry_after <= Duration::from_secs(60));
+
+        assert_eq!(rate_limiter.rate_limit_status("10.0.0.1", None).await, None);
+    }
+
     #[tokio::test]
     async fn test_cache_layer() {
         let config = CacheConfig::default();
@@ -1414,4 +2871,84 @@ mod tests {
         let cached_data = cache_layer.get_content(&content_hash).await;
         assert_eq!(cached_data, Some(data));
     }
+
+    fn content_hash(seed: u8) -> Hash {
+        Hash::from_bytes_array([seed; 32])
+    }
+
+    #[tokio::test]
+    async fn test_cache_lru_eviction() {
+        let mut config = CacheConfig::default();
+        config.max_cache_size = 300;
+        config.eviction_policy = CacheEvictionPolicy::LRU;
+        let cache_layer = CacheLayer::new(config);
+
+        for seed in 1..=3 {
+            cache_layer.cache_content(content_hash(seed), vec![seed; 100], None).await;
+            tokio::time::sleep(Duration::from_millis(2)).await;
+        }
+        // Le premier contenu devient le plus récemment utilisé
+        assert!(cache_layer.get_content(&content_hash(1)).await.is_some());
+
+        cache_layer.cache_content(content_hash(4), vec![4; 100], None).await;
+
+        assert!(cache_layer.get_content(&content_hash(2)).await.is_none());
+        assert!(cache_layer.get_content(&content_hash(1)).await.is_some());
+        let metrics = cache_layer.get_metrics().await;
+        assert_eq!(metrics.evictions, 1);
+        assert_eq!(metrics.current_cache_size, 300);
+        assert_eq!(metrics.cache_hits, 2);
+        assert_eq!(metrics.cache_misses, 1);
+        assert!((metrics.hit_ratio - 2.0 / 3.0).abs() < 1e-9);
+    }
+
+    #[tokio::test]
+    async fn test_cache_lfu_and_fifo_eviction() {
+        let mut config = CacheConfig::default();
+        config.max_cache_size = 200;
+        config.eviction_policy = CacheEvictionPolicy::LFU;
+        let lfu = CacheLayer::new(config.clone());
+
+        lfu.cache_content(content_hash(1), vec![1; 100], None).await;
+        lfu.cache_content(content_hash(2), vec![2; 100], None).await;
+        lfu.get_content(&content_hash(1)).await;
+        lfu.get_content(&content_hash(1)).await;
+        lfu.get_content(&content_hash(2)).await;
+        // Le contenu le moins consulté est évincé
+        lfu.cache_content(content_hash(3), vec![3; 100], None).await;
+        assert!(lfu.get_content(&content_hash(2)).await.is_none());
+        assert!(lfu.get_content(&content_hash(1)).await.is_some());
+
+        config.eviction_policy = CacheEvictionPolicy::FIFO;
+        let fifo = CacheLayer::new(config);
+        fifo.cache_content(content_hash(1), vec![1; 100], None).await;
+        tokio::time::sleep(Duration::from_millis(2)).await;
+        fifo.cache_content(content_hash(2), vec![2; 100], None).await;
+        fifo.get_content(&content_hash(1)).await;
+        // Une entrée de 200 bytes impose d'évincer les deux
+        fifo.cache_content(content_hash(3), vec![3; 200], None).await;
+        let metrics = fifo.get_metrics().await;
+        assert_eq!(metrics.evictions, 2);
+        assert_eq!(metrics.current_cache_size, 200);
+
+        // Une entrée plus grande que le cache n'est pas mise en cache
+        fifo.cache_content(content_hash(4), vec![4; 201], None).await;
+        assert_eq!(fifo.get_metrics().await.current_cache_size, 200);
+    }
+
+    #[tokio::test]
+    async fn test_cache_replacement_keeps_size_accurate() {
+        let mut config = CacheConfig::default();
+        config.max_cache_size = 1_000;
+        let cache_layer = CacheLayer::new(config);
+
+        cache_layer.cache_content(content_hash(1), vec![1; 400], None).await;
+        cache_layer.cache_content(content_hash(1), vec![1; 100], None).await;
+        assert_eq!(cache_layer.get_metrics().await.current_cache_size, 100);
+
+        // Une entrée expirée libère sa taille à la lecture
+        cache_layer.cache_content(content_hash(2), vec![2; 50], Some(Duration::ZERO)).await;
+        assert!(cache_layer.get_content(&content_hash(2)).await.is_none());
+        assert_eq!(cache_layer.get_metrics().await.current_cache_size, 100);
+    }
 }
\ No newline at end of file
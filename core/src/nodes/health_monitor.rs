@@ -7,8 +7,10 @@
 //! - Mécanismes de récupération automatique
 //! - Collecte et analyse des métriques de performance
 
+use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, VecDeque};
+use std::future::Future;
 use std::sync::Arc;
 use std::time::{Duration, SystemTime};
 use tokio::sync::{RwLock, Mutex};
@@ -29,8 +31,10 @@ pub struct HealthMonitorConfig {
     pub alert_interval: Duration,
     /// Récupération automatique activée
     pub auto_recovery_enabled: bool,
-    /// Nombre maximum de tentatives de récupération
+    /// Nombre maximum de redémarrages avant abandon et replanification du contenu
     pub max_recovery_attempts: u32,
+    /// Échecs de récupération consécutifs avant escalade d'alerte
+    pub escalation_threshold: u32,
     /// Configuration des métriques
     pub metrics_config: MetricsCollectionConfig,
     /// Configuration des alertes
@@ -177,6 +181,19 @@ pub enum HealthStatus {
     Recovering,
 }
 
+impl HealthStatus {
+    /// Statut de stockage correspondant, qui décide si le nœud compte comme
+    /// réplique saine
+    pub fn storage_status(&self) -> crate::storage::NodeStatus {
+        match self {
+            HealthStatus::Healthy | HealthStatus::Warning => crate::storage::NodeStatus::Active,
+            HealthStatus::Recovering => crate::storage::NodeStatus::Maintenance,
+            HealthStatus::Unresponsive => crate::storage::NodeStatus::Offline,
+            HealthStatus::Critical => crate::storage::NodeStatus::Failed,
+        }
+    }
+}
+
 /// Informations de santé d'un nœud
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NodeHealth {
@@ -249,6 +266,8 @@ pub enum AlertType {
     LowDiskSpace,
     /// Problème de synchronisation
     SyncIssue,
+    /// Récupération automatique en échec
+    RecoveryFailed,
 }
 
 /// Niveaux de sévérité d'alerte
@@ -282,6 +301,10 @@ pub enum AlertStatus {
 pub struct AutoRecoverySystem {
     /// Configuration
     config: AutoRecoveryConfig,
+    /// Nombre maximum de redémarrages par nœud
+    max_restarts: u32,
+    /// Échecs consécutifs avant escalade
+    escalation_threshold: u32,
     /// Tentatives de récupération en cours
     active_recoveries: Arc<RwLock<HashMap<NodeId, RecoveryAttempt>>>,
     /// Historique des récupérations
@@ -301,6 +324,61 @@ pub struct RecoveryAttempt {
     pub started_at: SystemTime,
     /// Statut
     pub status: RecoveryStatus,
+    /// Échecs consécutifs
+    pub failures: u32,
+    /// Heure à partir de laquelle une nouvelle tentative est autorisée
+    pub next_attempt_at: SystemTime,
+    /// Une alerte d'escalade a déjà été émise
+    pub escalated: bool,
+}
+
+/// Opérations de récupération fournies par le gestionnaire de nœuds
+#[async_trait]
+pub trait RecoveryHandler: Send + Sync {
+    /// Vérifie la santé d'un nœud
+    async fn check_health(&self, node_id: &NodeId) -> Result<NodeHealth>;
+
+    /// Redémarre un nœud
+    async fn restart_node(&self, node_id: &NodeId) -> Result<()>;
+
+    /// Replace ailleurs le contenu stocké par un nœud ; retourne le nombre de répliques replanifiées
+    async fn reschedule_content(&self, node_id: &NodeId) -> Result<u32>;
+}
+
+/// Résultat d'une passe de supervision sur un nœud
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RecoveryOutcome {
+    /// Nœud sain, aucune action
+    Healthy,
+    /// Nœud défaillant mais récupération automatique désactivée
+    Disabled,
+    /// Nœud redevenu sain après récupération
+    Recovered,
+    /// Attente de la fin du délai de backoff
+    Waiting { until: SystemTime },
+    /// Redémarrage tenté sans succès
+    RestartFailed { attempt: u32, next_attempt_at: SystemTime },
+    /// Redémarrages épuisés : le contenu du nœud a été replanifié
+    Exhausted { rescheduled_replicas: u32 },
+    /// Récupération déjà abandonnée pour ce nœud
+    Abandoned,
+}
+
+/// Prochaine étape de récupération pour un nœud défaillant
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum RecoveryStep {
+    Restart { attempt: u32 },
+    Wait { until: SystemTime },
+    Abandoned,
+}
+
+/// Bilan d'un redémarrage échoué
+#[derive(Debug, Clone)]
+struct RecoveryFailure {
+    failures: u32,
+    next_attempt_at: SystemTime,
+    escalate: bool,
+    exhausted: bool,
 }
 
 /// Statut de récupération
@@ -335,6 +413,108 @@ pub struct RecoveryRecord {
     pub details: String,
 }
 
+impl AutoRecoverySystem {
+    /// Délai d'attente après `failures` échecs consécutifs
+    pub fn backoff_delay(&self, failures: u32) -> Duration {
+        if failures == 0 {
+            return Duration::ZERO;
+        }
+        let delay = if self.config.exponential_backoff {
+            self.config.retry_delay.saturating_mul(2u32.saturating_pow(failures - 1))
+        } else {
+            self.config.retry_delay
+        };
+        delay.min(self.config.max_retry_delay)
+    }
+
+    /// Tentative de récupération en cours pour un nœud
+    pub async fn get_recovery_attempt(&self, node_id: &NodeId) -> Option<RecoveryAttempt> {
+        self.active_recoveries.read().await.get(node_id).cloned()
+    }
+
+    /// Historique des récupérations terminées
+    pub async fn get_recovery_history(&self) -> Vec<RecoveryRecord> {
+        self.recovery_history.read().await.clone()
+    }
+
+    /// Détermine la prochaine étape pour un nœud défaillant
+    async fn next_step(&self, node_id: &NodeId, now: SystemTime) -> RecoveryStep {
+        match self.active_recoveries.read().await.get(node_id) {
+            None => RecoveryStep::Restart { attempt: 1 },
+            Some(attempt) if attempt.status == RecoveryStatus::Failed => RecoveryStep::Abandoned,
+            Some(attempt) if now < attempt.next_attempt_at => RecoveryStep::Wait { until: attempt.next_attempt_at },
+            Some(attempt) => RecoveryStep::Restart { attempt: attempt.attempt_number + 1 },
+        }
+    }
+
+    /// Enregistre le début d'un redémarrage
+    async fn begin_attempt(&self, node_id: &NodeId, attempt_number: u32, now: SystemTime) {
+        let mut active = self.active_recoveries.write().await;
+        let attempt = active.entry(node_id.clone()).or_insert_with(|| RecoveryAttempt {
+            node_id: node_id.clone(),
+            action: RecoveryAction::RestartNode,
+            attempt_number: 0,
+            started_at: now,
+            status: RecoveryStatus::InProgress,
+            failures: 0,
+            next_attempt_at: now,
+            escalated: false,
+        });
+        attempt.attempt_number = attempt_number;
+        attempt.status = RecoveryStatus::InProgress;
+    }
+
+    /// Enregistre l'échec d'un redémarrage et calcule le backoff suivant
+    async fn record_failure(&self, node_id: &NodeId, now: SystemTime) -> Option<RecoveryFailure> {
+        let mut active = self.active_recoveries.write().await;
+        let attempt = active.get_mut(node_id)?;
+
+        attempt.failures += 1;
+        attempt.next_attempt_at = now + self.backoff_delay(attempt.failures);
+        let exhausted = attempt.attempt_number >= self.max_restarts;
+        let escalate = !attempt.escalated && (attempt.failures >= self.escalation_threshold || exhausted);
+        attempt.escalated |= escalate;
+
+        if exhausted {
+            attempt.status = RecoveryStatus::Failed;
+            self.recovery_history.write().await.push(Self::record(attempt, RecoveryStatus::Failed,
+                "Redémarrages épuisés, contenu replanifié".to_string()));
+        }
+
+        Some(RecoveryFailure {
+            failures: attempt.failures,
+            next_attempt_at: attempt.next_attempt_at,
+            escalate,
+            exhausted,
+        })
+    }
+
+    /// Clôture la récupération d'un nœud redevenu sain
+    async fn record_success(&self, node_id: &NodeId) -> bool {
+        let Some(attempt) = self.active_recoveries.write().await.remove(node_id) else {
+            return false;
+        };
+        if attempt.status == RecoveryStatus::Failed {
+            return false;
+        }
+        self.recovery_history.write().await.push(Self::record(&attempt, RecoveryStatus::Successful,
+            "Récupération automatique réussie".to_string()));
+        true
+    }
+
+    fn record(attempt: &RecoveryAttempt, final_status: RecoveryStatus, details: String) -> RecoveryRecord {
+        RecoveryRecord {
+            node_id: attempt.node_id.clone(),
+            action: attempt.action.clone(),
+            total_attempts: attempt.attempt_number,
+            started_at: chrono::DateTime::<chrono::Utc>::from(attempt.started_at),
+            completed_at: chrono::Utc::now(),
+            final_status,
+            details,
+        }
+    }
+}
+
 /// Système d'alertes
 #[derive(Debug)]
 pub struct AlertSystem {
@@ -396,6 +576,7 @@ impl Default for HealthMonitorConfig {
             alert_interval: Duration::from_secs(300), // 5 minutes
             auto_recovery_enabled: true,
             max_recovery_attempts: 3,
+            escalation_threshold: 2,
             metrics_config: MetricsCollectionConfig::default(),
             alert_config: AlertConfig::default(),
             recovery_config: AutoRecoveryConfig::default(),
@@ -479,6 +660,8 @@ impl HealthMonitor {
 
         let auto_recovery = AutoRecoverySystem {
             config: config.recovery_config.clone(),
+            max_restarts: config.max_recovery_attempts,
+            escalation_threshold: config.escalation_threshold,
             active_recoveries: Arc::new(RwLock::new(HashMap::new())),
             recovery_history: Arc::new(RwLock::new(Vec::new())),
         };
@@ -507,6 +690,15 @@ impl HealthMonitor {
 
     /// Effectue un check de santé sur un nœud spécifique
     pub async fn check_node_health(&self, node_id: &NodeId, node: &dyn super::Node) -> Result<NodeHealth> {
+        self.run_health_check(node_id, node.health_check()).await
+    }
+
+    /// Exécute un check de santé avec timeout et enregistre son résultat
+    async fn run_health_check(
+        &self,
+        node_id: &NodeId,
+        check: impl Future<Output = Result<NodeHealth>>,
+    ) -> Result<NodeHealth> {
         let check_start = SystemTime::now();
         
         // Met à jour les statistiques
@@ -516,7 +708,7 @@ impl HealthMonitor {
         }
 
         // Effectue le check de santé
-        match tokio::time::timeout(self.config.check_timeout, node.health_check()).await {
+        match tokio::time::timeout(self.config.check_timeout, check).await {
             Ok(Ok(health)) => {
                 // Check réussi
                 {
@@ -660,11 +852,6 @@ impl HealthMonitor {
         // Envoie l'alerte via les canaux configurés
         self.send_alert_notification(&alert).await?;
 
-        // Déclenche la récupération automatique si activée
-        if self.config.auto_recovery_enabled && severity >= AlertSeverity::Error {
-            self.trigger_auto_recovery(node_id, &alert_type).await?;
-        }
-
         tracing::warn!("Alerte créée: {} - {} - {}", alert_type_to_string(&alert_type), severity_to_string(&severity), message);
         Ok(())
     }
@@ -704,113 +891,98 @@ impl HealthMonitor {
         Ok(())
     }
 
-    /// Déclenche la récupération automatique
-    async fn trigger_auto_recovery(&self, node_id: &NodeId, alert_type: &AlertType) -> Result<()> {
-        let mut auto_recovery = self.auto_recovery.lock().await;
-        
-        // Vérifie si une récupération est déjà en cours
-        {
-            let active_recoveries = auto_recovery.active_recoveries.read().await;
-            if active_recoveries.contains_key(node_id) {
-                tracing::debug!("Récupération déjà en cours pour le nœud {:?}", node_id);
-                return Ok(());
+    /// Supervise un nœud et applique la politique de récupération automatique
+    ///
+    /// Un nœud critique ou injoignable est redémarré via `handler`, avec un
+    /// backoff exponentiel entre les tentatives. Une alerte est escaladée après
+    /// `escalation_threshold` échecs ; une fois `max_recovery_attempts`
+    /// redémarrages épuisés, son contenu est replanifié sur d'autres nœuds.
+    pub async fn supervise_node(&self, node_id: &NodeId, handler: &dyn RecoveryHandler) -> Result<RecoveryOutcome> {
+        self.supervise_node_at(node_id, handler, SystemTime::now()).await
+    }
+
+    /// Variante de `supervise_node` avec une horloge explicite
+    pub async fn supervise_node_at(
+        &self,
+        node_id: &NodeId,
+        handler: &dyn RecoveryHandler,
+        now: SystemTime,
+    ) -> Result<RecoveryOutcome> {
+        let healthy = self.probe(node_id, handler).await;
+        let auto_recovery = self.auto_recovery.lock().await;
+
+        if healthy {
+            if auto_recovery.record_success(node_id).await {
+                self.monitoring_stats.write().await.recoveries_successful += 1;
+                tracing::info!("Nœud {:?} rétabli par la récupération automatique", node_id);
+                return Ok(RecoveryOutcome::Recovered);
             }
+            return Ok(RecoveryOutcome::Healthy);
         }
 
-        // Détermine l'action de récupération appropriée
-        let recovery_action = match alert_type {
-            AlertType::HighResourceUsage => RecoveryAction::ClearCache,
-            AlertType::HighLatency => RecoveryAction::ResetConnections,
-            AlertType::NodeUnresponsive => RecoveryAction::RestartNode,
-            AlertType::ConnectivityIssue => RecoveryAction::ResetConnections,
-            AlertType::SyncIssue => RecoveryAction::Resynchronize,
-            _ => RecoveryAction::RestartNode, // Action par défaut
-        };
-
-        // Démarre la tentative de récupération
-        let recovery_attempt = RecoveryAttempt {
-            node_id: node_id.clone(),
-            action: recovery_action.clone(),
-            attempt_number: 1,
-            started_at: SystemTime::now(),
-            status: RecoveryStatus::InProgress,
-        };
-
-        {
-            let mut active_recoveries = auto_recovery.active_recoveries.write().await;
-            active_recoveries.insert(node_id.clone(), recovery_attempt);
+        if !self.config.auto_recovery_enabled || !self.config.recovery_config.enabled {
+            return Ok(RecoveryOutcome::Disabled);
         }
 
-        // Met à jour les statistiques
-        {
-            let mut stats = self.monitoring_stats.write().await;
-            stats.recoveries_attempted += 1;
-        }
+        let attempt = match auto_recovery.next_step(node_id, now).await {
+            RecoveryStep::Abandoned => return Ok(RecoveryOutcome::Abandoned),
+            RecoveryStep::Wait { until } => return Ok(RecoveryOutcome::Waiting { until }),
+            RecoveryStep::Restart { attempt } => attempt,
+        };
 
-        tracing::info!("Récupération automatique démarrée pour {:?}: {:?}", node_id, recovery_action);
+        auto_recovery.begin_attempt(node_id, attempt, now).await;
+        self.monitoring_stats.write().await.recoveries_attempted += 1;
+        tracing::info!("Redémarrage du nœud {:?} (tentative {})", node_id, attempt);
 
-        // Simule l'exécution de l'action de récupération
-        // Dans la réalité, cela appellerait les méthodes appropriées du nœud
-        tokio::spawn(self.execute_recovery_action(node_id.clone(), recovery_action));
+        let recovered = match handler.restart_node(node_id).await {
+            Ok(()) => self.probe(node_id, handler).await,
+            Err(e) => {
+                tracing::warn!("Redémarrage du nœud {:?} échoué: {}", node_id, e);
+                false
+            }
+        };
 
-        Ok(())
-    }
+        if recovered {
+            auto_recovery.record_success(node_id).await;
+            self.monitoring_stats.write().await.recoveries_successful += 1;
+            return Ok(RecoveryOutcome::Recovered);
+        }
 
-    /// Exécute une action de récupération
-    async fn execute_recovery_action(&self, node_id: NodeId, action: RecoveryAction) -> Result<()> {
-        tracing::info!("Exécution de l'action de récupération {:?} pour {:?}", action, node_id);
+        let Some(failure) = auto_recovery.record_failure(node_id, now).await else {
+            return Ok(RecoveryOutcome::Abandoned);
+        };
+        drop(auto_recovery);
 
-        // Simulation de l'exécution de l'action
-        match action {
-            RecoveryAction::RestartNode => {
-                tokio::time::sleep(Duration::from_secs(5)).await;
-            },
-            RecoveryAction::ClearCache => {
-                tokio::time::sleep(Duration::from_secs(2)).await;
-            },
-            RecoveryAction::ResetConnections => {
-                tokio::time::sleep(Duration::from_secs(3)).await;
-            },
-            RecoveryAction::Resynchronize => {
-                tokio::time::sleep(Duration::from_secs(10)).await;
-            },
-            RecoveryAction::SwitchMode => {
-                tokio::time::sleep(Duration::from_secs(1)).await;
-            },
+        if failure.escalate {
+            self.create_alert(node_id, AlertType::RecoveryFailed, AlertSeverity::Critical,
+                format!("Récupération automatique échouée {} fois", failure.failures)).await?;
         }
 
-        // Marque la récupération comme terminée
-        {
-            let auto_recovery = self.auto_recovery.lock().await;
-            let mut active_recoveries = auto_recovery.active_recoveries.write().await;
-            if let Some(mut attempt) = active_recoveries.remove(&node_id) {
-                attempt.status = RecoveryStatus::Successful;
-                
-                // Ajoute à l'historique
-                let mut history = auto_recovery.recovery_history.write().await;
-                history.push(RecoveryRecord {
-                    node_id: node_id.clone(),
-                    action,
-                    total_attempts: attempt.attempt_number,
-                    started_at: chrono::DateTime::from_timestamp(
-                        attempt.started_at.duration_since(SystemTime::UNIX_EPOCH)
-                            .unwrap_or(Duration::ZERO).as_secs() as i64, 0
-                    ).unwrap_or_else(chrono::Utc::now),
-                    completed_at: chrono::Utc::now(),
-                    final_status: RecoveryStatus::Successful,
-                    details: "Récupération automatique réussie".to_string(),
-                });
-            }
+        if failure.exhausted {
+            let rescheduled_replicas = match handler.reschedule_content(node_id).await {
+                Ok(count) => count,
+                Err(e) => {
+                    tracing::error!("Replanification du contenu du nœud {:?} échouée: {}", node_id, e);
+                    0
+                }
+            };
+            return Ok(RecoveryOutcome::Exhausted { rescheduled_replicas });
         }
 
-        // Met à jour les statistiques
-        {
-            let mut stats = self.monitoring_stats.write().await;
-            stats.recoveries_successful += 1;
+        Ok(RecoveryOutcome::RestartFailed { attempt, next_attempt_at: failure.next_attempt_at })
+    }
+
+    /// Vérifie la santé d'un nœud via le gestionnaire ; faux si critique ou injoignable
+    async fn probe(&self, node_id: &NodeId, handler: &dyn RecoveryHandler) -> bool {
+        match self.run_health_check(node_id, handler.check_health(node_id)).await {
+            Ok(health) => !matches!(health.status, HealthStatus::Critical | HealthStatus::Unresponsive),
+            Err(_) => false,
         }
+    }
 
-        tracing::info!("Action de récupération {:?} terminée avec succès pour {:?}", action, node_id);
-        Ok(())
+    /// Obtient la tentative de récupération en cours pour un nœud
+    pub async fn get_recovery_attempt(&self, node_id: &NodeId) -> Option<RecoveryAttempt> {
+        self.auto_recovery.lock().await.get_recovery_attempt(node_id).await
     }
 
     /// Obtient les actions recommandées pour un type d'alerte
@@ -823,6 +995,7 @@ impl HealthMonitor {
             AlertType::ConnectivityIssue => vec![RecoveryAction::ResetConnections],
             AlertType::LowDiskSpace => vec![RecoveryAction::ClearCache],
             AlertType::SyncIssue => vec![RecoveryAction::Resynchronize],
+            AlertType::RecoveryFailed => Vec::new(),
         }
     }
 
@@ -908,6 +1081,7 @@ fn alert_type_to_string(alert_type: &AlertType) -> &'static str {
         AlertType::ConnectivityIssue => "Problème connectivité",
         AlertType::LowDiskSpace => "Espace disque faible",
         AlertType::SyncIssue => "Problème synchronisation",
+        AlertType::RecoveryFailed => "Récupération en échec",
     }
 }
 
@@ -954,6 +1128,94 @@ mod tests {
         assert_ne!(status, HealthStatus::Critical);
     }
 
+    /// Nœud dont le health check échoue systématiquement
+    #[derive(Default)]
+    struct FailingNode {
+        restarts: std::sync::atomic::AtomicU32,
+        reschedules: std::sync::atomic::AtomicU32,
+    }
+
+    #[async_trait]
+    impl RecoveryHandler for FailingNode {
+        async fn check_health(&self, _node_id: &NodeId) -> Result<NodeHealth> {
+            Ok(NodeHealth {
+                status: HealthStatus::Critical,
+                uptime: Duration::ZERO,
+                cpu_usage: 0.0,
+                memory_usage: 0.0,
+                storage_usage: 0.0,
+                network_latency: Duration::ZERO,
+                error_rate: 0.0,
+                last_check: SystemTime::now(),
+            })
+        }
+
+        async fn restart_node(&self, _node_id: &NodeId) -> Result<()> {
+            self.restarts.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(())
+        }
+
+        async fn reschedule_content(&self, _node_id: &NodeId) -> Result<u32> {
+            self.reschedules.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(4)
+        }
+    }
+
+    #[tokio::test]
+    async fn test_auto_recovery_backs_off_and_escalates() {
+        let config = HealthMonitorConfig {
+            max_recovery_attempts: 3,
+            escalation_threshold: 2,
+            recovery_config: AutoRecoveryConfig {
+                retry_delay: Duration::from_secs(10),
+                max_retry_delay: Duration::from_secs(600),
+                ..AutoRecoveryConfig::default()
+            },
+            ..HealthMonitorConfig::default()
+        };
+        let monitor = HealthMonitor::new(config).await.unwrap();
+        let node = FailingNode::default();
+        let node_id = NodeId::from(crate::crypto::Hash::zero());
+        let t0 = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+        let at = |secs: u64| t0 + Duration::from_secs(secs);
+
+        assert_eq!(
+            monitor.supervise_node_at(&node_id, &node, t0).await.unwrap(),
+            RecoveryOutcome::RestartFailed { attempt: 1, next_attempt_at: at(10) }
+        );
+        assert_eq!(
+            monitor.supervise_node_at(&node_id, &node, at(5)).await.unwrap(),
+            RecoveryOutcome::Waiting { until: at(10) }
+        );
+        assert!(monitor.get_active_alerts().await.iter().all(|a| a.alert_type != AlertType::RecoveryFailed));
+
+        // Le délai double à chaque échec
+        assert_eq!(
+            monitor.supervise_node_at(&node_id, &node, at(10)).await.unwrap(),
+            RecoveryOutcome::RestartFailed { attempt: 2, next_attempt_at: at(30) }
+        );
+        let escalations = monitor.get_active_alerts().await.into_iter()
+            .filter(|a| a.alert_type == AlertType::RecoveryFailed)
+            .count();
+        assert_eq!(escalations, 1);
+
+        assert_eq!(
+            monitor.supervise_node_at(&node_id, &node, at(30)).await.unwrap(),
+            RecoveryOutcome::Exhausted { rescheduled_replicas: 4 }
+        );
+        assert_eq!(
+            monitor.supervise_node_at(&node_id, &node, at(3600)).await.unwrap(),
+            RecoveryOutcome::Abandoned
+        );
+
+        assert_eq!(node.restarts.load(std::sync::atomic::Ordering::SeqCst), 3);
+        assert_eq!(node.reschedules.load(std::sync::atomic::Ordering::SeqCst), 1);
+        let stats = monitor.get_monitoring_stats().await;
+        assert_eq!(stats.recoveries_attempted, 3);
+        assert_eq!(stats.recoveries_successful, 0);
+        assert_eq!(monitor.get_recovery_attempt(&node_id).await.unwrap().status, RecoveryStatus::Failed);
+    }
+
     #[test]
     fn test_alert_severity_ordering() {
         assert!(AlertSeverity::Critical > AlertSeverity::Error);
@@ -11,20 +11,21 @@ use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
 use std::sync::Arc;
 use std::time::{Duration, SystemTime};
-use tokio::sync::{RwLock, Mutex};
+use tokio::sync::{mpsc, RwLock, Mutex};
 use async_trait::async_trait;
 use regex::Regex;
 
-use crate::crypto::{Hash, PublicKey, PrivateKey};
-use crate::consensus::{NodeId, ConsensusScore};
+use crate::crypto::{Hash, Signer};
+use crate::consensus::{NodeId, ConsensusScore, BandwidthReporter, TransferDirection};
 use crate::storage::{
     StorageManager, StorageNodeInfo, ContentMetadata, DistributedStorage,
     StorageType, NodeStatus
 };
-use crate::error::Result;
+use crate::error::{CoreError, Result};
+use crate::serialization::{serialize_with_format, deserialize_with_format, SerializationFormat};
 use super::{
     Node, NodeType, NodeConfiguration, NetworkMessage, MessageType,
-    NodeHealth, NodeMetrics, GeneralNodeMetrics, HealthStatus
+    NodeHealth, NodeMetrics, GeneralNodeMetrics, HealthStatus, DrainReport, InFlight
 };
 
 /// Types de spécialisation pour les Light Storage Nodes
@@ -55,8 +56,11 @@ pub struct ContentFilter {
     pub url_patterns: Vec<String>,
     /// Types MIME acceptés
     pub accepted_mime_types: Vec<String>,
-    /// Domaines préférés
+    /// Domaines acceptés (`*.gov` accepte tous les sous-domaines de `gov`)
     pub preferred_domains: Vec<String>,
+    /// Régions géographiques acceptées (`eu` accepte `eu-west-1`)
+    #[serde(default)]
+    pub accepted_regions: Vec<String>,
     /// Langues acceptées (codes ISO)
     pub accepted_languages: Vec<String>,
     /// Taille minimale de fichier (bytes)
@@ -67,6 +71,75 @@ pub struct ContentFilter {
     pub min_popularity_score: Option<u64>,
     /// Période temporelle (pour spécialisation temporelle)
     pub temporal_range: Option<TemporalRange>,
+    /// Règles éliminatoires, toutes requises
+    #[serde(default)]
+    pub rules: Vec<FilterRule>,
+}
+
+/// Règle de filtrage évaluée sur les métadonnées d'un contenu
+///
+/// « Images de moins de 5 Mo marquées `news` » s'écrit en JSON :
+///
+/// ```json
+/// [
+///   { "rule": "content_type", "patterns": ["image/*"] },
+///   { "rule": "size", "max": 5242880 },
+///   { "rule": "tags", "any": ["news"] }
+/// ]
+/// ```
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "rule", rename_all = "snake_case")]
+pub enum FilterRule {
+    /// Type MIME correspondant à l'un des motifs (`image/*` accepté)
+    ContentType { patterns: Vec<String> },
+    /// Taille en octets, bornes incluses
+    Size {
+        #[serde(default)]
+        min: Option<u64>,
+        #[serde(default)]
+        max: Option<u64>,
+    },
+    /// Tags du contenu, comparés sans tenir compte de la casse
+    Tags {
+        /// Au moins un de ces tags (ignoré si vide)
+        #[serde(default)]
+        any: Vec<String>,
+        /// Tous ces tags
+        #[serde(default)]
+        all: Vec<String>,
+        /// Aucun de ces tags
+        #[serde(default)]
+        none: Vec<String>,
+    },
+    /// Au moins une région préférée du contenu acceptée (`eu` accepte `eu-west-1`)
+    Regions { regions: Vec<String> },
+    /// Au moins une des sous-règles
+    AnyOf { rules: Vec<FilterRule> },
+    /// Négation d'une règle
+    Not { rule: Box<FilterRule> },
+}
+
+impl FilterRule {
+    /// Évalue la règle sur les métadonnées d'un contenu
+    pub fn matches(&self, metadata: &ContentMetadata) -> bool {
+        match self {
+            FilterRule::ContentType { patterns } => {
+                patterns.iter().any(|pattern| mime_type_matches(pattern, &metadata.content_type))
+            }
+            FilterRule::Size { min, max } => {
+                min.map_or(true, |min| metadata.size >= min) && max.map_or(true, |max| metadata.size <= max)
+            }
+            FilterRule::Tags { any, all, none } => {
+                let tags: HashSet<String> = metadata.tags.iter().map(|tag| tag.to_lowercase()).collect();
+                let has = |tag: &String| tags.contains(&tag.to_lowercase());
+                (any.is_empty() || any.iter().any(has)) && all.iter().all(has) && !none.iter().any(has)
+            }
+            FilterRule::Regions { regions } => metadata.preferred_regions.iter()
+                .any(|region| regions.iter().any(|accepted| region_matches(accepted, region))),
+            FilterRule::AnyOf { rules } => rules.iter().any(|rule| rule.matches(metadata)),
+            FilterRule::Not { rule } => !rule.matches(metadata),
+        }
+    }
 }
 
 /// Période temporelle pour la spécialisation
@@ -133,6 +206,8 @@ pub enum LightStorageStatus {
     CacheOptimization,
     /// Maintenance
     Maintenance,
+    /// Drainage avant arrêt : plus de nouveaux contenus, lectures servies
+    Draining,
     /// Échec de spécialisation
     SpecializationFailed,
     /// Arrêt en cours
@@ -156,6 +231,8 @@ pub struct LightStorageMetrics {
     pub cache_hit_rate: f64,
     /// Nombre de requêtes servies
     pub requests_served: u64,
+    /// Contenus refusés car hors spécialisation
+    pub rejected_content_count: u64,
     /// Taux de participation au consensus
     pub consensus_participation_rate: f64,
     /// Score de spécialisation
@@ -180,8 +257,8 @@ pub struct LightStorageNode {
     config: LightStorageConfig,
     /// Identifiant du nœud
     node_id: NodeId,
-    /// Clés cryptographiques
-    keypair: (PublicKey, PrivateKey),
+    /// Signataire du nœud (clé locale ou signataire externe)
+    signer: Arc<dyn Signer>,
     /// Statut actuel
     status: Arc<RwLock<LightStorageStatus>>,
     /// Gestionnaire de stockage
@@ -196,8 +273,18 @@ pub struct LightStorageNode {
     compiled_filters: Arc<RwLock<Vec<Regex>>>,
     /// Dernière optimisation du cache
     last_cache_optimization: Arc<Mutex<SystemTime>>,
+    /// Remontée des transferts réels vers la preuve de bande passante
+    bandwidth_reporter: Option<BandwidthReporter>,
     /// Heure de démarrage
     start_time: SystemTime,
+    /// Requêtes de stockage et de lecture en cours
+    in_flight: InFlight,
+    /// Tâche périodique de nettoyage du stockage local
+    cleanup_task: Option<tokio::task::JoinHandle<()>>,
+    /// Tâches de fond du stockage : intégrité, filtre et rééquilibrage
+    storage_tasks: Vec<tokio::task::JoinHandle<()>>,
+    /// Remontée du statut de santé du nœud au rééquilibrage
+    storage_status: Option<mpsc::UnboundedSender<(NodeId, NodeStatus)>>,
 }
 
 /// Métadonnées d'archive dans l'index local
@@ -250,6 +337,7 @@ impl Default for LightStorageConfig {
                 storage_config: None,
                 network_config: super::NetworkConfiguration::default(),
                 security_config: super::SecurityConfiguration::default(),
+                content_filter: None,
             },
             storage_capacity: 5_000_000_000_000, // 5TB
             specialization: StorageSpecialization::ContentType,
@@ -265,6 +353,40 @@ impl Default for LightStorageConfig {
     }
 }
 
+impl ContentFilter {
+    /// Indique si un contenu satisfait les critères éliminatoires du filtre
+    ///
+    /// Types MIME, régions et règles ; les critères portant sur l'URL
+    /// d'origine sont vérifiés par `LightStorageNode::check_content_filters`.
+    pub fn matches(&self, metadata: &ContentMetadata) -> bool {
+        self.accepts_mime_type(&metadata.content_type)
+            && self.accepts_regions(&metadata.preferred_regions)
+            && self.failing_rule(metadata).is_none()
+    }
+
+    /// Première règle non satisfaite par le contenu
+    pub fn failing_rule(&self, metadata: &ContentMetadata) -> Option<&FilterRule> {
+        self.rules.iter().find(|rule| !rule.matches(metadata))
+    }
+
+    fn accepts_mime_type(&self, content_type: &str) -> bool {
+        self.accepted_mime_types.is_empty() || self.targets_content_type(content_type)
+    }
+
+    fn accepts_regions(&self, regions: &[String]) -> bool {
+        self.accepted_regions.is_empty()
+            || regions.iter().any(|region| self.accepted_regions.iter().any(|accepted| region_matches(accepted, region)))
+    }
+
+    /// Indique si le filtre cible explicitement ce type MIME
+    ///
+    /// Un filtre sans type MIME accepte tout contenu mais n'est spécialisé
+    /// pour aucun : il ne cible donc aucun type.
+    pub fn targets_content_type(&self, content_type: &str) -> bool {
+        self.accepted_mime_types.iter().any(|accepted| mime_type_matches(accepted, content_type))
+    }
+}
+
 impl Default for ContentFilter {
     fn default() -> Self {
         Self {
@@ -272,11 +394,13 @@ impl Default for ContentFilter {
             url_patterns: vec![r".*\.html$".to_string(), r".*\.htm$".to_string()],
             accepted_mime_types: vec!["text/html".to_string()],
             preferred_domains: Vec::new(),
+            accepted_regions: Vec::new(),
             accepted_languages: Vec::new(),
             min_file_size: None,
             max_file_size: None,
             min_popularity_score: None,
             temporal_range: None,
+            rules: Vec::new(),
         }
     }
 }
@@ -296,7 +420,7 @@ impl LightStorageNode {
     /// Crée une nouvelle instance de Light Storage Node
     pub fn new(
         config: LightStorageConfig,
-        keypair: (PublicKey, PrivateKey),
+        signer: Arc<dyn Signer>,
         storage_manager: StorageManager,
     ) -> Result<Self> {
         // Valide la configuration
@@ -305,16 +429,7 @@ impl LightStorageNode {
         let node_id = config.node_config.node_id.clone();
         let start_time = SystemTime::now();
 
-        // Compile les filtres regex
-        let mut compiled_filters = Vec::new();
-        for pattern in &config.content_filter.url_patterns {
-            match Regex::new(pattern) {
-                Ok(regex) => compiled_filters.push(regex),
-                Err(e) => {
-                    tracing::warn!("Erreur compilation regex '{}': {}", pattern, e);
-                }
-            }
-        }
+        let compiled_filters = Self::compile_url_patterns(&config.content_filter);
 
         let initial_metrics = LightStorageMetrics {
             general: GeneralNodeMetrics {
@@ -335,6 +450,7 @@ impl LightStorageNode {
             cached_popular_content: 0,
             cache_hit_rate: 0.0,
             requests_served: 0,
+            rejected_content_count: 0,
             consensus_participation_rate: 0.0,
             specialization_score: 0.0,
             storage_efficiency: 0.0,
@@ -343,7 +459,7 @@ impl LightStorageNode {
         Ok(Self {
             config,
             node_id,
-            keypair,
+            signer,
             status: Arc::new(RwLock::new(LightStorageStatus::Initializing)),
             storage_manager: Arc::new(Mutex::new(storage_manager)),
             local_archive_index: Arc::new(RwLock::new(HashMap::new())),
@@ -351,10 +467,128 @@ impl LightStorageNode {
             metrics: Arc::new(RwLock::new(initial_metrics)),
             compiled_filters: Arc::new(RwLock::new(compiled_filters)),
             last_cache_optimization: Arc::new(Mutex::new(start_time)),
+            bandwidth_reporter: None,
             start_time,
+            in_flight: InFlight::default(),
+            cleanup_task: None,
+            storage_tasks: Vec::new(),
+            storage_status: None,
         })
     }
 
+    /// Compile les filtres regex des URLs ; les motifs invalides sont ignorés
+    fn compile_url_patterns(filter: &ContentFilter) -> Vec<Regex> {
+        filter.url_patterns.iter()
+            .filter_map(|pattern| match Regex::new(pattern) {
+                Ok(regex) => Some(regex),
+                Err(e) => {
+                    tracing::warn!("Erreur compilation regex '{}': {}", pattern, e);
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Remplace le filtre de contenu sans redémarrer le nœud
+    pub async fn update_content_filter(&mut self, filter: ContentFilter) {
+        *self.compiled_filters.write().await = Self::compile_url_patterns(&filter);
+        self.config.specialization = filter.specialization.clone();
+        self.config.content_filter = filter;
+    }
+
+    /// Filtre de contenu actuellement appliqué
+    pub fn content_filter(&self) -> &ContentFilter {
+        &self.config.content_filter
+    }
+
+    /// Vérifie les critères stricts de la spécialisation
+    ///
+    /// Types MIME, domaines, motifs d'URL, régions et règles sont
+    /// éliminatoires dès qu'ils sont renseignés ; les autres critères ne font
+    /// que pondérer le score de `evaluate_content_match`.
+    pub async fn check_content_filters(
+        &self,
+        metadata: &ContentMetadata,
+        source_url: Option<&str>,
+    ) -> std::result::Result<(), ContentRejection> {
+        let filter = &self.config.content_filter;
+
+        if !filter.accepts_mime_type(&metadata.content_type) {
+            return Err(ContentRejection::MimeTypeNotAccepted {
+                content_type: metadata.content_type.clone(),
+            });
+        }
+
+        if !filter.preferred_domains.is_empty() {
+            let host = source_url
+                .and_then(|url| url::Url::parse(url).ok())
+                .and_then(|url| url.host_str().map(str::to_lowercase))
+                .ok_or(ContentRejection::MissingSourceUrl)?;
+            if !filter.preferred_domains.iter().any(|domain| domain_matches(domain, &host)) {
+                return Err(ContentRejection::DomainNotAccepted { domain: host });
+            }
+        }
+
+        if let Some(url) = source_url {
+            let patterns = self.compiled_filters.read().await;
+            if !patterns.is_empty() && !patterns.iter().any(|regex| regex.is_match(url)) {
+                return Err(ContentRejection::UrlPatternMismatch { url: url.to_string() });
+            }
+        }
+
+        if !filter.accepts_regions(&metadata.preferred_regions) {
+            return Err(ContentRejection::RegionNotAccepted {
+                regions: metadata.preferred_regions.clone(),
+            });
+        }
+
+        if let Some(rule) = filter.failing_rule(metadata) {
+            return Err(ContentRejection::RuleNotSatisfied { rule: rule.clone() });
+        }
+
+        Ok(())
+    }
+
+    /// Traite une demande de stockage reçue du réseau
+    ///
+    /// Un contenu hors spécialisation est refusé avec un motif typé pour que la
+    /// couche de réplication le replace sur un autre nœud.
+    pub async fn handle_store_request(
+        &mut self,
+        request: ContentStoreRequest,
+    ) -> Result<std::result::Result<SpecializedStorageResult, ContentRejection>> {
+        if *self.status.read().await == LightStorageStatus::Draining {
+            let rejection = ContentRejection::NodeDraining;
+            self.record_rejection(&request.content_hash, &rejection).await;
+            return Ok(Err(rejection));
+        }
+
+        if let Err(rejection) = self.check_content_filters(&request.metadata, request.source_url.as_deref()).await {
+            self.record_rejection(&request.content_hash, &rejection).await;
+            return Ok(Err(rejection));
+        }
+
+        let result = self.store_specialized_content(request.content_hash, &request.data, request.metadata).await?;
+        if !result.stored {
+            let rejection = ContentRejection::SpecializationMismatch { match_score: result.match_score };
+            self.record_rejection(&request.content_hash, &rejection).await;
+            return Ok(Err(rejection));
+        }
+
+        Ok(Ok(result))
+    }
+
+    /// Comptabilise un contenu refusé
+    async fn record_rejection(&self, content_hash: &Hash, rejection: &ContentRejection) {
+        tracing::debug!("Contenu {:?} refusé: {:?}", content_hash, rejection);
+        self.metrics.write().await.rejected_content_count += 1;
+    }
+
+    /// Branche la remontée des transferts servis vers le consensus
+    pub fn set_bandwidth_reporter(&mut self, reporter: BandwidthReporter) {
+        self.bandwidth_reporter = Some(reporter);
+    }
+
     /// Évalue si un contenu correspond à la spécialisation
     pub async fn evaluate_content_match(&self, metadata: &ContentMetadata) -> f64 {
         let filter = &self.config.content_filter;
@@ -416,6 +650,14 @@ impl LightStorageNode {
         data: &[u8],
         metadata: ContentMetadata,
     ) -> Result<SpecializedStorageResult> {
+        // Compté avant la vérification du statut pour que `drain` l'attende
+        let _request = self.in_flight.enter();
+        if *self.status.read().await == LightStorageStatus::Draining {
+            return Err(CoreError::ServiceUnavailable {
+                message: "Nœud en cours de drainage, contenu refusé".to_string(),
+            });
+        }
+
         // Évalue la correspondance avec la spécialisation
         let match_score = self.evaluate_content_match(&metadata).await;
         
@@ -473,6 +715,8 @@ impl LightStorageNode {
 
     /// Récupère du contenu depuis l'index local ou le cache
     pub async fn retrieve_specialized_content(&self, content_hash: &Hash) -> Result<Vec<u8>> {
+        let _request = self.in_flight.enter();
+
         // Vérifie d'abord le cache populaire
         {
             let mut cache = self.popular_cache.write().await;
@@ -516,6 +760,23 @@ impl LightStorageNode {
         Ok(data)
     }
 
+    /// Sert du contenu spécialisé à un pair et signale le transfert au consensus
+    pub async fn serve_content(&self, content_hash: &Hash, peer: &NodeId) -> Result<Vec<u8>> {
+        let started = SystemTime::now();
+        let data = self.retrieve_specialized_content(content_hash).await?;
+
+        if let Some(reporter) = &self.bandwidth_reporter {
+            reporter.report(
+                peer.clone(),
+                data.len() as u64,
+                started.elapsed().unwrap_or(Duration::ZERO),
+                TransferDirection::Upload,
+            );
+        }
+
+        Ok(data)
+    }
+
     /// Met en cache du contenu populaire
     pub async fn cache_popular_content(
         &self,
@@ -728,6 +989,27 @@ impl Node for LightStorageNode {
             *status = LightStorageStatus::Operational;
         }
 
+        // Nettoyage local selon la politique configurée
+        if let Some(storage_config) = &self.config.node_config.storage_config {
+            if let Some(task) = self.cleanup_task.take() {
+                task.abort();
+            }
+            self.cleanup_task = StorageManager::start_cleanup_task(
+                self.storage_manager.clone(),
+                self.node_id.clone(),
+                storage_config.cleanup_policy.clone(),
+            );
+        }
+
+        // Intégrité, filtre de contenus et rééquilibrage des répliques
+        for task in self.storage_tasks.drain(..) {
+            task.abort();
+        }
+        let (status_sender, status_changes) = mpsc::unbounded_channel();
+        self.storage_tasks = self.storage_manager.lock().await
+            .start_background_tasks(self.node_id.clone(), status_changes);
+        self.storage_status = Some(status_sender);
+
         tracing::info!("Light Storage Node démarré avec succès");
         Ok(())
     }
@@ -735,6 +1017,14 @@ impl Node for LightStorageNode {
     async fn stop(&mut self) -> Result<()> {
         tracing::info!("Arrêt du Light Storage Node: {:?}", self.node_id);
 
+        if let Some(task) = self.cleanup_task.take() {
+            task.abort();
+        }
+        for task in self.storage_tasks.drain(..) {
+            task.abort();
+        }
+        self.storage_status = None;
+
         {
             let mut status = self.status.write().await;
             *status = LightStorageStatus::Stopping;
@@ -769,6 +1059,11 @@ impl Node for LightStorageNode {
             _ => HealthStatus::Warning,
         };
 
+        if let Some(status_sender) = &self.storage_status {
+            // La tâche de rééquilibrage ne s'arrête qu'avec le nœud
+            let _ = status_sender.send((self.node_id.clone(), health_status.storage_status()));
+        }
+
         Ok(NodeHealth {
             status: health_status,
             uptime: self.start_time.elapsed().unwrap_or(Duration::ZERO),
@@ -809,16 +1104,69 @@ impl Node for LightStorageNode {
                     payload: Vec::new(),
                     timestamp: chrono::Utc::now(),
                     ttl: 60,
+                    request_id: message.request_id.clone(),
                 }))
             },
             MessageType::ContentStore => {
-                // Évalue si le contenu correspond à notre spécialisation
-                // Dans la réalité, on déserialiserait le payload pour obtenir les métadonnées
-                Ok(None)
+                // Le payload contient la demande de stockage sérialisée
+                let Ok(request) = deserialize_with_format::<ContentStoreRequest>(
+                    &message.payload,
+                    SerializationFormat::Bincode,
+                ) else {
+                    return Ok(None);
+                };
+
+                let content_hash = request.content_hash;
+                let (message_type, payload) = match self.handle_store_request(request).await? {
+                    Ok(_) => (MessageType::ContentStore, content_hash.as_bytes().to_vec()),
+                    Err(reason) => (
+                        MessageType::ContentRejected,
+                        serialize_with_format(
+                            &ContentStoreRejection { content_hash, reason },
+                            SerializationFormat::Bincode,
+                        )?,
+                    ),
+                };
+
+                Ok(Some(NetworkMessage {
+                    message_id: crate::crypto::compute_hash(
+                        &message.message_id.as_bytes(),
+                        crate::crypto::HashAlgorithm::Blake3
+                    ),
+                    sender: self.node_id.clone(),
+                    recipient: Some(message.sender),
+                    message_type,
+                    payload,
+                    timestamp: chrono::Utc::now(),
+                    ttl: 60,
+                    request_id: message.request_id.clone(),
+                }))
             },
             MessageType::ContentRetrieve => {
                 // Vérifie si nous avons le contenu spécialisé demandé
-                Ok(None)
+                let Ok(content_hash) = Hash::from_bytes(&message.payload) else {
+                    return Ok(None);
+                };
+
+                let data = match self.serve_content(&content_hash, &message.sender).await {
+                    Ok(data) => data,
+                    Err(crate::error::CoreError::NotFound { .. }) => return Ok(None),
+                    Err(e) => return Err(e),
+                };
+
+                Ok(Some(NetworkMessage {
+                    message_id: crate::crypto::compute_hash(
+                        &message.message_id.as_bytes(),
+                        crate::crypto::HashAlgorithm::Blake3
+                    ),
+                    sender: self.node_id.clone(),
+                    recipient: Some(message.sender),
+                    message_type: MessageType::ContentRetrieve,
+                    payload: data,
+                    timestamp: chrono::Utc::now(),
+                    ttl: 60,
+                    request_id: message.request_id.clone(),
+                }))
             },
             _ => Ok(None),
         }
@@ -830,9 +1178,93 @@ impl Node for LightStorageNode {
     }
 
     async fn update_config(&mut self, config: super::NodeConfiguration) -> Result<()> {
+        if let Some(filter) = config.content_filter.clone() {
+            self.update_content_filter(filter).await;
+        }
         self.config.node_config = config;
         Ok(())
     }
+
+    async fn drain(&self, timeout: Duration) -> Result<DrainReport> {
+        tracing::info!("Drainage du Light Storage Node: {:?}", self.node_id);
+        *self.status.write().await = LightStorageStatus::Draining;
+
+        let in_flight_remaining = self.in_flight.wait_idle(timeout).await;
+        if in_flight_remaining > 0 {
+            tracing::warn!("Drainage expiré avec {} requêtes en cours", in_flight_remaining);
+        }
+
+        // Les lectures restent servies : ce nœud est la source des copies
+        let replication = self.storage_manager.lock().await.evacuate_node(&self.node_id).await;
+        Ok(DrainReport { in_flight_remaining, replication })
+    }
+
+    async fn in_flight_transfers(&self) -> usize {
+        self.in_flight.count()
+    }
+}
+
+/// Demande de stockage transportée par un message `ContentStore`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContentStoreRequest {
+    /// Hash du contenu
+    pub content_hash: Hash,
+    /// URL d'origine du contenu archivé
+    pub source_url: Option<String>,
+    /// Métadonnées du contenu
+    pub metadata: ContentMetadata,
+    /// Données du contenu
+    pub data: Vec<u8>,
+}
+
+/// Motif du refus d'un contenu par un nœud spécialisé
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ContentRejection {
+    /// Type MIME hors des types acceptés
+    MimeTypeNotAccepted { content_type: String },
+    /// Domaine d'origine hors des domaines acceptés
+    DomainNotAccepted { domain: String },
+    /// Filtre par domaine sans URL d'origine exploitable
+    MissingSourceUrl,
+    /// URL ne correspondant à aucun motif
+    UrlPatternMismatch { url: String },
+    /// Aucune région du contenu n'est acceptée
+    RegionNotAccepted { regions: Vec<String> },
+    /// Règle du filtre non satisfaite
+    RuleNotSatisfied { rule: FilterRule },
+    /// Score de correspondance insuffisant
+    SpecializationMismatch { match_score: f64 },
+    /// Nœud en cours de drainage avant arrêt
+    NodeDraining,
+}
+
+/// Réponse `ContentRejected` à une demande de stockage
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ContentStoreRejection {
+    /// Hash du contenu refusé
+    pub content_hash: Hash,
+    /// Motif du refus
+    pub reason: ContentRejection,
+}
+
+/// Compare un type MIME à un type accepté (`image/*` accepte tous les types d'images)
+fn mime_type_matches(accepted: &str, content_type: &str) -> bool {
+    let content_type = content_type.split(';').next().unwrap_or_default().trim();
+    match accepted.strip_suffix("/*") {
+        Some(family) => content_type.split('/').next() == Some(family),
+        None => accepted.eq_ignore_ascii_case(content_type),
+    }
+}
+
+/// Compare un hôte à un domaine accepté, sous-domaines inclus
+fn domain_matches(accepted: &str, host: &str) -> bool {
+    let accepted = accepted.trim_start_matches("*.").trim_start_matches('.').to_lowercase();
+    host == accepted || host.ends_with(&format!(".{}", accepted))
+}
+
+/// Compare une région à une région acceptée (`eu` accepte `eu-west-1`)
+fn region_matches(accepted: &str, region: &str) -> bool {
+    region == accepted || region.starts_with(&format!("{}-", accepted))
 }
 
 /// Résultat du stockage spécialisé
@@ -924,7 +1356,7 @@ impl LightStorageConfig {
 mod tests {
     use super::*;
     use crate::crypto::generate_keypair;
-    // use crate::storage::StorageConfig;
+    use crate::storage::StorageConfig;
 
     #[test]
     fn test_content_filter_default() {
@@ -968,7 +1400,7 @@ mod tests {
             }
         ).await.unwrap();
 
-        let node = LightStorageNode::new(config, keypair, storage_manager);
+        let node = LightStorageNode::new(config, Arc::new(keypair), storage_manager);
         assert!(node.is_ok());
     }
 
@@ -995,4 +1427,235 @@ mod tests {
         config.replication_factor = 5; // Valide
         assert!(config.validate().is_ok());
     }
-}
\ No newline at end of file
+
+    async fn create_specialized_node(filter: ContentFilter) -> LightStorageNode {
+        let storage_manager = StorageManager::new(
+            StorageConfig::default(),
+            crate::storage::manager::StoragePolicy {
+                default_replication_strategy: crate::storage::replication::ReplicationStrategy::Fixed {
+                    replica_count: 3
+                },
+                node_preferences: HashMap::new(),
+                retention_policies: Vec::new(),
+                alert_thresholds: crate::storage::manager::AlertThresholds::default(),
+            }
+        ).await.unwrap();
+
+        let config = LightStorageConfig {
+            specialization: filter.specialization.clone(),
+            content_filter: filter,
+            ..LightStorageConfig::default()
+        };
+        LightStorageNode::new(config, Arc::new(generate_keypair().unwrap()), storage_manager).unwrap()
+    }
+
+    fn pdf_gov_eu_filter() -> ContentFilter {
+        ContentFilter {
+            specialization: StorageSpecialization::Domain,
+            url_patterns: Vec::new(),
+            accepted_mime_types: vec!["application/pdf".to_string()],
+            preferred_domains: vec!["*.gov".to_string()],
+            accepted_regions: vec!["eu".to_string()],
+            ..ContentFilter::default()
+        }
+    }
+
+    fn metadata(content_type: &str, size: u64, region: &str, tags: &[&str]) -> ContentMetadata {
+        ContentMetadata {
+            content_hash: Hash::zero(),
+            size,
+            content_type: content_type.to_string(),
+            title: None,
+            description: None,
+            importance: crate::storage::replication::ContentImportance::Medium,
+            popularity: 10,
+            created_at: chrono::Utc::now(),
+            preferred_regions: vec![region.to_string()],
+            redundancy_level: 3,
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+        }
+    }
+
+    fn store_message(content_type: &str, url: &str, region: &str) -> NetworkMessage {
+        store_message_with(url, metadata(content_type, b"contenu archive".len() as u64, region, &[]))
+    }
+
+    fn store_message_with(url: &str, metadata: ContentMetadata) -> NetworkMessage {
+        let data = b"contenu archive".to_vec();
+        let content_hash = crate::crypto::compute_blake3(&data);
+        let request = ContentStoreRequest {
+            content_hash,
+            source_url: Some(url.to_string()),
+            metadata: ContentMetadata { content_hash, ..metadata },
+            data,
+        };
+
+        NetworkMessage {
+            message_id: content_hash,
+            sender: NodeId::from(Hash::zero()),
+            recipient: None,
+            message_type: MessageType::ContentStore,
+            payload: serialize_with_format(&request, SerializationFormat::Bincode).unwrap(),
+            timestamp: chrono::Utc::now(),
+            ttl: 60,
+            request_id: None,
+        }
+    }
+
+    fn rejection_of(response: Option<NetworkMessage>) -> ContentRejection {
+        let response = response.expect("réponse attendue");
+        assert_eq!(response.message_type, MessageType::ContentRejected);
+        deserialize_with_format::<ContentStoreRejection>(&response.payload, SerializationFormat::Bincode)
+            .unwrap()
+            .reason
+    }
+
+    #[tokio::test]
+    async fn test_content_store_applies_specialization_filters() {
+        let mut node = create_specialized_node(pdf_gov_eu_filter()).await;
+
+        let accepted = node.handle_message(store_message("application/pdf", "https://data.example.gov/report.pdf", "eu-west-1"))
+            .await.unwrap().unwrap();
+        assert_eq!(accepted.message_type, MessageType::ContentStore);
+
+        let rejected = node.handle_message(store_message("text/html", "https://data.example.gov/", "eu-west-1")).await.unwrap();
+        assert_eq!(rejection_of(rejected), ContentRejection::MimeTypeNotAccepted { content_type: "text/html".to_string() });
+
+        let rejected = node.handle_message(store_message("application/pdf", "https://example.com/report.pdf", "eu-west-1")).await.unwrap();
+        assert_eq!(rejection_of(rejected), ContentRejection::DomainNotAccepted { domain: "example.com".to_string() });
+
+        let rejected = node.handle_message(store_message("application/pdf", "https://data.example.gov/report.pdf", "us-east-1")).await.unwrap();
+        assert_eq!(rejection_of(rejected), ContentRejection::RegionNotAccepted { regions: vec!["us-east-1".to_string()] });
+
+        assert_eq!(node.metrics.read().await.rejected_content_count, 3);
+    }
+
+    #[tokio::test]
+    async fn test_draining_node_rejects_new_content() {
+        let mut node = create_specialized_node(pdf_gov_eu_filter()).await;
+        let message = || store_message("application/pdf", "https://data.example.gov/report.pdf", "eu-west-1");
+        let accepted = node.handle_message(message()).await.unwrap().unwrap();
+        assert_eq!(accepted.message_type, MessageType::ContentStore);
+
+        let report = node.drain(Duration::from_secs(1)).await.unwrap();
+        assert_eq!(report.in_flight_remaining, 0);
+        assert_eq!(node.in_flight_transfers().await, 0);
+        assert_eq!(*node.status.read().await, LightStorageStatus::Draining);
+
+        assert_eq!(rejection_of(node.handle_message(message()).await.unwrap()), ContentRejection::NodeDraining);
+        let content_hash = crate::crypto::compute_blake3(b"contenu archive");
+        assert!(matches!(
+            node.store_specialized_content(content_hash, b"contenu archive", metadata("application/pdf", 15, "eu-west-1", &[])).await,
+            Err(CoreError::ServiceUnavailable { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_update_config_replaces_filters_at_runtime() {
+        let mut node = create_specialized_node(pdf_gov_eu_filter()).await;
+        let message = || store_message("text/html", "https://example.com/index.html", "us-east-1");
+        assert!(matches!(
+            rejection_of(node.handle_message(message()).await.unwrap()),
+            ContentRejection::MimeTypeNotAccepted { .. }
+        ));
+
+        let mut node_config = node.config.node_config.clone();
+        node_config.content_filter = Some(ContentFilter::default());
+        node.update_config(node_config).await.unwrap();
+
+        assert_eq!(node.config.specialization, StorageSpecialization::ContentType);
+        let response = node.handle_message(message()).await.unwrap().unwrap();
+        assert_eq!(response.message_type, MessageType::ContentStore);
+    }
+
+    fn news_images_filter() -> ContentFilter {
+        let rules = serde_json::from_value(serde_json::json!([
+            { "rule": "content_type", "patterns": ["image/*"] },
+            { "rule": "size", "max": 5 * 1024 * 1024 },
+            { "rule": "tags", "any": ["news"], "none": ["nsfw"] },
+        ])).unwrap();
+        ContentFilter {
+            url_patterns: Vec::new(),
+            accepted_mime_types: Vec::new(),
+            rules,
+            ..ContentFilter::default()
+        }
+    }
+
+    #[test]
+    fn test_filter_rules_size_boundaries_and_tags() {
+        let filter = news_images_filter();
+        let max = 5 * 1024 * 1024;
+
+        assert!(filter.matches(&metadata("image/png", max, "eu-west-1", &["news"])));
+        assert!(filter.matches(&metadata("image/jpeg", 0, "eu-west-1", &["News", "sport"])));
+        assert!(!filter.matches(&metadata("image/png", max + 1, "eu-west-1", &["news"])));
+        assert!(!filter.matches(&metadata("video/mp4", 1024, "eu-west-1", &["news"])));
+
+        let size = FilterRule::Size { min: Some(10), max: Some(20) };
+        assert!(!size.matches(&metadata("image/png", 9, "eu", &[])));
+        assert!(size.matches(&metadata("image/png", 10, "eu", &[])));
+        assert!(size.matches(&metadata("image/png", 20, "eu", &[])));
+        assert!(!size.matches(&metadata("image/png", 21, "eu", &[])));
+
+        // Intersection des ensembles de tags
+        assert!(!filter.matches(&metadata("image/png", 1024, "eu", &[])));
+        assert!(!filter.matches(&metadata("image/png", 1024, "eu", &["sport"])));
+        assert!(!filter.matches(&metadata("image/png", 1024, "eu", &["news", "NSFW"])));
+        let tags = FilterRule::Tags {
+            any: Vec::new(),
+            all: vec!["news".to_string(), "europe".to_string()],
+            none: Vec::new(),
+        };
+        assert!(tags.matches(&metadata("image/png", 1, "eu", &["europe", "news", "extra"])));
+        assert!(!tags.matches(&metadata("image/png", 1, "eu", &["news"])));
+
+        // Composition et régions
+        let rule = FilterRule::AnyOf { rules: vec![
+            FilterRule::Regions { regions: vec!["eu".to_string()] },
+            FilterRule::Not { rule: Box::new(FilterRule::ContentType { patterns: vec!["image/*".to_string()] }) },
+        ] };
+        assert!(rule.matches(&metadata("image/png", 1, "eu-west-1", &[])));
+        assert!(rule.matches(&metadata("text/html", 1, "us-east-1", &[])));
+        assert!(!rule.matches(&metadata("image/png", 1, "us-east-1", &[])));
+
+        // Format de configuration stable
+        let json = serde_json::to_value(&filter.rules).unwrap();
+        assert_eq!(json[1], serde_json::json!({ "rule": "size", "min": null, "max": max }));
+        let back: Vec<FilterRule> = serde_json::from_value(json).unwrap();
+        assert_eq!(back, filter.rules);
+    }
+
+    #[tokio::test]
+    async fn test_store_offer_rejected_by_filter_rules() {
+        let mut node = create_specialized_node(news_images_filter()).await;
+
+        let accepted = node.handle_message(store_message_with(
+            "https://example.com/photo.png",
+            metadata("image/png", 2048, "eu-west-1", &["news"]),
+        )).await.unwrap().unwrap();
+        assert_eq!(accepted.message_type, MessageType::ContentStore);
+
+        let rejected = node.handle_message(store_message_with(
+            "https://example.com/photo.png",
+            metadata("image/png", 2048, "eu-west-1", &["sport"]),
+        )).await.unwrap();
+        assert!(matches!(
+            rejection_of(rejected),
+            ContentRejection::RuleNotSatisfied { rule: FilterRule::Tags { .. } }
+        ));
+        assert_eq!(node.metrics.read().await.rejected_content_count, 1);
+    }
+
+    #[test]
+    fn test_filter_matchers() {
+        assert!(mime_type_matches("image/*", "image/png"));
+        assert!(mime_type_matches("text/html", "text/html; charset=utf-8"));
+        assert!(!mime_type_matches("image/*", "text/html"));
+        assert!(domain_matches("*.gov", "data.gov"));
+        assert!(domain_matches("example.org", "www.example.org"));
+        assert!(!domain_matches("*.gov", "gov.example.com"));
+        assert!(region_matches("eu", "eu-west-1"));
+        assert!(!region_matches("eu", "europe"));
+    }
+}
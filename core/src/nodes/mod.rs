@@ -43,16 +43,17 @@ pub mod full_archive;
 pub mod light_storage;
 pub mod relay;
 pub mod gateway;
+pub mod bootstrap;
 
 // Re-exports publics pour faciliter l'utilisation
-pub use node_manager::{NodeManager, NodeConfig, NodeManagerStats};
+pub use node_manager::{NodeManager, NodeConfig, NodeManagerStats, RestartPhase};
 pub use node_registry::{
     NodeRegistry, NodeRegistryConfig, NodeInfo, NodeCapabilities, 
-    NodeStatus, GeographicIndex, ReputationScore
+    NodeStatus, GeographicIndex, ReputationScore, NodeFilter
 };
 pub use health_monitor::{
     HealthMonitor, HealthMonitorConfig, NodeHealth, PerformanceMetrics,
-    AlertSystem, AutoRecoverySystem, HealthStatus
+    AlertSystem, AutoRecoverySystem, HealthStatus, RecoveryHandler, RecoveryOutcome
 };
 pub use full_archive::{
     FullArchiveNode, FullArchiveConfig, ArchiveNodeCapabilities,
@@ -60,24 +61,28 @@ pub use full_archive::{
 };
 pub use light_storage::{
     LightStorageNode, LightStorageConfig, StorageSpecialization,
-    ContentFilter, LightStorageMetrics, LightStorageStatus
+    ContentFilter, FilterRule, LightStorageMetrics, LightStorageStatus,
+    ContentStoreRequest, ContentRejection, ContentStoreRejection
 };
 pub use relay::{
     RelayNode, RelayNodeConfig, PeerConnection, MessageRouter,
-    NetworkMetrics, RelayNodeStatus
+    NetworkMetrics, RelayNodeStatus, ForwardDecision, RelayTransport
 };
 pub use gateway::{
     GatewayNode, GatewayNodeConfig, ApiEndpoint, LoadBalancer,
-    CacheLayer, RateLimiter, SecurityStack, GatewayMetrics
+    CacheLayer, RateLimiter, SecurityStack, GatewayMetrics, CircuitState
 };
+pub use bootstrap::{NodeConfigError, ConfigIssue, NODE_CONFIG_ENV};
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::Duration;
 use crate::crypto::{Hash, PublicKey};
 use crate::consensus::NodeId;
 use crate::storage::{
-    NodeType as StorageNodeType, StorageNodeInfo
+    NodeType as StorageNodeType, StorageNodeInfo, KeyEncryptionKey, RebalanceReport
 };
 use crate::error::Result;
 
@@ -110,6 +115,85 @@ pub trait Node: Send + Sync {
     
     /// Met à jour la configuration
     async fn update_config(&mut self, config: NodeConfiguration) -> Result<()>;
+
+    /// Prépare le nœud à un arrêt planifié sans perte de données
+    ///
+    /// Le nœud refuse les nouvelles requêtes, attend la fin de celles en cours
+    /// pendant au plus `timeout`, puis re-réplique ailleurs les contenus dont
+    /// il porterait des répliques nécessaires. `stop()` peut ensuite être
+    /// appelé sans risque. Sans effet par défaut.
+    async fn drain(&self, _timeout: Duration) -> Result<DrainReport> {
+        Ok(DrainReport::default())
+    }
+
+    /// Nombre de transferts en cours, attendus pendant le drainage
+    async fn in_flight_transfers(&self) -> usize {
+        0
+    }
+}
+
+/// Résultat du drainage d'un nœud
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct DrainReport {
+    /// Requêtes encore en cours à l'expiration du délai
+    pub in_flight_remaining: usize,
+    /// Re-réplication des contenus du nœud (nœuds de stockage uniquement)
+    pub replication: RebalanceReport,
+}
+
+impl DrainReport {
+    /// Indique si le nœud peut être arrêté sans perte de requêtes ni de redondance
+    pub fn is_complete(&self) -> bool {
+        self.in_flight_remaining == 0
+            && self.replication.unrecoverable == 0
+            && self.replication.jobs_completed == self.replication.jobs_scheduled
+    }
+}
+
+/// Compteur de requêtes en cours sur un nœud
+///
+/// Les clones partagent le même compteur.
+#[derive(Debug, Clone, Default)]
+pub struct InFlight(Arc<AtomicUsize>);
+
+impl InFlight {
+    /// Délai entre deux vérifications pendant l'attente
+    const POLL_INTERVAL: Duration = Duration::from_millis(50);
+
+    /// Compte une requête jusqu'à la destruction du garde retourné
+    pub fn enter(&self) -> InFlightGuard {
+        self.0.fetch_add(1, Ordering::SeqCst);
+        InFlightGuard(self.0.clone())
+    }
+
+    /// Nombre de requêtes en cours
+    pub fn count(&self) -> usize {
+        self.0.load(Ordering::SeqCst)
+    }
+
+    /// Attend la fin des requêtes en cours pendant au plus `timeout`
+    ///
+    /// Retourne le nombre de requêtes encore en cours à l'issue de l'attente.
+    pub async fn wait_idle(&self, timeout: Duration) -> usize {
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            let count = self.count();
+            if count == 0 || tokio::time::Instant::now() >= deadline {
+                return count;
+            }
+            tokio::time::sleep(Self::POLL_INTERVAL).await;
+        }
+    }
+}
+
+/// Garde d'une requête en cours, voir [`InFlight::enter`]
+#[derive(Debug)]
+pub struct InFlightGuard(Arc<AtomicUsize>);
+
+impl Drop for InFlightGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
 }
 
 /// Types de nœuds supportés par ArchiveChain
@@ -247,6 +331,9 @@ pub struct NodeConfiguration {
     pub network_config: NetworkConfiguration,
     /// Configuration de sécurité
     pub security_config: SecurityConfiguration,
+    /// Filtre de contenu des Light Storage Nodes, appliqué à chaud par `update_config`
+    #[serde(default)]
+    pub content_filter: Option<ContentFilter>,
 }
 
 /// Configuration du stockage pour un nœud
@@ -282,7 +369,10 @@ pub struct NetworkConfiguration {
 /// Configuration de sécurité
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct SecurityConfiguration {
-    /// Clé privée du nœud
+    /// Clé privée du nœud, lue par le signataire par défaut (`FileSigner`)
+    ///
+    /// Inutilisée quand la clé est détenue par un signataire externe, voir
+    /// `NodeManager::create_node_with_signer`.
     pub private_key_path: String,
     /// Certificat TLS
     pub tls_cert_path: Option<String>,
@@ -292,6 +382,12 @@ pub struct SecurityConfiguration {
     pub trusted_ca_paths: Vec<String>,
     /// Chiffrement des communications requis
     pub require_encryption: bool,
+    /// Tolère les pairs P2P en clair malgré un certificat TLS (migration)
+    #[serde(default)]
+    pub allow_plaintext_fallback: bool,
+    /// Clé maîtresse du chiffrement au repos (à défaut, la clé privée du nœud)
+    #[serde(default)]
+    pub storage_kek_path: Option<String>,
 }
 
 /// Politique de nettoyage du stockage
@@ -324,6 +420,19 @@ pub struct NetworkMessage {
     pub timestamp: chrono::DateTime<chrono::Utc>,
     /// TTL du message
     pub ttl: u32,
+    /// Identifiant de corrélation de la requête API à l'origine du message
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+}
+
+impl NetworkMessage {
+    /// Rattache le message à la requête API en cours de traitement, s'il y en a une
+    pub fn with_current_request_id(mut self) -> Self {
+        if self.request_id.is_none() {
+            self.request_id = crate::api::middleware::current_request_id();
+        }
+        self
+    }
 }
 
 /// Types de messages réseau
@@ -347,6 +456,8 @@ pub enum MessageType {
     ConsensusResponse,
     /// Stockage de contenu
     ContentStore,
+    /// Refus typé d'un stockage (contenu hors spécialisation)
+    ContentRejected,
     /// Récupération de contenu
     ContentRetrieve,
     /// Métadonnées de contenu
@@ -409,7 +520,34 @@ impl Default for SecurityConfiguration {
             tls_key_path: None,
             trusted_ca_paths: Vec::new(),
             require_encryption: false,
+            allow_plaintext_fallback: false,
+            storage_kek_path: None,
+        }
+    }
+}
+
+impl NodeConfiguration {
+    /// Clé maîtresse du chiffrement au repos, si `encryption_enabled`
+    pub fn storage_kek(&self) -> Result<Option<KeyEncryptionKey>> {
+        let enabled = self.storage_config.as_ref().is_some_and(|storage| storage.encryption_enabled);
+        if !enabled {
+            return Ok(None);
         }
+
+        let security = &self.security_config;
+        let path = security.storage_kek_path.as_deref().unwrap_or(&security.private_key_path);
+        KeyEncryptionKey::load(path).map(Some)
+    }
+
+    /// Répertoire des chunks du nœud, propre à son ID sous `data_directory`
+    pub fn chunk_directory(&self) -> Option<String> {
+        self.storage_config.as_ref().map(|storage| {
+            std::path::Path::new(&storage.data_directory)
+                .join("chunks")
+                .join(self.node_id.hash().to_hex())
+                .display()
+                .to_string()
+        })
     }
 }
 
@@ -452,6 +590,23 @@ mod tests {
         assert_eq!(light_storage.to_storage_node_type(), StorageNodeType::LightStorage);
     }
 
+    #[tokio::test]
+    async fn test_in_flight_wait_idle() {
+        let in_flight = InFlight::default();
+        let guard = in_flight.enter();
+        let other = in_flight.clone().enter();
+        assert_eq!(in_flight.count(), 2);
+
+        drop(other);
+        assert_eq!(in_flight.wait_idle(Duration::from_millis(10)).await, 1);
+
+        tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            drop(guard);
+        });
+        assert_eq!(in_flight.wait_idle(Duration::from_secs(5)).await, 0);
+    }
+
     #[test]
     fn test_network_message() {
         let msg = NetworkMessage {
@@ -462,12 +617,39 @@ mod tests {
             payload: vec![1, 2, 3],
             timestamp: chrono::Utc::now(),
             ttl: 60,
+            request_id: None,
         };
         
         assert_eq!(msg.message_type, MessageType::Ping);
         assert_eq!(msg.payload, vec![1, 2, 3]);
     }
 
+    #[tokio::test]
+    async fn test_network_message_carries_request_id() {
+        let msg = NetworkMessage {
+            message_id: Hash::zero(),
+            sender: NodeId::from(Hash::zero()),
+            recipient: None,
+            message_type: MessageType::ContentRetrieve,
+            payload: Vec::new(),
+            timestamp: chrono::Utc::now(),
+            ttl: 60,
+            request_id: None,
+        };
+
+        let tagged = crate::api::middleware::with_request_id("req-42".to_string(), async {
+            msg.clone().with_current_request_id()
+        }).await;
+        assert_eq!(tagged.request_id.as_deref(), Some("req-42"));
+        assert_eq!(msg.with_current_request_id().request_id, None);
+
+        // Les messages d'anciens nœuds, sans identifiant, restent lisibles
+        let mut json = serde_json::to_value(&tagged).unwrap();
+        json.as_object_mut().unwrap().remove("request_id");
+        let decoded: NetworkMessage = serde_json::from_value(json).unwrap();
+        assert_eq!(decoded.request_id, None);
+    }
+
     #[test]
     fn test_default_configurations() {
         let network_config = NetworkConfiguration::default();
@@ -9,12 +9,13 @@
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::path::Path;
 use std::sync::Arc;
 use std::time::{Duration, SystemTime};
 use tokio::sync::{RwLock, Mutex};
 use async_trait::async_trait;
 
-use crate::crypto::{Hash, PublicKey, PrivateKey, generate_keypair};
+use crate::crypto::{compute_blake3, Hash, FileSigner, Signer, generate_keypair};
 use crate::consensus::{NodeId, ProofOfArchive, ConsensusConfig};
 use crate::storage::{
     StorageManager, StorageConfig, StoragePolicy, 
@@ -22,17 +23,22 @@ use crate::storage::{
 };
 use crate::blockchain::{Blockchain, BlockchainConfig};
 use crate::error::Result;
+use crate::shutdown::{ShutdownHook, ShutdownPhase};
+use crate::token::{EconomicModel, SlashingEvent};
 use super::{
     Node, NodeType, NodeConfiguration, NetworkMessage,
     FullArchiveNode, FullArchiveConfig,
     LightStorageNode, LightStorageConfig,
-    RelayNode, RelayNodeConfig,
+    RelayNode, RelayNodeConfig, RelayTransport,
     GatewayNode, GatewayNodeConfig,
     NodeHealth, HealthStatus,
-    health_monitor::{HealthMonitor, HealthMonitorConfig},
-    node_registry::{NodeRegistry, NodeRegistryConfig, NodeInfo},
+    health_monitor::{HealthMonitor, HealthMonitorConfig, RecoveryHandler, RecoveryOutcome},
+    node_registry::{NodeRegistry, NodeRegistryConfig, NodeInfo, NodeFilter, NodeStatus as RegistryNodeStatus},
 };
 
+/// Intervalle de sondage pendant un redémarrage progressif
+const ROLLING_RESTART_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
 /// Configuration du Node Manager
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NodeConfig {
@@ -73,6 +79,13 @@ pub struct ClusterConfig {
     pub auto_scaling: AutoScalingConfig,
     /// Régions géographiques
     pub geographic_regions: Vec<String>,
+    /// Délai accordé à un nœud redémarré pour redevenir sain
+    #[serde(default = "default_restart_health_timeout")]
+    pub restart_health_timeout: Duration,
+}
+
+fn default_restart_health_timeout() -> Duration {
+    Duration::from_secs(120)
 }
 
 /// Stratégies de basculement
@@ -122,6 +135,10 @@ pub struct NodeManagerStats {
     pub resource_utilization: ResourceUtilization,
     /// Événements récents
     pub recent_events: Vec<NodeEvent>,
+    /// Récupérations automatiques tentées
+    pub recoveries_attempted: u64,
+    /// Récupérations automatiques réussies
+    pub recoveries_succeeded: u64,
 }
 
 /// Utilisation des ressources
@@ -192,12 +209,19 @@ pub enum EventSeverity {
     Critical,
 }
 
+/// Nœud géré : les opérations longues ne verrouillent que ce nœud
+type ManagedNode = Arc<RwLock<Box<dyn Node + Send + Sync>>>;
+
 /// Gestionnaire central des nœuds
+///
+/// Les clones partagent le même état : la supervision lancée par
+/// `start_monitoring` en détient un.
+#[derive(Clone)]
 pub struct NodeManager {
     /// Configuration
     config: NodeConfig,
-    /// Nœuds gérés
-    managed_nodes: Arc<RwLock<HashMap<NodeId, Box<dyn Node + Send + Sync>>>>,
+    /// Nœuds gérés, chacun derrière son propre verrou
+    managed_nodes: Arc<RwLock<HashMap<NodeId, ManagedNode>>>,
     /// Registre des nœuds
     node_registry: Arc<Mutex<NodeRegistry>>,
     /// Moniteur de santé
@@ -216,6 +240,15 @@ pub struct NodeManager {
     cluster_start_time: SystemTime,
     /// Tâches de maintenance en cours
     maintenance_tasks: Arc<Mutex<HashMap<NodeId, MaintenanceTask>>>,
+    /// Phase de chaque nœud du dernier redémarrage progressif
+    restart_progress: Arc<RwLock<HashMap<NodeId, RestartPhase>>>,
+    /// Transport des messages relayés par les Relay Nodes créés
+    relay_transport: Option<Arc<dyn RelayTransport>>,
+    /// Modèle économique dont les stakes de validateurs sont slashés pour
+    /// les défis de stockage manqués
+    economics: Option<Arc<std::sync::RwLock<EconomicModel>>>,
+    /// Boucle de supervision lancée par `start_monitoring`
+    monitoring_task: Arc<Mutex<Option<tokio::task::JoinHandle<()>>>>,
 }
 
 /// Tâche de maintenance
@@ -248,6 +281,26 @@ pub enum MaintenanceType {
     Synchronization,
 }
 
+/// Phase d'un nœud pendant un redémarrage progressif
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RestartPhase {
+    /// En attente de son lot
+    Pending,
+    /// Drainage : plus de nouveaux contenus, transferts en cours attendus
+    Draining,
+    /// Arrêt puis redémarrage
+    Restarting,
+    /// Redémarré, en attente d'un health check sain
+    AwaitingHealth,
+    /// Redémarré et sain
+    Completed,
+    /// Échec ; l'opération est interrompue
+    Failed {
+        /// Cause de l'échec
+        reason: String,
+    },
+}
+
 /// Statut de maintenance
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum MaintenanceStatus {
@@ -287,6 +340,7 @@ impl Default for ClusterConfig {
             failover_strategy: FailoverStrategy::Automatic,
             auto_scaling: AutoScalingConfig::default(),
             geographic_regions: vec!["us-east-1".to_string(), "eu-west-1".to_string()],
+            restart_health_timeout: default_restart_health_timeout(),
         }
     }
 }
@@ -351,6 +405,8 @@ impl NodeManager {
                 average_network_latency: Duration::ZERO,
             },
             recent_events: Vec::new(),
+            recoveries_attempted: 0,
+            recoveries_succeeded: 0,
         };
 
         Ok(Self {
@@ -365,13 +421,96 @@ impl NodeManager {
             stats: Arc::new(RwLock::new(initial_stats)),
             cluster_start_time,
             maintenance_tasks: Arc::new(Mutex::new(HashMap::new())),
+            restart_progress: Arc::new(RwLock::new(HashMap::new())),
+            relay_transport: None,
+            economics: None,
+            monitoring_task: Arc::new(Mutex::new(None)),
         })
     }
 
-    /// Crée et enregistre un nouveau nœud
+    /// Fait émettre par `transport` (en général le `P2PManager`) les messages
+    /// relayés par les Relay Nodes créés ensuite
+    pub fn with_relay_transport(mut self, transport: Arc<dyn RelayTransport>) -> Self {
+        self.relay_transport = Some(transport);
+        self
+    }
+
+    /// Slashe les stakes de validateurs de `economics` pour les défis de
+    /// stockage manqués (voir `settle_storage_challenges`)
+    pub fn with_economic_model(mut self, economics: Arc<std::sync::RwLock<EconomicModel>>) -> Self {
+        self.economics = Some(economics);
+        self
+    }
+
+    /// Crée et enregistre un nouveau nœud signant avec sa clé configurée
+    ///
+    /// La clé est lue par un `FileSigner` depuis le `private_key_path` de
+    /// `custom_config`, à défaut de la configuration du type de nœud. Sans
+    /// fichier de clé, une clé éphémère est générée : l'identité du nœud ne
+    /// survit alors pas à un redémarrage.
     pub async fn create_node(&self, node_type: NodeType, custom_config: Option<NodeConfiguration>) -> Result<NodeId> {
-        let keypair = generate_keypair()?;
-        let node_id = NodeId::from_public_key(keypair.public_key());
+        let key_path = match &custom_config {
+            Some(custom) => custom.security_config.private_key_path.clone(),
+            None => self.default_node_configuration(&node_type).security_config.private_key_path.clone(),
+        };
+
+        let signer: Arc<dyn Signer> = if Path::new(&key_path).exists() {
+            Arc::new(FileSigner::load(&key_path)?)
+        } else {
+            tracing::warn!("Clé du nœud {} introuvable, clé éphémère générée", key_path);
+            Arc::new(generate_keypair()?)
+        };
+        self.create_node_with_signer(node_type, custom_config, signer).await
+    }
+
+    /// Configuration par défaut d'un type de nœud
+    fn default_node_configuration(&self, node_type: &NodeType) -> &NodeConfiguration {
+        match node_type {
+            NodeType::FullArchive { .. } => &self.config.full_archive_config.node_config,
+            NodeType::LightStorage { .. } => &self.config.light_storage_config.node_config,
+            NodeType::Relay { .. } => &self.config.relay_config.node_config,
+            NodeType::Gateway { .. } => &self.config.gateway_config.node_config,
+        }
+    }
+
+    /// Configuration du stockage d'un nœud
+    ///
+    /// Chaque nœud conserve ses chunks sous son propre `data_directory`, pour
+    /// que son contenu survive à un redémarrage sans être partagé avec les
+    /// autres nœuds du cluster.
+    fn node_storage_config(&self, node_config: &NodeConfiguration) -> StorageConfig {
+        let mut storage_config = self.config.storage_config.clone();
+        if let Some(chunk_directory) = node_config.chunk_directory() {
+            storage_config.chunk_path = Some(chunk_directory);
+        }
+        storage_config
+    }
+
+    /// Crée et enregistre un nouveau nœud dont la clé est détenue par `signer`
+    ///
+    /// Permet de brancher un signataire externe (HSM, KMS) : la clé privée
+    /// ne passe jamais par le nœud. L'identifiant dérive de sa clé publique.
+    pub async fn create_node_with_signer(
+        &self,
+        node_type: NodeType,
+        custom_config: Option<NodeConfiguration>,
+        signer: Arc<dyn Signer>,
+    ) -> Result<NodeId> {
+        let node_id = NodeId::from_public_key(&signer.public_key());
+        // Deux nœuds partageant une clé auraient la même identité
+        if self.managed_nodes.read().await.contains_key(&node_id) {
+            return Err(crate::error::CoreError::Validation {
+                message: format!("Le nœud {:?} est déjà géré: chaque nœud doit avoir sa propre clé", node_id),
+            });
+        }
+
+        // Le type du nœud plafonne son poids dans l'élection des leaders, et ses
+        // transferts alimentent la preuve de bande passante du cluster
+        let bandwidth_reporter = {
+            let mut consensus = self.consensus_engine.lock().await;
+            consensus.set_declared_weight(node_id.clone(), node_type.minimum_requirements().consensus_weight)?;
+            consensus.bandwidth_reporter(node_id.clone())
+        };
 
         // Crée le nœud selon son type
         let node: Box<dyn Node + Send + Sync> = match node_type {
@@ -389,7 +528,7 @@ impl NodeManager {
                     // Créer une copie du storage manager pour le nœud
                     // Dans une vraie implémentation, on partagerait ou créerait une instance séparée
                     StorageManager::new(
-                        self.config.storage_config.clone(),
+                        self.node_storage_config(&config.node_config),
                         StoragePolicy {
                             default_replication_strategy: ReplicationStrategy::fixed(
                                 self.config.cluster_config.default_replication_factor,
@@ -400,6 +539,9 @@ impl NodeManager {
                         },
                     ).await?
                 };
+                if let Some(kek) = config.node_config.storage_kek()? {
+                    storage_manager.enable_encryption(kek).await?;
+                }
 
                 let blockchain = {
                     let bc = self.blockchain.read().await;
@@ -409,13 +551,15 @@ impl NodeManager {
 
                 let consensus_engine = ProofOfArchive::new(self.config.consensus_config.clone())?;
 
-                Box::new(FullArchiveNode::new(
+                let mut node = FullArchiveNode::new(
                     config,
-                    keypair,
+                    signer,
                     storage_manager,
                     blockchain,
                     consensus_engine,
-                )?)
+                )?;
+                node.set_bandwidth_reporter(bandwidth_reporter);
+                Box::new(node)
             },
             NodeType::LightStorage { .. } => {
                 let mut config = self.config.light_storage_config.clone();
@@ -427,7 +571,7 @@ impl NodeManager {
                 }
 
                 let storage_manager = StorageManager::new(
-                    self.config.storage_config.clone(),
+                    self.node_storage_config(&config.node_config),
                     StoragePolicy {
                         default_replication_strategy: ReplicationStrategy::fixed(
                             self.config.cluster_config.default_replication_factor,
@@ -437,8 +581,13 @@ impl NodeManager {
                         alert_thresholds: AlertThresholds::default(),
                     },
                 ).await?;
+                if let Some(kek) = config.node_config.storage_kek()? {
+                    storage_manager.enable_encryption(kek).await?;
+                }
 
-                Box::new(LightStorageNode::new(config, keypair, storage_manager)?)
+                let mut node = LightStorageNode::new(config, signer, storage_manager)?;
+                node.set_bandwidth_reporter(bandwidth_reporter);
+                Box::new(node)
             },
             NodeType::Relay { .. } => {
                 let mut config = self.config.relay_config.clone();
@@ -449,7 +598,12 @@ impl NodeManager {
                     config.node_config.node_type = node_type.clone();
                 }
 
-                Box::new(RelayNode::new(config, keypair)?)
+                let mut node = RelayNode::new(config, signer)?;
+                if let Some(transport) = &self.relay_transport {
+                    node = node.with_transport(transport.clone());
+                }
+                node.set_bandwidth_reporter(bandwidth_reporter).await;
+                Box::new(node)
             },
             NodeType::Gateway { .. } => {
                 let mut config = self.config.gateway_config.clone();
@@ -460,14 +614,14 @@ impl NodeManager {
                     config.node_config.node_type = node_type.clone();
                 }
 
-                Box::new(GatewayNode::new(config, keypair)?)
+                Box::new(GatewayNode::new(config, signer)?)
             },
         };
 
         // Enregistre le nœud
         {
             let mut nodes = self.managed_nodes.write().await;
-            nodes.insert(node_id.clone(), node);
+            nodes.insert(node_id.clone(), Arc::new(RwLock::new(node)));
         }
 
         // Enregistre dans le registre
@@ -518,9 +672,14 @@ impl NodeManager {
 
     /// Démarre un nœud
     pub async fn start_node(&self, node_id: &NodeId) -> Result<()> {
-        let mut nodes = self.managed_nodes.write().await;
-        if let Some(node) = nodes.get_mut(node_id) {
-            node.start().await?;
+        let found = match self.managed_node(node_id).await {
+            Some(node) => {
+                node.write().await.start().await?;
+                true
+            }
+            None => false,
+        };
+        if found {
 
             // Enregistre l'événement
             self.log_event(NodeEvent {
@@ -544,10 +703,14 @@ impl NodeManager {
 
     /// Arrête un nœud
     pub async fn stop_node(&self, node_id: &NodeId) -> Result<()> {
-        let mut nodes = self.managed_nodes.write().await;
-        if let Some(node) = nodes.get_mut(node_id) {
-            node.stop().await?;
-
+        let found = match self.managed_node(node_id).await {
+            Some(node) => {
+                node.write().await.stop().await?;
+                true
+            }
+            None => false,
+        };
+        if found {
             // Enregistre l'événement
             self.log_event(NodeEvent {
                 timestamp: chrono::Utc::now(),
@@ -609,13 +772,72 @@ impl NodeManager {
         Ok(())
     }
 
+    /// Redémarre par lots les nœuds gérés correspondant au filtre
+    ///
+    /// Chaque lot est drainé (statut `Maintenance` au registre, plus de nouvelles
+    /// requêtes, requêtes en cours attendues au plus `drain_timeout`, contenus
+    /// re-répliqués ailleurs, voir [`Node::drain`]), redémarré,
+    /// puis chacun de ses nœuds doit redevenir `Healthy` dans
+    /// `restart_health_timeout` avant le lot suivant : au plus `batch_size` nœuds
+    /// sont indisponibles à la fois. Un nœud qui ne redevient pas sain interrompt
+    /// l'opération avec une erreur ; les lots suivants restent `Pending`.
+    pub async fn rolling_restart(&self, filter: NodeFilter, batch_size: usize, drain_timeout: Duration) -> Result<()> {
+        if batch_size == 0 {
+            return Err(crate::error::CoreError::Validation {
+                message: "La taille des lots doit être non nulle".to_string(),
+            });
+        }
+
+        let targets: Vec<NodeId> = {
+            let candidates = self.node_registry.lock().await.find_nodes(filter).await;
+            let nodes = self.managed_nodes.read().await;
+            candidates.into_iter()
+                .map(|info| info.node_id)
+                .filter(|node_id| nodes.contains_key(node_id))
+                .collect()
+        };
+        *self.restart_progress.write().await = targets.iter()
+            .map(|node_id| (node_id.clone(), RestartPhase::Pending))
+            .collect();
+        tracing::info!("Redémarrage progressif de {} nœuds par lots de {}", targets.len(), batch_size);
+
+        for batch in targets.chunks(batch_size) {
+            for node_id in batch {
+                self.drain_node(node_id, drain_timeout).await?;
+            }
+
+            for node_id in batch {
+                self.set_restart_phase(node_id, RestartPhase::Restarting).await;
+                let restarted = match self.stop_node(node_id).await {
+                    Ok(()) => self.start_node(node_id).await,
+                    Err(e) => Err(e),
+                };
+                if let Err(e) = restarted {
+                    return Err(self.fail_restart(node_id, format!("Redémarrage impossible: {}", e)).await);
+                }
+            }
+
+            for node_id in batch {
+                self.set_restart_phase(node_id, RestartPhase::AwaitingHealth).await;
+                self.await_healthy(node_id).await?;
+                self.complete_restart(node_id).await?;
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Phase de chaque nœud du dernier redémarrage progressif
+    pub async fn rolling_restart_progress(&self) -> HashMap<NodeId, RestartPhase> {
+        self.restart_progress.read().await.clone()
+    }
+
     /// Effectue un health check sur tous les nœuds
     pub async fn health_check_all_nodes(&self) -> Result<HashMap<NodeId, NodeHealth>> {
         let mut health_results = HashMap::new();
-        let nodes = self.managed_nodes.read().await;
 
-        for (node_id, node) in nodes.iter() {
-            match node.health_check().await {
+        for (node_id, node) in self.managed_node_handles().await {
+            match node.read().await.health_check().await {
                 Ok(health) => {
                     health_results.insert(node_id.clone(), health);
                 },
@@ -637,6 +859,118 @@ impl NodeManager {
         Ok(health_results)
     }
 
+    /// Applique la politique de récupération automatique à tous les nœuds gérés
+    ///
+    /// Les nœuds critiques sont redémarrés selon la politique du `HealthMonitor` ;
+    /// ceux dont les redémarrages sont épuisés voient leur contenu replanifié.
+    pub async fn run_auto_recovery(&self) -> Result<HashMap<NodeId, RecoveryOutcome>> {
+        let node_ids = self.get_managed_nodes().await;
+        let mut outcomes = HashMap::new();
+
+        for node_id in node_ids {
+            let outcome = {
+                let monitor = self.health_monitor.lock().await;
+                monitor.supervise_node(&node_id, self).await?
+            };
+
+            match &outcome {
+                RecoveryOutcome::Recovered => self.log_event(NodeEvent {
+                    timestamp: chrono::Utc::now(),
+                    node_id: node_id.clone(),
+                    event_type: NodeEventType::NodeRecovered,
+                    message: "Nœud récupéré automatiquement".to_string(),
+                    severity: EventSeverity::Info,
+                }).await,
+                RecoveryOutcome::Exhausted { rescheduled_replicas } => self.log_event(NodeEvent {
+                    timestamp: chrono::Utc::now(),
+                    node_id: node_id.clone(),
+                    event_type: NodeEventType::NodeFailed,
+                    message: format!("Récupération abandonnée, {} répliques replanifiées", rescheduled_replicas),
+                    severity: EventSeverity::Critical,
+                }).await,
+                _ => {}
+            }
+
+            outcomes.insert(node_id, outcome);
+        }
+
+        let monitoring = self.health_monitor.lock().await.get_monitoring_stats().await;
+        {
+            let mut stats = self.stats.write().await;
+            stats.recoveries_attempted = monitoring.recoveries_attempted;
+            stats.recoveries_succeeded = monitoring.recoveries_successful;
+        }
+
+        Ok(outcomes)
+    }
+
+    /// Règle les défis de stockage terminés
+    ///
+    /// Les défis restés sans réponse sont consignés comme expirés, puis les
+    /// issues enregistrées depuis le dernier règlement sont répercutées sur la
+    /// réputation des nœuds du registre et appliquées aux stakes du modèle
+    /// économique rattaché (`with_economic_model`) : un validateur qui manque
+    /// trop de défis est slashé. Retourne les slashings.
+    pub async fn settle_storage_challenges(&self) -> Result<Vec<SlashingEvent>> {
+        let outcomes = {
+            let mut consensus = self.consensus_engine.lock().await;
+            consensus.expire_storage_challenges(chrono::Utc::now())?;
+            consensus.take_challenge_outcomes()
+        };
+        if !outcomes.is_empty() {
+            self.node_registry.lock().await.apply_challenge_outcomes(&outcomes).await;
+        }
+        let Some(economics) = &self.economics else {
+            return Ok(Vec::new());
+        };
+        if outcomes.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        // Les slashings d'un règlement sont rattachés aux défis qui les motivent
+        let challenge_ids: Vec<u8> = outcomes.iter()
+            .flat_map(|entry| entry.challenge_id.as_bytes().to_vec())
+            .collect();
+        let settlement_hash = compute_blake3(&challenge_ids);
+
+        let mut economics = economics.write().unwrap_or_else(|poisoned| poisoned.into_inner());
+        economics.apply_storage_challenge_outcomes(&outcomes, settlement_hash)
+            .map_err(|e| crate::error::CoreError::Internal {
+                message: format!("Slashing des défis de stockage manqués impossible: {}", e),
+            })
+    }
+
+    /// Lance la supervision périodique du cluster
+    ///
+    /// À chaque `health_monitor_config.check_interval`, la politique de
+    /// récupération automatique est appliquée puis les défis de stockage
+    /// terminés sont réglés. La supervision s'arrête avec `stop_all_nodes`.
+    pub async fn start_monitoring(&self) -> Result<()> {
+        let manager = self.clone();
+        let check_interval = self.config.health_monitor_config.check_interval;
+        let task = tokio::spawn(async move {
+            let mut interval = tokio::time::interval(check_interval);
+            loop {
+                interval.tick().await;
+                if let Err(e) = manager.run_auto_recovery().await {
+                    tracing::warn!("Récupération automatique échouée: {}", e);
+                }
+                match manager.settle_storage_challenges().await {
+                    Ok(events) if !events.is_empty() => tracing::warn!(
+                        "{} validateur(s) slashé(s) pour défis de stockage manqués", events.len()
+                    ),
+                    Ok(_) => {}
+                    Err(e) => tracing::error!("Règlement des défis de stockage échoué: {}", e),
+                }
+            }
+        });
+
+        if let Some(previous) = self.monitoring_task.lock().await.replace(task) {
+            previous.abort();
+        }
+        Ok(())
+    }
+
     /// Gère le basculement automatique
     pub async fn handle_node_failure(&self, failed_node_id: &NodeId) -> Result<()> {
         if self.config.cluster_config.failover_strategy != FailoverStrategy::Automatic {
@@ -702,8 +1036,9 @@ impl NodeManager {
             },
         };
 
-        // Crée le nœud de remplacement
-        let replacement_id = self.create_node(replacement_node_type, None).await?;
+        // Crée le nœud de remplacement, sous une nouvelle identité
+        let signer: Arc<dyn Signer> = Arc::new(generate_keypair()?);
+        let replacement_id = self.create_node_with_signer(replacement_node_type, None, signer).await?;
         
         // Démarre le nouveau nœud
         self.start_node(&replacement_id).await?;
@@ -721,9 +1056,116 @@ impl NodeManager {
         Ok(replacement_id)
     }
 
+    /// Met un nœud en maintenance et le draine avant son arrêt
+    async fn drain_node(&self, node_id: &NodeId, drain_timeout: Duration) -> Result<()> {
+        self.set_restart_phase(node_id, RestartPhase::Draining).await;
+        self.maintenance_tasks.lock().await.insert(node_id.clone(), MaintenanceTask {
+            node_id: node_id.clone(),
+            task_type: MaintenanceType::Restart,
+            started_at: SystemTime::now(),
+            estimated_duration: drain_timeout + self.config.cluster_config.restart_health_timeout,
+            status: MaintenanceStatus::InProgress,
+        });
+        self.set_registry_status(node_id, RegistryNodeStatus::Maintenance).await?;
+        self.log_event(NodeEvent {
+            timestamp: chrono::Utc::now(),
+            node_id: node_id.clone(),
+            event_type: NodeEventType::MaintenanceStarted,
+            message: "Drainage avant redémarrage progressif".to_string(),
+            severity: EventSeverity::Info,
+        }).await;
+
+        // Seul le verrou du nœud drainé est tenu pendant l'attente
+        let report = match self.managed_node(node_id).await {
+            Some(node) => node.read().await.drain(drain_timeout).await?,
+            None => return Ok(()),
+        };
+        if report.is_complete() {
+            tracing::info!(
+                "Nœud {:?} drainé, {} réplique(s) copiée(s) ailleurs",
+                node_id, report.replication.jobs_completed
+            );
+        } else {
+            tracing::warn!(
+                "Drainage incomplet du nœud {:?}: {} transfert(s) en cours, {}/{} copie(s), {} contenu(s) sans cible",
+                node_id, report.in_flight_remaining, report.replication.jobs_completed,
+                report.replication.jobs_scheduled, report.replication.unrecoverable
+            );
+        }
+        Ok(())
+    }
+
+    /// Attend qu'un nœud redémarré redevienne sain
+    async fn await_healthy(&self, node_id: &NodeId) -> Result<()> {
+        let timeout = self.config.cluster_config.restart_health_timeout;
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            if let Ok(health) = self.check_health(node_id).await {
+                if health.status == HealthStatus::Healthy {
+                    return Ok(());
+                }
+            }
+            if tokio::time::Instant::now() >= deadline {
+                let reason = format!("Nœud non sain {:?} après son redémarrage", timeout);
+                return Err(self.fail_restart(node_id, reason).await);
+            }
+            tokio::time::sleep(ROLLING_RESTART_POLL_INTERVAL).await;
+        }
+    }
+
+    /// Remet en service un nœud redémarré
+    async fn complete_restart(&self, node_id: &NodeId) -> Result<()> {
+        self.set_registry_status(node_id, RegistryNodeStatus::Active).await?;
+        if let Some(task) = self.maintenance_tasks.lock().await.get_mut(node_id) {
+            task.status = MaintenanceStatus::Completed;
+        }
+        self.set_restart_phase(node_id, RestartPhase::Completed).await;
+        self.log_event(NodeEvent {
+            timestamp: chrono::Utc::now(),
+            node_id: node_id.clone(),
+            event_type: NodeEventType::MaintenanceCompleted,
+            message: "Nœud redémarré et sain".to_string(),
+            severity: EventSeverity::Info,
+        }).await;
+        Ok(())
+    }
+
+    /// Interrompt un redémarrage progressif ; le nœud reste en maintenance
+    async fn fail_restart(&self, node_id: &NodeId, reason: String) -> crate::error::CoreError {
+        if let Some(task) = self.maintenance_tasks.lock().await.get_mut(node_id) {
+            task.status = MaintenanceStatus::Failed;
+        }
+        self.set_restart_phase(node_id, RestartPhase::Failed { reason: reason.clone() }).await;
+        self.log_event(NodeEvent {
+            timestamp: chrono::Utc::now(),
+            node_id: node_id.clone(),
+            event_type: NodeEventType::NodeFailed,
+            message: format!("Redémarrage progressif interrompu: {}", reason),
+            severity: EventSeverity::Error,
+        }).await;
+
+        crate::error::CoreError::Internal {
+            message: format!("Redémarrage progressif interrompu au nœud {:?}: {}", node_id, reason),
+        }
+    }
+
+    async fn set_restart_phase(&self, node_id: &NodeId, phase: RestartPhase) {
+        self.restart_progress.write().await.insert(node_id.clone(), phase);
+    }
+
+    /// Annonce le statut d'un nœud au registre
+    async fn set_registry_status(&self, node_id: &NodeId, status: RegistryNodeStatus) -> Result<()> {
+        let mut registry = self.node_registry.lock().await;
+        let Some(mut info) = registry.get_node_info(node_id).await? else {
+            return Ok(());
+        };
+        info.status = status;
+        registry.update_node_info(node_id, info).await
+    }
+
     /// Met à jour les statistiques du cluster
     async fn update_stats(&self) -> Result<()> {
-        let nodes = self.managed_nodes.read().await;
+        let nodes = self.managed_node_handles().await;
         let mut stats = self.stats.write().await;
 
         // Compte les nœuds par type
@@ -732,7 +1174,8 @@ impl NodeManager {
         let mut maintenance_nodes = 0;
         let mut failed_nodes = 0;
 
-        for (node_id, node) in nodes.iter() {
+        for (node_id, node) in &nodes {
+            let node = node.read().await;
             let node_type = format!("{:?}", node.node_type());
             *nodes_per_type.entry(node_type).or_insert(0) += 1;
 
@@ -793,6 +1236,24 @@ impl NodeManager {
         stats.clone()
     }
 
+    /// Nœud géré, sans garder le verrou de la table
+    async fn managed_node(&self, node_id: &NodeId) -> Option<ManagedNode> {
+        self.managed_nodes.read().await.get(node_id).cloned()
+    }
+
+    /// Nœuds gérés à cet instant, sans garder le verrou de la table
+    async fn managed_node_handles(&self) -> Vec<(NodeId, ManagedNode)> {
+        self.managed_nodes.read().await
+            .iter()
+            .map(|(node_id, node)| (node_id.clone(), node.clone()))
+            .collect()
+    }
+
+    /// Chaîne du cluster, à suivre par l'API (`ServerBuilder::with_live_chain`)
+    pub fn blockchain(&self) -> Arc<RwLock<Blockchain>> {
+        self.blockchain.clone()
+    }
+
     /// Obtient les nœuds gérés
     pub async fn get_managed_nodes(&self) -> Vec<NodeId> {
         let nodes = self.managed_nodes.read().await;
@@ -817,6 +1278,10 @@ impl NodeManager {
 
     /// Arrête tous les nœuds
     pub async fn stop_all_nodes(&self) -> Result<()> {
+        if let Some(task) = self.monitoring_task.lock().await.take() {
+            task.abort();
+        }
+
         let node_ids: Vec<NodeId> = {
             let nodes = self.managed_nodes.read().await;
             nodes.keys().cloned().collect()
@@ -828,10 +1293,62 @@ impl NodeManager {
             }
         }
 
+        // Conserve les pairs connus pour le prochain démarrage
+        let registry = self.node_registry.lock().await;
+        if let Err(e) = registry.persist().await {
+            tracing::warn!("Sauvegarde du registre impossible: {}", e);
+        }
+
+        Ok(())
+    }
+
+    /// Sauvegarde le pool de transactions
+    ///
+    /// Le verrou d'écriture attend la fin d'un éventuel ajout de bloc en cours :
+    /// la chaîne n'est jamais sauvegardée au milieu d'une écriture.
+    pub async fn flush_pending_writes(&self) -> Result<()> {
+        let mut blockchain = self.blockchain.write().await;
+        blockchain.flush_transaction_pool()?;
         Ok(())
     }
 }
 
+#[async_trait]
+impl ShutdownHook for NodeManager {
+    fn name(&self) -> &str {
+        "nodes"
+    }
+
+    async fn on_shutdown(&self, phase: ShutdownPhase) -> Result<()> {
+        match phase {
+            ShutdownPhase::Flush => self.flush_pending_writes().await,
+            // Arrête les nœuds gérés et sauvegarde le registre
+            ShutdownPhase::Persist => self.stop_all_nodes().await,
+            _ => Ok(()),
+        }
+    }
+}
+
+#[async_trait]
+impl RecoveryHandler for NodeManager {
+    async fn check_health(&self, node_id: &NodeId) -> Result<NodeHealth> {
+        let node = self.managed_node(node_id).await.ok_or_else(|| crate::error::CoreError::NotFound {
+            message: format!("Nœud {:?} non trouvé", node_id),
+        })?;
+        let health = node.read().await.health_check().await;
+        health
+    }
+
+    async fn restart_node(&self, node_id: &NodeId) -> Result<()> {
+        self.start_node(node_id).await
+    }
+
+    async fn reschedule_content(&self, node_id: &NodeId) -> Result<u32> {
+        let storage = self.storage_manager.lock().await;
+        storage.reschedule_node_content(node_id).await
+    }
+}
+
 impl NodeConfig {
     /// Valide la configuration
     pub fn validate(&self) -> Result<()> {
@@ -933,6 +1450,29 @@ mod tests {
         assert!(node_manager.stop_node(&node_id).await.is_ok());
     }
 
+    #[tokio::test]
+    async fn test_create_node_signs_with_configured_key() {
+        let node_manager = NodeManager::new(NodeConfig::default()).await.unwrap();
+        let keypair = generate_keypair().unwrap();
+        let dir = tempfile::tempdir().unwrap();
+        let key_path = dir.path().join("node.key");
+        std::fs::write(&key_path, keypair.private_key().to_hex()).unwrap();
+
+        let node_type = NodeType::Relay {
+            bandwidth_capacity: 1_000_000_000,
+            max_connections: 1000,
+        };
+        let mut node_config = node_manager.config.relay_config.node_config.clone();
+        node_config.security_config.private_key_path = key_path.display().to_string();
+
+        let node_id = node_manager.create_node(node_type.clone(), Some(node_config.clone())).await.unwrap();
+        assert_eq!(node_id, NodeId::from_public_key(keypair.public_key()));
+        assert_eq!(node_manager.consensus_engine.lock().await.declared_weight(&node_id), 0.3);
+
+        // La même clé ne peut pas servir à un second nœud
+        assert!(node_manager.create_node(node_type, Some(node_config)).await.is_err());
+    }
+
     #[test]
     fn test_maintenance_task() {
         let task = MaintenanceTask {
@@ -960,4 +1500,163 @@ mod tests {
         assert_eq!(event.event_type, NodeEventType::NodeStarted);
         assert_eq!(event.severity, EventSeverity::Info);
     }
-}
\ No newline at end of file
+
+    /// Nœud simulé comptant les nœuds arrêtés simultanément
+    struct MockNode {
+        node_id: NodeId,
+        running: bool,
+        /// Redevient sain après redémarrage
+        recovers: bool,
+        down: Arc<std::sync::atomic::AtomicUsize>,
+        max_down: Arc<std::sync::atomic::AtomicUsize>,
+    }
+
+    #[async_trait]
+    impl Node for MockNode {
+        fn node_type(&self) -> NodeType {
+            NodeType::Relay { bandwidth_capacity: 1_000_000, max_connections: 10 }
+        }
+
+        fn node_id(&self) -> &NodeId {
+            &self.node_id
+        }
+
+        async fn start(&mut self) -> Result<()> {
+            self.running = true;
+            self.down.fetch_sub(1, std::sync::atomic::Ordering::SeqCst);
+            Ok(())
+        }
+
+        async fn stop(&mut self) -> Result<()> {
+            self.running = false;
+            let down = self.down.fetch_add(1, std::sync::atomic::Ordering::SeqCst) + 1;
+            self.max_down.fetch_max(down, std::sync::atomic::Ordering::SeqCst);
+            Ok(())
+        }
+
+        async fn health_check(&self) -> Result<NodeHealth> {
+            Ok(NodeHealth {
+                status: if self.running && self.recovers { HealthStatus::Healthy } else { HealthStatus::Critical },
+                uptime: Duration::ZERO,
+                cpu_usage: 0.0,
+                memory_usage: 0.0,
+                storage_usage: 0.0,
+                network_latency: Duration::ZERO,
+                error_rate: 0.0,
+                last_check: SystemTime::now(),
+            })
+        }
+
+        async fn get_metrics(&self) -> Result<Box<dyn super::super::NodeMetrics>> {
+            Err(crate::error::CoreError::Internal { message: "non simulé".to_string() })
+        }
+
+        async fn handle_message(&mut self, _message: NetworkMessage) -> Result<Option<NetworkMessage>> {
+            Ok(None)
+        }
+
+        async fn sync_with_network(&mut self) -> Result<()> {
+            Ok(())
+        }
+
+        async fn update_config(&mut self, _config: NodeConfiguration) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Cluster de nœuds simulés ; l'ordre de redémarrage suit les identifiants
+    async fn mock_cluster(count: u8, unhealthy: Option<u8>) -> (NodeManager, Vec<NodeId>, Arc<std::sync::atomic::AtomicUsize>) {
+        let mut config = NodeConfig::default();
+        config.cluster_config.restart_health_timeout = Duration::from_millis(300);
+        let manager = NodeManager::new(config).await.unwrap();
+        let down = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let max_down = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+
+        let mut node_ids = Vec::new();
+        for i in 1..=count {
+            let node_id = NodeId::from(Hash::new([i; 32]));
+            let node: Box<dyn Node + Send + Sync> = Box::new(MockNode {
+                node_id: node_id.clone(),
+                running: true,
+                recovers: unhealthy != Some(i),
+                down: down.clone(),
+                max_down: max_down.clone(),
+            });
+            manager.managed_nodes.write().await.insert(node_id.clone(), Arc::new(RwLock::new(node)));
+            manager.node_registry.lock().await.register_node(NodeInfo {
+                node_id: node_id.clone(),
+                node_type: super::super::node_registry::NodeType::Relay,
+                address: format!("10.0.0.{}:9000", i),
+                region: "eu-west-1".to_string(),
+                capabilities: super::super::node_registry::NodeCapabilities {
+                    storage_capacity: 0,
+                    bandwidth_capacity: 1_000_000,
+                    consensus_weight: 0.3,
+                    api_endpoints: Vec::new(),
+                },
+                status: RegistryNodeStatus::Active,
+                registered_at: chrono::Utc::now(),
+                last_heartbeat: chrono::Utc::now(),
+                performance_metrics: super::super::node_registry::PerformanceMetrics {
+                    cpu_usage: 0.0,
+                    memory_usage: 0.0,
+                    storage_usage: 0.0,
+                    network_latency: Duration::ZERO,
+                    uptime: Duration::ZERO,
+                },
+            }).await.unwrap();
+            node_ids.push(node_id);
+        }
+
+        (manager, node_ids, max_down)
+    }
+
+    #[tokio::test]
+    async fn test_rolling_restart_completes_all_batches() {
+        let (manager, node_ids, _) = mock_cluster(5, None).await;
+
+        manager.rolling_restart(NodeFilter::default(), 2, Duration::from_millis(50)).await.unwrap();
+
+        let progress = manager.rolling_restart_progress().await;
+        assert_eq!(progress.len(), 5);
+        assert!(progress.values().all(|phase| *phase == RestartPhase::Completed));
+
+        let registry = manager.node_registry.lock().await;
+        for node_id in &node_ids {
+            let info = registry.get_node_info(node_id).await.unwrap().unwrap();
+            assert_eq!(info.status, RegistryNodeStatus::Active);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rolling_restart_stops_at_unhealthy_node() {
+        let (manager, node_ids, _) = mock_cluster(3, Some(2)).await;
+
+        let result = manager.rolling_restart(NodeFilter::default(), 1, Duration::from_millis(50)).await;
+        assert!(result.is_err());
+
+        let progress = manager.rolling_restart_progress().await;
+        assert_eq!(progress[&node_ids[0]], RestartPhase::Completed);
+        assert!(matches!(progress[&node_ids[1]], RestartPhase::Failed { .. }));
+        assert_eq!(progress[&node_ids[2]], RestartPhase::Pending);
+
+        // Le nœud défaillant reste en maintenance, le suivant n'a pas été touché
+        let registry = manager.node_registry.lock().await;
+        assert_eq!(registry.get_node_info(&node_ids[1]).await.unwrap().unwrap().status, RegistryNodeStatus::Maintenance);
+        assert_eq!(registry.get_node_info(&node_ids[2]).await.unwrap().unwrap().status, RegistryNodeStatus::Active);
+    }
+
+    #[tokio::test]
+    async fn test_rolling_restart_respects_batch_size() {
+        let (manager, _, max_down) = mock_cluster(7, None).await;
+
+        manager.rolling_restart(NodeFilter::default(), 3, Duration::from_millis(50)).await.unwrap();
+        assert_eq!(max_down.load(std::sync::atomic::Ordering::SeqCst), 3);
+
+        // Un filtre sans correspondance ne redémarre rien
+        let filter = NodeFilter { region: Some("ap-south-1".to_string()), ..NodeFilter::default() };
+        manager.rolling_restart(filter, 3, Duration::from_millis(50)).await.unwrap();
+        assert!(manager.rolling_restart_progress().await.is_empty());
+        assert!(manager.rolling_restart(NodeFilter::default(), 0, Duration::ZERO).await.is_err());
+    }
+}
@@ -9,15 +9,25 @@
 
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, HashSet};
+use std::path::Path;
 use std::sync::Arc;
 use std::time::{Duration, SystemTime};
 use tokio::sync::{RwLock, Mutex};
 
 use crate::crypto::{Hash, PublicKey};
-use crate::consensus::NodeId;
-use crate::error::Result;
+use crate::consensus::{NodeId, ChallengeAuditEntry, ChallengeOutcome};
+use crate::error::{Result, SerializationError};
 use super::ApiType;
 
+/// Facteur de décroissance de la disponibilité par heartbeat manqué
+const AVAILABILITY_DECAY_PER_MISSED_HEARTBEAT: f64 = 0.9;
+
+/// Facteur appliqué à la fiabilité pour chaque défi de stockage échoué ou expiré
+const RELIABILITY_PENALTY_PER_FAILED_CHALLENGE: f64 = 0.8;
+
+/// Poids d'un défi de stockage réussi dans la moyenne mobile de fiabilité
+const RELIABILITY_GAIN_PER_PASSED_CHALLENGE: f64 = 0.05;
+
 /// Configuration du Node Registry
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NodeRegistryConfig {
@@ -195,6 +205,30 @@ pub enum DiscoveryEventType {
     TimeoutDetected,
 }
 
+/// Filtre de recherche de nœuds
+#[derive(Debug, Clone, Default)]
+pub struct NodeFilter {
+    /// Type de nœud requis
+    pub node_type: Option<NodeType>,
+    /// Région requise
+    pub region: Option<String>,
+    /// Score de réputation minimum
+    pub min_reputation: Option<f64>,
+    /// Capacité de stockage libre minimale (bytes)
+    pub min_free_capacity: Option<u64>,
+    /// Statut requis
+    pub status: Option<NodeStatus>,
+}
+
+/// Contenu persisté du registre
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct RegistrySnapshot {
+    /// Nœuds connus
+    nodes: Vec<NodeInfo>,
+    /// Scores de réputation associés
+    reputation_scores: Vec<(NodeId, ReputationScore)>,
+}
+
 /// Registre distribué des nœuds
 pub struct NodeRegistry {
     /// Configuration
@@ -251,6 +285,23 @@ impl Default for NodeRegistryConfig {
     }
 }
 
+impl NodeCapabilities {
+    /// Capacité de stockage encore libre selon le taux d'utilisation
+    pub fn free_storage(&self, storage_usage: f64) -> u64 {
+        let free_ratio = (1.0 - storage_usage).clamp(0.0, 1.0);
+        (self.storage_capacity as f64 * free_ratio) as u64
+    }
+}
+
+impl ReputationScore {
+    /// Recalcule le score global à partir des composantes
+    fn recompute_overall(&mut self) {
+        self.overall_score = (self.performance_score * 0.4 +
+                              self.reliability_score * 0.3 +
+                              self.availability_score * 0.3).min(1.0);
+    }
+}
+
 impl NodeRegistry {
     /// Crée un nouveau registre de nœuds
     pub async fn new(config: NodeRegistryConfig) -> Result<Self> {
@@ -465,10 +516,11 @@ impl NodeRegistry {
         let reliability_factor = (metrics.uptime.as_secs() as f64 / 86400.0).min(1.0); // Max 1 jour
         reputation.reliability_score = alpha * reliability_factor + (1.0 - alpha) * reputation.reliability_score;
 
+        // Un heartbeat reçu restaure progressivement la disponibilité
+        reputation.availability_score = alpha * 1.0 + (1.0 - alpha) * reputation.availability_score;
+
         // Score global combiné
-        reputation.overall_score = (reputation.performance_score * 0.4 + 
-                                   reputation.reliability_score * 0.3 + 
-                                   reputation.availability_score * 0.3).min(1.0);
+        reputation.recompute_overall();
 
         // Enregistre dans l'historique
         reputation.score_history.push(HistoricalScore {
@@ -528,8 +580,71 @@ impl NodeRegistry {
         Ok(discovered)
     }
 
+    /// Fait décroître la réputation des nœuds ayant manqué des heartbeats
+    ///
+    /// Chaque intervalle de heartbeat écoulé sans signe de vie réduit la
+    /// disponibilité ; retourne le nombre de nœuds pénalisés.
+    pub async fn decay_reputations(&self, now: chrono::DateTime<chrono::Utc>) -> u32 {
+        let interval = match chrono::Duration::from_std(self.config.heartbeat_interval) {
+            Ok(interval) if interval > chrono::Duration::zero() => interval,
+            _ => return 0,
+        };
+
+        let nodes = self.registered_nodes.read().await;
+        let mut scores = self.reputation_scores.write().await;
+        let mut decayed = 0;
+
+        for (node_id, node_info) in nodes.iter() {
+            let Some(reputation) = scores.get_mut(node_id) else { continue };
+
+            // Les heartbeats déjà pénalisés ne le sont pas deux fois
+            let reference = node_info.last_heartbeat.max(reputation.last_updated);
+            let missed = (now - reference).num_milliseconds() / interval.num_milliseconds();
+            if missed <= 0 {
+                continue;
+            }
+
+            reputation.availability_score *= AVAILABILITY_DECAY_PER_MISSED_HEARTBEAT.powi(missed as i32);
+            reputation.recompute_overall();
+            reputation.last_updated = reference + interval * missed as i32;
+            decayed += 1;
+        }
+
+        decayed
+    }
+
+    /// Répercute les issues des défis de stockage sur la réputation des nœuds
+    ///
+    /// Un défi échoué ou expiré réduit la fiabilité, un défi réussi la rapproche
+    /// de 1.0 ; retourne le nombre d'entrées appliquées à un nœud connu.
+    pub async fn apply_challenge_outcomes(&self, entries: &[ChallengeAuditEntry]) -> u32 {
+        let mut scores = self.reputation_scores.write().await;
+        let mut applied = 0;
+
+        for entry in entries {
+            let Some(reputation) = scores.get_mut(&entry.node_id) else { continue };
+
+            reputation.reliability_score = match entry.outcome {
+                ChallengeOutcome::Passed => {
+                    reputation.reliability_score * (1.0 - RELIABILITY_GAIN_PER_PASSED_CHALLENGE)
+                        + RELIABILITY_GAIN_PER_PASSED_CHALLENGE
+                }
+                ChallengeOutcome::Failed | ChallengeOutcome::TimedOut => {
+                    reputation.reliability_score * RELIABILITY_PENALTY_PER_FAILED_CHALLENGE
+                }
+            };
+            reputation.recompute_overall();
+            reputation.interaction_count += 1;
+            applied += 1;
+        }
+
+        applied
+    }
+
     /// Nettoie les nœuds inactifs
     pub async fn cleanup_inactive_nodes(&mut self) -> Result<u32> {
+        self.decay_reputations(chrono::Utc::now()).await;
+
         let mut removed_count = 0;
         let timeout_threshold = SystemTime::now() - self.config.node_timeout;
         let mut nodes_to_remove = Vec::new();
@@ -611,6 +726,142 @@ impl NodeRegistry {
         geo_index.clone()
     }
 
+    /// Recherche les nœuds correspondant à un filtre
+    ///
+    /// Les résultats sont triés par réputation décroissante puis par
+    /// identifiant pour un ordre stable.
+    pub async fn find_nodes(&self, filter: NodeFilter) -> Vec<NodeInfo> {
+        let nodes = self.registered_nodes.read().await;
+        let scores = self.reputation_scores.read().await;
+
+        let mut matches: Vec<(f64, &NodeInfo)> = nodes.iter()
+            .filter_map(|(node_id, node)| {
+                let reputation = scores.get(node_id)
+                    .map(|s| s.overall_score)
+                    .unwrap_or(0.5);
+
+                if filter.node_type.as_ref().map_or(false, |t| &node.node_type != t) {
+                    return None;
+                }
+                if filter.region.as_ref().map_or(false, |r| &node.region != r) {
+                    return None;
+                }
+                if filter.status.as_ref().map_or(false, |s| &node.status != s) {
+                    return None;
+                }
+                if filter.min_reputation.map_or(false, |min| reputation < min) {
+                    return None;
+                }
+                if let Some(min_free) = filter.min_free_capacity {
+                    let free = node.capabilities.free_storage(node.performance_metrics.storage_usage);
+                    if free < min_free {
+                        return None;
+                    }
+                }
+
+                Some((reputation, node))
+            })
+            .collect();
+
+        matches.sort_by(|a, b| {
+            b.0.partial_cmp(&a.0)
+                .unwrap_or(std::cmp::Ordering::Equal)
+                .then_with(|| a.1.node_id.0.as_bytes().cmp(b.1.node_id.0.as_bytes()))
+        });
+
+        matches.into_iter().map(|(_, node)| node.clone()).collect()
+    }
+
+    /// Sauvegarde le registre sur disque (JSON)
+    pub async fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let snapshot = {
+            let nodes = self.registered_nodes.read().await;
+            let scores = self.reputation_scores.read().await;
+            RegistrySnapshot {
+                nodes: nodes.values().cloned().collect(),
+                reputation_scores: scores.iter()
+                    .map(|(id, score)| (id.clone(), score.clone()))
+                    .collect(),
+            }
+        };
+
+        let data = serde_json::to_vec_pretty(&snapshot).map_err(SerializationError::from)?;
+
+        // Écriture atomique via un fichier temporaire
+        let path = path.as_ref();
+        let tmp_path = path.with_extension("tmp");
+        tokio::fs::write(&tmp_path, data).await
+            .map_err(|e| crate::error::CoreError::Internal {
+                message: format!("Écriture du registre impossible: {}", e),
+            })?;
+        tokio::fs::rename(&tmp_path, path).await
+            .map_err(|e| crate::error::CoreError::Internal {
+                message: format!("Écriture du registre impossible: {}", e),
+            })?;
+
+        tracing::debug!("Registre sauvegardé: {} nœuds", snapshot.nodes.len());
+        Ok(())
+    }
+
+    /// Charge un registre depuis le disque
+    ///
+    /// Les nœuds chargés remplacent les entrées existantes de même
+    /// identifiant ; retourne le nombre de nœuds restaurés.
+    pub async fn load(&self, path: impl AsRef<Path>) -> Result<usize> {
+        let path = path.as_ref();
+        let data = match tokio::fs::read(path).await {
+            Ok(data) => data,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(0),
+            Err(e) => {
+                return Err(crate::error::CoreError::Internal {
+                    message: format!("Lecture du registre impossible: {}", e),
+                })
+            }
+        };
+
+        let snapshot: RegistrySnapshot = serde_json::from_slice(&data)
+            .map_err(SerializationError::from)?;
+        let restored = snapshot.nodes.len();
+
+        {
+            let mut nodes = self.registered_nodes.write().await;
+            let mut scores = self.reputation_scores.write().await;
+            let mut geo_index = self.geographic_index.write().await;
+
+            for node_info in snapshot.nodes {
+                let node_id = node_info.node_id.clone();
+                if let Some(previous) = nodes.insert(node_id.clone(), node_info.clone()) {
+                    if let Some(region_nodes) = geo_index.nodes_by_region.get_mut(&previous.region) {
+                        region_nodes.retain(|id| id != &node_id);
+                    }
+                }
+                geo_index.nodes_by_region
+                    .entry(node_info.region.clone())
+                    .or_insert_with(Vec::new)
+                    .push(node_id);
+                geo_index.available_regions.insert(node_info.region);
+            }
+            geo_index.nodes_by_region.retain(|_, ids| !ids.is_empty());
+
+            for (node_id, score) in snapshot.reputation_scores {
+                scores.insert(node_id, score);
+            }
+        }
+
+        self.update_stats().await;
+
+        tracing::info!("Registre restauré depuis {}: {} nœuds", path.display(), restored);
+        Ok(restored)
+    }
+
+    /// Sauvegarde le registre au chemin configuré si la persistance est active
+    pub async fn persist(&self) -> Result<()> {
+        if self.config.persistence_enabled {
+            self.save(&self.config.persistence_path).await?;
+        }
+        Ok(())
+    }
+
     /// Recommande des nœuds pour une opération
     pub async fn recommend_nodes(&self, criteria: NodeSelectionCriteria) -> Vec<NodeId> {
         let nodes = self.registered_nodes.read().await;
@@ -713,9 +964,8 @@ impl NodeRegistry {
 
     /// Charge les données persistées
     async fn load_persisted_data(&self) -> Result<()> {
-        // Simulation de chargement des données persistées
-        // Dans la réalité, on chargerait depuis un fichier JSON ou une base de données
         tracing::debug!("Chargement des données persistées depuis {}", self.config.persistence_path);
+        self.load(&self.config.persistence_path).await?;
         Ok(())
     }
 
@@ -910,4 +1160,162 @@ mod tests {
         let better_score = NodeRegistry::calculate_performance_score(&better_metrics);
         assert!(better_score > score);
     }
-}
\ No newline at end of file
+
+    fn create_test_node(id: u8, node_type: NodeType, region: &str, storage_usage: f64) -> NodeInfo {
+        NodeInfo {
+            node_id: NodeId::from(Hash::from_bytes_array([id; 32])),
+            node_type,
+            address: format!("127.0.0.{}:8080", id),
+            region: region.to_string(),
+            capabilities: NodeCapabilities {
+                storage_capacity: 1_000_000_000,
+                bandwidth_capacity: 100_000_000,
+                consensus_weight: 1.0,
+                api_endpoints: vec![ApiType::Rest],
+            },
+            status: NodeStatus::Active,
+            registered_at: chrono::Utc::now(),
+            last_heartbeat: chrono::Utc::now(),
+            performance_metrics: PerformanceMetrics {
+                cpu_usage: 0.3,
+                memory_usage: 0.4,
+                storage_usage,
+                network_latency: Duration::from_millis(40),
+                uptime: Duration::from_secs(3600),
+            },
+        }
+    }
+
+    fn test_registry_config() -> NodeRegistryConfig {
+        NodeRegistryConfig {
+            persistence_enabled: false,
+            ..NodeRegistryConfig::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_find_nodes_filters_and_persistence() {
+        let mut registry = NodeRegistry::new(test_registry_config()).await.unwrap();
+
+        registry.register_node(create_test_node(1, NodeType::FullArchive, "eu-west-1", 0.2)).await.unwrap();
+        registry.register_node(create_test_node(2, NodeType::FullArchive, "eu-west-1", 0.95)).await.unwrap();
+        registry.register_node(create_test_node(3, NodeType::FullArchive, "us-east-1", 0.2)).await.unwrap();
+        registry.register_node(create_test_node(4, NodeType::Relay, "eu-west-1", 0.0)).await.unwrap();
+        let mut offline = create_test_node(5, NodeType::FullArchive, "eu-west-1", 0.1);
+        offline.status = NodeStatus::Offline;
+        registry.register_node(offline).await.unwrap();
+
+        // Le nœud 1 gagne en réputation
+        let good_metrics = create_test_node(1, NodeType::FullArchive, "eu-west-1", 0.2).performance_metrics;
+        for _ in 0..5 {
+            registry.process_heartbeat(&NodeId::from(Hash::from_bytes_array([1; 32])), good_metrics.clone()).await.unwrap();
+        }
+
+        let filter = NodeFilter {
+            node_type: Some(NodeType::FullArchive),
+            region: Some("eu-west-1".to_string()),
+            status: Some(NodeStatus::Active),
+            ..NodeFilter::default()
+        };
+        let found = registry.find_nodes(filter.clone()).await;
+        let ids: Vec<u8> = found.iter().map(|n| n.node_id.0.as_bytes()[0]).collect();
+        assert_eq!(ids, vec![1, 2]);
+
+        let with_capacity = NodeFilter {
+            min_free_capacity: Some(500_000_000),
+            ..filter.clone()
+        };
+        let found = registry.find_nodes(with_capacity.clone()).await;
+        assert_eq!(found.len(), 1);
+        assert_eq!(found[0].node_id.0.as_bytes()[0], 1);
+
+        let reputable = NodeFilter {
+            min_reputation: Some(0.99),
+            ..NodeFilter::default()
+        };
+        assert!(registry.find_nodes(reputable).await.is_empty());
+
+        // Sauvegarde puis rechargement dans un registre neuf
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("registry.json");
+        registry.save(&path).await.unwrap();
+
+        let reloaded = NodeRegistry::new(test_registry_config()).await.unwrap();
+        assert_eq!(reloaded.load(&path).await.unwrap(), 5);
+
+        let ids_of = |nodes: Vec<NodeInfo>| -> Vec<NodeId> { nodes.into_iter().map(|n| n.node_id).collect() };
+        assert_eq!(ids_of(reloaded.find_nodes(filter.clone()).await), ids_of(registry.find_nodes(filter).await));
+        assert_eq!(
+            ids_of(reloaded.find_nodes(with_capacity.clone()).await),
+            ids_of(registry.find_nodes(with_capacity).await)
+        );
+        assert_eq!(reloaded.list_nodes_by_region("eu-west-1").await.len(), 4);
+        assert!(reloaded.get_geographic_index().await.available_regions.contains("us-east-1"));
+    }
+
+    #[tokio::test]
+    async fn test_reputation_decays_on_missed_heartbeats() {
+        let mut registry = NodeRegistry::new(test_registry_config()).await.unwrap();
+        let node = create_test_node(1, NodeType::FullArchive, "eu-west-1", 0.2);
+        let node_id = node.node_id.clone();
+        registry.register_node(node).await.unwrap();
+
+        let before = registry.get_reputation_score(&node_id).await.unwrap();
+        let now = chrono::Utc::now();
+
+        // Aucun heartbeat manqué : pas de pénalité
+        assert_eq!(registry.decay_reputations(now).await, 0);
+
+        // Trois intervalles sans heartbeat
+        let later = now + chrono::Duration::seconds(95);
+        assert_eq!(registry.decay_reputations(later).await, 1);
+        let after = registry.get_reputation_score(&node_id).await.unwrap();
+        assert!(after.availability_score < before.availability_score);
+        assert!(after.overall_score < before.overall_score);
+
+        // Un second passage au même instant ne pénalise pas deux fois
+        assert_eq!(registry.decay_reputations(later).await, 0);
+        let again = registry.get_reputation_score(&node_id).await.unwrap();
+        assert_eq!(again.availability_score, after.availability_score);
+    }
+
+    #[tokio::test]
+    async fn test_challenge_outcomes_update_reliability() {
+        let mut registry = NodeRegistry::new(test_registry_config()).await.unwrap();
+        let node = create_test_node(1, NodeType::FullArchive, "eu-west-1", 0.2);
+        let node_id = node.node_id.clone();
+        registry.register_node(node).await.unwrap();
+
+        let entry = |outcome| ChallengeAuditEntry {
+            node_id: node_id.clone(),
+            challenge_id: Hash::zero(),
+            archive_hash: Hash::zero(),
+            nonce: 1,
+            issued_at: chrono::Utc::now(),
+            recorded_at: chrono::Utc::now(),
+            outcome,
+            response_latency_ms: None,
+        };
+        let unknown = ChallengeAuditEntry {
+            node_id: NodeId::from(Hash::from_bytes_array([9; 32])),
+            ..entry(ChallengeOutcome::Failed)
+        };
+
+        let before = registry.get_reputation_score(&node_id).await.unwrap();
+        let applied = registry.apply_challenge_outcomes(&[
+            entry(ChallengeOutcome::Failed),
+            entry(ChallengeOutcome::TimedOut),
+            unknown,
+        ]).await;
+        assert_eq!(applied, 2);
+
+        let failed = registry.get_reputation_score(&node_id).await.unwrap();
+        assert!(failed.reliability_score < before.reliability_score);
+        assert!(failed.overall_score < before.overall_score);
+        assert_eq!(failed.interaction_count, before.interaction_count + 2);
+
+        registry.apply_challenge_outcomes(&[entry(ChallengeOutcome::Passed)]).await;
+        let passed = registry.get_reputation_score(&node_id).await.unwrap();
+        assert!(passed.reliability_score > failed.reliability_score);
+    }
+}
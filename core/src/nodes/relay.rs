@@ -12,11 +12,11 @@ use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::Arc;
 use std::time::{Duration, SystemTime};
 use std::net::SocketAddr;
-use tokio::sync::{RwLock, Mutex};
+use tokio::sync::{Notify, RwLock, Mutex};
 use async_trait::async_trait;
 
-use crate::crypto::{Hash, PublicKey, PrivateKey, Signature};
-use crate::consensus::NodeId;
+use crate::crypto::{Hash, Signer, Signature};
+use crate::consensus::{NodeId, BandwidthReporter, TransferDirection};
 use crate::error::Result;
 use super::{
     Node, NodeType, NodeConfiguration, NetworkMessage, MessageType,
@@ -61,6 +61,13 @@ pub struct RoutingConfiguration {
     pub max_hops: u32,
     /// Table de routage activée
     pub enable_routing_table: bool,
+    /// Durée de validité d'une route apprise sans nouvelle annonce
+    #[serde(default = "default_route_expiry")]
+    pub route_expiry: Duration,
+}
+
+fn default_route_expiry() -> Duration {
+    Duration::from_secs(300)
 }
 
 /// Algorithmes de routage supportés
@@ -189,6 +196,8 @@ pub struct MessageRouter {
     config: RoutingConfiguration,
     /// Métriques de routage
     metrics: Arc<RwLock<RoutingMetrics>>,
+    /// Remontée des transferts relayés vers la preuve de bande passante
+    bandwidth_reporter: Option<BandwidthReporter>,
 }
 
 /// Entrée de la table de routage
@@ -257,6 +266,15 @@ pub struct NetworkMetrics {
     pub peers_discovered: u32,
     /// Score de connectivité
     pub connectivity_score: f64,
+    /// Messages transmis vers un prochain saut connu
+    #[serde(default)]
+    pub messages_forwarded: u64,
+    /// Messages abandonnés à l'expiration de leur TTL
+    #[serde(default)]
+    pub messages_dropped_expired: u64,
+    /// Messages dirigés sans route connue, diffusés en gossip
+    #[serde(default)]
+    pub messages_no_route: u64,
 }
 
 /// Métriques de routage
@@ -286,14 +304,23 @@ impl NodeMetrics for NetworkMetrics {
     }
 }
 
+/// Émission des messages relayés vers un pair
+///
+/// Implémenté par `P2PManager`, qui transporte les messages en encodage canonique.
+#[async_trait]
+pub trait RelayTransport: Send + Sync {
+    /// Envoie `message` au pair `peer`
+    async fn send(&self, peer: &NodeId, message: &NetworkMessage) -> Result<()>;
+}
+
 /// Relay Node - Nœud de relais pour les communications P2P
 pub struct RelayNode {
     /// Configuration du nœud
     config: RelayNodeConfig,
     /// Identifiant du nœud
     node_id: NodeId,
-    /// Clés cryptographiques
-    keypair: (PublicKey, PrivateKey),
+    /// Signataire du nœud (clé locale ou signataire externe)
+    signer: Arc<dyn Signer>,
     /// Statut actuel
     status: Arc<RwLock<RelayNodeStatus>>,
     /// Connexions P2P actives
@@ -304,6 +331,14 @@ pub struct RelayNode {
     metrics: Arc<RwLock<NetworkMetrics>>,
     /// Cache minimal pour les métadonnées
     minimal_cache: Arc<RwLock<HashMap<Hash, CachedMetadata>>>,
+    /// Messages relayés en attente d'émission, avec leur pair de destination
+    outbound: Arc<Mutex<VecDeque<(NodeId, NetworkMessage)>>>,
+    /// Signale l'arrivée de messages dans `outbound`
+    outbound_ready: Arc<Notify>,
+    /// Transport des messages relayés ; sans lui, `outbound` n'est vidé que par `take_outbound`
+    transport: Option<Arc<dyn RelayTransport>>,
+    /// Tâche d'émission de `outbound`, active entre `start` et `stop`
+    outbound_task: Option<tokio::task::JoinHandle<()>>,
     /// Heure de démarrage
     start_time: SystemTime,
 }
@@ -337,6 +372,7 @@ impl Default for RelayNodeConfig {
                 storage_config: None,
                 network_config: super::NetworkConfiguration::default(),
                 security_config: super::SecurityConfiguration::default(),
+                content_filter: None,
             },
             bandwidth_capacity: 1_000_000_000, // 1 GB/s
             max_connections: 1000,
@@ -359,6 +395,7 @@ impl Default for RoutingConfiguration {
             routing_timeout: Duration::from_secs(30),
             max_hops: 16,
             enable_routing_table: true,
+            route_expiry: default_route_expiry(),
         }
     }
 }
@@ -413,12 +450,35 @@ impl MessageRouter {
                 average_processing_time: Duration::ZERO,
                 routing_table_size: 0,
             })),
+            bandwidth_reporter: None,
+        }
+    }
+
+    /// Branche la remontée des transferts relayés vers le consensus
+    pub fn set_bandwidth_reporter(&mut self, reporter: BandwidthReporter) {
+        self.bandwidth_reporter = Some(reporter);
+    }
+
+    /// Signale l'envoi d'un message à `peer`
+    ///
+    /// Seules les réponses de récupération, qui portent le contenu livré, sont
+    /// signalées comme des livraisons, identifiées par le hash de ce contenu.
+    /// Une demande de récupération ne porte que le hash demandé : elle reste un
+    /// simple upload.
+    fn report_forward(&self, peer: NodeId, message: &NetworkMessage, duration: Duration) {
+        let Some(reporter) = &self.bandwidth_reporter else { return };
+        let bytes = message.payload.len() as u64;
+
+        match delivered_content(message) {
+            Some(content_hash) => reporter.report_delivery(peer, content_hash, bytes, duration),
+            None => reporter.report(peer, bytes, duration, TransferDirection::Upload),
         }
     }
 
     /// Route un message vers sa destination
     pub async fn route_message(&self, message: NetworkMessage) -> Result<RoutingResult> {
         let start_time = SystemTime::now();
+        let message = message.with_current_request_id();
 
         // Vérifie si le message a déjà été traité (évite les boucles)
         {
@@ -426,7 +486,7 @@ impl MessageRouter {
             if cache.contains(&message.message_id) {
                 return Ok(RoutingResult::Duplicate);
             }
-            cache.insert(message.message_id);
+            cache.insert(message.message_id.clone());
             
             // Nettoie le cache si trop grand
             if cache.len() > 10000 {
@@ -479,6 +539,12 @@ impl MessageRouter {
                     // Simule l'envoi du message
                     tracing::debug!("Routage message {:?} vers {:?}", 
                         queued_message.message.message_id, next_hop);
+
+                    self.report_forward(
+                        next_hop,
+                        &queued_message.message,
+                        queued_message.queued_at.elapsed().unwrap_or(Duration::ZERO),
+                    );
                     
                     let mut metrics = self.metrics.write().await;
                     metrics.messages_routed += 1;
@@ -505,6 +571,15 @@ impl MessageRouter {
 
     /// Trouve la meilleure route pour un message
     async fn find_route(&self, message: &NetworkMessage, peer_connections: &HashMap<NodeId, PeerConnection>) -> Option<NodeId> {
+        // Un message dirigé suit la table de routage quand une route est connue
+        if self.config.routing_algorithm != RoutingAlgorithm::Flooding {
+            if let Some(recipient) = &message.recipient {
+                if let Some(next_hop) = self.next_hop_at(recipient, peer_connections, SystemTime::now()).await {
+                    return Some(next_hop);
+                }
+            }
+        }
+
         match self.config.routing_algorithm {
             RoutingAlgorithm::Flooding => {
                 // Envoie à tous les pairs connectés
@@ -552,13 +627,172 @@ impl MessageRouter {
             metrics.routing_table_size = routing_table.len();
         }
     }
+
+    /// Apprend une route vers l'émetteur d'une annonce reçue via `via`
+    pub async fn observe_announcement(&self, announcement: &NetworkMessage, via: &NodeId, link_latency: Duration) {
+        self.observe_announcement_at(announcement, via, link_latency, SystemTime::now()).await
+    }
+
+    /// Variante de [`Self::observe_announcement`] à une date donnée
+    ///
+    /// Une route existante et encore valide n'est remplacée que par une route de
+    /// latence inférieure ou par une annonce du même prochain saut.
+    pub async fn observe_announcement_at(
+        &self,
+        announcement: &NetworkMessage,
+        via: &NodeId,
+        link_latency: Duration,
+        now: SystemTime,
+    ) {
+        if !self.config.enable_routing_table || announcement.message_type != MessageType::NodeAnnouncement {
+            return;
+        }
+
+        let destination = announcement.sender.clone();
+        let cost = link_latency.as_millis().min(u32::MAX as u128) as u32;
+
+        let mut routing_table = self.routing_table.write().await;
+        if let Some(existing) = routing_table.get(&destination) {
+            let fresh = !self.is_stale(existing, now);
+            if fresh && existing.next_hop != *via && existing.cost <= cost {
+                return;
+            }
+        }
+
+        routing_table.insert(destination.clone(), RouteEntry {
+            hops: if *via == destination { 1 } else { 2 }, // au moins deux sauts via un intermédiaire
+            destination,
+            next_hop: via.clone(),
+            cost,
+            last_updated: now,
+            reliability: 0.9,
+        });
+
+        let mut metrics = self.metrics.write().await;
+        metrics.routing_table_size = routing_table.len();
+    }
+
+    /// Supprime les routes non rafraîchies depuis `route_expiry`
+    pub async fn expire_stale_routes_at(&self, now: SystemTime) -> usize {
+        let mut routing_table = self.routing_table.write().await;
+        let before = routing_table.len();
+        routing_table.retain(|_, entry| !self.is_stale(entry, now));
+
+        let mut metrics = self.metrics.write().await;
+        metrics.routing_table_size = routing_table.len();
+        before - routing_table.len()
+    }
+
+    /// Prochain saut vers une destination : pair direct, sinon route valide dont le
+    /// prochain saut est connecté
+    pub async fn next_hop_at(
+        &self,
+        destination: &NodeId,
+        peer_connections: &HashMap<NodeId, PeerConnection>,
+        now: SystemTime,
+    ) -> Option<NodeId> {
+        if peer_connections.get(destination).map_or(false, PeerConnection::is_usable) {
+            return Some(destination.clone());
+        }
+
+        let routing_table = self.routing_table.read().await;
+        routing_table.get(destination)
+            .filter(|entry| !self.is_stale(entry, now))
+            .filter(|entry| peer_connections.get(&entry.next_hop).map_or(false, PeerConnection::is_usable))
+            .map(|entry| entry.next_hop.clone())
+    }
+
+    /// Décide du relais d'un message reçu de `from`
+    ///
+    /// Le TTL est décrémenté ; un message dirigé part vers son prochain saut, et
+    /// n'est diffusé en gossip que faute de route connue.
+    pub async fn forward_at(
+        &self,
+        message: NetworkMessage,
+        from: Option<&NodeId>,
+        peer_connections: &HashMap<NodeId, PeerConnection>,
+        now: SystemTime,
+    ) -> ForwardDecision {
+        {
+            let mut cache = self.message_cache.write().await;
+            if !cache.insert(message.message_id.clone()) {
+                return ForwardDecision::Duplicate;
+            }
+            if cache.len() > 10000 {
+                cache.clear();
+            }
+        }
+
+        if message.ttl == 0 {
+            self.metrics.write().await.messages_dropped += 1;
+            return ForwardDecision::Expired;
+        }
+
+        let mut message = message.with_current_request_id();
+        message.ttl = (message.ttl - 1).min(self.config.max_message_ttl);
+
+        let decision = match &message.recipient {
+            Some(recipient) => match self.next_hop_at(recipient, peer_connections, now).await {
+                Some(next_hop) => ForwardDecision::Forward { next_hop, message },
+                None => ForwardDecision::Gossip {
+                    peers: Self::gossip_peers(&message, from, peer_connections),
+                    message,
+                    no_route: true,
+                },
+            },
+            None => ForwardDecision::Gossip {
+                peers: Self::gossip_peers(&message, from, peer_connections),
+                message,
+                no_route: false,
+            },
+        };
+
+        if let Some(message) = decision.message() {
+            for peer in decision.targets() {
+                self.report_forward(peer, message, Duration::ZERO);
+            }
+        }
+
+        self.metrics.write().await.messages_routed += 1;
+        decision
+    }
+
+    /// Pairs utilisables, hors émetteur et pair de provenance
+    fn gossip_peers(message: &NetworkMessage, from: Option<&NodeId>, peer_connections: &HashMap<NodeId, PeerConnection>) -> Vec<NodeId> {
+        peer_connections.values()
+            .filter(|conn| conn.is_usable())
+            .filter(|conn| conn.peer_id != message.sender && Some(&conn.peer_id) != from)
+            .map(|conn| conn.peer_id.clone())
+            .collect()
+    }
+
+    fn is_stale(&self, entry: &RouteEntry, now: SystemTime) -> bool {
+        now.duration_since(entry.last_updated).unwrap_or(Duration::ZERO) > self.config.route_expiry
+    }
+}
+
+/// Hash du contenu livré par une réponse de récupération
+///
+/// Une demande de récupération a pour charge utile le hash demandé ; une
+/// réponse porte le contenu lui-même, dont le hash identifie la livraison.
+fn delivered_content(message: &NetworkMessage) -> Option<Hash> {
+    let is_request = message.payload.len() == crate::crypto::hash::HASH_SIZE;
+    (message.message_type == MessageType::ContentRetrieve && !is_request && !message.payload.is_empty())
+        .then(|| crate::crypto::compute_blake3(&message.payload))
+}
+
+impl PeerConnection {
+    /// Indique si des messages peuvent être émis vers ce pair
+    pub fn is_usable(&self) -> bool {
+        matches!(self.status, ConnectionStatus::Connected | ConnectionStatus::Authenticated)
+    }
 }
 
 impl RelayNode {
     /// Crée une nouvelle instance de Relay Node
     pub fn new(
         config: RelayNodeConfig,
-        keypair: (PublicKey, PrivateKey),
+        signer: Arc<dyn Signer>,
     ) -> Result<Self> {
         // Valide la configuration
         config.validate()?;
@@ -590,21 +824,34 @@ impl RelayNode {
             packet_loss_rate: 0.0,
             peers_discovered: 0,
             connectivity_score: 0.0,
+            messages_forwarded: 0,
+            messages_dropped_expired: 0,
+            messages_no_route: 0,
         };
 
         Ok(Self {
             config,
             node_id,
-            keypair,
+            signer,
             status: Arc::new(RwLock::new(RelayNodeStatus::Initializing)),
             peer_connections: Arc::new(RwLock::new(HashMap::new())),
             message_router: Arc::new(Mutex::new(message_router)),
             metrics: Arc::new(RwLock::new(initial_metrics)),
             minimal_cache: Arc::new(RwLock::new(HashMap::new())),
+            outbound: Arc::new(Mutex::new(VecDeque::new())),
+            outbound_ready: Arc::new(Notify::new()),
+            transport: None,
+            outbound_task: None,
             start_time,
         })
     }
 
+    /// Émet les messages relayés par `transport`
+    pub fn with_transport(mut self, transport: Arc<dyn RelayTransport>) -> Self {
+        self.transport = Some(transport);
+        self
+    }
+
     /// Ajoute une connexion P2P
     pub async fn add_peer_connection(&self, peer_connection: PeerConnection) -> Result<()> {
         {
@@ -700,6 +947,124 @@ impl RelayNode {
         Ok(latency)
     }
 
+    /// Branche la remontée des messages relayés vers le consensus
+    pub async fn set_bandwidth_reporter(&self, reporter: BandwidthReporter) {
+        self.message_router.lock().await.set_bandwidth_reporter(reporter);
+    }
+
+    /// Relaie un message reçu du pair `from`
+    pub async fn relay_message(&self, message: NetworkMessage, from: Option<NodeId>) -> Result<ForwardDecision> {
+        self.relay_message_at(message, from, SystemTime::now()).await
+    }
+
+    /// Variante de [`Self::relay_message`] à une date donnée
+    ///
+    /// Les annonces de nœuds alimentent la table de routage avant d'être relayées ;
+    /// les messages à émettre sont placés dans la file sortante.
+    pub async fn relay_message_at(&self, message: NetworkMessage, from: Option<NodeId>, now: SystemTime) -> Result<ForwardDecision> {
+        let connections = self.peer_connections.read().await;
+        let router = self.message_router.lock().await;
+
+        // Sans pair de provenance connu, l'émetteur est supposé voisin direct
+        let via = from.clone().or_else(|| Some(message.sender.clone()).filter(|sender| connections.contains_key(sender)));
+        if message.message_type == MessageType::NodeAnnouncement {
+            if let Some(via) = &via {
+                let latency = connections.get(via).map(|conn| conn.latency).unwrap_or(Duration::ZERO);
+                router.observe_announcement_at(&message, via, latency, now).await;
+            }
+        }
+
+        let decision = router.forward_at(message, via.as_ref(), &connections, now).await;
+
+        {
+            let mut metrics = self.metrics.write().await;
+            match &decision {
+                ForwardDecision::Forward { .. } => {
+                    metrics.messages_forwarded += 1;
+                    metrics.messages_routed_success += 1;
+                }
+                ForwardDecision::Gossip { peers, no_route, .. } => {
+                    if *no_route {
+                        metrics.messages_no_route += 1;
+                    }
+                    if peers.is_empty() {
+                        metrics.messages_routed_failed += 1;
+                    } else {
+                        metrics.messages_routed_success += 1;
+                    }
+                }
+                ForwardDecision::Expired => metrics.messages_dropped_expired += 1,
+                ForwardDecision::Duplicate => {}
+            }
+        }
+
+        if let Some(message) = decision.message() {
+            let mut outbound = self.outbound.lock().await;
+            for peer in decision.targets() {
+                outbound.push_back((peer, message.clone()));
+            }
+            self.outbound_ready.notify_one();
+        }
+
+        Ok(decision)
+    }
+
+    /// Retire les messages relayés en attente d'émission
+    pub async fn take_outbound(&self) -> Vec<(NodeId, NetworkMessage)> {
+        self.outbound.lock().await.drain(..).collect()
+    }
+
+    /// Émet par le transport les messages relayés en attente
+    ///
+    /// Retourne le nombre de messages remis au transport ; sans transport, les
+    /// messages restent en file.
+    pub async fn flush_outbound(&self) -> usize {
+        match &self.transport {
+            Some(transport) => Self::send_outbound(&self.outbound, transport.as_ref(), &self.metrics).await,
+            None => 0,
+        }
+    }
+
+    async fn send_outbound(
+        outbound: &Mutex<VecDeque<(NodeId, NetworkMessage)>>,
+        transport: &dyn RelayTransport,
+        metrics: &RwLock<NetworkMetrics>,
+    ) -> usize {
+        // La file est libérée avant les envois, qui peuvent être lents
+        let pending: Vec<_> = outbound.lock().await.drain(..).collect();
+        let mut sent = 0;
+        for (peer, message) in pending {
+            match transport.send(&peer, &message).await {
+                Ok(()) => sent += 1,
+                Err(e) => {
+                    tracing::debug!("Émission du message {:?} vers {:?} échouée: {}", message.message_id, peer, e);
+                    metrics.write().await.messages_routed_failed += 1;
+                }
+            }
+        }
+        sent
+    }
+
+    /// Démarre l'émission des messages relayés dès leur mise en file
+    fn start_outbound_task(&self) -> Option<tokio::task::JoinHandle<()>> {
+        let transport = self.transport.clone()?;
+        let outbound = self.outbound.clone();
+        let ready = self.outbound_ready.clone();
+        let metrics = self.metrics.clone();
+
+        Some(tokio::spawn(async move {
+            loop {
+                ready.notified().await;
+                Self::send_outbound(&outbound, transport.as_ref(), &metrics).await;
+            }
+        }))
+    }
+
+    /// Supprime les routes expirées de la table de routage
+    pub async fn expire_stale_routes_at(&self, now: SystemTime) -> usize {
+        self.message_router.lock().await.expire_stale_routes_at(now).await
+    }
+
     /// Traite les messages en file d'attente
     pub async fn process_message_queue(&self) -> Result<u32> {
         let connections = self.peer_connections.read().await;
@@ -799,6 +1164,13 @@ impl Node for RelayNode {
         // Optimise le routage initial
         self.optimize_routing().await?;
 
+        if let Some(task) = self.outbound_task.take() {
+            task.abort();
+        }
+        self.outbound_task = self.start_outbound_task();
+        // Messages mis en file avant le démarrage
+        self.outbound_ready.notify_one();
+
         {
             let mut status = self.status.write().await;
             *status = RelayNodeStatus::Operational;
@@ -816,6 +1188,10 @@ impl Node for RelayNode {
             *status = RelayNodeStatus::Stopping;
         }
 
+        if let Some(task) = self.outbound_task.take() {
+            task.abort();
+        }
+
         // Ferme toutes les connexions
         {
             let mut connections = self.peer_connections.write().await;
@@ -897,6 +1273,7 @@ impl Node for RelayNode {
                     payload: Vec::new(),
                     timestamp: chrono::Utc::now(),
                     ttl: 60,
+                    request_id: message.request_id.clone(),
                 }))
             },
             MessageType::NodeDiscovery => {
@@ -905,9 +1282,8 @@ impl Node for RelayNode {
                 Ok(None)
             },
             _ => {
-                // Route le message vers sa destination
-                let router = self.message_router.lock().await;
-                router.route_message(message).await?;
+                // Relaie le message vers sa destination
+                self.relay_message(message, None).await?;
                 Ok(None)
             }
         }
@@ -916,6 +1292,7 @@ impl Node for RelayNode {
     async fn sync_with_network(&mut self) -> Result<()> {
         // Traite la file d'attente des messages
         self.process_message_queue().await?;
+        self.expire_stale_routes_at(SystemTime::now()).await;
         
         // Découvre de nouveaux pairs
         self.discover_peers().await?;
@@ -946,6 +1323,38 @@ pub enum RoutingResult {
     NoRoute,
 }
 
+/// Décision de relais d'un message
+#[derive(Debug, Clone)]
+pub enum ForwardDecision {
+    /// Transmis au prochain saut de la route
+    Forward { next_hop: NodeId, message: NetworkMessage },
+    /// Diffusé aux pairs ; `no_route` signale un message dirigé sans route connue
+    Gossip { peers: Vec<NodeId>, message: NetworkMessage, no_route: bool },
+    /// Abandonné, TTL épuisé
+    Expired,
+    /// Déjà relayé
+    Duplicate,
+}
+
+impl ForwardDecision {
+    /// Pairs vers lesquels le message est émis
+    pub fn targets(&self) -> Vec<NodeId> {
+        match self {
+            ForwardDecision::Forward { next_hop, .. } => vec![next_hop.clone()],
+            ForwardDecision::Gossip { peers, .. } => peers.clone(),
+            ForwardDecision::Expired | ForwardDecision::Duplicate => Vec::new(),
+        }
+    }
+
+    /// Message relayé, TTL décrémenté
+    pub fn message(&self) -> Option<&NetworkMessage> {
+        match self {
+            ForwardDecision::Forward { message, .. } | ForwardDecision::Gossip { message, .. } => Some(message),
+            ForwardDecision::Expired | ForwardDecision::Duplicate => None,
+        }
+    }
+}
+
 /// Résultat de découverte de pairs
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PeerDiscoveryResult {
@@ -1041,7 +1450,7 @@ mod tests {
         let config = RelayNodeConfig::default();
         let keypair = generate_keypair().unwrap();
 
-        let node = RelayNode::new(config, keypair);
+        let node = RelayNode::new(config, Arc::new(keypair));
         assert!(node.is_ok());
     }
 
@@ -1058,6 +1467,7 @@ mod tests {
             payload: Vec::new(),
             timestamp: chrono::Utc::now(),
             ttl: 60,
+            request_id: None,
         };
 
         let result = router.route_message(message).await;
@@ -1065,6 +1475,167 @@ mod tests {
         assert_eq!(result.unwrap(), RoutingResult::Queued);
     }
 
+    fn node(tag: &str) -> NodeId {
+        NodeId::from(crate::crypto::compute_blake3(tag.as_bytes()))
+    }
+
+    fn peer(peer_id: NodeId, latency_ms: u64) -> PeerConnection {
+        PeerConnection {
+            peer_id,
+            address: "127.0.0.1:9000".parse().unwrap(),
+            status: ConnectionStatus::Connected,
+            latency: Duration::from_millis(latency_ms),
+            available_bandwidth: 1_000_000,
+            last_activity: SystemTime::now(),
+            messages_routed: 0,
+            reliability_score: 1.0,
+        }
+    }
+
+    fn message(id: &str, sender: &NodeId, recipient: Option<&NodeId>, message_type: MessageType) -> NetworkMessage {
+        NetworkMessage {
+            message_id: crate::crypto::compute_blake3(id.as_bytes()),
+            sender: sender.clone(),
+            recipient: recipient.cloned(),
+            message_type,
+            payload: vec![0u8; 16],
+            timestamp: chrono::Utc::now(),
+            ttl: 8,
+            request_id: None,
+        }
+    }
+
+    async fn relay_with_peers(peers: Vec<PeerConnection>) -> RelayNode {
+        let keypair = generate_keypair().unwrap();
+        let relay = RelayNode::new(
+            RelayNodeConfig::default(),
+            Arc::new(keypair),
+        ).unwrap();
+        for connection in peers {
+            relay.add_peer_connection(connection).await.unwrap();
+        }
+        relay
+    }
+
+    #[tokio::test]
+    async fn test_directed_message_follows_learned_route() {
+        // Topologie en ligne A — Relay — B, B étant annoncé par C (plus lent) et par lui-même
+        let (a, b, c) = (node("a"), node("b"), node("c"));
+        let relay = relay_with_peers(vec![peer(a.clone(), 5), peer(c.clone(), 80)]).await;
+        let now = SystemTime::now();
+
+        // B n'est pas voisin direct : il est d'abord appris via C
+        relay.relay_message_at(message("ann-1", &b, None, MessageType::NodeAnnouncement), Some(c.clone()), now).await.unwrap();
+        relay.add_peer_connection(peer(b.clone(), 10)).await.unwrap();
+        relay.relay_message_at(message("ann-2", &b, None, MessageType::NodeAnnouncement), Some(b.clone()), now).await.unwrap();
+        relay.take_outbound().await;
+
+        let decision = relay
+            .relay_message_at(message("direct", &a, Some(&b), MessageType::ContentRetrieve), Some(a.clone()), now)
+            .await
+            .unwrap();
+        assert!(matches!(decision, ForwardDecision::Forward { ref next_hop, .. } if *next_hop == b));
+
+        let outbound = relay.take_outbound().await;
+        assert_eq!(outbound.len(), 1);
+        assert_eq!(outbound[0].0, b);
+        assert_eq!(outbound[0].1.ttl, 7);
+
+        // Un TTL épuisé est abandonné
+        let mut expired = message("expired", &a, Some(&b), MessageType::ContentRetrieve);
+        expired.ttl = 0;
+        let decision = relay.relay_message_at(expired, Some(a.clone()), now).await.unwrap();
+        assert!(matches!(decision, ForwardDecision::Expired));
+        assert!(relay.take_outbound().await.is_empty());
+
+        let metrics = relay.metrics.read().await;
+        assert_eq!(metrics.messages_forwarded, 1);
+        assert_eq!(metrics.messages_dropped_expired, 1);
+        assert_eq!(metrics.messages_no_route, 0);
+    }
+
+    /// Transport qui enregistre les envois et refuse ceux vers `unreachable`
+    #[derive(Default)]
+    struct RecordingTransport {
+        sent: Mutex<Vec<(NodeId, Hash)>>,
+        unreachable: Option<NodeId>,
+    }
+
+    #[async_trait]
+    impl RelayTransport for RecordingTransport {
+        async fn send(&self, peer: &NodeId, message: &NetworkMessage) -> Result<()> {
+            if self.unreachable.as_ref() == Some(peer) {
+                return Err(crate::error::CoreError::Internal { message: "pair injoignable".to_string() });
+            }
+            self.sent.lock().await.push((peer.clone(), message.message_id.clone()));
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_relayed_messages_are_sent_through_transport() {
+        let (a, b, c) = (node("a"), node("b"), node("c"));
+        let transport = Arc::new(RecordingTransport { unreachable: Some(c.clone()), ..Default::default() });
+        let mut relay = relay_with_peers(vec![peer(a.clone(), 5), peer(b.clone(), 10), peer(c.clone(), 10)])
+            .await
+            .with_transport(transport.clone());
+        let now = SystemTime::now();
+
+        relay.relay_message_at(message("ann", &b, None, MessageType::NodeAnnouncement), Some(b.clone()), now).await.unwrap();
+        assert_eq!(relay.flush_outbound().await, 1);
+        assert_eq!(relay.metrics.read().await.messages_routed_failed, 1);
+        transport.sent.lock().await.clear();
+
+        // Une fois démarré, le nœud émet les messages dès leur mise en file
+        relay.start().await.unwrap();
+        let direct = message("direct", &a, Some(&b), MessageType::ContentRetrieve);
+        let direct_id = direct.message_id.clone();
+        relay.relay_message_at(direct, Some(a.clone()), now).await.unwrap();
+
+        tokio::time::timeout(Duration::from_secs(1), async {
+            while transport.sent.lock().await.is_empty() {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .unwrap();
+        assert_eq!(*transport.sent.lock().await, vec![(b.clone(), direct_id)]);
+        assert!(relay.take_outbound().await.is_empty());
+        relay.stop().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_latency_preference_and_route_expiry_fallback() {
+        let (a, b, c, d) = (node("a"), node("b"), node("c"), node("d"));
+        let relay = relay_with_peers(vec![peer(a.clone(), 5), peer(b.clone(), 40), peer(c.clone(), 10)]).await;
+        let now = SystemTime::now();
+
+        // D est annoncé via B puis via C, plus rapide : la route passe par C
+        relay.relay_message_at(message("ann-b", &d, None, MessageType::NodeAnnouncement), Some(b.clone()), now).await.unwrap();
+        relay.relay_message_at(message("ann-c", &d, None, MessageType::NodeAnnouncement), Some(c.clone()), now).await.unwrap();
+        relay.take_outbound().await;
+
+        let decision = relay
+            .relay_message_at(message("fresh", &a, Some(&d), MessageType::ContentRetrieve), Some(a.clone()), now)
+            .await
+            .unwrap();
+        assert_eq!(decision.targets(), vec![c.clone()]);
+        relay.take_outbound().await;
+
+        // Sans nouvelle annonce, la route expire et le message repart en gossip
+        let later = now + RoutingConfiguration::default().route_expiry + Duration::from_secs(1);
+        let decision = relay
+            .relay_message_at(message("stale", &a, Some(&d), MessageType::ContentRetrieve), Some(a.clone()), later)
+            .await
+            .unwrap();
+        assert!(matches!(decision, ForwardDecision::Gossip { no_route: true, .. }));
+
+        let targets: HashSet<NodeId> = relay.take_outbound().await.into_iter().map(|(peer, _)| peer).collect();
+        assert_eq!(targets, [b.clone(), c.clone()].into_iter().collect());
+        assert_eq!(relay.expire_stale_routes_at(later).await, 1);
+        assert_eq!(relay.metrics.read().await.messages_no_route, 1);
+    }
+
     #[test]
     fn test_routing_algorithms() {
         assert_eq!(RoutingAlgorithm::Flooding, RoutingAlgorithm::Flooding);
@@ -1077,4 +1648,20 @@ mod tests {
         assert_eq!(status, ConnectionStatus::Connected);
         assert_ne!(status, ConnectionStatus::Disconnected);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_only_retrieval_responses_count_as_deliveries() {
+        let (a, b) = (node("a"), node("b"));
+        let content = b"<html>archive</html>".to_vec();
+
+        let mut request = message("request", &a, Some(&b), MessageType::ContentRetrieve);
+        request.payload = crate::crypto::compute_blake3(&content).as_bytes().to_vec();
+        assert_eq!(delivered_content(&request), None);
+
+        let mut response = message("response", &b, Some(&a), MessageType::ContentRetrieve);
+        response.payload = content.clone();
+        assert_eq!(delivered_content(&response), Some(crate::crypto::compute_blake3(&content)));
+
+        assert_eq!(delivered_content(&message("ping", &a, Some(&b), MessageType::Ping)), None);
+    }
+}
@@ -1,9 +1,29 @@
 //! Module de sérialisation pour ArchiveChain
 //! 
-//! Fournit des fonctions de sérialisation/désérialisation avec bincode et CBOR
+//! Fournit des fonctions de sérialisation/désérialisation avec bincode et CBOR,
+//! ainsi que l'encodage canonique versionné des blocs, transactions et
+//! messages réseau
 
-use serde::{Serialize, Deserialize};
-use crate::error::{SerializationError, Result};
+use std::collections::{BTreeMap, HashMap};
+use bincode::Options;
+use chrono::{DateTime, Utc};
+use serde::{Serialize, Serializer, Deserialize};
+use crate::crypto::{Hash, HashAlgorithm, PublicKey, Signature};
+use crate::crypto::keys::PUBLIC_KEY_SIZE;
+use crate::error::{CoreError, SerializationError, Result};
+
+/// Version courante de l'encodage canonique
+///
+/// - 1 : bincode à configuration figée, qui suivait encore l'ordre de
+///   déclaration des champs ;
+/// - 2 : disposition explicite champ par champ (voir `CanonicalLayout`).
+///
+/// Toute évolution de la disposition des octets doit incrémenter cette
+/// version et conserver le décodage des versions précédentes.
+pub const CANONICAL_FORMAT_VERSION: u8 = 2;
+
+/// Version de l'encodage bincode figé, toujours accepté en lecture
+const BINCODE_CANONICAL_VERSION: u8 = 1;
 
 /// Formats de sérialisation supportés
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -14,6 +34,8 @@ pub enum SerializationFormat {
     Cbor,
     /// JSON - Format texte pour le debug et APIs
     Json,
+    /// Encodage canonique versionné, réservé aux types `CanonicalEncoding`
+    Canonical,
 }
 
 /// Trait pour les objets sérialisables
@@ -41,7 +63,7 @@ pub trait Serializable: Serialize + for<'de> Deserialize<'de> {
                 let data = self.serialize(format)?;
                 Ok(data.len())
             }
-            SerializationFormat::Json => {
+            SerializationFormat::Json | SerializationFormat::Canonical => {
                 let data = self.serialize(format)?;
                 Ok(data.len())
             }
@@ -49,6 +71,471 @@ pub trait Serializable: Serialize + for<'de> Deserialize<'de> {
     }
 }
 
+/// Encodage canonique versionné pour les objets de la chaîne
+///
+/// Les octets commencent par `CANONICAL_FORMAT_VERSION`, suivi des champs
+/// écrits un à un par `CanonicalLayout` : la disposition ne dépend ni de serde
+/// ni de l'ordre de déclaration des champs, réordonner une structure ne change
+/// donc ni les octets ni la chaîne. Les encodages antérieurs (bincode figé de
+/// la version 1, bincode brut sans version) restent décodables.
+pub trait CanonicalEncoding: CanonicalLayout + Serialize + for<'de> Deserialize<'de> {
+    /// Encode l'objet sous sa forme canonique
+    fn serialize_canonical(&self) -> Result<Vec<u8>> {
+        let mut writer = CanonicalWriter::new();
+        writer.write_u8(CANONICAL_FORMAT_VERSION);
+        self.write_canonical(&mut writer);
+        Ok(writer.into_bytes())
+    }
+
+    /// Décode un objet canonique, ou un encodage antérieur à la disposition explicite
+    fn deserialize_canonical(data: &[u8]) -> Result<Self> {
+        let version = data.first().copied().unwrap_or_default();
+        if version == CANONICAL_FORMAT_VERSION {
+            let mut reader = CanonicalReader::new(&data[1..]);
+            if let Ok(object) = Self::read_canonical(&mut reader) {
+                if reader.finish().is_ok() {
+                    return Ok(object);
+                }
+            }
+        }
+        if version == BINCODE_CANONICAL_VERSION {
+            if let Ok(object) = bincode_canonical_options().deserialize(&data[1..]) {
+                return Ok(object);
+            }
+        }
+
+        // Sans en-tête de version reconnu, il s'agit d'un encodage historique
+        bincode::deserialize(data).map_err(|err| {
+            if version > CANONICAL_FORMAT_VERSION {
+                SerializationError::UnsupportedFormat {
+                    format: format!("version canonique {}", version),
+                }
+                .into()
+            } else {
+                SerializationError::Bincode(err).into()
+            }
+        })
+    }
+
+    /// Taille de l'encodage canonique, octet de version compris
+    fn canonical_size(&self) -> usize {
+        let mut writer = CanonicalWriter::new();
+        self.write_canonical(&mut writer);
+        writer.len() + 1
+    }
+}
+
+/// Configuration bincode figée de la version 1 de l'encodage canonique
+fn bincode_canonical_options() -> impl Options {
+    bincode::DefaultOptions::new()
+        .with_fixint_encoding()
+        .with_little_endian()
+        .reject_trailing_bytes()
+}
+
+/// Disposition explicite d'un type dans l'encodage canonique
+///
+/// Chaque implémentation fixe elle-même l'ordre de ses champs. Les briques
+/// de base sont :
+/// - entiers little-endian de taille fixe, booléens sur un octet (0 ou 1) ;
+/// - séquences et chaînes UTF-8 précédées de leur longueur en u64 ;
+/// - `Option` précédée d'un tag (0 absente, 1 présente) ;
+/// - dates en secondes Unix (i64) puis nanosecondes (u32) ;
+/// - maps écrites par ordre croissant de clés ;
+/// - énumérations identifiées par un discriminant u8 explicite ;
+/// - hashs, clés publiques et signatures en octets bruts de taille fixe.
+pub trait CanonicalLayout: Sized {
+    /// Écrit les champs dans l'ordre canonique
+    fn write_canonical(&self, writer: &mut CanonicalWriter);
+
+    /// Relit les champs écrits par `write_canonical`
+    fn read_canonical(reader: &mut CanonicalReader<'_>) -> Result<Self>;
+}
+
+/// Tampon d'écriture de l'encodage canonique
+#[derive(Debug, Default)]
+pub struct CanonicalWriter {
+    buffer: Vec<u8>,
+}
+
+impl CanonicalWriter {
+    /// Crée un tampon vide
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Écrit un octet
+    pub fn write_u8(&mut self, value: u8) {
+        self.buffer.push(value);
+    }
+
+    /// Écrit un u32 little-endian
+    pub fn write_u32(&mut self, value: u32) {
+        self.buffer.extend_from_slice(&value.to_le_bytes());
+    }
+
+    /// Écrit un u64 little-endian
+    pub fn write_u64(&mut self, value: u64) {
+        self.buffer.extend_from_slice(&value.to_le_bytes());
+    }
+
+    /// Écrit un i64 little-endian
+    pub fn write_i64(&mut self, value: i64) {
+        self.buffer.extend_from_slice(&value.to_le_bytes());
+    }
+
+    /// Écrit un booléen sur un octet
+    pub fn write_bool(&mut self, value: bool) {
+        self.write_u8(u8::from(value));
+    }
+
+    /// Écrit la longueur d'une séquence
+    pub fn write_len(&mut self, len: usize) {
+        self.write_u64(len as u64);
+    }
+
+    /// Écrit des octets tels quels, sans préfixe de longueur
+    pub fn write_raw(&mut self, bytes: &[u8]) {
+        self.buffer.extend_from_slice(bytes);
+    }
+
+    /// Nombre d'octets écrits
+    pub fn len(&self) -> usize {
+        self.buffer.len()
+    }
+
+    /// Indique si rien n'a été écrit
+    pub fn is_empty(&self) -> bool {
+        self.buffer.is_empty()
+    }
+
+    /// Octets encodés
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.buffer
+    }
+}
+
+/// Curseur de lecture de l'encodage canonique
+#[derive(Debug)]
+pub struct CanonicalReader<'a> {
+    data: &'a [u8],
+    position: usize,
+}
+
+impl<'a> CanonicalReader<'a> {
+    /// Crée un curseur au début des données
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data, position: 0 }
+    }
+
+    /// Lit `len` octets tels quels
+    pub fn read_raw(&mut self, len: usize) -> Result<&'a [u8]> {
+        let end = self.position
+            .checked_add(len)
+            .filter(|end| *end <= self.data.len())
+            .ok_or_else(|| canonical_error("données tronquées"))?;
+        let bytes = &self.data[self.position..end];
+        self.position = end;
+        Ok(bytes)
+    }
+
+    /// Lit un tableau d'octets de taille fixe
+    pub fn read_array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let mut array = [0u8; N];
+        array.copy_from_slice(self.read_raw(N)?);
+        Ok(array)
+    }
+
+    /// Lit un octet
+    pub fn read_u8(&mut self) -> Result<u8> {
+        Ok(self.read_array::<1>()?[0])
+    }
+
+    /// Lit un u32 little-endian
+    pub fn read_u32(&mut self) -> Result<u32> {
+        Ok(u32::from_le_bytes(self.read_array()?))
+    }
+
+    /// Lit un u64 little-endian
+    pub fn read_u64(&mut self) -> Result<u64> {
+        Ok(u64::from_le_bytes(self.read_array()?))
+    }
+
+    /// Lit un i64 little-endian
+    pub fn read_i64(&mut self) -> Result<i64> {
+        Ok(i64::from_le_bytes(self.read_array()?))
+    }
+
+    /// Lit un booléen, seuls 0 et 1 sont acceptés
+    pub fn read_bool(&mut self) -> Result<bool> {
+        match self.read_u8()? {
+            0 => Ok(false),
+            1 => Ok(true),
+            other => Err(canonical_error(format!("booléen invalide: {}", other))),
+        }
+    }
+
+    /// Lit la longueur d'une séquence
+    ///
+    /// Chaque élément occupant au moins un octet, une longueur supérieure aux
+    /// octets restants est rejetée avant toute allocation.
+    pub fn read_len(&mut self) -> Result<usize> {
+        let len = self.read_u64()?;
+        usize::try_from(len)
+            .ok()
+            .filter(|len| *len <= self.remaining())
+            .ok_or_else(|| canonical_error(format!("longueur {} supérieure aux données restantes", len)))
+    }
+
+    /// Nombre d'octets restant à lire
+    pub fn remaining(&self) -> usize {
+        self.data.len() - self.position
+    }
+
+    /// Vérifie que toutes les données ont été consommées
+    pub fn finish(self) -> Result<()> {
+        match self.remaining() {
+            0 => Ok(()),
+            extra => Err(canonical_error(format!("{} octets surnuméraires", extra))),
+        }
+    }
+}
+
+fn canonical_error(message: impl Into<String>) -> CoreError {
+    SerializationError::Canonical(message.into()).into()
+}
+
+impl CanonicalLayout for u8 {
+    fn write_canonical(&self, writer: &mut CanonicalWriter) {
+        writer.write_u8(*self);
+    }
+
+    fn read_canonical(reader: &mut CanonicalReader<'_>) -> Result<Self> {
+        reader.read_u8()
+    }
+}
+
+impl CanonicalLayout for u32 {
+    fn write_canonical(&self, writer: &mut CanonicalWriter) {
+        writer.write_u32(*self);
+    }
+
+    fn read_canonical(reader: &mut CanonicalReader<'_>) -> Result<Self> {
+        reader.read_u32()
+    }
+}
+
+impl CanonicalLayout for u64 {
+    fn write_canonical(&self, writer: &mut CanonicalWriter) {
+        writer.write_u64(*self);
+    }
+
+    fn read_canonical(reader: &mut CanonicalReader<'_>) -> Result<Self> {
+        reader.read_u64()
+    }
+}
+
+impl CanonicalLayout for bool {
+    fn write_canonical(&self, writer: &mut CanonicalWriter) {
+        writer.write_bool(*self);
+    }
+
+    fn read_canonical(reader: &mut CanonicalReader<'_>) -> Result<Self> {
+        reader.read_bool()
+    }
+}
+
+impl CanonicalLayout for String {
+    fn write_canonical(&self, writer: &mut CanonicalWriter) {
+        writer.write_len(self.len());
+        writer.write_raw(self.as_bytes());
+    }
+
+    fn read_canonical(reader: &mut CanonicalReader<'_>) -> Result<Self> {
+        let len = reader.read_len()?;
+        String::from_utf8(reader.read_raw(len)?.to_vec())
+            .map_err(|_| canonical_error("chaîne UTF-8 invalide"))
+    }
+}
+
+impl<T: CanonicalLayout> CanonicalLayout for Vec<T> {
+    fn write_canonical(&self, writer: &mut CanonicalWriter) {
+        writer.write_len(self.len());
+        for item in self {
+            item.write_canonical(writer);
+        }
+    }
+
+    fn read_canonical(reader: &mut CanonicalReader<'_>) -> Result<Self> {
+        let len = reader.read_len()?;
+        let mut items = Vec::with_capacity(len);
+        for _ in 0..len {
+            items.push(T::read_canonical(reader)?);
+        }
+        Ok(items)
+    }
+}
+
+impl<T: CanonicalLayout> CanonicalLayout for Option<T> {
+    fn write_canonical(&self, writer: &mut CanonicalWriter) {
+        match self {
+            None => writer.write_u8(0),
+            Some(value) => {
+                writer.write_u8(1);
+                value.write_canonical(writer);
+            }
+        }
+    }
+
+    fn read_canonical(reader: &mut CanonicalReader<'_>) -> Result<Self> {
+        match reader.read_u8()? {
+            0 => Ok(None),
+            1 => Ok(Some(T::read_canonical(reader)?)),
+            other => Err(canonical_error(format!("tag d'option invalide: {}", other))),
+        }
+    }
+}
+
+impl<A: CanonicalLayout, B: CanonicalLayout> CanonicalLayout for (A, B) {
+    fn write_canonical(&self, writer: &mut CanonicalWriter) {
+        self.0.write_canonical(writer);
+        self.1.write_canonical(writer);
+    }
+
+    fn read_canonical(reader: &mut CanonicalReader<'_>) -> Result<Self> {
+        Ok((A::read_canonical(reader)?, B::read_canonical(reader)?))
+    }
+}
+
+impl<K, V> CanonicalLayout for HashMap<K, V>
+where
+    K: CanonicalLayout + Ord + std::hash::Hash,
+    V: CanonicalLayout,
+{
+    fn write_canonical(&self, writer: &mut CanonicalWriter) {
+        let mut entries: Vec<_> = self.iter().collect();
+        entries.sort_by(|a, b| a.0.cmp(b.0));
+        writer.write_len(entries.len());
+        for (key, value) in entries {
+            key.write_canonical(writer);
+            value.write_canonical(writer);
+        }
+    }
+
+    fn read_canonical(reader: &mut CanonicalReader<'_>) -> Result<Self> {
+        let len = reader.read_len()?;
+        let mut map = HashMap::with_capacity(len);
+        for _ in 0..len {
+            let key = K::read_canonical(reader)?;
+            if map.insert(key, V::read_canonical(reader)?).is_some() {
+                return Err(canonical_error("clé de map dupliquée"));
+            }
+        }
+        Ok(map)
+    }
+}
+
+impl CanonicalLayout for DateTime<Utc> {
+    fn write_canonical(&self, writer: &mut CanonicalWriter) {
+        writer.write_i64(self.timestamp());
+        writer.write_u32(self.timestamp_subsec_nanos());
+    }
+
+    fn read_canonical(reader: &mut CanonicalReader<'_>) -> Result<Self> {
+        let seconds = reader.read_i64()?;
+        let nanos = reader.read_u32()?;
+        DateTime::from_timestamp(seconds, nanos).ok_or_else(|| canonical_error("date hors limites"))
+    }
+}
+
+impl CanonicalLayout for Hash {
+    fn write_canonical(&self, writer: &mut CanonicalWriter) {
+        writer.write_raw(self.as_bytes());
+    }
+
+    fn read_canonical(reader: &mut CanonicalReader<'_>) -> Result<Self> {
+        Ok(Hash::new(reader.read_array()?))
+    }
+}
+
+impl CanonicalLayout for PublicKey {
+    fn write_canonical(&self, writer: &mut CanonicalWriter) {
+        writer.write_raw(self.as_bytes());
+    }
+
+    fn read_canonical(reader: &mut CanonicalReader<'_>) -> Result<Self> {
+        PublicKey::from_bytes(reader.read_raw(PUBLIC_KEY_SIZE)?)
+    }
+}
+
+impl CanonicalLayout for Signature {
+    fn write_canonical(&self, writer: &mut CanonicalWriter) {
+        writer.write_raw(self.as_bytes());
+    }
+
+    fn read_canonical(reader: &mut CanonicalReader<'_>) -> Result<Self> {
+        Ok(Signature::new(reader.read_array()?))
+    }
+}
+
+/// Disposition d'une structure : ses champs, dans l'ordre donné
+///
+/// L'ordre listé ici fait foi ; il est indépendant de l'ordre de déclaration.
+macro_rules! canonical_struct {
+    ($type:ty { $($field:ident),* $(,)? }) => {
+        impl CanonicalLayout for $type {
+            fn write_canonical(&self, writer: &mut CanonicalWriter) {
+                $(self.$field.write_canonical(writer);)*
+            }
+
+            fn read_canonical(reader: &mut CanonicalReader<'_>) -> Result<Self> {
+                Ok(Self {
+                    $($field: CanonicalLayout::read_canonical(reader)?,)*
+                })
+            }
+        }
+    };
+}
+
+/// Disposition d'une énumération sans données : un discriminant u8 explicite
+macro_rules! canonical_enum {
+    ($type:ty { $($variant:ident = $tag:literal),* $(,)? }) => {
+        impl CanonicalLayout for $type {
+            fn write_canonical(&self, writer: &mut CanonicalWriter) {
+                writer.write_u8(match self {
+                    $(Self::$variant => $tag,)*
+                });
+            }
+
+            fn read_canonical(reader: &mut CanonicalReader<'_>) -> Result<Self> {
+                match reader.read_u8()? {
+                    $($tag => Ok(Self::$variant),)*
+                    other => Err(canonical_error(format!(
+                        "discriminant {} inconnu pour {}",
+                        other,
+                        stringify!($type)
+                    ))),
+                }
+            }
+        }
+    };
+}
+
+/// Sérialise une `HashMap` par ordre croissant de clés
+///
+/// À utiliser avec `#[serde(serialize_with = "...")]` : l'ordre d'itération
+/// d'une `HashMap` varie d'un processus à l'autre, ce qui rendrait l'encodage
+/// d'un même bloc différent d'un nœud à l'autre. La disposition des octets est
+/// celle d'une map ordinaire, la désérialisation est donc inchangée.
+pub fn serialize_ordered_map<K, V, S>(map: &HashMap<K, V>, serializer: S) -> std::result::Result<S::Ok, S::Error>
+where
+    K: Ord + Serialize,
+    V: Serialize,
+    S: Serializer,
+{
+    map.iter().collect::<BTreeMap<_, _>>().serialize(serializer)
+}
+
 /// Sérialise un objet avec le format spécifié
 pub fn serialize_with_format<T: Serialize>(
     obj: &T,
@@ -67,6 +554,7 @@ pub fn serialize_with_format<T: Serialize>(
             let json_str = serde_json::to_string(obj)?;
             Ok(json_str.into_bytes())
         }
+        SerializationFormat::Canonical => Err(canonical_unavailable()),
     }
 }
 
@@ -89,9 +577,18 @@ pub fn deserialize_with_format<T: for<'de> Deserialize<'de>>(
                 })?;
             Ok(serde_json::from_str(json_str)?)
         }
+        SerializationFormat::Canonical => Err(canonical_unavailable()),
     }
 }
 
+/// L'encodage canonique passe par `CanonicalEncoding`, pas par serde
+fn canonical_unavailable() -> CoreError {
+    SerializationError::UnsupportedFormat {
+        format: "encodage canonique hors CanonicalEncoding".to_string(),
+    }
+    .into()
+}
+
 /// Compresse des données avec différents algorithmes
 pub fn compress_data(data: &[u8], algorithm: CompressionAlgorithm) -> Result<Vec<u8>> {
     match algorithm {
@@ -167,6 +664,19 @@ impl SerializedData {
     ) -> Result<Self> {
         // Sérialise l'objet
         let serialized = serialize_with_format(obj, format)?;
+        Self::from_serialized(serialized, format, compression)
+    }
+
+    /// Crée des données sérialisées à partir de l'encodage canonique d'un objet
+    pub fn from_canonical<T: CanonicalEncoding>(obj: &T, compression: CompressionAlgorithm) -> Result<Self> {
+        Self::from_serialized(obj.serialize_canonical()?, SerializationFormat::Canonical, compression)
+    }
+
+    fn from_serialized(
+        serialized: Vec<u8>,
+        format: SerializationFormat,
+        compression: CompressionAlgorithm,
+    ) -> Result<Self> {
         let original_size = serialized.len();
         
         // Compresse si nécessaire
@@ -187,6 +697,20 @@ impl SerializedData {
 
     /// Désérialise vers un objet
     pub fn to_object<T: for<'de> Deserialize<'de>>(&self) -> Result<T> {
+        deserialize_with_format(&self.payload()?, self.format)
+    }
+
+    /// Désérialise un objet de la chaîne, encodé canoniquement ou avant l'encodage canonique
+    pub fn to_canonical<T: CanonicalEncoding>(&self) -> Result<T> {
+        let payload = self.payload()?;
+        match self.format {
+            SerializationFormat::Canonical => T::deserialize_canonical(&payload),
+            format => deserialize_with_format(&payload, format),
+        }
+    }
+
+    /// Octets sérialisés, après vérification et décompression
+    fn payload(&self) -> Result<Vec<u8>> {
         // Vérifie le checksum
         let calculated_checksum = crate::crypto::compute_hash(&self.data, crate::crypto::HashAlgorithm::Blake3);
         if calculated_checksum != self.checksum {
@@ -201,8 +725,7 @@ impl SerializedData {
             return Err(SerializationError::Cbor("Size mismatch after decompression".to_string()).into());
         }
 
-        // Désérialise
-        deserialize_with_format(&decompressed, self.format)
+        Ok(decompressed)
     }
 
     /// Obtient le ratio de compression
@@ -226,28 +749,26 @@ pub mod blockchain_serialization {
     use crate::block::Block;
     use crate::transaction::Transaction;
 
-    /// Sérialise un bloc pour le stockage
+    /// Sérialise un bloc pour le stockage, sous sa forme canonique
     pub fn serialize_block(block: &Block) -> Result<SerializedData> {
-        SerializedData::from_object(
-            block,
-            SerializationFormat::Bincode,
-            CompressionAlgorithm::Zstd,
-        )
+        SerializedData::from_canonical(block, CompressionAlgorithm::Zstd)
     }
 
     /// Désérialise un bloc depuis le stockage
+    ///
+    /// Les blocs stockés en bincode avant l'encodage canonique restent lisibles.
     pub fn deserialize_block(data: &SerializedData) -> Result<Block> {
-        data.to_object()
+        data.to_canonical()
     }
 
     /// Sérialise une transaction pour le réseau
     pub fn serialize_transaction(tx: &Transaction) -> Result<Vec<u8>> {
-        serialize_with_format(tx, SerializationFormat::Bincode)
+        tx.serialize_canonical()
     }
 
     /// Désérialise une transaction depuis le réseau
     pub fn deserialize_transaction(data: &[u8]) -> Result<Transaction> {
-        deserialize_with_format(data, SerializationFormat::Bincode)
+        Transaction::deserialize_canonical(data)
     }
 
     /// Sérialise pour l'API JSON
@@ -277,6 +798,270 @@ impl Serializable for crate::transaction::Transaction {}
 impl Serializable for crate::block::ArchiveBlock {}
 impl Serializable for crate::crypto::Hash {}
 
+impl CanonicalEncoding for crate::block::Block {}
+impl CanonicalEncoding for crate::block::BlockHeader {}
+impl CanonicalEncoding for crate::transaction::Transaction {}
+impl CanonicalEncoding for crate::nodes::NetworkMessage {}
+
+// Disposition canonique des types de la chaîne. Les discriminants et l'ordre
+// des champs ci-dessous font partie du format : les modifier impose une
+// nouvelle `CANONICAL_FORMAT_VERSION`.
+
+canonical_enum!(HashAlgorithm { Blake3 = 0, Sha3 = 1 });
+
+canonical_struct!(crate::block::BlockHeader {
+    height,
+    previous_hash,
+    block_hash,
+    merkle_root,
+    timestamp,
+    difficulty,
+    nonce,
+    version,
+    size,
+    transaction_count,
+    archive_count,
+    state_root,
+    snapshot_manifest,
+    producer,
+    producer_signature,
+});
+
+canonical_struct!(crate::block::Block { header, body });
+
+canonical_struct!(crate::block::BlockBody {
+    transactions,
+    archives,
+    content_index,
+    storage_proof,
+});
+
+canonical_struct!(crate::block::ContentIndex {
+    keyword_index,
+    content_type_index,
+    domain_index,
+    language_index,
+    temporal_index,
+    stats,
+});
+
+canonical_struct!(crate::block::body::IndexStats {
+    total_entries,
+    unique_keywords,
+    content_types,
+    unique_domains,
+    languages,
+});
+
+canonical_struct!(crate::block::StorageProof {
+    proof_root,
+    archive_proofs,
+    generated_at,
+    algorithm,
+    proof_metadata,
+});
+
+canonical_struct!(crate::block::body::ArchiveStorageProof {
+    archive_hash,
+    merkle_proof,
+    challenge,
+    response,
+    proof_signature,
+});
+
+canonical_struct!(crate::state::MerkleProof {
+    leaf_hash,
+    path,
+    root_hash,
+    algorithm,
+});
+
+canonical_struct!(crate::block::body::StorageChallenge {
+    positions,
+    sample_size,
+    nonce,
+    timestamp,
+});
+
+canonical_struct!(crate::block::body::StorageChallengeResponse {
+    samples,
+    sample_hash,
+    timestamp,
+});
+
+canonical_struct!(crate::block::body::ProofMetadata {
+    total_archives,
+    total_size,
+    generation_time_ms,
+    protocol_version,
+});
+
+canonical_enum!(crate::block::CompressionType {
+    None = 0,
+    Gzip = 1,
+    Brotli = 2,
+    Lz4 = 3,
+    Zstd = 4,
+});
+
+canonical_struct!(crate::block::ArchiveBlock {
+    archive_id,
+    original_url,
+    capture_timestamp,
+    content_type,
+    compression,
+    size_compressed,
+    size_original,
+    checksum,
+    metadata,
+    verification_hash,
+    previous_capture,
+    revisit_of,
+});
+
+canonical_struct!(crate::block::ArchiveMetadata {
+    title,
+    description,
+    keywords,
+    content_type,
+    language,
+    author,
+    published_at,
+    custom_metadata,
+    external_links_count,
+    resource_count,
+    quality_score,
+    content_flags,
+});
+
+canonical_struct!(crate::block::archive_metadata::ContentFlags {
+    has_javascript,
+    has_forms,
+    has_media,
+    has_ads,
+    is_sensitive,
+    is_complete,
+});
+
+canonical_struct!(crate::transaction::Transaction {
+    tx_id,
+    tx_type,
+    inputs,
+    outputs,
+    fee,
+    nonce,
+    sender,
+    timestamp,
+    data,
+    signature,
+});
+
+canonical_struct!(crate::transaction::TransactionInput {
+    previous_tx,
+    output_index,
+    unlock_script,
+    signature,
+});
+
+canonical_struct!(crate::transaction::TransactionOutput {
+    amount,
+    recipient,
+    lock_script,
+});
+
+canonical_struct!(crate::transaction::MultisigSignature { signer, signature });
+
+/// Discriminants alignés sur ceux du hash de transaction
+impl CanonicalLayout for crate::transaction::TransactionType {
+    fn write_canonical(&self, writer: &mut CanonicalWriter) {
+        use crate::transaction::TransactionType;
+
+        match self {
+            TransactionType::Transfer => writer.write_u8(0),
+            TransactionType::Archive => writer.write_u8(1),
+            TransactionType::Stake => writer.write_u8(2),
+            TransactionType::Governance => writer.write_u8(3),
+            TransactionType::TokenTransfer { from, to, amount, fee, nonce } => {
+                writer.write_u8(4);
+                from.write_canonical(writer);
+                to.write_canonical(writer);
+                amount.write_canonical(writer);
+                fee.write_canonical(writer);
+                nonce.write_canonical(writer);
+            }
+            TransactionType::Multisig { signers, threshold, signatures } => {
+                writer.write_u8(5);
+                signers.write_canonical(writer);
+                threshold.write_canonical(writer);
+                signatures.write_canonical(writer);
+            }
+        }
+    }
+
+    fn read_canonical(reader: &mut CanonicalReader<'_>) -> Result<Self> {
+        use crate::transaction::TransactionType;
+
+        Ok(match reader.read_u8()? {
+            0 => TransactionType::Transfer,
+            1 => TransactionType::Archive,
+            2 => TransactionType::Stake,
+            3 => TransactionType::Governance,
+            4 => TransactionType::TokenTransfer {
+                from: CanonicalLayout::read_canonical(reader)?,
+                to: CanonicalLayout::read_canonical(reader)?,
+                amount: reader.read_u64()?,
+                fee: reader.read_u64()?,
+                nonce: reader.read_u64()?,
+            },
+            5 => TransactionType::Multisig {
+                signers: CanonicalLayout::read_canonical(reader)?,
+                threshold: reader.read_u32()?,
+                signatures: CanonicalLayout::read_canonical(reader)?,
+            },
+            other => {
+                return Err(canonical_error(format!("type de transaction inconnu: {}", other)));
+            }
+        })
+    }
+}
+
+impl CanonicalLayout for crate::consensus::NodeId {
+    fn write_canonical(&self, writer: &mut CanonicalWriter) {
+        self.0.write_canonical(writer);
+    }
+
+    fn read_canonical(reader: &mut CanonicalReader<'_>) -> Result<Self> {
+        Ok(Self(Hash::read_canonical(reader)?))
+    }
+}
+
+canonical_enum!(crate::nodes::MessageType {
+    Ping = 0,
+    Pong = 1,
+    NodeDiscovery = 2,
+    NodeAnnouncement = 3,
+    SyncRequest = 4,
+    SyncResponse = 5,
+    ConsensusChallenge = 6,
+    ConsensusResponse = 7,
+    ContentStore = 8,
+    ContentRejected = 9,
+    ContentRetrieve = 10,
+    ContentMetadata = 11,
+    Error = 12,
+});
+
+canonical_struct!(crate::nodes::NetworkMessage {
+    message_id,
+    sender,
+    recipient,
+    message_type,
+    payload,
+    timestamp,
+    ttl,
+    request_id,
+});
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -375,7 +1160,179 @@ mod tests {
         let serialized = blockchain_serialization::serialize_block(&block).unwrap();
         let deserialized = blockchain_serialization::deserialize_block(&serialized).unwrap();
         
+        assert_eq!(serialized.format, SerializationFormat::Canonical);
         assert_eq!(block.height(), deserialized.height());
         assert_eq!(block.hash(), deserialized.hash());
+
+        // Bloc stocké en bincode avant l'encodage canonique
+        let stored = SerializedData::from_object(&block, SerializationFormat::Bincode, CompressionAlgorithm::Zstd).unwrap();
+        let legacy = blockchain_serialization::deserialize_block(&stored).unwrap();
+        assert_eq!(legacy.header, block.header);
+    }
+
+    #[test]
+    fn test_canonical_block_roundtrip() {
+        use crate::block::{BlockBuilder, Block};
+        use crate::crypto::HashAlgorithm;
+
+        let block = BlockBuilder::new(3, Hash::zero(), HashAlgorithm::Blake3)
+            .build()
+            .unwrap();
+
+        let data = block.serialize_canonical().unwrap();
+        assert_eq!(data[0], CANONICAL_FORMAT_VERSION);
+        assert_eq!(data.len(), block.canonical_size());
+
+        let decoded = Block::deserialize_canonical(&data).unwrap();
+        assert_eq!(decoded.hash(), block.hash());
+        assert_eq!(decoded.header, block.header);
+    }
+
+    #[test]
+    fn test_canonical_decodes_legacy_encoding() {
+        use crate::block::{BlockBuilder, Block, BlockHeader};
+        use crate::crypto::HashAlgorithm;
+
+        // Hauteur 1 : le premier octet historique coïncide avec la version
+        let block = BlockBuilder::new(1, Hash::zero(), HashAlgorithm::Blake3)
+            .build()
+            .unwrap();
+
+        let legacy = bincode::serialize(&block).unwrap();
+        let decoded = Block::deserialize_canonical(&legacy).unwrap();
+        assert_eq!(decoded.header, block.header);
+
+        let legacy_header = bincode::serialize(&block.header).unwrap();
+        assert_eq!(BlockHeader::deserialize_canonical(&legacy_header).unwrap(), block.header);
+    }
+
+    #[test]
+    fn test_canonical_decodes_bincode_version() {
+        use crate::block::{BlockBuilder, Block};
+        use crate::crypto::HashAlgorithm;
+
+        let block = BlockBuilder::new(5, Hash::zero(), HashAlgorithm::Blake3)
+            .build()
+            .unwrap();
+
+        let mut data = vec![BINCODE_CANONICAL_VERSION];
+        data.extend(bincode_canonical_options().serialize(&block).unwrap());
+        let decoded = Block::deserialize_canonical(&data).unwrap();
+        assert_eq!(decoded.header, block.header);
+    }
+
+    #[test]
+    fn test_canonical_header_layout_is_explicit() {
+        use crate::block::BlockHeader;
+        use chrono::TimeZone;
+
+        let header = BlockHeader {
+            height: 7,
+            previous_hash: Hash::new([1; 32]),
+            block_hash: Hash::new([2; 32]),
+            merkle_root: Hash::new([3; 32]),
+            timestamp: Utc.timestamp_opt(1_700_000_000, 5).unwrap(),
+            difficulty: 4,
+            nonce: 9,
+            version: 1,
+            size: 128,
+            transaction_count: 2,
+            archive_count: 0,
+            state_root: Some(Hash::new([4; 32])),
+            snapshot_manifest: None,
+            producer: None,
+            producer_signature: None,
+        };
+
+        let mut expected = vec![CANONICAL_FORMAT_VERSION];
+        expected.extend_from_slice(&7u64.to_le_bytes());
+        expected.extend_from_slice(&[1; 32]);
+        expected.extend_from_slice(&[2; 32]);
+        expected.extend_from_slice(&[3; 32]);
+        expected.extend_from_slice(&1_700_000_000i64.to_le_bytes());
+        expected.extend_from_slice(&5u32.to_le_bytes());
+        expected.extend_from_slice(&4u64.to_le_bytes());
+        expected.extend_from_slice(&9u64.to_le_bytes());
+        expected.extend_from_slice(&1u32.to_le_bytes());
+        expected.extend_from_slice(&128u32.to_le_bytes());
+        expected.extend_from_slice(&2u32.to_le_bytes());
+        expected.extend_from_slice(&0u32.to_le_bytes());
+        expected.push(1);
+        expected.extend_from_slice(&[4; 32]);
+        expected.extend_from_slice(&[0, 0, 0]);
+
+        let data = header.serialize_canonical().unwrap();
+        assert_eq!(data, expected);
+        assert_eq!(BlockHeader::deserialize_canonical(&data).unwrap(), header);
+    }
+
+    #[test]
+    fn test_canonical_network_message_roundtrip() {
+        use crate::consensus::NodeId;
+        use crate::nodes::{MessageType, NetworkMessage};
+
+        let message = NetworkMessage {
+            message_id: Hash::new([7; 32]),
+            sender: NodeId(Hash::new([8; 32])),
+            recipient: None,
+            message_type: MessageType::ContentRetrieve,
+            payload: vec![1, 2, 3],
+            timestamp: Utc::now(),
+            ttl: 16,
+            request_id: Some("req-1".to_string()),
+        };
+
+        let data = message.serialize_canonical().unwrap();
+        assert_eq!(data.len(), message.canonical_size());
+
+        let decoded = NetworkMessage::deserialize_canonical(&data).unwrap();
+        assert_eq!(decoded.message_id, message.message_id);
+        assert_eq!(decoded.sender, message.sender);
+        assert_eq!(decoded.message_type, MessageType::ContentRetrieve);
+        assert_eq!(decoded.payload, message.payload);
+        assert_eq!(decoded.timestamp, message.timestamp);
+        assert_eq!(decoded.request_id, message.request_id);
+    }
+
+    #[test]
+    fn test_canonical_reader_rejects_oversized_length() {
+        let mut data = vec![CANONICAL_FORMAT_VERSION];
+        data.extend_from_slice(&u64::MAX.to_le_bytes());
+
+        let mut reader = CanonicalReader::new(&data[1..]);
+        assert!(Vec::<u8>::read_canonical(&mut reader).is_err());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_canonical_encoding_ignores_map_order() {
+        use crate::block::BlockBuilder;
+        use crate::crypto::HashAlgorithm;
+
+        let block = BlockBuilder::new(0, Hash::zero(), HashAlgorithm::Blake3)
+            .build()
+            .unwrap();
+        let keywords: Vec<String> = (0..32).map(|i| format!("mot-{}", i)).collect();
+
+        let mut forward = block.clone();
+        for keyword in &keywords {
+            forward.body.content_index.keyword_index.insert(keyword.clone(), vec![Hash::zero()]);
+        }
+        let mut backward = block;
+        for keyword in keywords.iter().rev() {
+            backward.body.content_index.keyword_index.insert(keyword.clone(), vec![Hash::zero()]);
+        }
+
+        assert_eq!(
+            forward.serialize_canonical().unwrap(),
+            backward.serialize_canonical().unwrap()
+        );
+    }
+
+    #[test]
+    fn test_canonical_rejects_unknown_version() {
+        use crate::transaction::Transaction;
+
+        let err = Transaction::deserialize_canonical(&[CANONICAL_FORMAT_VERSION + 1, 0, 0]).unwrap_err();
+        assert!(err.to_string().contains("version canonique"));
+    }
+}
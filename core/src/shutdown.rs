@@ -0,0 +1,342 @@
+//! Arrêt coordonné du nœud
+//!
+//! Le `ShutdownCoordinator` attend SIGINT/SIGTERM puis arrête les composants
+//! dans un ordre fixe :
+//!
+//! 1. **Drainage** : les serveurs REST/gRPC/WebSocket cessent d'accepter des
+//!    connexions et terminent les requêtes en cours dans le délai imparti ;
+//! 2. **Vidage** : le pool de transactions et les écritures d'état en attente
+//!    sont persistés, une fois qu'aucune requête ne peut plus en produire ;
+//! 3. **Réseau** : le P2P annonce sa déconnexion aux pairs puis s'arrête ;
+//! 4. **Persistance** : registre des nœuds et instantanés de métriques.
+//!
+//! Chaque composant implémente [`ShutdownHook`] et n'agit que dans les phases
+//! qui le concernent.
+
+use async_trait::async_trait;
+use futures::future::join_all;
+use serde::{Deserialize, Serialize};
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::watch;
+
+use crate::error::{CoreError, Result};
+
+/// Configuration de l'arrêt
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ShutdownConfig {
+    /// Délai accordé aux serveurs pour drainer les requêtes en cours
+    pub drain_timeout: Duration,
+    /// Délai accordé à chaque composant dans les phases suivantes
+    pub step_timeout: Duration,
+}
+
+impl Default for ShutdownConfig {
+    fn default() -> Self {
+        Self {
+            drain_timeout: Duration::from_secs(30),
+            step_timeout: Duration::from_secs(10),
+        }
+    }
+}
+
+/// Jeton d'arrêt partagé entre les composants
+///
+/// Les clones partagent le même état : déclencher l'un déclenche tous les autres.
+#[derive(Debug, Clone)]
+pub struct ShutdownToken {
+    sender: Arc<watch::Sender<bool>>,
+}
+
+impl ShutdownToken {
+    /// Crée un jeton non déclenché
+    pub fn new() -> Self {
+        let (sender, _) = watch::channel(false);
+        Self { sender: Arc::new(sender) }
+    }
+
+    /// Déclenche l'arrêt
+    pub fn trigger(&self) {
+        self.sender.send_replace(true);
+    }
+
+    /// Indique si l'arrêt a été déclenché
+    pub fn is_triggered(&self) -> bool {
+        *self.sender.borrow()
+    }
+
+    /// Se résout dès que l'arrêt est déclenché
+    pub async fn triggered(&self) {
+        let mut receiver = self.sender.subscribe();
+        let _ = receiver.wait_for(|triggered| *triggered).await;
+    }
+
+    /// Se résout quand le délai de drainage est écoulé après le déclenchement
+    pub async fn drain_deadline(&self, drain_timeout: Duration) {
+        self.triggered().await;
+        tokio::time::sleep(drain_timeout).await;
+    }
+}
+
+impl Default for ShutdownToken {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Fin du drainage d'un serveur
+#[derive(Debug, Clone)]
+pub struct Drained {
+    receiver: watch::Receiver<bool>,
+}
+
+impl Drained {
+    /// Lance un serveur dont l'arrêt est piloté par `token`
+    ///
+    /// `server` doit lui-même cesser d'accepter des connexions au déclenchement
+    /// du jeton ; si le drainage dépasse `drain_timeout`, le serveur est
+    /// abandonné et considéré comme arrêté.
+    pub fn spawn<F>(name: &'static str, token: ShutdownToken, drain_timeout: Duration, server: F) -> Self
+    where
+        F: Future<Output = ()> + Send + 'static,
+    {
+        let (sender, receiver) = watch::channel(false);
+
+        tokio::spawn(async move {
+            tokio::select! {
+                _ = server => tracing::info!("{} server drained", name),
+                _ = token.drain_deadline(drain_timeout) => {
+                    tracing::warn!("{} server did not drain within {:?}, dropping remaining connections", name, drain_timeout);
+                }
+            }
+            sender.send_replace(true);
+        });
+
+        Self { receiver }
+    }
+
+    /// Se résout quand le serveur est arrêté
+    pub async fn wait(&self) {
+        let mut receiver = self.receiver.clone();
+        // Une tâche serveur disparue compte comme arrêtée
+        let _ = receiver.wait_for(|drained| *drained).await;
+    }
+}
+
+/// Phases de l'arrêt, dans leur ordre d'exécution
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+pub enum ShutdownPhase {
+    /// Arrêt des serveurs et drainage des requêtes en cours
+    Drain,
+    /// Vidage du pool de transactions et des écritures d'état
+    Flush,
+    /// Déconnexion annoncée aux pairs et arrêt du P2P
+    Network,
+    /// Sauvegarde du registre et des métriques
+    Persist,
+}
+
+impl ShutdownPhase {
+    /// Toutes les phases, dans l'ordre
+    pub const ALL: [ShutdownPhase; 4] = [
+        ShutdownPhase::Drain,
+        ShutdownPhase::Flush,
+        ShutdownPhase::Network,
+        ShutdownPhase::Persist,
+    ];
+}
+
+/// Composant participant à l'arrêt coordonné
+#[async_trait]
+pub trait ShutdownHook: Send + Sync {
+    /// Nom du composant dans les journaux et le rapport
+    fn name(&self) -> &str;
+
+    /// Exécute la part du composant dans une phase ; ignore les autres phases
+    async fn on_shutdown(&self, phase: ShutdownPhase) -> Result<()>;
+}
+
+/// Résultat d'une étape de l'arrêt
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum StepOutcome {
+    /// Étape terminée
+    Completed,
+    /// Étape en échec
+    Failed(String),
+    /// Étape interrompue par son délai
+    TimedOut,
+}
+
+/// Rapport de l'arrêt, une entrée par composant et par phase
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ShutdownReport {
+    /// Étapes exécutées, dans l'ordre
+    pub steps: Vec<(ShutdownPhase, String, StepOutcome)>,
+}
+
+impl ShutdownReport {
+    /// Vrai si toutes les étapes se sont terminées
+    pub fn is_clean(&self) -> bool {
+        self.steps.iter().all(|(_, _, outcome)| *outcome == StepOutcome::Completed)
+    }
+}
+
+/// Orchestrateur de l'arrêt du nœud
+pub struct ShutdownCoordinator {
+    config: ShutdownConfig,
+    token: ShutdownToken,
+    hooks: Vec<Arc<dyn ShutdownHook>>,
+}
+
+impl ShutdownCoordinator {
+    /// Crée un coordinateur sans composant
+    pub fn new(config: ShutdownConfig) -> Self {
+        Self {
+            config,
+            token: ShutdownToken::new(),
+            hooks: Vec::new(),
+        }
+    }
+
+    /// Jeton à transmettre aux serveurs (`ServerState::with_shutdown_token`)
+    pub fn token(&self) -> ShutdownToken {
+        self.token.clone()
+    }
+
+    /// Ajoute un composant à l'arrêt
+    pub fn with_hook(mut self, hook: Arc<dyn ShutdownHook>) -> Self {
+        self.hooks.push(hook);
+        self
+    }
+
+    /// Attend SIGINT ou SIGTERM puis arrête le nœud
+    ///
+    /// Un déclenchement du jeton par un autre composant lance aussi l'arrêt.
+    pub async fn run_until_signal(&self) -> ShutdownReport {
+        tokio::select! {
+            signal = wait_for_signal() => tracing::info!("Received {}, shutting down", signal),
+            _ = self.token.triggered() => tracing::info!("Shutdown requested"),
+        }
+        self.shutdown().await
+    }
+
+    /// Déclenche l'arrêt et exécute les phases dans l'ordre
+    ///
+    /// Les composants d'une même phase s'arrêtent en parallèle ; une étape en
+    /// échec ou hors délai n'empêche pas les phases suivantes.
+    pub async fn shutdown(&self) -> ShutdownReport {
+        self.token.trigger();
+
+        let mut report = ShutdownReport::default();
+        for phase in ShutdownPhase::ALL {
+            let timeout = match phase {
+                ShutdownPhase::Drain => self.config.drain_timeout,
+                _ => self.config.step_timeout,
+            };
+
+            let steps = self.hooks.iter().map(|hook| async move {
+                let outcome = match tokio::time::timeout(timeout, hook.on_shutdown(phase)).await {
+                    Ok(Ok(())) => StepOutcome::Completed,
+                    Ok(Err(e)) => {
+                        tracing::error!("{} failed during {:?}: {}", hook.name(), phase, e);
+                        StepOutcome::Failed(e.to_string())
+                    }
+                    Err(_) => {
+                        tracing::warn!("{} did not finish {:?} within {:?}", hook.name(), phase, timeout);
+                        StepOutcome::TimedOut
+                    }
+                };
+                (phase, hook.name().to_string(), outcome)
+            });
+            report.steps.extend(join_all(steps).await);
+        }
+
+        tracing::info!("Shutdown complete");
+        report
+    }
+}
+
+#[cfg(unix)]
+async fn wait_for_signal() -> &'static str {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    let mut terminate = match signal(SignalKind::terminate()) {
+        Ok(terminate) => terminate,
+        Err(e) => {
+            tracing::warn!("Cannot listen for SIGTERM: {}", e);
+            let _ = tokio::signal::ctrl_c().await;
+            return "SIGINT";
+        }
+    };
+
+    tokio::select! {
+        _ = tokio::signal::ctrl_c() => "SIGINT",
+        _ = terminate.recv() => "SIGTERM",
+    }
+}
+
+#[cfg(not(unix))]
+async fn wait_for_signal() -> &'static str {
+    let _ = tokio::signal::ctrl_c().await;
+    "SIGINT"
+}
+
+/// Convertit l'erreur d'un composant en erreur d'arrêt
+pub(crate) fn shutdown_error(component: &str, error: impl std::fmt::Display) -> CoreError {
+    CoreError::Internal {
+        message: format!("{} shutdown failed: {}", component, error),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::sync::Mutex;
+
+    struct RecordingHook {
+        name: &'static str,
+        log: Arc<Mutex<Vec<(ShutdownPhase, &'static str)>>>,
+        stall_in: Option<ShutdownPhase>,
+    }
+
+    #[async_trait]
+    impl ShutdownHook for RecordingHook {
+        fn name(&self) -> &str {
+            self.name
+        }
+
+        async fn on_shutdown(&self, phase: ShutdownPhase) -> Result<()> {
+            if self.stall_in == Some(phase) {
+                std::future::pending::<()>().await;
+            }
+            self.log.lock().await.push((phase, self.name));
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_phases_run_in_order_and_stalled_steps_time_out() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let coordinator = ShutdownCoordinator::new(ShutdownConfig {
+            drain_timeout: Duration::from_millis(50),
+            step_timeout: Duration::from_millis(50),
+        })
+        .with_hook(Arc::new(RecordingHook { name: "api", log: log.clone(), stall_in: Some(ShutdownPhase::Drain) }))
+        .with_hook(Arc::new(RecordingHook { name: "p2p", log: log.clone(), stall_in: None }));
+        let token = coordinator.token();
+
+        let report = coordinator.shutdown().await;
+
+        assert!(token.is_triggered());
+        assert!(!report.is_clean());
+        assert_eq!(report.steps.len(), 8);
+        assert_eq!(report.steps[0], (ShutdownPhase::Drain, "api".to_string(), StepOutcome::TimedOut));
+
+        // Le composant bloqué au drainage participe quand même aux phases suivantes
+        let log = log.lock().await;
+        let phases: Vec<_> = log.iter().map(|(phase, _)| *phase).collect();
+        assert!(phases.windows(2).all(|pair| pair[0] <= pair[1]));
+        assert_eq!(log.iter().filter(|(_, name)| *name == "api").count(), 3);
+    }
+}
@@ -1,8 +1,12 @@
 //! Machine d'état pour ArchiveChain
 
 use std::collections::HashMap;
-use crate::crypto::Hash;
-use crate::error::{StateError, Result};
+use crate::crypto::{Hash, HashAlgorithm, PublicKey, compute_blake3};
+use crate::error::{StateError, SerializationError, Result};
+use super::{MerkleTree, StateRoot, StateSnapshot};
+
+/// Préfixe des clés d'état stockant le dernier nonce appliqué d'un compte
+const ACCOUNT_NONCE_PREFIX: &[u8] = b"account_nonce:";
 
 /// Clé d'état dans la machine d'état
 pub type StateKey = Hash;
@@ -52,10 +56,32 @@ impl StateMachine {
             }
         }
         
-        self.transitions.push(transition);
+        // L'ancienne valeur est celle de l'état, pas celle annoncée par l'appelant
+        self.transitions.push(StateTransition { old_value, ..transition });
         Ok(())
     }
 
+    /// Retire de l'historique les transitions appliquées depuis le dernier appel
+    pub fn take_transitions(&mut self) -> Vec<StateTransition> {
+        std::mem::take(&mut self.transitions)
+    }
+
+    /// Annule des transitions, de la plus récente à la plus ancienne
+    ///
+    /// Les annulations ne sont pas inscrites dans l'historique.
+    pub fn revert_transitions(&mut self, transitions: &[StateTransition]) {
+        for transition in transitions.iter().rev() {
+            match &transition.old_value {
+                Some(value) => {
+                    self.state.insert(transition.key.clone(), value.clone());
+                }
+                None => {
+                    self.state.remove(&transition.key);
+                }
+            }
+        }
+    }
+
     /// Obtient une valeur d'état
     pub fn get(&self, key: &StateKey) -> Option<&StateValue> {
         self.state.get(key)
@@ -89,6 +115,31 @@ impl StateMachine {
         Ok(old_value)
     }
 
+    /// Clé d'état du nonce d'un compte
+    pub fn account_nonce_key(account: &PublicKey) -> StateKey {
+        let mut data = Vec::with_capacity(ACCOUNT_NONCE_PREFIX.len() + account.as_bytes().len());
+        data.extend_from_slice(ACCOUNT_NONCE_PREFIX);
+        data.extend_from_slice(account.as_bytes());
+        compute_blake3(&data)
+    }
+
+    /// Dernier nonce appliqué pour un compte (None si aucune transaction)
+    pub fn account_nonce(&self, account: &PublicKey) -> Option<u64> {
+        let value = self.state.get(&Self::account_nonce_key(account))?;
+        let bytes: [u8; 8] = value.as_slice().try_into().ok()?;
+        Some(u64::from_le_bytes(bytes))
+    }
+
+    /// Nonce attendu pour la prochaine transaction d'un compte
+    pub fn next_nonce(&self, account: &PublicKey) -> u64 {
+        self.account_nonce(account).map_or(0, |nonce| nonce + 1)
+    }
+
+    /// Enregistre le dernier nonce appliqué pour un compte
+    pub fn set_account_nonce(&mut self, account: &PublicKey, nonce: u64) -> Result<()> {
+        self.set(Self::account_nonce_key(account), nonce.to_le_bytes().to_vec())
+    }
+
     /// Obtient toutes les clés
     pub fn keys(&self) -> Vec<&StateKey> {
         self.state.keys().collect()
@@ -103,6 +154,36 @@ impl StateMachine {
     pub fn is_empty(&self) -> bool {
         self.state.is_empty()
     }
+
+    /// Racine de Merkle de l'état actuel
+    pub fn state_root(&self, algorithm: HashAlgorithm) -> StateRoot {
+        let entries: Vec<(StateKey, StateValue)> = self.state
+            .iter()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
+        MerkleTree::compute_state_root(&entries, algorithm)
+    }
+
+    /// Crée un snapshot de l'état actuel
+    pub fn snapshot(&self) -> Result<StateSnapshot> {
+        let data = bincode::serialize(&self.state).map_err(SerializationError::Bincode)?;
+        Ok(StateSnapshot {
+            state_root: self.state_root(HashAlgorithm::Blake3),
+            timestamp: chrono::Utc::now(),
+            data,
+        })
+    }
+
+    /// Restaure l'état depuis un snapshot
+    ///
+    /// L'historique des transitions est vidé : il ne correspond plus à l'état restauré.
+    pub fn restore_snapshot(&mut self, snapshot: &StateSnapshot) -> Result<()> {
+        let state: HashMap<StateKey, StateValue> = bincode::deserialize(&snapshot.data)
+            .map_err(SerializationError::Bincode)?;
+        self.state = state;
+        self.transitions.clear();
+        Ok(())
+    }
 }
 
 impl Default for StateMachine {
@@ -3,9 +3,10 @@
 //! Fournit un arbre de Merkle efficace pour maintenir l'intégrité des données
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use crate::crypto::{Hash, HashAlgorithm, compute_hash, compute_combined_hash};
 use crate::error::{StateError, Result};
+use super::storage::{StateKey, StateValue};
 
 /// Nœud d'un arbre de Merkle
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -52,20 +53,52 @@ impl MerkleNode {
 }
 
 /// Preuve de Merkle pour vérifier qu'un élément fait partie de l'arbre
+///
+/// Nombre impair de nœuds : le dernier nœud d'un niveau sans voisin est promu
+/// tel quel au niveau supérieur, sans être dupliqué ni complété. Ce niveau
+/// n'ajoute donc aucune étape au chemin, et les arbres `[a, b, c]` et
+/// `[a, b, c, c]` ont des racines distinctes. La vérification est identique
+/// quel que soit le nombre de feuilles.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MerkleProof {
     /// Hash de l'élément à prouver
     pub leaf_hash: Hash,
-    /// Chemin de preuves (hash et position - true pour droite, false pour gauche)
+    /// Chemin de preuves (hash du voisin et sa position - true pour droite, false pour gauche)
     pub path: Vec<(Hash, bool)>,
     /// Hash de la racine
     pub root_hash: Hash,
+    /// Algorithme de hachage utilisé pour construire la preuve (Blake3 pour
+    /// les preuves sérialisées avant l'ajout de ce champ)
+    #[serde(default = "default_proof_algorithm")]
+    pub algorithm: HashAlgorithm,
+}
+
+fn default_proof_algorithm() -> HashAlgorithm {
+    HashAlgorithm::Blake3
 }
 
 impl MerkleProof {
-    /// Vérifie la validité de la preuve
-    pub fn verify(&self, algorithm: HashAlgorithm) -> bool {
-        let mut current_hash = self.leaf_hash.clone();
+    /// Vérifie la cohérence interne de la preuve (feuille -> racine incluse)
+    pub fn verify_path(&self, algorithm: HashAlgorithm) -> bool {
+        self.compute_root(&self.leaf_hash, algorithm) == self.root_hash
+    }
+
+    /// Vérifie qu'une feuille est incluse sous une racine de confiance
+    ///
+    /// Ne nécessite pas l'arbre complet : un client léger n'a besoin que de la
+    /// racine (issue d'un en-tête de bloc) et de la preuve.
+    pub fn verify(&self, root: &Hash, leaf_hash: &Hash) -> bool {
+        *leaf_hash == self.leaf_hash && self.compute_root(leaf_hash, self.algorithm) == *root
+    }
+
+    /// Vérifie qu'une paire clé/valeur d'état est incluse sous une racine de confiance
+    pub fn verify_state(&self, root: &Hash, key: &StateKey, value: &[u8]) -> bool {
+        self.verify(root, &MerkleTree::state_leaf_hash(key, value, self.algorithm))
+    }
+
+    /// Recalcule la racine en remontant le chemin de preuve
+    fn compute_root(&self, leaf_hash: &Hash, algorithm: HashAlgorithm) -> Hash {
+        let mut current_hash = leaf_hash.clone();
         
         for (sibling_hash, is_right) in &self.path {
             current_hash = if *is_right {
@@ -77,7 +110,7 @@ impl MerkleProof {
             };
         }
         
-        current_hash == self.root_hash
+        current_hash
     }
 }
 
@@ -92,6 +125,12 @@ pub struct MerkleTree {
     algorithm: HashAlgorithm,
     /// Index des feuilles pour un accès rapide
     leaf_indices: HashMap<Hash, usize>,
+    /// Index des feuilles par clé d'état (arbres construits avec `from_state`)
+    #[serde(default)]
+    leaf_keys: HashMap<StateKey, usize>,
+    /// Index du parent de chaque nœud (None pour la racine)
+    #[serde(default)]
+    parents: Vec<Option<usize>>,
 }
 
 impl MerkleTree {
@@ -102,28 +141,78 @@ impl MerkleTree {
             root_index: None,
             algorithm,
             leaf_indices: HashMap::new(),
+            leaf_keys: HashMap::new(),
+            parents: Vec::new(),
         }
     }
 
     /// Construit un arbre de Merkle à partir de données
     pub fn from_data(data_items: Vec<Vec<u8>>, algorithm: HashAlgorithm) -> Self {
+        let leaves = data_items
+            .into_iter()
+            .map(|data| (compute_hash(&data, algorithm), Some(data)))
+            .collect();
+        Self::from_leaves(leaves, algorithm)
+    }
+
+    /// Construit un arbre à partir de hashs existants
+    pub fn from_hashes(hashes: Vec<Hash>, algorithm: HashAlgorithm) -> Self {
+        let leaves = hashes.into_iter().map(|hash| (hash, None)).collect();
+        Self::from_leaves(leaves, algorithm)
+    }
+
+    /// Construit un arbre d'état à partir de paires clé/valeur
+    ///
+    /// Les entrées sont triées par clé afin que la racine soit déterministe.
+    /// Chaque feuille engage à la fois la clé et la valeur.
+    pub fn from_state(entries: &[(StateKey, StateValue)], algorithm: HashAlgorithm) -> Self {
+        let mut sorted: Vec<&(StateKey, StateValue)> = entries.iter().collect();
+        sorted.sort_by(|(a, _), (b, _)| a.as_bytes().cmp(b.as_bytes()));
+        sorted.dedup_by(|(a, _), (b, _)| a == b);
+
+        let leaves = sorted
+            .iter()
+            .map(|(key, value)| (Self::state_leaf_hash(key, value, algorithm), Some(value.clone())))
+            .collect();
+        let mut tree = Self::from_leaves(leaves, algorithm);
+
+        for (index, (key, _)) in sorted.into_iter().enumerate() {
+            tree.leaf_keys.insert(key.clone(), index);
+        }
+
+        tree
+    }
+
+    /// Calcule la racine d'état d'un ensemble de paires clé/valeur
+    ///
+    /// Retourne `Hash::zero()` pour un état vide.
+    pub fn compute_state_root(entries: &[(StateKey, StateValue)], algorithm: HashAlgorithm) -> Hash {
+        Self::from_state(entries, algorithm)
+            .root_hash()
+            .cloned()
+            .unwrap_or_else(Hash::zero)
+    }
+
+    /// Hash d'une feuille d'état (engage la clé et la valeur)
+    pub fn state_leaf_hash(key: &StateKey, value: &[u8], algorithm: HashAlgorithm) -> Hash {
+        compute_combined_hash(&[key.as_bytes(), value], algorithm)
+    }
+
+    /// Construit l'arbre niveau par niveau à partir des feuilles
+    fn from_leaves(leaves: Vec<(Hash, Option<Vec<u8>>)>, algorithm: HashAlgorithm) -> Self {
         let mut tree = Self::new(algorithm);
         
-        if data_items.is_empty() {
+        if leaves.is_empty() {
             return tree;
         }
         
         // Crée les feuilles
         let mut current_level: Vec<usize> = Vec::new();
-        for data in data_items {
-            let hash = compute_hash(&data, algorithm);
-            let leaf = MerkleNode::Leaf {
-                hash: hash.clone(),
-                data: Some(data),
-            };
+        for (hash, data) in leaves {
             let index = tree.nodes.len();
-            tree.leaf_indices.insert(hash, index);
-            tree.nodes.push(leaf);
+            tree.leaf_indices.insert(hash.clone(), index);
+            tree.nodes.push(MerkleNode::Leaf { hash, data });
+            tree.parents.push(None);
             current_level.push(index);
         }
         
@@ -137,13 +226,7 @@ impl MerkleTree {
                     // Paire complète
                     let left_idx = chunk[0];
                     let right_idx = chunk[1];
-                    let left_hash = tree.nodes[left_idx].hash();
-                    let right_hash = tree.nodes[right_idx].hash();
-                    
-                    let combined_hash = compute_combined_hash(
-                        &[left_hash.as_bytes(), right_hash.as_bytes()],
-                        algorithm
-                    );
+                    let combined_hash = tree.combine_children(left_idx, right_idx);
                     
                     let internal = MerkleNode::Internal {
                         hash: combined_hash,
@@ -153,6 +236,9 @@ impl MerkleTree {
                     
                     let index = tree.nodes.len();
                     tree.nodes.push(internal);
+                    tree.parents.push(None);
+                    tree.parents[left_idx] = Some(index);
+                    tree.parents[right_idx] = Some(index);
                     next_level.push(index);
                 } else {
                     // Nœud orphelin - promouvoir au niveau suivant
@@ -171,65 +257,104 @@ impl MerkleTree {
         tree
     }
 
-    /// Construit un arbre à partir de hashs existants
-    pub fn from_hashes(hashes: Vec<Hash>, algorithm: HashAlgorithm) -> Self {
-        let mut tree = Self::new(algorithm);
-        
-        if hashes.is_empty() {
-            return tree;
+    /// Calcule le hash combiné de deux nœuds enfants
+    fn combine_children(&self, left: usize, right: usize) -> Hash {
+        compute_combined_hash(
+            &[self.nodes[left].hash().as_bytes(), self.nodes[right].hash().as_bytes()],
+            self.algorithm
+        )
+    }
+
+    /// Met à jour la valeur d'une clé d'état et retourne la nouvelle racine
+    ///
+    /// Si la clé existe déjà, seul le chemin feuille -> racine est recalculé.
+    /// Une nouvelle clé modifie la forme de l'arbre et provoque une reconstruction.
+    pub fn update_leaf(&mut self, key: StateKey, new_value: StateValue) -> Result<Hash> {
+        self.batch_update(&[(key, new_value)])
+    }
+
+    /// Met à jour plusieurs clés d'état et retourne la nouvelle racine
+    ///
+    /// Chaque nœud interne affecté n'est recalculé qu'une seule fois, même
+    /// lorsque plusieurs feuilles partagent un ancêtre.
+    pub fn batch_update(&mut self, updates: &[(StateKey, StateValue)]) -> Result<Hash> {
+        if updates.iter().any(|(key, _)| !self.leaf_keys.contains_key(key)) {
+            return self.rebuild_with(updates);
         }
-        
-        // Crée les feuilles sans données
-        let mut current_level: Vec<usize> = Vec::new();
-        for hash in hashes {
-            let leaf = MerkleNode::Leaf {
-                hash: hash.clone(),
-                data: None,
+
+        let mut dirty = BTreeSet::new();
+        for (key, value) in updates {
+            let leaf_index = self.leaf_keys[key];
+            let new_hash = Self::state_leaf_hash(key, value, self.algorithm);
+
+            let old_hash = self.nodes[leaf_index].hash().clone();
+            if self.leaf_indices.get(&old_hash) == Some(&leaf_index) {
+                self.leaf_indices.remove(&old_hash);
+            }
+            self.leaf_indices.insert(new_hash.clone(), leaf_index);
+            self.nodes[leaf_index] = MerkleNode::Leaf {
+                hash: new_hash,
+                data: Some(value.clone()),
             };
-            let index = tree.nodes.len();
-            tree.leaf_indices.insert(hash, index);
-            tree.nodes.push(leaf);
-            current_level.push(index);
+
+            if let Some(parent) = self.parent_of(leaf_index) {
+                dirty.insert(parent);
+            }
         }
-        
-        // Construit l'arbre comme précédemment
-        while current_level.len() > 1 {
-            let mut next_level = Vec::new();
-            
-            for chunk in current_level.chunks(2) {
-                if chunk.len() == 2 {
-                    let left_idx = chunk[0];
-                    let right_idx = chunk[1];
-                    let left_hash = tree.nodes[left_idx].hash();
-                    let right_hash = tree.nodes[right_idx].hash();
-                    
-                    let combined_hash = compute_combined_hash(
-                        &[left_hash.as_bytes(), right_hash.as_bytes()],
-                        algorithm
-                    );
-                    
-                    let internal = MerkleNode::Internal {
-                        hash: combined_hash,
-                        left: left_idx,
-                        right: right_idx,
-                    };
-                    
-                    let index = tree.nodes.len();
-                    tree.nodes.push(internal);
-                    next_level.push(index);
-                } else {
-                    next_level.push(chunk[0]);
-                }
+
+        // Les parents ont toujours un index supérieur à leurs enfants : traiter
+        // les index dans l'ordre croissant garantit que les enfants sont à jour.
+        while let Some(index) = dirty.pop_first() {
+            if let MerkleNode::Internal { left, right, .. } = self.nodes[index] {
+                let hash = self.combine_children(left, right);
+                self.nodes[index] = MerkleNode::Internal { hash, left, right };
+            }
+            if let Some(parent) = self.parent_of(index) {
+                dirty.insert(parent);
             }
-            
-            current_level = next_level;
         }
-        
-        if !current_level.is_empty() {
-            tree.root_index = Some(current_level[0]);
+
+        self.root_hash().cloned().ok_or_else(|| StateError::InvalidMerkleRoot.into())
+    }
+
+    /// Reconstruit l'arbre d'état en fusionnant les mises à jour aux entrées existantes
+    fn rebuild_with(&mut self, updates: &[(StateKey, StateValue)]) -> Result<Hash> {
+        let mut entries: HashMap<StateKey, StateValue> = self.state_entries()?.into_iter().collect();
+        for (key, value) in updates {
+            entries.insert(key.clone(), value.clone());
+        }
+
+        let entries: Vec<(StateKey, StateValue)> = entries.into_iter().collect();
+        *self = Self::from_state(&entries, self.algorithm);
+
+        self.root_hash().cloned().ok_or_else(|| StateError::InvalidMerkleRoot.into())
+    }
+
+    /// Retourne les paires clé/valeur d'un arbre d'état
+    fn state_entries(&self) -> Result<Vec<(StateKey, StateValue)>> {
+        if self.leaf_keys.is_empty() && !self.leaf_indices.is_empty() {
+            // Arbre construit à partir de données brutes ou de hashs, sans clés
+            return Err(StateError::InconsistentState.into());
+        }
+
+        self.leaf_keys
+            .iter()
+            .map(|(key, &index)| {
+                self.nodes[index]
+                    .data()
+                    .map(|value| (key.clone(), value.to_vec()))
+                    .ok_or_else(|| StateError::MerkleNodeNotFound.into())
+            })
+            .collect()
+    }
+
+    /// Retourne l'index du parent d'un nœud
+    fn parent_of(&self, index: usize) -> Option<usize> {
+        match self.parents.get(index) {
+            Some(parent) => *parent,
+            // Arbres désérialisés sans table des parents
+            None => self.find_parent_index(index),
         }
-        
-        tree
     }
 
     /// Obtient le hash de la racine
@@ -237,9 +362,13 @@ impl MerkleTree {
         self.root_index.map(|idx| self.nodes[idx].hash())
     }
 
-    /// Génère une preuve de Merkle pour un hash donné
-    pub fn generate_proof(&self, target_hash: &Hash) -> Result<MerkleProof> {
-        let leaf_index = self.leaf_indices.get(target_hash)
+    /// Génère une preuve de Merkle pour une clé d'état ou un hash de feuille
+    ///
+    /// La cible est d'abord recherchée parmi les clés d'état, puis parmi les
+    /// hashs de feuilles.
+    pub fn generate_proof(&self, target: &Hash) -> Result<MerkleProof> {
+        let leaf_index = self.leaf_keys.get(target)
+            .or_else(|| self.leaf_indices.get(target))
             .ok_or(StateError::MerkleNodeNotFound)?;
         
         let root_hash = self.root_hash()
@@ -250,37 +379,24 @@ impl MerkleTree {
         let mut current_index = *leaf_index;
         
         // Remonte l'arbre jusqu'à la racine
-        for node in &self.nodes {
-            if let MerkleNode::Internal { left, right, .. } = node {
+        while let Some(parent_idx) = self.parent_of(current_index) {
+            if let MerkleNode::Internal { left, right, .. } = &self.nodes[parent_idx] {
                 if *left == current_index {
                     // Le nœud courant est à gauche, ajoute le sibling droit
-                    let sibling_hash = self.nodes[*right].hash().clone();
-                    path.push((sibling_hash, true)); // true = sibling à droite
-                    
-                    // Trouve l'index du nœud parent
-                    if let Some(parent_idx) = self.find_parent_index(current_index) {
-                        current_index = parent_idx;
-                    } else {
-                        break;
-                    }
-                } else if *right == current_index {
+                    path.push((self.nodes[*right].hash().clone(), true)); // true = sibling à droite
+                } else {
                     // Le nœud courant est à droite, ajoute le sibling gauche
-                    let sibling_hash = self.nodes[*left].hash().clone();
-                    path.push((sibling_hash, false)); // false = sibling à gauche
-                    
-                    if let Some(parent_idx) = self.find_parent_index(current_index) {
-                        current_index = parent_idx;
-                    } else {
-                        break;
-                    }
+                    path.push((self.nodes[*left].hash().clone(), false)); // false = sibling à gauche
                 }
             }
+            current_index = parent_idx;
         }
         
         Ok(MerkleProof {
-            leaf_hash: target_hash.clone(),
+            leaf_hash: self.nodes[*leaf_index].hash().clone(),
             path,
             root_hash,
+            algorithm: self.algorithm,
         })
     }
 
@@ -416,12 +532,26 @@ mod tests {
             assert!(tree.contains(&target_hash));
             
             let proof = tree.generate_proof(&target_hash).unwrap();
-            assert!(proof.verify(HashAlgorithm::Blake3));
+            assert!(proof.verify_path(HashAlgorithm::Blake3));
             assert_eq!(proof.leaf_hash, target_hash);
             assert_eq!(proof.root_hash, *tree.root_hash().unwrap());
         }
     }
 
+    #[test]
+    fn test_proof_without_algorithm_deserializes_as_blake3() {
+        let data = vec![b"data 1".to_vec(), b"data 2".to_vec(), b"data 3".to_vec()];
+        let tree = MerkleTree::from_data(data, HashAlgorithm::Blake3);
+        let proof = tree.generate_proof(&compute_blake3(b"data 2")).unwrap();
+
+        let mut value = serde_json::to_value(&proof).unwrap();
+        value.as_object_mut().unwrap().remove("algorithm");
+
+        let legacy: MerkleProof = serde_json::from_value(value).unwrap();
+        assert_eq!(legacy.algorithm, HashAlgorithm::Blake3);
+        assert!(legacy.verify_path(legacy.algorithm));
+    }
+
     #[test]
     fn test_from_hashes() {
         let hashes = vec![
@@ -449,4 +579,88 @@ mod tests {
         let result = tree.generate_proof(&non_existent_hash);
         assert!(result.is_err());
     }
-}
\ No newline at end of file
+
+    fn state_entries(count: u8) -> Vec<(StateKey, StateValue)> {
+        (0..count)
+            .map(|i| (compute_blake3(&[i]), format!("value {}", i).into_bytes()))
+            .collect()
+    }
+
+    async fn storage_root(entries: &[(StateKey, StateValue)]) -> Hash {
+        use crate::state::{MemoryStateStorage, StateStorage};
+
+        let mut storage = MemoryStateStorage::new();
+        for (key, value) in entries {
+            storage.set(key.clone(), value.clone()).await.unwrap();
+        }
+        storage.calculate_state_root().await.unwrap()
+    }
+
+    #[tokio::test]
+    async fn test_update_leaf_matches_full_rebuild() {
+        let mut entries = state_entries(7);
+        let mut tree = MerkleTree::from_state(&entries, HashAlgorithm::Blake3);
+        assert_eq!(*tree.root_hash().unwrap(), storage_root(&entries).await);
+
+        let key = entries[3].0.clone();
+        let new_root = tree.update_leaf(key, b"updated".to_vec()).unwrap();
+        entries[3].1 = b"updated".to_vec();
+
+        assert_eq!(new_root, storage_root(&entries).await);
+        assert_eq!(new_root, MerkleTree::compute_state_root(&entries, HashAlgorithm::Blake3));
+        assert!(tree.verify_integrity());
+    }
+
+    #[tokio::test]
+    async fn test_batch_update_matches_full_rebuild() {
+        let mut entries = state_entries(10);
+        let mut tree = MerkleTree::from_state(&entries, HashAlgorithm::Blake3);
+
+        let new_key = compute_blake3(b"new key");
+        let updates = vec![
+            (entries[0].0.clone(), b"first".to_vec()),
+            (entries[9].0.clone(), b"last".to_vec()),
+            (entries[4].0.clone(), b"middle".to_vec()),
+        ];
+        let root = tree.batch_update(&updates).unwrap();
+        entries[0].1 = b"first".to_vec();
+        entries[9].1 = b"last".to_vec();
+        entries[4].1 = b"middle".to_vec();
+        assert_eq!(root, storage_root(&entries).await);
+
+        // Une nouvelle clé force une reconstruction mais la racine reste identique
+        let root = tree.batch_update(&[(new_key.clone(), b"inserted".to_vec())]).unwrap();
+        entries.push((new_key, b"inserted".to_vec()));
+        assert_eq!(root, storage_root(&entries).await);
+        assert_eq!(tree.leaf_count(), entries.len());
+    }
+
+    #[test]
+    fn test_state_proof_verification() {
+        let entries = state_entries(5);
+        let mut tree = MerkleTree::from_state(&entries, HashAlgorithm::Blake3);
+        let root = tree.root_hash().unwrap().clone();
+
+        for (key, value) in &entries {
+            let proof = tree.generate_proof(key).unwrap();
+            assert!(proof.verify_state(&root, key, value));
+            assert!(!proof.verify_state(&root, key, b"forged value"));
+            assert!(!proof.verify_state(&compute_blake3(b"other root"), key, value));
+        }
+
+        // Une preuve générée après une mise à jour incrémentale reste valide
+        let (key, _) = &entries[2];
+        let new_root = tree.update_leaf(key.clone(), b"changed".to_vec()).unwrap();
+        let proof = tree.generate_proof(key).unwrap();
+        assert!(proof.verify_state(&new_root, key, b"changed"));
+        assert!(!proof.verify_state(&root, key, b"changed"));
+    }
+
+    #[test]
+    fn test_batch_update_on_hash_tree_fails_for_unknown_keys() {
+        let data = vec![b"data 1".to_vec(), b"data 2".to_vec()];
+        let mut tree = MerkleTree::from_data(data, HashAlgorithm::Blake3);
+
+        assert!(tree.update_leaf(compute_blake3(b"key"), b"value".to_vec()).is_err());
+    }
+}
@@ -9,10 +9,12 @@ use serde::{Deserialize, Serialize};
 
 pub mod machine;
 pub mod merkle;
+pub mod snapshot;
 pub mod storage;
 
 pub use machine::{StateMachine, StateTransition};
 pub use merkle::{MerkleTree, MerkleProof, MerkleNode};
+pub use snapshot::{SnapshotManifest, DEFAULT_SNAPSHOT_CHUNK_SIZE};
 pub use storage::{StateKey, StateValue};
 
 use crate::crypto::Hash;
@@ -136,26 +138,19 @@ impl StateStorage for MemoryStateStorage {
     }
     
     async fn calculate_state_root(&self) -> Result<StateRoot> {
-        use crate::crypto::{compute_blake3, HashAlgorithm};
+        use crate::crypto::HashAlgorithm;
         
         let storage = self.storage.read()
             .map_err(|_| CoreError::State("Failed to acquire read lock".to_string()))?;
         
-        if storage.is_empty() {
-            return Ok(Hash::zero());
-        }
-        
-        // Crée une représentation déterministe de l'état
-        let mut pairs: Vec<(&StateKey, &StateValue)> = storage.iter().collect();
-        pairs.sort_by_key(|(key, _)| *key);
-        
-        let mut state_data = Vec::new();
-        for (key, value) in pairs {
-            state_data.extend_from_slice(&key.to_bytes());
-            state_data.extend_from_slice(&value.to_bytes());
-        }
+        // Racine de Merkle déterministe sur les paires clé/valeur triées,
+        // identique à celle maintenue incrémentalement par `MerkleTree`
+        let entries: Vec<(StateKey, StateValue)> = storage
+            .iter()
+            .map(|(key, value)| (key.clone(), value.clone()))
+            .collect();
         
-        Ok(Hash::from_bytes(compute_blake3(&state_data)))
+        Ok(MerkleTree::compute_state_root(&entries, HashAlgorithm::Blake3))
     }
     
     async fn create_snapshot(&self) -> Result<StateSnapshot> {
@@ -0,0 +1,164 @@
+//! Découpage des snapshots d'état pour la synchronisation rapide
+//!
+//! Un nœud publie son snapshot découpé en chunks et engage le hash du
+//! manifeste dans un en-tête de bloc (`BlockHeader::snapshot_manifest`).
+//! Un nœud qui se synchronise vérifie chaque chunk reçu contre ce manifeste
+//! avant de restaurer l'état.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::crypto::{compute_hash, Hash, HashAlgorithm};
+use crate::error::{CoreError, Result};
+use super::{StateRoot, StateSnapshot};
+
+/// Taille par défaut d'un chunk (128 KB, sous `max_message_size` une fois encodé en JSON)
+pub const DEFAULT_SNAPSHOT_CHUNK_SIZE: usize = 128 * 1024;
+
+/// Manifeste d'un snapshot découpé en chunks
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotManifest {
+    /// Hauteur du bloc dont le snapshot capture l'état
+    pub height: u64,
+    /// Racine d'état attendue après restauration
+    pub state_root: StateRoot,
+    /// Timestamp du snapshot
+    pub timestamp: DateTime<Utc>,
+    /// Taille totale des données sérialisées
+    pub total_size: u64,
+    /// Hash de chaque chunk, dans l'ordre
+    pub chunk_hashes: Vec<Hash>,
+}
+
+impl SnapshotManifest {
+    /// Découpe un snapshot en chunks d'au plus `chunk_size` octets
+    pub fn split(snapshot: &StateSnapshot, height: u64, chunk_size: usize) -> (Self, Vec<Vec<u8>>) {
+        let chunks: Vec<Vec<u8>> = snapshot.data
+            .chunks(chunk_size.max(1))
+            .map(<[u8]>::to_vec)
+            .collect();
+
+        let manifest = Self {
+            height,
+            state_root: snapshot.state_root.clone(),
+            timestamp: snapshot.timestamp,
+            total_size: snapshot.data.len() as u64,
+            chunk_hashes: chunks.iter().map(|chunk| chunk_hash(chunk)).collect(),
+        };
+
+        (manifest, chunks)
+    }
+
+    /// Nombre de chunks du snapshot
+    pub fn chunk_count(&self) -> usize {
+        self.chunk_hashes.len()
+    }
+
+    /// Hash engagé dans l'en-tête de bloc à la hauteur du snapshot
+    pub fn manifest_hash(&self) -> Hash {
+        let mut data = Vec::with_capacity(56 + self.chunk_hashes.len() * 32);
+        data.extend_from_slice(&self.height.to_le_bytes());
+        data.extend_from_slice(self.state_root.as_bytes());
+        data.extend_from_slice(&self.timestamp.timestamp().to_le_bytes());
+        data.extend_from_slice(&self.timestamp.timestamp_subsec_nanos().to_le_bytes());
+        data.extend_from_slice(&self.total_size.to_le_bytes());
+        for hash in &self.chunk_hashes {
+            data.extend_from_slice(hash.as_bytes());
+        }
+
+        compute_hash(&data, HashAlgorithm::Blake3)
+    }
+
+    /// Vérifie un chunk reçu contre son hash dans le manifeste
+    pub fn verify_chunk(&self, index: usize, data: &[u8]) -> bool {
+        self.chunk_hashes
+            .get(index)
+            .map_or(false, |expected| *expected == chunk_hash(data))
+    }
+
+    /// Réassemble le snapshot à partir de ses chunks, dans l'ordre
+    pub fn assemble(&self, chunks: Vec<Vec<u8>>) -> Result<StateSnapshot> {
+        if chunks.len() != self.chunk_count() {
+            return Err(CoreError::Validation {
+                message: format!(
+                    "Snapshot {}: {} chunks attendus, {} reçus",
+                    self.height, self.chunk_count(), chunks.len()
+                ),
+            });
+        }
+
+        let mut data = Vec::with_capacity(self.total_size as usize);
+        for (index, chunk) in chunks.into_iter().enumerate() {
+            if !self.verify_chunk(index, &chunk) {
+                return Err(CoreError::Validation {
+                    message: format!("Chunk {} du snapshot non conforme au manifeste", index),
+                });
+            }
+            data.extend(chunk);
+        }
+
+        if data.len() as u64 != self.total_size {
+            return Err(CoreError::Validation {
+                message: format!(
+                    "Taille du snapshot incorrecte: {} octets attendus, {} reçus",
+                    self.total_size, data.len()
+                ),
+            });
+        }
+
+        Ok(StateSnapshot {
+            state_root: self.state_root.clone(),
+            timestamp: self.timestamp,
+            data,
+        })
+    }
+}
+
+/// Hash d'un chunk de snapshot
+fn chunk_hash(data: &[u8]) -> Hash {
+    compute_hash(data, HashAlgorithm::Blake3)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot(size: usize) -> StateSnapshot {
+        StateSnapshot {
+            state_root: compute_hash(b"root", HashAlgorithm::Blake3),
+            timestamp: Utc::now(),
+            data: (0..size).map(|i| (i % 251) as u8).collect(),
+        }
+    }
+
+    #[test]
+    fn test_split_and_assemble_snapshot() {
+        let original = snapshot(1_000);
+        let (manifest, chunks) = SnapshotManifest::split(&original, 42, 300);
+
+        assert_eq!(manifest.chunk_count(), 4);
+        assert_eq!(chunks.last().unwrap().len(), 100);
+        assert!(chunks.iter().enumerate().all(|(i, chunk)| manifest.verify_chunk(i, chunk)));
+
+        let restored = manifest.assemble(chunks.clone()).unwrap();
+        assert_eq!(restored.data, original.data);
+        assert_eq!(restored.state_root, original.state_root);
+
+        // Un chunk altéré ou manquant est refusé
+        let mut tampered = chunks.clone();
+        tampered[2][0] ^= 0xFF;
+        assert!(!manifest.verify_chunk(2, &tampered[2]));
+        assert!(manifest.assemble(tampered).is_err());
+        assert!(manifest.assemble(chunks[..3].to_vec()).is_err());
+    }
+
+    #[test]
+    fn test_manifest_hash_commits_to_chunks() {
+        let (manifest, _) = SnapshotManifest::split(&snapshot(1_000), 42, 300);
+        let mut other = manifest.clone();
+        other.chunk_hashes.swap(0, 1);
+
+        assert_eq!(manifest.manifest_hash(), manifest.clone().manifest_hash());
+        assert_ne!(manifest.manifest_hash(), other.manifest_hash());
+    }
+}
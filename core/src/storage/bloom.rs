@@ -0,0 +1,263 @@
+//! Filtre de Bloom des contenus stockés localement
+//!
+//! Répond à « ce nœud a-t-il probablement ce contenu ? » sans accès disque
+//! ni requête DHT :
+//! - Taux de faux positifs configurable
+//! - Agrandissement lorsque le nombre d'éléments dépasse la capacité prévue
+//! - Reconstruction complète après un volume important de suppressions, pour
+//!   que le filtre ne dérive pas vers « toujours vrai »
+
+use serde::{Deserialize, Serialize};
+use std::time::Duration;
+use crate::crypto::Hash;
+
+/// Configuration du filtre de Bloom
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BloomConfig {
+    /// Nombre de contenus prévus à la création du filtre
+    pub expected_items: usize,
+    /// Taux de faux positifs visé (0 < taux < 1)
+    pub false_positive_rate: f64,
+    /// Part de suppressions (par rapport aux éléments insérés) déclenchant une reconstruction
+    pub rebuild_deletion_ratio: f64,
+    /// Intervalle de reconstruction périodique
+    pub rebuild_interval: Duration,
+}
+
+impl Default for BloomConfig {
+    fn default() -> Self {
+        Self {
+            expected_items: 100_000,
+            false_positive_rate: 0.01,
+            rebuild_deletion_ratio: 0.25,
+            rebuild_interval: Duration::from_secs(3600), // 1 heure
+        }
+    }
+}
+
+/// Filtre de Bloom sur des hashes de contenu
+///
+/// Les hashes étant déjà uniformes (Blake3), les positions sont dérivées par
+/// double hachage de deux mots de 64 bits extraits du hash.
+#[derive(Debug, Clone)]
+pub struct BloomFilter {
+    bits: Vec<u64>,
+    num_bits: u64,
+    num_hashes: u32,
+    capacity: usize,
+    items: usize,
+}
+
+impl BloomFilter {
+    /// Dimensionne un filtre pour `capacity` éléments au taux de faux positifs donné
+    pub fn with_rate(capacity: usize, false_positive_rate: f64) -> Self {
+        let capacity = capacity.max(1);
+        let rate = false_positive_rate.clamp(1e-9, 0.5);
+        let ln2 = std::f64::consts::LN_2;
+
+        // m = -n ln(p) / ln(2)^2 ; k = m/n ln(2)
+        let num_bits = ((-(capacity as f64) * rate.ln()) / (ln2 * ln2)).ceil().max(64.0) as u64;
+        let num_hashes = ((num_bits as f64 / capacity as f64) * ln2).round().max(1.0) as u32;
+
+        Self {
+            bits: vec![0; num_bits.div_ceil(64) as usize],
+            num_bits,
+            num_hashes,
+            capacity,
+            items: 0,
+        }
+    }
+
+    /// Ajoute un hash au filtre
+    pub fn insert(&mut self, hash: &Hash) {
+        for index in self.positions(hash) {
+            self.bits[(index / 64) as usize] |= 1 << (index % 64);
+        }
+        self.items += 1;
+    }
+
+    /// `false` si le hash est absent ; `true` s'il est probablement présent
+    pub fn might_contain(&self, hash: &Hash) -> bool {
+        self.positions(hash)
+            .all(|index| self.bits[(index / 64) as usize] & (1 << (index % 64)) != 0)
+    }
+
+    /// Nombre d'insertions depuis la création
+    pub fn len(&self) -> usize {
+        self.items
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items == 0
+    }
+
+    /// Capacité prévue au dimensionnement
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// Nombre de bits du filtre
+    pub fn num_bits(&self) -> u64 {
+        self.num_bits
+    }
+
+    /// Proportion de bits à 1
+    pub fn fill_ratio(&self) -> f64 {
+        let set: u64 = self.bits.iter().map(|word| word.count_ones() as u64).sum();
+        set as f64 / self.num_bits as f64
+    }
+
+    /// Taux de faux positifs estimé d'après le remplissage réel
+    pub fn estimated_false_positive_rate(&self) -> f64 {
+        self.fill_ratio().powi(self.num_hashes as i32)
+    }
+
+    fn positions<'a>(&'a self, hash: &Hash) -> impl Iterator<Item = u64> + 'a {
+        let bytes = hash.as_bytes();
+        let h1 = u64::from_le_bytes(bytes[0..8].try_into().unwrap());
+        let h2 = u64::from_le_bytes(bytes[8..16].try_into().unwrap()) | 1;
+        (0..self.num_hashes as u64).map(move |i| h1.wrapping_add(i.wrapping_mul(h2)) % self.num_bits)
+    }
+}
+
+/// Filtre des contenus d'un nœud, avec suivi des suppressions
+#[derive(Debug, Clone)]
+pub struct ContentFilter {
+    config: BloomConfig,
+    filter: BloomFilter,
+    deletions_since_rebuild: usize,
+    rebuilds: u64,
+}
+
+impl ContentFilter {
+    /// Crée un filtre vide
+    pub fn new(config: BloomConfig) -> Self {
+        let filter = BloomFilter::with_rate(config.expected_items, config.false_positive_rate);
+        Self { config, filter, deletions_since_rebuild: 0, rebuilds: 0 }
+    }
+
+    /// Configuration du filtre
+    pub fn config(&self) -> &BloomConfig {
+        &self.config
+    }
+
+    pub fn might_contain(&self, hash: &Hash) -> bool {
+        self.filter.might_contain(hash)
+    }
+
+    /// Enregistre un contenu ajouté
+    ///
+    /// Retourne `true` si le filtre a dépassé sa capacité et doit être
+    /// reconstruit plus grand pour tenir le taux de faux positifs.
+    pub fn record_insert(&mut self, hash: &Hash) -> bool {
+        self.filter.insert(hash);
+        self.filter.len() > self.filter.capacity()
+    }
+
+    /// Enregistre un contenu supprimé
+    ///
+    /// Un filtre de Bloom ne sait pas retirer d'élément : retourne `true`
+    /// lorsque les suppressions accumulées justifient une reconstruction.
+    pub fn record_removal(&mut self) -> bool {
+        self.deletions_since_rebuild += 1;
+        let threshold = (self.filter.len() as f64 * self.config.rebuild_deletion_ratio).ceil() as usize;
+        self.deletions_since_rebuild >= threshold.max(1)
+    }
+
+    /// Reconstruit le filtre à partir des contenus effectivement stockés
+    ///
+    /// La capacité suit le nombre de contenus (au moins `expected_items`).
+    pub fn rebuild<'a>(&mut self, hashes: impl ExactSizeIterator<Item = &'a Hash>) {
+        let capacity = self.config.expected_items.max(hashes.len() * 2);
+        let mut filter = BloomFilter::with_rate(capacity, self.config.false_positive_rate);
+        for hash in hashes {
+            filter.insert(hash);
+        }
+        self.filter = filter;
+        self.deletions_since_rebuild = 0;
+        self.rebuilds += 1;
+    }
+
+    /// Statistiques du filtre
+    pub fn stats(&self) -> BloomStats {
+        BloomStats {
+            items: self.filter.len(),
+            capacity: self.filter.capacity(),
+            num_bits: self.filter.num_bits(),
+            fill_ratio: self.filter.fill_ratio(),
+            estimated_false_positive_rate: self.filter.estimated_false_positive_rate(),
+            deletions_since_rebuild: self.deletions_since_rebuild,
+            rebuilds: self.rebuilds,
+        }
+    }
+}
+
+/// Statistiques du filtre de contenus
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BloomStats {
+    pub items: usize,
+    pub capacity: usize,
+    pub num_bits: u64,
+    pub fill_ratio: f64,
+    pub estimated_false_positive_rate: f64,
+    pub deletions_since_rebuild: usize,
+    pub rebuilds: u64,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::compute_blake3;
+
+    fn hashes(range: std::ops::Range<u32>) -> Vec<Hash> {
+        range.map(|i| compute_blake3(&i.to_le_bytes())).collect()
+    }
+
+    #[test]
+    fn test_bloom_filter_no_false_negatives() {
+        let mut filter = BloomFilter::with_rate(1_000, 0.01);
+        let stored = hashes(0..1_000);
+        for hash in &stored {
+            filter.insert(hash);
+        }
+        assert!(stored.iter().all(|hash| filter.might_contain(hash)));
+
+        let false_positives = hashes(1_000..11_000).iter().filter(|h| filter.might_contain(h)).count();
+        // Taux visé 1 % : marge large pour rester déterministe
+        assert!(false_positives < 300, "{} faux positifs", false_positives);
+        assert!(filter.estimated_false_positive_rate() < 0.03);
+    }
+
+    #[test]
+    fn test_false_positive_rate_sizes_filter() {
+        let loose = BloomFilter::with_rate(1_000, 0.1);
+        let strict = BloomFilter::with_rate(1_000, 0.001);
+        assert!(strict.num_bits() > loose.num_bits() * 2);
+        assert!(strict.num_hashes > loose.num_hashes);
+    }
+
+    #[test]
+    fn test_rebuild_after_deletions() {
+        let config = BloomConfig { expected_items: 100, rebuild_deletion_ratio: 0.4, ..BloomConfig::default() };
+        let mut content = ContentFilter::new(config);
+        let stored = hashes(0..100);
+        for hash in &stored {
+            assert!(!content.record_insert(hash));
+        }
+        assert!(content.record_insert(&compute_blake3(b"overflow")));
+
+        // Supprime la moitié des contenus : la reconstruction est demandée
+        let removed = &stored[..50];
+        let needs_rebuild = removed.iter().map(|_| content.record_removal()).last().unwrap();
+        assert!(needs_rebuild);
+
+        let kept = &stored[50..];
+        content.rebuild(kept.iter());
+        assert!(kept.iter().all(|hash| content.might_contain(hash)));
+        assert!(removed.iter().filter(|hash| content.might_contain(hash)).count() < 5);
+
+        let stats = content.stats();
+        assert_eq!((stats.items, stats.deletions_since_rebuild, stats.rebuilds), (50, 0, 1));
+        assert_eq!(stats.capacity, 100);
+    }
+}
@@ -0,0 +1,952 @@
+//! Pipeline de crawl des archives ArchiveChain
+//!
+//! Récupère une URL racine et les ressources qu'elle référence selon les
+//! `ArchiveOptions` de la requête :
+//! - Profondeur maximale de suivi des liens
+//! - Restriction au domaine racine et aux domaines autorisés
+//! - Filtrage des assets, du JavaScript et des types de contenu supportés
+//! - Budget de taille cumulée et respect de robots.txt
+//! - Refus des adresses non publiques, y compris après redirection
+//!
+//! Le résultat est une archive logique unique : un manifeste listant les
+//! ressources capturées, ignorées et en échec, accompagné des payloads prêts
+//! à être remis à la couche de stockage.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::net::{IpAddr, SocketAddr};
+use std::sync::Arc;
+use std::time::Duration;
+use regex::Regex;
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use url::{Host, Url};
+use crate::api::types::ArchiveOptions;
+use crate::constants::{DEFAULT_NETWORK_TIMEOUT, SUPPORTED_CONTENT_TYPES};
+use crate::crypto::{Hash, compute_blake3};
+use crate::error::{CoreError, Result, SerializationError};
+use super::{ContentImportance, ContentMetadata, DistributedStorage, DEFAULT_MAX_CONTENT_SIZE};
+
+/// Identifiant du crawler dans les en-têtes HTTP et robots.txt
+pub const CRAWLER_USER_AGENT: &str = "ArchiveChainBot";
+
+/// Niveau de redondance par défaut des ressources crawlées
+const DEFAULT_REDUNDANCY_LEVEL: u8 = 3;
+
+/// Importance attribuée aux ressources crawlées, qui fixe leur réplication
+pub const CRAWLED_CONTENT_IMPORTANCE: ContentImportance = ContentImportance::Medium;
+
+/// Nombre maximal de redirections suivies pour une ressource
+const MAX_REDIRECTS: usize = 5;
+
+/// Taille maximale d'un robots.txt
+const MAX_ROBOTS_SIZE: u64 = 512 * 1024;
+
+/// Raison pour laquelle une ressource référencée n'a pas été capturée
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SkipReason {
+    /// Profondeur maximale atteinte
+    MaxDepth,
+    /// Domaine hors du périmètre de l'archive
+    OutOfScope,
+    /// Interdit par robots.txt
+    RobotsTxt,
+    /// Assets exclus par les options
+    AssetsExcluded,
+    /// JavaScript exclu par les options
+    JavaScriptExcluded,
+    /// Type de contenu non supporté
+    UnsupportedContentType(String),
+    /// Budget de taille de l'archive dépassé
+    SizeLimit,
+}
+
+/// Ressource référencée mais volontairement ignorée
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SkippedResource {
+    pub url: String,
+    pub depth: u32,
+    pub reason: SkipReason,
+}
+
+/// Échec de récupération d'une sous-ressource
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CrawlFailure {
+    pub url: String,
+    pub depth: u32,
+    /// Code HTTP si le serveur a répondu
+    pub status: Option<u16>,
+    pub reason: String,
+}
+
+/// Entrée du manifeste pour une ressource capturée
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    pub url: String,
+    pub depth: u32,
+    pub content_hash: Hash,
+    pub size: u64,
+    pub content_type: String,
+}
+
+/// Manifeste d'une archive crawlée
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ArchiveManifest {
+    pub root_url: String,
+    /// Ressources capturées, la racine en premier
+    pub entries: Vec<ManifestEntry>,
+    /// Sous-ressources en échec (n'invalident pas l'archive)
+    pub failures: Vec<CrawlFailure>,
+    /// Ressources ignorées par les options de crawl
+    pub skipped: Vec<SkippedResource>,
+    /// Taille cumulée des ressources capturées
+    pub total_size: u64,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+}
+
+impl ArchiveManifest {
+    /// Hash de la ressource racine
+    pub fn root_hash(&self) -> &Hash {
+        &self.entries[0].content_hash
+    }
+
+    /// Vrai si toutes les sous-ressources ont été récupérées
+    pub fn is_complete(&self) -> bool {
+        self.failures.is_empty()
+    }
+
+    /// Sérialise le manifeste en JSON
+    pub fn to_json(&self) -> Result<Vec<u8>> {
+        serde_json::to_vec(self).map_err(|e| SerializationError::Json(e).into())
+    }
+}
+
+/// Ressource capturée avec son payload
+#[derive(Debug, Clone)]
+pub struct CrawledResource {
+    pub url: String,
+    pub depth: u32,
+    pub metadata: ContentMetadata,
+    pub data: Vec<u8>,
+}
+
+/// Résultat d'un crawl : manifeste et payloads
+#[derive(Debug, Clone)]
+pub struct CrawlResult {
+    pub manifest: ArchiveManifest,
+    pub resources: Vec<CrawledResource>,
+}
+
+impl CrawlResult {
+    /// Remet les ressources puis le manifeste à la couche de stockage
+    ///
+    /// Retourne le hash du manifeste, identifiant de l'archive logique.
+    pub async fn store_in<S>(&self, storage: &mut S) -> Result<Hash>
+    where
+        S: DistributedStorage + ?Sized,
+    {
+        for resource in &self.resources {
+            storage
+                .store_content(&resource.metadata.content_hash, &resource.data, resource.metadata.clone())
+                .await?;
+        }
+
+        let manifest = self.manifest.to_json()?;
+        let manifest_hash = compute_blake3(&manifest);
+        let root = &self.resources[0].metadata;
+        let metadata = ContentMetadata {
+            content_hash: manifest_hash,
+            size: manifest.len() as u64,
+            content_type: "application/json".to_string(),
+            title: root.title.clone(),
+            description: root.description.clone(),
+            importance: root.importance.clone(),
+            popularity: 0,
+            created_at: self.manifest.created_at,
+            preferred_regions: root.preferred_regions.clone(),
+            redundancy_level: root.redundancy_level,
+            tags: vec!["archive-manifest".to_string()],
+        };
+        storage.store_content(&manifest_hash, &manifest, metadata).await?;
+
+        Ok(manifest_hash)
+    }
+}
+
+/// Nature d'un lien extrait d'une page
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LinkKind {
+    /// Page à suivre (compte dans la profondeur)
+    Page,
+    /// Asset nécessaire au rendu de la page
+    Asset,
+    /// Script JavaScript
+    Script,
+}
+
+/// Règles robots.txt applicables au crawler
+#[derive(Debug, Clone, Default)]
+struct RobotsRules {
+    /// (autorisé, préfixe de chemin)
+    rules: Vec<(bool, String)>,
+}
+
+impl RobotsRules {
+    /// Parse un robots.txt ; le groupe spécifique au crawler prime sur `*`
+    fn parse(body: &str) -> Self {
+        let agent = CRAWLER_USER_AGENT.to_lowercase();
+        let mut specific = Vec::new();
+        let mut wildcard = Vec::new();
+        let mut group_agents: Vec<String> = Vec::new();
+        let mut in_rules = false;
+
+        for line in body.lines() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            let Some((key, value)) = line.split_once(':') else { continue };
+            let key = key.trim().to_lowercase();
+            let value = value.trim();
+
+            match key.as_str() {
+                "user-agent" => {
+                    if in_rules {
+                        group_agents.clear();
+                        in_rules = false;
+                    }
+                    group_agents.push(value.to_lowercase());
+                }
+                "allow" | "disallow" => {
+                    in_rules = true;
+                    // Un Disallow vide n'interdit rien
+                    if value.is_empty() {
+                        continue;
+                    }
+                    let rule = (key == "allow", value.to_string());
+                    if group_agents.iter().any(|a| a != "*" && agent.contains(a.as_str())) {
+                        specific.push(rule);
+                    } else if group_agents.iter().any(|a| a == "*") {
+                        wildcard.push(rule);
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Self { rules: if specific.is_empty() { wildcard } else { specific } }
+    }
+
+    /// La règle au préfixe le plus long l'emporte ; autorisé par défaut
+    fn is_allowed(&self, path: &str) -> bool {
+        self.rules
+            .iter()
+            .filter(|(_, prefix)| path.starts_with(prefix.as_str()))
+            .max_by_key(|(allow, prefix)| (prefix.len(), *allow))
+            .map(|(allow, _)| *allow)
+            .unwrap_or(true)
+    }
+}
+
+/// Réponse HTTP réussie
+struct Fetched {
+    content_type: String,
+    data: Vec<u8>,
+}
+
+/// Issue d'une requête HTTP unique
+enum Hop {
+    Fetched(Fetched),
+    /// Redirection vers l'URL résolue de l'en-tête `Location`
+    Redirect(Url),
+}
+
+/// Échec de récupération
+struct FetchError {
+    status: Option<u16>,
+    reason: String,
+    /// Ressource écartée par une règle du crawl plutôt qu'en échec
+    skip: Option<SkipReason>,
+}
+
+impl FetchError {
+    fn failed(status: Option<u16>, reason: impl Into<String>) -> Self {
+        Self { status, reason: reason.into(), skip: None }
+    }
+
+    fn skipped(skip: SkipReason, reason: impl Into<String>) -> Self {
+        Self { status: None, reason: reason.into(), skip: Some(skip) }
+    }
+}
+
+/// Adresse joignable publiquement : ni locale, ni privée, ni réservée
+fn is_public_address(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [first, second, ..] = ip.octets();
+            !(ip.is_private()
+                || ip.is_loopback()
+                || ip.is_link_local()
+                || ip.is_broadcast()
+                || ip.is_documentation()
+                || ip.is_unspecified()
+                || ip.is_multicast()
+                || first == 0
+                // 100.64.0.0/10 : NAT des opérateurs
+                || (first == 100 && second & 0xc0 == 64))
+        }
+        IpAddr::V6(ip) => {
+            if let Some(mapped) = ip.to_ipv4_mapped() {
+                return is_public_address(IpAddr::V4(mapped));
+            }
+            let first = ip.segments()[0];
+            !(ip.is_loopback()
+                || ip.is_unspecified()
+                || ip.is_multicast()
+                // fc00::/7 : adresses locales uniques
+                || first & 0xfe00 == 0xfc00
+                // fe80::/10 : lien local
+                || first & 0xffc0 == 0xfe80)
+        }
+    }
+}
+
+/// Résolveur DNS du crawler, qui écarte les adresses non publiques
+///
+/// Le filtrage a lieu à la connexion : un nom qui se met à résoudre vers une
+/// adresse interne entre deux requêtes reste injoignable.
+struct PublicAddressResolver {
+    allow_private_networks: bool,
+}
+
+impl Resolve for PublicAddressResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let allow_private_networks = self.allow_private_networks;
+        Box::pin(async move {
+            let host = name.as_str().to_string();
+            let addrs: Vec<SocketAddr> = tokio::net::lookup_host((host.as_str(), 0))
+                .await?
+                .filter(|addr| allow_private_networks || is_public_address(addr.ip()))
+                .collect();
+            if addrs.is_empty() {
+                return Err(format!("{} ne résout vers aucune adresse publique", host).into());
+            }
+            Ok(Box::new(addrs.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// Moteur de crawl d'une archive
+pub struct CrawlEngine {
+    client: reqwest::Client,
+    options: ArchiveOptions,
+    /// Autorise les adresses locales et privées (crawl d'un intranet, tests)
+    allow_private_networks: bool,
+    link_regex: Regex,
+    title_regex: Regex,
+    description_regex: Regex,
+}
+
+impl CrawlEngine {
+    /// Crée un moteur de crawl pour les options données
+    ///
+    /// Seules les adresses publiques sont jointes : les URL soumises ne
+    /// peuvent pas atteindre le réseau interne du nœud.
+    pub fn new(options: ArchiveOptions) -> Result<Self> {
+        Self::build(options, false)
+    }
+
+    /// Crée un moteur de crawl autorisé à joindre les adresses locales et privées
+    pub fn allowing_private_networks(options: ArchiveOptions) -> Result<Self> {
+        Self::build(options, true)
+    }
+
+    fn build(options: ArchiveOptions, allow_private_networks: bool) -> Result<Self> {
+        let timeout = options.timeout_seconds.unwrap_or(DEFAULT_NETWORK_TIMEOUT);
+        // Les redirections sont suivies à la main pour leur appliquer les filtres du crawl
+        let client = reqwest::Client::builder()
+            .user_agent(format!("{}/{}", CRAWLER_USER_AGENT, crate::VERSION))
+            .timeout(Duration::from_secs(timeout))
+            .redirect(reqwest::redirect::Policy::none())
+            .dns_resolver(Arc::new(PublicAddressResolver { allow_private_networks }))
+            .build()
+            .map_err(|e| CoreError::Internal {
+                message: format!("Client HTTP invalide: {}", e),
+            })?;
+
+        Ok(Self {
+            client,
+            options,
+            allow_private_networks,
+            link_regex: Regex::new(
+                r#"(?is)<(a|link|img|script|source|iframe|video|audio)\b[^>]*?\s(href|src)\s*=\s*["']([^"']+)["']"#,
+            )
+            .expect("regex de liens valide"),
+            title_regex: Regex::new(r"(?is)<title[^>]*>(.*?)</title>").expect("regex de titre valide"),
+            description_regex: Regex::new(
+                r#"(?is)<meta\s+[^>]*name\s*=\s*["']description["'][^>]*content\s*=\s*["']([^"']*)["']"#,
+            )
+            .expect("regex de description valide"),
+        })
+    }
+
+    /// Crawle l'URL racine et ses ressources
+    ///
+    /// Seul l'échec de la racine fait échouer l'archive ; les échecs des
+    /// sous-ressources sont consignés dans le manifeste.
+    pub async fn crawl(&self, root_url: &str) -> Result<CrawlResult> {
+        let root = Url::parse(root_url).map_err(|e| CoreError::Validation {
+            message: format!("URL racine invalide {}: {}", root_url, e),
+        })?;
+        if !matches!(root.scheme(), "http" | "https") {
+            return Err(CoreError::Validation {
+                message: format!("Schéma non supporté: {}", root.scheme()),
+            });
+        }
+
+        let mut robots: HashMap<String, RobotsRules> = HashMap::new();
+        if !self.robots_allowed(&root, &mut robots).await {
+            return Err(CoreError::Validation {
+                message: format!("{} est interdite par robots.txt", root),
+            });
+        }
+
+        let fetched = self.fetch(&root, &root, self.fetch_limit(0), &mut robots).await.map_err(|e| match e.skip {
+            Some(SkipReason::SizeLimit) => CoreError::Validation {
+                message: format!("{} dépasse la taille maximale de l'archive", root),
+            },
+            Some(_) => CoreError::Validation {
+                message: format!("{} n'est pas archivable: {}", root, e.reason),
+            },
+            None => CoreError::Internal {
+                message: format!("Échec de récupération de {}: {}", root, e.reason),
+            },
+        })?;
+        if !is_supported(&fetched.content_type) {
+            return Err(CoreError::Validation {
+                message: format!("Type de contenu non supporté: {}", fetched.content_type),
+            });
+        }
+
+        let created_at = chrono::Utc::now();
+        let mut manifest = ArchiveManifest {
+            root_url: root.to_string(),
+            entries: Vec::new(),
+            failures: Vec::new(),
+            skipped: Vec::new(),
+            total_size: 0,
+            created_at,
+        };
+        let mut resources = Vec::new();
+        let mut visited: HashSet<String> = HashSet::new();
+        visited.insert(root.to_string());
+
+        let mut queue: VecDeque<(Url, u32, LinkKind, Option<Fetched>)> = VecDeque::new();
+        queue.push_back((root.clone(), 0, LinkKind::Page, Some(fetched)));
+
+        while let Some((url, depth, kind, prefetched)) = queue.pop_front() {
+            let fetched = match prefetched {
+                Some(fetched) => fetched,
+                None => match self.fetch(&root, &url, self.fetch_limit(manifest.total_size), &mut robots).await {
+                    Ok(fetched) => fetched,
+                    Err(FetchError { skip: Some(reason), .. }) => {
+                        manifest.skipped.push(SkippedResource { url: url.to_string(), depth, reason });
+                        continue;
+                    }
+                    Err(e) => {
+                        tracing::debug!("Échec de crawl de {}: {}", url, e.reason);
+                        manifest.failures.push(CrawlFailure {
+                            url: url.to_string(),
+                            depth,
+                            status: e.status,
+                            reason: e.reason,
+                        });
+                        continue;
+                    }
+                },
+            };
+
+            if !is_supported(&fetched.content_type) {
+                manifest.skipped.push(SkippedResource {
+                    url: url.to_string(),
+                    depth,
+                    reason: SkipReason::UnsupportedContentType(fetched.content_type),
+                });
+                continue;
+            }
+            let size = fetched.data.len() as u64;
+            if self.exceeds_budget(manifest.total_size, size) {
+                manifest.skipped.push(SkippedResource { url: url.to_string(), depth, reason: SkipReason::SizeLimit });
+                continue;
+            }
+
+            let is_html = fetched.content_type == "text/html";
+            if is_html && kind == LinkKind::Page {
+                let html = String::from_utf8_lossy(&fetched.data);
+                for (link, link_kind) in self.extract_links(&url, &html) {
+                    if !visited.insert(link.to_string()) {
+                        continue;
+                    }
+                    let link_depth = depth + 1;
+                    match self.filter(&root, &link, link_kind, link_depth, &mut robots).await {
+                        Some(reason) => manifest.skipped.push(SkippedResource {
+                            url: link.to_string(),
+                            depth: link_depth,
+                            reason,
+                        }),
+                        None => queue.push_back((link, link_depth, link_kind, None)),
+                    }
+                }
+            }
+
+            let metadata = self.build_metadata(&url, &fetched, is_html, created_at);
+            manifest.total_size += size;
+            manifest.entries.push(ManifestEntry {
+                url: url.to_string(),
+                depth,
+                content_hash: metadata.content_hash,
+                size,
+                content_type: metadata.content_type.clone(),
+            });
+            resources.push(CrawledResource { url: url.to_string(), depth, metadata, data: fetched.data });
+        }
+
+        Ok(CrawlResult { manifest, resources })
+    }
+
+    /// Applique les filtres de périmètre, profondeur et robots.txt
+    ///
+    /// Les assets ne comptent pas dans la profondeur : une page capturée
+    /// garde ses feuilles de style et images même au niveau maximal.
+    async fn filter(
+        &self,
+        root: &Url,
+        link: &Url,
+        kind: LinkKind,
+        depth: u32,
+        robots: &mut HashMap<String, RobotsRules>,
+    ) -> Option<SkipReason> {
+        if !self.in_scope(root, link) {
+            return Some(SkipReason::OutOfScope);
+        }
+        match kind {
+            LinkKind::Page if depth > self.options.max_depth => return Some(SkipReason::MaxDepth),
+            LinkKind::Asset if !self.options.include_assets => return Some(SkipReason::AssetsExcluded),
+            LinkKind::Script if !self.options.include_assets || !self.options.preserve_javascript => {
+                return Some(SkipReason::JavaScriptExcluded)
+            }
+            _ => {}
+        }
+        if !self.robots_allowed(link, robots).await {
+            return Some(SkipReason::RobotsTxt);
+        }
+        None
+    }
+
+    /// Vérifie qu'un lien appartient au périmètre de l'archive
+    fn in_scope(&self, root: &Url, link: &Url) -> bool {
+        let Some(host) = link.host_str() else { return false };
+        if Some(host) == root.host_str() {
+            return true;
+        }
+        let allowed = self
+            .options
+            .allowed_domains
+            .iter()
+            .any(|domain| host == domain || host.ends_with(&format!(".{}", domain)));
+        allowed || (!self.options.same_domain_only && self.options.allowed_domains.is_empty())
+    }
+
+    fn exceeds_budget(&self, current: u64, additional: u64) -> bool {
+        self.options.max_total_size.map_or(false, |max| current + additional > max)
+    }
+
+    /// Taille maximale de la prochaine ressource, selon le budget restant
+    fn fetch_limit(&self, current: u64) -> u64 {
+        let remaining = self.options.max_total_size.map_or(u64::MAX, |max| max.saturating_sub(current));
+        remaining.min(DEFAULT_MAX_CONTENT_SIZE)
+    }
+
+    /// Consulte (et met en cache par origine) le robots.txt du site
+    async fn robots_allowed(&self, url: &Url, cache: &mut HashMap<String, RobotsRules>) -> bool {
+        if !self.options.respect_robots_txt {
+            return true;
+        }
+        let origin = url.origin().ascii_serialization();
+        if !cache.contains_key(&origin) {
+            let rules = match Url::parse(&format!("{}/robots.txt", origin)) {
+                Ok(robots_url) => match self.fetch_robots(&robots_url).await {
+                    Ok(fetched) => RobotsRules::parse(&String::from_utf8_lossy(&fetched.data)),
+                    // Pas de robots.txt exploitable : tout est autorisé
+                    Err(_) => RobotsRules::default(),
+                },
+                Err(_) => RobotsRules::default(),
+            };
+            cache.insert(origin.clone(), rules);
+        }
+        cache[&origin].is_allowed(url.path())
+    }
+
+    /// Récupère une ressource en suivant ses redirections
+    ///
+    /// Chaque cible de redirection est un nouveau lien : elle doit rester dans
+    /// le périmètre de l'archive et être autorisée par robots.txt.
+    async fn fetch(
+        &self,
+        root: &Url,
+        url: &Url,
+        limit: u64,
+        robots: &mut HashMap<String, RobotsRules>,
+    ) -> std::result::Result<Fetched, FetchError> {
+        let mut current = url.clone();
+        for _ in 0..=MAX_REDIRECTS {
+            match self.fetch_once(&current, limit).await? {
+                Hop::Fetched(fetched) => return Ok(fetched),
+                Hop::Redirect(location) => {
+                    if !self.in_scope(root, &location) {
+                        return Err(FetchError::skipped(
+                            SkipReason::OutOfScope,
+                            format!("redirection hors périmètre vers {}", location),
+                        ));
+                    }
+                    if !self.robots_allowed(&location, robots).await {
+                        return Err(FetchError::skipped(
+                            SkipReason::RobotsTxt,
+                            format!("redirection vers {} interdite par robots.txt", location),
+                        ));
+                    }
+                    current = location;
+                }
+            }
+        }
+        Err(FetchError::failed(None, format!("plus de {} redirections", MAX_REDIRECTS)))
+    }
+
+    /// Récupère un robots.txt, en ne suivant que les redirections vers le même hôte
+    async fn fetch_robots(&self, robots_url: &Url) -> std::result::Result<Fetched, FetchError> {
+        let mut current = robots_url.clone();
+        for _ in 0..=MAX_REDIRECTS {
+            match self.fetch_once(&current, MAX_ROBOTS_SIZE).await? {
+                Hop::Fetched(fetched) => return Ok(fetched),
+                Hop::Redirect(location) if location.host_str() == robots_url.host_str() => current = location,
+                Hop::Redirect(location) => {
+                    return Err(FetchError::failed(None, format!("robots.txt redirigé vers {}", location)))
+                }
+            }
+        }
+        Err(FetchError::failed(None, format!("plus de {} redirections", MAX_REDIRECTS)))
+    }
+
+    /// Exécute une requête unique, sans suivre de redirection
+    ///
+    /// Le corps est lu par morceaux et abandonné dès qu'il dépasse `limit`.
+    async fn fetch_once(&self, url: &Url, limit: u64) -> std::result::Result<Hop, FetchError> {
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(FetchError::failed(None, format!("schéma non supporté: {}", url.scheme())));
+        }
+        // Une adresse littérale ne passe pas par le résolveur
+        let literal = match url.host() {
+            Some(Host::Ipv4(ip)) => Some(IpAddr::V4(ip)),
+            Some(Host::Ipv6(ip)) => Some(IpAddr::V6(ip)),
+            _ => None,
+        };
+        if !self.allow_private_networks && literal.is_some_and(|ip| !is_public_address(ip)) {
+            return Err(FetchError::failed(None, format!("adresse non publique: {}", url)));
+        }
+
+        let mut response = self.client.get(url.clone()).send().await.map_err(|e| FetchError::failed(None, e.to_string()))?;
+
+        let status = response.status();
+        if status.is_redirection() {
+            let mut location = response
+                .headers()
+                .get(reqwest::header::LOCATION)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| url.join(value).ok())
+                .ok_or_else(|| FetchError::failed(Some(status.as_u16()), "redirection sans en-tête Location valide"))?;
+            location.set_fragment(None);
+            return Ok(Hop::Redirect(location));
+        }
+        if !status.is_success() {
+            return Err(FetchError::failed(Some(status.as_u16()), format!("HTTP {}", status)));
+        }
+
+        let content_type = response
+            .headers()
+            .get(reqwest::header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.split(';').next())
+            .map(|value| value.trim().to_lowercase())
+            .unwrap_or_else(|| "application/octet-stream".to_string());
+
+        let too_large = || FetchError::skipped(SkipReason::SizeLimit, format!("{} dépasse {} octets", url, limit));
+        if response.content_length().is_some_and(|length| length > limit) {
+            return Err(too_large());
+        }
+        let mut data = Vec::new();
+        while let Some(chunk) = response
+            .chunk()
+            .await
+            .map_err(|e| FetchError::failed(Some(status.as_u16()), e.to_string()))?
+        {
+            if data.len() as u64 + chunk.len() as u64 > limit {
+                return Err(too_large());
+            }
+            data.extend_from_slice(&chunk);
+        }
+
+        Ok(Hop::Fetched(Fetched { content_type, data }))
+    }
+
+    /// Extrait les liens href/src d'une page HTML, résolus et sans fragment
+    fn extract_links(&self, base: &Url, html: &str) -> Vec<(Url, LinkKind)> {
+        let mut links = Vec::new();
+        for captures in self.link_regex.captures_iter(html) {
+            let tag = captures[1].to_lowercase();
+            let raw = captures[3].trim();
+            if raw.starts_with('#') || raw.contains("javascript:") || raw.starts_with("mailto:") || raw.starts_with("data:") {
+                continue;
+            }
+            let Ok(mut link) = base.join(raw) else { continue };
+            if !matches!(link.scheme(), "http" | "https") {
+                continue;
+            }
+            link.set_fragment(None);
+
+            let kind = match tag.as_str() {
+                "a" | "iframe" => LinkKind::Page,
+                "script" => LinkKind::Script,
+                _ => LinkKind::Asset,
+            };
+            links.push((link, kind));
+        }
+        links
+    }
+
+    fn build_metadata(
+        &self,
+        url: &Url,
+        fetched: &Fetched,
+        is_html: bool,
+        created_at: chrono::DateTime<chrono::Utc>,
+    ) -> ContentMetadata {
+        let (title, description) = if is_html {
+            let html = String::from_utf8_lossy(&fetched.data);
+            (
+                self.title_regex.captures(&html).map(|c| c[1].trim().to_string()),
+                self.description_regex.captures(&html).map(|c| c[1].trim().to_string()),
+            )
+        } else {
+            (None, None)
+        };
+
+        let mut tags = vec!["crawl".to_string()];
+        if let Some(host) = url.host_str() {
+            tags.push(host.to_string());
+        }
+
+        ContentMetadata {
+            content_hash: compute_blake3(&fetched.data),
+            size: fetched.data.len() as u64,
+            content_type: fetched.content_type.clone(),
+            title,
+            description,
+            importance: CRAWLED_CONTENT_IMPORTANCE,
+            popularity: 0,
+            created_at,
+            preferred_regions: Vec::new(),
+            redundancy_level: DEFAULT_REDUNDANCY_LEVEL,
+            tags,
+        }
+    }
+}
+
+fn is_supported(content_type: &str) -> bool {
+    SUPPORTED_CONTENT_TYPES.contains(&content_type)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{http::header, response::{IntoResponse, Redirect}, routing::get, Router};
+
+    fn html(body: &'static str) -> impl IntoResponse {
+        ([(header::CONTENT_TYPE, "text/html; charset=utf-8")], body)
+    }
+
+    /// Lance un serveur HTTP local et retourne son URL de base
+    async fn spawn_site() -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+
+        let external = format!("http://localhost:{}/external", port);
+        let index: &'static str = Box::leak(
+            format!(
+                r#"<html><head><title>Accueil</title>
+                <meta name="description" content="Site de test">
+                <link rel="stylesheet" href="/style.css"></head>
+                <body><a href="/a">A</a> <a href="{}">Externe</a>
+                <a href="/private/secret">Privé</a> <a href="/a#section">A bis</a>
+                <img src="/missing.png"><script src="/app.js"></script></body></html>"#,
+                external
+            )
+            .into_boxed_str(),
+        );
+
+        let escape: &'static str = Box::leak(external.clone().into_boxed_str());
+        let app = Router::new()
+            .route("/", get(move || async move { html(index) }))
+            .route("/moved", get(|| async { Redirect::permanent("/a") }))
+            .route("/escape", get(move || async move { Redirect::temporary(escape) }))
+            .route("/hidden", get(|| async { Redirect::temporary("/private/secret") }))
+            .route("/a", get(|| async { html(r#"<a href="/b">B</a>"#) }))
+            .route("/b", get(|| async { html(r#"<a href="/c">C</a>"#) }))
+            .route("/c", get(|| async { html("<p>fin</p>") }))
+            .route("/external", get(|| async { html("<p>externe</p>") }))
+            .route("/private/secret", get(|| async { html("<p>secret</p>") }))
+            .route("/style.css", get(|| async { ([(header::CONTENT_TYPE, "text/css")], "body {}") }))
+            .route("/app.js", get(|| async { ([(header::CONTENT_TYPE, "application/javascript")], "1;") }))
+            .route("/robots.txt", get(|| async { "User-agent: *\nDisallow: /private\n" }));
+
+        tokio::spawn(async move {
+            axum::serve(listener, app).await.unwrap();
+        });
+
+        format!("http://127.0.0.1:{}/", port)
+    }
+
+    fn urls(result: &CrawlResult) -> Vec<String> {
+        result.manifest.entries.iter().map(|e| e.url.clone()).collect()
+    }
+
+    fn skip_reason(result: &CrawlResult, url: &str) -> Option<SkipReason> {
+        result.manifest.skipped.iter().find(|s| s.url == url).map(|s| s.reason.clone())
+    }
+
+    #[test]
+    fn test_robots_rules() {
+        let rules = RobotsRules::parse(
+            "User-agent: *\nDisallow: /\n\nUser-agent: ArchiveChainBot\nDisallow: /private\nAllow: /private/public\n",
+        );
+        assert!(rules.is_allowed("/index.html"));
+        assert!(!rules.is_allowed("/private/data"));
+        assert!(rules.is_allowed("/private/public/page"));
+        assert!(RobotsRules::default().is_allowed("/anything"));
+    }
+
+    #[tokio::test]
+    async fn test_crawl_limits_depth() {
+        let base = spawn_site().await;
+        let options = ArchiveOptions { max_depth: 1, ..ArchiveOptions::default() };
+        let result = CrawlEngine::allowing_private_networks(options).unwrap().crawl(&base).await.unwrap();
+
+        let crawled = urls(&result);
+        assert_eq!(crawled[0], base);
+        assert!(crawled.contains(&format!("{}a", base)));
+        assert!(crawled.contains(&format!("{}style.css", base)));
+        assert!(!crawled.contains(&format!("{}b", base)));
+        assert_eq!(skip_reason(&result, &format!("{}b", base)), Some(SkipReason::MaxDepth));
+
+        // Le fragment est retiré : /a#section n'est crawlé qu'une fois
+        assert_eq!(crawled.iter().filter(|u| u.ends_with("/a")).count(), 1);
+
+        let root = &result.resources[0].metadata;
+        assert_eq!(root.title.as_deref(), Some("Accueil"));
+        assert_eq!(root.description.as_deref(), Some("Site de test"));
+        assert_eq!(result.manifest.root_hash(), &root.content_hash);
+        assert_eq!(
+            result.manifest.total_size,
+            result.resources.iter().map(|r| r.data.len() as u64).sum::<u64>()
+        );
+
+        let deep = ArchiveOptions { max_depth: 3, ..ArchiveOptions::default() };
+        let result = CrawlEngine::allowing_private_networks(deep).unwrap().crawl(&base).await.unwrap();
+        assert!(urls(&result).contains(&format!("{}c", base)));
+    }
+
+    #[tokio::test]
+    async fn test_crawl_domain_scope_and_robots() {
+        let base = spawn_site().await;
+        let external = base.replace("127.0.0.1", "localhost") + "external";
+
+        let result = CrawlEngine::allowing_private_networks(ArchiveOptions::default()).unwrap().crawl(&base).await.unwrap();
+        assert_eq!(skip_reason(&result, &external), Some(SkipReason::OutOfScope));
+        assert_eq!(skip_reason(&result, &format!("{}private/secret", base)), Some(SkipReason::RobotsTxt));
+        assert_eq!(skip_reason(&result, &format!("{}app.js", base)), Some(SkipReason::JavaScriptExcluded));
+
+        let options = ArchiveOptions {
+            same_domain_only: false,
+            respect_robots_txt: false,
+            preserve_javascript: true,
+            ..ArchiveOptions::default()
+        };
+        let result = CrawlEngine::allowing_private_networks(options).unwrap().crawl(&base).await.unwrap();
+        let crawled = urls(&result);
+        assert!(crawled.contains(&external));
+        assert!(crawled.contains(&format!("{}private/secret", base)));
+        assert!(crawled.contains(&format!("{}app.js", base)));
+
+        let options = ArchiveOptions { allowed_domains: vec!["localhost".to_string()], ..ArchiveOptions::default() };
+        let result = CrawlEngine::allowing_private_networks(options).unwrap().crawl(&base).await.unwrap();
+        assert!(urls(&result).contains(&external));
+    }
+
+    #[tokio::test]
+    async fn test_crawl_records_partial_failures() {
+        let base = spawn_site().await;
+        let result = CrawlEngine::allowing_private_networks(ArchiveOptions::default()).unwrap().crawl(&base).await.unwrap();
+
+        assert!(!result.manifest.is_complete());
+        assert_eq!(result.manifest.failures.len(), 1);
+        let failure = &result.manifest.failures[0];
+        assert_eq!(failure.url, format!("{}missing.png", base));
+        assert_eq!(failure.status, Some(404));
+        assert!(urls(&result).contains(&format!("{}a", base)));
+
+        // Le budget de taille écarte les ressources suivantes sans échouer
+        let root_size = result.manifest.entries[0].size;
+        let options = ArchiveOptions { max_total_size: Some(root_size), ..ArchiveOptions::default() };
+        let result = CrawlEngine::allowing_private_networks(options).unwrap().crawl(&base).await.unwrap();
+        assert_eq!(result.manifest.entries.len(), 1);
+        assert!(result.manifest.skipped.iter().any(|s| s.reason == SkipReason::SizeLimit));
+
+        // Seul l'échec de la racine fait échouer l'archive
+        let missing = format!("{}missing.png", base);
+        assert!(CrawlEngine::allowing_private_networks(ArchiveOptions::default()).unwrap().crawl(&missing).await.is_err());
+    }
+
+    #[test]
+    fn test_public_addresses() {
+        for private in ["127.0.0.1", "10.1.2.3", "172.16.0.1", "192.168.1.1", "169.254.169.254", "100.64.0.1", "0.0.0.0", "::1", "fd00::1", "fe80::1", "::ffff:10.0.0.1"] {
+            assert!(!is_public_address(private.parse().unwrap()), "{}", private);
+        }
+        for public in ["93.184.216.34", "100.128.0.1", "2606:2800:220:1::1"] {
+            assert!(is_public_address(public.parse().unwrap()), "{}", public);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_crawl_refuses_private_addresses() {
+        let base = spawn_site().await;
+        assert!(CrawlEngine::new(ArchiveOptions::default()).unwrap().crawl(&base).await.is_err());
+
+        // Un nom qui résout vers une adresse locale est aussi refusé
+        let named = base.replace("127.0.0.1", "localhost");
+        assert!(CrawlEngine::new(ArchiveOptions::default()).unwrap().crawl(&named).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_crawl_redirects_follow_scope_and_robots() {
+        let base = spawn_site().await;
+        let engine = CrawlEngine::allowing_private_networks(ArchiveOptions::default()).unwrap();
+
+        // Redirection dans le périmètre : le contenu de la cible est capturé
+        let result = engine.crawl(&format!("{}moved", base)).await.unwrap();
+        assert!(String::from_utf8_lossy(&result.resources[0].data).contains("/b"));
+
+        // Hors périmètre ou interdite par robots.txt : refusée comme un lien
+        assert!(engine.crawl(&format!("{}escape", base)).await.is_err());
+        assert!(engine.crawl(&format!("{}hidden", base)).await.is_err());
+
+        let options = ArchiveOptions { respect_robots_txt: false, ..ArchiveOptions::default() };
+        let result = CrawlEngine::allowing_private_networks(options).unwrap().crawl(&format!("{}hidden", base)).await.unwrap();
+        assert!(String::from_utf8_lossy(&result.resources[0].data).contains("secret"));
+    }
+}
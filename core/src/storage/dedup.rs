@@ -0,0 +1,963 @@
+//! Déduplication par chunks pour le stockage ArchiveChain
+//!
+//! Découpe les contenus en chunks définis par le contenu (content-defined
+//! chunking), identifie chaque chunk par son hash Blake3 et ne conserve
+//! qu'une seule copie de chaque chunk :
+//! - Manifeste de chunks par hash de contenu
+//! - Compteurs de références par chunk
+//! - Reconstitution transparente des contenus
+//! - Vérification d'intégrité et ré-réplication des copies corrompues
+//! - Chiffrement au repos optionnel, avec une clé de données par contenu
+//! - Persistance optionnelle des copies et des manifestes sur disque
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use crate::crypto::{Hash, HashAlgorithm, compute_blake3, compute_hash};
+use crate::error::{CoreError, Result};
+use super::encryption::{ContentCipher, DataKey, KeyEncryptionKey, WrappedKey};
+
+/// Nombre de copies conservées par chunk
+pub const DEFAULT_CHUNK_REPLICAS: usize = 2;
+
+/// Table de gear pour le hash roulant (générée de façon déterministe)
+const GEAR: [u64; 256] = build_gear_table();
+
+const fn build_gear_table() -> [u64; 256] {
+    let mut table = [0u64; 256];
+    let mut state: u64 = 0x9E37_79B9_7F4A_7C15;
+    let mut i = 0;
+    while i < 256 {
+        // splitmix64
+        state = state.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = state;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        table[i] = z ^ (z >> 31);
+        i += 1;
+    }
+    table
+}
+
+/// Configuration du découpage en chunks
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChunkingConfig {
+    /// Taille minimale d'un chunk (bytes)
+    pub min_chunk_size: usize,
+    /// Taille moyenne visée (puissance de 2, bytes)
+    pub avg_chunk_size: usize,
+    /// Taille maximale d'un chunk (bytes)
+    pub max_chunk_size: usize,
+}
+
+impl Default for ChunkingConfig {
+    fn default() -> Self {
+        Self {
+            min_chunk_size: 2 * 1024,
+            avg_chunk_size: 8 * 1024,
+            max_chunk_size: 64 * 1024,
+        }
+    }
+}
+
+impl ChunkingConfig {
+    /// Découpe les données en chunks définis par le contenu
+    ///
+    /// Une frontière est placée lorsque le hash roulant satisfait le masque
+    /// dérivé de `avg_chunk_size` ; une insertion locale ne décale donc que
+    /// les chunks voisins.
+    pub fn split<'a>(&self, data: &'a [u8]) -> Vec<&'a [u8]> {
+        let min = self.min_chunk_size.max(1);
+        let max = self.max_chunk_size.max(min);
+        let mask = (self.avg_chunk_size.max(2).next_power_of_two() - 1) as u64;
+
+        let mut chunks = Vec::new();
+        let mut start = 0;
+
+        while start < data.len() {
+            let remaining = data.len() - start;
+            if remaining <= min {
+                chunks.push(&data[start..]);
+                break;
+            }
+
+            let limit = remaining.min(max);
+            let mut hash: u64 = 0;
+            let mut cut = limit;
+            for (i, byte) in data[start..start + limit].iter().enumerate() {
+                hash = (hash << 1).wrapping_add(GEAR[*byte as usize]);
+                if i + 1 >= min && hash & mask == 0 {
+                    cut = i + 1;
+                    break;
+                }
+            }
+
+            chunks.push(&data[start..start + cut]);
+            start += cut;
+        }
+
+        chunks
+    }
+}
+
+/// Manifeste décrivant un contenu comme une suite de chunks
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ChunkManifest {
+    /// Hash du contenu complet
+    pub content_hash: Hash,
+    /// Hashes des chunks dans l'ordre
+    pub chunks: Vec<Hash>,
+    /// Taille totale du contenu
+    pub total_size: u64,
+    /// Clé de données enveloppée, si le contenu est chiffré
+    #[serde(default)]
+    pub wrapped_key: Option<WrappedKey>,
+    /// Algorithme chiffrant les chunks, si le contenu est chiffré
+    #[serde(default)]
+    pub cipher: ContentCipher,
+}
+
+/// Emplacement d'une copie de chunk
+#[derive(Debug, Clone)]
+enum ReplicaData {
+    /// Copie en mémoire, pour un magasin sans répertoire
+    Memory(Vec<u8>),
+    /// Copie sur disque, relue à chaque accès
+    Disk(PathBuf),
+}
+
+/// Copie d'un chunk
+#[derive(Debug, Clone)]
+struct ChunkReplica {
+    data: ReplicaData,
+    /// Faux lorsque la copie a été détectée comme corrompue
+    available: bool,
+}
+
+impl ChunkReplica {
+    /// Lit les octets de la copie
+    fn read(&self) -> std::io::Result<Vec<u8>> {
+        match &self.data {
+            ReplicaData::Memory(data) => Ok(data.clone()),
+            ReplicaData::Disk(path) => std::fs::read(path),
+        }
+    }
+
+    /// Remplace les octets de la copie
+    ///
+    /// Sur disque, la copie est écrite dans un fichier temporaire puis
+    /// renommée : une écriture interrompue ne laisse pas de copie tronquée.
+    fn write(&mut self, bytes: &[u8]) -> std::io::Result<()> {
+        match &mut self.data {
+            ReplicaData::Memory(data) => {
+                data.clear();
+                data.extend_from_slice(bytes);
+                Ok(())
+            }
+            ReplicaData::Disk(path) => {
+                if let Some(parent) = path.parent() {
+                    std::fs::create_dir_all(parent)?;
+                }
+                let tmp_path = path.with_extension("tmp");
+                std::fs::write(&tmp_path, bytes)?;
+                std::fs::rename(&tmp_path, path)
+            }
+        }
+    }
+
+    /// Supprime la copie du disque
+    fn remove(&self) -> std::io::Result<()> {
+        match &self.data {
+            ReplicaData::Memory(_) => Ok(()),
+            ReplicaData::Disk(path) => match std::fs::remove_file(path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e),
+                _ => Ok(()),
+            },
+        }
+    }
+}
+
+/// Chunk stocké avec ses copies et son compteur de références
+#[derive(Debug, Clone)]
+struct StoredChunk {
+    replicas: Vec<ChunkReplica>,
+    size: u64,
+    ref_count: u32,
+}
+
+impl StoredChunk {
+    /// Données de la première copie saine et lisible
+    fn healthy_data(&self) -> Option<Vec<u8>> {
+        self.replicas.iter().filter(|r| r.available).find_map(|r| r.read().ok())
+    }
+
+    /// Déchiffre la première copie saine qui s'authentifie
+    ///
+    /// Retourne `Ok(None)` s'il ne reste aucune copie saine, et l'erreur
+    /// d'authentification si aucune copie ne se déchiffre.
+    fn decrypt(&self, key: &DataKey, cipher: ContentCipher, aad: &[u8]) -> Result<Option<Vec<u8>>> {
+        let mut failure = None;
+        for replica in self.replicas.iter().filter(|r| r.available) {
+            let Ok(sealed) = replica.read() else { continue };
+            match key.decrypt(cipher, &sealed, aad) {
+                Ok(plaintext) => return Ok(Some(plaintext)),
+                Err(e) => failure = Some(e),
+            }
+        }
+        failure.map_or(Ok(None), Err)
+    }
+
+    /// Restaure les copies indisponibles depuis une copie saine
+    ///
+    /// Une copie dont la réécriture échoue reste indisponible. Retourne le
+    /// nombre de copies restaurées.
+    fn re_replicate(&mut self) -> usize {
+        let Some(healthy) = self.healthy_data() else {
+            return 0;
+        };
+
+        let mut repaired = 0;
+        for replica in self.replicas.iter_mut().filter(|r| !r.available) {
+            match replica.write(&healthy) {
+                Ok(()) => {
+                    replica.available = true;
+                    repaired += 1;
+                }
+                Err(e) => tracing::warn!("Restauration d'une copie de chunk impossible: {}", e),
+            }
+        }
+        repaired
+    }
+
+    /// Supprime les copies du disque
+    fn remove_replicas(&self) {
+        for replica in &self.replicas {
+            if let Err(e) = replica.remove() {
+                tracing::warn!("Suppression d'une copie de chunk impossible: {}", e);
+            }
+        }
+    }
+}
+
+/// Résultat de la vérification d'intégrité d'un chunk
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChunkVerification {
+    /// Octets relus
+    pub bytes_read: u64,
+    /// Copies dont le hash ne correspond plus à l'adresse du chunk
+    pub corrupted_replicas: usize,
+    /// Copies restaurées depuis une copie saine
+    pub repaired_replicas: usize,
+    /// Aucune copie saine ne subsiste
+    pub unrecoverable: bool,
+}
+
+/// Résultat d'un stockage dédupliqué
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DedupOutcome {
+    /// Nombre de chunks du contenu
+    pub total_chunks: usize,
+    /// Chunks nouvellement stockés
+    pub new_chunks: usize,
+    /// Octets réellement écrits
+    pub bytes_written: u64,
+}
+
+/// Statistiques de déduplication
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DedupStats {
+    /// Taille cumulée des contenus référencés
+    pub logical_bytes: u64,
+    /// Taille réellement occupée par les chunks uniques
+    pub physical_bytes: u64,
+    /// Octets économisés par la déduplication
+    pub bytes_saved: u64,
+    /// Ratio logique / physique (1.0 sans déduplication)
+    pub dedup_ratio: f64,
+    /// Nombre de chunks uniques
+    pub unique_chunks: usize,
+    /// Nombre de contenus
+    pub manifests: usize,
+}
+
+/// Données authentifiées d'un chunk chiffré : hash du contenu et position
+fn chunk_aad(content_hash: &Hash, index: usize) -> Vec<u8> {
+    let mut aad = content_hash.as_bytes().to_vec();
+    aad.extend_from_slice(&(index as u32).to_le_bytes());
+    aad
+}
+
+/// Erreur d'entrée-sortie du magasin de chunks
+fn io_error(context: &str, e: std::io::Error) -> CoreError {
+    CoreError::Internal {
+        message: format!("{}: {}", context, e),
+    }
+}
+
+/// Magasin de chunks dédupliqués
+///
+/// Les opérations prennent `&mut self` : partagé derrière un `Mutex`, chaque
+/// stockage ou suppression met à jour les compteurs de façon atomique.
+///
+/// Avec le chiffrement activé, les chunks sont adressés par le hash de leur
+/// chiffré : la vérification d'intégrité reste inchangée, mais la
+/// déduplication ne joue plus qu'au sein d'un même contenu.
+///
+/// Sans répertoire, les copies ne vivent qu'en mémoire et ne survivent pas
+/// au redémarrage ; avec `with_directory`, chaque copie est un fichier
+/// distinct et la vérification d'intégrité relit réellement le disque.
+#[derive(Debug, Default)]
+pub struct ChunkStore {
+    /// Configuration du découpage
+    config: ChunkingConfig,
+    /// Chunks par hash
+    chunks: HashMap<Hash, StoredChunk>,
+    /// Manifestes par hash de contenu
+    manifests: HashMap<Hash, ChunkManifest>,
+    /// Octets occupés par les chunks
+    physical_bytes: u64,
+    /// Octets logiques référencés par les manifestes
+    logical_bytes: u64,
+    /// Nombre de copies par chunk
+    replicas_per_chunk: usize,
+    /// Clé maîtresse, si les nouveaux contenus sont chiffrés
+    encryption: Option<KeyEncryptionKey>,
+    /// Algorithme chiffrant les nouveaux contenus
+    cipher: ContentCipher,
+    /// Répertoire des copies et des manifestes, si le magasin est persistant
+    directory: Option<PathBuf>,
+}
+
+impl ChunkStore {
+    /// Crée un magasin de chunks
+    pub fn new(config: ChunkingConfig) -> Self {
+        Self {
+            config,
+            replicas_per_chunk: DEFAULT_CHUNK_REPLICAS,
+            cipher: ContentCipher::Aes256Gcm,
+            ..Self::default()
+        }
+    }
+
+    /// Définit le nombre de copies conservées pour les nouveaux chunks
+    pub fn with_replicas(mut self, replicas_per_chunk: usize) -> Self {
+        self.replicas_per_chunk = replicas_per_chunk;
+        self
+    }
+
+    /// Conserve les copies et les manifestes sous `directory`
+    ///
+    /// Les contenus déjà présents dans le répertoire sont rechargés ; une copie
+    /// manquante est marquée indisponible et sera restaurée par la prochaine
+    /// vérification d'intégrité. À appeler après `with_replicas`.
+    pub fn with_directory(mut self, directory: impl Into<PathBuf>) -> Result<Self> {
+        let directory = directory.into();
+        let manifest_dir = directory.join("manifests");
+        std::fs::create_dir_all(&manifest_dir)
+            .map_err(|e| io_error("Répertoire des chunks inaccessible", e))?;
+        self.directory = Some(directory);
+
+        let entries = std::fs::read_dir(&manifest_dir)
+            .map_err(|e| io_error("Répertoire des manifestes illisible", e))?;
+        for entry in entries {
+            let path = entry.map_err(|e| io_error("Répertoire des manifestes illisible", e))?.path();
+            // Les fichiers temporaires n'ont pas un nom de hash valide
+            let is_manifest = path.file_name()
+                .and_then(|name| name.to_str())
+                .is_some_and(|name| Hash::from_hex(name).is_ok());
+            if !is_manifest {
+                continue;
+            }
+
+            let bytes = std::fs::read(&path).map_err(|e| io_error("Manifeste illisible", e))?;
+            let manifest: ChunkManifest = serde_json::from_slice(&bytes).map_err(|e| CoreError::Internal {
+                message: format!("Manifeste {} invalide: {}", path.display(), e),
+            })?;
+            self.load_manifest(manifest);
+        }
+        Ok(self)
+    }
+
+    /// Reconstitue les chunks et les compteurs d'un manifeste rechargé
+    fn load_manifest(&mut self, manifest: ChunkManifest) {
+        for chunk_hash in &manifest.chunks {
+            if !self.chunks.contains_key(chunk_hash) {
+                let replicas: Vec<ChunkReplica> = (0..self.replicas_per_chunk.max(1))
+                    .map(|index| {
+                        let data = self.replica_data(index, chunk_hash);
+                        let available = matches!(&data, ReplicaData::Disk(path) if path.exists());
+                        ChunkReplica { data, available }
+                    })
+                    .collect();
+                let size = replicas.iter()
+                    .filter(|r| r.available)
+                    .find_map(|r| match &r.data {
+                        ReplicaData::Disk(path) => std::fs::metadata(path).ok().map(|m| m.len()),
+                        ReplicaData::Memory(data) => Some(data.len() as u64),
+                    })
+                    .unwrap_or(0);
+                self.physical_bytes += size;
+                self.chunks.insert(*chunk_hash, StoredChunk { replicas, size, ref_count: 0 });
+            }
+            if let Some(chunk) = self.chunks.get_mut(chunk_hash) {
+                chunk.ref_count += 1;
+            }
+        }
+
+        self.logical_bytes += manifest.total_size;
+        self.manifests.insert(manifest.content_hash, manifest);
+    }
+
+    /// Emplacement de la copie `index` d'un chunk
+    fn replica_data(&self, index: usize, chunk_hash: &Hash) -> ReplicaData {
+        match &self.directory {
+            Some(directory) => ReplicaData::Disk(
+                directory.join("chunks").join(index.to_string()).join(chunk_hash.to_hex()),
+            ),
+            None => ReplicaData::Memory(Vec::new()),
+        }
+    }
+
+    fn manifest_path(directory: &Path, content_hash: &Hash) -> PathBuf {
+        directory.join("manifests").join(content_hash.to_hex())
+    }
+
+    /// Écrit le manifeste d'un contenu, si le magasin est persistant
+    fn persist_manifest(&self, manifest: &ChunkManifest) -> Result<()> {
+        let Some(directory) = &self.directory else {
+            return Ok(());
+        };
+
+        let bytes = serde_json::to_vec(manifest).map_err(|e| CoreError::Internal {
+            message: format!("Sérialisation du manifeste {} impossible: {}", manifest.content_hash.to_hex(), e),
+        })?;
+        let path = Self::manifest_path(directory, &manifest.content_hash);
+        let tmp_path = path.with_extension("tmp");
+        std::fs::write(&tmp_path, bytes)
+            .and_then(|()| std::fs::rename(&tmp_path, &path))
+            .map_err(|e| io_error("Écriture du manifeste impossible", e))
+    }
+
+    /// Écrit les copies d'un nouveau chunk
+    fn create_chunk(&self, chunk_hash: &Hash, piece: &[u8]) -> Result<StoredChunk> {
+        let replicas = (0..self.replicas_per_chunk.max(1))
+            .map(|index| {
+                let mut replica = ChunkReplica { data: self.replica_data(index, chunk_hash), available: true };
+                replica.write(piece).map_err(|e| io_error("Écriture du chunk impossible", e))?;
+                Ok(replica)
+            })
+            .collect::<Result<Vec<_>>>()?;
+        Ok(StoredChunk { replicas, size: piece.len() as u64, ref_count: 0 })
+    }
+
+    /// Chiffre les nouveaux contenus avec des clés enveloppées par `kek`
+    pub fn with_encryption(mut self, kek: KeyEncryptionKey) -> Self {
+        self.encryption = Some(kek);
+        self
+    }
+
+    /// Choisit l'algorithme chiffrant les nouveaux contenus
+    ///
+    /// Les contenus déjà stockés restent lisibles : leur manifeste conserve
+    /// l'algorithme utilisé.
+    pub fn with_cipher(mut self, cipher: ContentCipher) -> Self {
+        self.cipher = cipher;
+        self
+    }
+
+    /// Vrai si les nouveaux contenus sont chiffrés
+    pub fn is_encrypted(&self) -> bool {
+        self.encryption.is_some()
+    }
+
+    /// Stocke un contenu en ne conservant que les chunks inédits
+    ///
+    /// Stocker deux fois le même hash de contenu est sans effet.
+    pub fn store(&mut self, content_hash: Hash, data: &[u8]) -> Result<DedupOutcome> {
+        if let Some(manifest) = self.manifests.get(&content_hash) {
+            return Ok(DedupOutcome {
+                total_chunks: manifest.chunks.len(),
+                new_chunks: 0,
+                bytes_written: 0,
+            });
+        }
+
+        let (data_key, wrapped_key) = match &self.encryption {
+            Some(kek) => {
+                let data_key = DataKey::generate();
+                let wrapped = kek.wrap_key(&data_key, &content_hash)?;
+                (Some(data_key), Some(wrapped))
+            }
+            None => (None, None),
+        };
+
+        // Les copies et le manifeste sont écrits avant toute mise à jour des
+        // compteurs : un échec d'écriture laisse le magasin inchangé.
+        let mut chunk_hashes = Vec::new();
+        let mut created: HashMap<Hash, StoredChunk> = HashMap::new();
+
+        for (index, piece) in self.config.split(data).into_iter().enumerate() {
+            let sealed;
+            let piece = match &data_key {
+                Some(key) => {
+                    sealed = key.encrypt(self.cipher, piece, &chunk_aad(&content_hash, index))?;
+                    sealed.as_slice()
+                }
+                None => piece,
+            };
+            let chunk_hash = compute_blake3(piece);
+            if !self.chunks.contains_key(&chunk_hash) && !created.contains_key(&chunk_hash) {
+                created.insert(chunk_hash, self.create_chunk(&chunk_hash, piece)?);
+            }
+            chunk_hashes.push(chunk_hash);
+        }
+
+        let manifest = ChunkManifest {
+            content_hash,
+            chunks: chunk_hashes,
+            total_size: data.len() as u64,
+            wrapped_key,
+            cipher: self.cipher,
+        };
+        self.persist_manifest(&manifest)?;
+
+        let new_chunks = created.len();
+        let bytes_written: u64 = created.values().map(|chunk| chunk.size).sum();
+        self.chunks.extend(created);
+        for chunk_hash in &manifest.chunks {
+            if let Some(chunk) = self.chunks.get_mut(chunk_hash) {
+                chunk.ref_count += 1;
+            }
+        }
+
+        self.physical_bytes += bytes_written;
+        self.logical_bytes += data.len() as u64;
+
+        let total_chunks = manifest.chunks.len();
+        self.manifests.insert(content_hash, manifest);
+
+        Ok(DedupOutcome { total_chunks, new_chunks, bytes_written })
+    }
+
+    /// Reconstitue un contenu depuis son manifeste
+    ///
+    /// Les contenus chiffrés sont déchiffrés de façon transparente ; un chiffré
+    /// altéré sur toutes ses copies échoue avec
+    /// `CryptoError::AuthenticationFailed`. Retourne `Ok(None)` si le contenu ou
+    /// l'un de ses chunks est indisponible.
+    pub fn retrieve(&self, content_hash: &Hash) -> Result<Option<Vec<u8>>> {
+        let Some(manifest) = self.manifests.get(content_hash) else {
+            return Ok(None);
+        };
+        let data_key = match &manifest.wrapped_key {
+            Some(wrapped) => {
+                let kek = self.encryption.as_ref().ok_or_else(|| CoreError::NotFound {
+                    message: format!("Clé maîtresse requise pour déchiffrer {}", content_hash.to_hex()),
+                })?;
+                Some(kek.unwrap_key(wrapped, content_hash)?)
+            }
+            None => None,
+        };
+
+        let mut data = Vec::with_capacity(manifest.total_size as usize);
+        for (index, chunk_hash) in manifest.chunks.iter().enumerate() {
+            let Some(chunk) = self.chunks.get(chunk_hash) else {
+                return Ok(None);
+            };
+            let piece = match &data_key {
+                Some(key) => chunk.decrypt(key, manifest.cipher, &chunk_aad(content_hash, index))?,
+                None => chunk.healthy_data(),
+            };
+            let Some(piece) = piece else {
+                return Ok(None);
+            };
+            data.extend_from_slice(&piece);
+        }
+        Ok(Some(data))
+    }
+
+    /// Ré-enveloppe les clés de données avec une nouvelle clé maîtresse
+    ///
+    /// Les chunks ne sont pas rechiffrés. Les manifestes ne sont modifiés que
+    /// si toutes les clés ont pu être désenveloppées ; sans chiffrement actif,
+    /// `new_kek` chiffre simplement les contenus suivants. Retourne le nombre
+    /// de clés ré-enveloppées.
+    pub fn rewrap_keys(&mut self, new_kek: KeyEncryptionKey) -> Result<usize> {
+        let mut rewrapped = Vec::new();
+        if let Some(current) = &self.encryption {
+            for (content_hash, manifest) in &self.manifests {
+                let Some(wrapped) = &manifest.wrapped_key else { continue };
+                let data_key = current.unwrap_key(wrapped, content_hash)?;
+                rewrapped.push((content_hash.clone(), new_kek.wrap_key(&data_key, content_hash)?));
+            }
+        }
+
+        let count = rewrapped.len();
+        for (content_hash, wrapped) in rewrapped {
+            if let Some(manifest) = self.manifests.get_mut(&content_hash) {
+                manifest.wrapped_key = Some(wrapped);
+            }
+            if let Some(manifest) = self.manifests.get(&content_hash) {
+                self.persist_manifest(manifest)?;
+            }
+        }
+        self.encryption = Some(new_kek);
+        Ok(count)
+    }
+
+    /// Supprime un contenu et libère les chunks qui ne sont plus référencés
+    ///
+    /// Retourne le nombre d'octets libérés.
+    pub fn release(&mut self, content_hash: &Hash) -> u64 {
+        let Some(manifest) = self.manifests.remove(content_hash) else {
+            return 0;
+        };
+        if let Some(directory) = &self.directory {
+            match std::fs::remove_file(Self::manifest_path(directory, content_hash)) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => {
+                    tracing::warn!("Suppression du manifeste {} impossible: {}", content_hash.to_hex(), e);
+                }
+                _ => {}
+            }
+        }
+
+        let mut freed = 0u64;
+        for chunk_hash in &manifest.chunks {
+            if let Some(chunk) = self.chunks.get_mut(chunk_hash) {
+                chunk.ref_count = chunk.ref_count.saturating_sub(1);
+                if chunk.ref_count == 0 {
+                    freed += chunk.size;
+                    chunk.remove_replicas();
+                    self.chunks.remove(chunk_hash);
+                }
+            }
+        }
+
+        self.physical_bytes -= freed;
+        self.logical_bytes -= manifest.total_size;
+        freed
+    }
+
+    /// Obtient le manifeste d'un contenu
+    pub fn manifest(&self, content_hash: &Hash) -> Option<&ChunkManifest> {
+        self.manifests.get(content_hash)
+    }
+
+    /// Nombre de références d'un chunk
+    pub fn chunk_ref_count(&self, chunk_hash: &Hash) -> u32 {
+        self.chunks.get(chunk_hash).map_or(0, |c| c.ref_count)
+    }
+
+    /// Hashes de tous les contenus stockés
+    pub fn content_hashes(&self) -> impl ExactSizeIterator<Item = &Hash> {
+        self.manifests.keys()
+    }
+
+    /// Hashes de tous les chunks stockés
+    pub fn chunk_hashes(&self) -> Vec<Hash> {
+        self.chunks.keys().copied().collect()
+    }
+
+    /// Nombre de copies saines d'un chunk
+    pub fn available_replicas(&self, chunk_hash: &Hash) -> usize {
+        self.chunks.get(chunk_hash).map_or(0, |c| c.replicas.iter().filter(|r| r.available).count())
+    }
+
+    /// Relit les copies d'un chunk et compare leur hash à son adresse
+    ///
+    /// Les copies corrompues ou illisibles sont marquées indisponibles puis
+    /// ré-répliquées depuis une copie saine. Une copie déjà indisponible,
+    /// par exemple absente au rechargement, est restaurée de la même façon.
+    /// Retourne `None` si le chunk n'existe pas.
+    pub fn verify_chunk(&mut self, chunk_hash: &Hash) -> Option<ChunkVerification> {
+        let chunk = self.chunks.get_mut(chunk_hash)?;
+        let mut verification = ChunkVerification::default();
+
+        for replica in chunk.replicas.iter_mut().filter(|r| r.available) {
+            let intact = match replica.read() {
+                Ok(data) => {
+                    verification.bytes_read += data.len() as u64;
+                    compute_hash(&data, HashAlgorithm::Blake3) == *chunk_hash
+                }
+                Err(_) => false,
+            };
+            if !intact {
+                replica.available = false;
+                verification.corrupted_replicas += 1;
+            }
+        }
+
+        verification.repaired_replicas = chunk.re_replicate();
+        verification.unrecoverable = chunk.healthy_data().is_none();
+        Some(verification)
+    }
+
+    /// Altère une copie d'un chunk pour simuler une corruption silencieuse
+    #[cfg(test)]
+    pub(crate) fn corrupt_replica(&mut self, chunk_hash: &Hash, replica: usize) {
+        let chunk = self.chunks.get_mut(chunk_hash).expect("chunk inconnu");
+        let replica = &mut chunk.replicas[replica];
+        let mut data = replica.read().expect("copie illisible");
+        data[0] ^= 0xFF;
+        replica.write(&data).expect("copie non modifiable");
+    }
+
+    /// Statistiques de déduplication
+    pub fn stats(&self) -> DedupStats {
+        DedupStats {
+            logical_bytes: self.logical_bytes,
+            physical_bytes: self.physical_bytes,
+            bytes_saved: self.logical_bytes.saturating_sub(self.physical_bytes),
+            dedup_ratio: if self.physical_bytes > 0 {
+                self.logical_bytes as f64 / self.physical_bytes as f64
+            } else {
+                1.0
+            },
+            unique_chunks: self.chunks.len(),
+            manifests: self.manifests.len(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Arc;
+    use tokio::sync::Mutex;
+
+    fn html_page(headline: &str) -> Vec<u8> {
+        let mut page = String::from("<html><head><title>Archive</title></head><body>\n");
+        page.push_str(&format!("<h1>{}</h1>\n", headline));
+        for i in 0..4000 {
+            page.push_str(&format!("<p class=\"entry\">Paragraphe {} du contenu archivé.</p>\n", i));
+        }
+        page.push_str("</body></html>\n");
+        page.into_bytes()
+    }
+
+    #[test]
+    fn test_chunking_respects_bounds() {
+        let config = ChunkingConfig::default();
+        let data = html_page("bornes");
+        let chunks = config.split(&data);
+
+        assert_eq!(chunks.iter().map(|c| c.len()).sum::<usize>(), data.len());
+        for chunk in &chunks[..chunks.len() - 1] {
+            assert!(chunk.len() >= config.min_chunk_size);
+            assert!(chunk.len() <= config.max_chunk_size);
+        }
+    }
+
+    #[test]
+    fn test_near_identical_pages_are_deduplicated() {
+        let mut store = ChunkStore::new(ChunkingConfig::default());
+        let first = html_page("Édition du matin");
+        let second = html_page("Édition du soir");
+        let first_hash = compute_blake3(&first);
+        let second_hash = compute_blake3(&second);
+
+        store.store(first_hash, &first).unwrap();
+        let outcome = store.store(second_hash, &second).unwrap();
+
+        let stats = store.stats();
+        assert!(outcome.bytes_written < second.len() as u64 / 4);
+        assert!(stats.physical_bytes < (first.len() + second.len()) as u64 * 6 / 10);
+        assert!(stats.dedup_ratio > 1.5);
+        assert_eq!(stats.bytes_saved, stats.logical_bytes - stats.physical_bytes);
+
+        assert_eq!(store.retrieve(&first_hash).unwrap(), Some(first));
+        assert_eq!(store.retrieve(&second_hash).unwrap(), Some(second));
+    }
+
+    #[test]
+    fn test_release_keeps_shared_chunks() {
+        let mut store = ChunkStore::new(ChunkingConfig::default());
+        let first = html_page("un");
+        let second = html_page("deux");
+        let first_hash = compute_blake3(&first);
+        let second_hash = compute_blake3(&second);
+
+        store.store(first_hash, &first).unwrap();
+        store.store(second_hash, &second).unwrap();
+
+        let freed = store.release(&first_hash);
+        assert!(freed < first.len() as u64);
+        assert!(store.retrieve(&first_hash).unwrap().is_none());
+        assert_eq!(store.retrieve(&second_hash).unwrap(), Some(second.clone()));
+
+        store.release(&second_hash);
+        let stats = store.stats();
+        assert_eq!(stats.physical_bytes, 0);
+        assert_eq!(stats.unique_chunks, 0);
+    }
+
+    #[test]
+    fn test_verify_chunk_repairs_corrupted_replica() {
+        let mut store = ChunkStore::new(ChunkingConfig::default()).with_replicas(3);
+        let page = html_page("intégrité");
+        let content_hash = compute_blake3(&page);
+        store.store(content_hash, &page).unwrap();
+
+        let chunk_hash = store.manifest(&content_hash).unwrap().chunks[0];
+        let healthy = store.verify_chunk(&chunk_hash).unwrap();
+        assert_eq!(healthy.corrupted_replicas, 0);
+        assert_eq!(healthy.bytes_read, store.chunks[&chunk_hash].size * 3);
+
+        store.corrupt_replica(&chunk_hash, 0);
+        store.corrupt_replica(&chunk_hash, 2);
+        let verification = store.verify_chunk(&chunk_hash).unwrap();
+        assert_eq!(verification.corrupted_replicas, 2);
+        assert_eq!(verification.repaired_replicas, 2);
+        assert!(!verification.unrecoverable);
+        assert_eq!(store.available_replicas(&chunk_hash), 3);
+        assert_eq!(store.retrieve(&content_hash).unwrap(), Some(page));
+
+        // Sans copie saine, le chunk reste indisponible
+        for replica in 0..3 {
+            store.corrupt_replica(&chunk_hash, replica);
+        }
+        let verification = store.verify_chunk(&chunk_hash).unwrap();
+        assert!(verification.unrecoverable);
+        assert_eq!(store.available_replicas(&chunk_hash), 0);
+        assert!(store.retrieve(&content_hash).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_directory_store_reloads_and_repairs_disk_copies() {
+        let dir = tempfile::tempdir().unwrap();
+        let page = html_page("persistant");
+        let content_hash = compute_blake3(&page);
+        let chunk_hash = {
+            let mut store = ChunkStore::new(ChunkingConfig::default()).with_directory(dir.path()).unwrap();
+            store.store(content_hash, &page).unwrap();
+            store.manifest(&content_hash).unwrap().chunks[0]
+        };
+
+        // Bit rot sur le disque pendant que le nœud est arrêté
+        let first = dir.path().join("chunks").join("0").join(chunk_hash.to_hex());
+        let second = dir.path().join("chunks").join("1").join(chunk_hash.to_hex());
+        let mut bytes = std::fs::read(&first).unwrap();
+        bytes[0] ^= 0xFF;
+        std::fs::write(&first, bytes).unwrap();
+
+        let mut store = ChunkStore::new(ChunkingConfig::default()).with_directory(dir.path()).unwrap();
+        assert_eq!(store.stats().manifests, 1);
+        assert_eq!(store.stats().logical_bytes, page.len() as u64);
+
+        let verification = store.verify_chunk(&chunk_hash).unwrap();
+        assert_eq!(verification.corrupted_replicas, 1);
+        assert_eq!(verification.repaired_replicas, 1);
+        assert_eq!(std::fs::read(&first).unwrap(), std::fs::read(&second).unwrap());
+        assert_eq!(store.retrieve(&content_hash).unwrap(), Some(page));
+
+        // La libération efface les copies et le manifeste
+        store.release(&content_hash);
+        assert!(!first.exists() && !second.exists());
+        let reloaded = ChunkStore::new(ChunkingConfig::default()).with_directory(dir.path()).unwrap();
+        assert_eq!(reloaded.stats().manifests, 0);
+    }
+
+    #[tokio::test]
+    async fn test_concurrent_stores_keep_refcounts_consistent() {
+        let store = Arc::new(Mutex::new(ChunkStore::new(ChunkingConfig::default())));
+        let page = Arc::new(html_page("partagé"));
+
+        let mut handles = Vec::new();
+        for i in 0..8u8 {
+            let store = store.clone();
+            let page = page.clone();
+            handles.push(tokio::spawn(async move {
+                // Même charge utile sous des hashes de contenu distincts
+                let content_hash = compute_blake3(&[i]);
+                store.lock().await.store(content_hash, &page).unwrap();
+                content_hash
+            }));
+        }
+
+        let mut hashes = Vec::new();
+        for handle in handles {
+            hashes.push(handle.await.unwrap());
+        }
+
+        let store = store.lock().await;
+        let manifest = store.manifest(&hashes[0]).unwrap();
+        for chunk_hash in &manifest.chunks {
+            assert_eq!(store.chunk_ref_count(chunk_hash), 8);
+        }
+        assert_eq!(store.stats().physical_bytes, page.len() as u64);
+    }
+
+    #[test]
+    fn test_encrypted_store_round_trip() {
+        let mut store = ChunkStore::new(ChunkingConfig::default())
+            .with_encryption(KeyEncryptionKey::from_bytes([7; 32]));
+        let page = html_page("chiffré");
+        let content_hash = compute_blake3(&page);
+        store.store(content_hash, &page).unwrap();
+
+        let manifest = store.manifest(&content_hash).unwrap();
+        assert!(manifest.wrapped_key.is_some());
+        // Aucun chunk ne contient le texte en clair
+        let first_chunk = store.chunks[&manifest.chunks[0]].replicas[0].read().unwrap();
+        assert!(!first_chunk.windows(8).any(|w| w == b"<html><h"));
+        assert_eq!(store.retrieve(&content_hash).unwrap(), Some(page));
+    }
+
+    #[test]
+    fn test_tampered_ciphertext_fails_authentication() {
+        let mut store = ChunkStore::new(ChunkingConfig::default())
+            .with_replicas(1)
+            .with_encryption(KeyEncryptionKey::from_bytes([7; 32]));
+        let page = html_page("altéré");
+        let content_hash = compute_blake3(&page);
+        store.store(content_hash, &page).unwrap();
+
+        let chunk_hash = store.manifest(&content_hash).unwrap().chunks[1];
+        store.corrupt_replica(&chunk_hash, 0);
+        assert!(matches!(
+            store.retrieve(&content_hash),
+            Err(CoreError::Crypto(crate::error::CryptoError::AuthenticationFailed(_)))
+        ));
+    }
+
+    #[test]
+    fn test_cipher_is_recorded_per_manifest() {
+        let kek = KeyEncryptionKey::from_bytes([7; 32]);
+        let mut store = ChunkStore::new(ChunkingConfig::default())
+            .with_encryption(kek.clone())
+            .with_cipher(ContentCipher::XChaCha20Poly1305);
+        let legacy = html_page("XChaCha20");
+        let legacy_hash = compute_blake3(&legacy);
+        store.store(legacy_hash, &legacy).unwrap();
+
+        // Les nouveaux contenus passent à AES-256-GCM, les anciens restent lisibles
+        store = store.with_cipher(ContentCipher::Aes256Gcm);
+        let page = html_page("AES-256-GCM");
+        let content_hash = compute_blake3(&page);
+        store.store(content_hash, &page).unwrap();
+
+        assert_eq!(store.manifest(&legacy_hash).unwrap().cipher, ContentCipher::XChaCha20Poly1305);
+        assert_eq!(store.manifest(&content_hash).unwrap().cipher, ContentCipher::Aes256Gcm);
+        assert_eq!(store.retrieve(&legacy_hash).unwrap(), Some(legacy));
+        assert_eq!(store.retrieve(&content_hash).unwrap(), Some(page));
+    }
+
+    #[test]
+    fn test_rewrap_keys_keeps_content_readable() {
+        let mut store = ChunkStore::new(ChunkingConfig::default())
+            .with_encryption(KeyEncryptionKey::from_bytes([7; 32]));
+        let page = html_page("rotation");
+        let content_hash = compute_blake3(&page);
+        store.store(content_hash, &page).unwrap();
+        let chunks_before = store.manifest(&content_hash).unwrap().chunks.clone();
+
+        let new_kek = KeyEncryptionKey::from_bytes([8; 32]);
+        let new_id = new_kek.id().clone();
+        assert_eq!(store.rewrap_keys(new_kek).unwrap(), 1);
+
+        let manifest = store.manifest(&content_hash).unwrap();
+        assert_eq!(manifest.wrapped_key.as_ref().unwrap().kek_id, new_id);
+        assert_eq!(manifest.chunks, chunks_before);
+        assert_eq!(store.retrieve(&content_hash).unwrap(), Some(page));
+    }
+}
@@ -211,6 +211,51 @@ impl DistributedHashTable {
         }
     }
 
+    /// Nœuds stockant un contenu, sans compter d'accès
+    pub fn storage_nodes(&self, content_hash: &Hash) -> Vec<NodeId> {
+        self.local_table.get(content_hash)
+            .map(|entry| entry.storage_nodes.clone())
+            .unwrap_or_default()
+    }
+
+    /// Retire un nœud de toutes les entrées ; retourne les contenus qu'il stockait
+    pub fn remove_storage_node(&mut self, node_id: &NodeId) -> Vec<Hash> {
+        let mut affected = Vec::new();
+        for entry in self.local_table.values_mut() {
+            let before = entry.storage_nodes.len();
+            entry.storage_nodes.retain(|n| n != node_id);
+            if entry.storage_nodes.len() != before {
+                entry.last_updated = SystemTime::now();
+                affected.push(entry.content_hash);
+            }
+        }
+        affected
+    }
+
+    /// Retire un nœud de l'entrée d'un seul contenu
+    pub fn remove_content_replica(&mut self, content_hash: &Hash, node_id: &NodeId) -> bool {
+        match self.local_table.get_mut(content_hash) {
+            Some(entry) if entry.storage_nodes.contains(node_id) => {
+                entry.storage_nodes.retain(|n| n != node_id);
+                entry.last_updated = SystemTime::now();
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Ajoute un nœud de stockage à une entrée existante
+    pub fn add_storage_node(&mut self, content_hash: &Hash, node_id: NodeId) -> bool {
+        match self.local_table.get_mut(content_hash) {
+            Some(entry) if !entry.storage_nodes.contains(&node_id) => {
+                entry.storage_nodes.push(node_id);
+                entry.last_updated = SystemTime::now();
+                true
+            }
+            _ => false,
+        }
+    }
+
     /// Recherche dans la DHT
     pub fn search(&self, query: &SearchQuery) -> Vec<SearchResult> {
         let mut results = Vec::new();
@@ -639,6 +684,8 @@ pub struct PopularityTracker {
     access_counts: HashMap<Hash, u64>,
     /// Timestamps des accès récents
     recent_accesses: HashMap<Hash, VecDeque<SystemTime>>,
+    /// Dernier accès par contenu, conservé au-delà de la fenêtre
+    last_accesses: HashMap<Hash, SystemTime>,
     /// Fenêtre de temps pour la popularité
     time_window: Duration,
 }
@@ -649,6 +696,7 @@ impl PopularityTracker {
         Self {
             access_counts: HashMap::new(),
             recent_accesses: HashMap::new(),
+            last_accesses: HashMap::new(),
             time_window,
         }
     }
@@ -659,6 +707,7 @@ impl PopularityTracker {
         
         // Incrémente le compteur global
         *self.access_counts.entry(content_hash).or_insert(0) += 1;
+        self.last_accesses.insert(content_hash, now);
         
         // Ajoute l'accès récent
         let recent = self.recent_accesses.entry(content_hash).or_insert_with(VecDeque::new);
@@ -681,6 +730,11 @@ impl PopularityTracker {
         self.access_counts.get(content_hash).copied().unwrap_or(0)
     }
 
+    /// Date du dernier accès à un contenu
+    pub fn last_access(&self, content_hash: &Hash) -> Option<SystemTime> {
+        self.last_accesses.get(content_hash).copied()
+    }
+
     /// Nettoie les anciens accès
     fn cleanup_old_accesses(&mut self, content_hash: Hash) {
         if let Some(accesses) = self.recent_accesses.get_mut(&content_hash) {
@@ -751,6 +805,26 @@ impl ContentDiscovery {
         self.content_index.add_content(content_hash, metadata);
     }
 
+    /// Nœuds stockant un contenu
+    pub fn storage_nodes(&self, content_hash: &Hash) -> Vec<NodeId> {
+        self.dht.storage_nodes(content_hash)
+    }
+
+    /// Retire un nœud défaillant de la DHT ; retourne les contenus qu'il stockait
+    pub fn remove_storage_node(&mut self, node_id: &NodeId) -> Vec<Hash> {
+        self.dht.remove_storage_node(node_id)
+    }
+
+    /// Retire la réplique d'un contenu sur un nœud
+    pub fn remove_content_replica(&mut self, content_hash: &Hash, node_id: &NodeId) -> bool {
+        self.dht.remove_content_replica(content_hash, node_id)
+    }
+
+    /// Enregistre une nouvelle réplique d'un contenu sur un nœud
+    pub fn add_storage_node(&mut self, content_hash: &Hash, node_id: NodeId) -> bool {
+        self.dht.add_storage_node(content_hash, node_id)
+    }
+
     /// Recherche du contenu
     pub async fn search(&mut self, query: SearchQuery) -> Result<SearchResults> {
         let start_time = SystemTime::now();
@@ -824,6 +898,11 @@ impl ContentDiscovery {
         self.popularity_tracker.record_access(content_hash);
     }
 
+    /// Date du dernier accès à un contenu
+    pub fn last_access(&self, content_hash: &Hash) -> Option<SystemTime> {
+        self.popularity_tracker.last_access(content_hash)
+    }
+
     /// Obtient les contenus les plus populaires
     pub fn get_popular_content(&mut self, limit: usize) -> Vec<(Hash, u64)> {
         self.popularity_tracker.get_top_content(limit)
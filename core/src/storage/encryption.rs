@@ -0,0 +1,251 @@
+//! Chiffrement au repos des archives
+//!
+//! Chaque contenu reçoit sa propre clé de données, tirée aléatoirement, qui
+//! chiffre ses chunks avec AES-256-GCM (XChaCha20-Poly1305 pour les contenus
+//! plus anciens). La clé de données est enveloppée par la clé maîtresse du
+//! nœud (KEK) et conservée dans le manifeste du contenu : changer de clé
+//! maîtresse ne demande que de ré-envelopper les manifestes, sans rechiffrer
+//! les données.
+
+use aes_gcm::{Aes256Gcm, Nonce};
+use chacha20poly1305::aead::{Aead, KeyInit, Payload};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use crate::crypto::Hash;
+use crate::error::{CoreError, CryptoError, Result};
+
+/// Taille des clés (bytes)
+pub const KEY_SIZE: usize = 32;
+
+/// Taille des nonces XChaCha20 (bytes)
+pub const NONCE_SIZE: usize = 24;
+
+/// Taille des nonces AES-256-GCM (bytes)
+pub const AES_GCM_NONCE_SIZE: usize = 12;
+
+/// Contexte de dérivation de la clé maîtresse depuis un fichier de clé
+const KEK_DERIVATION_CONTEXT: &str = "ArchiveChain storage KEK v1";
+
+/// Contexte de dérivation de l'identifiant public d'une clé maîtresse
+const KEK_ID_CONTEXT: &str = "ArchiveChain storage KEK id v1";
+
+/// Clé maîtresse du nœud, qui enveloppe les clés de données
+#[derive(Clone)]
+pub struct KeyEncryptionKey {
+    key: [u8; KEY_SIZE],
+    id: Hash,
+}
+
+impl KeyEncryptionKey {
+    /// Crée une clé maîtresse depuis des octets bruts
+    pub fn from_bytes(key: [u8; KEY_SIZE]) -> Self {
+        let id = Hash::from_bytes_array(blake3::derive_key(KEK_ID_CONTEXT, &key));
+        Self { key, id }
+    }
+
+    /// Dérive une clé maîtresse du contenu d'un fichier de clé
+    ///
+    /// Le fichier peut être la clé privée du nœud ou une clé dédiée ; seule
+    /// la clé dérivée est conservée en mémoire.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let material = std::fs::read(path).map_err(|e| CoreError::Internal {
+            message: format!("Lecture de la clé maîtresse {} impossible: {}", path.display(), e),
+        })?;
+        if material.is_empty() {
+            return Err(CoreError::Validation {
+                message: format!("Fichier de clé maîtresse vide: {}", path.display()),
+            });
+        }
+
+        Ok(Self::from_bytes(blake3::derive_key(KEK_DERIVATION_CONTEXT, &material)))
+    }
+
+    /// Identifiant public de la clé, enregistré avec chaque clé enveloppée
+    pub fn id(&self) -> &Hash {
+        &self.id
+    }
+
+    /// Enveloppe la clé de données d'un contenu
+    pub fn wrap_key(&self, data_key: &DataKey, content_hash: &Hash) -> Result<WrappedKey> {
+        let nonce = random_nonce();
+        let ciphertext = seal(&self.key, &nonce, &data_key.0, content_hash.as_bytes())?;
+
+        Ok(WrappedKey {
+            kek_id: self.id.clone(),
+            nonce,
+            ciphertext,
+        })
+    }
+
+    /// Désenveloppe la clé de données d'un contenu
+    pub fn unwrap_key(&self, wrapped: &WrappedKey, content_hash: &Hash) -> Result<DataKey> {
+        if wrapped.kek_id != self.id {
+            return Err(CoreError::NotFound {
+                message: format!("Clé maîtresse {} requise pour {}", wrapped.kek_id.to_hex(), content_hash.to_hex()),
+            });
+        }
+
+        let key = open(&self.key, &wrapped.nonce, &wrapped.ciphertext, content_hash.as_bytes())?;
+        let key: [u8; KEY_SIZE] = key.try_into().map_err(|_| {
+            CryptoError::AuthenticationFailed(format!("clé de données invalide pour {}", content_hash.to_hex()))
+        })?;
+        Ok(DataKey(key))
+    }
+}
+
+impl std::fmt::Debug for KeyEncryptionKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("KeyEncryptionKey").field("id", &self.id).finish_non_exhaustive()
+    }
+}
+
+/// Algorithme chiffrant les chunks d'un contenu, enregistré dans son manifeste
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum ContentCipher {
+    /// Algorithme des manifestes qui n'en précisent pas
+    #[default]
+    XChaCha20Poly1305,
+    /// Algorithme des nouveaux contenus
+    Aes256Gcm,
+}
+
+impl ContentCipher {
+    /// Taille du nonce placé devant chaque chiffré
+    pub fn nonce_size(&self) -> usize {
+        match self {
+            ContentCipher::XChaCha20Poly1305 => NONCE_SIZE,
+            ContentCipher::Aes256Gcm => AES_GCM_NONCE_SIZE,
+        }
+    }
+}
+
+/// Clé de données propre à un contenu
+pub struct DataKey([u8; KEY_SIZE]);
+
+impl DataKey {
+    /// Tire une nouvelle clé de données
+    pub fn generate() -> Self {
+        let mut key = [0u8; KEY_SIZE];
+        rand::rngs::OsRng.fill_bytes(&mut key);
+        Self(key)
+    }
+
+    /// Chiffre un chunk ; `aad` lie le chiffré à sa position dans le contenu
+    ///
+    /// Le nonce aléatoire précède le chiffré.
+    pub fn encrypt(&self, cipher: ContentCipher, plaintext: &[u8], aad: &[u8]) -> Result<Vec<u8>> {
+        let mut sealed = vec![0u8; cipher.nonce_size()];
+        rand::rngs::OsRng.fill_bytes(&mut sealed);
+        let ciphertext = match cipher {
+            ContentCipher::XChaCha20Poly1305 => {
+                let nonce: [u8; NONCE_SIZE] = sealed.as_slice().try_into().expect("taille du nonce");
+                seal(&self.0, &nonce, plaintext, aad)?
+            }
+            ContentCipher::Aes256Gcm => seal_aes_gcm(&self.0, &sealed, plaintext, aad)?,
+        };
+        sealed.extend(ciphertext);
+        Ok(sealed)
+    }
+
+    /// Déchiffre un chunk produit par `encrypt` avec le même algorithme
+    ///
+    /// Un chiffré altéré échoue avec `CryptoError::AuthenticationFailed`.
+    pub fn decrypt(&self, cipher: ContentCipher, sealed: &[u8], aad: &[u8]) -> Result<Vec<u8>> {
+        if sealed.len() < cipher.nonce_size() {
+            return Err(CryptoError::AuthenticationFailed("chiffré tronqué".to_string()).into());
+        }
+        let (nonce, ciphertext) = sealed.split_at(cipher.nonce_size());
+        match cipher {
+            ContentCipher::XChaCha20Poly1305 => {
+                let nonce: [u8; NONCE_SIZE] = nonce.try_into().expect("longueur vérifiée");
+                open(&self.0, &nonce, ciphertext, aad)
+            }
+            ContentCipher::Aes256Gcm => open_aes_gcm(&self.0, nonce, ciphertext, aad),
+        }
+    }
+}
+
+/// Clé de données enveloppée, conservée dans le manifeste du contenu
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct WrappedKey {
+    /// Clé maîtresse ayant enveloppé la clé de données
+    pub kek_id: Hash,
+    /// Nonce de l'enveloppe
+    pub nonce: [u8; NONCE_SIZE],
+    /// Clé de données chiffrée
+    pub ciphertext: Vec<u8>,
+}
+
+fn random_nonce() -> [u8; NONCE_SIZE] {
+    let mut nonce = [0u8; NONCE_SIZE];
+    rand::rngs::OsRng.fill_bytes(&mut nonce);
+    nonce
+}
+
+fn seal(key: &[u8; KEY_SIZE], nonce: &[u8; NONCE_SIZE], plaintext: &[u8], aad: &[u8]) -> Result<Vec<u8>> {
+    XChaCha20Poly1305::new(key.into())
+        .encrypt(XNonce::from_slice(nonce), Payload { msg: plaintext, aad })
+        .map_err(|_| CoreError::Internal {
+            message: "Échec du chiffrement XChaCha20-Poly1305".to_string(),
+        })
+}
+
+fn open(key: &[u8; KEY_SIZE], nonce: &[u8; NONCE_SIZE], ciphertext: &[u8], aad: &[u8]) -> Result<Vec<u8>> {
+    XChaCha20Poly1305::new(key.into())
+        .decrypt(XNonce::from_slice(nonce), Payload { msg: ciphertext, aad })
+        .map_err(|_| CryptoError::AuthenticationFailed("données altérées ou clé incorrecte".to_string()).into())
+}
+
+fn seal_aes_gcm(key: &[u8; KEY_SIZE], nonce: &[u8], plaintext: &[u8], aad: &[u8]) -> Result<Vec<u8>> {
+    Aes256Gcm::new(key.into())
+        .encrypt(Nonce::from_slice(nonce), Payload { msg: plaintext, aad })
+        .map_err(|_| CoreError::Internal {
+            message: "Échec du chiffrement AES-256-GCM".to_string(),
+        })
+}
+
+fn open_aes_gcm(key: &[u8; KEY_SIZE], nonce: &[u8], ciphertext: &[u8], aad: &[u8]) -> Result<Vec<u8>> {
+    Aes256Gcm::new(key.into())
+        .decrypt(Nonce::from_slice(nonce), Payload { msg: ciphertext, aad })
+        .map_err(|_| CryptoError::AuthenticationFailed("données altérées ou clé incorrecte".to_string()).into())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_wrapped_key_is_bound_to_kek_and_content() {
+        let kek = KeyEncryptionKey::from_bytes([1; KEY_SIZE]);
+        let other = KeyEncryptionKey::from_bytes([2; KEY_SIZE]);
+        let content = Hash::from_bytes_array([3; 32]);
+        let data_key = DataKey::generate();
+
+        let wrapped = kek.wrap_key(&data_key, &content).unwrap();
+        let sealed = data_key.encrypt(ContentCipher::Aes256Gcm, b"chunk", b"aad").unwrap();
+        assert_eq!(kek.unwrap_key(&wrapped, &content).unwrap().decrypt(ContentCipher::Aes256Gcm, &sealed, b"aad").unwrap(), b"chunk");
+
+        assert!(matches!(other.unwrap_key(&wrapped, &content), Err(CoreError::NotFound { .. })));
+        assert!(matches!(
+            kek.unwrap_key(&wrapped, &Hash::zero()),
+            Err(CoreError::Crypto(CryptoError::AuthenticationFailed(_)))
+        ));
+        assert!(data_key.decrypt(ContentCipher::Aes256Gcm, &sealed, b"autre position").is_err());
+    }
+
+    #[test]
+    fn test_content_ciphers_are_not_interchangeable() {
+        let data_key = DataKey::generate();
+        for cipher in [ContentCipher::XChaCha20Poly1305, ContentCipher::Aes256Gcm] {
+            let sealed = data_key.encrypt(cipher, b"chunk", b"aad").unwrap();
+            assert_eq!(sealed.len(), cipher.nonce_size() + b"chunk".len() + 16);
+            assert_eq!(data_key.decrypt(cipher, &sealed, b"aad").unwrap(), b"chunk");
+        }
+
+        let sealed = data_key.encrypt(ContentCipher::Aes256Gcm, b"chunk", b"aad").unwrap();
+        assert!(data_key.decrypt(ContentCipher::XChaCha20Poly1305, &sealed, b"aad").is_err());
+    }
+}
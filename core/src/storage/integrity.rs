@@ -0,0 +1,194 @@
+//! Accès vérifié aux répliques des nœuds
+//!
+//! Un contenu peut se dégrader silencieusement après son stockage (bits
+//! inversés, fichier tronqué) : toute copie de réplique est donc re-hachée en
+//! Blake3 aux deux bouts et comparée au `content_hash` enregistré. La
+//! vérification périodique des répliques du nœud local fait partie de la passe
+//! d'intégrité du `StorageManager`.
+
+use async_trait::async_trait;
+use std::path::PathBuf;
+use crate::consensus::NodeId;
+use crate::crypto::{compute_blake3, Hash};
+use crate::error::{CoreError, Result};
+
+/// Accès aux répliques stockées sur les nœuds
+#[async_trait]
+pub trait ReplicaStore: Send + Sync {
+    /// Contenus dont le nœud détient une réplique
+    async fn stored_content(&self, node_id: &NodeId) -> Result<Vec<Hash>>;
+
+    /// Lit la réplique d'un contenu détenue par un nœud
+    async fn read_replica(&self, node_id: &NodeId, content_hash: &Hash) -> Result<Vec<u8>>;
+
+    /// Nœuds détenant une réplique du contenu
+    async fn holders(&self, content_hash: &Hash) -> Result<Vec<NodeId>>;
+
+    /// Remplace la réplique de `target` par une copie de celle de `source`
+    async fn replicate(&self, content_hash: &Hash, source: &NodeId, target: &NodeId) -> Result<()>;
+}
+
+/// Copie la réplique de `source` vers `target` en la vérifiant aux deux bouts
+///
+/// La réplique source est re-hachée avant la copie, puis la réplique écrite
+/// est relue sur `target` : la copie n'est réussie que si elle correspond à
+/// `content_hash`.
+pub async fn copy_verified(
+    replicas: &dyn ReplicaStore,
+    content_hash: &Hash,
+    source: &NodeId,
+    target: &NodeId,
+) -> Result<()> {
+    let data = replicas.read_replica(source, content_hash).await?;
+    if compute_blake3(&data) != *content_hash {
+        return Err(CoreError::Validation {
+            message: format!("Réplique source de {} corrompue sur {}", content_hash.to_hex(), source.hash().to_hex()),
+        });
+    }
+
+    replicas.replicate(content_hash, source, target).await?;
+
+    let copied = replicas.read_replica(target, content_hash).await?;
+    if compute_blake3(&copied) != *content_hash {
+        return Err(CoreError::Validation {
+            message: format!("Copie de {} invalide sur {}", content_hash.to_hex(), target.hash().to_hex()),
+        });
+    }
+    Ok(())
+}
+
+/// Nœuds de `candidates` dont la réplique relue correspond à `content_hash`
+///
+/// Une réplique absente ou illisible compte comme non vérifiée.
+pub async fn verified_holders(
+    replicas: &dyn ReplicaStore,
+    content_hash: &Hash,
+    candidates: &[NodeId],
+) -> Vec<NodeId> {
+    let mut verified = Vec::new();
+    for node_id in candidates {
+        match replicas.read_replica(node_id, content_hash).await {
+            Ok(data) if compute_blake3(&data) == *content_hash => verified.push(node_id.clone()),
+            Ok(_) => tracing::warn!("Réplique de {} corrompue sur {}", content_hash.to_hex(), node_id.hash().to_hex()),
+            Err(_) => {}
+        }
+    }
+    verified
+}
+
+/// Répliques stockées sur disque, un répertoire par nœud
+///
+/// La réplique d'un contenu détenue par un nœud est le fichier
+/// `<root>/<node_id>/<content_hash>`. Les écritures passent par un fichier
+/// temporaire renommé une fois complet.
+#[derive(Debug, Clone)]
+pub struct DiskReplicaStore {
+    root: PathBuf,
+}
+
+impl DiskReplicaStore {
+    /// Crée un magasin dont les répliques sont rangées sous `root`
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn node_dir(&self, node_id: &NodeId) -> PathBuf {
+        self.root.join(node_id.hash().to_hex())
+    }
+
+    fn replica_path(&self, node_id: &NodeId, content_hash: &Hash) -> PathBuf {
+        self.node_dir(node_id).join(content_hash.to_hex())
+    }
+
+    /// Écrit la réplique d'un contenu sur un nœud
+    pub async fn write_replica(&self, node_id: &NodeId, content_hash: &Hash, data: &[u8]) -> Result<()> {
+        let io_error = |e: std::io::Error| CoreError::Internal {
+            message: format!("Écriture de la réplique {} impossible: {}", content_hash.to_hex(), e),
+        };
+
+        tokio::fs::create_dir_all(self.node_dir(node_id)).await.map_err(io_error)?;
+        let path = self.replica_path(node_id, content_hash);
+        let tmp_path = path.with_extension("tmp");
+        tokio::fs::write(&tmp_path, data).await.map_err(io_error)?;
+        tokio::fs::rename(&tmp_path, &path).await.map_err(io_error)
+    }
+
+    /// Supprime la réplique d'un contenu sur un nœud
+    pub async fn remove_replica(&self, node_id: &NodeId, content_hash: &Hash) -> Result<()> {
+        match tokio::fs::remove_file(self.replica_path(node_id, content_hash)).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(CoreError::Internal {
+                message: format!("Suppression de la réplique {} impossible: {}", content_hash.to_hex(), e),
+            }),
+        }
+    }
+}
+
+#[async_trait]
+impl ReplicaStore for DiskReplicaStore {
+    async fn stored_content(&self, node_id: &NodeId) -> Result<Vec<Hash>> {
+        let mut entries = match tokio::fs::read_dir(self.node_dir(node_id)).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => {
+                return Err(CoreError::Internal {
+                    message: format!("Répertoire des répliques illisible: {}", e),
+                })
+            }
+        };
+
+        let mut contents = Vec::new();
+        while let Some(entry) = entries.next_entry().await.map_err(|e| CoreError::Internal {
+            message: format!("Répertoire des répliques illisible: {}", e),
+        })? {
+            // Les fichiers temporaires et étrangers n'ont pas un nom de hash valide
+            if let Some(content_hash) = entry.file_name().to_str().and_then(|name| Hash::from_hex(name).ok()) {
+                contents.push(content_hash);
+            }
+        }
+        Ok(contents)
+    }
+
+    async fn read_replica(&self, node_id: &NodeId, content_hash: &Hash) -> Result<Vec<u8>> {
+        tokio::fs::read(self.replica_path(node_id, content_hash)).await
+            .map_err(|e| match e.kind() {
+                std::io::ErrorKind::NotFound => CoreError::NotFound {
+                    message: format!("Réplique {} absente du nœud {}", content_hash.to_hex(), node_id.hash().to_hex()),
+                },
+                _ => CoreError::Internal {
+                    message: format!("Lecture de la réplique {} impossible: {}", content_hash.to_hex(), e),
+                },
+            })
+    }
+
+    async fn holders(&self, content_hash: &Hash) -> Result<Vec<NodeId>> {
+        let mut entries = match tokio::fs::read_dir(&self.root).await {
+            Ok(entries) => entries,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => {
+                return Err(CoreError::Internal {
+                    message: format!("Répertoire des répliques illisible: {}", e),
+                })
+            }
+        };
+
+        let mut holders = Vec::new();
+        while let Some(entry) = entries.next_entry().await.map_err(|e| CoreError::Internal {
+            message: format!("Répertoire des répliques illisible: {}", e),
+        })? {
+            let Some(node_hash) = entry.file_name().to_str().and_then(|name| Hash::from_hex(name).ok()) else {
+                continue;
+            };
+            if tokio::fs::try_exists(entry.path().join(content_hash.to_hex())).await.unwrap_or(false) {
+                holders.push(NodeId::from(node_hash));
+            }
+        }
+        Ok(holders)
+    }
+
+    async fn replicate(&self, content_hash: &Hash, source: &NodeId, target: &NodeId) -> Result<()> {
+        let data = self.read_replica(source, content_hash).await?;
+        self.write_replica(target, content_hash, &data).await
+    }
+}
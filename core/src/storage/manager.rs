@@ -5,20 +5,35 @@
 //! - Interface unifiée pour les opérations de stockage
 //! - Gestion des politiques et stratégies globales
 //! - Monitoring et optimisation automatique
+//! - Re-vérification périodique de l'intégrité du contenu stocké
+//! - Filtre de Bloom local pour tester l'existence d'un contenu sans accès disque
+//! - Routage du contenu vers les Light Storage Nodes spécialisés pour son type
+//! - Ré-réplication du contenu des nœuds hors ligne ou surchargés
+//! - Nettoyage local selon la `CleanupPolicy`, sans perte de redondance
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
-use tokio::sync::{RwLock, Mutex};
-use crate::crypto::Hash;
+use futures_util::StreamExt;
+use tokio::sync::{mpsc, RwLock, Mutex};
+use crate::crypto::{compute_blake3, Hash};
 use crate::consensus::NodeId;
-use crate::error::Result;
+use crate::error::{ContentError, Result};
+use crate::nodes::{CleanupPolicy, ContentFilter as SpecializationFilter};
 use super::{
+    ContentImportance,
     ContentMetadata, StorageNodeInfo, StorageResult, StorageStatus, AvailabilityInfo,
     DistributedStorage, NodeType, StorageType, ReplicationStrategy, StorageMetrics,
-    SearchQuery, SearchResults, ReplicationManager, DistributionManager, 
-    ContentDiscovery, ArchiveStorage, BandwidthManager,
+    SearchResults, ReplicationManager, DistributionManager, 
+    ContentDiscovery, ArchiveStorage, BandwidthManager, NodeStatus,
+    dedup::{ChunkStore, ChunkingConfig},
+    encryption::KeyEncryptionKey,
+    integrity::{copy_verified, verified_holders, DiskReplicaStore, ReplicaStore},
+    search::{extract_text, SearchDocument, SearchFilter, SearchIndex},
+    bloom::{BloomConfig, BloomStats, ContentFilter},
+    metrics::{ErrorType, MetricsConfig},
     // replication::{ReplicationManager, ReplicationConfig},
     // distribution::{DistributionManager, DistributionConfig},
     // discovery::{ContentDiscovery, DiscoveryConfig},
@@ -27,6 +42,9 @@ use super::{
     // metrics::{MetricsConfig},
 };
 
+/// Taille maximale par défaut d'un contenu archivé (100 MB)
+pub const DEFAULT_MAX_CONTENT_SIZE: u64 = 100 * 1024 * 1024;
+
 /// Configuration principale du gestionnaire de stockage
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StorageConfig {
@@ -48,6 +66,73 @@ pub struct StorageConfig {
     pub optimization_interval: Duration,
     /// Seuil de redondance critique
     pub critical_redundancy_threshold: u32,
+    /// Intervalle entre deux passes de vérification d'intégrité
+    #[serde(default = "default_integrity_scan_interval")]
+    pub integrity_scan_interval: Duration,
+    /// Débit de lecture maximal de la vérification d'intégrité (bytes/sec, 0 = illimité)
+    #[serde(default = "default_integrity_scan_throughput")]
+    pub integrity_scan_throughput: u64,
+    /// Part des répliques du nœud local re-hachées à chaque passe d'intégrité (0.0-1.0)
+    #[serde(default = "default_integrity_sample_rate")]
+    pub integrity_sample_rate: f64,
+    /// Filtre de Bloom des contenus stockés localement
+    #[serde(default)]
+    pub content_filter: BloomConfig,
+    /// Intervalle entre deux passes de rééquilibrage des répliques
+    #[serde(default = "default_rebalance_interval")]
+    pub rebalance_interval: Duration,
+    /// Nombre maximal de copies de rééquilibrage simultanées
+    #[serde(default = "default_max_concurrent_rebalance_jobs")]
+    pub max_concurrent_rebalance_jobs: usize,
+    /// Délai entre le constat qu'un contenu dépasse la politique de nettoyage et son éviction
+    #[serde(default = "default_cleanup_grace_period")]
+    pub cleanup_grace_period: Duration,
+    /// Intervalle entre deux passes de nettoyage du stockage local
+    #[serde(default = "default_cleanup_interval")]
+    pub cleanup_interval: Duration,
+    /// Taille maximale d'un contenu archivé (bytes), refusé au-delà
+    #[serde(default = "default_max_content_size")]
+    pub max_content_size: u64,
+    /// Répertoire des répliques par nœud (`DiskReplicaStore`) ; sans lui,
+    /// aucune copie de rééquilibrage n'est possible
+    #[serde(default)]
+    pub replica_path: Option<String>,
+    /// Répertoire des copies de chunks et des manifestes du stockage local ;
+    /// sans lui, le contenu ne vit qu'en mémoire et disparaît au redémarrage
+    #[serde(default)]
+    pub chunk_path: Option<String>,
+}
+
+fn default_integrity_scan_interval() -> Duration {
+    Duration::from_secs(24 * 3600) // 1 jour
+}
+
+fn default_integrity_scan_throughput() -> u64 {
+    10 * 1024 * 1024 // 10 MB/s
+}
+
+fn default_integrity_sample_rate() -> f64 {
+    0.05 // Toutes les répliques relues en ~20 passes
+}
+
+fn default_rebalance_interval() -> Duration {
+    Duration::from_secs(300) // 5 minutes
+}
+
+fn default_max_concurrent_rebalance_jobs() -> usize {
+    4
+}
+
+fn default_cleanup_grace_period() -> Duration {
+    Duration::from_secs(24 * 3600) // 1 jour
+}
+
+fn default_cleanup_interval() -> Duration {
+    Duration::from_secs(3600) // 1 heure
+}
+
+fn default_max_content_size() -> u64 {
+    DEFAULT_MAX_CONTENT_SIZE
 }
 
 impl Default for StorageConfig {
@@ -62,6 +147,17 @@ impl Default for StorageConfig {
             node_sync_interval: Duration::from_secs(60), // 1 minute
             optimization_interval: Duration::from_secs(3600), // 1 heure
             critical_redundancy_threshold: 2, // Moins de 2 répliques = critique
+            integrity_scan_interval: default_integrity_scan_interval(),
+            integrity_scan_throughput: default_integrity_scan_throughput(),
+            integrity_sample_rate: default_integrity_sample_rate(),
+            content_filter: BloomConfig::default(),
+            rebalance_interval: default_rebalance_interval(),
+            max_concurrent_rebalance_jobs: default_max_concurrent_rebalance_jobs(),
+            cleanup_grace_period: default_cleanup_grace_period(),
+            cleanup_interval: default_cleanup_interval(),
+            max_content_size: default_max_content_size(),
+            replica_path: None,
+            chunk_path: None,
         }
     }
 }
@@ -87,6 +183,20 @@ pub struct StorageStats {
     pub searches_per_hour: u64,
     /// Contenu le plus populaire
     pub top_content: Vec<(Hash, u64)>,
+    /// Ratio de déduplication (taille logique / taille physique)
+    pub dedup_ratio: f64,
+    /// Octets économisés par la déduplication
+    pub bytes_saved: u64,
+    /// Copies corrompues détectées par la vérification d'intégrité
+    pub corruptions_detected: u64,
+    /// Copies corrompues restaurées depuis une copie saine
+    pub corruptions_repaired: u64,
+    /// Part des contenus routables placés sur un nœud spécialisé (0.0-1.0)
+    pub specialization_hit_rate: f64,
+    /// Contenus dont les répliques saines sont sous le minimum de leur stratégie
+    pub under_replicated_count: u64,
+    /// Copies de rééquilibrage en cours
+    pub rebalance_jobs_active: u32,
 }
 
 /// Politique de stockage
@@ -174,6 +284,30 @@ pub struct StorageManager {
     available_nodes: Arc<RwLock<HashMap<NodeId, StorageNodeInfo>>>,
     /// Cache des métadonnées de contenu
     content_metadata_cache: Arc<RwLock<HashMap<Hash, ContentMetadata>>>,
+    /// Magasin de chunks dédupliqués
+    chunk_store: Arc<Mutex<ChunkStore>>,
+    /// Index plein texte des contenus stockés
+    search_index: Arc<RwLock<SearchIndex<Hash>>>,
+    /// Cumul des passes de vérification d'intégrité
+    integrity_totals: Arc<Mutex<IntegrityScanReport>>,
+    /// Répliques locales corrompues et fenêtre d'échantillonnage
+    replica_scan: Arc<Mutex<ReplicaScanState>>,
+    /// Compteurs d'erreurs et alertes de la vérification d'intégrité
+    integrity_metrics: Arc<super::metrics::StorageMetrics>,
+    /// Filtre de Bloom des contenus stockés (verrou synchrone : lectures très courtes)
+    content_filter: Arc<std::sync::RwLock<ContentFilter>>,
+    /// Nœuds ayant refusé un contenu (hors spécialisation)
+    declined_placements: Arc<RwLock<HashMap<Hash, HashSet<NodeId>>>>,
+    /// Filtres de spécialisation des Light Storage Nodes
+    node_specializations: Arc<RwLock<HashMap<NodeId, SpecializationFilter>>>,
+    /// Compteurs du routage par spécialisation
+    specialization_stats: Arc<Mutex<SpecializationStats>>,
+    /// Copies de rééquilibrage en cours
+    rebalance_jobs_active: Arc<AtomicUsize>,
+    /// Contenus au-delà de la politique de nettoyage, avec la date du premier constat
+    cleanup_candidates: Arc<Mutex<HashMap<Hash, SystemTime>>>,
+    /// Journal des contenus évincés par le nettoyage
+    cleanup_audit: Arc<Mutex<VecDeque<CleanupAuditEntry>>>,
     /// Dernière optimisation
     last_optimization: Mutex<SystemTime>,
 }
@@ -193,9 +327,11 @@ impl StorageManager {
             ContentDiscovery::new(config.discovery.clone())
         ));
         
-        let archive_storage = Arc::new(Mutex::new(
-            ArchiveStorage::new(config.archive.clone())?
-        ));
+        let mut archive_storage = ArchiveStorage::new(config.archive.clone())?;
+        if let Some(replica_path) = &config.replica_path {
+            archive_storage = archive_storage.with_replica_store(Arc::new(DiskReplicaStore::new(replica_path)));
+        }
+        let archive_storage = Arc::new(Mutex::new(archive_storage));
         
         let bandwidth_manager = Arc::new(Mutex::new(
             BandwidthManager::new(config.bandwidth.clone())
@@ -205,6 +341,13 @@ impl StorageManager {
             StorageMetrics::new(config.metrics.clone())
         ));
 
+        let mut chunk_store = ChunkStore::new(ChunkingConfig::default());
+        if let Some(chunk_path) = &config.chunk_path {
+            chunk_store = chunk_store.with_directory(chunk_path)?;
+        }
+        let mut content_filter = ContentFilter::new(config.content_filter.clone());
+        content_filter.rebuild(chunk_store.content_hashes());
+
         Ok(Self {
             config,
             policy,
@@ -216,10 +359,40 @@ impl StorageManager {
             metrics_system,
             available_nodes: Arc::new(RwLock::new(HashMap::new())),
             content_metadata_cache: Arc::new(RwLock::new(HashMap::new())),
+            chunk_store: Arc::new(Mutex::new(chunk_store)),
+            search_index: Arc::new(RwLock::new(SearchIndex::new())),
+            integrity_totals: Arc::new(Mutex::new(IntegrityScanReport::default())),
+            replica_scan: Arc::new(Mutex::new(ReplicaScanState::default())),
+            integrity_metrics: Arc::new(super::metrics::StorageMetrics::new(MetricsConfig::default())),
+            content_filter: Arc::new(std::sync::RwLock::new(content_filter)),
+            declined_placements: Arc::new(RwLock::new(HashMap::new())),
+            node_specializations: Arc::new(RwLock::new(HashMap::new())),
+            specialization_stats: Arc::new(Mutex::new(SpecializationStats::default())),
+            rebalance_jobs_active: Arc::new(AtomicUsize::new(0)),
+            cleanup_candidates: Arc::new(Mutex::new(HashMap::new())),
+            cleanup_audit: Arc::new(Mutex::new(VecDeque::new())),
             last_optimization: Mutex::new(SystemTime::now()),
         })
     }
 
+    /// Copie les répliques de rééquilibrage via `replicas`
+    pub fn with_replica_store(mut self, replicas: Arc<dyn ReplicaStore>) -> Self {
+        self.archive_storage = Arc::new(Mutex::new(ArchiveStorage {
+            replicas: Some(replicas),
+        }));
+        self
+    }
+
+    /// Accès aux répliques des nœuds, s'il est configuré
+    pub async fn replica_store(&self) -> Option<Arc<dyn ReplicaStore>> {
+        self.archive_storage.lock().await.replica_store()
+    }
+
+    /// Nœuds de stockage connus
+    pub async fn storage_nodes(&self) -> Vec<StorageNodeInfo> {
+        self.available_nodes.read().await.values().cloned().collect()
+    }
+
     /// Met à jour la liste des nœuds disponibles
     pub async fn update_node_info(&self, node_id: NodeId, node_info: StorageNodeInfo) -> Result<()> {
         // Met à jour le cache des nœuds
@@ -251,10 +424,41 @@ impl StorageManager {
         Ok(())
     }
 
-    /// Recherche du contenu
-    pub async fn search_content(&self, query: SearchQuery) -> Result<SearchResults> {
-        let discovery = self.discovery_system.lock().await;
-        discovery.search(&query)
+    /// Recherche plein texte dans les contenus stockés
+    ///
+    /// `query` accepte des termes libres et des passages entre guillemets (voir
+    /// `SearchIndex::search`). Les résultats sont classés par pertinence ;
+    /// `offset` et `limit` en sélectionnent une page, `total_count` compte
+    /// tous les contenus trouvés.
+    pub async fn search_content(
+        &self,
+        query: &str,
+        filter: &SearchFilter,
+        offset: usize,
+        limit: usize,
+    ) -> Result<SearchResults> {
+        let started = std::time::Instant::now();
+        let hits = self.search_index.read().await.search(query, filter);
+        let content_cache = self.content_metadata_cache.read().await;
+
+        let results = hits.iter()
+            .skip(offset)
+            .take(limit)
+            .filter_map(|hit| content_cache.get(&hit.id).cloned())
+            .collect();
+
+        Ok(SearchResults {
+            results,
+            total_count: hits.len(),
+            search_time_ms: started.elapsed().as_millis() as u64,
+        })
+    }
+
+    /// Indexe les métadonnées et le texte d'un contenu
+    async fn index_content(&self, content_hash: &Hash, data: &[u8], metadata: &ContentMetadata) {
+        let mut document = SearchDocument::from(metadata);
+        document.body = extract_text(&metadata.content_type, data);
+        self.search_index.write().await.upsert(content_hash.clone(), document);
     }
 
     /// Obtient les contenus populaires
@@ -454,6 +658,7 @@ impl StorageManager {
 #[async_trait::async_trait]
 #[async_trait::async_trait]
 impl DistributedStorage for StorageManager {
+    #[tracing::instrument(name = "storage_store", skip_all, fields(content_hash = %content_hash, size = data.len()))]
     async fn store_content(
         &mut self,
         content_hash: &Hash,
@@ -461,12 +666,20 @@ impl DistributedStorage for StorageManager {
         metadata: ContentMetadata,
     ) -> Result<StorageResult> {
         let start_time = SystemTime::now();
+
+        // Refuse les contenus hors limites avant toute écriture
+        let size = data.len() as u64;
+        if size > self.config.max_content_size {
+            return Err(ContentError::ContentTooLarge { size, limit: self.config.max_content_size }.into());
+        }
+        metadata.check_limits()?;
         
         // Met en cache les métadonnées
         {
             let mut cache = self.content_metadata_cache.write().await;
             cache.insert(*content_hash, metadata.clone());
         }
+        self.index_content(content_hash, data, &metadata).await;
 
         // Crée la stratégie de réplication
         let strategy = {
@@ -481,13 +694,47 @@ impl DistributedStorage for StorageManager {
         };
 
         // Calcule le nombre optimal de répliques
-        let target_replicas = strategy.calculate_optimal_replicas(metadata.popularity);
+        let mut target_replicas = strategy.calculate_optimal_replicas(metadata.popularity);
+
+        // Placement contraint par région pour les stratégies géographiques
+        let placement = match &strategy {
+            ReplicationStrategy::Geographic { max_copies, .. } => {
+                let placement = self.plan_geographic_placement(&metadata, &strategy).await;
+                target_replicas = target_replicas
+                    .max(placement.nodes.len() as u32)
+                    .min(*max_copies as u32);
+                Some(placement)
+            }
+            _ => None,
+        };
 
-        // Sélectionne les nœuds pour la réplication
-        let selected_nodes = {
-            let replication = self.replication_manager.lock().await;
-            replication.select_nodes_for_replication(content_hash, target_replicas)?
+        // Sélectionne les nœuds pour la réplication, spécialisés d'abord
+        let selected_nodes = match &placement {
+            Some(placement) => placement.nodes.clone(),
+            None => {
+                let specialized = self.plan_specialized_placement(&metadata, target_replicas).await;
+                let mut selected = specialized.nodes;
+                if selected.len() < target_replicas as usize {
+                    let replication = self.replication_manager.lock().await;
+                    for node in replication.select_nodes_for_replication(content_hash, target_replicas)? {
+                        if selected.len() >= target_replicas as usize {
+                            break;
+                        }
+                        if !selected.contains(&node) {
+                            selected.push(node);
+                        }
+                    }
+                }
+                selected
+            }
+        };
+
+        // Déduplication : seuls les chunks inédits sont écrits
+        let dedup = {
+            let mut chunk_store = self.chunk_store.lock().await;
+            chunk_store.store(*content_hash, data)?
         };
+        self.track_stored_content(content_hash).await;
 
         // Stocke le contenu avec compression/chiffrement
         let stored_nodes = {
@@ -508,7 +755,8 @@ impl DistributedStorage for StorageManager {
         }
 
         let storage_time = start_time.elapsed().unwrap_or(Duration::ZERO);
-        let status = if stored_nodes.len() >= target_replicas as usize {
+        let regions = self.get_regions_for_nodes(&stored_nodes).await;
+        let mut status = if stored_nodes.len() >= target_replicas as usize {
             StorageStatus::Success
         } else if stored_nodes.len() > 0 {
             StorageStatus::Partial
@@ -516,16 +764,28 @@ impl DistributedStorage for StorageManager {
             StorageStatus::Failed
         };
 
+        // Chaque région requise doit porter au moins une réplique (disaster recovery),
+        // ainsi que chaque région préférée du contenu pour un placement géographique
+        if status == StorageStatus::Success {
+            let required = strategy.required_regions();
+            let preferred: &[String] = if placement.is_some() { &metadata.preferred_regions } else { &[] };
+            if !required.iter().chain(preferred).all(|region| regions.contains(region)) {
+                status = StorageStatus::Partial;
+            }
+        }
+
         Ok(StorageResult {
             content_hash: *content_hash,
-            stored_nodes,
             replica_count: stored_nodes.len() as u32,
+            total_size_stored: dedup.bytes_written * stored_nodes.len() as u64,
+            stored_nodes,
             storage_time,
-            total_size_stored: data.len() as u64 * stored_nodes.len() as u64,
+            regions,
             status,
         })
     }
 
+    #[tracing::instrument(name = "storage_retrieve", skip_all, fields(content_hash = %content_hash))]
     async fn retrieve_content(&self, content_hash: &Hash) -> Result<Vec<u8>> {
         // Enregistre l'accès pour la popularité
         {
@@ -533,6 +793,17 @@ impl DistributedStorage for StorageManager {
             discovery.record_content_access(*content_hash);
         }
 
+        // Reconstitution depuis le manifeste de chunks si disponible
+        let reassembled = {
+            let chunk_store = self.chunk_store.lock().await;
+            chunk_store.retrieve(content_hash)?
+        };
+        if let Some(data) = reassembled {
+            let mut metrics = self.metrics_system.lock().await;
+            metrics.record_retrieval_operation(data.len() as u64);
+            return Ok(data);
+        }
+
         // Trouve les nœuds disponibles
         let availability = self.check_availability(content_hash).await?;
         
@@ -609,11 +880,23 @@ impl DistributedStorage for StorageManager {
             availability_rate: 0.0,
             searches_per_hour: 0,
             top_content: Vec::new(),
+            dedup_ratio: 1.0,
+            bytes_saved: 0,
+            corruptions_detected: 0,
+            corruptions_repaired: 0,
+            specialization_hit_rate: 0.0,
+            under_replicated_count: 0,
+            rebalance_jobs_active: 0,
         }
     }
 }
 
 impl StorageManager {
+    /// Politique de stockage appliquée
+    pub fn policy(&self) -> &StoragePolicy {
+        &self.policy
+    }
+
     /// Version async des statistiques de stockage
     pub async fn get_storage_stats(&self) -> Result<StorageStats> {
         let nodes = self.available_nodes.read().await;
@@ -642,11 +925,15 @@ impl StorageManager {
         };
 
         let total_content_count = content_cache.len() as u64;
+        let dedup_stats = self.chunk_store.lock().await.stats();
+        let integrity = self.integrity_totals.lock().await.clone();
+        let specialization = self.specialization_stats.lock().await.clone();
         let popular_hashes = discovery.get_popular_content(10);
         let top_content: Vec<(Hash, u64)> = popular_hashes.into_iter()
             .enumerate()
             .map(|(i, hash)| (hash, (100 - i as u64).max(1)))
             .collect();
+        let under_replicated_count = Rebalancer::find_deficits(&nodes, &content_cache, &discovery).len() as u64;
 
         Ok(StorageStats {
             total_content_count,
@@ -658,9 +945,627 @@ impl StorageManager {
             availability_rate: 99.0, // À calculer selon la disponibilité réelle
             searches_per_hour: 0, // À tracker dans les métriques
             top_content,
+            dedup_ratio: dedup_stats.dedup_ratio,
+            bytes_saved: dedup_stats.bytes_saved,
+            corruptions_detected: integrity.corruptions_detected,
+            corruptions_repaired: integrity.corruptions_repaired,
+            specialization_hit_rate: specialization.hit_rate(),
+            under_replicated_count,
+            rebalance_jobs_active: self.rebalance_jobs_active.load(Ordering::Relaxed) as u32,
+        })
+    }
+
+    /// Exécute une passe complète de vérification d'intégrité
+    ///
+    /// La passe relit tous les chunks dédupliqués puis une fenêtre des
+    /// répliques de `local_node` (voir `integrity_sample_rate`).
+    pub async fn run_integrity_scan(&self, local_node: &NodeId) -> IntegrityScanReport {
+        self.integrity_scanner(local_node.clone()).run().await
+    }
+
+    /// Lance la tâche périodique de vérification d'intégrité
+    pub fn start_integrity_scan_task(&self, local_node: NodeId) -> tokio::task::JoinHandle<()> {
+        let scanner = self.integrity_scanner(local_node);
+        let scan_interval = self.config.integrity_scan_interval;
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(scan_interval);
+            loop {
+                interval.tick().await;
+                let report = scanner.run().await;
+                if report.corruptions_detected > 0 {
+                    tracing::warn!(
+                        "Intégrité du nœud {}: {} copie(s) corrompue(s), {} restaurée(s), {} chunk(s) et {} réplique(s) irrécupérable(s)",
+                        scanner.local_node.hash().to_hex(), report.corruptions_detected, report.corruptions_repaired,
+                        report.unrecoverable_chunks, report.unrecoverable_replicas
+                    );
+                }
+            }
+        })
+    }
+
+    /// Contenus dont la réplique locale est corrompue et pas encore restaurée
+    pub async fn corrupt_replicas(&self) -> Vec<Hash> {
+        self.replica_scan.lock().await.corrupt.iter().cloned().collect()
+    }
+
+    /// Compteurs d'erreurs et alertes levées par la vérification d'intégrité
+    pub fn integrity_metrics(&self) -> Arc<super::metrics::StorageMetrics> {
+        self.integrity_metrics.clone()
+    }
+
+    fn integrity_scanner(&self, local_node: NodeId) -> IntegrityScanner {
+        IntegrityScanner {
+            local_node,
+            chunk_store: self.chunk_store.clone(),
+            archive_storage: self.archive_storage.clone(),
+            replica_scan: self.replica_scan.clone(),
+            metrics: self.integrity_metrics.clone(),
+            totals: self.integrity_totals.clone(),
+            throughput: self.config.integrity_scan_throughput,
+            sample_rate: self.config.integrity_sample_rate.clamp(0.0, 1.0),
+        }
+    }
+
+    /// Supprime un contenu du stockage dédupliqué
+    ///
+    /// Les chunks encore référencés par d'autres contenus sont conservés ;
+    /// retourne le nombre d'octets libérés.
+    pub async fn delete_content(&self, content_hash: &Hash) -> Result<u64> {
+        let freed = {
+            let mut chunk_store = self.chunk_store.lock().await;
+            if chunk_store.manifest(content_hash).is_none() {
+                return Err(crate::error::CoreError::NotFound {
+                    message: format!("Contenu {:?} non trouvé", content_hash),
+                });
+            }
+            chunk_store.release(content_hash)
+        };
+
+        self.content_metadata_cache.write().await.remove(content_hash);
+        self.search_index.write().await.remove(content_hash);
+
+        let needs_rebuild = self.content_filter.write().unwrap().record_removal();
+        if needs_rebuild {
+            self.rebuild_content_filter().await;
+        }
+
+        Ok(freed)
+    }
+
+    /// Applique la politique de nettoyage au stockage local de `local_node`
+    ///
+    /// Un contenu n'est évincé que s'il dépasse la politique (âge, taille
+    /// totale ou rang LRU), qu'il n'est pas `ContentImportance::Critical` et
+    /// que ses répliques saines sur d'autres nœuds atteignent le minimum de sa
+    /// stratégie. Il doit de plus être resté au-delà de la politique pendant
+    /// `cleanup_grace_period` : le délai repart de zéro dès qu'une passe ne le
+    /// retient plus. Les détenteurs annoncés dans la DHT ne suffisent pas :
+    /// avant l'éviction, leurs répliques sont relues et re-hachées, et sans
+    /// magasin de répliques configuré rien n'est évincé. Chaque éviction est
+    /// ajoutée au journal d'audit.
+    pub async fn enforce_cleanup_policy(
+        &self,
+        local_node: &NodeId,
+        policy: &CleanupPolicy,
+        now: SystemTime,
+    ) -> Result<CleanupReport> {
+        let mut report = CleanupReport::default();
+        let selected = {
+            let nodes = self.available_nodes.read().await;
+            let content_cache = self.content_metadata_cache.read().await;
+            let discovery = self.discovery_system.lock().await;
+            let chunk_store = self.chunk_store.lock().await;
+
+            let mut candidates: Vec<CleanupCandidate> = content_cache.values()
+                .filter(|metadata| chunk_store.manifest(&metadata.content_hash).is_some())
+                .map(|metadata| {
+                    let created_at = UNIX_EPOCH + Duration::from_secs(metadata.created_at.timestamp().max(0) as u64);
+                    let holders_elsewhere: Vec<NodeId> = discovery.storage_nodes(&metadata.content_hash).into_iter()
+                        .filter(|node| node != local_node)
+                        .filter(|node| nodes.get(node).is_some_and(|info| info.status == NodeStatus::Active))
+                        .collect();
+                    CleanupCandidate {
+                        content_hash: metadata.content_hash,
+                        size: metadata.size,
+                        critical: metadata.importance == ContentImportance::Critical,
+                        created_at,
+                        last_used: discovery.last_access(&metadata.content_hash).unwrap_or(created_at),
+                        replicas_elsewhere: holders_elsewhere.len() as u32,
+                        holders_elsewhere,
+                        min_replicas: ReplicationStrategy::from_metadata(metadata).min_replicas() as u32,
+                    }
+                })
+                .collect();
+            // Les moins récemment utilisés d'abord
+            candidates.sort_by_key(|candidate| candidate.last_used);
+
+            Self::select_for_cleanup(candidates, policy, now, &mut report)
+        };
+
+        let mut due = Vec::new();
+        {
+            let mut flagged = self.cleanup_candidates.lock().await;
+            flagged.retain(|content_hash, _| selected.iter().any(|(candidate, _)| candidate.content_hash == *content_hash));
+            for (candidate, reason) in selected {
+                let flagged_at = *flagged.entry(candidate.content_hash).or_insert(now);
+                if now.duration_since(flagged_at).unwrap_or(Duration::ZERO) >= self.config.cleanup_grace_period {
+                    flagged.remove(&candidate.content_hash);
+                    due.push((candidate, reason, flagged_at));
+                } else {
+                    report.pending_grace += 1;
+                }
+            }
+        }
+
+        let replicas = self.archive_storage.lock().await.replica_store();
+        for (candidate, reason, flagged_at) in due {
+            // Les détenteurs DHT sont déclaratifs : seules les répliques relues comptent
+            let verified = match &replicas {
+                Some(replicas) => {
+                    verified_holders(replicas.as_ref(), &candidate.content_hash, &candidate.holders_elsewhere).await.len() as u32
+                }
+                None => 0,
+            };
+            if verified < candidate.min_replicas {
+                tracing::warn!(
+                    "Nettoyage: {:?} conservé, {}/{} réplique(s) vérifiée(s) ailleurs",
+                    candidate.content_hash, verified, candidate.min_replicas
+                );
+                report.unverified_replicas += 1;
+                continue;
+            }
+
+            let freed = match self.delete_content(&candidate.content_hash).await {
+                Ok(freed) => freed,
+                Err(e) => {
+                    tracing::warn!("Éviction de {:?} impossible: {}", candidate.content_hash, e);
+                    continue;
+                }
+            };
+            self.discovery_system.lock().await.remove_content_replica(&candidate.content_hash, local_node);
+
+            tracing::info!(
+                "Nettoyage: {:?} évincé ({:?}), {} réplique(s) vérifiée(s) ailleurs",
+                candidate.content_hash, reason, verified
+            );
+            let entry = CleanupAuditEntry {
+                content_hash: candidate.content_hash,
+                size: candidate.size,
+                reason,
+                replicas_elsewhere: verified,
+                flagged_at,
+                evicted_at: now,
+            };
+            report.bytes_freed += freed;
+            report.evicted.push(entry.clone());
+
+            let mut audit = self.cleanup_audit.lock().await;
+            if audit.len() >= CLEANUP_AUDIT_CAPACITY {
+                audit.pop_front();
+            }
+            audit.push_back(entry);
+        }
+
+        Ok(report)
+    }
+
+    /// Lance la tâche périodique de nettoyage du stockage local de `local_node`
+    ///
+    /// Une passe est exécutée à chaque `cleanup_interval` ; aucune tâche n'est
+    /// lancée pour `CleanupPolicy::None`.
+    pub fn start_cleanup_task(
+        manager: Arc<Mutex<Self>>,
+        local_node: NodeId,
+        policy: CleanupPolicy,
+    ) -> Option<tokio::task::JoinHandle<()>> {
+        if matches!(policy, CleanupPolicy::None) {
+            return None;
+        }
+
+        Some(tokio::spawn(async move {
+            let cleanup_interval = manager.lock().await.config.cleanup_interval;
+            let mut interval = tokio::time::interval(cleanup_interval);
+            loop {
+                interval.tick().await;
+                let result = manager.lock().await
+                    .enforce_cleanup_policy(&local_node, &policy, SystemTime::now())
+                    .await;
+                match result {
+                    Ok(report) if !report.evicted.is_empty() || report.unverified_replicas > 0 => tracing::info!(
+                        "Nettoyage : {} contenu(s) évincé(s), {} octet(s) libéré(s), {} conservé(s) faute de répliques vérifiées",
+                        report.evicted.len(), report.bytes_freed, report.unverified_replicas
+                    ),
+                    Ok(_) => {}
+                    Err(e) => tracing::warn!("Passe de nettoyage échouée: {}", e),
+                }
+            }
+        }))
+    }
+
+    /// Journal des évictions, de la plus ancienne à la plus récente
+    pub async fn cleanup_audit(&self) -> Vec<CleanupAuditEntry> {
+        self.cleanup_audit.lock().await.iter().cloned().collect()
+    }
+
+    /// Contenus au-delà de la politique et évinçables, avec leur motif
+    ///
+    /// `candidates` est trié du moins au plus récemment utilisé : les
+    /// politiques de taille et LRU retiennent les premiers évinçables.
+    fn select_for_cleanup(
+        candidates: Vec<CleanupCandidate>,
+        policy: &CleanupPolicy,
+        now: SystemTime,
+        report: &mut CleanupReport,
+    ) -> Vec<(CleanupCandidate, CleanupReason)> {
+        let mut selected = Vec::new();
+        match policy {
+            CleanupPolicy::None => {}
+            CleanupPolicy::Age { max_age } => {
+                for candidate in candidates {
+                    let age = now.duration_since(candidate.created_at).unwrap_or(Duration::ZERO);
+                    if age > *max_age && candidate.evictable(report) {
+                        selected.push((candidate, CleanupReason::Age { age, max_age: *max_age }));
+                    }
+                }
+            }
+            CleanupPolicy::Size { max_size } => {
+                let used: u64 = candidates.iter().map(|candidate| candidate.size).sum();
+                let mut remaining = used;
+                for candidate in candidates {
+                    if remaining <= *max_size {
+                        break;
+                    }
+                    if candidate.evictable(report) {
+                        remaining = remaining.saturating_sub(candidate.size);
+                        selected.push((candidate, CleanupReason::Size { used, max_size: *max_size }));
+                    }
+                }
+            }
+            CleanupPolicy::LeastRecentlyUsed { max_items } => {
+                let item_count = candidates.len() as u64;
+                let excess = item_count.saturating_sub(*max_items) as usize;
+                for candidate in candidates {
+                    if selected.len() >= excess {
+                        break;
+                    }
+                    if candidate.evictable(report) {
+                        selected.push((candidate, CleanupReason::LeastRecentlyUsed { item_count, max_items: *max_items }));
+                    }
+                }
+            }
+        }
+        selected
+    }
+
+    /// Active le chiffrement au repos des nouveaux contenus
+    ///
+    /// Si le chiffrement était déjà actif, les clés existantes sont
+    /// ré-enveloppées avec `kek`.
+    pub async fn enable_encryption(&self, kek: KeyEncryptionKey) -> Result<()> {
+        self.rewrap_keys(kek).await.map(|_| ())
+    }
+
+    /// Change la clé maîtresse du stockage sans rechiffrer les chunks
+    ///
+    /// Retourne le nombre de clés de données ré-enveloppées.
+    pub async fn rewrap_keys(&self, new_kek: KeyEncryptionKey) -> Result<usize> {
+        let rewrapped = self.chunk_store.lock().await.rewrap_keys(new_kek)?;
+        tracing::info!("Clé maîtresse du stockage renouvelée ({} clés ré-enveloppées)", rewrapped);
+        Ok(rewrapped)
+    }
+
+    /// Indique si ce nœud détient probablement un contenu
+    ///
+    /// `false` est certain ; `true` peut être un faux positif, au taux
+    /// configuré dans `StorageConfig::content_filter`. À consulter avant une
+    /// recherche DHT ou une lecture disque.
+    pub fn might_contain(&self, content_hash: &Hash) -> bool {
+        self.content_filter.read().unwrap().might_contain(content_hash)
+    }
+
+    /// Statistiques du filtre de contenus
+    pub fn content_filter_stats(&self) -> BloomStats {
+        self.content_filter.read().unwrap().stats()
+    }
+
+    /// Reconstruit le filtre de contenus depuis le magasin de chunks
+    pub async fn rebuild_content_filter(&self) {
+        Self::rebuild_filter_from(&self.chunk_store, &self.content_filter).await;
+    }
+
+    /// Lance la reconstruction périodique du filtre de contenus
+    pub fn start_content_filter_task(&self) -> tokio::task::JoinHandle<()> {
+        let chunk_store = self.chunk_store.clone();
+        let content_filter = self.content_filter.clone();
+        let rebuild_interval = self.config.content_filter.rebuild_interval;
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(rebuild_interval);
+            loop {
+                interval.tick().await;
+                Self::rebuild_filter_from(&chunk_store, &content_filter).await;
+            }
+        })
+    }
+
+    /// Ajoute un contenu stocké au filtre, reconstruit s'il est saturé
+    async fn track_stored_content(&self, content_hash: &Hash) {
+        let needs_rebuild = self.content_filter.write().unwrap().record_insert(content_hash);
+        if needs_rebuild {
+            self.rebuild_content_filter().await;
+        }
+    }
+
+    async fn rebuild_filter_from(chunk_store: &Mutex<ChunkStore>, content_filter: &std::sync::RwLock<ContentFilter>) {
+        let chunk_store = chunk_store.lock().await;
+        content_filter.write().unwrap().rebuild(chunk_store.content_hashes());
+    }
+
+    /// Replanifie les répliques hébergées par un nœud défaillant
+    ///
+    /// Le nœud est marqué `Failed` puis retiré de la DHT ; pour chaque contenu
+    /// qu'il stockait, la stratégie de réplication du contenu choisit un nœud de
+    /// remplacement qui ne détient pas encore de copie. Retourne le nombre de
+    /// répliques replanifiées.
+    pub async fn reschedule_node_content(&self, failed_node: &NodeId) -> Result<u32> {
+        let nodes = {
+            let mut nodes = self.available_nodes.write().await;
+            if let Some(info) = nodes.get_mut(failed_node) {
+                info.status = NodeStatus::Failed;
+            }
+            nodes.clone()
+        };
+
+        let mut discovery = self.discovery_system.lock().await;
+        let affected = discovery.remove_storage_node(failed_node);
+        if affected.is_empty() {
+            return Ok(0);
+        }
+
+        let mut replication = self.replication_manager.lock().await;
+        replication.update_available_nodes(nodes);
+
+        let content_cache = self.content_metadata_cache.read().await;
+        let mut rescheduled = 0;
+        for content_hash in affected {
+            if replication.get_strategy(&content_hash).is_none() {
+                if let Some(metadata) = content_cache.get(&content_hash) {
+                    replication.create_strategy(content_hash, metadata)?;
+                }
+            }
+
+            let holders = discovery.storage_nodes(&content_hash);
+            let candidates = match replication.select_nodes_for_replication(&content_hash, holders.len() as u32 + 1) {
+                Ok(candidates) => candidates,
+                Err(e) => {
+                    tracing::warn!("Replanification impossible pour {:?}: {}", content_hash, e);
+                    continue;
+                }
+            };
+
+            match candidates.into_iter().find(|node| !holders.contains(node)) {
+                Some(replacement) => {
+                    discovery.add_storage_node(&content_hash, replacement);
+                    rescheduled += 1;
+                }
+                None => tracing::warn!("Aucun nœud de remplacement pour {:?}", content_hash),
+            }
+        }
+
+        Ok(rescheduled)
+    }
+
+    /// Met à jour le statut d'un nœud
+    ///
+    /// Retourne `true` si le nœud cesse de compter comme réplique saine
+    /// (hors ligne, surchargé ou défaillant) : ses contenus doivent être
+    /// rééquilibrés.
+    pub async fn update_node_status(&self, node_id: &NodeId, status: NodeStatus) -> bool {
+        self.rebalancer().apply_status_change(node_id, status).await
+    }
+
+    /// Re-réplique ailleurs les contenus d'un nœud avant son arrêt planifié
+    ///
+    /// Le nœud passe en `Maintenance` et ne reçoit plus de nouvelles
+    /// répliques ; les contenus qui tomberaient sous leur minimum de répliques
+    /// saines sans lui sont copiés depuis ce nœud vers d'autres nœuds. Dans le
+    /// rapport, `unrecoverable` compte les contenus pour lesquels il manque des
+    /// nœuds cibles : arrêter le nœud réduirait leur redondance.
+    pub async fn evacuate_node(&self, node_id: &NodeId) -> RebalanceReport {
+        self.rebalancer().evacuate(node_id).await
+    }
+
+    /// Exécute une passe de rééquilibrage des répliques
+    ///
+    /// Chaque contenu dont les répliques saines (nœuds `Active`) sont sous le
+    /// minimum de sa stratégie reçoit des copies vers des nœuds disponibles,
+    /// choisis par `performance_score` en privilégiant les régions qui n'ont
+    /// pas encore de réplique. Au plus `max_concurrent_rebalance_jobs` copies
+    /// sont en cours à la fois.
+    pub async fn rebalance(&self) -> RebalanceReport {
+        self.rebalancer().run().await
+    }
+
+    /// Lance la boucle de rééquilibrage
+    ///
+    /// Une passe est exécutée à chaque `rebalance_interval` et dès qu'un
+    /// changement de statut reçu du moniteur de santé ou du registre des nœuds
+    /// fait perdre une réplique saine. La boucle s'arrête quand l'émetteur des
+    /// changements de statut est fermé.
+    pub fn start_rebalance_task(
+        &self,
+        mut status_changes: mpsc::UnboundedReceiver<(NodeId, NodeStatus)>,
+    ) -> tokio::task::JoinHandle<()> {
+        let rebalancer = self.rebalancer();
+        let rebalance_interval = self.config.rebalance_interval;
+
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(rebalance_interval);
+            loop {
+                tokio::select! {
+                    _ = interval.tick() => {}
+                    change = status_changes.recv() => {
+                        let Some((node_id, status)) = change else {
+                            break;
+                        };
+                        if !rebalancer.apply_status_change(&node_id, status).await {
+                            continue;
+                        }
+                    }
+                }
+                let report = rebalancer.run().await;
+                if report.jobs_scheduled > 0 || report.unrecoverable > 0 {
+                    tracing::info!(
+                        "Rééquilibrage : {} contenu(s) sous-répliqué(s), {}/{} copie(s) réussie(s), {} irrécupérable(s)",
+                        report.under_replicated, report.jobs_completed, report.jobs_scheduled, report.unrecoverable
+                    );
+                }
+            }
         })
     }
 
+    /// Lance les tâches de fond du stockage local
+    ///
+    /// Vérification d'intégrité des chunks et des répliques de `local_node`,
+    /// reconstruction du filtre de contenus et rééquilibrage des répliques,
+    /// alimenté par `status_changes`. Les tâches tournent jusqu'à ce que
+    /// l'appelant les interrompe.
+    pub fn start_background_tasks(
+        &self,
+        local_node: NodeId,
+        status_changes: mpsc::UnboundedReceiver<(NodeId, NodeStatus)>,
+    ) -> Vec<tokio::task::JoinHandle<()>> {
+        vec![
+            self.start_integrity_scan_task(local_node),
+            self.start_content_filter_task(),
+            self.start_rebalance_task(status_changes),
+        ]
+    }
+
+    fn rebalancer(&self) -> Rebalancer {
+        Rebalancer {
+            available_nodes: self.available_nodes.clone(),
+            content_metadata_cache: self.content_metadata_cache.clone(),
+            discovery_system: self.discovery_system.clone(),
+            archive_storage: self.archive_storage.clone(),
+            jobs_active: self.rebalance_jobs_active.clone(),
+            max_concurrent_jobs: self.config.max_concurrent_rebalance_jobs.max(1),
+        }
+    }
+
+    /// Replace un contenu refusé par un nœud spécialisé
+    ///
+    /// Le nœud est retiré des détenteurs du contenu et mémorisé pour ne plus
+    /// lui être proposé ; un autre nœud choisi par la stratégie de réplication
+    /// le remplace. Retourne ce nœud, ou `None` si aucun candidat ne reste.
+    pub async fn handle_store_rejection(&self, content_hash: &Hash, rejecting_node: &NodeId) -> Result<Option<NodeId>> {
+        let declined = {
+            let mut declined_placements = self.declined_placements.write().await;
+            let declined = declined_placements.entry(*content_hash).or_default();
+            declined.insert(rejecting_node.clone());
+            declined.clone()
+        };
+
+        let mut discovery = self.discovery_system.lock().await;
+        discovery.remove_content_replica(content_hash, rejecting_node);
+
+        let mut replication = self.replication_manager.lock().await;
+        replication.update_available_nodes(self.available_nodes.read().await.clone());
+        if replication.get_strategy(content_hash).is_none() {
+            if let Some(metadata) = self.content_metadata_cache.read().await.get(content_hash) {
+                replication.create_strategy(*content_hash, metadata)?;
+            }
+        }
+
+        let holders = discovery.storage_nodes(content_hash);
+        let target = (holders.len() + declined.len() + 1) as u32;
+        let replacement = replication.select_nodes_for_replication(content_hash, target)?
+            .into_iter()
+            .find(|node| !holders.contains(node) && !declined.contains(node));
+
+        match &replacement {
+            Some(node) => {
+                discovery.add_storage_node(content_hash, node.clone());
+            }
+            None => tracing::warn!("Aucun nœud n'accepte le contenu {:?}", content_hash),
+        }
+
+        Ok(replacement)
+    }
+
+    /// Enregistre le filtre de spécialisation d'un Light Storage Node
+    pub async fn register_node_specialization(&self, node_id: NodeId, filter: SpecializationFilter) {
+        self.node_specializations.write().await.insert(node_id, filter);
+    }
+
+    /// Retire le filtre de spécialisation d'un nœud
+    pub async fn unregister_node_specialization(&self, node_id: &NodeId) {
+        self.node_specializations.write().await.remove(node_id);
+    }
+
+    /// Compteurs du routage par spécialisation
+    pub async fn specialization_stats(&self) -> SpecializationStats {
+        self.specialization_stats.lock().await.clone()
+    }
+
+    /// Planifie le placement d'un contenu selon les spécialisations et met à jour les compteurs
+    pub async fn plan_specialized_placement(&self, metadata: &ContentMetadata, target_replicas: u32) -> SpecializedPlacement {
+        let placement = {
+            let nodes = self.available_nodes.read().await;
+            let specializations = self.node_specializations.read().await;
+            Self::place_by_specialization(&nodes, &specializations, &metadata.content_type, target_replicas as usize)
+        };
+        self.specialization_stats.lock().await.record(&placement);
+        placement
+    }
+
+    /// Place un contenu sur les nœuds spécialisés pour son type MIME
+    ///
+    /// Les Light Storage Nodes dont le filtre cible le type du contenu sont
+    /// choisis en premier, par score de performance. S'ils ne suffisent pas
+    /// (ou n'ont plus de capacité), les Full Archive Nodes complètent le placement.
+    pub fn place_by_specialization(
+        nodes: &HashMap<NodeId, StorageNodeInfo>,
+        specializations: &HashMap<NodeId, SpecializationFilter>,
+        content_type: &str,
+        target: usize,
+    ) -> SpecializedPlacement {
+        let by_score = |a: &&StorageNodeInfo, b: &&StorageNodeInfo| {
+            b.performance_score()
+                .partial_cmp(&a.performance_score())
+                .unwrap_or(std::cmp::Ordering::Equal)
+        };
+
+        let mut placement = SpecializedPlacement {
+            routable: specializations.values().any(|filter| filter.targets_content_type(content_type)),
+            ..SpecializedPlacement::default()
+        };
+
+        let mut specialized: Vec<&StorageNodeInfo> = nodes.values()
+            .filter(|node| node.node_type == NodeType::LightStorage && node.is_available_for_storage())
+            .filter(|node| specializations.get(&node.node_id).map_or(false, |f| f.targets_content_type(content_type)))
+            .collect();
+        specialized.sort_by(by_score);
+        for node in specialized.into_iter().take(target) {
+            placement.nodes.push(node.node_id.clone());
+            placement.specialized_nodes += 1;
+        }
+
+        if placement.nodes.len() < target && placement.routable {
+            let mut general: Vec<&StorageNodeInfo> = nodes.values()
+                .filter(|node| node.node_type == NodeType::FullArchive && node.is_available_for_storage())
+                .collect();
+            general.sort_by(by_score);
+            for node in general.into_iter().take(target - placement.nodes.len()) {
+                placement.nodes.push(node.node_id.clone());
+                placement.fallback_nodes += 1;
+            }
+        }
+
+        placement
+    }
+
     /// Sélectionne le nœud optimal pour récupérer du contenu
     async fn select_optimal_retrieval_node(&self, available_nodes: &[NodeId]) -> Result<NodeId> {
         let nodes = self.available_nodes.read().await;
@@ -683,6 +1588,114 @@ impl StorageManager {
         })
     }
 
+    /// Planifie le placement des répliques selon une stratégie géographique
+    pub async fn plan_geographic_placement(
+        &self,
+        metadata: &ContentMetadata,
+        strategy: &ReplicationStrategy,
+    ) -> RegionPlacement {
+        let (min_copies, max_copies) = match strategy {
+            ReplicationStrategy::Geographic { min_copies, max_copies, .. } => (*min_copies, *max_copies),
+            other => (other.max_replicas(), other.max_replicas()),
+        };
+
+        // Régions de la stratégie d'abord, puis préférences du contenu
+        let mut requested_regions: Vec<String> = Vec::new();
+        for region in strategy.required_regions().iter().chain(metadata.preferred_regions.iter()) {
+            if !requested_regions.contains(region) {
+                requested_regions.push(region.clone());
+            }
+        }
+
+        let nodes = self.available_nodes.read().await;
+        Self::place_across_regions(&nodes, &requested_regions, min_copies, max_copies)
+    }
+
+    /// Répartit les répliques entre les régions demandées
+    ///
+    /// Chaque région demandée reçoit d'abord une réplique sur son meilleur nœud.
+    /// Une région sans capacité disponible est remplacée par la région la plus
+    /// proche et reportée dans `unsatisfied_regions`. Les répliques restantes
+    /// sont ensuite distribuées en alternance entre les régions demandées.
+    ///
+    /// Le nombre de répliques ne dépasse jamais `max_copies` : les régions
+    /// demandées au-delà restent sans réplique et sont reportées dans
+    /// `unsatisfied_regions`.
+    pub fn place_across_regions(
+        nodes: &HashMap<NodeId, StorageNodeInfo>,
+        requested_regions: &[String],
+        min_copies: u8,
+        max_copies: u8,
+    ) -> RegionPlacement {
+        // Nœuds disponibles groupés par région, meilleurs scores en premier
+        let mut by_region: HashMap<String, Vec<&StorageNodeInfo>> = HashMap::new();
+        for node in nodes.values().filter(|n| n.is_available_for_storage()) {
+            by_region.entry(node.region.clone()).or_default().push(node);
+        }
+        for candidates in by_region.values_mut() {
+            candidates.sort_by(|a, b| {
+                b.performance_score()
+                    .partial_cmp(&a.performance_score())
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            });
+        }
+
+        let target = (min_copies as usize)
+            .max(requested_regions.len())
+            .min(max_copies as usize);
+
+        let mut placement = RegionPlacement::default();
+
+        // Une réplique par région demandée, avec repli sur la région la plus proche
+        for region in requested_regions {
+            if placement.nodes.len() >= target {
+                placement.unsatisfied_regions.push(region.clone());
+                continue;
+            }
+            if take_best_in_region(&mut by_region, region, &mut placement) {
+                continue;
+            }
+
+            placement.unsatisfied_regions.push(region.clone());
+            let fallback = by_region
+                .iter()
+                .filter(|(_, candidates)| !candidates.is_empty())
+                .map(|(candidate, _)| candidate.clone())
+                .max_by_key(|candidate| (region_proximity(region, candidate), std::cmp::Reverse(candidate.clone())));
+            if let Some(fallback) = fallback {
+                take_best_in_region(&mut by_region, &fallback, &mut placement);
+            }
+        }
+
+        // Complète jusqu'à la cible en alternant entre les régions demandées
+        while placement.nodes.len() < target {
+            let mut placed = false;
+            for region in requested_regions {
+                if placement.nodes.len() < target && take_best_in_region(&mut by_region, region, &mut placement) {
+                    placed = true;
+                }
+            }
+            if placed {
+                continue;
+            }
+
+            // Plus de capacité dans les régions demandées : meilleur nœud restant
+            let best_remaining = by_region
+                .iter()
+                .filter_map(|(region, candidates)| candidates.first().map(|n| (region.clone(), n.performance_score())))
+                .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+                .map(|(region, _)| region);
+            match best_remaining {
+                Some(region) => {
+                    take_best_in_region(&mut by_region, &region, &mut placement);
+                }
+                None => break,
+            }
+        }
+
+        placement
+    }
+
     /// Obtient les régions pour une liste de nœuds
     async fn get_regions_for_nodes(&self, nodes: &[NodeId]) -> Vec<String> {
         let node_infos = self.available_nodes.read().await;
@@ -695,13 +1708,632 @@ impl StorageManager {
     }
 }
 
-/// Rapport d'optimisation
-#[derive(Debug, Default, Clone, Serialize, Deserialize)]
-pub struct OptimizationReport {
-    /// Améliorations de distribution identifiées
-    pub distribution_improvements: u32,
-    /// Mises à jour de stratégies de réplication
-    pub replication_updates: u32,
+/// Rééquilibrage des répliques, partagé avec la tâche de fond
+#[derive(Clone)]
+struct Rebalancer {
+    available_nodes: Arc<RwLock<HashMap<NodeId, StorageNodeInfo>>>,
+    content_metadata_cache: Arc<RwLock<HashMap<Hash, ContentMetadata>>>,
+    discovery_system: Arc<Mutex<ContentDiscovery>>,
+    archive_storage: Arc<Mutex<ArchiveStorage>>,
+    jobs_active: Arc<AtomicUsize>,
+    max_concurrent_jobs: usize,
+}
+
+/// Répliques du nœud local en cours de vérification
+#[derive(Debug, Default)]
+struct ReplicaScanState {
+    /// Contenus dont la réplique locale est corrompue et pas encore restaurée
+    corrupt: HashSet<Hash>,
+    /// Position de la fenêtre d'échantillonnage dans la liste des contenus
+    cursor: usize,
+}
+
+impl ReplicaScanState {
+    /// Prochaine fenêtre d'échantillonnage, au moins un contenu par passe
+    ///
+    /// Avec un taux `r`, l'ensemble des contenus est relu en `1/r` passes.
+    fn next_sample(&mut self, contents: &[Hash], sample_rate: f64) -> Vec<Hash> {
+        if contents.is_empty() || sample_rate == 0.0 {
+            return Vec::new();
+        }
+
+        let size = ((contents.len() as f64 * sample_rate).ceil() as usize).clamp(1, contents.len());
+        let start = self.cursor % contents.len();
+        self.cursor = start + size;
+        contents.iter().cycle().skip(start).take(size).cloned().collect()
+    }
+}
+
+/// Passe de vérification d'intégrité du stockage local
+///
+/// Partage l'état du `StorageManager` : la tâche périodique et
+/// `run_integrity_scan` cumulent les mêmes compteurs.
+struct IntegrityScanner {
+    local_node: NodeId,
+    chunk_store: Arc<Mutex<ChunkStore>>,
+    archive_storage: Arc<Mutex<ArchiveStorage>>,
+    replica_scan: Arc<Mutex<ReplicaScanState>>,
+    metrics: Arc<super::metrics::StorageMetrics>,
+    totals: Arc<Mutex<IntegrityScanReport>>,
+    throughput: u64,
+    sample_rate: f64,
+}
+
+impl IntegrityScanner {
+    async fn run(&self) -> IntegrityScanReport {
+        let mut report = self.scan_chunks().await;
+        report.merge(&self.scan_replicas().await);
+        self.totals.lock().await.merge(&report);
+        report
+    }
+
+    /// Relit chaque chunk stocké et compare son hash à son adresse
+    ///
+    /// Le verrou du magasin n'est tenu que le temps de vérifier un chunk.
+    async fn scan_chunks(&self) -> IntegrityScanReport {
+        let chunk_hashes = self.chunk_store.lock().await.chunk_hashes();
+        let mut report = IntegrityScanReport::default();
+
+        for chunk_hash in chunk_hashes {
+            // Le chunk a pu être libéré depuis le début de la passe
+            let Some(verification) = self.chunk_store.lock().await.verify_chunk(&chunk_hash) else {
+                continue;
+            };
+
+            report.chunks_scanned += 1;
+            report.bytes_scanned += verification.bytes_read;
+            report.corruptions_detected += verification.corrupted_replicas as u64;
+            report.corruptions_repaired += verification.repaired_replicas as u64;
+
+            if verification.unrecoverable {
+                report.unrecoverable_chunks += 1;
+                tracing::error!("Chunk {:?} corrompu sans copie saine", chunk_hash);
+            } else if verification.corrupted_replicas > 0 {
+                tracing::warn!(
+                    "Chunk {:?}: {} copie(s) corrompue(s) ré-répliquée(s)",
+                    chunk_hash, verification.repaired_replicas
+                );
+            }
+
+            self.throttle(verification.bytes_read).await;
+        }
+
+        report
+    }
+
+    /// Re-hache une fenêtre des répliques du nœud local
+    ///
+    /// Une réplique corrompue ou illisible est signalée aux métriques
+    /// (`storage_errors`, `corrupt_replicas_found`) et à l'alerte
+    /// `CorruptReplica`, puis remplacée par une copie vérifiée d'un autre
+    /// détenteur. Sans magasin de répliques configuré, rien n'est relu.
+    async fn scan_replicas(&self) -> IntegrityScanReport {
+        let mut report = IntegrityScanReport::default();
+        let Some(replicas) = self.archive_storage.lock().await.replica_store() else {
+            return report;
+        };
+
+        let mut contents = match replicas.stored_content(&self.local_node).await {
+            Ok(contents) => contents,
+            Err(e) => {
+                tracing::error!("Liste des répliques du nœud {} illisible: {}", self.local_node.hash().to_hex(), e);
+                self.metrics.record_error(ErrorType::Storage).await;
+                return report;
+            }
+        };
+        contents.sort_by(|a, b| a.as_bytes().cmp(b.as_bytes()));
+
+        let sample = self.replica_scan.lock().await.next_sample(&contents, self.sample_rate);
+        for content_hash in sample {
+            report.replicas_scanned += 1;
+            match replicas.read_replica(&self.local_node, &content_hash).await {
+                Ok(data) => {
+                    report.bytes_scanned += data.len() as u64;
+                    self.throttle(data.len() as u64).await;
+                    if compute_blake3(&data) == content_hash {
+                        continue;
+                    }
+                }
+                // Une réplique illisible est traitée comme corrompue
+                Err(e) => tracing::warn!("Réplique {} illisible: {}", content_hash.to_hex(), e),
+            }
+
+            report.corruptions_detected += 1;
+            if self.repair_replica(replicas.as_ref(), &content_hash).await {
+                report.corruptions_repaired += 1;
+            } else {
+                report.unrecoverable_replicas += 1;
+            }
+        }
+
+        report
+    }
+
+    /// Marque la réplique corrompue, alerte puis la restaure depuis une copie saine
+    ///
+    /// Retourne `true` si la réplique a été restaurée et re-vérifiée.
+    async fn repair_replica(&self, replicas: &dyn ReplicaStore, content_hash: &Hash) -> bool {
+        self.replica_scan.lock().await.corrupt.insert(*content_hash);
+        self.metrics.record_corrupt_replica(&self.local_node, content_hash).await;
+
+        let holders = match replicas.holders(content_hash).await {
+            Ok(holders) => holders,
+            Err(e) => {
+                tracing::error!("Détenteurs de {} inconnus: {}", content_hash.to_hex(), e);
+                return false;
+            }
+        };
+
+        for source in holders.iter().filter(|holder| **holder != self.local_node) {
+            match copy_verified(replicas, content_hash, source, &self.local_node).await {
+                Ok(()) => {
+                    self.replica_scan.lock().await.corrupt.remove(content_hash);
+                    return true;
+                }
+                Err(e) => tracing::warn!(
+                    "Restauration de {} depuis {} échouée: {}",
+                    content_hash.to_hex(), source.hash().to_hex(), e
+                ),
+            }
+        }
+
+        tracing::error!(
+            "Réplique de {} sur le nœud {} corrompue sans copie saine",
+            content_hash.to_hex(), self.local_node.hash().to_hex()
+        );
+        false
+    }
+
+    /// Ralentit la lecture pour ne pas dépasser `throughput` octets par seconde
+    async fn throttle(&self, bytes_read: u64) {
+        if self.throughput > 0 && bytes_read > 0 {
+            tokio::time::sleep(Duration::from_secs_f64(bytes_read as f64 / self.throughput as f64)).await;
+        }
+    }
+}
+
+/// Copie d'une réplique vers un nouveau nœud
+#[derive(Debug, Clone)]
+struct RebalanceJob {
+    content_hash: Hash,
+    source: NodeId,
+    target: NodeId,
+}
+
+impl Rebalancer {
+    async fn apply_status_change(&self, node_id: &NodeId, status: NodeStatus) -> bool {
+        let mut nodes = self.available_nodes.write().await;
+        let Some(info) = nodes.get_mut(node_id) else {
+            return false;
+        };
+        let was_healthy = info.status == NodeStatus::Active;
+        info.status = status;
+        was_healthy && info.status != NodeStatus::Active
+    }
+
+    /// Contenus sous leur minimum de répliques saines, avec le nombre manquant
+    fn find_deficits(
+        nodes: &HashMap<NodeId, StorageNodeInfo>,
+        content_cache: &HashMap<Hash, ContentMetadata>,
+        discovery: &ContentDiscovery,
+    ) -> Vec<(Hash, usize)> {
+        content_cache.iter()
+            .filter_map(|(content_hash, metadata)| {
+                let min_replicas = ReplicationStrategy::from_metadata(metadata).min_replicas() as usize;
+                let healthy = discovery.storage_nodes(content_hash).iter()
+                    .filter(|node| nodes.get(*node).is_some_and(|info| info.status == NodeStatus::Active))
+                    .count();
+                (healthy < min_replicas).then(|| (*content_hash, min_replicas - healthy))
+            })
+            .collect()
+    }
+
+    /// Choisit la source et les cibles des copies d'un contenu
+    ///
+    /// La source est `source` si elle est donnée, sinon la réplique saine la
+    /// plus performante ; les cibles sont les meilleurs nœuds disponibles ne
+    /// détenant pas le contenu, une région encore absente passant avant une
+    /// région déjà couverte.
+    fn plan_jobs(
+        nodes: &HashMap<NodeId, StorageNodeInfo>,
+        content_hash: &Hash,
+        holders: &[NodeId],
+        missing: usize,
+        source: Option<&NodeId>,
+    ) -> Option<Vec<RebalanceJob>> {
+        let by_score = |a: &&StorageNodeInfo, b: &&StorageNodeInfo| {
+            b.performance_score()
+                .partial_cmp(&a.performance_score())
+                .unwrap_or(std::cmp::Ordering::Equal)
+        };
+
+        let mut healthy_holders: Vec<&StorageNodeInfo> = holders.iter()
+            .filter_map(|node| nodes.get(node))
+            .filter(|info| info.status == NodeStatus::Active)
+            .collect();
+        healthy_holders.sort_by(by_score);
+        let source = match source {
+            Some(source) => source.clone(),
+            None => healthy_holders.first()?.node_id.clone(),
+        };
+
+        let mut covered_regions: HashSet<String> = healthy_holders.iter().map(|info| info.region.clone()).collect();
+        let mut candidates: Vec<&StorageNodeInfo> = nodes.values()
+            .filter(|info| info.is_available_for_storage() && !holders.contains(&info.node_id))
+            .collect();
+        candidates.sort_by(by_score);
+
+        let mut jobs = Vec::new();
+        while jobs.len() < missing && !candidates.is_empty() {
+            let index = candidates.iter()
+                .position(|info| !covered_regions.contains(&info.region))
+                .unwrap_or(0);
+            let target = candidates.remove(index);
+            covered_regions.insert(target.region.clone());
+            jobs.push(RebalanceJob {
+                content_hash: *content_hash,
+                source: source.clone(),
+                target: target.node_id.clone(),
+            });
+        }
+        Some(jobs)
+    }
+
+    async fn run(&self) -> RebalanceReport {
+        let mut report = RebalanceReport::default();
+        let jobs = {
+            let nodes = self.available_nodes.read().await;
+            let content_cache = self.content_metadata_cache.read().await;
+            let discovery = self.discovery_system.lock().await;
+
+            let deficits = Self::find_deficits(&nodes, &content_cache, &discovery);
+            report.under_replicated = deficits.len() as u32;
+
+            let mut jobs = Vec::new();
+            for (content_hash, missing) in deficits {
+                let holders = discovery.storage_nodes(&content_hash);
+                match Self::plan_jobs(&nodes, &content_hash, &holders, missing, None) {
+                    Some(planned) => {
+                        if planned.len() < missing {
+                            tracing::warn!(
+                                "Rééquilibrage de {:?}: {} nœud(s) disponible(s) pour {} réplique(s) manquante(s)",
+                                content_hash, planned.len(), missing
+                            );
+                        }
+                        jobs.extend(planned);
+                    }
+                    None => {
+                        report.unrecoverable += 1;
+                        tracing::error!("Contenu {:?} sans réplique saine", content_hash);
+                    }
+                }
+            }
+            jobs
+        };
+        report.jobs_scheduled = jobs.len() as u32;
+        report.jobs_completed = self.execute(jobs).await;
+
+        report
+    }
+
+    /// Re-réplique les contenus d'un nœud qui va quitter le réseau
+    ///
+    /// Le nœud est mis en `Maintenance` : il ne reçoit plus de nouvelles
+    /// répliques et ne compte plus comme réplique saine. Chaque contenu qu'il
+    /// détient et qui passerait ainsi sous son minimum est copié depuis ce
+    /// nœud, encore en service, vers d'autres nœuds disponibles.
+    async fn evacuate(&self, node_id: &NodeId) -> RebalanceReport {
+        self.apply_status_change(node_id, NodeStatus::Maintenance).await;
+
+        let mut report = RebalanceReport::default();
+        let jobs = {
+            let nodes = self.available_nodes.read().await;
+            let content_cache = self.content_metadata_cache.read().await;
+            let discovery = self.discovery_system.lock().await;
+
+            let mut jobs = Vec::new();
+            for (content_hash, missing) in Self::find_deficits(&nodes, &content_cache, &discovery) {
+                let holders = discovery.storage_nodes(&content_hash);
+                if !holders.contains(node_id) {
+                    continue;
+                }
+                report.under_replicated += 1;
+
+                let planned = Self::plan_jobs(&nodes, &content_hash, &holders, missing, Some(node_id))
+                    .unwrap_or_default();
+                if planned.len() < missing {
+                    report.unrecoverable += 1;
+                    tracing::warn!(
+                        "Évacuation de {:?}: {} nœud(s) disponible(s) pour {} réplique(s) manquante(s)",
+                        content_hash, planned.len(), missing
+                    );
+                }
+                jobs.extend(planned);
+            }
+            jobs
+        };
+        report.jobs_scheduled = jobs.len() as u32;
+        report.jobs_completed = self.execute(jobs).await;
+
+        report
+    }
+
+    /// Exécute des copies, au plus `max_concurrent_jobs` à la fois ; retourne le nombre de réussites
+    async fn execute(&self, jobs: Vec<RebalanceJob>) -> u32 {
+        let completed = AtomicUsize::new(0);
+        futures_util::stream::iter(jobs)
+            .for_each_concurrent(self.max_concurrent_jobs, |job| {
+                let completed = &completed;
+                async move {
+                    if self.copy_replica(&job).await {
+                        completed.fetch_add(1, Ordering::Relaxed);
+                    }
+                }
+            })
+            .await;
+        completed.into_inner() as u32
+    }
+
+    /// Copie une réplique puis l'enregistre dans la DHT
+    ///
+    /// La cible n'est enregistrée comme détentrice qu'une fois sa copie relue
+    /// et vérifiée. Le verrou du stockage d'archive n'est tenu que le temps de
+    /// récupérer l'accès aux répliques : les copies concurrentes ne
+    /// s'attendent pas.
+    async fn copy_replica(&self, job: &RebalanceJob) -> bool {
+        let Some(replicas) = self.archive_storage.lock().await.replica_store() else {
+            tracing::warn!("Aucun magasin de répliques configuré: copie de {:?} impossible", job.content_hash);
+            return false;
+        };
+
+        self.jobs_active.fetch_add(1, Ordering::Relaxed);
+        let copied = copy_verified(replicas.as_ref(), &job.content_hash, &job.source, &job.target).await;
+        self.jobs_active.fetch_sub(1, Ordering::Relaxed);
+
+        match copied {
+            Ok(()) => {
+                self.discovery_system.lock().await.add_storage_node(&job.content_hash, job.target.clone());
+                true
+            }
+            Err(e) => {
+                tracing::warn!(
+                    "Copie de {:?} de {:?} vers {:?} échouée: {}",
+                    job.content_hash, job.source, job.target, e
+                );
+                false
+            }
+        }
+    }
+}
+
+/// Résultat d'une passe de rééquilibrage
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct RebalanceReport {
+    /// Contenus sous leur minimum de répliques saines au début de la passe
+    pub under_replicated: u32,
+    /// Copies planifiées
+    pub jobs_scheduled: u32,
+    /// Copies réussies
+    pub jobs_completed: u32,
+    /// Contenus sans aucune réplique saine pour servir de source
+    pub unrecoverable: u32,
+}
+
+/// Nombre d'évictions conservées dans le journal d'audit du nettoyage
+const CLEANUP_AUDIT_CAPACITY: usize = 10_000;
+
+/// Motif de l'éviction d'un contenu par la politique de nettoyage
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum CleanupReason {
+    /// Contenu plus ancien que `max_age`
+    Age { age: Duration, max_age: Duration },
+    /// Stockage local (`used` octets) au-delà de `max_size`
+    Size { used: u64, max_size: u64 },
+    /// Contenu parmi les moins récemment utilisés au-delà de `max_items`
+    LeastRecentlyUsed { item_count: u64, max_items: u64 },
+}
+
+/// Entrée du journal d'audit du nettoyage
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CleanupAuditEntry {
+    /// Contenu évincé
+    pub content_hash: Hash,
+    /// Taille logique du contenu
+    pub size: u64,
+    /// Motif de l'éviction
+    pub reason: CleanupReason,
+    /// Répliques saines sur d'autres nœuds au moment de l'éviction
+    pub replicas_elsewhere: u32,
+    /// Premier constat du dépassement de la politique
+    pub flagged_at: SystemTime,
+    /// Date de l'éviction
+    pub evicted_at: SystemTime,
+}
+
+/// Résultat d'une passe de nettoyage
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct CleanupReport {
+    /// Contenus évincés pendant la passe
+    pub evicted: Vec<CleanupAuditEntry>,
+    /// Octets libérés par la déduplication
+    pub bytes_freed: u64,
+    /// Contenus au-delà de la politique encore dans leur délai de grâce
+    pub pending_grace: u32,
+    /// Contenus au-delà de la politique conservés car critiques
+    pub protected_critical: u32,
+    /// Contenus au-delà de la politique conservés faute de répliques suffisantes ailleurs
+    pub insufficient_replicas: u32,
+    /// Contenus dus conservés car trop peu de leurs répliques ailleurs ont pu être relues
+    pub unverified_replicas: u32,
+}
+
+/// Contenu local examiné par le nettoyage
+#[derive(Debug, Clone)]
+struct CleanupCandidate {
+    content_hash: Hash,
+    size: u64,
+    critical: bool,
+    created_at: SystemTime,
+    last_used: SystemTime,
+    replicas_elsewhere: u32,
+    holders_elsewhere: Vec<NodeId>,
+    min_replicas: u32,
+}
+
+impl CleanupCandidate {
+    /// Vrai si le contenu peut être évincé ; sinon, compte la raison dans `report`
+    fn evictable(&self, report: &mut CleanupReport) -> bool {
+        if self.critical {
+            report.protected_critical += 1;
+            false
+        } else if self.replicas_elsewhere < self.min_replicas {
+            report.insufficient_replicas += 1;
+            false
+        } else {
+            true
+        }
+    }
+}
+
+/// Proximité entre deux identifiants de région (ex: "eu-west-1" / "eu-west-2")
+///
+/// Compte les segments initiaux communs : même zone > même continent > aucun.
+fn region_proximity(a: &str, b: &str) -> usize {
+    a.split('-')
+        .zip(b.split('-'))
+        .take_while(|(x, y)| x == y)
+        .count()
+}
+
+/// Place une réplique sur le meilleur nœud restant d'une région
+fn take_best_in_region(
+    by_region: &mut HashMap<String, Vec<&StorageNodeInfo>>,
+    region: &str,
+    placement: &mut RegionPlacement,
+) -> bool {
+    let Some(candidates) = by_region.get_mut(region) else {
+        return false;
+    };
+    if candidates.is_empty() {
+        return false;
+    }
+
+    let node = candidates.remove(0);
+    placement.nodes.push(node.node_id.clone());
+    if !placement.regions_used.contains(&node.region) {
+        placement.regions_used.push(node.region.clone());
+    }
+    true
+}
+
+/// Résultat d'un placement géographique des répliques
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct RegionPlacement {
+    /// Nœuds sélectionnés, dans l'ordre de placement
+    pub nodes: Vec<NodeId>,
+    /// Régions effectivement utilisées
+    pub regions_used: Vec<String>,
+    /// Régions demandées sans nœud disponible (remplacées par une région proche)
+    pub unsatisfied_regions: Vec<String>,
+}
+
+impl RegionPlacement {
+    /// Vérifie que chaque région demandée porte au moins une réplique
+    pub fn min_regions_satisfied(&self) -> bool {
+        self.unsatisfied_regions.is_empty()
+    }
+}
+
+/// Résultat d'un placement par spécialisation
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct SpecializedPlacement {
+    /// Nœuds sélectionnés, spécialisés d'abord
+    pub nodes: Vec<NodeId>,
+    /// Au moins un nœud enregistré est spécialisé pour ce type de contenu
+    pub routable: bool,
+    /// Nombre de nœuds spécialisés retenus
+    pub specialized_nodes: usize,
+    /// Nombre de Full Archive Nodes retenus en repli
+    pub fallback_nodes: usize,
+}
+
+/// Compteurs du routage par spécialisation
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct SpecializationStats {
+    /// Contenus placés
+    pub placements: u64,
+    /// Contenus pour lesquels un nœud spécialisé existe
+    pub routable_placements: u64,
+    /// Contenus routables placés sur au moins un nœud spécialisé
+    pub specialized_hits: u64,
+    /// Contenus routables replacés sur des Full Archive Nodes faute de capacité
+    pub fallbacks: u64,
+}
+
+impl SpecializationStats {
+    /// Enregistre l'issue d'un placement
+    pub fn record(&mut self, placement: &SpecializedPlacement) {
+        self.placements += 1;
+        if placement.routable {
+            self.routable_placements += 1;
+            if placement.specialized_nodes > 0 {
+                self.specialized_hits += 1;
+            } else {
+                self.fallbacks += 1;
+            }
+        }
+    }
+
+    /// Part des contenus routables placés sur un nœud spécialisé
+    pub fn hit_rate(&self) -> f64 {
+        if self.routable_placements == 0 {
+            return 0.0;
+        }
+        self.specialized_hits as f64 / self.routable_placements as f64
+    }
+}
+
+/// Rapport d'une passe de vérification d'intégrité
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct IntegrityScanReport {
+    /// Chunks vérifiés
+    pub chunks_scanned: u64,
+    /// Répliques du nœud local vérifiées
+    #[serde(default)]
+    pub replicas_scanned: u64,
+    /// Octets relus
+    pub bytes_scanned: u64,
+    /// Copies corrompues détectées
+    pub corruptions_detected: u64,
+    /// Copies restaurées depuis une copie saine
+    pub corruptions_repaired: u64,
+    /// Chunks dont aucune copie n'est saine
+    pub unrecoverable_chunks: u64,
+    /// Répliques locales corrompues sans copie saine ailleurs
+    #[serde(default)]
+    pub unrecoverable_replicas: u64,
+}
+
+impl IntegrityScanReport {
+    /// Ajoute les compteurs d'une autre passe
+    pub fn merge(&mut self, other: &IntegrityScanReport) {
+        self.chunks_scanned += other.chunks_scanned;
+        self.replicas_scanned += other.replicas_scanned;
+        self.bytes_scanned += other.bytes_scanned;
+        self.corruptions_detected += other.corruptions_detected;
+        self.corruptions_repaired += other.corruptions_repaired;
+        self.unrecoverable_chunks += other.unrecoverable_chunks;
+        self.unrecoverable_replicas += other.unrecoverable_replicas;
+    }
+}
+
+/// Rapport d'optimisation
+#[derive(Debug, Default, Clone, Serialize, Deserialize)]
+pub struct OptimizationReport {
+    /// Améliorations de distribution identifiées
+    pub distribution_improvements: u32,
+    /// Mises à jour de stratégies de réplication
+    pub replication_updates: u32,
     /// Actions de politiques de rétention appliquées
     pub retention_actions: u32,
 }
@@ -822,6 +2454,850 @@ mod tests {
         assert!(nodes.contains_key(&node_id));
     }
 
+    #[test]
+    fn test_storage_config_without_recent_fields_loads() {
+        let mut value = serde_json::to_value(StorageConfig::default()).unwrap();
+        let fields = value.as_object_mut().unwrap();
+        for field in [
+            "integrity_scan_interval",
+            "integrity_scan_throughput",
+            "integrity_sample_rate",
+            "content_filter",
+            "rebalance_interval",
+            "max_concurrent_rebalance_jobs",
+            "cleanup_grace_period",
+            "cleanup_interval",
+            "max_content_size",
+            "replica_path",
+            "chunk_path",
+        ] {
+            fields.remove(field);
+        }
+
+        let config: StorageConfig = serde_json::from_value(value).unwrap();
+        assert_eq!(config.integrity_scan_interval, Duration::from_secs(24 * 3600));
+        assert_eq!(config.integrity_sample_rate, 0.05);
+        assert_eq!(config.rebalance_interval, Duration::from_secs(300));
+        assert_eq!(config.max_concurrent_rebalance_jobs, 4);
+        assert_eq!(config.cleanup_grace_period, Duration::from_secs(24 * 3600));
+        assert_eq!(config.max_content_size, DEFAULT_MAX_CONTENT_SIZE);
+        assert!(config.chunk_path.is_none());
+    }
+
+    #[tokio::test]
+    async fn test_store_content_rejects_oversized_content() {
+        let config = StorageConfig {
+            max_content_size: 1024,
+            ..StorageConfig::default()
+        };
+        let policy = StoragePolicy {
+            node_preferences: HashMap::new(),
+            retention_policies: Vec::new(),
+            alert_thresholds: AlertThresholds::default(),
+        };
+        let mut manager = StorageManager::new(config, policy).await.unwrap();
+
+        let data = vec![0u8; 2048];
+        let content_hash = crate::crypto::compute_blake3(&data);
+        let result = manager.store_content(&content_hash, &data, create_test_metadata()).await;
+        assert!(matches!(
+            result,
+            Err(crate::error::CoreError::Content(ContentError::ContentTooLarge { size: 2048, limit: 1024 }))
+        ));
+
+        let metadata = ContentMetadata {
+            tags: vec!["x".repeat(crate::constants::MAX_TAG_LENGTH + 1)],
+            ..create_test_metadata()
+        };
+        let result = manager.store_content(&content_hash, &data[..512], metadata).await;
+        assert!(matches!(result, Err(crate::error::CoreError::Content(ContentError::TagTooLong { .. }))));
+        assert!(manager.content_metadata_cache.read().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_integrity_scan_repairs_bit_rot() {
+        let config = StorageConfig {
+            integrity_scan_throughput: 0,
+            ..StorageConfig::default()
+        };
+        let policy = StoragePolicy {
+            node_preferences: HashMap::new(),
+            retention_policies: Vec::new(),
+            alert_thresholds: AlertThresholds::default(),
+        };
+        let manager = StorageManager::new(config, policy).await.unwrap();
+
+        let data: Vec<u8> = (0..50_000u32).flat_map(|i| i.to_le_bytes()).collect();
+        let content_hash = crate::crypto::compute_blake3(&data);
+        let chunk_hash = {
+            let mut chunk_store = manager.chunk_store.lock().await;
+            chunk_store.store(content_hash, &data).unwrap();
+            let chunk_hash = chunk_store.manifest(&content_hash).unwrap().chunks[0];
+            chunk_store.corrupt_replica(&chunk_hash, 0);
+            chunk_hash
+        };
+
+        let local = NodeId::from(crate::crypto::compute_blake3(b"local"));
+        let report = manager.run_integrity_scan(&local).await;
+        assert_eq!(report.corruptions_detected, 1);
+        assert_eq!(report.corruptions_repaired, 1);
+        assert_eq!(report.unrecoverable_chunks, 0);
+        assert_eq!(report.bytes_scanned, data.len() as u64 * 2);
+
+        let chunk_store = manager.chunk_store.lock().await;
+        assert_eq!(chunk_store.available_replicas(&chunk_hash), 2);
+        assert_eq!(chunk_store.retrieve(&content_hash).unwrap(), Some(data));
+        drop(chunk_store);
+
+        // Une seconde passe ne trouve plus rien ; les compteurs restent cumulés
+        assert_eq!(manager.run_integrity_scan(&local).await.corruptions_detected, 0);
+        let stats = manager.get_storage_stats().await.unwrap();
+        assert_eq!(stats.corruptions_detected, 1);
+        assert_eq!(stats.corruptions_repaired, 1);
+    }
+
+    #[tokio::test]
+    async fn test_integrity_scan_repairs_corrupted_local_replica() {
+        let config = StorageConfig {
+            integrity_scan_throughput: 0,
+            integrity_sample_rate: 1.0,
+            ..StorageConfig::default()
+        };
+        let policy = StoragePolicy {
+            node_preferences: HashMap::new(),
+            retention_policies: Vec::new(),
+            alert_thresholds: AlertThresholds::default(),
+        };
+        let replica_dir = tempfile::tempdir().unwrap();
+        let replicas = Arc::new(DiskReplicaStore::new(replica_dir.path()));
+        let manager = StorageManager::new(config, policy).await.unwrap()
+            .with_replica_store(replicas.clone());
+
+        let local = NodeId::from(crate::crypto::compute_blake3(b"local"));
+        let peer = NodeId::from(crate::crypto::compute_blake3(b"peer"));
+        let blobs: Vec<Vec<u8>> = (0..4u8).map(|i| vec![i; 4096]).collect();
+        let hashes: Vec<Hash> = blobs.iter().map(|blob| crate::crypto::compute_blake3(blob)).collect();
+        for (hash, blob) in hashes.iter().zip(&blobs) {
+            replicas.write_replica(&local, hash, blob).await.unwrap();
+            replicas.write_replica(&peer, hash, blob).await.unwrap();
+        }
+        // Bit inversé dans une réplique locale
+        let mut rotten = blobs[2].clone();
+        rotten[100] ^= 0x01;
+        replicas.write_replica(&local, &hashes[2], &rotten).await.unwrap();
+
+        let report = manager.run_integrity_scan(&local).await;
+        assert_eq!(report.replicas_scanned, 4);
+        assert_eq!(report.corruptions_detected, 1);
+        assert_eq!(report.corruptions_repaired, 1);
+        assert_eq!(report.unrecoverable_replicas, 0);
+        assert_eq!(replicas.read_replica(&local, &hashes[2]).await.unwrap(), blobs[2]);
+        assert!(manager.corrupt_replicas().await.is_empty());
+
+        let metrics = manager.integrity_metrics();
+        assert_eq!(metrics.get_current_metrics().await.errors.corrupt_replicas_found, 1);
+        let alerts = metrics.get_active_alerts().await;
+        let alert = alerts.iter()
+            .find(|alert| alert.alert_type == crate::storage::metrics::AlertType::CorruptReplica)
+            .unwrap();
+        assert!(alert.message.contains(&hashes[2].to_hex()));
+        assert!(alert.message.contains(&local.hash().to_hex()));
+
+        // Sans copie saine, la réplique reste marquée corrompue
+        replicas.write_replica(&local, &hashes[0], b"corrompu").await.unwrap();
+        replicas.write_replica(&peer, &hashes[0], b"corrompu aussi").await.unwrap();
+        let report = manager.run_integrity_scan(&local).await;
+        assert_eq!(report.corruptions_detected, 1);
+        assert_eq!(report.unrecoverable_replicas, 1);
+        assert_eq!(manager.corrupt_replicas().await, vec![hashes[0]]);
+        assert_eq!(manager.get_storage_stats().await.unwrap().corruptions_detected, 2);
+    }
+
+    #[test]
+    fn test_replica_sampling_covers_all_content_over_passes() {
+        let contents: Vec<Hash> = (0..10u8).map(|i| crate::crypto::compute_blake3(&[i])).collect();
+        let mut scan = ReplicaScanState::default();
+
+        let mut sampled = HashSet::new();
+        for _ in 0..4 {
+            let sample = scan.next_sample(&contents, 0.25);
+            assert_eq!(sample.len(), 3);
+            sampled.extend(sample);
+        }
+        assert_eq!(sampled.len(), contents.len());
+        assert!(scan.next_sample(&contents, 0.0).is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_content_filter_tracks_stored_and_deleted_content() {
+        let config = StorageConfig {
+            content_filter: BloomConfig { expected_items: 64, rebuild_deletion_ratio: 0.5, ..BloomConfig::default() },
+            ..StorageConfig::default()
+        };
+        let policy = StoragePolicy {
+            node_preferences: HashMap::new(),
+            retention_policies: Vec::new(),
+            alert_thresholds: AlertThresholds::default(),
+        };
+        let manager = StorageManager::new(config, policy).await.unwrap();
+
+        let contents: Vec<(Hash, Vec<u8>)> = (0..64u32)
+            .map(|i| {
+                let data = format!("contenu {}", i).into_bytes();
+                (crate::crypto::compute_blake3(&data), data)
+            })
+            .collect();
+        for (hash, data) in &contents {
+            manager.chunk_store.lock().await.store(*hash, data).unwrap();
+            manager.track_stored_content(hash).await;
+        }
+        assert!(contents.iter().all(|(hash, _)| manager.might_contain(hash)));
+        assert_eq!(manager.content_filter_stats().rebuilds, 0);
+
+        // Dépasser la capacité prévue reconstruit un filtre plus grand
+        let extra = crate::crypto::compute_blake3(b"contenu en trop");
+        manager.chunk_store.lock().await.store(extra, b"contenu en trop").unwrap();
+        manager.track_stored_content(&extra).await;
+        let stats = manager.content_filter_stats();
+        assert_eq!(stats.rebuilds, 1);
+        assert_eq!(stats.capacity, 130);
+
+        // Les suppressions massives reconstruisent le filtre au lieu de le laisser dériver
+        let (deleted, kept) = contents.split_at(33);
+        for (hash, _) in deleted {
+            manager.delete_content(hash).await.unwrap();
+        }
+        let stats = manager.content_filter_stats();
+        assert_eq!(stats.rebuilds, 2);
+        assert_eq!(stats.items, kept.len() + 1);
+        assert!(kept.iter().all(|(hash, _)| manager.might_contain(hash)));
+        assert!(manager.might_contain(&extra));
+        assert!(deleted.iter().filter(|(hash, _)| manager.might_contain(hash)).count() < 5);
+    }
+
+    #[tokio::test]
+    async fn test_search_content_indexes_page_text() {
+        let config = StorageConfig::default();
+        let policy = StoragePolicy {
+            node_preferences: HashMap::new(),
+            retention_policies: Vec::new(),
+            alert_thresholds: AlertThresholds::default(),
+        };
+        let manager = StorageManager::new(config, policy).await.unwrap();
+
+        let pages: Vec<(Hash, String)> = (0..30u32)
+            .map(|i| {
+                let page = format!("<html><body><p>Bulletin {} sur la fonte des glaces</p></body></html>", i);
+                (crate::crypto::compute_blake3(page.as_bytes()), page)
+            })
+            .collect();
+        for (hash, page) in &pages {
+            let metadata = ContentMetadata { content_hash: hash.clone(), ..create_test_metadata() };
+            manager.content_metadata_cache.write().await.insert(hash.clone(), metadata.clone());
+            manager.index_content(hash, page.as_bytes(), &metadata).await;
+        }
+
+        let results = manager.search_content("\"fonte des glaces\"", &SearchFilter::default(), 20, 20).await.unwrap();
+        assert_eq!(results.total_count, 30);
+        assert_eq!(results.results.len(), 10);
+
+        let results = manager.search_content("bulletin 7", &SearchFilter::default(), 0, 20).await.unwrap();
+        assert_eq!(results.total_count, 1);
+        assert_eq!(results.results[0].content_hash, pages[7].0);
+
+        // Le balisage n'est pas indexé
+        let results = manager.search_content("body", &SearchFilter::default(), 0, 20).await.unwrap();
+        assert_eq!(results.total_count, 0);
+    }
+
+    fn create_region_node(seed: u8, region: &str, used_capacity: u64) -> (NodeId, StorageNodeInfo) {
+        let node_id = NodeId::from(crate::crypto::compute_blake3(&[seed]));
+        let mut info = create_test_node_info();
+        info.node_id = node_id.clone();
+        info.region = region.to_string();
+        info.used_capacity = used_capacity;
+        (node_id, info)
+    }
+
+    #[test]
+    fn test_geographic_placement_covers_each_region() {
+        let nodes: HashMap<NodeId, StorageNodeInfo> = vec![
+            create_region_node(1, "eu-west-1", 100_000_000),
+            create_region_node(2, "eu-west-1", 200_000_000),
+            create_region_node(3, "us-east-1", 100_000_000),
+            create_region_node(4, "ap-south-1", 100_000_000),
+        ].into_iter().collect();
+
+        let regions = vec!["eu-west-1".to_string(), "us-east-1".to_string()];
+        let placement = StorageManager::place_across_regions(&nodes, &regions, 3, 5);
+
+        assert!(placement.min_regions_satisfied());
+        assert_eq!(placement.nodes.len(), 3);
+        assert!(placement.regions_used.contains(&"eu-west-1".to_string()));
+        assert!(placement.regions_used.contains(&"us-east-1".to_string()));
+    }
+
+    #[test]
+    fn test_geographic_placement_falls_back_to_nearest_region() {
+        let nodes: HashMap<NodeId, StorageNodeInfo> = vec![
+            create_region_node(1, "eu-west-1", 100_000_000),
+            create_region_node(2, "us-east-2", 100_000_000),
+            // Nœud saturé : indisponible pour le stockage
+            create_region_node(3, "us-east-1", 950_000_000),
+            create_region_node(4, "ap-south-1", 100_000_000),
+        ].into_iter().collect();
+
+        let regions = vec!["eu-west-1".to_string(), "us-east-1".to_string()];
+        let placement = StorageManager::place_across_regions(&nodes, &regions, 2, 3);
+
+        assert!(!placement.min_regions_satisfied());
+        assert_eq!(placement.unsatisfied_regions, vec!["us-east-1".to_string()]);
+        assert!(placement.regions_used.contains(&"us-east-2".to_string()));
+        assert!(!placement.regions_used.contains(&"ap-south-1".to_string()));
+    }
+
+    #[test]
+    fn test_geographic_placement_never_exceeds_max_copies() {
+        let nodes: HashMap<NodeId, StorageNodeInfo> = vec![
+            create_region_node(1, "eu-west-1", 100_000_000),
+            create_region_node(2, "eu-west-1", 200_000_000),
+            create_region_node(3, "us-east-1", 100_000_000),
+            create_region_node(4, "us-east-1", 200_000_000),
+            create_region_node(5, "ap-south-1", 100_000_000),
+        ].into_iter().collect();
+
+        // min_copies au-delà de max_copies
+        let regions = vec!["eu-west-1".to_string(), "us-east-1".to_string()];
+        let placement = StorageManager::place_across_regions(&nodes, &regions, 5, 3);
+        assert_eq!(placement.nodes.len(), 3);
+        assert!(placement.min_regions_satisfied());
+
+        // Plus de régions demandées que de copies autorisées
+        let regions = vec!["eu-west-1".to_string(), "us-east-1".to_string(), "ap-south-1".to_string()];
+        let placement = StorageManager::place_across_regions(&nodes, &regions, 1, 2);
+        assert_eq!(placement.nodes.len(), 2);
+        assert_eq!(placement.unsatisfied_regions, vec!["ap-south-1".to_string()]);
+        assert!(!placement.min_regions_satisfied());
+    }
+
+    #[test]
+    fn test_region_proximity() {
+        assert!(region_proximity("us-east-1", "us-east-2") > region_proximity("us-east-1", "us-west-1"));
+        assert!(region_proximity("us-east-1", "us-west-1") > region_proximity("us-east-1", "eu-west-1"));
+    }
+
+    #[tokio::test]
+    async fn test_reschedule_failed_node_content() {
+        let config = StorageConfig::default();
+        let policy = StoragePolicy {
+            node_preferences: HashMap::new(),
+            retention_policies: Vec::new(),
+            alert_thresholds: AlertThresholds::default(),
+        };
+        let manager = StorageManager::new(config, policy).await.unwrap();
+
+        let (failed, failed_info) = create_region_node(1, "eu-west-1", 100_000_000);
+        let (holder, holder_info) = create_region_node(2, "eu-west-1", 100_000_000);
+        let (spare, spare_info) = create_region_node(3, "us-east-1", 100_000_000);
+        manager.add_nodes(vec![
+            (failed.clone(), failed_info),
+            (holder.clone(), holder_info),
+            (spare.clone(), spare_info),
+        ]).await.unwrap();
+
+        let metadata = create_test_metadata();
+        let content_hash = crate::crypto::compute_blake3(b"contenu replanifie");
+        manager.replication_manager.lock().await.create_strategy(content_hash, &metadata).unwrap();
+        manager.discovery_system.lock().await
+            .add_content(content_hash, metadata, vec![failed.clone(), holder.clone()]);
+
+        assert_eq!(manager.reschedule_node_content(&failed).await.unwrap(), 1);
+
+        let holders = manager.discovery_system.lock().await.storage_nodes(&content_hash);
+        assert_eq!(holders, vec![holder, spare]);
+        assert_eq!(manager.available_nodes.read().await[&failed].status, NodeStatus::Failed);
+
+        // Le nœud ne détient plus rien : une seconde replanification est sans effet
+        assert_eq!(manager.reschedule_node_content(&failed).await.unwrap(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_offline_holder_is_rebalanced_to_another_node() {
+        let config = StorageConfig::default();
+        let policy = StoragePolicy {
+            node_preferences: HashMap::new(),
+            retention_policies: Vec::new(),
+            alert_thresholds: AlertThresholds::default(),
+        };
+        let replica_dir = tempfile::tempdir().unwrap();
+        let replicas = Arc::new(DiskReplicaStore::new(replica_dir.path()));
+        let manager = StorageManager::new(config, policy).await.unwrap()
+            .with_replica_store(replicas.clone());
+
+        let (first, first_info) = create_region_node(1, "eu-west-1", 100_000_000);
+        let (second, second_info) = create_region_node(2, "eu-west-1", 100_000_000);
+        let (third, third_info) = create_region_node(3, "us-east-1", 100_000_000);
+        // Région non couverte : préférée au nœud européen plus performant
+        let (fourth, fourth_info) = create_region_node(4, "ap-south-1", 300_000_000);
+        let (fifth, fifth_info) = create_region_node(5, "eu-west-1", 50_000_000);
+        let (overloaded, mut overloaded_info) = create_region_node(6, "ap-south-1", 10_000_000);
+        overloaded_info.status = NodeStatus::Overloaded;
+        manager.add_nodes(vec![
+            (first.clone(), first_info),
+            (second.clone(), second_info),
+            (third.clone(), third_info),
+            (fourth.clone(), fourth_info),
+            (fifth, fifth_info),
+            (overloaded, overloaded_info),
+        ]).await.unwrap();
+
+        // Stratégie du contenu de test : au moins 3 répliques
+        let metadata = create_test_metadata();
+        let content_hash = crate::crypto::compute_blake3(b"contenu a reequilibrer");
+        for holder in [&first, &second, &third] {
+            replicas.write_replica(holder, &content_hash, b"contenu a reequilibrer").await.unwrap();
+        }
+        manager.content_metadata_cache.write().await.insert(content_hash, metadata.clone());
+        manager.discovery_system.lock().await
+            .add_content(content_hash, metadata, vec![first.clone(), second.clone(), third.clone()]);
+        assert_eq!(manager.get_storage_stats().await.unwrap().under_replicated_count, 0);
+
+        assert!(manager.update_node_status(&third, NodeStatus::Offline).await);
+        assert_eq!(manager.get_storage_stats().await.unwrap().under_replicated_count, 1);
+
+        let report = manager.rebalance().await;
+        assert_eq!(report.under_replicated, 1);
+        assert_eq!(report.jobs_scheduled, 1);
+        assert_eq!(report.jobs_completed, 1);
+
+        let holders = manager.discovery_system.lock().await.storage_nodes(&content_hash);
+        assert_eq!(holders, vec![first, second, third.clone(), fourth.clone()]);
+        assert_eq!(replicas.read_replica(&fourth, &content_hash).await.unwrap(), b"contenu a reequilibrer");
+        let stats = manager.get_storage_stats().await.unwrap();
+        assert_eq!(stats.under_replicated_count, 0);
+        assert_eq!(stats.rebalance_jobs_active, 0);
+
+        // Le compte est revenu à la cible : rien de plus à copier
+        assert_eq!(manager.rebalance().await.jobs_scheduled, 0);
+        assert!(!manager.update_node_status(&third, NodeStatus::Failed).await);
+    }
+
+    #[tokio::test]
+    async fn test_background_rebalance_follows_status_changes() {
+        let replica_dir = tempfile::tempdir().unwrap();
+        let replicas = Arc::new(DiskReplicaStore::new(replica_dir.path()));
+        let manager = StorageManager::new(StorageConfig::default(), StoragePolicy {
+            node_preferences: HashMap::new(),
+            retention_policies: Vec::new(),
+            alert_thresholds: AlertThresholds::default(),
+        }).await.unwrap().with_replica_store(replicas.clone());
+
+        let nodes: Vec<_> = (1..=4).map(|seed| create_region_node(seed, "eu-west-1", 100_000_000)).collect();
+        let ids: Vec<NodeId> = nodes.iter().map(|(node_id, _)| node_id.clone()).collect();
+        manager.add_nodes(nodes).await.unwrap();
+
+        let metadata = create_test_metadata();
+        let content_hash = crate::crypto::compute_blake3(b"contenu surveille");
+        for holder in &ids[..3] {
+            replicas.write_replica(holder, &content_hash, b"contenu surveille").await.unwrap();
+        }
+        manager.content_metadata_cache.write().await.insert(content_hash, metadata.clone());
+        manager.discovery_system.lock().await.add_content(content_hash, metadata, ids[..3].to_vec());
+
+        let (status_sender, status_changes) = mpsc::unbounded_channel();
+        let tasks = manager.start_background_tasks(ids[0].clone(), status_changes);
+        status_sender.send((ids[2].clone(), NodeStatus::Failed)).unwrap();
+
+        let copied = tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                if let Ok(data) = replicas.read_replica(&ids[3], &content_hash).await {
+                    return data;
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        }).await.unwrap();
+        assert_eq!(copied, b"contenu surveille");
+        for task in tasks {
+            task.abort();
+        }
+    }
+
+    #[tokio::test]
+    async fn test_evacuate_node_keeps_replicas_above_minimum() {
+        let config = StorageConfig::default();
+        let policy = StoragePolicy {
+            node_preferences: HashMap::new(),
+            retention_policies: Vec::new(),
+            alert_thresholds: AlertThresholds::default(),
+        };
+        let replica_dir = tempfile::tempdir().unwrap();
+        let replicas = Arc::new(DiskReplicaStore::new(replica_dir.path()));
+        let manager = StorageManager::new(config, policy).await.unwrap()
+            .with_replica_store(replicas.clone());
+
+        let nodes: Vec<(NodeId, StorageNodeInfo)> = ["eu-west-1", "eu-west-1", "us-east-1", "ap-south-1", "us-east-1"]
+            .iter()
+            .enumerate()
+            .map(|(i, region)| create_region_node(i as u8 + 1, region, 100_000_000))
+            .collect();
+        let ids: Vec<NodeId> = nodes.iter().map(|(node_id, _)| node_id.clone()).collect();
+        manager.add_nodes(nodes).await.unwrap();
+
+        // Au moins 3 répliques par contenu ; seuls les deux premiers dépendent de ids[0]
+        let contents = [
+            (b"seule copie".as_slice(), vec![ids[0].clone()]),
+            (b"au minimum".as_slice(), vec![ids[0].clone(), ids[1].clone(), ids[2].clone()]),
+            (b"hors du noeud".as_slice(), vec![ids[1].clone(), ids[2].clone(), ids[3].clone()]),
+        ];
+        let mut hashes = Vec::new();
+        for (data, holders) in contents {
+            let content_hash = crate::crypto::compute_blake3(data);
+            for holder in &holders {
+                replicas.write_replica(holder, &content_hash, data).await.unwrap();
+            }
+            let metadata = ContentMetadata { content_hash, ..create_test_metadata() };
+            manager.content_metadata_cache.write().await.insert(content_hash, metadata.clone());
+            manager.discovery_system.lock().await.add_content(content_hash, metadata, holders);
+            hashes.push(content_hash);
+        }
+
+        let report = manager.evacuate_node(&ids[0]).await;
+        assert_eq!(report.under_replicated, 2);
+        assert_eq!(report.jobs_scheduled, 4);
+        assert_eq!(report.jobs_completed, 4);
+        assert_eq!(report.unrecoverable, 0);
+
+        let discovery = manager.discovery_system.lock().await;
+        for content_hash in &hashes {
+            let others = discovery.storage_nodes(content_hash).into_iter().filter(|node| *node != ids[0]).count();
+            assert!(others >= 3, "{:?} n'a que {} réplique(s) hors du nœud drainé", content_hash, others);
+        }
+        assert_eq!(discovery.storage_nodes(&hashes[2]).len(), 3);
+        drop(discovery);
+
+        // Le nœud drainé ne reçoit plus de répliques et l'arrêter ne crée aucun déficit
+        assert!(!manager.available_nodes.read().await[&ids[0]].is_available_for_storage());
+        assert_eq!(manager.rebalance().await.jobs_scheduled, 0);
+    }
+
+    /// Répliques sur disque dont les copies vers `corrupt_target` sont altérées
+    struct CorruptingReplicas {
+        disk: DiskReplicaStore,
+        corrupt_target: NodeId,
+    }
+
+    #[async_trait::async_trait]
+    impl ReplicaStore for CorruptingReplicas {
+        async fn stored_content(&self, node_id: &NodeId) -> Result<Vec<Hash>> {
+            self.disk.stored_content(node_id).await
+        }
+
+        async fn read_replica(&self, node_id: &NodeId, content_hash: &Hash) -> Result<Vec<u8>> {
+            self.disk.read_replica(node_id, content_hash).await
+        }
+
+        async fn holders(&self, content_hash: &Hash) -> Result<Vec<NodeId>> {
+            self.disk.holders(content_hash).await
+        }
+
+        async fn replicate(&self, content_hash: &Hash, source: &NodeId, target: &NodeId) -> Result<()> {
+            let mut data = self.disk.read_replica(source, content_hash).await?;
+            if *target == self.corrupt_target {
+                data[0] ^= 0x01;
+            }
+            self.disk.write_replica(target, content_hash, &data).await
+        }
+    }
+
+    #[tokio::test]
+    async fn test_evacuation_ignores_unverified_copies() {
+        let policy = StoragePolicy {
+            node_preferences: HashMap::new(),
+            retention_policies: Vec::new(),
+            alert_thresholds: AlertThresholds::default(),
+        };
+        let nodes: Vec<(NodeId, StorageNodeInfo)> = (1..=4)
+            .map(|seed| create_region_node(seed, "eu-west-1", 100_000_000))
+            .collect();
+        let ids: Vec<NodeId> = nodes.iter().map(|(node_id, _)| node_id.clone()).collect();
+
+        // Sans magasin de répliques, aucune copie n'est réputée faite
+        let unconfigured = StorageManager::new(StorageConfig::default(), policy.clone()).await.unwrap();
+        unconfigured.add_nodes(nodes.clone()).await.unwrap();
+
+        let replica_dir = tempfile::tempdir().unwrap();
+        let disk = DiskReplicaStore::new(replica_dir.path());
+        let data = b"contenu a evacuer";
+        let content_hash = crate::crypto::compute_blake3(data);
+        disk.write_replica(&ids[0], &content_hash, data).await.unwrap();
+        let replicas = Arc::new(CorruptingReplicas { disk, corrupt_target: ids[3].clone() });
+        let manager = StorageManager::new(StorageConfig::default(), policy).await.unwrap()
+            .with_replica_store(replicas.clone());
+        manager.add_nodes(nodes).await.unwrap();
+
+        for manager in [&unconfigured, &manager] {
+            let metadata = ContentMetadata { content_hash, ..create_test_metadata() };
+            manager.content_metadata_cache.write().await.insert(content_hash, metadata.clone());
+            manager.discovery_system.lock().await.add_content(content_hash, metadata, vec![ids[0].clone()]);
+        }
+
+        let report = unconfigured.evacuate_node(&ids[0]).await;
+        assert_eq!(report.jobs_scheduled, 3);
+        assert_eq!(report.jobs_completed, 0);
+        assert_eq!(unconfigured.discovery_system.lock().await.storage_nodes(&content_hash), vec![ids[0].clone()]);
+
+        // La copie altérée sur ids[3] n'est pas enregistrée comme réplique
+        let report = manager.evacuate_node(&ids[0]).await;
+        assert_eq!(report.jobs_scheduled, 3);
+        assert_eq!(report.jobs_completed, 2);
+        let holders = manager.discovery_system.lock().await.storage_nodes(&content_hash);
+        assert!(holders.contains(&ids[1]) && holders.contains(&ids[2]));
+        assert!(!holders.contains(&ids[3]));
+
+        let drain = crate::nodes::DrainReport { in_flight_remaining: 0, replication: report };
+        assert!(!drain.is_complete());
+    }
+
+    /// Gestionnaire dont `ids[0]` est le nœud local, avec des contenus locaux créés il y a deux jours
+    ///
+    /// Chaque détenteur annoncé dans la DHT détient une réplique réelle dans le
+    /// magasin renvoyé.
+    async fn cleanup_fixture(
+        contents: &[(&[u8], ContentImportance, usize)],
+    ) -> (StorageManager, Vec<NodeId>, Vec<Hash>, DiskReplicaStore, tempfile::TempDir) {
+        let policy = StoragePolicy {
+            node_preferences: HashMap::new(),
+            retention_policies: Vec::new(),
+            alert_thresholds: AlertThresholds::default(),
+        };
+        let replica_dir = tempfile::tempdir().unwrap();
+        let replicas = DiskReplicaStore::new(replica_dir.path());
+        let manager = StorageManager::new(StorageConfig::default(), policy).await.unwrap()
+            .with_replica_store(Arc::new(replicas.clone()));
+
+        let nodes: Vec<(NodeId, StorageNodeInfo)> = (1..=4)
+            .map(|seed| create_region_node(seed, "eu-west-1", 100_000_000))
+            .collect();
+        let ids: Vec<NodeId> = nodes.iter().map(|(node_id, _)| node_id.clone()).collect();
+        manager.add_nodes(nodes).await.unwrap();
+
+        let mut hashes = Vec::new();
+        for (data, importance, replicas_elsewhere) in contents {
+            let content_hash = crate::crypto::compute_blake3(data);
+            let metadata = ContentMetadata {
+                content_hash,
+                size: data.len() as u64,
+                importance: importance.clone(),
+                created_at: chrono::Utc::now() - chrono::Duration::days(2),
+                ..create_test_metadata()
+            };
+            let holders: Vec<NodeId> = ids.iter().take(1 + replicas_elsewhere).cloned().collect();
+            for holder in &holders {
+                replicas.write_replica(holder, &content_hash, data).await.unwrap();
+            }
+            manager.chunk_store.lock().await.store(content_hash, data).unwrap();
+            manager.content_metadata_cache.write().await.insert(content_hash, metadata.clone());
+            manager.discovery_system.lock().await.add_content(content_hash, metadata, holders);
+            hashes.push(content_hash);
+        }
+        (manager, ids, hashes, replicas, replica_dir)
+    }
+
+    #[tokio::test]
+    async fn test_cleanup_respects_grace_period_replicas_and_criticality() {
+        let (manager, ids, hashes, _replicas, _replica_dir) = cleanup_fixture(&[
+            (b"ancien et replique".as_slice(), ContentImportance::Medium, 3),
+            (b"ancien et critique".as_slice(), ContentImportance::Critical, 3),
+            (b"ancien et peu replique".as_slice(), ContentImportance::Medium, 1),
+        ]).await;
+        let policy = CleanupPolicy::Age { max_age: Duration::from_secs(24 * 3600) };
+        let now = SystemTime::now();
+
+        // Premier constat : rien n'est supprimé avant la fin du délai de grâce
+        let report = manager.enforce_cleanup_policy(&ids[0], &policy, now).await.unwrap();
+        assert!(report.evicted.is_empty());
+        assert_eq!(report.pending_grace, 1);
+        assert_eq!(report.protected_critical, 1);
+        assert_eq!(report.insufficient_replicas, 1);
+
+        let later = now + manager.config.cleanup_grace_period;
+        let report = manager.enforce_cleanup_policy(&ids[0], &policy, later).await.unwrap();
+        assert_eq!(report.evicted.len(), 1);
+        assert_eq!(report.evicted[0].content_hash, hashes[0]);
+        assert_eq!(report.evicted[0].replicas_elsewhere, 3);
+        assert_eq!(report.evicted[0].flagged_at, now);
+        assert!(matches!(report.evicted[0].reason, CleanupReason::Age { .. }));
+        assert_eq!(report.protected_critical, 1);
+        assert_eq!(report.insufficient_replicas, 1);
+
+        assert!(!manager.discovery_system.lock().await.storage_nodes(&hashes[0]).contains(&ids[0]));
+        assert!(manager.content_metadata_cache.read().await.contains_key(&hashes[1]));
+        assert!(manager.content_metadata_cache.read().await.contains_key(&hashes[2]));
+        let audit = manager.cleanup_audit().await;
+        assert_eq!(audit.len(), 1);
+        assert_eq!(audit[0].content_hash, hashes[0]);
+    }
+
+    #[tokio::test]
+    async fn test_lru_cleanup_restarts_grace_period_when_content_is_used() {
+        let (manager, ids, hashes, _replicas, _replica_dir) = cleanup_fixture(&[
+            (b"premier contenu".as_slice(), ContentImportance::Medium, 3),
+            (b"second contenu".as_slice(), ContentImportance::Medium, 3),
+        ]).await;
+        let policy = CleanupPolicy::LeastRecentlyUsed { max_items: 1 };
+        let grace = manager.config.cleanup_grace_period;
+        let now = SystemTime::now();
+
+        manager.discovery_system.lock().await.record_content_access(hashes[1]);
+        let report = manager.enforce_cleanup_policy(&ids[0], &policy, now).await.unwrap();
+        assert_eq!(report.pending_grace, 1);
+
+        // Le premier contenu redevient le plus récent : le second prend sa place, avec un nouveau délai
+        manager.discovery_system.lock().await.record_content_access(hashes[0]);
+        let report = manager.enforce_cleanup_policy(&ids[0], &policy, now + grace).await.unwrap();
+        assert!(report.evicted.is_empty());
+        assert_eq!(report.pending_grace, 1);
+
+        let report = manager.enforce_cleanup_policy(&ids[0], &policy, now + grace * 2).await.unwrap();
+        assert_eq!(report.evicted.len(), 1);
+        assert_eq!(report.evicted[0].content_hash, hashes[1]);
+        assert_eq!(
+            report.evicted[0].reason,
+            CleanupReason::LeastRecentlyUsed { item_count: 2, max_items: 1 }
+        );
+    }
+
+    #[tokio::test]
+    async fn test_cleanup_keeps_content_whose_replicas_fail_verification() {
+        let (manager, ids, hashes, replicas, _replica_dir) = cleanup_fixture(&[
+            (b"replique annoncee mais alteree".as_slice(), ContentImportance::Medium, 3),
+        ]).await;
+        let policy = CleanupPolicy::Age { max_age: Duration::from_secs(24 * 3600) };
+        let now = SystemTime::now();
+
+        // La DHT annonce trois détenteurs, mais une seule copie est altérée
+        replicas.write_replica(&ids[3], &hashes[0], b"donnees alterees").await.unwrap();
+
+        let report = manager.enforce_cleanup_policy(&ids[0], &policy, now).await.unwrap();
+        assert_eq!(report.pending_grace, 1);
+        let later = now + manager.config.cleanup_grace_period;
+        let report = manager.enforce_cleanup_policy(&ids[0], &policy, later).await.unwrap();
+        assert!(report.evicted.is_empty());
+        assert_eq!(report.unverified_replicas, 1);
+        assert!(manager.content_metadata_cache.read().await.contains_key(&hashes[0]));
+        assert!(manager.discovery_system.lock().await.storage_nodes(&hashes[0]).contains(&ids[0]));
+        assert!(manager.cleanup_audit().await.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_store_rejection_falls_back_to_another_node() {
+        let config = StorageConfig::default();
+        let policy = StoragePolicy {
+            node_preferences: HashMap::new(),
+            retention_policies: Vec::new(),
+            alert_thresholds: AlertThresholds::default(),
+        };
+        let manager = StorageManager::new(config, policy).await.unwrap();
+
+        let (specialized, specialized_info) = create_region_node(1, "eu-west-1", 100_000_000);
+        let (fallback, fallback_info) = create_region_node(2, "eu-west-1", 100_000_000);
+        manager.add_nodes(vec![
+            (specialized.clone(), specialized_info),
+            (fallback.clone(), fallback_info),
+        ]).await.unwrap();
+
+        let metadata = create_test_metadata();
+        let content_hash = crate::crypto::compute_blake3(b"contenu refuse");
+        manager.content_metadata_cache.write().await.insert(content_hash, metadata.clone());
+        manager.discovery_system.lock().await
+            .add_content(content_hash, metadata, vec![specialized.clone()]);
+
+        let replacement = manager.handle_store_rejection(&content_hash, &specialized).await.unwrap();
+        assert_eq!(replacement, Some(fallback.clone()));
+        assert_eq!(manager.discovery_system.lock().await.storage_nodes(&content_hash), vec![fallback.clone()]);
+
+        // Plus aucun candidat une fois le remplaçant refusé à son tour
+        assert_eq!(manager.handle_store_rejection(&content_hash, &fallback).await.unwrap(), None);
+        assert!(manager.discovery_system.lock().await.storage_nodes(&content_hash).is_empty());
+    }
+
+    fn pdf_filter() -> SpecializationFilter {
+        SpecializationFilter {
+            accepted_mime_types: vec!["application/pdf".to_string()],
+            ..SpecializationFilter::default()
+        }
+    }
+
+    fn create_light_node(seed: u8, used_capacity: u64) -> (NodeId, StorageNodeInfo) {
+        let (node_id, mut info) = create_region_node(seed, "eu-west-1", used_capacity);
+        info.node_type = NodeType::LightStorage;
+        (node_id, info)
+    }
+
+    #[test]
+    fn test_specialized_nodes_preferred_for_content_type() {
+        let (pdf_a, pdf_a_info) = create_light_node(1, 100_000_000);
+        let (pdf_b, pdf_b_info) = create_light_node(2, 300_000_000);
+        let (html, html_info) = create_light_node(3, 100_000_000);
+        let (archive, archive_info) = create_region_node(4, "eu-west-1", 100_000_000);
+        let nodes: HashMap<NodeId, StorageNodeInfo> = vec![
+            (pdf_a.clone(), pdf_a_info),
+            (pdf_b.clone(), pdf_b_info),
+            (html.clone(), html_info),
+            (archive.clone(), archive_info),
+        ].into_iter().collect();
+        let specializations: HashMap<NodeId, SpecializationFilter> = vec![
+            (pdf_a.clone(), pdf_filter()),
+            (pdf_b.clone(), pdf_filter()),
+            (html.clone(), SpecializationFilter::default()),
+        ].into_iter().collect();
+
+        let placement = StorageManager::place_by_specialization(&nodes, &specializations, "application/pdf", 3);
+        assert!(placement.routable);
+        assert_eq!(placement.nodes, vec![pdf_a, pdf_b, archive]);
+        assert_eq!(placement.specialized_nodes, 2);
+        assert_eq!(placement.fallback_nodes, 1);
+
+        // Aucun nœud spécialisé pour les images : placement laissé à la réplication
+        let placement = StorageManager::place_by_specialization(&nodes, &specializations, "image/png", 3);
+        assert!(!placement.routable);
+        assert!(placement.nodes.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_specialization_falls_back_to_full_archive_and_tracks_hit_rate() {
+        let policy = StoragePolicy {
+            node_preferences: HashMap::new(),
+            retention_policies: Vec::new(),
+            alert_thresholds: AlertThresholds::default(),
+        };
+        let manager = StorageManager::new(StorageConfig::default(), policy).await.unwrap();
+
+        let (pdf, pdf_info) = create_light_node(1, 100_000_000);
+        let (archive, archive_info) = create_region_node(2, "eu-west-1", 100_000_000);
+        manager.add_nodes(vec![(pdf.clone(), pdf_info), (archive.clone(), archive_info)]).await.unwrap();
+        manager.register_node_specialization(pdf.clone(), pdf_filter()).await;
+
+        let mut metadata = create_test_metadata();
+        metadata.content_type = "application/pdf".to_string();
+        let placement = manager.plan_specialized_placement(&metadata, 1).await;
+        assert_eq!(placement.nodes, vec![pdf.clone()]);
+
+        // Nœud PDF saturé : repli sur le Full Archive Node
+        let (_, mut full_info) = create_light_node(1, 950_000_000);
+        full_info.node_id = pdf.clone();
+        manager.update_node_info(pdf.clone(), full_info).await.unwrap();
+        let placement = manager.plan_specialized_placement(&metadata, 1).await;
+        assert_eq!(placement.nodes, vec![archive]);
+        assert_eq!(placement.fallback_nodes, 1);
+
+        let stats = manager.specialization_stats().await;
+        assert_eq!(stats.routable_placements, 2);
+        assert_eq!(stats.specialized_hits, 1);
+        assert_eq!(stats.fallbacks, 1);
+        assert_eq!(manager.get_storage_stats().await.unwrap().specialization_hit_rate, 0.5);
+    }
+
     fn create_test_metadata() -> ContentMetadata {
         super::super::ContentMetadata {
             content_hash: Hash::zero(),
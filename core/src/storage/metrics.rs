@@ -6,22 +6,20 @@
 //! - Monitoring de la santé des nœuds
 //! - Alertes de capacité et disponibilité
 //! - Collecte et agrégation de données
+//! - Export au format texte Prometheus
 
 use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, VecDeque};
-use std::time::{Duration, SystemTime, Instant};
+use std::sync::Arc;
+use std::fmt::Write as _;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tokio::sync::{RwLock, Mutex};
-use tokio::time::{interval, sleep};
-use crate::crypto::Hash;
+use async_trait::async_trait;
 use crate::consensus::NodeId;
+use crate::crypto::Hash;
 use crate::error::Result;
-use super::{
-    ContentMetadata, StorageNodeInfo, NodeStatus,
-    replication::ReplicationMetrics,
-    distribution::DistributionStats,
-    discovery::DiscoveryStats,
-    bandwidth::BandwidthStats,
-};
+use crate::shutdown::{ShutdownHook, ShutdownPhase};
+use super::{StorageNodeInfo, NodeStatus};
 
 /// Configuration du système de métriques
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -40,6 +38,15 @@ pub struct MetricsConfig {
     pub detailed_metrics_retention: Duration,
     /// Export des métriques activé
     pub metrics_export_enabled: bool,
+    /// Identifiant du nœud, ajouté en label des métriques exportées
+    #[serde(default)]
+    pub node_id: Option<String>,
+    /// Région du nœud, ajoutée en label des métriques exportées
+    #[serde(default)]
+    pub region: Option<String>,
+    /// Fichier où l'historique des métriques est sauvegardé à l'arrêt
+    #[serde(default)]
+    pub snapshot_path: Option<String>,
 }
 
 impl Default for MetricsConfig {
@@ -52,6 +59,9 @@ impl Default for MetricsConfig {
             alert_thresholds: AlertThresholds::default(),
             detailed_metrics_retention: Duration::from_secs(7 * 24 * 3600), // 7 jours
             metrics_export_enabled: false,
+            node_id: None,
+            region: None,
+            snapshot_path: None,
         }
     }
 }
@@ -266,6 +276,9 @@ pub struct ErrorMetrics {
     pub mean_time_to_recovery: Duration,
     /// Dernière erreur critique
     pub last_critical_error: Option<SystemTime>,
+    /// Répliques corrompues trouvées par la vérification d'intégrité (cumul)
+    #[serde(default)]
+    pub corrupt_replicas_found: u64,
 }
 
 impl Default for ErrorMetrics {
@@ -279,10 +292,178 @@ impl Default for ErrorMetrics {
             validation_errors: 0,
             mean_time_to_recovery: Duration::ZERO,
             last_critical_error: None,
+            corrupt_replicas_found: 0,
+        }
+    }
+}
+
+impl CurrentMetrics {
+    /// Rend les métriques au format d'exposition texte de Prometheus
+    ///
+    /// `labels` est ajouté à chaque série (identifiant du nœud, région...). Les pourcentages
+    /// sont exportés en ratios 0-1 et les durées en secondes, selon les conventions Prometheus.
+    pub fn to_prometheus(&self, labels: &[(&str, &str)]) -> String {
+        let mut encoder = PrometheusEncoder::new(labels);
+        let performance = &self.performance;
+        let health = &self.health;
+        let capacity = &self.capacity;
+        let network = &self.network;
+        let errors = &self.errors;
+
+        // Performance
+        encoder.family("archivechain_storage_access_latency_milliseconds", "gauge", "Latence d'accès au contenu", &[
+            (&[("statistic", "average")], performance.average_access_latency as f64),
+            (&[("statistic", "median")], performance.median_access_latency as f64),
+            (&[("statistic", "p95")], performance.p95_access_latency as f64),
+        ]);
+        encoder.family("archivechain_storage_throughput_bytes_per_second", "gauge", "Débit de transfert", &[
+            (&[("statistic", "average")], performance.average_throughput as f64),
+            (&[("statistic", "peak")], performance.peak_throughput as f64),
+        ]);
+        encoder.gauge("archivechain_storage_operations_per_second", "Nombre d'opérations par seconde", performance.operations_per_second);
+        encoder.gauge("archivechain_storage_response_time_seconds", "Temps de réponse moyen du système", performance.average_response_time.as_secs_f64());
+        encoder.gauge("archivechain_storage_operation_success_ratio", "Taux de succès des opérations", performance.success_rate / 100.0);
+
+        // Santé
+        encoder.family("archivechain_storage_nodes", "gauge", "Nombre de nœuds de stockage par état", &[
+            (&[("state", "active")], health.active_nodes as f64),
+            (&[("state", "failed")], health.failed_nodes as f64),
+            (&[("state", "all")], health.total_nodes as f64),
+        ]);
+        encoder.gauge("archivechain_storage_nodes_online_ratio", "Proportion de nœuds en ligne", health.nodes_online_percentage / 100.0);
+        encoder.gauge("archivechain_storage_health_score", "Score de santé global (0-100)", health.overall_health_score as f64);
+        encoder.gauge("archivechain_storage_availability_ratio", "Disponibilité du système", health.system_availability / 100.0);
+        encoder.gauge("archivechain_storage_uptime_seconds", "Temps de fonctionnement", health.uptime.as_secs_f64());
+        encoder.family("archivechain_storage_restarts_total", "counter", "Nombre de redémarrages", &[
+            (&[], health.restart_count as f64),
+        ]);
+
+        // Capacité
+        encoder.family("archivechain_storage_capacity_bytes", "gauge", "Capacité de stockage", &[
+            (&[("kind", "total")], capacity.total_capacity as f64),
+            (&[("kind", "used")], capacity.used_capacity as f64),
+            (&[("kind", "available")], capacity.available_capacity as f64),
+        ]);
+        encoder.gauge("archivechain_storage_capacity_usage_ratio", "Proportion de la capacité utilisée", capacity.usage_percentage / 100.0);
+        encoder.gauge("archivechain_storage_capacity_growth_per_day", "Taux de croissance quotidien de l'utilisation", capacity.growth_rate_per_day);
+        if let Some(full_date) = capacity.estimated_full_date {
+            encoder.gauge("archivechain_storage_capacity_estimated_full_timestamp_seconds", "Date estimée de saturation", unix_seconds(full_date));
         }
+        encoder.gauge("archivechain_storage_contents", "Nombre de contenus stockés", capacity.content_count as f64);
+        encoder.gauge("archivechain_storage_content_average_size_bytes", "Taille moyenne des contenus", capacity.average_content_size as f64);
+
+        // Réseau
+        encoder.family("archivechain_network_bandwidth_bytes_per_second", "gauge", "Bande passante totale", &[
+            (&[("direction", "upload")], network.total_upload_bandwidth as f64),
+            (&[("direction", "download")], network.total_download_bandwidth as f64),
+        ]);
+        encoder.family("archivechain_network_bandwidth_usage_ratio", "gauge", "Utilisation de la bande passante", &[
+            (&[("direction", "upload")], network.upload_bandwidth_usage / 100.0),
+            (&[("direction", "download")], network.download_bandwidth_usage / 100.0),
+        ]);
+        encoder.gauge("archivechain_network_active_connections", "Nombre de connexions actives", network.active_connections as f64);
+        encoder.gauge("archivechain_network_latency_milliseconds", "Latence réseau moyenne entre nœuds", network.average_network_latency as f64);
+        encoder.gauge("archivechain_network_packet_loss_ratio", "Taux de perte de paquets", network.packet_loss_rate / 100.0);
+        encoder.gauge("archivechain_network_active_transfers", "Nombre de transferts en cours", network.active_transfers as f64);
+
+        // Erreurs
+        encoder.family("archivechain_storage_errors", "gauge", "Erreurs sur la dernière heure par type", &[
+            (&[("type", "all")], errors.total_errors_last_hour as f64),
+            (&[("type", "critical")], errors.critical_errors as f64),
+            (&[("type", "network")], errors.network_errors as f64),
+            (&[("type", "storage")], errors.storage_errors as f64),
+            (&[("type", "validation")], errors.validation_errors as f64),
+        ]);
+        encoder.gauge("archivechain_storage_error_rate_per_hour", "Taux d'erreurs par heure", errors.error_rate_per_hour);
+        encoder.gauge("archivechain_storage_mean_time_to_recovery_seconds", "Temps moyen de récupération", errors.mean_time_to_recovery.as_secs_f64());
+        encoder.family("archivechain_storage_corrupt_replicas_total", "counter", "Répliques corrompues trouvées par la vérification d'intégrité", &[
+            (&[], errors.corrupt_replicas_found as f64),
+        ]);
+        if let Some(last_error) = errors.last_critical_error {
+            encoder.gauge("archivechain_storage_last_critical_error_timestamp_seconds", "Date de la dernière erreur critique", unix_seconds(last_error));
+        }
+
+        encoder.finish()
     }
 }
 
+/// Source de métriques ajoutée à la cible de scrape Prometheus
+///
+/// Les compteurs exportés doivent être monotones ; les jauges reflètent le
+/// dernier état connu de la source.
+#[async_trait]
+pub trait PrometheusExporter: Send + Sync {
+    /// Rend les métriques de la source, `labels` étant ajouté à chaque série
+    async fn render_prometheus(&self, labels: &[(&str, &str)]) -> String;
+}
+
+/// Encodeur du format d'exposition texte de Prometheus
+pub struct PrometheusEncoder {
+    /// Labels communs, déjà formatés
+    common_labels: Vec<String>,
+    output: String,
+}
+
+impl PrometheusEncoder {
+    pub fn new(labels: &[(&str, &str)]) -> Self {
+        Self {
+            common_labels: labels.iter().map(|(name, value)| format_label(name, value)).collect(),
+            output: String::new(),
+        }
+    }
+
+    /// Ajoute une famille de métriques avec ses lignes HELP/TYPE et ses échantillons
+    pub fn family(&mut self, name: &str, kind: &str, help: &str, samples: &[(&[(&str, &str)], f64)]) {
+        let _ = writeln!(self.output, "# HELP {} {}", name, help.replace('\\', "\\\\").replace('\n', "\\n"));
+        let _ = writeln!(self.output, "# TYPE {} {}", name, kind);
+
+        for (labels, value) in samples {
+            let labels: Vec<String> = self.common_labels.iter()
+                .cloned()
+                .chain(labels.iter().map(|(name, value)| format_label(name, value)))
+                .collect();
+
+            if labels.is_empty() {
+                let _ = writeln!(self.output, "{} {}", name, format_value(*value));
+            } else {
+                let _ = writeln!(self.output, "{}{{{}}} {}", name, labels.join(","), format_value(*value));
+            }
+        }
+    }
+
+    pub fn gauge(&mut self, name: &str, help: &str, value: f64) {
+        self.family(name, "gauge", help, &[(&[], value)]);
+    }
+
+    pub fn counter(&mut self, name: &str, help: &str, value: f64) {
+        self.family(name, "counter", help, &[(&[], value)]);
+    }
+
+    /// Retourne le texte encodé
+    pub fn finish(self) -> String {
+        self.output
+    }
+}
+
+fn format_label(name: &str, value: &str) -> String {
+    let escaped = value.replace('\\', "\\\\").replace('"', "\\\"").replace('\n', "\\n");
+    format!("{}=\"{}\"", name, escaped)
+}
+
+fn format_value(value: f64) -> String {
+    if value.is_nan() {
+        "NaN".to_string()
+    } else if value.is_infinite() {
+        if value > 0.0 { "+Inf" } else { "-Inf" }.to_string()
+    } else {
+        value.to_string()
+    }
+}
+
+fn unix_seconds(time: SystemTime) -> f64 {
+    time.duration_since(UNIX_EPOCH).unwrap_or_default().as_secs_f64()
+}
+
 /// Point de données historique
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MetricsDataPoint {
@@ -324,6 +505,8 @@ struct EventCounters {
     latency_measurements: VecDeque<u32>,
     /// Erreurs par type
     error_counts: HashMap<ErrorType, u32>,
+    /// Répliques corrompues trouvées (jamais remis à zéro)
+    corrupt_replicas_found: u64,
 }
 
 /// Types d'erreurs
@@ -365,6 +548,7 @@ impl MetricsCollector {
 
     /// Enregistre une opération réussie
     pub async fn record_successful_operation(&self, latency_ms: u32, bytes_transferred: u64) {
+        let mut metrics = self.current_metrics.write().await;
         let mut counters = self.event_counters.lock().await;
         counters.successful_operations += 1;
         counters.bytes_transferred += bytes_transferred;
@@ -374,13 +558,29 @@ impl MetricsCollector {
         if counters.latency_measurements.len() > 1000 {
             counters.latency_measurements.pop_front();
         }
+
+        self.refresh_operation_metrics(&mut metrics, &counters);
     }
 
     /// Enregistre une opération échouée
     pub async fn record_failed_operation(&self, error_type: ErrorType) {
+        let mut metrics = self.current_metrics.write().await;
         let mut counters = self.event_counters.lock().await;
         counters.failed_operations += 1;
         *counters.error_counts.entry(error_type).or_insert(0) += 1;
+
+        self.refresh_operation_metrics(&mut metrics, &counters);
+    }
+
+    /// Enregistre une réplique corrompue, comptée aussi comme erreur de stockage
+    pub async fn record_corrupt_replica(&self) {
+        let mut metrics = self.current_metrics.write().await;
+        let mut counters = self.event_counters.lock().await;
+        counters.failed_operations += 1;
+        *counters.error_counts.entry(ErrorType::Storage).or_insert(0) += 1;
+        counters.corrupt_replicas_found += 1;
+
+        self.refresh_operation_metrics(&mut metrics, &counters);
     }
 
     /// Met à jour les métriques avec les données des nœuds
@@ -431,8 +631,15 @@ impl MetricsCollector {
         metrics.network.total_download_bandwidth = total_bandwidth;
         metrics.network.average_network_latency = average_latency;
 
-        // Met à jour les métriques de performance
         let counters = self.event_counters.lock().await;
+        self.refresh_operation_metrics(&mut metrics, &counters);
+    }
+
+    /// Recalcule les métriques dérivées des compteurs d'opérations
+    fn refresh_operation_metrics(&self, metrics: &mut CurrentMetrics, counters: &EventCounters) {
+        metrics.timestamp = SystemTime::now();
+
+        // Met à jour les métriques de performance
         if !counters.latency_measurements.is_empty() {
             let mut sorted_latencies: Vec<_> = counters.latency_measurements.iter().copied().collect();
             sorted_latencies.sort_unstable();
@@ -459,21 +666,22 @@ impl MetricsCollector {
         metrics.errors.network_errors = *counters.error_counts.get(&ErrorType::Network).unwrap_or(&0);
         metrics.errors.storage_errors = *counters.error_counts.get(&ErrorType::Storage).unwrap_or(&0);
         metrics.errors.validation_errors = *counters.error_counts.get(&ErrorType::Validation).unwrap_or(&0);
+        metrics.errors.corrupt_replicas_found = counters.corrupt_replicas_found;
 
         // Calcule l'uptime
         metrics.health.uptime = SystemTime::now().duration_since(self.start_time).unwrap_or_default();
         metrics.health.restart_count = counters.restart_count;
 
         // Score de santé global
-        metrics.health.overall_health_score = self.calculate_health_score(&metrics).await;
+        metrics.health.overall_health_score = Self::calculate_health_score(metrics);
     }
 
     /// Calcule le score de santé global
-    async fn calculate_health_score(&self, metrics: &CurrentMetrics) -> u8 {
+    fn calculate_health_score(metrics: &CurrentMetrics) -> u8 {
         let mut score = 100.0;
 
         // Pénalité pour les nœuds hors ligne
-        if metrics.health.nodes_online_percentage < 90.0 {
+        if metrics.health.total_nodes > 0 && metrics.health.nodes_online_percentage < 90.0 {
             score -= (90.0 - metrics.health.nodes_online_percentage) * 2.0;
         }
 
@@ -515,11 +723,54 @@ impl MetricsCollector {
         Ok(())
     }
 
+    /// Collecte un dernier point puis sauvegarde l'historique dans `snapshot_path`
+    pub async fn persist_snapshot(&self) -> Result<()> {
+        let Some(path) = self.config.snapshot_path.as_deref() else {
+            return Ok(());
+        };
+
+        self.collect_metrics_snapshot().await?;
+        let data = {
+            let history = self.history.read().await;
+            serde_json::to_vec(&*history).map_err(|e| crate::error::CoreError::Internal {
+                message: format!("Sérialisation des métriques impossible: {}", e),
+            })?
+        };
+
+        // Écriture atomique via un fichier temporaire
+        let path = std::path::Path::new(path);
+        let tmp_path = path.with_extension("tmp");
+        tokio::fs::write(&tmp_path, data).await
+            .map_err(|e| crate::error::CoreError::Internal {
+                message: format!("Écriture des métriques impossible: {}", e),
+            })?;
+        tokio::fs::rename(&tmp_path, path).await
+            .map_err(|e| crate::error::CoreError::Internal {
+                message: format!("Écriture des métriques impossible: {}", e),
+            })?;
+        Ok(())
+    }
+
     /// Obtient les métriques actuelles
     pub async fn get_current_metrics(&self) -> CurrentMetrics {
         self.current_metrics.read().await.clone()
     }
 
+    /// Exporte les métriques actuelles au format texte Prometheus
+    ///
+    /// Les labels `node_id` et `region` de la configuration sont ajoutés à chaque série.
+    pub async fn export_prometheus(&self) -> String {
+        self.current_metrics.read().await.to_prometheus(&self.prometheus_labels())
+    }
+
+    /// Labels `node_id` et `region` configurés, communs à toutes les séries exportées
+    pub fn prometheus_labels(&self) -> Vec<(&str, &str)> {
+        [("node_id", &self.config.node_id), ("region", &self.config.region)]
+            .into_iter()
+            .filter_map(|(name, value)| value.as_deref().map(|value| (name, value)))
+            .collect()
+    }
+
     /// Obtient l'historique des métriques
     pub async fn get_metrics_history(&self, duration: Duration) -> Vec<MetricsDataPoint> {
         let history = self.history.read().await;
@@ -550,8 +801,21 @@ impl MetricsCollector {
     }
 }
 
+#[async_trait]
+impl ShutdownHook for MetricsCollector {
+    fn name(&self) -> &str {
+        "metrics"
+    }
+
+    async fn on_shutdown(&self, phase: ShutdownPhase) -> Result<()> {
+        match phase {
+            ShutdownPhase::Persist => self.persist_snapshot().await,
+            _ => Ok(()),
+        }
+    }
+}
+
 /// Gestionnaire d'alertes
-#[derive(Debug)]
 pub struct AlertManager {
     /// Configuration des seuils
     thresholds: AlertThresholds,
@@ -580,6 +844,8 @@ pub enum AlertType {
     BandwidthSaturated,
     /// Santé système dégradée
     SystemHealthDegraded,
+    /// Réplique corrompue détectée par la vérification d'intégrité
+    CorruptReplica,
 }
 
 /// Alerte
@@ -619,6 +885,14 @@ pub enum AlertSeverity {
 /// Callback d'alerte
 type AlertCallback = Box<dyn Fn(&Alert) + Send + Sync>;
 
+impl std::fmt::Debug for AlertManager {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AlertManager")
+            .field("thresholds", &self.thresholds)
+            .finish_non_exhaustive()
+    }
+}
+
 impl AlertManager {
     /// Crée un nouveau gestionnaire d'alertes
     pub fn new(thresholds: AlertThresholds) -> Self {
@@ -714,6 +988,11 @@ impl AlertManager {
         new_alerts
     }
 
+    /// Déclenche une alerte levée hors des seuils (ex: vérification d'intégrité)
+    pub async fn raise_alert(&self, alert: Alert) {
+        self.activate_alert(alert).await;
+    }
+
     /// Active une alerte
     async fn activate_alert(&self, alert: Alert) {
         let mut active_alerts = self.active_alerts.write().await;
@@ -953,8 +1232,8 @@ impl CapacityMonitor {
 pub struct StorageMetrics {
     /// Configuration
     config: MetricsConfig,
-    /// Collecteur de métriques
-    collector: MetricsCollector,
+    /// Collecteur de métriques, partageable avec la cible de scrape `/metrics`
+    collector: Arc<MetricsCollector>,
     /// Gestionnaire d'alertes
     alert_manager: AlertManager,
     /// Moniteur de capacité
@@ -964,7 +1243,7 @@ pub struct StorageMetrics {
 impl StorageMetrics {
     /// Crée un nouveau système de métriques
     pub fn new(config: MetricsConfig) -> Self {
-        let collector = MetricsCollector::new(config.clone());
+        let collector = Arc::new(MetricsCollector::new(config.clone()));
         let alert_manager = AlertManager::new(config.alert_thresholds.clone());
         let capacity_monitor = CapacityMonitor::new();
 
@@ -976,6 +1255,11 @@ impl StorageMetrics {
         }
     }
 
+    /// Collecteur alimenté par ce système, à exposer sur `/metrics`
+    pub fn collector(&self) -> Arc<MetricsCollector> {
+        self.collector.clone()
+    }
+
     /// Enregistre une opération de stockage
     pub async fn record_storage_operation(&self, size: u64, replicas: u32) {
         let latency = 50; // Latence simulée
@@ -993,6 +1277,26 @@ impl StorageMetrics {
         self.collector.record_failed_operation(error_type).await;
     }
 
+    /// Enregistre une réplique corrompue et lève l'alerte correspondante
+    pub async fn record_corrupt_replica(&self, node_id: &NodeId, content_hash: &Hash) {
+        self.collector.record_corrupt_replica().await;
+
+        let alert = Alert {
+            alert_type: AlertType::CorruptReplica,
+            severity: AlertSeverity::Error,
+            message: format!(
+                "Réplique corrompue: contenu {} sur le nœud {}",
+                content_hash.to_hex(), node_id.hash().to_hex()
+            ),
+            trigger_value: 1.0,
+            threshold: 0.0,
+            triggered_at: SystemTime::now(),
+            is_active: true,
+            resolved_at: None,
+        };
+        self.alert_manager.raise_alert(alert).await;
+    }
+
     /// Met à jour avec les données des nœuds
     pub async fn update_node_data(&self, nodes: &HashMap<NodeId, StorageNodeInfo>) {
         self.collector.update_node_metrics(nodes).await;
@@ -1034,6 +1338,11 @@ impl StorageMetrics {
         self.capacity_monitor.get_trends().await
     }
 
+    /// Exporte les métriques actuelles au format texte Prometheus
+    pub async fn export_prometheus(&self) -> String {
+        self.collector.export_prometheus().await
+    }
+
     /// Nettoie les données anciennes
     pub async fn cleanup(&self) {
         self.collector.cleanup_old_data().await;
@@ -1044,13 +1353,14 @@ impl StorageMetrics {
         let current_metrics = self.get_current_metrics().await;
         let active_alerts = self.get_active_alerts().await;
         let capacity_trends = self.get_capacity_trends().await;
+        let system_status = self.calculate_system_status(&current_metrics, &active_alerts).await;
 
         SystemReport {
             timestamp: SystemTime::now(),
             metrics: current_metrics,
             active_alerts,
             capacity_trends,
-            system_status: self.calculate_system_status(&current_metrics, &active_alerts).await,
+            system_status,
         }
     }
 
@@ -1158,6 +1468,34 @@ mod tests {
         assert!(AlertSeverity::Warning > AlertSeverity::Info);
     }
 
+    #[tokio::test]
+    async fn test_export_prometheus() {
+        let config = MetricsConfig {
+            node_id: Some("node-1".to_string()),
+            region: Some("eu-\"west\"".to_string()),
+            ..Default::default()
+        };
+        let collector = MetricsCollector::new(config);
+        collector.record_successful_operation(120, 2048).await;
+        collector.record_failed_operation(ErrorType::Storage).await;
+
+        let output = collector.export_prometheus().await;
+
+        assert!(output.contains("# HELP archivechain_storage_operation_success_ratio "));
+        assert!(output.contains("# TYPE archivechain_storage_operation_success_ratio gauge\n"));
+        assert!(output.contains("archivechain_storage_operation_success_ratio{node_id=\"node-1\",region=\"eu-\\\"west\\\"\"} 0.5\n"));
+        assert!(output.contains("archivechain_storage_access_latency_milliseconds{node_id=\"node-1\",region=\"eu-\\\"west\\\"\",statistic=\"p95\"} 120\n"));
+        assert!(output.contains("archivechain_storage_errors{node_id=\"node-1\",region=\"eu-\\\"west\\\"\",type=\"storage\"} 1\n"));
+        assert!(output.contains("# TYPE archivechain_storage_restarts_total counter\n"));
+        assert!(!output.contains("estimated_full_timestamp"));
+
+        // Chaque famille a exactement une ligne HELP et une ligne TYPE
+        let help_lines = output.lines().filter(|l| l.starts_with("# HELP")).count();
+        let type_lines = output.lines().filter(|l| l.starts_with("# TYPE")).count();
+        assert_eq!(help_lines, type_lines);
+        assert!(output.lines().filter(|l| !l.starts_with('#')).all(|l| l.rsplit(' ').next().unwrap().parse::<f64>().is_ok()));
+    }
+
     #[tokio::test]
     async fn test_storage_metrics() {
         let config = MetricsConfig::default();
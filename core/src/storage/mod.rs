@@ -7,20 +7,38 @@
 //! - Système de découverte de contenu (DHT)
 //! - Gestion optimisée de la bande passante
 //! - Métriques et monitoring en temps réel
+//! - Crawl des archives web avec profondeur et périmètre configurables
 
 pub mod manager;
+pub mod dedup;
+pub mod encryption;
+pub mod bloom;
+pub mod crawler;
+pub mod search;
+pub mod integrity;
 // pub mod replication;
 // pub mod distribution;
 // pub mod discovery;
 // pub mod archive;
 // pub mod bandwidth;
-// pub mod metrics;
+pub mod metrics;
 
 // Re-exports publics
 pub use manager::{
     StorageManager, StorageConfig, StorageStats, StoragePolicy,
-    AlertThresholds, RetentionPolicy
+    AlertThresholds, RetentionPolicy, IntegrityScanReport,
+    SpecializedPlacement, SpecializationStats, RebalanceReport,
+    CleanupReport, CleanupAuditEntry, CleanupReason, DEFAULT_MAX_CONTENT_SIZE
 };
+pub use dedup::{ChunkStore, ChunkingConfig, ChunkManifest, ChunkVerification, DedupStats};
+pub use encryption::{ContentCipher, KeyEncryptionKey, DataKey, WrappedKey};
+pub use bloom::{BloomFilter, BloomConfig, BloomStats, ContentFilter};
+pub use crawler::{
+    CrawlEngine, CrawlResult, CrawledResource, ArchiveManifest, ManifestEntry,
+    CrawlFailure, SkippedResource, SkipReason, CRAWLED_CONTENT_IMPORTANCE
+};
+pub use search::{extract_text, SearchIndex, SearchDocument, SearchFilter, SearchHit};
+pub use integrity::{copy_verified, verified_holders, DiskReplicaStore, ReplicaStore};
 // pub use replication::{
 //     ReplicationStrategy, ReplicationManager, ContentImportance, 
 //     ReplicationMetrics, AdaptiveReplication
@@ -41,19 +59,22 @@ pub use manager::{
 //     BandwidthManager, BandwidthLimits, PriorityQueues, QoSPolicies,
 //     TransferManager, LoadBalancer
 // };
-// pub use metrics::{
-//     StorageMetrics, PerformanceMetrics, HealthMetrics, AlertManager,
-//     MetricsCollector, CapacityMonitor
-// };
+pub use metrics::{
+    PerformanceMetrics, HealthMetrics, AlertManager,
+    MetricsCollector, MetricsConfig, CurrentMetrics, CapacityMonitor,
+    PrometheusEncoder, PrometheusExporter
+};
 
 
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use std::sync::Arc;
 use std::time::Duration;
 use crate::crypto::{Hash, PublicKey};
 use crate::consensus::NodeId;
-use crate::error::Result;
+use crate::constants::{MAX_METADATA_SIZE, MAX_TAGS_PER_ARCHIVE, MAX_TAG_LENGTH};
+use crate::error::{ContentError, Result};
 
 /// Importance temporaire du contenu (version simplifiée)
 #[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
@@ -102,7 +123,12 @@ pub enum ReplicationStrategy {
 impl ReplicationStrategy {
     /// Crée une stratégie basée sur les métadonnées
     pub fn from_metadata(metadata: &ContentMetadata) -> Self {
-        match metadata.importance {
+        Self::for_importance(&metadata.importance)
+    }
+
+    /// Stratégie appliquée à un contenu selon sa seule importance
+    pub fn for_importance(importance: &ContentImportance) -> Self {
+        match importance {
             ContentImportance::Critical => Self::Fixed { copies: 15 },
             ContentImportance::High => Self::PopularityBased { min_copies: 5, max_copies: 10 },
             ContentImportance::Medium => Self::PopularityBased { min_copies: 3, max_copies: 7 },
@@ -110,6 +136,15 @@ impl ReplicationStrategy {
         }
     }
     
+    /// Obtient le nombre minimum de répliques saines à maintenir
+    pub fn min_replicas(&self) -> u8 {
+        match self {
+            Self::PopularityBased { min_copies, .. } => *min_copies,
+            Self::Fixed { copies } => *copies,
+            Self::Geographic { min_copies, .. } => *min_copies,
+        }
+    }
+
     /// Obtient le nombre maximum de répliques
     pub fn max_replicas(&self) -> u8 {
         match self {
@@ -127,6 +162,14 @@ impl ReplicationStrategy {
             Self::Geographic { max_copies, .. } => *max_copies = new_max,
         }
     }
+
+    /// Obtient les régions imposées par la stratégie (vide si non géographique)
+    pub fn required_regions(&self) -> &[String] {
+        match self {
+            Self::Geographic { regions, .. } => regions,
+            _ => &[],
+        }
+    }
 }
 
 /// Métriques de stockage simplifiées (temporaire)
@@ -268,14 +311,47 @@ impl ContentDiscovery {
 }
 
 /// Stockage d'archive temporaire
-#[derive(Debug)]
 pub struct ArchiveStorage {
-    // Placeholder for future implementation
+    /// Accès aux répliques des nœuds, requis pour copier un contenu
+    replicas: Option<Arc<dyn ReplicaStore>>,
+}
+
+impl std::fmt::Debug for ArchiveStorage {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ArchiveStorage")
+            .field("replicas", &self.replicas.is_some())
+            .finish()
+    }
 }
 
 impl ArchiveStorage {
     pub fn new(_config: ()) -> Result<Self> {
-        Ok(Self {})
+        Ok(Self { replicas: None })
+    }
+
+    /// Utilise `replicas` pour lire et écrire les répliques des nœuds
+    pub fn with_replica_store(mut self, replicas: Arc<dyn ReplicaStore>) -> Self {
+        self.replicas = Some(replicas);
+        self
+    }
+
+    /// Accès aux répliques des nœuds, s'il est configuré
+    pub fn replica_store(&self) -> Option<Arc<dyn ReplicaStore>> {
+        self.replicas.clone()
+    }
+
+    /// Copie la réplique de `source` vers `target`
+    ///
+    /// La copie est relue sur `target` et n'est réussie que si son hash
+    /// correspond à `content_hash` ; sans accès aux répliques configuré,
+    /// aucune copie n'est possible.
+    pub async fn replicate_content(&self, content_hash: &Hash, source: &NodeId, target: &NodeId) -> Result<()> {
+        let Some(replicas) = &self.replicas else {
+            return Err(crate::error::CoreError::Internal {
+                message: "Aucun magasin de répliques configuré: copie impossible".to_string(),
+            });
+        };
+        integrity::copy_verified(replicas.as_ref(), content_hash, source, target).await
     }
 }
 
@@ -443,6 +519,36 @@ pub struct ContentMetadata {
     pub tags: Vec<String>,
 }
 
+impl ContentMetadata {
+    /// Taille des champs textuels décrivant le contenu
+    pub fn text_size(&self) -> usize {
+        self.content_type.len()
+            + self.title.as_ref().map_or(0, String::len)
+            + self.description.as_ref().map_or(0, String::len)
+            + self.preferred_regions.iter().map(String::len).sum::<usize>()
+            + self.tags.iter().map(String::len).sum::<usize>()
+    }
+
+    /// Vérifie les métadonnées contre `MAX_METADATA_SIZE`, `MAX_TAGS_PER_ARCHIVE` et `MAX_TAG_LENGTH`
+    pub fn check_limits(&self) -> std::result::Result<(), ContentError> {
+        let size = self.text_size();
+        if size > MAX_METADATA_SIZE {
+            return Err(ContentError::MetadataTooLarge { size, limit: MAX_METADATA_SIZE });
+        }
+        if self.tags.len() > MAX_TAGS_PER_ARCHIVE {
+            return Err(ContentError::TooManyTags { count: self.tags.len(), limit: MAX_TAGS_PER_ARCHIVE });
+        }
+        if let Some(tag) = self.tags.iter().find(|tag| tag.chars().count() > MAX_TAG_LENGTH) {
+            return Err(ContentError::TagTooLong {
+                tag: tag.clone(),
+                length: tag.chars().count(),
+                limit: MAX_TAG_LENGTH,
+            });
+        }
+        Ok(())
+    }
+}
+
 /// Résultat d'une opération de stockage
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StorageResult {
@@ -456,6 +562,8 @@ pub struct StorageResult {
     pub storage_time: Duration,
     /// Taille totale stockée (avec réplication)
     pub total_size_stored: u64,
+    /// Régions effectivement utilisées pour les répliques
+    pub regions: Vec<String>,
     /// Statut de l'opération
     pub status: StorageStatus,
 }
@@ -538,4 +646,42 @@ mod tests {
         assert_eq!(metadata.size, 1024);
         assert_eq!(metadata.redundancy_level, 5);
     }
+
+    #[test]
+    fn test_content_metadata_limits() {
+        let metadata = ContentMetadata {
+            content_hash: Hash::zero(),
+            size: 1024,
+            content_type: "text/html".to_string(),
+            title: Some("Page".to_string()),
+            description: None,
+            importance: ContentImportance::Medium,
+            popularity: 0,
+            created_at: chrono::Utc::now(),
+            preferred_regions: Vec::new(),
+            redundancy_level: 3,
+            tags: vec!["web".to_string()],
+        };
+        assert_eq!(metadata.check_limits(), Ok(()));
+
+        let too_many = ContentMetadata {
+            tags: (0..=MAX_TAGS_PER_ARCHIVE).map(|i| format!("tag{}", i)).collect(),
+            ..metadata.clone()
+        };
+        assert_eq!(too_many.check_limits(), Err(ContentError::TooManyTags {
+            count: MAX_TAGS_PER_ARCHIVE + 1,
+            limit: MAX_TAGS_PER_ARCHIVE,
+        }));
+
+        let long_tag = "é".repeat(MAX_TAG_LENGTH + 1);
+        let too_long = ContentMetadata { tags: vec![long_tag.clone()], ..metadata.clone() };
+        assert_eq!(too_long.check_limits(), Err(ContentError::TagTooLong {
+            tag: long_tag,
+            length: MAX_TAG_LENGTH + 1,
+            limit: MAX_TAG_LENGTH,
+        }));
+
+        let too_large = ContentMetadata { description: Some("x".repeat(MAX_METADATA_SIZE)), ..metadata };
+        assert!(matches!(too_large.check_limits(), Err(ContentError::MetadataTooLarge { limit: MAX_METADATA_SIZE, .. })));
+    }
 }
\ No newline at end of file
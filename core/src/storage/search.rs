@@ -0,0 +1,584 @@
+//! Index plein texte des archives
+//!
+//! Index inversé sur le titre, la description, les tags, l'hôte de l'URL
+//! d'origine et le texte du contenu archivé (débarrassé de son balisage HTML).
+//! Les textes sont découpés sur les caractères non alphanumériques et passés
+//! en minuscules ; un terme de requête correspond à tout terme indexé dont il
+//! est le préfixe, et un document doit correspondre à tous les termes de la
+//! requête. Un passage entre guillemets (`"fonte des glaces"`) doit apparaître
+//! tel quel, mots consécutifs, dans un même champ.
+//!
+//! Le score d'un document est la fréquence des termes trouvés, pondérée par
+//! champ (une correspondance par préfixe compte moitié, la fréquence dans le
+//! contenu est amortie), multipliée par un bonus logarithmique de popularité.
+//! Comme `ArchiveHistory`, l'index est générique sur l'identifiant des
+//! documents.
+
+use chrono::{DateTime, Utc};
+use std::collections::{BTreeMap, HashMap};
+use std::hash::Hash as StdHash;
+
+use super::ContentMetadata;
+
+/// Poids d'une occurrence dans le titre
+const TITLE_WEIGHT: f64 = 3.0;
+/// Poids d'une occurrence dans un tag
+const TAG_WEIGHT: f64 = 2.0;
+/// Poids d'une occurrence dans l'hôte de l'URL
+const HOST_WEIGHT: f64 = 2.0;
+/// Poids d'une occurrence dans la description
+const DESCRIPTION_WEIGHT: f64 = 1.0;
+/// Poids d'un terme du contenu, amorti par le logarithme de sa fréquence
+const BODY_WEIGHT: f64 = 1.0;
+/// Poids de chaque mot d'un passage exact trouvé
+const PHRASE_WEIGHT: f64 = 2.0;
+/// Part du poids retenue pour une correspondance par préfixe
+const PREFIX_MATCH_FACTOR: f64 = 0.5;
+
+/// Champs indexés d'un document
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchDocument {
+    pub title: Option<String>,
+    pub description: Option<String>,
+    pub tags: Vec<String>,
+    /// URL d'origine, dont seul l'hôte est indexé
+    pub url: Option<String>,
+    /// Texte du contenu archivé, voir `extract_text`
+    pub body: Option<String>,
+    /// Type MIME, utilisé par les filtres
+    pub content_type: String,
+    /// Date de création, utilisée par les filtres
+    pub created_at: DateTime<Utc>,
+    /// Popularité (accès/jour), utilisée par le classement
+    pub popularity: u64,
+}
+
+impl From<&ContentMetadata> for SearchDocument {
+    fn from(metadata: &ContentMetadata) -> Self {
+        Self {
+            title: metadata.title.clone(),
+            description: metadata.description.clone(),
+            tags: metadata.tags.clone(),
+            url: None,
+            body: None,
+            content_type: metadata.content_type.clone(),
+            created_at: metadata.created_at,
+            popularity: metadata.popularity,
+        }
+    }
+}
+
+/// Filtres appliqués aux documents trouvés
+#[derive(Debug, Clone, Default)]
+pub struct SearchFilter {
+    /// Type MIME exact
+    pub content_type: Option<String>,
+    /// Tous ces tags doivent être présents
+    pub tags: Vec<String>,
+    /// Création à cette date ou après
+    pub created_after: Option<DateTime<Utc>>,
+    /// Création à cette date ou avant
+    pub created_before: Option<DateTime<Utc>>,
+}
+
+impl SearchFilter {
+    /// Vérifie qu'un document satisfait les filtres
+    pub fn matches(&self, document: &SearchDocument) -> bool {
+        if self.content_type.as_ref().map_or(false, |ct| *ct != document.content_type) {
+            return false;
+        }
+        if !self.tags.iter().all(|tag| document.tags.contains(tag)) {
+            return false;
+        }
+        if self.created_after.map_or(false, |after| document.created_at < after) {
+            return false;
+        }
+        if self.created_before.map_or(false, |before| document.created_at > before) {
+            return false;
+        }
+        true
+    }
+}
+
+/// Document trouvé par une recherche
+#[derive(Debug, Clone, PartialEq)]
+pub struct SearchHit<Id> {
+    pub id: Id,
+    /// Score de pertinence, plus élevé pour les meilleurs résultats
+    pub score: f64,
+}
+
+#[derive(Debug, Clone)]
+struct IndexedDocument {
+    document: SearchDocument,
+    /// Poids de chaque terme du document, pour le retirer de l'index
+    term_weights: HashMap<String, f64>,
+    /// Termes du titre, de la description et du contenu, dans l'ordre, pour
+    /// la recherche de passages exacts
+    phrase_fields: Vec<Vec<String>>,
+}
+
+/// Index inversé des documents
+#[derive(Debug, Clone)]
+pub struct SearchIndex<Id> {
+    /// Terme -> poids du terme dans chaque document qui le contient
+    ///
+    /// Ordonné pour parcourir les termes ayant un préfixe donné.
+    postings: BTreeMap<String, HashMap<Id, f64>>,
+    documents: HashMap<Id, IndexedDocument>,
+}
+
+impl<Id> Default for SearchIndex<Id> {
+    fn default() -> Self {
+        Self {
+            postings: BTreeMap::new(),
+            documents: HashMap::new(),
+        }
+    }
+}
+
+/// Découpe un texte en termes minuscules
+pub fn tokenize(text: &str) -> Vec<String> {
+    text.split(|c: char| !c.is_alphanumeric())
+        .filter(|token| !token.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+/// Sépare les termes libres des passages entre guillemets d'une requête
+///
+/// Un guillemet non refermé s'étend jusqu'à la fin de la requête.
+pub fn parse_query(query: &str) -> (Vec<String>, Vec<Vec<String>>) {
+    let mut terms = Vec::new();
+    let mut phrases = Vec::new();
+    for (i, segment) in query.split('"').enumerate() {
+        if i % 2 == 0 {
+            terms.extend(tokenize(segment));
+        } else {
+            let phrase = tokenize(segment);
+            if !phrase.is_empty() {
+                phrases.push(phrase);
+            }
+        }
+    }
+    (terms, phrases)
+}
+
+/// Texte indexable d'un contenu archivé
+///
+/// Le HTML est débarrassé de son balisage, les autres contenus textuels sont
+/// indexés tels quels. Un type inconnu est deviné depuis le contenu ; les
+/// contenus binaires ne sont pas indexés.
+pub fn extract_text(content_type: &str, content: &[u8]) -> Option<String> {
+    let content_type = content_type.to_ascii_lowercase();
+    let text = std::str::from_utf8(content).ok();
+    let looks_like_html = || text.map_or(false, |text| {
+        let head: String = text.trim_start().chars().take(256).collect::<String>().to_ascii_lowercase();
+        head.starts_with("<!doctype html") || head.starts_with("<html") || head.contains("<body")
+    });
+
+    if content_type.contains("html") || (!content_type.starts_with("text/") && looks_like_html()) {
+        return Some(html_to_text(&String::from_utf8_lossy(content)));
+    }
+    let text = text?;
+    let textual = content_type.starts_with("text/")
+        || text.chars().all(|c| !c.is_control() || c.is_whitespace());
+    textual.then(|| text.to_string())
+}
+
+/// Retire le balisage d'une page HTML
+///
+/// Les balises sont remplacées par un espace, le contenu des éléments
+/// `script` et `style` et les commentaires sont ignorés, et les entités
+/// courantes sont décodées.
+pub fn html_to_text(html: &str) -> String {
+    let mut text = String::with_capacity(html.len() / 2);
+    let mut rest = html;
+
+    while let Some(start) = rest.find('<') {
+        text.push_str(&decode_entities(&rest[..start]));
+        text.push(' ');
+        rest = &rest[start..];
+
+        if let Some(comment) = rest.strip_prefix("<!--") {
+            rest = comment.find("-->").map_or("", |end| &comment[end + 3..]);
+            continue;
+        }
+
+        let Some(end) = rest.find('>') else {
+            rest = "";
+            break;
+        };
+        let tag = rest[1..end].trim_start().to_ascii_lowercase();
+        rest = &rest[end + 1..];
+
+        for raw in ["script", "style"] {
+            let opens_raw = tag.strip_prefix(raw)
+                .map_or(false, |after| after.is_empty() || after.starts_with(|c: char| c.is_whitespace() || c == '/'));
+            if opens_raw && !tag.ends_with('/') {
+                let closing = format!("</{}", raw);
+                rest = rest.to_ascii_lowercase().find(&closing)
+                    .and_then(|at| rest[at..].find('>').map(|gt| &rest[at + gt + 1..]))
+                    .unwrap_or("");
+            }
+        }
+    }
+    text.push_str(&decode_entities(rest));
+    text
+}
+
+/// Décode les entités HTML courantes
+fn decode_entities(text: &str) -> String {
+    if !text.contains('&') {
+        return text.to_string();
+    }
+    text.replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Bonus multiplicatif de popularité, 1.0 pour un contenu jamais consulté
+fn popularity_boost(popularity: u64) -> f64 {
+    1.0 + (popularity as f64).ln_1p() / 10.0
+}
+
+impl<Id: Clone + Eq + StdHash + Ord> SearchIndex<Id> {
+    /// Crée un index vide
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Indexe un document, en remplaçant sa version précédente
+    pub fn upsert(&mut self, id: Id, document: SearchDocument) {
+        let _span = tracing::debug_span!("search_index_upsert").entered();
+        self.remove(&id);
+
+        let mut term_weights: HashMap<String, f64> = HashMap::new();
+        let mut add = |text: &str, weight: f64| {
+            for term in tokenize(text) {
+                *term_weights.entry(term).or_insert(0.0) += weight;
+            }
+        };
+        if let Some(title) = &document.title {
+            add(title, TITLE_WEIGHT);
+        }
+        if let Some(description) = &document.description {
+            add(description, DESCRIPTION_WEIGHT);
+        }
+        for tag in &document.tags {
+            add(tag, TAG_WEIGHT);
+        }
+        if let Some(host) = document.url.as_deref()
+            .and_then(|url| url::Url::parse(url).ok())
+            .and_then(|url| url.host_str().map(str::to_string))
+        {
+            add(&host, HOST_WEIGHT);
+        }
+
+        let phrase_fields: Vec<Vec<String>> = [&document.title, &document.description, &document.body]
+            .into_iter()
+            .flatten()
+            .map(|text| tokenize(text))
+            .collect();
+
+        // Le contenu peut être long : sa fréquence est amortie
+        if let Some(body) = phrase_fields.last().filter(|_| document.body.is_some()) {
+            let mut counts: HashMap<&str, usize> = HashMap::new();
+            for term in body {
+                *counts.entry(term.as_str()).or_insert(0) += 1;
+            }
+            for (term, count) in counts {
+                *term_weights.entry(term.to_string()).or_insert(0.0) += BODY_WEIGHT * (1.0 + (count as f64).ln());
+            }
+        }
+
+        for (term, weight) in &term_weights {
+            self.postings.entry(term.clone()).or_default().insert(id.clone(), *weight);
+        }
+        self.documents.insert(id, IndexedDocument { document, term_weights, phrase_fields });
+    }
+
+    /// Retire un document de l'index
+    pub fn remove(&mut self, id: &Id) -> bool {
+        let Some(indexed) = self.documents.remove(id) else {
+            return false;
+        };
+
+        for term in indexed.term_weights.keys() {
+            if let Some(documents) = self.postings.get_mut(term) {
+                documents.remove(id);
+                if documents.is_empty() {
+                    self.postings.remove(term);
+                }
+            }
+        }
+        true
+    }
+
+    /// Met à jour la popularité d'un document indexé
+    pub fn set_popularity(&mut self, id: &Id, popularity: u64) -> bool {
+        match self.documents.get_mut(id) {
+            Some(indexed) => {
+                indexed.document.popularity = popularity;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Document indexé
+    pub fn get(&self, id: &Id) -> Option<&SearchDocument> {
+        self.documents.get(id).map(|indexed| &indexed.document)
+    }
+
+    /// Nombre de documents indexés
+    pub fn len(&self) -> usize {
+        self.documents.len()
+    }
+
+    /// Indique si l'index est vide
+    pub fn is_empty(&self) -> bool {
+        self.documents.is_empty()
+    }
+
+    /// Nombre de termes distincts indexés
+    pub fn term_count(&self) -> usize {
+        self.postings.len()
+    }
+
+    /// Poids cumulé des termes indexés ayant `prefix` pour préfixe, par document
+    fn prefix_matches(&self, prefix: &str) -> HashMap<&Id, f64> {
+        let mut matches: HashMap<&Id, f64> = HashMap::new();
+        for (term, documents) in self.postings.range::<str, _>(prefix..) {
+            if !term.starts_with(prefix) {
+                break;
+            }
+            let factor = if term == prefix { 1.0 } else { PREFIX_MATCH_FACTOR };
+            for (id, weight) in documents {
+                *matches.entry(id).or_insert(0.0) += weight * factor;
+            }
+        }
+        matches
+    }
+
+    /// Documents contenant `phrase` mot pour mot dans un même champ
+    fn phrase_matches(&self, phrase: &[String]) -> HashMap<&Id, f64> {
+        let Some(candidates) = phrase.first().and_then(|first| self.postings.get(first)) else {
+            return HashMap::new();
+        };
+
+        candidates.keys()
+            .filter_map(|id| {
+                let indexed = self.documents.get(id)?;
+                let occurrences: usize = indexed.phrase_fields.iter()
+                    .map(|tokens| tokens.windows(phrase.len()).filter(|window| *window == phrase).count())
+                    .sum();
+                (occurrences > 0).then(|| {
+                    (id, PHRASE_WEIGHT * phrase.len() as f64 * (1.0 + (occurrences as f64).ln()))
+                })
+            })
+            .collect()
+    }
+
+    /// Recherche les documents correspondant à tous les termes et passages de `query`
+    ///
+    /// Les résultats sont triés par score décroissant, puis par identifiant.
+    /// Une requête sans terme retourne tous les documents satisfaisant les
+    /// filtres, classés par popularité.
+    pub fn search(&self, query: &str, filter: &SearchFilter) -> Vec<SearchHit<Id>> {
+        let (mut terms, phrases) = parse_query(query);
+        terms.sort_unstable();
+        terms.dedup();
+
+        let mut criteria = terms.iter()
+            .map(|term| self.prefix_matches(term))
+            .chain(phrases.iter().map(|phrase| self.phrase_matches(phrase)));
+        let text_scores: HashMap<&Id, f64> = match criteria.next() {
+            None => self.documents.keys().map(|id| (id, 1.0)).collect(),
+            Some(first) => {
+                let mut scores = first;
+                for matches in criteria {
+                    if scores.is_empty() {
+                        break;
+                    }
+                    scores.retain(|id, score| match matches.get(id) {
+                        Some(weight) => {
+                            *score += weight;
+                            true
+                        }
+                        None => false,
+                    });
+                }
+                scores
+            }
+        };
+
+        let mut hits: Vec<SearchHit<Id>> = text_scores.into_iter()
+            .filter_map(|(id, text_score)| {
+                let document = &self.documents.get(id)?.document;
+                filter.matches(document).then(|| SearchHit {
+                    id: id.clone(),
+                    score: text_score * popularity_boost(document.popularity),
+                })
+            })
+            .collect();
+
+        hits.sort_by(|a, b| {
+            b.score.total_cmp(&a.score).then_with(|| a.id.cmp(&b.id))
+        });
+        hits
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn document(title: &str, description: &str, tags: &[&str], url: &str) -> SearchDocument {
+        SearchDocument {
+            title: Some(title.to_string()),
+            description: Some(description.to_string()),
+            tags: tags.iter().map(|tag| tag.to_string()).collect(),
+            url: Some(url.to_string()),
+            body: None,
+            content_type: "text/html".to_string(),
+            created_at: Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap(),
+            popularity: 0,
+        }
+    }
+
+    /// 1000 archives synthétiques réparties sur 4 sujets, 2 types et 10 mois
+    fn synthetic_index() -> SearchIndex<u32> {
+        let topics = ["climate", "election", "football", "astronomy"];
+        let mut index = SearchIndex::new();
+        for i in 0..1000u32 {
+            let topic = topics[i as usize % topics.len()];
+            let mut doc = document(
+                &format!("Report {} on {}", i, topic),
+                &format!("Archived page number {} about {}", i, topic),
+                &[topic, if i % 2 == 0 { "even" } else { "odd" }],
+                &format!("https://site{}.example.org/{}", i % 10, i),
+            );
+            doc.content_type = if i % 5 == 0 { "application/pdf" } else { "text/html" }.to_string();
+            doc.created_at = Utc.with_ymd_and_hms(2024, 1 + (i % 10), 15, 0, 0, 0).unwrap();
+            doc.popularity = u64::from(i % 7);
+            index.upsert(i, doc);
+        }
+        index
+    }
+
+    #[test]
+    fn test_tokenize_folds_case_and_splits_punctuation() {
+        assert_eq!(tokenize("Hello, World! rust-lang 2024"), vec!["hello", "world", "rust", "lang", "2024"]);
+        assert!(tokenize(" -- ").is_empty());
+    }
+
+    #[test]
+    fn test_synthetic_archives_multi_term_ranking() {
+        let mut index = synthetic_index();
+        assert_eq!(index.len(), 1000);
+
+        // Tous les termes sont requis ; les préfixes correspondent
+        let hits = index.search("Clim REPORT", &SearchFilter::default());
+        assert_eq!(hits.len(), 250);
+        assert!(hits.iter().all(|hit| hit.id % 4 == 0));
+        assert!(index.search("climate football", &SearchFilter::default()).is_empty());
+
+        // À texte égal, la popularité départage
+        assert_eq!(index.get(&hits[0].id).unwrap().popularity, 6);
+        assert!(hits.windows(2).all(|pair| pair[0].score >= pair[1].score));
+
+        // Un terme présent dans le titre et dans un tag passe devant un terme
+        // présent seulement dans la description
+        index.upsert(2000, document("Solar eclipse", "", &["eclipse"], "https://sky.example.net"));
+        index.upsert(2001, document("Night sky", "A total eclipse photographed", &[], "https://sky.example.net"));
+        let hits = index.search("eclipse", &SearchFilter::default());
+        assert_eq!(hits.iter().map(|hit| hit.id).collect::<Vec<_>>(), vec![2000, 2001]);
+        let hits = index.search("night eclipse", &SearchFilter::default());
+        assert_eq!(hits.iter().map(|hit| hit.id).collect::<Vec<_>>(), vec![2001]);
+
+        // L'hôte de l'URL est indexé
+        assert_eq!(index.search("site3", &SearchFilter::default()).len(), 100);
+
+        // Réindexer remplace les anciens termes
+        index.upsert(2000, document("Lunar", "", &[], "https://moon.example.net"));
+        assert_eq!(index.search("eclipse", &SearchFilter::default()).len(), 1);
+        assert!(index.remove(&2001));
+        assert!(index.search("eclipse", &SearchFilter::default()).is_empty());
+    }
+
+    #[test]
+    fn test_synthetic_archives_filters() {
+        let index = synthetic_index();
+
+        let pdf = SearchFilter {
+            content_type: Some("application/pdf".to_string()),
+            ..SearchFilter::default()
+        };
+        let hits = index.search("report", &pdf);
+        assert_eq!(hits.len(), 200);
+        assert!(hits.iter().all(|hit| hit.id % 5 == 0));
+
+        let tagged = SearchFilter {
+            tags: vec!["football".to_string(), "even".to_string()],
+            ..SearchFilter::default()
+        };
+        let hits = index.search("", &tagged);
+        assert_eq!(hits.len(), 250);
+        assert!(hits.iter().all(|hit| hit.id % 4 == 2));
+
+        // Mars à mai inclus
+        let spring = SearchFilter {
+            created_after: Some(Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap()),
+            created_before: Some(Utc.with_ymd_and_hms(2024, 5, 15, 0, 0, 0).unwrap()),
+            ..SearchFilter::default()
+        };
+        let hits = index.search("astronomy", &spring);
+        assert!(hits.iter().all(|hit| (2..=4).contains(&(hit.id % 10)) && hit.id % 4 == 3));
+        assert_eq!(hits.len(), 50);
+    }
+
+    #[test]
+    fn test_body_text_and_phrase_queries() {
+        let html = "<html><head><title>T</title><style>p { color: red }</style>\
+            <script>var hidden = 'glacier';</script></head>\
+            <body><!-- glacier --><p>La fonte des glaces &amp; le niveau des mers</p></body></html>";
+        let text = extract_text("text/html; charset=utf-8", html.as_bytes()).unwrap();
+        assert!(text.contains("La fonte des glaces & le niveau des mers"));
+        assert!(!text.contains("glacier") && !text.contains("color"));
+        assert_eq!(extract_text("application/octet-stream", html.as_bytes()), Some(text.clone()));
+        assert_eq!(extract_text("unknown", b"plain notes"), Some("plain notes".to_string()));
+        assert!(extract_text("image/png", &[0x89, b'P', b'N', b'G', 0, 1, 2]).is_none());
+
+        let mut index = SearchIndex::new();
+        let mut adjacent = document("Climat", "", &[], "https://a.example.org");
+        adjacent.body = Some(text);
+        let mut scattered = document("Climat", "", &[], "https://b.example.org");
+        scattered.body = Some("Les glaces du pôle et la fonte du printemps".to_string());
+        let mut repeated = document("Climat", "", &[], "https://c.example.org");
+        repeated.body = Some("fonte des glaces, encore la fonte des glaces".to_string());
+        index.upsert(1u32, adjacent);
+        index.upsert(2, scattered);
+        index.upsert(3, repeated);
+
+        // Les termes libres se trouvent dans le contenu, dans n'importe quel ordre
+        let hits = index.search("glaces fonte", &SearchFilter::default());
+        assert_eq!(hits.len(), 3);
+
+        // Un passage exige des mots consécutifs ; les occurrences répétées classent devant
+        let hits = index.search("\"fonte des glaces\"", &SearchFilter::default());
+        assert_eq!(hits.iter().map(|hit| hit.id).collect::<Vec<_>>(), vec![3, 1]);
+        let hits = index.search("climat \"niveau des mers\"", &SearchFilter::default());
+        assert_eq!(hits.iter().map(|hit| hit.id).collect::<Vec<_>>(), vec![1]);
+        assert!(index.search("\"glaces fonte\"", &SearchFilter::default()).is_empty());
+
+        // Le passage est retiré avec le document
+        assert!(index.remove(&3));
+        assert_eq!(index.search("\"fonte des glaces\"", &SearchFilter::default()).len(), 1);
+    }
+}
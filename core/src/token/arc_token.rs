@@ -137,6 +137,24 @@ impl ARCToken {
         Ok(())
     }
 
+    /// Mint une récompense et émet `RewardDistributed`
+    pub fn mint_reward(&mut self, to: &PublicKey, amount: u64, reward_type: &str, tx_hash: Hash) -> TokenResult<()> {
+        self.mint(to, amount, tx_hash.clone())?;
+
+        self.emit_event(TokenEvent {
+            transaction_hash: tx_hash,
+            event_type: TokenEventType::RewardDistributed {
+                to: to.clone(),
+                amount,
+                reward_type: reward_type.to_string(),
+            },
+            timestamp: Utc::now(),
+            data: HashMap::new(),
+        });
+
+        Ok(())
+    }
+
     /// Transfère des tokens entre deux adresses
     pub fn transfer(&mut self, from: &PublicKey, to: &PublicKey, amount: u64, tx_hash: Hash) -> TokenResult<()> {
         self.internal_transfer(from, to, amount, tx_hash)
@@ -288,6 +306,34 @@ impl ARCToken {
         Ok(())
     }
 
+    /// Brûle des tokens verrouillés suite au slashing d'un stake
+    ///
+    /// Les tokens sortent du montant verrouillé sans repasser par la circulation.
+    pub fn slash_locked_tokens(&mut self, from: &PublicKey, amount: u64, reason: &str, tx_hash: Hash) -> TokenResult<()> {
+        if self.locked_tokens < amount {
+            return Err(TokenError::Internal {
+                message: "Pas assez de tokens verrouillés".to_string(),
+            });
+        }
+
+        self.locked_tokens -= amount;
+        self.burned_tokens += amount;
+        self.last_updated = Utc::now();
+
+        self.emit_event(TokenEvent {
+            transaction_hash: tx_hash,
+            event_type: TokenEventType::Slashed {
+                validator: from.clone(),
+                amount,
+                reason: reason.to_string(),
+            },
+            timestamp: Utc::now(),
+            data: HashMap::new(),
+        });
+
+        Ok(())
+    }
+
     /// Obtient les statistiques globales du token
     pub fn get_statistics(&self) -> TokenStatistics {
         TokenStatistics {
@@ -353,6 +399,8 @@ impl ARCToken {
                 TokenEventType::Unstaked { staker, .. } => staker == address,
                 TokenEventType::ProposalCreated { proposer, .. } => proposer == address,
                 TokenEventType::ProposalVoted { voter, .. } => voter == address,
+                TokenEventType::ProposalExecuted { recipient, .. } => recipient == address,
+                TokenEventType::Slashed { validator, .. } => validator == address,
             }
         }).collect()
     }
@@ -0,0 +1,147 @@
+//! Journal des livraisons de contenu pour ArchiveChain
+//!
+//! Deux sources alimentent le journal :
+//! - l'API consigne chaque requête de contenu qu'elle a effectivement servie
+//!   (identifiant de requête, demandeur, contenu, nœud source, volume) ;
+//! - les nœuds de stockage consignent les livraisons qu'ils revendiquent, en
+//!   citant l'identifiant de la requête servie, propagé par le message P2P.
+//!
+//! Seule une livraison adossée à une requête de l'API est rémunérable : un
+//! nœud ne peut pas facturer du trafic qu'il se serait envoyé à lui-même.
+//! Les gateways ne figurent pas dans ce journal : leurs livraisons depuis le
+//! cache sont rémunérées par la preuve de bande passante.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, VecDeque};
+use chrono::{DateTime, Utc};
+use crate::consensus::NodeId;
+use crate::crypto::{Hash, PublicKey};
+
+/// Nombre maximal de requêtes et de livraisons en attente de règlement ;
+/// au-delà, les plus anciennes sont oubliées
+pub const MAX_PENDING_DELIVERIES: usize = 100_000;
+
+/// Requête de contenu servie par l'API
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ServedRequest {
+    /// Contenu demandé
+    pub content_hash: Hash,
+    /// Utilisateur à l'origine de la requête
+    pub requester: String,
+    /// Nœud ayant fourni le contenu
+    pub node_id: NodeId,
+    /// Volume remis au demandeur (bytes)
+    pub bytes: u64,
+    pub timestamp: DateTime<Utc>,
+}
+
+/// Livraison revendiquée par un nœud
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct DeliveryEntry {
+    /// Clé de l'opérateur, bénéficiaire de la récompense
+    pub operator: PublicKey,
+    pub content_hash: Hash,
+    /// Volume livré (bytes)
+    pub bytes: u64,
+    /// Identifiant de la requête API servie
+    pub request_id: String,
+    pub timestamp: DateTime<Utc>,
+}
+
+impl DeliveryEntry {
+    /// Nœud de l'opérateur
+    pub fn node_id(&self) -> NodeId {
+        NodeId::from_public_key(&self.operator)
+    }
+}
+
+/// Journal des livraisons en attente de règlement
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DeliveryLog {
+    /// Requêtes servies par l'API, par identifiant
+    requests: HashMap<String, ServedRequest>,
+    /// Identifiants des requêtes, de la plus ancienne à la plus récente
+    #[serde(default)]
+    request_order: VecDeque<String>,
+    /// Livraisons revendiquées, dans l'ordre d'arrivée
+    entries: Vec<DeliveryEntry>,
+}
+
+impl DeliveryLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Consigne une requête servie par l'API
+    pub fn record_request(&mut self, request_id: impl Into<String>, request: ServedRequest) {
+        let request_id = request_id.into();
+        if self.requests.insert(request_id.clone(), request).is_none() {
+            self.request_order.push_back(request_id);
+        }
+        while self.requests.len() > MAX_PENDING_DELIVERIES {
+            let Some(oldest) = self.request_order.pop_front() else { break };
+            self.requests.remove(&oldest);
+        }
+        // Les identifiants déjà réglés ou oubliés ne sont retirés qu'ici
+        if self.request_order.len() > 2 * MAX_PENDING_DELIVERIES {
+            self.compact_order();
+        }
+    }
+
+    /// Consigne une livraison revendiquée par un nœud
+    pub fn record(&mut self, entry: DeliveryEntry) {
+        if self.entries.len() >= MAX_PENDING_DELIVERIES {
+            self.entries.remove(0);
+        }
+        self.entries.push(entry);
+    }
+
+    /// Nombre de requêtes servies en attente de règlement
+    pub fn pending_requests(&self) -> usize {
+        self.requests.len()
+    }
+
+    /// Livraisons en attente de règlement
+    pub fn entries(&self) -> &[DeliveryEntry] {
+        &self.entries
+    }
+
+    /// Volume rémunérable d'une livraison, `None` si elle est invérifiable
+    ///
+    /// La requête citée doit avoir été servie par l'API pour ce contenu et ce
+    /// nœud ; le volume retenu est borné par celui que l'API
+    /// a effectivement remis.
+    pub fn verified_bytes(&self, entry: &DeliveryEntry) -> Option<u64> {
+        let request = self.requests.get(&entry.request_id)?;
+        let matches = request.content_hash == entry.content_hash
+            && request.node_id == entry.node_id();
+        matches.then(|| entry.bytes.min(request.bytes))
+    }
+
+    /// Retire les livraisons antérieures ou égales à `until`
+    pub fn take_until(&mut self, until: DateTime<Utc>) -> Vec<DeliveryEntry> {
+        let (taken, kept) = std::mem::take(&mut self.entries)
+            .into_iter()
+            .partition(|entry| entry.timestamp <= until);
+        self.entries = kept;
+        taken
+    }
+
+    /// Retire une requête réglée : elle ne peut plus justifier de livraison
+    pub fn settle_request(&mut self, request_id: &str) -> Option<ServedRequest> {
+        self.requests.remove(request_id)
+    }
+
+    /// Oublie les requêtes servies avant `before` sans livraison revendiquée
+    pub fn prune_requests(&mut self, before: DateTime<Utc>) -> usize {
+        let count = self.requests.len();
+        self.requests.retain(|_, request| request.timestamp >= before);
+        self.compact_order();
+        count - self.requests.len()
+    }
+
+    fn compact_order(&mut self) {
+        let requests = &self.requests;
+        self.request_order.retain(|request_id| requests.contains_key(request_id));
+    }
+}
@@ -8,11 +8,11 @@
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
-use chrono::{DateTime, Utc, Duration};
+use chrono::{DateTime, Datelike, Months, Utc, Duration};
 use crate::crypto::{Hash, PublicKey};
 use super::{
     TokenOperationResult, TokenOperationError, ARCHIVAL_REWARDS_ALLOCATION, 
-    TEAM_ALLOCATION, COMMUNITY_RESERVE, PUBLIC_SALE, ARCToken
+    TEAM_ALLOCATION, COMMUNITY_RESERVE, PUBLIC_SALE, ARCToken, TokenConfig
 };
 
 /// Gestionnaire de distribution des tokens
@@ -58,6 +58,9 @@ pub struct TeamAllocation {
     pub total_allocation: u64,
     /// Montant déjà distribué
     pub distributed_amount: u64,
+    /// Montant cumulé effectivement réclamé par les bénéficiaires
+    #[serde(default)]
+    pub claimed_amount: u64,
     /// Schedule de vesting par bénéficiaire
     pub vesting_schedules: HashMap<PublicKey, VestingSchedule>,
     /// Date de début du vesting
@@ -123,6 +126,91 @@ pub struct VestingSchedule {
     pub last_claim_date: Option<DateTime<Utc>>,
 }
 
+impl VestingSchedule {
+    /// Montant total acquis (vested) à une date donnée
+    ///
+    /// Rien n'est acquis avant le cliff ; au cliff, les mois écoulés depuis le
+    /// début sont libérés d'un coup, puis l'acquisition est linéaire mois par
+    /// mois jusqu'à la date de fin.
+    pub fn vested_at(&self, now: DateTime<Utc>) -> u64 {
+        if now < self.cliff_date {
+            return 0;
+        }
+        if now >= self.end_date {
+            return self.total_allocation;
+        }
+
+        let total_months = elapsed_months(self.start_date, self.end_date).max(1);
+        let months = elapsed_months(self.start_date, now).min(total_months);
+
+        (self.total_allocation as u128 * months as u128 / total_months as u128) as u64
+    }
+
+    /// Montant réclamable à une date donnée (acquis moins déjà réclamé)
+    pub fn claimable_amount(&self, at: DateTime<Utc>) -> u64 {
+        self.vested_at(at).saturating_sub(self.claimed_amount)
+    }
+
+    /// État du vesting à une date donnée
+    pub fn status_at(&self, at: DateTime<Utc>) -> VestingStatus {
+        let unlocked = self.vested_at(at);
+        VestingStatus {
+            total_allocation: self.total_allocation,
+            unlocked,
+            claimed: self.claimed_amount,
+            claimable: unlocked.saturating_sub(self.claimed_amount),
+            remaining: self.total_allocation.saturating_sub(self.claimed_amount),
+            next_unlock_date: self.next_unlock_date(at),
+        }
+    }
+
+    /// Prochaine date à laquelle de nouveaux tokens seront acquis
+    fn next_unlock_date(&self, at: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        if at >= self.end_date {
+            return None;
+        }
+        if at < self.cliff_date {
+            return Some(self.cliff_date);
+        }
+        let next = self.start_date + Months::new(elapsed_months(self.start_date, at) + 1);
+        Some(next.min(self.end_date))
+    }
+}
+
+/// État du vesting d'un bénéficiaire : acquis, réclamé et restant
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct VestingStatus {
+    /// Allocation totale
+    pub total_allocation: u64,
+    /// Montant acquis (débloqué) à la date demandée
+    pub unlocked: u64,
+    /// Montant déjà réclamé
+    pub claimed: u64,
+    /// Montant acquis non encore réclamé
+    pub claimable: u64,
+    /// Allocation non encore réclamée, acquise ou non
+    pub remaining: u64,
+    /// Prochain déblocage (None une fois le vesting terminé)
+    pub next_unlock_date: Option<DateTime<Utc>>,
+}
+
+/// Nombre de mois calendaires complets écoulés entre deux dates
+fn elapsed_months(start: DateTime<Utc>, now: DateTime<Utc>) -> u32 {
+    if now <= start {
+        return 0;
+    }
+
+    let mut months = (now.year() - start.year()) * 12 + now.month() as i32 - start.month() as i32;
+    if months > 0 {
+        let anniversary = start.checked_add_months(Months::new(months as u32));
+        if anniversary.map_or(true, |date| date > now) {
+            months -= 1;
+        }
+    }
+
+    months.max(0) as u32
+}
+
 /// Proposition financée par la réserve communautaire
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct FundedProposal {
@@ -232,6 +320,11 @@ pub enum DistributionError {
 impl TokenDistribution {
     /// Crée une nouvelle distribution avec les allocations par défaut
     pub fn new() -> Self {
+        Self::from_config(&TokenConfig::default())
+    }
+
+    /// Crée une distribution dont le vesting équipe suit `team_vesting_years`
+    pub fn from_config(config: &TokenConfig) -> Self {
         let now = Utc::now();
         
         Self {
@@ -247,10 +340,11 @@ impl TokenDistribution {
             team_allocation: TeamAllocation {
                 total_allocation: TEAM_ALLOCATION,
                 distributed_amount: 0,
+                claimed_amount: 0,
                 vesting_schedules: HashMap::new(),
                 start_date: now,
                 cliff_duration_months: 12, // 1 an de cliff
-                total_vesting_months: 48,  // 4 ans total
+                total_vesting_months: config.team_vesting_years * 12, // 4 ans par défaut
             },
             community_reserve: CommunityReserve {
                 total_allocation: COMMUNITY_RESERVE,
@@ -286,13 +380,14 @@ impl TokenDistribution {
         }
 
         let start_date = self.team_allocation.start_date;
-        let cliff_date = start_date + Duration::days(365); // 1 an de cliff
-        let end_date = start_date + Duration::days(365 * 4); // 4 ans total
+        let cliff_months = self.team_allocation.cliff_duration_months;
+        let total_months = self.team_allocation.total_vesting_months.max(cliff_months).max(1);
+        let cliff_date = start_date + Months::new(cliff_months);
+        let end_date = start_date + Months::new(total_months);
         
-        // 25% disponible au cliff, 75% distribué mensuellement sur 3 ans
-        let cliff_amount = allocation / 4;
-        let remaining_amount = allocation - cliff_amount;
-        let monthly_release = remaining_amount / 36; // 36 mois après cliff
+        // Les mois du cliff sont libérés d'un coup, puis 1/total_months par mois
+        let cliff_amount = (allocation as u128 * cliff_months as u128 / total_months as u128) as u64;
+        let monthly_release = allocation / total_months as u64;
 
         let schedule = VestingSchedule {
             beneficiary: beneficiary.clone(),
@@ -320,52 +415,67 @@ impl TokenDistribution {
                 message: "Schedule de vesting non trouvé".to_string(),
             })?;
 
-        let now = Utc::now();
-        
-        // Vérifier si le cliff est atteint
+        Ok(schedule.claimable_amount(Utc::now()))
+    }
+
+    /// État du vesting d'un bénéficiaire à la date `at`
+    pub fn vesting_status(&self, beneficiary: &PublicKey, at: DateTime<Utc>) -> TokenOperationResult<VestingStatus> {
+        let schedule = self.team_allocation.vesting_schedules.get(beneficiary)
+            .ok_or_else(|| TokenOperationError::Internal {
+                message: "Schedule de vesting non trouvé".to_string(),
+            })?;
+
+        Ok(schedule.status_at(at))
+    }
+
+    /// Réclame les tokens équipe nouvellement acquis à la date `now`
+    ///
+    /// Seul le montant acquis depuis le dernier claim est minté ; un second
+    /// claim dans la même période retourne 0. Le total réclamé ne peut jamais
+    /// dépasser l'allocation du bénéficiaire.
+    pub fn claim_vested(
+        &mut self,
+        beneficiary: &PublicKey,
+        now: DateTime<Utc>,
+        token: &mut ARCToken,
+        tx_hash: Hash,
+    ) -> TokenOperationResult<u64> {
+        let schedule = self.team_allocation.vesting_schedules.get_mut(beneficiary)
+            .ok_or_else(|| TokenOperationError::Internal {
+                message: "Schedule de vesting non trouvé".to_string(),
+            })?;
+
         if now < schedule.cliff_date {
+            return Err(TokenOperationError::VestingPeriodNotReached);
+        }
+
+        let claimable = schedule.claimable_amount(now)
+            .min(schedule.total_allocation.saturating_sub(schedule.claimed_amount));
+        if claimable == 0 {
             return Ok(0);
         }
 
-        let mut vested_amount = schedule.cliff_amount;
+        token.mint(beneficiary, claimable, tx_hash)?;
 
-        // Calculer les tokens vested mensuellement depuis le cliff
-        if now > schedule.cliff_date {
-            let months_since_cliff = (now - schedule.cliff_date).num_days() / 30;
-            let months_since_cliff = months_since_cliff.min(36) as u64; // Max 36 mois
-            vested_amount += months_since_cliff * schedule.monthly_release;
-        }
+        schedule.claimed_amount += claimable;
+        schedule.last_claim_date = Some(now);
+        self.team_allocation.claimed_amount += claimable;
+        self.last_updated = Utc::now();
 
-        // Ne peut pas dépasser l'allocation totale
-        vested_amount = vested_amount.min(schedule.total_allocation);
-        
-        // Soustraire ce qui a déjà été réclamé
-        let available = vested_amount.saturating_sub(schedule.claimed_amount);
-        
-        Ok(available)
+        Ok(claimable)
     }
 
-    /// Effectue un claim de vesting pour un bénéficiaire
+    /// Effectue un claim de vesting pour un bénéficiaire à la date courante
     pub fn claim_vested_tokens(&mut self, beneficiary: &PublicKey, token: &mut ARCToken, tx_hash: Hash) -> TokenOperationResult<u64> {
-        let available_amount = self.calculate_vested_amount(beneficiary)?;
-        
-        if available_amount == 0 {
+        let claimed = self.claim_vested(beneficiary, Utc::now(), token, tx_hash)?;
+
+        if claimed == 0 {
             return Err(TokenOperationError::Internal {
                 message: "Aucun token disponible pour le claim".to_string(),
             });
         }
 
-        // Mint les tokens vers le bénéficiaire
-        token.mint(beneficiary, available_amount, tx_hash)?;
-
-        // Mettre à jour le schedule
-        if let Some(schedule) = self.team_allocation.vesting_schedules.get_mut(beneficiary) {
-            schedule.claimed_amount += available_amount;
-            schedule.last_claim_date = Some(Utc::now());
-        }
-
-        self.last_updated = Utc::now();
-        Ok(available_amount)
+        Ok(claimed)
     }
 
     /// Distribue des récompenses d'archivage
@@ -565,6 +675,101 @@ mod tests {
         assert!(vested > 0); // Devrait avoir des tokens vested après le cliff
     }
 
+    #[test]
+    fn test_team_vesting_follows_token_config() {
+        let config = TokenConfig {
+            team_vesting_years: 2,
+            ..TokenConfig::default()
+        };
+        let distribution = TokenDistribution::from_config(&config);
+        assert_eq!(distribution.team_allocation.total_vesting_months, 24);
+    }
+
+    #[test]
+    fn test_team_claims_over_four_years() {
+        use chrono::TimeZone;
+
+        let mut distribution = TokenDistribution::new();
+        let mut token = ARCToken::new();
+        let beneficiary = generate_keypair().unwrap().public_key().clone();
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let month = |m: u32| start + Months::new(m);
+
+        distribution.team_allocation.start_date = start;
+        distribution.add_team_vesting(beneficiary.clone(), 48_000_000).unwrap();
+
+        // Avant le cliff
+        let result = distribution.claim_vested(&beneficiary, month(11), &mut token, Hash::zero());
+        assert!(matches!(result, Err(TokenOperationError::VestingPeriodNotReached)));
+
+        // Au cliff : 12 mois sur 48
+        assert_eq!(distribution.claim_vested(&beneficiary, month(12), &mut token, Hash::zero()).unwrap(), 12_000_000);
+        // Double claim dans la même période
+        assert_eq!(distribution.claim_vested(&beneficiary, month(12) + Duration::days(3), &mut token, Hash::zero()).unwrap(), 0);
+
+        // Mi-parcours : 24 mois sur 48
+        assert_eq!(distribution.claim_vested(&beneficiary, month(24), &mut token, Hash::zero()).unwrap(), 12_000_000);
+
+        // Acquisition complète
+        assert_eq!(distribution.claim_vested(&beneficiary, month(48), &mut token, Hash::zero()).unwrap(), 24_000_000);
+        assert_eq!(distribution.claim_vested(&beneficiary, month(60), &mut token, Hash::zero()).unwrap(), 0);
+
+        assert_eq!(token.balance_of(&beneficiary), 48_000_000);
+        assert_eq!(distribution.team_allocation.claimed_amount, 48_000_000);
+        let schedule = &distribution.team_allocation.vesting_schedules[&beneficiary];
+        assert_eq!(schedule.claimed_amount, schedule.total_allocation);
+    }
+
+    #[test]
+    fn test_vesting_status_reports_unlocked_claimed_and_remaining() {
+        use chrono::TimeZone;
+
+        let mut distribution = TokenDistribution::new();
+        let mut token = ARCToken::new();
+        let beneficiary = generate_keypair().unwrap().public_key().clone();
+        let start = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        distribution.team_allocation.start_date = start;
+        distribution.add_team_vesting(beneficiary.clone(), 48_000_000).unwrap();
+
+        let status = distribution.vesting_status(&beneficiary, start + Months::new(6)).unwrap();
+        assert_eq!((status.unlocked, status.claimed, status.remaining), (0, 0, 48_000_000));
+        assert_eq!(status.next_unlock_date, Some(start + Months::new(12)));
+
+        distribution.claim_vested(&beneficiary, start + Months::new(12), &mut token, Hash::zero()).unwrap();
+
+        // 18 mois acquis, 12 déjà réclamés
+        let status = distribution.vesting_status(&beneficiary, start + Months::new(18)).unwrap();
+        assert_eq!(status.unlocked, 18_000_000);
+        assert_eq!(status.claimed, 12_000_000);
+        assert_eq!(status.claimable, 6_000_000);
+        assert_eq!(status.remaining, 36_000_000);
+        assert_eq!(status.next_unlock_date, Some(start + Months::new(19)));
+
+        // Seule la part acquise non réclamée est versée
+        assert_eq!(distribution.claim_vested(&beneficiary, start + Months::new(18), &mut token, Hash::zero()).unwrap(), 6_000_000);
+        let status = distribution.vesting_status(&beneficiary, start + Months::new(48)).unwrap();
+        assert_eq!(status.claimable, 30_000_000);
+        assert_eq!(status.next_unlock_date, None);
+    }
+
+    #[test]
+    fn test_vesting_schedule_monthly_steps() {
+        use chrono::TimeZone;
+
+        let mut distribution = TokenDistribution::new();
+        let beneficiary = generate_keypair().unwrap().public_key().clone();
+        let start = Utc.with_ymd_and_hms(2024, 1, 31, 12, 0, 0).unwrap();
+        distribution.team_allocation.start_date = start;
+        distribution.add_team_vesting(beneficiary.clone(), 4_800).unwrap();
+
+        let schedule = &distribution.team_allocation.vesting_schedules[&beneficiary];
+        assert_eq!(schedule.claimable_amount(start + Months::new(12) - Duration::seconds(1)), 0);
+        assert_eq!(schedule.claimable_amount(start + Months::new(12)), 1_200);
+        assert_eq!(schedule.claimable_amount(start + Months::new(13) - Duration::seconds(1)), 1_200);
+        assert_eq!(schedule.claimable_amount(start + Months::new(13)), 1_300);
+        assert_eq!(schedule.claimable_amount(start + Months::new(100)), 4_800);
+    }
+
     #[test]
     fn test_community_proposal_funding() {
         let mut distribution = TokenDistribution::new();
@@ -11,11 +11,13 @@ use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use chrono::{DateTime, Utc, Duration};
 use crate::crypto::{Hash, PublicKey};
+use crate::consensus::ChallengeAuditEntry;
 use super::{
     TokenOperationResult, TokenOperationError, ARCToken, TokenDistribution,
     RewardSystem, StakingSystem, Treasury, DeflationaryMechanisms,
     TOTAL_SUPPLY, GlobalTokenMetrics, TokenConfig
 };
+use super::staking::SlashingEvent;
 
 /// Modèle économique principal d'ArchiveChain
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -431,7 +433,7 @@ impl EconomicModel {
         
         Self {
             token: ARCToken::new(),
-            distribution: TokenDistribution::new(),
+            distribution: TokenDistribution::from_config(&config.token_config),
             rewards: RewardSystem::new(
                 super::ARCHIVAL_REWARDS_ALLOCATION,
                 super::rewards::RewardConfig::default(),
@@ -514,6 +516,17 @@ impl EconomicModel {
         Ok(())
     }
 
+    /// Slashe les validateurs ayant manqué trop de défis de stockage
+    ///
+    /// Les métriques globales (tokens brûlés et stakés) sont recalculées ensuite.
+    pub fn apply_storage_challenge_outcomes(&mut self, entries: &[ChallengeAuditEntry], tx_hash: Hash) -> TokenOperationResult<Vec<SlashingEvent>> {
+        let events = self.staking.apply_challenge_outcomes(entries, &mut self.token, tx_hash)?;
+        if !events.is_empty() {
+            self.update_all_metrics()?;
+        }
+        Ok(events)
+    }
+
     /// Calcule les métriques dérivées complexes
     fn calculate_derived_metrics(&mut self) {
         // Vélocité des tokens (approximation)
@@ -968,4 +981,31 @@ mod tests {
         // Les ajustements peuvent être vides si aucun n'est nécessaire
         assert!(adjustments.len() >= 0);
     }
+
+    #[test]
+    fn test_storage_challenge_slashing_updates_global_metrics() {
+        use crate::consensus::{ChallengeOutcome, NodeId};
+
+        let mut model = EconomicModel::default();
+        let validator = crate::crypto::generate_keypair().unwrap().public_key().clone();
+        model.token.mint(&validator, 10_000_000, Hash::zero()).unwrap();
+        model.staking.create_validator_stake(validator.clone(), 10_000_000, 0.05, &mut model.token, Hash::zero()).unwrap();
+
+        let now = Utc::now();
+        let entries: Vec<ChallengeAuditEntry> = (0..4u8).map(|i| ChallengeAuditEntry {
+            node_id: NodeId::from_public_key(&validator),
+            challenge_id: Hash::from_bytes(&[i; 32]).unwrap(),
+            archive_hash: Hash::zero(),
+            nonce: i as u64,
+            issued_at: now,
+            recorded_at: now,
+            outcome: ChallengeOutcome::Failed,
+            response_latency_ms: None,
+        }).collect();
+
+        let events = model.apply_storage_challenge_outcomes(&entries, Hash::zero()).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(model.metrics.token_metrics.total_burned, events[0].slashed_amount);
+        assert_eq!(model.metrics.token_metrics.total_staked, 10_000_000 - events[0].slashed_amount);
+    }
 }
\ No newline at end of file
@@ -9,21 +9,27 @@
 //! - Treasury communautaire
 
 pub mod arc_token;
+pub mod delivery;
 pub mod distribution;
 pub mod economics;
 pub mod rewards;
 pub mod staking;
 pub mod treasury;
 pub mod deflation;
+#[cfg(feature = "economic-simulation")]
+pub mod simulation;
 
 // Re-exports principaux
 pub use arc_token::{ARCToken, TokenError, TokenResult};
-pub use distribution::{TokenDistribution, VestingSchedule, DistributionError};
+pub use distribution::{TokenDistribution, VestingSchedule, VestingStatus, DistributionError};
 pub use economics::{EconomicModel, EconomicMetrics, RewardCalculation};
-pub use rewards::{RewardSystem, RewardPool, RewardType, RewardDistribution};
-pub use staking::{StakingSystem, StakeInfo, GovernanceStake, ValidatorStake};
+pub use rewards::{RewardSystem, RewardPool, RewardType, RewardDistribution, ArchivedContentLookup, DiscoveryClaim, DiscoveryClaimStatus, BandwidthStatement, DeliverySettlement, DeliveryRateConfig};
+pub use delivery::{DeliveryLog, DeliveryEntry, ServedRequest, MAX_PENDING_DELIVERIES};
+pub use staking::{StakingSystem, StakeInfo, GovernanceStake, ValidatorStake, SlashingEvent, SlashReason, SlashingConfig};
 pub use treasury::{Treasury, TreasuryProposal, ProposalStatus};
 pub use deflation::{DeflationaryMechanisms, BurnRecord, LongtermBonusRecord};
+#[cfg(feature = "economic-simulation")]
+pub use simulation::{SimulationParams, SimulationReport, SimulationMonth};
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
@@ -102,6 +108,30 @@ pub enum TokenOperationError {
     
     #[error("Proposition de governance non trouvée : {proposal_id}")]
     ProposalNotFound { proposal_id: Hash },
+
+    #[error("Proposition déjà exécutée : {proposal_id}")]
+    ProposalAlreadyExecuted { proposal_id: Hash },
+
+    #[error("Fonds du treasury insuffisants : requis {required}, disponible {available}")]
+    InsufficientTreasuryFunds { required: u64, available: u64 },
+
+    #[error("Jalons incohérents : {scheduled} planifiés pour {requested} demandés")]
+    InvalidMilestoneSchedule { requested: u64, scheduled: u64 },
+
+    #[error("Jalon refusé : {message}")]
+    MilestoneRejected { message: String },
+
+    #[error("Délégation circulaire : {delegate} délègue déjà (directement ou non) à {delegator}")]
+    DelegationCycle { delegator: String, delegate: String },
+
+    #[error("Transaction multisig refusée : {message}")]
+    MultisigRejected { message: String },
+
+    #[error("Découverte refusée : {message}")]
+    DiscoveryRejected { message: String },
+
+    #[error("Réclamation de bande passante refusée : {message}")]
+    BandwidthClaimRejected { message: String },
     
     #[error("Erreur interne : {message}")]
     Internal { message: String },
@@ -165,6 +195,18 @@ pub enum TokenEventType {
         voting_power: u64,
         support: bool,
     },
+    /// Proposition du treasury exécutée, fonds versés au bénéficiaire
+    ProposalExecuted {
+        proposal_id: Hash,
+        recipient: PublicKey,
+        amount: u64,
+    },
+    /// Stake de validateur slashé (tokens verrouillés brûlés)
+    Slashed {
+        validator: PublicKey,
+        amount: u64,
+        reason: String,
+    },
 }
 
 /// Métriques globales du système token
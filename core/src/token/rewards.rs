@@ -7,10 +7,13 @@
 //! - Découverte : 25-100 ARC (importance + impact)
 
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use chrono::{DateTime, Utc, Duration};
-use crate::crypto::{Hash, PublicKey};
+use crate::crypto::{Hash, HashAlgorithm, PublicKey, compute_hash};
+use crate::block::normalize_url;
+use crate::consensus::{BandwidthScore, DeliveryEpoch, DeliveryLedger, NodeId};
 use super::{TokenOperationResult, TokenOperationError, ARCToken};
+use super::delivery::DeliveryLog;
 
 /// Système de récompenses principal
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -27,6 +30,15 @@ pub struct RewardSystem {
     pub economic_model: EconomicModel,
     /// Historique des distributions
     pub distribution_history: Vec<RewardDistribution>,
+    /// Réclamations de découverte, par hash de contenu
+    #[serde(default)]
+    pub discovery_claims: HashMap<Hash, DiscoveryClaim>,
+    /// Volume de livraisons réglé sur le mois en cours
+    #[serde(default)]
+    pub delivery_volume: DeliveryVolume,
+    /// Époques de livraison déjà payées, par identifiant de défi
+    #[serde(default)]
+    pub claimed_delivery_epochs: HashSet<Hash>,
     /// Métriques de performance
     pub performance_metrics: PerformanceMetrics,
     /// Configuration
@@ -48,6 +60,9 @@ pub struct RewardPool {
     pub distributed_amount: u64,
     /// Montant disponible
     pub available_amount: u64,
+    /// Montant réservé par des récompenses en attente de finalisation
+    #[serde(default)]
+    pub reserved_amount: u64,
     /// Limite de distribution par période
     pub period_limit: u64,
     /// Montant distribué cette période
@@ -200,6 +215,69 @@ pub struct RewardConfig {
     pub adaptive_rewards_enabled: bool,
     /// Seuils de qualité minimums
     pub quality_thresholds: QualityThresholds,
+    /// Délai accordé au stockage d'un contenu découvert (en heures)
+    #[serde(default = "default_discovery_storage_window_hours")]
+    pub discovery_storage_window_hours: u32,
+    /// Tarif des livraisons de contenu réglées depuis le `DeliveryLog`
+    #[serde(default)]
+    pub delivery_rate: DeliveryRateConfig,
+}
+
+fn default_discovery_storage_window_hours() -> u32 {
+    48
+}
+
+/// Tarif dégressif des livraisons de contenu
+///
+/// Le tarif marginal part de `max_rate_per_gb` et tend vers `min_rate_per_gb`
+/// à mesure que le volume réglé du mois croît : il vaut
+/// `min + (max - min) × h / (h + v)` pour un volume mensuel `v`, où `h` est
+/// `half_premium_volume_gb`. L'émission reste ainsi bornée par le tarif plancher.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeliveryRateConfig {
+    /// Tarif du premier GB du mois (ARC/GB)
+    pub max_rate_per_gb: f64,
+    /// Tarif plancher (ARC/GB)
+    pub min_rate_per_gb: f64,
+    /// Volume mensuel auquel la prime au-dessus du plancher est divisée par deux (GB)
+    pub half_premium_volume_gb: f64,
+}
+
+impl Default for DeliveryRateConfig {
+    fn default() -> Self {
+        Self {
+            max_rate_per_gb: 5.0,
+            min_rate_per_gb: 1.0,
+            half_premium_volume_gb: 10_000.0,
+        }
+    }
+}
+
+impl DeliveryRateConfig {
+    /// Tarif marginal pour un volume mensuel déjà réglé (ARC/GB)
+    pub fn rate_at(&self, monthly_gb: f64) -> f64 {
+        let premium = self.max_rate_per_gb - self.min_rate_per_gb;
+        self.min_rate_per_gb + premium * self.half_premium_volume_gb / (self.half_premium_volume_gb + monthly_gb)
+    }
+
+    /// Montant dû pour faire passer le volume mensuel de `from_gb` à `to_gb`
+    ///
+    /// Intégrale du tarif marginal : le résultat ne dépend pas du découpage
+    /// des règlements au sein du mois.
+    pub fn amount_between(&self, from_gb: f64, to_gb: f64) -> f64 {
+        let premium = self.max_rate_per_gb - self.min_rate_per_gb;
+        let half = self.half_premium_volume_gb;
+        self.min_rate_per_gb * (to_gb - from_gb) + premium * half * ((half + to_gb) / (half + from_gb)).ln()
+    }
+}
+
+/// Volume de livraisons réglé sur un mois calendaire
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DeliveryVolume {
+    /// Début du mois couvert
+    pub period_start: DateTime<Utc>,
+    /// Volume réglé depuis le début du mois (bytes)
+    pub settled_bytes: u64,
 }
 
 /// Seuils de qualité pour différents types de récompenses
@@ -286,6 +364,8 @@ impl Default for RewardConfig {
                 minimum_bandwidth_performance: 0.9,      // 90% minimum
                 minimum_discovery_relevance: 0.7,        // 70% minimum
             },
+            discovery_storage_window_hours: default_discovery_storage_window_hours(),
+            delivery_rate: DeliveryRateConfig::default(),
         }
     }
 }
@@ -311,6 +391,9 @@ impl RewardSystem {
             discovery_pool: RewardPool::new(RewardType::ContentDiscovery, discovery_allocation, period_duration),
             economic_model,
             distribution_history: Vec::new(),
+            discovery_claims: HashMap::new(),
+            delivery_volume: DeliveryVolume::default(),
+            claimed_delivery_epochs: HashSet::new(),
             performance_metrics: PerformanceMetrics::new(),
             config,
             created_at: now,
@@ -432,7 +515,7 @@ impl RewardSystem {
             recipients.insert(contribution.provider.clone(), allocation.clone());
             total_amount += allocation.final_amount;
 
-            token.mint(&contribution.provider, allocation.final_amount, tx_hash)?;
+            token.mint_reward(&contribution.provider, allocation.final_amount, "bandwidth_service", tx_hash.clone())?;
         }
 
         self.bandwidth_pool.distributed_amount += total_amount;
@@ -440,11 +523,11 @@ impl RewardSystem {
         self.bandwidth_pool.distributed_this_period += total_amount;
 
         let distribution = RewardDistribution {
-            distribution_id: Hash::from_bytes([
-                &tx_hash.as_bytes()[..16],
+            distribution_id: compute_hash(&[
+                tx_hash.as_bytes(),
                 &Utc::now().timestamp().to_le_bytes(),
                 &[2u8], // Different from others
-            ].concat().try_into().unwrap()),
+            ].concat(), HashAlgorithm::Blake3),
             reward_type: RewardType::BandwidthService,
             recipients,
             total_amount,
@@ -467,6 +550,182 @@ impl RewardSystem {
         Ok(distribution)
     }
 
+    /// Relevé des livraisons d'un opérateur et de la récompense réclamable
+    ///
+    /// Seul le volume des époques corroborées par un défi de bande passante et
+    /// non encore payées est valorisé.
+    pub fn bandwidth_statement(&self, provider: &PublicKey, ledger: &DeliveryLedger, score: &BandwidthScore) -> TokenOperationResult<BandwidthStatement> {
+        if ledger.node_id != NodeId::from_public_key(provider) {
+            return Err(TokenOperationError::BandwidthClaimRejected {
+                message: "Le registre de livraisons n'appartient pas à l'opérateur".to_string(),
+            });
+        }
+        let claimable_bytes: u64 = self.unclaimed_epochs(ledger).map(|epoch| epoch.bytes).sum();
+        let claimable_amount = if claimable_bytes == 0
+            || score.combined_score < self.config.quality_thresholds.minimum_bandwidth_performance
+        {
+            0
+        } else {
+            let contribution = bandwidth_contribution(provider, claimable_bytes, score);
+            self.calculate_bandwidth_reward(&contribution)?.final_amount
+        };
+
+        Ok(BandwidthStatement {
+            provider: provider.clone(),
+            period_start: ledger.period_start,
+            bytes_served: ledger.bytes_served,
+            pending_bytes: ledger.pending_bytes(),
+            claimable_bytes,
+            claimable_amount,
+        })
+    }
+
+    /// Verse la récompense du volume corroboré et non réclamé d'un opérateur
+    ///
+    /// Les époques payées sont consignées dans `claimed_delivery_epochs` : un
+    /// même registre présenté deux fois ne rapporte qu'une fois.
+    pub fn claim_bandwidth_reward(
+        &mut self,
+        provider: &PublicKey,
+        ledger: &DeliveryLedger,
+        score: &BandwidthScore,
+        token: &mut ARCToken,
+        tx_hash: Hash,
+    ) -> TokenOperationResult<RewardDistribution> {
+        let statement = self.bandwidth_statement(provider, ledger, score)?;
+        if statement.claimable_bytes == 0 {
+            return Err(TokenOperationError::BandwidthClaimRejected {
+                message: "Aucune livraison corroborée à réclamer".to_string(),
+            });
+        }
+        if statement.claimable_amount == 0 {
+            return Err(TokenOperationError::BandwidthClaimRejected {
+                message: format!("Performance insuffisante : {:.2}", score.combined_score),
+            });
+        }
+        if self.bandwidth_pool.available_amount < statement.claimable_amount {
+            return Err(TokenOperationError::InsufficientRewardPool);
+        }
+
+        let epochs: Vec<Hash> = self.unclaimed_epochs(ledger).map(|epoch| epoch.epoch_id.clone()).collect();
+        let contribution = bandwidth_contribution(provider, statement.claimable_bytes, score);
+        let distribution = self.distribute_bandwidth_rewards(vec![contribution], token, tx_hash)?;
+        self.claimed_delivery_epochs.extend(epochs);
+        Ok(distribution)
+    }
+
+    /// Époques corroborées d'un registre non encore payées
+    fn unclaimed_epochs<'a>(&'a self, ledger: &'a DeliveryLedger) -> impl Iterator<Item = &'a DeliveryEpoch> + 'a {
+        ledger.epochs.iter().filter(|epoch| !self.claimed_delivery_epochs.contains(&epoch.epoch_id))
+    }
+
+    /// Règle les livraisons de contenu consignées jusqu'à `now`
+    ///
+    /// Les livraisons sont agrégées par opérateur ; une livraison dont la
+    /// requête n'a pas été servie par l'API, ou qui cite une requête déjà
+    /// réglée, est écartée. Le montant total suit le tarif dégressif du mois
+    /// et est réparti au prorata des volumes. Il est prélevé sur le pool de
+    /// bande passante, part de l'allocation de récompenses d'archivage.
+    ///
+    /// Si le pool ne couvre pas le règlement, le journal est laissé intact.
+    pub fn settle_deliveries(
+        &mut self,
+        log: &mut DeliveryLog,
+        now: DateTime<Utc>,
+        token: &mut ARCToken,
+        tx_hash: Hash,
+    ) -> TokenOperationResult<DeliverySettlement> {
+        let mut bytes_per_operator: HashMap<PublicKey, u64> = HashMap::new();
+        let mut settled_requests = HashSet::new();
+        let mut excluded_entries = 0;
+        let mut period_start = None;
+
+        for entry in log.entries().iter().filter(|entry| entry.timestamp <= now) {
+            let verified = log.verified_bytes(entry)
+                .filter(|_| settled_requests.insert(entry.request_id.clone()));
+            match verified {
+                Some(bytes) => {
+                    *bytes_per_operator.entry(entry.operator.clone()).or_insert(0) += bytes;
+                    period_start = Some(period_start.map_or(entry.timestamp, |start: DateTime<Utc>| start.min(entry.timestamp)));
+                }
+                None => excluded_entries += 1,
+            }
+        }
+
+        let month = crate::consensus::bandwidth_proof::month_start(now);
+        let monthly_bytes = if self.delivery_volume.period_start < month { 0 } else { self.delivery_volume.settled_bytes };
+        let settled_bytes: u64 = bytes_per_operator.values().sum();
+
+        let gb = 1024.0 * 1024.0 * 1024.0;
+        let from_gb = monthly_bytes as f64 / gb;
+        let total = self.config.delivery_rate.amount_between(from_gb, from_gb + settled_bytes as f64 / gb);
+        let average_rate = if settled_bytes == 0 { 0.0 } else { total / (settled_bytes as f64 / gb) };
+
+        let mut recipients = HashMap::new();
+        for (operator, bytes) in &bytes_per_operator {
+            let amount = (total * *bytes as f64 / settled_bytes as f64) as u64;
+            recipients.insert(operator.clone(), RewardAllocation {
+                recipient: operator.clone(),
+                base_amount: amount,
+                multipliers: Vec::new(),
+                bonuses: Vec::new(),
+                final_amount: amount,
+                calculation_details: format!(
+                    "{:.2} GB × {:.3} ARC/GB (tarif moyen du règlement) = {} ARC",
+                    *bytes as f64 / gb, average_rate, amount
+                ),
+            });
+        }
+        let total_amount: u64 = recipients.values().map(|allocation| allocation.final_amount).sum();
+        if self.bandwidth_pool.available_amount < total_amount {
+            return Err(TokenOperationError::InsufficientRewardPool);
+        }
+
+        log.take_until(now);
+        for request_id in &settled_requests {
+            log.settle_request(request_id);
+        }
+
+        for allocation in recipients.values().filter(|allocation| allocation.final_amount > 0) {
+            token.mint_reward(&allocation.recipient, allocation.final_amount, "content_delivery", tx_hash.clone())?;
+        }
+
+        self.delivery_volume = DeliveryVolume { period_start: month, settled_bytes: monthly_bytes + settled_bytes };
+        self.bandwidth_pool.distributed_amount += total_amount;
+        self.bandwidth_pool.available_amount -= total_amount;
+        self.bandwidth_pool.distributed_this_period += total_amount;
+
+        let distribution = RewardDistribution {
+            distribution_id: compute_hash(&[
+                tx_hash.as_bytes(),
+                &now.timestamp().to_le_bytes(),
+                &[4u8],
+            ].concat(), HashAlgorithm::Blake3),
+            reward_type: RewardType::BandwidthService,
+            recipients,
+            total_amount,
+            criteria: RewardCriteria {
+                period_start: period_start.unwrap_or(now),
+                period_end: now,
+                specific_criteria: serde_json::json!({
+                    "settled_bytes": settled_bytes,
+                    "monthly_bytes_before": monthly_bytes,
+                    "average_rate_per_gb": average_rate,
+                    "excluded_entries": excluded_entries,
+                }),
+                minimum_thresholds: HashMap::new(),
+            },
+            distribution_date: now,
+            transaction_hash: tx_hash,
+        };
+
+        self.distribution_history.push(distribution.clone());
+        self.update_performance_metrics();
+        self.last_updated = now;
+
+        Ok(DeliverySettlement { distribution, settled_bytes, excluded_entries })
+    }
+
     /// Calcule les récompenses de découverte
     pub fn distribute_discovery_rewards(&mut self, contributions: Vec<DiscoveryContribution>, token: &mut ARCToken, tx_hash: Hash) -> TokenOperationResult<RewardDistribution> {
         let mut recipients = HashMap::new();
@@ -516,6 +775,175 @@ impl RewardSystem {
         Ok(distribution)
     }
 
+    /// Réclame la récompense de première découverte d'un contenu
+    ///
+    /// Le contenu ne doit jamais avoir été archivé ni réclamé. Le montant
+    /// (25-100 ARC) dépend de la rareté du domaine et est réservé sur le pool
+    /// d'archivage ; il n'est versé qu'une fois le stockage confirmé par
+    /// `finalize_discovery_reward` dans le délai `discovery_storage_window_hours`.
+    ///
+    /// L'URL réclamée est liée au hash : le versement exige que ce contenu
+    /// soit archivé sous cette même URL, sans quoi un domaine rare pourrait
+    /// être revendiqué pour n'importe quel contenu.
+    pub fn claim_discovery_reward(
+        &mut self,
+        discoverer: &PublicKey,
+        content_hash: Hash,
+        url: &str,
+        index: &dyn ArchivedContentLookup,
+    ) -> TokenOperationResult<DiscoveryClaim> {
+        let now = Utc::now();
+        let domain = discovery_domain(url).ok_or_else(|| TokenOperationError::DiscoveryRejected {
+            message: format!("URL sans domaine : {}", url),
+        })?;
+
+        if index.is_archived(&content_hash) {
+            return Err(TokenOperationError::DiscoveryRejected {
+                message: format!("Contenu déjà archivé : {}", content_hash.to_hex()),
+            });
+        }
+        if self.discovery_claims.get(&content_hash).is_some_and(|claim| claim.status != DiscoveryClaimStatus::Expired) {
+            return Err(TokenOperationError::DiscoveryRejected {
+                message: format!("Découverte déjà réclamée : {}", content_hash.to_hex()),
+            });
+        }
+
+        // Les découvertes en cours comptent comme des archives du domaine
+        let claimed_in_domain = self.discovery_claims.values()
+            .filter(|claim| claim.domain == domain && claim.status != DiscoveryClaimStatus::Expired)
+            .count();
+        let domain_archives = index.domain_archive_count(&domain) + claimed_in_domain;
+        let amount = self.calculate_discovery_claim_amount(domain_archives);
+
+        if self.archival_pool.available_amount < amount {
+            return Err(TokenOperationError::InsufficientRewardPool);
+        }
+        self.archival_pool.available_amount -= amount;
+        self.archival_pool.reserved_amount += amount;
+
+        let claim = DiscoveryClaim {
+            discoverer: discoverer.clone(),
+            content_hash: content_hash.clone(),
+            url: url.to_string(),
+            domain,
+            amount,
+            claimed_at: now,
+            expires_at: now + Duration::hours(self.config.discovery_storage_window_hours as i64),
+            status: DiscoveryClaimStatus::Pending,
+        };
+        self.discovery_claims.insert(content_hash, claim.clone());
+        self.last_updated = now;
+
+        Ok(claim)
+    }
+
+    /// Verse la récompense d'une découverte dont le stockage est confirmé
+    ///
+    /// Le stockage est vérifié dans `index` : le contenu doit y être archivé
+    /// sous l'URL réclamée. Une réclamation encore non vérifiable reste en
+    /// attente jusqu'à son expiration.
+    pub fn finalize_discovery_reward(
+        &mut self,
+        content_hash: &Hash,
+        index: &dyn ArchivedContentLookup,
+        token: &mut ARCToken,
+        tx_hash: Hash,
+    ) -> TokenOperationResult<RewardDistribution> {
+        let now = Utc::now();
+        let claim = self.discovery_claims.get(content_hash)
+            .filter(|claim| claim.status == DiscoveryClaimStatus::Pending && claim.expires_at > now)
+            .cloned()
+            .ok_or_else(|| TokenOperationError::DiscoveryRejected {
+                message: format!("Aucune découverte en attente pour {}", content_hash.to_hex()),
+            })?;
+
+        let archived_url = index.archived_url(content_hash).ok_or_else(|| TokenOperationError::DiscoveryRejected {
+            message: format!("Contenu pas encore archivé : {}", content_hash.to_hex()),
+        })?;
+        if normalize_url(&archived_url) != normalize_url(&claim.url) {
+            return Err(TokenOperationError::DiscoveryRejected {
+                message: format!("Contenu archivé depuis {} et non depuis {}", archived_url, claim.url),
+            });
+        }
+
+        token.mint_reward(&claim.discoverer, claim.amount, "content_discovery", tx_hash.clone())?;
+
+        self.archival_pool.reserved_amount = self.archival_pool.reserved_amount.saturating_sub(claim.amount);
+        self.archival_pool.distributed_amount += claim.amount;
+        self.archival_pool.distributed_this_period += claim.amount;
+        if let Some(stored) = self.discovery_claims.get_mut(content_hash) {
+            stored.status = DiscoveryClaimStatus::Finalized;
+        }
+
+        let allocation = RewardAllocation {
+            recipient: claim.discoverer.clone(),
+            base_amount: self.economic_model.base_discovery_reward,
+            multipliers: Vec::new(),
+            bonuses: Vec::new(),
+            final_amount: claim.amount,
+            calculation_details: format!("Première découverte sur {} : {} ARC", claim.domain, claim.amount),
+        };
+        let distribution = RewardDistribution {
+            distribution_id: compute_hash(&[tx_hash.as_bytes(), content_hash.as_bytes()].concat(), HashAlgorithm::Blake3),
+            reward_type: RewardType::ContentDiscovery,
+            recipients: HashMap::from([(claim.discoverer.clone(), allocation)]),
+            total_amount: claim.amount,
+            criteria: RewardCriteria {
+                period_start: claim.claimed_at,
+                period_end: now,
+                specific_criteria: serde_json::json!({
+                    "content_hash": content_hash.to_hex(),
+                    "domain": claim.domain,
+                }),
+                minimum_thresholds: HashMap::new(),
+            },
+            distribution_date: now,
+            transaction_hash: tx_hash,
+        };
+
+        self.distribution_history.push(distribution.clone());
+        self.update_performance_metrics();
+        self.last_updated = now;
+
+        Ok(distribution)
+    }
+
+    /// Découvertes en attente de confirmation du stockage
+    pub fn pending_discovery_claims(&self) -> Vec<Hash> {
+        self.discovery_claims.values()
+            .filter(|claim| claim.status == DiscoveryClaimStatus::Pending)
+            .map(|claim| claim.content_hash.clone())
+            .collect()
+    }
+
+    /// Expire les découvertes non stockées dans le délai imparti
+    ///
+    /// Leur montant réservé retourne au pool d'archivage ; retourne les hash
+    /// de contenu concernés.
+    pub fn expire_discovery_claims(&mut self, now: DateTime<Utc>) -> Vec<Hash> {
+        let mut expired = Vec::new();
+        for claim in self.discovery_claims.values_mut() {
+            if claim.status == DiscoveryClaimStatus::Pending && claim.expires_at <= now {
+                claim.status = DiscoveryClaimStatus::Expired;
+                self.archival_pool.reserved_amount = self.archival_pool.reserved_amount.saturating_sub(claim.amount);
+                self.archival_pool.available_amount += claim.amount;
+                expired.push(claim.content_hash.clone());
+            }
+        }
+        expired
+    }
+
+    /// Montant d'une découverte selon le nombre d'archives déjà connues du domaine
+    ///
+    /// Un domaine absent de l'index rapporte le maximum ; le montant décroît
+    /// vers la récompense de base à mesure que le domaine est couvert.
+    fn calculate_discovery_claim_amount(&self, domain_archives: usize) -> u64 {
+        let base = self.economic_model.base_discovery_reward;
+        let max = (base as f64 * self.economic_model.max_discovery_importance_multiplier) as u64;
+        let rarity = 1.0 / (1.0 + domain_archives as f64);
+        base + ((max - base) as f64 * rarity) as u64
+    }
+
     /// Calcule la récompense d'archivage pour une contribution
     fn calculate_archival_reward(&self, contribution: &ArchivalContribution) -> TokenOperationResult<RewardAllocation> {
         let base_amount = self.economic_model.base_archive_reward;
@@ -742,6 +1170,7 @@ impl RewardPool {
             total_allocation,
             distributed_amount: 0,
             available_amount: total_allocation,
+            reserved_amount: 0,
             period_limit,
             distributed_this_period: 0,
             period_reset_date: Utc::now() + period_duration,
@@ -795,6 +1224,46 @@ pub struct BandwidthContribution {
     pub error_rate: f64,
 }
 
+/// Contribution de bande passante pour un volume corroboré
+///
+/// Le temps de réponse et le taux d'erreur n'interviennent pas dans le calcul ;
+/// seul le score combiné est repris.
+fn bandwidth_contribution(provider: &PublicKey, bytes: u64, score: &BandwidthScore) -> BandwidthContribution {
+    BandwidthContribution {
+        provider: provider.clone(),
+        bytes_served: bytes,
+        performance_score: score.combined_score,
+        average_response_time_ms: 0,
+        error_rate: 0.0,
+    }
+}
+
+/// Relevé de bande passante d'un opérateur
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BandwidthStatement {
+    pub provider: PublicKey,
+    /// Début du mois en cours
+    pub period_start: DateTime<Utc>,
+    /// Volume livré ce mois (bytes)
+    pub bytes_served: u64,
+    /// Volume en attente d'un défi de bande passante (bytes)
+    pub pending_bytes: u64,
+    /// Volume corroboré non encore réclamé (bytes)
+    pub claimable_bytes: u64,
+    /// Récompense réclamable (ARC)
+    pub claimable_amount: u64,
+}
+
+/// Résultat d'un règlement des livraisons de contenu
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeliverySettlement {
+    pub distribution: RewardDistribution,
+    /// Volume rémunéré (bytes)
+    pub settled_bytes: u64,
+    /// Livraisons écartées faute de requête API correspondante
+    pub excluded_entries: usize,
+}
+
 /// Contribution de découverte
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DiscoveryContribution {
@@ -806,6 +1275,52 @@ pub struct DiscoveryContribution {
     pub discovery_date: DateTime<Utc>,
 }
 
+/// Accès à l'index des contenus archivés pour les récompenses de découverte
+pub trait ArchivedContentLookup {
+    /// Indique si le contenu est déjà archivé sur la chaîne
+    fn is_archived(&self, content_hash: &Hash) -> bool;
+
+    /// URL d'origine sous laquelle le contenu a été archivé, `None` s'il ne l'est pas
+    fn archived_url(&self, content_hash: &Hash) -> Option<String>;
+
+    /// Nombre d'archives déjà connues pour un domaine
+    fn domain_archive_count(&self, domain: &str) -> usize;
+}
+
+/// Réclamation d'une récompense de découverte
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DiscoveryClaim {
+    pub discoverer: PublicKey,
+    pub content_hash: Hash,
+    pub url: String,
+    pub domain: String,
+    /// Montant réservé sur le pool d'archivage
+    pub amount: u64,
+    pub claimed_at: DateTime<Utc>,
+    /// Échéance du stockage du contenu
+    pub expires_at: DateTime<Utc>,
+    pub status: DiscoveryClaimStatus,
+}
+
+/// État d'une réclamation de découverte
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum DiscoveryClaimStatus {
+    /// En attente du stockage du contenu
+    Pending,
+    /// Récompense versée
+    Finalized,
+    /// Contenu non stocké dans le délai, réservation libérée
+    Expired,
+}
+
+/// Domaine d'une URL, en minuscules et sans port
+fn discovery_domain(url: &str) -> Option<String> {
+    let after_protocol = &url[url.find("://")? + 3..];
+    let authority = after_protocol.split(['/', '?', '#']).next()?;
+    let host = authority.rsplit('@').next()?.split(':').next()?;
+    (!host.is_empty()).then(|| host.to_lowercase())
+}
+
 /// Statistiques du système de récompenses
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct RewardSystemStatistics {
@@ -827,6 +1342,7 @@ pub struct PoolStatus {
 mod tests {
     use super::*;
     use crate::crypto::generate_keypair;
+    use crate::token::TokenEventType;
 
     #[test]
     fn test_reward_system_creation() {
@@ -907,6 +1423,231 @@ mod tests {
         assert_eq!(allocation.multipliers.len(), 1); // Performance multiplier
     }
 
+    #[test]
+    fn test_bandwidth_claim_from_corroborated_deliveries() {
+        use crate::consensus::NodeId;
+
+        let mut system = RewardSystem::new(1_000_000, RewardConfig::default());
+        let mut token = ARCToken::new();
+        let operator = generate_keypair().unwrap().public_key().clone();
+        let gb = 1024 * 1024 * 1024u64;
+        let score = BandwidthScore {
+            upload_score: 1.0,
+            download_score: 1.0,
+            latency_score: 1.0,
+            availability_score: 1.0,
+            combined_score: 1.0,
+        };
+        let mut ledger = DeliveryLedger {
+            node_id: NodeId::from_public_key(&operator),
+            period_start: Utc::now(),
+            bytes_served: 130 * gb,
+            bytes_per_content: HashMap::new(),
+            pending_per_peer: HashMap::from([(NodeId::from(Hash::zero()), 10 * gb)]),
+            epochs: std::collections::VecDeque::from([DeliveryEpoch {
+                epoch_id: Hash::from_bytes_array([1; 32]),
+                bytes: 120 * gb,
+                corroborated_at: Utc::now(),
+            }]),
+        };
+
+        // 120 GB corroborés × 1 ARC/GB × 5 (performance) + 10 % de bonus
+        let statement = system.bandwidth_statement(&operator, &ledger, &score).unwrap();
+        assert_eq!(statement.claimable_bytes, 120 * gb);
+        assert_eq!(statement.pending_bytes, 10 * gb);
+        assert_eq!(statement.claimable_amount, 612);
+
+        let distribution = system.claim_bandwidth_reward(&operator, &ledger, &score, &mut token, Hash::zero()).unwrap();
+        assert_eq!(distribution.total_amount, 612);
+        assert_eq!(token.balance_of(&operator), 612);
+        assert_eq!(system.bandwidth_pool.distributed_amount, 612);
+
+        // Le même registre présenté à nouveau ne rapporte rien
+        assert!(matches!(
+            system.claim_bandwidth_reward(&operator, &ledger, &score, &mut token, Hash::zero()),
+            Err(TokenOperationError::BandwidthClaimRejected { .. })
+        ));
+        assert_eq!(token.balance_of(&operator), 612);
+
+        // Ni le registre d'un autre nœud
+        let other = generate_keypair().unwrap().public_key().clone();
+        assert!(system.bandwidth_statement(&other, &ledger, &score).is_err());
+
+        // Nouvelle époque, mais performance insuffisante : refus
+        ledger.epochs.push_back(DeliveryEpoch {
+            epoch_id: Hash::from_bytes_array([2; 32]),
+            bytes: gb,
+            corroborated_at: Utc::now(),
+        });
+        assert_eq!(system.bandwidth_statement(&operator, &ledger, &score).unwrap().claimable_bytes, gb);
+        let weak = BandwidthScore { combined_score: 0.5, ..score };
+        assert_eq!(system.bandwidth_statement(&operator, &ledger, &weak).unwrap().claimable_amount, 0);
+        assert!(system.claim_bandwidth_reward(&operator, &ledger, &weak, &mut token, Hash::zero()).is_err());
+    }
+
+    #[test]
+    fn test_delivery_settlement_pays_verified_deliveries_proportionally() {
+        use crate::consensus::NodeId;
+        use crate::token::{DeliveryEntry, DeliveryLog, ServedRequest};
+
+        let mut system = RewardSystem::new(1_000_000, RewardConfig::default());
+        let mut token = ARCToken::new();
+        let node_a = generate_keypair().unwrap().public_key().clone();
+        let node_b = generate_keypair().unwrap().public_key().clone();
+        let gb = 1024 * 1024 * 1024u64;
+        let now = Utc::now();
+        let content = compute_hash(b"archive", HashAlgorithm::Blake3);
+
+        let mut log = DeliveryLog::new();
+        let serve = |log: &mut DeliveryLog, request_id: &str, operator: &PublicKey, bytes: u64| {
+            log.record_request(request_id, ServedRequest {
+                content_hash: content,
+                requester: "alice".to_string(),
+                node_id: NodeId::from_public_key(operator),
+                bytes,
+                timestamp: now,
+            });
+            log.record(DeliveryEntry {
+                operator: operator.clone(),
+                content_hash: content,
+                bytes,
+                request_id: request_id.to_string(),
+                timestamp: now,
+            });
+        };
+
+        // 300 GB servis par A, 100 GB par B
+        for i in 0..3 {
+            serve(&mut log, &format!("req-a-{}", i), &node_a, 100 * gb);
+        }
+        serve(&mut log, "req-b-0", &node_b, 100 * gb);
+
+        // Livraisons invérifiables : requête inconnue, requête servie par un
+        // autre nœud, requête rejouée
+        let forged = DeliveryEntry {
+            operator: node_b.clone(),
+            content_hash: content,
+            bytes: 500 * gb,
+            request_id: "req-forged".to_string(),
+            timestamp: now,
+        };
+        log.record(forged.clone());
+        log.record(DeliveryEntry { request_id: "req-a-0".to_string(), ..forged.clone() });
+        log.record(DeliveryEntry { operator: node_a.clone(), request_id: "req-a-1".to_string(), ..forged });
+
+        let settlement = system.settle_deliveries(&mut log, now, &mut token, Hash::zero()).unwrap();
+        assert_eq!(settlement.settled_bytes, 400 * gb);
+        assert_eq!(settlement.excluded_entries, 3);
+        assert!(log.entries().is_empty());
+
+        // 400 GB au tarif dégressif : 400 + 4 × 10 000 × ln(1,04) ≈ 1 968 ARC, réparti 3:1
+        assert_eq!(token.balance_of(&node_a), 1476);
+        assert_eq!(token.balance_of(&node_b), 492);
+        assert_eq!(settlement.distribution.total_amount, 1968);
+        assert_eq!(system.bandwidth_pool.distributed_amount, 1968);
+        assert_eq!(system.delivery_volume.settled_bytes, 400 * gb);
+        assert_eq!(token.events.iter().filter(|event| matches!(
+            &event.event_type,
+            TokenEventType::RewardDistributed { reward_type, .. } if reward_type == "content_delivery"
+        )).count(), 2);
+
+        // Une requête réglée ne peut plus justifier de livraison
+        log.record(DeliveryEntry {
+            operator: node_a.clone(),
+            content_hash: content,
+            bytes: 100 * gb,
+            request_id: "req-a-2".to_string(),
+            timestamp: now,
+        });
+        let replay = system.settle_deliveries(&mut log, now, &mut token, Hash::zero()).unwrap();
+        assert_eq!((replay.settled_bytes, replay.excluded_entries), (0, 1));
+        assert_eq!(token.balance_of(&node_a), 1476);
+
+        // Un fort volume mensuel rapproche le tarif du plancher de 1 ARC/GB
+        system.delivery_volume.settled_bytes = 1_000_000 * gb;
+        serve(&mut log, "req-b-1", &node_b, 100 * gb);
+        let late = system.settle_deliveries(&mut log, now, &mut token, Hash::zero()).unwrap();
+        assert!((100..=104).contains(&late.distribution.total_amount));
+    }
+
+    struct FakeIndex {
+        archived: HashMap<Hash, String>,
+        domains: HashMap<String, usize>,
+    }
+
+    impl ArchivedContentLookup for FakeIndex {
+        fn is_archived(&self, content_hash: &Hash) -> bool {
+            self.archived.contains_key(content_hash)
+        }
+
+        fn archived_url(&self, content_hash: &Hash) -> Option<String> {
+            self.archived.get(content_hash).cloned()
+        }
+
+        fn domain_archive_count(&self, domain: &str) -> usize {
+            self.domains.get(domain).copied().unwrap_or(0)
+        }
+    }
+
+    #[test]
+    fn test_discovery_claims() {
+        let mut system = RewardSystem::new(1_000_000, RewardConfig::default());
+        let mut token = ARCToken::new();
+        let discoverer = generate_keypair().unwrap().public_key().clone();
+        let mut index = FakeIndex {
+            archived: HashMap::from([(Hash::from_bytes(&[9; 32]).unwrap(), "https://known.net/".to_string())]),
+            domains: HashMap::from([("example.com".to_string(), 3)]),
+        };
+        let pool_before = system.archival_pool.available_amount;
+
+        // Domaine inconnu de l'index : récompense maximale
+        let content = Hash::from_bytes(&[1; 32]).unwrap();
+        let claim = system.claim_discovery_reward(&discoverer, content.clone(), "https://Rare.org:8443/page", &index).unwrap();
+        assert_eq!(claim.domain, "rare.org");
+        assert_eq!(claim.amount, 100);
+        assert_eq!(system.archival_pool.available_amount, pool_before - 100);
+
+        // Domaine déjà couvert : moins, mais dans la plage 25-100
+        let covered = system.claim_discovery_reward(&discoverer, Hash::from_bytes(&[2; 32]).unwrap(), "https://example.com/a", &index).unwrap();
+        assert!(covered.amount >= 25 && covered.amount < claim.amount);
+
+        // Doublon et contenu déjà archivé refusés
+        assert!(matches!(
+            system.claim_discovery_reward(&discoverer, content.clone(), "https://rare.org/page", &index),
+            Err(TokenOperationError::DiscoveryRejected { .. })
+        ));
+        assert!(system.claim_discovery_reward(&discoverer, Hash::from_bytes(&[9; 32]).unwrap(), "https://new.net/", &index).is_err());
+        assert_eq!(system.archival_pool.available_amount, pool_before - 100 - covered.amount);
+
+        // Stockage non confirmé, puis sous une autre URL : rien n'est versé
+        assert!(system.finalize_discovery_reward(&content, &index, &mut token, Hash::zero()).is_err());
+        index.archived.insert(content.clone(), "https://example.com/other".to_string());
+        assert!(matches!(
+            system.finalize_discovery_reward(&content, &index, &mut token, Hash::zero()),
+            Err(TokenOperationError::DiscoveryRejected { .. })
+        ));
+        assert_eq!(token.balance_of(&discoverer), 0);
+        assert_eq!(system.pending_discovery_claims().len(), 2);
+
+        // Stockage confirmé sous l'URL réclamée : versement et événement
+        index.archived.insert(content.clone(), "https://rare.org:8443/page".to_string());
+        system.finalize_discovery_reward(&content, &index, &mut token, Hash::zero()).unwrap();
+        assert_eq!(token.balance_of(&discoverer), 100);
+        assert!(token.events.iter().any(|event| matches!(
+            &event.event_type,
+            TokenEventType::RewardDistributed { amount: 100, reward_type, .. } if reward_type == "content_discovery"
+        )));
+        assert_eq!(system.archival_pool.distributed_amount, 100);
+        assert!(system.finalize_discovery_reward(&content, &index, &mut token, Hash::zero()).is_err());
+
+        // Contenu jamais stocké : la réservation revient au pool
+        let expired = system.expire_discovery_claims(Utc::now() + Duration::hours(49));
+        assert_eq!(expired, vec![Hash::from_bytes(&[2; 32]).unwrap()]);
+        assert_eq!(system.archival_pool.available_amount, pool_before - 100);
+        assert_eq!(system.archival_pool.reserved_amount, 0);
+        assert!(system.finalize_discovery_reward(&expired[0], &index, &mut token, Hash::zero()).is_err());
+    }
+
     #[test]
     fn test_discovery_reward_calculation() {
         let config = RewardConfig::default();
@@ -0,0 +1,347 @@
+//! Simulation économique pluriannuelle (feature `economic-simulation`)
+//!
+//! Projette mois par mois l'offre en circulation, les burns et la consommation
+//! du pool de récompenses d'archivage à partir d'hypothèses d'activité réseau.
+//! La simulation ne modifie pas le modèle : elle part de son état courant.
+//!
+//! Invariant : supply en circulation + tokens brûlés + pool restant + réserves
+//! non libérées = `TOTAL_SUPPLY` à chaque pas.
+
+use serde::{Deserialize, Serialize};
+
+use super::economics::EconomicModel;
+use super::{TokenOperationError, TokenOperationResult, ARCHIVAL_REWARDS_ALLOCATION, TOTAL_SUPPLY};
+
+/// Hypothèses d'une simulation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimulationParams {
+    /// Supply en circulation au départ (par défaut celle du modèle)
+    pub initial_circulating_supply: Option<u64>,
+    /// Archives créées le premier mois
+    pub archives_per_month: u64,
+    /// Croissance mensuelle du nombre d'archives
+    pub archive_growth_rate: f64,
+    /// Récompense de base par archive (ARC)
+    pub reward_per_archive: u64,
+    /// Volume de frais du premier mois (ARC)
+    pub fee_volume_per_month: u64,
+    /// Croissance mensuelle du volume de frais
+    pub fee_growth_rate: f64,
+    /// Part de la supply en circulation stakée au départ
+    pub initial_staking_ratio: f64,
+    /// Croissance mensuelle des tokens stakés
+    pub staking_growth_rate: f64,
+    /// Part des récompenses éligible aux multiplicateurs long terme
+    pub longterm_storage_share: f64,
+}
+
+impl Default for SimulationParams {
+    fn default() -> Self {
+        Self {
+            initial_circulating_supply: None,
+            archives_per_month: 2_000_000,
+            archive_growth_rate: 0.01,
+            reward_per_archive: 100, // récompense d'archivage de base
+            fee_volume_per_month: 50_000_000,
+            fee_growth_rate: 0.01,
+            initial_staking_ratio: 0.20,
+            staking_growth_rate: 0.02,
+            longterm_storage_share: 0.30,
+        }
+    }
+}
+
+/// État projeté à la fin d'un mois
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimulationMonth {
+    /// Mois de simulation (à partir de 1)
+    pub month: u32,
+    /// Supply en circulation
+    pub circulating_supply: u64,
+    /// Total brûlé depuis la création du token
+    pub total_burned: u64,
+    /// Brûlé pendant le mois
+    pub burned: u64,
+    /// Récompenses d'archivage émises pendant le mois
+    pub rewards_emitted: u64,
+    /// Pool de récompenses d'archivage restant
+    pub reward_pool_remaining: u64,
+    /// Part du pool d'archivage consommée (0.0-1.0)
+    pub reward_pool_depletion: f64,
+    /// Tokens stakés
+    pub staked_tokens: u64,
+    /// Multiplicateur long terme moyen appliqué
+    pub longterm_multiplier: f64,
+    /// Indice de santé économique projeté (0.0-1.0)
+    pub health_index: f64,
+}
+
+/// Rapport de simulation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SimulationReport {
+    /// Hypothèses utilisées
+    pub params: SimulationParams,
+    /// Série mensuelle
+    pub months: Vec<SimulationMonth>,
+    /// Récompenses émises sur la période
+    pub total_emitted: u64,
+    /// Tokens brûlés sur la période
+    pub total_burned: u64,
+    /// Mois d'épuisement du pool d'archivage, observé ou extrapolé
+    pub pool_exhaustion_month: Option<u32>,
+    /// Avertissements (épuisement anticipé du pool...)
+    pub warnings: Vec<String>,
+}
+
+impl SimulationReport {
+    /// Dernier état projeté
+    pub fn final_month(&self) -> Option<&SimulationMonth> {
+        self.months.last()
+    }
+}
+
+impl EconomicModel {
+    /// Projette l'économie du token sur `years` années, par pas mensuel
+    pub fn simulate(&self, params: SimulationParams, years: u32) -> TokenOperationResult<SimulationReport> {
+        if years == 0 {
+            return Err(TokenOperationError::Internal {
+                message: "La simulation doit couvrir au moins une année".to_string(),
+            });
+        }
+        let rates = [params.archive_growth_rate, params.fee_growth_rate, params.staking_growth_rate];
+        if rates.iter().any(|rate| !rate.is_finite() || *rate < -1.0)
+            || !(0.0..=1.0).contains(&params.initial_staking_ratio)
+            || !(0.0..=1.0).contains(&params.longterm_storage_share)
+        {
+            return Err(TokenOperationError::Internal {
+                message: "Paramètres de simulation invalides".to_string(),
+            });
+        }
+
+        let token_config = &self.config.token_config;
+        let multipliers = &self.deflation.longterm_bonus_system.multipliers;
+        let schedule_months = token_config.archival_rewards_years.saturating_mul(12);
+        let optimal_staking_ratio = self.config.staking_params.optimal_staking_ratio / 100.0;
+
+        // État initial, borné pour respecter l'offre totale
+        let mut total_burned_all_time = self.token.burned_tokens.min(TOTAL_SUPPLY);
+        let mut pool = ARCHIVAL_REWARDS_ALLOCATION
+            .saturating_sub(self.distribution.get_distribution_stats().archival_rewards_distributed)
+            .min(TOTAL_SUPPLY - total_burned_all_time);
+        let mut circulating = params.initial_circulating_supply
+            .unwrap_or(self.token.circulating_supply)
+            .min(TOTAL_SUPPLY - total_burned_all_time - pool);
+        let reserved = TOTAL_SUPPLY - total_burned_all_time - pool - circulating;
+        let initial_pool = pool;
+
+        let mut archives = params.archives_per_month as f64;
+        let mut fees = params.fee_volume_per_month as f64;
+        let mut staked = circulating as f64 * params.initial_staking_ratio;
+
+        let mut months = Vec::with_capacity(years as usize * 12);
+        let mut total_emitted = 0u64;
+        let mut total_burned = 0u64;
+        let mut pool_exhaustion_month = None;
+
+        for month in 1..=years.saturating_mul(12) {
+            // Multiplicateur long terme selon l'ancienneté du contenu stocké
+            let tier = match month {
+                0..=5 => 1.0,
+                6..=11 => multipliers.six_months,
+                12..=23 => multipliers.one_year,
+                _ => multipliers.two_years_plus,
+            }.min(multipliers.max_multiplier);
+            let longterm_multiplier = 1.0 + params.longterm_storage_share * (tier - 1.0);
+
+            // Émission : demande d'archivage, jamais au-delà du pool restant
+            let demand = (archives * params.reward_per_archive as f64 * longterm_multiplier).max(0.0);
+            let emitted = (demand.min(u64::MAX as f64) as u64).min(pool);
+            pool -= emitted;
+            circulating += emitted;
+            total_emitted += emitted;
+            if pool == 0 && pool_exhaustion_month.is_none() && initial_pool > 0 {
+                pool_exhaustion_month = Some(month);
+            }
+
+            // Burn d'une part des frais
+            let burned = ((fees.max(0.0) * token_config.burn_rate) as u64).min(circulating);
+            circulating -= burned;
+            total_burned += burned;
+            total_burned_all_time += burned;
+
+            // Staking, plafonné à la supply en circulation
+            staked = (staked * (1.0 + params.staking_growth_rate)).min(circulating as f64);
+
+            debug_assert_eq!(circulating + total_burned_all_time + pool + reserved, TOTAL_SUPPLY);
+
+            let health_index = Self::projected_health_index(
+                circulating,
+                staked,
+                optimal_staking_ratio,
+                emitted,
+                burned,
+                pool,
+                initial_pool,
+                month,
+                schedule_months,
+            );
+
+            months.push(SimulationMonth {
+                month,
+                circulating_supply: circulating,
+                total_burned: total_burned_all_time,
+                burned,
+                rewards_emitted: emitted,
+                reward_pool_remaining: pool,
+                reward_pool_depletion: if initial_pool > 0 {
+                    1.0 - pool as f64 / initial_pool as f64
+                } else {
+                    1.0
+                },
+                staked_tokens: staked as u64,
+                longterm_multiplier,
+                health_index,
+            });
+
+            archives *= 1.0 + params.archive_growth_rate;
+            fees *= 1.0 + params.fee_growth_rate;
+        }
+
+        // Extrapolation au rythme du dernier mois si le pool n'est pas épuisé
+        let simulated_months = months.len() as u32;
+        let pool_exhaustion_month = pool_exhaustion_month.or_else(|| {
+            months.last()
+                .filter(|last| last.rewards_emitted > 0)
+                .map(|last| simulated_months + pool.div_ceil(last.rewards_emitted).min(u32::MAX as u64) as u32)
+        });
+
+        let mut warnings = Vec::new();
+        if let Some(month) = pool_exhaustion_month.filter(|month| *month < schedule_months) {
+            warnings.push(format!(
+                "Le pool de récompenses d'archivage s'épuise au mois {} avant la fin du calendrier de {} ans",
+                month, token_config.archival_rewards_years
+            ));
+        }
+
+        Ok(SimulationReport {
+            params,
+            months,
+            total_emitted,
+            total_burned,
+            pool_exhaustion_month,
+            warnings,
+        })
+    }
+
+    /// Indice de santé projeté : staking, stabilité de l'offre et tenue du pool
+    #[allow(clippy::too_many_arguments)]
+    fn projected_health_index(
+        circulating: u64,
+        staked: f64,
+        optimal_staking_ratio: f64,
+        emitted: u64,
+        burned: u64,
+        pool: u64,
+        initial_pool: u64,
+        month: u32,
+        schedule_months: u32,
+    ) -> f64 {
+        let staking_health = if circulating > 0 && optimal_staking_ratio > 0.0 {
+            (staked / circulating as f64 / optimal_staking_ratio).min(1.0)
+        } else {
+            0.0
+        };
+
+        let annual_supply_change = if circulating > 0 {
+            (emitted as f64 - burned as f64) * 12.0 / circulating as f64 * 100.0
+        } else {
+            0.0
+        };
+        let stability_health = 1.0 - (annual_supply_change.abs() / 10.0).min(1.0);
+
+        // Pool restant comparé au reliquat prévu par un calendrier linéaire
+        let scheduled_remaining = if schedule_months > 0 {
+            initial_pool as f64 * (1.0 - month as f64 / schedule_months as f64).max(0.0)
+        } else {
+            0.0
+        };
+        let pool_health = if scheduled_remaining > 0.0 {
+            (pool as f64 / scheduled_remaining).min(1.0)
+        } else {
+            1.0
+        };
+
+        (staking_health + stability_health + pool_health) / 3.0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::token::PUBLIC_SALE;
+
+    fn params() -> SimulationParams {
+        SimulationParams {
+            initial_circulating_supply: Some(PUBLIC_SALE),
+            ..SimulationParams::default()
+        }
+    }
+
+    #[test]
+    fn test_simulation_conserves_supply() {
+        let model = EconomicModel::default();
+        let report = model.simulate(params(), 12).unwrap();
+        assert_eq!(report.months.len(), 144);
+
+        // Pool, circulation et burns se compensent exactement
+        let mut previous_circulating = PUBLIC_SALE;
+        for month in &report.months {
+            assert_eq!(
+                month.circulating_supply,
+                previous_circulating + month.rewards_emitted - month.burned
+            );
+            assert!(month.circulating_supply + month.total_burned + month.reward_pool_remaining <= TOTAL_SUPPLY);
+            assert!((0.0..=1.0).contains(&month.health_index));
+            previous_circulating = month.circulating_supply;
+        }
+
+        // La demande par défaut épuise le pool avant les 10 ans : rien n'est émis au-delà
+        let final_month = report.final_month().unwrap();
+        assert_eq!(report.total_emitted, ARCHIVAL_REWARDS_ALLOCATION);
+        assert_eq!(final_month.reward_pool_remaining, 0);
+        assert!(report.pool_exhaustion_month.unwrap() < 120);
+        assert_eq!(report.warnings.len(), 1);
+    }
+
+    #[test]
+    fn test_doubling_fee_volume_doubles_burn() {
+        let model = EconomicModel::default();
+        let base = params();
+        let doubled = SimulationParams {
+            fee_volume_per_month: base.fee_volume_per_month * 2,
+            ..base.clone()
+        };
+
+        let base_report = model.simulate(base, 5).unwrap();
+        let doubled_report = model.simulate(doubled, 5).unwrap();
+
+        // Écart d'arrondi d'au plus une unité par mois
+        let expected = base_report.total_burned as i128 * 2;
+        assert!((doubled_report.total_burned as i128 - expected).abs() <= 60);
+        assert_eq!(base_report.total_emitted, doubled_report.total_emitted);
+    }
+
+    #[test]
+    fn test_sustainable_activity_raises_no_warning() {
+        let model = EconomicModel::default();
+        let report = model.simulate(SimulationParams {
+            archives_per_month: 1_000_000,
+            archive_growth_rate: 0.0,
+            ..params()
+        }, 2).unwrap();
+
+        assert!(report.warnings.is_empty());
+        assert!(report.pool_exhaustion_month.unwrap() >= 120);
+        assert!(model.simulate(params(), 0).is_err());
+    }
+}
@@ -6,11 +6,13 @@
 //! - Système de vote et propositions
 //! - Délégation de pouvoir de vote
 //! - Récompenses de staking
+//! - Slashing des validateurs défaillants
 
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use chrono::{DateTime, Utc, Duration};
 use crate::crypto::{Hash, PublicKey, Signature};
+use crate::consensus::{ChallengeAuditEntry, NodeId, ValidatorStakes};
 use super::{TokenOperationResult, TokenOperationError, ARCToken};
 
 /// Système de staking principal
@@ -28,6 +30,12 @@ pub struct StakingSystem {
     pub config: StakingConfig,
     /// Métriques du système
     pub metrics: StakingMetrics,
+    /// Historique des slashings appliqués
+    #[serde(default)]
+    pub slashing_history: Vec<SlashingEvent>,
+    /// Défis de stockage manqués par validateur, pas encore sanctionnés
+    #[serde(default)]
+    pub missed_challenges: HashMap<PublicKey, Vec<MissedChallenge>>,
     /// Timestamp de création
     pub created_at: DateTime<Utc>,
     /// Dernière mise à jour
@@ -47,7 +55,10 @@ pub struct GovernanceStake {
     pub lock_duration_days: u32,
     /// Date de fin de lock
     pub lock_end_date: DateTime<Utc>,
-    /// Multiplicateur de pouvoir de vote
+    /// Multiplicateur de pouvoir de vote à la création du stake
+    ///
+    /// Le multiplicateur effectif décroît ensuite avec la durée de lock restante
+    /// (voir `StakingSystem::duration_multiplier`).
     pub voting_power_multiplier: f64,
     /// Votes récents
     pub recent_votes: Vec<VoteRecord>,
@@ -84,6 +95,9 @@ pub struct ValidatorStake {
     pub penalties: Vec<ValidatorPenalty>,
     /// Statut du validateur
     pub status: ValidatorStatus,
+    /// Fin de la suspension de la sélection des leaders (après un slashing)
+    #[serde(default)]
+    pub suspended_until: Option<DateTime<Utc>>,
 }
 
 /// Information sur un délégateur
@@ -134,6 +148,9 @@ pub struct GovernanceProposal {
     pub votes_abstain: u64,
     /// Détails des votes
     pub vote_details: HashMap<PublicKey, Vote>,
+    /// Pouvoir de vote de chaque adresse, figé à la création de la proposition
+    #[serde(default)]
+    pub voting_power_snapshot: HashMap<PublicKey, u64>,
     /// Statut de la proposition
     pub status: ProposalStatus,
     /// Résultat de l'exécution (si applicable)
@@ -147,7 +164,7 @@ pub struct VoteDelegation {
     pub delegator: PublicKey,
     /// Délégué (receveur du pouvoir de vote)
     pub delegate: PublicKey,
-    /// Montant de pouvoir de vote délégué
+    /// Montant de stake délégué (le multiplicateur de durée du délégateur s'y applique)
     pub voting_power_delegated: u64,
     /// Date de délégation
     pub delegation_date: DateTime<Utc>,
@@ -219,6 +236,99 @@ pub struct ValidatorPenalty {
     pub transaction_hash: Hash,
 }
 
+/// Motif d'un slashing
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SlashReason {
+    /// Trop de défis de stockage manqués ou échoués
+    MissedStorageChallenges,
+    /// Comportement malveillant
+    MaliciousBehavior,
+    /// Violation des règles
+    RuleViolation,
+}
+
+impl SlashReason {
+    fn penalty_type(&self) -> PenaltyType {
+        match self {
+            SlashReason::MissedStorageChallenges => PenaltyType::PoorPerformance,
+            SlashReason::MaliciousBehavior => PenaltyType::MaliciousBehavior,
+            SlashReason::RuleViolation => PenaltyType::RuleViolation,
+        }
+    }
+
+    fn label(&self) -> &'static str {
+        match self {
+            SlashReason::MissedStorageChallenges => "missed_storage_challenges",
+            SlashReason::MaliciousBehavior => "malicious_behavior",
+            SlashReason::RuleViolation => "rule_violation",
+        }
+    }
+}
+
+/// Enregistrement d'un slashing de validateur
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlashingEvent {
+    /// Validateur slashé
+    pub validator: PublicKey,
+    /// Motif du slashing
+    pub reason: SlashReason,
+    /// Preuves (identifiants des défis de stockage échoués, etc.)
+    pub evidence: Vec<Hash>,
+    /// Fraction demandée avant escalade
+    pub requested_fraction: f64,
+    /// Fraction effectivement appliquée
+    pub applied_fraction: f64,
+    /// Montant brûlé
+    pub slashed_amount: u64,
+    /// Rang de l'infraction dans la fenêtre d'escalade (1 = première)
+    pub offense_count: u32,
+    /// Date du slashing
+    pub slashed_at: DateTime<Utc>,
+    /// Fin de la suspension de la sélection des leaders
+    pub suspended_until: DateTime<Utc>,
+    /// Hash de la transaction de slashing
+    pub transaction_hash: Hash,
+}
+
+/// Défi de stockage manqué par un validateur
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MissedChallenge {
+    /// Identifiant du défi
+    pub challenge_id: Hash,
+    /// Date d'enregistrement de l'échec
+    pub recorded_at: DateTime<Utc>,
+}
+
+/// Paramètres de slashing des validateurs
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SlashingConfig {
+    /// Fraction du stake brûlée pour une première infraction
+    pub base_slash_fraction: f64,
+    /// Multiplicateur appliqué à chaque récidive dans la fenêtre
+    pub escalation_factor: f64,
+    /// Fraction maximum brûlée en un seul slashing
+    pub max_slash_fraction: f64,
+    /// Fenêtre de prise en compte des récidives et des défis manqués (jours)
+    pub offense_window_days: u32,
+    /// Durée de suspension de la sélection des leaders (heures)
+    pub suspension_cooldown_hours: u32,
+    /// Nombre de défis manqués toléré dans la fenêtre avant slashing
+    pub max_missed_challenges: u32,
+}
+
+impl Default for SlashingConfig {
+    fn default() -> Self {
+        Self {
+            base_slash_fraction: 0.01,      // 1% du stake
+            escalation_factor: 2.0,         // double à chaque récidive
+            max_slash_fraction: 0.5,        // 50% max par slashing
+            offense_window_days: 30,        // 30 jours
+            suspension_cooldown_hours: 24,  // 24h hors sélection
+            max_missed_challenges: 3,       // slashing au 4e défi manqué
+        }
+    }
+}
+
 /// Configuration du système de staking
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct StakingConfig {
@@ -242,6 +352,9 @@ pub struct StakingConfig {
     pub default_approval_threshold: f64,
     /// Commission maximum des validateurs (%)
     pub max_validator_commission: f64,
+    /// Paramètres de slashing
+    #[serde(default)]
+    pub slashing: SlashingConfig,
 }
 
 /// Métriques du système de staking
@@ -294,7 +407,7 @@ pub enum StakeType {
 }
 
 /// Statuts de stake
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum StakeStatus {
     /// Actif
     Active,
@@ -309,7 +422,7 @@ pub enum StakeStatus {
 }
 
 /// Statuts de validateur
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ValidatorStatus {
     /// Actif
     Active,
@@ -367,7 +480,7 @@ pub enum VotePosition {
 }
 
 /// Statut de délégation
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum DelegationStatus {
     /// Active
     Active,
@@ -416,6 +529,7 @@ impl Default for StakingConfig {
             minimum_quorum_percentage: 15.0,     // 15% de quorum
             default_approval_threshold: 60.0,    // 60% d'approbation
             max_validator_commission: 20.0,      // 20% commission max
+            slashing: SlashingConfig::default(),
         }
     }
 }
@@ -430,6 +544,8 @@ impl StakingSystem {
             delegations: HashMap::new(),
             config,
             metrics: StakingMetrics::new(),
+            slashing_history: Vec::new(),
+            missed_challenges: HashMap::new(),
             created_at: Utc::now(),
             last_updated: Utc::now(),
         }
@@ -461,8 +577,7 @@ impl StakingSystem {
         token.lock_tokens(&staker, amount, "governance_stake", tx_hash)?;
 
         // Calculer le multiplicateur de pouvoir de vote basé sur la durée de lock
-        let lock_multiplier = 1.0 + (lock_duration_days as f64 / 365.0) * (self.config.max_lock_duration_multiplier - 1.0);
-        let lock_multiplier = lock_multiplier.min(self.config.max_lock_duration_multiplier);
+        let lock_multiplier = self.lock_multiplier(lock_duration_days as f64);
 
         let stake = GovernanceStake {
             staker: staker.clone(),
@@ -521,6 +636,7 @@ impl StakingSystem {
             rewards_distributed_to_delegators: 0,
             penalties: Vec::new(),
             status: ValidatorStatus::Active,
+            suspended_until: None,
         };
 
         self.validator_stakes.insert(validator, stake);
@@ -558,8 +674,12 @@ impl StakingSystem {
         let voting_start = now + Duration::hours(24); // 24h de délai avant le vote
         let voting_end = voting_start + Duration::days(self.config.proposal_voting_duration_days as i64);
 
+        // Le pouvoir de vote est figé : un stake ou une délégation déplacés
+        // après la création ne votent pas une seconde fois
+        let voting_power_snapshot = self.voting_power_snapshot(now);
+
         // Calculer le quorum requis
-        let total_voting_power = self.calculate_total_voting_power();
+        let total_voting_power: u64 = voting_power_snapshot.values().sum();
         let required_quorum = required_quorum.unwrap_or(
             (total_voting_power as f64 * self.config.minimum_quorum_percentage / 100.0) as u64
         );
@@ -580,6 +700,7 @@ impl StakingSystem {
             votes_against: 0,
             votes_abstain: 0,
             vote_details: HashMap::new(),
+            voting_power_snapshot,
             status: ProposalStatus::Voting,
             execution_result: None,
         };
@@ -611,8 +732,8 @@ impl StakingSystem {
             });
         }
 
-        // Calculer le pouvoir de vote
-        let voting_power = self.calculate_voting_power(&voter)?;
+        // Pouvoir de vote à la création de la proposition
+        let voting_power = proposal.voting_power_snapshot.get(&voter).copied().unwrap_or(0);
         if voting_power == 0 {
             return Err(TokenOperationError::InsufficientStake {
                 required: self.config.min_governance_stake,
@@ -738,32 +859,191 @@ impl StakingSystem {
         Ok(())
     }
 
-    /// Calcule le pouvoir de vote d'une adresse
-    pub fn calculate_voting_power(&self, address: &PublicKey) -> TokenOperationResult<u64> {
-        let mut total_power = 0;
+    /// Délègue une partie de son stake de gouvernance à une autre adresse
+    ///
+    /// Un délégateur n'a qu'un délégué à la fois ; une nouvelle délégation vers le
+    /// même délégué s'ajoute à la précédente. Les cycles de délégation sont refusés.
+    pub fn delegate(&mut self, from: PublicKey, to: PublicKey, amount: u64) -> TokenOperationResult<()> {
+        if amount == 0 {
+            return Err(TokenOperationError::InvalidAmount { amount });
+        }
 
-        // Pouvoir de vote du stake de gouvernance
-        if let Some(stake) = self.governance_stakes.get(address) {
-            if stake.status == StakeStatus::Active || stake.status == StakeStatus::Locked {
-                total_power += (stake.amount as f64 * stake.voting_power_multiplier) as u64;
+        let staked = self.governance_stakes.get(&from)
+            .filter(|stake| matches!(stake.status, StakeStatus::Active | StakeStatus::Locked))
+            .map(|stake| stake.amount)
+            .unwrap_or(0);
+
+        let current = self.delegations.get(&from)
+            .filter(|delegation| delegation.status == DelegationStatus::Active);
+
+        if let Some(delegation) = current {
+            if delegation.delegate != to {
+                return Err(TokenOperationError::Internal {
+                    message: "Délégation déjà active vers une autre adresse".to_string(),
+                });
             }
         }
 
-        // Pouvoir de vote délégué
-        for delegation in self.delegations.values() {
-            if delegation.delegate == *address && delegation.status == DelegationStatus::Active {
-                total_power += delegation.voting_power_delegated;
-            }
+        let already_delegated = current.map(|d| d.voting_power_delegated).unwrap_or(0);
+        if already_delegated + amount > staked {
+            return Err(TokenOperationError::InsufficientStake {
+                required: already_delegated + amount,
+                provided: staked,
+            });
+        }
+
+        if self.leads_to(&to, &from) {
+            return Err(TokenOperationError::DelegationCycle {
+                delegator: from.to_string(),
+                delegate: to.to_string(),
+            });
+        }
+
+        let now = Utc::now();
+        let delegation = self.delegations.entry(from.clone())
+            .and_modify(|delegation| {
+                if delegation.status != DelegationStatus::Active {
+                    delegation.voting_power_delegated = 0;
+                    delegation.delegation_date = now;
+                }
+                delegation.delegate = to.clone();
+                delegation.status = DelegationStatus::Active;
+            })
+            .or_insert_with(|| VoteDelegation {
+                delegator: from,
+                delegate: to,
+                voting_power_delegated: 0,
+                delegation_date: now,
+                expiration_date: None,
+                status: DelegationStatus::Active,
+            });
+        delegation.voting_power_delegated += amount;
+
+        self.update_metrics();
+        Ok(())
+    }
+
+    /// Révoque tout ou partie d'une délégation ; retourne le montant encore délégué
+    pub fn undelegate(&mut self, from: PublicKey, to: PublicKey, amount: u64) -> TokenOperationResult<u64> {
+        let delegation = self.delegations.get_mut(&from)
+            .filter(|delegation| delegation.status == DelegationStatus::Active && delegation.delegate == to)
+            .ok_or_else(|| TokenOperationError::Internal {
+                message: "Aucune délégation active vers cette adresse".to_string(),
+            })?;
+
+        if amount == 0 || amount > delegation.voting_power_delegated {
+            return Err(TokenOperationError::InvalidAmount { amount });
+        }
+
+        delegation.voting_power_delegated -= amount;
+        if delegation.voting_power_delegated == 0 {
+            delegation.status = DelegationStatus::Revoked;
         }
+        let remaining = delegation.voting_power_delegated;
 
-        Ok(total_power)
+        self.update_metrics();
+        Ok(remaining)
+    }
+
+    /// Multiplicateur de pouvoir de vote pour une durée de lock donnée (1x à `max_lock_duration_multiplier`)
+    fn lock_multiplier(&self, lock_days: f64) -> f64 {
+        let multiplier = 1.0 + (lock_days.max(0.0) / 365.0) * (self.config.max_lock_duration_multiplier - 1.0);
+        multiplier.min(self.config.max_lock_duration_multiplier)
+    }
+
+    /// Multiplicateur effectif d'un stake à la date `now`
+    ///
+    /// Il décroît avec la durée de lock restante (arrondie au jour supérieur) et
+    /// retombe à 1x une fois le lock terminé.
+    pub fn duration_multiplier(&self, stake: &GovernanceStake, now: DateTime<Utc>) -> f64 {
+        let remaining_seconds = (stake.lock_end_date - now).num_seconds().max(0);
+        let remaining_days = (remaining_seconds + 86_399) / 86_400;
+        self.lock_multiplier(remaining_days as f64)
+    }
+
+    /// Pouvoir de vote effectif d'une adresse : stake propre non délégué et stakes
+    /// reçus en délégation, chacun pondéré par le multiplicateur de durée de son stakeur
+    pub fn effective_voting_power(&self, address: &PublicKey) -> u64 {
+        self.effective_voting_power_at(address, Utc::now())
+    }
+
+    /// Pouvoir de vote effectif d'une adresse à la date `now`
+    pub fn effective_voting_power_at(&self, address: &PublicKey, now: DateTime<Utc>) -> u64 {
+        let received: u64 = self.delegations.values()
+            .filter(|delegation| delegation.delegate == *address && delegation.status == DelegationStatus::Active)
+            .map(|delegation| self.weighted_stake(&delegation.delegator, delegation.voting_power_delegated, now))
+            .sum();
+
+        self.stake_voting_power_at(address, now) + received
+    }
+
+    /// Pouvoir de vote effectif de chaque adresse à la date `now`
+    ///
+    /// Seules les adresses disposant d'un pouvoir non nul y figurent ; leur
+    /// somme est le pouvoir de vote total à cette date.
+    pub fn voting_power_snapshot(&self, now: DateTime<Utc>) -> HashMap<PublicKey, u64> {
+        let delegates = self.delegations.values()
+            .filter(|delegation| delegation.status == DelegationStatus::Active)
+            .map(|delegation| &delegation.delegate);
+        self.governance_stakes.keys()
+            .chain(delegates)
+            .filter_map(|address| {
+                let power = self.effective_voting_power_at(address, now);
+                (power > 0).then(|| (address.clone(), power))
+            })
+            .collect()
+    }
+
+    /// Calcule le pouvoir de vote d'une adresse
+    pub fn calculate_voting_power(&self, address: &PublicKey) -> TokenOperationResult<u64> {
+        Ok(self.effective_voting_power(address))
+    }
+
+    /// Pouvoir de vote issu du seul stake de gouvernance non délégué
+    pub fn stake_voting_power(&self, address: &PublicKey) -> u64 {
+        self.stake_voting_power_at(address, Utc::now())
+    }
+
+    fn stake_voting_power_at(&self, address: &PublicKey, now: DateTime<Utc>) -> u64 {
+        let Some(stake) = self.governance_stakes.get(address) else { return 0 };
+        let delegated = self.delegations.get(address)
+            .filter(|delegation| delegation.status == DelegationStatus::Active)
+            .map(|delegation| delegation.voting_power_delegated)
+            .unwrap_or(0);
+
+        self.weighted_stake(address, stake.amount.saturating_sub(delegated), now)
+    }
+
+    /// Pondère un montant du stake de `staker` par son multiplicateur de durée
+    fn weighted_stake(&self, staker: &PublicKey, amount: u64, now: DateTime<Utc>) -> u64 {
+        self.governance_stakes.get(staker)
+            .filter(|stake| matches!(stake.status, StakeStatus::Active | StakeStatus::Locked))
+            .map(|stake| (amount as f64 * self.duration_multiplier(stake, now)) as u64)
+            .unwrap_or(0)
+    }
+
+    /// Indique si la chaîne de délégations partant de `start` atteint `target`
+    fn leads_to(&self, start: &PublicKey, target: &PublicKey) -> bool {
+        let mut current = start;
+        // Les cycles étant refusés, une chaîne ne peut dépasser le nombre de délégations
+        for _ in 0..=self.delegations.len() {
+            if current == target {
+                return true;
+            }
+            match self.delegations.get(current).filter(|d| d.status == DelegationStatus::Active) {
+                Some(delegation) => current = &delegation.delegate,
+                None => return false,
+            }
+        }
+        false
     }
 
     /// Calcule le pouvoir de vote total du système
-    fn calculate_total_voting_power(&self) -> u64 {
+    pub fn calculate_total_voting_power(&self) -> u64 {
+        let now = Utc::now();
         self.governance_stakes.values()
-            .filter(|stake| stake.status == StakeStatus::Active || stake.status == StakeStatus::Locked)
-            .map(|stake| (stake.amount as f64 * stake.voting_power_multiplier) as u64)
+            .filter(|stake| matches!(stake.status, StakeStatus::Active | StakeStatus::Locked))
+            .map(|stake| (stake.amount as f64 * self.duration_multiplier(stake, now)) as u64)
             .sum()
     }
 
@@ -871,6 +1151,159 @@ impl StakingSystem {
         Ok((validator_own_reward, delegator_rewards))
     }
 
+    /// Slashe un validateur en brûlant une fraction de son stake
+    pub fn slash(&mut self, validator: &PublicKey, reason: SlashReason, fraction: f64, evidence: Vec<Hash>, token: &mut ARCToken, tx_hash: Hash) -> TokenOperationResult<SlashingEvent> {
+        self.slash_at(validator, reason, fraction, evidence, token, tx_hash, Utc::now())
+    }
+
+    /// Slashe un validateur à la date `now`
+    ///
+    /// La fraction est multipliée par `escalation_factor` pour chaque slashing
+    /// antérieur dans la fenêtre d'escalade, dans la limite de `max_slash_fraction`.
+    /// Le validateur est ensuite suspendu de la sélection des leaders.
+    #[allow(clippy::too_many_arguments)]
+    pub fn slash_at(&mut self, validator: &PublicKey, reason: SlashReason, fraction: f64, evidence: Vec<Hash>, token: &mut ARCToken, tx_hash: Hash, now: DateTime<Utc>) -> TokenOperationResult<SlashingEvent> {
+        if !(fraction > 0.0 && fraction <= 1.0) {
+            return Err(TokenOperationError::Internal {
+                message: format!("Fraction de slashing invalide : {}", fraction),
+            });
+        }
+
+        let config = self.config.slashing.clone();
+        let window_start = now - Duration::days(config.offense_window_days as i64);
+        let prior_offenses = self.slashing_history.iter()
+            .filter(|e| &e.validator == validator && e.slashed_at > window_start)
+            .count() as u32;
+
+        let stake = self.validator_stakes.get_mut(validator).ok_or_else(|| TokenOperationError::Internal {
+            message: "Validateur introuvable".to_string(),
+        })?;
+
+        let applied_fraction = (fraction * config.escalation_factor.max(1.0).powi(prior_offenses as i32))
+            .min(config.max_slash_fraction)
+            .min(1.0);
+        let slashed_amount = ((stake.amount as f64 * applied_fraction).floor() as u64).min(stake.amount);
+
+        token.slash_locked_tokens(validator, slashed_amount, reason.label(), tx_hash.clone())?;
+
+        let suspended_until = now + Duration::hours(config.suspension_cooldown_hours as i64);
+        stake.amount -= slashed_amount;
+        stake.status = ValidatorStatus::Slashed;
+        stake.suspended_until = Some(suspended_until);
+        stake.penalties.push(ValidatorPenalty {
+            penalty_type: reason.penalty_type(),
+            penalty_amount: slashed_amount,
+            reason: reason.label().to_string(),
+            penalty_date: now,
+            transaction_hash: tx_hash.clone(),
+        });
+
+        let event = SlashingEvent {
+            validator: validator.clone(),
+            reason,
+            evidence,
+            requested_fraction: fraction,
+            applied_fraction,
+            slashed_amount,
+            offense_count: prior_offenses + 1,
+            slashed_at: now,
+            suspended_until,
+            transaction_hash: tx_hash,
+        };
+
+        self.slashing_history.push(event.clone());
+        self.metrics.total_validator_staked = self.metrics.total_validator_staked.saturating_sub(slashed_amount);
+        self.metrics.total_penalties_applied += slashed_amount;
+        self.update_metrics();
+
+        Ok(event)
+    }
+
+    /// Indique si un validateur peut être sélectionné comme leader
+    pub fn is_eligible_for_leader_selection(&self, validator: &PublicKey) -> bool {
+        self.is_eligible_for_leader_selection_at(validator, Utc::now())
+    }
+
+    /// Indique si un validateur peut être sélectionné comme leader à la date `now`
+    pub fn is_eligible_for_leader_selection_at(&self, validator: &PublicKey, now: DateTime<Utc>) -> bool {
+        self.validator_stakes.get(validator).map_or(false, |stake| {
+            match stake.status {
+                ValidatorStatus::Active => true,
+                ValidatorStatus::Slashed => stake.suspended_until.map_or(true, |until| now >= until),
+                _ => false,
+            }
+        })
+    }
+
+    /// Validateurs éligibles à la sélection des leaders à `at`, avec leur stake propre et délégué
+    pub fn leader_candidates_at(&self, at: DateTime<Utc>) -> Vec<(PublicKey, u64)> {
+        self.validator_stakes.values()
+            .filter(|stake| self.is_eligible_for_leader_selection_at(&stake.validator, at))
+            .map(|stake| (stake.validator.clone(), stake.amount.saturating_add(stake.delegated_amount)))
+            .collect()
+    }
+
+    /// Réactive les validateurs dont la suspension est terminée ; retourne leur nombre
+    pub fn release_expired_suspensions_at(&mut self, now: DateTime<Utc>) -> usize {
+        let mut released = 0;
+        for stake in self.validator_stakes.values_mut() {
+            if stake.status == ValidatorStatus::Slashed && stake.suspended_until.map_or(true, |until| now >= until) {
+                stake.status = ValidatorStatus::Active;
+                stake.suspended_until = None;
+                released += 1;
+            }
+        }
+        if released > 0 {
+            self.update_metrics();
+        }
+        released
+    }
+
+    /// Répercute les résultats de défis de stockage sur les stakes de validateurs
+    ///
+    /// Un validateur qui manque plus de `max_missed_challenges` défis dans la
+    /// fenêtre d'escalade est slashé, avec les défis manqués comme preuves.
+    /// Les résultats sont appliqués en bloc : en cas d'erreur, ni les stakes ni
+    /// `token` ne sont modifiés.
+    pub fn apply_challenge_outcomes(&mut self, entries: &[ChallengeAuditEntry], token: &mut ARCToken, tx_hash: Hash) -> TokenOperationResult<Vec<SlashingEvent>> {
+        let mut staged = self.clone();
+        let mut staged_token = token.clone();
+        let events = staged.stage_challenge_outcomes(entries, &mut staged_token, tx_hash)?;
+        *self = staged;
+        *token = staged_token;
+        Ok(events)
+    }
+
+    fn stage_challenge_outcomes(&mut self, entries: &[ChallengeAuditEntry], token: &mut ARCToken, tx_hash: Hash) -> TokenOperationResult<Vec<SlashingEvent>> {
+        let validators: HashMap<NodeId, PublicKey> = self.validator_stakes.keys()
+            .map(|key| (NodeId::from_public_key(key), key.clone()))
+            .collect();
+        let window = Duration::days(self.config.slashing.offense_window_days as i64);
+        let threshold = self.config.slashing.max_missed_challenges as usize;
+        let mut events = Vec::new();
+
+        for entry in entries.iter().filter(|e| !e.passed()) {
+            let Some(validator) = validators.get(&entry.node_id) else {
+                continue;
+            };
+
+            let missed = self.missed_challenges.entry(validator.clone()).or_default();
+            missed.retain(|m| m.recorded_at > entry.recorded_at - window);
+            missed.push(MissedChallenge {
+                challenge_id: entry.challenge_id.clone(),
+                recorded_at: entry.recorded_at,
+            });
+
+            if missed.len() > threshold {
+                let evidence = std::mem::take(missed).into_iter().map(|m| m.challenge_id).collect();
+                let fraction = self.config.slashing.base_slash_fraction;
+                events.push(self.slash_at(validator, SlashReason::MissedStorageChallenges, fraction, evidence, token, tx_hash.clone(), entry.recorded_at)?);
+            }
+        }
+
+        Ok(events)
+    }
+
     /// Met à jour les métriques du système
     fn update_metrics(&mut self) {
         self.metrics.governance_stakers_count = self.governance_stakes.len();
@@ -900,7 +1333,7 @@ impl StakingSystem {
             Some(StakeInfo {
                 stake_type: StakeType::Governance,
                 amount: gov_stake.amount,
-                voting_power: (gov_stake.amount as f64 * gov_stake.voting_power_multiplier) as u64,
+                voting_power: self.effective_voting_power(address),
                 accumulated_rewards: gov_stake.accumulated_rewards,
                 status: gov_stake.status.clone(),
             })
@@ -953,10 +1386,17 @@ impl Default for StakingSystem {
     }
 }
 
+impl ValidatorStakes for StakingSystem {
+    fn leader_candidates(&self, at: DateTime<Utc>) -> Vec<(PublicKey, u64)> {
+        self.leader_candidates_at(at)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use crate::crypto::generate_keypair;
+    use crate::token::TokenEventType;
 
     #[test]
     fn test_staking_system_creation() {
@@ -1087,4 +1527,251 @@ mod tests {
             assert!(validator_stake.delegators.contains_key(&delegator));
         }
     }
-}
\ No newline at end of file
+
+    fn staked_system(amounts: &[u64], lock_days: u32) -> (StakingSystem, Vec<PublicKey>) {
+        let mut system = StakingSystem::default();
+        let mut token = ARCToken::new();
+        let tx_hash = Hash::zero();
+        let mut keys = Vec::new();
+
+        for &amount in amounts {
+            let staker = generate_keypair().unwrap().public_key().clone();
+            token.mint(&staker, amount, tx_hash).unwrap();
+            system.create_governance_stake(staker.clone(), amount, lock_days, &mut token, tx_hash).unwrap();
+            keys.push(staker);
+        }
+        (system, keys)
+    }
+
+    #[test]
+    fn test_votes_use_power_snapshotted_at_proposal_creation() {
+        let (mut system, keys) = staked_system(&[2_000_000, 1_000_000], 365);
+        let (alice, bob) = (keys[0].clone(), keys[1].clone());
+
+        let proposal_id = system.create_proposal(alice.clone(), "Snapshot".to_string(), String::new(), ProposalType::General, None, None).unwrap();
+        assert_eq!(system.proposals[&proposal_id].voting_power_snapshot.values().sum::<u64>(), 6_000_000);
+        system.proposals.get_mut(&proposal_id).unwrap().voting_start = Utc::now() - Duration::hours(1);
+
+        // Alice délègue après la création : son pouvoir ne vote pas deux fois
+        system.delegate(alice.clone(), bob.clone(), 2_000_000).unwrap();
+        system.vote_on_proposal(alice.clone(), proposal_id, VotePosition::For, None, Signature::zero()).unwrap();
+        system.vote_on_proposal(bob.clone(), proposal_id, VotePosition::For, None, Signature::zero()).unwrap();
+        assert_eq!(system.proposals[&proposal_id].votes_for, 6_000_000);
+
+        // Un stake créé après la création ne vote pas
+        let (mut late, late_keys) = staked_system(&[5_000_000], 365);
+        system.governance_stakes.extend(late.governance_stakes.drain());
+        assert!(matches!(
+            system.vote_on_proposal(late_keys[0].clone(), proposal_id, VotePosition::Against, None, Signature::zero()),
+            Err(TokenOperationError::InsufficientStake { .. })
+        ));
+    }
+
+    #[test]
+    fn test_vote_delegation_and_revocation() {
+        let (mut system, keys) = staked_system(&[2_000_000, 1_000_000], 365);
+        let (alice, bob) = (keys[0].clone(), keys[1].clone());
+
+        // Lock d'un an : multiplicateur 2x
+        assert_eq!(system.effective_voting_power(&alice), 4_000_000);
+        assert_eq!(system.effective_voting_power(&bob), 2_000_000);
+
+        system.delegate(alice.clone(), bob.clone(), 500_000).unwrap();
+        system.delegate(alice.clone(), bob.clone(), 500_000).unwrap();
+        assert_eq!(system.effective_voting_power(&alice), 2_000_000);
+        assert_eq!(system.effective_voting_power(&bob), 4_000_000);
+        // La délégation ne crée pas de pouvoir de vote
+        assert_eq!(system.calculate_total_voting_power(), 6_000_000);
+
+        // Impossible de déléguer plus que son stake
+        assert!(matches!(
+            system.delegate(alice.clone(), bob.clone(), 1_500_000),
+            Err(TokenOperationError::InsufficientStake { .. })
+        ));
+
+        assert_eq!(system.undelegate(alice.clone(), bob.clone(), 400_000).unwrap(), 600_000);
+        assert_eq!(system.effective_voting_power(&bob), 3_200_000);
+        assert_eq!(system.undelegate(alice.clone(), bob.clone(), 600_000).unwrap(), 0);
+        assert_eq!(system.delegations[&alice].status, DelegationStatus::Revoked);
+        assert_eq!(system.effective_voting_power(&alice), 4_000_000);
+        assert!(system.undelegate(alice, bob, 1).is_err());
+    }
+
+    #[test]
+    fn test_delegation_cycles_are_rejected() {
+        let (mut system, keys) = staked_system(&[1_000_000, 1_000_000, 1_000_000], 30);
+
+        assert!(matches!(
+            system.delegate(keys[0].clone(), keys[0].clone(), 1_000),
+            Err(TokenOperationError::DelegationCycle { .. })
+        ));
+
+        system.delegate(keys[0].clone(), keys[1].clone(), 1_000).unwrap();
+        system.delegate(keys[1].clone(), keys[2].clone(), 1_000).unwrap();
+        assert!(matches!(
+            system.delegate(keys[2].clone(), keys[0].clone(), 1_000),
+            Err(TokenOperationError::DelegationCycle { .. })
+        ));
+
+        // Une fois la chaîne rompue, la délégation redevient possible
+        system.undelegate(keys[1].clone(), keys[2].clone(), 1_000).unwrap();
+        system.delegate(keys[2].clone(), keys[0].clone(), 1_000).unwrap();
+    }
+
+    #[test]
+    fn test_voting_power_decays_with_remaining_lock() {
+        let (system, keys) = staked_system(&[1_000_000], 365);
+        let staker = &keys[0];
+        let now = Utc::now();
+
+        assert_eq!(system.effective_voting_power_at(staker, now), 2_000_000);
+
+        // À mi-parcours, il reste environ six mois de lock
+        let halfway = system.effective_voting_power_at(staker, now + Duration::days(182));
+        assert!(halfway > 1_400_000 && halfway < 1_600_000);
+
+        // Lock terminé : plus de bonus de durée
+        assert_eq!(system.effective_voting_power_at(staker, now + Duration::days(400)), 1_000_000);
+    }
+
+    fn validator_system() -> (StakingSystem, ARCToken, PublicKey) {
+        let mut system = StakingSystem::default();
+        let mut token = ARCToken::new();
+        let validator = generate_keypair().unwrap().public_key().clone();
+        token.mint(&validator, 20_000_000, Hash::zero()).unwrap();
+        system.create_validator_stake(validator.clone(), 10_000_000, 0.05, &mut token, Hash::zero()).unwrap();
+        (system, token, validator)
+    }
+
+    #[test]
+    fn test_slash_burns_stake_and_suspends() {
+        let (mut system, mut token, validator) = validator_system();
+        let now = Utc::now();
+        let evidence = vec![Hash::from_bytes(&[7u8; 32]).unwrap()];
+
+        let event = system.slash_at(&validator, SlashReason::MissedStorageChallenges, 0.05, evidence.clone(), &mut token, Hash::zero(), now).unwrap();
+
+        assert_eq!(event.slashed_amount, 500_000);
+        assert_eq!(event.offense_count, 1);
+        assert_eq!(event.evidence, evidence);
+        assert_eq!(system.validator_stakes[&validator].amount, 9_500_000);
+        assert_eq!(system.validator_stakes[&validator].penalties.len(), 1);
+        assert_eq!(system.metrics.total_validator_staked, 9_500_000);
+        assert_eq!(system.metrics.total_penalties_applied, 500_000);
+        assert_eq!(token.burned_tokens, 500_000);
+        assert_eq!(token.locked_tokens, 9_500_000);
+        assert!(token.validate_integrity().is_ok());
+        assert!(matches!(
+            token.events.last().unwrap().event_type,
+            TokenEventType::Slashed { amount: 500_000, .. }
+        ));
+        assert!(!system.is_eligible_for_leader_selection_at(&validator, now));
+    }
+
+    #[test]
+    fn test_slash_escalates_within_window() {
+        let (mut system, mut token, validator) = validator_system();
+        let now = Utc::now();
+
+        let first = system.slash_at(&validator, SlashReason::MissedStorageChallenges, 0.01, vec![], &mut token, Hash::zero(), now).unwrap();
+        let second = system.slash_at(&validator, SlashReason::MissedStorageChallenges, 0.01, vec![], &mut token, Hash::zero(), now + Duration::days(1)).unwrap();
+        assert_eq!(first.applied_fraction, 0.01);
+        assert_eq!(second.applied_fraction, 0.02);
+        assert_eq!(second.offense_count, 2);
+
+        // Hors de la fenêtre, la fraction revient au niveau de base
+        let later = system.slash_at(&validator, SlashReason::MissedStorageChallenges, 0.01, vec![], &mut token, Hash::zero(), now + Duration::days(60)).unwrap();
+        assert_eq!(later.applied_fraction, 0.01);
+        assert_eq!(later.offense_count, 1);
+    }
+
+    #[test]
+    fn test_suspension_cooldown_expires() {
+        let (mut system, mut token, validator) = validator_system();
+        let now = Utc::now();
+        system.slash_at(&validator, SlashReason::RuleViolation, 0.01, vec![], &mut token, Hash::zero(), now).unwrap();
+
+        let cooldown = Duration::hours(system.config.slashing.suspension_cooldown_hours as i64);
+        assert!(!system.is_eligible_for_leader_selection_at(&validator, now + cooldown - Duration::minutes(1)));
+        assert!(system.is_eligible_for_leader_selection_at(&validator, now + cooldown));
+
+        assert_eq!(system.release_expired_suspensions_at(now + cooldown), 1);
+        assert_eq!(system.validator_stakes[&validator].status, ValidatorStatus::Active);
+        assert_eq!(system.metrics.active_validators_count, 1);
+    }
+
+    #[test]
+    fn test_stake_never_goes_negative() {
+        let (mut system, mut token, validator) = validator_system();
+        system.config.slashing.max_slash_fraction = 1.0;
+        let now = Utc::now();
+
+        for i in 0..10 {
+            system.slash_at(&validator, SlashReason::MaliciousBehavior, 1.0, vec![], &mut token, Hash::zero(), now + Duration::minutes(i)).unwrap();
+        }
+
+        assert_eq!(system.validator_stakes[&validator].amount, 0);
+        assert_eq!(system.metrics.total_validator_staked, 0);
+        assert_eq!(token.burned_tokens, 10_000_000);
+        assert_eq!(token.locked_tokens, 0);
+        assert!(token.validate_integrity().is_ok());
+        assert!(system.slash_at(&validator, SlashReason::MaliciousBehavior, 1.5, vec![], &mut token, Hash::zero(), now).is_err());
+    }
+
+    #[test]
+    fn test_missed_challenges_trigger_slashing() {
+        use crate::consensus::ChallengeOutcome;
+
+        let (mut system, mut token, validator) = validator_system();
+        let node_id = NodeId::from_public_key(&validator);
+        let now = Utc::now();
+        let entries: Vec<ChallengeAuditEntry> = (0..4u8).map(|i| ChallengeAuditEntry {
+            node_id: node_id.clone(),
+            challenge_id: Hash::from_bytes(&[i; 32]).unwrap(),
+            archive_hash: Hash::zero(),
+            nonce: i as u64,
+            issued_at: now,
+            recorded_at: now + Duration::minutes(i as i64),
+            outcome: ChallengeOutcome::TimedOut,
+            response_latency_ms: None,
+        }).collect();
+
+        // Les défis manqués sous le seuil ne déclenchent rien
+        let events = system.apply_challenge_outcomes(&entries[..3], &mut token, Hash::zero()).unwrap();
+        assert!(events.is_empty());
+
+        let events = system.apply_challenge_outcomes(&entries[3..], &mut token, Hash::zero()).unwrap();
+        assert_eq!(events.len(), 1);
+        assert_eq!(events[0].reason, SlashReason::MissedStorageChallenges);
+        assert_eq!(events[0].evidence.len(), 4);
+        assert!(system.missed_challenges[&validator].is_empty());
+    }
+
+    #[test]
+    fn test_failed_challenge_slashing_leaves_state_untouched() {
+        use crate::consensus::ChallengeOutcome;
+
+        let (mut system, mut token, validator) = validator_system();
+        // Fraction invalide : le slashing échoue une fois le seuil franchi
+        system.config.slashing.base_slash_fraction = 2.0;
+        let node_id = NodeId::from_public_key(&validator);
+        let now = Utc::now();
+        let entries: Vec<ChallengeAuditEntry> = (0..4u8).map(|i| ChallengeAuditEntry {
+            node_id: node_id.clone(),
+            challenge_id: Hash::from_bytes(&[i; 32]).unwrap(),
+            archive_hash: Hash::zero(),
+            nonce: i as u64,
+            issued_at: now,
+            recorded_at: now + Duration::minutes(i as i64),
+            outcome: ChallengeOutcome::Failed,
+            response_latency_ms: None,
+        }).collect();
+        let stake_before = system.validator_stakes[&validator].amount;
+        let burned_before = token.burned_tokens;
+
+        assert!(system.apply_challenge_outcomes(&entries, &mut token, Hash::zero()).is_err());
+        assert!(!system.missed_challenges.contains_key(&validator));
+        assert_eq!(system.validator_stakes[&validator].amount, stake_before);
+        assert_eq!(token.burned_tokens, burned_before);
+    }
+}
@@ -10,8 +10,10 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use chrono::{DateTime, Utc, Duration};
-use crate::crypto::{Hash, PublicKey, Signature};
-use super::{TokenOperationResult, TokenOperationError, ARCToken, COMMUNITY_RESERVE};
+use crate::crypto::{compute_blake3, Hash, PublicKey, Signature};
+use crate::transaction::{Transaction, TransactionOutput, TransactionValidator};
+use super::{TokenOperationResult, TokenOperationError, TokenConfig, TokenEvent, TokenEventType, ARCToken, COMMUNITY_RESERVE};
+use super::staking::StakingSystem;
 
 /// Système de treasury principal
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -36,6 +38,9 @@ pub struct Treasury {
     pub metrics: TreasuryMetrics,
     /// Historique des transactions
     pub transaction_history: Vec<TreasuryTransaction>,
+    /// Événements de gouvernance émis (création de propositions, votes)
+    #[serde(default)]
+    pub events: Vec<TokenEvent>,
     /// Timestamp de création
     pub created_at: DateTime<Utc>,
     /// Dernière mise à jour
@@ -79,6 +84,35 @@ pub struct TreasuryProposal {
     pub evaluation_report: Option<EvaluationReport>,
     /// Résultat du vote
     pub voting_result: Option<VotingResult>,
+    /// Relecteurs habilités à valider les jalons, fixés à l'approbation
+    #[serde(default)]
+    pub milestone_reviewers: Vec<PublicKey>,
+    /// Votes de validation de chaque jalon, par votant
+    #[serde(default)]
+    pub milestone_votes: HashMap<Hash, HashMap<PublicKey, TreasuryVote>>,
+    /// Pouvoir de vote de chaque adresse, figé à la soumission on-chain
+    #[serde(default)]
+    pub voting_power_snapshot: HashMap<PublicKey, u64>,
+}
+
+impl TreasuryProposal {
+    /// Pouvoir de vote de `voter` à la soumission de la proposition
+    ///
+    /// Une proposition enregistrée sans instantané retombe sur le pouvoir courant.
+    fn voting_power_at_submission(&self, voter: &PublicKey, staking: &StakingSystem) -> u64 {
+        if self.voting_power_snapshot.is_empty() {
+            return staking.effective_voting_power(voter);
+        }
+        self.voting_power_snapshot.get(voter).copied().unwrap_or(0)
+    }
+
+    /// Pouvoir de vote total servant au quorum de la proposition
+    fn total_voting_power(&self, staking: &StakingSystem) -> u64 {
+        if self.voting_power_snapshot.is_empty() {
+            return staking.calculate_total_voting_power();
+        }
+        self.voting_power_snapshot.values().sum()
+    }
 }
 
 /// Budget approuvé
@@ -219,6 +253,22 @@ pub struct TreasuryConfig {
     pub max_project_duration_months: u32,
     /// Pourcentage maximum du treasury par proposition
     pub max_treasury_percentage_per_proposal: f64,
+    /// Signataires autorisés des débours multisig
+    #[serde(default)]
+    pub disbursement_signers: Vec<PublicKey>,
+    /// Nombre de signatures requises pour un débours multisig
+    #[serde(default)]
+    pub disbursement_threshold: u32,
+    /// Relecteurs désignés pour valider les jalons des propositions approuvées
+    #[serde(default)]
+    pub milestone_reviewers: Vec<PublicKey>,
+    /// Délai de grâce après la date prévue d'un jalon avant reprise des fonds (jours)
+    #[serde(default = "default_milestone_grace_period_days")]
+    pub milestone_grace_period_days: u32,
+}
+
+fn default_milestone_grace_period_days() -> u32 {
+    30
 }
 
 /// Métriques du treasury
@@ -458,16 +508,20 @@ pub enum ProposalCategory {
     Other,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum ProposalStatus {
     Draft,
     Submitted,
     UnderReview,
     Voting,
     Approved,
+    /// Approuvée et entièrement déboursée
+    Executed,
     Rejected,
     Expired,
     Withdrawn,
+    /// Annulée, fonds non déboursés rendus au treasury
+    Cancelled,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -554,13 +608,22 @@ pub enum BudgetCategory {
     Other,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
 pub enum MilestoneStatus {
     Planned,
     InProgress,
     Completed,
     Delayed,
     Cancelled,
+    /// Non atteint à la fin du délai de grâce, fonds repris
+    Expired,
+}
+
+impl MilestoneStatus {
+    /// Vrai tant que le jalon peut encore être validé
+    pub fn is_pending(&self) -> bool {
+        matches!(self, MilestoneStatus::Planned | MilestoneStatus::InProgress | MilestoneStatus::Delayed)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -599,6 +662,10 @@ impl Default for TreasuryConfig {
             max_active_proposals: 20,                       // Max 20 propositions actives
             max_project_duration_months: 24,                // Max 2 ans par projet
             max_treasury_percentage_per_proposal: 5.0,      // Max 5% du treasury
+            disbursement_signers: Vec::new(),               // Débours multisig désactivés
+            disbursement_threshold: 0,
+            milestone_reviewers: Vec::new(),                // Validation des jalons par vote seulement
+            milestone_grace_period_days: default_milestone_grace_period_days(),
         }
     }
 }
@@ -617,13 +684,257 @@ impl Treasury {
             config,
             metrics: TreasuryMetrics::new(),
             transaction_history: Vec::new(),
+            events: Vec::new(),
             created_at: Utc::now(),
             last_updated: Utc::now(),
         }
     }
 
-    /// Soumet une nouvelle proposition
-    pub fn submit_proposal(&mut self, proposer: PublicKey, title: String, description: String, category: ProposalCategory, requested_amount: u64, budget_breakdown: Vec<BudgetItem>, beneficiary: PublicKey, milestones: Vec<Milestone>) -> TokenOperationResult<Hash> {
+    /// Soumet une proposition de financement ouverte immédiatement au vote on-chain
+    ///
+    /// Le proposeur doit détenir un stake de gouvernance d'au moins
+    /// `TokenConfig::min_governance_stake` et le montant ne peut dépasser les fonds disponibles.
+    pub fn submit_proposal(&mut self, proposer: PublicKey, amount: u64, recipient: PublicKey, description: String, staking: &StakingSystem, token_config: &TokenConfig) -> TokenOperationResult<Hash> {
+        self.submit_milestone_proposal(proposer, amount, recipient, description, Vec::new(), staking, token_config)
+    }
+
+    /// Soumet une proposition au vote on-chain, versée par jalons
+    ///
+    /// Les montants des jalons doivent totaliser le montant demandé. Une fois la
+    /// proposition approuvée, chaque jalon n'est versé qu'après validation par vote
+    /// (`vote_milestone`) ou par un relecteur désigné (`complete_milestone`).
+    pub fn submit_milestone_proposal(&mut self, proposer: PublicKey, amount: u64, recipient: PublicKey, description: String, milestones: Vec<Milestone>, staking: &StakingSystem, token_config: &TokenConfig) -> TokenOperationResult<Hash> {
+        let stake_amount = staking.governance_stakes.get(&proposer)
+            .map(|stake| stake.amount)
+            .unwrap_or(0);
+        if stake_amount < token_config.min_governance_stake {
+            return Err(TokenOperationError::InsufficientStake {
+                required: token_config.min_governance_stake,
+                provided: stake_amount,
+            });
+        }
+
+        if amount == 0 {
+            return Err(TokenOperationError::InvalidAmount { amount });
+        }
+
+        if amount > self.available_funds {
+            return Err(TokenOperationError::InsufficientBalance {
+                required: amount,
+                available: self.available_funds,
+            });
+        }
+
+        validate_milestones(amount, &milestones)?;
+
+        let now = Utc::now();
+        let proposal_id = compute_blake3(&[
+            proposer.as_bytes().as_slice(),
+            recipient.as_bytes().as_slice(),
+            &amount.to_le_bytes(),
+            description.as_bytes(),
+            &now.timestamp_millis().to_le_bytes(),
+            &(self.metrics.total_proposals as u64).to_le_bytes(),
+        ].concat());
+
+        let proposal = TreasuryProposal {
+            proposal_id,
+            proposer: proposer.clone(),
+            title: description.chars().take(64).collect(),
+            description,
+            category: ProposalCategory::Other,
+            requested_amount: amount,
+            budget_breakdown: Vec::new(),
+            beneficiary: recipient,
+            milestones,
+            success_criteria: Vec::new(),
+            submitted_at: now,
+            voting_period: VotingPeriod {
+                start_date: now,
+                end_date: now + Duration::days(self.config.default_voting_duration_days as i64),
+                voting_type: VotingType::Weighted,
+            },
+            votes: HashMap::new(),
+            status: ProposalStatus::Voting,
+            assigned_committee: None,
+            evaluation_report: None,
+            voting_result: None,
+            milestone_reviewers: Vec::new(),
+            milestone_votes: HashMap::new(),
+            voting_power_snapshot: staking.voting_power_snapshot(now),
+        };
+
+        self.proposals.insert(proposal_id, proposal);
+        self.metrics.total_proposals += 1;
+        self.events.push(TokenEvent {
+            transaction_hash: Hash::zero(),
+            event_type: TokenEventType::ProposalCreated {
+                proposer,
+                proposal_id,
+                stake_amount,
+            },
+            timestamp: now,
+            data: HashMap::new(),
+        });
+        self.update_metrics();
+
+        Ok(proposal_id)
+    }
+
+    /// Vote pour ou contre une proposition avec le pouvoir issu du stake de gouvernance
+    ///
+    /// La proposition est clôturée dès que le quorum est atteint. Retourne le statut
+    /// de la proposition après prise en compte du vote.
+    pub fn vote(&mut self, voter: PublicKey, proposal_id: Hash, support: bool, staking: &StakingSystem) -> TokenOperationResult<ProposalStatus> {
+        let now = Utc::now();
+
+        let proposal = self.proposals.get_mut(&proposal_id)
+            .ok_or(TokenOperationError::ProposalNotFound { proposal_id })?;
+        let voting_power = proposal.voting_power_at_submission(&voter, staking);
+        let total_voting_power = proposal.total_voting_power(staking);
+
+        if proposal.status != ProposalStatus::Voting {
+            return Err(TokenOperationError::Internal {
+                message: "Proposition non ouverte au vote".to_string(),
+            });
+        }
+
+        if now > proposal.voting_period.end_date {
+            return Err(TokenOperationError::Internal {
+                message: "Période de vote fermée".to_string(),
+            });
+        }
+
+        if proposal.votes.contains_key(&voter) {
+            return Err(TokenOperationError::Internal {
+                message: "Vote déjà enregistré".to_string(),
+            });
+        }
+
+        if voting_power == 0 {
+            return Err(TokenOperationError::InsufficientStake { required: 1, provided: 0 });
+        }
+
+        proposal.votes.insert(voter.clone(), TreasuryVote {
+            voter: voter.clone(),
+            position: if support { VotePosition::For } else { VotePosition::Against },
+            voting_power,
+            justification: None,
+            vote_date: now,
+            signature: Signature::zero(),
+        });
+
+        self.events.push(TokenEvent {
+            transaction_hash: Hash::zero(),
+            event_type: TokenEventType::ProposalVoted {
+                voter,
+                proposal_id,
+                voting_power,
+                support,
+            },
+            timestamp: now,
+            data: HashMap::new(),
+        });
+
+        let status = self.settle_proposal(proposal_id, total_voting_power, now, false)?;
+        self.update_metrics();
+        Ok(status)
+    }
+
+    /// Clôture les propositions dont la période de vote est terminée
+    ///
+    /// Retourne les propositions clôturées avec leur statut final.
+    pub fn process_expired_proposals(&mut self, now: DateTime<Utc>, staking: &StakingSystem) -> TokenOperationResult<Vec<(Hash, ProposalStatus)>> {
+        let expired: Vec<(Hash, u64)> = self.proposals.values()
+            .filter(|p| p.status == ProposalStatus::Voting && now > p.voting_period.end_date)
+            .map(|p| (p.proposal_id, p.total_voting_power(staking)))
+            .collect();
+
+        let mut settled = Vec::with_capacity(expired.len());
+        for (proposal_id, total_power) in expired {
+            let status = self.settle_proposal(proposal_id, total_power, now, true)?;
+            settled.push((proposal_id, status));
+        }
+
+        if !settled.is_empty() {
+            self.update_metrics();
+        }
+        Ok(settled)
+    }
+
+    /// Décide de l'issue d'une proposition en vote
+    ///
+    /// Avant l'échéance, seule l'atteinte du quorum clôture la proposition. À l'échéance,
+    /// une proposition sans aucun vote expire et une proposition sous le quorum est rejetée.
+    ///
+    /// L'issue ne dépend que du quorum et de la majorité. Les fonds d'une proposition
+    /// approuvée sont réservés sur `available_funds` s'ils suffisent, sinon la réservation
+    /// est retentée à l'exécution.
+    fn settle_proposal(&mut self, proposal_id: Hash, total_voting_power: u64, now: DateTime<Utc>, deadline_reached: bool) -> TokenOperationResult<ProposalStatus> {
+        let proposal = self.proposals.get_mut(&proposal_id)
+            .ok_or(TokenOperationError::ProposalNotFound { proposal_id })?;
+
+        let mut votes_for = 0u64;
+        let mut votes_against = 0u64;
+        let mut votes_abstain = 0u64;
+        for vote in proposal.votes.values() {
+            match vote.position {
+                VotePosition::For => votes_for += vote.voting_power,
+                VotePosition::Against => votes_against += vote.voting_power,
+                VotePosition::Abstain => votes_abstain += vote.voting_power,
+            }
+        }
+
+        let total_votes = votes_for + votes_against + votes_abstain;
+        let quorum_reached = total_voting_power > 0
+            && (total_votes as f64 / total_voting_power as f64) * 100.0 >= self.config.minimum_quorum_percentage;
+
+        if !quorum_reached && !deadline_reached {
+            return Ok(ProposalStatus::Voting);
+        }
+
+        if total_votes == 0 {
+            proposal.status = ProposalStatus::Expired;
+            return Ok(ProposalStatus::Expired);
+        }
+
+        let approval_rate = if votes_for + votes_against > 0 {
+            (votes_for as f64 / (votes_for + votes_against) as f64) * 100.0
+        } else {
+            0.0
+        };
+        let approval_threshold_met = approval_rate >= self.config.approval_threshold_percentage;
+        let approved = quorum_reached && approval_threshold_met;
+
+        proposal.voting_result = Some(VotingResult {
+            votes_for,
+            votes_against,
+            votes_abstain,
+            quorum_reached,
+            approval_threshold_met,
+            result: approved,
+            finalized_at: now,
+        });
+
+        if !approved {
+            proposal.status = ProposalStatus::Rejected;
+            self.metrics.rejected_proposals += 1;
+            return Ok(ProposalStatus::Rejected);
+        }
+
+        proposal.status = ProposalStatus::Approved;
+        proposal.milestone_reviewers = self.config.milestone_reviewers.clone();
+        let amount = proposal.requested_amount;
+        self.metrics.approved_proposals += 1;
+        if amount <= self.available_funds {
+            self.allocate(proposal_id);
+        }
+
+        Ok(ProposalStatus::Approved)
+    }
+
+    /// Soumet une nouvelle proposition détaillée (budget, jalons, catégorie)
+    pub fn submit_detailed_proposal(&mut self, proposer: PublicKey, title: String, description: String, category: ProposalCategory, requested_amount: u64, budget_breakdown: Vec<BudgetItem>, beneficiary: PublicKey, milestones: Vec<Milestone>) -> TokenOperationResult<Hash> {
         // Validations
         if requested_amount < self.config.min_proposal_amount {
             return Err(TokenOperationError::InvalidAmount { amount: requested_amount });
@@ -646,6 +957,8 @@ impl Treasury {
             });
         }
 
+        validate_milestones(requested_amount, &milestones)?;
+
         // Générer ID de proposition
         let proposal_id = Hash::from_bytes([
             &proposer.as_bytes()[..16],
@@ -678,6 +991,9 @@ impl Treasury {
             assigned_committee: None,
             evaluation_report: None,
             voting_result: None,
+            milestone_reviewers: Vec::new(),
+            milestone_votes: HashMap::new(),
+            voting_power_snapshot: HashMap::new(),
         };
 
         self.proposals.insert(proposal_id, proposal);
@@ -785,6 +1101,7 @@ impl Treasury {
 
         if approved {
             proposal.status = ProposalStatus::Approved;
+            proposal.milestone_reviewers = self.config.milestone_reviewers.clone();
             self.approve_proposal(proposal_id)?;
             self.metrics.approved_proposals += 1;
         } else {
@@ -959,6 +1276,515 @@ impl Treasury {
         Ok(disbursement.amount)
     }
 
+    /// Exécute une proposition approuvée en versant les fonds demandés au bénéficiaire
+    ///
+    /// Les fonds non réservés à l'approbation sont prélevés sur `available_funds`,
+    /// sans quoi l'exécution échoue avec `InsufficientTreasuryFunds` et la
+    /// proposition reste exécutable. Les fonds sont versés avant toute mise à jour
+    /// des compteurs du treasury, qui ne sont modifiés que si le versement réussit ;
+    /// une proposition n'est exécutée qu'une fois.
+    ///
+    /// Lorsque des signataires de débours sont configurés, le versement passe par
+    /// `execute_disbursement`.
+    pub fn execute_proposal(&mut self, proposal_id: Hash, token: &mut ARCToken, tx_hash: Hash) -> TokenOperationResult<u64> {
+        if !self.config.disbursement_signers.is_empty() {
+            return Err(TokenOperationError::MultisigRejected {
+                message: "le débours de cette proposition exige le multisig".to_string(),
+            });
+        }
+
+        let proposal = self.disbursable_proposal(proposal_id)?;
+        let amount = proposal.requested_amount;
+        let beneficiary = proposal.beneficiary.clone();
+        let title = proposal.title.clone();
+        let needs_allocation = self.check_funding(proposal_id, amount)?;
+
+        token.mint(&beneficiary, amount, tx_hash)?;
+        self.complete_disbursement(proposal_id, needs_allocation, format!("Exécution: {}", title), tx_hash);
+
+        self.update_metrics();
+        Ok(amount)
+    }
+
+    /// Construit la transaction multisig de débours d'une proposition approuvée
+    ///
+    /// La transaction verse le montant demandé au bénéficiaire et référence la
+    /// proposition dans ses données. Elle doit ensuite être signée par au moins
+    /// `disbursement_threshold` des `disbursement_signers` avant `execute_disbursement`.
+    pub fn disbursement_transaction(&self, proposal_id: Hash, fee: u64) -> TokenOperationResult<Transaction> {
+        let proposal = self.disbursable_proposal(proposal_id)?;
+        if self.config.disbursement_signers.is_empty() {
+            return Err(TokenOperationError::MultisigRejected {
+                message: "aucun signataire de débours configuré".to_string(),
+            });
+        }
+
+        Ok(Transaction::multisig(
+            self.config.disbursement_signers.clone(),
+            self.config.disbursement_threshold,
+            vec![TransactionOutput {
+                amount: proposal.requested_amount,
+                recipient: proposal.beneficiary.clone(),
+                lock_script: Vec::new(),
+            }],
+            fee,
+            proposal_id.as_bytes().to_vec(),
+        ))
+    }
+
+    /// Exécute un débours multisig et verse les fonds alloués au bénéficiaire
+    ///
+    /// La transaction doit porter la politique de signature du treasury, réunir
+    /// le seuil de signatures distinctes et correspondre exactement à la
+    /// proposition référencée. Une proposition n'est déboursée qu'une fois.
+    pub fn execute_disbursement(&mut self, transaction: &Transaction, validator: &TransactionValidator, token: &mut ARCToken) -> TokenOperationResult<u64> {
+        let crate::transaction::TransactionType::Multisig { signers, threshold, .. } = &transaction.tx_type else {
+            return Err(TokenOperationError::MultisigRejected {
+                message: "le débours doit être une transaction multisig".to_string(),
+            });
+        };
+        if self.config.disbursement_signers.is_empty()
+            || *signers != self.config.disbursement_signers
+            || *threshold != self.config.disbursement_threshold
+        {
+            return Err(TokenOperationError::MultisigRejected {
+                message: "politique de signature différente de celle du treasury".to_string(),
+            });
+        }
+
+        validator.validate_multisig(transaction)
+            .map_err(|e| TokenOperationError::MultisigRejected { message: e.to_string() })?;
+
+        let proposal_id = Hash::from_bytes(&transaction.data)
+            .map_err(|_| TokenOperationError::MultisigRejected {
+                message: "référence de proposition invalide".to_string(),
+            })?;
+        let proposal = self.disbursable_proposal(proposal_id)?;
+        let amount = proposal.requested_amount;
+        let beneficiary = proposal.beneficiary.clone();
+        let title = proposal.title.clone();
+
+        match transaction.outputs.as_slice() {
+            [output] if output.amount == amount && output.recipient == beneficiary => {}
+            _ => {
+                return Err(TokenOperationError::MultisigRejected {
+                    message: "les sorties ne correspondent pas à la proposition".to_string(),
+                })
+            }
+        }
+
+        let needs_allocation = self.check_funding(proposal_id, amount)?;
+
+        token.mint(&beneficiary, amount, *transaction.hash())?;
+        self.complete_disbursement(proposal_id, needs_allocation, format!("Débours multisig: {}", title), *transaction.hash());
+
+        self.update_metrics();
+        Ok(amount)
+    }
+
+    /// Valide un jalon d'une proposition approuvée en tant que relecteur désigné
+    ///
+    /// Seuls les relecteurs fixés à l'approbation de la proposition peuvent valider
+    /// ses jalons. Le montant du jalon passe des fonds alloués aux fonds déboursés.
+    pub fn complete_milestone(&mut self, reviewer: &PublicKey, proposal_id: Hash, milestone_id: Hash, token: &mut ARCToken, tx_hash: Hash) -> TokenOperationResult<u64> {
+        let proposal = self.proposals.get(&proposal_id)
+            .ok_or(TokenOperationError::ProposalNotFound { proposal_id: proposal_id.clone() })?;
+        if !proposal.milestone_reviewers.contains(reviewer) {
+            return Err(TokenOperationError::MilestoneRejected {
+                message: "relecteur non désigné pour cette proposition".to_string(),
+            });
+        }
+
+        let amount = self.release_milestone(&proposal_id, &milestone_id, Utc::now(), token, tx_hash)?;
+        self.update_metrics();
+        Ok(amount)
+    }
+
+    /// Vote pour ou contre la validation d'un jalon avec le pouvoir issu du stake de gouvernance
+    ///
+    /// Le proposeur et le bénéficiaire ne votent pas sur leurs propres jalons, et
+    /// leur pouvoir est retiré du total servant au quorum. Le jalon est versé dès
+    /// que les votes exprimés atteignent le quorum et que la part des votes pour
+    /// atteint le seuil d'approbation. Retourne le statut du jalon après prise en
+    /// compte du vote.
+    pub fn vote_milestone(&mut self, voter: PublicKey, proposal_id: Hash, milestone_id: Hash, approve: bool, staking: &StakingSystem, token: &mut ARCToken, tx_hash: Hash) -> TokenOperationResult<MilestoneStatus> {
+        let now = Utc::now();
+        let status = self.pending_milestone(&proposal_id, &milestone_id, now)?.status.clone();
+        let proposal = self.proposals.get_mut(&proposal_id)
+            .ok_or(TokenOperationError::ProposalNotFound { proposal_id: proposal_id.clone() })?;
+        if voter == proposal.proposer || voter == proposal.beneficiary {
+            return Err(TokenOperationError::MilestoneRejected {
+                message: "le proposeur et le bénéficiaire ne votent pas sur leurs jalons".to_string(),
+            });
+        }
+
+        let voting_power = staking.effective_voting_power(&voter);
+        if voting_power == 0 {
+            return Err(TokenOperationError::InsufficientStake { required: 1, provided: 0 });
+        }
+
+        let votes = proposal.milestone_votes.entry(milestone_id.clone()).or_default();
+        if votes.contains_key(&voter) {
+            return Err(TokenOperationError::Internal {
+                message: "Vote déjà enregistré".to_string(),
+            });
+        }
+        votes.insert(voter.clone(), TreasuryVote {
+            voter,
+            position: if approve { VotePosition::For } else { VotePosition::Against },
+            voting_power,
+            justification: None,
+            vote_date: now,
+            signature: Signature::zero(),
+        });
+
+        let (mut votes_for, mut votes_against) = (0u64, 0u64);
+        for vote in votes.values() {
+            match vote.position {
+                VotePosition::For => votes_for += vote.voting_power,
+                VotePosition::Against => votes_against += vote.voting_power,
+                VotePosition::Abstain => {}
+            }
+        }
+
+        let mut excluded_power = staking.effective_voting_power(&proposal.proposer);
+        if proposal.beneficiary != proposal.proposer {
+            excluded_power += staking.effective_voting_power(&proposal.beneficiary);
+        }
+        let eligible_power = staking.calculate_total_voting_power().saturating_sub(excluded_power);
+        let cast = votes_for + votes_against;
+        let quorum_reached = eligible_power > 0
+            && (cast as f64 / eligible_power as f64) * 100.0 >= self.config.minimum_quorum_percentage;
+        let approval_threshold_met = cast > 0
+            && (votes_for as f64 / cast as f64) * 100.0 >= self.config.approval_threshold_percentage;
+        if !quorum_reached || !approval_threshold_met {
+            return Ok(status);
+        }
+
+        self.release_milestone(&proposal_id, &milestone_id, now, token, tx_hash)?;
+        self.update_metrics();
+        Ok(MilestoneStatus::Completed)
+    }
+
+    /// Reprend les fonds des jalons non atteints à la fin du délai de grâce
+    ///
+    /// Les fonds réservés pour ces jalons retournent dans `available_funds`.
+    /// Retourne les jalons expirés avec le montant repris.
+    pub fn process_expired_milestones(&mut self, now: DateTime<Utc>) -> Vec<(Hash, Hash, u64)> {
+        let grace_period = Duration::days(self.config.milestone_grace_period_days as i64);
+        let expired: Vec<(Hash, Hash)> = self.proposals.values()
+            .filter(|p| p.status == ProposalStatus::Approved)
+            .flat_map(|p| {
+                p.milestones.iter()
+                    .filter(|m| m.status.is_pending() && now > m.target_date + grace_period)
+                    .map(move |m| (p.proposal_id.clone(), m.milestone_id.clone()))
+            })
+            .collect();
+
+        let mut clawed_back = Vec::with_capacity(expired.len());
+        for (proposal_id, milestone_id) in expired {
+            let allocated = self.is_allocated(proposal_id.clone());
+            let Some(milestone) = self.proposals.get_mut(&proposal_id)
+                .and_then(|p| p.milestones.iter_mut().find(|m| m.milestone_id == milestone_id))
+            else {
+                continue;
+            };
+            milestone.status = MilestoneStatus::Expired;
+            let amount = if allocated { milestone.payment_amount } else { 0 };
+            let description = format!("Jalon expiré: {}", milestone.name);
+
+            if amount > 0 {
+                self.allocated_funds -= amount;
+                self.available_funds += amount;
+                self.record_transaction(TransactionType::Refund, amount, None, None, Some(proposal_id.clone()), description, Hash::zero());
+            }
+            self.close_milestone_schedule(&proposal_id);
+            clawed_back.push((proposal_id, milestone_id, amount));
+        }
+
+        if !clawed_back.is_empty() {
+            self.update_metrics();
+        }
+        clawed_back
+    }
+
+    /// Annule une proposition non close et rend ses fonds non déboursés
+    ///
+    /// Seuls le proposeur et les relecteurs de jalons peuvent l'annuler. Les jalons
+    /// en attente sont annulés ; les fonds réservés et non encore versés retournent
+    /// dans `available_funds`. Retourne le montant rendu.
+    pub fn cancel_proposal(&mut self, caller: &PublicKey, proposal_id: Hash) -> TokenOperationResult<u64> {
+        let allocated = self.is_allocated(proposal_id.clone());
+        let proposal = self.proposals.get_mut(&proposal_id)
+            .ok_or(TokenOperationError::ProposalNotFound { proposal_id: proposal_id.clone() })?;
+
+        // Avant l'approbation, les relecteurs sont ceux de la configuration
+        let reviewers = match proposal.status {
+            ProposalStatus::Approved => &proposal.milestone_reviewers,
+            _ => &self.config.milestone_reviewers,
+        };
+        if *caller != proposal.proposer && !reviewers.contains(caller) {
+            return Err(TokenOperationError::Unauthorized { address: caller.to_hex() });
+        }
+
+        let approved = match proposal.status {
+            ProposalStatus::Submitted | ProposalStatus::UnderReview | ProposalStatus::Voting => false,
+            ProposalStatus::Approved => true,
+            ProposalStatus::Executed => return Err(TokenOperationError::ProposalAlreadyExecuted { proposal_id }),
+            _ => {
+                return Err(TokenOperationError::Internal {
+                    message: "Proposition déjà close".to_string(),
+                })
+            }
+        };
+
+        let undisbursed = if proposal.milestones.is_empty() {
+            proposal.requested_amount
+        } else {
+            proposal.milestones.iter()
+                .filter(|m| m.status.is_pending())
+                .map(|m| m.payment_amount)
+                .sum()
+        };
+        for milestone in proposal.milestones.iter_mut().filter(|m| m.status.is_pending()) {
+            milestone.status = MilestoneStatus::Cancelled;
+        }
+        proposal.status = ProposalStatus::Cancelled;
+        let description = format!("Annulation: {}", proposal.title);
+
+        for budget in self.approved_budgets.values_mut().filter(|b| b.proposal_id == proposal_id) {
+            budget.status = BudgetStatus::Cancelled;
+        }
+
+        let refunded = if approved && allocated { undisbursed } else { 0 };
+        if refunded > 0 {
+            self.allocated_funds -= refunded;
+            self.available_funds += refunded;
+            self.record_transaction(TransactionType::Refund, refunded, None, None, Some(proposal_id), description, Hash::zero());
+        }
+
+        self.update_metrics();
+        Ok(refunded)
+    }
+
+    /// État des jalons d'une proposition
+    pub fn milestone_report(&self, proposal_id: Hash) -> TokenOperationResult<Vec<MilestoneReport>> {
+        let proposal = self.proposals.get(&proposal_id)
+            .ok_or(TokenOperationError::ProposalNotFound { proposal_id: proposal_id.clone() })?;
+        let grace_period = Duration::days(self.config.milestone_grace_period_days as i64);
+
+        Ok(proposal.milestones.iter()
+            .map(|milestone| MilestoneReport {
+                proposal_id: proposal_id.clone(),
+                milestone_id: milestone.milestone_id.clone(),
+                name: milestone.name.clone(),
+                description: milestone.description.clone(),
+                amount: milestone.payment_amount,
+                target_date: milestone.target_date,
+                grace_deadline: milestone.target_date + grace_period,
+                completed_date: milestone.completed_date,
+                status: milestone.status.clone(),
+            })
+            .collect())
+    }
+
+    /// Jalon en attente d'une proposition approuvée, dans son délai de grâce
+    fn pending_milestone(&self, proposal_id: &Hash, milestone_id: &Hash, now: DateTime<Utc>) -> TokenOperationResult<&Milestone> {
+        let proposal = self.proposals.get(proposal_id)
+            .ok_or(TokenOperationError::ProposalNotFound { proposal_id: proposal_id.clone() })?;
+        if proposal.status != ProposalStatus::Approved {
+            return Err(TokenOperationError::Internal {
+                message: "Proposition non approuvée".to_string(),
+            });
+        }
+
+        let milestone = proposal.milestones.iter()
+            .find(|m| m.milestone_id == *milestone_id)
+            .ok_or_else(|| TokenOperationError::MilestoneRejected {
+                message: format!("jalon inconnu : {}", milestone_id),
+            })?;
+        if !milestone.status.is_pending() {
+            return Err(TokenOperationError::MilestoneRejected {
+                message: format!("jalon déjà clos ({:?})", milestone.status),
+            });
+        }
+        if now > milestone.target_date + Duration::days(self.config.milestone_grace_period_days as i64) {
+            return Err(TokenOperationError::MilestoneRejected {
+                message: "délai de grâce du jalon dépassé".to_string(),
+            });
+        }
+        Ok(milestone)
+    }
+
+    /// Verse un jalon validé au bénéficiaire
+    ///
+    /// Comme pour `execute_proposal`, les fonds non réservés à l'approbation sont
+    /// réservés au premier versement, et le treasury n'est mis à jour qu'une fois
+    /// les fonds émis.
+    fn release_milestone(&mut self, proposal_id: &Hash, milestone_id: &Hash, now: DateTime<Utc>, token: &mut ARCToken, tx_hash: Hash) -> TokenOperationResult<u64> {
+        let milestone = self.pending_milestone(proposal_id, milestone_id, now)?;
+        let amount = milestone.payment_amount;
+        let description = format!("Débours jalon: {}", milestone.name);
+        let proposal = &self.proposals[proposal_id];
+        let beneficiary = proposal.beneficiary.clone();
+        let requested_amount = proposal.requested_amount;
+
+        let needs_allocation = !self.is_allocated(proposal_id.clone());
+        if needs_allocation {
+            self.check_funding(proposal_id.clone(), requested_amount)?;
+        } else if self.allocated_funds < amount {
+            return Err(TokenOperationError::InsufficientRewardPool);
+        }
+
+        token.mint(&beneficiary, amount, tx_hash.clone())?;
+        if needs_allocation {
+            self.allocate(proposal_id.clone());
+        }
+
+        if let Some(milestone) = self.proposals.get_mut(proposal_id)
+            .and_then(|p| p.milestones.iter_mut().find(|m| m.milestone_id == *milestone_id))
+        {
+            milestone.status = MilestoneStatus::Completed;
+            milestone.completed_date = Some(now);
+        }
+        self.allocated_funds -= amount;
+        self.disbursed_funds += amount;
+        self.record_transaction(TransactionType::Disbursement, amount, None, Some(beneficiary), Some(proposal_id.clone()), description, tx_hash);
+        self.close_milestone_schedule(proposal_id);
+
+        Ok(amount)
+    }
+
+    /// Clôt une proposition dont aucun jalon n'est plus en attente
+    ///
+    /// Elle est exécutée si au moins un jalon a été versé, expirée sinon.
+    fn close_milestone_schedule(&mut self, proposal_id: &Hash) {
+        let Some(proposal) = self.proposals.get_mut(proposal_id) else {
+            return;
+        };
+        if proposal.milestones.iter().any(|m| m.status.is_pending()) {
+            return;
+        }
+
+        let disbursed: u64 = proposal.milestones.iter()
+            .filter(|m| m.status == MilestoneStatus::Completed)
+            .map(|m| m.payment_amount)
+            .sum();
+        if disbursed == 0 {
+            proposal.status = ProposalStatus::Expired;
+            return;
+        }
+
+        proposal.status = ProposalStatus::Executed;
+        let recipient = proposal.beneficiary.clone();
+        self.events.push(TokenEvent {
+            transaction_hash: Hash::zero(),
+            event_type: TokenEventType::ProposalExecuted {
+                proposal_id: proposal_id.clone(),
+                recipient,
+                amount: disbursed,
+            },
+            timestamp: Utc::now(),
+            data: HashMap::new(),
+        });
+    }
+
+    /// Vrai si les fonds de la proposition ont été réservés
+    fn is_allocated(&self, proposal_id: Hash) -> bool {
+        self.transaction_history.iter().any(|tx| {
+            tx.reference == Some(proposal_id) && matches!(tx.transaction_type, TransactionType::Allocation)
+        })
+    }
+
+    /// Réserve les fonds d'une proposition sur `available_funds`
+    fn allocate(&mut self, proposal_id: Hash) {
+        let Some(proposal) = self.proposals.get(&proposal_id) else {
+            return;
+        };
+        let amount = proposal.requested_amount;
+        let beneficiary = proposal.beneficiary.clone();
+        let description = format!("Allocation pour: {}", proposal.title);
+
+        self.available_funds -= amount;
+        self.allocated_funds += amount;
+        self.record_transaction(TransactionType::Allocation, amount, None, Some(beneficiary), Some(proposal_id), description, Hash::zero());
+    }
+
+    /// Vérifie que les fonds d'une proposition peuvent être versés
+    ///
+    /// Retourne `true` si les fonds doivent encore être réservés sur `available_funds`.
+    fn check_funding(&self, proposal_id: Hash, amount: u64) -> TokenOperationResult<bool> {
+        if self.is_allocated(proposal_id) {
+            if self.allocated_funds < amount {
+                return Err(TokenOperationError::InsufficientRewardPool);
+            }
+            return Ok(false);
+        }
+        if self.available_funds < amount {
+            return Err(TokenOperationError::InsufficientTreasuryFunds {
+                required: amount,
+                available: self.available_funds,
+            });
+        }
+        Ok(true)
+    }
+
+    /// Enregistre le versement d'une proposition une fois les fonds émis
+    fn complete_disbursement(&mut self, proposal_id: Hash, needs_allocation: bool, description: String, tx_hash: Hash) {
+        if needs_allocation {
+            self.allocate(proposal_id);
+        }
+        let Some(proposal) = self.proposals.get_mut(&proposal_id) else {
+            return;
+        };
+        proposal.status = ProposalStatus::Executed;
+        let amount = proposal.requested_amount;
+        let beneficiary = proposal.beneficiary.clone();
+
+        self.allocated_funds -= amount;
+        self.disbursed_funds += amount;
+        self.record_transaction(TransactionType::Disbursement, amount, None, Some(beneficiary.clone()), Some(proposal_id), description, tx_hash);
+        self.events.push(TokenEvent {
+            transaction_hash: tx_hash,
+            event_type: TokenEventType::ProposalExecuted {
+                proposal_id,
+                recipient: beneficiary,
+                amount,
+            },
+            timestamp: Utc::now(),
+            data: HashMap::new(),
+        });
+    }
+
+    /// Proposition approuvée, sans jalons et pas encore déboursée
+    fn disbursable_proposal(&self, proposal_id: Hash) -> TokenOperationResult<&TreasuryProposal> {
+        let proposal = self.proposals.get(&proposal_id)
+            .ok_or(TokenOperationError::ProposalNotFound { proposal_id })?;
+
+        if proposal.status == ProposalStatus::Executed {
+            return Err(TokenOperationError::ProposalAlreadyExecuted { proposal_id });
+        }
+        if proposal.status != ProposalStatus::Approved {
+            return Err(TokenOperationError::Internal {
+                message: "Proposition non approuvée".to_string(),
+            });
+        }
+        // Les projets à jalons sont déboursés par `disburse_milestone_payment`
+        if !proposal.milestones.is_empty() {
+            return Err(TokenOperationError::Internal {
+                message: "Proposition déboursée par jalons".to_string(),
+            });
+        }
+        let already_disbursed = self.transaction_history.iter().any(|tx| {
+            tx.reference == Some(proposal_id) && matches!(tx.transaction_type, TransactionType::Disbursement)
+        });
+        if already_disbursed {
+            return Err(TokenOperationError::ProposalAlreadyExecuted { proposal_id });
+        }
+
+        Ok(proposal)
+    }
+
     /// Enregistre une transaction
     fn record_transaction(&mut self, transaction_type: TransactionType, amount: u64, from: Option<PublicKey>, to: Option<PublicKey>, reference: Option<Hash>, description: String, blockchain_tx_hash: Hash) {
         let transaction_id = Hash::from_bytes([
@@ -1012,7 +1838,15 @@ impl Treasury {
     }
 
     /// Obtient les statistiques du treasury
+    ///
+    /// Le rapport inclut l'état des jalons des propositions approuvées ou closes.
     pub fn get_treasury_statistics(&self) -> TreasuryStatistics {
+        let milestones = self.proposals.values()
+            .filter(|p| matches!(p.status, ProposalStatus::Approved | ProposalStatus::Executed | ProposalStatus::Expired | ProposalStatus::Cancelled))
+            .filter_map(|p| self.milestone_report(p.proposal_id.clone()).ok())
+            .flatten()
+            .collect();
+
         TreasuryStatistics {
             available_funds: self.available_funds,
             allocated_funds: self.allocated_funds,
@@ -1024,6 +1858,7 @@ impl Treasury {
             completed_projects: self.metrics.completed_projects,
             fund_utilization_rate: self.metrics.fund_utilization_rate,
             project_success_rate: self.metrics.project_success_rate,
+            milestones,
         }
     }
 }
@@ -1058,6 +1893,54 @@ pub struct TreasuryStatistics {
     pub completed_projects: usize,
     pub fund_utilization_rate: f64,
     pub project_success_rate: f64,
+    /// Jalons des propositions approuvées ou closes
+    pub milestones: Vec<MilestoneReport>,
+}
+
+/// État d'un jalon dans le rapport du treasury
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MilestoneReport {
+    pub proposal_id: Hash,
+    pub milestone_id: Hash,
+    pub name: String,
+    pub description: String,
+    pub amount: u64,
+    /// Date prévue
+    pub target_date: DateTime<Utc>,
+    /// Au-delà, les fonds du jalon non atteint sont repris
+    pub grace_deadline: DateTime<Utc>,
+    pub completed_date: Option<DateTime<Utc>>,
+    pub status: MilestoneStatus,
+}
+
+/// Vérifie le planning de jalons d'une proposition
+///
+/// Un planning vide est accepté (versement en une fois) ; sinon les montants
+/// des jalons, d'identifiants distincts, doivent totaliser le montant demandé.
+fn validate_milestones(requested_amount: u64, milestones: &[Milestone]) -> TokenOperationResult<()> {
+    if milestones.is_empty() {
+        return Ok(());
+    }
+
+    let scheduled = milestones.iter()
+        .try_fold(0u64, |total, m| total.checked_add(m.payment_amount))
+        .unwrap_or(u64::MAX);
+    if scheduled != requested_amount {
+        return Err(TokenOperationError::InvalidMilestoneSchedule {
+            requested: requested_amount,
+            scheduled,
+        });
+    }
+
+    let mut ids: Vec<&Hash> = milestones.iter().map(|m| &m.milestone_id).collect();
+    ids.sort();
+    ids.dedup();
+    if ids.len() != milestones.len() {
+        return Err(TokenOperationError::MilestoneRejected {
+            message: "identifiants de jalons en double".to_string(),
+        });
+    }
+    Ok(())
 }
 
 impl Default for Treasury {
@@ -1070,6 +1953,7 @@ impl Default for Treasury {
 mod tests {
     use super::*;
     use crate::crypto::generate_keypair;
+    use crate::token::staking::{GovernanceStake, StakeStatus};
 
     #[test]
     fn test_treasury_creation() {
@@ -1107,7 +1991,7 @@ mod tests {
                 milestone_id: Hash::zero(),
                 name: "Phase 1".to_string(),
                 description: "Initial development".to_string(),
-                payment_amount: 50_000,
+                payment_amount: 100_000,
                 completion_criteria: vec!["Deliverable 1 completed".to_string()],
                 target_date: Utc::now() + Duration::days(90),
                 completed_date: None,
@@ -1115,7 +1999,7 @@ mod tests {
             },
         ];
 
-        let proposal_id = treasury.submit_proposal(
+        let proposal_id = treasury.submit_detailed_proposal(
             proposer.clone(),
             "Test Project".to_string(),
             "A test project for the treasury".to_string(),
@@ -1139,7 +2023,7 @@ mod tests {
         let voter = voter_keypair.public_key().clone();
 
         // Submit proposal
-        let proposal_id = treasury.submit_proposal(
+        let proposal_id = treasury.submit_detailed_proposal(
             proposer.clone(),
             "Test Project".to_string(),
             "A test project".to_string(),
@@ -1170,6 +2054,397 @@ mod tests {
         assert_eq!(treasury.proposals[&proposal_id].votes.len(), 1);
     }
 
+    fn governance_stake(staker: &PublicKey, amount: u64, multiplier: f64) -> GovernanceStake {
+        GovernanceStake {
+            staker: staker.clone(),
+            amount,
+            start_date: Utc::now(),
+            lock_duration_days: 365,
+            lock_end_date: Utc::now() + Duration::days(365),
+            voting_power_multiplier: multiplier,
+            recent_votes: Vec::new(),
+            accumulated_rewards: 0,
+            last_reward_claim: None,
+            status: StakeStatus::Locked,
+        }
+    }
+
+    /// Un proposeur et trois votants stakés : 10M, 2M et 88M de pouvoir de vote
+    fn governance_setup() -> (StakingSystem, Vec<PublicKey>) {
+        let mut staking = StakingSystem::default();
+        let keys: Vec<PublicKey> = (0..4).map(|_| generate_keypair().unwrap().public_key().clone()).collect();
+        staking.governance_stakes.insert(keys[0].clone(), governance_stake(&keys[0], 5_000_000, 2.0));
+        staking.governance_stakes.insert(keys[1].clone(), governance_stake(&keys[1], 1_000_000, 2.0));
+        staking.governance_stakes.insert(keys[2].clone(), governance_stake(&keys[2], 44_000_000, 2.0));
+        (staking, keys)
+    }
+
+    #[test]
+    fn test_governance_proposal_quorum_reached() {
+        let mut treasury = Treasury::default();
+        let (staking, keys) = governance_setup();
+        let config = TokenConfig::default();
+
+        let proposal_id = treasury.submit_proposal(keys[0].clone(), 250_000, keys[3].clone(), "Financer un miroir d'archives".to_string(), &staking, &config).unwrap();
+        assert!(matches!(treasury.events.last().unwrap().event_type, TokenEventType::ProposalCreated { stake_amount: 5_000_000, .. }));
+
+        // 10M sur 100M de pouvoir total : quorum de 10% atteint, clôture immédiate
+        let status = treasury.vote(keys[0].clone(), proposal_id, true, &staking).unwrap();
+        assert_eq!(status, ProposalStatus::Approved);
+        assert_eq!(treasury.available_funds, COMMUNITY_RESERVE - 250_000);
+        assert_eq!(treasury.allocated_funds, 250_000);
+        assert_eq!(treasury.metrics.approved_proposals, 1);
+        assert!(matches!(treasury.events.last().unwrap().event_type, TokenEventType::ProposalVoted { voting_power: 10_000_000, support: true, .. }));
+
+        // Plus de vote possible une fois la proposition clôturée
+        assert!(treasury.vote(keys[1].clone(), proposal_id, false, &staking).is_err());
+    }
+
+    #[test]
+    fn test_governance_vote_uses_power_at_submission() {
+        let mut treasury = Treasury::default();
+        let (mut staking, keys) = governance_setup();
+
+        let proposal_id = treasury.submit_proposal(keys[0].clone(), 250_000, keys[3].clone(), "Financer un miroir d'archives".to_string(), &staking, &TokenConfig::default()).unwrap();
+
+        // Stake et délégation postérieurs à la soumission : sans effet sur ce vote
+        staking.governance_stakes.insert(keys[3].clone(), governance_stake(&keys[3], 500_000_000, 2.0));
+        staking.delegate(keys[1].clone(), keys[0].clone(), 1_000_000).unwrap();
+        assert!(matches!(
+            treasury.vote(keys[3].clone(), proposal_id, true, &staking),
+            Err(TokenOperationError::InsufficientStake { .. })
+        ));
+
+        // 10M sur les 100M figés à la soumission : quorum atteint malgré le nouveau stake
+        assert_eq!(treasury.vote(keys[0].clone(), proposal_id, true, &staking).unwrap(), ProposalStatus::Approved);
+        assert!(matches!(treasury.events.last().unwrap().event_type, TokenEventType::ProposalVoted { voting_power: 10_000_000, .. }));
+    }
+
+    #[test]
+    fn test_multisig_disbursement() {
+        let mut treasury = Treasury::default();
+        let (staking, keys) = governance_setup();
+        let committee: Vec<_> = (0..3).map(|_| generate_keypair().unwrap()).collect();
+        treasury.config.disbursement_signers = committee.iter().map(|k| k.public_key().clone()).collect();
+        treasury.config.disbursement_threshold = 2;
+        let validator = TransactionValidator::default();
+        let mut token = ARCToken::new();
+
+        let proposal_id = treasury.submit_proposal(keys[0].clone(), 250_000, keys[3].clone(), "Financer un miroir d'archives".to_string(), &staking, &TokenConfig::default()).unwrap();
+        // Pas de débours avant l'approbation
+        assert!(treasury.disbursement_transaction(proposal_id, 10).is_err());
+        assert_eq!(treasury.vote(keys[0].clone(), proposal_id, true, &staking).unwrap(), ProposalStatus::Approved);
+
+        let mut tx = treasury.disbursement_transaction(proposal_id, 10).unwrap();
+        tx.add_multisig_signature(committee[1].private_key()).unwrap();
+        match treasury.execute_disbursement(&tx, &validator, &mut token) {
+            Err(TokenOperationError::MultisigRejected { message }) => assert!(message.contains("1 valides sur 2 requises"), "{}", message),
+            other => panic!("MultisigRejected attendu, obtenu {:?}", other),
+        }
+
+        // Une politique de signature plus permissive est refusée
+        let mut lax = Transaction::multisig(treasury.config.disbursement_signers.clone(), 1, tx.outputs.clone(), 10, tx.data.clone());
+        lax.add_multisig_signature(committee[1].private_key()).unwrap();
+        assert!(treasury.execute_disbursement(&lax, &validator, &mut token).is_err());
+
+        tx.add_multisig_signature(committee[2].private_key()).unwrap();
+        assert_eq!(treasury.execute_disbursement(&tx, &validator, &mut token).unwrap(), 250_000);
+        assert_eq!(token.balance_of(&keys[3]), 250_000);
+        assert_eq!(treasury.allocated_funds, 0);
+        assert_eq!(treasury.disbursed_funds, 250_000);
+
+        // Une proposition n'est déboursée qu'une fois
+        assert!(treasury.execute_disbursement(&tx, &validator, &mut token).is_err());
+    }
+
+    #[test]
+    fn test_approved_proposal_executed_once() {
+        let mut treasury = Treasury::default();
+        let (staking, keys) = governance_setup();
+        let config = TokenConfig::default();
+        let mut token = ARCToken::new();
+
+        let proposal_id = treasury.submit_proposal(keys[0].clone(), 250_000, keys[3].clone(), "Financer un miroir d'archives".to_string(), &staking, &config).unwrap();
+        // Pas d'exécution pendant le vote
+        assert!(treasury.execute_proposal(proposal_id, &mut token, Hash::zero()).is_err());
+        assert_eq!(treasury.vote(keys[0].clone(), proposal_id, true, &staking).unwrap(), ProposalStatus::Approved);
+
+        assert_eq!(treasury.execute_proposal(proposal_id, &mut token, Hash::zero()).unwrap(), 250_000);
+        assert_eq!(token.balance_of(&keys[3]), 250_000);
+        assert_eq!(treasury.proposals[&proposal_id].status, ProposalStatus::Executed);
+        assert_eq!(treasury.available_funds, COMMUNITY_RESERVE - 250_000);
+        assert_eq!(treasury.allocated_funds, 0);
+        assert_eq!(treasury.disbursed_funds, 250_000);
+        assert!(matches!(
+            treasury.events.last().unwrap().event_type,
+            TokenEventType::ProposalExecuted { amount: 250_000, .. }
+        ));
+
+        assert!(matches!(
+            treasury.execute_proposal(proposal_id, &mut token, Hash::zero()),
+            Err(TokenOperationError::ProposalAlreadyExecuted { .. })
+        ));
+        assert_eq!(token.balance_of(&keys[3]), 250_000);
+        assert_eq!(treasury.disbursed_funds, 250_000);
+
+        // Quorum atteint mais majorité contre : rejetée, jamais exécutable
+        let rejected = treasury.submit_proposal(keys[0].clone(), 100_000, keys[3].clone(), "Proposition contestée".to_string(), &staking, &config).unwrap();
+        assert_eq!(treasury.vote(keys[2].clone(), rejected, false, &staking).unwrap(), ProposalStatus::Rejected);
+        assert!(treasury.execute_proposal(rejected, &mut token, Hash::zero()).is_err());
+        assert_eq!(treasury.allocated_funds, 0);
+    }
+
+    #[test]
+    fn test_proposal_execution_requires_available_funds() {
+        let mut treasury = Treasury::default();
+        let (staking, keys) = governance_setup();
+        let mut token = ARCToken::new();
+
+        let proposal_id = treasury.submit_proposal(keys[0].clone(), 250_000, keys[3].clone(), "Financer un miroir d'archives".to_string(), &staking, &TokenConfig::default()).unwrap();
+        // Fonds engagés ailleurs pendant le vote : l'approbation ne réserve rien
+        treasury.available_funds = 100_000;
+        assert_eq!(treasury.vote(keys[0].clone(), proposal_id, true, &staking).unwrap(), ProposalStatus::Approved);
+        assert_eq!(treasury.allocated_funds, 0);
+
+        assert!(matches!(
+            treasury.execute_proposal(proposal_id, &mut token, Hash::zero()),
+            Err(TokenOperationError::InsufficientTreasuryFunds { required: 250_000, available: 100_000 })
+        ));
+        assert_eq!(treasury.proposals[&proposal_id].status, ProposalStatus::Approved);
+        assert_eq!(treasury.available_funds, 100_000);
+        assert_eq!(treasury.disbursed_funds, 0);
+        assert_eq!(token.balance_of(&keys[3]), 0);
+
+        // Exécutable une fois le treasury réapprovisionné
+        treasury.available_funds += 200_000;
+        assert_eq!(treasury.execute_proposal(proposal_id, &mut token, Hash::zero()).unwrap(), 250_000);
+        assert_eq!(treasury.available_funds, 50_000);
+        assert_eq!(treasury.allocated_funds, 0);
+        assert_eq!(treasury.disbursed_funds, 250_000);
+    }
+
+    fn milestone(id: u8, amount: u64, due_in_days: i64) -> Milestone {
+        Milestone {
+            milestone_id: Hash::new([id; 32]),
+            name: format!("Jalon {}", id),
+            description: "Livrable intermédiaire".to_string(),
+            payment_amount: amount,
+            completion_criteria: Vec::new(),
+            target_date: Utc::now() + Duration::days(due_in_days),
+            completed_date: None,
+            status: MilestoneStatus::Planned,
+        }
+    }
+
+    #[test]
+    fn test_milestone_payouts() {
+        let mut treasury = Treasury::default();
+        let (staking, keys) = governance_setup();
+        let reviewer = generate_keypair().unwrap().public_key().clone();
+        treasury.config.milestone_reviewers = vec![reviewer.clone()];
+        let mut token = ARCToken::new();
+
+        let milestones = vec![milestone(1, 100_000, 30), milestone(2, 100_000, 60), milestone(3, 50_000, 90)];
+        let (m1, m2, m3) = (milestones[0].milestone_id.clone(), milestones[1].milestone_id.clone(), milestones[2].milestone_id.clone());
+        let proposal_id = treasury.submit_milestone_proposal(keys[0].clone(), 250_000, keys[3].clone(), "Indexeur plein texte".to_string(), milestones, &staking, &TokenConfig::default()).unwrap();
+        // Pas de versement avant l'approbation
+        assert!(treasury.complete_milestone(&reviewer, proposal_id.clone(), m1.clone(), &mut token, Hash::zero()).is_err());
+        assert_eq!(treasury.vote(keys[0].clone(), proposal_id.clone(), true, &staking).unwrap(), ProposalStatus::Approved);
+        // Les jalons ne passent pas par l'exécution en une fois
+        assert!(treasury.execute_proposal(proposal_id.clone(), &mut token, Hash::zero()).is_err());
+
+        // Relecteur désigné à l'approbation
+        assert!(matches!(
+            treasury.complete_milestone(&keys[1], proposal_id.clone(), m1.clone(), &mut token, Hash::zero()),
+            Err(TokenOperationError::MilestoneRejected { .. })
+        ));
+        assert_eq!(treasury.complete_milestone(&reviewer, proposal_id.clone(), m1.clone(), &mut token, Hash::zero()).unwrap(), 100_000);
+        assert_eq!(token.balance_of(&keys[3]), 100_000);
+        assert_eq!(treasury.allocated_funds, 150_000);
+        assert_eq!(treasury.disbursed_funds, 100_000);
+        assert!(treasury.complete_milestone(&reviewer, proposal_id.clone(), m1, &mut token, Hash::zero()).is_err());
+
+        // Le proposeur et le bénéficiaire ne valident pas leurs propres jalons
+        for key in [&keys[0], &keys[3]] {
+            assert!(matches!(
+                treasury.vote_milestone(key.clone(), proposal_id.clone(), m2.clone(), true, &staking, &mut token, Hash::zero()),
+                Err(TokenOperationError::MilestoneRejected { .. })
+            ));
+        }
+
+        // Vote de gouvernance sur 90M éligibles : 2M pour restent sous le quorum,
+        // puis 88M contre l'atteignent sans majorité
+        assert_eq!(treasury.vote_milestone(keys[1].clone(), proposal_id.clone(), m2.clone(), true, &staking, &mut token, Hash::zero()).unwrap(), MilestoneStatus::Planned);
+        assert_eq!(treasury.vote_milestone(keys[2].clone(), proposal_id.clone(), m2.clone(), false, &staking, &mut token, Hash::zero()).unwrap(), MilestoneStatus::Planned);
+        assert_eq!(treasury.allocated_funds, 150_000);
+        assert_eq!(token.balance_of(&keys[3]), 100_000);
+        assert_eq!(treasury.complete_milestone(&reviewer, proposal_id.clone(), m2, &mut token, Hash::zero()).unwrap(), 100_000);
+        assert_eq!(token.balance_of(&keys[3]), 200_000);
+        assert_eq!(treasury.proposals[&proposal_id].status, ProposalStatus::Approved);
+
+        // Quorum et majorité atteints : le jalon est versé
+        assert_eq!(treasury.vote_milestone(keys[2].clone(), proposal_id.clone(), m3, true, &staking, &mut token, Hash::zero()).unwrap(), MilestoneStatus::Completed);
+        assert_eq!(token.balance_of(&keys[3]), 250_000);
+        assert_eq!(treasury.proposals[&proposal_id].status, ProposalStatus::Executed);
+        assert_eq!(treasury.available_funds, COMMUNITY_RESERVE - 250_000);
+        assert_eq!(treasury.allocated_funds, 0);
+        assert_eq!(treasury.disbursed_funds, 250_000);
+        assert!(matches!(
+            treasury.events.last().unwrap().event_type,
+            TokenEventType::ProposalExecuted { amount: 250_000, .. }
+        ));
+
+        let report = treasury.get_treasury_statistics().milestones;
+        assert_eq!(report.len(), 3);
+        assert!(report.iter().all(|m| m.status == MilestoneStatus::Completed && m.completed_date.is_some()));
+    }
+
+    #[test]
+    fn test_expired_milestone_funds_clawed_back() {
+        let mut treasury = Treasury::default();
+        let (staking, keys) = governance_setup();
+        let reviewer = generate_keypair().unwrap().public_key().clone();
+        treasury.config.milestone_reviewers = vec![reviewer.clone()];
+        let mut token = ARCToken::new();
+
+        let milestones = vec![milestone(1, 150_000, 10), milestone(2, 100_000, 60)];
+        let (m1, m2) = (milestones[0].milestone_id.clone(), milestones[1].milestone_id.clone());
+        let proposal_id = treasury.submit_milestone_proposal(keys[0].clone(), 250_000, keys[3].clone(), "Miroir régional".to_string(), milestones, &staking, &TokenConfig::default()).unwrap();
+        assert_eq!(treasury.vote(keys[0].clone(), proposal_id.clone(), true, &staking).unwrap(), ProposalStatus::Approved);
+        assert_eq!(treasury.complete_milestone(&reviewer, proposal_id.clone(), m1, &mut token, Hash::zero()).unwrap(), 150_000);
+
+        // Jalon 2 en retard mais encore dans son délai de grâce de 30 jours
+        assert!(treasury.process_expired_milestones(Utc::now() + Duration::days(80)).is_empty());
+
+        let clawed_back = treasury.process_expired_milestones(Utc::now() + Duration::days(91));
+        assert_eq!(clawed_back, vec![(proposal_id.clone(), m2.clone(), 100_000)]);
+        assert_eq!(treasury.available_funds, COMMUNITY_RESERVE - 150_000);
+        assert_eq!(treasury.allocated_funds, 0);
+        assert_eq!(treasury.disbursed_funds, 150_000);
+        assert_eq!(treasury.proposals[&proposal_id].status, ProposalStatus::Executed);
+
+        let report = treasury.milestone_report(proposal_id.clone()).unwrap();
+        assert_eq!(report[1].status, MilestoneStatus::Expired);
+        // Un jalon expiré ne peut plus être versé
+        assert!(treasury.complete_milestone(&reviewer, proposal_id, m2, &mut token, Hash::zero()).is_err());
+        assert_eq!(token.balance_of(&keys[3]), 150_000);
+    }
+
+    #[test]
+    fn test_cancelled_proposal_returns_undisbursed_funds() {
+        let mut treasury = Treasury::default();
+        let (staking, keys) = governance_setup();
+        let reviewer = generate_keypair().unwrap().public_key().clone();
+        treasury.config.milestone_reviewers = vec![reviewer.clone()];
+        let mut token = ARCToken::new();
+
+        let milestones = vec![milestone(1, 100_000, 30), milestone(2, 150_000, 60)];
+        let (m1, m2) = (milestones[0].milestone_id.clone(), milestones[1].milestone_id.clone());
+        let proposal_id = treasury.submit_milestone_proposal(keys[0].clone(), 250_000, keys[3].clone(), "Outils de crawl".to_string(), milestones, &staking, &TokenConfig::default()).unwrap();
+        assert_eq!(treasury.vote(keys[0].clone(), proposal_id.clone(), true, &staking).unwrap(), ProposalStatus::Approved);
+        treasury.complete_milestone(&reviewer, proposal_id.clone(), m1, &mut token, Hash::zero()).unwrap();
+
+        // Ni un votant ni le bénéficiaire ne peuvent annuler
+        for key in [&keys[1], &keys[3]] {
+            assert!(matches!(
+                treasury.cancel_proposal(key, proposal_id.clone()),
+                Err(TokenOperationError::Unauthorized { .. })
+            ));
+        }
+        assert_eq!(treasury.cancel_proposal(&reviewer, proposal_id.clone()).unwrap(), 150_000);
+        assert_eq!(treasury.proposals[&proposal_id].status, ProposalStatus::Cancelled);
+        assert_eq!(treasury.available_funds, COMMUNITY_RESERVE - 100_000);
+        assert_eq!(treasury.allocated_funds, 0);
+        assert_eq!(treasury.disbursed_funds, 100_000);
+        assert!(treasury.complete_milestone(&reviewer, proposal_id.clone(), m2, &mut token, Hash::zero()).is_err());
+        assert!(treasury.cancel_proposal(&keys[0], proposal_id).is_err());
+    }
+
+    #[test]
+    fn test_milestone_amounts_must_sum_to_request() {
+        let mut treasury = Treasury::default();
+        let (staking, keys) = governance_setup();
+
+        let milestones = vec![milestone(1, 100_000, 30), milestone(2, 100_000, 60)];
+        assert!(matches!(
+            treasury.submit_milestone_proposal(keys[0].clone(), 250_000, keys[3].clone(), "Planning incomplet".to_string(), milestones, &staking, &TokenConfig::default()),
+            Err(TokenOperationError::InvalidMilestoneSchedule { requested: 250_000, scheduled: 200_000 })
+        ));
+
+        let duplicated = vec![milestone(1, 100_000, 30), milestone(1, 150_000, 60)];
+        assert!(treasury.submit_milestone_proposal(keys[0].clone(), 250_000, keys[3].clone(), "Jalons en double".to_string(), duplicated, &staking, &TokenConfig::default()).is_err());
+
+        let result = treasury.submit_detailed_proposal(
+            keys[0].clone(),
+            "Planning excédentaire".to_string(),
+            "Jalons supérieurs au montant demandé".to_string(),
+            ProposalCategory::Development,
+            100_000,
+            vec![],
+            keys[3].clone(),
+            vec![milestone(1, 150_000, 30)],
+        );
+        assert!(matches!(result, Err(TokenOperationError::InvalidMilestoneSchedule { requested: 100_000, scheduled: 150_000 })));
+        assert!(treasury.proposals.is_empty());
+    }
+
+    #[test]
+    fn test_governance_proposal_quorum_missed() {
+        let mut treasury = Treasury::default();
+        let (staking, keys) = governance_setup();
+        let config = TokenConfig::default();
+
+        let proposal_id = treasury.submit_proposal(keys[0].clone(), 250_000, keys[3].clone(), "Audit du consensus".to_string(), &staking, &config).unwrap();
+
+        // 2M sur 100M : la proposition reste ouverte
+        assert_eq!(treasury.vote(keys[1].clone(), proposal_id, true, &staking).unwrap(), ProposalStatus::Voting);
+        assert!(treasury.vote(keys[1].clone(), proposal_id, true, &staking).is_err());
+
+        let after_deadline = Utc::now() + Duration::days(treasury.config.default_voting_duration_days as i64 + 1);
+        let settled = treasury.process_expired_proposals(after_deadline, &staking).unwrap();
+        assert_eq!(settled, vec![(proposal_id, ProposalStatus::Rejected)]);
+        assert!(!treasury.proposals[&proposal_id].voting_result.as_ref().unwrap().quorum_reached);
+        assert_eq!(treasury.available_funds, COMMUNITY_RESERVE);
+        assert_eq!(treasury.allocated_funds, 0);
+    }
+
+    #[test]
+    fn test_governance_proposal_expiry() {
+        let mut treasury = Treasury::default();
+        let (staking, keys) = governance_setup();
+        let config = TokenConfig::default();
+
+        let proposal_id = treasury.submit_proposal(keys[0].clone(), 250_000, keys[3].clone(), "Proposition oubliée".to_string(), &staking, &config).unwrap();
+
+        assert!(treasury.process_expired_proposals(Utc::now(), &staking).unwrap().is_empty());
+
+        let after_deadline = Utc::now() + Duration::days(treasury.config.default_voting_duration_days as i64 + 1);
+        let settled = treasury.process_expired_proposals(after_deadline, &staking).unwrap();
+        assert_eq!(settled, vec![(proposal_id, ProposalStatus::Expired)]);
+        assert_eq!(treasury.allocated_funds, 0);
+    }
+
+    #[test]
+    fn test_governance_proposal_submission_checks() {
+        let mut treasury = Treasury::default();
+        let (staking, keys) = governance_setup();
+        let config = TokenConfig::default();
+
+        // Stake de 1M insuffisant avec un minimum relevé
+        let strict_config = TokenConfig { min_governance_stake: 2_000_000, ..TokenConfig::default() };
+        assert!(matches!(
+            treasury.submit_proposal(keys[1].clone(), 1_000, keys[3].clone(), "Trop peu staké".to_string(), &staking, &strict_config),
+            Err(TokenOperationError::InsufficientStake { required: 2_000_000, provided: 1_000_000 })
+        ));
+
+        // Montant supérieur aux fonds disponibles
+        assert!(matches!(
+            treasury.submit_proposal(keys[0].clone(), COMMUNITY_RESERVE + 1, keys[3].clone(), "Trop cher".to_string(), &staking, &config),
+            Err(TokenOperationError::InsufficientBalance { .. })
+        ));
+        assert!(treasury.proposals.is_empty());
+    }
+
     #[test]
     fn test_invalid_proposal_amount() {
         let mut treasury = Treasury::default();
@@ -1177,7 +2452,7 @@ mod tests {
         let proposer = keypair.public_key().clone();
 
         // Try to submit proposal with amount too small
-        let result = treasury.submit_proposal(
+        let result = treasury.submit_detailed_proposal(
             proposer.clone(),
             "Too Small".to_string(),
             "Too small amount".to_string(),
@@ -1191,7 +2466,7 @@ mod tests {
         assert!(result.is_err());
 
         // Try to submit proposal with amount too large
-        let result = treasury.submit_proposal(
+        let result = treasury.submit_detailed_proposal(
             proposer.clone(),
             "Too Large".to_string(),
             "Too large amount".to_string(),
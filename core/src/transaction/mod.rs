@@ -3,10 +3,14 @@
 pub mod pool;
 pub mod validation;
 pub mod types;
+pub mod transfer;
+pub mod receipt;
 
-pub use types::{Transaction, TransactionType, TransactionInput, TransactionOutput};
-pub use pool::TransactionPool;
+pub use types::{Transaction, TransactionType, TransactionInput, TransactionOutput, MultisigSignature};
+pub use pool::{TransactionPool, PoolStats};
 pub use validation::{TransactionValidator, Validatable};
+pub use transfer::{TokenTransferProcessor, TokenLedger, FeeSplit, BlockExecution, TransferExecution};
+pub use receipt::TransactionReceipt;
 
 use crate::error::{TransactionError, Result};
 
@@ -1,17 +1,47 @@
 //! Pool de transactions pour ArchiveChain
 
-use std::collections::HashMap;
+use std::cmp::Ordering;
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+use std::time::Duration;
+use chrono::{DateTime, Utc};
+use crate::constants::{DEFAULT_BLOCK_SIZE, MAX_TRANSACTIONS_PER_BLOCK};
 use crate::crypto::Hash;
+use crate::crypto::keys::PUBLIC_KEY_SIZE;
 use crate::error::{TransactionError, Result};
 use super::types::Transaction;
 
+/// Emplacement (émetteur, nonce) occupé par une transaction
+type SenderSlot = ([u8; PUBLIC_KEY_SIZE], u64);
+
+/// Durée de séjour par défaut d'une transaction dans le pool
+pub const DEFAULT_TRANSACTION_TTL: Duration = Duration::from_secs(3 * 3600);
+
+/// Statistiques du pool de transactions
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct PoolStats {
+    /// Nombre de transactions en attente
+    pub count: usize,
+    /// Taille cumulée des transactions en bytes
+    pub total_bytes: usize,
+    /// Plus petits frais en attente
+    pub min_fee: Option<u64>,
+    /// Plus grands frais en attente
+    pub max_fee: Option<u64>,
+}
+
 /// Pool de transactions en attente
 #[derive(Debug, Clone)]
 pub struct TransactionPool {
     /// Transactions en attente, indexées par hash
     pending: HashMap<Hash, Transaction>,
+    /// Index (émetteur, nonce) -> transaction, pour le remplacement par frais
+    by_sender_nonce: HashMap<SenderSlot, Hash>,
+    /// Heure d'entrée de chaque transaction dans le pool
+    received_at: HashMap<Hash, DateTime<Utc>>,
     /// Nombre maximum de transactions dans le pool
     max_size: usize,
+    /// Durée au-delà de laquelle une transaction en attente est abandonnée
+    ttl: Duration,
 }
 
 impl TransactionPool {
@@ -19,27 +49,102 @@ impl TransactionPool {
     pub fn new(max_size: usize) -> Self {
         Self {
             pending: HashMap::new(),
+            by_sender_nonce: HashMap::new(),
+            received_at: HashMap::new(),
             max_size,
+            ttl: DEFAULT_TRANSACTION_TTL,
         }
     }
 
+    /// Définit la durée de séjour maximale des transactions
+    pub fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
     /// Ajoute une transaction au pool
+    ///
+    /// Une transaction ayant le même émetteur et le même nonce qu'une transaction
+    /// en attente la remplace si ses frais sont strictement supérieurs. Lorsque le
+    /// pool est plein, la transaction la moins prioritaire (frais par byte) est
+    /// évincée si la nouvelle paie davantage ; sinon la transaction est refusée.
     pub fn add_transaction(&mut self, transaction: Transaction) -> Result<()> {
-        if self.pending.len() >= self.max_size {
-            return Err(TransactionError::Invalid.into());
-        }
+        self.add_transaction_at(transaction, Utc::now())
+    }
 
+    /// Variante de `add_transaction` avec une horloge explicite
+    pub fn add_transaction_at(&mut self, transaction: Transaction, now: DateTime<Utc>) -> Result<()> {
         if !transaction.is_valid()? {
             return Err(TransactionError::Invalid.into());
         }
 
+        self.remove_expired(now);
+
+        let slot = Self::sender_slot(&transaction);
+        let replaced = match slot.as_ref().and_then(|slot| self.by_sender_nonce.get(slot)) {
+            Some(existing_id) => {
+                let existing_fee = self.pending.get(existing_id).map(|tx| tx.fee).unwrap_or(0);
+                if transaction.fee <= existing_fee {
+                    return Err(TransactionError::ReplacementFeeTooLow.into());
+                }
+                Some(existing_id.clone())
+            }
+            None => None,
+        };
+
+        let evicted = if replaced.is_none() && self.pending.len() >= self.max_size {
+            let incoming = Candidate::new(&transaction, 0);
+            let lowest = self.pending.values()
+                .map(|tx| Candidate::new(tx, 0))
+                .min()
+                .filter(|lowest| incoming > *lowest)
+                .map(|lowest| lowest.transaction.tx_id.clone());
+            match lowest {
+                Some(lowest) => Some(lowest),
+                None => return Err(TransactionError::PoolFull.into()),
+            }
+        } else {
+            None
+        };
+
+        for removed_id in replaced.iter().chain(evicted.iter()) {
+            self.remove_transaction(removed_id);
+        }
+        if let Some(slot) = slot {
+            self.by_sender_nonce.insert(slot, transaction.tx_id.clone());
+        }
+        self.received_at.insert(transaction.tx_id.clone(), now);
         self.pending.insert(transaction.tx_id.clone(), transaction);
         Ok(())
     }
 
+    /// Retire les transactions restées plus longtemps que le TTL ; retourne leur nombre
+    pub fn remove_expired(&mut self, now: DateTime<Utc>) -> usize {
+        let expired: Vec<Hash> = self.received_at.iter()
+            .filter(|(_, received_at)| {
+                now.signed_duration_since(**received_at)
+                    .to_std()
+                    .map_or(false, |age| age >= self.ttl)
+            })
+            .map(|(tx_id, _)| tx_id.clone())
+            .collect();
+
+        for tx_id in &expired {
+            self.remove_transaction(tx_id);
+        }
+        expired.len()
+    }
+
     /// Retire une transaction du pool
     pub fn remove_transaction(&mut self, tx_id: &Hash) -> Option<Transaction> {
-        self.pending.remove(tx_id)
+        let transaction = self.pending.remove(tx_id)?;
+        self.received_at.remove(tx_id);
+        if let Some(slot) = Self::sender_slot(&transaction) {
+            if self.by_sender_nonce.get(&slot) == Some(tx_id) {
+                self.by_sender_nonce.remove(&slot);
+            }
+        }
+        Some(transaction)
     }
 
     /// Obtient une transaction par son ID
@@ -52,9 +157,81 @@ impl TransactionPool {
         self.pending.values().collect()
     }
 
+    /// Sélectionne les meilleures transactions pour un bloc
+    ///
+    /// Les transactions sont ordonnées par frais par byte décroissants, dans la
+    /// limite de `max_count` (plafonné à `MAX_TRANSACTIONS_PER_BLOCK`) et de
+    /// `max_size_bytes` (plafonné à `DEFAULT_BLOCK_SIZE`). Les transactions d'un même émetteur sont toujours
+    /// sélectionnées dans l'ordre de leurs nonces. La sélection est déterministe ;
+    /// les transactions restent dans le pool jusqu'à leur inclusion dans un bloc.
+    pub fn take_best(&self, max_count: usize, max_size_bytes: usize) -> Vec<Transaction> {
+        let max_count = max_count.min(MAX_TRANSACTIONS_PER_BLOCK);
+        let max_size_bytes = max_size_bytes.min(DEFAULT_BLOCK_SIZE);
+
+        // Files par émetteur, triées par nonce ; une file par transaction sans émetteur
+        let mut by_sender: HashMap<[u8; PUBLIC_KEY_SIZE], Vec<&Transaction>> = HashMap::new();
+        let mut queues: Vec<VecDeque<&Transaction>> = Vec::new();
+        for transaction in self.pending.values() {
+            match &transaction.sender {
+                Some(sender) => by_sender.entry(*sender.as_bytes()).or_default().push(transaction),
+                None => queues.push(VecDeque::from(vec![transaction])),
+            }
+        }
+        for mut transactions in by_sender.into_values() {
+            transactions.sort_by(|a, b| a.nonce.cmp(&b.nonce).then_with(|| a.tx_id.as_bytes().cmp(b.tx_id.as_bytes())));
+            queues.push(transactions.into());
+        }
+
+        let mut heap = BinaryHeap::new();
+        for (queue, transactions) in queues.iter_mut().enumerate() {
+            if let Some(transaction) = transactions.pop_front() {
+                heap.push(Candidate::new(transaction, queue));
+            }
+        }
+
+        let mut selected = Vec::new();
+        let mut used_bytes = 0usize;
+        while let Some(candidate) = heap.pop() {
+            if selected.len() >= max_count {
+                break;
+            }
+
+            // Une transaction trop grande bloque les nonces suivants de son émetteur
+            if used_bytes + candidate.size > max_size_bytes {
+                continue;
+            }
+
+            used_bytes += candidate.size;
+            selected.push(candidate.transaction.clone());
+
+            if let Some(next) = queues[candidate.queue].pop_front() {
+                heap.push(Candidate::new(next, candidate.queue));
+            }
+        }
+
+        selected
+    }
+
+    /// Somme des frais de toutes les transactions en attente
+    pub fn total_fee_potential(&self) -> u64 {
+        self.pending.values().map(|tx| tx.fee).fold(0u64, |acc, fee| acc.saturating_add(fee))
+    }
+
+    /// Statistiques du pool
+    pub fn stats(&self) -> PoolStats {
+        PoolStats {
+            count: self.pending.len(),
+            total_bytes: self.pending.values().map(|tx| tx.size_bytes()).sum(),
+            min_fee: self.pending.values().map(|tx| tx.fee).min(),
+            max_fee: self.pending.values().map(|tx| tx.fee).max(),
+        }
+    }
+
     /// Vide le pool
     pub fn clear(&mut self) {
         self.pending.clear();
+        self.by_sender_nonce.clear();
+        self.received_at.clear();
     }
 
     /// Retourne la taille du pool
@@ -66,10 +243,210 @@ impl TransactionPool {
     pub fn is_full(&self) -> bool {
         self.pending.len() >= self.max_size
     }
+
+    /// Emplacement (émetteur, nonce) d'une transaction signée par un compte
+    fn sender_slot(transaction: &Transaction) -> Option<SenderSlot> {
+        transaction.sender.as_ref().map(|sender| (*sender.as_bytes(), transaction.nonce))
+    }
 }
 
 impl Default for TransactionPool {
     fn default() -> Self {
         Self::new(10000) // Pool par défaut de 10k transactions
     }
-}
\ No newline at end of file
+}
+
+/// Transaction candidate à l'inclusion, ordonnée par frais par byte
+struct Candidate<'a> {
+    transaction: &'a Transaction,
+    size: usize,
+    queue: usize,
+}
+
+impl<'a> Candidate<'a> {
+    fn new(transaction: &'a Transaction, queue: usize) -> Self {
+        Self {
+            transaction,
+            size: transaction.size_bytes().max(1),
+            queue,
+        }
+    }
+}
+
+impl Ord for Candidate<'_> {
+    fn cmp(&self, other: &Self) -> Ordering {
+        // Compare fee/size sans flottants : fee_a * size_b vs fee_b * size_a
+        let lhs = self.transaction.fee as u128 * other.size as u128;
+        let rhs = other.transaction.fee as u128 * self.size as u128;
+        lhs.cmp(&rhs)
+            .then_with(|| self.transaction.fee.cmp(&other.transaction.fee))
+            // À égalité, le plus petit identifiant passe en premier
+            .then_with(|| other.transaction.tx_id.as_bytes().cmp(self.transaction.tx_id.as_bytes()))
+    }
+}
+
+impl PartialOrd for Candidate<'_> {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl PartialEq for Candidate<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for Candidate<'_> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::crypto::{generate_keypair, PublicKey};
+    use crate::transaction::types::{TransactionBuilder, TransactionOutput, TransactionType};
+
+    fn create_transaction(sender: &PublicKey, nonce: u64, fee: u64) -> Transaction {
+        TransactionBuilder::new(TransactionType::Archive)
+            .add_output(TransactionOutput {
+                amount: 1000,
+                recipient: sender.clone(),
+                lock_script: Vec::new(),
+            })
+            .sender(sender.clone())
+            .nonce(nonce)
+            .fee(fee)
+            .build()
+    }
+
+    fn new_sender() -> PublicKey {
+        generate_keypair().unwrap().public_key().clone()
+    }
+
+    #[test]
+    fn test_take_best_orders_by_fee_per_byte() {
+        let mut pool = TransactionPool::default();
+        for fee in [10, 50, 30] {
+            pool.add_transaction(create_transaction(&new_sender(), 0, fee)).unwrap();
+        }
+
+        let fees: Vec<u64> = pool.take_best(10, usize::MAX).iter().map(|tx| tx.fee).collect();
+        assert_eq!(fees, vec![50, 30, 10]);
+        assert_eq!(pool.size(), 3);
+        assert_eq!(pool.total_fee_potential(), 90);
+    }
+
+    #[test]
+    fn test_take_best_respects_limits() {
+        let mut pool = TransactionPool::default();
+        for fee in 1..=5 {
+            pool.add_transaction(create_transaction(&new_sender(), 0, fee)).unwrap();
+        }
+
+        assert_eq!(pool.take_best(2, usize::MAX).len(), 2);
+
+        let one_tx = pool.pending_transactions()[0].size_bytes();
+        let selected = pool.take_best(10, one_tx);
+        assert_eq!(selected.len(), 1);
+        assert_eq!(selected[0].fee, 5);
+    }
+
+    #[test]
+    fn test_take_best_keeps_sender_nonce_order() {
+        let mut pool = TransactionPool::default();
+        let sender = new_sender();
+        pool.add_transaction(create_transaction(&sender, 0, 1)).unwrap();
+        pool.add_transaction(create_transaction(&sender, 1, 100)).unwrap();
+        pool.add_transaction(create_transaction(&new_sender(), 0, 50)).unwrap();
+
+        let selected = pool.take_best(10, usize::MAX);
+        let nonce_0 = selected.iter().position(|tx| tx.sender.as_ref() == Some(&sender) && tx.nonce == 0).unwrap();
+        let nonce_1 = selected.iter().position(|tx| tx.sender.as_ref() == Some(&sender) && tx.nonce == 1).unwrap();
+        assert!(nonce_0 < nonce_1);
+
+        // Sélection déterministe
+        let again: Vec<Hash> = pool.take_best(10, usize::MAX).into_iter().map(|tx| tx.tx_id).collect();
+        let first: Vec<Hash> = selected.into_iter().map(|tx| tx.tx_id).collect();
+        assert_eq!(first, again);
+    }
+
+    #[test]
+    fn test_replace_by_fee() {
+        let mut pool = TransactionPool::default();
+        let sender = new_sender();
+        let original = create_transaction(&sender, 7, 10);
+        pool.add_transaction(original.clone()).unwrap();
+
+        // Frais identiques ou inférieurs : refusé
+        assert!(pool.add_transaction(create_transaction(&sender, 7, 10)).is_err());
+
+        let replacement = create_transaction(&sender, 7, 25);
+        pool.add_transaction(replacement.clone()).unwrap();
+
+        assert_eq!(pool.size(), 1);
+        assert!(pool.get_transaction(&original.tx_id).is_none());
+        assert_eq!(pool.get_transaction(&replacement.tx_id).unwrap().fee, 25);
+
+        pool.remove_transaction(&replacement.tx_id);
+        assert!(pool.add_transaction(create_transaction(&sender, 7, 1)).is_ok());
+    }
+
+    #[test]
+    fn test_full_pool_evicts_lowest_fee() {
+        let mut pool = TransactionPool::new(2);
+        let cheap = create_transaction(&new_sender(), 0, 10);
+        let sender = new_sender();
+        let pending = create_transaction(&sender, 0, 20);
+        pool.add_transaction(cheap.clone()).unwrap();
+        pool.add_transaction(pending.clone()).unwrap();
+
+        // Ne paie pas mieux que la moins prioritaire : refusée
+        assert!(matches!(
+            pool.add_transaction(create_transaction(&new_sender(), 0, 5)),
+            Err(crate::error::CoreError::Transaction(TransactionError::PoolFull))
+        ));
+
+        let rich = create_transaction(&new_sender(), 0, 30);
+        pool.add_transaction(rich.clone()).unwrap();
+        assert_eq!(pool.size(), 2);
+        assert!(pool.get_transaction(&cheap.tx_id).is_none());
+        assert!(pool.get_transaction(&rich.tx_id).is_some());
+
+        // Le remplacement par frais n'a pas besoin de place libre
+        let bumped = create_transaction(&sender, 0, 40);
+        pool.add_transaction(bumped.clone()).unwrap();
+        assert!(pool.get_transaction(&pending.tx_id).is_none());
+        assert!(pool.get_transaction(&rich.tx_id).is_some());
+        assert!(pool.add_transaction(create_transaction(&sender, 0, 40)).is_err());
+    }
+
+    #[test]
+    fn test_expired_transactions_are_dropped() {
+        let mut pool = TransactionPool::new(10).with_ttl(Duration::from_secs(60));
+        let t0 = Utc::now();
+        let old = create_transaction(&new_sender(), 0, 10);
+        let fresh = create_transaction(&new_sender(), 0, 20);
+        pool.add_transaction_at(old.clone(), t0).unwrap();
+        pool.add_transaction_at(fresh.clone(), t0 + chrono::Duration::seconds(30)).unwrap();
+
+        assert_eq!(pool.remove_expired(t0 + chrono::Duration::seconds(59)), 0);
+        assert_eq!(pool.remove_expired(t0 + chrono::Duration::seconds(60)), 1);
+        assert!(pool.get_transaction(&old.tx_id).is_none());
+        assert!(pool.get_transaction(&fresh.tx_id).is_some());
+    }
+
+    #[test]
+    fn test_pool_stats() {
+        let mut pool = TransactionPool::default();
+        assert_eq!(pool.stats(), PoolStats::default());
+
+        for fee in [15, 3, 42] {
+            pool.add_transaction(create_transaction(&new_sender(), 0, fee)).unwrap();
+        }
+        let stats = pool.stats();
+        let bytes: usize = pool.pending_transactions().iter().map(|tx| tx.size_bytes()).sum();
+        assert_eq!(stats.count, 3);
+        assert_eq!(stats.total_bytes, bytes);
+        assert_eq!(stats.min_fee, Some(3));
+        assert_eq!(stats.max_fee, Some(42));
+    }
+}
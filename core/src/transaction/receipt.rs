@@ -0,0 +1,60 @@
+//! Reçus de transactions
+//!
+//! Un reçu est créé pour chaque transaction incluse dans la chaîne principale.
+//! Il indique le bloc d'inclusion, les frais prélevés et les événements token
+//! émis par son exécution, ce qui permet à un portefeuille de confirmer un
+//! transfert et d'en afficher l'effet sur les soldes. Une transaction n'est
+//! incluse que si elle s'exécute : un transfert non couvert par le solde
+//! rejette le bloc entier, le reçu atteste donc le succès de l'exécution.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use crate::crypto::{Hash, PublicKey};
+use crate::token::{TokenEvent, TokenEventType};
+
+/// Reçu d'une transaction incluse dans un bloc
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionReceipt {
+    /// Hash de la transaction
+    pub transaction_hash: Hash,
+    /// Hash du bloc d'inclusion
+    pub block_hash: Hash,
+    /// Hauteur du bloc d'inclusion
+    pub block_height: u64,
+    /// Position de la transaction dans le bloc
+    pub index: u32,
+    /// Frais prélevés par l'exécution (transferts de tokens), brûlés ou reversés au producteur
+    pub gas_used: u64,
+    /// Événements token émis, dans l'ordre d'émission
+    pub events: Vec<TokenEvent>,
+    /// Horodatage du bloc d'inclusion
+    pub timestamp: DateTime<Utc>,
+}
+
+impl TransactionReceipt {
+    /// Variation du solde de `account` due aux événements du reçu
+    ///
+    /// Les mises en stake et les slashings sont comptés comme des sorties, les
+    /// retraits de stake comme des entrées.
+    pub fn balance_change(&self, account: &PublicKey) -> i128 {
+        self.events.iter().map(|event| match &event.event_type {
+            TokenEventType::Transfer { from, to, amount } => {
+                let mut change = 0i128;
+                if to == account {
+                    change += *amount as i128;
+                }
+                if from == account {
+                    change -= *amount as i128;
+                }
+                change
+            }
+            TokenEventType::RewardDistributed { to, amount, .. } if to == account => *amount as i128,
+            TokenEventType::Unstaked { staker, amount, .. } if staker == account => *amount as i128,
+            TokenEventType::Burn { from, amount } if from == account => -(*amount as i128),
+            TokenEventType::Staked { staker, amount, .. } if staker == account => -(*amount as i128),
+            TokenEventType::Slashed { validator, amount, .. } if validator == account => -(*amount as i128),
+            _ => 0,
+        }).sum()
+    }
+}
@@ -0,0 +1,392 @@
+//! Application des transferts de tokens ARC
+//!
+//! Relie les transactions `TransactionType::TokenTransfer` au token ARC : chaque
+//! transfert est validé (signature, nonce, solde), puis le montant est déplacé
+//! et les frais passent par le partage burn/récompense des mécanismes
+//! déflationnistes. Les nonces ne sont enregistrés que par la chaîne, avec
+//! ceux des autres transactions du bloc.
+
+use std::collections::HashMap;
+
+use crate::block::Block;
+use crate::crypto::{Hash, PublicKey};
+use crate::error::{CoreError, Result, TransactionError};
+use crate::state::StateMachine;
+use crate::token::deflation::DeflationConfig;
+use crate::token::{system_address, ARCToken, DeflationaryMechanisms, TokenEvent, TokenOperationError};
+use super::types::{Transaction, TransactionType};
+use super::validation::TransactionValidator;
+
+/// Répartition des frais d'un ou plusieurs transferts
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FeeSplit {
+    /// Part des frais brûlée
+    pub burned: u64,
+    /// Part des frais versée au producteur du bloc
+    pub rewarded: u64,
+}
+
+/// Effet d'un transfert inclus dans un bloc
+#[derive(Debug, Clone, Default)]
+pub struct TransferExecution {
+    /// Frais prélevés
+    pub fees: FeeSplit,
+    /// Événements émis par le token, dans l'ordre d'émission
+    pub events: Vec<TokenEvent>,
+}
+
+/// Résultat de l'application des transferts d'un bloc
+#[derive(Debug, Clone)]
+pub struct BlockExecution {
+    /// Registre après le bloc
+    pub ledger: TokenLedger,
+    /// Frais de tous les transferts du bloc
+    pub fees: FeeSplit,
+    /// Effet de chaque transfert, par hash de transaction
+    pub transfers: HashMap<Hash, TransferExecution>,
+}
+
+/// Soldes et mécanismes déflationnistes sur lesquels s'appliquent les transferts
+#[derive(Debug, Clone)]
+pub struct TokenLedger {
+    pub token: ARCToken,
+    pub deflation: DeflationaryMechanisms,
+}
+
+impl TokenLedger {
+    pub fn new(token: ARCToken, deflation: DeflationaryMechanisms) -> Self {
+        Self { token, deflation }
+    }
+}
+
+impl Default for TokenLedger {
+    fn default() -> Self {
+        Self::new(ARCToken::new(), DeflationaryMechanisms::new(DeflationConfig::default()))
+    }
+}
+
+/// Applique les transferts de tokens aux soldes
+#[derive(Debug, Default)]
+pub struct TokenTransferProcessor {
+    validator: TransactionValidator,
+}
+
+impl TokenTransferProcessor {
+    /// Crée un processeur utilisant le validateur donné
+    pub fn new(validator: TransactionValidator) -> Self {
+        Self { validator }
+    }
+
+    /// Validateur utilisé avant chaque application
+    pub fn validator(&self) -> &TransactionValidator {
+        &self.validator
+    }
+
+    /// Valide puis applique un transfert isolé
+    ///
+    /// Le nonce doit suivre celui enregistré dans `state`, mais n'y est pas
+    /// enregistré : c'est l'inclusion du transfert dans un bloc qui le consomme.
+    pub fn apply_transfer(
+        &self,
+        transaction: &Transaction,
+        state: &StateMachine,
+        ledger: &mut TokenLedger,
+        fee_recipient: &PublicKey,
+    ) -> Result<FeeSplit> {
+        self.validator.validate_token_transfer(transaction, state, &ledger.token)?;
+        self.move_funds(transaction, ledger, fee_recipient)
+    }
+
+    /// Applique un transfert dont le nonce a déjà été vérifié par la chaîne
+    ///
+    /// Le montant passe de `from` à `to`, les frais sont brûlés selon le taux de
+    /// `deflation` et le reste revient à `fee_recipient`. Un transfert refusé
+    /// par la validation (signature, solde) ne modifie pas le registre.
+    pub fn execute_transfer(
+        &self,
+        transaction: &Transaction,
+        ledger: &mut TokenLedger,
+        fee_recipient: &PublicKey,
+    ) -> Result<FeeSplit> {
+        self.validator.validate_transfer_funds(transaction, &ledger.token)?;
+        self.move_funds(transaction, ledger, fee_recipient)
+    }
+
+    fn move_funds(&self, transaction: &Transaction, ledger: &mut TokenLedger, fee_recipient: &PublicKey) -> Result<FeeSplit> {
+        let TransactionType::TokenTransfer { from, to, amount, fee, .. } = &transaction.tx_type else {
+            return Err(TransactionError::Invalid.into());
+        };
+        let tx_hash = transaction.hash().clone();
+        let token = &mut ledger.token;
+
+        token.transfer(from, to, *amount, tx_hash.clone()).map_err(token_error)?;
+
+        let mut split = FeeSplit::default();
+        if *fee > 0 {
+            let system = system_address();
+            token.transfer(from, &system, *fee, tx_hash.clone()).map_err(token_error)?;
+            split.burned = ledger.deflation.burn_transaction_fees(*fee, tx_hash.clone(), token).map_err(token_error)?;
+            split.rewarded = fee - split.burned;
+            // Sans producteur identifié, la part non brûlée reste au système
+            if split.rewarded > 0 && *fee_recipient != system {
+                token.transfer(&system, fee_recipient, split.rewarded, tx_hash).map_err(token_error)?;
+            }
+        }
+
+        Ok(split)
+    }
+
+    /// Applique les transferts de tokens d'un bloc, dans l'ordre
+    ///
+    /// Les nonces sont vérifiés et enregistrés par la chaîne. L'application
+    /// est atomique : `ledger` n'est pas modifié et le registre résultant est
+    /// retourné, ou une erreur au premier transfert invalide. Les autres types
+    /// de transactions sont ignorés.
+    pub fn apply_block(
+        &self,
+        block: &Block,
+        ledger: &TokenLedger,
+        fee_recipient: &PublicKey,
+    ) -> Result<BlockExecution> {
+        let mut execution = BlockExecution {
+            ledger: ledger.clone(),
+            fees: FeeSplit::default(),
+            transfers: HashMap::new(),
+        };
+
+        for transaction in block.body.transactions.iter().filter(|tx| tx.is_token_transfer()) {
+            let first_event = execution.ledger.token.events.len();
+            let fees = self.execute_transfer(transaction, &mut execution.ledger, fee_recipient)?;
+            execution.fees.burned += fees.burned;
+            execution.fees.rewarded += fees.rewarded;
+            execution.transfers.insert(transaction.hash().clone(), TransferExecution {
+                fees,
+                events: execution.ledger.token.events[first_event..].to_vec(),
+            });
+        }
+
+        Ok(execution)
+    }
+}
+
+/// Convertit une erreur du token en erreur de transaction
+fn token_error(error: TokenOperationError) -> CoreError {
+    match error {
+        TokenOperationError::InsufficientBalance { .. } => TransactionError::InsufficientBalance.into(),
+        other => CoreError::Internal { message: other.to_string() },
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::block::BlockBuilder;
+    use crate::crypto::{generate_keypair, Hash, HashAlgorithm, KeyPair};
+    use proptest::prelude::*;
+
+    const INITIAL_BALANCE: u64 = 10_000;
+
+    struct Ledger {
+        accounts: Vec<KeyPair>,
+        producer: PublicKey,
+        state: StateMachine,
+        ledger: TokenLedger,
+        processor: TokenTransferProcessor,
+    }
+
+    impl Ledger {
+        fn new(accounts: usize) -> Self {
+            let accounts: Vec<KeyPair> = (0..accounts).map(|_| generate_keypair().unwrap()).collect();
+            let mut ledger = TokenLedger::default();
+            for account in &accounts {
+                ledger.token.mint(account.public_key(), INITIAL_BALANCE, Hash::zero()).unwrap();
+            }
+            Self {
+                accounts,
+                producer: generate_keypair().unwrap().public_key().clone(),
+                state: StateMachine::new(),
+                ledger,
+                processor: TokenTransferProcessor::default(),
+            }
+        }
+
+        fn signed(&self, from: usize, to: usize, amount: u64, fee: u64, nonce: u64) -> Transaction {
+            let sender = &self.accounts[from];
+            let mut tx = Transaction::token_transfer(
+                sender.public_key().clone(),
+                self.accounts[to].public_key().clone(),
+                amount,
+                fee,
+                nonce,
+            );
+            tx.sign(sender.private_key()).unwrap();
+            tx
+        }
+
+        fn next_nonce(&self, account: usize) -> u64 {
+            self.state.next_nonce(self.accounts[account].public_key())
+        }
+
+        fn validate(&self, tx: &Transaction) -> Result<()> {
+            self.processor.validator().validate_token_transfer(tx, &self.state, &self.ledger.token)
+        }
+
+        /// Applique le transfert puis consomme son nonce, comme son inclusion dans un bloc
+        fn apply(&mut self, tx: &Transaction) -> Result<FeeSplit> {
+            let split = self.processor.apply_transfer(tx, &self.state, &mut self.ledger, &self.producer)?;
+            self.state.set_account_nonce(tx.sender.as_ref().unwrap(), tx.nonce)?;
+            Ok(split)
+        }
+
+        fn balance(&self, account: usize) -> u64 {
+            self.ledger.token.balance_of(self.accounts[account].public_key())
+        }
+
+        fn accounted_supply(&self) -> u64 {
+            self.ledger.token.balances.values().sum::<u64>() + self.ledger.token.burned_tokens
+        }
+    }
+
+    #[test]
+    fn test_transfer_moves_balances_and_splits_fee() {
+        let mut ledger = Ledger::new(2);
+        let tx = ledger.signed(0, 1, 1_000, 100, 0);
+
+        let split = ledger.apply(&tx).unwrap();
+        assert_eq!(split, FeeSplit { burned: 10, rewarded: 90 });
+        assert_eq!(ledger.balance(0), INITIAL_BALANCE - 1_100);
+        assert_eq!(ledger.balance(1), INITIAL_BALANCE + 1_000);
+        assert_eq!(ledger.ledger.token.balance_of(&ledger.producer), 90);
+        assert_eq!(ledger.ledger.token.burned_tokens, 10);
+        assert_eq!(ledger.next_nonce(0), 1);
+
+        // Rejeu de la même transaction signée
+        assert!(matches!(
+            ledger.apply(&tx),
+            Err(CoreError::Transaction(TransactionError::InvalidNonce { expected: 1, got: 0 }))
+        ));
+    }
+
+    #[test]
+    fn test_insufficient_balance_and_atomic_block() {
+        let mut ledger = Ledger::new(2);
+        let too_much = ledger.signed(0, 1, INITIAL_BALANCE, 1, 0);
+        assert!(matches!(
+            ledger.validate(&too_much),
+            Err(CoreError::Transaction(TransactionError::InsufficientBalance))
+        ));
+
+        // Le troisième transfert dépasse le solde restant : tout le bloc est rejeté
+        let block_with = |transactions: Vec<Transaction>| {
+            BlockBuilder::new(1, Hash::zero(), HashAlgorithm::Blake3)
+                .add_transactions(transactions)
+                .build()
+                .unwrap()
+        };
+        let mut transactions = vec![
+            ledger.signed(0, 1, 500, 10, 0),
+            ledger.signed(1, 0, 500, 10, 0),
+            ledger.signed(0, 1, INITIAL_BALANCE, 10, 1),
+        ];
+        let block = block_with(transactions.clone());
+        let result = ledger.processor.apply_block(&block, &ledger.ledger, &ledger.producer);
+        assert!(matches!(result, Err(CoreError::Transaction(TransactionError::InsufficientBalance))));
+
+        transactions.pop();
+        let block = block_with(transactions);
+        let before = ledger.accounted_supply();
+        let execution = ledger.processor.apply_block(&block, &ledger.ledger, &ledger.producer).unwrap();
+        assert_eq!(execution.fees, FeeSplit { burned: 2, rewarded: 18 });
+        // Transfert, frais vers le système, burn et récompense du producteur
+        let first = &execution.transfers[block.body.transactions[0].hash()];
+        assert_eq!(first.fees, FeeSplit { burned: 1, rewarded: 9 });
+        assert_eq!(first.events.len(), 4);
+        // Le registre d'origine n'est pas modifié, le registre résultant conserve la supply
+        assert_eq!(ledger.balance(0), INITIAL_BALANCE);
+        ledger.ledger = execution.ledger;
+        assert_eq!(ledger.balance(0), INITIAL_BALANCE - 10);
+        assert_eq!(ledger.ledger.token.balance_of(&ledger.producer), 18);
+        assert_eq!(ledger.accounted_supply(), before);
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(24))]
+
+        #[test]
+        fn prop_valid_transfers_preserve_supply(
+            transfers in prop::collection::vec((0usize..3, 0usize..3, 1u64..4_000, 1u64..200), 1..20)
+        ) {
+            let mut ledger = Ledger::new(3);
+            let initial = ledger.accounted_supply();
+            let mut applied = [0u64; 3];
+
+            for (from, to, amount, fee) in transfers {
+                let tx = ledger.signed(from, to, amount, fee, ledger.next_nonce(from));
+                match ledger.apply(&tx) {
+                    Ok(split) => {
+                        prop_assert_eq!(split.burned + split.rewarded, fee);
+                        applied[from] += 1;
+                    }
+                    Err(CoreError::Transaction(TransactionError::InsufficientBalance)) => {
+                        prop_assert!(ledger.balance(from) < amount + fee);
+                    }
+                    Err(e) => prop_assert!(false, "unexpected error: {}", e),
+                }
+                prop_assert_eq!(ledger.accounted_supply(), initial);
+                prop_assert!(ledger.ledger.token.validate_integrity().is_ok());
+            }
+
+            for (account, count) in applied.iter().enumerate() {
+                prop_assert_eq!(ledger.next_nonce(account), *count);
+            }
+        }
+
+        #[test]
+        fn prop_out_of_order_nonce_is_rejected(applied in 0u64..4, offset in 1u64..5, backwards in any::<bool>()) {
+            let mut ledger = Ledger::new(2);
+            for nonce in 0..applied {
+                ledger.apply(&ledger.signed(0, 1, 10, 1, nonce)).unwrap();
+            }
+
+            let expected = ledger.next_nonce(0);
+            let nonce = if backwards && expected > 0 {
+                expected.saturating_sub(offset)
+            } else {
+                expected + offset
+            };
+            let result = ledger.validate(&ledger.signed(0, 1, 10, 1, nonce));
+            let is_invalid_nonce = matches!(
+                result,
+                Err(CoreError::Transaction(TransactionError::InvalidNonce { expected: e, got })) if e == expected && got == nonce
+            );
+            prop_assert!(is_invalid_nonce);
+        }
+
+        #[test]
+        fn prop_bad_signature_is_rejected(amount in 1u64..1_000, tamper in 0u8..3) {
+            let ledger = Ledger::new(3);
+            let mut tx = ledger.signed(0, 1, amount, 5, 0);
+            match tamper {
+                // Signé par un autre compte
+                0 => tx.sign(ledger.accounts[2].private_key()).unwrap(),
+                // Montant modifié après signature
+                1 => {
+                    if let TransactionType::TokenTransfer { amount, .. } = &mut tx.tx_type {
+                        *amount += 1;
+                    }
+                }
+                // Destinataire modifié après signature
+                _ => {
+                    if let TransactionType::TokenTransfer { to, .. } = &mut tx.tx_type {
+                        *to = ledger.accounts[2].public_key().clone();
+                    }
+                }
+            }
+            let is_invalid_signature = matches!(
+                ledger.validate(&tx),
+                Err(CoreError::Transaction(TransactionError::InvalidSignature))
+            );
+            prop_assert!(is_invalid_signature);
+        }
+    }
+}
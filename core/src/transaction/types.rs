@@ -2,8 +2,9 @@
 
 use serde::{Deserialize, Serialize};
 use chrono::{DateTime, Utc};
-use crate::crypto::{Hash, HashAlgorithm, Signature, PublicKey, compute_hash};
+use crate::crypto::{Hash, HashAlgorithm, Signature, PublicKey, Signer, compute_hash, sign_data, verify_signature};
 use crate::error::{TransactionError, Result};
+use crate::serialization::CanonicalEncoding;
 
 /// Types de transactions supportées
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -16,6 +17,32 @@ pub enum TransactionType {
     Stake,
     /// Transaction de gouvernance
     Governance,
+    /// Transfert de tokens ARC entre comptes, signé par `from`
+    TokenTransfer {
+        from: PublicKey,
+        to: PublicKey,
+        amount: u64,
+        fee: u64,
+        nonce: u64,
+    },
+    /// Transaction exigeant `threshold` signatures parmi `signers`
+    ///
+    /// Les signatures portent sur le corps de la transaction et ne font pas
+    /// partie du hash : elles peuvent être collectées après construction.
+    Multisig {
+        signers: Vec<PublicKey>,
+        threshold: u32,
+        signatures: Vec<MultisigSignature>,
+    },
+}
+
+/// Signature d'un co-signataire d'une transaction multisig
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MultisigSignature {
+    /// Clé du co-signataire, qui doit figurer parmi les signataires autorisés
+    pub signer: PublicKey,
+    /// Signature du corps de la transaction
+    pub signature: Signature,
 }
 
 /// Entrée d'une transaction (UTXO)
@@ -57,6 +84,9 @@ pub struct Transaction {
     pub fee: u64,
     /// Nonce pour éviter les replays
     pub nonce: u64,
+    /// Clé publique de l'émetteur (compte auquel le nonce s'applique)
+    #[serde(default)]
+    pub sender: Option<PublicKey>,
     /// Timestamp de création
     pub timestamp: DateTime<Utc>,
     /// Données additionnelles (pour les contrats, etc.)
@@ -83,12 +113,48 @@ impl Transaction {
             outputs: Vec::new(),
             fee: 0,
             nonce: 0,
+            sender: None,
             timestamp,
             data,
             signature: Signature::zero(),
         }
     }
 
+    /// Crée une transaction de transfert de tokens ARC (non signée)
+    ///
+    /// Les frais et le nonce sont reportés sur la transaction pour que le pool
+    /// et les validateurs les traitent comme ceux des autres transactions.
+    pub fn token_transfer(from: PublicKey, to: PublicKey, amount: u64, fee: u64, nonce: u64) -> Self {
+        TransactionBuilder::new(TransactionType::TokenTransfer {
+            from: from.clone(),
+            to,
+            amount,
+            fee,
+            nonce,
+        })
+            .sender(from)
+            .fee(fee)
+            .nonce(nonce)
+            .build()
+    }
+
+    /// Crée une transaction multisig (sans signature) versant `outputs`
+    ///
+    /// Les co-signataires ajoutent ensuite leur signature avec `add_multisig_signature`.
+    pub fn multisig(signers: Vec<PublicKey>, threshold: u32, outputs: Vec<TransactionOutput>, fee: u64, data: Vec<u8>) -> Self {
+        let mut builder = TransactionBuilder::new(TransactionType::Multisig {
+            signers,
+            threshold,
+            signatures: Vec::new(),
+        })
+            .fee(fee)
+            .data(data);
+        for output in outputs {
+            builder = builder.add_output(output);
+        }
+        builder.build()
+    }
+
     /// Calcule l'ID de la transaction
     fn calculate_tx_id(from: &Hash, to: &Hash, amount: u64, timestamp: DateTime<Utc>) -> Hash {
         let mut data = Vec::new();
@@ -122,7 +188,27 @@ impl Transaction {
             TransactionType::Archive => 1,
             TransactionType::Stake => 2,
             TransactionType::Governance => 3,
+            TransactionType::TokenTransfer { .. } => 4,
+            TransactionType::Multisig { .. } => 5,
         });
+        match &self.tx_type {
+            TransactionType::TokenTransfer { from, to, amount, fee, nonce } => {
+                data.extend_from_slice(from.as_bytes());
+                data.extend_from_slice(to.as_bytes());
+                data.extend_from_slice(&amount.to_le_bytes());
+                data.extend_from_slice(&fee.to_le_bytes());
+                data.extend_from_slice(&nonce.to_le_bytes());
+            }
+            // Les signatures collectées sont exclues, comme la signature finale
+            TransactionType::Multisig { signers, threshold, .. } => {
+                data.extend_from_slice(&(signers.len() as u32).to_le_bytes());
+                for signer in signers {
+                    data.extend_from_slice(signer.as_bytes());
+                }
+                data.extend_from_slice(&threshold.to_le_bytes());
+            }
+            _ => {}
+        }
         
         // Inputs
         data.extend_from_slice(&(self.inputs.len() as u32).to_le_bytes());
@@ -143,14 +229,139 @@ impl Transaction {
         // Autres champs
         data.extend_from_slice(&self.fee.to_le_bytes());
         data.extend_from_slice(&self.nonce.to_le_bytes());
+        if let Some(sender) = &self.sender {
+            data.extend_from_slice(sender.as_bytes());
+        }
         data.extend_from_slice(&self.timestamp.timestamp().to_le_bytes());
         data.extend_from_slice(&self.data);
         
         data
     }
 
+    /// Signe la transaction avec le signataire de l'émetteur
+    ///
+    /// L'émetteur devient la clé publique du signataire, couverte par la
+    /// signature : c'est son compte dont la transaction consomme un nonce.
+    pub fn sign(&mut self, signer: &dyn Signer) -> Result<()> {
+        let sender = signer.public_key();
+        if self.sender.as_ref() != Some(&sender) {
+            self.sender = Some(sender);
+            self.tx_id = self.calculate_hash(HashAlgorithm::Blake3);
+        }
+        self.signature = sign_data(&self.serialize_for_hash(), signer)?;
+        Ok(())
+    }
+
+    /// Compte dont la transaction consomme un nonce
+    ///
+    /// Une transaction qui déclare un émetteur doit être signée par sa clé,
+    /// et une transaction signée doit déclarer son émetteur ; seules les
+    /// transactions non signées sans émetteur n'appartiennent à aucun compte.
+    pub fn nonce_account(&self) -> Result<Option<&PublicKey>> {
+        match &self.sender {
+            Some(sender) if self.verify_signature(sender) => Ok(Some(sender)),
+            None if self.signature.is_zero() => Ok(None),
+            _ => Err(TransactionError::InvalidSignature.into()),
+        }
+    }
+
+    /// Vérifie la signature de la transaction pour une clé publique
+    pub fn verify_signature(&self, public_key: &PublicKey) -> bool {
+        !self.signature.is_zero()
+            && verify_signature(&self.serialize_for_hash(), &self.signature, public_key).unwrap_or(false)
+    }
+
+    /// Vrai pour les transferts de tokens ARC entre comptes
+    pub fn is_token_transfer(&self) -> bool {
+        matches!(self.tx_type, TransactionType::TokenTransfer { .. })
+    }
+
+    /// Vrai pour les transactions à signatures multiples
+    pub fn is_multisig(&self) -> bool {
+        matches!(self.tx_type, TransactionType::Multisig { .. })
+    }
+
+    /// Ajoute la signature d'un co-signataire d'une transaction multisig
+    ///
+    /// La clé publique correspondante doit figurer parmi les signataires et
+    /// ne pas avoir déjà signé.
+    pub fn add_multisig_signature(&mut self, key: &dyn Signer) -> Result<()> {
+        let signer = key.public_key();
+        let signature = sign_data(&self.serialize_for_hash(), key)?;
+
+        let TransactionType::Multisig { signers, signatures, .. } = &mut self.tx_type else {
+            return Err(TransactionError::Invalid.into());
+        };
+        if !signers.contains(&signer) {
+            return Err(TransactionError::UnknownSigner { signer: signer.to_hex() }.into());
+        }
+        if signatures.iter().any(|existing| existing.signer == signer) {
+            return Err(TransactionError::DuplicateSignature { signer: signer.to_hex() }.into());
+        }
+
+        signatures.push(MultisigSignature { signer, signature });
+        Ok(())
+    }
+
+    /// Vérifie les signatures d'une transaction multisig
+    ///
+    /// Chaque signature doit provenir d'un signataire autorisé, être valide sur
+    /// le corps de la transaction et être la seule de son signataire ; au moins
+    /// `threshold` signatures sont requises. Retourne le nombre de signatures valides.
+    pub fn verify_multisig(&self) -> Result<u32> {
+        let TransactionType::Multisig { signers, threshold, signatures } = &self.tx_type else {
+            return Err(TransactionError::Invalid.into());
+        };
+
+        let body = self.serialize_for_hash();
+        let mut seen: Vec<&PublicKey> = Vec::with_capacity(signatures.len());
+        for entry in signatures {
+            if seen.contains(&&entry.signer) {
+                return Err(TransactionError::DuplicateSignature { signer: entry.signer.to_hex() }.into());
+            }
+            if !signers.contains(&entry.signer) {
+                return Err(TransactionError::UnknownSigner { signer: entry.signer.to_hex() }.into());
+            }
+            if entry.signature.is_zero()
+                || !verify_signature(&body, &entry.signature, &entry.signer).unwrap_or(false)
+            {
+                return Err(TransactionError::InvalidSignature.into());
+            }
+            seen.push(&entry.signer);
+        }
+
+        let valid = seen.len() as u32;
+        if valid < *threshold {
+            return Err(TransactionError::ThresholdNotMet { required: *threshold, valid }.into());
+        }
+        Ok(valid)
+    }
+
     /// Vérifie si la transaction est valide
     pub fn is_valid(&self) -> Result<bool> {
+        // Les transferts de tokens reposent sur les soldes des comptes, sans UTXO
+        if let TransactionType::TokenTransfer { from, amount, fee, nonce, .. } = &self.tx_type {
+            return Ok(*amount > 0
+                && *fee == self.fee
+                && *nonce == self.nonce
+                && self.sender.as_ref() == Some(from)
+                && amount.checked_add(*fee).is_some()
+                && self.timestamp <= Utc::now());
+        }
+
+        // Une politique multisig doit être atteignable et sans signataire en double
+        if let TransactionType::Multisig { signers, threshold, .. } = &self.tx_type {
+            let mut unique = signers.clone();
+            unique.sort_by(|a, b| a.as_bytes().cmp(b.as_bytes()));
+            unique.dedup();
+            return Ok(*threshold > 0
+                && (*threshold as usize) <= signers.len()
+                && unique.len() == signers.len()
+                && !self.outputs.is_empty()
+                && self.outputs.iter().try_fold(self.fee, |total, o| total.checked_add(o.amount)).is_some()
+                && self.timestamp <= Utc::now());
+        }
+
         // Vérifications de base
         if self.inputs.is_empty() && self.tx_type != TransactionType::Archive {
             return Ok(false);
@@ -211,8 +422,13 @@ impl Transaction {
     }
 
     /// Obtient le montant total des sorties
+    ///
+    /// Pour un transfert de tokens, il s'agit du montant transféré.
     pub fn total_output_amount(&self) -> u64 {
-        self.outputs.iter().map(|o| o.amount).sum()
+        match &self.tx_type {
+            TransactionType::TokenTransfer { amount, .. } => *amount,
+            _ => self.outputs.iter().map(|o| o.amount).sum(),
+        }
     }
 
     /// Vérifie si c'est une transaction coinbase (génération de nouveaux tokens)
@@ -220,9 +436,9 @@ impl Transaction {
         self.inputs.is_empty() && self.tx_type == TransactionType::Archive
     }
 
-    /// Obtient la taille de la transaction en bytes
+    /// Obtient la taille de la transaction en bytes (encodage canonique)
     pub fn size_bytes(&self) -> usize {
-        bincode::serialized_size(self).unwrap_or(0) as usize
+        self.canonical_size()
     }
 
     /// Calcule les frais par byte
@@ -244,6 +460,7 @@ pub struct TransactionBuilder {
     outputs: Vec<TransactionOutput>,
     fee: u64,
     nonce: u64,
+    sender: Option<PublicKey>,
     data: Vec<u8>,
 }
 
@@ -256,6 +473,7 @@ impl TransactionBuilder {
             outputs: Vec::new(),
             fee: 0,
             nonce: 0,
+            sender: None,
             data: Vec::new(),
         }
     }
@@ -284,6 +502,12 @@ impl TransactionBuilder {
         self
     }
 
+    /// Définit l'émetteur
+    pub fn sender(mut self, sender: PublicKey) -> Self {
+        self.sender = Some(sender);
+        self
+    }
+
     /// Ajoute des données
     pub fn data(mut self, data: Vec<u8>) -> Self {
         self.data = data;
@@ -302,6 +526,7 @@ impl TransactionBuilder {
             outputs: self.outputs,
             fee: self.fee,
             nonce: self.nonce,
+            sender: self.sender,
             timestamp,
             data: self.data,
             signature: Signature::zero(),
@@ -1,7 +1,10 @@
 //! Validation des transactions pour ArchiveChain
 
+use crate::crypto::PublicKey;
 use crate::error::{TransactionError, Result};
-use super::types::Transaction;
+use crate::state::StateMachine;
+use crate::token::ARCToken;
+use super::types::{Transaction, TransactionType};
 
 /// Validateur de transactions
 #[derive(Debug)]
@@ -61,6 +64,96 @@ impl TransactionValidator {
 
         Ok(true)
     }
+
+    /// Vérifie que le nonce est exactement le suivant attendu
+    pub fn validate_nonce(&self, transaction: &Transaction, expected: u64) -> Result<()> {
+        if transaction.nonce != expected {
+            return Err(TransactionError::InvalidNonce {
+                expected,
+                got: transaction.nonce,
+            }.into());
+        }
+        Ok(())
+    }
+
+    /// Valide une transaction par rapport à l'état courant
+    ///
+    /// En plus des règles de `validate`, le nonce de l'émetteur doit suivre
+    /// le dernier nonce appliqué, ce qui empêche le rejeu d'une transaction
+    /// signée. L'émetteur doit être la clé signataire ; les transactions non
+    /// signées sans émetteur ne portent pas de compte.
+    pub fn validate_with_state(&self, transaction: &Transaction, state: &StateMachine) -> Result<bool> {
+        if !self.validate(transaction)? {
+            return Ok(false);
+        }
+
+        if let Some(sender) = transaction.nonce_account()? {
+            self.validate_nonce(transaction, state.next_nonce(sender))?;
+        }
+
+        Ok(true)
+    }
+
+    /// Valide un transfert de tokens ARC par rapport à l'état et aux soldes
+    ///
+    /// La signature doit provenir de `from`, le nonce doit suivre le dernier
+    /// nonce appliqué du compte (un transfert déjà appliqué est donc rejeté) et
+    /// le solde doit couvrir le montant et les frais.
+    pub fn validate_token_transfer(&self, transaction: &Transaction, state: &StateMachine, token: &ARCToken) -> Result<()> {
+        let TransactionType::TokenTransfer { from, .. } = &transaction.tx_type else {
+            return Err(TransactionError::Invalid.into());
+        };
+
+        self.validate_transfer_signature(transaction, from)?;
+        self.validate_nonce(transaction, state.next_nonce(from))?;
+        Self::validate_balance(transaction, token)
+    }
+
+    /// Valide un transfert de tokens sans son nonce, vérifié par la chaîne pour tout le bloc
+    pub fn validate_transfer_funds(&self, transaction: &Transaction, token: &ARCToken) -> Result<()> {
+        let TransactionType::TokenTransfer { from, .. } = &transaction.tx_type else {
+            return Err(TransactionError::Invalid.into());
+        };
+
+        self.validate_transfer_signature(transaction, from)?;
+        Self::validate_balance(transaction, token)
+    }
+
+    fn validate_transfer_signature(&self, transaction: &Transaction, from: &PublicKey) -> Result<()> {
+        if !self.validate(transaction)? {
+            return Err(TransactionError::Invalid.into());
+        }
+
+        if !transaction.verify_signature(from) {
+            return Err(TransactionError::InvalidSignature.into());
+        }
+        Ok(())
+    }
+
+    fn validate_balance(transaction: &Transaction, token: &ARCToken) -> Result<()> {
+        let TransactionType::TokenTransfer { from, amount, fee, .. } = &transaction.tx_type else {
+            return Err(TransactionError::Invalid.into());
+        };
+
+        let required = amount.checked_add(*fee).ok_or(TransactionError::Invalid)?;
+        if token.balance_of(from) < required {
+            return Err(TransactionError::InsufficientBalance.into());
+        }
+        Ok(())
+    }
+
+    /// Valide une transaction multisig
+    ///
+    /// En plus des règles de `validate`, au moins `threshold` signataires
+    /// distincts et autorisés doivent avoir signé le corps de la transaction.
+    /// Une signature en double ou d'une clé inconnue fait échouer la validation.
+    pub fn validate_multisig(&self, transaction: &Transaction) -> Result<u32> {
+        if !transaction.is_multisig() || !self.validate(transaction)? {
+            return Err(TransactionError::Invalid.into());
+        }
+
+        transaction.verify_multisig()
+    }
 }
 
 impl Default for TransactionValidator {
@@ -93,6 +186,108 @@ mod tests {
         
         assert!(validator.validate(&tx).unwrap());
     }
+
+    #[test]
+    fn test_nonce_must_follow_state() {
+        let validator = TransactionValidator::default();
+        let keypair = generate_keypair().unwrap();
+        let sender = keypair.public_key().clone();
+        let mut state = StateMachine::new();
+
+        let build = |nonce: u64| {
+            let mut transaction = TransactionBuilder::new(TransactionType::Archive)
+                .add_output(TransactionOutput {
+                    amount: 1000,
+                    recipient: sender.clone(),
+                    lock_script: Vec::new(),
+                })
+                .nonce(nonce)
+                .fee(10)
+                .build();
+            transaction.sign(keypair.private_key()).unwrap();
+            transaction
+        };
+
+        assert!(validator.validate_with_state(&build(0), &state).unwrap());
+        state.set_account_nonce(&sender, 0).unwrap();
+
+        // Rejeu du même nonce
+        match validator.validate_with_state(&build(0), &state) {
+            Err(crate::error::CoreError::Transaction(TransactionError::InvalidNonce { expected, got })) => {
+                assert_eq!((expected, got), (1, 0));
+            }
+            other => panic!("InvalidNonce attendu, obtenu {:?}", other),
+        }
+
+        // Saut de nonce
+        assert!(validator.validate_with_state(&build(2), &state).is_err());
+        assert!(validator.validate_with_state(&build(1), &state).unwrap());
+    }
+
+    #[test]
+    fn test_multisig_threshold_and_duplicates() {
+        let validator = TransactionValidator::default();
+        let keys: Vec<_> = (0..3).map(|_| generate_keypair().unwrap()).collect();
+        let signers: Vec<_> = keys.iter().map(|k| k.public_key().clone()).collect();
+        let outsider = generate_keypair().unwrap();
+
+        let mut tx = Transaction::multisig(
+            signers.clone(),
+            2,
+            vec![TransactionOutput {
+                amount: 5_000,
+                recipient: outsider.public_key().clone(),
+                lock_script: Vec::new(),
+            }],
+            10,
+            Vec::new(),
+        );
+        let tx_id = tx.tx_id;
+
+        // Une seule signature : seuil non atteint
+        tx.add_multisig_signature(keys[0].private_key()).unwrap();
+        match validator.validate_multisig(&tx) {
+            Err(crate::error::CoreError::Transaction(TransactionError::ThresholdNotMet { required, valid })) => {
+                assert_eq!((required, valid), (2, 1));
+            }
+            other => panic!("ThresholdNotMet attendu, obtenu {:?}", other),
+        }
+
+        // Le même signataire ne compte qu'une fois
+        assert!(matches!(
+            tx.add_multisig_signature(keys[0].private_key()),
+            Err(crate::error::CoreError::Transaction(TransactionError::DuplicateSignature { .. }))
+        ));
+        let mut duplicated = tx.clone();
+        if let TransactionType::Multisig { signatures, .. } = &mut duplicated.tx_type {
+            let first = signatures[0].clone();
+            signatures.push(first);
+        }
+        assert!(matches!(
+            validator.validate_multisig(&duplicated),
+            Err(crate::error::CoreError::Transaction(TransactionError::DuplicateSignature { .. }))
+        ));
+
+        // Une clé hors de la liste ne peut pas signer
+        assert!(matches!(
+            tx.add_multisig_signature(outsider.private_key()),
+            Err(crate::error::CoreError::Transaction(TransactionError::UnknownSigner { .. }))
+        ));
+
+        tx.add_multisig_signature(keys[2].private_key()).unwrap();
+        assert_eq!(validator.validate_multisig(&tx).unwrap(), 2);
+        // Les signatures ne modifient pas l'identifiant de la transaction
+        assert_eq!(tx.tx_id, tx_id);
+
+        // Une signature sur un autre corps est rejetée
+        let mut tampered = tx.clone();
+        tampered.outputs[0].amount = 50_000;
+        assert!(validator.validate_multisig(&tampered).is_err());
+
+        // Seuil inatteignable
+        let impossible = Transaction::multisig(signers, 4, tx.outputs.clone(), 10, Vec::new());
+        assert!(!impossible.is_valid().unwrap());
+    }
 }
 
 /// Trait pour les types qui peuvent être validés